@@ -0,0 +1,86 @@
+//! Scheduled account deletion
+//!
+//! Anonymizes the accounts whose deletion grace period is over, through the
+//! `anonymize_user` database function Vanguard's admin override also uses.
+//! With `ACCOUNT_DELETION_SUBMISSIONS=delete` their submissions are deleted
+//! too, and so are their stored files: ZIPs, compiled artifacts and kept
+//! test outputs. Their refresh sessions are refused from then on; access
+//! tokens run out on their own.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Statistics from an account deletion run
+#[derive(Debug, Default)]
+pub struct AccountDeletionStats {
+    pub anonymized: u64,
+    pub files_removed: u64,
+    pub errors: u64,
+}
+
+/// Anonymizes accounts whose deletion is due
+pub struct AccountDeleter {
+    config: Arc<Config>,
+    db_pool: PgPool,
+}
+
+impl AccountDeleter {
+    pub fn new(config: Arc<Config>, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Anonymize every account scheduled for deletion by now
+    pub async fn delete_due(&self) -> Result<AccountDeletionStats> {
+        let mut stats = AccountDeletionStats::default();
+
+        let due: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM users \
+             WHERE deletion_scheduled_for <= NOW() AND anonymized_at IS NULL",
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        for user_id in due {
+            let files: Vec<String> = match sqlx::query_scalar("SELECT anonymize_user($1, $2)")
+                .bind(user_id)
+                .bind(self.config.accounts.delete_submissions)
+                .fetch_all(&self.db_pool)
+                .await
+            {
+                Ok(files) => files,
+                Err(e) => {
+                    tracing::warn!("Failed to anonymize user {}: {}", user_id, e);
+                    stats.errors += 1;
+                    continue;
+                }
+            };
+            stats.anonymized += 1;
+            tracing::info!(user_id = %user_id, "Anonymized account after deletion request");
+
+            for file in files {
+                match self
+                    .config
+                    .storage
+                    .storage_roots
+                    .remove_submission_file(Path::new(&file))
+                    .await
+                {
+                    Ok(true) => stats.files_removed += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!("Failed to remove submission file {}: {}", file, e);
+                        stats.errors += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,284 @@
+//! Problem package backups
+//!
+//! Snapshots every problem whose package changed since its last backup into
+//! a new archive version (see `olympus_common::problem_backup`), so a problem
+//! deleted by accident can be restored by an admin. A package counts as
+//! changed when its fingerprint differs: a hash of its table rows and of the
+//! path, size and modification time of each binary. Only the newest
+//! `PROBLEM_BACKUP_KEEP_VERSIONS` archives of a problem are kept; archives of
+//! deleted problems are pruned the same way and never expire otherwise.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use olympus_common::problem_backup::{
+    archive_path, problem_column, BackupManifest, ARCHIVE_BINARIES_PREFIX, BACKUP_TABLES,
+    FORMAT_VERSION, MANIFEST_FILE,
+};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::config::Config;
+use crate::metrics;
+
+/// Statistics from a backup run
+#[derive(Debug, Default)]
+pub struct BackupStats {
+    pub problems: u64,
+    pub backed_up: u64,
+    pub unchanged: u64,
+    pub pruned: u64,
+    pub errors: u64,
+}
+
+/// A file of the problem's binaries directory
+struct PackageFile {
+    /// Path relative to the binaries directory, `/`-separated
+    name: String,
+    path: PathBuf,
+    mode: u32,
+}
+
+/// Writes problem package archives and prunes old versions
+pub struct BackupRunner {
+    config: Arc<Config>,
+    db_pool: PgPool,
+}
+
+impl BackupRunner {
+    pub fn new(config: Arc<Config>, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Back up every changed problem, then prune old archives
+    pub async fn backup(&self) -> Result<BackupStats> {
+        let mut stats = BackupStats::default();
+
+        let problems: Vec<(Uuid, String)> =
+            sqlx::query_as("SELECT id, title FROM problems ORDER BY created_at")
+                .fetch_all(&self.db_pool)
+                .await?;
+
+        for (problem_id, title) in problems {
+            stats.problems += 1;
+            match self.backup_problem(problem_id, &title).await {
+                Ok(true) => stats.backed_up += 1,
+                Ok(false) => stats.unchanged += 1,
+                Err(e) => {
+                    tracing::warn!("Failed to back up problem {}: {:#}", problem_id, e);
+                    stats.errors += 1;
+                }
+            }
+        }
+        metrics::PROBLEM_BACKUPS.inc_by(stats.backed_up);
+
+        stats.pruned = self.prune(&mut stats.errors).await?;
+        Ok(stats)
+    }
+
+    /// Write a new archive version if the package changed. Returns whether
+    /// one was written.
+    async fn backup_problem(&self, problem_id: Uuid, title: &str) -> Result<bool> {
+        let mut tables = BTreeMap::new();
+        for &table in BACKUP_TABLES {
+            // Rows are ordered so an unchanged table hashes the same
+            let sql = format!(
+                "SELECT COALESCE(jsonb_agg(to_jsonb(t) ORDER BY to_jsonb(t)::text), '[]')::text \
+                 FROM {} t WHERE t.{} = $1",
+                table,
+                problem_column(table)
+            );
+            let rows: String = sqlx::query_scalar(&sql)
+                .bind(problem_id)
+                .fetch_one(&self.db_pool)
+                .await?;
+            tables.insert(table.to_string(), serde_json::from_str(&rows)?);
+        }
+
+        let binaries_dir = self
+            .config
+            .storage
+            .problem_binaries_path
+            .join(problem_id.to_string());
+        let (files, fingerprint) = {
+            let tables_json = serde_json::to_vec(&tables)?;
+            tokio::task::spawn_blocking(move || scan_binaries(&binaries_dir, &tables_json))
+                .await??
+        };
+
+        let latest: Option<(i32, String)> = sqlx::query_as(
+            "SELECT version, fingerprint FROM problem_backups \
+             WHERE problem_id = $1 ORDER BY version DESC LIMIT 1",
+        )
+        .bind(problem_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+        if latest.as_ref().is_some_and(|(_, f)| *f == fingerprint) {
+            return Ok(false);
+        }
+        let version = latest.map_or(1, |(v, _)| v + 1);
+
+        let manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            problem_id,
+            version,
+            created_at: Utc::now(),
+            tables,
+        };
+        let path = archive_path(&self.config.storage.base_path, problem_id, version);
+        let (size_bytes, checksum) = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || write_archive(&path, &manifest, &files)).await??
+        };
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO problem_backups
+                (problem_id, problem_title, version, archive_path, size_bytes, checksum, fingerprint)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(problem_id)
+        .bind(title)
+        .bind(version)
+        .bind(path.to_string_lossy().as_ref())
+        .bind(size_bytes as i64)
+        .bind(&checksum)
+        .bind(&fingerprint)
+        .execute(&self.db_pool)
+        .await;
+        if let Err(e) = inserted {
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(e.into());
+        }
+
+        tracing::info!(
+            problem_id = %problem_id,
+            version,
+            size_bytes,
+            "Backed up problem package"
+        );
+        Ok(true)
+    }
+
+    /// Remove archives beyond the newest `keep_versions` of each problem
+    async fn prune(&self, errors: &mut u64) -> Result<u64> {
+        let stale: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT id, archive_path FROM (
+                SELECT id, archive_path,
+                       ROW_NUMBER() OVER (PARTITION BY problem_id ORDER BY version DESC) AS rank
+                FROM problem_backups
+            ) b
+            WHERE rank > $1
+            "#,
+        )
+        .bind(self.config.backups.keep_versions)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut pruned = 0;
+        for (backup_id, path) in stale {
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    tracing::warn!("Failed to remove backup archive {}: {}", path, e);
+                    *errors += 1;
+                    continue;
+                }
+            }
+            sqlx::query("DELETE FROM problem_backups WHERE id = $1")
+                .bind(backup_id)
+                .execute(&self.db_pool)
+                .await?;
+            pruned += 1;
+        }
+        Ok(pruned)
+    }
+}
+
+/// List the problem's binaries and fingerprint them together with its
+/// table rows
+fn scan_binaries(dir: &Path, tables_json: &[u8]) -> Result<(Vec<PackageFile>, String)> {
+    let mut hasher = Sha256::new();
+    hasher.update(tables_json);
+
+    let mut files = Vec::new();
+    if dir.is_dir() {
+        for entry in WalkDir::new(dir).sort_by_file_name() {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let meta = entry.metadata()?;
+            let name = entry
+                .path()
+                .strip_prefix(dir)?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let modified = meta
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            hasher.update(format!("\n{}\0{}\0{}", name, meta.len(), modified));
+            files.push(PackageFile {
+                name,
+                path: entry.into_path(),
+                mode: meta.permissions().mode(),
+            });
+        }
+    }
+
+    Ok((files, format!("{:x}", hasher.finalize())))
+}
+
+/// Write the archive through a temporary file. Returns its size and SHA-256.
+fn write_archive(
+    path: &Path,
+    manifest: &BackupManifest,
+    files: &[PackageFile],
+) -> Result<(u64, String)> {
+    let dir = path.parent().context("Archive path has no parent")?;
+    std::fs::create_dir_all(dir)?;
+    let tmp_path = path.with_extension("zip.tmp");
+
+    let written = (|| -> Result<()> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut writer = ZipWriter::new(File::create(&tmp_path)?);
+
+        writer.start_file(MANIFEST_FILE, options)?;
+        serde_json::to_writer_pretty(&mut writer, manifest)?;
+
+        for file in files {
+            writer.start_file(
+                format!("{}{}", ARCHIVE_BINARIES_PREFIX, file.name),
+                options.unix_permissions(file.mode),
+            )?;
+            io::copy(&mut File::open(&file.path)?, &mut writer)?;
+        }
+        writer.finish()?.flush()?;
+        Ok(())
+    })();
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(&tmp_path, path)?;
+
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
@@ -1,17 +1,20 @@
 //! Cleanup job implementations
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Result;
+use olympus_common::storage::{DEBUG_OUTPUTS_DIR, USER_BINARIES_DIR};
 use sqlx::PgPool;
 use tokio::fs;
 use walkdir::WalkDir;
 
+use crate::cleanup_policy::{ArtifactClass, CleanupPolicy};
 use crate::config::Config;
 use crate::specs::{
     CleanupContext, CleanupSpec, CleanupSpecExt, CreatedOlderThan, HasActiveSubmission,
-    HasProblemRecord, HasSubmissionRecord, IsDirectory, IsFile, LastAccessOlderThan,
+    HasProblemRecord, HasSubmissionRecord, IsDirectory, IsFile, IsRecordedArtifact,
+    LastAccessOlderThan,
 };
 
 /// Statistics from a cleanup run
@@ -22,6 +25,50 @@ pub struct CleanupStats {
     pub dirs_deleted: u64,
     pub bytes_freed: u64,
     pub errors: u64,
+    /// What would have been deleted (dry runs only)
+    pub candidates: Vec<CleanupCandidate>,
+}
+
+/// Something a dry run would have deleted
+#[derive(Debug, serde::Serialize)]
+pub struct CleanupCandidate {
+    #[serde(flatten)]
+    pub target: CleanupTarget,
+    pub bytes: u64,
+}
+
+/// What a cleanup candidate is, stored as `{ "path": ... }` or
+/// `{ "submission_id": ... }` in the report
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupTarget {
+    /// A file or directory
+    Path(PathBuf),
+    /// Database rows of a submission without stored files
+    SubmissionId(uuid::Uuid),
+}
+
+impl From<&Path> for CleanupTarget {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl CleanupStats {
+    fn record_candidate(&mut self, target: impl Into<CleanupTarget>, bytes: u64) {
+        let target = target.into();
+        tracing::info!("Dry run: would delete {:?}", target);
+        self.bytes_freed += bytes;
+        self.candidates.push(CleanupCandidate { target, bytes });
+    }
+}
+
+/// Artifact row from `submission_artifacts`
+#[derive(Debug, sqlx::FromRow)]
+struct ArtifactRow {
+    id: uuid::Uuid,
+    kind: String,
+    path: String,
 }
 
 /// Cleanup job runner
@@ -35,8 +82,49 @@ impl CleanupRunner {
         Self { config, db_pool }
     }
 
+    /// Run the cleanup job for an artifact class under the given policy.
+    ///
+    /// Dry runs delete nothing and store their candidates in `cleanup_reports`.
+    pub async fn run(&self, class: ArtifactClass, policy: &CleanupPolicy) -> Result<CleanupStats> {
+        let stats = match class {
+            ArtifactClass::Testcases => self.cleanup_stale_testcases(policy).await?,
+            ArtifactClass::Temp => self.cleanup_orphan_temp(policy).await?,
+            ArtifactClass::Binaries => self.cleanup_orphan_binaries(policy).await?,
+            ArtifactClass::Submissions => self.cleanup_old_submissions(policy).await?,
+        };
+
+        if policy.dry_run {
+            self.save_report(class, &stats).await?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Store a dry run's candidates, keeping a week of reports.
+    async fn save_report(&self, class: ArtifactClass, stats: &CleanupStats) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO cleanup_reports (artifact_class, items, items_count, total_bytes, errors)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(class.as_str())
+        .bind(serde_json::to_value(&stats.candidates)?)
+        .bind(stats.candidates.len() as i32)
+        .bind(stats.bytes_freed as i64)
+        .bind(stats.errors as i32)
+        .execute(&self.db_pool)
+        .await?;
+
+        sqlx::query("DELETE FROM cleanup_reports WHERE created_at < NOW() - INTERVAL '7 days'")
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Clean stale test cases (not accessed in X hours)
-    pub async fn cleanup_stale_testcases(&self) -> Result<CleanupStats> {
+    pub async fn cleanup_stale_testcases(&self, policy: &CleanupPolicy) -> Result<CleanupStats> {
         let mut stats = CleanupStats::default();
         let testcases_path = &self.config.storage.testcases_path;
 
@@ -45,7 +133,7 @@ impl CleanupRunner {
             return Ok(stats);
         }
 
-        let stale_hours = self.config.schedules.testcase_stale_hours;
+        let stale_hours = policy.retention_hours;
         tracing::info!(
             "Cleaning testcases older than {} hours in {:?}",
             stale_hours,
@@ -72,6 +160,12 @@ impl CleanupRunner {
             let ctx = CleanupContext::new(entry.path(), &self.db_pool);
 
             if spec.is_satisfied_by(&ctx).await {
+                if policy.dry_run {
+                    let bytes = self.calculate_dir_size(entry.path()).await;
+                    stats.record_candidate(entry.path(), bytes);
+                    continue;
+                }
+
                 match self.delete_directory(entry.path()).await {
                     Ok(bytes) => {
                         stats.dirs_deleted += 1;
@@ -96,7 +190,7 @@ impl CleanupRunner {
     }
 
     /// Clean orphaned temp directories (older than X hours with no active submission)
-    pub async fn cleanup_orphan_temp(&self) -> Result<CleanupStats> {
+    pub async fn cleanup_orphan_temp(&self, policy: &CleanupPolicy) -> Result<CleanupStats> {
         let mut stats = CleanupStats::default();
         let temp_path = &self.config.storage.temp_path;
 
@@ -105,7 +199,7 @@ impl CleanupRunner {
             return Ok(stats);
         }
 
-        let orphan_hours = self.config.schedules.temp_orphan_hours;
+        let orphan_hours = policy.retention_hours;
         tracing::info!(
             "Cleaning orphan temp dirs older than {} hours in {:?}",
             orphan_hours,
@@ -131,6 +225,12 @@ impl CleanupRunner {
             let ctx = CleanupContext::new(entry.path(), &self.db_pool);
 
             if spec.is_satisfied_by(&ctx).await {
+                if policy.dry_run {
+                    let bytes = self.calculate_dir_size(entry.path()).await;
+                    stats.record_candidate(entry.path(), bytes);
+                    continue;
+                }
+
                 match self.delete_directory(entry.path()).await {
                     Ok(bytes) => {
                         stats.dirs_deleted += 1;
@@ -154,22 +254,76 @@ impl CleanupRunner {
         Ok(stats)
     }
 
-    /// Clean orphaned user binaries (no submission record)
-    pub async fn cleanup_orphan_binaries(&self) -> Result<CleanupStats> {
+    /// Clean orphaned user binaries (artifacts whose submission was deleted)
+    pub async fn cleanup_orphan_binaries(&self, policy: &CleanupPolicy) -> Result<CleanupStats> {
         let mut stats = CleanupStats::default();
+
+        tracing::info!(
+            "Cleaning orphan binaries detached for over {} hours",
+            policy.retention_hours
+        );
+
+        // Deleting a submission detaches its artifact row; keep a grace period
+        let orphans = sqlx::query_as::<_, ArtifactRow>(
+            r#"
+            SELECT id, kind, path FROM submission_artifacts
+            WHERE submission_id IS NULL
+            AND created_at < NOW() - INTERVAL '1 hour' * $1
+            "#,
+        )
+        .bind(policy.retention_hours as i32)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        for artifact in orphans {
+            stats.files_scanned += 1;
+
+            if policy.dry_run {
+                self.record_artifact_candidate(&artifact, &mut stats).await;
+                continue;
+            }
+
+            if !self.remove_artifact(&artifact, &mut stats).await {
+                continue;
+            }
+
+            if let Err(e) = sqlx::query("DELETE FROM submission_artifacts WHERE id = $1")
+                .bind(artifact.id)
+                .execute(&self.db_pool)
+                .await
+            {
+                tracing::error!("Failed to delete artifact record: {}", e);
+                stats.errors += 1;
+            }
+        }
+
+        self.cleanup_legacy_binaries(policy, &mut stats).await;
+
+        tracing::info!(
+            "Binary cleanup complete: {} files deleted, {} bytes freed",
+            stats.files_deleted,
+            stats.bytes_freed
+        );
+
+        Ok(stats)
+    }
+
+    /// Clean `{id}_bin` files in the binaries directory that no submission
+    /// or artifact record refers to, such as binaries compiled before
+    /// artifacts were recorded whose submission was since deleted.
+    async fn cleanup_legacy_binaries(&self, policy: &CleanupPolicy, stats: &mut CleanupStats) {
         let binaries_path = &self.config.storage.binaries_path;
 
         if !binaries_path.exists() {
             tracing::debug!("Binaries directory does not exist, skipping");
-            return Ok(stats);
+            return;
         }
 
-        tracing::info!("Cleaning orphan binaries in {:?}", binaries_path);
-
-        // Build cleanup spec: file AND older than 1 day AND no submission record
+        // Build cleanup spec: file AND older than retention AND no record
         let spec = IsFile
-            .and(CreatedOlderThan::days(1))
-            .and(HasSubmissionRecord.not());
+            .and(CreatedOlderThan::hours(policy.retention_hours))
+            .and(HasSubmissionRecord.not())
+            .and(IsRecordedArtifact.not());
 
         for entry in WalkDir::new(binaries_path).min_depth(1).max_depth(1) {
             let entry = match entry {
@@ -185,6 +339,12 @@ impl CleanupRunner {
             let ctx = CleanupContext::new(entry.path(), &self.db_pool);
 
             if spec.is_satisfied_by(&ctx).await {
+                if policy.dry_run {
+                    let bytes = ctx.metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+                    stats.record_candidate(entry.path(), bytes);
+                    continue;
+                }
+
                 match self.delete_file(entry.path()).await {
                     Ok(bytes) => {
                         stats.files_deleted += 1;
@@ -198,58 +358,93 @@ impl CleanupRunner {
                 }
             }
         }
-
-        tracing::info!(
-            "Binary cleanup complete: {} files deleted, {} bytes freed",
-            stats.files_deleted,
-            stats.bytes_freed
-        );
-
-        Ok(stats)
     }
 
     /// Clean old submissions (based on retention policy)
-    pub async fn cleanup_old_submissions(&self) -> Result<CleanupStats> {
+    pub async fn cleanup_old_submissions(&self, policy: &CleanupPolicy) -> Result<CleanupStats> {
         let mut stats = CleanupStats::default();
-        let retention_days = self.config.schedules.submission_retention_days;
-
-        if retention_days == 0 {
-            tracing::debug!("Submission retention disabled, skipping");
-            return Ok(stats);
-        }
+        let retention_hours = policy.retention_hours;
 
-        tracing::info!("Cleaning submissions older than {} days", retention_days);
+        tracing::info!("Cleaning submissions older than {} hours", retention_hours);
 
         // Get old submissions from database
-        let old_submissions = sqlx::query_scalar::<_, uuid::Uuid>(
+        let old_submissions = sqlx::query_as::<_, (uuid::Uuid, Option<String>)>(
             r#"
-            SELECT id FROM submissions 
-            WHERE created_at < NOW() - INTERVAL '1 day' * $1
-            AND status NOT IN ('PENDING', 'COMPILING', 'JUDGING')
+            SELECT s.id, s.storage_root FROM submissions s
+            WHERE s.created_at < NOW() - INTERVAL '1 hour' * $1
+            AND s.status NOT IN ('PENDING', 'COMPILING', 'JUDGING')
             "#,
         )
-        .bind(retention_days as i32)
+        .bind(retention_hours as i32)
         .fetch_all(&self.db_pool)
         .await?;
 
-        for submission_id in old_submissions {
+        for (submission_id, storage_root) in old_submissions {
             stats.files_scanned += 1;
 
-            // Delete associated files
-            let binary_path = self
-                .config
-                .storage
-                .binaries_path
-                .join(format!("{}_bin", submission_id));
+            // Outputs kept for its failing tests are under the storage root
+            // recorded with it, which stays put if the contest's root changes
+            let debug_dir = match storage_root.as_deref() {
+                None => Some(self.config.storage.debug_outputs_path.clone()),
+                Some(root) => self
+                    .config
+                    .storage
+                    .storage_roots
+                    .resolve(Some(root))
+                    .map(|root| root.join(DEBUG_OUTPUTS_DIR)),
+            };
+            let Some(debug_dir) = debug_dir.map(|dir| dir.join(submission_id.to_string())) else {
+                tracing::warn!(
+                    "Storage root {:?} of submission {} is not configured; keeping it",
+                    storage_root,
+                    submission_id
+                );
+                stats.errors += 1;
+                continue;
+            };
+
+            let artifact = sqlx::query_as::<_, ArtifactRow>(
+                "SELECT id, kind, path FROM submission_artifacts WHERE submission_id = $1",
+            )
+            .bind(submission_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+
+            if policy.dry_run {
+                match artifact {
+                    Some(artifact) => self.record_artifact_candidate(&artifact, &mut stats).await,
+                    None => {
+                        // No artifact; only the database rows would go
+                        stats.record_candidate(CleanupTarget::SubmissionId(submission_id), 0);
+                    }
+                }
+                continue;
+            }
+
+            // Delete the compiled artifact and its record
 
-            if binary_path.exists() {
-                match self.delete_file(&binary_path).await {
+            if let Some(artifact) = artifact {
+                if self.remove_artifact(&artifact, &mut stats).await {
+                    if let Err(e) = sqlx::query("DELETE FROM submission_artifacts WHERE id = $1")
+                        .bind(artifact.id)
+                        .execute(&self.db_pool)
+                        .await
+                    {
+                        tracing::error!("Failed to delete artifact record: {}", e);
+                        stats.errors += 1;
+                    }
+                }
+            }
+
+            // Delete outputs kept for its failing tests
+            if debug_dir.exists() {
+                match self.delete_directory(&debug_dir).await {
                     Ok(bytes) => {
-                        stats.files_deleted += 1;
+                        stats.dirs_deleted += 1;
                         stats.bytes_freed += bytes;
                     }
                     Err(e) => {
-                        tracing::error!("Failed to delete binary {:?}: {}", binary_path, e);
+                        tracing::error!("Failed to delete {:?}: {}", debug_dir, e);
                         stats.errors += 1;
                     }
                 }
@@ -284,6 +479,64 @@ impl CleanupRunner {
         Ok(stats)
     }
 
+    /// Remove an artifact from disk, returning whether its record may go.
+    ///
+    /// Paths outside the binaries directories (the shared one and those of
+    /// contest storage roots) are never touched.
+    async fn remove_artifact(&self, artifact: &ArtifactRow, stats: &mut CleanupStats) -> bool {
+        let path = Path::new(&artifact.path);
+        if !self
+            .config
+            .storage
+            .storage_roots
+            .contains(USER_BINARIES_DIR, path)
+        {
+            tracing::warn!("Artifact {:?} is outside the binaries directories", path);
+            stats.errors += 1;
+            return false;
+        }
+
+        // Already gone; only the record is left
+        if !path.exists() {
+            return true;
+        }
+
+        let result = if artifact.kind == "bundle" {
+            self.delete_directory(path).await
+        } else {
+            self.delete_file(path).await
+        };
+
+        match result {
+            Ok(bytes) => {
+                if artifact.kind == "bundle" {
+                    stats.dirs_deleted += 1;
+                } else {
+                    stats.files_deleted += 1;
+                }
+                stats.bytes_freed += bytes;
+                tracing::info!("Deleted artifact: {:?}", path);
+                true
+            }
+            Err(e) => {
+                tracing::error!("Failed to delete artifact {:?}: {}", path, e);
+                stats.errors += 1;
+                false
+            }
+        }
+    }
+
+    /// Record an artifact as a dry-run candidate.
+    async fn record_artifact_candidate(&self, artifact: &ArtifactRow, stats: &mut CleanupStats) {
+        let path = Path::new(&artifact.path);
+        let bytes = if artifact.kind == "bundle" {
+            self.calculate_dir_size(path).await
+        } else {
+            fs::metadata(path).await.map(|m| m.len()).unwrap_or(0)
+        };
+        stats.record_candidate(path, bytes);
+    }
+
     /// Delete a directory recursively and return bytes freed
     async fn delete_directory(&self, path: &Path) -> Result<u64> {
         let bytes = self.calculate_dir_size(path).await;
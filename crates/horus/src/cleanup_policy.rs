@@ -0,0 +1,121 @@
+//! Cleanup policies
+//!
+//! Retention and dry-run settings per artifact class. The environment
+//! thresholds are the defaults; admins override them per class through the
+//! admin API, which stores only the overridden fields in `cleanup_policies`.
+//! The store is reloaded on every `config_reload` signal for "horus" and the
+//! scheduler reads it at the start of each run, so edits apply without a
+//! restart.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+
+use crate::config::Config;
+
+pub use olympus_common::cleanup::ArtifactClass;
+
+/// Settings for one artifact class
+#[derive(Debug, Clone, Copy)]
+pub struct CleanupPolicy {
+    /// Whether the scheduled job does anything
+    pub enabled: bool,
+    /// Age in hours after which an artifact is eligible for deletion
+    pub retention_hours: u64,
+    /// Report candidates to `cleanup_reports` instead of deleting them
+    pub dry_run: bool,
+}
+
+/// Shared, reloadable cleanup policy store.
+#[derive(Clone)]
+pub struct CleanupPolicyStore {
+    /// Policies from the environment, before any override
+    defaults: Arc<HashMap<ArtifactClass, CleanupPolicy>>,
+    inner: Arc<RwLock<HashMap<ArtifactClass, CleanupPolicy>>>,
+}
+
+impl CleanupPolicyStore {
+    /// Create a store holding the environment defaults.
+    pub fn from_config(config: &Config) -> Self {
+        let schedules = &config.schedules;
+        let policy = |enabled, retention_hours| CleanupPolicy {
+            enabled,
+            retention_hours,
+            dry_run: false,
+        };
+
+        let defaults = HashMap::from([
+            (
+                ArtifactClass::Testcases,
+                policy(true, schedules.testcase_stale_hours),
+            ),
+            (
+                ArtifactClass::Temp,
+                policy(true, schedules.temp_orphan_hours),
+            ),
+            (ArtifactClass::Binaries, policy(true, 24)),
+            (
+                ArtifactClass::Submissions,
+                policy(
+                    schedules.submission_retention_days > 0,
+                    schedules.submission_retention_days * 24,
+                ),
+            ),
+        ]);
+
+        Self {
+            inner: Arc::new(RwLock::new(defaults.clone())),
+            defaults: Arc::new(defaults),
+        }
+    }
+
+    /// Apply the overrides stored in the database to the environment
+    /// defaults. Fields left NULL, and classes without a row, keep them.
+    pub async fn load_from_db(&self, db: &PgPool) -> Result<usize> {
+        let rows = sqlx::query_as::<_, CleanupPolicyRow>(
+            "SELECT artifact_class, enabled, retention_hours, dry_run FROM cleanup_policies",
+        )
+        .fetch_all(db)
+        .await?;
+
+        let mut policies = (*self.defaults).clone();
+        let mut count = 0;
+        for row in rows {
+            let Some(class) = ArtifactClass::parse(&row.artifact_class) else {
+                tracing::warn!("Skipping unknown cleanup class '{}'", row.artifact_class);
+                continue;
+            };
+            let policy = policies.get_mut(&class).expect("every class has a default");
+            if let Some(enabled) = row.enabled {
+                policy.enabled = enabled;
+            }
+            if let Some(hours) = row.retention_hours {
+                policy.retention_hours = hours.max(1) as u64;
+            }
+            policy.dry_run = row.dry_run;
+            count += 1;
+        }
+
+        *self.inner.write().await = policies;
+
+        tracing::info!("Loaded {} cleanup policy overrides from database", count);
+        Ok(count)
+    }
+
+    /// Current policy for a class.
+    pub async fn get(&self, class: ArtifactClass) -> CleanupPolicy {
+        self.inner.read().await[&class]
+    }
+}
+
+/// Database row for cleanup policy queries.
+#[derive(Debug, sqlx::FromRow)]
+struct CleanupPolicyRow {
+    artifact_class: String,
+    enabled: Option<bool>,
+    retention_hours: Option<i32>,
+    dry_run: bool,
+}
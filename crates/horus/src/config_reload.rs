@@ -1,8 +1,9 @@
 //! Redis pub/sub based configuration reloading.
 //!
 //! Listens for config reload notifications on the `config_reload` channel.
-//! When a message targeting the "horus" service is received, policies are
-//! reloaded from the database and cached in memory.
+//! When a message targeting the "horus" service is received, rule and cleanup
+//! policies are reloaded from the database and cached in memory. A
+//! "horus:dry_run" message runs every cleanup class as a dry run.
 
 use std::sync::Arc;
 
@@ -12,6 +13,13 @@ use tokio::sync::RwLock;
 
 use olympus_rules::config::RuleConfig;
 
+use crate::cleaner::CleanupRunner;
+use crate::cleanup_policy::{ArtifactClass, CleanupPolicyStore};
+use crate::config::Config;
+
+/// Payload requesting an on-demand dry run of every cleanup class
+const DRY_RUN_SIGNAL: &str = "horus:dry_run";
+
 /// A loaded cleanup policy from the database.
 #[derive(Debug, Clone)]
 pub struct LoadedPolicy {
@@ -31,6 +39,12 @@ pub struct PolicyStore {
     inner: Arc<RwLock<Vec<LoadedPolicy>>>,
 }
 
+impl Default for PolicyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PolicyStore {
     /// Create a new empty policy store.
     pub fn new() -> Self {
@@ -80,17 +94,6 @@ impl PolicyStore {
     pub async fn get_policies(&self) -> Vec<LoadedPolicy> {
         self.inner.read().await.clone()
     }
-
-    /// Get a specific policy by name.
-    #[allow(dead_code)]
-    pub async fn get_policy(&self, name: &str) -> Option<LoadedPolicy> {
-        self.inner
-            .read()
-            .await
-            .iter()
-            .find(|p| p.name == name)
-            .cloned()
-    }
 }
 
 /// Database row for policy queries.
@@ -108,13 +111,14 @@ struct PolicyRow {
 /// When a message with payload `"horus"` is received, all policies are
 /// reloaded from the database.
 pub fn start_config_reload_listener(
-    redis_url: String,
+    config: Arc<Config>,
     db: PgPool,
     store: PolicyStore,
+    cleanup_policies: CleanupPolicyStore,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         loop {
-            match run_subscriber(&redis_url, &db, &store).await {
+            match run_subscriber(&config, &db, &store, &cleanup_policies).await {
                 Ok(()) => {
                     tracing::info!("Config reload subscriber exited cleanly");
                     break;
@@ -132,8 +136,13 @@ pub fn start_config_reload_listener(
 }
 
 /// Inner subscription loop with automatic reconnect on failure.
-async fn run_subscriber(redis_url: &str, db: &PgPool, store: &PolicyStore) -> Result<()> {
-    let client = redis::Client::open(redis_url)?;
+async fn run_subscriber(
+    config: &Arc<Config>,
+    db: &PgPool,
+    store: &PolicyStore,
+    cleanup_policies: &CleanupPolicyStore,
+) -> Result<()> {
+    let client = redis::Client::open(config.redis_url.as_str())?;
     let mut pubsub = client.get_async_pubsub().await?;
     pubsub.subscribe("config_reload").await?;
     tracing::info!("Subscribed to config_reload channel");
@@ -160,6 +169,16 @@ async fn run_subscriber(redis_url: &str, db: &PgPool, store: &PolicyStore) -> Re
                             tracing::error!("Failed to reload policies: {}", e);
                         }
                     }
+                    if let Err(e) = cleanup_policies.load_from_db(db).await {
+                        tracing::error!("Failed to reload cleanup policies: {}", e);
+                    }
+                } else if payload == DRY_RUN_SIGNAL {
+                    tracing::info!("Received cleanup dry-run request");
+                    tokio::spawn(run_dry_runs(
+                        config.clone(),
+                        db.clone(),
+                        cleanup_policies.clone(),
+                    ));
                 } else {
                     tracing::debug!("Ignoring config_reload for service: {}", payload);
                 }
@@ -170,3 +189,22 @@ async fn run_subscriber(redis_url: &str, db: &PgPool, store: &PolicyStore) -> Re
         }
     }
 }
+
+/// Dry-run every cleanup class under its current retention, storing reports.
+async fn run_dry_runs(config: Arc<Config>, db: PgPool, cleanup_policies: CleanupPolicyStore) {
+    let runner = CleanupRunner::new(config, db);
+    for class in ArtifactClass::ALL {
+        let mut policy = cleanup_policies.get(class).await;
+        policy.dry_run = true;
+
+        match runner.run(class, &policy).await {
+            Ok(stats) => tracing::info!(
+                "{} dry run: {} candidates, {} bytes",
+                class.as_str(),
+                stats.candidates.len(),
+                stats.bytes_freed
+            ),
+            Err(e) => tracing::error!("{} dry run failed: {}", class.as_str(), e),
+        }
+    }
+}
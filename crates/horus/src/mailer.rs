@@ -0,0 +1,182 @@
+//! Notification email delivery
+//!
+//! `notify_user` flags a notification for email when the recipient asked
+//! for that kind by email; this sends the flagged ones over SMTP. Each
+//! notification is first claimed (`email_claimed_at`) in a short transaction
+//! that skips rows other runs hold, so overlapping runs never pick up the
+//! same row and no lock is held while talking to the SMTP server. The send
+//! is bounded by a timeout and its outcome recorded afterwards. A claim left
+//! behind by a crash expires and the notification is sent again. Failed sends
+//! are recorded and retried on later runs up to the attempt limit.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::config::EmailConfig;
+use crate::metrics;
+
+/// Statistics from a delivery run
+#[derive(Debug, Default)]
+pub struct DeliveryStats {
+    pub sent: u64,
+    pub failed: u64,
+}
+
+/// A notification waiting to be emailed, with its recipient
+#[derive(Debug, FromRow)]
+struct PendingEmail {
+    id: Uuid,
+    title: String,
+    body: String,
+    address: String,
+    username: String,
+}
+
+/// Sends notifications flagged for email
+pub struct Mailer {
+    config: EmailConfig,
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl Mailer {
+    pub fn new(config: EmailConfig) -> Result<Self> {
+        let from = config
+            .from
+            .parse()
+            .with_context(|| format!("Invalid EMAIL_FROM address: {}", config.from))?;
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(&config.smtp_url)
+            .context("Invalid SMTP_URL")?
+            .build();
+
+        Ok(Self {
+            config,
+            from,
+            transport,
+        })
+    }
+
+    /// Email up to a batch of pending notifications, oldest first
+    pub async fn deliver_pending(&self, db_pool: &PgPool) -> Result<DeliveryStats> {
+        let mut stats = DeliveryStats::default();
+        // Failed this run; retried on the next one rather than straight away
+        let mut failed: HashSet<Uuid> = HashSet::new();
+
+        for _ in 0..self.config.batch_size {
+            let Some(pending) = self.claim_next(db_pool, &failed).await? else {
+                break;
+            };
+
+            let sent = tokio::time::timeout(self.send_timeout(), self.send(&pending))
+                .await
+                .unwrap_or_else(|_| Err(anyhow!("Timed out talking to the SMTP server")));
+
+            match sent {
+                Ok(()) => {
+                    sqlx::query(
+                        "UPDATE notifications \
+                         SET emailed_at = NOW(), email_error = NULL, email_claimed_at = NULL \
+                         WHERE id = $1",
+                    )
+                    .bind(pending.id)
+                    .execute(db_pool)
+                    .await?;
+                    stats.sent += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(notification_id = %pending.id, "Email delivery failed: {:#}", e);
+                    sqlx::query(
+                        "UPDATE notifications \
+                         SET email_attempts = email_attempts + 1, email_error = $2, \
+                             email_claimed_at = NULL \
+                         WHERE id = $1",
+                    )
+                    .bind(pending.id)
+                    .bind(format!("{:#}", e))
+                    .execute(db_pool)
+                    .await?;
+                    failed.insert(pending.id);
+                    stats.failed += 1;
+                }
+            }
+        }
+
+        metrics::EMAILS_SENT.inc_by(stats.sent);
+        metrics::EMAIL_FAILURES.inc_by(stats.failed);
+
+        Ok(stats)
+    }
+
+    /// Claim the oldest unsent notification, skipping those failed this run.
+    /// The claim is committed before returning.
+    async fn claim_next(
+        &self,
+        db_pool: &PgPool,
+        failed: &HashSet<Uuid>,
+    ) -> Result<Option<PendingEmail>> {
+        let pending = sqlx::query_as::<_, PendingEmail>(
+            r#"
+            UPDATE notifications n
+            SET email_claimed_at = NOW()
+            FROM users u
+            WHERE n.id = (
+                    SELECT id FROM notifications
+                    WHERE email AND emailed_at IS NULL
+                      AND email_attempts < $1
+                      AND created_at > NOW() - make_interval(hours => $2)
+                      AND id <> ALL($3)
+                      AND (email_claimed_at IS NULL
+                           OR email_claimed_at < NOW() - make_interval(secs => $4))
+                    ORDER BY created_at
+                    LIMIT 1
+                    FOR UPDATE SKIP LOCKED
+                )
+              AND u.id = n.user_id
+            RETURNING n.id, n.title, n.body, u.email AS address, u.username
+            "#,
+        )
+        .bind(self.config.max_attempts)
+        .bind(self.config.max_age_hours as i32)
+        .bind(failed.iter().copied().collect::<Vec<_>>())
+        .bind(self.claim_expiry().as_secs_f64())
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(pending)
+    }
+
+    fn send_timeout(&self) -> Duration {
+        Duration::from_secs(self.config.send_timeout_secs)
+    }
+
+    /// Age after which a claim belongs to a run that died mid-send. Well past
+    /// the send timeout, so a live run always records its outcome first.
+    fn claim_expiry(&self) -> Duration {
+        self.send_timeout() * 2 + Duration::from_secs(60)
+    }
+
+    async fn send(&self, pending: &PendingEmail) -> Result<()> {
+        let to = Mailbox::new(
+            Some(pending.username.clone()),
+            pending
+                .address
+                .parse()
+                .context("Invalid recipient address")?,
+        );
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&pending.title)
+            .header(ContentType::TEXT_PLAIN)
+            .body(pending.body.clone())?;
+
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
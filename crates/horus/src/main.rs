@@ -5,12 +5,31 @@
 //! - Cleans orphaned temp directories
 //! - Removes binaries for deleted submissions
 //! - Optional: Archives old submissions based on retention policy
-
+//! - Re-queues or fails submissions stuck in 'compiling'/'judging'
+//! - Records storage usage snapshots and alerts on budget overruns
+//! - Alerts and flags judging as degraded when queued messages wait too long
+//! - Reminds participants of upcoming contests and emails notifications
+//! - Reaps orphaned compilation containers and dangling images
+//! - Recomputes practice problem recommendations
+//! - Backs up problem packages into versioned archives
+//! - Anonymizes accounts whose deletion grace period is over
+
+mod accounts;
+mod backup;
 mod cleaner;
+mod cleanup_policy;
 mod config;
 pub mod config_reload;
+mod mailer;
+mod metrics;
+mod notifier;
+mod queue_age;
+mod reaper;
+mod recommender;
+mod reconciler;
 mod scheduler;
 mod specs;
+mod usage;
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -18,8 +37,10 @@ use std::sync::Arc;
 use anyhow::Result;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use crate::cleanup_policy::CleanupPolicyStore;
 use crate::config::Config;
 use crate::config_reload::{start_config_reload_listener, PolicyStore};
+use crate::metrics::MetricsServer;
 use crate::scheduler::CleanupScheduler;
 
 #[tokio::main]
@@ -86,16 +107,34 @@ async fn main() -> Result<()> {
         ),
     }
 
+    // Cleanup policies start from the environment defaults
+    let cleanup_policies = CleanupPolicyStore::from_config(&config);
+    if let Err(e) = cleanup_policies.load_from_db(&db_pool).await {
+        tracing::warn!(
+            "Could not load cleanup policies from database, using environment defaults: {}",
+            e
+        );
+    }
+
     // Start Redis pub/sub listener for config reload
     let _reload_handle = start_config_reload_listener(
-        config.redis_url.clone(),
+        config.clone(),
         db_pool.clone(),
         policy_store.clone(),
+        cleanup_policies.clone(),
     );
     tracing::info!("Config reload listener started");
 
+    // Start metrics server
+    let metrics_port = config.metrics_port;
+    tokio::spawn(async move {
+        if let Err(e) = MetricsServer::run(metrics_port).await {
+            tracing::error!("Metrics server error: {}", e);
+        }
+    });
+
     // Create and setup scheduler
-    let mut scheduler = CleanupScheduler::new(config, db_pool).await?;
+    let mut scheduler = CleanupScheduler::new(config, db_pool, cleanup_policies).await?;
     scheduler.setup_jobs().await?;
 
     tracing::info!("Horus ready, starting scheduler");
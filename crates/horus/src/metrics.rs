@@ -0,0 +1,148 @@
+//! Prometheus metrics for Horus
+
+use std::sync::LazyLock;
+
+use prometheus::{Encoder, IntCounter, IntGauge, Registry, TextEncoder};
+
+/// Global metrics registry
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+/// Compilation containers found on the last reaper run
+pub static LABELLED_CONTAINERS: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "horus_labelled_containers",
+        "Compilation containers present on the last reaper run",
+    )
+    .expect("Failed to create gauge")
+});
+
+/// Orphaned containers removed
+pub static CONTAINERS_REAPED: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "horus_containers_reaped_total",
+        "Orphaned compilation containers force-removed",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Dangling images pruned
+pub static IMAGES_PRUNED: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new("horus_images_pruned_total", "Dangling images pruned")
+        .expect("Failed to create counter")
+});
+
+/// Reaper operations that failed
+pub static REAPER_ERRORS: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "horus_reaper_errors_total",
+        "Container removals and image prunes that failed",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Notification emails sent
+pub static EMAILS_SENT: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new("horus_emails_sent_total", "Notification emails sent")
+        .expect("Failed to create counter")
+});
+
+/// Notification emails that failed to send
+pub static EMAIL_FAILURES: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "horus_email_failures_total",
+        "Notification emails the SMTP server did not accept",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Users whose recommendations were recomputed
+pub static RECOMMENDATION_USERS: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "horus_recommendation_users_total",
+        "Users whose practice recommendations were recomputed",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Problem package archives written
+pub static PROBLEM_BACKUPS: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "horus_problem_backups_total",
+        "Problem package backup archives written",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Age of the oldest unacknowledged judge queue message on the last check
+pub static OLDEST_QUEUE_AGE: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "horus_oldest_queue_age_seconds",
+        "Age of the oldest unacknowledged compile or run message",
+    )
+    .expect("Failed to create gauge")
+});
+
+/// Initialize and register all metrics
+pub fn init_metrics() {
+    REGISTRY
+        .register(Box::new(LABELLED_CONTAINERS.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(CONTAINERS_REAPED.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(IMAGES_PRUNED.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(REAPER_ERRORS.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(EMAILS_SENT.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(EMAIL_FAILURES.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(RECOMMENDATION_USERS.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(PROBLEM_BACKUPS.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(OLDEST_QUEUE_AGE.clone()))
+        .expect("Failed to register metric");
+}
+
+/// HTTP server for Prometheus metrics endpoint
+pub struct MetricsServer;
+
+impl MetricsServer {
+    /// Run the metrics server
+    pub async fn run(port: u16) -> anyhow::Result<()> {
+        use axum::{routing::get, Router};
+        use std::net::SocketAddr;
+
+        // Initialize metrics
+        init_metrics();
+
+        let app = Router::new()
+            .route("/metrics", get(Self::metrics_handler))
+            .route("/health", get(|| async { "OK" }));
+
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        tracing::info!("Metrics server listening on {}", addr);
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, app).await?;
+
+        Ok(())
+    }
+
+    async fn metrics_handler() -> String {
+        let encoder = TextEncoder::new();
+        let metric_families = REGISTRY.gather();
+        let mut buffer = Vec::new();
+        encoder.encode(&metric_families, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
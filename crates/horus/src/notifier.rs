@@ -0,0 +1,87 @@
+//! Scheduled notifications
+//!
+//! Vanguard and Minos notify users as events happen; events that are only a
+//! matter of time are raised here. Registered participants get a
+//! `contest_starting` reminder at each configured lead time before a contest
+//! starts (24 hours and 1 hour by default). Each reminder covers the window
+//! down to the next shorter lead, so someone registering 30 minutes before
+//! the start gets only the last one. The unique index on (participant,
+//! contest, lead) keeps a reminder from going out twice, even across
+//! restarts or overlapping runs.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use olympus_common::NotificationKind;
+use sqlx::PgPool;
+
+use crate::config::Config;
+
+/// Statistics from a notification run
+#[derive(Debug, Default)]
+pub struct NotifyStats {
+    pub contest_reminders: u64,
+}
+
+/// Raises notifications for upcoming events
+pub struct Notifier {
+    config: Arc<Config>,
+    db_pool: PgPool,
+}
+
+impl Notifier {
+    pub fn new(config: Arc<Config>, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Remind participants of contests entering one of the reminder windows
+    pub async fn notify_upcoming(&self) -> Result<NotifyStats> {
+        let kind = NotificationKind::ContestStarting.as_str();
+        let mut stats = NotifyStats::default();
+
+        for (lead, next_lead) in
+            reminder_windows(&self.config.notifications.contest_reminder_leads_mins)
+        {
+            // Participants already sent this or a later reminder are skipped
+            let result = sqlx::query(
+                r#"
+                SELECT notify_user(
+                    cp.user_id, $1,
+                    c.title || ' starts soon',
+                    'The contest starts at '
+                        || to_char(c.start_time AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI') || ' UTC.',
+                    c.id, NULL, $2
+                )
+                FROM contest_participants cp
+                JOIN contests c ON c.id = cp.contest_id
+                WHERE c.start_time > NOW() + make_interval(mins => $3)
+                  AND c.start_time <= NOW() + make_interval(mins => $2)
+                  AND cp.status = 'registered'
+                  AND NOT EXISTS (
+                      SELECT 1 FROM notifications n
+                      WHERE n.user_id = cp.user_id AND n.contest_id = c.id AND n.kind = $1
+                        AND n.reminder_lead_mins <= $2
+                  )
+                "#,
+            )
+            .bind(kind)
+            .bind(lead as i32)
+            .bind(next_lead as i32)
+            .execute(&self.db_pool)
+            .await?;
+
+            stats.contest_reminders += result.rows_affected();
+        }
+
+        Ok(stats)
+    }
+}
+
+/// Each reminder lead paired with the next shorter one (0 for the last).
+/// `leads` is sorted longest first.
+fn reminder_windows(leads: &[u64]) -> impl Iterator<Item = (u64, u64)> + '_ {
+    leads
+        .iter()
+        .enumerate()
+        .map(|(i, &lead)| (lead, leads.get(i + 1).copied().unwrap_or(0)))
+}
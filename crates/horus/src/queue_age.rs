@@ -0,0 +1,237 @@
+//! Judge queue age alerting
+//!
+//! Measures how long the oldest unacknowledged message has been waiting in
+//! each compile and run stream. Once one waits longer than `max_age_secs`,
+//! judging counts as degraded: Horus sets the `judging_degraded` Redis key,
+//! which Vanguard's health endpoint reports, and raises an alert. A
+//! `recovered` alert follows once every stream is back under the limit.
+//! Alerts are published on the `queue_alerts` Redis channel and, if
+//! configured, POSTed to a webhook.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use olympus_common::stream_id::stream_id_age_ms;
+use olympus_common::JudgePriority;
+use redis::aio::MultiplexedConnection;
+use redis::streams::{StreamInfoGroupsReply, StreamPendingReply, StreamRangeReply};
+
+use crate::config::Config;
+use crate::metrics;
+
+/// Redis pub/sub channel queue age alerts are published on
+pub const QUEUE_ALERT_CHANNEL: &str = "queue_alerts";
+
+/// Redis key holding the current delay while judging is degraded
+pub const DEGRADED_KEY: &str = "judging_degraded";
+
+/// Lifetime of the degraded flag. Every check refreshes it, so it only
+/// lapses if Horus stops checking.
+const DEGRADED_KEY_TTL_SECS: u64 = 600;
+
+/// Webhook requests slower than this are abandoned
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The stream whose oldest message has waited longest
+#[derive(Debug, Clone)]
+pub struct OldestMessage {
+    pub stream: String,
+    pub age_secs: u64,
+}
+
+/// Statistics from a queue age check
+#[derive(Debug, Default)]
+pub struct QueueAgeStats {
+    pub streams_checked: u64,
+    pub oldest: Option<OldestMessage>,
+    pub degraded: bool,
+    pub alerts: u64,
+}
+
+/// Watches the age of queued judge messages
+pub struct QueueAgeMonitor {
+    config: Arc<Config>,
+    http: reqwest::Client,
+}
+
+impl QueueAgeMonitor {
+    pub fn new(config: Arc<Config>) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()?;
+        Ok(Self { config, http })
+    }
+
+    /// Measure every judge stream, update the degraded flag, and alert when
+    /// judging becomes degraded or recovers
+    pub async fn check(&self) -> Result<QueueAgeStats> {
+        let mut stats = QueueAgeStats::default();
+        let max_age_secs = self.config.queue_age.max_age_secs;
+        let reconcile = &self.config.reconcile;
+
+        let client = redis::Client::open(self.config.redis_url.as_str())?;
+        let mut conn = client.get_multiplexed_async_connection().await?;
+        let now_ms = Utc::now().timestamp_millis();
+
+        let run_streams = reconcile
+            .worker_profiles
+            .streams(&reconcile.run_stream)
+            .into_iter()
+            .map(|(stream, _, _)| stream)
+            .collect();
+        let queues = [
+            (
+                JudgePriority::streams(&reconcile.compile_stream),
+                &reconcile.compile_group,
+            ),
+            (run_streams, &reconcile.run_group),
+        ];
+        for (streams, group) in queues {
+            for stream in streams {
+                stats.streams_checked += 1;
+                let Some(id) = oldest_unacked_id(&mut conn, &stream, group).await? else {
+                    continue;
+                };
+                let age_secs = stream_id_age_ms(&id, now_ms).unwrap_or(0) as u64 / 1000;
+                if stats.oldest.as_ref().is_none_or(|o| age_secs > o.age_secs) {
+                    stats.oldest = Some(OldestMessage { stream, age_secs });
+                }
+            }
+        }
+
+        let oldest_age_secs = stats.oldest.as_ref().map_or(0, |o| o.age_secs);
+        metrics::OLDEST_QUEUE_AGE.set(oldest_age_secs as i64);
+
+        stats.degraded = max_age_secs > 0 && oldest_age_secs > max_age_secs;
+        let was_degraded: bool = redis::cmd("EXISTS")
+            .arg(DEGRADED_KEY)
+            .query_async(&mut conn)
+            .await?;
+
+        if stats.degraded {
+            let oldest = stats.oldest.as_ref().expect("degraded implies a message");
+            let payload = serde_json::json!({
+                "level": "degraded",
+                "stream": oldest.stream,
+                "oldest_age_secs": oldest.age_secs,
+                "max_age_secs": max_age_secs,
+                "checked_at": Utc::now().to_rfc3339(),
+            });
+            redis::cmd("SET")
+                .arg(DEGRADED_KEY)
+                .arg(payload.to_string())
+                .arg("EX")
+                .arg(DEGRADED_KEY_TTL_SECS)
+                .query_async::<()>(&mut conn)
+                .await?;
+
+            if !was_degraded {
+                tracing::error!(
+                    stream = %oldest.stream,
+                    oldest_age_secs = oldest.age_secs,
+                    max_age_secs,
+                    "Judging is degraded: queued message older than the limit"
+                );
+                stats.alerts += 1;
+                self.alert(&mut conn, &payload).await;
+            }
+        } else if was_degraded {
+            redis::cmd("DEL")
+                .arg(DEGRADED_KEY)
+                .query_async::<()>(&mut conn)
+                .await?;
+
+            tracing::info!(oldest_age_secs, max_age_secs, "Judging queues recovered");
+            let payload = serde_json::json!({
+                "level": "recovered",
+                "oldest_age_secs": oldest_age_secs,
+                "max_age_secs": max_age_secs,
+                "checked_at": Utc::now().to_rfc3339(),
+            });
+            stats.alerts += 1;
+            self.alert(&mut conn, &payload).await;
+        }
+
+        Ok(stats)
+    }
+
+    /// Publish an alert and send it to the webhook; failures are logged and
+    /// otherwise ignored.
+    async fn alert(&self, conn: &mut MultiplexedConnection, payload: &serde_json::Value) {
+        if let Err(e) = redis::cmd("PUBLISH")
+            .arg(QUEUE_ALERT_CHANNEL)
+            .arg(payload.to_string())
+            .query_async::<i64>(conn)
+            .await
+        {
+            tracing::warn!("Failed to publish queue alert: {}", e);
+        }
+
+        let Some(url) = &self.config.queue_age.webhook_url else {
+            return;
+        };
+        let sent = self
+            .http
+            .post(url)
+            .json(payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = sent {
+            tracing::warn!("Failed to send queue alert webhook: {}", e);
+        }
+    }
+}
+
+/// Id of the oldest message of `stream` that `group` has not acknowledged:
+/// the oldest pending entry, or else the first entry not yet delivered.
+/// Without the group every entry counts as undelivered.
+async fn oldest_unacked_id(
+    conn: &mut MultiplexedConnection,
+    stream: &str,
+    group: &str,
+) -> Result<Option<String>> {
+    let exists: bool = redis::cmd("EXISTS").arg(stream).query_async(conn).await?;
+    if !exists {
+        return Ok(None);
+    }
+
+    let groups: StreamInfoGroupsReply = redis::cmd("XINFO")
+        .arg("GROUPS")
+        .arg(stream)
+        .query_async(conn)
+        .await?;
+    let last_delivered = groups
+        .groups
+        .into_iter()
+        .find(|g| g.name == group)
+        .map(|g| g.last_delivered_id);
+
+    let start = match &last_delivered {
+        Some(last_delivered) => {
+            // XPENDING <stream> <group> → [count, min id, max id, consumers]
+            let pending: StreamPendingReply = redis::cmd("XPENDING")
+                .arg(stream)
+                .arg(group)
+                .query_async(conn)
+                .await?;
+            if let StreamPendingReply::Data(data) = pending {
+                return Ok(Some(data.start_id));
+            }
+            format!("({}", last_delivered)
+        }
+        None => "-".to_string(),
+    };
+
+    let undelivered: StreamRangeReply = redis::cmd("XRANGE")
+        .arg(stream)
+        .arg(&start)
+        .arg("+")
+        .arg("COUNT")
+        .arg(1)
+        .query_async(conn)
+        .await?;
+    Ok(undelivered.ids.into_iter().next().map(|entry| entry.id))
+}
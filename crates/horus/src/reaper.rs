@@ -0,0 +1,213 @@
+//! Orphaned container reaper
+//!
+//! Sisyphus labels every compilation container with the submission it
+//! builds (`algojudge.submission`) and starts it with `--rm`, but a worker
+//! crash or daemon restart can leave one behind. This job force-removes
+//! labelled containers older than the configured age whose submission is
+//! no longer compiling, and prunes dangling images (old layers of re-pulled
+//! runtime images) past their minimum age.
+
+use std::collections::HashSet;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::metrics;
+
+/// Label Sisyphus puts on compilation containers
+const SUBMISSION_LABEL: &str = "algojudge.submission";
+
+/// Statistics from a reaper run
+#[derive(Debug, Default)]
+pub struct ReapStats {
+    pub containers_seen: u64,
+    pub containers_removed: u64,
+    pub images_pruned: u64,
+    pub errors: u64,
+}
+
+/// A labelled container as reported by `docker inspect`
+#[derive(Debug)]
+struct LabelledContainer {
+    id: String,
+    submission_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+/// Removes compilation containers and images nothing uses any more
+pub struct ContainerReaper {
+    config: Arc<Config>,
+    db_pool: PgPool,
+}
+
+impl ContainerReaper {
+    pub fn new(config: Arc<Config>, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Reap orphaned containers, then prune dangling images if enabled
+    pub async fn reap(&self) -> Result<ReapStats> {
+        let mut stats = ReapStats::default();
+
+        let containers = self.labelled_containers().await?;
+        stats.containers_seen = containers.len() as u64;
+
+        let cutoff =
+            Utc::now() - Duration::minutes(self.config.reaper.container_max_age_mins as i64);
+        let candidates: Vec<&LabelledContainer> = containers
+            .iter()
+            .filter(|c| c.created_at < cutoff)
+            .collect();
+
+        if !candidates.is_empty() {
+            let submission_ids: Vec<Uuid> =
+                candidates.iter().filter_map(|c| c.submission_id).collect();
+            let compiling: HashSet<Uuid> = sqlx::query_scalar(
+                "SELECT id FROM submissions WHERE id = ANY($1) AND status = 'compiling'",
+            )
+            .bind(&submission_ids)
+            .fetch_all(&self.db_pool)
+            .await?
+            .into_iter()
+            .collect();
+
+            for container in candidates {
+                if container
+                    .submission_id
+                    .is_some_and(|id| compiling.contains(&id))
+                {
+                    continue;
+                }
+                match self.remove_container(&container.id).await {
+                    Ok(()) => {
+                        tracing::info!(
+                            container = %container.id,
+                            submission_id = ?container.submission_id,
+                            created_at = %container.created_at,
+                            "Removed orphaned compilation container"
+                        );
+                        stats.containers_removed += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!(container = %container.id, "{}", e);
+                        stats.errors += 1;
+                    }
+                }
+            }
+        }
+
+        if self.config.reaper.prune_images {
+            match self.prune_dangling_images().await {
+                Ok(pruned) => stats.images_pruned = pruned,
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    stats.errors += 1;
+                }
+            }
+        }
+
+        metrics::LABELLED_CONTAINERS.set(stats.containers_seen as i64);
+        metrics::CONTAINERS_REAPED.inc_by(stats.containers_removed);
+        metrics::IMAGES_PRUNED.inc_by(stats.images_pruned);
+        metrics::REAPER_ERRORS.inc_by(stats.errors);
+
+        Ok(stats)
+    }
+
+    /// Every container carrying the submission label, running or not
+    async fn labelled_containers(&self) -> Result<Vec<LabelledContainer>> {
+        let ids = self
+            .docker_output(&[
+                "ps",
+                "--all",
+                "--quiet",
+                "--filter",
+                &format!("label={}", SUBMISSION_LABEL),
+            ])
+            .await?;
+        let ids: Vec<&str> = ids.split_whitespace().collect();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let format = format!(
+            "{{{{.Id}}}}\t{{{{index .Config.Labels \"{}\"}}}}\t{{{{.Created}}}}",
+            SUBMISSION_LABEL
+        );
+        let mut args = vec!["inspect", "--format", format.as_str()];
+        args.extend(ids);
+        let output = self.docker_output(&args).await?;
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let id = fields.next()?.to_string();
+                let submission_id = fields.next()?.parse().ok();
+                let created_at = DateTime::parse_from_rfc3339(fields.next()?)
+                    .ok()?
+                    .with_timezone(&Utc);
+                Some(LabelledContainer {
+                    id,
+                    submission_id,
+                    created_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<()> {
+        self.docker_output(&["rm", "--force", id]).await?;
+        Ok(())
+    }
+
+    /// Prune dangling images older than the minimum age, returning how many
+    /// were deleted
+    async fn prune_dangling_images(&self) -> Result<u64> {
+        let output = self
+            .docker_output(&[
+                "image",
+                "prune",
+                "--force",
+                "--filter",
+                "dangling=true",
+                "--filter",
+                &format!("until={}h", self.config.reaper.image_min_age_hours),
+            ])
+            .await?;
+
+        Ok(output
+            .lines()
+            .filter(|line| line.starts_with("deleted:"))
+            .count() as u64)
+    }
+
+    /// Run a docker command and return its standard output
+    async fn docker_output(&self, args: &[&str]) -> Result<String> {
+        let mut cmd = Command::new("docker");
+        cmd.args(args).stdin(Stdio::null());
+        if let Some(ref ver) = self.config.reaper.docker_api_version {
+            cmd.env("DOCKER_API_VERSION", ver);
+        }
+
+        let output = cmd
+            .output()
+            .await
+            .with_context(|| format!("Failed to run docker {}", args[0]))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "docker {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
@@ -0,0 +1,303 @@
+//! Practice problem recommendations
+//!
+//! Rates each recently active user from the difficulty of the problems they
+//! solved and ranks the public problems they have not solved for them. The
+//! rating is the mean difficulty rating of their hardest solves, padded with
+//! the easiest level until there are enough of them, so a single lucky solve
+//! doesn't jump a beginner to expert problems. Recommendations aim one
+//! stretch step above it:
+//!
+//! ```text
+//! score = 0.6 * closeness(difficulty, rating + stretch)
+//!       + 0.3 if a tag was rarely solved (weak_tag)
+//!       + 0.15 if a tag is among the user's most solved (favorite_tag)
+//!       + 0.1 * popularity
+//! ```
+//!
+//! Each user's profile and recommendations are replaced in one transaction,
+//! so readers never see a half-written list.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::Result;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::metrics;
+
+/// Rating of each difficulty level, easiest first
+const DIFFICULTY_RATINGS: [(&str, f64); 4] = [
+    ("easy", 800.0),
+    ("medium", 1200.0),
+    ("hard", 1600.0),
+    ("expert", 2000.0),
+];
+
+/// Hardest solves that make up the rating
+const RATED_SOLVES: usize = 10;
+
+/// How far above their rating a user is pushed
+const STRETCH: f64 = 200.0;
+
+/// Rating distance at which a problem stops counting as close
+const CLOSENESS_RANGE: f64 = 800.0;
+
+/// Solves of a tag below which it counts as weak
+const WEAK_TAG_SOLVES: u32 = 2;
+
+/// Solves needed before any tag can count as weak
+const MIN_SOLVES_FOR_TAGS: usize = 5;
+
+/// Most solved tags that count as favorites
+const FAVORITE_TAGS: usize = 3;
+
+/// Statistics from a recommendation run
+#[derive(Debug, Default)]
+pub struct RecommendStats {
+    pub users: u64,
+    pub recommendations: u64,
+}
+
+/// A public problem that can be recommended
+#[derive(Debug, FromRow)]
+struct Candidate {
+    id: Uuid,
+    difficulty: Option<String>,
+    tags: Vec<String>,
+    solvers: i64,
+}
+
+/// A problem the user solved
+#[derive(Debug, FromRow)]
+struct Solve {
+    problem_id: Uuid,
+    difficulty: Option<String>,
+    tags: Vec<String>,
+}
+
+/// One ranked recommendation
+#[derive(Debug)]
+struct Recommendation {
+    problem_id: Uuid,
+    score: f64,
+    reason: &'static str,
+    tag: Option<String>,
+}
+
+/// Computes the per-user recommendation table
+pub struct Recommender {
+    config: Arc<Config>,
+    db_pool: PgPool,
+}
+
+impl Recommender {
+    pub fn new(config: Arc<Config>, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Recompute recommendations for every user active within the window
+    pub async fn recommend(&self) -> Result<RecommendStats> {
+        let settings = &self.config.recommendations;
+        let mut stats = RecommendStats::default();
+
+        let candidates = sqlx::query_as::<_, Candidate>(
+            r#"
+            SELECT p.id, p.difficulty, COALESCE(p.tags, '{}') as tags,
+                   (SELECT COUNT(DISTINCT us.user_id) FROM user_solves us
+                    WHERE us.problem_id = p.id) as solvers
+            FROM problems p
+            WHERE p.is_public AND p.organization_id IS NULL
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        let max_solvers = candidates.iter().map(|c| c.solvers).max().unwrap_or(0);
+
+        let users: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT DISTINCT user_id FROM user_activity_days \
+             WHERE day >= CURRENT_DATE - $1::int AND submissions > 0",
+        )
+        .bind(settings.active_days as i32)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        for user_id in users {
+            let solves = sqlx::query_as::<_, Solve>(
+                r#"
+                SELECT p.id as problem_id, p.difficulty, COALESCE(p.tags, '{}') as tags
+                FROM problems p
+                WHERE p.id IN (SELECT problem_id FROM user_solves WHERE user_id = $1)
+                "#,
+            )
+            .bind(user_id)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+            let rating = practice_rating(
+                solves
+                    .iter()
+                    .filter_map(|s| s.difficulty.as_deref().and_then(difficulty_rating)),
+            );
+            let target = rating + STRETCH;
+            let ranked =
+                rank_candidates(&candidates, &solves, target, max_solvers, settings.per_user);
+
+            let mut tx = self.db_pool.begin().await?;
+            sqlx::query(
+                r#"
+                INSERT INTO user_practice_profiles
+                    (user_id, rating, problems_solved, target_difficulty, computed_at)
+                VALUES ($1, $2, $3, $4, NOW())
+                ON CONFLICT (user_id) DO UPDATE SET
+                    rating = EXCLUDED.rating,
+                    problems_solved = EXCLUDED.problems_solved,
+                    target_difficulty = EXCLUDED.target_difficulty,
+                    computed_at = EXCLUDED.computed_at
+                "#,
+            )
+            .bind(user_id)
+            .bind(rating.round() as i32)
+            .bind(solves.len() as i32)
+            .bind(nearest_difficulty(target))
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("DELETE FROM problem_recommendations WHERE user_id = $1")
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO problem_recommendations
+                    (user_id, problem_id, rank, score, reason, tag, computed_at)
+                SELECT $1, r.problem_id, r.rank, r.score, r.reason, r.tag, NOW()
+                FROM UNNEST($2::uuid[], $3::int[], $4::float8[], $5::varchar[], $6::text[])
+                    AS r(problem_id, rank, score, reason, tag)
+                "#,
+            )
+            .bind(user_id)
+            .bind(ranked.iter().map(|r| r.problem_id).collect::<Vec<_>>())
+            .bind((1..=ranked.len() as i32).collect::<Vec<_>>())
+            .bind(ranked.iter().map(|r| r.score).collect::<Vec<_>>())
+            .bind(ranked.iter().map(|r| r.reason).collect::<Vec<_>>())
+            .bind(ranked.iter().map(|r| r.tag.clone()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            stats.users += 1;
+            stats.recommendations += ranked.len() as u64;
+        }
+
+        metrics::RECOMMENDATION_USERS.inc_by(stats.users);
+
+        Ok(stats)
+    }
+}
+
+fn difficulty_rating(difficulty: &str) -> Option<f64> {
+    DIFFICULTY_RATINGS
+        .iter()
+        .find(|(name, _)| *name == difficulty)
+        .map(|(_, rating)| *rating)
+}
+
+/// The difficulty level whose rating is closest to `rating`
+fn nearest_difficulty(rating: f64) -> &'static str {
+    DIFFICULTY_RATINGS
+        .iter()
+        .min_by(|a, b| (a.1 - rating).abs().total_cmp(&(b.1 - rating).abs()))
+        .map(|(name, _)| *name)
+        .unwrap_or("easy")
+}
+
+/// Mean rating of the hardest solves, padded with the easiest level
+fn practice_rating(solved: impl Iterator<Item = f64>) -> f64 {
+    let mut solved: Vec<f64> = solved.collect();
+    solved.sort_by(|a, b| b.total_cmp(a));
+    solved.truncate(RATED_SOLVES);
+
+    let baseline = DIFFICULTY_RATINGS[0].1;
+    let padding = (RATED_SOLVES - solved.len()) as f64 * baseline;
+    (solved.iter().sum::<f64>() + padding) / RATED_SOLVES as f64
+}
+
+/// Score the unsolved candidates for one user and keep the best `limit`
+fn rank_candidates(
+    candidates: &[Candidate],
+    solves: &[Solve],
+    target: f64,
+    max_solvers: i64,
+    limit: usize,
+) -> Vec<Recommendation> {
+    let solved: HashSet<Uuid> = solves.iter().map(|s| s.problem_id).collect();
+
+    let mut tag_solves: HashMap<&str, u32> = HashMap::new();
+    for tag in solves.iter().flat_map(|s| s.tags.iter()) {
+        *tag_solves.entry(tag.as_str()).or_default() += 1;
+    }
+    let mut by_count: Vec<(&str, u32)> = tag_solves.iter().map(|(t, c)| (*t, *c)).collect();
+    by_count.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let favorites: HashSet<&str> = by_count
+        .iter()
+        .take(FAVORITE_TAGS)
+        .map(|(tag, _)| *tag)
+        .collect();
+    let tags_known = solves.len() >= MIN_SOLVES_FOR_TAGS;
+
+    let popularity_scale = ((max_solvers + 1) as f64).ln();
+
+    let mut ranked: Vec<Recommendation> = candidates
+        .iter()
+        .filter(|c| !solved.contains(&c.id))
+        .map(|c| {
+            let closeness = match c.difficulty.as_deref().and_then(difficulty_rating) {
+                Some(rating) => (1.0 - (rating - target).abs() / CLOSENESS_RANGE).max(0.0),
+                // Unrated problems are a guess either way
+                None => 0.5,
+            };
+            let popularity = if popularity_scale > 0.0 {
+                ((c.solvers + 1) as f64).ln() / popularity_scale
+            } else {
+                0.0
+            };
+            let mut score = 0.6 * closeness + 0.1 * popularity;
+
+            let weak_tag = c
+                .tags
+                .iter()
+                .map(|t| (t, tag_solves.get(t.as_str()).copied().unwrap_or(0)))
+                .filter(|(_, count)| *count < WEAK_TAG_SOLVES)
+                .min_by_key(|(_, count)| *count)
+                .map(|(tag, _)| tag);
+            let favorite_tag = c.tags.iter().find(|t| favorites.contains(t.as_str()));
+
+            let (reason, tag) = match (weak_tag, favorite_tag) {
+                (Some(tag), _) if tags_known => {
+                    score += 0.3;
+                    ("weak_tag", Some(tag.clone()))
+                }
+                (_, Some(tag)) => {
+                    score += 0.15;
+                    ("favorite_tag", Some(tag.clone()))
+                }
+                _ => ("next_step", None),
+            };
+
+            Recommendation {
+                problem_id: c.id,
+                score,
+                reason,
+                tag,
+            }
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked.truncate(limit);
+    ranked
+}
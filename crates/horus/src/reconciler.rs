@@ -0,0 +1,380 @@
+//! Stuck submission reconciliation
+//!
+//! A worker that dies after claiming a job but before finishing it leaves the
+//! submission in 'compiling' or 'judging' forever (admins cannot rejudge
+//! in-progress submissions). This job finds submissions that have been in a
+//! transient state longer than the threshold and whose stream entry is not
+//! being worked on, then either re-queues them or, once the re-queue budget
+//! is spent, marks them 'system_error' with a reason.
+//!
+//! A pending (claimed, un-acked) stream entry only counts as live while its
+//! idle time is below the threshold; older entries belong to dead consumers
+//! and are acknowledged before re-queueing so they are not processed twice.
+//! Every priority stream of the compile and run queues is inspected, the
+//! latter for every worker profile; stuck submissions are re-queued at
+//! normal priority, run jobs on their problem's profile.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use olympus_common::worker_profile::profile_stream;
+use olympus_common::JudgePriority;
+use redis::aio::MultiplexedConnection;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Maximum pending entries inspected per stream and run
+const PENDING_SCAN_LIMIT: usize = 1000;
+
+/// Statistics from a reconciliation run
+#[derive(Debug, Default)]
+pub struct ReconcileStats {
+    pub scanned: u64,
+    pub still_running: u64,
+    pub requeued: u64,
+    pub failed: u64,
+    pub errors: u64,
+}
+
+/// Submission row in a transient state
+#[derive(Debug, sqlx::FromRow)]
+struct StuckSubmission {
+    id: Uuid,
+    status: String,
+    submission_type: String,
+    language: Option<String>,
+    file_path: Option<String>,
+    artifact_path: Option<String>,
+    organization_id: Option<Uuid>,
+    reconcile_attempts: i32,
+    worker_profile: String,
+}
+
+/// Pending stream entries for one submission
+#[derive(Debug, Default)]
+struct PendingEntries {
+    /// Some entry is claimed and was active within the threshold
+    live: bool,
+    /// Entries abandoned by dead consumers, by priority stream
+    stale_ids: HashMap<String, Vec<String>>,
+}
+
+/// Stuck submission reconciler
+pub struct Reconciler {
+    config: Arc<Config>,
+    db_pool: PgPool,
+}
+
+impl Reconciler {
+    pub fn new(config: Arc<Config>, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Re-queue or fail submissions stuck in 'compiling'/'judging'
+    pub async fn reconcile_stuck_submissions(&self) -> Result<ReconcileStats> {
+        let mut stats = ReconcileStats::default();
+        let stuck_after_mins = self.config.reconcile.stuck_after_mins;
+
+        // 'judging' sets judged_at when Minos picks the job up; 'compiling'
+        // has no start timestamp, so fall back to the submission time.
+        let candidates = sqlx::query_as::<_, StuckSubmission>(
+            r#"
+            SELECT s.id, s.status, s.submission_type, s.language, s.file_path,
+                   a.path AS artifact_path, s.organization_id, s.reconcile_attempts,
+                   p.worker_profile
+            FROM submissions s
+            JOIN problems p ON p.id = s.problem_id
+            LEFT JOIN submission_artifacts a ON a.submission_id = s.id
+            WHERE (s.status = 'compiling' AND s.submitted_at < NOW() - INTERVAL '1 minute' * $1)
+               OR (s.status = 'judging'
+                   AND COALESCE(s.judged_at, s.submitted_at) < NOW() - INTERVAL '1 minute' * $1)
+            ORDER BY s.submitted_at
+            "#,
+        )
+        .bind(stuck_after_mins as i32)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        if candidates.is_empty() {
+            tracing::debug!("No submissions in transient states past threshold");
+            return Ok(stats);
+        }
+
+        let client = redis::Client::open(self.config.redis_url.as_str())?;
+        let mut conn = client.get_multiplexed_async_connection().await?;
+
+        let max_idle = Duration::from_secs(stuck_after_mins * 60);
+        let reconcile = &self.config.reconcile;
+        let compile_pending = pending_entries(
+            &mut conn,
+            &JudgePriority::streams(&reconcile.compile_stream),
+            &reconcile.compile_group,
+            max_idle,
+        )
+        .await?;
+        let run_streams: Vec<String> = reconcile
+            .worker_profiles
+            .streams(&reconcile.run_stream)
+            .into_iter()
+            .map(|(stream, _, _)| stream)
+            .collect();
+        let run_pending =
+            pending_entries(&mut conn, &run_streams, &reconcile.run_group, max_idle).await?;
+
+        for submission in candidates {
+            stats.scanned += 1;
+
+            let (group, pending) = if submission.status == "compiling" {
+                (&reconcile.compile_group, &compile_pending)
+            } else {
+                (&reconcile.run_group, &run_pending)
+            };
+            let entries = pending.get(&submission.id);
+
+            if entries.is_some_and(|e| e.live) {
+                stats.still_running += 1;
+                continue;
+            }
+
+            if let Some(entries) = entries {
+                if let Err(e) = ack_entries(&mut conn, group, &entries.stale_ids).await {
+                    tracing::error!(
+                        submission_id = %submission.id,
+                        "Failed to acknowledge abandoned entries: {}",
+                        e
+                    );
+                    stats.errors += 1;
+                    continue;
+                }
+            }
+
+            let result = if submission.reconcile_attempts < reconcile.max_requeues {
+                self.requeue(&mut conn, &submission).await.map(|_| {
+                    stats.requeued += 1;
+                })
+            } else {
+                self.mark_system_error(&submission).await.map(|_| {
+                    stats.failed += 1;
+                })
+            };
+
+            if let Err(e) = result {
+                tracing::error!(
+                    submission_id = %submission.id,
+                    "Failed to reconcile stuck submission: {}",
+                    e
+                );
+                stats.errors += 1;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Put a stuck submission back on the stream it was taken from.
+    async fn requeue(
+        &self,
+        conn: &mut MultiplexedConnection,
+        submission: &StuckSubmission,
+    ) -> Result<()> {
+        let reconcile = &self.config.reconcile;
+
+        // Reset the status first so a worker picking the entry up immediately
+        // does not race with this update.
+        let status = if submission.status == "compiling" {
+            "pending"
+        } else {
+            "compiled"
+        };
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE submissions
+            SET status = $1,
+                reconcile_attempts = reconcile_attempts + 1
+            WHERE id = $2 AND status = $3
+            "#,
+        )
+        .bind(status)
+        .bind(submission.id)
+        .bind(&submission.status)
+        .execute(&self.db_pool)
+        .await?;
+
+        // A worker finished the job since the scan; nothing to do
+        if updated.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        let mut cmd = redis::cmd("XADD");
+        if submission.status == "compiling" {
+            cmd.arg(&reconcile.compile_stream)
+                .arg("*")
+                .arg("submission_id")
+                .arg(submission.id.to_string())
+                .arg("type")
+                .arg(&submission.submission_type);
+
+            if submission.submission_type == "zip" {
+                if let Some(ref file_path) = submission.file_path {
+                    cmd.arg("file_path").arg(file_path);
+                }
+            }
+
+            if let Some(ref language) = submission.language {
+                cmd.arg("language").arg(language);
+            }
+        } else {
+            cmd.arg(profile_stream(
+                &reconcile.run_stream,
+                &submission.worker_profile,
+            ))
+            .arg("*")
+            .arg("submission_id")
+            .arg(submission.id.to_string());
+
+            // Minos loads the artifact from the database; this is informational
+            if let Some(ref artifact_path) = submission.artifact_path {
+                cmd.arg("binary_path").arg(artifact_path);
+            }
+        }
+
+        if let Some(tenant) = submission.organization_id {
+            cmd.arg("tenant").arg(tenant.to_string());
+        }
+
+        let stream_id: String = cmd.query_async(conn).await?;
+
+        tracing::warn!(
+            submission_id = %submission.id,
+            previous_status = %submission.status,
+            stream_id = %stream_id,
+            attempt = submission.reconcile_attempts + 1,
+            "Re-queued stuck submission"
+        );
+
+        Ok(())
+    }
+
+    /// Give up on a stuck submission and record why.
+    async fn mark_system_error(&self, submission: &StuckSubmission) -> Result<()> {
+        let reason = format!(
+            "System error: submission was stuck in '{}' for over {} minutes \
+             (worker likely crashed); re-queued {} time(s) without completing",
+            submission.status,
+            self.config.reconcile.stuck_after_mins,
+            submission.reconcile_attempts
+        );
+
+        sqlx::query(
+            r#"
+            UPDATE submissions
+            SET status = 'system_error',
+                compilation_log = $1,
+                judged_at = NOW()
+            WHERE id = $2 AND status = $3
+            "#,
+        )
+        .bind(&reason)
+        .bind(submission.id)
+        .bind(&submission.status)
+        .execute(&self.db_pool)
+        .await?;
+
+        tracing::warn!(
+            submission_id = %submission.id,
+            previous_status = %submission.status,
+            "Marked stuck submission as system_error"
+        );
+
+        Ok(())
+    }
+}
+
+/// Collect pending entries of a consumer group across `streams`, keyed by
+/// submission.
+async fn pending_entries(
+    conn: &mut MultiplexedConnection,
+    streams: &[String],
+    group: &str,
+    max_idle: Duration,
+) -> Result<HashMap<Uuid, PendingEntries>> {
+    let mut entries: HashMap<Uuid, PendingEntries> = HashMap::new();
+
+    for stream in streams {
+        // Missing stream or group simply means nothing is pending
+        let pending: Vec<(String, String, u64, u64)> = redis::cmd("XPENDING")
+            .arg(stream)
+            .arg(group)
+            .arg("-")
+            .arg("+")
+            .arg(PENDING_SCAN_LIMIT)
+            .query_async(conn)
+            .await
+            .unwrap_or_default();
+
+        for (message_id, _consumer, idle_ms, _delivery_count) in pending {
+            let Some(submission_id) = entry_submission_id(conn, stream, &message_id).await? else {
+                continue;
+            };
+
+            let entry = entries.entry(submission_id).or_default();
+            if Duration::from_millis(idle_ms) < max_idle {
+                entry.live = true;
+            } else {
+                entry
+                    .stale_ids
+                    .entry(stream.clone())
+                    .or_default()
+                    .push(message_id);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Read the `submission_id` field of a single stream entry.
+async fn entry_submission_id(
+    conn: &mut MultiplexedConnection,
+    stream: &str,
+    message_id: &str,
+) -> Result<Option<Uuid>> {
+    let entries: Vec<(String, HashMap<String, String>)> = redis::cmd("XRANGE")
+        .arg(stream)
+        .arg(message_id)
+        .arg(message_id)
+        .query_async(conn)
+        .await?;
+
+    Ok(entries
+        .into_iter()
+        .next()
+        .and_then(|(_, fields)| fields.get("submission_id")?.parse().ok()))
+}
+
+/// Acknowledge abandoned entries so no worker reclaims them later.
+async fn ack_entries(
+    conn: &mut MultiplexedConnection,
+    group: &str,
+    stale_ids: &HashMap<String, Vec<String>>,
+) -> Result<()> {
+    for (stream, message_ids) in stale_ids {
+        if message_ids.is_empty() {
+            continue;
+        }
+
+        let _: i64 = redis::cmd("XACK")
+            .arg(stream)
+            .arg(group)
+            .arg(message_ids)
+            .query_async(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -6,43 +6,80 @@ use anyhow::Result;
 use sqlx::PgPool;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
+use crate::accounts::AccountDeleter;
+use crate::backup::BackupRunner;
 use crate::cleaner::CleanupRunner;
+use crate::cleanup_policy::{ArtifactClass, CleanupPolicyStore};
 use crate::config::Config;
+use crate::mailer::Mailer;
+use crate::notifier::Notifier;
+use crate::queue_age::QueueAgeMonitor;
+use crate::reaper::ContainerReaper;
+use crate::recommender::Recommender;
+use crate::reconciler::Reconciler;
+use crate::usage::UsageReporter;
 
 /// Scheduler that runs cleanup jobs on cron schedules
 pub struct CleanupScheduler {
     config: Arc<Config>,
     db_pool: PgPool,
+    policies: CleanupPolicyStore,
     scheduler: JobScheduler,
 }
 
 impl CleanupScheduler {
     /// Create a new cleanup scheduler
-    pub async fn new(config: Arc<Config>, db_pool: PgPool) -> Result<Self> {
+    pub async fn new(
+        config: Arc<Config>,
+        db_pool: PgPool,
+        policies: CleanupPolicyStore,
+    ) -> Result<Self> {
         let scheduler = JobScheduler::new().await?;
 
         Ok(Self {
             config,
             db_pool,
+            policies,
             scheduler,
         })
     }
 
     /// Add all cleanup jobs to the scheduler
     pub async fn setup_jobs(&mut self) -> Result<()> {
-        // Testcase cleanup job
-        self.add_testcase_cleanup_job().await?;
+        // Cleanup jobs; each checks its policy's `enabled` flag when it fires
+        let schedules = &self.config.schedules;
+        self.add_cleanup_job(ArtifactClass::Testcases, &schedules.testcase_cleanup)
+            .await?;
+        self.add_cleanup_job(ArtifactClass::Temp, &schedules.temp_cleanup)
+            .await?;
+        self.add_cleanup_job(ArtifactClass::Binaries, &schedules.binary_cleanup)
+            .await?;
+        self.add_cleanup_job(ArtifactClass::Submissions, &schedules.submission_cleanup)
+            .await?;
 
-        // Temp directory cleanup job
-        self.add_temp_cleanup_job().await?;
+        // Stuck submission reconciliation job
+        self.add_reconcile_job().await?;
 
-        // Binary cleanup job
-        self.add_binary_cleanup_job().await?;
+        // Storage usage reporting job
+        self.add_usage_job().await?;
 
-        // Submission cleanup job (if enabled)
-        if self.config.schedules.submission_retention_days > 0 {
-            self.add_submission_cleanup_job().await?;
-        }
+        // Judge queue age alerting
+        self.add_queue_age_job().await?;
+
+        // Contest reminders and notification emails
+        self.add_notification_job().await?;
+
+        // Orphaned container reaping
+        self.add_reaper_job().await?;
+
+        // Practice problem recommendations
+        self.add_recommendation_job().await?;
+
+        // Problem package backups
+        self.add_backup_job().await?;
+
+        // Account deletion after the grace period
+        self.add_account_deletion_job().await?;
 
         Ok(())
     }
@@ -59,34 +96,50 @@ impl CleanupScheduler {
         Ok(())
     }
 
-    /// Add testcase cleanup job
-    async fn add_testcase_cleanup_job(&self) -> Result<()> {
+    /// Add a cleanup job for an artifact class.
+    ///
+    /// The policy is read when the job fires, so disabling a class, changing
+    /// its retention, or switching it to dry-run takes effect on the next run.
+    async fn add_cleanup_job(&self, class: ArtifactClass, cron_expr: &str) -> Result<()> {
         let config = self.config.clone();
         let db_pool = self.db_pool.clone();
-        let cron_expr = self.config.schedules.testcase_cleanup.clone();
+        let policies = self.policies.clone();
 
-        tracing::info!("Adding testcase cleanup job: {}", cron_expr);
+        tracing::info!("Adding {} cleanup job: {}", class.as_str(), cron_expr);
 
-        let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
+        let job = Job::new_async(cron_expr, move |_uuid, _lock| {
             let config = config.clone();
             let db_pool = db_pool.clone();
+            let policies = policies.clone();
 
             Box::pin(async move {
-                tracing::info!("Running testcase cleanup job");
+                let policy = policies.get(class).await;
+                if !policy.enabled {
+                    tracing::debug!("{} cleanup disabled, skipping", class.as_str());
+                    return;
+                }
+
+                tracing::info!(
+                    "Running {} cleanup job{}",
+                    class.as_str(),
+                    if policy.dry_run { " (dry run)" } else { "" }
+                );
                 let runner = CleanupRunner::new(config, db_pool);
 
-                match runner.cleanup_stale_testcases().await {
+                match runner.run(class, &policy).await {
                     Ok(stats) => {
                         tracing::info!(
-                            "Testcase cleanup: scanned={}, deleted={}, bytes_freed={}, errors={}",
+                            "{} cleanup: scanned={}, deleted={}, candidates={}, bytes_freed={}, errors={}",
+                            class.as_str(),
                             stats.files_scanned,
-                            stats.dirs_deleted,
+                            stats.files_deleted + stats.dirs_deleted,
+                            stats.candidates.len(),
                             stats.bytes_freed,
                             stats.errors
                         );
                     }
                     Err(e) => {
-                        tracing::error!("Testcase cleanup failed: {}", e);
+                        tracing::error!("{} cleanup failed: {}", class.as_str(), e);
                     }
                 }
             })
@@ -96,34 +149,35 @@ impl CleanupScheduler {
         Ok(())
     }
 
-    /// Add temp directory cleanup job
-    async fn add_temp_cleanup_job(&self) -> Result<()> {
+    /// Add stuck submission reconciliation job
+    async fn add_reconcile_job(&self) -> Result<()> {
         let config = self.config.clone();
         let db_pool = self.db_pool.clone();
-        let cron_expr = self.config.schedules.temp_cleanup.clone();
+        let cron_expr = self.config.reconcile.schedule.clone();
 
-        tracing::info!("Adding temp cleanup job: {}", cron_expr);
+        tracing::info!("Adding stuck submission reconcile job: {}", cron_expr);
 
         let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
             let config = config.clone();
             let db_pool = db_pool.clone();
 
             Box::pin(async move {
-                tracing::info!("Running temp cleanup job");
-                let runner = CleanupRunner::new(config, db_pool);
+                tracing::info!("Running stuck submission reconcile job");
+                let reconciler = Reconciler::new(config, db_pool);
 
-                match runner.cleanup_orphan_temp().await {
+                match reconciler.reconcile_stuck_submissions().await {
                     Ok(stats) => {
                         tracing::info!(
-                            "Temp cleanup: scanned={}, deleted={}, bytes_freed={}, errors={}",
-                            stats.files_scanned,
-                            stats.dirs_deleted,
-                            stats.bytes_freed,
+                            "Reconcile: scanned={}, still_running={}, requeued={}, failed={}, errors={}",
+                            stats.scanned,
+                            stats.still_running,
+                            stats.requeued,
+                            stats.failed,
                             stats.errors
                         );
                     }
                     Err(e) => {
-                        tracing::error!("Temp cleanup failed: {}", e);
+                        tracing::error!("Stuck submission reconcile failed: {}", e);
                     }
                 }
             })
@@ -133,34 +187,166 @@ impl CleanupScheduler {
         Ok(())
     }
 
-    /// Add binary cleanup job
-    async fn add_binary_cleanup_job(&self) -> Result<()> {
+    /// Add storage usage reporting job
+    async fn add_usage_job(&self) -> Result<()> {
         let config = self.config.clone();
         let db_pool = self.db_pool.clone();
-        let cron_expr = self.config.schedules.binary_cleanup.clone();
+        let cron_expr = self.config.usage.schedule.clone();
 
-        tracing::info!("Adding binary cleanup job: {}", cron_expr);
+        tracing::info!("Adding storage usage job: {}", cron_expr);
 
         let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
             let config = config.clone();
             let db_pool = db_pool.clone();
 
             Box::pin(async move {
-                tracing::info!("Running binary cleanup job");
-                let runner = CleanupRunner::new(config, db_pool);
+                tracing::info!("Running storage usage job");
+                let reporter = UsageReporter::new(config, db_pool);
 
-                match runner.cleanup_orphan_binaries().await {
+                match reporter.report_usage().await {
                     Ok(stats) => {
                         tracing::info!(
-                            "Binary cleanup: scanned={}, deleted={}, bytes_freed={}, errors={}",
-                            stats.files_scanned,
-                            stats.files_deleted,
-                            stats.bytes_freed,
+                            "Storage usage: directories={}, total_bytes={}, alerts={}, pruned={}",
+                            stats.directories,
+                            stats.total_bytes,
+                            stats.alerts,
+                            stats.snapshots_pruned
+                        );
+                    }
+                    Err(e) => {
+                        tracing::error!("Storage usage job failed: {}", e);
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        Ok(())
+    }
+
+    /// Add judge queue age alerting job
+    async fn add_queue_age_job(&self) -> Result<()> {
+        let monitor = Arc::new(QueueAgeMonitor::new(self.config.clone())?);
+        let cron_expr = self.config.queue_age.schedule.clone();
+
+        tracing::info!("Adding queue age job: {}", cron_expr);
+
+        let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
+            let monitor = monitor.clone();
+
+            Box::pin(async move {
+                match monitor.check().await {
+                    Ok(stats) if stats.degraded || stats.alerts > 0 => {
+                        tracing::info!(
+                            "Queue age: streams={}, oldest={:?}, degraded={}, alerts={}",
+                            stats.streams_checked,
+                            stats.oldest,
+                            stats.degraded,
+                            stats.alerts
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!("Queue age check failed: {}", e);
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        Ok(())
+    }
+
+    /// Add scheduled notification job.
+    ///
+    /// Reminders are raised first so those flagged for email go out in the
+    /// same run.
+    async fn add_notification_job(&self) -> Result<()> {
+        let config = self.config.clone();
+        let db_pool = self.db_pool.clone();
+        let cron_expr = self.config.notifications.schedule.clone();
+
+        let mailer = match self.config.notifications.email.clone() {
+            Some(email) => Some(Arc::new(Mailer::new(email)?)),
+            None => {
+                tracing::warn!("SMTP_URL not set, notifications will not be emailed");
+                None
+            }
+        };
+
+        tracing::info!("Adding notification job: {}", cron_expr);
+
+        let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
+            let config = config.clone();
+            let db_pool = db_pool.clone();
+            let mailer = mailer.clone();
+
+            Box::pin(async move {
+                let notifier = Notifier::new(config, db_pool.clone());
+
+                match notifier.notify_upcoming().await {
+                    Ok(stats) if stats.contest_reminders > 0 => {
+                        tracing::info!(
+                            "Notifications: contest_reminders={}",
+                            stats.contest_reminders
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!("Notification job failed: {}", e);
+                    }
+                }
+
+                let Some(mailer) = mailer else {
+                    return;
+                };
+                match mailer.deliver_pending(&db_pool).await {
+                    Ok(stats) if stats.sent + stats.failed > 0 => {
+                        tracing::info!(
+                            "Notification emails: sent={}, failed={}",
+                            stats.sent,
+                            stats.failed
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!("Notification email delivery failed: {}", e);
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        Ok(())
+    }
+
+    /// Add orphaned container reaper job
+    async fn add_reaper_job(&self) -> Result<()> {
+        let config = self.config.clone();
+        let db_pool = self.db_pool.clone();
+        let cron_expr = self.config.reaper.schedule.clone();
+
+        tracing::info!("Adding container reaper job: {}", cron_expr);
+
+        let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
+            let config = config.clone();
+            let db_pool = db_pool.clone();
+
+            Box::pin(async move {
+                let reaper = ContainerReaper::new(config, db_pool);
+
+                match reaper.reap().await {
+                    Ok(stats) => {
+                        tracing::info!(
+                            "Container reaper: seen={}, removed={}, images_pruned={}, errors={}",
+                            stats.containers_seen,
+                            stats.containers_removed,
+                            stats.images_pruned,
                             stats.errors
                         );
                     }
                     Err(e) => {
-                        tracing::error!("Binary cleanup failed: {}", e);
+                        tracing::error!("Container reaper failed: {}", e);
                     }
                 }
             })
@@ -170,34 +356,104 @@ impl CleanupScheduler {
         Ok(())
     }
 
-    /// Add submission cleanup job
-    async fn add_submission_cleanup_job(&self) -> Result<()> {
+    /// Add practice problem recommendation job
+    async fn add_recommendation_job(&self) -> Result<()> {
         let config = self.config.clone();
         let db_pool = self.db_pool.clone();
-        let cron_expr = self.config.schedules.submission_cleanup.clone();
+        let cron_expr = self.config.recommendations.schedule.clone();
 
-        tracing::info!("Adding submission cleanup job: {}", cron_expr);
+        tracing::info!("Adding recommendation job: {}", cron_expr);
 
         let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
             let config = config.clone();
             let db_pool = db_pool.clone();
 
             Box::pin(async move {
-                tracing::info!("Running submission cleanup job");
-                let runner = CleanupRunner::new(config, db_pool);
+                let recommender = Recommender::new(config, db_pool);
 
-                match runner.cleanup_old_submissions().await {
+                match recommender.recommend().await {
                     Ok(stats) => {
                         tracing::info!(
-                            "Submission cleanup: scanned={}, deleted={}, bytes_freed={}, errors={}",
-                            stats.files_scanned,
-                            stats.files_deleted,
-                            stats.bytes_freed,
+                            "Recommendations: users={}, recommendations={}",
+                            stats.users,
+                            stats.recommendations
+                        );
+                    }
+                    Err(e) => {
+                        tracing::error!("Recommendation job failed: {}", e);
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        Ok(())
+    }
+
+    /// Add problem package backup job
+    async fn add_backup_job(&self) -> Result<()> {
+        let config = self.config.clone();
+        let db_pool = self.db_pool.clone();
+        let cron_expr = self.config.backups.schedule.clone();
+
+        tracing::info!("Adding problem backup job: {}", cron_expr);
+
+        let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
+            let config = config.clone();
+            let db_pool = db_pool.clone();
+
+            Box::pin(async move {
+                let runner = BackupRunner::new(config, db_pool);
+
+                match runner.backup().await {
+                    Ok(stats) => {
+                        tracing::info!(
+                            "Problem backups: problems={}, backed_up={}, unchanged={}, pruned={}, errors={}",
+                            stats.problems,
+                            stats.backed_up,
+                            stats.unchanged,
+                            stats.pruned,
+                            stats.errors
+                        );
+                    }
+                    Err(e) => {
+                        tracing::error!("Problem backup job failed: {}", e);
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        Ok(())
+    }
+
+    /// Add scheduled account deletion job
+    async fn add_account_deletion_job(&self) -> Result<()> {
+        let config = self.config.clone();
+        let db_pool = self.db_pool.clone();
+        let cron_expr = self.config.accounts.schedule.clone();
+
+        tracing::info!("Adding account deletion job: {}", cron_expr);
+
+        let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
+            let config = config.clone();
+            let db_pool = db_pool.clone();
+
+            Box::pin(async move {
+                let deleter = AccountDeleter::new(config, db_pool);
+
+                match deleter.delete_due().await {
+                    Ok(stats) if stats.anonymized + stats.errors > 0 => {
+                        tracing::info!(
+                            "Account deletion: anonymized={}, files_removed={}, errors={}",
+                            stats.anonymized,
+                            stats.files_removed,
                             stats.errors
                         );
                     }
+                    Ok(_) => {}
                     Err(e) => {
-                        tracing::error!("Submission cleanup failed: {}", e);
+                        tracing::error!("Account deletion job failed: {}", e);
                     }
                 }
             })
@@ -3,7 +3,6 @@
 use std::path::Path;
 use std::time::{Duration, SystemTime};
 
-use anyhow::Result;
 use async_trait::async_trait;
 use sqlx::PgPool;
 use tokio::fs;
@@ -37,9 +36,6 @@ impl<'a> CleanupContext<'a> {
 pub trait CleanupSpec: Send + Sync {
     /// Check if the path satisfies this specification
     async fn is_satisfied_by(&self, ctx: &CleanupContext<'_>) -> bool;
-
-    /// Get description of this specification
-    fn description(&self) -> &'static str;
 }
 
 // ============================================================================
@@ -54,10 +50,6 @@ impl CleanupSpec for IsFile {
     async fn is_satisfied_by(&self, ctx: &CleanupContext<'_>) -> bool {
         ctx.metadata.as_ref().map(|m| m.is_file()).unwrap_or(false)
     }
-
-    fn description(&self) -> &'static str {
-        "is a file"
-    }
 }
 
 /// Specification that matches directories
@@ -68,10 +60,6 @@ impl CleanupSpec for IsDirectory {
     async fn is_satisfied_by(&self, ctx: &CleanupContext<'_>) -> bool {
         ctx.metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false)
     }
-
-    fn description(&self) -> &'static str {
-        "is a directory"
-    }
 }
 
 // ============================================================================
@@ -121,10 +109,6 @@ impl CleanupSpec for LastAccessOlderThan {
 
         false
     }
-
-    fn description(&self) -> &'static str {
-        "last access older than threshold"
-    }
 }
 
 /// Specification that matches files/dirs created before duration
@@ -138,12 +122,6 @@ impl CreatedOlderThan {
             duration: Duration::from_secs(hours * 3600),
         }
     }
-
-    pub fn days(days: u64) -> Self {
-        Self {
-            duration: Duration::from_secs(days * 24 * 3600),
-        }
-    }
 }
 
 #[async_trait]
@@ -160,10 +138,6 @@ impl CleanupSpec for CreatedOlderThan {
         }
         false
     }
-
-    fn description(&self) -> &'static str {
-        "created older than threshold"
-    }
 }
 
 // ============================================================================
@@ -197,10 +171,6 @@ impl CleanupSpec for HasActiveSubmission {
 
         false
     }
-
-    fn description(&self) -> &'static str {
-        "has active submission in database"
-    }
 }
 
 /// Specification that checks if a binary has a corresponding submission
@@ -226,9 +196,23 @@ impl CleanupSpec for HasSubmissionRecord {
 
         false
     }
+}
 
-    fn description(&self) -> &'static str {
-        "has submission record in database"
+/// Specification that checks if a path is recorded in `submission_artifacts`
+pub struct IsRecordedArtifact;
+
+#[async_trait]
+impl CleanupSpec for IsRecordedArtifact {
+    async fn is_satisfied_by(&self, ctx: &CleanupContext<'_>) -> bool {
+        let result = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM submission_artifacts WHERE path = $1)",
+        )
+        .bind(ctx.path.to_string_lossy().as_ref())
+        .fetch_one(ctx.db_pool)
+        .await;
+
+        // When in doubt, treat it as recorded so it is left alone
+        result.unwrap_or(true)
     }
 }
 
@@ -256,10 +240,6 @@ impl CleanupSpec for HasProblemRecord {
 
         false
     }
-
-    fn description(&self) -> &'static str {
-        "has problem record in database"
-    }
 }
 
 // ============================================================================
@@ -277,27 +257,6 @@ impl<A: CleanupSpec, B: CleanupSpec> CleanupSpec for And<A, B> {
     async fn is_satisfied_by(&self, ctx: &CleanupContext<'_>) -> bool {
         self.left.is_satisfied_by(ctx).await && self.right.is_satisfied_by(ctx).await
     }
-
-    fn description(&self) -> &'static str {
-        "AND combination"
-    }
-}
-
-/// OR combinator for cleanup specs
-pub struct Or<A, B> {
-    pub left: A,
-    pub right: B,
-}
-
-#[async_trait]
-impl<A: CleanupSpec, B: CleanupSpec> CleanupSpec for Or<A, B> {
-    async fn is_satisfied_by(&self, ctx: &CleanupContext<'_>) -> bool {
-        self.left.is_satisfied_by(ctx).await || self.right.is_satisfied_by(ctx).await
-    }
-
-    fn description(&self) -> &'static str {
-        "OR combination"
-    }
 }
 
 /// NOT combinator for cleanup specs
@@ -310,10 +269,6 @@ impl<A: CleanupSpec> CleanupSpec for Not<A> {
     async fn is_satisfied_by(&self, ctx: &CleanupContext<'_>) -> bool {
         !self.inner.is_satisfied_by(ctx).await
     }
-
-    fn description(&self) -> &'static str {
-        "NOT combination"
-    }
 }
 
 // ============================================================================
@@ -328,13 +283,6 @@ pub trait CleanupSpecExt: CleanupSpec + Sized {
         }
     }
 
-    fn or<B: CleanupSpec>(self, other: B) -> Or<Self, B> {
-        Or {
-            left: self,
-            right: other,
-        }
-    }
-
     fn not(self) -> Not<Self> {
         Not { inner: self }
     }
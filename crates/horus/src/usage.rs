@@ -0,0 +1,270 @@
+//! Storage usage reporting
+//!
+//! Measures each storage directory on a schedule and records one snapshot row
+//! per directory in `storage_usage_snapshots`, which backs the admin storage
+//! API. Directories with a configured budget raise a warning once usage
+//! crosses `warn_percent` of it and an error once it is exceeded; both are
+//! also published on the `storage_alerts` Redis channel.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use walkdir::WalkDir;
+
+use crate::config::Config;
+
+/// Redis pub/sub channel budget alerts are published on
+pub const STORAGE_ALERT_CHANNEL: &str = "storage_alerts";
+
+/// Usage of one storage directory
+#[derive(Debug)]
+pub struct DirectoryUsage {
+    pub category: &'static str,
+    pub bytes: u64,
+    pub file_count: u64,
+    pub budget: Option<u64>,
+}
+
+/// How close a directory is to its budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetLevel {
+    Warning,
+    Exceeded,
+}
+
+impl BudgetLevel {
+    /// Level for `bytes` against `budget`, if either threshold is crossed.
+    pub fn evaluate(bytes: u64, budget: u64, warn_percent: u64) -> Option<Self> {
+        if bytes > budget {
+            Some(Self::Exceeded)
+        } else if u128::from(bytes) * 100 >= u128::from(budget) * u128::from(warn_percent) {
+            Some(Self::Warning)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Exceeded => "exceeded",
+        }
+    }
+}
+
+/// Statistics from a usage run
+#[derive(Debug, Default)]
+pub struct UsageStats {
+    pub directories: u64,
+    pub total_bytes: u64,
+    pub alerts: u64,
+    pub snapshots_pruned: u64,
+}
+
+/// Storage usage reporter
+pub struct UsageReporter {
+    config: Arc<Config>,
+    db_pool: PgPool,
+}
+
+impl UsageReporter {
+    pub fn new(config: Arc<Config>, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Measure every storage directory, record a snapshot, and raise alerts
+    pub async fn report_usage(&self) -> Result<UsageStats> {
+        let mut stats = UsageStats::default();
+        let storage = &self.config.storage;
+        let usage_config = &self.config.usage;
+
+        let directories: Vec<(&'static str, PathBuf, Option<u64>)> = vec![
+            (
+                "submissions",
+                storage.submissions_path.clone(),
+                usage_config.submissions_budget,
+            ),
+            (
+                "binaries",
+                storage.binaries_path.clone(),
+                usage_config.binaries_budget,
+            ),
+            (
+                "problem_binaries",
+                storage.problem_binaries_path.clone(),
+                None,
+            ),
+            (
+                "testcases",
+                storage.testcases_path.clone(),
+                usage_config.testcases_budget,
+            ),
+            ("temp", storage.temp_path.clone(), None),
+            ("debug_outputs", storage.debug_outputs_path.clone(), None),
+        ];
+
+        let usage = tokio::task::spawn_blocking(move || {
+            directories
+                .into_iter()
+                .map(|(category, path, budget)| {
+                    let (bytes, file_count) = measure_directory(&path);
+                    DirectoryUsage {
+                        category,
+                        bytes,
+                        file_count,
+                        budget,
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .await?;
+
+        let categories: Vec<&str> = usage.iter().map(|u| u.category).collect();
+        let bytes: Vec<i64> = usage.iter().map(|u| u.bytes as i64).collect();
+        let file_counts: Vec<i64> = usage.iter().map(|u| u.file_count as i64).collect();
+        let budgets: Vec<Option<i64>> = usage.iter().map(|u| u.budget.map(|b| b as i64)).collect();
+
+        // One timestamp per run so the API can group a snapshot's rows
+        sqlx::query(
+            r#"
+            INSERT INTO storage_usage_snapshots (category, bytes, file_count, budget_bytes, recorded_at)
+            SELECT u.category, u.bytes, u.file_count, u.budget_bytes, NOW()
+            FROM UNNEST($1::text[], $2::bigint[], $3::bigint[], $4::bigint[])
+                AS u(category, bytes, file_count, budget_bytes)
+            "#,
+        )
+        .bind(&categories)
+        .bind(&bytes)
+        .bind(&file_counts)
+        .bind(&budgets)
+        .execute(&self.db_pool)
+        .await?;
+
+        for dir in &usage {
+            stats.directories += 1;
+            stats.total_bytes += dir.bytes;
+
+            let Some(budget) = dir.budget else {
+                continue;
+            };
+            let Some(level) = BudgetLevel::evaluate(dir.bytes, budget, usage_config.warn_percent)
+            else {
+                continue;
+            };
+
+            stats.alerts += 1;
+            match level {
+                BudgetLevel::Warning => tracing::warn!(
+                    category = dir.category,
+                    bytes = dir.bytes,
+                    budget_bytes = budget,
+                    "Storage usage above {}% of budget",
+                    usage_config.warn_percent
+                ),
+                BudgetLevel::Exceeded => tracing::error!(
+                    category = dir.category,
+                    bytes = dir.bytes,
+                    budget_bytes = budget,
+                    "Storage budget exceeded"
+                ),
+            }
+            self.publish_alert(dir, budget, level).await;
+        }
+
+        let pruned = sqlx::query(
+            "DELETE FROM storage_usage_snapshots WHERE recorded_at < NOW() - INTERVAL '1 day' * $1",
+        )
+        .bind(usage_config.retention_days as i32)
+        .execute(&self.db_pool)
+        .await?;
+        stats.snapshots_pruned = pruned.rows_affected();
+
+        Ok(stats)
+    }
+
+    /// Publish a budget alert; failures are logged and otherwise ignored.
+    async fn publish_alert(&self, dir: &DirectoryUsage, budget: u64, level: BudgetLevel) {
+        let payload = serde_json::json!({
+            "category": dir.category,
+            "level": level.as_str(),
+            "bytes": dir.bytes,
+            "budget_bytes": budget,
+        });
+
+        let publish = async {
+            let client = redis::Client::open(self.config.redis_url.as_str())?;
+            let mut conn = client.get_multiplexed_async_connection().await?;
+            redis::cmd("PUBLISH")
+                .arg(STORAGE_ALERT_CHANNEL)
+                .arg(payload.to_string())
+                .query_async::<i64>(&mut conn)
+                .await?;
+            Ok::<_, anyhow::Error>(())
+        };
+
+        if let Err(e) = publish.await {
+            tracing::warn!(
+                "Failed to publish storage alert for {}: {}",
+                dir.category,
+                e
+            );
+        }
+    }
+}
+
+/// Total size in bytes and number of files below `path` (0 if missing).
+fn measure_directory(path: &Path) -> (u64, u64) {
+    let mut bytes = 0u64;
+    let mut files = 0u64;
+    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            files += 1;
+            bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    (bytes, files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_evaluate_below_warning_threshold() {
+        assert_eq!(BudgetLevel::evaluate(0, 100 * MB, 80), None);
+        assert_eq!(BudgetLevel::evaluate(79 * MB, 100 * MB, 80), None);
+    }
+
+    #[test]
+    fn test_evaluate_warns_from_the_threshold_up_to_the_budget() {
+        assert_eq!(
+            BudgetLevel::evaluate(80 * MB, 100 * MB, 80),
+            Some(BudgetLevel::Warning)
+        );
+        assert_eq!(
+            BudgetLevel::evaluate(100 * MB, 100 * MB, 80),
+            Some(BudgetLevel::Warning)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_exceeded_over_the_budget() {
+        assert_eq!(
+            BudgetLevel::evaluate(100 * MB + 1, 100 * MB, 80),
+            Some(BudgetLevel::Exceeded)
+        );
+    }
+
+    #[test]
+    fn test_evaluate_does_not_overflow() {
+        assert_eq!(
+            BudgetLevel::evaluate(u64::MAX - 1, u64::MAX, 80),
+            Some(BudgetLevel::Warning)
+        );
+        assert_eq!(BudgetLevel::evaluate(u64::MAX / 2, u64::MAX, 80), None);
+    }
+}
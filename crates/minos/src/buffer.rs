@@ -0,0 +1,294 @@
+//! Local buffer for results that could not be saved
+//!
+//! When Postgres is unreachable after a submission was judged, the verdict
+//! is written to a file in this worker's buffer directory and the job is
+//! acknowledged, instead of failing it and judging it again until the
+//! retries run out. A background task flushes the buffer once the database
+//! is reachable again: each result is saved, announced and its file
+//! removed. Users see the verdict arrive late rather than the submission
+//! cycle through retries.
+//!
+//! Files are written under a temporary name, synced and renamed, so a crash
+//! never leaves a partial one behind. There is one file per submission; a
+//! newer result for the same submission replaces the buffered one.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::consumer::{JudgeJob, ResultWriter};
+use crate::metrics::{BUFFERED_RESULTS, BUFFERED_RESULTS_FLUSHED};
+use crate::verdict::SubmissionResult;
+
+/// How often the flusher tries to save buffered results
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Extension of buffered result files
+const RESULT_EXTENSION: &str = "json";
+
+/// Extension given to files that could not be read or saved, so they are
+/// kept for inspection without being retried forever
+const REJECTED_EXTENSION: &str = "rejected";
+
+/// A judged submission waiting to be saved
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BufferedResult {
+    pub job: JudgeJob,
+    pub result: SubmissionResult,
+    pub buffered_at: DateTime<Utc>,
+}
+
+impl BufferedResult {
+    pub fn new(job: JudgeJob, result: SubmissionResult) -> Self {
+        Self {
+            job,
+            result,
+            buffered_at: Utc::now(),
+        }
+    }
+}
+
+/// Directory of buffered results
+pub struct ResultBuffer {
+    dir: PathBuf,
+}
+
+impl ResultBuffer {
+    /// Open (creating if needed) the buffer directory
+    pub fn open(dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create result buffer {}", dir.display()))?;
+        let buffer = Self { dir };
+        BUFFERED_RESULTS.set(buffer.files()?.len() as i64);
+        Ok(buffer)
+    }
+
+    /// Durably store a result
+    pub fn push(&self, entry: &BufferedResult) -> Result<()> {
+        let path = self
+            .dir
+            .join(format!("{}.{}", entry.job.submission_id, RESULT_EXTENSION));
+        let tmp = path.with_extension("tmp");
+
+        let mut file = fs::File::create(&tmp)
+            .with_context(|| format!("Failed to create {}", tmp.display()))?;
+        file.write_all(&serde_json::to_vec(entry)?)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+
+        BUFFERED_RESULTS.set(self.files()?.len() as i64);
+        Ok(())
+    }
+
+    /// Buffered results, oldest first. Files that cannot be parsed are
+    /// set aside.
+    pub fn pending(&self) -> Result<Vec<(PathBuf, BufferedResult)>> {
+        let mut entries = Vec::new();
+        for path in self.files()? {
+            let parsed = fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| Ok(serde_json::from_slice::<BufferedResult>(&bytes)?));
+            match parsed {
+                Ok(entry) => entries.push((path, entry)),
+                Err(e) => {
+                    tracing::error!("Unreadable buffered result {}: {}", path.display(), e);
+                    self.reject(&path)?;
+                }
+            }
+        }
+        entries.sort_by_key(|(_, entry)| entry.buffered_at);
+        Ok(entries)
+    }
+
+    /// Remove a result once it is saved
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Set a result aside without retrying it
+    pub fn reject(&self, path: &Path) -> Result<()> {
+        fs::rename(path, path.with_extension(REJECTED_EXTENSION))?;
+        Ok(())
+    }
+
+    fn files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == RESULT_EXTENSION) {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Flush buffered results until shutdown
+pub async fn run_flusher(
+    buffer: Arc<ResultBuffer>,
+    writer: Arc<ResultWriter>,
+    shutdown: Arc<AtomicBool>,
+) {
+    while !shutdown.load(Ordering::SeqCst) {
+        if let Err(e) = flush(&buffer, &writer).await {
+            tracing::error!("Result buffer flush failed: {}", e);
+        }
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+    }
+}
+
+/// Save buffered results in order, stopping at the first sign the database
+/// is still unavailable
+async fn flush(buffer: &ResultBuffer, writer: &ResultWriter) -> Result<()> {
+    for (path, entry) in buffer.pending()? {
+        match writer
+            .save_results(&entry.job, &entry.result, Some(entry.buffered_at))
+            .await
+        {
+            Ok(written) => {
+                buffer.remove(&path)?;
+                BUFFERED_RESULTS_FLUSHED.inc();
+                if written {
+                    writer.announce(&entry.job, &entry.result).await;
+                    tracing::info!(
+                        "Flushed buffered result of submission {} ({}, buffered {}s)",
+                        entry.job.submission_id,
+                        entry.result.verdict.code(),
+                        (Utc::now() - entry.buffered_at).num_seconds()
+                    );
+                } else {
+                    tracing::info!(
+                        "Dropped buffered result of submission {}: judged again since",
+                        entry.job.submission_id
+                    );
+                }
+            }
+            Err(e) if is_db_unavailable(&e) => {
+                tracing::debug!(
+                    "Database still unavailable, keeping buffered results: {}",
+                    e
+                );
+                break;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to save buffered result of submission {}: {}",
+                    entry.job.submission_id,
+                    e
+                );
+                buffer.reject(&path)?;
+            }
+        }
+    }
+
+    BUFFERED_RESULTS.set(buffer.files()?.len() as i64);
+    Ok(())
+}
+
+/// Whether an error means Postgres could not be reached, as opposed to a
+/// query it rejected
+pub fn is_db_unavailable(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<sqlx::Error>() {
+        Some(
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed,
+        ) => true,
+        // Connection exceptions and server shutdown/startup
+        Some(sqlx::Error::Database(db)) => db
+            .code()
+            .is_some_and(|code| code.starts_with("08") || code.starts_with("57P")),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verdict::{TestCaseResult, Verdict};
+    use uuid::Uuid;
+
+    fn entry(submission_id: Uuid, verdict: Verdict) -> BufferedResult {
+        let testcase = match verdict {
+            Verdict::Accepted => TestCaseResult::accepted(1, 10, 1024),
+            _ => TestCaseResult::wrong_answer(1, 10, 1024, None),
+        };
+        let job = serde_json::from_value(serde_json::json!({
+            "submission_id": submission_id,
+            "user_id": Uuid::new_v4(),
+            "problem_id": Uuid::new_v4(),
+            "contest_id": null,
+            "time_limit_ms": 1000,
+            "memory_limit_kb": 262144,
+            "num_testcases": 1,
+            "max_threads": 1,
+            "network_allowed": false,
+        }))
+        .unwrap();
+        BufferedResult::new(job, SubmissionResult::from_testcases(vec![testcase], 1))
+    }
+
+    #[test]
+    fn test_push_and_pending_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = ResultBuffer::open(dir.path().join("results")).unwrap();
+
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        buffer.push(&entry(first, Verdict::Accepted)).unwrap();
+        buffer.push(&entry(second, Verdict::WrongAnswer)).unwrap();
+
+        let pending = buffer.pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].1.job.submission_id, first);
+        assert_eq!(pending[0].1.result.verdict, Verdict::Accepted);
+        assert_eq!(pending[1].1.result.verdict, Verdict::WrongAnswer);
+
+        buffer.remove(&pending[0].0).unwrap();
+        assert_eq!(buffer.pending().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_newer_result_replaces_buffered_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = ResultBuffer::open(dir.path().to_path_buf()).unwrap();
+
+        let id = Uuid::new_v4();
+        buffer.push(&entry(id, Verdict::WrongAnswer)).unwrap();
+        buffer.push(&entry(id, Verdict::Accepted)).unwrap();
+
+        let pending = buffer.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].1.result.verdict, Verdict::Accepted);
+    }
+
+    #[test]
+    fn test_unreadable_files_are_set_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = ResultBuffer::open(dir.path().to_path_buf()).unwrap();
+        fs::write(dir.path().join("broken.json"), b"{not json").unwrap();
+
+        assert!(buffer.pending().unwrap().is_empty());
+        assert!(dir.path().join("broken.rejected").exists());
+    }
+
+    #[test]
+    fn test_only_connection_errors_count_as_unavailable() {
+        assert!(is_db_unavailable(&sqlx::Error::PoolTimedOut.into()));
+        assert!(is_db_unavailable(
+            &sqlx::Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionRefused)).into()
+        ));
+        assert!(!is_db_unavailable(&sqlx::Error::RowNotFound.into()));
+        assert!(!is_db_unavailable(&anyhow::anyhow!("checker crashed")));
+    }
+}
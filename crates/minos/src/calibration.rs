@@ -0,0 +1,168 @@
+//! Startup benchmark calibration.
+//!
+//! Judge workers run on heterogeneous hardware, so raw run times are not
+//! comparable between them. At startup each worker runs a fixed, CPU-bound
+//! benchmark and derives a speed factor relative to the reference host:
+//!
+//! ```text
+//! factor = measured_ms / reference_ms      (> 1.0 means slower than reference)
+//! normalized_ms = raw_ms / factor
+//! ```
+//!
+//! Limits (TLE/MLE) are always enforced on raw measurements; the factor only
+//! affects the times reported for benchmark-scored problems.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use crate::config::CalibrationConfig;
+
+/// Factors outside this range are treated as measurement noise and clamped.
+const MIN_FACTOR: f64 = 0.25;
+const MAX_FACTOR: f64 = 4.0;
+
+/// Sieve bound for the benchmark workload.
+const SIEVE_LIMIT: usize = 2_000_000;
+
+/// Integer-mixing iterations for the benchmark workload.
+const MIX_ITERATIONS: u64 = 20_000_000;
+
+/// Speed factor of this worker relative to the reference judge host.
+#[derive(Debug, Clone, Copy)]
+pub struct Calibration {
+    /// Multiplicative speed factor (1.0 = reference speed)
+    pub factor: f64,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self { factor: 1.0 }
+    }
+}
+
+impl Calibration {
+    /// Build a calibration from a measured benchmark time.
+    pub fn from_measurement(measured_ms: f64, reference_ms: f64) -> Self {
+        let factor = if measured_ms > 0.0 && reference_ms > 0.0 {
+            (measured_ms / reference_ms).clamp(MIN_FACTOR, MAX_FACTOR)
+        } else {
+            1.0
+        };
+
+        Self { factor }
+    }
+
+    /// Run the calibration benchmark according to configuration.
+    ///
+    /// The workload runs on a blocking thread so the runtime is not stalled.
+    pub async fn run(config: &CalibrationConfig) -> Self {
+        if let Some(factor) = config.factor_override {
+            tracing::info!(factor, "Using configured calibration factor");
+            return Self {
+                factor: factor.clamp(MIN_FACTOR, MAX_FACTOR),
+            };
+        }
+
+        if !config.enabled {
+            tracing::info!("Calibration disabled, using factor 1.0");
+            return Self::default();
+        }
+
+        let rounds = config.rounds.max(1);
+        let measured = tokio::task::spawn_blocking(move || median_benchmark_ms(rounds)).await;
+
+        match measured {
+            Ok(measured_ms) => {
+                let calibration = Self::from_measurement(measured_ms, config.reference_ms);
+                tracing::info!(
+                    measured_ms = format!("{:.1}", measured_ms),
+                    reference_ms = config.reference_ms,
+                    factor = format!("{:.3}", calibration.factor),
+                    "Calibration benchmark complete"
+                );
+                calibration
+            }
+            Err(e) => {
+                tracing::warn!("Calibration benchmark failed, using factor 1.0: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Normalize a raw run time to reference-host milliseconds.
+    pub fn normalize_ms(&self, raw_ms: u64) -> u64 {
+        (raw_ms as f64 / self.factor).round() as u64
+    }
+}
+
+/// Run the benchmark `rounds` times and return the median wall time in ms.
+fn median_benchmark_ms(rounds: u32) -> f64 {
+    // Warm-up round to fault in pages and settle CPU frequency.
+    black_box(benchmark_workload());
+
+    let mut samples: Vec<f64> = (0..rounds)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(benchmark_workload());
+            start.elapsed().as_secs_f64() * 1000.0
+        })
+        .collect();
+
+    samples.sort_by(|a, b| a.total_cmp(b));
+    samples[samples.len() / 2]
+}
+
+/// Fixed CPU/memory-bound workload: a prime sieve plus an integer mix loop.
+fn benchmark_workload() -> u64 {
+    let mut composite = vec![false; SIEVE_LIMIT + 1];
+    let mut primes = 0u64;
+    for i in 2..=SIEVE_LIMIT {
+        if !composite[i] {
+            primes += 1;
+            let mut j = i * i;
+            while j <= SIEVE_LIMIT {
+                composite[j] = true;
+                j += i;
+            }
+        }
+    }
+
+    let mut x = black_box(0x9E37_79B9_7F4A_7C15u64);
+    for _ in 0..MIX_ITERATIONS {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+    }
+
+    primes ^ x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factor_from_measurement() {
+        let cal = Calibration::from_measurement(500.0, 250.0);
+        assert!((cal.factor - 2.0).abs() < f64::EPSILON);
+
+        // Out-of-range measurements are clamped
+        let cal = Calibration::from_measurement(10_000.0, 250.0);
+        assert!((cal.factor - MAX_FACTOR).abs() < f64::EPSILON);
+
+        // Degenerate input falls back to reference speed
+        let cal = Calibration::from_measurement(0.0, 250.0);
+        assert!((cal.factor - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_normalize_ms() {
+        let slow = Calibration::from_measurement(500.0, 250.0);
+        assert_eq!(slow.normalize_ms(1000), 500);
+
+        let fast = Calibration::from_measurement(125.0, 250.0);
+        assert_eq!(fast.normalize_ms(1000), 2000);
+
+        assert_eq!(Calibration::default().normalize_ms(1234), 1234);
+    }
+}
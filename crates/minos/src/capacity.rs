@@ -0,0 +1,258 @@
+//! Host capacity for submission runs.
+//!
+//! Every submission run reserves cores (its thread limit) and memory (its
+//! memory limit) from a pool sized to the host and waits while the pool is
+//! short, so runs of the judge consumer and the regeneration worker never
+//! oversubscribe the machine and distort each other's timing. A run asking
+//! for more than the whole pool reserves all of it.
+//!
+//! `EXECUTION_CORES` and `EXECUTION_MEMORY_MB` can shrink the pool but not
+//! grow it past what was detected.
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::metrics::{EXECUTIONS_WAITING, EXECUTION_CAPACITY, EXECUTION_IN_USE};
+
+/// Cores and memory available to submission runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostCapacity {
+    pub cores: u32,
+    pub memory_mb: u32,
+}
+
+impl HostCapacity {
+    /// Cores this process may run on (affinity and cgroup quota) and the
+    /// smaller of the host's memory and its cgroup's limit
+    pub fn detect() -> Self {
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get() as u32)
+            .unwrap_or(1);
+
+        let total = std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|text| parse_meminfo_mb(&text));
+        let cgroup = std::fs::read_to_string("/sys/fs/cgroup/memory.max")
+            .ok()
+            .and_then(|text| parse_memory_max_mb(&text));
+        let memory_mb = match (total, cgroup) {
+            (Some(total), Some(cgroup)) => total.min(cgroup),
+            (total, cgroup) => total.or(cgroup).unwrap_or(1024),
+        };
+
+        Self {
+            cores: cores.max(1),
+            memory_mb: memory_mb.max(1),
+        }
+    }
+
+    /// Apply configured limits. A limit above the detected capacity is
+    /// refused and the detected value kept.
+    pub fn limited(self, cores: Option<u32>, memory_mb: Option<u32>) -> Self {
+        let clamp = |resource: &str, detected: u32, configured: Option<u32>| match configured {
+            Some(value) if value > detected => {
+                tracing::warn!(
+                    configured = value,
+                    detected,
+                    "Refusing to raise execution {} beyond the detected capacity",
+                    resource
+                );
+                detected
+            }
+            Some(value) => value.max(1),
+            None => detected,
+        };
+
+        Self {
+            cores: clamp("cores", self.cores, cores),
+            memory_mb: clamp("memory", self.memory_mb, memory_mb),
+        }
+    }
+}
+
+/// `MemTotal` in MB from the contents of `/proc/meminfo`
+fn parse_meminfo_mb(meminfo: &str) -> Option<u32> {
+    let kb: u64 = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()?;
+    u32::try_from(kb / 1024).ok()
+}
+
+/// A cgroup v2 `memory.max` in MB; `None` when unlimited
+fn parse_memory_max_mb(memory_max: &str) -> Option<u32> {
+    let bytes: u64 = memory_max.trim().parse().ok()?;
+    Some(u32::try_from(bytes / (1024 * 1024)).unwrap_or(u32::MAX))
+}
+
+/// The pool of cores and memory submission runs reserve from
+pub struct ExecutionSlots {
+    capacity: HostCapacity,
+    cores: Semaphore,
+    memory: Semaphore,
+}
+
+/// Cores and memory held by a running submission, returned on drop
+pub struct SlotPermit<'a> {
+    _cores: SemaphorePermit<'a>,
+    _memory: SemaphorePermit<'a>,
+    cores: u32,
+    memory_mb: u32,
+}
+
+impl Drop for SlotPermit<'_> {
+    fn drop(&mut self) {
+        EXECUTION_IN_USE
+            .with_label_values(&["cores"])
+            .sub(self.cores.into());
+        EXECUTION_IN_USE
+            .with_label_values(&["memory_bytes"])
+            .sub(i64::from(self.memory_mb) * 1024 * 1024);
+    }
+}
+
+/// Counts a run as waiting until dropped, also when the wait is cancelled
+struct Waiting;
+
+impl Waiting {
+    fn start() -> Self {
+        EXECUTIONS_WAITING.inc();
+        Self
+    }
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        EXECUTIONS_WAITING.dec();
+    }
+}
+
+impl ExecutionSlots {
+    pub fn new(capacity: HostCapacity) -> Self {
+        EXECUTION_CAPACITY
+            .with_label_values(&["cores"])
+            .set(capacity.cores.into());
+        EXECUTION_CAPACITY
+            .with_label_values(&["memory_bytes"])
+            .set(i64::from(capacity.memory_mb) * 1024 * 1024);
+
+        Self {
+            capacity,
+            cores: Semaphore::new(capacity.cores as usize),
+            memory: Semaphore::new(capacity.memory_mb as usize),
+        }
+    }
+
+    /// Cores and MB a run with these limits reserves, at most the whole pool
+    fn demand(&self, max_threads: i32, memory_limit_kb: u64) -> (u32, u32) {
+        let cores = (max_threads.max(1) as u32).min(self.capacity.cores);
+        let memory_mb = u32::try_from(memory_limit_kb.div_ceil(1024).max(1))
+            .unwrap_or(u32::MAX)
+            .min(self.capacity.memory_mb);
+        (cores, memory_mb)
+    }
+
+    /// Wait until a run with these limits fits on the host. Cores are
+    /// always taken before memory, so waiting runs cannot deadlock.
+    pub async fn acquire(&self, max_threads: i32, memory_limit_kb: u64) -> SlotPermit<'_> {
+        let (cores, memory_mb) = self.demand(max_threads, memory_limit_kb);
+
+        let waiting = Waiting::start();
+        let core_permit = self
+            .cores
+            .acquire_many(cores)
+            .await
+            .expect("execution slots are never closed");
+        let memory_permit = self
+            .memory
+            .acquire_many(memory_mb)
+            .await
+            .expect("execution slots are never closed");
+        drop(waiting);
+
+        EXECUTION_IN_USE
+            .with_label_values(&["cores"])
+            .add(cores.into());
+        EXECUTION_IN_USE
+            .with_label_values(&["memory_bytes"])
+            .add(i64::from(memory_mb) * 1024 * 1024);
+
+        SlotPermit {
+            _cores: core_permit,
+            _memory: memory_permit,
+            cores,
+            memory_mb,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slots(cores: u32, memory_mb: u32) -> ExecutionSlots {
+        ExecutionSlots::new(HostCapacity { cores, memory_mb })
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let meminfo = "MemTotal:       16318480 kB\nMemFree:         1203344 kB\n";
+        assert_eq!(parse_meminfo_mb(meminfo), Some(15936));
+        assert_eq!(parse_meminfo_mb("MemFree: 12 kB\n"), None);
+    }
+
+    #[test]
+    fn test_parse_memory_max() {
+        assert_eq!(parse_memory_max_mb("4294967296\n"), Some(4096));
+        assert_eq!(parse_memory_max_mb("max\n"), None);
+    }
+
+    #[test]
+    fn test_limits_cannot_exceed_detected() {
+        let detected = HostCapacity {
+            cores: 8,
+            memory_mb: 16384,
+        };
+        assert_eq!(
+            detected.limited(Some(4), Some(8192)),
+            HostCapacity {
+                cores: 4,
+                memory_mb: 8192
+            }
+        );
+        assert_eq!(detected.limited(Some(32), Some(65536)), detected);
+        assert_eq!(detected.limited(None, None), detected);
+    }
+
+    #[test]
+    fn test_demand_is_capped_at_capacity() {
+        let slots = slots(4, 2048);
+        assert_eq!(slots.demand(1, 256 * 1024), (1, 256));
+        assert_eq!(slots.demand(0, 1), (1, 1));
+        assert_eq!(slots.demand(64, 8 * 1024 * 1024), (4, 2048));
+    }
+
+    #[tokio::test]
+    async fn test_runs_wait_for_capacity() {
+        let slots = slots(2, 1024);
+
+        let first = slots.acquire(2, 512 * 1024).await;
+        assert_eq!(slots.cores.available_permits(), 0);
+        assert_eq!(slots.memory.available_permits(), 512);
+
+        // No core left until the first run ends
+        let second =
+            tokio::time::timeout(std::time::Duration::from_millis(50), slots.acquire(1, 1024))
+                .await;
+        assert!(second.is_err());
+
+        drop(first);
+        let second = slots.acquire(1, 1024).await;
+        assert_eq!(slots.cores.available_permits(), 1);
+        drop(second);
+        assert_eq!(slots.memory.available_permits(), 1024);
+    }
+}
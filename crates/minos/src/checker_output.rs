@@ -0,0 +1,153 @@
+//! Cleaning checker output before it is stored.
+//!
+//! Checkers and verifiers write whatever they like: megabytes of diagnostics,
+//! terminal escape codes, NUL bytes PostgreSQL refuses in text columns, and
+//! the absolute paths of the files the judge passed them. Everything a
+//! checker said is run through [`CheckerOutputFilter::apply`] before it is
+//! written to `submission_results`, `test_reproductions` or
+//! `checker_test_results`, and so before the API returns it.
+
+use crate::config::Config;
+
+/// Stands in for the directories of a redacted path
+const REDACTED_DIR: &str = "...";
+
+/// Truncates checker output, strips control characters and optionally
+/// redacts host paths
+#[derive(Debug, Clone)]
+pub struct CheckerOutputFilter {
+    /// Bytes of output kept (0 keeps everything)
+    limit_bytes: usize,
+    /// Host directories whose paths are reduced to their file name
+    redact_roots: Vec<String>,
+}
+
+impl CheckerOutputFilter {
+    pub fn new(limit_bytes: usize, redact_roots: Vec<String>) -> Self {
+        Self {
+            limit_bytes,
+            redact_roots: redact_roots
+                .into_iter()
+                .map(|root| root.trim_end_matches('/').to_string())
+                .filter(|root| !root.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Filter for this worker: its storage directory is redacted when
+    /// `CHECKER_OUTPUT_REDACT_PATHS` is on
+    pub fn from_config(config: &Config) -> Self {
+        let redact_roots = if config.execution.redact_checker_paths {
+            vec![config.storage.base_path.to_string_lossy().into_owned()]
+        } else {
+            Vec::new()
+        };
+        Self::new(
+            config.execution.checker_output_limit_bytes as usize,
+            redact_roots,
+        )
+    }
+
+    /// Clean checker output for storage
+    pub fn apply(&self, output: &str) -> String {
+        let mut cleaned: String = output.chars().filter(|&c| is_kept(c)).collect();
+        for root in &self.redact_roots {
+            cleaned = redact_paths(&cleaned, root);
+        }
+        self.truncate(cleaned)
+    }
+
+    /// Cut output past the limit on a character boundary, noting how much
+    /// was dropped
+    fn truncate(&self, mut output: String) -> String {
+        if self.limit_bytes == 0 || output.len() <= self.limit_bytes {
+            return output;
+        }
+        let mut end = self.limit_bytes;
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        let dropped = output.len() - end;
+        output.truncate(end);
+        output.push_str(&format!("\n... [{} bytes truncated]", dropped));
+        output
+    }
+}
+
+/// Newlines and tabs are kept; other control characters (NUL, escape
+/// sequences, carriage returns) are dropped
+fn is_kept(c: char) -> bool {
+    c == '\n' || c == '\t' || !c.is_control()
+}
+
+/// Characters that end a path quoted in checker output
+fn ends_path(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '\'' | '"' | '`' | ':' | ',' | ';' | '(' | ')' | '<' | '>'
+        )
+}
+
+/// Replace every path under `root` with `.../<file name>`
+fn redact_paths(output: &str, root: &str) -> String {
+    let mut redacted = String::with_capacity(output.len());
+    let mut rest = output;
+    while let Some(start) = rest.find(root) {
+        let after = &rest[start + root.len()..];
+        // `/mnt/data` must not match inside `/mnt/database`
+        if !(after.is_empty() || after.starts_with('/') || after.starts_with(ends_path)) {
+            redacted.push_str(&rest[..start + root.len()]);
+            rest = after;
+            continue;
+        }
+        let len = after.find(ends_path).unwrap_or(after.len());
+        let path = &after[..len];
+        redacted.push_str(&rest[..start]);
+        redacted.push_str(REDACTED_DIR);
+        if let Some(name) = path.rsplit('/').next().filter(|name| !name.is_empty()) {
+            redacted.push('/');
+            redacted.push_str(name);
+        }
+        rest = &after[len..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_control_characters() {
+        let filter = CheckerOutputFilter::new(0, Vec::new());
+        assert_eq!(
+            filter.apply("wrong\0 answer\x1b[31m\r\n\tline 2"),
+            "wrong answer[31m\n\tline 2"
+        );
+    }
+
+    #[test]
+    fn test_truncates_on_char_boundary() {
+        let filter = CheckerOutputFilter::new(4, Vec::new());
+        assert_eq!(filter.apply("abc"), "abc");
+        assert_eq!(filter.apply("abcé"), "abc\n... [2 bytes truncated]");
+        assert_eq!(filter.apply("abcdef"), "abcd\n... [2 bytes truncated]");
+    }
+
+    #[test]
+    fn test_redacts_host_paths() {
+        let filter = CheckerOutputFilter::new(0, vec!["/mnt/data/".to_string()]);
+        assert_eq!(
+            filter.apply("cannot open '/mnt/data/temp/abc/output_003.txt': no such file"),
+            "cannot open '.../output_003.txt': no such file"
+        );
+        assert_eq!(
+            filter.apply("/mnt/data/testcases/p/1.in:2: expected 5"),
+            ".../1.in:2: expected 5"
+        );
+        assert_eq!(filter.apply("see /mnt/database"), "see /mnt/database");
+        assert_eq!(filter.apply("in /mnt/data"), "in ...");
+    }
+}
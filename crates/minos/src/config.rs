@@ -3,6 +3,11 @@
 use std::env;
 use std::path::PathBuf;
 
+use olympus_common::storage::StorageRoots;
+use olympus_common::worker_profile::{
+    is_valid_profile_name, profile_stream, DEFAULT_WORKER_PROFILE,
+};
+
 /// Minos configuration loaded from environment variables
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -24,6 +29,20 @@ pub struct Config {
     /// Stream name for judge jobs
     pub stream_name: String,
 
+    /// Worker profile of this instance; only jobs of problems assigned to
+    /// it are read (see `olympus_common::worker_profile`)
+    pub worker_profile: String,
+
+    /// Stream name for on-demand test input regeneration
+    pub regen_stream_name: String,
+
+    /// Idle time after which a regeneration request left pending by a
+    /// worker that died is claimed by another (milliseconds)
+    pub regen_claim_idle_ms: u64,
+
+    /// Stream name for sample runs compiled by Sisyphus
+    pub sample_stream_name: String,
+
     /// Block timeout for XREADGROUP (milliseconds)
     pub block_timeout_ms: usize,
 
@@ -38,6 +57,9 @@ pub struct Config {
 
     /// Execution limits
     pub execution: ExecutionConfig,
+
+    /// Startup benchmark calibration
+    pub calibration: CalibrationConfig,
 }
 
 /// Storage path configuration
@@ -46,9 +68,6 @@ pub struct StorageConfig {
     /// Base storage path
     pub base_path: PathBuf,
 
-    /// User binaries directory
-    pub binaries_path: PathBuf,
-
     /// Problem binaries directory (generators, checkers)
     pub problem_binaries_path: PathBuf,
 
@@ -57,25 +76,36 @@ pub struct StorageConfig {
 
     /// Temporary execution directory
     pub temp_path: PathBuf,
+
+    /// Participant outputs kept for failing tests
+    pub debug_outputs_path: PathBuf,
+
+    /// Results judged while the database was unreachable, per worker
+    pub result_buffer_path: PathBuf,
+
+    /// Storage roots contests may keep their artifacts and outputs under
+    pub storage_roots: StorageRoots,
 }
 
 /// Execution limits configuration
 #[derive(Debug, Clone)]
 pub struct ExecutionConfig {
-    /// Default time limit in milliseconds
-    pub default_time_limit_ms: u64,
+    /// Output size limit in bytes
+    pub output_limit_bytes: u64,
 
-    /// Maximum time limit in milliseconds
-    pub max_time_limit_ms: u64,
+    /// Bytes of a failing test's output kept for download
+    pub debug_output_limit_bytes: u64,
 
-    /// Default memory limit in KB
-    pub default_memory_limit_kb: u64,
+    /// Milliseconds a submission may go without using CPU time or writing
+    /// output before it is stopped as idle (0 disables the check)
+    pub idleness_limit_ms: u64,
 
-    /// Maximum memory limit in KB
-    pub max_memory_limit_kb: u64,
+    /// Wall-clock watchdog as a multiple of the time limit, for runs whose
+    /// CPU time is enforced through cgroup accounting
+    pub wall_time_factor: f64,
 
-    /// Output size limit in bytes
-    pub output_limit_bytes: u64,
+    /// Milliseconds between SIGTERM and SIGKILL when a run is stopped
+    pub kill_grace_ms: u64,
 
     /// Generator time limit in milliseconds
     pub generator_time_limit_ms: u64,
@@ -89,33 +119,98 @@ pub struct ExecutionConfig {
     /// Checker memory limit in KB
     pub checker_memory_limit_kb: u64,
 
+    /// Bytes of checker output stored per test (0 keeps everything)
+    pub checker_output_limit_bytes: u64,
+
+    /// Reduce paths under the storage directory in checker output to their
+    /// file name
+    pub redact_checker_paths: bool,
+
     /// Hard ceiling for per-problem max_threads.
     /// The value from the DB is clamped to this at execution time.
     /// Controlled by the `MAX_THREADS_LIMIT` env var (default: 64).
     pub max_threads_limit: i32,
+
+    /// Network namespace (e.g. `/run/netns/minos-egress`) that submissions
+    /// of `network_allowed` problems join. Unset keeps them isolated.
+    pub egress_netns: Option<PathBuf>,
+
+    /// Interface inside the egress namespace whose counters are used to
+    /// measure the traffic of each run
+    pub egress_interface: String,
+
+    /// Count CPU instructions of every run, not just for problems ranked by
+    /// instructions
+    pub count_instructions: bool,
+
+    /// Cores concurrent submission runs may reserve together; capped at the
+    /// detected cores (`None` = all of them)
+    pub execution_cores: Option<u32>,
+
+    /// Memory (MB) concurrent submission runs may reserve together; capped
+    /// at the detected memory (`None` = all of it)
+    pub execution_memory_mb: Option<u32>,
+}
+
+/// Startup calibration benchmark configuration
+#[derive(Debug, Clone)]
+pub struct CalibrationConfig {
+    /// Run the calibration benchmark at startup
+    pub enabled: bool,
+
+    /// Median benchmark time (ms) on the reference judge host.
+    /// A worker measuring exactly this gets a speed factor of 1.0.
+    pub reference_ms: f64,
+
+    /// Number of benchmark rounds (the median is used)
+    pub rounds: u32,
+
+    /// Fixed speed factor, skipping the benchmark entirely when set
+    pub factor_override: Option<f64>,
 }
 
 impl Config {
+    /// Base of the run streams this worker reads: its profile's
+    pub fn run_stream(&self) -> String {
+        profile_stream(&self.stream_name, &self.worker_profile)
+    }
+
     /// Load configuration from environment variables
     pub fn from_env() -> Self {
         let base_path = PathBuf::from(
             env::var("STORAGE_BASE_PATH").unwrap_or_else(|_| "/mnt/data".to_string()),
         );
+        let worker_profile =
+            env::var("WORKER_PROFILE").unwrap_or_else(|_| DEFAULT_WORKER_PROFILE.to_string());
+        assert!(
+            is_valid_profile_name(&worker_profile),
+            "WORKER_PROFILE must be lowercase letters, digits, '_' or '-'"
+        );
+        let worker_id = env::var("WORKER_ID").unwrap_or_else(|_| {
+            format!(
+                "minos_worker_{}",
+                uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
+            )
+        });
 
         Self {
             environment: env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()),
             database_url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
             redis_url: env::var("REDIS_URL")
                 .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
-            worker_id: env::var("WORKER_ID").unwrap_or_else(|_| {
-                format!(
-                    "minos_worker_{}",
-                    uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
-                )
-            }),
+            worker_id: worker_id.clone(),
             consumer_group: env::var("CONSUMER_GROUP")
                 .unwrap_or_else(|_| "minos_group".to_string()),
             stream_name: env::var("STREAM_NAME").unwrap_or_else(|_| "run_queue".to_string()),
+            worker_profile,
+            regen_stream_name: env::var("REGEN_STREAM_NAME")
+                .unwrap_or_else(|_| "testcase_regen".to_string()),
+            regen_claim_idle_ms: env::var("REGEN_CLAIM_IDLE_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(600_000),
+            sample_stream_name: env::var("SAMPLE_STREAM_NAME")
+                .unwrap_or_else(|_| "sample_run_queue".to_string()),
             block_timeout_ms: env::var("BLOCK_TIMEOUT_MS")
                 .ok()
                 .and_then(|v| v.parse().ok())
@@ -129,33 +224,38 @@ impl Config {
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(9091),
             storage: StorageConfig {
-                binaries_path: base_path.join("binaries/users"),
                 problem_binaries_path: base_path.join("binaries/problems"),
                 testcases_path: base_path.join("testcases"),
                 temp_path: base_path.join("temp"),
+                debug_outputs_path: base_path.join("debug_outputs"),
+                result_buffer_path: env::var("RESULT_BUFFER_PATH")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| base_path.join("result_buffer").join(&worker_id)),
+                storage_roots: StorageRoots::from_env(base_path.clone()),
                 base_path,
             },
             execution: ExecutionConfig {
-                default_time_limit_ms: env::var("DEFAULT_TIME_LIMIT_MS")
+                output_limit_bytes: env::var("OUTPUT_LIMIT_BYTES")
                     .ok()
                     .and_then(|v| v.parse().ok())
-                    .unwrap_or(2000),
-                max_time_limit_ms: env::var("MAX_TIME_LIMIT_MS")
+                    .unwrap_or(64 * 1024 * 1024), // 64 MB
+                debug_output_limit_bytes: env::var("DEBUG_OUTPUT_LIMIT_BYTES")
                     .ok()
                     .and_then(|v| v.parse().ok())
-                    .unwrap_or(30000),
-                default_memory_limit_kb: env::var("DEFAULT_MEMORY_LIMIT_KB")
+                    .unwrap_or(1024 * 1024), // 1 MB
+                idleness_limit_ms: env::var("IDLENESS_LIMIT_MS")
                     .ok()
                     .and_then(|v| v.parse().ok())
-                    .unwrap_or(256 * 1024), // 256 MB
-                max_memory_limit_kb: env::var("MAX_MEMORY_LIMIT_KB")
+                    .unwrap_or(1000),
+                wall_time_factor: env::var("WALL_TIME_FACTOR")
                     .ok()
                     .and_then(|v| v.parse().ok())
-                    .unwrap_or(1024 * 1024), // 1 GB
-                output_limit_bytes: env::var("OUTPUT_LIMIT_BYTES")
+                    .filter(|f: &f64| *f >= 1.0)
+                    .unwrap_or(2.0),
+                kill_grace_ms: env::var("KILL_GRACE_MS")
                     .ok()
                     .and_then(|v| v.parse().ok())
-                    .unwrap_or(64 * 1024 * 1024), // 64 MB
+                    .unwrap_or(200),
                 generator_time_limit_ms: env::var("GENERATOR_TIME_LIMIT_MS")
                     .ok()
                     .and_then(|v| v.parse().ok())
@@ -172,10 +272,48 @@ impl Config {
                     .ok()
                     .and_then(|v| v.parse().ok())
                     .unwrap_or(4 * 1024 * 1024), // 4 GB
+                checker_output_limit_bytes: env::var("CHECKER_OUTPUT_LIMIT_BYTES")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(64 * 1024), // 64 KB
+                redact_checker_paths: env::var("CHECKER_OUTPUT_REDACT_PATHS")
+                    .map(|v| v != "false" && v != "0")
+                    .unwrap_or(true),
                 max_threads_limit: env::var("MAX_THREADS_LIMIT")
                     .ok()
                     .and_then(|v| v.parse().ok())
                     .unwrap_or(64),
+                egress_netns: env::var("SUBMISSION_EGRESS_NETNS")
+                    .ok()
+                    .filter(|v| !v.is_empty())
+                    .map(PathBuf::from),
+                egress_interface: env::var("SUBMISSION_EGRESS_INTERFACE")
+                    .unwrap_or_else(|_| "egress0".to_string()),
+                count_instructions: env::var("INSTRUCTION_COUNTING")
+                    .map(|v| v == "true" || v == "1")
+                    .unwrap_or(false),
+                execution_cores: env::var("EXECUTION_CORES")
+                    .ok()
+                    .and_then(|v| v.parse().ok()),
+                execution_memory_mb: env::var("EXECUTION_MEMORY_MB")
+                    .ok()
+                    .and_then(|v| v.parse().ok()),
+            },
+            calibration: CalibrationConfig {
+                enabled: env::var("CALIBRATION_ENABLED")
+                    .map(|v| v != "false" && v != "0")
+                    .unwrap_or(true),
+                reference_ms: env::var("CALIBRATION_REFERENCE_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(250.0),
+                rounds: env::var("CALIBRATION_ROUNDS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(5),
+                factor_override: env::var("CALIBRATION_FACTOR")
+                    .ok()
+                    .and_then(|v| v.parse().ok()),
             },
         }
     }
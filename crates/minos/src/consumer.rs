@@ -5,43 +5,288 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use deadpool_redis::redis;
+use olympus_common::{JudgePriority, NotificationKind, OutputComparison};
+use olympus_rules::pipeline::{self, Pipeline};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::buffer::{self, BufferedResult, ResultBuffer};
+use crate::calibration::Calibration;
+use crate::capacity::ExecutionSlots;
+use crate::checker_output::CheckerOutputFilter;
 use crate::config::Config;
 use crate::executor::{ExecutionContext, Executor};
+use crate::integrity;
 use crate::metrics::{self, ACTIVE_JOBS, JOBS_FAILED, JOBS_PROCESSED};
-use crate::verdict::{SubmissionResult, Verdict};
+use crate::scoring::{self, ScoringPlan};
+use crate::test_limits::LimitOverrides;
+use crate::testcase::generator_seed;
+use crate::verdict::{SubmissionResult, TestCaseResult, Verdict};
+
+/// Pub/sub channel for contest standings changes.
+const LEADERBOARD_CHANNEL: &str = "leaderboard_updates";
+
+/// Redis key prefix for the admin queue pause flag (`queue_paused:<stream>`).
+const PAUSE_KEY_PREFIX: &str = "queue_paused:";
+
+/// How often to re-check the pause flag while paused (in milliseconds).
+const PAUSE_POLL_INTERVAL_MS: u64 = 2000;
 
 /// Job payload – built from stream message + database lookup.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JudgeJob {
     pub submission_id: Uuid,
+    pub user_id: Uuid,
     pub problem_id: Uuid,
     pub contest_id: Option<Uuid>,
+    /// Submitted after the contest ended: judged normally, but it does not
+    /// affect standings or performance scores.
+    #[serde(default)]
+    pub is_practice: bool,
+    /// Submitted by the contest team before the start: judged normally,
+    /// but like practice it does not affect standings or scores.
+    #[serde(default)]
+    pub is_test: bool,
     pub time_limit_ms: u64,
     pub memory_limit_kb: u64,
     pub num_testcases: i32,
+    /// Version of the problem's generator, part of every test's seed.
+    #[serde(default)]
+    pub generator_version: i32,
     /// Maximum threads the submission may use (1 = single-threaded).
     pub max_threads: i32,
     /// Whether network access is allowed during execution.
     pub network_allowed: bool,
+    /// Whether reported times are normalized by the worker calibration factor.
+    #[serde(default)]
+    pub benchmark_scored: bool,
+    /// Whether the contest ranks accepted submissions by runtime.
+    #[serde(default)]
+    pub performance_scored: bool,
+    /// Whether the contest values problems by their number of solvers.
+    #[serde(default)]
+    pub dynamic_scored: bool,
+    /// Whether the problem ranks by CPU instructions instead of runtime.
+    #[serde(default)]
+    pub rank_by_instructions: bool,
+    /// Whether tests earn weighted partial credit instead of pass/fail.
+    #[serde(default)]
+    pub partial_scoring: bool,
+    /// Judging pipeline replacing the default generate/run/check steps.
+    #[serde(default)]
+    pub pipeline: Option<Pipeline>,
+    /// Built-in comparison with the reference answer, replacing the checker.
+    #[serde(default)]
+    pub output_comparison: Option<OutputComparison>,
+    /// Tolerance of the `float` comparison.
+    #[serde(default)]
+    pub comparison_epsilon: f64,
+    /// Compiled artifact path from `submission_artifacts`, if recorded.
+    #[serde(default)]
+    pub artifact_path: Option<String>,
+    /// Storage root of the contest, when it keeps its data off the shared
+    /// volume.
+    #[serde(default)]
+    pub storage_root: Option<String>,
+    /// Time and memory limits set on individual tests and subtasks.
+    #[serde(skip)]
+    pub limit_overrides: LimitOverrides,
+    /// Organization (tenant) the submission was made under.
+    #[serde(default)]
+    pub tenant: Option<Uuid>,
+    /// Organization owning the problem, if any.
+    #[serde(default)]
+    pub problem_tenant: Option<Uuid>,
+    /// Id of the API request that queued the submission, for log correlation.
+    #[serde(default)]
+    pub request_id: Option<String>,
     #[serde(default)]
     pub retry_count: u32,
+    /// Priority stream the job was read from; retries go back to it.
+    #[serde(default)]
+    pub priority: JudgePriority,
+}
+
+impl JudgeJob {
+    /// Look up submission and problem metadata from the database.
+    pub async fn load(db_pool: &PgPool, submission_id: Uuid, retry_count: u32) -> Result<Self> {
+        let row = sqlx::query_as::<_, SubmissionProblemRow>(
+            r#"
+            SELECT s.user_id, s.problem_id, s.contest_id, s.is_practice, s.is_test,
+                   COALESCE(cp.time_limit_ms, p.time_limit_ms) AS time_limit_ms,
+                   COALESCE(cp.memory_limit_kb, p.memory_limit_kb) AS memory_limit_kb,
+                   p.num_test_cases,
+                   p.generator_version,
+                   COALESCE(cp.max_threads, p.max_threads) AS max_threads,
+                   COALESCE(cp.network_allowed, p.network_allowed) AS network_allowed,
+                   p.benchmark_scored,
+                   p.rank_by_instructions,
+                   p.partial_scoring,
+                   p.pipeline::text AS pipeline,
+                   p.output_comparison,
+                   p.comparison_epsilon,
+                   c.scoring_type,
+                   a.path AS artifact_path,
+                   s.storage_root,
+                   s.organization_id,
+                   p.organization_id AS problem_organization_id
+            FROM submissions s
+            JOIN problems p ON p.id = s.problem_id
+            LEFT JOIN contests c ON c.id = s.contest_id
+            LEFT JOIN submission_artifacts a ON a.submission_id = s.id
+            LEFT JOIN contest_problems cp
+                   ON cp.contest_id = s.contest_id AND cp.problem_id = s.problem_id
+            WHERE s.id = $1
+            "#,
+        )
+        .bind(submission_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(|| anyhow!("Submission {} not found in database", submission_id))?;
+
+        let pipeline = row
+            .pipeline
+            .as_deref()
+            .map(Pipeline::parse)
+            .transpose()
+            .map_err(|e| anyhow!("Problem {} has an unusable pipeline: {}", row.problem_id, e))?;
+        let limit_overrides = LimitOverrides::load(db_pool, row.problem_id).await?;
+
+        Ok(Self {
+            submission_id,
+            user_id: row.user_id,
+            problem_id: row.problem_id,
+            contest_id: row.contest_id,
+            is_practice: row.is_practice,
+            is_test: row.is_test,
+            time_limit_ms: row.time_limit_ms as u64,
+            memory_limit_kb: row.memory_limit_kb as u64,
+            num_testcases: row.num_test_cases,
+            generator_version: row.generator_version,
+            max_threads: row.max_threads,
+            network_allowed: row.network_allowed,
+            benchmark_scored: row.benchmark_scored,
+            performance_scored: row.scoring_type.as_deref() == Some("performance"),
+            dynamic_scored: row.scoring_type.as_deref() == Some("dynamic"),
+            rank_by_instructions: row.rank_by_instructions,
+            partial_scoring: row.partial_scoring,
+            pipeline,
+            output_comparison: row
+                .output_comparison
+                .as_deref()
+                .and_then(OutputComparison::parse),
+            comparison_epsilon: row.comparison_epsilon,
+            artifact_path: row.artifact_path,
+            storage_root: row.storage_root,
+            limit_overrides,
+            tenant: row.organization_id,
+            problem_tenant: row.problem_organization_id,
+            request_id: None,
+            retry_count,
+            priority: JudgePriority::default(),
+        })
+    }
+
+    /// Why the job may not be judged under its tenant: a problem owned by an
+    /// organization is only judged for submissions made under it.
+    pub fn tenant_violation(&self) -> Option<String> {
+        match self.problem_tenant {
+            Some(owner) if self.tenant != Some(owner) => Some(format!(
+                "Problem {} belongs to organization {}; submission {} was made under {}",
+                self.problem_id,
+                owner,
+                self.submission_id,
+                self.tenant
+                    .map(|t| format!("organization {}", t))
+                    .unwrap_or_else(|| "no organization".to_string())
+            )),
+            _ => None,
+        }
+    }
+
+    /// Whether the verdict feeds the contest standings: a contest
+    /// submission that is neither practice nor a test.
+    pub fn counts_for_standings(&self) -> bool {
+        !self.is_practice && !self.is_test
+    }
+
+    /// Contest whose problem this verdict rescores: performance and dynamic
+    /// scoring rescale every accepted submission to the problem
+    pub fn rescored_contest(&self) -> Option<Uuid> {
+        self.contest_id.filter(|_| {
+            (self.performance_scored || self.dynamic_scored) && self.counts_for_standings()
+        })
+    }
+
+    /// Whether the contest ranks this accepted submission by its instruction
+    /// count, so every test must have been counted
+    pub fn needs_instruction_counts(&self) -> bool {
+        self.performance_scored && self.rank_by_instructions
+    }
+
+    /// Execution context for running this job's compiled artifact
+    pub fn execution_context(&self) -> Result<ExecutionContext> {
+        let binary_path = self
+            .artifact_path
+            .as_deref()
+            .map(std::path::PathBuf::from)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No compiled artifact recorded for submission {}",
+                    self.submission_id
+                )
+            })?;
+
+        Ok(ExecutionContext {
+            submission_id: self.submission_id,
+            problem_id: self.problem_id,
+            time_limit_ms: self.time_limit_ms,
+            memory_limit_kb: self.memory_limit_kb,
+            num_testcases: self.num_testcases,
+            generator_version: self.generator_version,
+            max_threads: self.max_threads,
+            network_allowed: self.network_allowed,
+            partial_scoring: self.partial_scoring,
+            count_instructions: self.rank_by_instructions,
+            pipeline: self.pipeline.clone(),
+            output_comparison: self.output_comparison,
+            comparison_epsilon: self.comparison_epsilon,
+            binary_path,
+            storage_root: self.storage_root.clone(),
+            limit_overrides: self.limit_overrides.clone(),
+        })
+    }
 }
 
 /// Row returned by the submission+problem DB lookup.
 #[derive(Debug, sqlx::FromRow)]
 struct SubmissionProblemRow {
+    user_id: Uuid,
     problem_id: Uuid,
     contest_id: Option<Uuid>,
+    is_practice: bool,
+    is_test: bool,
     time_limit_ms: i32,
     memory_limit_kb: i32,
     num_test_cases: i32,
+    generator_version: i32,
     max_threads: i32,
     network_allowed: bool,
+    benchmark_scored: bool,
+    rank_by_instructions: bool,
+    partial_scoring: bool,
+    pipeline: Option<String>,
+    output_comparison: Option<String>,
+    comparison_epsilon: f64,
+    scoring_type: Option<String>,
+    artifact_path: Option<String>,
+    storage_root: Option<String>,
+    organization_id: Option<Uuid>,
+    problem_organization_id: Option<Uuid>,
 }
 
 /// Judge consumer that processes jobs from Redis Stream
@@ -51,17 +296,25 @@ pub struct JudgeConsumer {
     redis_pool: deadpool_redis::Pool,
     shutdown: Arc<AtomicBool>,
     executor: Executor,
+    calibration: Calibration,
+    writer: Arc<ResultWriter>,
+    buffer: Arc<ResultBuffer>,
 }
 
 impl JudgeConsumer {
     /// Create a new judge consumer
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: Config,
         db_pool: PgPool,
         redis_pool: deadpool_redis::Pool,
         shutdown: Arc<AtomicBool>,
+        calibration: Calibration,
+        writer: Arc<ResultWriter>,
+        buffer: Arc<ResultBuffer>,
+        slots: Arc<ExecutionSlots>,
     ) -> Self {
-        let executor = Executor::new(config.storage.clone(), config.execution.clone());
+        let executor = Executor::new(config.storage.clone(), config.execution.clone(), slots);
 
         Self {
             config,
@@ -69,36 +322,41 @@ impl JudgeConsumer {
             redis_pool,
             shutdown,
             executor,
+            calibration,
+            writer,
+            buffer,
         }
     }
 
-    /// Initialize consumer group
+    /// Initialize consumer groups on every priority stream
     pub async fn initialize(&self) -> Result<()> {
         let mut conn = self.redis_pool.get().await?;
 
-        // Create consumer group (ignore error if already exists)
-        let result: Result<(), redis::RedisError> = redis::cmd("XGROUP")
-            .arg("CREATE")
-            .arg(&self.config.stream_name)
-            .arg(&self.config.consumer_group)
-            .arg("$")
-            .arg("MKSTREAM")
-            .query_async(&mut *conn)
-            .await;
-
-        match result {
-            Ok(_) => {
-                tracing::info!(
-                    "Created consumer group '{}' on stream '{}'",
-                    self.config.consumer_group,
-                    self.config.stream_name
-                );
-            }
-            Err(e) if e.to_string().contains("BUSYGROUP") => {
-                tracing::debug!("Consumer group already exists");
-            }
-            Err(e) => {
-                return Err(anyhow!("Failed to create consumer group: {}", e));
+        for stream in JudgePriority::streams(&self.config.run_stream()) {
+            // Create consumer group (ignore error if already exists)
+            let result: Result<(), redis::RedisError> = redis::cmd("XGROUP")
+                .arg("CREATE")
+                .arg(&stream)
+                .arg(&self.config.consumer_group)
+                .arg("$")
+                .arg("MKSTREAM")
+                .query_async(&mut *conn)
+                .await;
+
+            match result {
+                Ok(_) => {
+                    tracing::info!(
+                        "Created consumer group '{}' on stream '{}'",
+                        self.config.consumer_group,
+                        stream
+                    );
+                }
+                Err(e) if e.to_string().contains("BUSYGROUP") => {
+                    tracing::debug!("Consumer group already exists on '{}'", stream);
+                }
+                Err(e) => {
+                    return Err(anyhow!("Failed to create consumer group: {}", e));
+                }
             }
         }
 
@@ -108,9 +366,10 @@ impl JudgeConsumer {
     /// Run the consumer loop
     pub async fn run(&mut self) -> Result<()> {
         tracing::info!(
-            "Starting judge consumer '{}' in group '{}'",
+            "Starting judge consumer '{}' in group '{}' with worker profile '{}'",
             self.config.worker_id,
-            self.config.consumer_group
+            self.config.consumer_group,
+            self.config.worker_profile
         );
 
         // First, claim any pending messages that may have been abandoned.
@@ -159,30 +418,32 @@ impl JudgeConsumer {
     async fn claim_pending_messages(&self) -> Result<()> {
         let mut conn = self.redis_pool.get().await?;
 
-        // Get pending messages older than 60 seconds
-        let pending: Vec<(String, String, u64, u64)> = redis::cmd("XPENDING")
-            .arg(&self.config.stream_name)
-            .arg(&self.config.consumer_group)
-            .arg("-")
-            .arg("+")
-            .arg(10)
-            .query_async(&mut *conn)
-            .await
-            .unwrap_or_default();
-
-        for (message_id, _consumer, idle_time, _delivery_count) in pending {
-            // Claim messages idle for more than 60 seconds
-            if idle_time > 60000 {
-                tracing::info!("Claiming abandoned message: {}", message_id);
-
-                let _: Result<(), _> = redis::cmd("XCLAIM")
-                    .arg(&self.config.stream_name)
-                    .arg(&self.config.consumer_group)
-                    .arg(&self.config.worker_id)
-                    .arg(60000) // Min idle time
-                    .arg(&message_id)
-                    .query_async(&mut *conn)
-                    .await;
+        for stream in JudgePriority::streams(&self.config.run_stream()) {
+            // Get pending messages older than 60 seconds
+            let pending: Vec<(String, String, u64, u64)> = redis::cmd("XPENDING")
+                .arg(&stream)
+                .arg(&self.config.consumer_group)
+                .arg("-")
+                .arg("+")
+                .arg(10)
+                .query_async(&mut *conn)
+                .await
+                .unwrap_or_default();
+
+            for (message_id, _consumer, idle_time, _delivery_count) in pending {
+                // Claim messages idle for more than 60 seconds
+                if idle_time > 60000 {
+                    tracing::info!("Claiming abandoned message: {} on {}", message_id, stream);
+
+                    let _: Result<(), _> = redis::cmd("XCLAIM")
+                        .arg(&stream)
+                        .arg(&self.config.consumer_group)
+                        .arg(&self.config.worker_id)
+                        .arg(60000) // Min idle time
+                        .arg(&message_id)
+                        .query_async(&mut *conn)
+                        .await;
+                }
             }
         }
 
@@ -193,104 +454,210 @@ impl JudgeConsumer {
     async fn process_next_job(&self) -> Result<bool> {
         let mut conn = self.redis_pool.get().await?;
 
-        // Read from stream with consumer group
-        let result: Vec<redis::Value> = redis::cmd("XREADGROUP")
-            .arg("GROUP")
-            .arg(&self.config.consumer_group)
-            .arg(&self.config.worker_id)
-            .arg("COUNT")
-            .arg(1)
-            .arg("BLOCK")
-            .arg(self.config.block_timeout_ms)
-            .arg("STREAMS")
-            .arg(&self.config.stream_name)
-            .arg(">")
+        // Honour the admin pause flag before taking new work; pausing the
+        // run queue pauses the streams of every worker profile
+        let paused: bool = redis::cmd("EXISTS")
+            .arg(format!("{}{}", PAUSE_KEY_PREFIX, self.config.stream_name))
             .query_async(&mut *conn)
             .await?;
+        if paused {
+            tracing::debug!("Run queue paused, not reading new jobs");
+            tokio::time::sleep(Duration::from_millis(PAUSE_POLL_INTERVAL_MS)).await;
+            return Ok(false);
+        }
 
+        let result = self.read_next_message(&mut conn).await?;
         if result.is_empty() {
             return Ok(false);
         }
 
         // Parse minimal fields from the stream message
-        let (message_id, submission_id, retry_count) = self.parse_stream_message(&result)?;
-
-        // Look up problem_id and limits from the database
-        let job = self.load_job_from_db(submission_id, retry_count).await?;
-
-        tracing::info!(
-            "Processing job for submission {} (problem: {}, message: {})",
-            job.submission_id,
-            job.problem_id,
-            message_id
+        let (message_id, submission_id, retry_count, priority, request_id) =
+            self.parse_stream_message(&result)?;
+
+        // Everything logged for the job carries the id of the request that
+        // queued it
+        let span = tracing::info_span!(
+            "judge_job",
+            submission_id = %submission_id,
+            request_id = request_id.as_deref().unwrap_or_default(),
         );
+        async move {
+            // Look up problem_id and limits from the database
+            let mut job = JudgeJob::load(&self.db_pool, submission_id, retry_count).await?;
+            job.priority = priority;
+            job.request_id = request_id;
 
-        ACTIVE_JOBS.inc();
+            tracing::info!(
+                tenant = ?job.tenant,
+                priority = %job.priority,
+                "Processing job for submission {} (problem: {}, message: {})",
+                job.submission_id,
+                job.problem_id,
+                message_id
+            );
 
-        // Process the job
-        let result = self.judge_submission(&job).await;
+            // Organization problems are judged only for their own tenant;
+            // retrying cannot change that
+            if let Some(violation) = job.tenant_violation() {
+                JOBS_FAILED.inc();
+                self.send_to_dead_letter(&job, &violation).await?;
+                self.ack_message(&job, &message_id).await?;
+                return Ok(true);
+            }
 
-        ACTIVE_JOBS.dec();
+            ACTIVE_JOBS.inc();
+
+            // Process the job
+            let result = self.judge_submission(&job).await;
+
+            ACTIVE_JOBS.dec();
+
+            match result {
+                Ok(submission_result) => {
+                    // Update database with results. If Postgres is unreachable
+                    // the verdict is kept on disk and flushed once it is back,
+                    // instead of judging the submission again.
+                    match self
+                        .writer
+                        .save_results(&job, &submission_result, None)
+                        .await
+                    {
+                        Ok(_) => {
+                            // Notify live scoreboards and the author (best-effort)
+                            self.writer.announce(&job, &submission_result).await;
+                        }
+                        Err(e) if buffer::is_db_unavailable(&e) => {
+                            tracing::warn!(
+                                "Database unavailable, buffering result of submission {}: {}",
+                                job.submission_id,
+                                e
+                            );
+                            self.buffer.push(&BufferedResult::new(
+                                job.clone(),
+                                submission_result.clone(),
+                            ))?;
+                        }
+                        Err(e) => return Err(e),
+                    }
 
-        match result {
-            Ok(submission_result) => {
-                // Update database with results
-                self.save_results(&job, &submission_result).await?;
+                    // Record metrics
+                    JOBS_PROCESSED.inc();
+                    metrics::record_verdict(submission_result.verdict.code());
 
-                // Record metrics
-                JOBS_PROCESSED.inc();
-                metrics::record_verdict(submission_result.verdict.code());
+                    // Acknowledge the message
+                    self.ack_message(&job, &message_id).await?;
 
-                // Acknowledge the message
-                self.ack_message(&message_id).await?;
+                    tracing::info!(
+                        "Submission {} judged: {} ({}/{} passed)",
+                        job.submission_id,
+                        submission_result.verdict.code(),
+                        submission_result.passed_count,
+                        submission_result.total_count
+                    );
+                }
+                Err(e) if e.to_string() == "QUEUE_PENDING" => {
+                    // Binaries not ready — submission marked as queue_pending.
+                    // ACK the message without retry or dead-letter.
+                    self.ack_message(&job, &message_id).await?;
+
+                    tracing::info!(
+                        "Submission {} deferred (queue_pending) — binaries not ready",
+                        job.submission_id,
+                    );
+                }
+                Err(e) => {
+                    tracing::error!("Failed to judge submission {}: {}", job.submission_id, e);
+                    JOBS_FAILED.inc();
+
+                    // Handle retry or dead letter
+                    if job.retry_count < self.config.max_retries {
+                        self.retry_job(&job, &e.to_string()).await?;
+                    } else {
+                        self.send_to_dead_letter(&job, &e.to_string()).await?;
+                    }
 
-                tracing::info!(
-                    "Submission {} judged: {} ({}/{} passed)",
-                    job.submission_id,
-                    submission_result.verdict.code(),
-                    submission_result.passed_count,
-                    submission_result.total_count
-                );
-            }
-            Err(e) if e.to_string() == "QUEUE_PENDING" => {
-                // Binaries not ready — submission marked as queue_pending.
-                // ACK the message without retry or dead-letter.
-                self.ack_message(&message_id).await?;
-
-                tracing::info!(
-                    "Submission {} deferred (queue_pending) — binaries not ready",
-                    job.submission_id,
-                );
+                    // Acknowledge original message
+                    self.ack_message(&job, &message_id).await?;
+                }
             }
-            Err(e) => {
-                tracing::error!("Failed to judge submission {}: {}", job.submission_id, e);
-                JOBS_FAILED.inc();
 
-                // Handle retry or dead letter
-                if job.retry_count < self.config.max_retries {
-                    self.retry_job(&job, &e.to_string()).await?;
-                } else {
-                    self.send_to_dead_letter(&job, &e.to_string()).await?;
-                }
+            Ok(true)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Read the next message, draining higher priority streams first.
+    ///
+    /// Each stream is polled without blocking, highest priority first. Only
+    /// when all are empty does the worker block on all of them at once;
+    /// Redis then replies with the single stream that received an entry.
+    async fn read_next_message(
+        &self,
+        conn: &mut deadpool_redis::Connection,
+    ) -> Result<Vec<redis::Value>> {
+        let streams = JudgePriority::streams(&self.config.run_stream());
+
+        for stream in &streams {
+            let result: Vec<redis::Value> = redis::cmd("XREADGROUP")
+                .arg("GROUP")
+                .arg(&self.config.consumer_group)
+                .arg(&self.config.worker_id)
+                .arg("COUNT")
+                .arg(1)
+                .arg("STREAMS")
+                .arg(stream)
+                .arg(">")
+                .query_async(&mut **conn)
+                .await?;
 
-                // Acknowledge original message
-                self.ack_message(&message_id).await?;
+            if !result.is_empty() {
+                return Ok(result);
             }
         }
 
-        Ok(true)
+        let result: Vec<redis::Value> = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(&self.config.consumer_group)
+            .arg(&self.config.worker_id)
+            .arg("COUNT")
+            .arg(1)
+            .arg("BLOCK")
+            .arg(self.config.block_timeout_ms)
+            .arg("STREAMS")
+            .arg(&streams)
+            .arg(vec![">"; streams.len()])
+            .query_async(&mut **conn)
+            .await?;
+
+        Ok(result)
     }
 
     /// Parse Redis stream message into minimal fields.
     /// Only `submission_id` is required from the stream; all other job
-    /// metadata is looked up from the database via `load_job_from_db`.
-    fn parse_stream_message(&self, result: &[redis::Value]) -> Result<(String, Uuid, u32)> {
+    /// metadata is looked up from the database via `JudgeJob::load`.
+    /// The priority comes from the name of the stream it was read from;
+    /// `request_id` is set for submissions queued through the API.
+    fn parse_stream_message(
+        &self,
+        result: &[redis::Value],
+    ) -> Result<(String, Uuid, u32, JudgePriority, Option<String>)> {
         // XREADGROUP returns: [[stream_name, [[message_id, [field, value, ...]]]]]
         let stream_data = match result.first() {
             Some(redis::Value::Array(data)) => data,
             _ => return Err(anyhow!("Invalid stream response format")),
         };
 
+        let priority = match stream_data.first() {
+            Some(redis::Value::BulkString(name)) => JudgePriority::from_stream_name(
+                &self.config.run_stream(),
+                &String::from_utf8_lossy(name),
+            )
+            .unwrap_or_default(),
+            _ => return Err(anyhow!("Invalid stream name")),
+        };
+
         let messages = match stream_data.get(1) {
             Some(redis::Value::Array(msgs)) => msgs,
             _ => return Err(anyhow!("No messages in response")),
@@ -332,54 +699,22 @@ impl JudgeConsumer {
             .and_then(|v| v.parse().ok())
             .unwrap_or(0);
 
-        Ok((message_id, submission_id, retry_count))
-    }
-
-    /// Look up submission and problem metadata from the database.
-    async fn load_job_from_db(&self, submission_id: Uuid, retry_count: u32) -> Result<JudgeJob> {
-        let row = sqlx::query_as::<_, SubmissionProblemRow>(
-            r#"
-            SELECT s.problem_id, s.contest_id,
-                   COALESCE(cp.time_limit_ms, p.time_limit_ms) AS time_limit_ms,
-                   COALESCE(cp.memory_limit_kb, p.memory_limit_kb) AS memory_limit_kb,
-                   p.num_test_cases,
-                   COALESCE(cp.max_threads, p.max_threads) AS max_threads,
-                   COALESCE(cp.network_allowed, p.network_allowed) AS network_allowed
-            FROM submissions s
-            JOIN problems p ON p.id = s.problem_id
-            LEFT JOIN contest_problems cp
-                   ON cp.contest_id = s.contest_id AND cp.problem_id = s.problem_id
-            WHERE s.id = $1
-            "#,
-        )
-        .bind(submission_id)
-        .fetch_optional(&self.db_pool)
-        .await?
-        .ok_or_else(|| anyhow!("Submission {} not found in database", submission_id))?;
+        let request_id = field_map.remove("request_id");
 
-        Ok(JudgeJob {
-            submission_id,
-            problem_id: row.problem_id,
-            contest_id: row.contest_id,
-            time_limit_ms: row.time_limit_ms as u64,
-            memory_limit_kb: row.memory_limit_kb as u64,
-            num_testcases: row.num_test_cases,
-            max_threads: row.max_threads,
-            network_allowed: row.network_allowed,
-            retry_count,
-        })
+        Ok((message_id, submission_id, retry_count, priority, request_id))
     }
 
     /// Judge a submission
     async fn judge_submission(&self, job: &JudgeJob) -> Result<SubmissionResult> {
-        // Check if generator and checker binaries exist for this problem.
-        // If either is missing, the problem is not yet ready for judging.
+        // Check if the binaries the problem is judged with exist (generator
+        // and checker or reference, or those its pipeline runs). If any is missing, the
+        // problem is not yet ready for judging.
         // Mark submission as "queue_pending" and return without error.
-        if !self.problem_binaries_ready(job.problem_id).await {
+        if !self.problem_binaries_ready(job).await {
             tracing::info!(
                 submission_id = %job.submission_id,
                 problem_id = %job.problem_id,
-                "Problem binaries (generator/checker) not ready — setting queue_pending"
+                "Problem binaries not ready — setting queue_pending"
             );
 
             sqlx::query("UPDATE submissions SET status = 'queue_pending' WHERE id = $1")
@@ -392,99 +727,81 @@ impl JudgeConsumer {
             return Err(anyhow!("QUEUE_PENDING"));
         }
 
+        // A damaged generator, checker or reference would judge the
+        // submission wrongly; fail it clearly instead
+        let binaries_path = &self.executor.storage_config().problem_binaries_path;
+        if let Some(problem) =
+            integrity::check_problem_binaries(&self.db_pool, binaries_path, job).await?
+        {
+            tracing::error!(
+                submission_id = %job.submission_id,
+                problem_id = %job.problem_id,
+                "Problem failed its integrity check: {}",
+                problem
+            );
+            return Ok(SubmissionResult::from_testcases(
+                vec![TestCaseResult::problem_integrity_error(1, problem)],
+                job.num_testcases,
+            ));
+        }
+
+        let ctx = job.execution_context()?;
+
         // Update status to judging
         sqlx::query("UPDATE submissions SET status = 'judging', judged_at = NOW() WHERE id = $1")
             .bind(job.submission_id)
             .execute(&self.db_pool)
             .await?;
 
-        let ctx = ExecutionContext {
-            submission_id: job.submission_id,
-            problem_id: job.problem_id,
-            contest_id: job.contest_id,
-            time_limit_ms: job.time_limit_ms,
-            memory_limit_kb: job.memory_limit_kb,
-            num_testcases: job.num_testcases,
-            max_threads: job.max_threads,
-            network_allowed: job.network_allowed,
-        };
-
         // Execute and judge
-        let result = self.executor.execute(&ctx).await?;
+        let mut result = self.executor.execute(&ctx).await?;
 
-        // Record execution metrics
+        // Record execution metrics (raw, as measured on this worker)
         metrics::record_execution(
             &job.problem_id.to_string(),
             result.max_time_ms as f64 / 1000.0,
             result.max_memory_kb * 1024,
         );
+        if ctx.network_allowed {
+            let network_bytes = result
+                .testcase_results
+                .iter()
+                .filter_map(|tc| tc.network_bytes)
+                .sum();
+            metrics::record_network(&job.problem_id.to_string(), network_bytes);
+        }
 
-        Ok(result)
-    }
+        // Ranking by instructions needs every test counted; another worker
+        // may have working perf counters
+        if job.needs_instruction_counts()
+            && result.verdict == Verdict::Accepted
+            && result.total_instructions.is_none()
+        {
+            return Err(anyhow!(
+                "Instruction counts unavailable on worker {}",
+                self.config.worker_id
+            ));
+        }
 
-    /// Save judging results to database
-    async fn save_results(&self, job: &JudgeJob, result: &SubmissionResult) -> Result<()> {
-        // Update submission status
-        sqlx::query(
-            r#"
-            UPDATE submissions 
-            SET status = $1, 
-                score = $2,
-                max_time_ms = $3,
-                max_memory_kb = $4,
-                passed_test_cases = $5,
-                total_test_cases = $6,
-                judged_at = NOW()
-            WHERE id = $7
-            "#,
-        )
-        .bind(result.verdict.to_db_string())
-        .bind(result.score)
-        .bind(result.max_time_ms as i64)
-        .bind(result.max_memory_kb as i64)
-        .bind(result.passed_count)
-        .bind(result.total_count)
-        .bind(job.submission_id)
-        .execute(&self.db_pool)
-        .await?;
+        // Limits were enforced on raw times; only the reported times are normalized
+        if job.benchmark_scored {
+            result.apply_calibration(&self.calibration);
+        }
 
-        // Insert individual test case results (skip any Pending placeholders)
-        for tc in &result.testcase_results {
-            if tc.verdict == Verdict::Pending {
-                continue;
-            }
-            sqlx::query(
-                r#"
-                INSERT INTO submission_results 
-                (submission_id, test_case_number, verdict, time_ms, memory_kb, checker_output)
-                VALUES ($1, $2, $3, $4, $5, $6)
-                ON CONFLICT (submission_id, test_case_number) 
-                DO UPDATE SET 
-                    verdict = EXCLUDED.verdict,
-                    time_ms = EXCLUDED.time_ms,
-                    memory_kb = EXCLUDED.memory_kb,
-                    checker_output = EXCLUDED.checker_output
-                "#,
-            )
-            .bind(job.submission_id)
-            .bind(tc.testcase_number)
-            .bind(tc.verdict.to_db_string())
-            .bind(tc.time_ms as i64)
-            .bind(tc.memory_kb as i64)
-            .bind(tc.error_message.clone())
-            .execute(&self.db_pool)
-            .await?;
+        if job.partial_scoring {
+            let plan = ScoringPlan::load(&self.db_pool, job.problem_id).await?;
+            result.apply_partial_scoring(&plan);
         }
 
-        Ok(())
+        Ok(result)
     }
 
-    /// Acknowledge a message
-    async fn ack_message(&self, message_id: &str) -> Result<()> {
+    /// Acknowledge a message on the stream the job was read from
+    async fn ack_message(&self, job: &JudgeJob, message_id: &str) -> Result<()> {
         let mut conn = self.redis_pool.get().await?;
 
         redis::cmd("XACK")
-            .arg(&self.config.stream_name)
+            .arg(job.priority.stream_name(&self.config.run_stream()))
             .arg(&self.config.consumer_group)
             .arg(message_id)
             .query_async::<i64>(&mut *conn)
@@ -505,9 +822,9 @@ impl JudgeConsumer {
             error
         );
 
-        // Add back to stream with incremented retry count
-        redis::cmd("XADD")
-            .arg(&self.config.stream_name)
+        // Add back to its priority stream with incremented retry count
+        let mut xadd = redis::cmd("XADD");
+        xadd.arg(job.priority.stream_name(&self.config.run_stream()))
             .arg("*")
             .arg("submission_id")
             .arg(job.submission_id.to_string())
@@ -522,9 +839,11 @@ impl JudgeConsumer {
             .arg("num_testcases")
             .arg(job.num_testcases.to_string())
             .arg("retry_count")
-            .arg((job.retry_count + 1).to_string())
-            .query_async::<String>(&mut *conn)
-            .await?;
+            .arg((job.retry_count + 1).to_string());
+        if let Some(ref request_id) = job.request_id {
+            xadd.arg("request_id").arg(request_id);
+        }
+        xadd.query_async::<String>(&mut *conn).await?;
 
         Ok(())
     }
@@ -540,8 +859,8 @@ impl JudgeConsumer {
         );
 
         // Add to dead letter stream
-        redis::cmd("XADD")
-            .arg("run_queue_dlq")
+        let mut xadd = redis::cmd("XADD");
+        xadd.arg(format!("{}_dlq", self.config.stream_name))
             .arg("*")
             .arg("submission_id")
             .arg(job.submission_id.to_string())
@@ -555,8 +874,12 @@ impl JudgeConsumer {
             .arg(job.retry_count.to_string())
             .arg("failed_at")
             .arg(chrono::Utc::now().to_rfc3339())
-            .query_async::<String>(&mut *conn)
-            .await?;
+            .arg("worker_profile")
+            .arg(&self.config.worker_profile);
+        if let Some(ref request_id) = job.request_id {
+            xadd.arg("request_id").arg(request_id);
+        }
+        xadd.query_async::<String>(&mut *conn).await?;
 
         // Update submission status to system_error
         sqlx::query(
@@ -576,17 +899,599 @@ impl JudgeConsumer {
         Ok(())
     }
 
-    /// Check whether both generator and checker binaries exist for a problem.
-    async fn problem_binaries_ready(&self, problem_id: Uuid) -> bool {
+    /// Check whether every problem binary the job is judged with exists.
+    async fn problem_binaries_ready(&self, job: &JudgeJob) -> bool {
         let base = self
             .executor
             .storage_config()
             .problem_binaries_path
-            .join(problem_id.to_string());
+            .join(job.problem_id.to_string());
 
-        let generator_exists = base.join("generator").exists();
-        let checker_exists = base.join("checker").exists();
+        pipeline::required_binaries(job.pipeline.as_ref(), job.output_comparison.is_some())
+            .into_iter()
+            .filter_map(|program| program.binary_name())
+            .all(|name| base.join(name).exists())
+    }
+}
 
-        generator_exists && checker_exists
+/// Writes judging results to the database and announces them. Shared by
+/// the consumer and the result buffer flusher.
+pub struct ResultWriter {
+    db_pool: PgPool,
+    redis_pool: deadpool_redis::Pool,
+    worker_id: String,
+    checker_output: CheckerOutputFilter,
+}
+
+impl ResultWriter {
+    pub fn new(
+        db_pool: PgPool,
+        redis_pool: deadpool_redis::Pool,
+        worker_id: String,
+        checker_output: CheckerOutputFilter,
+    ) -> Self {
+        Self {
+            db_pool,
+            redis_pool,
+            worker_id,
+            checker_output,
+        }
+    }
+
+    /// Save judging results to database.
+    ///
+    /// The verdict, the per-test results and the rescoring it causes are
+    /// written in one transaction. A result buffered at `buffered_at` is
+    /// skipped only when it is stale: a verdict judged after it was buffered
+    /// exists, or the submission went back to compiling for a rejudge. It
+    /// still replaces the `system_error` the reconciler gives a submission
+    /// that looked stuck during the outage. Returns whether the result was
+    /// written.
+    pub async fn save_results(
+        &self,
+        job: &JudgeJob,
+        result: &SubmissionResult,
+        buffered_at: Option<DateTime<Utc>>,
+    ) -> Result<bool> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let rescored_contest = job.rescored_contest();
+        if let Some(contest_id) = rescored_contest {
+            lock_contest_problem(&mut tx, contest_id, job.problem_id).await?;
+        }
+
+        // Update submission status
+        let updated = sqlx::query(
+            r#"
+            UPDATE submissions 
+            SET status = $1, 
+                score = $2,
+                max_time_ms = $3,
+                max_memory_kb = $4,
+                passed_test_cases = $5,
+                total_test_cases = $6,
+                raw_max_time_ms = $7,
+                calibration_factor = $8,
+                judged_by = $9,
+                total_instructions = $11,
+                judged_at = NOW()
+            WHERE id = $10
+              AND ($12::timestamptz IS NULL
+                   OR (status NOT IN ('pending', 'compiling')
+                       AND (judged_at IS NULL OR judged_at < $12
+                            OR status = 'system_error')))
+            "#,
+        )
+        .bind(result.verdict.to_db_string())
+        .bind(result.score)
+        .bind(result.max_time_ms as i64)
+        .bind(result.max_memory_kb as i64)
+        .bind(result.passed_count)
+        .bind(result.total_count)
+        .bind(result.raw_max_time_ms.map(|t| t as i64))
+        .bind(result.calibration_factor)
+        .bind(&self.worker_id)
+        .bind(job.submission_id)
+        .bind(result.total_instructions.map(|i| i as i64))
+        .bind(buffered_at)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !updated {
+            return Ok(false);
+        }
+
+        // Insert individual test case results (skip any Pending placeholders)
+        for tc in &result.testcase_results {
+            if tc.verdict == Verdict::Pending {
+                continue;
+            }
+            let retained = result
+                .retained_outputs
+                .iter()
+                .find(|r| r.testcase_number == tc.testcase_number);
+            sqlx::query(
+                r#"
+                INSERT INTO submission_results 
+                (submission_id, test_case_number, verdict, time_ms, memory_kb, checker_output, raw_time_ms, checker_score,
+                 generator_seed, generator_version, output_path, output_size_bytes, output_truncated, network_bytes,
+                 instructions, time_limit_ms, memory_limit_kb)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                ON CONFLICT (submission_id, test_case_number) 
+                DO UPDATE SET 
+                    verdict = EXCLUDED.verdict,
+                    time_ms = EXCLUDED.time_ms,
+                    raw_time_ms = EXCLUDED.raw_time_ms,
+                    memory_kb = EXCLUDED.memory_kb,
+                    checker_output = EXCLUDED.checker_output,
+                    checker_score = EXCLUDED.checker_score,
+                    generator_seed = EXCLUDED.generator_seed,
+                    generator_version = EXCLUDED.generator_version,
+                    output_path = EXCLUDED.output_path,
+                    output_size_bytes = EXCLUDED.output_size_bytes,
+                    output_truncated = EXCLUDED.output_truncated,
+                    network_bytes = EXCLUDED.network_bytes,
+                    instructions = EXCLUDED.instructions,
+                    time_limit_ms = EXCLUDED.time_limit_ms,
+                    memory_limit_kb = EXCLUDED.memory_limit_kb
+                "#,
+            )
+            .bind(job.submission_id)
+            .bind(tc.testcase_number)
+            .bind(tc.verdict.to_db_string())
+            .bind(tc.time_ms as i64)
+            .bind(tc.memory_kb as i64)
+            .bind(
+                tc.error_message
+                    .as_deref()
+                    .map(|m| self.checker_output.apply(m)),
+            )
+            .bind(tc.raw_time_ms.map(|t| t as i64))
+            .bind(tc.checker_score)
+            .bind(generator_seed(
+                job.problem_id,
+                tc.testcase_number,
+                job.generator_version,
+            ))
+            .bind(job.generator_version)
+            .bind(retained.map(|r| r.path.to_string_lossy().to_string()))
+            .bind(retained.map(|r| r.size_bytes as i64))
+            .bind(retained.is_some_and(|r| r.truncated))
+            .bind(tc.network_bytes.map(|b| b as i64))
+            .bind(tc.instructions.map(|i| i as i64))
+            .bind(tc.time_limit_ms.map(|ms| ms as i32))
+            .bind(tc.memory_limit_kb.map(|kb| kb as i32))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(contest_id) = rescored_contest {
+            rescore_problem(&mut tx, job, contest_id).await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Publish the verdict to live scoreboards and tell the author
+    /// (best-effort).
+    pub async fn announce(&self, job: &JudgeJob, result: &SubmissionResult) {
+        self.publish_leaderboard_update(job, result).await;
+        self.notify_verdict(job, result).await;
+    }
+
+    /// Publish a standings change for live contest submissions (not practice or tests).
+    ///
+    /// Vanguard relays these to leaderboard SSE subscribers. `rescored` tells
+    /// clients that other rows may have changed too (performance and
+    /// dynamic scoring).
+    async fn publish_leaderboard_update(&self, job: &JudgeJob, result: &SubmissionResult) {
+        let Some(contest_id) = job.contest_id.filter(|_| job.counts_for_standings()) else {
+            return;
+        };
+
+        let payload = serde_json::json!({
+            "contest_id": contest_id,
+            "user_id": job.user_id,
+            "problem_id": job.problem_id,
+            "submission_id": job.submission_id,
+            "verdict": result.verdict.to_db_string(),
+            "rescored": job.dynamic_scored || job.performance_scored,
+        });
+
+        let publish = async {
+            let mut conn = self.redis_pool.get().await?;
+            redis::cmd("PUBLISH")
+                .arg(LEADERBOARD_CHANNEL)
+                .arg(payload.to_string())
+                .query_async::<i64>(&mut *conn)
+                .await?;
+            Ok::<_, anyhow::Error>(())
+        };
+
+        if let Err(e) = publish.await {
+            tracing::warn!(
+                "Failed to publish leaderboard update for submission {}: {}",
+                job.submission_id,
+                e
+            );
+        }
+    }
+
+    /// Add a verdict notification to the author's feed (and email, if they
+    /// asked for it). Bulk rejudges are skipped so they do not flood feeds.
+    async fn notify_verdict(&self, job: &JudgeJob, result: &SubmissionResult) {
+        if job.priority == JudgePriority::Low {
+            return;
+        }
+
+        let notify = sqlx::query(
+            r#"
+            SELECT notify_user($1, $2, p.title || ': ' || $3, $4, $5, $6)
+            FROM problems p
+            WHERE p.id = $7
+            "#,
+        )
+        .bind(job.user_id)
+        .bind(NotificationKind::VerdictReady.as_str())
+        .bind(result.verdict.code())
+        .bind(format!(
+            "{}/{} tests passed",
+            result.passed_count, result.total_count
+        ))
+        .bind(job.contest_id)
+        .bind(job.submission_id)
+        .bind(job.problem_id)
+        .execute(&self.db_pool)
+        .await;
+
+        if let Err(e) = notify {
+            tracing::warn!(
+                "Failed to notify author of submission {}: {}",
+                job.submission_id,
+                e
+            );
+        }
+    }
+}
+
+/// Rescore the problem of a job in `rescored_contest`.
+///
+/// Not only accepted verdicts: a rejudge that takes the fastest submission
+/// away leaves the others scaled against a stale best, and one that takes
+/// an accepted verdict away changes the solver count. The transaction must
+/// hold the lock of `lock_contest_problem`.
+async fn rescore_problem(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    job: &JudgeJob,
+    contest_id: Uuid,
+) -> Result<()> {
+    if job.performance_scored {
+        rescore_performance(tx, contest_id, job.problem_id, job.rank_by_instructions).await
+    } else {
+        rescore_dynamic(tx, contest_id, job.problem_id).await
+    }
+}
+
+/// Recompute runtime-relative scores for a performance-scored problem.
+///
+/// The fastest accepted submission gets 100 points and every other
+/// accepted submission is scaled by `best_time / its_time`. Only rows
+/// whose score actually changes are touched, so a non-best submission
+/// updates just itself while a new best rescales the whole problem.
+///
+/// With `by_instructions` the cost is `total_instructions` instead of
+/// `max_time_ms`; submissions judged without counts keep their score. The
+/// transaction must hold the lock of `lock_contest_problem`.
+async fn rescore_performance(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contest_id: Uuid,
+    problem_id: Uuid,
+    by_instructions: bool,
+) -> Result<()> {
+    let updated = sqlx::query(
+        r#"
+        WITH measured AS (
+            SELECT id, GREATEST(cost, 1) AS cost
+            FROM (
+                SELECT id,
+                       CASE WHEN $3 THEN total_instructions ELSE max_time_ms END AS cost
+                FROM submissions
+                WHERE contest_id = $1 AND problem_id = $2 AND status = 'accepted'
+                  AND NOT is_practice AND NOT is_test
+            ) s
+            WHERE cost IS NOT NULL
+        ),
+        best AS (
+            SELECT MIN(cost) AS best_cost FROM measured
+        ),
+        rescored AS (
+            SELECT m.id,
+                   ROUND(100.0 * best.best_cost / m.cost)::INTEGER AS new_score
+            FROM measured m, best
+            WHERE best.best_cost IS NOT NULL
+        )
+        UPDATE submissions s
+        SET score = r.new_score
+        FROM rescored r
+        WHERE s.id = r.id AND s.score IS DISTINCT FROM r.new_score
+        "#,
+    )
+    .bind(contest_id)
+    .bind(problem_id)
+    .bind(by_instructions)
+    .execute(&mut **tx)
+    .await?;
+
+    tracing::debug!(
+        contest_id = %contest_id,
+        problem_id = %problem_id,
+        by_instructions,
+        rows = updated.rows_affected(),
+        "Rescored performance problem"
+    );
+
+    Ok(())
+}
+
+/// Lock a contest problem's row until the transaction ends.
+///
+/// Verdicts saved concurrently for a rescored problem then rescore one after
+/// the other: each reads the solvers (or the best cost) only once the
+/// previous one has committed, so the last scores written reflect every
+/// accepted submission.
+/// Taken before the submission row is updated, so every transaction locks
+/// in the same order.
+async fn lock_contest_problem(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contest_id: Uuid,
+    problem_id: Uuid,
+) -> Result<()> {
+    sqlx::query(
+        "SELECT 1 FROM contest_problems WHERE contest_id = $1 AND problem_id = $2 FOR UPDATE",
+    )
+    .bind(contest_id)
+    .bind(problem_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Recompute the value of a problem in a dynamic-scoring contest.
+///
+/// Every accepted submission to the problem scores its current value
+/// (see `scoring::dynamic_value`), based on how many participants have
+/// solved it. Only rows whose score changes are touched. The transaction
+/// must hold the lock of `lock_contest_problem`.
+async fn rescore_dynamic(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contest_id: Uuid,
+    problem_id: Uuid,
+) -> Result<()> {
+    let (points, solvers, min_percent, decay): (i32, i64, i32, i32) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(cp.max_score, p.max_score),
+               (SELECT COUNT(DISTINCT s.user_id) FROM submissions s
+                WHERE s.contest_id = $1 AND s.problem_id = $2
+                  AND s.status = 'accepted' AND NOT s.is_practice AND NOT s.is_test),
+               c.dynamic_min_percent,
+               c.dynamic_decay
+        FROM contest_problems cp
+        JOIN problems p ON p.id = cp.problem_id
+        JOIN contests c ON c.id = cp.contest_id
+        WHERE cp.contest_id = $1 AND cp.problem_id = $2
+        "#,
+    )
+    .bind(contest_id)
+    .bind(problem_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let value = scoring::dynamic_value(points, solvers, min_percent, decay);
+    let updated = sqlx::query(
+        r#"
+        UPDATE submissions
+        SET score = $3
+        WHERE contest_id = $1 AND problem_id = $2 AND status = 'accepted'
+          AND NOT is_practice AND NOT is_test AND score IS DISTINCT FROM $3
+        "#,
+    )
+    .bind(contest_id)
+    .bind(problem_id)
+    .bind(value as f64)
+    .execute(&mut **tx)
+    .await?;
+
+    tracing::debug!(
+        contest_id = %contest_id,
+        problem_id = %problem_id,
+        solvers,
+        value,
+        rows = updated.rows_affected(),
+        "Rescored dynamic problem"
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(tenant: Option<Uuid>, problem_tenant: Option<Uuid>) -> JudgeJob {
+        serde_json::from_value(serde_json::json!({
+            "submission_id": Uuid::new_v4(),
+            "user_id": Uuid::new_v4(),
+            "problem_id": Uuid::new_v4(),
+            "time_limit_ms": 1000,
+            "memory_limit_kb": 262144,
+            "num_testcases": 10,
+            "max_threads": 1,
+            "network_allowed": false,
+            "tenant": tenant,
+            "problem_tenant": problem_tenant,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_problems_without_an_organization_judge_for_anyone() {
+        assert!(job(None, None).tenant_violation().is_none());
+        assert!(job(Some(Uuid::new_v4()), None).tenant_violation().is_none());
+    }
+
+    #[test]
+    fn test_organization_problems_judge_for_their_tenant_only() {
+        let org = Uuid::new_v4();
+        assert!(job(Some(org), Some(org)).tenant_violation().is_none());
+        assert!(job(None, Some(org)).tenant_violation().is_some());
+        assert!(job(Some(Uuid::new_v4()), Some(org))
+            .tenant_violation()
+            .is_some());
+    }
+
+    #[test]
+    fn test_instruction_counts_needed_only_when_performance_scored() {
+        let mut job = job(None, None);
+        job.rank_by_instructions = true;
+        assert!(!job.needs_instruction_counts());
+
+        job.performance_scored = true;
+        assert!(job.needs_instruction_counts());
+
+        job.rank_by_instructions = false;
+        assert!(!job.needs_instruction_counts());
+    }
+
+    #[test]
+    fn test_only_standings_verdicts_of_rescored_contests_rescore() {
+        let contest = Uuid::new_v4();
+        let mut job = job(None, None);
+        job.contest_id = Some(contest);
+        assert_eq!(job.rescored_contest(), None);
+
+        job.performance_scored = true;
+        assert_eq!(job.rescored_contest(), Some(contest));
+
+        job.performance_scored = false;
+        job.dynamic_scored = true;
+        assert_eq!(job.rescored_contest(), Some(contest));
+
+        job.is_practice = true;
+        assert_eq!(job.rescored_contest(), None);
+    }
+
+    /// Pool on a fresh schema holding just the rows rescoring touches, from
+    /// `TEST_DATABASE_URL`. `None` (the test is skipped) when it is unset.
+    async fn rescoring_db() -> Option<(PgPool, String)> {
+        use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+        use std::str::FromStr;
+
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        let schema = format!("rescore_{}", Uuid::new_v4().simple());
+        let admin = PgPool::connect(&url).await.unwrap();
+        sqlx::query(&format!("CREATE SCHEMA {}", schema))
+            .execute(&admin)
+            .await
+            .unwrap();
+
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new().connect_with(options).await.unwrap();
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE contest_problems (
+                contest_id UUID NOT NULL,
+                problem_id UUID NOT NULL,
+                PRIMARY KEY (contest_id, problem_id)
+            );
+            CREATE TABLE submissions (
+                id UUID PRIMARY KEY,
+                contest_id UUID,
+                problem_id UUID NOT NULL,
+                status VARCHAR(20) NOT NULL,
+                score DOUBLE PRECISION,
+                max_time_ms BIGINT,
+                total_instructions BIGINT,
+                is_practice BOOLEAN NOT NULL DEFAULT FALSE,
+                is_test BOOLEAN NOT NULL DEFAULT FALSE
+            );
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        Some((pool, schema))
+    }
+
+    /// Accept a submission with `max_time_ms` and rescore as `save_results`
+    /// does, holding the transaction open for a while before committing.
+    async fn accept_and_rescore(pool: PgPool, job: JudgeJob, submission_id: Uuid, time_ms: i64) {
+        let contest_id = job.rescored_contest().unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        lock_contest_problem(&mut tx, contest_id, job.problem_id)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE submissions SET status = 'accepted', max_time_ms = $2 WHERE id = $1")
+            .bind(submission_id)
+            .bind(time_ms)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        rescore_problem(&mut tx, &job, contest_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_performance_rescores_see_each_others_best() {
+        let Some((pool, schema)) = rescoring_db().await else {
+            return;
+        };
+        let contest = Uuid::new_v4();
+        let mut job = job(None, None);
+        job.contest_id = Some(contest);
+        job.performance_scored = true;
+        sqlx::query("INSERT INTO contest_problems (contest_id, problem_id) VALUES ($1, $2)")
+            .bind(contest)
+            .bind(job.problem_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let (slow, fast) = (Uuid::new_v4(), Uuid::new_v4());
+        for id in [slow, fast] {
+            sqlx::query(
+                "INSERT INTO submissions (id, contest_id, problem_id, status) VALUES ($1, $2, $3, 'judging')",
+            )
+            .bind(id)
+            .bind(contest)
+            .bind(job.problem_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Without the lock each transaction would scale against only its
+        // own submission and both would score 100
+        tokio::join!(
+            accept_and_rescore(pool.clone(), job.clone(), slow, 200),
+            accept_and_rescore(pool.clone(), job.clone(), fast, 100),
+        );
+
+        let score = |id: Uuid| {
+            sqlx::query_scalar::<_, Option<f64>>("SELECT score FROM submissions WHERE id = $1")
+                .bind(id)
+                .fetch_one(&pool)
+        };
+        assert_eq!(score(slow).await.unwrap(), Some(50.0));
+        assert_eq!(score(fast).await.unwrap(), Some(100.0));
+
+        sqlx::query(&format!("DROP SCHEMA {} CASCADE", schema))
+            .execute(&pool)
+            .await
+            .unwrap();
     }
 }
@@ -0,0 +1,338 @@
+//! Built-in output comparison for problems without a custom checker.
+//!
+//! A problem with `output_comparison` set is judged by comparing the
+//! submission's output with the answer its reference binary writes for the
+//! same input, in one of four modes:
+//!
+//! - `exact`: byte for byte
+//! - `tokens`: the same whitespace-separated tokens
+//! - `float`: tokens, where two numbers match within an absolute or
+//!   relative epsilon
+//! - `case_insensitive`: tokens, ignoring ASCII case
+//!
+//! Comments follow testlib's wording so verdict details read the same as
+//! with a custom checker.
+//!
+//! Sample runs also show where an output first differs from the expected
+//! one, line by line ([`first_difference`]).
+
+use olympus_common::OutputComparison;
+
+use crate::testcase::CheckerResult;
+
+/// Longest token or line quoted in a comment
+const MAX_EXCERPT_CHARS: usize = 32;
+
+/// Longest line quoted in a [`LineDifference`]
+const MAX_DIFF_LINE_CHARS: usize = 256;
+
+/// The first line where an output differs from the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineDifference {
+    /// Line number (1-indexed)
+    pub line: usize,
+    /// Expected line; `None` past the end of the expected output
+    pub expected: Option<String>,
+    /// Line found; `None` past the end of the output
+    pub actual: Option<String>,
+}
+
+/// Compare a submission's output with the reference answer
+pub fn compare(mode: OutputComparison, epsilon: f64, output: &str, answer: &str) -> CheckerResult {
+    match mode {
+        OutputComparison::Exact => compare_exact(output, answer),
+        OutputComparison::Tokens => compare_tokens(output, answer, |a, b| a == b),
+        OutputComparison::Float => {
+            compare_tokens(output, answer, |a, b| floats_match(a, b, epsilon))
+        }
+        OutputComparison::CaseInsensitive => {
+            compare_tokens(output, answer, |a, b| a.eq_ignore_ascii_case(b))
+        }
+    }
+}
+
+fn compare_exact(output: &str, answer: &str) -> CheckerResult {
+    if output == answer {
+        return CheckerResult::Accepted(format!("ok {} lines", answer.lines().count()));
+    }
+
+    let mut found_lines = output.lines();
+    for (index, expected) in answer.lines().enumerate() {
+        match found_lines.next() {
+            Some(found) if found == expected => {}
+            Some(found) => {
+                return CheckerResult::WrongAnswer(format!(
+                    "{} line differs - expected: '{}', found: '{}'",
+                    ordinal(index + 1),
+                    excerpt(expected),
+                    excerpt(found)
+                ))
+            }
+            None => {
+                return CheckerResult::WrongAnswer(format!(
+                    "Unexpected end of output - expected {} lines, found {}",
+                    answer.lines().count(),
+                    index
+                ))
+            }
+        }
+    }
+    if found_lines.next().is_some() {
+        return CheckerResult::WrongAnswer(format!(
+            "Extra output - expected {} lines, found {}",
+            answer.lines().count(),
+            output.lines().count()
+        ));
+    }
+
+    // Same lines, different line endings or final newline
+    CheckerResult::WrongAnswer("Output differs in line endings or the final newline".to_string())
+}
+
+fn compare_tokens(
+    output: &str,
+    answer: &str,
+    matches: impl Fn(&str, &str) -> bool,
+) -> CheckerResult {
+    let mut found_tokens = output.split_whitespace();
+    let mut count = 0;
+    for expected in answer.split_whitespace() {
+        count += 1;
+        match found_tokens.next() {
+            Some(found) if matches(found, expected) => {}
+            Some(found) => {
+                return CheckerResult::WrongAnswer(format!(
+                    "{} token differs - expected: '{}', found: '{}'",
+                    ordinal(count),
+                    excerpt(expected),
+                    excerpt(found)
+                ))
+            }
+            None => {
+                return CheckerResult::WrongAnswer(format!(
+                    "Unexpected end of output - expected {} tokens, found {}",
+                    answer.split_whitespace().count(),
+                    count - 1
+                ))
+            }
+        }
+    }
+    if found_tokens.next().is_some() {
+        return CheckerResult::WrongAnswer(format!(
+            "Extra output - expected {} tokens, found {}",
+            count,
+            output.split_whitespace().count()
+        ));
+    }
+
+    CheckerResult::Accepted(format!("ok {} tokens", count))
+}
+
+/// Tokens match if equal, or if both are numbers no further apart than
+/// `epsilon` absolutely or relative to the expected value
+fn floats_match(found: &str, expected: &str, epsilon: f64) -> bool {
+    if found == expected {
+        return true;
+    }
+    match (found.parse::<f64>(), expected.parse::<f64>()) {
+        (Ok(found), Ok(expected)) if found.is_finite() && expected.is_finite() => {
+            let diff = (found - expected).abs();
+            diff <= epsilon || diff <= epsilon * expected.abs()
+        }
+        _ => false,
+    }
+}
+
+/// First line where `output` and `answer` differ, ignoring trailing
+/// whitespace on each line and trailing blank lines; `None` if they agree
+pub fn first_difference(output: &str, answer: &str) -> Option<LineDifference> {
+    let lines = |text: &str| -> Vec<String> {
+        let mut lines: Vec<String> = text.lines().map(|l| l.trim_end().to_string()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    };
+    let found = lines(output);
+    let expected = lines(answer);
+
+    (0..found.len().max(expected.len()))
+        .find(|&i| found.get(i) != expected.get(i))
+        .map(|i| LineDifference {
+            line: i + 1,
+            expected: expected.get(i).map(|l| truncate(l, MAX_DIFF_LINE_CHARS)),
+            actual: found.get(i).map(|l| truncate(l, MAX_DIFF_LINE_CHARS)),
+        })
+}
+
+fn excerpt(s: &str) -> String {
+    truncate(s, MAX_EXCERPT_CHARS)
+}
+
+/// `s` cut to `max` characters, marked with `...` when cut
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let cut: String = s.chars().take(max).collect();
+        format!("{}...", cut)
+    }
+}
+
+/// `1st`, `2nd`, `3rd`, `4th`, ..., `11th`, `21st`
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accepted(result: CheckerResult) -> bool {
+        matches!(result, CheckerResult::Accepted(_))
+    }
+
+    fn comment(result: CheckerResult) -> String {
+        match result {
+            CheckerResult::WrongAnswer(comment) => comment,
+            other => panic!("expected a wrong answer, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_exact_requires_identical_bytes() {
+        assert!(accepted(compare(
+            OutputComparison::Exact,
+            0.0,
+            "1 2\n3\n",
+            "1 2\n3\n"
+        )));
+        assert_eq!(
+            comment(compare(
+                OutputComparison::Exact,
+                0.0,
+                "1 2\n4\n",
+                "1 2\n3\n"
+            )),
+            "2nd line differs - expected: '3', found: '4'"
+        );
+        assert_eq!(
+            comment(compare(OutputComparison::Exact, 0.0, "1 2\n3", "1 2\n3\n")),
+            "Output differs in line endings or the final newline"
+        );
+        assert!(
+            comment(compare(OutputComparison::Exact, 0.0, "1\n", "1\n2\n"))
+                .starts_with("Unexpected end of output")
+        );
+    }
+
+    #[test]
+    fn test_tokens_ignore_whitespace() {
+        assert!(accepted(compare(
+            OutputComparison::Tokens,
+            0.0,
+            "1   2\r\n3",
+            "1 2\n3\n"
+        )));
+        assert_eq!(
+            comment(compare(OutputComparison::Tokens, 0.0, "1 2 3 4", "1 2 3")),
+            "Extra output - expected 3 tokens, found 4"
+        );
+        assert_eq!(
+            comment(compare(OutputComparison::Tokens, 0.0, "YES", "yes")),
+            "1st token differs - expected: 'yes', found: 'YES'"
+        );
+    }
+
+    #[test]
+    fn test_float_epsilon() {
+        let eps = 1e-6;
+        assert!(accepted(compare(
+            OutputComparison::Float,
+            eps,
+            "0.3333333 x",
+            "0.333333333 x"
+        )));
+        // Relative to large values
+        assert!(accepted(compare(
+            OutputComparison::Float,
+            eps,
+            "1000000000.5",
+            "1000000000"
+        )));
+        assert!(!accepted(compare(
+            OutputComparison::Float,
+            eps,
+            "0.334",
+            "0.333"
+        )));
+        assert!(!accepted(compare(OutputComparison::Float, eps, "nan", "0")));
+        // Non-numeric tokens compare exactly
+        assert!(!accepted(compare(
+            OutputComparison::Float,
+            eps,
+            "Yes",
+            "yes"
+        )));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert!(accepted(compare(
+            OutputComparison::CaseInsensitive,
+            0.0,
+            "YES\nNo",
+            "yes no"
+        )));
+        assert!(!accepted(compare(
+            OutputComparison::CaseInsensitive,
+            0.0,
+            "yes",
+            "no"
+        )));
+    }
+
+    #[test]
+    fn test_first_difference() {
+        assert_eq!(first_difference("1 2  \n3\n\n", "1 2\n3"), None);
+        assert_eq!(
+            first_difference("1 2\n4\n", "1 2\n3\n"),
+            Some(LineDifference {
+                line: 2,
+                expected: Some("3".to_string()),
+                actual: Some("4".to_string()),
+            })
+        );
+        assert_eq!(
+            first_difference("1\n", "1\n2\n"),
+            Some(LineDifference {
+                line: 2,
+                expected: Some("2".to_string()),
+                actual: None,
+            })
+        );
+        assert_eq!(
+            first_difference("1\n2\n", "1\n").map(|d| (d.line, d.expected)),
+            Some((2, None))
+        );
+    }
+
+    #[test]
+    fn test_ordinals() {
+        let names: Vec<String> = [1, 2, 3, 4, 11, 12, 13, 21, 102, 111]
+            .into_iter()
+            .map(ordinal)
+            .collect();
+        assert_eq!(
+            names,
+            ["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "102nd", "111th"]
+        );
+    }
+}
@@ -1,20 +1,62 @@
 //! Sandboxed executor for user submissions
 
 use std::os::unix::fs::PermissionsExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
 use std::time::Instant;
 
 use anyhow::{anyhow, Result};
+use nix::sys::signal::Signal;
+use olympus_common::storage::{DEBUG_OUTPUTS_DIR, USER_BINARIES_DIR};
+use olympus_common::OutputComparison;
+use olympus_rules::pipeline::{Pipeline, PipelineStep, StepProgram};
 use tokio::fs;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
-use tokio::time::{timeout, Duration};
+use tokio::time::Duration;
 use uuid::Uuid;
 
+use crate::capacity::ExecutionSlots;
 use crate::config::{ExecutionConfig, StorageConfig};
-use crate::sandbox::Sandbox;
-use crate::testcase::{CheckerResult, TestCase, TestCaseManager};
-use crate::verdict::{SubmissionResult, TestCaseResult, Verdict};
+use crate::default_checker;
+use crate::network::EgressNetwork;
+use crate::perf::InstructionCounter;
+use crate::sandbox::{ResourceUsage, Sandbox, SandboxedOutput};
+use crate::test_limits::{LimitOverrides, TestLimits};
+use crate::testcase::{generator_seed, CheckerResult, TestCase, TestCaseManager};
+use crate::verdict::{RetainedOutput, SubmissionResult, TestCaseResult, TimeLimitKind, Verdict};
+
+/// Most failing-test outputs kept per submission (partial scoring runs every
+/// test, so a submission can fail many)
+const MAX_RETAINED_OUTPUTS: usize = 5;
+
+/// How often a running submission is checked for CPU time and idleness
+const IDLE_POLL_INTERVAL_MS: u64 = 100;
+
+/// Wall-clock allowance over the time limit when CPU time cannot be measured
+const WALL_GRACE_MS: u64 = 100;
+
+/// What a debug run keeps besides the result, each part cut to the
+/// requested capture limit
+#[derive(Debug, Default)]
+pub struct DebugCapture {
+    pub stdout: String,
+    pub stderr: String,
+    /// What the program wrote to its output file
+    pub output: String,
+    /// The checker's comment
+    pub checker_log: Option<String>,
+    /// Whether any part was cut at the limit
+    pub truncated: bool,
+}
+
+/// Standard streams of a run that exited on its own
+#[derive(Debug, Default)]
+struct RunStreams {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
 
 /// Execution context for a submission
 pub struct ExecutionContext {
@@ -22,18 +64,48 @@ pub struct ExecutionContext {
     pub submission_id: Uuid,
     /// Problem ID
     pub problem_id: Uuid,
-    /// Contest ID (None for standalone submissions)
-    pub contest_id: Option<Uuid>,
     /// Time limit in milliseconds
     pub time_limit_ms: u64,
     /// Memory limit in KB
     pub memory_limit_kb: u64,
     /// Number of test cases
     pub num_testcases: i32,
+    /// Version of the problem's generator, part of every test's seed
+    pub generator_version: i32,
     /// Maximum number of threads the submission may spawn (1 = single-threaded)
     pub max_threads: i32,
     /// Whether the submission is allowed network access during execution
     pub network_allowed: bool,
+    /// Built-in comparison with the reference answer replacing the checker
+    /// (ignored with a pipeline, which runs its own checker step)
+    pub output_comparison: Option<OutputComparison>,
+    /// Tolerance of the `float` comparison
+    pub comparison_epsilon: f64,
+    /// Whether every test is run and scored (instead of stopping at the first failure)
+    pub partial_scoring: bool,
+    /// Whether CPU instructions must be counted (the problem is ranked by them)
+    pub count_instructions: bool,
+    /// Judging pipeline replacing the default generate/run/check steps
+    pub pipeline: Option<Pipeline>,
+    /// Compiled artifact recorded by Sisyphus in `submission_artifacts`
+    pub binary_path: PathBuf,
+    /// Storage root of the contest (None for the shared volume)
+    pub storage_root: Option<String>,
+    /// Limits set on individual tests and subtasks
+    pub limit_overrides: LimitOverrides,
+}
+
+impl ExecutionContext {
+    /// Limits test `number` runs under: its own or its subtask's, if set
+    pub fn limits_for(&self, number: i32) -> TestLimits {
+        self.limit_overrides.apply(
+            number,
+            TestLimits {
+                time_limit_ms: self.time_limit_ms,
+                memory_limit_kb: self.memory_limit_kb,
+            },
+        )
+    }
 }
 
 /// Sandboxed executor
@@ -41,19 +113,35 @@ pub struct Executor {
     storage: StorageConfig,
     execution: ExecutionConfig,
     testcase_manager: TestCaseManager,
+    egress: Option<EgressNetwork>,
+    /// Host capacity shared by every executor of this process
+    slots: Arc<ExecutionSlots>,
 }
 
 impl Executor {
     /// Create a new executor
-    pub fn new(storage: StorageConfig, execution: ExecutionConfig) -> Self {
+    pub fn new(
+        storage: StorageConfig,
+        execution: ExecutionConfig,
+        slots: Arc<ExecutionSlots>,
+    ) -> Self {
         let testcase_manager = TestCaseManager::new(storage.clone(), execution.clone());
+        let egress = EgressNetwork::from_config(&execution);
         Self {
             storage,
             execution,
             testcase_manager,
+            egress,
+            slots,
         }
     }
 
+    /// Egress namespace a run joins, if it is allowed network access and
+    /// one is configured
+    fn egress_for(&self, network_allowed: bool) -> Option<&EgressNetwork> {
+        self.egress.as_ref().filter(|_| network_allowed)
+    }
+
     /// Expose the storage config for binary path lookups.
     pub fn storage_config(&self) -> &StorageConfig {
         &self.storage
@@ -61,7 +149,383 @@ impl Executor {
 
     /// Execute a submission against all test cases
     pub async fn execute(&self, ctx: &ExecutionContext) -> Result<SubmissionResult> {
-        // Clamp max_threads to the system-wide limit (defense in depth)
+        let effective_max_threads = self.effective_max_threads(ctx);
+        let binary_path = self.prepare_binary(ctx).await?;
+
+        // Create temp directory for this execution
+        let temp_dir = self.storage.temp_path.join(ctx.submission_id.to_string());
+        fs::create_dir_all(&temp_dir).await?;
+
+        // Outputs kept by an earlier judging of this submission are stale
+        let debug_dir = self
+            .debug_outputs_dir(ctx)?
+            .join(ctx.submission_id.to_string());
+        if debug_dir.exists() {
+            fs::remove_dir_all(&debug_dir).await?;
+        }
+
+        // Get or generate test cases; a pipeline produces each test's files
+        // itself
+        let testcases = match ctx.pipeline {
+            Some(_) => Vec::new(),
+            None => {
+                self.testcase_manager
+                    .get_testcases(ctx.problem_id, ctx.num_testcases, ctx.generator_version)
+                    .await?
+            }
+        };
+        let total = match ctx.pipeline {
+            Some(_) => ctx.num_testcases.max(0) as usize,
+            None => testcases.len(),
+        };
+
+        let mut results = Vec::with_capacity(total);
+        let mut retained_outputs = Vec::new();
+
+        for idx in 0..total {
+            let (number, result, output_path) = match &ctx.pipeline {
+                Some(pipeline) => {
+                    let number = idx as i32 + 1;
+                    let work_dir = temp_dir.join(format!("test_{:03}", number));
+                    let seed = generator_seed(ctx.problem_id, number, ctx.generator_version);
+                    let result = self
+                        .run_pipeline_test(
+                            ctx,
+                            pipeline,
+                            effective_max_threads,
+                            &binary_path,
+                            number,
+                            &seed,
+                            &work_dir,
+                        )
+                        .await;
+                    let output_path = pipeline.submission_output().map(|f| work_dir.join(f));
+                    (number, result, output_path)
+                }
+                None => {
+                    let Some(testcase) = testcases.get(idx) else {
+                        break;
+                    };
+                    let result = self
+                        .run_testcase(
+                            ctx,
+                            effective_max_threads,
+                            &binary_path,
+                            testcase,
+                            &temp_dir,
+                            None,
+                        )
+                        .await;
+                    let output_path = temp_dir.join(format!("output_{:03}.txt", testcase.number));
+                    (testcase.number, result, Some(output_path))
+                }
+            };
+
+            match result {
+                Ok(tc_result) => {
+                    let tc_result = tc_result.with_limits(ctx.limits_for(number));
+                    let failed = tc_result.verdict.is_failure();
+                    let judge_error = tc_result.verdict == Verdict::JudgeError;
+                    results.push(tc_result);
+
+                    if let Some(output_path) = output_path.filter(|_| {
+                        failed && !judge_error && retained_outputs.len() < MAX_RETAINED_OUTPUTS
+                    }) {
+                        match self.retain_output(ctx, number, &output_path).await {
+                            Ok(Some(retained)) => retained_outputs.push(retained),
+                            Ok(None) => {}
+                            Err(e) => tracing::warn!(
+                                "Failed to keep output of testcase {}: {}",
+                                number,
+                                e
+                            ),
+                        }
+                    }
+
+                    // Stop on first failure unless every test earns points;
+                    // a judge error always ends the run
+                    if judge_error || (failed && !ctx.partial_scoring) {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Judge error on testcase {}: {}", number, e);
+                    results.push(TestCaseResult::judge_error(number, e.to_string()));
+                    break;
+                }
+            }
+
+            // Pipeline files are only needed until the test is judged
+            if ctx.pipeline.is_some() {
+                let _ = fs::remove_dir_all(temp_dir.join(format!("test_{:03}", number))).await;
+            }
+        }
+
+        // Cleanup temp directory
+        if let Err(e) = fs::remove_dir_all(&temp_dir).await {
+            tracing::warn!("Failed to cleanup temp dir: {}", e);
+        }
+
+        let mut result = SubmissionResult::from_testcases(results, total as i32);
+        result.retained_outputs = retained_outputs;
+        Ok(result)
+    }
+
+    /// Run a single test of a submission again with the seed and generator
+    /// version recorded for it, regenerating the input if it was evicted.
+    /// Pipelines are rerun in full with the recorded seed.
+    pub async fn reproduce_test(
+        &self,
+        ctx: &ExecutionContext,
+        number: i32,
+        seed: &str,
+        reproduction_id: Uuid,
+    ) -> Result<TestCaseResult> {
+        let effective_max_threads = self.effective_max_threads(ctx);
+        let binary_path = self.prepare_binary(ctx).await?;
+
+        let temp_dir = self
+            .storage
+            .temp_path
+            .join(format!("repro_{}", reproduction_id));
+
+        let result = match &ctx.pipeline {
+            Some(pipeline) => {
+                self.run_pipeline_test(
+                    ctx,
+                    pipeline,
+                    effective_max_threads,
+                    &binary_path,
+                    number,
+                    seed,
+                    &temp_dir,
+                )
+                .await
+            }
+            None => {
+                let input_path = self
+                    .testcase_manager
+                    .regenerate_input(ctx.problem_id, number, seed, ctx.generator_version)
+                    .await?;
+                let testcase = TestCase { number, input_path };
+
+                fs::create_dir_all(&temp_dir).await?;
+                self.run_testcase(
+                    ctx,
+                    effective_max_threads,
+                    &binary_path,
+                    &testcase,
+                    &temp_dir,
+                    None,
+                )
+                .await
+            }
+        };
+
+        if let Err(e) = fs::remove_dir_all(&temp_dir).await {
+            tracing::warn!("Failed to cleanup temp dir: {}", e);
+        }
+
+        result
+    }
+
+    /// Reproduce a single test like [`Self::reproduce_test`], keeping the
+    /// program's stdout, stderr and output file and the checker's comment,
+    /// each up to `capture_limit` bytes. Streams are only kept when the
+    /// program exits on its own, and pipelines keep only the submission's
+    /// output file.
+    pub async fn debug_test(
+        &self,
+        ctx: &ExecutionContext,
+        number: i32,
+        seed: &str,
+        reproduction_id: Uuid,
+        capture_limit: usize,
+    ) -> Result<(TestCaseResult, DebugCapture)> {
+        let effective_max_threads = self.effective_max_threads(ctx);
+        let binary_path = self.prepare_binary(ctx).await?;
+
+        let temp_dir = self
+            .storage
+            .temp_path
+            .join(format!("debug_{}", reproduction_id));
+
+        let mut streams = RunStreams::default();
+        let (result, output_path) = match &ctx.pipeline {
+            Some(pipeline) => {
+                let result = self
+                    .run_pipeline_test(
+                        ctx,
+                        pipeline,
+                        effective_max_threads,
+                        &binary_path,
+                        number,
+                        seed,
+                        &temp_dir,
+                    )
+                    .await;
+                (
+                    result,
+                    pipeline.submission_output().map(|f| temp_dir.join(f)),
+                )
+            }
+            None => {
+                let input_path = self
+                    .testcase_manager
+                    .regenerate_input(ctx.problem_id, number, seed, ctx.generator_version)
+                    .await?;
+                let testcase = TestCase { number, input_path };
+
+                fs::create_dir_all(&temp_dir).await?;
+                let result = self
+                    .run_testcase(
+                        ctx,
+                        effective_max_threads,
+                        &binary_path,
+                        &testcase,
+                        &temp_dir,
+                        Some(&mut streams),
+                    )
+                    .await;
+                (
+                    result,
+                    Some(temp_dir.join(format!("output_{:03}.txt", number))),
+                )
+            }
+        };
+
+        let output = match &output_path {
+            Some(path) => read_capped(path, capture_limit).await,
+            None => Vec::new(),
+        };
+
+        if let Err(e) = fs::remove_dir_all(&temp_dir).await {
+            tracing::warn!("Failed to cleanup temp dir: {}", e);
+        }
+
+        let result = result?;
+        let mut truncated = false;
+        // Stored as Postgres text, which cannot hold NUL
+        let mut cap = |bytes: &[u8]| {
+            truncated |= bytes.len() > capture_limit;
+            String::from_utf8_lossy(&bytes[..bytes.len().min(capture_limit)]).replace('\0', "")
+        };
+        let capture = DebugCapture {
+            stdout: cap(&streams.stdout),
+            stderr: cap(&streams.stderr),
+            output: cap(&output),
+            checker_log: result.checker_comment.as_deref().map(|c| cap(c.as_bytes())),
+            truncated,
+        };
+
+        Ok((result, capture))
+    }
+
+    /// Run the submission on a sample from the statement and compare its
+    /// output with the statement's, keeping stdout, stderr and output like
+    /// [`Self::debug_test`]. Samples are compared with the problem's
+    /// built-in comparison, or by tokens for problems with a checker or a
+    /// pipeline, which are not run.
+    pub async fn run_sample(
+        &self,
+        ctx: &ExecutionContext,
+        number: i32,
+        input: &str,
+        expected: &str,
+        capture_limit: usize,
+    ) -> Result<(TestCaseResult, DebugCapture)> {
+        let effective_max_threads = self.effective_max_threads(ctx);
+        let binary_path = self.prepare_binary(ctx).await?;
+
+        let temp_dir = self
+            .storage
+            .temp_path
+            .join(format!("sample_{}_{}", ctx.submission_id, number));
+        let input_path = temp_dir.join(format!("input_{:03}.txt", number));
+        let output_path = temp_dir.join(format!("output_{:03}.txt", number));
+
+        let mut streams = RunStreams::default();
+        let result = async {
+            fs::create_dir_all(&temp_dir).await?;
+            fs::write(&input_path, input).await?;
+
+            let args = [
+                input_path.to_string_lossy().into_owned(),
+                output_path.to_string_lossy().into_owned(),
+            ];
+            let start = Instant::now();
+            let run = self
+                .execute_sandboxed(
+                    &binary_path,
+                    &args,
+                    Some(&input_path),
+                    Some(&output_path),
+                    ctx.time_limit_ms,
+                    ctx.memory_limit_kb,
+                    effective_max_threads,
+                    ctx.network_allowed,
+                    false,
+                    Some(&mut streams),
+                )
+                .await?;
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+
+            if let Some(failure) = run.failure(number, elapsed_ms, ctx.time_limit_ms) {
+                return Ok(failure);
+            }
+            let memory_kb = run.memory_kb();
+
+            let output_size = fs::metadata(&output_path)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0);
+            if output_size > self.execution.output_limit_bytes {
+                return Ok(TestCaseResult::output_limit_exceeded(
+                    number, elapsed_ms, memory_kb,
+                ));
+            }
+
+            let output = fs::read(&output_path).await.unwrap_or_default();
+            let checker_result = default_checker::compare(
+                ctx.output_comparison.unwrap_or(OutputComparison::Tokens),
+                ctx.comparison_epsilon,
+                &String::from_utf8_lossy(&output),
+                expected,
+            );
+            Ok::<_, anyhow::Error>(checker_verdict(
+                number,
+                elapsed_ms,
+                memory_kb,
+                checker_result,
+            ))
+        }
+        .await;
+
+        let output = read_capped(&output_path, capture_limit).await;
+        if let Err(e) = fs::remove_dir_all(&temp_dir).await {
+            tracing::warn!("Failed to cleanup temp dir: {}", e);
+        }
+
+        let result = result?;
+        let mut truncated = false;
+        // Stored as Postgres text, which cannot hold NUL
+        let mut cap = |bytes: &[u8]| {
+            truncated |= bytes.len() > capture_limit;
+            String::from_utf8_lossy(&bytes[..bytes.len().min(capture_limit)]).replace('\0', "")
+        };
+        let capture = DebugCapture {
+            stdout: cap(&streams.stdout),
+            stderr: cap(&streams.stderr),
+            output: cap(&output),
+            checker_log: result.checker_comment.as_deref().map(|c| cap(c.as_bytes())),
+            truncated,
+        };
+
+        Ok((result, capture))
+    }
+
+    /// Clamp max_threads to the system-wide limit (defense in depth)
+    fn effective_max_threads(&self, ctx: &ExecutionContext) -> i32 {
         let effective_max_threads = ctx.max_threads.min(self.execution.max_threads_limit).max(1);
         if ctx.max_threads > self.execution.max_threads_limit {
             tracing::warn!(
@@ -72,12 +536,24 @@ impl Executor {
                 effective_max_threads,
             );
         }
+        effective_max_threads
+    }
 
-        // Get path to compiled binary
-        let binary_path = self
+    /// Check the submission's artifact and make it executable
+    async fn prepare_binary(&self, ctx: &ExecutionContext) -> Result<PathBuf> {
+        // Only run artifacts from a binaries directory, ours or a contest's
+        let binary_path = ctx.binary_path.clone();
+        if !self
             .storage
-            .binaries_path
-            .join(format!("{}_bin", ctx.submission_id));
+            .storage_roots
+            .contains(USER_BINARIES_DIR, &binary_path)
+        {
+            return Err(anyhow!(
+                "Artifact {} for submission {} is outside the binaries directory",
+                binary_path.display(),
+                ctx.submission_id
+            ));
+        }
 
         if !binary_path.exists() {
             return Err(anyhow!(
@@ -86,10 +562,6 @@ impl Executor {
             ));
         }
 
-        // Create temp directory for this execution
-        let temp_dir = self.storage.temp_path.join(ctx.submission_id.to_string());
-        fs::create_dir_all(&temp_dir).await?;
-
         // Ensure binary (or run.sh for interpreted languages) is executable.
         // For interpreted languages Sisyphus stores a *directory* containing
         // run.sh and the source files.
@@ -111,52 +583,59 @@ impl Executor {
             fs::set_permissions(&run_sh, perms).await?;
         }
 
-        // Get or generate test cases
-        let testcases = self
-            .testcase_manager
-            .get_testcases(ctx.problem_id, ctx.num_testcases)
-            .await?;
-
-        let mut results = Vec::with_capacity(testcases.len());
+        Ok(binary_path)
+    }
 
-        for testcase in &testcases {
-            let result = self
-                .run_testcase(
-                    ctx,
-                    effective_max_threads,
-                    &binary_path,
-                    testcase,
-                    &temp_dir,
-                )
-                .await;
+    /// Directory outputs kept for failing tests go in: under the contest's
+    /// storage root, or the shared one without it
+    fn debug_outputs_dir(&self, ctx: &ExecutionContext) -> Result<PathBuf> {
+        match ctx.storage_root.as_deref() {
+            None => Ok(self.storage.debug_outputs_path.clone()),
+            Some(root) => self
+                .storage
+                .storage_roots
+                .resolve(Some(root))
+                .map(|root| root.join(DEBUG_OUTPUTS_DIR))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Storage root {} of submission {} is not in CONTEST_STORAGE_ROOTS",
+                        root,
+                        ctx.submission_id
+                    )
+                }),
+        }
+    }
 
-            match result {
-                Ok(tc_result) => {
-                    let failed = tc_result.verdict.is_failure();
-                    results.push(tc_result);
+    /// Copy a failing test's output (up to the debug size cap) out of the
+    /// temp directory so it can be downloaded. `None` if it wrote nothing.
+    async fn retain_output(
+        &self,
+        ctx: &ExecutionContext,
+        number: i32,
+        output_path: &Path,
+    ) -> Result<Option<RetainedOutput>> {
+        let size_bytes = match fs::metadata(output_path).await {
+            Ok(meta) => meta.len(),
+            Err(_) => return Ok(None),
+        };
 
-                    // Stop on first failure (can be made configurable)
-                    if failed {
-                        break;
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Judge error on testcase {}: {}", testcase.number, e);
-                    results.push(TestCaseResult::judge_error(testcase.number, e.to_string()));
-                    break;
-                }
-            }
-        }
+        let debug_dir = self
+            .debug_outputs_dir(ctx)?
+            .join(ctx.submission_id.to_string());
+        fs::create_dir_all(&debug_dir).await?;
+        let path = debug_dir.join(format!("output_{:03}.txt", number));
 
-        // Cleanup temp directory
-        if let Err(e) = fs::remove_dir_all(&temp_dir).await {
-            tracing::warn!("Failed to cleanup temp dir: {}", e);
-        }
+        let limit = self.execution.debug_output_limit_bytes;
+        let mut source = fs::File::open(output_path).await?.take(limit);
+        let mut dest = fs::File::create(&path).await?;
+        tokio::io::copy(&mut source, &mut dest).await?;
 
-        Ok(SubmissionResult::from_testcases(
-            results,
-            testcases.len() as i32,
-        ))
+        Ok(Some(RetainedOutput {
+            testcase_number: number,
+            path,
+            size_bytes,
+            truncated: size_bytes > limit,
+        }))
     }
 
     /// Run a single test case
@@ -167,142 +646,463 @@ impl Executor {
         binary_path: &Path,
         testcase: &TestCase,
         temp_dir: &Path,
+        streams: Option<&mut RunStreams>,
     ) -> Result<TestCaseResult> {
         let output_path = temp_dir.join(format!("output_{:03}.txt", testcase.number));
+        let limits = ctx.limits_for(testcase.number);
+
+        // Traffic is the change in the egress counters across the run
+        let egress_run = match self.egress_for(ctx.network_allowed) {
+            Some(egress) => Some(egress.begin_run().await?),
+            None => None,
+        };
 
         // Execute the binary with file arguments: ./binary <input_file> <output_file>
+        let args = [
+            testcase.input_path.to_string_lossy().into_owned(),
+            output_path.to_string_lossy().into_owned(),
+        ];
         let start = Instant::now();
 
         let result = self
             .execute_sandboxed(
                 binary_path,
-                &testcase.input_path,
-                &output_path,
-                ctx.time_limit_ms,
-                ctx.memory_limit_kb,
+                &args,
+                Some(&testcase.input_path),
+                Some(&output_path),
+                limits.time_limit_ms,
+                limits.memory_limit_kb,
                 effective_max_threads,
                 ctx.network_allowed,
+                ctx.count_instructions || self.execution.count_instructions,
+                streams,
             )
             .await?;
 
         let elapsed_ms = start.elapsed().as_millis() as u64;
 
-        // Check execution result
-        match result {
-            ExecutionResult::Success { memory_kb } => {
-                // Check output size
-                let output_size = fs::metadata(&output_path)
-                    .await
-                    .map(|m| m.len())
-                    .unwrap_or(0);
+        let network_bytes = match egress_run {
+            Some(run) => Some(run.finish().await?),
+            None => None,
+        };
 
-                if output_size > self.execution.output_limit_bytes {
-                    return Ok(TestCaseResult::output_limit_exceeded(
-                        testcase.number,
-                        elapsed_ms,
-                        memory_kb,
-                    ));
-                }
+        let instructions = match result {
+            ExecutionResult::Success { instructions, .. } => instructions,
+            _ => None,
+        };
 
-                // Run checker
-                let checker_result = self
-                    .testcase_manager
+        let testcase_result = self
+            .judge_run(ctx, testcase, &output_path, result, elapsed_ms)
+            .await?;
+        Ok(TestCaseResult {
+            network_bytes,
+            instructions,
+            ..testcase_result
+        })
+    }
+
+    /// Turn the outcome of a run into a test case result, running the
+    /// checker on successful runs
+    async fn judge_run(
+        &self,
+        ctx: &ExecutionContext,
+        testcase: &TestCase,
+        output_path: &Path,
+        result: ExecutionResult,
+        elapsed_ms: u64,
+    ) -> Result<TestCaseResult> {
+        let time_limit_ms = ctx.limits_for(testcase.number).time_limit_ms;
+        if let Some(failure) = result.failure(testcase.number, elapsed_ms, time_limit_ms) {
+            return Ok(failure);
+        }
+        let memory_kb = result.memory_kb();
+
+        // Check output size
+        let output_size = fs::metadata(output_path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        if output_size > self.execution.output_limit_bytes {
+            return Ok(TestCaseResult::output_limit_exceeded(
+                testcase.number,
+                elapsed_ms,
+                memory_kb,
+            ));
+        }
+
+        let checker_result = match ctx.output_comparison {
+            Some(mode) => {
+                self.compare_with_reference(ctx, mode, testcase, output_path)
+                    .await?
+            }
+            None => {
+                self.testcase_manager
                     .run_checker(
                         ctx.problem_id,
                         &testcase.input_path,
-                        &output_path,
+                        output_path,
                         // For custom checker, we don't have expected output
                         // The checker generates/knows the expected result
                         &testcase.input_path, // Pass input as "answer" for interoperability
                     )
-                    .await?;
+                    .await?
+            }
+        };
 
-                match checker_result {
-                    CheckerResult::Accepted(_) => Ok(TestCaseResult::accepted(
-                        testcase.number,
-                        elapsed_ms,
-                        memory_kb,
-                    )),
-                    CheckerResult::WrongAnswer(comment) => Ok(TestCaseResult::wrong_answer(
-                        testcase.number,
-                        elapsed_ms,
-                        memory_kb,
-                        Some(comment),
-                    )),
-                    CheckerResult::PartialCredit(_points, comment) => {
-                        // For now, treat partial as wrong answer
-                        // TODO: Implement partial scoring
-                        Ok(TestCaseResult::wrong_answer(
-                            testcase.number,
-                            elapsed_ms,
-                            memory_kb,
-                            Some(comment),
+        Ok(checker_verdict(
+            testcase.number,
+            elapsed_ms,
+            memory_kb,
+            checker_result,
+        ))
+    }
+
+    /// Judge a test's output with the built-in comparison, against the
+    /// answer the problem's reference binary writes next to it as
+    /// `reference <input> <answer>`
+    async fn compare_with_reference(
+        &self,
+        ctx: &ExecutionContext,
+        mode: OutputComparison,
+        testcase: &TestCase,
+        output_path: &Path,
+    ) -> Result<CheckerResult> {
+        let answer_path = output_path.with_file_name(format!("answer_{:03}.txt", testcase.number));
+        let args = [
+            testcase.input_path.to_string_lossy().into_owned(),
+            answer_path.to_string_lossy().into_owned(),
+        ];
+
+        match self
+            .run_problem_binary(
+                ctx.problem_id,
+                StepProgram::Reference,
+                &args,
+                None,
+                None,
+                false,
+            )
+            .await
+        {
+            Ok(run) if run.exit_code == 0 => {}
+            Ok(run) => {
+                return Ok(CheckerResult::JudgeError(format!(
+                    "Reference exited with code {}: {}",
+                    run.exit_code,
+                    String::from_utf8_lossy(&run.stderr)
+                )))
+            }
+            Err(e) => {
+                return Ok(CheckerResult::JudgeError(format!(
+                    "Reference sandbox error: {}",
+                    e
+                )))
+            }
+        }
+
+        let output = fs::read(output_path).await.unwrap_or_default();
+        let answer = fs::read(&answer_path).await?;
+        Ok(default_checker::compare(
+            mode,
+            ctx.comparison_epsilon,
+            &String::from_utf8_lossy(&output),
+            &String::from_utf8_lossy(&answer),
+        ))
+    }
+
+    /// Run one test through the problem's pipeline, with the files its
+    /// steps pass each other in `work_dir`.
+    ///
+    /// The test's time is the sum of its submission steps and its memory
+    /// their peak. The first submission step to fail decides the verdict,
+    /// otherwise the checker does; a generator or reference step that
+    /// fails is a judge error.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_pipeline_test(
+        &self,
+        ctx: &ExecutionContext,
+        pipeline: &Pipeline,
+        effective_max_threads: i32,
+        binary_path: &Path,
+        number: i32,
+        seed: &str,
+        work_dir: &Path,
+    ) -> Result<TestCaseResult> {
+        let order = pipeline.execution_order().map_err(|e| anyhow!(e))?;
+        fs::create_dir_all(work_dir).await?;
+
+        let egress = self.egress_for(ctx.network_allowed);
+        let mut elapsed_ms = 0;
+        let mut memory_kb = 0;
+        let mut instructions = Some(0);
+        let mut network_bytes = None;
+
+        for idx in order {
+            let step = &pipeline.steps[idx];
+            let args = step.render_args(|name| match name {
+                "test" => number.to_string(),
+                "seed" => seed.to_string(),
+                file => work_dir.join(file).to_string_lossy().into_owned(),
+            });
+
+            if step.program != StepProgram::Submission {
+                let output = self.run_problem_step(ctx.problem_id, step, &args).await;
+
+                // The checker runs last
+                if step.program == StepProgram::Checker {
+                    let checker_result = match output {
+                        Ok(output) => CheckerResult::from_exit(
+                            output.exit_code,
+                            String::from_utf8_lossy(&output.stdout).to_string(),
+                            String::from_utf8_lossy(&output.stderr).to_string(),
+                        ),
+                        Err(e) => {
+                            CheckerResult::JudgeError(format!("Checker sandbox error: {}", e))
+                        }
+                    };
+                    return Ok(TestCaseResult {
+                        network_bytes,
+                        instructions,
+                        ..checker_verdict(number, elapsed_ms, memory_kb, checker_result)
+                    });
+                }
+
+                let output = match output {
+                    Ok(output) if output.exit_code == 0 => output,
+                    Ok(output) => {
+                        return Ok(TestCaseResult::judge_error(
+                            number,
+                            format!(
+                                "Step '{}' exited with code {}: {}",
+                                step.name,
+                                output.exit_code,
+                                String::from_utf8_lossy(&output.stderr)
+                            ),
                         ))
                     }
-                    CheckerResult::JudgeError(msg) => {
-                        Ok(TestCaseResult::judge_error(testcase.number, msg))
+                    Err(e) => {
+                        return Ok(TestCaseResult::judge_error(
+                            number,
+                            format!("Step '{}' failed: {}", step.name, e),
+                        ))
                     }
+                };
+                if let Some(file) = &step.stdout {
+                    fs::write(work_dir.join(file), &output.stdout).await?;
                 }
+                continue;
             }
-            ExecutionResult::TimeLimitExceeded => Ok(TestCaseResult::time_limit_exceeded(
-                testcase.number,
-                ctx.time_limit_ms,
-                0,
-            )),
-            ExecutionResult::MemoryLimitExceeded { memory_kb } => Ok(
-                TestCaseResult::memory_limit_exceeded(testcase.number, elapsed_ms, memory_kb),
-            ),
-            ExecutionResult::RuntimeError {
-                exit_code,
-                message,
-                memory_kb,
-            } => {
-                tracing::debug!(
-                    testcase = testcase.number,
-                    exit_code,
-                    %message,
-                    "Runtime error on testcase"
-                );
-                Ok(TestCaseResult::runtime_error(
-                    testcase.number,
-                    elapsed_ms,
-                    memory_kb,
-                    exit_code,
-                    message,
-                ))
+
+            // A test's or subtask's override wins over the step's limits
+            let TestLimits {
+                time_limit_ms,
+                memory_limit_kb,
+            } = ctx.limit_overrides.apply(
+                number,
+                TestLimits {
+                    time_limit_ms: step.time_limit_ms.unwrap_or(ctx.time_limit_ms),
+                    memory_limit_kb: step.memory_limit_kb.unwrap_or(ctx.memory_limit_kb),
+                },
+            );
+            let input_path = step.inputs.first().map(|f| work_dir.join(f));
+            let output_path = step.outputs.first().map(|f| work_dir.join(f));
+
+            let egress_run = match egress {
+                Some(egress) => Some(egress.begin_run().await?),
+                None => None,
+            };
+            let start = Instant::now();
+
+            let result = self
+                .execute_sandboxed(
+                    binary_path,
+                    &args,
+                    input_path.as_deref(),
+                    output_path.as_deref(),
+                    time_limit_ms,
+                    memory_limit_kb,
+                    effective_max_threads,
+                    ctx.network_allowed,
+                    ctx.count_instructions || self.execution.count_instructions,
+                    None,
+                )
+                .await?;
+
+            elapsed_ms += start.elapsed().as_millis() as u64;
+            if let Some(run) = egress_run {
+                let bytes = run.finish().await?;
+                network_bytes = Some(network_bytes.unwrap_or(0) + bytes);
+            }
+
+            if let Some(failure) = result.failure(number, elapsed_ms, time_limit_ms) {
+                return Ok(TestCaseResult {
+                    network_bytes,
+                    ..failure
+                });
+            }
+            memory_kb = memory_kb.max(result.memory_kb());
+            instructions = instructions
+                .zip(result.instructions())
+                .map(|(total, step)| total + step);
+
+            for file in &step.outputs {
+                let size = fs::metadata(work_dir.join(file))
+                    .await
+                    .map(|m| m.len())
+                    .unwrap_or(0);
+                if size > self.execution.output_limit_bytes {
+                    return Ok(TestCaseResult {
+                        network_bytes,
+                        ..TestCaseResult::output_limit_exceeded(number, elapsed_ms, memory_kb)
+                    });
+                }
             }
         }
+
+        Err(anyhow!(
+            "Pipeline of problem {} has no checker step",
+            ctx.problem_id
+        ))
+    }
+
+    /// Run a generator, reference or checker step in its own sandbox, with
+    /// the configured generator or checker limits unless the step sets its
+    /// own.
+    async fn run_problem_step(
+        &self,
+        problem_id: Uuid,
+        step: &PipelineStep,
+        args: &[String],
+    ) -> Result<SandboxedOutput> {
+        if step.program.binary_name().is_none() {
+            return Err(anyhow!(
+                "Step '{}' does not run a problem binary",
+                step.name
+            ));
+        }
+
+        self.run_problem_binary(
+            problem_id,
+            step.program,
+            args,
+            step.time_limit_ms,
+            step.memory_limit_kb,
+            step.stdout.is_some(),
+        )
+        .await
+    }
+
+    /// Run one of the problem's binaries in its own sandbox, with the
+    /// configured generator or checker limits unless overridden. Checker
+    /// output is always captured.
+    async fn run_problem_binary(
+        &self,
+        problem_id: Uuid,
+        program: StepProgram,
+        args: &[String],
+        time_limit_ms: Option<u64>,
+        memory_limit_kb: Option<u64>,
+        capture_stdout: bool,
+    ) -> Result<SandboxedOutput> {
+        let name = program
+            .binary_name()
+            .ok_or_else(|| anyhow!("{:?} is not a problem binary", program))?;
+        let path = self
+            .storage
+            .problem_binaries_path
+            .join(problem_id.to_string())
+            .join(name);
+
+        if !path.exists() {
+            return Err(anyhow!("{} not found for problem {}", name, problem_id));
+        }
+        let mut perms = fs::metadata(&path).await?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&path, perms).await?;
+
+        let (default_time_ms, default_memory_kb) = match program {
+            StepProgram::Checker => (
+                self.execution.checker_time_limit_ms,
+                self.execution.checker_memory_limit_kb,
+            ),
+            _ => (
+                self.execution.generator_time_limit_ms,
+                self.execution.generator_memory_limit_kb,
+            ),
+        };
+
+        // Problem binaries are single-threaded; small PID buffer.
+        let sandbox_id = format!("step_{}", Uuid::new_v4());
+        let sandbox =
+            Sandbox::create(&sandbox_id, memory_limit_kb.unwrap_or(default_memory_kb), 1).await;
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let result = sandbox
+            .run_sandboxed(
+                &path,
+                &args,
+                time_limit_ms.unwrap_or(default_time_ms),
+                false, // problem binaries never need network
+                program == StepProgram::Checker || capture_stdout,
+            )
+            .await;
+
+        sandbox.cleanup().await;
+        result
     }
 
     /// Execute binary in a sandboxed environment.
     ///
     /// The binary is invoked as: `./binary <input_file> <output_file>`
-    /// instead of using stdin/stdout piping, which avoids broken-pipe
-    /// errors with large I/O.
+    /// (or with a pipeline step's arguments) instead of using stdin/stdout
+    /// piping, which avoids broken-pipe errors with large I/O.
+    /// `input_path` and `output_path` are exported as `INPUT_FILE` and
+    /// `OUTPUT_FILE`; growth of the output also counts as progress for the
+    /// idleness check.
     ///
     /// For interpreted languages the "binary" is a directory containing
     /// `run.sh` and the source files.  In that case we invoke
-    /// `bash run.sh <input_file> <output_file>` with the directory as cwd.
+    /// `bash run.sh <args>` with the directory as cwd.
     ///
     /// ## Sandboxing
     ///
     /// * **cgroups v2** – memory limit (`memory.max`), swap disabled
     ///   (`memory.swap.max 0`), PID/thread limit (`pids.max`).
-    /// * **Network namespace** – `unshare(CLONE_NEWNET)` via `pre_exec`
-    ///   when `network_allowed` is `false`.
+    /// * **Network namespace** – `unshare(CLONE_NEWNET)` via `pre_exec`,
+    ///   or `setns` into the restricted egress namespace when
+    ///   `network_allowed` is `true` and one is configured.
+    /// * **Output cap** – `RLIMIT_FSIZE` just above `output_limit_bytes`, so
+    ///   a runaway writer is stopped by `SIGXFSZ` instead of filling the disk.
+    /// * **Time** – with cgroups the limit applies to CPU time (one time
+    ///   limit per allowed thread), polled from `cpu.stat`, with a
+    ///   wall-clock watchdog at `wall_time_factor` times the limit; a run
+    ///   the watchdog stops before it used the time limit in CPU time was
+    ///   mostly waiting and is reported idle. Without cgroups only the wall
+    ///   clock is enforced. `RLIMIT_CPU` backs both up. Stopped runs get
+    ///   `SIGTERM`, then `SIGKILL` after `kill_grace_ms`.
+    /// * **Idleness** – with cgroups, a run that neither uses CPU time nor
+    ///   grows its output for `idleness_limit_ms` is killed (ILE) rather
+    ///   than left to sleep until the time limit.
     /// * **Resource metrics** – peak memory from `memory.peak` (cgroup) or
-    ///   `VmPeak` (`/proc`); CPU time from `cpu.stat`.
+    ///   `VmPeak` (`/proc`); CPU time from `cpu.stat`; with
+    ///   `count_instructions`, user-space instructions from perf counters
+    ///   on the cgroup.
     ///
     /// When cgroups are unavailable the executor degrades gracefully.
+    #[allow(clippy::too_many_arguments)]
     async fn execute_sandboxed(
         &self,
         binary_path: &Path,
-        input_path: &Path,
-        output_path: &Path,
+        args: &[String],
+        input_path: Option<&Path>,
+        output_path: Option<&Path>,
         time_limit_ms: u64,
         memory_limit_kb: u64,
         max_threads: i32,
         network_allowed: bool,
+        count_instructions: bool,
+        streams: Option<&mut RunStreams>,
     ) -> Result<ExecutionResult> {
         tracing::debug!(
             binary = %binary_path.display(),
@@ -313,10 +1113,32 @@ impl Executor {
             "Executing submission binary"
         );
 
+        let egress = match self.egress_for(network_allowed) {
+            Some(egress) => Some(egress.open()?),
+            None => {
+                if network_allowed {
+                    tracing::debug!("No egress netns configured — running without network");
+                }
+                None
+            }
+        };
+
+        // Wait for cores and memory to run on; held until the run returns
+        let _slot = self.slots.acquire(max_threads, memory_limit_kb).await;
+
         // ── 1. Create sandbox (cgroups v2 resource limits) ──────────
         let sandbox_id = Uuid::new_v4().to_string();
         let sandbox = Sandbox::create(&sandbox_id, memory_limit_kb, max_threads).await;
 
+        // Counters follow the cgroup, so they are attached before the child
+        // joins it and pick up every thread and child process
+        let counter = match sandbox.cgroup_dir().filter(|_| count_instructions) {
+            Some(dir) => InstructionCounter::open(dir)
+                .inspect_err(|e| tracing::debug!("Instruction counting unavailable: {e:#}"))
+                .ok(),
+            None => None,
+        };
+
         // ── 2. Build the command (without spawning) ─────────────────
         let mut cmd = if binary_path.is_dir() {
             // Interpreted language: run.sh inside the directory.
@@ -334,17 +1156,13 @@ impl Executor {
 
             if forwards_args {
                 let mut c = Command::new("bash");
-                c.arg(&run_sh)
-                    .arg(input_path)
-                    .arg(output_path)
-                    .current_dir(binary_path);
+                c.arg(&run_sh).args(args).current_dir(binary_path);
                 c
             } else {
                 let cmd_line = run_sh_content
                     .lines()
                     .map(str::trim)
-                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
-                    .last()
+                    .rfind(|l| !l.is_empty() && !l.starts_with('#'))
                     .unwrap_or("bash run.sh");
 
                 tracing::debug!(
@@ -354,24 +1172,27 @@ impl Executor {
 
                 let mut c = Command::new("bash");
                 c.arg("-c")
-                    .arg(format!("{} \"$1\" \"$2\"", cmd_line))
+                    .arg(format!("{} \"$@\"", cmd_line))
                     .arg("_") // $0 placeholder
-                    .arg(input_path)
-                    .arg(output_path)
+                    .args(args)
                     .current_dir(binary_path);
                 c
             }
         } else {
             let mut c = Command::new(binary_path);
-            c.arg(input_path).arg(output_path);
+            c.args(args);
             c
         };
 
         // ── 3. Common I/O and environment setup ─────────────────────
-        cmd.env("INPUT_FILE", input_path)
-            .env("OUTPUT_FILE", output_path)
-            .env("MAX_THREADS", max_threads.to_string())
-            .env("NETWORK_ALLOWED", if network_allowed { "1" } else { "0" })
+        if let Some(input_path) = input_path {
+            cmd.env("INPUT_FILE", input_path);
+        }
+        if let Some(output_path) = output_path {
+            cmd.env("OUTPUT_FILE", output_path);
+        }
+        cmd.env("MAX_THREADS", max_threads.to_string())
+            .env("NETWORK_ALLOWED", if egress.is_some() { "1" } else { "0" })
             .env("TIME_LIMIT_MS", time_limit_ms.to_string())
             .env("MEMORY_LIMIT_KB", memory_limit_kb.to_string())
             .stdin(Stdio::null())
@@ -390,11 +1211,21 @@ impl Executor {
                 });
             }
         }
-        // b) Network namespace isolation — completely disables networking.
-        //    If the container lacks CAP_SYS_ADMIN the unshare call will
-        //    return EPERM.  In that case we log a warning (via stderr,
-        //    since we are post-fork) and continue without isolation.
-        if !network_allowed {
+        // b) Network. Submissions allowed network access join the egress
+        //    namespace; failing to do so fails the spawn rather than
+        //    leaving them on the judge's own network.
+        //    Everything else gets an empty namespace — completely disables
+        //    networking. If the container lacks CAP_SYS_ADMIN the unshare
+        //    call will return EPERM.  In that case we log a warning (via
+        //    stderr, since we are post-fork) and continue without isolation.
+        if let Some(netns) = egress {
+            unsafe {
+                cmd.pre_exec(move || {
+                    nix::sched::setns(&netns, nix::sched::CloneFlags::CLONE_NEWNET)?;
+                    Ok(())
+                });
+            }
+        } else {
             unsafe {
                 cmd.pre_exec(|| {
                     match nix::sched::unshare(nix::sched::CloneFlags::CLONE_NEWNET) {
@@ -413,15 +1244,90 @@ impl Executor {
             }
         }
 
+        // c) Output cap. One byte over the limit is allowed so the size
+        //    check after the run still sees the overflow when the program
+        //    ignores SIGXFSZ.
+        let max_file_size = self.execution.output_limit_bytes + 1;
+        unsafe {
+            cmd.pre_exec(move || {
+                use nix::sys::resource::{setrlimit, Resource};
+                setrlimit(Resource::RLIMIT_FSIZE, max_file_size, max_file_size)?;
+                Ok(())
+            });
+        }
+
+        // d) CPU time backstop for when the cgroup poll below is late or
+        //    unavailable: SIGXCPU a second past the CPU limit, SIGKILL a
+        //    second later.
+        let cpu_limit_ms = time_limit_ms.saturating_mul(max_threads.max(1) as u64);
+        let cpu_rlimit_secs = cpu_limit_ms.div_ceil(1000) + 1;
+        unsafe {
+            cmd.pre_exec(move || {
+                use nix::sys::resource::{setrlimit, Resource};
+                setrlimit(Resource::RLIMIT_CPU, cpu_rlimit_secs, cpu_rlimit_secs + 1)?;
+                Ok(())
+            });
+        }
+
         // ── 5. Spawn and wait ───────────────────────────────────────
         let child = cmd.spawn()?;
         let child_pid = child.id();
 
-        let time_limit = Duration::from_millis(time_limit_ms + 100); // small buffer
-        let result = timeout(time_limit, child.wait_with_output()).await;
+        // CPU time and idleness are judged by cgroup CPU accounting, so only
+        // with cgroups; otherwise the wall clock is the time limit
+        let cpu_accounting = sandbox.cgroup_procs_path().is_some();
+        let wall_limit = if cpu_accounting {
+            Duration::from_millis((time_limit_ms as f64 * self.execution.wall_time_factor) as u64)
+        } else {
+            Duration::from_millis(time_limit_ms + WALL_GRACE_MS)
+        };
+        let idleness_limit = Duration::from_millis(self.execution.idleness_limit_ms);
+        let watch_idleness = !idleness_limit.is_zero() && cpu_accounting;
+
+        let mut wait = Box::pin(child.wait_with_output());
+        let deadline = tokio::time::sleep(wall_limit);
+        tokio::pin!(deadline);
+        let mut poll = tokio::time::interval(Duration::from_millis(IDLE_POLL_INTERVAL_MS));
+        let mut progress = (0u64, 0u64);
+        let mut last_progress = Instant::now();
+
+        let result = loop {
+            tokio::select! {
+                output = &mut wait => break Ok(output),
+                _ = &mut deadline => break Err(Stopped::WallClock),
+                _ = poll.tick(), if cpu_accounting => {
+                    let cpu_time_ms = sandbox.read_usage(child_pid).await.cpu_time_ms;
+                    if cpu_time_ms >= cpu_limit_ms {
+                        break Err(Stopped::CpuTime);
+                    }
+                    if watch_idleness {
+                        let output_bytes = match output_path {
+                            Some(path) => fs::metadata(path).await.map(|m| m.len()).unwrap_or(0),
+                            None => 0,
+                        };
+                        if (cpu_time_ms, output_bytes) != progress {
+                            progress = (cpu_time_ms, output_bytes);
+                            last_progress = Instant::now();
+                        } else if last_progress.elapsed() >= idleness_limit {
+                            break Err(Stopped::Idle);
+                        }
+                    }
+                }
+            }
+        };
+
+        // A stopped run gets SIGTERM and the grace period to exit
+        if result.is_err() {
+            sandbox.signal_all(child_pid, Signal::SIGTERM).await;
+            let grace = Duration::from_millis(self.execution.kill_grace_ms);
+            let _ = tokio::time::timeout(grace, &mut wait).await;
+        }
+        // Kills the child if it is still running (kill_on_drop)
+        drop(wait);
 
         // ── 6. Collect resource metrics and clean up sandbox ────────
         let usage = sandbox.read_usage(child_pid).await;
+        let instructions = counter.and_then(|c| c.read().ok());
         let oom_killed = sandbox.was_oom_killed().await;
         sandbox.cleanup().await;
 
@@ -429,9 +1335,16 @@ impl Executor {
         match result {
             Ok(Ok(output)) => {
                 let memory_kb = usage.memory_kb;
+                if let Some(streams) = streams {
+                    streams.stdout.clone_from(&output.stdout);
+                    streams.stderr.clone_from(&output.stderr);
+                }
 
                 if output.status.success() {
-                    Ok(ExecutionResult::Success { memory_kb })
+                    Ok(ExecutionResult::Success {
+                        memory_kb,
+                        instructions,
+                    })
                 } else {
                     let exit_code = output.status.code().unwrap_or(-1);
                     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -441,15 +1354,13 @@ impl Executor {
                     {
                         use std::os::unix::process::ExitStatusExt;
                         if let Some(signal) = output.status.signal() {
-                            // SIGKILL (9) from cgroup OOM killer
-                            if signal == 9 && (oom_killed || memory_kb >= memory_limit_kb) {
-                                return Ok(ExecutionResult::MemoryLimitExceeded { memory_kb });
-                            }
-                            return Ok(ExecutionResult::RuntimeError {
-                                exit_code: -signal,
-                                message: format!("Killed by signal {}", signal),
-                                memory_kb,
-                            });
+                            return Ok(signal_result(
+                                signal,
+                                &usage,
+                                oom_killed,
+                                memory_limit_kb,
+                                cpu_limit_ms,
+                            ));
                         }
                     }
 
@@ -465,21 +1376,99 @@ impl Executor {
                 }
             }
             Ok(Err(e)) => Err(anyhow!("Failed to execute process: {}", e)),
-            Err(_) => {
-                // Timeout — child is killed via kill_on_drop
-                Ok(ExecutionResult::TimeLimitExceeded)
-            }
+            Err(stopped) => Ok(stopped_result(
+                stopped,
+                &usage,
+                cpu_accounting,
+                time_limit_ms,
+            )),
         }
     }
 }
 
+/// What a run killed by `signal` ran into
+fn signal_result(
+    signal: i32,
+    usage: &ResourceUsage,
+    oom_killed: bool,
+    memory_limit_kb: u64,
+    cpu_limit_ms: u64,
+) -> ExecutionResult {
+    let memory_kb = usage.memory_kb;
+    let killed = signal == Signal::SIGKILL as i32;
+
+    // SIGKILL from the cgroup OOM killer
+    if killed && (oom_killed || memory_kb >= memory_limit_kb) {
+        return ExecutionResult::MemoryLimitExceeded { memory_kb };
+    }
+    // A write past RLIMIT_FSIZE
+    if signal == Signal::SIGXFSZ as i32 {
+        return ExecutionResult::OutputLimitExceeded { memory_kb };
+    }
+    // SIGXCPU at the soft RLIMIT_CPU, or SIGKILL at the hard one
+    if signal == Signal::SIGXCPU as i32 || (killed && usage.cpu_time_ms >= cpu_limit_ms) {
+        return ExecutionResult::TimeLimitExceeded {
+            memory_kb,
+            kind: TimeLimitKind::Cpu,
+        };
+    }
+    ExecutionResult::RuntimeError {
+        exit_code: -signal,
+        message: format!("Killed by signal {}", signal),
+        memory_kb,
+    }
+}
+
+/// What a run stopped by the watchdog ran into
+fn stopped_result(
+    stopped: Stopped,
+    usage: &ResourceUsage,
+    cpu_accounting: bool,
+    time_limit_ms: u64,
+) -> ExecutionResult {
+    let memory_kb = usage.memory_kb;
+    match stopped {
+        Stopped::CpuTime => ExecutionResult::TimeLimitExceeded {
+            memory_kb,
+            kind: TimeLimitKind::Cpu,
+        },
+        // Waiting rather than computing for most of the wall-clock limit
+        Stopped::WallClock if cpu_accounting && usage.cpu_time_ms < time_limit_ms => {
+            ExecutionResult::IdlenessLimitExceeded { memory_kb }
+        }
+        Stopped::WallClock => ExecutionResult::TimeLimitExceeded {
+            memory_kb,
+            kind: TimeLimitKind::Wall,
+        },
+        Stopped::Idle => ExecutionResult::IdlenessLimitExceeded { memory_kb },
+    }
+}
+
+/// Why a run was stopped before it exited
+#[derive(Debug)]
+enum Stopped {
+    /// Used up its CPU time
+    CpuTime,
+    /// Reached the wall-clock watchdog
+    WallClock,
+    /// Neither used CPU time nor wrote output for the idleness limit
+    Idle,
+}
+
 /// Result of executing a binary
 #[derive(Debug)]
 enum ExecutionResult {
     /// Successful execution
-    Success { memory_kb: u64 },
+    Success {
+        memory_kb: u64,
+        instructions: Option<u64>,
+    },
     /// Time limit exceeded
-    TimeLimitExceeded,
+    TimeLimitExceeded { memory_kb: u64, kind: TimeLimitKind },
+    /// Wrote past the output limit
+    OutputLimitExceeded { memory_kb: u64 },
+    /// Stopped for neither using CPU time nor writing output
+    IdlenessLimitExceeded { memory_kb: u64 },
     /// Memory limit exceeded
     MemoryLimitExceeded { memory_kb: u64 },
     /// Runtime error (crash, non-zero exit)
@@ -489,3 +1478,234 @@ enum ExecutionResult {
         memory_kb: u64,
     },
 }
+
+impl ExecutionResult {
+    /// Peak memory of the run
+    fn memory_kb(&self) -> u64 {
+        match *self {
+            Self::Success { memory_kb, .. }
+            | Self::TimeLimitExceeded { memory_kb, .. }
+            | Self::OutputLimitExceeded { memory_kb }
+            | Self::IdlenessLimitExceeded { memory_kb }
+            | Self::MemoryLimitExceeded { memory_kb }
+            | Self::RuntimeError { memory_kb, .. } => memory_kb,
+        }
+    }
+
+    /// Instructions counted for a successful run
+    fn instructions(&self) -> Option<u64> {
+        match *self {
+            Self::Success { instructions, .. } => instructions,
+            _ => None,
+        }
+    }
+
+    /// Test case result of a run that did not exit successfully, `None`
+    /// if it did
+    fn failure(&self, number: i32, elapsed_ms: u64, time_limit_ms: u64) -> Option<TestCaseResult> {
+        match self {
+            Self::Success { .. } => None,
+            Self::TimeLimitExceeded { memory_kb, kind } => Some(
+                TestCaseResult::time_limit_exceeded(number, time_limit_ms, *memory_kb, *kind),
+            ),
+            Self::OutputLimitExceeded { memory_kb } => Some(TestCaseResult::output_limit_exceeded(
+                number, elapsed_ms, *memory_kb,
+            )),
+            Self::IdlenessLimitExceeded { memory_kb } => Some(
+                TestCaseResult::idleness_limit_exceeded(number, elapsed_ms, *memory_kb),
+            ),
+            Self::MemoryLimitExceeded { memory_kb } => Some(TestCaseResult::memory_limit_exceeded(
+                number, elapsed_ms, *memory_kb,
+            )),
+            Self::RuntimeError {
+                exit_code,
+                message,
+                memory_kb,
+            } => {
+                tracing::debug!(
+                    testcase = number,
+                    exit_code,
+                    %message,
+                    "Runtime error on testcase"
+                );
+                Some(TestCaseResult::runtime_error(
+                    number,
+                    elapsed_ms,
+                    *memory_kb,
+                    *exit_code,
+                    message.clone(),
+                ))
+            }
+        }
+    }
+}
+
+/// Test case result for a run the checker judged
+fn checker_verdict(
+    number: i32,
+    elapsed_ms: u64,
+    memory_kb: u64,
+    checker_result: CheckerResult,
+) -> TestCaseResult {
+    match checker_result {
+        CheckerResult::Accepted(comment) => TestCaseResult {
+            checker_comment: Some(comment).filter(|c| !c.is_empty()),
+            ..TestCaseResult::accepted(number, elapsed_ms, memory_kb)
+        },
+        CheckerResult::WrongAnswer(comment) => {
+            TestCaseResult::wrong_answer(number, elapsed_ms, memory_kb, Some(comment))
+        }
+        CheckerResult::PresentationError(comment) => {
+            TestCaseResult::presentation_error(number, elapsed_ms, memory_kb, Some(comment))
+        }
+        CheckerResult::PartialCredit(points, comment) => {
+            TestCaseResult::partial_credit(number, elapsed_ms, memory_kb, points, Some(comment))
+        }
+        CheckerResult::JudgeError(msg) => TestCaseResult::judge_error(number, msg),
+    }
+}
+
+/// Up to `limit + 1` bytes of a file, so a longer file shows as cut;
+/// nothing if it cannot be read
+async fn read_capped(path: &Path, limit: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if let Ok(file) = fs::File::open(path).await {
+        let _ = file.take(limit as u64 + 1).read_to_end(&mut bytes).await;
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMORY_LIMIT_KB: u64 = 256 * 1024;
+    const CPU_LIMIT_MS: u64 = 1000;
+
+    fn usage(memory_kb: u64, cpu_time_ms: u64) -> ResourceUsage {
+        ResourceUsage {
+            memory_kb,
+            cpu_time_ms,
+        }
+    }
+
+    fn killed_by(signal: Signal, usage: &ResourceUsage, oom_killed: bool) -> ExecutionResult {
+        signal_result(
+            signal as i32,
+            usage,
+            oom_killed,
+            MEMORY_LIMIT_KB,
+            CPU_LIMIT_MS,
+        )
+    }
+
+    #[test]
+    fn test_sigxcpu_is_cpu_time_limit() {
+        let result = killed_by(Signal::SIGXCPU, &usage(1024, 900), false);
+        assert!(matches!(
+            result,
+            ExecutionResult::TimeLimitExceeded {
+                kind: TimeLimitKind::Cpu,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_sigkill_past_cpu_limit_is_cpu_time_limit() {
+        let result = killed_by(Signal::SIGKILL, &usage(1024, CPU_LIMIT_MS), false);
+        assert!(matches!(
+            result,
+            ExecutionResult::TimeLimitExceeded {
+                kind: TimeLimitKind::Cpu,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_oom_sigkill_is_memory_limit() {
+        let result = killed_by(Signal::SIGKILL, &usage(1024, CPU_LIMIT_MS), true);
+        assert!(matches!(
+            result,
+            ExecutionResult::MemoryLimitExceeded { memory_kb: 1024 }
+        ));
+
+        let result = killed_by(Signal::SIGKILL, &usage(MEMORY_LIMIT_KB, 10), false);
+        assert!(matches!(
+            result,
+            ExecutionResult::MemoryLimitExceeded { .. }
+        ));
+    }
+
+    #[test]
+    fn test_sigxfsz_is_output_limit() {
+        let result = killed_by(Signal::SIGXFSZ, &usage(1024, 10), false);
+        assert!(matches!(
+            result,
+            ExecutionResult::OutputLimitExceeded { .. }
+        ));
+    }
+
+    #[test]
+    fn test_other_signals_are_runtime_errors() {
+        let result = killed_by(Signal::SIGSEGV, &usage(1024, 10), false);
+        assert!(matches!(
+            result,
+            ExecutionResult::RuntimeError { exit_code, .. } if exit_code == -(Signal::SIGSEGV as i32)
+        ));
+
+        // A SIGKILL under every limit came from outside the run
+        let result = killed_by(Signal::SIGKILL, &usage(1024, 10), false);
+        assert!(matches!(result, ExecutionResult::RuntimeError { .. }));
+    }
+
+    #[test]
+    fn test_cpu_watchdog_is_cpu_time_limit() {
+        let result = stopped_result(Stopped::CpuTime, &usage(1024, 1200), true, 1000);
+        assert!(matches!(
+            result,
+            ExecutionResult::TimeLimitExceeded {
+                kind: TimeLimitKind::Cpu,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_wall_clock_while_computing_is_wall_time_limit() {
+        let result = stopped_result(Stopped::WallClock, &usage(1024, 1000), true, 1000);
+        assert!(matches!(
+            result,
+            ExecutionResult::TimeLimitExceeded {
+                kind: TimeLimitKind::Wall,
+                ..
+            }
+        ));
+
+        // Without CPU accounting the wall clock is the only limit
+        let result = stopped_result(Stopped::WallClock, &usage(1024, 0), false, 1000);
+        assert!(matches!(
+            result,
+            ExecutionResult::TimeLimitExceeded {
+                kind: TimeLimitKind::Wall,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_waiting_is_idleness_limit() {
+        let result = stopped_result(Stopped::WallClock, &usage(1024, 50), true, 1000);
+        assert!(matches!(
+            result,
+            ExecutionResult::IdlenessLimitExceeded { .. }
+        ));
+
+        let result = stopped_result(Stopped::Idle, &usage(1024, 50), true, 1000);
+        assert!(matches!(
+            result,
+            ExecutionResult::IdlenessLimitExceeded { .. }
+        ));
+    }
+}
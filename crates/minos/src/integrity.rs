@@ -0,0 +1,168 @@
+//! Problem bundle integrity checks
+//!
+//! Vanguard records the SHA-256 of every generator, checker and reference
+//! upload in `problem_binary_versions`. Before each judging run Minos hashes
+//! the active binaries the problem is judged with and compares them to the
+//! checksum of the active version, so a binary damaged on disk or only
+//! partly written fails the submission with `problem_integrity_error`
+//! instead of producing wrong verdicts.
+//!
+//! Generated test inputs get a `<input>.sha256` file next to them. A cached
+//! input that no longer matches it is generated again.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use olympus_rules::pipeline;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tokio::fs;
+
+use crate::consumer::JudgeJob;
+
+/// SHA-256 of `data`, hex encoded
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// SHA-256 of a file's contents, hex encoded
+pub async fn sha256_file(path: &Path) -> Result<String> {
+    let data = fs::read(path).await?;
+    Ok(tokio::task::spawn_blocking(move || sha256_hex(&data)).await?)
+}
+
+/// Path of the checksum file recorded for a generated file
+pub fn checksum_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// Record `checksum` as the checksum of the file at `path`
+pub async fn record_checksum(path: &Path, checksum: &str) -> Result<()> {
+    fs::write(checksum_path(path), checksum).await?;
+    Ok(())
+}
+
+/// Whether the file at `path` still matches its recorded checksum. Files
+/// generated before checksums were recorded have none and count as intact.
+pub async fn matches_recorded_checksum(path: &Path) -> Result<bool> {
+    let expected = match fs::read_to_string(checksum_path(path)).await {
+        Ok(expected) => expected,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(sha256_file(path).await? == expected.trim())
+}
+
+/// How long to wait before checking a mismatching binary again
+const RECHECK_DELAY: Duration = Duration::from_secs(2);
+
+/// Check the problem binaries `job` is judged with against the checksums
+/// of their active versions.
+///
+/// Returns a description of the first binary that is missing or does not
+/// match, `None` if all are intact. Binaries uploaded before versioning have
+/// no recorded checksum and are not checked. Vanguard records a new version
+/// before it replaces the active file, so a judge in between sees the new
+/// checksum with the old bytes; a mismatch is only reported if it is still
+/// there when the versions are read and the files hashed again.
+pub async fn check_problem_binaries(
+    db: &PgPool,
+    binaries_path: &Path,
+    job: &JudgeJob,
+) -> Result<Option<String>> {
+    let Some(mismatch) = find_binary_mismatch(db, binaries_path, job).await? else {
+        return Ok(None);
+    };
+    tracing::warn!(
+        problem_id = %job.problem_id,
+        mismatch = %mismatch,
+        "Problem binary does not match its checksum; checking again"
+    );
+    tokio::time::sleep(RECHECK_DELAY).await;
+    find_binary_mismatch(db, binaries_path, job).await
+}
+
+/// One pass of `check_problem_binaries`
+async fn find_binary_mismatch(
+    db: &PgPool,
+    binaries_path: &Path,
+    job: &JudgeJob,
+) -> Result<Option<String>> {
+    let recorded: Vec<(String, i32, String)> = sqlx::query_as(
+        r#"
+        SELECT v.kind, v.version, v.checksum
+        FROM problem_binary_versions v
+        JOIN problems p ON p.id = v.problem_id
+        WHERE v.problem_id = $1
+          AND v.version = CASE v.kind
+                WHEN 'generator' THEN p.generator_version
+                WHEN 'checker' THEN p.checker_version
+                ELSE p.reference_version
+              END
+        "#,
+    )
+    .bind(job.problem_id)
+    .fetch_all(db)
+    .await?;
+
+    let dir = binaries_path.join(job.problem_id.to_string());
+    let required =
+        pipeline::required_binaries(job.pipeline.as_ref(), job.output_comparison.is_some());
+
+    for name in required
+        .into_iter()
+        .filter_map(|program| program.binary_name())
+    {
+        let Some((_, version, checksum)) = recorded.iter().find(|(kind, _, _)| kind == name) else {
+            continue;
+        };
+
+        let actual = match sha256_file(&dir.join(name)).await {
+            Ok(actual) => actual,
+            Err(e) => return Ok(Some(format!("{} v{} is unreadable: {}", name, version, e))),
+        };
+        if &actual != checksum {
+            return Ok(Some(format!(
+                "{} v{} does not match the checksum recorded on upload (expected {}, found {})",
+                name, version, checksum, actual
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recorded_checksum_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input_001.txt");
+        fs::write(&input, b"3\n1 2 3\n").await.unwrap();
+        record_checksum(&input, &sha256_hex(b"3\n1 2 3\n"))
+            .await
+            .unwrap();
+        assert!(matches_recorded_checksum(&input).await.unwrap());
+
+        // A partial write
+        fs::write(&input, b"3\n1 2").await.unwrap();
+        assert!(!matches_recorded_checksum(&input).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_files_without_checksum_count_as_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input_001.txt");
+        fs::write(&input, b"1\n").await.unwrap();
+        assert!(matches_recorded_checksum(&input).await.unwrap());
+        assert_eq!(
+            checksum_path(&input),
+            dir.path().join("input_001.txt.sha256")
+        );
+    }
+}
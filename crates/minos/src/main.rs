@@ -5,12 +5,25 @@
 //! Consumes compiled submissions from Redis Stream, executes them
 //! against test cases in a sandboxed environment, and records verdicts.
 
+mod buffer;
+mod calibration;
+mod capacity;
+mod checker_output;
 mod config;
 mod consumer;
+mod default_checker;
 mod executor;
+mod integrity;
 mod metrics;
+mod network;
+mod perf;
+mod regenerate;
+mod samples;
 mod sandbox;
+mod scoring;
+mod test_limits;
 mod testcase;
+mod time_limit;
 mod verdict;
 
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -19,9 +32,15 @@ use std::sync::Arc;
 use anyhow::Result;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use crate::buffer::ResultBuffer;
+use crate::calibration::Calibration;
+use crate::capacity::{ExecutionSlots, HostCapacity};
+use crate::checker_output::CheckerOutputFilter;
 use crate::config::Config;
-use crate::consumer::JudgeConsumer;
+use crate::consumer::{JudgeConsumer, ResultWriter};
 use crate::metrics::MetricsServer;
+use crate::regenerate::RegenWorker;
+use crate::samples::SampleWorker;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -39,6 +58,18 @@ async fn main() -> Result<()> {
     // Load configuration
     let config = Config::from_env();
     tracing::info!("Environment: {}", config.environment);
+    tracing::info!(
+        "Worker profile: {} (reading {})",
+        config.worker_profile,
+        config.run_stream()
+    );
+    match &config.execution.egress_netns {
+        Some(netns) => tracing::info!(
+            "Network-enabled submissions use egress netns {}",
+            netns.display()
+        ),
+        None => tracing::info!("No egress netns configured — all submissions run without network"),
+    }
 
     // Create database pool
     tracing::info!("Connecting to database...");
@@ -91,8 +122,73 @@ async fn main() -> Result<()> {
         }
     });
 
+    // Calibrate this worker against the reference judge host
+    let calibration = Calibration::run(&config.calibration).await;
+    metrics::CALIBRATION_FACTOR.set(calibration.factor);
+    metrics::WORKER_PROFILE
+        .with_label_values(&[&config.worker_profile])
+        .set(1);
+
+    // Submission runs of all workers share the host's cores and memory
+    let capacity = HostCapacity::detect().limited(
+        config.execution.execution_cores,
+        config.execution.execution_memory_mb,
+    );
+    tracing::info!(
+        "Execution capacity: {} cores, {} MB",
+        capacity.cores,
+        capacity.memory_mb
+    );
+    let slots = Arc::new(ExecutionSlots::new(capacity));
+
+    // Serve test input regeneration and reproduction requests alongside judging
+    let regen_worker = RegenWorker::new(
+        config.clone(),
+        db_pool.clone(),
+        redis_pool.clone(),
+        shutdown.clone(),
+        calibration,
+        slots.clone(),
+    );
+    tokio::spawn(regen_worker.run());
+
+    // Serve sample runs on their own stream so they never wait behind judging
+    let sample_worker = SampleWorker::new(
+        config.clone(),
+        db_pool.clone(),
+        redis_pool.clone(),
+        shutdown.clone(),
+        slots.clone(),
+    );
+    tokio::spawn(sample_worker.run());
+
+    // Keep results judged during database outages and flush them when it returns
+    let buffer = Arc::new(ResultBuffer::open(
+        config.storage.result_buffer_path.clone(),
+    )?);
+    let writer = Arc::new(ResultWriter::new(
+        db_pool.clone(),
+        redis_pool.clone(),
+        config.worker_id.clone(),
+        CheckerOutputFilter::from_config(&config),
+    ));
+    tokio::spawn(buffer::run_flusher(
+        buffer.clone(),
+        writer.clone(),
+        shutdown.clone(),
+    ));
+
     // Create and initialize consumer
-    let mut consumer = JudgeConsumer::new(config, db_pool, redis_pool, shutdown);
+    let mut consumer = JudgeConsumer::new(
+        config,
+        db_pool,
+        redis_pool,
+        shutdown,
+        calibration,
+        writer,
+        buffer,
+        slots,
+    );
     consumer.initialize().await?;
 
     tracing::info!("Minos ready, starting judge consumer loop");
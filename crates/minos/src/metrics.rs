@@ -3,8 +3,8 @@
 use std::sync::LazyLock;
 
 use prometheus::{
-    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
-    TextEncoder,
+    Encoder, Gauge, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    Opts, Registry, TextEncoder,
 };
 
 /// Global metrics registry
@@ -37,6 +37,15 @@ pub static MEMORY_USAGE: LazyLock<HistogramVec> = LazyLock::new(|| {
     HistogramVec::new(opts, &["problem_id"]).expect("Failed to create histogram")
 });
 
+/// Bytes transferred by submissions run in the egress namespace
+pub static NETWORK_BYTES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    let opts = Opts::new(
+        "judge_network_bytes_total",
+        "Bytes sent and received by network-enabled submissions",
+    );
+    IntCounterVec::new(opts, &["problem_id"]).expect("Failed to create counter")
+});
+
 /// Verdict counter by type
 pub static VERDICT_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
     let opts = Opts::new("judge_verdict_total", "Total verdicts by type");
@@ -70,6 +79,69 @@ pub static TESTCASES_GENERATED: LazyLock<IntCounter> = LazyLock::new(|| {
     .expect("Failed to create counter")
 });
 
+/// Calibration speed factor of this worker relative to the reference host
+pub static CALIBRATION_FACTOR: LazyLock<Gauge> = LazyLock::new(|| {
+    Gauge::new(
+        "judge_calibration_factor",
+        "Benchmark speed factor of this judge worker (1.0 = reference)",
+    )
+    .expect("Failed to create gauge")
+});
+
+/// Results waiting in the local buffer for the database
+pub static BUFFERED_RESULTS: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "judge_buffered_results",
+        "Judged results waiting in the local buffer for the database",
+    )
+    .expect("Failed to create gauge")
+});
+
+/// Buffered results flushed
+pub static BUFFERED_RESULTS_FLUSHED: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "judge_buffered_results_flushed_total",
+        "Buffered results flushed once the database was reachable",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Worker profile this instance judges, always 1
+pub static WORKER_PROFILE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    let opts = Opts::new(
+        "judge_worker_profile_info",
+        "Worker profile whose run streams this judge worker reads",
+    );
+    IntGaugeVec::new(opts, &["profile"]).expect("Failed to create gauge")
+});
+
+/// Cores and memory submission runs may reserve on this host
+pub static EXECUTION_CAPACITY: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    let opts = Opts::new(
+        "judge_execution_capacity",
+        "Cores and memory bytes concurrent submission runs may reserve",
+    );
+    IntGaugeVec::new(opts, &["resource"]).expect("Failed to create gauge")
+});
+
+/// Cores and memory reserved by running submissions
+pub static EXECUTION_IN_USE: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    let opts = Opts::new(
+        "judge_execution_in_use",
+        "Cores and memory bytes reserved by running submissions",
+    );
+    IntGaugeVec::new(opts, &["resource"]).expect("Failed to create gauge")
+});
+
+/// Submission runs waiting for cores or memory
+pub static EXECUTIONS_WAITING: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "judge_executions_waiting",
+        "Submission runs waiting for host capacity",
+    )
+    .expect("Failed to create gauge")
+});
+
 /// Initialize and register all metrics
 pub fn init_metrics() {
     REGISTRY
@@ -78,6 +150,9 @@ pub fn init_metrics() {
     REGISTRY
         .register(Box::new(MEMORY_USAGE.clone()))
         .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(NETWORK_BYTES.clone()))
+        .expect("Failed to register metric");
     REGISTRY
         .register(Box::new(VERDICT_TOTAL.clone()))
         .expect("Failed to register metric");
@@ -93,6 +168,27 @@ pub fn init_metrics() {
     REGISTRY
         .register(Box::new(TESTCASES_GENERATED.clone()))
         .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(CALIBRATION_FACTOR.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(BUFFERED_RESULTS.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(BUFFERED_RESULTS_FLUSHED.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(WORKER_PROFILE.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(EXECUTION_CAPACITY.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(EXECUTION_IN_USE.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(EXECUTIONS_WAITING.clone()))
+        .expect("Failed to register metric");
 }
 
 /// Record a verdict
@@ -110,6 +206,11 @@ pub fn record_execution(problem_id: &str, duration_secs: f64, memory_bytes: u64)
         .observe(memory_bytes as f64);
 }
 
+/// Record traffic of a network-enabled submission
+pub fn record_network(problem_id: &str, bytes: u64) {
+    NETWORK_BYTES.with_label_values(&[problem_id]).inc_by(bytes);
+}
+
 /// HTTP server for Prometheus metrics endpoint
 pub struct MetricsServer;
 
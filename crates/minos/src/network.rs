@@ -0,0 +1,138 @@
+//! Restricted egress for network-enabled submissions.
+//!
+//! Submissions of `network_allowed` problems join a pre-provisioned network
+//! namespace (`SUBMISSION_EGRESS_NETNS`, built by
+//! `scripts/setup-egress-netns.sh`) instead of getting an empty one. Its only
+//! way out is a NAT'd veth pair with metadata, private and loopback ranges
+//! dropped and bandwidth capped by `tc`.
+//!
+//! The namespace is shared by every run of the process, so network-enabled
+//! runs take turns in it: the traffic of a run is then the change in the
+//! namespace interface's byte counters across it, with nothing else moving
+//! them. Without a configured namespace these submissions stay fully
+//! isolated.
+
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use nix::sched::{setns, CloneFlags};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::config::ExecutionConfig;
+
+/// Held by the run currently using the egress namespace
+static NAMESPACE_IN_USE: Mutex<()> = Mutex::const_new(());
+
+/// The egress namespace submissions are attached to
+#[derive(Debug, Clone)]
+pub struct EgressNetwork {
+    netns: PathBuf,
+    interface: String,
+}
+
+impl EgressNetwork {
+    pub fn new(netns: PathBuf, interface: String) -> Self {
+        Self { netns, interface }
+    }
+
+    pub fn from_config(config: &ExecutionConfig) -> Option<Self> {
+        config
+            .egress_netns
+            .clone()
+            .map(|netns| Self::new(netns, config.egress_interface.clone()))
+    }
+
+    /// Open the namespace so a child can join it with `setns` after fork
+    pub fn open(&self) -> Result<File> {
+        File::open(&self.netns)
+            .with_context(|| format!("Cannot open egress netns {}", self.netns.display()))
+    }
+
+    /// Wait for the namespace to be free and claim it for one run
+    pub async fn begin_run(&self) -> Result<EgressRun<'_>> {
+        let guard = NAMESPACE_IN_USE.lock().await;
+        let before = self.bytes_transferred().await?;
+        Ok(EgressRun {
+            network: self,
+            before,
+            _guard: guard,
+        })
+    }
+
+    /// Bytes received plus sent on the namespace's interface so far
+    async fn bytes_transferred(&self) -> Result<u64> {
+        let netns = self.open()?;
+        let interface = self.interface.clone();
+
+        tokio::task::spawn_blocking(move || {
+            // setns switches the calling thread for good, so the counters
+            // are read from a throwaway thread rather than a pool thread
+            std::thread::spawn(move || {
+                setns(&netns, CloneFlags::CLONE_NEWNET)?;
+                let dev = std::fs::read_to_string("/proc/thread-self/net/dev")?;
+                parse_net_dev(&dev, &interface)
+                    .ok_or_else(|| anyhow!("Interface {} not found in egress netns", interface))
+            })
+            .join()
+            .map_err(|_| anyhow!("Egress counter thread panicked"))?
+        })
+        .await?
+    }
+}
+
+/// A run holding the egress namespace
+pub struct EgressRun<'a> {
+    network: &'a EgressNetwork,
+    before: u64,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl EgressRun<'_> {
+    /// Release the namespace, returning the bytes the run transferred
+    pub async fn finish(self) -> Result<u64> {
+        let after = self.network.bytes_transferred().await?;
+        Ok(after.saturating_sub(self.before))
+    }
+}
+
+/// Sum of the receive and transmit byte counters of `interface` in the
+/// contents of `/proc/net/dev`
+fn parse_net_dev(dev: &str, interface: &str) -> Option<u64> {
+    dev.lines().skip(2).find_map(|line| {
+        let (name, counters) = line.split_once(':')?;
+        if name.trim() != interface {
+            return None;
+        }
+        let fields: Vec<u64> = counters
+            .split_whitespace()
+            .map(|f| f.parse().ok())
+            .collect::<Option<_>>()?;
+        // rx_bytes is the first column, tx_bytes the ninth
+        Some(fields.first()? + fields.get(8)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:     120       2    0    0    0     0          0         0      120       2    0    0    0     0       0          0
+egress0:   15000      12    0    0    0     0          0         0     2500      10    0    0    0     0       0          0
+";
+
+    #[test]
+    fn test_parse_net_dev() {
+        assert_eq!(parse_net_dev(NET_DEV, "egress0"), Some(17_500));
+        assert_eq!(parse_net_dev(NET_DEV, "lo"), Some(240));
+    }
+
+    #[test]
+    fn test_parse_net_dev_missing_interface() {
+        assert_eq!(parse_net_dev(NET_DEV, "eth0"), None);
+        assert_eq!(parse_net_dev("", "egress0"), None);
+    }
+}
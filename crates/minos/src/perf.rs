@@ -0,0 +1,163 @@
+//! CPU instruction counting via perf counters.
+//!
+//! Wall-clock time varies with load and hardware, so benchmarking contests
+//! can rank by retired instructions instead. A counter is opened on every
+//! online CPU for the run's cgroup before the process is spawned, counting
+//! user-space instructions of every task in it (threads and children
+//! included). This needs cgroups v2, a hardware PMU and `CAP_PERFMON` (or
+//! `CAP_SYS_ADMIN`); when any of them is missing no count is recorded.
+
+use std::fs::File;
+use std::io::Read;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+
+/// `perf_event_attr` flag bits
+const ATTR_EXCLUDE_KERNEL: u64 = 1 << 5;
+const ATTR_EXCLUDE_HV: u64 = 1 << 6;
+
+/// `read_format` bits: report how long the counter was scheduled, so counts
+/// can be scaled when the PMU is multiplexed
+const FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+const FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+
+const PERF_FLAG_PID_CGROUP: libc::c_ulong = 1 << 2;
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+/// First 64 bytes of `struct perf_event_attr` (`PERF_ATTR_SIZE_VER0`)
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    kind: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+/// Instructions retired by the tasks of one cgroup
+pub struct InstructionCounter {
+    counters: Vec<OwnedFd>,
+}
+
+impl InstructionCounter {
+    /// Start counting for `cgroup_dir` on every online CPU
+    pub fn open(cgroup_dir: &Path) -> Result<Self> {
+        let online = std::fs::read_to_string("/sys/devices/system/cpu/online")
+            .context("read online CPUs")?;
+        let cpus = parse_cpu_list(&online)
+            .ok_or_else(|| anyhow!("Unexpected online CPU list: {}", online.trim()))?;
+        let cgroup = File::open(cgroup_dir).context("open cgroup dir")?;
+
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config: PERF_COUNT_HW_INSTRUCTIONS,
+            read_format: FORMAT_TOTAL_TIME_ENABLED | FORMAT_TOTAL_TIME_RUNNING,
+            flags: ATTR_EXCLUDE_KERNEL | ATTR_EXCLUDE_HV,
+            ..Default::default()
+        };
+
+        let counters = cpus
+            .into_iter()
+            .map(|cpu| {
+                // SAFETY: attr outlives the call and matches the size it reports
+                let fd = unsafe {
+                    libc::syscall(
+                        libc::SYS_perf_event_open,
+                        &attr as *const PerfEventAttr,
+                        cgroup.as_raw_fd(),
+                        cpu as libc::c_int,
+                        -1 as libc::c_int,
+                        PERF_FLAG_PID_CGROUP | PERF_FLAG_FD_CLOEXEC,
+                    )
+                };
+                if fd < 0 {
+                    return Err(anyhow!(
+                        "perf_event_open on CPU {}: {}",
+                        cpu,
+                        std::io::Error::last_os_error()
+                    ));
+                }
+                // SAFETY: a non-negative return is a new fd owned by us
+                Ok(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { counters })
+    }
+
+    /// Instructions counted so far, summed over all CPUs
+    pub fn read(&self) -> Result<u64> {
+        let mut total = 0u64;
+        for counter in &self.counters {
+            let mut buf = [0u8; 24];
+            File::from(counter.try_clone()?).read_exact(&mut buf)?;
+            let field = |i: usize| u64::from_ne_bytes(buf[i * 8..i * 8 + 8].try_into().unwrap());
+            total = total.saturating_add(scale_count(field(0), field(1), field(2)));
+        }
+        Ok(total)
+    }
+}
+
+/// Parse a kernel CPU list such as `0-3,6,8-9`
+fn parse_cpu_list(list: &str) -> Option<Vec<u32>> {
+    let mut cpus = Vec::new();
+    for part in list.trim().split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((first, last)) => cpus.extend(first.parse::<u32>().ok()?..=last.parse().ok()?),
+            None => cpus.push(part.parse().ok()?),
+        }
+    }
+    (!cpus.is_empty()).then_some(cpus)
+}
+
+/// Extrapolate a count to the whole run when the counter was only scheduled
+/// on the PMU for part of it
+fn scale_count(value: u64, time_enabled: u64, time_running: u64) -> u64 {
+    if time_running == 0 {
+        0
+    } else if time_running >= time_enabled {
+        value
+    } else {
+        (value as u128 * time_enabled as u128 / time_running as u128) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attr_matches_ver0_size() {
+        assert_eq!(std::mem::size_of::<PerfEventAttr>(), 64);
+    }
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0\n"), Some(vec![0]));
+        assert_eq!(parse_cpu_list("0-3"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(parse_cpu_list("0-1,4,6-7\n"), Some(vec![0, 1, 4, 6, 7]));
+        assert_eq!(parse_cpu_list(""), None);
+        assert_eq!(parse_cpu_list("0-x"), None);
+    }
+
+    #[test]
+    fn test_scale_count() {
+        assert_eq!(scale_count(1_000, 50, 50), 1_000);
+        // Scheduled for half the run: extrapolate
+        assert_eq!(scale_count(1_000, 100, 50), 2_000);
+        // Never scheduled (e.g. no task of the cgroup ran on this CPU)
+        assert_eq!(scale_count(0, 100, 0), 0);
+    }
+}
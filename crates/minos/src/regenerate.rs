@@ -0,0 +1,856 @@
+//! On-demand test input regeneration and single-test reproduction
+//!
+//! Horus evicts cached test cases, so a download of a test's input can find
+//! it missing. Vanguard then queues a request on the regeneration stream and
+//! whichever Minos worker reads it reruns the generator with the seed
+//! recorded for that test.
+//!
+//! Admins can also ask for one test of a submission to be run again. Those
+//! requests arrive on the same stream (`kind = reproduce`) and point at a
+//! `test_reproductions` row, which the worker fills in with the new result.
+//! Debug runs are reproductions with a capture limit: the worker also keeps
+//! the program's stdout, stderr and output and the checker's comment.
+//!
+//! Setters run their checker's self-test suite the same way (`kind =
+//! checker_test`): the worker runs the checker on every case of the suite
+//! and records each verdict in `checker_test_results`.
+//!
+//! Time limit calibrations (`kind = calibrate`) run every reference solution
+//! of a problem on all tests under a raised limit, record each one's slowest
+//! test and store the suggested time limit on the `time_limit_calibrations`
+//! row, along with the worker and its speed factor.
+//!
+//! Reference baselines (`kind = baseline`) judge a problem's model reference
+//! solution under a performance-scored contest's limits and store its time
+//! and memory on the `contest_problem_baselines` row for the leaderboard.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use deadpool_redis::redis;
+use olympus_common::ReferenceRole;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::calibration::Calibration;
+use crate::capacity::ExecutionSlots;
+use crate::checker_output::CheckerOutputFilter;
+use crate::config::Config;
+use crate::consumer::JudgeJob;
+use crate::executor::{ExecutionContext, Executor};
+use crate::testcase::{CheckerResult, TestCaseManager};
+use crate::time_limit::{suggest_time_limit, ReferenceRun};
+use crate::verdict::{SubmissionResult, Verdict};
+
+/// A single request read from the regeneration stream
+#[derive(Debug)]
+struct RegenRequest {
+    message_id: String,
+    kind: RequestKind,
+}
+
+#[derive(Debug)]
+enum RequestKind {
+    /// Regenerate an evicted input
+    Input {
+        problem_id: Uuid,
+        test: i32,
+        seed: String,
+        version: i32,
+    },
+    /// Run one test of a submission again
+    Reproduce { reproduction_id: Uuid },
+    /// Run a problem's checker self-test suite
+    CheckerTest { run_id: Uuid },
+    /// Calibrate a problem's time limit from its reference solutions
+    Calibrate { calibration_id: Uuid },
+    /// Judge a contest problem's reference solution as a baseline
+    Baseline { baseline_id: Uuid },
+}
+
+/// Pending reproduction loaded from `test_reproductions`
+#[derive(Debug, sqlx::FromRow)]
+struct ReproductionRow {
+    submission_id: Uuid,
+    test_case_number: i32,
+    generator_seed: String,
+    generator_version: i32,
+    /// Set for debug runs
+    capture_limit_bytes: Option<i32>,
+}
+
+/// A case of a checker self-test suite
+#[derive(Debug, sqlx::FromRow)]
+struct CheckerTestRow {
+    test_number: i32,
+    input: String,
+    output: String,
+    answer: Option<String>,
+    expected_verdict: String,
+}
+
+/// Pending calibration loaded from `time_limit_calibrations`
+#[derive(Debug, sqlx::FromRow)]
+struct CalibrationRow {
+    problem_id: Uuid,
+    multiplier: f64,
+    ceiling_ms: i32,
+}
+
+/// Pending baseline loaded from `contest_problem_baselines`, with the
+/// contest's limits for the problem
+#[derive(Debug, sqlx::FromRow)]
+struct BaselineRow {
+    contest_id: Uuid,
+    problem_id: Uuid,
+    submission_id: Uuid,
+    time_limit_ms: i32,
+    memory_limit_kb: i32,
+    max_threads: i32,
+    network_allowed: bool,
+}
+
+/// Worker that serves the regeneration stream
+pub struct RegenWorker {
+    config: Config,
+    db_pool: PgPool,
+    redis_pool: deadpool_redis::Pool,
+    shutdown: Arc<AtomicBool>,
+    testcase_manager: TestCaseManager,
+    executor: Executor,
+    /// Speed factor recorded with time limit calibrations
+    calibration: Calibration,
+    checker_output: CheckerOutputFilter,
+}
+
+impl RegenWorker {
+    /// Create a new regeneration worker
+    pub fn new(
+        config: Config,
+        db_pool: PgPool,
+        redis_pool: deadpool_redis::Pool,
+        shutdown: Arc<AtomicBool>,
+        calibration: Calibration,
+        slots: Arc<ExecutionSlots>,
+    ) -> Self {
+        let testcase_manager =
+            TestCaseManager::new(config.storage.clone(), config.execution.clone());
+        let executor = Executor::new(config.storage.clone(), config.execution.clone(), slots);
+        let checker_output = CheckerOutputFilter::from_config(&config);
+
+        Self {
+            config,
+            db_pool,
+            redis_pool,
+            shutdown,
+            testcase_manager,
+            executor,
+            calibration,
+            checker_output,
+        }
+    }
+
+    /// Create the consumer group (ignore error if it already exists)
+    async fn initialize(&self) -> Result<()> {
+        let mut conn = self.redis_pool.get().await?;
+
+        let result: Result<(), redis::RedisError> = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(&self.config.regen_stream_name)
+            .arg(&self.config.consumer_group)
+            .arg("$")
+            .arg("MKSTREAM")
+            .query_async(&mut *conn)
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) if e.to_string().contains("BUSYGROUP") => Ok(()),
+            Err(e) => Err(anyhow!(
+                "Failed to create regeneration consumer group: {}",
+                e
+            )),
+        }
+    }
+
+    /// Run the worker loop
+    pub async fn run(self) {
+        if let Err(e) = self.initialize().await {
+            tracing::error!("{}", e);
+        }
+
+        while !self.shutdown.load(Ordering::SeqCst) {
+            if let Err(e) = self.process_next_request().await {
+                let err_msg = e.to_string();
+                tracing::error!("Error processing regeneration request: {}", err_msg);
+
+                if err_msg.contains("NOGROUP") {
+                    if let Err(init_err) = self.initialize().await {
+                        tracing::error!("{}", init_err);
+                    }
+                }
+
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+
+    /// Serve the next request, if any. Requests a dead worker left pending
+    /// for `regen_claim_idle_ms` are served before new ones.
+    async fn process_next_request(&self) -> Result<()> {
+        let mut conn = self.redis_pool.get().await?;
+
+        // XAUTOCLAIM replies [next_cursor, [[id, fields]], deleted_ids]
+        let claimed: Vec<redis::Value> = redis::cmd("XAUTOCLAIM")
+            .arg(&self.config.regen_stream_name)
+            .arg(&self.config.consumer_group)
+            .arg(&self.config.worker_id)
+            .arg(self.config.regen_claim_idle_ms)
+            .arg("0-0")
+            .arg("COUNT")
+            .arg(1)
+            .query_async(&mut *conn)
+            .await?;
+
+        let message = match claimed.get(1) {
+            Some(redis::Value::Array(messages)) if !messages.is_empty() => {
+                tracing::info!("Claimed an abandoned regeneration request");
+                messages.first().cloned()
+            }
+            _ => {
+                let result: Vec<redis::Value> = redis::cmd("XREADGROUP")
+                    .arg("GROUP")
+                    .arg(&self.config.consumer_group)
+                    .arg(&self.config.worker_id)
+                    .arg("COUNT")
+                    .arg(1)
+                    .arg("BLOCK")
+                    .arg(self.config.block_timeout_ms)
+                    .arg("STREAMS")
+                    .arg(&self.config.regen_stream_name)
+                    .arg(">")
+                    .query_async(&mut *conn)
+                    .await?;
+                first_message(&result).cloned()
+            }
+        };
+        let Some(redis::Value::Array(message)) = message else {
+            return Ok(());
+        };
+
+        let message_id = match message.first() {
+            Some(redis::Value::BulkString(id)) => String::from_utf8_lossy(id).to_string(),
+            _ => return Err(anyhow!("Invalid message ID")),
+        };
+
+        // A malformed request would be claimed again forever; drop it
+        let request = match parse_request(&message_id, &message) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::error!(
+                    "Dropping malformed regeneration request {}: {}",
+                    message_id,
+                    e
+                );
+                self.ack(&mut conn, &message_id).await?;
+                return Ok(());
+            }
+        };
+
+        match request.kind {
+            RequestKind::Input {
+                problem_id,
+                test,
+                seed,
+                version,
+            } => {
+                // Failures are logged, not retried: the download is simply retried
+                if let Err(e) = self
+                    .testcase_manager
+                    .regenerate_input(problem_id, test, &seed, version)
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to regenerate input {} for problem {}: {}",
+                        test,
+                        problem_id,
+                        e
+                    );
+                }
+            }
+            RequestKind::Reproduce { reproduction_id } => {
+                if let Err(e) = self.reproduce(reproduction_id).await {
+                    tracing::error!("Reproduction {} failed: {}", reproduction_id, e);
+                    sqlx::query(
+                        r#"
+                        UPDATE test_reproductions
+                        SET status = 'failed', error = $1, completed_at = NOW()
+                        WHERE id = $2
+                        "#,
+                    )
+                    .bind(e.to_string())
+                    .bind(reproduction_id)
+                    .execute(&self.db_pool)
+                    .await?;
+                }
+            }
+            RequestKind::CheckerTest { run_id } => {
+                if let Err(e) = self.run_checker_tests(run_id).await {
+                    tracing::error!("Checker test run {} failed: {}", run_id, e);
+                    sqlx::query(
+                        r#"
+                        UPDATE checker_test_runs
+                        SET status = 'failed', error = $1, completed_at = NOW()
+                        WHERE id = $2
+                        "#,
+                    )
+                    .bind(e.to_string())
+                    .bind(run_id)
+                    .execute(&self.db_pool)
+                    .await?;
+                }
+            }
+            RequestKind::Calibrate { calibration_id } => {
+                if let Err(e) = self.calibrate_time_limit(calibration_id).await {
+                    tracing::error!("Time limit calibration {} failed: {}", calibration_id, e);
+                    sqlx::query(
+                        r#"
+                        UPDATE time_limit_calibrations
+                        SET status = 'failed', error = $1, worker_id = $2, completed_at = NOW()
+                        WHERE id = $3
+                        "#,
+                    )
+                    .bind(e.to_string())
+                    .bind(&self.config.worker_id)
+                    .bind(calibration_id)
+                    .execute(&self.db_pool)
+                    .await?;
+                }
+            }
+            RequestKind::Baseline { baseline_id } => {
+                if let Err(e) = self.judge_baseline(baseline_id).await {
+                    tracing::error!("Reference baseline {} failed: {}", baseline_id, e);
+                    sqlx::query(
+                        r#"
+                        UPDATE contest_problem_baselines
+                        SET status = 'failed', error = $1, worker_id = $2, completed_at = NOW()
+                        WHERE id = $3
+                        "#,
+                    )
+                    .bind(e.to_string())
+                    .bind(&self.config.worker_id)
+                    .bind(baseline_id)
+                    .execute(&self.db_pool)
+                    .await?;
+                }
+            }
+        }
+
+        self.ack(&mut conn, &request.message_id).await
+    }
+
+    async fn ack(&self, conn: &mut deadpool_redis::Connection, message_id: &str) -> Result<()> {
+        redis::cmd("XACK")
+            .arg(&self.config.regen_stream_name)
+            .arg(&self.config.consumer_group)
+            .arg(message_id)
+            .query_async::<i64>(&mut **conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Run a pending reproduction and record its result
+    async fn reproduce(&self, reproduction_id: Uuid) -> Result<()> {
+        let Some(row) = sqlx::query_as::<_, ReproductionRow>(
+            r#"
+            SELECT submission_id, test_case_number, generator_seed, generator_version,
+                   capture_limit_bytes
+            FROM test_reproductions
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(reproduction_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let job = JudgeJob::load(&self.db_pool, row.submission_id, 0).await?;
+        if job.generator_version != row.generator_version {
+            return Err(anyhow!(
+                "Generator changed since the test was judged (v{} -> v{})",
+                row.generator_version,
+                job.generator_version
+            ));
+        }
+
+        let ctx = job.execution_context()?;
+        let (result, capture) = match row.capture_limit_bytes {
+            Some(limit) => {
+                let (result, capture) = self
+                    .executor
+                    .debug_test(
+                        &ctx,
+                        row.test_case_number,
+                        &row.generator_seed,
+                        reproduction_id,
+                        limit.max(0) as usize,
+                    )
+                    .await?;
+                (result, Some(capture))
+            }
+            None => {
+                let result = self
+                    .executor
+                    .reproduce_test(
+                        &ctx,
+                        row.test_case_number,
+                        &row.generator_seed,
+                        reproduction_id,
+                    )
+                    .await?;
+                (result, None)
+            }
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE test_reproductions
+            SET status = 'completed', verdict = $1, time_ms = $2, memory_kb = $3,
+                checker_output = $4, stdout = $5, stderr = $6, program_output = $7,
+                checker_log = $8, output_truncated = $9, completed_at = NOW()
+            WHERE id = $10
+            "#,
+        )
+        .bind(result.verdict.to_db_string())
+        .bind(result.time_ms as i64)
+        .bind(result.memory_kb as i64)
+        .bind(
+            result
+                .error_message
+                .as_deref()
+                .map(|m| self.checker_output.apply(m)),
+        )
+        .bind(capture.as_ref().map(|c| c.stdout.as_str()))
+        .bind(capture.as_ref().map(|c| c.stderr.as_str()))
+        .bind(capture.as_ref().map(|c| c.output.as_str()))
+        .bind(capture.as_ref().and_then(|c| c.checker_log.as_deref()))
+        .bind(capture.as_ref().map(|c| c.truncated))
+        .bind(reproduction_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        tracing::info!(
+            "Reproduced test {} of submission {}: {}",
+            row.test_case_number,
+            row.submission_id,
+            result.verdict.to_db_string()
+        );
+
+        Ok(())
+    }
+
+    /// Run a pending checker self-test suite and record each case's verdict
+    async fn run_checker_tests(&self, run_id: Uuid) -> Result<()> {
+        let Some(problem_id) = sqlx::query_scalar::<_, Uuid>(
+            "SELECT problem_id FROM checker_test_runs WHERE id = $1 AND status = 'pending'",
+        )
+        .bind(run_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let tests = sqlx::query_as::<_, CheckerTestRow>(
+            r#"
+            SELECT test_number, input, output, answer, expected_verdict
+            FROM checker_tests
+            WHERE problem_id = $1
+            ORDER BY test_number
+            "#,
+        )
+        .bind(problem_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let dir = self
+            .config
+            .storage
+            .temp_path
+            .join(format!("checker_test_{}", run_id));
+        tokio::fs::create_dir_all(&dir).await?;
+        let result = self.check_cases(problem_id, run_id, &tests, &dir).await;
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        let mismatches = result?;
+
+        sqlx::query(
+            r#"
+            UPDATE checker_test_runs
+            SET status = 'completed', total = $1, mismatches = $2, completed_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(tests.len() as i32)
+        .bind(mismatches)
+        .bind(run_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        tracing::info!(
+            "Checker self-test of problem {}: {} of {} case(s) mismatched",
+            problem_id,
+            mismatches,
+            tests.len()
+        );
+
+        Ok(())
+    }
+
+    /// Run the checker on every case in `dir`, returning the mismatches
+    async fn check_cases(
+        &self,
+        problem_id: Uuid,
+        run_id: Uuid,
+        tests: &[CheckerTestRow],
+        dir: &std::path::Path,
+    ) -> Result<i32> {
+        let mut mismatches = 0;
+        for test in tests {
+            let input_path = dir.join(format!("input_{:03}.txt", test.test_number));
+            let output_path = dir.join(format!("output_{:03}.txt", test.test_number));
+            tokio::fs::write(&input_path, &test.input).await?;
+            tokio::fs::write(&output_path, &test.output).await?;
+            let answer_path = match &test.answer {
+                Some(answer) => {
+                    let path = dir.join(format!("answer_{:03}.txt", test.test_number));
+                    tokio::fs::write(&path, answer).await?;
+                    path
+                }
+                // Judging passes the input as the answer
+                None => input_path.clone(),
+            };
+
+            let (verdict, comment) = match self
+                .testcase_manager
+                .run_checker(problem_id, &input_path, &output_path, &answer_path)
+                .await
+            {
+                Ok(result) => result.into_verdict(),
+                Err(e) => CheckerResult::JudgeError(e.to_string()).into_verdict(),
+            };
+            if verdict.as_str() != test.expected_verdict {
+                mismatches += 1;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO checker_test_results
+                    (run_id, test_number, expected_verdict, verdict, checker_output)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(run_id)
+            .bind(test.test_number)
+            .bind(&test.expected_verdict)
+            .bind(verdict.as_str())
+            .bind(self.checker_output.apply(&comment))
+            .execute(&self.db_pool)
+            .await?;
+        }
+        Ok(mismatches)
+    }
+
+    /// Run every reference solution of a pending calibration and record the
+    /// suggested time limit
+    async fn calibrate_time_limit(&self, calibration_id: Uuid) -> Result<()> {
+        let Some(row) = sqlx::query_as::<_, CalibrationRow>(
+            r#"
+            SELECT problem_id, multiplier, ceiling_ms
+            FROM time_limit_calibrations
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(calibration_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let solutions: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT submission_id, role
+            FROM problem_reference_solutions
+            WHERE problem_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(row.problem_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut runs = Vec::with_capacity(solutions.len());
+        for (submission_id, role) in solutions {
+            let role = if role == ReferenceRole::Slow.as_str() {
+                ReferenceRole::Slow
+            } else {
+                ReferenceRole::Model
+            };
+
+            let (run, passed, total, max_memory_kb, error) = match self
+                .run_reference(submission_id, row.ceiling_ms as u64)
+                .await
+            {
+                Ok(result) => (
+                    ReferenceRun {
+                        role,
+                        verdict: result.verdict,
+                        max_time_ms: result.max_time_ms,
+                    },
+                    result.passed_count,
+                    result.total_count,
+                    result.max_memory_kb,
+                    None,
+                ),
+                Err(e) => (
+                    ReferenceRun {
+                        role,
+                        verdict: Verdict::JudgeError,
+                        max_time_ms: 0,
+                    },
+                    0,
+                    0,
+                    0,
+                    Some(e.to_string()),
+                ),
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO time_limit_calibration_results
+                    (calibration_id, submission_id, role, verdict, max_time_ms,
+                     max_memory_kb, passed, total, error)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                "#,
+            )
+            .bind(calibration_id)
+            .bind(submission_id)
+            .bind(role.as_str())
+            .bind(run.verdict.to_db_string())
+            .bind(run.max_time_ms as i32)
+            .bind(max_memory_kb as i32)
+            .bind(passed)
+            .bind(total)
+            .bind(error)
+            .execute(&self.db_pool)
+            .await?;
+
+            runs.push(run);
+        }
+
+        let suggestion = suggest_time_limit(&runs, row.multiplier).map_err(|e| anyhow!(e))?;
+
+        sqlx::query(
+            r#"
+            UPDATE time_limit_calibrations
+            SET status = 'completed', model_time_ms = $1, slow_time_ms = $2,
+                suggested_time_limit_ms = $3, separation = $4, worker_id = $5,
+                worker_speed_factor = $6, completed_at = NOW()
+            WHERE id = $7
+            "#,
+        )
+        .bind(suggestion.model_time_ms as i32)
+        .bind(suggestion.slow_time_ms.map(|ms| ms as i32))
+        .bind(suggestion.time_limit_ms as i32)
+        .bind(suggestion.separation.as_str())
+        .bind(&self.config.worker_id)
+        .bind(self.calibration.factor)
+        .bind(calibration_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        tracing::info!(
+            "Calibrated time limit of problem {}: {} ms ({}), model {} ms, slow {:?} ms",
+            row.problem_id,
+            suggestion.time_limit_ms,
+            suggestion.separation.as_str(),
+            suggestion.model_time_ms,
+            suggestion.slow_time_ms
+        );
+
+        Ok(())
+    }
+
+    /// Judge a pending baseline's reference solution the way the contest's
+    /// submissions are judged and record its time and memory
+    async fn judge_baseline(&self, baseline_id: Uuid) -> Result<()> {
+        let Some(row) = sqlx::query_as::<_, BaselineRow>(
+            r#"
+            SELECT b.contest_id, b.problem_id, b.submission_id,
+                   COALESCE(cp.time_limit_ms, p.time_limit_ms) AS time_limit_ms,
+                   COALESCE(cp.memory_limit_kb, p.memory_limit_kb) AS memory_limit_kb,
+                   COALESCE(cp.max_threads, p.max_threads) AS max_threads,
+                   COALESCE(cp.network_allowed, p.network_allowed) AS network_allowed
+            FROM contest_problem_baselines b
+            JOIN problems p ON p.id = b.problem_id
+            LEFT JOIN contest_problems cp
+                   ON cp.contest_id = b.contest_id AND cp.problem_id = b.problem_id
+            WHERE b.id = $1 AND b.status = 'pending'
+            "#,
+        )
+        .bind(baseline_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        // The reference may have been submitted outside the contest; judge
+        // it under the limits the contest's submissions get
+        let job = JudgeJob::load(&self.db_pool, row.submission_id, 0).await?;
+        let mut ctx = job.execution_context()?;
+        ctx.time_limit_ms = row.time_limit_ms as u64;
+        ctx.memory_limit_kb = row.memory_limit_kb as u64;
+        ctx.max_threads = row.max_threads;
+        ctx.network_allowed = row.network_allowed;
+
+        let mut result = self.executor.execute(&ctx).await?;
+        if job.benchmark_scored {
+            result.apply_calibration(&self.calibration);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE contest_problem_baselines
+            SET status = 'completed', verdict = $1, max_time_ms = $2, max_memory_kb = $3,
+                total_instructions = $4, worker_id = $5, worker_speed_factor = $6,
+                completed_at = NOW()
+            WHERE id = $7
+            "#,
+        )
+        .bind(result.verdict.to_db_string())
+        .bind(result.max_time_ms as i32)
+        .bind(result.max_memory_kb as i32)
+        .bind(result.total_instructions.map(|i| i as i64))
+        .bind(&self.config.worker_id)
+        .bind(self.calibration.factor)
+        .bind(baseline_id)
+        .execute(&self.db_pool)
+        .await?;
+
+        tracing::info!(
+            "Judged reference baseline of problem {} in contest {}: {}, {} ms, {} KB",
+            row.problem_id,
+            row.contest_id,
+            result.verdict.to_db_string(),
+            result.max_time_ms,
+            result.max_memory_kb
+        );
+
+        Ok(())
+    }
+
+    /// Run a reference solution on every test with the calibration's limit
+    async fn run_reference(
+        &self,
+        submission_id: Uuid,
+        ceiling_ms: u64,
+    ) -> Result<SubmissionResult> {
+        let job = JudgeJob::load(&self.db_pool, submission_id, 0).await?;
+        let mut ctx = job.execution_context()?;
+        ctx.time_limit_ms = ceiling_ms;
+        // Every test runs under the ceiling, including those with their own
+        // time limit
+        ctx.limit_overrides = ctx.limit_overrides.without_time_limits();
+        self.execute_detached(ctx).await
+    }
+
+    /// Run a submission under an id of its own, so the run neither shares a
+    /// working directory with a judging of the same submission nor replaces
+    /// the outputs kept from it. Outputs the run itself keeps are discarded.
+    async fn execute_detached(&self, mut ctx: ExecutionContext) -> Result<SubmissionResult> {
+        let submission_id = ctx.submission_id;
+        ctx.submission_id = Uuid::new_v4();
+        tracing::debug!(
+            "Running submission {} as detached run {}",
+            submission_id,
+            ctx.submission_id
+        );
+
+        let mut result = self.executor.execute(&ctx).await?;
+        let kept_dir = result
+            .retained_outputs
+            .first()
+            .and_then(|output| output.path.parent());
+        if let Some(dir) = kept_dir {
+            let _ = tokio::fs::remove_dir_all(dir).await;
+        }
+        result.retained_outputs.clear();
+        Ok(result)
+    }
+}
+
+/// First message of an XREADGROUP reply:
+/// [[stream_name, [[message_id, [field, value, ...]]]]]
+fn first_message(result: &[redis::Value]) -> Option<&redis::Value> {
+    let Some(redis::Value::Array(stream_data)) = result.first() else {
+        return None;
+    };
+    let Some(redis::Value::Array(messages)) = stream_data.get(1) else {
+        return None;
+    };
+    messages.first()
+}
+
+/// Parse a message's `[field, value, ...]` into a request
+fn parse_request(message_id: &str, message: &[redis::Value]) -> Result<RegenRequest> {
+    let mut fields = HashMap::new();
+    if let Some(redis::Value::Array(values)) = message.get(1) {
+        for chunk in values.chunks(2) {
+            if let [redis::Value::BulkString(key), redis::Value::BulkString(value)] = chunk {
+                fields.insert(
+                    String::from_utf8_lossy(key).to_string(),
+                    String::from_utf8_lossy(value).to_string(),
+                );
+            }
+        }
+    }
+
+    let field = |name: &str| {
+        fields
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing {}", name))
+    };
+
+    let kind = match fields.get("kind").map(String::as_str) {
+        Some("reproduce") => RequestKind::Reproduce {
+            reproduction_id: field("reproduction_id")?.parse()?,
+        },
+        Some("checker_test") => RequestKind::CheckerTest {
+            run_id: field("run_id")?.parse()?,
+        },
+        Some("calibrate") => RequestKind::Calibrate {
+            calibration_id: field("calibration_id")?.parse()?,
+        },
+        Some("baseline") => RequestKind::Baseline {
+            baseline_id: field("baseline_id")?.parse()?,
+        },
+        _ => RequestKind::Input {
+            problem_id: field("problem_id")?.parse()?,
+            test: field("test")?.parse()?,
+            seed: field("seed")?,
+            version: field("version")?.parse()?,
+        },
+    };
+
+    Ok(RegenRequest {
+        message_id: message_id.to_string(),
+        kind,
+    })
+}
@@ -0,0 +1,356 @@
+//! Sample runs
+//!
+//! Contestants can try their code on a problem's sample before submitting.
+//! Vanguard records a `sample_runs` row, Sisyphus compiles the code and
+//! queues the run here on a stream of its own, so sample runs never wait
+//! behind judging. The worker runs the program on the statement's sample,
+//! records the verdict, output and first differing line in
+//! `sample_run_results` and removes the compiled artifact.
+//!
+//! Nothing is scored: sample runs leave submissions and standings alone.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use deadpool_redis::redis;
+use olympus_common::OutputComparison;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::capacity::ExecutionSlots;
+use crate::config::Config;
+use crate::default_checker::first_difference;
+use crate::executor::{ExecutionContext, Executor};
+use crate::test_limits::LimitOverrides;
+use crate::verdict::Verdict;
+
+/// Bytes of stdout, stderr and output kept per sample
+const SAMPLE_CAPTURE_LIMIT_BYTES: usize = 64 * 1024;
+
+/// Compiled sample run loaded from `sample_runs`, with the limits of its
+/// problem (or contest problem)
+#[derive(Debug, sqlx::FromRow)]
+struct SampleRunRow {
+    problem_id: Uuid,
+    binary_path: Option<String>,
+    time_limit_ms: i32,
+    memory_limit_kb: i32,
+    max_threads: i32,
+    network_allowed: bool,
+    output_comparison: Option<String>,
+    comparison_epsilon: f64,
+    generator_version: i32,
+    sample_input: Option<String>,
+    sample_output: Option<String>,
+}
+
+/// Worker that serves the sample run stream
+pub struct SampleWorker {
+    config: Config,
+    db_pool: PgPool,
+    redis_pool: deadpool_redis::Pool,
+    shutdown: Arc<AtomicBool>,
+    executor: Executor,
+}
+
+impl SampleWorker {
+    /// Create a new sample run worker
+    pub fn new(
+        config: Config,
+        db_pool: PgPool,
+        redis_pool: deadpool_redis::Pool,
+        shutdown: Arc<AtomicBool>,
+        slots: Arc<ExecutionSlots>,
+    ) -> Self {
+        let executor = Executor::new(config.storage.clone(), config.execution.clone(), slots);
+
+        Self {
+            config,
+            db_pool,
+            redis_pool,
+            shutdown,
+            executor,
+        }
+    }
+
+    /// Create the consumer group (ignore error if it already exists)
+    async fn initialize(&self) -> Result<()> {
+        let mut conn = self.redis_pool.get().await?;
+
+        let result: Result<(), redis::RedisError> = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(&self.config.sample_stream_name)
+            .arg(&self.config.consumer_group)
+            .arg("$")
+            .arg("MKSTREAM")
+            .query_async(&mut *conn)
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) if e.to_string().contains("BUSYGROUP") => Ok(()),
+            Err(e) => Err(anyhow!("Failed to create sample run consumer group: {}", e)),
+        }
+    }
+
+    /// Run the worker loop
+    pub async fn run(self) {
+        if let Err(e) = self.initialize().await {
+            tracing::error!("{}", e);
+        }
+
+        while !self.shutdown.load(Ordering::SeqCst) {
+            if let Err(e) = self.process_next_run().await {
+                let err_msg = e.to_string();
+                tracing::error!("Error processing sample run: {}", err_msg);
+
+                if err_msg.contains("NOGROUP") {
+                    if let Err(init_err) = self.initialize().await {
+                        tracing::error!("{}", init_err);
+                    }
+                }
+
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+
+    /// Serve the next sample run, if any
+    async fn process_next_run(&self) -> Result<()> {
+        let mut conn = self.redis_pool.get().await?;
+
+        let result: Vec<redis::Value> = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(&self.config.consumer_group)
+            .arg(&self.config.worker_id)
+            .arg("COUNT")
+            .arg(1)
+            .arg("BLOCK")
+            .arg(self.config.block_timeout_ms)
+            .arg("STREAMS")
+            .arg(&self.config.sample_stream_name)
+            .arg(">")
+            .query_async(&mut *conn)
+            .await?;
+
+        let Some((message_id, run_id)) = parse_message(&result)? else {
+            return Ok(());
+        };
+
+        if let Err(e) = self.run_samples(run_id).await {
+            tracing::error!("Sample run {} failed: {}", run_id, e);
+            sqlx::query(
+                r#"
+                UPDATE sample_runs
+                SET status = 'failed', error = $1, completed_at = NOW()
+                WHERE id = $2
+                "#,
+            )
+            .bind(e.to_string())
+            .bind(run_id)
+            .execute(&self.db_pool)
+            .await?;
+        }
+
+        redis::cmd("XACK")
+            .arg(&self.config.sample_stream_name)
+            .arg(&self.config.consumer_group)
+            .arg(&message_id)
+            .query_async::<i64>(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Run a compiled sample run on the problem's sample, record the
+    /// result and remove the artifact
+    async fn run_samples(&self, run_id: Uuid) -> Result<()> {
+        let Some(row) = sqlx::query_as::<_, SampleRunRow>(
+            r#"
+            SELECT r.problem_id, r.binary_path,
+                   COALESCE(cp.time_limit_ms, p.time_limit_ms) AS time_limit_ms,
+                   COALESCE(cp.memory_limit_kb, p.memory_limit_kb) AS memory_limit_kb,
+                   COALESCE(cp.max_threads, p.max_threads) AS max_threads,
+                   COALESCE(cp.network_allowed, p.network_allowed) AS network_allowed,
+                   p.output_comparison,
+                   p.comparison_epsilon,
+                   p.generator_version,
+                   p.sample_input,
+                   p.sample_output
+            FROM sample_runs r
+            JOIN problems p ON p.id = r.problem_id
+            LEFT JOIN contest_problems cp
+                   ON cp.contest_id = r.contest_id AND cp.problem_id = r.problem_id
+            WHERE r.id = $1 AND r.status = 'running'
+            "#,
+        )
+        .bind(run_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let binary_path = row
+            .binary_path
+            .as_deref()
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("No compiled artifact recorded for sample run {}", run_id))?;
+        let result = self.run_and_record(run_id, &row, &binary_path).await;
+        remove_artifact(&binary_path).await;
+        result
+    }
+
+    async fn run_and_record(
+        &self,
+        run_id: Uuid,
+        row: &SampleRunRow,
+        binary_path: &Path,
+    ) -> Result<()> {
+        let (Some(input), Some(expected)) = (&row.sample_input, &row.sample_output) else {
+            return Err(anyhow!("Problem {} has no sample", row.problem_id));
+        };
+
+        let ctx = ExecutionContext {
+            submission_id: run_id,
+            problem_id: row.problem_id,
+            time_limit_ms: row.time_limit_ms as u64,
+            memory_limit_kb: row.memory_limit_kb as u64,
+            num_testcases: 1,
+            generator_version: row.generator_version,
+            max_threads: row.max_threads,
+            network_allowed: row.network_allowed,
+            output_comparison: row
+                .output_comparison
+                .as_deref()
+                .and_then(OutputComparison::parse),
+            comparison_epsilon: row.comparison_epsilon,
+            partial_scoring: false,
+            count_instructions: false,
+            pipeline: None,
+            binary_path: binary_path.to_path_buf(),
+            storage_root: None,
+            // Per-test limits are set on the judge's tests, not the sample
+            limit_overrides: LimitOverrides::default(),
+        };
+
+        let number = 1;
+        let (result, capture) = self
+            .executor
+            .run_sample(&ctx, number, input, expected, SAMPLE_CAPTURE_LIMIT_BYTES)
+            .await?;
+
+        let diff = match result.verdict {
+            Verdict::WrongAnswer | Verdict::PresentationError => {
+                first_difference(&capture.output, expected)
+            }
+            _ => None,
+        };
+
+        let mut tx = self.db_pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO sample_run_results (
+                run_id, sample_number, verdict, time_ms, memory_kb, message,
+                output, stderr, output_truncated, diff_line, diff_expected, diff_actual
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(run_id)
+        .bind(number)
+        .bind(result.verdict.to_db_string())
+        .bind(result.time_ms as i64)
+        .bind(result.memory_kb as i64)
+        .bind(
+            result
+                .error_message
+                .as_deref()
+                .or(capture.checker_log.as_deref()),
+        )
+        .bind(&capture.output)
+        .bind(&capture.stderr)
+        .bind(capture.truncated)
+        .bind(diff.as_ref().map(|d| d.line as i32))
+        .bind(diff.as_ref().and_then(|d| d.expected.as_deref()))
+        .bind(diff.as_ref().and_then(|d| d.actual.as_deref()))
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE sample_runs
+            SET status = 'completed', binary_path = NULL, completed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(run_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        tracing::info!(
+            "Sample run {} of problem {}: {}",
+            run_id,
+            row.problem_id,
+            result.verdict.to_db_string()
+        );
+
+        Ok(())
+    }
+}
+
+/// Remove a sample run's artifact: a binary, or a directory with `run.sh`
+/// for interpreted languages
+async fn remove_artifact(path: &Path) {
+    let removed = match tokio::fs::metadata(path).await {
+        Ok(meta) if meta.is_dir() => tokio::fs::remove_dir_all(path).await,
+        Ok(_) => tokio::fs::remove_file(path).await,
+        Err(_) => return,
+    };
+    if let Err(e) = removed {
+        tracing::warn!("Failed to remove sample artifact {}: {}", path.display(), e);
+    }
+}
+
+/// Message id and run id of an XREADGROUP reply, or `None` if nothing was
+/// read
+fn parse_message(result: &[redis::Value]) -> Result<Option<(String, Uuid)>> {
+    let Some(redis::Value::Array(stream_data)) = result.first() else {
+        return Ok(None);
+    };
+    let Some(redis::Value::Array(messages)) = stream_data.get(1) else {
+        return Ok(None);
+    };
+    let Some(redis::Value::Array(message)) = messages.first() else {
+        return Ok(None);
+    };
+
+    let message_id = match message.first() {
+        Some(redis::Value::BulkString(id)) => String::from_utf8_lossy(id).to_string(),
+        _ => return Err(anyhow!("Invalid message ID")),
+    };
+
+    let mut fields = HashMap::new();
+    if let Some(redis::Value::Array(values)) = message.get(1) {
+        for chunk in values.chunks(2) {
+            if let [redis::Value::BulkString(key), redis::Value::BulkString(value)] = chunk {
+                fields.insert(
+                    String::from_utf8_lossy(key).to_string(),
+                    String::from_utf8_lossy(value).to_string(),
+                );
+            }
+        }
+    }
+
+    let run_id = fields
+        .get("run_id")
+        .ok_or_else(|| anyhow!("Missing run_id"))?
+        .parse()?;
+
+    Ok(Some((message_id, run_id)))
+}
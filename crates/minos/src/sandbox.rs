@@ -10,6 +10,8 @@ use std::process::Stdio;
 use std::sync::Once;
 
 use anyhow::{anyhow, Context, Result};
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
 use tokio::fs;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
@@ -84,8 +86,6 @@ pub struct ResourceUsage {
     pub memory_kb: u64,
     /// Total CPU time consumed in milliseconds (user + system).
     pub cpu_time_ms: u64,
-    /// `true` when metrics came from cgroup accounting.
-    pub from_cgroup: bool,
 }
 
 impl Sandbox {
@@ -179,6 +179,31 @@ impl Sandbox {
         }
     }
 
+    /// Return the cgroup directory, e.g. for attaching perf counters.
+    ///
+    /// Returns `None` if cgroups are unavailable.
+    pub fn cgroup_dir(&self) -> Option<&Path> {
+        self.cgroup_available.then_some(self.cgroup_dir.as_path())
+    }
+
+    /// Send `signal` to every process in the sandbox, or only to `pid` when
+    /// cgroups are unavailable.
+    pub async fn signal_all(&self, pid: Option<u32>, signal: Signal) {
+        let pids: Vec<i32> = if self.cgroup_available {
+            fs::read_to_string(self.cgroup_dir.join("cgroup.procs"))
+                .await
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|l| l.trim().parse().ok())
+                .collect()
+        } else {
+            pid.map(|p| p as i32).into_iter().collect()
+        };
+        for pid in pids {
+            let _ = kill(Pid::from_raw(pid), signal);
+        }
+    }
+
     /// Returns `true` if the cgroup recorded an OOM-kill event.
     pub async fn was_oom_killed(&self) -> bool {
         if !self.cgroup_available {
@@ -221,7 +246,6 @@ impl Sandbox {
         ResourceUsage {
             memory_kb,
             cpu_time_ms,
-            from_cgroup: true,
         }
     }
 
@@ -277,7 +301,6 @@ impl Sandbox {
         ResourceUsage {
             memory_kb,
             cpu_time_ms: 0,
-            from_cgroup: false,
         }
     }
 
@@ -366,7 +389,7 @@ impl Sandbox {
                 {
                     use std::os::unix::process::ExitStatusExt;
                     if let Some(signal) = output.status.signal() {
-                        if signal == 9 && (oom_killed || usage.memory_kb > 0) {
+                        if signal == Signal::SIGKILL as i32 && (oom_killed || usage.memory_kb > 0) {
                             return Err(anyhow!(
                                 "Process killed by OOM (signal {}, peak memory {}KB)",
                                 signal,
@@ -385,8 +408,6 @@ impl Sandbox {
                     stdout: output.stdout,
                     stderr: output.stderr,
                     exit_code: output.status.code().unwrap_or(-1),
-                    memory_kb: usage.memory_kb,
-                    oom_killed,
                 })
             }
             Ok(Err(e)) => Err(anyhow!("Failed to execute sandboxed process: {}", e)),
@@ -428,8 +449,4 @@ pub struct SandboxedOutput {
     pub stderr: Vec<u8>,
     /// Process exit code (`-1` if unavailable).
     pub exit_code: i32,
-    /// Peak memory usage in KB (from cgroup or `/proc`).
-    pub memory_kb: u64,
-    /// Whether the process was killed by the cgroup OOM killer.
-    pub oom_killed: bool,
 }
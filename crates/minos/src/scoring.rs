@@ -0,0 +1,232 @@
+//! Partial scoring.
+//!
+//! On problems with `partial_scoring` enabled every test earns a fraction of
+//! its weight: 1 when accepted, the checker's reported points (exit code 7)
+//! when partially correct, and 0 otherwise. Tests are then aggregated per
+//! subtask and subtasks by their points:
+//!
+//! ```text
+//! subtask_fraction = Σ(earned × weight) / Σ(weight)     over its tests
+//! score            = 100 × Σ(points × subtask_fraction) / Σ(points)
+//! ```
+//!
+//! Without subtasks all tests form a single group. Tests outside every
+//! subtask do not contribute, and tests that were never run earn nothing.
+//!
+//! In `dynamic` contests a problem is instead worth less the more
+//! participants solve it (`dynamic_value`), and every accepted submission
+//! scores its current value.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::verdict::TestCaseResult;
+
+/// Contiguous range of tests worth a share of the problem's points.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct Subtask {
+    /// First test case number (inclusive, 1-indexed)
+    pub first_test: i32,
+    /// Last test case number (inclusive)
+    pub last_test: i32,
+    /// Relative points of this subtask
+    pub points: i32,
+}
+
+/// Test weights and subtasks of a partial-scoring problem.
+#[derive(Debug, Clone, Default)]
+pub struct ScoringPlan {
+    /// Weight overrides by test case number (missing = 1)
+    pub weights: HashMap<i32, i32>,
+    /// Subtasks (empty = the whole problem is one group)
+    pub subtasks: Vec<Subtask>,
+}
+
+impl ScoringPlan {
+    /// Load test weights and subtasks for a problem.
+    pub async fn load(db: &PgPool, problem_id: Uuid) -> Result<Self> {
+        let weights = sqlx::query_as::<_, (i32, i32)>(
+            "SELECT case_number, score_weight FROM test_cases WHERE problem_id = $1",
+        )
+        .bind(problem_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+        let subtasks = sqlx::query_as::<_, Subtask>(
+            r#"
+            SELECT first_test, last_test, points
+            FROM problem_subtasks
+            WHERE problem_id = $1
+            ORDER BY subtask_number
+            "#,
+        )
+        .bind(problem_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(Self { weights, subtasks })
+    }
+
+    /// Weight of a test case.
+    fn weight(&self, testcase_number: i32) -> f64 {
+        self.weights
+            .get(&testcase_number)
+            .copied()
+            .unwrap_or(1)
+            .max(0) as f64
+    }
+
+    /// Weighted fraction earned over an inclusive test range.
+    fn group_fraction(&self, earned: &HashMap<i32, f64>, first: i32, last: i32) -> f64 {
+        let (mut got, mut total) = (0.0, 0.0);
+        for n in first..=last {
+            let weight = self.weight(n);
+            got += earned.get(&n).copied().unwrap_or(0.0) * weight;
+            total += weight;
+        }
+
+        if total > 0.0 {
+            got / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Score (0-100, two decimals) for a set of test results.
+    pub fn score(&self, results: &[TestCaseResult], total_count: i32) -> f64 {
+        let earned: HashMap<i32, f64> = results
+            .iter()
+            .map(|r| (r.testcase_number, r.earned()))
+            .collect();
+
+        let fraction = if self.subtasks.is_empty() {
+            self.group_fraction(&earned, 1, total_count)
+        } else {
+            let (mut got, mut total) = (0.0, 0.0);
+            for subtask in &self.subtasks {
+                let last = subtask.last_test.min(total_count);
+                let points = subtask.points.max(0) as f64;
+                got += points * self.group_fraction(&earned, subtask.first_test, last);
+                total += points;
+            }
+
+            if total > 0.0 {
+                got / total
+            } else {
+                0.0
+            }
+        };
+
+        (fraction * 10_000.0).round() / 100.0
+    }
+}
+
+/// Current value of a problem in a dynamic-scoring contest.
+///
+/// The first solver earns the full `points`. Each further solver lowers the
+/// value along a parabola until `decay` more participants have solved it;
+/// from then on it stays at `min_percent` of `points`:
+///
+/// ```text
+/// min   = points × min_percent / 100
+/// value = min + (points - min) × max(0, 1 - ((solvers - 1) / decay)²)
+/// ```
+pub fn dynamic_value(points: i32, solvers: i64, min_percent: i32, decay: i32) -> i32 {
+    let points = points.max(0) as f64;
+    let min = points * min_percent.clamp(0, 100) as f64 / 100.0;
+    let progress = (solvers.max(1) - 1) as f64 / decay.max(1) as f64;
+    let value = min + (points - min) * (1.0 - progress * progress).max(0.0);
+    value.round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verdict::TimeLimitKind;
+
+    fn results(earned: &[Option<f64>]) -> Vec<TestCaseResult> {
+        earned
+            .iter()
+            .enumerate()
+            .map(|(i, points)| match points {
+                Some(p) => TestCaseResult::partial_credit(i as i32 + 1, 10, 100, *p, None),
+                None => {
+                    TestCaseResult::time_limit_exceeded(i as i32 + 1, 1000, 100, TimeLimitKind::Cpu)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unweighted_average() {
+        let plan = ScoringPlan::default();
+        let res = results(&[Some(1.0), Some(0.5), None, Some(0.25)]);
+        assert_eq!(plan.score(&res, 4), 43.75);
+
+        // Tests that never ran earn nothing
+        assert_eq!(plan.score(&res[..2], 4), 37.5);
+    }
+
+    #[test]
+    fn test_weights() {
+        let plan = ScoringPlan {
+            weights: HashMap::from([(1, 3), (2, 0)]),
+            subtasks: Vec::new(),
+        };
+        let res = results(&[Some(1.0), Some(0.0), Some(0.0)]);
+        assert_eq!(plan.score(&res, 3), 75.0);
+    }
+
+    #[test]
+    fn test_subtasks() {
+        let plan = ScoringPlan {
+            weights: HashMap::new(),
+            subtasks: vec![
+                Subtask {
+                    first_test: 1,
+                    last_test: 2,
+                    points: 30,
+                },
+                Subtask {
+                    first_test: 3,
+                    last_test: 5,
+                    points: 70,
+                },
+            ],
+        };
+        let res = results(&[Some(1.0), Some(1.0), Some(1.0), Some(0.5), None]);
+        assert_eq!(plan.score(&res, 5), 65.0);
+    }
+
+    #[test]
+    fn test_checker_points_are_clamped() {
+        let plan = ScoringPlan::default();
+        let res = results(&[Some(1.7), Some(-2.0), Some(f64::NAN)]);
+        assert_eq!(plan.score(&res, 3), 33.33);
+    }
+
+    #[test]
+    fn test_dynamic_value_decays_to_minimum() {
+        // Nobody or a single solver: full points
+        assert_eq!(dynamic_value(500, 0, 30, 20), 500);
+        assert_eq!(dynamic_value(500, 1, 30, 20), 500);
+        // Halfway through the decay: 75% of the range is left
+        assert_eq!(dynamic_value(500, 11, 30, 20), 413);
+        assert_eq!(dynamic_value(500, 21, 30, 20), 150);
+        assert_eq!(dynamic_value(500, 1000, 30, 20), 150);
+    }
+
+    #[test]
+    fn test_dynamic_value_is_monotonic() {
+        let values: Vec<i32> = (1..=30).map(|n| dynamic_value(100, n, 10, 25)).collect();
+        assert!(values.windows(2).all(|w| w[0] >= w[1]), "{:?}", values);
+        // Degenerate settings stay in range
+        assert_eq!(dynamic_value(100, 5, 100, 0), 100);
+        assert_eq!(dynamic_value(100, 5, 0, 0), 0);
+    }
+}
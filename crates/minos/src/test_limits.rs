@@ -0,0 +1,216 @@
+//! Per-test time and memory limits.
+//!
+//! A test case or a subtask can override the limits submissions run under,
+//! e.g. a single huge stress test getting twice the time. A test's own
+//! override wins over its subtask's, and either replaces the problem's (or
+//! the contest's) limit. Time and memory are overridden independently.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Limits a test runs under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestLimits {
+    pub time_limit_ms: u64,
+    pub memory_limit_kb: u64,
+}
+
+/// Limits set on a test case or subtask (missing = inherited).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimitOverride {
+    pub time_limit_ms: Option<u64>,
+    pub memory_limit_kb: Option<u64>,
+}
+
+/// Subtask whose tests share a limit override.
+#[derive(Debug, Clone, PartialEq)]
+struct SubtaskOverride {
+    first_test: i32,
+    last_test: i32,
+    limits: LimitOverride,
+}
+
+/// Limit overrides of a problem's tests and subtasks.
+#[derive(Debug, Clone, Default)]
+pub struct LimitOverrides {
+    tests: HashMap<i32, LimitOverride>,
+    subtasks: Vec<SubtaskOverride>,
+}
+
+#[derive(sqlx::FromRow)]
+struct OverrideRow {
+    first_test: i32,
+    last_test: i32,
+    time_limit_ms: Option<i32>,
+    memory_limit_kb: Option<i32>,
+}
+
+impl OverrideRow {
+    fn limits(&self) -> LimitOverride {
+        LimitOverride {
+            time_limit_ms: self.time_limit_ms.map(|ms| ms.max(1) as u64),
+            memory_limit_kb: self.memory_limit_kb.map(|kb| kb.max(1) as u64),
+        }
+    }
+}
+
+impl LimitOverrides {
+    /// Load the overrides set on a problem's tests and subtasks.
+    pub async fn load(db: &PgPool, problem_id: Uuid) -> Result<Self> {
+        let tests = sqlx::query_as::<_, OverrideRow>(
+            r#"
+            SELECT case_number AS first_test, case_number AS last_test,
+                   time_limit_ms, memory_limit_kb
+            FROM test_cases
+            WHERE problem_id = $1
+              AND (time_limit_ms IS NOT NULL OR memory_limit_kb IS NOT NULL)
+            "#,
+        )
+        .bind(problem_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| (row.first_test, row.limits()))
+        .collect();
+
+        let subtasks = sqlx::query_as::<_, OverrideRow>(
+            r#"
+            SELECT first_test, last_test, time_limit_ms, memory_limit_kb
+            FROM problem_subtasks
+            WHERE problem_id = $1
+              AND (time_limit_ms IS NOT NULL OR memory_limit_kb IS NOT NULL)
+            ORDER BY subtask_number
+            "#,
+        )
+        .bind(problem_id)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| SubtaskOverride {
+            first_test: row.first_test,
+            last_test: row.last_test,
+            limits: row.limits(),
+        })
+        .collect();
+
+        Ok(Self { tests, subtasks })
+    }
+
+    /// Limits test `number` runs under, given the limits it would inherit.
+    pub fn apply(&self, number: i32, base: TestLimits) -> TestLimits {
+        let test = self.tests.get(&number).copied().unwrap_or_default();
+        let subtask = self
+            .subtasks
+            .iter()
+            .find(|s| (s.first_test..=s.last_test).contains(&number))
+            .map(|s| s.limits)
+            .unwrap_or_default();
+
+        TestLimits {
+            time_limit_ms: test
+                .time_limit_ms
+                .or(subtask.time_limit_ms)
+                .unwrap_or(base.time_limit_ms),
+            memory_limit_kb: test
+                .memory_limit_kb
+                .or(subtask.memory_limit_kb)
+                .unwrap_or(base.memory_limit_kb),
+        }
+    }
+
+    /// The same overrides with only their memory limits left.
+    pub fn without_time_limits(&self) -> Self {
+        let strip = |limits: LimitOverride| LimitOverride {
+            time_limit_ms: None,
+            ..limits
+        };
+        Self {
+            tests: self
+                .tests
+                .iter()
+                .map(|(&number, &limits)| (number, strip(limits)))
+                .collect(),
+            subtasks: self
+                .subtasks
+                .iter()
+                .map(|s| SubtaskOverride {
+                    limits: strip(s.limits),
+                    ..s.clone()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: TestLimits = TestLimits {
+        time_limit_ms: 1000,
+        memory_limit_kb: 262144,
+    };
+
+    fn overrides() -> LimitOverrides {
+        LimitOverrides {
+            tests: HashMap::from([(
+                5,
+                LimitOverride {
+                    time_limit_ms: Some(2000),
+                    memory_limit_kb: None,
+                },
+            )]),
+            subtasks: vec![SubtaskOverride {
+                first_test: 4,
+                last_test: 6,
+                limits: LimitOverride {
+                    time_limit_ms: Some(1500),
+                    memory_limit_kb: Some(524288),
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn test_no_overrides_inherit() {
+        assert_eq!(LimitOverrides::default().apply(1, BASE), BASE);
+        assert_eq!(overrides().apply(3, BASE), BASE);
+    }
+
+    #[test]
+    fn test_subtask_override() {
+        assert_eq!(
+            overrides().apply(4, BASE),
+            TestLimits {
+                time_limit_ms: 1500,
+                memory_limit_kb: 524288,
+            }
+        );
+    }
+
+    #[test]
+    fn test_test_override_wins_per_limit() {
+        // The test's time wins; its memory falls back to the subtask's
+        assert_eq!(
+            overrides().apply(5, BASE),
+            TestLimits {
+                time_limit_ms: 2000,
+                memory_limit_kb: 524288,
+            }
+        );
+    }
+
+    #[test]
+    fn test_without_time_limits_keeps_memory() {
+        assert_eq!(
+            overrides().without_time_limits().apply(5, BASE),
+            TestLimits {
+                time_limit_ms: 1000,
+                memory_limit_kb: 524288,
+            }
+        );
+    }
+}
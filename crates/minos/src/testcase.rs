@@ -8,13 +8,31 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Result};
+use olympus_common::storage::GENERATOR_VERSION_MARKER;
+use olympus_common::CheckerVerdict;
+use sha2::{Digest, Sha256};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 
 use crate::config::{ExecutionConfig, StorageConfig};
+use crate::integrity;
 use crate::sandbox::Sandbox;
 
+/// Seed the generator is run with for a test case.
+///
+/// Derived from the problem, the test number and the generator version, so
+/// every worker produces the same input for a test and uploading a new
+/// generator changes all of them. Recorded per result so a test can be
+/// regenerated or re-run later.
+pub fn generator_seed(problem_id: Uuid, number: i32, generator_version: i32) -> String {
+    let digest = Sha256::digest(format!("{}:{}:{}", problem_id, number, generator_version));
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    // Keep it positive for generators that parse a signed 64-bit seed
+    (u64::from_be_bytes(bytes) >> 1).to_string()
+}
+
 /// Test case input/output pair
 #[derive(Debug, Clone)]
 pub struct TestCase {
@@ -22,8 +40,24 @@ pub struct TestCase {
     pub number: i32,
     /// Path to input file
     pub input_path: PathBuf,
-    /// Path to expected output file (if pre-generated)
-    pub output_path: Option<PathBuf>,
+}
+
+impl TestCase {
+    /// Path of a test's input within a problem's test case directory
+    pub fn input_path(dir: &Path, number: i32) -> PathBuf {
+        dir.join(format!("input_{:03}.txt", number))
+    }
+}
+
+/// Unique hidden sibling of `dir` for building or retiring a test case
+/// directory, e.g. `.<problem_id>.tmp-<uuid>`. Leftovers from a crashed
+/// worker go stale and are removed by Horus like any other test case dir.
+fn sibling_path(dir: &Path, kind: &str) -> PathBuf {
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    dir.with_file_name(format!(".{}.{}-{}", name, kind, Uuid::new_v4()))
 }
 
 /// Test case manager handles generation and caching
@@ -43,11 +77,14 @@ impl TestCaseManager {
         &self,
         problem_id: Uuid,
         num_testcases: i32,
+        generator_version: i32,
     ) -> Result<Vec<TestCase>> {
         let testcase_dir = self.storage.testcases_path.join(problem_id.to_string());
 
-        // Check if test cases already exist
-        if self.testcases_exist(&testcase_dir, num_testcases).await {
+        // Check if intact test cases already exist for this generator
+        if self.cached_version(&testcase_dir).await == Some(generator_version)
+            && self.testcases_intact(&testcase_dir, num_testcases).await?
+        {
             tracing::debug!("Using cached test cases for problem {}", problem_id);
             self.touch_testcase_dir(&testcase_dir).await?;
             return self.load_testcases(&testcase_dir, num_testcases).await;
@@ -55,18 +92,99 @@ impl TestCaseManager {
 
         // Generate test cases
         tracing::info!("Generating test cases for problem {}", problem_id);
-        self.generate_testcases(problem_id, num_testcases).await
+        self.generate_testcases(problem_id, num_testcases, generator_version)
+            .await
+    }
+
+    /// Generator version the cached inputs were produced by, if known
+    async fn cached_version(&self, dir: &Path) -> Option<i32> {
+        let marker = fs::read_to_string(dir.join(GENERATOR_VERSION_MARKER))
+            .await
+            .ok()?;
+        marker.trim().parse().ok()
+    }
+
+    /// Prepare the test case directory for `generator_version`, replacing
+    /// one holding inputs produced by any other generator with an empty one.
+    async fn prepare_testcase_dir(&self, dir: &Path, generator_version: i32) -> Result<()> {
+        if self.cached_version(dir).await == Some(generator_version) {
+            return Ok(());
+        }
+
+        let staging = self.create_staging_dir(dir, generator_version).await?;
+        self.install_testcase_dir(&staging, dir, generator_version)
+            .await
+    }
+
+    /// Create an empty sibling of `dir` marked with `generator_version` to
+    /// build test cases in before they are moved into place
+    async fn create_staging_dir(&self, dir: &Path, generator_version: i32) -> Result<PathBuf> {
+        let staging = sibling_path(dir, "tmp");
+        fs::create_dir_all(&staging).await?;
+        fs::write(
+            staging.join(GENERATOR_VERSION_MARKER),
+            generator_version.to_string(),
+        )
+        .await?;
+        Ok(staging)
+    }
+
+    /// Move a fully built staging directory to `dir`.
+    ///
+    /// The previous directory is renamed aside first and removed afterwards,
+    /// so judges never see a directory that is half old and half new. If
+    /// another worker installed the same generator version in between, its
+    /// directory is kept and ours is discarded.
+    async fn install_testcase_dir(
+        &self,
+        staging: &Path,
+        dir: &Path,
+        generator_version: i32,
+    ) -> Result<()> {
+        let retired = sibling_path(dir, "old");
+        let had_previous = match fs::rename(dir, &retired).await {
+            Ok(()) => true,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => {
+                let _ = fs::remove_dir_all(staging).await;
+                return Err(e.into());
+            }
+        };
+
+        let installed = fs::rename(staging, dir).await;
+        if had_previous {
+            let _ = fs::remove_dir_all(&retired).await;
+        }
+
+        match installed {
+            Ok(()) => Ok(()),
+            Err(_) if self.cached_version(dir).await == Some(generator_version) => {
+                let _ = fs::remove_dir_all(staging).await;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = fs::remove_dir_all(staging).await;
+                Err(e.into())
+            }
+        }
     }
 
-    /// Check if all test cases exist in cache
-    async fn testcases_exist(&self, dir: &Path, count: i32) -> bool {
+    /// Check if all test cases exist in cache and match their checksums
+    async fn testcases_intact(&self, dir: &Path, count: i32) -> Result<bool> {
         for i in 1..=count {
-            let input_path = dir.join(format!("input_{:03}.txt", i));
+            let input_path = TestCase::input_path(dir, i);
             if !input_path.exists() {
-                return false;
+                return Ok(false);
+            }
+            if !integrity::matches_recorded_checksum(&input_path).await? {
+                tracing::warn!(
+                    "Cached input {} does not match its checksum, regenerating",
+                    input_path.display()
+                );
+                return Ok(false);
             }
         }
-        true
+        Ok(true)
     }
 
     /// Touch directory to update last access time
@@ -83,17 +201,9 @@ impl TestCaseManager {
         let mut testcases = Vec::with_capacity(count as usize);
 
         for i in 1..=count {
-            let input_path = dir.join(format!("input_{:03}.txt", i));
-            let output_path = dir.join(format!("output_{:03}.txt", i));
-
             testcases.push(TestCase {
                 number: i,
-                input_path,
-                output_path: if output_path.exists() {
-                    Some(output_path)
-                } else {
-                    None
-                },
+                input_path: TestCase::input_path(dir, i),
             });
         }
 
@@ -109,7 +219,123 @@ impl TestCaseManager {
         &self,
         problem_id: Uuid,
         num_testcases: i32,
+        generator_version: i32,
     ) -> Result<Vec<TestCase>> {
+        let generator_path = self.prepare_generator(problem_id).await?;
+
+        // Build the inputs next to the testcase directory and move them into
+        // place once all of them exist
+        let testcase_dir = self.storage.testcases_path.join(problem_id.to_string());
+        let staging_dir = self
+            .create_staging_dir(&testcase_dir, generator_version)
+            .await?;
+
+        if let Err(e) = self
+            .generate_into(
+                &generator_path,
+                problem_id,
+                num_testcases,
+                generator_version,
+                &staging_dir,
+            )
+            .await
+        {
+            let _ = fs::remove_dir_all(&staging_dir).await;
+            return Err(e);
+        }
+        self.install_testcase_dir(&staging_dir, &testcase_dir, generator_version)
+            .await?;
+
+        // Touch the directory
+        self.touch_testcase_dir(&testcase_dir).await?;
+
+        tracing::info!(
+            "Generated {} test cases for problem {} (generator v{}, sandboxed, mem_limit={}KB)",
+            num_testcases,
+            problem_id,
+            generator_version,
+            self.execution.generator_memory_limit_kb,
+        );
+
+        self.load_testcases(&testcase_dir, num_testcases).await
+    }
+
+    /// Run the generator for every test case, writing the inputs and their
+    /// checksums into `dir`
+    async fn generate_into(
+        &self,
+        generator_path: &Path,
+        problem_id: Uuid,
+        num_testcases: i32,
+        generator_version: i32,
+        dir: &Path,
+    ) -> Result<()> {
+        for i in 1..=num_testcases {
+            let input_path = TestCase::input_path(dir, i);
+            let checksum = self
+                .generate_input(
+                    generator_path,
+                    problem_id,
+                    i,
+                    &generator_seed(problem_id, i, generator_version),
+                    &input_path,
+                )
+                .await?;
+            integrity::record_checksum(&input_path, &checksum).await?;
+        }
+        Ok(())
+    }
+
+    /// Regenerate a single evicted test input with the seed recorded for it.
+    ///
+    /// Does nothing if the input is already cached for `generator_version`.
+    /// Returns the input's path.
+    pub async fn regenerate_input(
+        &self,
+        problem_id: Uuid,
+        number: i32,
+        seed: &str,
+        generator_version: i32,
+    ) -> Result<PathBuf> {
+        let testcase_dir = self.storage.testcases_path.join(problem_id.to_string());
+        let input_path = TestCase::input_path(&testcase_dir, number);
+        if self.cached_version(&testcase_dir).await == Some(generator_version)
+            && input_path.exists()
+        {
+            if integrity::matches_recorded_checksum(&input_path).await? {
+                return Ok(input_path);
+            }
+            tracing::warn!(
+                "Cached input {} does not match its checksum, regenerating",
+                input_path.display()
+            );
+        }
+
+        let generator_path = self.prepare_generator(problem_id).await?;
+        self.prepare_testcase_dir(&testcase_dir, generator_version)
+            .await?;
+
+        // Generate next to the target and rename, so a concurrent judge never
+        // reads a partial input
+        let partial_path = testcase_dir.join(format!(".input_{:03}.{}", number, Uuid::new_v4()));
+        let checksum = self
+            .generate_input(&generator_path, problem_id, number, seed, &partial_path)
+            .await?;
+        integrity::record_checksum(&input_path, &checksum).await?;
+        fs::rename(&partial_path, &input_path).await?;
+
+        tracing::info!(
+            "Regenerated input {} for problem {} (seed {})",
+            number,
+            problem_id,
+            seed
+        );
+
+        Ok(input_path)
+    }
+
+    /// Path of the problem's generator, made executable.
+    async fn prepare_generator(&self, problem_id: Uuid) -> Result<PathBuf> {
         let generator_path = self
             .storage
             .problem_binaries_path
@@ -126,78 +352,66 @@ impl TestCaseManager {
         perms.set_mode(0o755);
         fs::set_permissions(&generator_path, perms).await?;
 
-        // Create testcase directory
-        let testcase_dir = self.storage.testcases_path.join(problem_id.to_string());
-        fs::create_dir_all(&testcase_dir).await?;
+        Ok(generator_path)
+    }
 
-        let mut testcases = Vec::with_capacity(num_testcases as usize);
+    /// Run the generator for one test case and write its stdout to
+    /// `input_path`. Returns the input's checksum.
+    async fn generate_input(
+        &self,
+        generator_path: &Path,
+        problem_id: Uuid,
+        number: i32,
+        seed: &str,
+        input_path: &Path,
+    ) -> Result<String> {
+        // Create a per-invocation sandbox with generator resource limits.
+        let sandbox_id = format!("gen_{}_{}", problem_id, number);
+        let sandbox = Sandbox::create(
+            &sandbox_id,
+            self.execution.generator_memory_limit_kb,
+            // Generators are single-threaded; small PID buffer.
+            1,
+        )
+        .await;
 
-        for i in 1..=num_testcases {
-            let input_path = testcase_dir.join(format!("input_{:03}.txt", i));
-
-            // Create a per-invocation sandbox with generator resource limits.
-            let sandbox_id = format!("gen_{}_{}", problem_id, i);
-            let sandbox = Sandbox::create(
-                &sandbox_id,
-                self.execution.generator_memory_limit_kb,
-                // Generators are single-threaded; small PID buffer.
-                1,
+        // `generator <test_number> <seed>`: generators that pick a test shape
+        // by its number keep working, and randomized ones seed from the second
+        let number_arg = number.to_string();
+        let result = sandbox
+            .run_sandboxed(
+                generator_path,
+                &[&number_arg, seed],
+                self.execution.generator_time_limit_ms,
+                false, // generators never need network
+                true,  // capture stdout → test case input
             )
             .await;
 
-            let tc_num = i.to_string();
-            let result = sandbox
-                .run_sandboxed(
-                    &generator_path,
-                    &[&tc_num],
-                    self.execution.generator_time_limit_ms,
-                    false, // generators never need network
-                    true,  // capture stdout → test case input
-                )
-                .await;
-
-            sandbox.cleanup().await;
-
-            let output = result.map_err(|e| {
-                anyhow!(
-                    "Generator failed for testcase {} (problem {}): {}",
-                    i,
-                    problem_id,
-                    e
-                )
-            })?;
-
-            if output.exit_code != 0 {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(anyhow!(
-                    "Generator exited with code {} for testcase {}: {}",
-                    output.exit_code,
-                    i,
-                    stderr
-                ));
-            }
-
-            // Write input to file
-            fs::write(&input_path, &output.stdout).await?;
+        sandbox.cleanup().await;
 
-            testcases.push(TestCase {
-                number: i,
-                input_path,
-                output_path: None, // We use checker, not pre-generated output
-            });
+        let output = result.map_err(|e| {
+            anyhow!(
+                "Generator failed for testcase {} (problem {}): {}",
+                number,
+                problem_id,
+                e
+            )
+        })?;
+
+        if output.exit_code != 0 {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!(
+                "Generator exited with code {} for testcase {}: {}",
+                output.exit_code,
+                number,
+                stderr
+            ));
         }
 
-        // Touch the directory
-        self.touch_testcase_dir(&testcase_dir).await?;
-
-        tracing::info!(
-            "Generated {} test cases for problem {} (sandboxed, mem_limit={}KB)",
-            num_testcases,
-            problem_id,
-            self.execution.generator_memory_limit_kb,
-        );
-
-        Ok(testcases)
+        // Write input to file
+        fs::write(input_path, &output.stdout).await?;
+        Ok(integrity::sha256_hex(&output.stdout))
     }
 
     /// Run the checker to verify output.
@@ -257,39 +471,11 @@ impl TestCaseManager {
         sandbox.cleanup().await;
 
         match result {
-            Ok(output) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-                // Testlib exit codes:
-                // 0 = AC (accepted)
-                // 1 = WA (wrong answer)
-                // 2 = PE (presentation error, treated as WA)
-                // 3 = FAIL (judge error)
-                // 7 = Points (partial credit)
-                match output.exit_code {
-                    0 => Ok(CheckerResult::Accepted(stdout)),
-                    1 | 2 => Ok(CheckerResult::WrongAnswer(if stderr.is_empty() {
-                        stdout
-                    } else {
-                        stderr
-                    })),
-                    3 => Ok(CheckerResult::JudgeError(stderr)),
-                    7 => {
-                        // Parse partial points from output
-                        let points = stdout
-                            .lines()
-                            .next()
-                            .and_then(|l| l.parse::<f64>().ok())
-                            .unwrap_or(0.0);
-                        Ok(CheckerResult::PartialCredit(points, stdout))
-                    }
-                    code => Ok(CheckerResult::JudgeError(format!(
-                        "Checker exited with code {}: {}",
-                        code, stderr
-                    ))),
-                }
-            }
+            Ok(output) => Ok(CheckerResult::from_exit(
+                output.exit_code,
+                String::from_utf8_lossy(&output.stdout).to_string(),
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )),
             Err(e) => {
                 // Sandbox-level failure (timeout, OOM, spawn error)
                 Ok(CheckerResult::JudgeError(format!(
@@ -308,8 +494,120 @@ pub enum CheckerResult {
     Accepted(String),
     /// Output is incorrect
     WrongAnswer(String),
+    /// Output is right but badly formatted
+    PresentationError(String),
     /// Partial credit (0.0 - 1.0 typically)
     PartialCredit(f64, String),
     /// Internal checker error
     JudgeError(String),
 }
+
+impl CheckerResult {
+    /// Interpret a checker run by its exit code.
+    ///
+    /// Testlib exit codes:
+    /// 0 = AC (accepted)
+    /// 1 = WA (wrong answer)
+    /// 2 = PE (presentation error)
+    /// 3 = FAIL (judge error)
+    /// 7 = Points (partial credit)
+    pub fn from_exit(exit_code: i32, stdout: String, stderr: String) -> Self {
+        // Testlib writes its verdict comment to stderr
+        let comment = |stdout: String, stderr: String| {
+            if stderr.is_empty() {
+                stdout
+            } else {
+                stderr
+            }
+        };
+
+        match exit_code {
+            0 => CheckerResult::Accepted(stdout),
+            1 => CheckerResult::WrongAnswer(comment(stdout, stderr)),
+            2 => CheckerResult::PresentationError(comment(stdout, stderr)),
+            3 => CheckerResult::JudgeError(stderr),
+            7 => {
+                // Parse partial points from output
+                let points = stdout
+                    .lines()
+                    .next()
+                    .and_then(|l| l.parse::<f64>().ok())
+                    .unwrap_or(0.0);
+                CheckerResult::PartialCredit(points, stdout)
+            }
+            code => {
+                CheckerResult::JudgeError(format!("Checker exited with code {}: {}", code, stderr))
+            }
+        }
+    }
+
+    /// Verdict of the run and the checker's comment
+    pub fn into_verdict(self) -> (CheckerVerdict, String) {
+        match self {
+            CheckerResult::Accepted(comment) => (CheckerVerdict::Accepted, comment),
+            CheckerResult::WrongAnswer(comment) => (CheckerVerdict::WrongAnswer, comment),
+            CheckerResult::PresentationError(comment) => {
+                (CheckerVerdict::PresentationError, comment)
+            }
+            CheckerResult::PartialCredit(_, comment) => (CheckerVerdict::PartialCredit, comment),
+            CheckerResult::JudgeError(comment) => (CheckerVerdict::Fail, comment),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generator_seed_is_stable() {
+        let problem = Uuid::parse_str("6f1c2a3e-9b7d-4c1e-8a2f-0d3b5e7c9a1b").unwrap();
+        let seed = generator_seed(problem, 3, 1);
+        assert_eq!(seed, generator_seed(problem, 3, 1));
+        assert!(seed.parse::<i64>().is_ok());
+    }
+
+    #[test]
+    fn test_checker_exit_codes() {
+        let run = |code| CheckerResult::from_exit(code, "out".to_string(), "err".to_string());
+        assert!(matches!(run(0), CheckerResult::Accepted(_)));
+        assert!(matches!(run(1), CheckerResult::WrongAnswer(c) if c == "err"));
+        assert!(matches!(run(2), CheckerResult::PresentationError(c) if c == "err"));
+        assert!(matches!(run(3), CheckerResult::JudgeError(_)));
+        assert!(matches!(run(42), CheckerResult::JudgeError(_)));
+    }
+
+    #[test]
+    fn test_checker_verdicts() {
+        let verdict = |code| {
+            CheckerResult::from_exit(code, "0.5".to_string(), String::new())
+                .into_verdict()
+                .0
+        };
+        assert_eq!(verdict(0), CheckerVerdict::Accepted);
+        assert_eq!(verdict(1), CheckerVerdict::WrongAnswer);
+        assert_eq!(verdict(2), CheckerVerdict::PresentationError);
+        assert_eq!(verdict(3), CheckerVerdict::Fail);
+        assert_eq!(verdict(7), CheckerVerdict::PartialCredit);
+        assert_eq!(verdict(139), CheckerVerdict::Fail);
+    }
+
+    #[test]
+    fn test_sibling_path_stays_next_to_dir() {
+        let dir = Path::new("/mnt/data/testcases/abc");
+        let staging = sibling_path(dir, "tmp");
+        assert_eq!(staging.parent(), dir.parent());
+        let name = staging.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with(".abc.tmp-"));
+        assert_ne!(staging, sibling_path(dir, "tmp"));
+    }
+
+    #[test]
+    fn test_generator_seed_varies() {
+        let problem = Uuid::new_v4();
+        let seed = generator_seed(problem, 1, 1);
+        assert_ne!(seed, generator_seed(problem, 2, 1));
+        assert_ne!(seed, generator_seed(problem, 1, 2));
+        assert_ne!(seed, generator_seed(Uuid::new_v4(), 1, 1));
+    }
+}
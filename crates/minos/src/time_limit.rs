@@ -0,0 +1,202 @@
+//! Time limit suggestion from reference solutions.
+//!
+//! Setters mark reference solutions as `model` (must pass) or `slow` (must
+//! not). A calibration run measures each one's slowest test on a judge
+//! worker; the suggested limit is the slowest model time times a safety
+//! multiplier, rounded up to a 50 ms step, as long as that stays below the
+//! fastest slow solution:
+//!
+//! ```text
+//! limit = round_up(model_ms * multiplier)      if limit < slow_ms
+//! limit = (model_ms + slow_ms) / 2             otherwise (tight)
+//! ```
+//!
+//! Slow solutions that give wrong answers or crash say nothing about speed
+//! and are left out. Times are raw measurements on the worker that ran the
+//! calibration, the same ones limits are enforced on.
+
+use olympus_common::ReferenceRole;
+
+use crate::verdict::Verdict;
+
+/// Limits are suggested in steps of this many milliseconds
+const STEP_MS: u64 = 50;
+
+/// Smallest time limit a problem can have
+const MIN_TIME_LIMIT_MS: u64 = 100;
+
+/// Largest time limit a problem can have
+const MAX_TIME_LIMIT_MS: u64 = 30_000;
+
+/// Outcome of running one reference solution
+#[derive(Debug, Clone, Copy)]
+pub struct ReferenceRun {
+    pub role: ReferenceRole,
+    pub verdict: Verdict,
+    /// Slowest test (ms); the run's time limit for a time limit verdict
+    pub max_time_ms: u64,
+}
+
+/// How well the suggested limit separates model from slow solutions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separation {
+    /// The full margin fits below every slow solution
+    Clear,
+    /// It does not; the limit splits the gap between them
+    Tight,
+    /// A slow solution is no slower than a model one
+    None,
+}
+
+impl Separation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Separation::Clear => "clear",
+            Separation::Tight => "tight",
+            Separation::None => "none",
+        }
+    }
+}
+
+/// Suggested time limit and the measurements it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Slowest test of the slowest model solution
+    pub model_time_ms: u64,
+    /// Slowest test of the fastest slow solution, if any counted
+    pub slow_time_ms: Option<u64>,
+    pub time_limit_ms: u64,
+    pub separation: Separation,
+}
+
+/// Suggest a time limit from reference runs, or explain why none can be
+/// suggested.
+pub fn suggest_time_limit(runs: &[ReferenceRun], multiplier: f64) -> Result<Suggestion, String> {
+    let mut model_time_ms: Option<u64> = None;
+    for run in runs.iter().filter(|r| r.role == ReferenceRole::Model) {
+        if run.verdict != Verdict::Accepted {
+            return Err(format!(
+                "A model solution was not accepted ({})",
+                run.verdict.to_db_string()
+            ));
+        }
+        model_time_ms = Some(model_time_ms.unwrap_or(0).max(run.max_time_ms));
+    }
+    let model_time_ms = model_time_ms.ok_or_else(|| "No model solution was run".to_string())?;
+    if model_time_ms >= MAX_TIME_LIMIT_MS {
+        return Err(format!(
+            "The slowest model solution needs {} ms, above the largest time limit ({} ms)",
+            model_time_ms, MAX_TIME_LIMIT_MS
+        ));
+    }
+
+    let slow_time_ms = runs
+        .iter()
+        .filter(|r| {
+            r.role == ReferenceRole::Slow
+                && matches!(r.verdict, Verdict::Accepted | Verdict::TimeLimitExceeded)
+        })
+        .map(|r| r.max_time_ms)
+        .min();
+
+    let target = round_up((model_time_ms as f64 * multiplier).ceil() as u64)
+        .clamp(MIN_TIME_LIMIT_MS, MAX_TIME_LIMIT_MS);
+
+    let (time_limit_ms, separation) = match slow_time_ms {
+        Some(slow) if target >= slow => {
+            let midpoint = ((model_time_ms + slow) / 2).max(MIN_TIME_LIMIT_MS);
+            if midpoint > model_time_ms && midpoint < slow {
+                (midpoint, Separation::Tight)
+            } else {
+                (target, Separation::None)
+            }
+        }
+        _ => (target, Separation::Clear),
+    };
+
+    Ok(Suggestion {
+        model_time_ms,
+        slow_time_ms,
+        time_limit_ms,
+        separation,
+    })
+}
+
+fn round_up(ms: u64) -> u64 {
+    ms.div_ceil(STEP_MS) * STEP_MS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(role: ReferenceRole, verdict: Verdict, max_time_ms: u64) -> ReferenceRun {
+        ReferenceRun {
+            role,
+            verdict,
+            max_time_ms,
+        }
+    }
+
+    #[test]
+    fn test_clear_margin_below_slow() {
+        let runs = [
+            run(ReferenceRole::Model, Verdict::Accepted, 180),
+            run(ReferenceRole::Model, Verdict::Accepted, 310),
+            run(ReferenceRole::Slow, Verdict::TimeLimitExceeded, 5000),
+        ];
+        let suggestion = suggest_time_limit(&runs, 3.0).unwrap();
+        assert_eq!(suggestion.model_time_ms, 310);
+        assert_eq!(suggestion.slow_time_ms, Some(5000));
+        // 930 rounded up to the next step
+        assert_eq!(suggestion.time_limit_ms, 950);
+        assert_eq!(suggestion.separation, Separation::Clear);
+    }
+
+    #[test]
+    fn test_tight_splits_the_gap() {
+        let runs = [
+            run(ReferenceRole::Model, Verdict::Accepted, 400),
+            run(ReferenceRole::Slow, Verdict::Accepted, 900),
+        ];
+        let suggestion = suggest_time_limit(&runs, 3.0).unwrap();
+        assert_eq!(suggestion.time_limit_ms, 650);
+        assert_eq!(suggestion.separation, Separation::Tight);
+    }
+
+    #[test]
+    fn test_no_separation_keeps_margin() {
+        let runs = [
+            run(ReferenceRole::Model, Verdict::Accepted, 400),
+            run(ReferenceRole::Slow, Verdict::Accepted, 380),
+        ];
+        let suggestion = suggest_time_limit(&runs, 2.0).unwrap();
+        assert_eq!(suggestion.time_limit_ms, 800);
+        assert_eq!(suggestion.separation, Separation::None);
+    }
+
+    #[test]
+    fn test_wrong_slow_solutions_are_ignored() {
+        let runs = [
+            run(ReferenceRole::Model, Verdict::Accepted, 20),
+            run(ReferenceRole::Slow, Verdict::WrongAnswer, 5),
+        ];
+        let suggestion = suggest_time_limit(&runs, 3.0).unwrap();
+        assert_eq!(suggestion.slow_time_ms, None);
+        // Raised to the smallest limit
+        assert_eq!(suggestion.time_limit_ms, 100);
+        assert_eq!(suggestion.separation, Separation::Clear);
+    }
+
+    #[test]
+    fn test_failing_or_missing_model_has_no_suggestion() {
+        let runs = [run(ReferenceRole::Model, Verdict::WrongAnswer, 100)];
+        assert!(suggest_time_limit(&runs, 3.0).is_err());
+
+        let runs = [run(ReferenceRole::Slow, Verdict::Accepted, 100)];
+        assert!(suggest_time_limit(&runs, 3.0).is_err());
+
+        let runs = [run(ReferenceRole::Model, Verdict::Accepted, 30_000)];
+        assert!(suggest_time_limit(&runs, 3.0).is_err());
+    }
+}
@@ -1,7 +1,13 @@
 //! Verdict types and determination logic
 
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use crate::calibration::Calibration;
+use crate::scoring::ScoringPlan;
+use crate::test_limits::TestLimits;
+
 /// Verdict for a single test case or entire submission
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -10,6 +16,8 @@ pub enum Verdict {
     Accepted,
     /// Output does not match expected
     WrongAnswer,
+    /// Output is right but badly formatted (checker exit code 2)
+    PresentationError,
     /// Exceeded time limit
     TimeLimitExceeded,
     /// Exceeded memory limit
@@ -18,8 +26,12 @@ pub enum Verdict {
     RuntimeError,
     /// Output too large
     OutputLimitExceeded,
+    /// Neither used CPU time nor wrote output for too long
+    IdlenessLimitExceeded,
     /// Internal judge error
     JudgeError,
+    /// A problem binary does not match the checksum recorded on upload
+    ProblemIntegrityError,
     /// Currently being judged
     Judging,
     /// Waiting in queue
@@ -32,11 +44,14 @@ impl Verdict {
         match self {
             Verdict::Accepted => "AC",
             Verdict::WrongAnswer => "WA",
+            Verdict::PresentationError => "PE",
             Verdict::TimeLimitExceeded => "TLE",
             Verdict::MemoryLimitExceeded => "MLE",
             Verdict::RuntimeError => "RE",
             Verdict::OutputLimitExceeded => "OLE",
+            Verdict::IdlenessLimitExceeded => "ILE",
             Verdict::JudgeError => "JE",
+            Verdict::ProblemIntegrityError => "PIE",
             Verdict::Judging => "JG",
             Verdict::Pending => "PD",
         }
@@ -50,40 +65,23 @@ impl Verdict {
         )
     }
 
-    /// Check if verdict is final (not pending/judging)
-    pub fn is_final(&self) -> bool {
-        !matches!(self, Verdict::Pending | Verdict::Judging)
-    }
-
     /// Convert to database string representation (must match CHECK constraints).
-    pub fn to_db_string(&self) -> &'static str {
+    pub fn to_db_string(self) -> &'static str {
         match self {
             Verdict::Accepted => "accepted",
             Verdict::WrongAnswer => "wrong_answer",
+            Verdict::PresentationError => "presentation_error",
             Verdict::TimeLimitExceeded => "time_limit",
             Verdict::MemoryLimitExceeded => "memory_limit",
             Verdict::RuntimeError => "runtime_error",
-            Verdict::OutputLimitExceeded => "runtime_error", // mapped to runtime_error in DB
+            Verdict::OutputLimitExceeded => "output_limit",
+            Verdict::IdlenessLimitExceeded => "idleness_limit",
             Verdict::JudgeError => "system_error",
+            Verdict::ProblemIntegrityError => "problem_integrity_error",
             Verdict::Judging => "judging",
             Verdict::Pending => "pending",
         }
     }
-
-    /// Create from database string
-    pub fn from_db_string(s: &str) -> Option<Self> {
-        match s {
-            "accepted" => Some(Verdict::Accepted),
-            "wrong_answer" => Some(Verdict::WrongAnswer),
-            "time_limit" => Some(Verdict::TimeLimitExceeded),
-            "memory_limit" => Some(Verdict::MemoryLimitExceeded),
-            "runtime_error" => Some(Verdict::RuntimeError),
-            "system_error" => Some(Verdict::JudgeError),
-            "judging" => Some(Verdict::Judging),
-            "pending" => Some(Verdict::Pending),
-            _ => None,
-        }
-    }
 }
 
 impl std::fmt::Display for Verdict {
@@ -92,8 +90,26 @@ impl std::fmt::Display for Verdict {
     }
 }
 
+/// Which clock a run exceeded its time limit on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeLimitKind {
+    /// Used more CPU time than allowed
+    Cpu,
+    /// Still busy when the wall-clock watchdog fired
+    Wall,
+}
+
+impl TimeLimitKind {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Cpu => "Time limit exceeded (CPU time)",
+            Self::Wall => "Time limit exceeded (wall clock)",
+        }
+    }
+}
+
 /// Result of executing a single test case
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestCaseResult {
     /// Test case number (1-indexed)
     pub testcase_number: i32,
@@ -104,6 +120,9 @@ pub struct TestCaseResult {
     /// Execution time in milliseconds
     pub time_ms: u64,
 
+    /// Measured time before calibration (set only when `time_ms` was normalized)
+    pub raw_time_ms: Option<u64>,
+
     /// Peak memory usage in KB
     pub memory_kb: u64,
 
@@ -115,6 +134,21 @@ pub struct TestCaseResult {
 
     /// Checker output/comment (if any)
     pub checker_comment: Option<String>,
+
+    /// Fraction of the test's weight earned (set when the checker reported points)
+    pub checker_score: Option<f64>,
+
+    /// Bytes sent and received (set only for runs in the egress namespace)
+    pub network_bytes: Option<u64>,
+
+    /// CPU instructions retired (set only when perf counters were read)
+    pub instructions: Option<u64>,
+
+    /// Time limit the test ran under, after per-test overrides
+    pub time_limit_ms: Option<u64>,
+
+    /// Memory limit the test ran under, after per-test overrides
+    pub memory_limit_kb: Option<u64>,
 }
 
 impl TestCaseResult {
@@ -124,10 +158,16 @@ impl TestCaseResult {
             testcase_number,
             verdict: Verdict::Accepted,
             time_ms,
+            raw_time_ms: None,
             memory_kb,
             exit_code: Some(0),
             error_message: None,
             checker_comment: None,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
         }
     }
 
@@ -142,23 +182,110 @@ impl TestCaseResult {
             testcase_number,
             verdict: Verdict::WrongAnswer,
             time_ms,
+            raw_time_ms: None,
             memory_kb,
             exit_code: Some(0),
             error_message: None,
             checker_comment: comment,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
+        }
+    }
+
+    /// Create a presentation error result
+    pub fn presentation_error(
+        testcase_number: i32,
+        time_ms: u64,
+        memory_kb: u64,
+        comment: Option<String>,
+    ) -> Self {
+        Self {
+            verdict: Verdict::PresentationError,
+            ..Self::wrong_answer(testcase_number, time_ms, memory_kb, comment)
+        }
+    }
+
+    /// Create a result from checker-reported points (clamped to 0..=1).
+    ///
+    /// Full points count as accepted; anything less is a wrong answer that
+    /// still earns its share on partial-scoring problems.
+    pub fn partial_credit(
+        testcase_number: i32,
+        time_ms: u64,
+        memory_kb: u64,
+        points: f64,
+        comment: Option<String>,
+    ) -> Self {
+        let points = if points.is_finite() {
+            points.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let verdict = if points >= 1.0 {
+            Verdict::Accepted
+        } else {
+            Verdict::WrongAnswer
+        };
+
+        Self {
+            testcase_number,
+            verdict,
+            time_ms,
+            raw_time_ms: None,
+            memory_kb,
+            exit_code: Some(0),
+            error_message: None,
+            checker_comment: comment,
+            checker_score: Some(points),
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
+        }
+    }
+
+    /// Record the limits the test ran under.
+    pub fn with_limits(self, limits: TestLimits) -> Self {
+        Self {
+            time_limit_ms: Some(limits.time_limit_ms),
+            memory_limit_kb: Some(limits.memory_limit_kb),
+            ..self
+        }
+    }
+
+    /// Fraction of this test's weight earned (accepted = 1, failed = 0).
+    pub fn earned(&self) -> f64 {
+        match self.checker_score {
+            Some(points) => points,
+            None if self.verdict == Verdict::Accepted => 1.0,
+            None => 0.0,
         }
     }
 
     /// Create a time limit exceeded result
-    pub fn time_limit_exceeded(testcase_number: i32, time_limit_ms: u64, memory_kb: u64) -> Self {
+    pub fn time_limit_exceeded(
+        testcase_number: i32,
+        time_limit_ms: u64,
+        memory_kb: u64,
+        kind: TimeLimitKind,
+    ) -> Self {
         Self {
             testcase_number,
             verdict: Verdict::TimeLimitExceeded,
             time_ms: time_limit_ms,
+            raw_time_ms: None,
             memory_kb,
             exit_code: None,
-            error_message: Some("Time limit exceeded".to_string()),
+            error_message: Some(kind.message().to_string()),
             checker_comment: None,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
         }
     }
 
@@ -168,10 +295,16 @@ impl TestCaseResult {
             testcase_number,
             verdict: Verdict::MemoryLimitExceeded,
             time_ms,
+            raw_time_ms: None,
             memory_kb: memory_limit_kb,
             exit_code: None,
             error_message: Some("Memory limit exceeded".to_string()),
             checker_comment: None,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
         }
     }
 
@@ -187,10 +320,16 @@ impl TestCaseResult {
             testcase_number,
             verdict: Verdict::RuntimeError,
             time_ms,
+            raw_time_ms: None,
             memory_kb,
             exit_code: Some(exit_code),
             error_message: Some(message),
             checker_comment: None,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
         }
     }
 
@@ -200,10 +339,24 @@ impl TestCaseResult {
             testcase_number,
             verdict: Verdict::JudgeError,
             time_ms: 0,
+            raw_time_ms: None,
             memory_kb: 0,
             exit_code: None,
             error_message: Some(message),
             checker_comment: None,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
+        }
+    }
+
+    /// Create a result for a problem that failed its integrity check
+    pub fn problem_integrity_error(testcase_number: i32, message: String) -> Self {
+        Self {
+            verdict: Verdict::ProblemIntegrityError,
+            ..Self::judge_error(testcase_number, message)
         }
     }
 
@@ -213,16 +366,41 @@ impl TestCaseResult {
             testcase_number,
             verdict: Verdict::OutputLimitExceeded,
             time_ms,
+            raw_time_ms: None,
             memory_kb,
             exit_code: None,
             error_message: Some("Output limit exceeded".to_string()),
             checker_comment: None,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
+        }
+    }
+
+    /// Create an idleness limit exceeded result
+    pub fn idleness_limit_exceeded(testcase_number: i32, time_ms: u64, memory_kb: u64) -> Self {
+        Self {
+            testcase_number,
+            verdict: Verdict::IdlenessLimitExceeded,
+            time_ms,
+            raw_time_ms: None,
+            memory_kb,
+            exit_code: None,
+            error_message: Some("Idleness limit exceeded".to_string()),
+            checker_comment: None,
+            checker_score: None,
+            network_bytes: None,
+            instructions: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
         }
     }
 }
 
 /// Aggregated result for entire submission
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmissionResult {
     /// Overall verdict
     pub verdict: Verdict,
@@ -242,11 +420,37 @@ pub struct SubmissionResult {
     /// Maximum memory across all test cases (KB)
     pub max_memory_kb: u64,
 
+    /// CPU instructions summed over all test cases (set only when every
+    /// test was counted)
+    pub total_instructions: Option<u64>,
+
     /// First failing test case number (if any)
     pub first_failure: Option<i32>,
 
     /// Score (0-100)
     pub score: f64,
+
+    /// Maximum measured time before calibration (set only when normalized)
+    pub raw_max_time_ms: Option<u64>,
+
+    /// Calibration factor applied to reported times (if any)
+    pub calibration_factor: Option<f64>,
+
+    /// Participant outputs kept for failing tests
+    pub retained_outputs: Vec<RetainedOutput>,
+}
+
+/// Participant output of a failing test, kept for debugging downloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetainedOutput {
+    /// Test case number (1-indexed)
+    pub testcase_number: i32,
+    /// Path of the kept copy
+    pub path: PathBuf,
+    /// Size of the full output in bytes
+    pub size_bytes: u64,
+    /// Whether the kept copy was cut at the size cap
+    pub truncated: bool,
 }
 
 impl SubmissionResult {
@@ -259,6 +463,11 @@ impl SubmissionResult {
 
         let max_time_ms = results.iter().map(|r| r.time_ms).max().unwrap_or(0);
         let max_memory_kb = results.iter().map(|r| r.memory_kb).max().unwrap_or(0);
+        let total_instructions = if results.is_empty() {
+            None
+        } else {
+            results.iter().map(|r| r.instructions).sum()
+        };
 
         // Find first failure
         let first_failure = results
@@ -266,9 +475,16 @@ impl SubmissionResult {
             .find(|r| r.verdict.is_failure())
             .map(|r| r.testcase_number);
 
-        // Determine overall verdict
+        // Determine overall verdict (a judge error anywhere voids the run)
         let verdict = if passed_count == total_count {
             Verdict::Accepted
+        } else if results
+            .iter()
+            .any(|r| r.verdict == Verdict::ProblemIntegrityError)
+        {
+            Verdict::ProblemIntegrityError
+        } else if results.iter().any(|r| r.verdict == Verdict::JudgeError) {
+            Verdict::JudgeError
         } else if let Some(first_fail) = results.iter().find(|r| r.verdict.is_failure()) {
             first_fail.verdict
         } else {
@@ -289,8 +505,32 @@ impl SubmissionResult {
             total_count,
             max_time_ms,
             max_memory_kb,
+            total_instructions,
             first_failure,
             score,
+            raw_max_time_ms: None,
+            calibration_factor: None,
+            retained_outputs: Vec::new(),
         }
     }
+
+    /// Normalize reported times by a worker calibration factor.
+    ///
+    /// Raw measurements are kept alongside so limits and audits can still
+    /// refer to what was actually observed on this worker.
+    pub fn apply_calibration(&mut self, calibration: &Calibration) {
+        for tc in &mut self.testcase_results {
+            tc.raw_time_ms = Some(tc.time_ms);
+            tc.time_ms = calibration.normalize_ms(tc.time_ms);
+        }
+
+        self.raw_max_time_ms = Some(self.max_time_ms);
+        self.max_time_ms = calibration.normalize_ms(self.max_time_ms);
+        self.calibration_factor = Some(calibration.factor);
+    }
+
+    /// Replace the pass-ratio score with weighted partial credit.
+    pub fn apply_partial_scoring(&mut self, plan: &ScoringPlan) {
+        self.score = plan.score(&self.testcase_results, self.total_count);
+    }
 }
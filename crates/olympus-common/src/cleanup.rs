@@ -0,0 +1,40 @@
+//! Artifact classes Horus cleans.
+//!
+//! Shared by Horus, which runs a cleanup job per class, and the admin API,
+//! which edits the per-class policies in `cleanup_policies`.
+
+/// Class of stored artifacts a cleanup job is responsible for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactClass {
+    /// Cached test case directories of deleted problems
+    Testcases,
+    /// Execution temp directories without an active submission
+    Temp,
+    /// Compiled artifacts detached from their submission
+    Binaries,
+    /// Finished submissions past retention, with their artifacts
+    Submissions,
+}
+
+impl ArtifactClass {
+    pub const ALL: [Self; 4] = [
+        Self::Testcases,
+        Self::Temp,
+        Self::Binaries,
+        Self::Submissions,
+    ];
+
+    /// Name stored in `cleanup_policies.artifact_class`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Testcases => "testcases",
+            Self::Temp => "temp",
+            Self::Binaries => "binaries",
+            Self::Submissions => "submissions",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == s)
+    }
+}
@@ -0,0 +1,534 @@
+//! Message catalog for API errors.
+//!
+//! Vanguard answers errors with a stable `code` and an English `message`.
+//! This catalog holds the generic message for each error code, and for each
+//! validation rule, in every supported locale, so frontends for non-English
+//! users can show the `message` they get back instead of mapping codes
+//! themselves. Templates take named parameters in braces (`{secs}`).
+//!
+//! Keys are error codes (`NOT_FOUND`), `VALIDATION_FAILED` for the summary
+//! of failed field rules, and `field.<rule>` for those rules (`field.email`,
+//! `field.length.between`).
+
+/// Locale of the built-in messages
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales with a catalog (`zh` is Simplified Chinese)
+pub const SUPPORTED_LOCALES: [&str; 7] = ["en", "es", "pt", "fr", "de", "ru", "zh"];
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+const EN: Catalog = &[
+    ("UNAUTHORIZED", "Authentication required"),
+    ("INVALID_CREDENTIALS", "Invalid credentials"),
+    ("FORBIDDEN", "Access denied"),
+    ("NOT_FOUND", "The requested resource was not found"),
+    ("VALIDATION_ERROR", "The request is invalid"),
+    (
+        "VALIDATION_FAILED",
+        "Validation failed for {count} field(s)",
+    ),
+    ("CONFLICT", "The request conflicts with the current state"),
+    ("RATE_LIMIT_EXCEEDED", "Rate limit exceeded"),
+    (
+        "SUBMISSION_COOLDOWN",
+        "Please wait {secs} seconds before submitting to this problem again",
+    ),
+    ("QUOTA_EXCEEDED", "Quota exceeded"),
+    (
+        "CONTEST_PAUSED",
+        "Contest is paused; submissions are not accepted until it resumes",
+    ),
+    (
+        "SERVICE_UNAVAILABLE",
+        "The service is temporarily unavailable",
+    ),
+    (
+        "JUDGE_OVERLOADED",
+        "The judge is busy; practice submissions are paused, try again in {secs} seconds",
+    ),
+    ("DATABASE_ERROR", "A database error occurred"),
+    ("CACHE_ERROR", "A cache error occurred"),
+    ("INTERNAL_ERROR", "An internal error occurred"),
+    ("TOKEN_ERROR", "The token is invalid or expired"),
+    (
+        "field.length.between",
+        "Length must be between {min} and {max}",
+    ),
+    ("field.length.min", "Length must be at least {min}"),
+    ("field.length.max", "Length must be at most {max}"),
+    ("field.length.equal", "Length must be exactly {equal}"),
+    ("field.range.between", "Must be between {min} and {max}"),
+    ("field.range.min", "Must be at least {min}"),
+    ("field.range.max", "Must be at most {max}"),
+    ("field.email", "Must be a valid email address"),
+    ("field.url", "Must be a valid URL"),
+    ("field.required", "Is required"),
+    ("field.must_match", "Does not match"),
+    ("field.regex", "Has an invalid format"),
+    ("field.invalid", "Invalid value"),
+];
+
+const ES: Catalog = &[
+    ("UNAUTHORIZED", "Se requiere autenticación"),
+    ("INVALID_CREDENTIALS", "Credenciales no válidas"),
+    ("FORBIDDEN", "Acceso denegado"),
+    ("NOT_FOUND", "No se encontró el recurso solicitado"),
+    ("VALIDATION_ERROR", "La solicitud no es válida"),
+    (
+        "VALIDATION_FAILED",
+        "La validación falló en {count} campo(s)",
+    ),
+    (
+        "CONFLICT",
+        "La solicitud entra en conflicto con el estado actual",
+    ),
+    ("RATE_LIMIT_EXCEEDED", "Se superó el límite de solicitudes"),
+    (
+        "SUBMISSION_COOLDOWN",
+        "Espera {secs} segundos antes de volver a enviar a este problema",
+    ),
+    ("QUOTA_EXCEEDED", "Se superó la cuota"),
+    (
+        "CONTEST_PAUSED",
+        "El concurso está en pausa; no se aceptan envíos hasta que se reanude",
+    ),
+    (
+        "SERVICE_UNAVAILABLE",
+        "El servicio no está disponible temporalmente",
+    ),
+    (
+        "JUDGE_OVERLOADED",
+        "El juez está saturado; los envíos de práctica están en pausa, inténtalo de nuevo en {secs} segundos",
+    ),
+    ("DATABASE_ERROR", "Se produjo un error de base de datos"),
+    ("CACHE_ERROR", "Se produjo un error de caché"),
+    ("INTERNAL_ERROR", "Se produjo un error interno"),
+    ("TOKEN_ERROR", "El token no es válido o ha caducado"),
+    (
+        "field.length.between",
+        "La longitud debe estar entre {min} y {max}",
+    ),
+    ("field.length.min", "La longitud debe ser al menos {min}"),
+    ("field.length.max", "La longitud debe ser como máximo {max}"),
+    (
+        "field.length.equal",
+        "La longitud debe ser exactamente {equal}",
+    ),
+    ("field.range.between", "Debe estar entre {min} y {max}"),
+    ("field.range.min", "Debe ser al menos {min}"),
+    ("field.range.max", "Debe ser como máximo {max}"),
+    (
+        "field.email",
+        "Debe ser una dirección de correo electrónico válida",
+    ),
+    ("field.url", "Debe ser una URL válida"),
+    ("field.required", "Es obligatorio"),
+    ("field.must_match", "No coincide"),
+    ("field.regex", "El formato no es válido"),
+    ("field.invalid", "Valor no válido"),
+];
+
+const PT: Catalog = &[
+    ("UNAUTHORIZED", "Autenticação necessária"),
+    ("INVALID_CREDENTIALS", "Credenciais inválidas"),
+    ("FORBIDDEN", "Acesso negado"),
+    ("NOT_FOUND", "O recurso solicitado não foi encontrado"),
+    ("VALIDATION_ERROR", "A solicitação é inválida"),
+    (
+        "VALIDATION_FAILED",
+        "A validação falhou em {count} campo(s)",
+    ),
+    (
+        "CONFLICT",
+        "A solicitação entra em conflito com o estado atual",
+    ),
+    ("RATE_LIMIT_EXCEEDED", "Limite de requisições excedido"),
+    (
+        "SUBMISSION_COOLDOWN",
+        "Aguarde {secs} segundos antes de enviar novamente para este problema",
+    ),
+    ("QUOTA_EXCEEDED", "Cota excedida"),
+    (
+        "CONTEST_PAUSED",
+        "A competição está pausada; envios não são aceitos até que ela seja retomada",
+    ),
+    (
+        "SERVICE_UNAVAILABLE",
+        "O serviço está temporariamente indisponível",
+    ),
+    (
+        "JUDGE_OVERLOADED",
+        "O juiz está sobrecarregado; os envios de prática estão pausados, tente novamente em {secs} segundos",
+    ),
+    ("DATABASE_ERROR", "Ocorreu um erro no banco de dados"),
+    ("CACHE_ERROR", "Ocorreu um erro de cache"),
+    ("INTERNAL_ERROR", "Ocorreu um erro interno"),
+    ("TOKEN_ERROR", "O token é inválido ou expirou"),
+    (
+        "field.length.between",
+        "O comprimento deve estar entre {min} e {max}",
+    ),
+    (
+        "field.length.min",
+        "O comprimento deve ser de pelo menos {min}",
+    ),
+    (
+        "field.length.max",
+        "O comprimento deve ser de no máximo {max}",
+    ),
+    (
+        "field.length.equal",
+        "O comprimento deve ser exatamente {equal}",
+    ),
+    ("field.range.between", "Deve estar entre {min} e {max}"),
+    ("field.range.min", "Deve ser pelo menos {min}"),
+    ("field.range.max", "Deve ser no máximo {max}"),
+    ("field.email", "Deve ser um endereço de e-mail válido"),
+    ("field.url", "Deve ser uma URL válida"),
+    ("field.required", "É obrigatório"),
+    ("field.must_match", "Não corresponde"),
+    ("field.regex", "O formato é inválido"),
+    ("field.invalid", "Valor inválido"),
+];
+
+const FR: Catalog = &[
+    ("UNAUTHORIZED", "Authentification requise"),
+    ("INVALID_CREDENTIALS", "Identifiants invalides"),
+    ("FORBIDDEN", "Accès refusé"),
+    ("NOT_FOUND", "La ressource demandée est introuvable"),
+    ("VALIDATION_ERROR", "La requête est invalide"),
+    (
+        "VALIDATION_FAILED",
+        "La validation a échoué pour {count} champ(s)",
+    ),
+    ("CONFLICT", "La requête est en conflit avec l'état actuel"),
+    ("RATE_LIMIT_EXCEEDED", "Limite de requêtes dépassée"),
+    (
+        "SUBMISSION_COOLDOWN",
+        "Veuillez patienter {secs} secondes avant de soumettre de nouveau à ce problème",
+    ),
+    ("QUOTA_EXCEEDED", "Quota dépassé"),
+    (
+        "CONTEST_PAUSED",
+        "Le concours est en pause ; les soumissions ne sont pas acceptées avant sa reprise",
+    ),
+    (
+        "SERVICE_UNAVAILABLE",
+        "Le service est temporairement indisponible",
+    ),
+    (
+        "JUDGE_OVERLOADED",
+        "Le juge est saturé ; les soumissions d'entraînement sont suspendues, réessayez dans {secs} secondes",
+    ),
+    (
+        "DATABASE_ERROR",
+        "Une erreur de base de données s'est produite",
+    ),
+    ("CACHE_ERROR", "Une erreur de cache s'est produite"),
+    ("INTERNAL_ERROR", "Une erreur interne s'est produite"),
+    ("TOKEN_ERROR", "Le jeton est invalide ou a expiré"),
+    (
+        "field.length.between",
+        "La longueur doit être comprise entre {min} et {max}",
+    ),
+    ("field.length.min", "La longueur doit être d'au moins {min}"),
+    ("field.length.max", "La longueur doit être d'au plus {max}"),
+    (
+        "field.length.equal",
+        "La longueur doit être exactement {equal}",
+    ),
+    (
+        "field.range.between",
+        "Doit être compris entre {min} et {max}",
+    ),
+    ("field.range.min", "Doit être au moins {min}"),
+    ("field.range.max", "Doit être au plus {max}"),
+    ("field.email", "Doit être une adresse e-mail valide"),
+    ("field.url", "Doit être une URL valide"),
+    ("field.required", "Est obligatoire"),
+    ("field.must_match", "Ne correspond pas"),
+    ("field.regex", "Le format est invalide"),
+    ("field.invalid", "Valeur invalide"),
+];
+
+const DE: Catalog = &[
+    ("UNAUTHORIZED", "Authentifizierung erforderlich"),
+    ("INVALID_CREDENTIALS", "Ungültige Anmeldedaten"),
+    ("FORBIDDEN", "Zugriff verweigert"),
+    (
+        "NOT_FOUND",
+        "Die angeforderte Ressource wurde nicht gefunden",
+    ),
+    ("VALIDATION_ERROR", "Die Anfrage ist ungültig"),
+    (
+        "VALIDATION_FAILED",
+        "Validierung für {count} Feld(er) fehlgeschlagen",
+    ),
+    (
+        "CONFLICT",
+        "Die Anfrage steht im Konflikt mit dem aktuellen Zustand",
+    ),
+    ("RATE_LIMIT_EXCEEDED", "Anfragelimit überschritten"),
+    (
+        "SUBMISSION_COOLDOWN",
+        "Bitte warte {secs} Sekunden, bevor du erneut zu dieser Aufgabe einreichst",
+    ),
+    ("QUOTA_EXCEEDED", "Kontingent überschritten"),
+    (
+        "CONTEST_PAUSED",
+        "Der Wettbewerb ist pausiert; Einreichungen werden erst nach der Fortsetzung angenommen",
+    ),
+    (
+        "SERVICE_UNAVAILABLE",
+        "Der Dienst ist vorübergehend nicht verfügbar",
+    ),
+    (
+        "JUDGE_OVERLOADED",
+        "Der Judge ist ausgelastet; Übungseinreichungen sind pausiert, versuche es in {secs} Sekunden erneut",
+    ),
+    ("DATABASE_ERROR", "Ein Datenbankfehler ist aufgetreten"),
+    ("CACHE_ERROR", "Ein Cache-Fehler ist aufgetreten"),
+    ("INTERNAL_ERROR", "Ein interner Fehler ist aufgetreten"),
+    ("TOKEN_ERROR", "Das Token ist ungültig oder abgelaufen"),
+    (
+        "field.length.between",
+        "Die Länge muss zwischen {min} und {max} liegen",
+    ),
+    (
+        "field.length.min",
+        "Die Länge muss mindestens {min} betragen",
+    ),
+    (
+        "field.length.max",
+        "Die Länge darf höchstens {max} betragen",
+    ),
+    (
+        "field.length.equal",
+        "Die Länge muss genau {equal} betragen",
+    ),
+    (
+        "field.range.between",
+        "Muss zwischen {min} und {max} liegen",
+    ),
+    ("field.range.min", "Muss mindestens {min} sein"),
+    ("field.range.max", "Darf höchstens {max} sein"),
+    ("field.email", "Muss eine gültige E-Mail-Adresse sein"),
+    ("field.url", "Muss eine gültige URL sein"),
+    ("field.required", "Ist erforderlich"),
+    ("field.must_match", "Stimmt nicht überein"),
+    ("field.regex", "Das Format ist ungültig"),
+    ("field.invalid", "Ungültiger Wert"),
+];
+
+const RU: Catalog = &[
+    ("UNAUTHORIZED", "Требуется аутентификация"),
+    ("INVALID_CREDENTIALS", "Неверные учётные данные"),
+    ("FORBIDDEN", "Доступ запрещён"),
+    ("NOT_FOUND", "Запрошенный ресурс не найден"),
+    ("VALIDATION_ERROR", "Некорректный запрос"),
+    ("VALIDATION_FAILED", "Ошибка проверки полей: {count}"),
+    ("CONFLICT", "Запрос конфликтует с текущим состоянием"),
+    ("RATE_LIMIT_EXCEEDED", "Превышен лимит запросов"),
+    (
+        "SUBMISSION_COOLDOWN",
+        "Подождите {secs} с, прежде чем снова отправлять решение этой задачи",
+    ),
+    ("QUOTA_EXCEEDED", "Превышена квота"),
+    (
+        "CONTEST_PAUSED",
+        "Соревнование приостановлено; решения не принимаются до его возобновления",
+    ),
+    ("SERVICE_UNAVAILABLE", "Сервис временно недоступен"),
+    (
+        "JUDGE_OVERLOADED",
+        "Система проверки перегружена; тренировочные посылки приостановлены, повторите через {secs} с",
+    ),
+    ("DATABASE_ERROR", "Произошла ошибка базы данных"),
+    ("CACHE_ERROR", "Произошла ошибка кэша"),
+    ("INTERNAL_ERROR", "Произошла внутренняя ошибка"),
+    ("TOKEN_ERROR", "Токен недействителен или истёк"),
+    (
+        "field.length.between",
+        "Длина должна быть от {min} до {max}",
+    ),
+    ("field.length.min", "Длина должна быть не меньше {min}"),
+    ("field.length.max", "Длина должна быть не больше {max}"),
+    ("field.length.equal", "Длина должна быть ровно {equal}"),
+    (
+        "field.range.between",
+        "Значение должно быть от {min} до {max}",
+    ),
+    ("field.range.min", "Значение должно быть не меньше {min}"),
+    ("field.range.max", "Значение должно быть не больше {max}"),
+    (
+        "field.email",
+        "Должен быть корректный адрес электронной почты",
+    ),
+    ("field.url", "Должен быть корректный URL"),
+    ("field.required", "Обязательное поле"),
+    ("field.must_match", "Значения не совпадают"),
+    ("field.regex", "Неверный формат"),
+    ("field.invalid", "Недопустимое значение"),
+];
+
+const ZH: Catalog = &[
+    ("UNAUTHORIZED", "需要登录认证"),
+    ("INVALID_CREDENTIALS", "凭据无效"),
+    ("FORBIDDEN", "拒绝访问"),
+    ("NOT_FOUND", "未找到请求的资源"),
+    ("VALIDATION_ERROR", "请求无效"),
+    ("VALIDATION_FAILED", "{count} 个字段验证失败"),
+    ("CONFLICT", "请求与当前状态冲突"),
+    ("RATE_LIMIT_EXCEEDED", "超出请求频率限制"),
+    ("SUBMISSION_COOLDOWN", "请等待 {secs} 秒后再向此题提交"),
+    ("QUOTA_EXCEEDED", "超出配额"),
+    ("CONTEST_PAUSED", "比赛已暂停，恢复前不接受提交"),
+    ("SERVICE_UNAVAILABLE", "服务暂时不可用"),
+    (
+        "JUDGE_OVERLOADED",
+        "评测繁忙，练习提交已暂停，请在 {secs} 秒后重试",
+    ),
+    ("DATABASE_ERROR", "发生数据库错误"),
+    ("CACHE_ERROR", "发生缓存错误"),
+    ("INTERNAL_ERROR", "发生内部错误"),
+    ("TOKEN_ERROR", "令牌无效或已过期"),
+    ("field.length.between", "长度必须在 {min} 到 {max} 之间"),
+    ("field.length.min", "长度不能少于 {min}"),
+    ("field.length.max", "长度不能超过 {max}"),
+    ("field.length.equal", "长度必须为 {equal}"),
+    ("field.range.between", "必须在 {min} 到 {max} 之间"),
+    ("field.range.min", "不能小于 {min}"),
+    ("field.range.max", "不能大于 {max}"),
+    ("field.email", "必须是有效的电子邮件地址"),
+    ("field.url", "必须是有效的 URL"),
+    ("field.required", "必填"),
+    ("field.must_match", "不匹配"),
+    ("field.regex", "格式无效"),
+    ("field.invalid", "值无效"),
+];
+
+fn catalog(locale: &str) -> Option<Catalog> {
+    match locale {
+        "en" => Some(EN),
+        "es" => Some(ES),
+        "pt" => Some(PT),
+        "fr" => Some(FR),
+        "de" => Some(DE),
+        "ru" => Some(RU),
+        "zh" => Some(ZH),
+        _ => None,
+    }
+}
+
+/// First supported locale for the preferences, most preferred first,
+/// matched on the primary language (`pt-BR` gets `pt`). Falls back to the
+/// default locale.
+pub fn negotiate<S: AsRef<str>>(preferred: &[S]) -> &'static str {
+    preferred
+        .iter()
+        .find_map(|tag| {
+            let language = tag.as_ref().split(['-', '_']).next().unwrap_or_default();
+            SUPPORTED_LOCALES
+                .iter()
+                .find(|l| l.eq_ignore_ascii_case(language))
+                .copied()
+        })
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Template for `key` in `locale`, if the catalog has one
+pub fn message(locale: &str, key: &str) -> Option<&'static str> {
+    catalog(locale)?
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, template)| *template)
+}
+
+/// Fill a template's `{name}` parameters
+pub fn render(template: &str, params: &[(&str, String)]) -> String {
+    params
+        .iter()
+        .fold(template.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `{name}` parameters of a template, in order of appearance
+    fn placeholders(template: &str) -> Vec<&str> {
+        let mut names = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            names.push(&rest[start + 1..start + len]);
+            rest = &rest[start + len + 1..];
+        }
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_negotiate_takes_first_supported_language() {
+        assert_eq!(negotiate(&["ja", "fr-CA", "de"]), "fr");
+        assert_eq!(negotiate(&["pt-BR"]), "pt");
+        assert_eq!(negotiate(&["zh_Hans"]), "zh");
+        assert_eq!(negotiate(&["DE-at"]), "de");
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_default() {
+        assert_eq!(negotiate::<&str>(&[]), DEFAULT_LOCALE);
+        assert_eq!(negotiate(&["ja", "ko"]), DEFAULT_LOCALE);
+        assert_eq!(negotiate(&[""]), DEFAULT_LOCALE);
+    }
+
+    #[test]
+    fn test_message_lookup() {
+        assert_eq!(message("de", "FORBIDDEN"), Some("Zugriff verweigert"));
+        assert_eq!(message("ja", "FORBIDDEN"), None);
+        assert_eq!(message("en", "NO_SUCH_KEY"), None);
+    }
+
+    #[test]
+    fn test_render_fills_placeholders() {
+        let params = [("min", "3".to_string()), ("max", "20".to_string())];
+        assert_eq!(
+            render("Length must be between {min} and {max}", &params),
+            "Length must be between 3 and 20"
+        );
+        assert_eq!(render("{min}..{min}", &params), "3..3");
+        // Parameters the template lacks are ignored, and missing ones stay
+        assert_eq!(render("At least {min}", &params), "At least 3");
+        assert_eq!(render("Wait {secs}s", &params), "Wait {secs}s");
+    }
+
+    #[test]
+    fn test_every_catalog_covers_the_english_keys() {
+        for locale in SUPPORTED_LOCALES {
+            let catalog = catalog(locale).unwrap_or_else(|| panic!("no catalog for {}", locale));
+            assert_eq!(
+                catalog.len(),
+                EN.len(),
+                "{} has extra or missing keys",
+                locale
+            );
+            for (key, english) in EN {
+                let template = message(locale, key)
+                    .unwrap_or_else(|| panic!("{} has no message for {}", locale, key));
+                assert_eq!(
+                    placeholders(template),
+                    placeholders(english),
+                    "{} message for {} has other parameters",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+}
@@ -1,7 +1,13 @@
 //! Common types, errors, and utilities for Olympus services.
 
+pub mod cleanup;
 pub mod error;
+pub mod i18n;
+pub mod problem_backup;
+pub mod storage;
+pub mod stream_id;
 pub mod types;
+pub mod worker_profile;
 
 pub use error::AppError;
 pub use types::*;
@@ -0,0 +1,64 @@
+//! Problem package backups.
+//!
+//! Horus snapshots each problem whose package changed into a versioned ZIP
+//! archive, `{PROBLEM_BACKUPS_DIR}/{problem_id}/v{version}.zip` under the
+//! base storage path, and Vanguard restores them. An archive holds
+//! [`MANIFEST_FILE`] with the problem's rows from every table in
+//! [`BACKUP_TABLES`], and the problem's binaries directory under
+//! [`ARCHIVE_BINARIES_PREFIX`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::PROBLEM_BACKUPS_DIR;
+
+/// Manifest format written by this version
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Archive entry holding the [`BackupManifest`]
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Archive prefix of the files from the problem's binaries directory
+pub const ARCHIVE_BINARIES_PREFIX: &str = "binaries/";
+
+/// Tables making up a problem package, in the order they are restored.
+/// Submissions, contest links and reference solutions are not part of it.
+pub const BACKUP_TABLES: &[&str] = &[
+    "problems",
+    "test_cases",
+    "problem_subtasks",
+    "problem_statements",
+    "problem_binary_versions",
+    "checker_tests",
+];
+
+/// Column of `table` naming the problem a row belongs to
+pub fn problem_column(table: &str) -> &'static str {
+    if table == "problems" {
+        "id"
+    } else {
+        "problem_id"
+    }
+}
+
+/// Contents of [`MANIFEST_FILE`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub problem_id: Uuid,
+    pub version: i32,
+    pub created_at: DateTime<Utc>,
+    /// Rows of each table as JSON objects, keyed by table name
+    pub tables: BTreeMap<String, serde_json::Value>,
+}
+
+/// Archive of `version` of a problem's package
+pub fn archive_path(base: &Path, problem_id: Uuid, version: i32) -> PathBuf {
+    base.join(PROBLEM_BACKUPS_DIR)
+        .join(problem_id.to_string())
+        .join(format!("v{}.zip", version))
+}
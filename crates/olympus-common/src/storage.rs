@@ -0,0 +1,156 @@
+//! Storage locations of contest data.
+//!
+//! Submissions and compiled artifacts live on the shared data volume
+//! (`STORAGE_BASE_PATH`, `/mnt/data`) unless their contest names a storage
+//! root of its own, for institutions that must keep their data on a
+//! particular volume. Contest roots are limited to the directories listed in
+//! `CONTEST_STORAGE_ROOTS`, and each has the same layout as the base.
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// ZIP submissions, under a storage root
+pub const SUBMISSIONS_DIR: &str = "submissions";
+
+/// Compiled submission artifacts, under a storage root
+pub const USER_BINARIES_DIR: &str = "binaries/users";
+
+/// Outputs kept for failing tests, under a storage root
+pub const DEBUG_OUTPUTS_DIR: &str = "debug_outputs";
+
+/// Problem generators, checkers and reference binaries, under the base
+pub const PROBLEM_BINARIES_DIR: &str = "binaries/problems";
+
+/// Versioned problem package archives, under the base
+pub const PROBLEM_BACKUPS_DIR: &str = "backups/problems";
+
+/// Marker Minos writes in a problem's test case directory naming the
+/// generator version the cached inputs came from
+pub const GENERATOR_VERSION_MARKER: &str = ".generator_version";
+
+/// The base storage directory and the roots contests may use instead
+#[derive(Debug, Clone)]
+pub struct StorageRoots {
+    base: PathBuf,
+    contest_roots: Vec<PathBuf>,
+}
+
+impl StorageRoots {
+    /// Relative contest roots are ignored
+    pub fn new(base: impl Into<PathBuf>, contest_roots: Vec<PathBuf>) -> Self {
+        Self {
+            base: base.into(),
+            contest_roots: contest_roots
+                .into_iter()
+                .filter(|root| root.is_absolute())
+                .collect(),
+        }
+    }
+
+    /// `base` and the comma-separated roots of `CONTEST_STORAGE_ROOTS`
+    pub fn from_env(base: impl Into<PathBuf>) -> Self {
+        let contest_roots = env::var("CONTEST_STORAGE_ROOTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|root| !root.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        Self::new(base, contest_roots)
+    }
+
+    pub fn base(&self) -> &Path {
+        &self.base
+    }
+
+    /// Roots a contest may be given
+    pub fn contest_roots(&self) -> &[PathBuf] {
+        &self.contest_roots
+    }
+
+    /// Whether `root` is one of the configured contest roots
+    pub fn is_contest_root(&self, root: &str) -> bool {
+        self.contest_roots.iter().any(|r| r == Path::new(root))
+    }
+
+    /// Root holding a contest's data: its own, or the base without one.
+    /// `None` when the contest's root is not configured on this service.
+    pub fn resolve(&self, contest_root: Option<&str>) -> Option<&Path> {
+        match contest_root {
+            None => Some(&self.base),
+            Some(root) => self
+                .contest_roots
+                .iter()
+                .find(|r| *r == Path::new(root))
+                .map(PathBuf::as_path),
+        }
+    }
+
+    /// Whether `path` is inside `dir` (e.g. [`USER_BINARIES_DIR`]) of the
+    /// base or of a contest root
+    pub fn contains(&self, dir: &str, path: &Path) -> bool {
+        std::iter::once(&self.base)
+            .chain(&self.contest_roots)
+            .any(|root| path.starts_with(root.join(dir)))
+    }
+
+    /// Remove a stored file of a deleted submission: its ZIP, an output
+    /// kept for a failing test, or its compiled artifact (a file, or a
+    /// bundle directory). Paths outside those directories of the storage
+    /// roots are refused. Returns whether anything was removed; a path
+    /// already gone is not an error.
+    pub async fn remove_submission_file(&self, path: &Path) -> io::Result<bool> {
+        let stored = [SUBMISSIONS_DIR, USER_BINARIES_DIR, DEBUG_OUTPUTS_DIR]
+            .iter()
+            .any(|dir| self.contains(dir, path));
+        if !stored {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is outside the submission directories", path.display()),
+            ));
+        }
+
+        let removed = match tokio::fs::symlink_metadata(path).await {
+            Ok(meta) if meta.is_dir() => tokio::fs::remove_dir_all(path).await,
+            Ok(_) => tokio::fs::remove_file(path).await,
+            Err(e) => Err(e),
+        };
+        match removed {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_remove_submission_file_stays_in_submission_directories() {
+        let base = env::temp_dir().join(format!("storage-{}", uuid::Uuid::new_v4()));
+        let roots = StorageRoots::new(&base, Vec::new());
+        let bundle = base.join(USER_BINARIES_DIR).join("abc_bin");
+        let output = base.join(DEBUG_OUTPUTS_DIR).join("abc").join("1.out");
+        let testcase = base.join("testcases").join("1.in");
+        for file in [bundle.join("run.sh"), output.clone(), testcase.clone()] {
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(&file, b"data").unwrap();
+        }
+
+        assert!(roots.remove_submission_file(&bundle).await.unwrap());
+        assert!(!bundle.exists());
+        assert!(roots.remove_submission_file(&output).await.unwrap());
+        assert!(!output.exists());
+        // Already gone
+        assert!(!roots.remove_submission_file(&output).await.unwrap());
+
+        let outside = roots.remove_submission_file(&testcase).await.unwrap_err();
+        assert_eq!(outside.kind(), io::ErrorKind::PermissionDenied);
+        assert!(testcase.exists());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+//! Redis stream entry ids.
+//!
+//! An id is `<ms>-<seq>`, where `<ms>` is the time the entry was added, so
+//! the age of a queued job can be read off its id without fetching it.
+
+/// Whether `id` looks like a stream entry id (`<ms>` or `<ms>-<seq>`)
+pub fn is_stream_id(id: &str) -> bool {
+    id.splitn(2, '-')
+        .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Milliseconds since the entry with `id` was added, never negative, or
+/// `None` if `id` does not start with a timestamp
+pub fn stream_id_age_ms(id: &str, now_ms: i64) -> Option<i64> {
+    let added_ms: i64 = id.split('-').next()?.parse().ok()?;
+    Some((now_ms - added_ms).max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_stream_id() {
+        assert!(is_stream_id("1700000000000-0"));
+        assert!(is_stream_id("1700000000000-12"));
+        assert!(is_stream_id("1700000000000"));
+
+        assert!(!is_stream_id(""));
+        assert!(!is_stream_id("-"));
+        assert!(!is_stream_id("1700000000000-"));
+        assert!(!is_stream_id("-0"));
+        assert!(!is_stream_id("1700000000000-0-1"));
+        assert!(!is_stream_id("abc-0"));
+        assert!(!is_stream_id("+"));
+        assert!(!is_stream_id("1700000000000-*"));
+    }
+
+    #[test]
+    fn test_stream_id_age_ms() {
+        let now_ms = 1_700_000_005_000;
+        assert_eq!(stream_id_age_ms("1700000000000-0", now_ms), Some(5_000));
+        assert_eq!(stream_id_age_ms("1700000000000", now_ms), Some(5_000));
+        // Clock skew never gives a negative age
+        assert_eq!(stream_id_age_ms("1700000009000-3", now_ms), Some(0));
+        assert_eq!(stream_id_age_ms("abc-0", now_ms), None);
+        assert_eq!(stream_id_age_ms("", now_ms), None);
+    }
+}
@@ -19,7 +19,7 @@ pub type SubmissionId = Uuid;
 pub type TestCaseId = Uuid;
 
 /// User role in the system
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum UserRole {
     /// Full system access
@@ -27,17 +27,12 @@ pub enum UserRole {
     /// Can create/manage contests and problems
     Organizer,
     /// Can participate in contests and submit solutions
+    #[default]
     Participant,
     /// Can view public contests and leaderboards
     Spectator,
 }
 
-impl Default for UserRole {
-    fn default() -> Self {
-        UserRole::Participant
-    }
-}
-
 impl std::fmt::Display for UserRole {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -61,6 +56,8 @@ pub enum SubmissionStatus {
     Compiled,
     /// Compilation failed
     CompilationError,
+    /// ZIP archive rejected before compiling
+    ValidationFailed,
     /// Currently running tests
     Running,
     /// All tests passed
@@ -84,6 +81,7 @@ impl std::fmt::Display for SubmissionStatus {
             SubmissionStatus::Compiling => write!(f, "COMPILING"),
             SubmissionStatus::Compiled => write!(f, "COMPILED"),
             SubmissionStatus::CompilationError => write!(f, "COMPILATION_ERROR"),
+            SubmissionStatus::ValidationFailed => write!(f, "VALIDATION_FAILED"),
             SubmissionStatus::Running => write!(f, "RUNNING"),
             SubmissionStatus::Accepted => write!(f, "ACCEPTED"),
             SubmissionStatus::WrongAnswer => write!(f, "WRONG_ANSWER"),
@@ -113,36 +111,12 @@ pub enum Verdict {
     PresentationError,
 }
 
-/// Supported runtime/language for submissions
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Runtime {
-    Cpp,
-    C,
-    Rust,
-    Go,
-    Python,
-    Zig,
-}
-
-impl std::fmt::Display for Runtime {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Runtime::Cpp => write!(f, "cpp"),
-            Runtime::C => write!(f, "c"),
-            Runtime::Rust => write!(f, "rust"),
-            Runtime::Go => write!(f, "go"),
-            Runtime::Python => write!(f, "python"),
-            Runtime::Zig => write!(f, "zig"),
-        }
-    }
-}
-
 /// Contest status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ContestStatus {
     /// Contest is being prepared
+    #[default]
     Draft,
     /// Contest is open for registration
     Registration,
@@ -154,9 +128,199 @@ pub enum ContestStatus {
     Archived,
 }
 
-impl Default for ContestStatus {
-    fn default() -> Self {
-        ContestStatus::Draft
+/// Priority of a compile/run job.
+///
+/// Each level has its own Redis stream and workers drain higher levels
+/// first. `Normal` uses the base stream name so existing deployments keep
+/// working; the other levels append a suffix (`compile_queue:high`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JudgePriority {
+    /// Submissions to a running contest
+    High,
+    /// Practice and standalone submissions
+    #[default]
+    Normal,
+    /// Rejudges and other bulk re-queues
+    Low,
+}
+
+impl JudgePriority {
+    /// All levels, in the order workers drain them
+    pub const ALL: [JudgePriority; 3] = [
+        JudgePriority::High,
+        JudgePriority::Normal,
+        JudgePriority::Low,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JudgePriority::High => "high",
+            JudgePriority::Normal => "normal",
+            JudgePriority::Low => "low",
+        }
+    }
+
+    /// Stream for this level, derived from the base stream name
+    pub fn stream_name(&self, base: &str) -> String {
+        match self {
+            JudgePriority::Normal => base.to_string(),
+            other => format!("{}:{}", base, other.as_str()),
+        }
+    }
+
+    /// Streams for every level, highest priority first
+    pub fn streams(base: &str) -> Vec<String> {
+        Self::ALL.iter().map(|p| p.stream_name(base)).collect()
+    }
+
+    /// Level whose stream is `stream`, if it derives from `base`
+    pub fn from_stream_name(base: &str, stream: &str) -> Option<JudgePriority> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.stream_name(base) == stream)
+    }
+}
+
+impl std::fmt::Display for JudgePriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Event a user can be notified about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A clarification the user asked was answered
+    ClarificationAnswered,
+    /// One of the user's submissions was judged
+    VerdictReady,
+    /// A contest the user registered for starts soon
+    ContestStarting,
+    /// The user got a seat in a contest they were waitlisted for
+    WaitlistPromoted,
+}
+
+impl NotificationKind {
+    /// Every kind, in the order preferences are listed
+    pub const ALL: [NotificationKind; 4] = [
+        NotificationKind::ClarificationAnswered,
+        NotificationKind::VerdictReady,
+        NotificationKind::ContestStarting,
+        NotificationKind::WaitlistPromoted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::ClarificationAnswered => "clarification_answered",
+            NotificationKind::VerdictReady => "verdict_ready",
+            NotificationKind::ContestStarting => "contest_starting",
+            NotificationKind::WaitlistPromoted => "waitlist_promoted",
+        }
+    }
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Verdict a checker gives a contestant output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckerVerdict {
+    Accepted,
+    WrongAnswer,
+    PresentationError,
+    PartialCredit,
+    /// The checker itself failed (crash, timeout or testlib `FAIL`)
+    Fail,
+}
+
+impl CheckerVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckerVerdict::Accepted => "accepted",
+            CheckerVerdict::WrongAnswer => "wrong_answer",
+            CheckerVerdict::PresentationError => "presentation_error",
+            CheckerVerdict::PartialCredit => "partial_credit",
+            CheckerVerdict::Fail => "fail",
+        }
+    }
+}
+
+impl std::fmt::Display for CheckerVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Built-in comparison of a contestant's output with the reference
+/// answer, judging problems that have no custom checker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputComparison {
+    /// Byte for byte
+    Exact,
+    /// Same whitespace-separated tokens
+    Tokens,
+    /// Same tokens, numbers within an absolute or relative epsilon
+    Float,
+    /// Same tokens, ignoring ASCII case
+    CaseInsensitive,
+}
+
+impl OutputComparison {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputComparison::Exact => "exact",
+            OutputComparison::Tokens => "tokens",
+            OutputComparison::Float => "float",
+            OutputComparison::CaseInsensitive => "case_insensitive",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "exact" => Some(OutputComparison::Exact),
+            "tokens" => Some(OutputComparison::Tokens),
+            "float" => Some(OutputComparison::Float),
+            "case_insensitive" => Some(OutputComparison::CaseInsensitive),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for OutputComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a reference solution is for in time limit calibration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceRole {
+    /// Intended solution; the limit must leave it a safety margin
+    Model,
+    /// Too slow to pass; the limit must reject it
+    Slow,
+}
+
+impl ReferenceRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceRole::Model => "model",
+            ReferenceRole::Slow => "slow",
+        }
+    }
+}
+
+impl std::fmt::Display for ReferenceRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -210,3 +374,52 @@ impl<T> PaginatedResponse<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_priority_uses_the_base_stream() {
+        assert_eq!(JudgePriority::Normal.stream_name("run_queue"), "run_queue");
+    }
+
+    #[test]
+    fn test_other_priorities_append_a_suffix() {
+        assert_eq!(
+            JudgePriority::High.stream_name("compile_queue"),
+            "compile_queue:high"
+        );
+        assert_eq!(
+            JudgePriority::Low.stream_name("compile_queue"),
+            "compile_queue:low"
+        );
+    }
+
+    #[test]
+    fn test_streams_are_listed_highest_priority_first() {
+        assert_eq!(
+            JudgePriority::streams("run_queue"),
+            ["run_queue:high", "run_queue", "run_queue:low"]
+        );
+    }
+
+    #[test]
+    fn test_from_stream_name_round_trips() {
+        for priority in JudgePriority::ALL {
+            let stream = priority.stream_name("run_queue");
+            assert_eq!(
+                JudgePriority::from_stream_name("run_queue", &stream),
+                Some(priority)
+            );
+        }
+        assert_eq!(
+            JudgePriority::from_stream_name("run_queue", "compile_queue:high"),
+            None
+        );
+        assert_eq!(
+            JudgePriority::from_stream_name("run_queue", "run_queue:urgent"),
+            None
+        );
+    }
+}
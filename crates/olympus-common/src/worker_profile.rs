@@ -0,0 +1,91 @@
+//! Worker profiles of judge machines.
+//!
+//! A problem is assigned a profile (`standard`, `highmem`, `longrun`, ...)
+//! and its run jobs go to that profile's streams, so problems that need a
+//! lot of memory or time land on the machines provisioned for them. Each
+//! Minos instance runs under one profile (`WORKER_PROFILE`) and only reads
+//! its streams. The `standard` profile uses the base stream name so existing
+//! deployments keep working; other profiles append it (`run_queue.highmem`),
+//! and priorities are applied on top (`run_queue.highmem:high`). The
+//! profiles a deployment runs are listed in `WORKER_PROFILES`.
+
+use std::env;
+
+use crate::types::JudgePriority;
+
+/// Profile of problems without one, and of workers without `WORKER_PROFILE`
+pub const DEFAULT_WORKER_PROFILE: &str = "standard";
+
+/// Longest profile name, matching the `problems.worker_profile` column
+pub const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// Whether `name` can be used as a profile: lowercase letters, digits,
+/// `_` and `-`
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LEN
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-')
+}
+
+/// Base stream of a profile's jobs, derived from the base stream name
+pub fn profile_stream(base: &str, profile: &str) -> String {
+    if profile == DEFAULT_WORKER_PROFILE {
+        base.to_string()
+    } else {
+        format!("{}.{}", base, profile)
+    }
+}
+
+/// The worker profiles a deployment runs
+#[derive(Debug, Clone)]
+pub struct WorkerProfiles {
+    profiles: Vec<String>,
+}
+
+impl WorkerProfiles {
+    /// `standard` is always included; invalid names are ignored
+    pub fn new(profiles: impl IntoIterator<Item = String>) -> Self {
+        let mut all = vec![DEFAULT_WORKER_PROFILE.to_string()];
+        for profile in profiles {
+            if is_valid_profile_name(&profile) && !all.contains(&profile) {
+                all.push(profile);
+            }
+        }
+        Self { profiles: all }
+    }
+
+    /// The comma-separated profiles of `WORKER_PROFILES`
+    pub fn from_env() -> Self {
+        let profiles = env::var("WORKER_PROFILES")
+            .unwrap_or_default()
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>();
+        Self::new(profiles)
+    }
+
+    pub fn contains(&self, profile: &str) -> bool {
+        self.profiles.iter().any(|p| p == profile)
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.profiles
+    }
+
+    /// Every profile and priority stream derived from `base`, with the
+    /// profile and priority each belongs to
+    pub fn streams(&self, base: &str) -> Vec<(String, &str, JudgePriority)> {
+        self.profiles
+            .iter()
+            .flat_map(|profile| {
+                let profile_base = profile_stream(base, profile);
+                JudgePriority::ALL
+                    .into_iter()
+                    .map(move |p| (p.stream_name(&profile_base), profile.as_str(), p))
+            })
+            .collect()
+    }
+}
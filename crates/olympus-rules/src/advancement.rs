@@ -0,0 +1,138 @@
+//! Advancement rules for contest series.
+//!
+//! When a round of a series (round 1, 2, 3, ...) carries its participants
+//! over to the next one, these rules decide who advances from the previous
+//! round's final standings. Every rule that is set must hold; with no rules
+//! every participant advances.
+
+use serde::{Deserialize, Serialize};
+
+/// Conditions a participant of the previous round must meet to advance
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdvancementRules {
+    /// Only the top N ranks advance (ties at rank N all advance)
+    #[serde(default)]
+    pub top: Option<u32>,
+    /// Minimum total score
+    #[serde(default)]
+    pub min_score: Option<f64>,
+    /// Minimum number of solved problems
+    #[serde(default)]
+    pub min_solved: Option<i64>,
+}
+
+/// A participant's final result in the previous round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Standing {
+    /// Leaderboard rank (ties share a rank), `None` without any submission
+    pub rank: Option<u32>,
+    pub total_score: f64,
+    pub problems_solved: i64,
+}
+
+impl AdvancementRules {
+    /// Check the rules make sense on their own.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.top == Some(0) {
+            return Err("top must be at least 1".to_string());
+        }
+        if let Some(min_score) = self.min_score {
+            if !min_score.is_finite() || min_score < 0.0 {
+                return Err("min_score must be a non-negative number".to_string());
+            }
+        }
+        if self.min_solved.is_some_and(|n| n < 0) {
+            return Err("min_solved must not be negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether no rule is set, so everyone advances
+    pub fn is_empty(&self) -> bool {
+        self.top.is_none() && self.min_score.is_none() && self.min_solved.is_none()
+    }
+
+    /// Whether a participant with this standing advances
+    pub fn admits(&self, standing: &Standing) -> bool {
+        let within_top = match self.top {
+            Some(top) => standing.rank.is_some_and(|rank| rank <= top),
+            None => true,
+        };
+        within_top
+            && self.min_score.is_none_or(|min| standing.total_score >= min)
+            && self
+                .min_solved
+                .is_none_or(|min| standing.problems_solved >= min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standing(rank: Option<u32>, total_score: f64, problems_solved: i64) -> Standing {
+        Standing {
+            rank,
+            total_score,
+            problems_solved,
+        }
+    }
+
+    #[test]
+    fn test_no_rules_admit_everyone() {
+        let rules = AdvancementRules::default();
+        assert!(rules.is_empty());
+        assert!(rules.admits(&standing(None, 0.0, 0)));
+        assert!(rules.admits(&standing(Some(500), 10.0, 1)));
+    }
+
+    #[test]
+    fn test_top_includes_ties_and_excludes_unranked() {
+        let rules = AdvancementRules {
+            top: Some(3),
+            ..Default::default()
+        };
+        assert!(rules.admits(&standing(Some(1), 300.0, 3)));
+        assert!(rules.admits(&standing(Some(3), 100.0, 1)));
+        assert!(!rules.admits(&standing(Some(4), 100.0, 1)));
+        assert!(!rules.admits(&standing(None, 0.0, 0)));
+    }
+
+    #[test]
+    fn test_all_rules_must_hold() {
+        let rules = AdvancementRules {
+            top: Some(10),
+            min_score: Some(150.0),
+            min_solved: Some(2),
+        };
+        assert!(rules.admits(&standing(Some(5), 150.0, 2)));
+        assert!(!rules.admits(&standing(Some(5), 149.5, 2)));
+        assert!(!rules.admits(&standing(Some(5), 200.0, 1)));
+        assert!(!rules.admits(&standing(Some(11), 200.0, 2)));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(AdvancementRules::default().validate().is_ok());
+        for invalid in [
+            AdvancementRules {
+                top: Some(0),
+                ..Default::default()
+            },
+            AdvancementRules {
+                min_score: Some(-1.0),
+                ..Default::default()
+            },
+            AdvancementRules {
+                min_score: Some(f64::NAN),
+                ..Default::default()
+            },
+            AdvancementRules {
+                min_solved: Some(-1),
+                ..Default::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err(), "{:?}", invalid);
+        }
+    }
+}
@@ -3,9 +3,13 @@
 //! These specifications perform async database/cache lookups to evaluate
 //! user permissions for contests, problems, and submissions.
 
+#[cfg(feature = "auth")]
+use crate::collaborators::CollaboratorPermission;
 #[cfg(feature = "auth")]
 use crate::context::AuthContext;
 #[cfg(feature = "auth")]
+use crate::roles::Permission;
+#[cfg(feature = "auth")]
 use crate::specification::Specification;
 #[cfg(feature = "auth")]
 use async_trait::async_trait;
@@ -28,7 +32,19 @@ impl Specification<AuthContext> for IsValidUser {
     }
 }
 
-/// Check if the user has admin role.
+/// Check if the user's role grants a site permission.
+#[cfg(feature = "auth")]
+pub struct HasPermission(pub Permission);
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for HasPermission {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        ctx.permissions.contains(self.0)
+    }
+}
+
+/// Check if the user's role may administer the platform.
 #[cfg(feature = "auth")]
 pub struct IsAdmin;
 
@@ -36,11 +52,11 @@ pub struct IsAdmin;
 #[async_trait]
 impl Specification<AuthContext> for IsAdmin {
     async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
-        ctx.role == "admin"
+        ctx.permissions.contains(Permission::ManagePlatform)
     }
 }
 
-/// Check if the user has organizer role (or higher).
+/// Check if the user's role may create contests (organizers and admins).
 #[cfg(feature = "auth")]
 pub struct IsOrganizer;
 
@@ -48,7 +64,54 @@ pub struct IsOrganizer;
 #[async_trait]
 impl Specification<AuthContext> for IsOrganizer {
     async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
-        ctx.role == "admin" || ctx.role == "organizer"
+        ctx.permissions.contains(Permission::CreateContests)
+    }
+}
+
+/// Resource an [`AuthContext`] can be scoped to.
+#[cfg(feature = "auth")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Contest,
+    Problem,
+    Submission,
+}
+
+#[cfg(feature = "auth")]
+impl Resource {
+    /// Table holding the resource.
+    pub fn table(self) -> &'static str {
+        match self {
+            Self::Contest => "contests",
+            Self::Problem => "problems",
+            Self::Submission => "submissions",
+        }
+    }
+
+    /// Target id of this resource in the context, if set.
+    pub fn id_in(self, ctx: &AuthContext) -> Option<Uuid> {
+        match self {
+            Self::Contest => ctx.contest_id,
+            Self::Problem => ctx.problem_id,
+            Self::Submission => ctx.submission_id,
+        }
+    }
+}
+
+/// Check if the user owns the context's resource of the given kind
+/// (contest/problem owner, submission author).
+#[cfg(feature = "auth")]
+pub struct IsOwner(pub Resource);
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for IsOwner {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        match self.0 {
+            Resource::Contest => IsContestOwner.is_satisfied_by(ctx).await,
+            Resource::Problem => IsProblemOwner.is_satisfied_by(ctx).await,
+            Resource::Submission => IsSubmissionOwner.is_satisfied_by(ctx).await,
+        }
     }
 }
 
@@ -56,6 +119,175 @@ impl Specification<AuthContext> for IsOrganizer {
 // Contest-scoped rules
 // =============================================================================
 
+/// Check if the context's contest is currently running (started, not ended).
+/// Requires `ctx.contest_id` to be set.
+#[cfg(feature = "auth")]
+pub struct ContestIsRunning;
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for ContestIsRunning {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        let Some(contest_id) = ctx.contest_id else {
+            tracing::warn!("ContestIsRunning evaluated without contest_id in context");
+            return false;
+        };
+
+        let result: Result<Option<bool>, _> = sqlx::query_scalar(
+            "SELECT NOW() >= start_time AND NOW() < end_time FROM contests WHERE id = $1",
+        )
+        .bind(contest_id)
+        .fetch_optional(ctx.db.as_ref())
+        .await;
+
+        result.ok().flatten().unwrap_or(false)
+    }
+}
+
+/// Check if the context's contest has ended. Submissions to an ended
+/// contest are practice ("upsolving") submissions.
+/// Requires `ctx.contest_id` to be set.
+#[cfg(feature = "auth")]
+pub struct ContestHasEnded;
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for ContestHasEnded {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        let Some(contest_id) = ctx.contest_id else {
+            tracing::warn!("ContestHasEnded evaluated without contest_id in context");
+            return false;
+        };
+
+        let result: Result<Option<bool>, _> =
+            sqlx::query_scalar("SELECT NOW() >= end_time FROM contests WHERE id = $1")
+                .bind(contest_id)
+                .fetch_optional(ctx.db.as_ref())
+                .await;
+
+        result.ok().flatten().unwrap_or(false)
+    }
+}
+
+/// Check if the context's contest is public.
+/// Requires `ctx.contest_id` to be set.
+#[cfg(feature = "auth")]
+pub struct ContestIsPublic;
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for ContestIsPublic {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        let Some(contest_id) = ctx.contest_id else {
+            tracing::warn!("ContestIsPublic evaluated without contest_id in context");
+            return false;
+        };
+
+        let result: Result<Option<bool>, _> =
+            sqlx::query_scalar("SELECT is_public FROM contests WHERE id = $1")
+                .bind(contest_id)
+                .fetch_optional(ctx.db.as_ref())
+                .await;
+
+        result.ok().flatten().unwrap_or(false)
+    }
+}
+
+/// Check if the context's contest has an open pause (see `contest_pauses`).
+/// Requires `ctx.contest_id` to be set.
+#[cfg(feature = "auth")]
+pub struct ContestIsPaused;
+
+#[cfg(feature = "auth")]
+impl ContestIsPaused {
+    /// Whether the contest is paused, surfacing lookup errors for callers
+    /// that must not treat them as "not paused".
+    pub async fn lookup(contest_id: Uuid, db: &sqlx::PgPool) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM contest_pauses WHERE contest_id = $1 AND resumed_at IS NULL)",
+        )
+        .bind(contest_id)
+        .fetch_one(db)
+        .await
+    }
+}
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for ContestIsPaused {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        let Some(contest_id) = ctx.contest_id else {
+            tracing::warn!("ContestIsPaused evaluated without contest_id in context");
+            return false;
+        };
+
+        Self::lookup(contest_id, ctx.db.as_ref())
+            .await
+            .unwrap_or(false)
+    }
+}
+
+/// Check if the context's contest has ended and publishes its submission
+/// sources to the user: to everyone (`source_visibility = 'public'`) or to
+/// its registered participants (`'participants'`).
+/// Requires `ctx.contest_id` to be set.
+#[cfg(feature = "auth")]
+pub struct ContestSourcesVisible;
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for ContestSourcesVisible {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        let Some(contest_id) = ctx.contest_id else {
+            tracing::warn!("ContestSourcesVisible evaluated without contest_id in context");
+            return false;
+        };
+
+        let result: Result<Option<bool>, _> = sqlx::query_scalar(
+            r#"SELECT NOW() >= c.end_time AND (
+                c.source_visibility = 'public'
+                OR (c.source_visibility = 'participants' AND EXISTS(
+                    SELECT 1 FROM contest_participants cp
+                    WHERE cp.contest_id = c.id AND cp.user_id = $2
+                ))
+            )
+            FROM contests c WHERE c.id = $1"#,
+        )
+        .bind(contest_id)
+        .bind(ctx.user_id)
+        .fetch_optional(ctx.db.as_ref())
+        .await;
+
+        result.ok().flatten().unwrap_or(false)
+    }
+}
+
+/// Check if the context's contest has ended and lets participants download
+/// the test data of their failing submissions (`test_data_downloads`).
+/// Requires `ctx.contest_id` to be set.
+#[cfg(feature = "auth")]
+pub struct ContestTestDataOpen;
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for ContestTestDataOpen {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        let Some(contest_id) = ctx.contest_id else {
+            tracing::warn!("ContestTestDataOpen evaluated without contest_id in context");
+            return false;
+        };
+
+        let result: Result<Option<bool>, _> = sqlx::query_scalar(
+            "SELECT NOW() >= end_time AND test_data_downloads FROM contests WHERE id = $1",
+        )
+        .bind(contest_id)
+        .fetch_optional(ctx.db.as_ref())
+        .await;
+
+        result.ok().flatten().unwrap_or(false)
+    }
+}
+
 /// Check if the user is a participant in the context's contest.
 /// Requires `ctx.contest_id` to be set.
 #[cfg(feature = "auth")]
@@ -142,35 +374,51 @@ impl Specification<AuthContext> for IsContestOwner {
     }
 }
 
-/// Check if the user can add problems to the context's contest.
-/// (Collaborator with can_add_problems permission or contest owner)
+/// Check if the user is a collaborator of the context's contest holding
+/// the given permission.
 /// Requires `ctx.contest_id` to be set.
 #[cfg(feature = "auth")]
-pub struct CanAddProblems;
+pub struct IsCollaboratorWith(pub CollaboratorPermission);
 
 #[cfg(feature = "auth")]
 #[async_trait]
-impl Specification<AuthContext> for CanAddProblems {
+impl Specification<AuthContext> for IsCollaboratorWith {
     async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
         let Some(contest_id) = ctx.contest_id else {
-            tracing::warn!("CanAddProblems evaluated without contest_id in context");
+            tracing::warn!("IsCollaboratorWith evaluated without contest_id in context");
             return false;
         };
 
-        // Check if collaborator with can_add_problems permission
-        let result: Result<Option<bool>, _> = sqlx::query_scalar(
-            r#"SELECT can_add_problems FROM contest_collaborators 
-               WHERE contest_id = $1 AND user_id = $2"#,
-        )
-        .bind(contest_id)
-        .bind(ctx.user_id)
-        .fetch_optional(ctx.db.as_ref())
-        .await;
+        let sql = format!(
+            "SELECT {} FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2",
+            self.0.column()
+        );
+        let result: Result<Option<bool>, _> = sqlx::query_scalar(&sql)
+            .bind(contest_id)
+            .bind(ctx.user_id)
+            .fetch_optional(ctx.db.as_ref())
+            .await;
 
         result.ok().flatten().unwrap_or(false)
     }
 }
 
+/// Check if the user can add problems to the context's contest.
+/// (Collaborator with can_add_problems permission)
+/// Requires `ctx.contest_id` to be set.
+#[cfg(feature = "auth")]
+pub struct CanAddProblems;
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for CanAddProblems {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        IsCollaboratorWith(CollaboratorPermission::AddProblems)
+            .is_satisfied_by(ctx)
+            .await
+    }
+}
+
 // =============================================================================
 // Problem-scoped rules
 // =============================================================================
@@ -204,7 +452,7 @@ impl Specification<AuthContext> for IsProblemOwner {
 }
 
 /// Check if the user can access problem binaries (generator/checker).
-/// Access granted if: admin, problem owner, or collaborator of any contest containing the problem.
+/// Access granted if: contest moderator, problem owner, or collaborator of any contest containing the problem.
 /// Requires `ctx.problem_id` to be set.
 #[cfg(feature = "auth")]
 pub struct CanAccessProblemBinaries;
@@ -218,8 +466,8 @@ impl Specification<AuthContext> for CanAccessProblemBinaries {
             return false;
         };
 
-        // Admin can access everything
-        if ctx.role == "admin" {
+        // Moderators can access everything
+        if ctx.permissions.contains(Permission::ModerateContests) {
             return true;
         }
 
@@ -258,6 +506,41 @@ impl Specification<AuthContext> for CanAccessProblemBinaries {
     }
 }
 
+/// Check if the user is a collaborator holding the given permission on any
+/// contest containing the context's problem, or owns such a contest.
+/// Requires `ctx.problem_id` to be set.
+#[cfg(feature = "auth")]
+pub struct IsProblemCollaboratorWith(pub CollaboratorPermission);
+
+#[cfg(feature = "auth")]
+#[async_trait]
+impl Specification<AuthContext> for IsProblemCollaboratorWith {
+    async fn is_satisfied_by(&self, ctx: &AuthContext) -> bool {
+        let Some(problem_id) = ctx.problem_id else {
+            tracing::warn!("IsProblemCollaboratorWith evaluated without problem_id in context");
+            return false;
+        };
+
+        let sql = format!(
+            r#"SELECT EXISTS(
+                SELECT 1 FROM contest_problems cp
+                JOIN contests c ON c.id = cp.contest_id
+                LEFT JOIN contest_collaborators cc ON cc.contest_id = c.id AND cc.user_id = $2
+                WHERE cp.problem_id = $1
+                AND (c.owner_id = $2 OR cc.{} = true)
+            )"#,
+            self.0.column()
+        );
+        let result: Result<Option<bool>, _> = sqlx::query_scalar(&sql)
+            .bind(problem_id)
+            .bind(ctx.user_id)
+            .fetch_one(ctx.db.as_ref())
+            .await;
+
+        result.ok().flatten().unwrap_or(false)
+    }
+}
+
 // =============================================================================
 // Submission-scoped rules
 // =============================================================================
@@ -358,12 +641,13 @@ impl Specification<AuthContext> for NotRateLimited {
 // Composite rule examples (for documentation)
 // =============================================================================
 
-/// Example: Can user submit to a contest?
-/// `IsValidUser & ((NotRateLimited & IsParticipant) | IsAdmin | IsCollaborator)`
 #[cfg(feature = "auth")]
 pub mod composites {
     //! Pre-built composite authorization rules.
     //!
+    //! Example: Can user submit to a contest?
+    //! `IsValidUser & ((NotRateLimited & IsParticipant) | IsAdmin | IsCollaborator)`
+    //!
     //! These demonstrate how to combine specs using operators.
     //!
     //! # Example
@@ -0,0 +1,245 @@
+//! Contest collaborator roles and permission matrix.
+//!
+//! Every collaborator holds a set of permission flags (stored as
+//! `can_*` columns on `contest_collaborators`). Roles are shortcuts that
+//! fill in a default set of flags; individual flags can still be overridden.
+//! The contest owner and admins implicitly hold every permission.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A single permission a contest collaborator may hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollaboratorPermission {
+    /// Change contest settings (times, visibility, scoring)
+    EditContest,
+    /// Add, remove, and reorder contest problems
+    AddProblems,
+    /// Edit statements, limits, scoring, generators and checkers of contest problems
+    EditProblems,
+    /// View source code and results of every submission in the contest
+    ViewAllSources,
+    /// Rejudge contest submissions
+    Rejudge,
+    /// Remove or disqualify participants
+    ManageParticipants,
+    /// Answer participant clarification requests
+    AnswerClarifications,
+    /// Publish the contest editorial
+    PublishEditorial,
+    /// Submit test solutions before the contest starts
+    TestSolutions,
+}
+
+impl CollaboratorPermission {
+    /// Every permission, in column order.
+    pub const ALL: [CollaboratorPermission; 9] = [
+        Self::EditContest,
+        Self::AddProblems,
+        Self::EditProblems,
+        Self::ViewAllSources,
+        Self::Rejudge,
+        Self::ManageParticipants,
+        Self::AnswerClarifications,
+        Self::PublishEditorial,
+        Self::TestSolutions,
+    ];
+
+    /// Column on `contest_collaborators` storing this permission.
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::EditContest => "can_edit_contest",
+            Self::AddProblems => "can_add_problems",
+            Self::EditProblems => "can_edit_problems",
+            Self::ViewAllSources => "can_view_submissions",
+            Self::Rejudge => "can_rejudge",
+            Self::ManageParticipants => "can_manage_participants",
+            Self::AnswerClarifications => "can_answer_clarifications",
+            Self::PublishEditorial => "can_publish_editorial",
+            Self::TestSolutions => "can_test_solutions",
+        }
+    }
+}
+
+/// Role shortcut that expands to a default permission set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollaboratorRole {
+    /// Full control of the contest except managing collaborators
+    CoOwner,
+    /// Prepares problems and supports the contest while it runs
+    CoSetter,
+    /// Tests problems before the start and answers clarifications
+    Tester,
+    /// Read-only access to submissions
+    Observer,
+}
+
+impl CollaboratorRole {
+    /// Every role, from most to least privileged.
+    pub const ALL: [CollaboratorRole; 4] =
+        [Self::CoOwner, Self::CoSetter, Self::Tester, Self::Observer];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CoOwner => "co-owner",
+            Self::CoSetter => "co-setter",
+            Self::Tester => "tester",
+            Self::Observer => "observer",
+        }
+    }
+
+    /// Default permissions granted by this role.
+    pub fn default_permissions(self) -> CollaboratorPermissions {
+        use CollaboratorPermission::*;
+
+        let granted: &[CollaboratorPermission] = match self {
+            Self::CoOwner => &CollaboratorPermission::ALL,
+            Self::CoSetter => &[
+                AddProblems,
+                EditProblems,
+                ViewAllSources,
+                Rejudge,
+                AnswerClarifications,
+                PublishEditorial,
+                TestSolutions,
+            ],
+            Self::Tester => &[ViewAllSources, AnswerClarifications, TestSolutions],
+            Self::Observer => &[ViewAllSources],
+        };
+
+        let mut permissions = CollaboratorPermissions::default();
+        for permission in granted {
+            permissions.set(*permission, true);
+        }
+        permissions
+    }
+}
+
+impl fmt::Display for CollaboratorRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CollaboratorRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("Unknown collaborator role '{}'", s))
+    }
+}
+
+/// Permission flags of one collaborator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CollaboratorPermissions {
+    pub can_edit_contest: bool,
+    pub can_add_problems: bool,
+    pub can_edit_problems: bool,
+    pub can_view_submissions: bool,
+    pub can_rejudge: bool,
+    pub can_manage_participants: bool,
+    pub can_answer_clarifications: bool,
+    pub can_publish_editorial: bool,
+    pub can_test_solutions: bool,
+}
+
+impl CollaboratorPermissions {
+    /// Whether the given permission is granted.
+    pub fn allows(&self, permission: CollaboratorPermission) -> bool {
+        *self.flag(permission)
+    }
+
+    /// Grant or revoke a permission.
+    pub fn set(&mut self, permission: CollaboratorPermission, granted: bool) {
+        *self.flag_mut(permission) = granted;
+    }
+
+    fn flag(&self, permission: CollaboratorPermission) -> &bool {
+        use CollaboratorPermission::*;
+
+        match permission {
+            EditContest => &self.can_edit_contest,
+            AddProblems => &self.can_add_problems,
+            EditProblems => &self.can_edit_problems,
+            ViewAllSources => &self.can_view_submissions,
+            Rejudge => &self.can_rejudge,
+            ManageParticipants => &self.can_manage_participants,
+            AnswerClarifications => &self.can_answer_clarifications,
+            PublishEditorial => &self.can_publish_editorial,
+            TestSolutions => &self.can_test_solutions,
+        }
+    }
+
+    fn flag_mut(&mut self, permission: CollaboratorPermission) -> &mut bool {
+        use CollaboratorPermission::*;
+
+        match permission {
+            EditContest => &mut self.can_edit_contest,
+            AddProblems => &mut self.can_add_problems,
+            EditProblems => &mut self.can_edit_problems,
+            ViewAllSources => &mut self.can_view_submissions,
+            Rejudge => &mut self.can_rejudge,
+            ManageParticipants => &mut self.can_manage_participants,
+            AnswerClarifications => &mut self.can_answer_clarifications,
+            PublishEditorial => &mut self.can_publish_editorial,
+            TestSolutions => &mut self.can_test_solutions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in CollaboratorRole::ALL {
+            assert_eq!(role.as_str().parse::<CollaboratorRole>(), Ok(role));
+        }
+        assert!("problem-setter".parse::<CollaboratorRole>().is_err());
+    }
+
+    #[test]
+    fn test_role_presets() {
+        let co_owner = CollaboratorRole::CoOwner.default_permissions();
+        assert!(CollaboratorPermission::ALL
+            .iter()
+            .all(|p| co_owner.allows(*p)));
+
+        let co_setter = CollaboratorRole::CoSetter.default_permissions();
+        assert!(co_setter.allows(CollaboratorPermission::EditProblems));
+        assert!(co_setter.allows(CollaboratorPermission::Rejudge));
+        assert!(co_setter.allows(CollaboratorPermission::TestSolutions));
+        assert!(!co_setter.allows(CollaboratorPermission::EditContest));
+        assert!(!co_setter.allows(CollaboratorPermission::ManageParticipants));
+
+        let tester = CollaboratorRole::Tester.default_permissions();
+        assert!(tester.allows(CollaboratorPermission::AnswerClarifications));
+        assert!(tester.allows(CollaboratorPermission::TestSolutions));
+        assert!(!tester.allows(CollaboratorPermission::AddProblems));
+
+        let observer = CollaboratorRole::Observer.default_permissions();
+        assert_eq!(
+            CollaboratorPermission::ALL
+                .iter()
+                .filter(|p| observer.allows(**p))
+                .collect::<Vec<_>>(),
+            vec![&CollaboratorPermission::ViewAllSources]
+        );
+    }
+
+    #[test]
+    fn test_set_and_allows() {
+        let mut permissions = CollaboratorPermissions::default();
+        for permission in CollaboratorPermission::ALL {
+            assert!(!permissions.allows(permission));
+            permissions.set(permission, true);
+            assert!(permissions.allows(permission));
+        }
+        assert!(permissions.can_publish_editorial && permissions.can_view_submissions);
+    }
+}
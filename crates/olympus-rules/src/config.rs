@@ -55,6 +55,7 @@ impl RuleConfig {
     }
 
     /// Create a NOT wrapper
+    #[allow(clippy::should_implement_trait)]
     pub fn not(rule: RuleConfig) -> Self {
         RuleConfig::Not {
             rule: Box::new(rule),
@@ -6,6 +6,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 #[cfg(feature = "auth")]
 use std::sync::Arc;
+
+#[cfg(feature = "auth")]
+use crate::roles::PermissionSet;
 #[cfg(feature = "auth")]
 use uuid::Uuid;
 
@@ -88,8 +91,10 @@ pub struct ExecutionContext {
 pub struct AuthContext {
     /// Current user ID
     pub user_id: Uuid,
-    /// Current user's role (admin, organizer, participant, spectator)
+    /// Current user's role (admin, organizer, participant, spectator or custom)
     pub role: String,
+    /// Site permissions granted by the role
+    pub permissions: PermissionSet,
     /// Is the user currently banned?
     pub is_banned: bool,
     /// Database pool for async lookups (wrapped in Arc for Clone)
@@ -110,6 +115,7 @@ impl std::fmt::Debug for AuthContext {
         f.debug_struct("AuthContext")
             .field("user_id", &self.user_id)
             .field("role", &self.role)
+            .field("permissions", &self.permissions)
             .field("is_banned", &self.is_banned)
             .field("contest_id", &self.contest_id)
             .field("problem_id", &self.problem_id)
@@ -131,6 +137,7 @@ impl AuthContext {
         Self {
             user_id,
             role,
+            permissions: PermissionSet::default(),
             is_banned,
             db,
             redis,
@@ -140,6 +147,12 @@ impl AuthContext {
         }
     }
 
+    /// Set the site permissions granted by the user's role
+    pub fn with_permissions(mut self, permissions: PermissionSet) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
     /// Set target contest for evaluation
     pub fn with_contest(mut self, contest_id: Uuid) -> Self {
         self.contest_id = Some(contest_id);
@@ -21,10 +21,14 @@
 //!
 //! - `auth` - Enable authorization rules that require database/Redis access
 
+pub mod advancement;
+pub mod collaborators;
 pub mod config;
 pub mod context;
 pub mod operators;
+pub mod pipeline;
 pub mod registry;
+pub mod roles;
 pub mod rules;
 pub mod specification;
 
@@ -33,10 +37,16 @@ pub mod auth_rules;
 
 /// Prelude module - import everything you need with `use olympus_rules::prelude::*`
 pub mod prelude {
+    pub use crate::advancement::{AdvancementRules, Standing};
+    pub use crate::collaborators::{
+        CollaboratorPermission, CollaboratorPermissions, CollaboratorRole,
+    };
     pub use crate::config::{CleanupAction, CleanupPolicy, NamedRuleConfig, RuleConfig};
     pub use crate::context::{EvalContext, ExecutionContext, FileContext};
     pub use crate::operators::Spec;
+    pub use crate::pipeline::{Pipeline, PipelineStep, StepProgram};
     pub use crate::registry::{execution_context_registry, file_context_registry, SpecRegistry};
+    pub use crate::roles::{BuiltinRole, Permission, PermissionSet};
     pub use crate::rules::*;
     pub use crate::specification::{
         AllOf, AlwaysFalse, AlwaysTrue, And, AnyOf, BoxedSpec, Not, Or, Specification,
@@ -0,0 +1,515 @@
+//! Per-problem judging pipelines.
+//!
+//! By default every test is judged by generating its input, running the
+//! submission on it and checking the output. A problem may instead define a
+//! pipeline (`problems.pipeline`): a list of steps, each running one program
+//! with arguments and optional limits, that pass files to each other. Steps
+//! form a DAG through those files; Minos runs them once per test in
+//! dependency order, takes the submission's verdict from its own steps and
+//! the final verdict from the checker step.
+//!
+//! ```json
+//! {"steps": [
+//!   {"name": "generate", "program": "generator", "args": ["{test}", "{seed}"], "stdout": "input"},
+//!   {"name": "solve", "program": "submission", "args": ["{input}", "{output}"],
+//!    "inputs": ["input"], "outputs": ["output"]},
+//!   {"name": "reference", "program": "reference", "args": ["{input}", "{answer}"],
+//!    "inputs": ["input"], "outputs": ["answer"]},
+//!   {"name": "check", "program": "checker", "args": ["{input}", "{output}", "{answer}"],
+//!    "inputs": ["input", "output", "answer"]}
+//! ]}
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Most steps a pipeline may have
+pub const MAX_STEPS: usize = 16;
+
+/// Highest time limit a step may set, in milliseconds
+pub const MAX_STEP_TIME_LIMIT_MS: u64 = 60_000;
+
+/// Highest memory limit a step may set, in KB (4 GB)
+pub const MAX_STEP_MEMORY_LIMIT_KB: u64 = 4 * 1024 * 1024;
+
+/// Longest step or file name
+const MAX_NAME_LEN: usize = 32;
+
+/// Placeholders every step may use besides its files
+const BUILTIN_PLACEHOLDERS: [&str; 2] = ["test", "seed"];
+
+/// Problem binaries a problem without a pipeline needs
+pub const DEFAULT_BINARIES: [StepProgram; 2] = [StepProgram::Generator, StepProgram::Checker];
+
+/// Problem binaries a problem without a pipeline needs when a built-in
+/// output comparison replaces its checker: the reference writes the answers
+pub const COMPARISON_BINARIES: [StepProgram; 2] = [StepProgram::Generator, StepProgram::Reference];
+
+/// Program a step runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepProgram {
+    /// The compiled submission being judged
+    Submission,
+    /// The problem's generator
+    Generator,
+    /// The problem's reference solution
+    Reference,
+    /// The problem's checker; its exit code is the test's verdict
+    Checker,
+}
+
+impl StepProgram {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Submission => "submission",
+            Self::Generator => "generator",
+            Self::Reference => "reference",
+            Self::Checker => "checker",
+        }
+    }
+
+    /// File name of the problem binary the step runs, `None` for the
+    /// submission
+    pub fn binary_name(self) -> Option<&'static str> {
+        match self {
+            Self::Submission => None,
+            other => Some(other.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for StepProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One step of a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineStep {
+    /// Unique within the pipeline
+    pub name: String,
+    pub program: StepProgram,
+    /// Command-line arguments. `{test}` and `{seed}` expand to the test
+    /// number and its generator seed, `{file}` to the path of one of the
+    /// step's input or output files.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Files the step reads, each written by an earlier step
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// Files the step writes
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    /// File the step's standard output is saved to (not for submission
+    /// steps, whose output is discarded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdout: Option<String>,
+    /// Overrides the problem's time limit (submission steps) or the
+    /// configured generator/checker time limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_ms: Option<u64>,
+    /// Overrides the matching memory limit, like `time_limit_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_kb: Option<u64>,
+}
+
+impl PipelineStep {
+    /// Every file the step writes, including its saved standard output.
+    pub fn produced_files(&self) -> impl Iterator<Item = &str> {
+        self.outputs
+            .iter()
+            .map(String::as_str)
+            .chain(self.stdout.as_deref())
+    }
+
+    /// Arguments with every `{placeholder}` replaced by `value(placeholder)`.
+    pub fn render_args(&self, value: impl Fn(&str) -> String) -> Vec<String> {
+        self.args
+            .iter()
+            .map(|arg| {
+                let mut rendered = String::with_capacity(arg.len());
+                let mut rest = arg.as_str();
+                while let Some((name, start, end)) = next_placeholder(rest) {
+                    rendered.push_str(&rest[..start]);
+                    rendered.push_str(&value(name));
+                    rest = &rest[end..];
+                }
+                rendered.push_str(rest);
+                rendered
+            })
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        check_name("Step", &self.name)?;
+        if self.program == StepProgram::Submission && self.stdout.is_some() {
+            return Err(format!(
+                "Submission step '{}' cannot save its standard output",
+                self.name
+            ));
+        }
+
+        let mut files = HashSet::new();
+        for file in self
+            .inputs
+            .iter()
+            .map(String::as_str)
+            .chain(self.produced_files())
+        {
+            check_name("File", file)?;
+            if BUILTIN_PLACEHOLDERS.contains(&file) {
+                return Err(format!("File name '{}' is reserved", file));
+            }
+            if !files.insert(file) {
+                return Err(format!(
+                    "Step '{}' lists file '{}' more than once",
+                    self.name, file
+                ));
+            }
+        }
+
+        for arg in &self.args {
+            let mut rest = arg.as_str();
+            while let Some((name, _, end)) = next_placeholder(rest) {
+                if !files.contains(name) && !BUILTIN_PLACEHOLDERS.contains(&name) {
+                    return Err(format!(
+                        "Step '{}' uses unknown placeholder '{{{}}}'",
+                        self.name, name
+                    ));
+                }
+                rest = &rest[end..];
+            }
+        }
+
+        if self
+            .time_limit_ms
+            .is_some_and(|ms| ms == 0 || ms > MAX_STEP_TIME_LIMIT_MS)
+        {
+            return Err(format!(
+                "Step '{}' time limit must be 1-{} ms",
+                self.name, MAX_STEP_TIME_LIMIT_MS
+            ));
+        }
+        if self
+            .memory_limit_kb
+            .is_some_and(|kb| kb == 0 || kb > MAX_STEP_MEMORY_LIMIT_KB)
+        {
+            return Err(format!(
+                "Step '{}' memory limit must be 1-{} KB",
+                self.name, MAX_STEP_MEMORY_LIMIT_KB
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// A problem's judging pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    pub steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    /// Parse a stored pipeline, rejecting one that is not valid.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let pipeline: Self =
+            serde_json::from_str(json).map_err(|e| format!("Invalid pipeline: {}", e))?;
+        pipeline.validate()?;
+        Ok(pipeline)
+    }
+
+    /// Check the pipeline can be run: unique names, every input written by
+    /// exactly one step, no cycles, one checker and at least one submission
+    /// step.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.steps.is_empty() || self.steps.len() > MAX_STEPS {
+            return Err(format!("A pipeline must have 1-{} steps", MAX_STEPS));
+        }
+
+        let mut names = HashSet::new();
+        for step in &self.steps {
+            step.validate()?;
+            if !names.insert(step.name.as_str()) {
+                return Err(format!("Duplicate step name '{}'", step.name));
+            }
+        }
+
+        if !self
+            .steps
+            .iter()
+            .any(|s| s.program == StepProgram::Submission)
+        {
+            return Err("A pipeline needs at least one submission step".to_string());
+        }
+        let mut checkers = self
+            .steps
+            .iter()
+            .filter(|s| s.program == StepProgram::Checker);
+        match (checkers.next(), checkers.next()) {
+            (Some(checker), None) => {
+                if checker.produced_files().next().is_some() {
+                    return Err(format!(
+                        "Checker step '{}' cannot write files",
+                        checker.name
+                    ));
+                }
+            }
+            _ => return Err("A pipeline needs exactly one checker step".to_string()),
+        }
+
+        self.execution_order().map(|_| ())
+    }
+
+    /// Indices of the steps in the order they run: every step after the
+    /// steps writing its inputs, the checker last, otherwise in declaration
+    /// order.
+    pub fn execution_order(&self) -> Result<Vec<usize>, String> {
+        let mut producer = HashMap::new();
+        for (idx, step) in self.steps.iter().enumerate() {
+            for file in step.produced_files() {
+                if let Some(other) = producer.insert(file, idx) {
+                    return Err(format!(
+                        "File '{}' is written by both '{}' and '{}'",
+                        file, self.steps[other].name, step.name
+                    ));
+                }
+            }
+        }
+
+        let mut depends_on = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            let mut deps = HashSet::new();
+            for input in &step.inputs {
+                let idx = *producer.get(input.as_str()).ok_or_else(|| {
+                    format!(
+                        "Step '{}' reads '{}', which no step writes",
+                        step.name, input
+                    )
+                })?;
+                deps.insert(idx);
+            }
+            depends_on.push(deps);
+        }
+
+        let mut order = Vec::with_capacity(self.steps.len());
+        let mut done = vec![false; self.steps.len()];
+        while order.len() < self.steps.len() {
+            let ready = |idx: &usize| !done[*idx] && depends_on[*idx].iter().all(|&dep| done[dep]);
+            // Nothing reads the checker's files, so it can always wait
+            let next = (0..self.steps.len())
+                .filter(ready)
+                .find(|&idx| self.steps[idx].program != StepProgram::Checker)
+                .or_else(|| (0..self.steps.len()).find(ready))
+                .ok_or_else(|| {
+                    let stuck: Vec<&str> = (0..self.steps.len())
+                        .filter(|&idx| !done[idx])
+                        .map(|idx| self.steps[idx].name.as_str())
+                        .collect();
+                    format!("Steps {} depend on each other", stuck.join(", "))
+                })?;
+            done[next] = true;
+            order.push(next);
+        }
+
+        Ok(order)
+    }
+
+    /// First file written by the last submission step to run, kept when a
+    /// test fails like a default run's output.
+    pub fn submission_output(&self) -> Option<&str> {
+        self.execution_order()
+            .ok()?
+            .into_iter()
+            .rev()
+            .map(|idx| &self.steps[idx])
+            .find(|step| step.program == StepProgram::Submission)?
+            .outputs
+            .first()
+            .map(String::as_str)
+    }
+
+    /// Problem binaries the pipeline runs, each once.
+    pub fn required_binaries(&self) -> Vec<StepProgram> {
+        let mut programs = Vec::new();
+        for step in &self.steps {
+            if step.program != StepProgram::Submission && !programs.contains(&step.program) {
+                programs.push(step.program);
+            }
+        }
+        programs
+    }
+}
+
+/// Problem binaries that must be uploaded before a problem can be judged.
+/// A pipeline always runs its own checker step, so `compares_output` only
+/// matters without one.
+pub fn required_binaries(pipeline: Option<&Pipeline>, compares_output: bool) -> Vec<StepProgram> {
+    match pipeline {
+        Some(pipeline) => pipeline.required_binaries(),
+        None if compares_output => COMPARISON_BINARIES.to_vec(),
+        None => DEFAULT_BINARIES.to_vec(),
+    }
+}
+
+/// Step and file names are short lowercase identifiers, usable in paths.
+fn check_name(what: &str, name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} name '{}' must be 1-{} characters of a-z, 0-9, '_' or '-'",
+            what, name, MAX_NAME_LEN
+        ))
+    }
+}
+
+/// First `{name}` in `s`: the name and the byte range of the placeholder.
+fn next_placeholder(s: &str) -> Option<(&str, usize, usize)> {
+    let start = s.find('{')?;
+    let len = s[start..].find('}')?;
+    Some((&s[start + 1..start + len], start, start + len + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(name: &str, program: StepProgram, inputs: &[&str], outputs: &[&str]) -> PipelineStep {
+        PipelineStep {
+            name: name.to_string(),
+            program,
+            args: Vec::new(),
+            inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            outputs: outputs.iter().map(|s| s.to_string()).collect(),
+            stdout: None,
+            time_limit_ms: None,
+            memory_limit_kb: None,
+        }
+    }
+
+    fn reference_pipeline() -> Pipeline {
+        Pipeline {
+            steps: vec![
+                step(
+                    "check",
+                    StepProgram::Checker,
+                    &["input", "output", "answer"],
+                    &[],
+                ),
+                step("solve", StepProgram::Submission, &["input"], &["output"]),
+                step("reference", StepProgram::Reference, &["input"], &["answer"]),
+                PipelineStep {
+                    args: vec!["{test}".to_string(), "{seed}".to_string()],
+                    stdout: Some("input".to_string()),
+                    ..step("generate", StepProgram::Generator, &[], &[])
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_execution_order_follows_files() {
+        let pipeline = reference_pipeline();
+        pipeline.validate().unwrap();
+
+        let names: Vec<&str> = pipeline
+            .execution_order()
+            .unwrap()
+            .into_iter()
+            .map(|idx| pipeline.steps[idx].name.as_str())
+            .collect();
+        assert_eq!(names, ["generate", "solve", "reference", "check"]);
+        assert_eq!(pipeline.submission_output(), Some("output"));
+        assert_eq!(
+            pipeline.required_binaries(),
+            [
+                StepProgram::Checker,
+                StepProgram::Reference,
+                StepProgram::Generator
+            ]
+        );
+        assert_eq!(required_binaries(None, false), DEFAULT_BINARIES);
+        assert_eq!(required_binaries(None, true), COMPARISON_BINARIES);
+        assert_eq!(
+            required_binaries(Some(&pipeline), true),
+            pipeline.required_binaries()
+        );
+    }
+
+    #[test]
+    fn test_rejects_broken_graphs() {
+        let mut cyclic = reference_pipeline();
+        cyclic.steps[3].inputs.push("answer".to_string());
+        assert!(cyclic
+            .validate()
+            .unwrap_err()
+            .contains("depend on each other"));
+
+        let mut dangling = reference_pipeline();
+        dangling.steps[1].inputs.push("hint".to_string());
+        assert!(dangling.validate().unwrap_err().contains("no step writes"));
+
+        let mut twice = reference_pipeline();
+        twice.steps[2].outputs = vec!["output".to_string()];
+        assert!(twice.validate().unwrap_err().contains("written by both"));
+
+        let mut no_checker = reference_pipeline();
+        no_checker.steps.remove(0);
+        assert!(no_checker.validate().is_err());
+
+        let mut no_submission = reference_pipeline();
+        no_submission.steps.remove(1);
+        assert!(no_submission.validate().is_err());
+    }
+
+    #[test]
+    fn test_step_rules() {
+        let mut pipeline = reference_pipeline();
+        pipeline.steps[1].args = vec!["{input}".to_string(), "{missing}".to_string()];
+        assert!(pipeline.validate().unwrap_err().contains("{missing}"));
+
+        let mut pipeline = reference_pipeline();
+        pipeline.steps[1].name = "Solve!".to_string();
+        assert!(pipeline.validate().is_err());
+
+        let mut pipeline = reference_pipeline();
+        pipeline.steps[1].time_limit_ms = Some(MAX_STEP_TIME_LIMIT_MS + 1);
+        assert!(pipeline.validate().is_err());
+
+        let mut pipeline = reference_pipeline();
+        pipeline.steps[1].stdout = Some("log".to_string());
+        assert!(pipeline.validate().is_err());
+
+        assert!(Pipeline::parse(r#"{"steps": [], "extra": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_render_args() {
+        let step = PipelineStep {
+            args: vec![
+                "{input}".to_string(),
+                "--seed={seed}".to_string(),
+                "plain".to_string(),
+            ],
+            ..step("solve", StepProgram::Submission, &["input"], &[])
+        };
+        let args = step.render_args(|name| match name {
+            "input" => "/tmp/work/input".to_string(),
+            "seed" => "42".to_string(),
+            other => panic!("unexpected placeholder {}", other),
+        });
+        assert_eq!(args, ["/tmp/work/input", "--seed=42", "plain"]);
+    }
+}
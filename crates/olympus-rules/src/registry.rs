@@ -4,7 +4,7 @@
 //! JSON configurations, enabling admin dashboard rule management.
 
 use crate::config::RuleConfig;
-use crate::specification::{AllOf, AnyOf, BoxedSpec, Not, Specification};
+use crate::specification::{AllOf, AnyOf, BoxedSpec, Not};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -216,15 +216,47 @@ pub fn auth_context_registry() -> SpecRegistry<AuthContext> {
     registry.register("IsValidUser", |_| Some(Arc::new(IsValidUser)));
     registry.register("IsAdmin", |_| Some(Arc::new(IsAdmin)));
     registry.register("IsOrganizer", |_| Some(Arc::new(IsOrganizer)));
+    registry.register("HasPermission", |params| {
+        let permission = params.get("permission")?.as_str()?.parse().ok()?;
+        Some(Arc::new(HasPermission(permission)))
+    });
+
+    registry.register("IsOwner", |params| {
+        let resource = match params.get("resource")?.as_str()? {
+            "contest" => Resource::Contest,
+            "problem" => Resource::Problem,
+            "submission" => Resource::Submission,
+            _ => return None,
+        };
+        Some(Arc::new(IsOwner(resource)))
+    });
 
     // Contest-scoped rules
+    registry.register("ContestIsRunning", |_| Some(Arc::new(ContestIsRunning)));
+    registry.register("ContestIsPaused", |_| Some(Arc::new(ContestIsPaused)));
+    registry.register("ContestHasEnded", |_| Some(Arc::new(ContestHasEnded)));
+    registry.register("ContestIsPublic", |_| Some(Arc::new(ContestIsPublic)));
+    registry.register("ContestSourcesVisible", |_| {
+        Some(Arc::new(ContestSourcesVisible))
+    });
+    registry.register("ContestTestDataOpen", |_| {
+        Some(Arc::new(ContestTestDataOpen))
+    });
     registry.register("IsParticipant", |_| Some(Arc::new(IsParticipant)));
     registry.register("IsCollaborator", |_| Some(Arc::new(IsCollaborator)));
     registry.register("IsContestOwner", |_| Some(Arc::new(IsContestOwner)));
     registry.register("CanAddProblems", |_| Some(Arc::new(CanAddProblems)));
+    registry.register("IsCollaboratorWith", |params| {
+        let permission = collaborator_permission(params)?;
+        Some(Arc::new(IsCollaboratorWith(permission)))
+    });
 
     // Problem-scoped rules
     registry.register("IsProblemOwner", |_| Some(Arc::new(IsProblemOwner)));
+    registry.register("IsProblemCollaboratorWith", |params| {
+        let permission = collaborator_permission(params)?;
+        Some(Arc::new(IsProblemCollaboratorWith(permission)))
+    });
     registry.register("CanAccessProblemBinaries", |_| {
         Some(Arc::new(CanAccessProblemBinaries))
     });
@@ -252,6 +284,17 @@ pub fn auth_context_registry() -> SpecRegistry<AuthContext> {
     registry
 }
 
+/// Parse the `permission` parameter (its column name, e.g. `can_rejudge`).
+#[cfg(feature = "auth")]
+fn collaborator_permission(
+    params: &HashMap<String, Value>,
+) -> Option<crate::collaborators::CollaboratorPermission> {
+    let column = params.get("permission")?.as_str()?;
+    crate::collaborators::CollaboratorPermission::ALL
+        .into_iter()
+        .find(|p| p.column() == column)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
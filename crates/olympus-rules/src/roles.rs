@@ -0,0 +1,233 @@
+//! Site roles and the permissions they grant.
+//!
+//! A user's role (`users.role`) names a row of `roles`, and the role's
+//! permissions are rows of `role_permissions`. The built-in roles below
+//! cannot be deleted and start with their default permissions; admins may
+//! add custom roles, e.g. a moderator who answers clarifications in every
+//! contest but cannot create contests.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A single site-wide permission a role may grant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// Admin area: users, roles, queues, runtimes and system statistics
+    ManagePlatform,
+    /// Create contests
+    CreateContests,
+    /// Create problems
+    CreateProblems,
+    /// Create problem sheets
+    CreateSheets,
+    /// Create organizations
+    CreateOrganizations,
+    /// See private contests, problems, sheets and other users' submissions
+    ViewPrivateContent,
+    /// Act as staff, with every collaborator permission, on every contest
+    /// and problem
+    ModerateContests,
+    /// Answer clarification requests in every contest
+    AnswerClarifications,
+}
+
+impl Permission {
+    /// Every permission.
+    pub const ALL: [Permission; 8] = [
+        Self::ManagePlatform,
+        Self::CreateContests,
+        Self::CreateProblems,
+        Self::CreateSheets,
+        Self::CreateOrganizations,
+        Self::ViewPrivateContent,
+        Self::ModerateContests,
+        Self::AnswerClarifications,
+    ];
+
+    /// Name stored in `role_permissions.permission`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ManagePlatform => "manage_platform",
+            Self::CreateContests => "create_contests",
+            Self::CreateProblems => "create_problems",
+            Self::CreateSheets => "create_sheets",
+            Self::CreateOrganizations => "create_organizations",
+            Self::ViewPrivateContent => "view_private_content",
+            Self::ModerateContests => "moderate_contests",
+            Self::AnswerClarifications => "answer_clarifications",
+        }
+    }
+
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == s)
+            .ok_or_else(|| format!("Unknown permission '{}'", s))
+    }
+}
+
+/// Permissions granted by one role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PermissionSet(u16);
+
+impl PermissionSet {
+    /// Every permission.
+    pub fn all() -> Self {
+        Permission::ALL.into_iter().collect()
+    }
+
+    /// Whether the given permission is granted.
+    pub fn contains(&self, permission: Permission) -> bool {
+        self.0 & permission.bit() != 0
+    }
+
+    /// Grant a permission.
+    pub fn insert(&mut self, permission: Permission) {
+        self.0 |= permission.bit();
+    }
+
+    /// Granted permissions, in [`Permission::ALL`] order.
+    pub fn iter(&self) -> impl Iterator<Item = Permission> + '_ {
+        Permission::ALL.into_iter().filter(|p| self.contains(*p))
+    }
+}
+
+impl FromIterator<Permission> for PermissionSet {
+    fn from_iter<I: IntoIterator<Item = Permission>>(iter: I) -> Self {
+        let mut set = Self::default();
+        for permission in iter {
+            set.insert(permission);
+        }
+        set
+    }
+}
+
+/// Roles every installation has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinRole {
+    /// Runs the platform; holds every permission
+    Admin,
+    /// Hosts contests and writes problems
+    Organizer,
+    /// Default role of registered users
+    Participant,
+    /// Watches contests without competing
+    Spectator,
+}
+
+impl BuiltinRole {
+    /// Every built-in role, from most to least privileged.
+    pub const ALL: [BuiltinRole; 4] = [
+        Self::Admin,
+        Self::Organizer,
+        Self::Participant,
+        Self::Spectator,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Organizer => "organizer",
+            Self::Participant => "participant",
+            Self::Spectator => "spectator",
+        }
+    }
+
+    /// Permissions the role is created with.
+    pub fn default_permissions(self) -> PermissionSet {
+        use Permission::*;
+
+        match self {
+            Self::Admin => PermissionSet::all(),
+            Self::Organizer => [
+                CreateContests,
+                CreateProblems,
+                CreateSheets,
+                CreateOrganizations,
+            ]
+            .into_iter()
+            .collect(),
+            Self::Participant | Self::Spectator => PermissionSet::default(),
+        }
+    }
+}
+
+impl fmt::Display for BuiltinRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BuiltinRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("'{}' is not a built-in role", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(permission.as_str().parse::<Permission>(), Ok(permission));
+        }
+        assert!("create_everything".parse::<Permission>().is_err());
+    }
+
+    #[test]
+    fn test_builtin_presets() {
+        let admin = BuiltinRole::Admin.default_permissions();
+        assert!(Permission::ALL.iter().all(|p| admin.contains(*p)));
+
+        let organizer = BuiltinRole::Organizer.default_permissions();
+        assert!(organizer.contains(Permission::CreateContests));
+        assert!(organizer.contains(Permission::CreateProblems));
+        assert!(!organizer.contains(Permission::ManagePlatform));
+        assert!(!organizer.contains(Permission::ViewPrivateContent));
+
+        assert_eq!(
+            BuiltinRole::Participant.default_permissions(),
+            PermissionSet::default()
+        );
+        assert_eq!("spectator".parse(), Ok(BuiltinRole::Spectator));
+        assert!("moderator".parse::<BuiltinRole>().is_err());
+    }
+
+    #[test]
+    fn test_permission_set() {
+        let mut set = PermissionSet::default();
+        for permission in Permission::ALL {
+            assert!(!set.contains(permission));
+            set.insert(permission);
+            assert!(set.contains(permission));
+        }
+        assert_eq!(set, PermissionSet::all());
+
+        let moderator: PermissionSet = [Permission::AnswerClarifications].into_iter().collect();
+        assert_eq!(
+            moderator.iter().collect::<Vec<_>>(),
+            vec![Permission::AnswerClarifications]
+        );
+    }
+}
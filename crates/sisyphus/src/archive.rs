@@ -0,0 +1,262 @@
+//! Validation and extraction of ZIP submissions.
+//!
+//! Vanguard only checks an upload's size and magic bytes before queueing it;
+//! everything that needs to walk the archive happens here, as it is
+//! extracted into the build directory. An archive that breaks a rule fails
+//! with a [`ValidationError`], which the consumer records as the
+//! `validation_failed` status instead of a compilation error.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result};
+use zip::ZipArchive;
+
+/// How many times larger than the archive its contents may be
+const MAX_EXPANSION_RATIO: u64 = 5;
+
+/// Files every ZIP submission must have at its root
+const REQUIRED_FILES: [&str; 2] = ["compile.sh", "run.sh"];
+
+/// Why a ZIP submission was rejected.
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationError {
+    #[error("Invalid ZIP file: {0}")]
+    InvalidArchive(String),
+    #[error("ZIP contains path traversal (..): rejected for security")]
+    PathTraversal,
+    #[error("ZIP contains absolute path: rejected for security")]
+    AbsolutePath,
+    #[error("ZIP contains symlinks: rejected for security")]
+    Symlink,
+    #[error("ZIP uncompressed size exceeds limit (potential zip bomb)")]
+    TooLarge,
+    #[error("ZIP must contain {0}")]
+    MissingFile(&'static str),
+}
+
+impl From<zip::result::ZipError> for ValidationError {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::InvalidArchive(e.to_string())
+    }
+}
+
+/// Validate the archive at `zip_path` and extract it into `dest_dir`.
+/// Blocking; run it off the async runtime.
+///
+/// Entry names, symlinks, declared sizes and required files are checked
+/// from the central directory before anything is written. The size limit
+/// is enforced again on the bytes actually inflated, since declared sizes
+/// can lie. Failing to write the build directory is returned as a plain
+/// I/O error so the job can be retried.
+pub fn extract(zip_path: &Path, dest_dir: &Path) -> Result<()> {
+    let file = File::open(zip_path)
+        .with_context(|| format!("Failed to open ZIP: {}", zip_path.display()))?;
+    let max_size = file.metadata()?.len() * MAX_EXPANSION_RATIO;
+    let mut archive = ZipArchive::new(BufReader::new(file)).map_err(ValidationError::from)?;
+
+    validate_entries(&mut archive, max_size)?;
+
+    let mut remaining = max_size;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(ValidationError::from)?;
+        let outpath = dest_dir.join(entry.name());
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&outpath)?;
+        } else {
+            if let Some(parent) = outpath.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut outfile = File::create(&outpath)?;
+            remaining -= copy_limited(&mut entry, &mut outfile, remaining)?;
+        }
+
+        // Keep the permission bits, never setuid/setgid
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Some(mode) = entry.unix_mode() {
+                std::fs::set_permissions(&outpath, std::fs::Permissions::from_mode(mode & 0o777))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check every entry's metadata without inflating anything.
+fn validate_entries<R: Read + io::Seek>(
+    archive: &mut ZipArchive<R>,
+    max_size: u64,
+) -> Result<(), ValidationError> {
+    let mut found = [false; REQUIRED_FILES.len()];
+    let mut total_size: u64 = 0;
+
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        let name = entry.name();
+
+        for component in Path::new(name).components() {
+            match component {
+                Component::ParentDir => return Err(ValidationError::PathTraversal),
+                Component::RootDir | Component::Prefix(_) => {
+                    return Err(ValidationError::AbsolutePath)
+                }
+                Component::CurDir | Component::Normal(_) => {}
+            }
+        }
+
+        if entry.is_symlink() {
+            return Err(ValidationError::Symlink);
+        }
+
+        total_size = total_size.saturating_add(entry.size());
+        if total_size > max_size {
+            return Err(ValidationError::TooLarge);
+        }
+
+        let normalized = name.trim_start_matches("./");
+        for (required, found) in REQUIRED_FILES.iter().zip(found.iter_mut()) {
+            *found |= normalized == *required;
+        }
+    }
+
+    match REQUIRED_FILES.iter().zip(found).find(|(_, found)| !found) {
+        Some((missing, _)) => Err(ValidationError::MissingFile(missing)),
+        None => Ok(()),
+    }
+}
+
+/// Copy at most `limit` bytes of an entry, returning how many were copied.
+/// Read errors mean a corrupt entry; write errors are the build directory's.
+fn copy_limited(entry: &mut impl Read, out: &mut impl Write, limit: u64) -> Result<u64> {
+    let mut buf = [0u8; 64 * 1024];
+    let mut copied: u64 = 0;
+
+    loop {
+        let n = match entry.read(&mut buf) {
+            Ok(0) => return Ok(copied),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(ValidationError::InvalidArchive(e.to_string()).into()),
+        };
+        copied += n as u64;
+        if copied > limit {
+            return Err(ValidationError::TooLarge.into());
+        }
+        out.write_all(&buf[..n])?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::write::SimpleFileOptions;
+
+    fn make_zip(dir: &Path, build: impl FnOnce(&mut zip::ZipWriter<File>)) -> std::path::PathBuf {
+        let path = dir.join("submission.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        build(&mut writer);
+        writer.finish().unwrap();
+        path
+    }
+
+    fn add_file(writer: &mut zip::ZipWriter<File>, name: &str, contents: &[u8]) {
+        writer
+            .start_file(name, SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(contents).unwrap();
+    }
+
+    fn validation_error(result: Result<()>) -> ValidationError {
+        result
+            .unwrap_err()
+            .downcast::<ValidationError>()
+            .expect("expected a validation error")
+    }
+
+    #[test]
+    fn test_extracts_valid_submission() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = make_zip(dir.path(), |w| {
+            add_file(w, "compile.sh", b"gcc -O2 src/main.c -o main");
+            add_file(w, "./run.sh", b"./main");
+            add_file(w, "src/main.c", b"int main() { return 0; }");
+        });
+
+        let build = dir.path().join("build");
+        extract(&zip, &build).unwrap();
+        assert!(build.join("compile.sh").is_file());
+        assert!(build.join("run.sh").is_file());
+        assert_eq!(
+            std::fs::read(build.join("src/main.c")).unwrap(),
+            b"int main() { return 0; }"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unsafe_entries() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let zip = make_zip(dir.path(), |w| {
+            add_file(w, "compile.sh", b"true");
+            add_file(w, "run.sh", b"true");
+            add_file(w, "../escape.sh", b"true");
+        });
+        let build = dir.path().join("build");
+        assert!(matches!(
+            validation_error(extract(&zip, &build)),
+            ValidationError::PathTraversal
+        ));
+        assert!(!build.exists());
+
+        let zip = make_zip(dir.path(), |w| {
+            add_file(w, "compile.sh", b"true");
+            add_file(w, "run.sh", b"true");
+            w.add_symlink("data", "/etc/passwd", SimpleFileOptions::default())
+                .unwrap();
+        });
+        assert!(matches!(
+            validation_error(extract(&zip, &build)),
+            ValidationError::Symlink
+        ));
+    }
+
+    #[test]
+    fn test_rejects_missing_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = make_zip(dir.path(), |w| add_file(w, "compile.sh", b"true"));
+
+        let err = validation_error(extract(&zip, &dir.path().join("build")));
+        assert_eq!(err.to_string(), "ZIP must contain run.sh");
+    }
+
+    #[test]
+    fn test_rejects_zip_bomb() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = make_zip(dir.path(), |w| {
+            add_file(w, "compile.sh", b"true");
+            add_file(w, "run.sh", b"true");
+            add_file(w, "zeros.bin", &vec![0u8; 1024 * 1024]);
+        });
+
+        assert!(matches!(
+            validation_error(extract(&zip, &dir.path().join("build"))),
+            ValidationError::TooLarge
+        ));
+    }
+
+    #[test]
+    fn test_copy_limited_counts_inflated_bytes() {
+        let mut out = Vec::new();
+        assert_eq!(copy_limited(&mut &[1u8; 10][..], &mut out, 10).unwrap(), 10);
+
+        let err = copy_limited(&mut &[1u8; 11][..], &mut Vec::new(), 10).unwrap_err();
+        assert!(matches!(
+            err.downcast::<ValidationError>().unwrap(),
+            ValidationError::TooLarge
+        ));
+    }
+}
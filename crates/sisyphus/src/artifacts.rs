@@ -0,0 +1,173 @@
+//! Compiled artifact bookkeeping.
+//!
+//! Every successful compilation leaves one artifact under the binaries
+//! directory: a single executable, or a bundle directory containing `run.sh`
+//! (interpreted languages and ZIP submissions). Its path, size and checksum
+//! are recorded in `submission_artifacts`, which is where Minos and Horus
+//! look artifacts up instead of deriving paths from the submission id.
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Shape of a compiled artifact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// A single executable file
+    Binary,
+    /// A directory with `run.sh` and its supporting files
+    Bundle,
+}
+
+impl ArtifactKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::Bundle => "bundle",
+        }
+    }
+}
+
+/// A compiled artifact on disk.
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub path: PathBuf,
+    pub kind: ArtifactKind,
+    /// Total size of all files in bytes
+    pub size_bytes: u64,
+    /// Hex SHA-256 of the file, or of every bundle file's relative path and
+    /// contents in path order
+    pub checksum: String,
+}
+
+impl Artifact {
+    /// Measure the artifact at `path`. Blocking; run it off the async runtime.
+    pub fn inspect(path: &Path) -> io::Result<Self> {
+        let mut hasher = Sha256::new();
+
+        let (kind, size_bytes) = if path.is_dir() {
+            let mut files = Vec::new();
+            collect_files(path, path, &mut files)?;
+            files.sort();
+
+            let mut size = 0;
+            for relative in &files {
+                hasher.update(relative.to_string_lossy().as_bytes());
+                hasher.update([0]);
+                size += hash_file(&path.join(relative), &mut hasher)?;
+            }
+            (ArtifactKind::Bundle, size)
+        } else {
+            (ArtifactKind::Binary, hash_file(path, &mut hasher)?)
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            kind,
+            size_bytes,
+            checksum: format!("{:x}", hasher.finalize()),
+        })
+    }
+}
+
+/// Record (or replace, after a recompilation) a submission's artifact.
+pub async fn record(db: &PgPool, submission_id: &Uuid, artifact: &Artifact) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO submission_artifacts (submission_id, kind, path, size_bytes, checksum)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (submission_id) DO UPDATE
+        SET kind = EXCLUDED.kind,
+            path = EXCLUDED.path,
+            size_bytes = EXCLUDED.size_bytes,
+            checksum = EXCLUDED.checksum,
+            created_at = NOW()
+        "#,
+    )
+    .bind(submission_id)
+    .bind(artifact.kind.as_str())
+    .bind(artifact.path.to_string_lossy().as_ref())
+    .bind(artifact.size_bytes as i64)
+    .bind(&artifact.checksum)
+    .execute(db)
+    .await
+    .context("Failed to record submission artifact")?;
+    Ok(())
+}
+
+/// Collect the paths of all files below `dir`, relative to `root`.
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            out.push(relative.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Feed a file into the hasher, returning its size.
+fn hash_file(path: &Path, hasher: &mut Sha256) -> io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = [0u8; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_artifact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main");
+        std::fs::write(&path, b"hello").unwrap();
+
+        let artifact = Artifact::inspect(&path).unwrap();
+        assert_eq!(artifact.kind, ArtifactKind::Binary);
+        assert_eq!(artifact.size_bytes, 5);
+        assert_eq!(
+            artifact.checksum,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+
+    #[test]
+    fn test_bundle_checksum_is_stable() {
+        let make_bundle = |files: &[(&str, &str)]| {
+            let dir = tempfile::tempdir().unwrap();
+            for (name, contents) in files {
+                let path = dir.path().join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, contents).unwrap();
+            }
+            let artifact = Artifact::inspect(dir.path()).unwrap();
+            (dir, artifact)
+        };
+
+        let (_a, a) = make_bundle(&[("run.sh", "python3 main.py"), ("src/main.py", "print(1)")]);
+        let (_b, b) = make_bundle(&[("src/main.py", "print(1)"), ("run.sh", "python3 main.py")]);
+        let (_c, c) = make_bundle(&[("run.sh", "python3 main.py"), ("src/main.py", "print(2)")]);
+
+        assert_eq!(a.kind, ArtifactKind::Bundle);
+        assert_eq!(a.size_bytes, 23);
+        assert_eq!(a.checksum, b.checksum);
+        assert_ne!(a.checksum, c.checksum);
+    }
+}
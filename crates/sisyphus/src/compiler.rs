@@ -1,18 +1,22 @@
 //! Compilation logic for Sisyphus.
 //!
 //! Both ZIP and source-code submissions are compiled inside ephemeral
-//! Docker containers.  The language (when known) selects the image so
-//! the right toolchain is available.  For ZIP submissions the user's
-//! `compile.sh` is executed; for legacy source submissions we generate
-//! the appropriate compiler invocation automatically.
+//! Docker containers.  The language (when known) is resolved through the
+//! runtime registry, which selects the image so the right toolchain is
+//! available.  For ZIP submissions the user's `compile.sh` is executed;
+//! for legacy source submissions and sample runs the runtime's compile
+//! template is used.
 
 use anyhow::{anyhow, Context, Result};
 use std::path::{Path, PathBuf};
 use tokio::fs;
+use uuid::Uuid;
 
+use crate::archive;
 use crate::config::Config;
 use crate::consumer::CompileJob;
 use crate::container::{ensure_image, resolve_image, run_in_container};
+use crate::runtimes::RuntimeDefinition;
 
 /// Compiler handles the compilation of submissions.
 pub struct Compiler {
@@ -25,17 +29,30 @@ impl Compiler {
         Self { config }
     }
 
-    /// Compile a submission and return the path to the compiled binary.
-    pub async fn compile(&self, job: &CompileJob) -> Result<String> {
+    /// Compile a submission and return the path to the compiled binary,
+    /// saved in `binaries_dir`.
+    ///
+    /// `runtime` is the registry entry for the job's language hint, if any.
+    pub async fn compile(
+        &self,
+        job: &CompileJob,
+        runtime: Option<&RuntimeDefinition>,
+        binaries_dir: &Path,
+    ) -> Result<String> {
         match job.job_type.as_str() {
-            "zip" => self.compile_zip(job).await,
-            "source" => self.compile_source(job).await,
+            "zip" => self.compile_zip(job, runtime, binaries_dir).await,
+            "source" => self.compile_source(job, runtime, binaries_dir).await,
             other => Err(anyhow!("Unknown job type: {}", other)),
         }
     }
 
     /// Compile a ZIP submission inside a language-specific Docker container.
-    async fn compile_zip(&self, job: &CompileJob) -> Result<String> {
+    async fn compile_zip(
+        &self,
+        job: &CompileJob,
+        runtime: Option<&RuntimeDefinition>,
+        binaries_dir: &Path,
+    ) -> Result<String> {
         let file_path = job
             .file_path
             .as_ref()
@@ -56,7 +73,7 @@ impl Compiler {
             "Created build directory"
         );
 
-        // Extract ZIP to build directory
+        // Validate and extract ZIP to build directory
         self.extract_zip(file_path, build_dir).await?;
 
         // Log extracted directory contents for debugging
@@ -90,7 +107,7 @@ impl Compiler {
         }
 
         // Resolve the container image from the language hint
-        let spec = resolve_image(&self.config, job.language.as_deref());
+        let spec = resolve_image(&self.config, runtime);
 
         // Ensure the image exists locally (pull if needed)
         ensure_image(&self.config, &spec.image).await?;
@@ -123,6 +140,7 @@ impl Compiler {
         // Run compile.sh inside the container
         let output = run_in_container(
             &self.config,
+            job.submission_id,
             &spec,
             build_dir,
             &["sh", "-c", "./compile.sh"],
@@ -134,21 +152,39 @@ impl Compiler {
         }
 
         // Find and copy the compiled binary
-        let binary_path = self.save_binary(job, build_dir).await?;
+        let binary_path = self
+            .save_binary(job.submission_id, build_dir, binaries_dir)
+            .await?;
 
         Ok(binary_path)
     }
 
     /// Compile a source code submission inside a language-specific Docker container.
-    async fn compile_source(&self, job: &CompileJob) -> Result<String> {
-        let language = job
-            .language
-            .as_ref()
-            .ok_or_else(|| anyhow!("Source submission missing language"))?;
+    async fn compile_source(
+        &self,
+        job: &CompileJob,
+        runtime: Option<&RuntimeDefinition>,
+        binaries_dir: &Path,
+    ) -> Result<String> {
+        let runtime = runtime.ok_or_else(|| anyhow!("Source submission missing language"))?;
 
         // Fetch source code from database
         let source_code = self.fetch_source_code(&job.submission_id).await?;
 
+        self.compile_code(job.submission_id, &source_code, runtime, binaries_dir)
+            .await
+    }
+
+    /// Compile source code with a runtime's compile template inside its
+    /// Docker container and save the result to `binaries_dir` as
+    /// `{id}_bin`.
+    pub async fn compile_code(
+        &self,
+        id: Uuid,
+        source_code: &str,
+        runtime: &RuntimeDefinition,
+        binaries_dir: &Path,
+    ) -> Result<String> {
         // Create temp directory for build under the shared volume
         fs::create_dir_all(&self.config.build_dir_base)
             .await
@@ -157,114 +193,50 @@ impl Compiler {
             .context("Failed to create temp build directory")?;
         let build_dir = temp_dir.path();
 
-        // Write source file and determine compile command
-        let (source_file, compile_cmd) = self.get_compile_command(language, build_dir)?;
-        fs::write(build_dir.join(&source_file), &source_code).await?;
-
-        // Resolve the container image
-        let spec = resolve_image(&self.config, Some(language));
-        ensure_image(&self.config, &spec.image).await?;
-
-        // Build a single shell string so we can run it as `sh -c "..."`
-        let shell_cmd = compile_cmd.join(" ");
+        // Write source file (and run.sh for runtimes with a run command)
+        fs::write(build_dir.join(&runtime.source_file), source_code).await?;
+        if let Some(script) = runtime.run_script() {
+            fs::write(build_dir.join("run.sh"), script).await?;
+        }
 
-        let output =
-            run_in_container(&self.config, &spec, build_dir, &["sh", "-c", &shell_cmd]).await?;
+        if let Some(shell_cmd) = runtime.compile_shell_command() {
+            // Resolve the container image
+            let spec = resolve_image(&self.config, Some(runtime));
+            ensure_image(&self.config, &spec.image).await?;
+
+            let output = run_in_container(
+                &self.config,
+                id,
+                &spec,
+                build_dir,
+                &["sh", "-c", &shell_cmd],
+            )
+            .await?;
 
-        if !output.success {
-            return Err(anyhow!("Compilation failed:\n{}", output.stderr));
+            if !output.success {
+                return Err(anyhow!("Compilation failed:\n{}", output.stderr));
+            }
         }
 
         // Save the binary
-        let binary_path = self.save_binary(job, build_dir).await?;
+        let binary_path = self.save_binary(id, build_dir, binaries_dir).await?;
 
         Ok(binary_path)
     }
 
-    /// Extract a ZIP file to a directory.
+    /// Validate a ZIP submission and extract it to a directory.
     async fn extract_zip(&self, zip_path: &str, dest_dir: &Path) -> Result<()> {
         let zip_path = PathBuf::from(zip_path);
         let dest_dir = dest_dir.to_path_buf();
 
         // Run extraction in blocking task
-        tokio::task::spawn_blocking(move || {
-            let file = std::fs::File::open(&zip_path)
-                .with_context(|| format!("Failed to open ZIP: {}", zip_path.display()))?;
-            let mut archive = zip::ZipArchive::new(file).context("Failed to read ZIP archive")?;
-
-            for i in 0..archive.len() {
-                let mut file = archive.by_index(i)?;
-                let outpath = dest_dir.join(file.name());
-
-                if file.name().ends_with('/') {
-                    std::fs::create_dir_all(&outpath)?;
-                } else {
-                    if let Some(parent) = outpath.parent() {
-                        if !parent.exists() {
-                            std::fs::create_dir_all(parent)?;
-                        }
-                    }
-                    let mut outfile = std::fs::File::create(&outpath)?;
-                    std::io::copy(&mut file, &mut outfile)?;
-                }
-
-                // Set permissions on Unix
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    if let Some(mode) = file.unix_mode() {
-                        std::fs::set_permissions(&outpath, std::fs::Permissions::from_mode(mode))?;
-                    }
-                }
-            }
-
-            Ok::<(), anyhow::Error>(())
-        })
-        .await??;
+        tokio::task::spawn_blocking(move || archive::extract(&zip_path, &dest_dir)).await??;
 
         Ok(())
     }
 
-    /// Get the source file name and compile command for a language.
-    fn get_compile_command(
-        &self,
-        language: &str,
-        _build_dir: &Path,
-    ) -> Result<(String, Vec<&str>)> {
-        match language {
-            "cpp" | "c++" => Ok((
-                "main.cpp".to_string(),
-                vec!["g++", "-O2", "-std=c++17", "-o", "main", "main.cpp"],
-            )),
-            "c" => Ok((
-                "main.c".to_string(),
-                vec!["gcc", "-O2", "-std=c11", "-o", "main", "main.c"],
-            )),
-            "rust" => Ok((
-                "main.rs".to_string(),
-                vec!["rustc", "-O", "-o", "main", "main.rs"],
-            )),
-            "go" => Ok((
-                "main.go".to_string(),
-                vec!["go", "build", "-o", "main", "main.go"],
-            )),
-            "python" => {
-                // Python doesn't need compilation, just syntax check
-                Ok((
-                    "main.py".to_string(),
-                    vec!["python3", "-m", "py_compile", "main.py"],
-                ))
-            }
-            "zig" => Ok((
-                "main.zig".to_string(),
-                vec!["zig", "build-exe", "-O", "ReleaseFast", "main.zig"],
-            )),
-            other => Err(anyhow!("Unsupported language: {}", other)),
-        }
-    }
-
-    /// Find the compiled binary and save it to the binaries directory.
-    async fn save_binary(&self, job: &CompileJob, build_dir: &Path) -> Result<String> {
+    /// Find the compiled binary and save it to `binaries_dir`.
+    async fn save_binary(&self, id: Uuid, build_dir: &Path, binaries_dir: &Path) -> Result<String> {
         // Look for common binary names
         let binary_names = ["main", "a.out", "solution", "run"];
         let mut binary_path = None;
@@ -281,7 +253,10 @@ impl Compiler {
         let run_script = build_dir.join("run.sh");
         if binary_path.is_none() && run_script.exists() {
             // For ZIP submissions, copy the entire build directory as the "binary"
-            let dest_dir = format!("{}/{}_bin", self.config.binaries_path, job.submission_id);
+            let dest_dir = binaries_dir
+                .join(format!("{}_bin", id))
+                .to_string_lossy()
+                .into_owned();
 
             // Create destination directory
             fs::create_dir_all(&dest_dir).await?;
@@ -296,7 +271,10 @@ impl Compiler {
         let binary_path = binary_path.ok_or_else(|| anyhow!("No compiled binary found"))?;
 
         // Create destination path
-        let dest_path = format!("{}/{}_bin", self.config.binaries_path, job.submission_id);
+        let dest_path = binaries_dir
+            .join(format!("{}_bin", id))
+            .to_string_lossy()
+            .into_owned();
 
         // Ensure parent directory exists
         if let Some(parent) = Path::new(&dest_path).parent() {
@@ -343,7 +321,7 @@ impl Compiler {
     }
 
     /// Fetch source code from database for legacy source submissions.
-    async fn fetch_source_code(&self, submission_id: &uuid::Uuid) -> Result<String> {
+    async fn fetch_source_code(&self, submission_id: &Uuid) -> Result<String> {
         // This would require database access, which we don't have in the compiler
         // For now, return an error - source code should be passed in the job
         Err(anyhow!(
@@ -352,25 +330,3 @@ impl Compiler {
         ))
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_get_compile_command() {
-        let config = Config::from_env();
-        let compiler = Compiler::new(config);
-        let build_dir = PathBuf::from("/tmp/test");
-
-        let (file, cmd) = compiler.get_compile_command("cpp", &build_dir).unwrap();
-        assert_eq!(file, "main.cpp");
-        assert!(cmd.contains(&"g++"));
-
-        let (file, cmd) = compiler.get_compile_command("python", &build_dir).unwrap();
-        assert_eq!(file, "main.py");
-        assert!(cmd.contains(&"python3"));
-
-        assert!(compiler.get_compile_command("unknown", &build_dir).is_err());
-    }
-}
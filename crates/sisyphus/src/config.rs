@@ -2,22 +2,7 @@
 
 use std::env;
 
-/// Per-language Docker image overrides.
-///
-/// Each field can be set via the corresponding env var
-/// (e.g. `CONTAINER_IMAGE_CPP=gcc:14`).  When unset the
-/// container module falls back to sensible defaults.
-#[derive(Debug, Clone, Default)]
-pub struct ContainerImages {
-    pub cpp: Option<String>,
-    pub c: Option<String>,
-    pub rust: Option<String>,
-    pub go: Option<String>,
-    pub python: Option<String>,
-    pub zig: Option<String>,
-    /// Fallback image when the language is unknown or not specified.
-    pub generic: Option<String>,
-}
+use olympus_common::storage::StorageRoots;
 
 /// Sisyphus configuration
 #[derive(Debug, Clone)]
@@ -36,20 +21,27 @@ pub struct Config {
     pub compile_stream: String,
     /// Stream name for run jobs (output)
     pub run_stream: String,
+    /// Stream name for sample runs to compile
+    pub sample_compile_stream: String,
+    /// Stream name for compiled sample runs (output)
+    pub sample_run_stream: String,
+    /// Prometheus metrics port
+    pub metrics_port: u16,
     /// Compilation timeout in seconds
     pub compile_timeout_secs: u64,
-    /// Base path for submission storage
-    pub submissions_path: String,
     /// Base path for compiled binaries
     pub binaries_path: String,
+    /// Storage roots contests may keep their artifacts under instead
+    pub storage_roots: StorageRoots,
     /// Enable network during compilation (for package downloads)
     pub network_enabled: bool,
     /// Maximum memory for compilation (in bytes)
     pub max_memory_bytes: u64,
     /// Maximum CPU cores for compilation
     pub max_cpu_cores: u32,
-    /// Per-language Docker image overrides
-    pub container_images: ContainerImages,
+    /// Image used when a submission has no language hint.
+    /// Per-language images come from the `runtimes` table.
+    pub generic_image: Option<String>,
     /// Docker API version to negotiate with the daemon.
     /// Set this when the client binary is older than the daemon's
     /// minimum supported API version (e.g. "1.44").
@@ -99,12 +91,18 @@ impl Config {
             compile_stream: env::var("COMPILE_STREAM")
                 .unwrap_or_else(|_| "compile_queue".to_string()),
             run_stream: env::var("RUN_STREAM").unwrap_or_else(|_| "run_queue".to_string()),
+            sample_compile_stream: env::var("SAMPLE_COMPILE_STREAM")
+                .unwrap_or_else(|_| "sample_compile_queue".to_string()),
+            sample_run_stream: env::var("SAMPLE_RUN_STREAM")
+                .unwrap_or_else(|_| "sample_run_queue".to_string()),
+            metrics_port: env::var("METRICS_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(9093),
             compile_timeout_secs: env::var("COMPILE_TIMEOUT_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(30),
-            submissions_path: env::var("SUBMISSIONS_PATH")
-                .unwrap_or_else(|_| "/mnt/data/submissions".to_string()),
             binaries_path: env::var("BINARIES_PATH")
                 .unwrap_or_else(|_| "/mnt/data/binaries/users".to_string()),
             network_enabled: env::var("NETWORK_ENABLED")
@@ -118,16 +116,11 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(2),
-            container_images: ContainerImages {
-                cpp: env::var("CONTAINER_IMAGE_CPP").ok(),
-                c: env::var("CONTAINER_IMAGE_C").ok(),
-                rust: env::var("CONTAINER_IMAGE_RUST").ok(),
-                go: env::var("CONTAINER_IMAGE_GO").ok(),
-                python: env::var("CONTAINER_IMAGE_PYTHON").ok(),
-                zig: env::var("CONTAINER_IMAGE_ZIG").ok(),
-                generic: env::var("CONTAINER_IMAGE_GENERIC").ok(),
-            },
+            generic_image: env::var("CONTAINER_IMAGE_GENERIC").ok(),
             docker_api_version: env::var("DOCKER_API_VERSION").ok(),
+            storage_roots: StorageRoots::from_env(
+                env::var("STORAGE_BASE_PATH").unwrap_or_else(|_| "/mnt/data".to_string()),
+            ),
             build_dir_base: env::var("BUILD_DIR_BASE")
                 .unwrap_or_else(|_| "/mnt/data/temp/builds".to_string()),
             data_path: env::var("STORAGE_BASE_PATH").unwrap_or_else(|_| "/mnt/data".to_string()),
@@ -3,16 +3,26 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use deadpool_redis::redis;
 use deadpool_redis::Pool as RedisPool;
+use olympus_common::storage::USER_BINARIES_DIR;
+use olympus_common::worker_profile::{profile_stream, DEFAULT_WORKER_PROFILE};
+use olympus_common::JudgePriority;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::archive::ValidationError;
+use crate::artifacts::{self, Artifact};
 use crate::compiler::Compiler;
 use crate::config::Config;
+use crate::docker::DockerSupervisor;
+use crate::metrics;
+use crate::runtimes;
 
 /// Maximum retry attempts for a job before moving to dead letter.
 const MAX_RETRIES: u32 = 3;
@@ -20,6 +30,12 @@ const MAX_RETRIES: u32 = 3;
 /// Base delay for exponential backoff (in milliseconds).
 const BASE_RETRY_DELAY_MS: u64 = 1000;
 
+/// Redis key prefix for the admin queue pause flag (`queue_paused:<stream>`).
+const PAUSE_KEY_PREFIX: &str = "queue_paused:";
+
+/// How often to re-check the pause flag while paused (in milliseconds).
+const PAUSE_POLL_INTERVAL_MS: u64 = 2000;
+
 /// A compilation job from the queue.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompileJob {
@@ -27,8 +43,17 @@ pub struct CompileJob {
     pub job_type: String, // "source" or "zip"
     pub file_path: Option<String>,
     pub language: Option<String>,
+    /// Organization the submission was made under, passed on to Minos
+    #[serde(default)]
+    pub tenant: Option<String>,
+    /// Id of the API request that queued the job, for log correlation
+    #[serde(default)]
+    pub request_id: Option<String>,
     #[serde(default)]
     pub retry_count: u32,
+    /// Priority stream the job was read from; carried over to the run queue
+    #[serde(default)]
+    pub priority: JudgePriority,
 }
 
 /// Redis Stream consumer for compilation jobs.
@@ -37,6 +62,7 @@ pub struct JobConsumer {
     db: PgPool,
     redis: RedisPool,
     compiler: Compiler,
+    docker: DockerSupervisor,
     shutdown: Arc<AtomicBool>,
 }
 
@@ -44,11 +70,13 @@ impl JobConsumer {
     /// Create a new job consumer.
     pub fn new(config: Config, db: PgPool, redis: RedisPool, shutdown: Arc<AtomicBool>) -> Self {
         let compiler = Compiler::new(config.clone());
+        let docker = DockerSupervisor::new(config.clone());
         Self {
             config,
             db,
             redis,
             compiler,
+            docker,
             shutdown,
         }
     }
@@ -57,13 +85,11 @@ impl JobConsumer {
     pub async fn initialize(&mut self) -> Result<()> {
         let mut conn = self.redis.get().await?;
 
-        // Create consumer group for compile_queue
-        self.create_consumer_group(
-            &mut conn,
-            &self.config.compile_stream,
-            &self.config.consumer_group,
-        )
-        .await?;
+        // Create consumer groups for every compile_queue priority stream
+        for stream in JudgePriority::streams(&self.config.compile_stream) {
+            self.create_consumer_group(&mut conn, &stream, &self.config.consumer_group)
+                .await?;
+        }
 
         // Create dead letter stream consumer group
         let dead_letter_stream = format!("{}_dead_letter", self.config.compile_stream);
@@ -106,6 +132,11 @@ impl JobConsumer {
 
     /// Run the consumer loop.
     pub async fn run(&mut self) -> Result<()> {
+        // Take no jobs until containers can be started
+        if !self.docker.wait_until_available(&self.shutdown).await {
+            return Ok(());
+        }
+
         while !self.shutdown.load(Ordering::SeqCst) {
             match self.process_next_job().await {
                 Ok(processed) => {
@@ -141,44 +172,52 @@ impl JobConsumer {
     async fn process_next_job(&mut self) -> Result<bool> {
         let mut conn = self.redis.get().await?;
 
-        // Read from stream with consumer group (blocking for 5 seconds)
-        let result: redis::Value = redis::cmd("XREADGROUP")
-            .arg("GROUP")
-            .arg(&self.config.consumer_group)
-            .arg(&self.config.consumer_name)
-            .arg("COUNT")
-            .arg(1)
-            .arg("BLOCK")
-            .arg(5000) // 5 second timeout
-            .arg("STREAMS")
-            .arg(&self.config.compile_stream)
-            .arg(">") // Only new messages
+        // Honour the admin pause flag before taking new work
+        let paused: bool = redis::cmd("EXISTS")
+            .arg(format!(
+                "{}{}",
+                PAUSE_KEY_PREFIX, self.config.compile_stream
+            ))
             .query_async(&mut *conn)
             .await?;
+        if paused {
+            tracing::debug!("Compile queue paused, not reading new jobs");
+            tokio::time::sleep(tokio::time::Duration::from_millis(PAUSE_POLL_INTERVAL_MS)).await;
+            return Ok(false);
+        }
 
-        // Parse the result
-        let messages = match result {
-            redis::Value::Nil => return Ok(false),
-            redis::Value::Array(streams) => {
-                if streams.is_empty() {
-                    return Ok(false);
-                }
-                streams
-            }
-            _ => return Ok(false),
+        let messages = match self.read_next_message(&mut conn).await? {
+            Some(messages) => messages,
+            None => return Ok(false),
         };
 
         // Extract message from nested structure
         // Format: [[stream_name, [[message_id, [field, value, ...]]]]]
-        let (message_id, mut job) = match self.parse_stream_message(&messages) {
+        let (message_id, job) = match self.parse_stream_message(&messages) {
             Some(parsed) => parsed,
             None => return Ok(false),
         };
 
+        // Everything logged for the job carries the id of the request that
+        // queued it
+        let span = tracing::info_span!(
+            "compile_job",
+            submission_id = %job.submission_id,
+            request_id = job.request_id.as_deref().unwrap_or_default(),
+        );
+        self.process_job(message_id, job).instrument(span).await?;
+
+        Ok(true)
+    }
+
+    /// Compile one job and pass it on to judging, retry or the dead letter
+    /// queue.
+    async fn process_job(&self, message_id: String, mut job: CompileJob) -> Result<()> {
         tracing::info!(
             submission_id = %job.submission_id,
             message_id = %message_id,
             retry_count = job.retry_count,
+            priority = %job.priority,
             "Processing compilation job"
         );
 
@@ -186,10 +225,76 @@ impl JobConsumer {
         self.update_submission_status(&job.submission_id, "compiling")
             .await?;
 
-        // Compile the submission
-        let compile_result = self.compiler.compile(&job).await;
+        // Resolve the language hint through the runtime registry
+        let runtime = match job.language.as_deref() {
+            Some(lang) => runtimes::find_enabled(&self.db, lang).await?,
+            None => None,
+        };
+
+        // Artifacts go under the contest's storage root if it has one
+        let binaries_dir = self.binaries_dir(job.submission_id).await?;
+
+        // Pin the image to a digest and compile the submission
+        let started = std::time::Instant::now();
+        let compile_result = match (job.language.as_deref(), runtime) {
+            (Some(lang), None) => Err(anyhow!("Unsupported or disabled language: {}", lang)),
+            (_, Some(runtime)) => {
+                match runtimes::pin_image(&self.db, &self.config, job.submission_id, runtime).await
+                {
+                    Ok(runtime) => {
+                        // A missing version never fails the compile
+                        if let Err(e) = runtimes::record_version(
+                            &self.db,
+                            &self.config,
+                            job.submission_id,
+                            &runtime,
+                        )
+                        .await
+                        {
+                            tracing::warn!(
+                                submission_id = %job.submission_id,
+                                runtime = %runtime.name,
+                                error = %e,
+                                "Failed to record runtime version"
+                            );
+                        }
+                        self.compiler
+                            .compile(&job, Some(&runtime), &binaries_dir)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            (None, None) => self.compiler.compile(&job, None, &binaries_dir).await,
+        };
+
+        // A failure while the daemon is unreachable says nothing about the
+        // submission
+        let daemon_lost = match &compile_result {
+            Err(e) if e.downcast_ref::<ValidationError>().is_none() => {
+                !self.docker.is_available().await
+            }
+            _ => false,
+        };
+        if !daemon_lost {
+            metrics::record_compile(job.language.as_deref(), started.elapsed().as_secs_f64());
+            metrics::JOBS_PROCESSED.inc();
+        }
 
         match compile_result {
+            Err(e) if daemon_lost => {
+                tracing::warn!(
+                    submission_id = %job.submission_id,
+                    error = %e,
+                    "Docker daemon unavailable, re-queueing job without using a retry"
+                );
+
+                self.requeue_for_retry(&job).await?;
+                self.ack_message(&job, &message_id).await?;
+
+                // Pause consumption until the daemon is back
+                self.docker.wait_until_available(&self.shutdown).await;
+            }
             Ok(binary_path) => {
                 tracing::info!(
                     submission_id = %job.submission_id,
@@ -197,16 +302,36 @@ impl JobConsumer {
                     "Compilation successful"
                 );
 
-                // Update status and store binary path
-                self.update_compilation_success(&job.submission_id, &binary_path)
-                    .await?;
+                // Record the artifact so Minos and Horus can find it
+                let path = std::path::PathBuf::from(&binary_path);
+                let artifact = tokio::task::spawn_blocking(move || Artifact::inspect(&path))
+                    .await?
+                    .context("Failed to inspect compiled artifact")?;
+                artifacts::record(&self.db, &job.submission_id, &artifact).await?;
+
+                // Update status
+                self.update_compilation_success(&job.submission_id).await?;
 
                 // Queue for judging
-                self.queue_for_judging(&job.submission_id, &binary_path)
+                self.queue_for_judging(&job, &binary_path).await?;
+
+                // Acknowledge the message
+                self.ack_message(&job, &message_id).await?;
+            }
+            Err(e) if e.downcast_ref::<ValidationError>().is_some() => {
+                // The archive itself is unacceptable; retrying cannot help
+                tracing::warn!(
+                    submission_id = %job.submission_id,
+                    error = %e,
+                    "ZIP submission failed validation"
+                );
+
+                self.update_validation_failure(&job.submission_id, &e.to_string())
                     .await?;
+                metrics::record_failure("validation_failed");
 
                 // Acknowledge the message
-                self.ack_message(&message_id).await?;
+                self.ack_message(&job, &message_id).await?;
             }
             Err(e) => {
                 let error_msg = e.to_string();
@@ -232,9 +357,10 @@ impl JobConsumer {
 
                     // Re-queue for retry
                     self.requeue_for_retry(&job).await?;
+                    metrics::RETRIES.inc();
 
                     // Acknowledge original message
-                    self.ack_message(&message_id).await?;
+                    self.ack_message(&job, &message_id).await?;
                 } else if is_retryable && job.retry_count >= MAX_RETRIES {
                     // Move to dead letter queue
                     tracing::error!(
@@ -250,9 +376,10 @@ impl JobConsumer {
                         &format!("Max retries exceeded: {}", error_msg),
                     )
                     .await?;
+                    metrics::record_failure("max_retries");
 
                     // Acknowledge original message
-                    self.ack_message(&message_id).await?;
+                    self.ack_message(&job, &message_id).await?;
                 } else {
                     // Non-retryable error (e.g., actual compilation error)
                     tracing::warn!(
@@ -263,14 +390,15 @@ impl JobConsumer {
 
                     self.update_compilation_failure(&job.submission_id, &error_msg)
                         .await?;
+                    metrics::record_failure("compilation_error");
 
                     // Acknowledge the message
-                    self.ack_message(&message_id).await?;
+                    self.ack_message(&job, &message_id).await?;
                 }
             }
         }
 
-        Ok(true)
+        Ok(())
     }
 
     /// Check if an error is retryable.
@@ -291,12 +419,59 @@ impl JobConsumer {
             .any(|pattern| error_lower.contains(pattern))
     }
 
-    /// Acknowledge a message in the stream.
-    async fn ack_message(&self, message_id: &str) -> Result<()> {
+    /// Read the next message, draining higher priority streams first.
+    ///
+    /// Each stream is polled without blocking, highest priority first. Only
+    /// when all are empty does the consumer block on all of them at once;
+    /// Redis then replies with the single stream that received an entry.
+    async fn read_next_message(
+        &self,
+        conn: &mut deadpool_redis::Connection,
+    ) -> Result<Option<Vec<redis::Value>>> {
+        let streams = JudgePriority::streams(&self.config.compile_stream);
+
+        for stream in &streams {
+            let result: redis::Value = redis::cmd("XREADGROUP")
+                .arg("GROUP")
+                .arg(&self.config.consumer_group)
+                .arg(&self.config.consumer_name)
+                .arg("COUNT")
+                .arg(1)
+                .arg("STREAMS")
+                .arg(stream)
+                .arg(">") // Only new messages
+                .query_async(&mut **conn)
+                .await?;
+
+            if let Some(messages) = non_empty_reply(result) {
+                return Ok(Some(messages));
+            }
+        }
+
+        // Nothing queued anywhere (blocking for 5 seconds)
+        let result: redis::Value = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(&self.config.consumer_group)
+            .arg(&self.config.consumer_name)
+            .arg("COUNT")
+            .arg(1)
+            .arg("BLOCK")
+            .arg(5000) // 5 second timeout
+            .arg("STREAMS")
+            .arg(&streams)
+            .arg(vec![">"; streams.len()])
+            .query_async(&mut **conn)
+            .await?;
+
+        Ok(non_empty_reply(result))
+    }
+
+    /// Acknowledge a message in the stream the job was read from.
+    async fn ack_message(&self, job: &CompileJob, message_id: &str) -> Result<()> {
         let mut conn = self.redis.get().await?;
 
         let _: i64 = redis::cmd("XACK")
-            .arg(&self.config.compile_stream)
+            .arg(job.priority.stream_name(&self.config.compile_stream))
             .arg(&self.config.consumer_group)
             .arg(message_id)
             .query_async(&mut *conn)
@@ -306,12 +481,12 @@ impl JobConsumer {
         Ok(())
     }
 
-    /// Re-queue a job for retry.
+    /// Re-queue a job for retry at its original priority.
     async fn requeue_for_retry(&self, job: &CompileJob) -> Result<()> {
         let mut conn = self.redis.get().await?;
 
         let mut cmd = redis::cmd("XADD");
-        cmd.arg(&self.config.compile_stream)
+        cmd.arg(job.priority.stream_name(&self.config.compile_stream))
             .arg("*")
             .arg("submission_id")
             .arg(job.submission_id.to_string())
@@ -328,6 +503,14 @@ impl JobConsumer {
             cmd.arg("language").arg(language);
         }
 
+        if let Some(ref tenant) = job.tenant {
+            cmd.arg("tenant").arg(tenant);
+        }
+
+        if let Some(ref request_id) = job.request_id {
+            cmd.arg("request_id").arg(request_id);
+        }
+
         let _: String = cmd.query_async(&mut *conn).await?;
 
         tracing::debug!(
@@ -366,7 +549,16 @@ impl JobConsumer {
             cmd.arg("language").arg(language);
         }
 
+        if let Some(ref tenant) = job.tenant {
+            cmd.arg("tenant").arg(tenant);
+        }
+
+        if let Some(ref request_id) = job.request_id {
+            cmd.arg("request_id").arg(request_id);
+        }
+
         let stream_id: String = cmd.query_async(&mut *conn).await?;
+        metrics::DEAD_LETTERS.inc();
 
         tracing::info!(
             submission_id = %job.submission_id,
@@ -386,6 +578,15 @@ impl JobConsumer {
             _ => return None,
         };
 
+        // The stream name tells which priority the job was queued at
+        let priority = match stream_arr.first()? {
+            redis::Value::BulkString(s) => String::from_utf8_lossy(s).to_string(),
+            redis::Value::SimpleString(s) => s.clone(),
+            _ => return None,
+        };
+        let priority = JudgePriority::from_stream_name(&self.config.compile_stream, &priority)
+            .unwrap_or_default();
+
         let msgs = stream_arr.get(1)?;
         let msgs_arr = match msgs {
             redis::Value::Array(arr) => arr,
@@ -436,6 +637,8 @@ impl JobConsumer {
             .unwrap_or_else(|| "zip".to_string());
         let file_path = data.get("file_path").cloned();
         let language = data.get("language").cloned();
+        let tenant = data.get("tenant").cloned();
+        let request_id = data.get("request_id").cloned();
         let retry_count = data
             .get("retry_count")
             .and_then(|s| s.parse().ok())
@@ -448,7 +651,10 @@ impl JobConsumer {
                 job_type,
                 file_path,
                 language,
+                tenant,
+                request_id,
                 retry_count,
+                priority,
             },
         ))
     }
@@ -464,21 +670,43 @@ impl JobConsumer {
         Ok(())
     }
 
+    /// Directory a submission's artifact is saved in: `binaries/users`
+    /// under the storage root recorded with it, or `BINARIES_PATH` without
+    /// one.
+    async fn binaries_dir(&self, submission_id: Uuid) -> Result<PathBuf> {
+        let storage_root: Option<String> = sqlx::query_scalar(
+            "SELECT storage_root FROM submissions WHERE id = $1",
+        )
+        .bind(submission_id)
+        .fetch_optional(&self.db)
+        .await?
+        .flatten();
+
+        let Some(root) = storage_root else {
+            return Ok(PathBuf::from(&self.config.binaries_path));
+        };
+        self.config
+            .storage_roots
+            .resolve(Some(&root))
+            .map(|root| root.join(USER_BINARIES_DIR))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Storage root {} of submission {} is not in CONTEST_STORAGE_ROOTS",
+                    root,
+                    submission_id
+                )
+            })
+    }
+
     /// Update submission on successful compilation.
-    async fn update_compilation_success(
-        &self,
-        submission_id: &Uuid,
-        binary_path: &str,
-    ) -> Result<()> {
+    async fn update_compilation_success(&self, submission_id: &Uuid) -> Result<()> {
         sqlx::query(
             r#"UPDATE submissions 
                SET status = 'compiled', 
-                   compiled_at = NOW(),
-                   file_path = COALESCE(file_path, $2)
+                   compiled_at = NOW()
                WHERE id = $1"#,
         )
         .bind(submission_id)
-        .bind(binary_path)
         .execute(&self.db)
         .await
         .context("Failed to update compilation success")?;
@@ -506,26 +734,80 @@ impl JobConsumer {
         Ok(())
     }
 
-    /// Queue the compiled submission for judging.
-    async fn queue_for_judging(&self, submission_id: &Uuid, binary_path: &str) -> Result<()> {
+    /// Update submission on a ZIP archive rejected before compiling.
+    async fn update_validation_failure(
+        &self,
+        submission_id: &Uuid,
+        error_message: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"UPDATE submissions 
+               SET status = 'validation_failed', 
+                   compiled_at = NOW(),
+                   compilation_log = $2
+               WHERE id = $1"#,
+        )
+        .bind(submission_id)
+        .bind(error_message)
+        .execute(&self.db)
+        .await
+        .context("Failed to update validation failure")?;
+        Ok(())
+    }
+
+    /// Queue the compiled submission for judging on the run streams of its
+    /// problem's worker profile.
+    async fn queue_for_judging(&self, job: &CompileJob, binary_path: &str) -> Result<()> {
+        let worker_profile: String = sqlx::query_scalar(
+            r#"
+            SELECT p.worker_profile FROM submissions s
+            JOIN problems p ON p.id = s.problem_id
+            WHERE s.id = $1
+            "#,
+        )
+        .bind(job.submission_id)
+        .fetch_optional(&self.db)
+        .await?
+        .unwrap_or_else(|| DEFAULT_WORKER_PROFILE.to_string());
+        let run_stream = profile_stream(&self.config.run_stream, &worker_profile);
+
         let mut conn = self.redis.get().await?;
 
-        let stream_id: String = redis::cmd("XADD")
-            .arg(&self.config.run_stream)
+        let mut cmd = redis::cmd("XADD");
+        cmd.arg(job.priority.stream_name(&run_stream))
             .arg("*")
             .arg("submission_id")
-            .arg(submission_id.to_string())
+            .arg(job.submission_id.to_string())
             .arg("binary_path")
-            .arg(binary_path)
-            .query_async(&mut *conn)
-            .await?;
+            .arg(binary_path);
+
+        if let Some(ref tenant) = job.tenant {
+            cmd.arg("tenant").arg(tenant);
+        }
+
+        if let Some(ref request_id) = job.request_id {
+            cmd.arg("request_id").arg(request_id);
+        }
+
+        let stream_id: String = cmd.query_async(&mut *conn).await?;
 
         tracing::info!(
-            submission_id = %submission_id,
+            submission_id = %job.submission_id,
             stream_id = %stream_id,
+            tenant = ?job.tenant,
+            priority = %job.priority,
+            worker_profile = %worker_profile,
             "Queued for judging"
         );
 
         Ok(())
     }
 }
+
+/// The streams of an XREADGROUP reply, or `None` if nothing was read.
+fn non_empty_reply(result: redis::Value) -> Option<Vec<redis::Value>> {
+    match result {
+        redis::Value::Array(streams) if !streams.is_empty() => Some(streams),
+        _ => None,
+    }
+}
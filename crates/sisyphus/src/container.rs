@@ -7,11 +7,16 @@
 
 use std::path::Path;
 use std::process::Stdio;
+use std::time::Instant;
 
 use anyhow::{anyhow, Context, Result};
 use tokio::process::Command;
+use uuid::Uuid;
 
 use crate::config::Config;
+use crate::docker::{CONTAINER_PREFIX, SUBMISSION_LABEL, WORKER_LABEL};
+use crate::metrics::{self, ActiveContainer};
+use crate::runtimes::RuntimeDefinition;
 
 /// Apply `DOCKER_API_VERSION` env var to a [`Command`] when configured.
 fn apply_api_version(cmd: &mut Command, config: &Config) {
@@ -20,7 +25,7 @@ fn apply_api_version(cmd: &mut Command, config: &Config) {
     }
 }
 
-// ── Language → Docker image mapping ────────────────────────────────────────
+// ── Runtime → container settings ───────────────────────────────────────────
 
 /// Resolved container settings for a single compilation run.
 #[derive(Debug, Clone)]
@@ -29,56 +34,42 @@ pub struct ContainerSpec {
     pub image: String,
     /// Human-readable language label (for logging).
     pub language: String,
+    /// Hard timeout for the container run, in seconds.
+    pub timeout_secs: u64,
+    /// Memory limit for the container, in bytes.
+    pub memory_bytes: u64,
 }
 
-/// Determine the container image for a given language string.
+/// Determine the container settings for a registered runtime.
 ///
-/// If the caller passes some language hint we use that; otherwise we fall
-/// back to a generic `ubuntu:24.04` image (compile.sh must bring its own
+/// Image (pinned to its digest when known) and limits come from the runtime
+/// registry, falling back to the
+/// service defaults for unset limits. Without a runtime (no language hint)
+/// we use a generic `ubuntu:24.04` image (compile.sh must bring its own
 /// tooling in that case).
-pub fn resolve_image(config: &Config, language: Option<&str>) -> ContainerSpec {
-    let lang = language.unwrap_or("generic");
-    let image = match lang {
-        "cpp" | "c++" => config
-            .container_images
-            .cpp
-            .clone()
-            .unwrap_or_else(|| "gcc:latest".to_string()),
-        "c" => config
-            .container_images
-            .c
-            .clone()
-            .unwrap_or_else(|| "gcc:latest".to_string()),
-        "rust" => config
-            .container_images
-            .rust
-            .clone()
-            .unwrap_or_else(|| "rust:1.85-bookworm".to_string()),
-        "go" => config
-            .container_images
-            .go
-            .clone()
-            .unwrap_or_else(|| "golang:1.23-bookworm".to_string()),
-        "python" => config
-            .container_images
-            .python
-            .clone()
-            .unwrap_or_else(|| "python:3.12-bookworm".to_string()),
-        "zig" => config
-            .container_images
-            .zig
-            .clone()
-            .unwrap_or_else(|| "euantorano/zig:0.13.0".to_string()),
-        _ => config
-            .container_images
-            .generic
-            .clone()
-            .unwrap_or_else(|| "ubuntu:24.04".to_string()),
-    };
-
-    ContainerSpec {
-        image,
-        language: lang.to_string(),
+pub fn resolve_image(config: &Config, runtime: Option<&RuntimeDefinition>) -> ContainerSpec {
+    match runtime {
+        Some(rt) => ContainerSpec {
+            image: rt.image_reference(),
+            language: rt.name.clone(),
+            timeout_secs: rt
+                .compile_timeout_secs
+                .map(|s| s as u64)
+                .unwrap_or(config.compile_timeout_secs),
+            memory_bytes: rt
+                .compile_memory_limit_mb
+                .map(|mb| mb as u64 * 1024 * 1024)
+                .unwrap_or(config.max_memory_bytes),
+        },
+        None => ContainerSpec {
+            image: config
+                .generic_image
+                .clone()
+                .unwrap_or_else(|| "ubuntu:24.04".to_string()),
+            language: "generic".to_string(),
+            timeout_secs: config.compile_timeout_secs,
+            memory_bytes: config.max_memory_bytes,
+        },
     }
 }
 
@@ -88,20 +79,23 @@ pub fn resolve_image(config: &Config, language: Option<&str>) -> ContainerSpec {
 #[derive(Debug)]
 pub struct ContainerOutput {
     pub success: bool,
-    pub exit_code: Option<i32>,
     pub stdout: String,
     pub stderr: String,
 }
 
 /// Run a command inside a fresh Docker container.
 ///
+/// * The container is named `algojudge-<uuid>` and labelled with this
+///   worker's consumer name and the submission, so it can be removed if a
+///   daemon restart or a crash orphans it.
 /// * The host `build_dir` is bind-mounted at `/workspace` inside the
 ///   container.
 /// * Network access is controlled by `config.network_enabled`.
-/// * Memory / CPU constraints come from `config`.
+/// * Memory / timeout come from `spec`; CPU constraints from `config`.
 /// * A hard timeout is enforced via `tokio::time::timeout`.
 pub async fn run_in_container(
     config: &Config,
+    submission_id: Uuid,
     spec: &ContainerSpec,
     build_dir: &Path,
     command: &[&str],
@@ -111,9 +105,12 @@ pub async fn run_in_container(
 
     args.push("run".into());
     args.push("--rm".into());
+    args.push(format!("--name={}{}", CONTAINER_PREFIX, Uuid::new_v4()));
+    args.push(format!("--label={}={}", WORKER_LABEL, config.consumer_name));
+    args.push(format!("--label={}={}", SUBMISSION_LABEL, submission_id));
 
     // ── Resource constraints ──────────────────────────────
-    args.push(format!("--memory={}b", config.max_memory_bytes));
+    args.push(format!("--memory={}b", spec.memory_bytes));
     args.push(format!("--cpus={}", config.max_cpu_cores));
 
     // PID limit to prevent fork-bombs
@@ -210,9 +207,10 @@ pub async fn run_in_container(
     let child = cmd
         .spawn()
         .context("Failed to spawn docker process — is the Docker socket mounted?")?;
+    let _active = ActiveContainer::start();
 
     // Enforce a hard timeout
-    let timeout_dur = tokio::time::Duration::from_secs(config.compile_timeout_secs);
+    let timeout_dur = tokio::time::Duration::from_secs(spec.timeout_secs);
     let result = tokio::time::timeout(timeout_dur, child.wait_with_output()).await;
 
     match result {
@@ -222,7 +220,6 @@ pub async fn run_in_container(
 
             Ok(ContainerOutput {
                 success: output.status.success(),
-                exit_code: output.status.code(),
                 stdout,
                 stderr,
             })
@@ -230,7 +227,7 @@ pub async fn run_in_container(
         Ok(Err(e)) => Err(anyhow!("Docker command execution failed: {}", e)),
         Err(_) => Err(anyhow!(
             "Compilation timed out after {} seconds",
-            config.compile_timeout_secs
+            spec.timeout_secs
         )),
     }
 }
@@ -258,6 +255,7 @@ pub async fn ensure_image(config: &Config, image: &str) -> Result<()> {
     }
 
     tracing::info!(image = %image, "Pulling Docker image…");
+    let started = Instant::now();
     let mut pull_cmd = Command::new("docker");
     pull_cmd
         .args(["pull", image])
@@ -269,6 +267,7 @@ pub async fn ensure_image(config: &Config, image: &str) -> Result<()> {
         .output()
         .await
         .context("Failed to run docker pull")?;
+    metrics::record_image_pull(started, pull.status.success());
 
     if pull.status.success() {
         tracing::info!(image = %image, "Docker image pulled successfully");
@@ -283,3 +282,37 @@ pub async fn ensure_image(config: &Config, image: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Registry digests of a local image (`RepoDigests`, `repository@sha256:...`).
+///
+/// Empty for images that were built locally rather than pulled.
+pub async fn repo_digests(config: &Config, image: &str) -> Result<Vec<String>> {
+    let mut cmd = Command::new("docker");
+    cmd.args([
+        "image",
+        "inspect",
+        "--format",
+        "{{json .RepoDigests}}",
+        image,
+    ])
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+    apply_api_version(&mut cmd, config);
+    let output = cmd
+        .output()
+        .await
+        .context("Failed to run docker image inspect")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "docker image inspect {} failed: {}",
+            image,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let digests: Option<Vec<String>> =
+        serde_json::from_slice(&output.stdout).context("Unexpected docker image inspect output")?;
+    Ok(digests.unwrap_or_default())
+}
@@ -0,0 +1,176 @@
+//! Docker daemon supervision.
+//!
+//! Compilation containers are started through the `docker` CLI, so a
+//! daemon restart makes every build in flight fail in ways that look like
+//! compile errors. The supervisor lets the consumer tell the two apart:
+//! when a job fails while the daemon is unreachable, the job is re-queued
+//! without using up a retry, consumption pauses until the daemon answers
+//! again, and the containers this worker left behind are removed.
+
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use tokio::process::Command;
+
+use crate::config::Config;
+
+/// Name prefix of every compilation container
+pub const CONTAINER_PREFIX: &str = "algojudge-";
+
+/// Label naming the worker (consumer name) that started a container
+pub const WORKER_LABEL: &str = "algojudge.worker";
+
+/// Label naming the submission a container compiles; Horus reaps labelled
+/// containers whose submission is no longer compiling
+pub const SUBMISSION_LABEL: &str = "algojudge.submission";
+
+/// How long a daemon ping may take before the daemon counts as down
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First delay between reconnection attempts
+const BASE_RECONNECT_DELAY_MS: u64 = 1000;
+
+/// Longest delay between reconnection attempts
+const MAX_RECONNECT_DELAY_MS: u64 = 30_000;
+
+/// Watches the Docker daemon compilation containers run on.
+pub struct DockerSupervisor {
+    config: Config,
+}
+
+impl DockerSupervisor {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Whether the daemon answers (`docker version` reaches the server).
+    pub async fn is_available(&self) -> bool {
+        let mut cmd = self.docker();
+        cmd.args(["version", "--format", "{{.Server.Version}}"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        matches!(
+            tokio::time::timeout(PING_TIMEOUT, cmd.status()).await,
+            Ok(Ok(status)) if status.success()
+        )
+    }
+
+    /// Wait until the daemon answers, backing off between attempts, then
+    /// remove the containers this worker left behind.
+    ///
+    /// Returns `false` if shutdown was requested before the daemon came back.
+    pub async fn wait_until_available(&self, shutdown: &AtomicBool) -> bool {
+        let mut attempt = 0;
+        while !self.is_available().await {
+            if shutdown.load(Ordering::SeqCst) {
+                return false;
+            }
+            let delay = reconnect_delay(attempt);
+            tracing::warn!(
+                attempt = attempt + 1,
+                delay_ms = delay.as_millis() as u64,
+                "Docker daemon unavailable, compile queue paused"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+
+        if attempt > 0 {
+            tracing::info!(attempts = attempt, "Docker daemon reachable again");
+        }
+
+        match self.remove_orphans().await {
+            Ok(0) => {}
+            Ok(removed) => tracing::info!(removed, "Removed orphaned compilation containers"),
+            Err(e) => tracing::warn!(error = %e, "Failed to remove orphaned containers"),
+        }
+        true
+    }
+
+    /// Remove every compilation container started by this worker. Builds
+    /// interrupted by a daemon restart can leave them behind despite `--rm`.
+    pub async fn remove_orphans(&self) -> Result<usize> {
+        let mut list = self.docker();
+        list.args([
+            "ps",
+            "--all",
+            "--quiet",
+            "--filter",
+            &format!("name=^{}", CONTAINER_PREFIX),
+            "--filter",
+            &format!("label={}={}", WORKER_LABEL, self.config.consumer_name),
+        ])
+        .stdin(Stdio::null());
+        let output = list
+            .output()
+            .await
+            .context("Failed to list compilation containers")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "docker ps failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let ids: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut remove = self.docker();
+        remove
+            .args(["rm", "--force"])
+            .args(&ids)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null());
+        let output = remove
+            .output()
+            .await
+            .context("Failed to remove compilation containers")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "docker rm failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(ids.len())
+    }
+
+    fn docker(&self) -> Command {
+        let mut cmd = Command::new("docker");
+        if let Some(ref ver) = self.config.docker_api_version {
+            cmd.env("DOCKER_API_VERSION", ver);
+        }
+        cmd
+    }
+}
+
+/// Delay before reconnection attempt `attempt + 1`: doubling from one
+/// second, capped at thirty.
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    let delay = BASE_RECONNECT_DELAY_MS.saturating_mul(1u64 << attempt.min(16));
+    Duration::from_millis(delay.min(MAX_RECONNECT_DELAY_MS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backs_off_to_cap() {
+        assert_eq!(reconnect_delay(0), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(1), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(4), Duration::from_secs(16));
+        assert_eq!(reconnect_delay(5), Duration::from_secs(30));
+        assert_eq!(reconnect_delay(u32::MAX), Duration::from_secs(30));
+    }
+}
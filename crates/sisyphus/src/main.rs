@@ -1,12 +1,19 @@
 //! Sisyphus - Compilation Worker for Olympus
 //!
 //! Consumes compilation jobs from Redis Stream, compiles submissions,
-//! and queues successful compilations for judging.
+//! and queues successful compilations for judging. Sample runs are
+//! compiled on a stream of their own.
 
+mod archive;
+mod artifacts;
 mod compiler;
 mod config;
 mod consumer;
 mod container;
+mod docker;
+mod metrics;
+mod runtimes;
+mod samples;
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -16,6 +23,8 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::config::Config;
 use crate::consumer::JobConsumer;
+use crate::metrics::MetricsServer;
+use crate::samples::SampleCompiler;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -77,6 +86,23 @@ async fn main() -> Result<()> {
         shutdown_clone.store(true, Ordering::SeqCst);
     });
 
+    // Start metrics server
+    let metrics_port = config.metrics_port;
+    tokio::spawn(async move {
+        if let Err(e) = MetricsServer::run(metrics_port).await {
+            tracing::error!("Metrics server error: {}", e);
+        }
+    });
+
+    // Compile sample runs alongside submissions
+    let sample_compiler = SampleCompiler::new(
+        config.clone(),
+        db_pool.clone(),
+        redis_pool.clone(),
+        shutdown.clone(),
+    );
+    tokio::spawn(sample_compiler.run());
+
     // Create and initialize consumer
     let mut consumer = JobConsumer::new(config, db_pool, redis_pool, shutdown);
     consumer.initialize().await?;
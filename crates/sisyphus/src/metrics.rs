@@ -0,0 +1,175 @@
+//! Prometheus metrics for Sisyphus
+
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+
+/// Global metrics registry
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+/// Compilation duration histogram
+pub static COMPILE_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    let opts = HistogramOpts::new(
+        "compile_duration_seconds",
+        "Time spent compiling submissions",
+    )
+    .buckets(vec![0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0]);
+
+    HistogramVec::new(opts, &["language"]).expect("Failed to create histogram")
+});
+
+/// Jobs processed counter
+pub static JOBS_PROCESSED: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "compile_jobs_processed_total",
+        "Total compile jobs processed",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Jobs that produced no binary, by reason
+pub static JOBS_FAILED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    let opts = Opts::new(
+        "compile_jobs_failed_total",
+        "Compile jobs that produced no binary, by reason",
+    );
+    IntCounterVec::new(opts, &["reason"]).expect("Failed to create counter")
+});
+
+/// Retries queued after infrastructure errors
+pub static RETRIES: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "compile_retries_total",
+        "Compile jobs re-queued for another attempt",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Jobs moved to the dead letter stream
+pub static DEAD_LETTERS: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "compile_dead_letter_total",
+        "Compile jobs moved to the dead letter stream",
+    )
+    .expect("Failed to create counter")
+});
+
+/// Compilation containers currently running
+pub static ACTIVE_CONTAINERS: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "compile_active_containers",
+        "Compilation containers currently running",
+    )
+    .expect("Failed to create gauge")
+});
+
+/// Image pull duration histogram
+pub static IMAGE_PULL_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    let opts = HistogramOpts::new(
+        "compile_image_pull_duration_seconds",
+        "Time spent pulling compiler images",
+    )
+    .buckets(vec![1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0]);
+
+    HistogramVec::new(opts, &["result"]).expect("Failed to create histogram")
+});
+
+/// Initialize and register all metrics
+pub fn init_metrics() {
+    REGISTRY
+        .register(Box::new(COMPILE_DURATION.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(JOBS_PROCESSED.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(JOBS_FAILED.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(RETRIES.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(DEAD_LETTERS.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(ACTIVE_CONTAINERS.clone()))
+        .expect("Failed to register metric");
+    REGISTRY
+        .register(Box::new(IMAGE_PULL_DURATION.clone()))
+        .expect("Failed to register metric");
+}
+
+/// Record a compilation's duration; submissions without a language hint
+/// count as `generic`
+pub fn record_compile(language: Option<&str>, duration_secs: f64) {
+    COMPILE_DURATION
+        .with_label_values(&[language.unwrap_or("generic")])
+        .observe(duration_secs);
+}
+
+/// Record a job that produced no binary
+pub fn record_failure(reason: &str) {
+    JOBS_FAILED.with_label_values(&[reason]).inc();
+}
+
+/// Record an image pull started at `started`
+pub fn record_image_pull(started: Instant, success: bool) {
+    let result = if success { "success" } else { "failure" };
+    IMAGE_PULL_DURATION
+        .with_label_values(&[result])
+        .observe(started.elapsed().as_secs_f64());
+}
+
+/// Counts a running container in `compile_active_containers` until dropped
+pub struct ActiveContainer;
+
+impl ActiveContainer {
+    pub fn start() -> Self {
+        ACTIVE_CONTAINERS.inc();
+        Self
+    }
+}
+
+impl Drop for ActiveContainer {
+    fn drop(&mut self) {
+        ACTIVE_CONTAINERS.dec();
+    }
+}
+
+/// HTTP server for Prometheus metrics endpoint
+pub struct MetricsServer;
+
+impl MetricsServer {
+    /// Run the metrics server
+    pub async fn run(port: u16) -> anyhow::Result<()> {
+        use axum::{routing::get, Router};
+        use std::net::SocketAddr;
+
+        // Initialize metrics
+        init_metrics();
+
+        let app = Router::new()
+            .route("/metrics", get(Self::metrics_handler))
+            .route("/health", get(|| async { "OK" }));
+
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        tracing::info!("Metrics server listening on {}", addr);
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, app).await?;
+
+        Ok(())
+    }
+
+    async fn metrics_handler() -> String {
+        let encoder = TextEncoder::new();
+        let metric_families = REGISTRY.gather();
+        let mut buffer = Vec::new();
+        encoder.encode(&metric_families, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
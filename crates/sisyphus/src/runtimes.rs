@@ -0,0 +1,496 @@
+//! Runtime (language) registry lookup.
+//!
+//! Languages are registered by admins in the `runtimes` table. Each entry
+//! carries the Docker image, the source file name, and shell templates for
+//! compiling and running. Templates may use two placeholders:
+//!
+//! * `{source}` – the runtime's source file name (e.g. `main.cpp`)
+//! * `{binary}` – the output binary name Sisyphus looks for (`main`)
+//!
+//! The registry is read on every job, so admin changes apply immediately.
+//!
+//! Images are compiled from an exact digest rather than their tag. When a
+//! runtime has no digest yet, the tag is pulled and the digest it resolved
+//! to is recorded on the runtime. A contest submission uses the digest its
+//! contest pinned on first use instead, and every submission records the
+//! digest it was compiled with.
+//!
+//! A runtime's `version_command` prints its toolchain version. It is run
+//! once per digest and the first line of its output kept in
+//! `runtime_versions`; every submission records the version it was
+//! compiled with.
+
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::container;
+
+/// Binary name compile templates are expected to produce.
+pub const BINARY_NAME: &str = "main";
+
+/// Longest version string kept (`runtime_versions.version`)
+const MAX_VERSION_LEN: usize = 200;
+
+/// A registered runtime as stored in the `runtimes` table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RuntimeDefinition {
+    /// Language key (e.g. `cpp`)
+    pub name: String,
+    /// Docker image to compile in
+    pub image: String,
+    /// Digest the image is pinned to (`sha256:...`)
+    pub image_digest: Option<String>,
+    /// Source file written for source-code submissions
+    pub source_file: String,
+    /// Compile command template (`None` = no compilation step)
+    pub compile_command: Option<String>,
+    /// Run command template (`None` = execute the binary directly)
+    pub run_command: Option<String>,
+    /// Compile timeout override in seconds
+    pub compile_timeout_secs: Option<i32>,
+    /// Compile memory limit override in MB
+    pub compile_memory_limit_mb: Option<i32>,
+    /// Command printing the toolchain version (`None` = not reported)
+    pub version_command: Option<String>,
+}
+
+impl RuntimeDefinition {
+    /// Image reference to run: `repository@digest` when pinned, otherwise
+    /// the registered tag.
+    pub fn image_reference(&self) -> String {
+        match &self.image_digest {
+            Some(digest) => format!("{}@{}", repository(&self.image), digest),
+            None => self.image.clone(),
+        }
+    }
+
+    /// Substitute `{source}` and `{binary}` in a command template.
+    pub fn render(&self, template: &str) -> String {
+        template
+            .replace("{source}", &self.source_file)
+            .replace("{binary}", BINARY_NAME)
+    }
+
+    /// Rendered compile command, if this runtime has a compile step.
+    pub fn compile_shell_command(&self) -> Option<String> {
+        self.compile_command.as_deref().map(|t| self.render(t))
+    }
+
+    /// Contents of a `run.sh` for runtimes with a run command.
+    ///
+    /// Minos passes the input and output file paths as `$1` and `$2`.
+    pub fn run_script(&self) -> Option<String> {
+        self.run_command
+            .as_deref()
+            .map(|t| format!("#!/bin/bash\nexec {} \"$@\"\n", self.render(t)))
+    }
+}
+
+/// Look up an enabled runtime by name.
+pub async fn find_enabled(db: &PgPool, name: &str) -> Result<Option<RuntimeDefinition>> {
+    let runtime = sqlx::query_as::<_, RuntimeDefinition>(
+        r#"
+        SELECT name, image, image_digest, source_file, compile_command, run_command,
+               compile_timeout_secs, compile_memory_limit_mb, version_command
+        FROM runtimes
+        WHERE name = $1 AND is_enabled
+        "#,
+    )
+    .bind(name)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(runtime)
+}
+
+/// Pin the runtime's image for a submission and record the digest on it.
+///
+/// Resolves and stores the runtime's digest if it has none, then applies
+/// the pin of the submission's contest, pinning the runtime's digest if
+/// this is the contest's first submission in the language. Images without
+/// a registry digest (built locally) are used by tag and not recorded.
+pub async fn pin_image(
+    db: &PgPool,
+    config: &Config,
+    submission_id: Uuid,
+    mut runtime: RuntimeDefinition,
+) -> Result<RuntimeDefinition> {
+    if runtime.image_digest.is_none() {
+        container::ensure_image(config, &runtime.image).await?;
+        let digests = container::repo_digests(config, &runtime.image).await?;
+        match select_repo_digest(&runtime.image, &digests) {
+            Some(digest) => {
+                runtime.image_digest = Some(record_resolved_digest(db, &runtime, &digest).await?)
+            }
+            None => {
+                tracing::warn!(
+                    runtime = %runtime.name,
+                    image = %runtime.image,
+                    "Image has no registry digest, compiling from the tag"
+                );
+                return Ok(runtime);
+            }
+        }
+    }
+
+    let contest_id: Option<Uuid> =
+        sqlx::query_scalar("SELECT contest_id FROM submissions WHERE id = $1")
+            .bind(submission_id)
+            .fetch_optional(db)
+            .await?
+            .flatten();
+
+    if let Some(contest_id) = contest_id {
+        let (image, digest): (String, String) = sqlx::query_as(
+            r#"
+            WITH pinned AS (
+                INSERT INTO contest_runtime_pins (contest_id, runtime_name, image, image_digest)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (contest_id, runtime_name) DO NOTHING
+                RETURNING image, image_digest
+            )
+            SELECT image, image_digest FROM pinned
+            UNION ALL
+            SELECT image, image_digest FROM contest_runtime_pins
+            WHERE contest_id = $1 AND runtime_name = $2
+            LIMIT 1
+            "#,
+        )
+        .bind(contest_id)
+        .bind(&runtime.name)
+        .bind(&runtime.image)
+        .bind(&runtime.image_digest)
+        .fetch_one(db)
+        .await?;
+
+        runtime.image = image;
+        runtime.image_digest = Some(digest);
+    }
+
+    sqlx::query("UPDATE submissions SET image_digest = $2 WHERE id = $1")
+        .bind(submission_id)
+        .bind(&runtime.image_digest)
+        .execute(db)
+        .await?;
+
+    Ok(runtime)
+}
+
+/// Record the toolchain version a submission is compiled with.
+///
+/// The version of a digest is read from `runtime_versions`, or detected by
+/// running the runtime's version command in its image and stored there.
+/// Images without a digest are probed every time. Returns the version, or
+/// `None` for runtimes without a version command.
+pub async fn record_version(
+    db: &PgPool,
+    config: &Config,
+    submission_id: Uuid,
+    runtime: &RuntimeDefinition,
+) -> Result<Option<String>> {
+    let Some(command) = runtime.version_command.as_deref() else {
+        return Ok(None);
+    };
+
+    let known: Option<String> = match &runtime.image_digest {
+        Some(digest) => {
+            sqlx::query_scalar(
+                r#"
+                SELECT version FROM runtime_versions
+                WHERE runtime_name = $1 AND image_digest = $2 AND version_command = $3
+                "#,
+            )
+            .bind(&runtime.name)
+            .bind(digest)
+            .bind(command)
+            .fetch_optional(db)
+            .await?
+        }
+        None => None,
+    };
+
+    let version = match known {
+        Some(version) => version,
+        None => {
+            let spec = container::resolve_image(config, Some(runtime));
+            container::ensure_image(config, &spec.image).await?;
+
+            tokio::fs::create_dir_all(&config.build_dir_base).await?;
+            let scratch = tempfile::tempdir_in(&config.build_dir_base)?;
+            let output = container::run_in_container(
+                config,
+                submission_id,
+                &spec,
+                scratch.path(),
+                &["sh", "-c", command],
+            )
+            .await?;
+            let Some(version) = output
+                .success
+                .then(|| version_line(&output.stdout, &output.stderr))
+                .flatten()
+            else {
+                tracing::warn!(
+                    runtime = %runtime.name,
+                    command = %command,
+                    stderr = %output.stderr.trim(),
+                    "Version command printed no version"
+                );
+                return Ok(None);
+            };
+
+            if let Some(digest) = &runtime.image_digest {
+                sqlx::query(
+                    r#"
+                    INSERT INTO runtime_versions (runtime_name, image_digest, version_command, version)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (runtime_name, image_digest) DO UPDATE
+                    SET version_command = EXCLUDED.version_command,
+                        version = EXCLUDED.version,
+                        detected_at = NOW()
+                    "#,
+                )
+                .bind(&runtime.name)
+                .bind(digest)
+                .bind(command)
+                .bind(&version)
+                .execute(db)
+                .await?;
+
+                tracing::info!(
+                    runtime = %runtime.name,
+                    digest = %digest,
+                    version = %version,
+                    "Recorded runtime version"
+                );
+            }
+            version
+        }
+    };
+
+    sqlx::query("UPDATE submissions SET runtime_version = $2 WHERE id = $1")
+        .bind(submission_id)
+        .bind(&version)
+        .execute(db)
+        .await?;
+
+    Ok(Some(version))
+}
+
+/// First non-empty line a version command printed, preferring stdout
+/// (some tools print their version on stderr).
+pub fn version_line(stdout: &str, stderr: &str) -> Option<String> {
+    let first_line = |text: &str| {
+        text.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| line.chars().take(MAX_VERSION_LEN).collect::<String>())
+    };
+    first_line(stdout).or_else(|| first_line(stderr))
+}
+
+/// Store a digest resolved from the runtime's tag, unless another worker
+/// already did or the image changed meanwhile. Returns the digest to use.
+async fn record_resolved_digest(
+    db: &PgPool,
+    runtime: &RuntimeDefinition,
+    digest: &str,
+) -> Result<String> {
+    let recorded = sqlx::query(
+        r#"
+        UPDATE runtimes SET image_digest = $3, digest_updated_at = NOW()
+        WHERE name = $1 AND image = $2 AND image_digest IS NULL
+        "#,
+    )
+    .bind(&runtime.name)
+    .bind(&runtime.image)
+    .bind(digest)
+    .execute(db)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if recorded {
+        sqlx::query(
+            r#"
+            INSERT INTO runtime_image_rollouts (runtime_name, image, image_digest)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(&runtime.name)
+        .bind(&runtime.image)
+        .bind(digest)
+        .execute(db)
+        .await?;
+
+        tracing::info!(
+            runtime = %runtime.name,
+            image = %runtime.image,
+            digest = %digest,
+            "Recorded runtime image digest"
+        );
+        return Ok(digest.to_string());
+    }
+
+    let current: Option<String> =
+        sqlx::query_scalar("SELECT image_digest FROM runtimes WHERE name = $1 AND image = $2")
+            .bind(&runtime.name)
+            .bind(&runtime.image)
+            .fetch_optional(db)
+            .await?
+            .flatten();
+
+    Ok(current.unwrap_or_else(|| digest.to_string()))
+}
+
+/// Repository part of an image reference, without its tag or digest.
+///
+/// A `:` only starts a tag after the last `/`, so registry ports
+/// (`registry:5000/gcc:14`) are kept.
+pub fn repository(image: &str) -> &str {
+    let image = image.split('@').next().unwrap_or(image);
+    let name_start = image.rfind('/').map_or(0, |i| i + 1);
+    match image[name_start..].find(':') {
+        Some(i) => &image[..name_start + i],
+        None => image,
+    }
+}
+
+/// Pick the digest of `image` from `docker image inspect`'s `RepoDigests`
+/// (`repository@sha256:...` entries, one per repository the image was
+/// pulled from).
+pub fn select_repo_digest(image: &str, repo_digests: &[String]) -> Option<String> {
+    let wanted = normalize_repository(repository(image));
+    repo_digests.iter().find_map(|entry| {
+        let (repo, digest) = entry.split_once('@')?;
+        (normalize_repository(repo) == wanted && digest.starts_with("sha256:"))
+            .then(|| digest.to_string())
+    })
+}
+
+/// Drop the implicit Docker Hub prefixes (`docker.io/`, `library/`).
+fn normalize_repository(repo: &str) -> &str {
+    let repo = repo.strip_prefix("docker.io/").unwrap_or(repo);
+    repo.strip_prefix("library/").unwrap_or(repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime(
+        name: &str,
+        source: &str,
+        compile: Option<&str>,
+        run: Option<&str>,
+    ) -> RuntimeDefinition {
+        RuntimeDefinition {
+            name: name.to_string(),
+            image: "example:latest".to_string(),
+            image_digest: None,
+            source_file: source.to_string(),
+            compile_command: compile.map(str::to_string),
+            run_command: run.map(str::to_string),
+            compile_timeout_secs: None,
+            compile_memory_limit_mb: None,
+            version_command: None,
+        }
+    }
+
+    #[test]
+    fn test_compile_command_rendering() {
+        let cpp = runtime(
+            "cpp",
+            "main.cpp",
+            Some("g++ -O2 -std=c++17 -o {binary} {source}"),
+            None,
+        );
+        assert_eq!(
+            cpp.compile_shell_command().unwrap(),
+            "g++ -O2 -std=c++17 -o main main.cpp"
+        );
+        assert!(cpp.run_script().is_none());
+
+        let interpreted = runtime("ruby", "main.rb", None, None);
+        assert!(interpreted.compile_shell_command().is_none());
+    }
+
+    #[test]
+    fn test_run_script_forwards_args() {
+        let python = runtime(
+            "python",
+            "main.py",
+            Some("python3 -m py_compile {source}"),
+            Some("python3 {source}"),
+        );
+        let script = python.run_script().unwrap();
+        assert!(script.starts_with("#!/bin/bash\n"));
+        assert!(script.contains("exec python3 main.py \"$@\""));
+    }
+
+    #[test]
+    fn test_repository_strips_tag_and_digest() {
+        assert_eq!(repository("gcc:14"), "gcc");
+        assert_eq!(repository("gcc"), "gcc");
+        assert_eq!(repository("euantorano/zig:0.13.0"), "euantorano/zig");
+        assert_eq!(
+            repository("registry:5000/tools/gcc:14"),
+            "registry:5000/tools/gcc"
+        );
+        assert_eq!(repository("registry:5000/gcc"), "registry:5000/gcc");
+        assert_eq!(repository("gcc:14@sha256:abc"), "gcc");
+    }
+
+    #[test]
+    fn test_image_reference_uses_digest() {
+        let mut cpp = runtime("cpp", "main.cpp", None, None);
+        assert_eq!(cpp.image_reference(), "example:latest");
+
+        cpp.image_digest = Some("sha256:0123".to_string());
+        assert_eq!(cpp.image_reference(), "example@sha256:0123");
+    }
+
+    #[test]
+    fn test_select_repo_digest_matches_repository() {
+        let digests = vec![
+            "mirror.local/gcc@sha256:aaaa".to_string(),
+            "gcc@sha256:bbbb".to_string(),
+        ];
+        assert_eq!(
+            select_repo_digest("gcc:latest", &digests).as_deref(),
+            Some("sha256:bbbb")
+        );
+        assert_eq!(
+            select_repo_digest("docker.io/library/gcc:14", &digests).as_deref(),
+            Some("sha256:bbbb")
+        );
+        assert_eq!(
+            select_repo_digest("mirror.local/gcc:14", &digests).as_deref(),
+            Some("sha256:aaaa")
+        );
+        // Built locally: no registry digest
+        assert_eq!(select_repo_digest("zig:dev", &[]), None);
+    }
+
+    #[test]
+    fn test_version_line_takes_first_line() {
+        let gcc = "g++ (GCC) 13.2.0\nCopyright (C) 2023 Free Software Foundation, Inc.\n";
+        assert_eq!(version_line(gcc, "").as_deref(), Some("g++ (GCC) 13.2.0"));
+        assert_eq!(
+            version_line("\n  rustc 1.79.0 (129f3b996 2024-06-10)\n", "").as_deref(),
+            Some("rustc 1.79.0 (129f3b996 2024-06-10)")
+        );
+        // Older interpreters print their version on stderr
+        assert_eq!(
+            version_line("", "Python 2.7.18\n").as_deref(),
+            Some("Python 2.7.18")
+        );
+        assert_eq!(version_line(" \n", ""), None);
+        assert_eq!(
+            version_line(&"x".repeat(500), "").map(|v| v.len()),
+            Some(MAX_VERSION_LEN)
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Compilation of sample runs.
+//!
+//! Contestants can run their code on a problem's sample without making a
+//! submission. Vanguard records a `sample_runs` row and queues it on a
+//! stream of its own, served by this worker next to the job consumer so
+//! sample runs never wait behind submissions. Compiled runs are passed on
+//! to Minos on the sample run stream; compile errors end the run here.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use deadpool_redis::redis;
+use deadpool_redis::Pool as RedisPool;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::compiler::Compiler;
+use crate::config::Config;
+use crate::docker::DockerSupervisor;
+use crate::runtimes;
+
+/// Directory under `BINARIES_PATH` sample artifacts are saved in until
+/// Minos has run them
+const SAMPLE_BINARIES_DIR: &str = "samples";
+
+/// Worker that compiles sample runs.
+pub struct SampleCompiler {
+    config: Config,
+    db: PgPool,
+    redis: RedisPool,
+    compiler: Compiler,
+    docker: DockerSupervisor,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl SampleCompiler {
+    /// Create a new sample run compiler.
+    pub fn new(config: Config, db: PgPool, redis: RedisPool, shutdown: Arc<AtomicBool>) -> Self {
+        let compiler = Compiler::new(config.clone());
+        let docker = DockerSupervisor::new(config.clone());
+        Self {
+            config,
+            db,
+            redis,
+            compiler,
+            docker,
+            shutdown,
+        }
+    }
+
+    /// Create the consumer group (ignore error if it already exists).
+    async fn initialize(&self) -> Result<()> {
+        let mut conn = self.redis.get().await?;
+
+        let result: Result<(), redis::RedisError> = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(&self.config.sample_compile_stream)
+            .arg(&self.config.consumer_group)
+            .arg("$")
+            .arg("MKSTREAM")
+            .query_async(&mut *conn)
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) if e.to_string().contains("BUSYGROUP") => Ok(()),
+            Err(e) => Err(anyhow!("Failed to create sample consumer group: {}", e)),
+        }
+    }
+
+    /// Run the worker loop.
+    pub async fn run(self) {
+        if let Err(e) = self.initialize().await {
+            tracing::error!("{}", e);
+        }
+
+        while !self.shutdown.load(Ordering::SeqCst) {
+            if let Err(e) = self.process_next_run().await {
+                let err_msg = e.to_string();
+                tracing::error!("Error processing sample run: {}", err_msg);
+
+                if err_msg.contains("NOGROUP") {
+                    if let Err(init_err) = self.initialize().await {
+                        tracing::error!("{}", init_err);
+                    }
+                }
+
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+        }
+    }
+
+    /// Compile the next sample run, if any.
+    async fn process_next_run(&self) -> Result<()> {
+        let mut conn = self.redis.get().await?;
+
+        let result: redis::Value = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(&self.config.consumer_group)
+            .arg(&self.config.consumer_name)
+            .arg("COUNT")
+            .arg(1)
+            .arg("BLOCK")
+            .arg(5000) // 5 second timeout
+            .arg("STREAMS")
+            .arg(&self.config.sample_compile_stream)
+            .arg(">")
+            .query_async(&mut *conn)
+            .await?;
+
+        let Some((message_id, run_id)) = parse_message(&result)? else {
+            return Ok(());
+        };
+
+        if let Err(e) = self.compile_run(run_id).await {
+            tracing::error!(run_id = %run_id, error = %e, "Sample run failed");
+            sqlx::query(
+                r#"
+                UPDATE sample_runs
+                SET status = 'failed', error = $1, completed_at = NOW()
+                WHERE id = $2
+                "#,
+            )
+            .bind(e.to_string())
+            .bind(run_id)
+            .execute(&self.db)
+            .await?;
+        }
+
+        let _: i64 = redis::cmd("XACK")
+            .arg(&self.config.sample_compile_stream)
+            .arg(&self.config.consumer_group)
+            .arg(&message_id)
+            .query_async(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Compile a pending sample run and queue it for Minos, or record its
+    /// compile error.
+    async fn compile_run(&self, run_id: Uuid) -> Result<()> {
+        let Some((language, source_code)) = sqlx::query_as::<_, (String, String)>(
+            r#"
+            UPDATE sample_runs SET status = 'compiling'
+            WHERE id = $1 AND status = 'pending'
+            RETURNING language, source_code
+            "#,
+        )
+        .bind(run_id)
+        .fetch_optional(&self.db)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let runtime = runtimes::find_enabled(&self.db, &language)
+            .await?
+            .ok_or_else(|| anyhow!("Unsupported or disabled language: {}", language))?;
+
+        let binaries_dir = PathBuf::from(&self.config.binaries_path).join(SAMPLE_BINARIES_DIR);
+        let binary_path = match self
+            .compiler
+            .compile_code(run_id, &source_code, &runtime, &binaries_dir)
+            .await
+        {
+            Ok(binary_path) => binary_path,
+            // A failure while the daemon is unreachable says nothing about the code
+            Err(e) if !self.docker.is_available().await => return Err(e),
+            Err(e) => {
+                tracing::info!(run_id = %run_id, "Sample run failed to compile");
+                sqlx::query(
+                    r#"
+                    UPDATE sample_runs
+                    SET status = 'compilation_error', compile_log = $2, completed_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(run_id)
+                .bind(e.to_string())
+                .execute(&self.db)
+                .await?;
+                return Ok(());
+            }
+        };
+
+        sqlx::query("UPDATE sample_runs SET status = 'running', binary_path = $2 WHERE id = $1")
+            .bind(run_id)
+            .bind(&binary_path)
+            .execute(&self.db)
+            .await?;
+
+        let mut conn = self.redis.get().await?;
+        let stream_id: String = redis::cmd("XADD")
+            .arg(&self.config.sample_run_stream)
+            .arg("*")
+            .arg("run_id")
+            .arg(run_id.to_string())
+            .query_async(&mut *conn)
+            .await?;
+
+        tracing::info!(
+            run_id = %run_id,
+            stream_id = %stream_id,
+            "Sample run queued for execution"
+        );
+
+        Ok(())
+    }
+}
+
+/// Message id and run id of an XREADGROUP reply, or `None` if nothing was
+/// read.
+fn parse_message(result: &redis::Value) -> Result<Option<(String, Uuid)>> {
+    // Structure: [[stream_name, [[message_id, [field, value, ...]]]]]
+    let redis::Value::Array(streams) = result else {
+        return Ok(None);
+    };
+    let Some(redis::Value::Array(stream)) = streams.first() else {
+        return Ok(None);
+    };
+    let Some(redis::Value::Array(messages)) = stream.get(1) else {
+        return Ok(None);
+    };
+    let Some(redis::Value::Array(message)) = messages.first() else {
+        return Ok(None);
+    };
+
+    let message_id = match message.first() {
+        Some(redis::Value::BulkString(id)) => String::from_utf8_lossy(id).to_string(),
+        _ => return Err(anyhow!("Invalid message ID")),
+    };
+
+    let mut fields = HashMap::new();
+    if let Some(redis::Value::Array(values)) = message.get(1) {
+        for chunk in values.chunks(2) {
+            if let [redis::Value::BulkString(key), redis::Value::BulkString(value)] = chunk {
+                fields.insert(
+                    String::from_utf8_lossy(key).to_string(),
+                    String::from_utf8_lossy(value).to_string(),
+                );
+            }
+        }
+    }
+
+    let run_id = fields
+        .get("run_id")
+        .ok_or_else(|| anyhow!("Missing run_id"))?
+        .parse()?;
+
+    Ok(Some((message_id, run_id)))
+}
@@ -0,0 +1,16 @@
+-- Migration: Add benchmark-scored problems and judge calibration tracking
+-- Minos workers run a calibration benchmark at startup; for problems marked
+-- benchmark_scored the reported times are normalized by the worker's speed factor.
+
+-- Mark problems whose run times should be normalized across judge workers
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS benchmark_scored BOOLEAN NOT NULL DEFAULT false;
+
+-- Record the raw measurement and the factor used to normalize it
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS raw_max_time_ms INTEGER,
+    ADD COLUMN IF NOT EXISTS calibration_factor DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS judged_by VARCHAR(128);
+
+ALTER TABLE submission_results
+    ADD COLUMN IF NOT EXISTS raw_time_ms INTEGER;
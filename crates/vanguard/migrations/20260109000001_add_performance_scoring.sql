@@ -0,0 +1,14 @@
+-- Migration: Add performance (time-ranked) scoring for contests
+-- In 'performance' contests, accepted submissions are scored by runtime
+-- relative to the fastest accepted submission for the same problem
+-- (best time = 100 points, others scaled proportionally).
+
+ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_scoring_type_check;
+ALTER TABLE contests
+    ADD CONSTRAINT contests_scoring_type_check
+    CHECK (scoring_type IN ('icpc', 'ioi', 'custom', 'performance'));
+
+-- Speeds up best-time lookups when rescoring a problem
+CREATE INDEX IF NOT EXISTS idx_submissions_contest_problem_accepted
+    ON submissions(contest_id, problem_id, max_time_ms)
+    WHERE status = 'accepted';
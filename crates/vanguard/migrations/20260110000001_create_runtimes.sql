@@ -0,0 +1,55 @@
+-- Migration: Create runtimes table (dynamic language registry)
+-- Languages were previously hardcoded in Vanguard (request enum) and Sisyphus
+-- (image + compile command). They are now registered by admins and resolved
+-- from this table by all services.
+
+CREATE TABLE IF NOT EXISTS runtimes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Language key used in submissions (e.g. 'cpp', 'python')
+    name VARCHAR(32) NOT NULL UNIQUE CHECK (name ~ '^[a-z0-9][a-z0-9_+-]*$'),
+    display_name VARCHAR(64) NOT NULL,
+
+    -- Docker image Sisyphus compiles in
+    image VARCHAR(255) NOT NULL,
+
+    -- Source file name written for source-code submissions (e.g. 'main.cpp')
+    source_file VARCHAR(64) NOT NULL,
+
+    -- Shell templates. Placeholders: {source} = source_file, {binary} = 'main'
+    -- compile_command NULL means no compilation step.
+    -- run_command NULL means the compiled binary is executed directly;
+    -- otherwise Sisyphus writes it to run.sh for Minos.
+    compile_command TEXT,
+    run_command TEXT,
+
+    -- Per-runtime compilation limits (NULL = Sisyphus defaults)
+    compile_timeout_secs INTEGER CHECK (compile_timeout_secs IS NULL OR compile_timeout_secs > 0),
+    compile_memory_limit_mb INTEGER CHECK (compile_memory_limit_mb IS NULL OR compile_memory_limit_mb > 0),
+
+    -- Disabled runtimes are rejected for new submissions
+    is_enabled BOOLEAN NOT NULL DEFAULT true,
+
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_runtimes_enabled ON runtimes(is_enabled);
+
+-- Trigger for updated_at
+DROP TRIGGER IF EXISTS update_runtimes_updated_at ON runtimes;
+CREATE TRIGGER update_runtimes_updated_at
+    BEFORE UPDATE ON runtimes
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Seed the previously hardcoded languages
+INSERT INTO runtimes (name, display_name, image, source_file, compile_command, run_command) VALUES
+    ('cpp',    'C++17',       'gcc:latest',            'main.cpp', 'g++ -O2 -std=c++17 -o {binary} {source}', NULL),
+    ('c',      'C11',         'gcc:latest',            'main.c',   'gcc -O2 -std=c11 -o {binary} {source}',   NULL),
+    ('rust',   'Rust',        'rust:1.85-bookworm',    'main.rs',  'rustc -O -o {binary} {source}',           NULL),
+    ('go',     'Go',          'golang:1.23-bookworm',  'main.go',  'go build -o {binary} {source}',           NULL),
+    ('python', 'Python 3',    'python:3.12-bookworm',  'main.py',  'python3 -m py_compile {source}',          'python3 {source}'),
+    ('zig',    'Zig',         'euantorano/zig:0.13.0', 'main.zig', 'zig build-exe -O ReleaseFast {source}',   NULL)
+ON CONFLICT (name) DO NOTHING;
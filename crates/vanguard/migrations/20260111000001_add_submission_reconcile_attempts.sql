@@ -0,0 +1,12 @@
+-- Migration: Track stuck-submission reconciliation
+-- Horus periodically re-queues submissions left in 'compiling' or 'judging'
+-- by a worker that died mid-job. The counter bounds how often a submission
+-- is re-queued before it is marked 'system_error' instead.
+
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS reconcile_attempts INTEGER NOT NULL DEFAULT 0;
+
+-- Speeds up the periodic scan for submissions in transient states
+CREATE INDEX IF NOT EXISTS idx_submissions_transient_status
+    ON submissions(status)
+    WHERE status IN ('compiling', 'judging');
@@ -0,0 +1,6 @@
+-- Migration: Per-contest language override for contest problems
+-- When set, replaces the problem's allowed_languages within that contest.
+-- The effective list is still intersected with contests.allowed_languages.
+
+ALTER TABLE contest_problems
+    ADD COLUMN IF NOT EXISTS allowed_languages TEXT[];
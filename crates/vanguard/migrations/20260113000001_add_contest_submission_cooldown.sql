@@ -0,0 +1,9 @@
+-- Migration: Per-contest resubmission cooldown
+-- Minimum seconds between a user's submissions to the same problem in a
+-- contest. Enforced by Vanguard with a Redis key per (contest, user, problem).
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS submission_cooldown_secs INTEGER NOT NULL DEFAULT 0
+    CHECK (submission_cooldown_secs >= 0 AND submission_cooldown_secs <= 3600);
+
+COMMENT ON COLUMN contests.submission_cooldown_secs IS 'Seconds between submissions to the same problem per user (0 = disabled)';
@@ -0,0 +1,32 @@
+-- Migration: Fractional partial scoring
+-- On partial-scoring problems every test earns the checker's score (0..1)
+-- times its weight; tests are aggregated per subtask and the problem score
+-- is stored as a fraction of 100 instead of a whole number.
+
+ALTER TABLE submissions ALTER COLUMN score TYPE DOUBLE PRECISION;
+ALTER TABLE submission_results ALTER COLUMN checker_score TYPE DOUBLE PRECISION;
+
+-- Test weights live in test_cases.score_weight (rows are optional, default 1)
+ALTER TABLE test_cases
+    ADD CONSTRAINT test_cases_score_weight_check CHECK (score_weight >= 0);
+
+-- Subtasks: contiguous test ranges worth a share of the problem's points
+CREATE TABLE IF NOT EXISTS problem_subtasks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+
+    -- Subtask number (1-indexed)
+    subtask_number INTEGER NOT NULL,
+
+    -- Inclusive test case range
+    first_test INTEGER NOT NULL CHECK (first_test >= 1),
+    last_test INTEGER NOT NULL,
+
+    -- Relative points of this subtask
+    points INTEGER NOT NULL CHECK (points > 0),
+
+    CHECK (last_test >= first_test),
+    UNIQUE (problem_id, subtask_number)
+);
+
+CREATE INDEX idx_problem_subtasks_problem ON problem_subtasks(problem_id);
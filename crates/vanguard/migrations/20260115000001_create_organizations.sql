@@ -0,0 +1,39 @@
+-- Migration: Organizations (universities, companies, training groups)
+-- Users join organizations; an organization can host private contests that
+-- only its members can see, and leaderboards can be filtered by organization.
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    slug VARCHAR(50) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+
+    -- Anyone holding the code may join (NULL = invitation only)
+    join_code VARCHAR(64),
+
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- owner: the creator; admin: manages members and hosts contests
+    role VARCHAR(20) NOT NULL DEFAULT 'member' CHECK (role IN ('owner', 'admin', 'member')),
+
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    added_by UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    PRIMARY KEY (organization_id, user_id)
+);
+
+CREATE INDEX idx_organization_members_user ON organization_members(user_id);
+
+-- Hosting organization; non-public hosted contests are visible to members only
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_contests_organization
+    ON contests(organization_id) WHERE organization_id IS NOT NULL;
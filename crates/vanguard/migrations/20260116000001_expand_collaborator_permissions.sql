@@ -0,0 +1,33 @@
+-- Migration: Collaborator permission matrix
+-- Collaborators get fine-grained permission flags; roles (co-owner,
+-- co-setter, tester, observer) are shortcuts that fill in default flags.
+-- can_view_submissions now means "view source of every contest submission".
+
+ALTER TABLE contest_collaborators
+    ADD COLUMN IF NOT EXISTS can_edit_problems BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS can_rejudge BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS can_manage_participants BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS can_answer_clarifications BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS can_publish_editorial BOOLEAN NOT NULL DEFAULT false;
+
+-- Problem binaries were previously guarded by can_add_problems
+UPDATE contest_collaborators SET can_edit_problems = can_add_problems;
+
+-- problem-setter is superseded by co-setter; observers are new
+ALTER TABLE contest_collaborators DROP CONSTRAINT IF EXISTS contest_collaborators_role_check;
+UPDATE contest_collaborators SET role = 'co-setter' WHERE role = 'problem-setter';
+ALTER TABLE contest_collaborators
+    ADD CONSTRAINT contest_collaborators_role_check
+    CHECK (role IN ('co-owner', 'co-setter', 'tester', 'observer'));
+
+-- Existing co-owners keep full control
+UPDATE contest_collaborators
+SET can_edit_contest = true,
+    can_add_problems = true,
+    can_edit_problems = true,
+    can_view_submissions = true,
+    can_rejudge = true,
+    can_manage_participants = true,
+    can_answer_clarifications = true,
+    can_publish_editorial = true
+WHERE role = 'co-owner';
@@ -0,0 +1,8 @@
+-- Migration: Submission source visibility after contest end
+-- private: sources stay visible to their author and contest staff only
+-- participants: after the contest ends, every participant may read all sources
+-- public: after the contest ends, any signed-in user may read all sources
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS source_visibility VARCHAR(20) NOT NULL DEFAULT 'private'
+    CHECK (source_visibility IN ('private', 'participants', 'public'));
@@ -0,0 +1,25 @@
+-- Migration: Anti-cheat telemetry for proctored contests
+-- Clients of a proctored contest report large pastes and tab switches while
+-- participants work; organizers read them back as a per-participant
+-- integrity report. One narrow row per event keeps the table compact.
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS is_proctored BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS contest_telemetry_events (
+    id BIGSERIAL PRIMARY KEY,
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    problem_id UUID REFERENCES problems(id) ON DELETE SET NULL,
+    submission_id UUID REFERENCES submissions(id) ON DELETE SET NULL,
+    -- paste: amount = pasted characters; tab_switch: amount = milliseconds away
+    event_type VARCHAR(16) NOT NULL CHECK (event_type IN ('paste', 'tab_switch')),
+    amount INTEGER NOT NULL DEFAULT 0 CHECK (amount >= 0),
+    occurred_at TIMESTAMPTZ NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contest_telemetry_contest_user
+    ON contest_telemetry_events(contest_id, user_id);
+CREATE INDEX IF NOT EXISTS idx_contest_telemetry_submission
+    ON contest_telemetry_events(submission_id) WHERE submission_id IS NOT NULL;
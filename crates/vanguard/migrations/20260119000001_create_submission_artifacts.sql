@@ -0,0 +1,36 @@
+-- Migration: Compiled submission artifacts
+-- Sisyphus records every compiled artifact here; Minos runs the recorded
+-- path and Horus deletes artifacts by walking this table instead of globbing
+-- the binaries directory. Deleting a submission detaches its artifact
+-- (submission_id becomes NULL) so Horus can still remove the file.
+
+CREATE TABLE IF NOT EXISTS submission_artifacts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_id UUID UNIQUE REFERENCES submissions(id) ON DELETE SET NULL,
+    -- binary: single executable; bundle: directory with run.sh
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('binary', 'bundle')),
+    path TEXT NOT NULL UNIQUE,
+    -- NULL for artifacts compiled before this table existed (see backfill)
+    size_bytes BIGINT CHECK (size_bytes >= 0),
+    -- Hex SHA-256 of the file (or of the bundle's files in path order)
+    checksum VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_submission_artifacts_detached
+    ON submission_artifacts(created_at) WHERE submission_id IS NULL;
+
+-- Backfill the artifacts of submissions compiled before this table existed.
+-- Sisyphus saved them as {submission_id}_bin in the default binaries
+-- directory: a bundle for ZIP submissions and languages with a run command,
+-- a single binary otherwise. Their size and checksum were never measured.
+INSERT INTO submission_artifacts (submission_id, kind, path, created_at)
+SELECT s.id,
+       CASE WHEN s.submission_type = 'zip' OR r.run_command IS NOT NULL
+            THEN 'bundle' ELSE 'binary' END,
+       '/mnt/data/binaries/users/' || s.id || '_bin',
+       s.submitted_at
+FROM submissions s
+LEFT JOIN runtimes r ON r.name = s.language
+WHERE s.status NOT IN ('pending', 'compiling', 'compilation_error')
+ON CONFLICT DO NOTHING;
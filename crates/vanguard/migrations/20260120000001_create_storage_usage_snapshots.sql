@@ -0,0 +1,18 @@
+-- Migration: Storage usage snapshots
+-- Horus measures each storage directory on a schedule and records one row per
+-- category per run; the admin API reads the latest run and the history.
+
+CREATE TABLE IF NOT EXISTS storage_usage_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    category VARCHAR(32) NOT NULL CHECK (
+        category IN ('submissions', 'binaries', 'problem_binaries', 'testcases', 'temp')
+    ),
+    bytes BIGINT NOT NULL CHECK (bytes >= 0),
+    file_count BIGINT NOT NULL CHECK (file_count >= 0),
+    -- Configured budget at measurement time; NULL when none is set
+    budget_bytes BIGINT,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_storage_usage_category_time
+    ON storage_usage_snapshots(category, recorded_at DESC);
@@ -0,0 +1,37 @@
+-- Migration: Admin-editable cleanup policies
+-- Overrides of Horus's environment cleanup settings, one row per artifact
+-- class an admin has edited. NULL fields keep the environment value. Horus
+-- reloads these on the `config_reload` "horus" signal. Dry runs store what
+-- they would have deleted in cleanup_reports instead of deleting it.
+
+CREATE TABLE IF NOT EXISTS cleanup_policies (
+    artifact_class VARCHAR(32) PRIMARY KEY CHECK (
+        artifact_class IN ('testcases', 'temp', 'binaries', 'submissions')
+    ),
+    enabled BOOLEAN,
+    -- Age after which an artifact is eligible for deletion
+    retention_hours INTEGER CHECK (retention_hours > 0),
+    dry_run BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS update_cleanup_policies_updated_at ON cleanup_policies;
+CREATE TRIGGER update_cleanup_policies_updated_at
+    BEFORE UPDATE ON cleanup_policies
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE IF NOT EXISTS cleanup_reports (
+    id BIGSERIAL PRIMARY KEY,
+    artifact_class VARCHAR(32) NOT NULL,
+    -- [{ "path": "...", "bytes": 123 }, ...]
+    items JSONB NOT NULL DEFAULT '[]',
+    items_count INTEGER NOT NULL DEFAULT 0,
+    total_bytes BIGINT NOT NULL DEFAULT 0,
+    errors INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_cleanup_reports_class_time
+    ON cleanup_reports(artifact_class, created_at DESC);
@@ -0,0 +1,22 @@
+-- Migration: Organizations as tenants
+-- Problems can belong to an organization (visible to its members only and
+-- usable only in its contests). Submissions carry the tenant they were made
+-- under, which is also tagged on judge stream messages. Site admins can cap
+-- a tenant's submission rate and stored bytes.
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_problems_organization
+    ON problems(organization_id) WHERE organization_id IS NOT NULL;
+
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_organization
+    ON submissions(organization_id) WHERE organization_id IS NOT NULL;
+
+-- NULL = no tenant-specific limit
+ALTER TABLE organizations
+    ADD COLUMN IF NOT EXISTS submissions_per_minute INTEGER CHECK (submissions_per_minute > 0),
+    ADD COLUMN IF NOT EXISTS storage_quota_bytes BIGINT CHECK (storage_quota_bytes > 0);
@@ -0,0 +1,39 @@
+-- Migration: Contest pauses
+-- Organizers can pause a running contest (e.g. during an infrastructure
+-- incident). Submissions are rejected while a pause is open, and contest
+-- time used for scoring does not advance during pauses.
+
+CREATE TABLE IF NOT EXISTS contest_pauses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    reason TEXT,
+    paused_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resumed_at TIMESTAMPTZ,               -- NULL while the pause is open
+    paused_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    resumed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    CHECK (resumed_at IS NULL OR resumed_at >= paused_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contest_pauses_contest
+    ON contest_pauses(contest_id, paused_at);
+
+-- At most one open pause per contest
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contest_pauses_open
+    ON contest_pauses(contest_id) WHERE resumed_at IS NULL;
+
+-- Seconds of contest time elapsed at `at`: time since the start minus the
+-- parts of pauses that began before `at`.
+CREATE OR REPLACE FUNCTION contest_elapsed_seconds(p_contest_id UUID, p_at TIMESTAMPTZ)
+RETURNS BIGINT AS $$
+    SELECT GREATEST(
+        EXTRACT(EPOCH FROM (p_at - c.start_time))
+        - COALESCE((
+            SELECT SUM(EXTRACT(EPOCH FROM (LEAST(COALESCE(cp.resumed_at, p_at), p_at) - cp.paused_at)))
+            FROM contest_pauses cp
+            WHERE cp.contest_id = c.id AND cp.paused_at < p_at
+        ), 0),
+        0
+    )::BIGINT
+    FROM contests c
+    WHERE c.id = p_contest_id
+$$ LANGUAGE SQL STABLE;
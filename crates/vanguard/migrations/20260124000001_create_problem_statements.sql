@@ -0,0 +1,29 @@
+-- Migration: Localized problem statements
+-- The statement stored on `problems` is written in `statement_locale`;
+-- translations into other locales live in `problem_statements`. Locales are
+-- BCP 47 tags such as `en`, `pt-BR` or `zh-Hant`.
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS statement_locale VARCHAR(35) NOT NULL DEFAULT 'en';
+
+CREATE TABLE IF NOT EXISTS problem_statements (
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    locale VARCHAR(35) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL,
+    input_format TEXT,
+    output_format TEXT,
+    constraints TEXT,
+    sample_explanation TEXT,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (problem_id, locale)
+);
+
+-- Trigger for updated_at
+DROP TRIGGER IF EXISTS update_problem_statements_updated_at ON problem_statements;
+CREATE TRIGGER update_problem_statements_updated_at
+    BEFORE UPDATE ON problem_statements
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,23 @@
+-- Migration: Per-test input/output downloads for debugging
+-- Minos records the generator seed of every test result and keeps the
+-- participant's output (up to a size cap) for failing tests. Problem staff
+-- may download a test's input and the kept output; participants may too for
+-- their own submissions once the contest has ended, if the contest opts in.
+
+ALTER TABLE submission_results
+    ADD COLUMN IF NOT EXISTS generator_seed VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS output_path TEXT,
+    ADD COLUMN IF NOT EXISTS output_size_bytes BIGINT,  -- full output size; the kept copy may be cut
+    ADD COLUMN IF NOT EXISTS output_truncated BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS test_data_downloads BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Kept outputs are measured by Horus like the other storage directories
+ALTER TABLE storage_usage_snapshots
+    DROP CONSTRAINT IF EXISTS storage_usage_snapshots_category_check;
+ALTER TABLE storage_usage_snapshots
+    ADD CONSTRAINT storage_usage_snapshots_category_check CHECK (
+        category IN ('submissions', 'binaries', 'problem_binaries', 'testcases', 'temp',
+                     'debug_outputs')
+    );
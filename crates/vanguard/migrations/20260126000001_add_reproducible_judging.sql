@@ -0,0 +1,34 @@
+-- Migration: Reproducible judging
+-- Generators are run as `generator <test_number> <seed>`, where the seed is
+-- derived from (problem_id, test_number, generator_version). The version is
+-- bumped on every generator upload and each test result records the seed
+-- and version it was judged with, so any test can be run again exactly.
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS generator_version INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE submission_results
+    ADD COLUMN IF NOT EXISTS generator_version INTEGER;
+
+-- Single-test re-runs requested by admins
+CREATE TABLE IF NOT EXISTS test_reproductions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    test_case_number INTEGER NOT NULL,
+    generator_seed VARCHAR(64) NOT NULL,
+    generator_version INTEGER NOT NULL,
+    original_verdict VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'completed', 'failed')),
+    verdict VARCHAR(20),
+    time_ms INTEGER,
+    memory_kb INTEGER,
+    checker_output TEXT,
+    error TEXT,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_test_reproductions_submission
+    ON test_reproductions(submission_id, created_at DESC);
@@ -0,0 +1,38 @@
+-- Migration: Presentation error, output limit and idleness limit verdicts
+-- Output limit exceeded used to be stored as `runtime_error`; it now has
+-- its own value, as do checker presentation errors (testlib exit code 2,
+-- previously `wrong_answer`) and idle runs.
+
+ALTER TABLE submissions DROP CONSTRAINT IF EXISTS submissions_status_check;
+
+ALTER TABLE submissions ADD CONSTRAINT submissions_status_check CHECK (status IN (
+    'pending',            -- Waiting in queue
+    'compiling',          -- Being compiled by Sisyphus
+    'compiled',           -- Compilation successful, waiting for judge
+    'queue_pending',      -- Compiled, but waiting for problem binaries (generator/checker)
+    'judging',            -- Being judged by Minos
+    'accepted',           -- All test cases passed
+    'wrong_answer',       -- Output mismatch
+    'presentation_error', -- Right answer, wrong format
+    'time_limit',         -- Exceeded time limit
+    'memory_limit',       -- Exceeded memory limit
+    'output_limit',       -- Wrote more than the output limit
+    'idleness_limit',     -- Neither used CPU nor wrote output for too long
+    'runtime_error',      -- Runtime crash/error
+    'compilation_error',  -- Failed to compile
+    'system_error'        -- Internal system error
+));
+
+ALTER TABLE submission_results DROP CONSTRAINT IF EXISTS submission_results_verdict_check;
+
+ALTER TABLE submission_results ADD CONSTRAINT submission_results_verdict_check CHECK (verdict IN (
+    'accepted',
+    'wrong_answer',
+    'presentation_error',
+    'time_limit',
+    'memory_limit',
+    'output_limit',
+    'idleness_limit',
+    'runtime_error',
+    'system_error'
+));
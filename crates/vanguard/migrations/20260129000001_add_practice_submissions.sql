@@ -0,0 +1,9 @@
+-- Migration: Practice ("upsolving") submissions
+-- Submissions to a contest's problems after it has ended are judged as usual
+-- but flagged `is_practice` and left out of the standings.
+
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS is_practice BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_contest_practice
+    ON submissions(contest_id, is_practice) WHERE contest_id IS NOT NULL;
@@ -0,0 +1,52 @@
+-- Migration: Problem sheets
+-- Curated, ordered collections of problems ("gyms" / training sheets) that
+-- exist independently of contests. A sheet is visible to everyone when
+-- public, otherwise to its owner and, if it belongs to an organization, to
+-- that organization's members. Users mark sheet problems as done; a problem
+-- also counts as done once the user has an accepted submission for it.
+
+CREATE TABLE IF NOT EXISTS problem_sheets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL,
+    is_public BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_problem_sheets_owner ON problem_sheets(owner_id);
+CREATE INDEX IF NOT EXISTS idx_problem_sheets_organization
+    ON problem_sheets(organization_id) WHERE organization_id IS NOT NULL;
+
+-- Trigger for updated_at
+DROP TRIGGER IF EXISTS update_problem_sheets_updated_at ON problem_sheets;
+CREATE TRIGGER update_problem_sheets_updated_at
+    BEFORE UPDATE ON problem_sheets
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Problems on a sheet, in display order
+CREATE TABLE IF NOT EXISTS problem_sheet_items (
+    sheet_id UUID NOT NULL REFERENCES problem_sheets(id) ON DELETE CASCADE,
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    note TEXT,
+    PRIMARY KEY (sheet_id, problem_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_problem_sheet_items_order
+    ON problem_sheet_items(sheet_id, position);
+
+-- Problems a user has marked as done on a sheet
+CREATE TABLE IF NOT EXISTS problem_sheet_progress (
+    sheet_id UUID NOT NULL REFERENCES problem_sheets(id) ON DELETE CASCADE,
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    completed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (sheet_id, problem_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_problem_sheet_progress_user
+    ON problem_sheet_progress(user_id, sheet_id);
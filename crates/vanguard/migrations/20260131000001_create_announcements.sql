@@ -0,0 +1,30 @@
+-- Migration: System announcements
+-- Admin-managed notices (maintenance windows, incident reports) shown as a
+-- banner between `starts_at` and `ends_at`. Announcements with a
+-- `contest_id` are only shown alongside that contest.
+
+CREATE TABLE IF NOT EXISTS announcements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    severity VARCHAR(16) NOT NULL DEFAULT 'info'
+        CHECK (severity IN ('info', 'warning', 'critical')),
+    contest_id UUID REFERENCES contests(id) ON DELETE CASCADE,
+    starts_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ends_at TIMESTAMPTZ,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT announcements_display_window CHECK (ends_at IS NULL OR ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcements_window ON announcements(starts_at, ends_at);
+CREATE INDEX IF NOT EXISTS idx_announcements_contest
+    ON announcements(contest_id) WHERE contest_id IS NOT NULL;
+
+-- Trigger for updated_at
+DROP TRIGGER IF EXISTS update_announcements_updated_at ON announcements;
+CREATE TRIGGER update_announcements_updated_at
+    BEFORE UPDATE ON announcements
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
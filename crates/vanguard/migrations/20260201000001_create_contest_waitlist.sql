@@ -0,0 +1,14 @@
+-- Migration: Contest waitlist
+-- Users who tried to register for a full contest. When a seat frees up the
+-- earliest entry is moved into contest_participants.
+
+CREATE TABLE IF NOT EXISTS contest_waitlist (
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (contest_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contest_waitlist_order
+    ON contest_waitlist(contest_id, joined_at, user_id);
+CREATE INDEX IF NOT EXISTS idx_contest_waitlist_user ON contest_waitlist(user_id);
@@ -0,0 +1,24 @@
+-- Migration: Versioned problem binaries
+-- Every generator/checker upload is kept as `{kind}.v{version}` next to the
+-- active `generator`/`checker` file, with its checksum, so a bad upload can
+-- be rolled back. `problems.generator_version` and `problems.checker_version`
+-- name the active version of each binary.
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS checker_version INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS problem_binary_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('generator', 'checker')),
+    version INTEGER NOT NULL,
+    checksum VARCHAR(64) NOT NULL,  -- SHA-256, hex
+    size_bytes BIGINT NOT NULL,
+    file_path TEXT NOT NULL,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (problem_id, kind, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_problem_binary_versions_uploaded_by
+    ON problem_binary_versions(uploaded_by) WHERE uploaded_by IS NOT NULL;
@@ -0,0 +1,25 @@
+-- Migration: Upload scan results
+-- One row per scanned problem binary or ZIP submission. `action` records
+-- whether the upload was let through (clean, or audit-only mode) or rejected.
+-- Rejected submissions are never stored, so `target_id` has no foreign key.
+
+CREATE TABLE IF NOT EXISTS upload_scans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    target_type VARCHAR(16) NOT NULL
+        CHECK (target_type IN ('generator', 'checker', 'submission')),
+    target_id UUID NOT NULL,
+    scanner VARCHAR(32) NOT NULL,
+    status VARCHAR(16) NOT NULL CHECK (status IN ('clean', 'infected', 'error')),
+    detail TEXT,  -- Matched signature, or why the scan failed
+    checksum VARCHAR(64) NOT NULL,  -- SHA-256, hex
+    size_bytes BIGINT NOT NULL,
+    action VARCHAR(16) NOT NULL CHECK (action IN ('allowed', 'rejected')),
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_upload_scans_created ON upload_scans(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_upload_scans_flagged
+    ON upload_scans(created_at DESC) WHERE status <> 'clean';
+CREATE INDEX IF NOT EXISTS idx_upload_scans_uploaded_by
+    ON upload_scans(uploaded_by) WHERE uploaded_by IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Migration: Network traffic per test case
+-- Bytes sent and received by a run of a network_allowed problem, measured on
+-- the judge's egress namespace. NULL for runs without network access.
+
+ALTER TABLE submission_results
+    ADD COLUMN IF NOT EXISTS network_bytes BIGINT;
@@ -0,0 +1,14 @@
+-- Migration: CPU instruction counts
+-- Judges with perf counters record the user-space instructions retired by
+-- each test run, and their sum per submission. Problems with
+-- rank_by_instructions are ranked by that sum instead of max_time_ms in
+-- performance-scored contests.
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS rank_by_instructions BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS total_instructions BIGINT;
+
+ALTER TABLE submission_results
+    ADD COLUMN IF NOT EXISTS instructions BIGINT;
@@ -0,0 +1,108 @@
+-- Per-user statistics behind GET /api/v1/users/{id}/stats, kept current
+-- incrementally by a trigger on submissions so the endpoint never scans a
+-- user's submission history. The trigger sees every writer (Vanguard,
+-- Sisyphus, Minos, rejudges, account merges) without each having to
+-- remember to update the stats.
+
+-- Submissions and accepted submissions per user and UTC day
+CREATE TABLE IF NOT EXISTS user_activity_days (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    submissions INTEGER NOT NULL DEFAULT 0,
+    accepted INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, day)
+);
+
+-- Problems a user has solved, once per language, with the first accepted
+-- submission's time
+CREATE TABLE IF NOT EXISTS user_solves (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    language VARCHAR(32) NOT NULL,
+    first_solved_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, problem_id, language)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_solves_problem ON user_solves(problem_id);
+
+-- Backfill from existing submissions
+INSERT INTO user_activity_days (user_id, day, submissions, accepted)
+SELECT user_id, (submitted_at AT TIME ZONE 'UTC')::date, COUNT(*),
+       COUNT(*) FILTER (WHERE status = 'accepted')
+FROM submissions
+GROUP BY 1, 2
+ON CONFLICT (user_id, day) DO NOTHING;
+
+INSERT INTO user_solves (user_id, problem_id, language, first_solved_at)
+SELECT user_id, problem_id, COALESCE(language, 'unknown'), MIN(submitted_at)
+FROM submissions
+WHERE status = 'accepted'
+GROUP BY 1, 2, 3
+ON CONFLICT (user_id, problem_id, language) DO NOTHING;
+
+-- Retract what the old row contributed, then add the new row
+CREATE OR REPLACE FUNCTION track_user_stats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+        AND OLD.status IS NOT DISTINCT FROM NEW.status
+        AND OLD.user_id = NEW.user_id
+        AND OLD.problem_id = NEW.problem_id
+        AND OLD.language IS NOT DISTINCT FROM NEW.language
+        AND OLD.submitted_at = NEW.submitted_at
+    THEN
+        RETURN NULL;
+    END IF;
+
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE user_activity_days
+        SET submissions = submissions - 1,
+            accepted = accepted - (OLD.status = 'accepted')::int
+        WHERE user_id = OLD.user_id
+          AND day = (OLD.submitted_at AT TIME ZONE 'UTC')::date;
+
+        IF OLD.status = 'accepted' THEN
+            DELETE FROM user_solves
+            WHERE user_id = OLD.user_id AND problem_id = OLD.problem_id
+              AND language = COALESCE(OLD.language, 'unknown');
+
+            -- Another accepted submission may still solve it. Skipped while
+            -- the user or problem itself is being deleted.
+            INSERT INTO user_solves (user_id, problem_id, language, first_solved_at)
+            SELECT OLD.user_id, OLD.problem_id, COALESCE(OLD.language, 'unknown'), MIN(s.submitted_at)
+            FROM submissions s
+            WHERE s.user_id = OLD.user_id AND s.problem_id = OLD.problem_id
+              AND COALESCE(s.language, 'unknown') = COALESCE(OLD.language, 'unknown')
+              AND s.status = 'accepted' AND s.id <> OLD.id
+              AND EXISTS (SELECT 1 FROM users WHERE id = OLD.user_id)
+              AND EXISTS (SELECT 1 FROM problems WHERE id = OLD.problem_id)
+            HAVING COUNT(*) > 0;
+        END IF;
+    END IF;
+
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        INSERT INTO user_activity_days (user_id, day, submissions, accepted)
+        VALUES (NEW.user_id, (NEW.submitted_at AT TIME ZONE 'UTC')::date, 1,
+                (NEW.status = 'accepted')::int)
+        ON CONFLICT (user_id, day) DO UPDATE SET
+            submissions = user_activity_days.submissions + 1,
+            accepted = user_activity_days.accepted + EXCLUDED.accepted;
+
+        IF NEW.status = 'accepted' THEN
+            INSERT INTO user_solves (user_id, problem_id, language, first_solved_at)
+            VALUES (NEW.user_id, NEW.problem_id, COALESCE(NEW.language, 'unknown'), NEW.submitted_at)
+            ON CONFLICT (user_id, problem_id, language) DO UPDATE SET
+                first_solved_at = LEAST(user_solves.first_solved_at, EXCLUDED.first_solved_at);
+        END IF;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS track_user_stats ON submissions;
+CREATE TRIGGER track_user_stats
+    AFTER INSERT OR DELETE OR UPDATE OF status, user_id, problem_id, language, submitted_at
+    ON submissions
+    FOR EACH ROW
+    EXECUTE FUNCTION track_user_stats();
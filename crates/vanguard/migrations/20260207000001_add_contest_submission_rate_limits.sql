@@ -0,0 +1,14 @@
+-- Migration: Per-contest submission rate limits
+-- While a contest runs, organizers may replace the global submission rate
+-- limit with their own hourly limit per user, and cap how many times a user
+-- may submit to each problem. Enforced by Vanguard's submission rate-limit
+-- middleware with contest-scoped Redis keys. NULL keeps the global default.
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS submissions_per_hour INTEGER
+    CHECK (submissions_per_hour >= 1 AND submissions_per_hour <= 10000),
+    ADD COLUMN IF NOT EXISTS max_submissions_per_problem INTEGER
+    CHECK (max_submissions_per_problem >= 1 AND max_submissions_per_problem <= 10000);
+
+COMMENT ON COLUMN contests.submissions_per_hour IS 'Submissions per hour per user during the contest (NULL = global default)';
+COMMENT ON COLUMN contests.max_submissions_per_problem IS 'Submissions per user to each problem during the contest (NULL = unlimited)';
@@ -0,0 +1,53 @@
+-- Migration: Roles and permissions
+-- users.role names a row of roles; what the role may do is the set of
+-- role_permissions rows, checked by Vanguard's policy layer instead of
+-- comparing role names. Built-in roles cannot be deleted; admins may add
+-- custom ones (e.g. a moderator who answers clarifications everywhere but
+-- cannot create contests).
+
+CREATE TABLE IF NOT EXISTS roles (
+    name VARCHAR(20) PRIMARY KEY CHECK (name ~ '^[a-z][a-z0-9_-]*$'),
+    description TEXT,
+    is_builtin BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role VARCHAR(20) NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    permission VARCHAR(32) NOT NULL CHECK (permission IN (
+        'manage_platform', 'create_contests', 'create_problems', 'create_sheets',
+        'create_organizations', 'view_private_content', 'moderate_contests',
+        'answer_clarifications'
+    )),
+    PRIMARY KEY (role, permission)
+);
+
+INSERT INTO roles (name, description, is_builtin) VALUES
+    ('admin', 'Runs the platform', TRUE),
+    ('organizer', 'Hosts contests and writes problems', TRUE),
+    ('participant', 'Default role of registered users', TRUE),
+    ('spectator', 'Watches contests without competing', TRUE)
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role, permission)
+SELECT 'admin', permission
+FROM unnest(ARRAY[
+    'manage_platform', 'create_contests', 'create_problems', 'create_sheets',
+    'create_organizations', 'view_private_content', 'moderate_contests',
+    'answer_clarifications'
+]) AS permission
+UNION ALL
+SELECT 'organizer', permission
+FROM unnest(ARRAY[
+    'create_contests', 'create_problems', 'create_sheets', 'create_organizations'
+]) AS permission
+ON CONFLICT (role, permission) DO NOTHING;
+
+-- Any other role already assigned becomes a custom role without permissions
+INSERT INTO roles (name)
+SELECT DISTINCT role FROM users
+ON CONFLICT (name) DO NOTHING;
+
+ALTER TABLE users
+    ADD CONSTRAINT users_role_fkey FOREIGN KEY (role) REFERENCES roles(name);
@@ -0,0 +1,8 @@
+-- Migration: Public scoreboard feed
+-- Organizers of onsite events may publish an unauthenticated JSON feed of
+-- the standings for projector displays. Off by default.
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS scoreboard_feed BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN contests.scoreboard_feed IS 'Serve GET /contests/{id}/scoreboard without authentication';
@@ -0,0 +1,26 @@
+-- Migration: Validation failed status
+-- Vanguard now only checks a ZIP upload's size and magic bytes; Sisyphus
+-- validates the archive's entries (paths, symlinks, expansion ratio,
+-- required scripts) while extracting it and marks rejected archives
+-- `validation_failed`, with the reason in `compilation_log`.
+
+ALTER TABLE submissions DROP CONSTRAINT IF EXISTS submissions_status_check;
+
+ALTER TABLE submissions ADD CONSTRAINT submissions_status_check CHECK (status IN (
+    'pending',            -- Waiting in queue
+    'compiling',          -- Being compiled by Sisyphus
+    'compiled',           -- Compilation successful, waiting for judge
+    'queue_pending',      -- Compiled, but waiting for problem binaries (generator/checker)
+    'judging',            -- Being judged by Minos
+    'accepted',           -- All test cases passed
+    'wrong_answer',       -- Output mismatch
+    'presentation_error', -- Right answer, wrong format
+    'time_limit',         -- Exceeded time limit
+    'memory_limit',       -- Exceeded memory limit
+    'output_limit',       -- Wrote more than the output limit
+    'idleness_limit',     -- Neither used CPU nor wrote output for too long
+    'runtime_error',      -- Runtime crash/error
+    'compilation_error',  -- Failed to compile
+    'validation_failed',  -- ZIP archive rejected before compiling
+    'system_error'        -- Internal system error
+));
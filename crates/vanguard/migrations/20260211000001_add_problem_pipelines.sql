@@ -0,0 +1,18 @@
+-- Migration: Per-problem judging pipelines
+-- `pipeline` holds an optional list of steps (see olympus_rules::pipeline)
+-- that Minos runs for every test instead of the built-in generate, run and
+-- check sequence. Pipelines may run a reference solution, uploaded and
+-- versioned like the generator and checker.
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS pipeline JSONB,
+    ADD COLUMN IF NOT EXISTS reference_path VARCHAR(512),
+    ADD COLUMN IF NOT EXISTS reference_version INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE problem_binary_versions DROP CONSTRAINT IF EXISTS problem_binary_versions_kind_check;
+ALTER TABLE problem_binary_versions ADD CONSTRAINT problem_binary_versions_kind_check
+    CHECK (kind IN ('generator', 'checker', 'reference'));
+
+ALTER TABLE upload_scans DROP CONSTRAINT IF EXISTS upload_scans_target_type_check;
+ALTER TABLE upload_scans ADD CONSTRAINT upload_scans_target_type_check
+    CHECK (target_type IN ('generator', 'checker', 'reference', 'submission'));
@@ -0,0 +1,71 @@
+-- Migration: In-app notifications
+-- Each row is one entry in a user's notification feed. `email` records
+-- whether the user also wants this kind of event by email (from
+-- `notification_preferences`, in-app only by default); `emailed_at` is set
+-- once it has been delivered.
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(32) NOT NULL
+        CHECK (kind IN ('clarification_answered', 'verdict_ready', 'contest_starting')),
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL DEFAULT '',
+    contest_id UUID REFERENCES contests(id) ON DELETE CASCADE,
+    submission_id UUID REFERENCES submissions(id) ON DELETE CASCADE,
+    email BOOLEAN NOT NULL DEFAULT FALSE,
+    emailed_at TIMESTAMPTZ,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread
+    ON notifications(user_id) WHERE read_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_notifications_email_pending
+    ON notifications(created_at) WHERE email AND emailed_at IS NULL;
+
+-- A participant is told a contest is starting once
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_contest_starting
+    ON notifications(user_id, contest_id) WHERE kind = 'contest_starting';
+
+-- Which events a user also wants by email. Kinds without a row are in-app only.
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(32) NOT NULL
+        CHECK (kind IN ('clarification_answered', 'verdict_ready', 'contest_starting')),
+    email BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, kind)
+);
+
+DROP TRIGGER IF EXISTS update_notification_preferences_updated_at ON notification_preferences;
+CREATE TRIGGER update_notification_preferences_updated_at
+    BEFORE UPDATE ON notification_preferences
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Add a notification to a user's feed, flagged for email when the user
+-- asked for this kind of event by email. Used by every service that emits
+-- notifications so the preference lookup lives in one place. A duplicate of
+-- a one-off notification (see the unique indexes) is skipped.
+CREATE OR REPLACE FUNCTION notify_user(
+    p_user_id UUID,
+    p_kind VARCHAR,
+    p_title VARCHAR,
+    p_body TEXT,
+    p_contest_id UUID,
+    p_submission_id UUID
+)
+RETURNS VOID AS $$
+    INSERT INTO notifications (user_id, kind, title, body, contest_id, submission_id, email)
+    VALUES (
+        p_user_id, p_kind, p_title, p_body, p_contest_id, p_submission_id,
+        COALESCE(
+            (SELECT email FROM notification_preferences
+             WHERE user_id = p_user_id AND kind = p_kind),
+            FALSE
+        )
+    )
+    ON CONFLICT DO NOTHING;
+$$ LANGUAGE sql;
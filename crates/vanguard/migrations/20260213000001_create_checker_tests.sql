@@ -0,0 +1,47 @@
+-- Migration: Checker self-tests
+-- Setters keep a suite of (input, output, expected verdict) cases for a
+-- problem's checker. Running the suite is queued on the regeneration
+-- stream; a Minos worker runs the checker on every case in the sandbox and
+-- records what it said, so broken checkers are caught before a contest.
+
+CREATE TABLE IF NOT EXISTS checker_tests (
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    test_number INTEGER NOT NULL,
+    input TEXT NOT NULL,
+    -- Contestant output the checker is given
+    output TEXT NOT NULL,
+    -- Jury answer; the input is passed instead when NULL, as in judging
+    answer TEXT,
+    expected_verdict VARCHAR(20) NOT NULL
+        CHECK (expected_verdict IN ('accepted', 'wrong_answer', 'presentation_error',
+                                    'partial_credit', 'fail')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (problem_id, test_number)
+);
+
+CREATE TABLE IF NOT EXISTS checker_test_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    -- Checker version the suite was run against
+    checker_version INTEGER NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'completed', 'failed')),
+    total INTEGER NOT NULL,
+    mismatches INTEGER,
+    error TEXT,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_checker_test_runs_problem
+    ON checker_test_runs(problem_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS checker_test_results (
+    run_id UUID NOT NULL REFERENCES checker_test_runs(id) ON DELETE CASCADE,
+    test_number INTEGER NOT NULL,
+    expected_verdict VARCHAR(20) NOT NULL,
+    verdict VARCHAR(20) NOT NULL,
+    checker_output TEXT,
+    PRIMARY KEY (run_id, test_number)
+);
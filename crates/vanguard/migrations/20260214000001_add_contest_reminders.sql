@@ -0,0 +1,49 @@
+-- Migration: Contest reminders and email delivery tracking
+-- Horus sends `contest_starting` reminders at several lead times (24 hours
+-- and 1 hour before the start by default). `reminder_lead_mins` records which
+-- reminder a notification is, and the unique index now allows one per lead.
+-- Existing reminders were all sent an hour ahead.
+
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS reminder_lead_mins INTEGER,
+    ADD COLUMN IF NOT EXISTS email_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS email_error TEXT;
+
+UPDATE notifications SET reminder_lead_mins = 60
+WHERE kind = 'contest_starting' AND reminder_lead_mins IS NULL;
+
+DROP INDEX IF EXISTS idx_notifications_contest_starting;
+
+-- A participant gets each reminder of a contest once
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_contest_reminder
+    ON notifications(user_id, contest_id, reminder_lead_mins)
+    WHERE kind = 'contest_starting';
+
+-- Same as before, with the reminder lead for `contest_starting`. Dropped
+-- first so calls with six arguments don't find two candidates.
+DROP FUNCTION IF EXISTS notify_user(UUID, VARCHAR, VARCHAR, TEXT, UUID, UUID);
+
+CREATE OR REPLACE FUNCTION notify_user(
+    p_user_id UUID,
+    p_kind VARCHAR,
+    p_title VARCHAR,
+    p_body TEXT,
+    p_contest_id UUID,
+    p_submission_id UUID,
+    p_reminder_lead_mins INTEGER DEFAULT NULL
+)
+RETURNS VOID AS $$
+    INSERT INTO notifications (
+        user_id, kind, title, body, contest_id, submission_id, reminder_lead_mins, email
+    )
+    VALUES (
+        p_user_id, p_kind, p_title, p_body, p_contest_id, p_submission_id,
+        p_reminder_lead_mins,
+        COALESCE(
+            (SELECT email FROM notification_preferences
+             WHERE user_id = p_user_id AND kind = p_kind),
+            FALSE
+        )
+    )
+    ON CONFLICT DO NOTHING;
+$$ LANGUAGE sql;
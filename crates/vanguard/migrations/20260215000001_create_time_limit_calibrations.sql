@@ -0,0 +1,58 @@
+-- Migration: Time limit calibration from reference solutions
+-- Setters mark compiled submissions to a problem as its reference solutions:
+-- `model` solutions must pass with a safety margin, `slow` ones must not.
+-- A calibration runs all of them on a judge worker under a raised time limit
+-- and suggests a limit from the measured times.
+
+CREATE TABLE IF NOT EXISTS problem_reference_solutions (
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('model', 'slow')),
+    marked_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (problem_id, submission_id)
+);
+
+-- One calibration run; the suggestion and the worker that measured it are
+-- filled in once the run completes
+CREATE TABLE IF NOT EXISTS time_limit_calibrations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'completed', 'failed')),
+    -- Safety margin over the slowest model solution
+    multiplier DOUBLE PRECISION NOT NULL,
+    -- Time limit the solutions run under
+    ceiling_ms INTEGER NOT NULL,
+    current_time_limit_ms INTEGER NOT NULL,
+    model_time_ms INTEGER,
+    slow_time_ms INTEGER,
+    suggested_time_limit_ms INTEGER,
+    -- clear: the margin fits below the slow solutions; tight: it does not
+    -- and the suggestion splits the gap; none: a slow solution is as fast
+    -- as a model one
+    separation VARCHAR(16) CHECK (separation IN ('clear', 'tight', 'none')),
+    worker_id VARCHAR(128),
+    worker_speed_factor DOUBLE PRECISION,
+    error TEXT,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_limit_calibrations_problem
+    ON time_limit_calibrations(problem_id, created_at DESC);
+
+-- How each reference solution did in a calibration
+CREATE TABLE IF NOT EXISTS time_limit_calibration_results (
+    calibration_id UUID NOT NULL REFERENCES time_limit_calibrations(id) ON DELETE CASCADE,
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('model', 'slow')),
+    verdict VARCHAR(32) NOT NULL,
+    max_time_ms INTEGER NOT NULL,
+    max_memory_kb INTEGER NOT NULL,
+    passed INTEGER NOT NULL,
+    total INTEGER NOT NULL,
+    error TEXT,
+    PRIMARY KEY (calibration_id, submission_id)
+);
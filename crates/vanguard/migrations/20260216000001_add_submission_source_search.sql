@@ -0,0 +1,10 @@
+-- Migration: Submission source search
+-- Admins search submission sources for a given string (e.g. a leaked
+-- solution) during cheating investigations. A trigram index lets substring
+-- LIKE/ILIKE patterns use an index instead of scanning every source.
+-- Archive (ZIP) submissions have no `source_code` and are not searched.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_source_trgm
+    ON submissions USING gin (source_code gin_trgm_ops);
@@ -0,0 +1,45 @@
+-- Migration: Runtime image digests and contest pins
+-- A runtime's image tag can move (`gcc:latest` is rebuilt upstream), so
+-- Sisyphus compiles from an exact digest instead. The first compile after a
+-- runtime is registered or its image changes records the digest the tag
+-- resolved to; admins roll it forward explicitly after that. Contests pin
+-- the digest each runtime had when it was first used in them, so updates
+-- mid-contest don't change how submissions compile.
+
+-- Current digest (`sha256:...`); NULL until Sisyphus resolves the tag
+ALTER TABLE runtimes
+    ADD COLUMN IF NOT EXISTS image_digest VARCHAR(80),
+    ADD COLUMN IF NOT EXISTS digest_updated_at TIMESTAMPTZ;
+
+-- Digest a submission was compiled with (NULL for the generic image and for
+-- images without a registry digest)
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS image_digest VARCHAR(80);
+
+CREATE TABLE IF NOT EXISTS contest_runtime_pins (
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    runtime_name VARCHAR(32) NOT NULL REFERENCES runtimes(name) ON DELETE CASCADE,
+    -- Image as registered when pinned, so later image changes don't apply
+    image VARCHAR(255) NOT NULL,
+    image_digest VARCHAR(80) NOT NULL,
+    -- NULL when Sisyphus pinned it on first use
+    pinned_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    pinned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (contest_id, runtime_name)
+);
+
+-- Every digest change, for runtimes (contest_id NULL) and contest pins
+CREATE TABLE IF NOT EXISTS runtime_image_rollouts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    runtime_name VARCHAR(32) NOT NULL,
+    contest_id UUID REFERENCES contests(id) ON DELETE CASCADE,
+    image VARCHAR(255) NOT NULL,
+    previous_digest VARCHAR(80),
+    image_digest VARCHAR(80) NOT NULL,
+    -- NULL when Sisyphus recorded a newly resolved tag
+    rolled_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_runtime_image_rollouts_runtime
+    ON runtime_image_rollouts(runtime_name, created_at DESC);
@@ -0,0 +1,8 @@
+-- Migration: Staggered problem release within contests
+-- A contest problem with `visible_from` stays hidden from participants (not
+-- listed, not submittable) until that time, e.g. one problem per day.
+-- NULL releases it with the contest start. Owners, collaborators and
+-- moderators always see every problem.
+
+ALTER TABLE contest_problems
+    ADD COLUMN IF NOT EXISTS visible_from TIMESTAMPTZ;
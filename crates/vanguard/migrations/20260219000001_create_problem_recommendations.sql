@@ -0,0 +1,34 @@
+-- Migration: Practice problem recommendations
+-- Horus periodically rates each recently active user from the difficulty of
+-- the problems they solved and ranks unsolved public problems for them by
+-- how close they are to that level and how well their tags fill gaps in the
+-- user's solves. GET /api/v1/problems/recommended reads the result.
+
+-- Practice rating behind a user's recommendations
+CREATE TABLE IF NOT EXISTS user_practice_profiles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    rating INTEGER NOT NULL,
+    problems_solved INTEGER NOT NULL,
+    -- Difficulty the recommendations aim at, one step of stretch above the rating
+    target_difficulty VARCHAR(20) NOT NULL
+        CHECK (target_difficulty IN ('easy', 'medium', 'hard', 'expert')),
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Replaced as a whole for a user on every run
+CREATE TABLE IF NOT EXISTS problem_recommendations (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    -- next_step: at the user's level; weak_tag: practices a tag they have
+    -- rarely solved; favorite_tag: a tag they solve often
+    reason VARCHAR(16) NOT NULL CHECK (reason IN ('next_step', 'weak_tag', 'favorite_tag')),
+    -- The tag a tag reason refers to
+    tag TEXT,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, problem_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_problem_recommendations_rank
+    ON problem_recommendations(user_id, rank);
@@ -0,0 +1,31 @@
+-- Migration: Contest blog posts
+-- Markdown posts organizers attach to a contest: announcements before it,
+-- editorials and wrap-ups after it. A post is public between `visible_from`
+-- and `visible_until`; without `visible_from` it is a draft only the
+-- contest's staff can read.
+
+CREATE TABLE IF NOT EXISTS contest_posts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    kind VARCHAR(16) NOT NULL DEFAULT 'announcement'
+        CHECK (kind IN ('announcement', 'editorial', 'general')),
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    visible_from TIMESTAMPTZ,
+    visible_until TIMESTAMPTZ,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT contest_posts_visibility_window
+        CHECK (visible_until IS NULL OR visible_from IS NULL OR visible_until > visible_from)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contest_posts_contest
+    ON contest_posts(contest_id, visible_from DESC);
+
+-- Trigger for updated_at
+DROP TRIGGER IF EXISTS update_contest_posts_updated_at ON contest_posts;
+CREATE TRIGGER update_contest_posts_updated_at
+    BEFORE UPDATE ON contest_posts
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
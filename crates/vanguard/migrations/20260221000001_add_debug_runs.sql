@@ -0,0 +1,12 @@
+-- Migration: Submission debug runs
+-- A debug run is a test reproduction that also keeps what the program
+-- printed and the checker's comment, each cut to capture_limit_bytes, for
+-- investigating disputed verdicts.
+
+ALTER TABLE test_reproductions
+    ADD COLUMN IF NOT EXISTS capture_limit_bytes INTEGER,
+    ADD COLUMN IF NOT EXISTS stdout TEXT,
+    ADD COLUMN IF NOT EXISTS stderr TEXT,
+    ADD COLUMN IF NOT EXISTS program_output TEXT,
+    ADD COLUMN IF NOT EXISTS checker_log TEXT,
+    ADD COLUMN IF NOT EXISTS output_truncated BOOLEAN;
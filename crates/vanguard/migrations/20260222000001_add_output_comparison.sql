@@ -0,0 +1,10 @@
+-- Migration: Built-in output comparison
+-- A problem with output_comparison set has no checker: the submission's
+-- output is compared with the answer its reference binary writes, exactly,
+-- token by token, with a float tolerance, or ignoring case. NULL keeps the
+-- custom checker.
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS output_comparison VARCHAR(20)
+        CHECK (output_comparison IN ('exact', 'tokens', 'float', 'case_insensitive')),
+    ADD COLUMN IF NOT EXISTS comparison_epsilon DOUBLE PRECISION NOT NULL DEFAULT 1e-6;
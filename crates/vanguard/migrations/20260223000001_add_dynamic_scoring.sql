@@ -0,0 +1,16 @@
+-- Migration: Add dynamic (solver-count based) scoring for contests
+-- In 'dynamic' contests a problem is worth its full points to the first
+-- solver and less with every further solver, down to dynamic_min_percent of
+-- its points once dynamic_decay more participants have solved it. Minos
+-- rescores every accepted submission to the problem on each verdict.
+
+ALTER TABLE contests DROP CONSTRAINT IF EXISTS contests_scoring_type_check;
+ALTER TABLE contests
+    ADD CONSTRAINT contests_scoring_type_check
+    CHECK (scoring_type IN ('icpc', 'ioi', 'custom', 'performance', 'dynamic'));
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS dynamic_min_percent INTEGER NOT NULL DEFAULT 30
+        CHECK (dynamic_min_percent BETWEEN 0 AND 100),
+    ADD COLUMN IF NOT EXISTS dynamic_decay INTEGER NOT NULL DEFAULT 20
+        CHECK (dynamic_decay >= 1);
@@ -0,0 +1,28 @@
+-- Migration: Per-user resource quotas
+-- Site admins can cap how many problems a user owns, how many bytes their
+-- problems' binaries and test data take, and how many rejudges they may
+-- have running at once. NULL = no limit.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS max_problems INTEGER CHECK (max_problems >= 0),
+    ADD COLUMN IF NOT EXISTS max_storage_bytes BIGINT CHECK (max_storage_bytes > 0),
+    ADD COLUMN IF NOT EXISTS max_concurrent_rejudges INTEGER
+        CHECK (max_concurrent_rejudges > 0);
+
+-- A rejudge request; it runs while any of its submissions is unjudged
+CREATE TABLE IF NOT EXISTS rejudge_batches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    submission_count INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rejudge_batches_requested_by
+    ON rejudge_batches(requested_by, created_at DESC);
+
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS rejudge_batch_id UUID
+        REFERENCES rejudge_batches(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_rejudge_batch
+    ON submissions(rejudge_batch_id) WHERE rejudge_batch_id IS NOT NULL;
@@ -0,0 +1,44 @@
+-- Migration: Problem integrity error verdict
+-- Before judging, Minos compares the problem's active generator, checker
+-- and reference binaries with the SHA-256 recorded in
+-- `problem_binary_versions` when they were uploaded. A binary that is
+-- missing or no longer matches fails the submission with
+-- `problem_integrity_error`, the mismatch described in its first test
+-- result, instead of judging it with a damaged binary.
+
+ALTER TABLE submissions DROP CONSTRAINT IF EXISTS submissions_status_check;
+
+ALTER TABLE submissions ADD CONSTRAINT submissions_status_check CHECK (status IN (
+    'pending',                 -- Waiting in queue
+    'compiling',               -- Being compiled by Sisyphus
+    'compiled',                -- Compilation successful, waiting for judge
+    'queue_pending',           -- Compiled, but waiting for problem binaries (generator/checker)
+    'judging',                 -- Being judged by Minos
+    'accepted',                -- All test cases passed
+    'wrong_answer',            -- Output mismatch
+    'presentation_error',      -- Right answer, wrong format
+    'time_limit',              -- Exceeded time limit
+    'memory_limit',            -- Exceeded memory limit
+    'output_limit',            -- Wrote more than the output limit
+    'idleness_limit',          -- Neither used CPU nor wrote output for too long
+    'runtime_error',           -- Runtime crash/error
+    'compilation_error',       -- Failed to compile
+    'validation_failed',       -- ZIP archive rejected before compiling
+    'problem_integrity_error', -- A problem binary failed its checksum
+    'system_error'             -- Internal system error
+));
+
+ALTER TABLE submission_results DROP CONSTRAINT IF EXISTS submission_results_verdict_check;
+
+ALTER TABLE submission_results ADD CONSTRAINT submission_results_verdict_check CHECK (verdict IN (
+    'accepted',
+    'wrong_answer',
+    'presentation_error',
+    'time_limit',
+    'memory_limit',
+    'output_limit',
+    'idleness_limit',
+    'runtime_error',
+    'problem_integrity_error',
+    'system_error'
+));
@@ -0,0 +1,11 @@
+-- Migration: Mirror contests
+-- A mirror replays a past contest for a training group: the same problems
+-- and duration from a new start time, with its own leaderboard and never
+-- rated. `mirror_of` links it to the original contest (a mirror of a mirror
+-- links to the same original) so their statistics can be compared.
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS mirror_of UUID REFERENCES contests(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_contests_mirror_of
+    ON contests(mirror_of) WHERE mirror_of IS NOT NULL;
@@ -0,0 +1,32 @@
+-- Migration: Saved contest filters
+-- Users save contest list filter combinations under a name ("upcoming
+-- rated", "Div. 2") and apply them to `GET /contests` with
+-- `?saved_filter=<id>`, so every client gets the same results. `filters`
+-- holds the list query's filter parameters.
+
+CREATE TABLE IF NOT EXISTS saved_contest_filters (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    filters JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+DROP TRIGGER IF EXISTS update_saved_contest_filters_updated_at ON saved_contest_filters;
+CREATE TRIGGER update_saved_contest_filters_updated_at
+    BEFORE UPDATE ON saved_contest_filters
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- The combinations saved filters lean on: rated contests by start time,
+-- scoring type, and title search ("Div. 2")
+CREATE INDEX IF NOT EXISTS idx_contests_rated_start
+    ON contests(start_time DESC) WHERE is_rated;
+CREATE INDEX IF NOT EXISTS idx_contests_scoring_start
+    ON contests(scoring_type, start_time DESC);
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE INDEX IF NOT EXISTS idx_contests_title_trgm
+    ON contests USING gin (title gin_trgm_ops);
@@ -0,0 +1,11 @@
+-- Migration: Per-contest storage root
+-- Institutions with data residency requirements keep a contest's ZIP
+-- submissions, compiled artifacts and kept test outputs on a volume of
+-- their own. `storage_root` must be one of the directories listed in
+-- CONTEST_STORAGE_ROOTS and mirrors the layout of the shared data volume
+-- (`submissions/`, `binaries/users/`, `debug_outputs/`). NULL keeps the
+-- contest on the shared volume.
+
+ALTER TABLE contests
+    ADD COLUMN IF NOT EXISTS storage_root TEXT
+        CHECK (storage_root IS NULL OR storage_root LIKE '/%');
@@ -0,0 +1,27 @@
+-- Migration: Reference solution baselines on benchmarking leaderboards
+-- A performance-scored contest can show how the setter's model reference
+-- solution does on each problem. Minos judges it on the same workers as the
+-- participants, under the contest's limits, and its time and memory are
+-- listed beside the leaderboard without being ranked or scored.
+
+CREATE TABLE IF NOT EXISTS contest_problem_baselines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    -- The model reference solution that was judged
+    submission_id UUID NOT NULL REFERENCES submissions(id) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'completed', 'failed')),
+    verdict VARCHAR(32),
+    -- Normalized by the worker's speed factor for benchmark-scored problems
+    max_time_ms INTEGER,
+    max_memory_kb INTEGER,
+    total_instructions BIGINT,
+    worker_id VARCHAR(128),
+    worker_speed_factor DOUBLE PRECISION,
+    error TEXT,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    UNIQUE (contest_id, problem_id)
+);
@@ -0,0 +1,22 @@
+-- Migration: Per-test time and memory limits
+-- A test case or a subtask can override the problem's limits, e.g. a single
+-- huge stress test getting twice the time. A test's override wins over its
+-- subtask's; either replaces the problem's (and the contest's) limit. NULL
+-- inherits.
+
+ALTER TABLE test_cases
+    ADD COLUMN IF NOT EXISTS time_limit_ms INTEGER
+        CHECK (time_limit_ms BETWEEN 100 AND 60000),
+    ADD COLUMN IF NOT EXISTS memory_limit_kb INTEGER
+        CHECK (memory_limit_kb BETWEEN 16384 AND 2097152);
+
+ALTER TABLE problem_subtasks
+    ADD COLUMN IF NOT EXISTS time_limit_ms INTEGER
+        CHECK (time_limit_ms BETWEEN 100 AND 60000),
+    ADD COLUMN IF NOT EXISTS memory_limit_kb INTEGER
+        CHECK (memory_limit_kb BETWEEN 16384 AND 2097152);
+
+-- Limits each test actually ran under
+ALTER TABLE submission_results
+    ADD COLUMN IF NOT EXISTS time_limit_ms INTEGER,
+    ADD COLUMN IF NOT EXISTS memory_limit_kb INTEGER;
@@ -0,0 +1,25 @@
+-- Migration: Problem package backups
+-- Horus snapshots each problem whose package (metadata, test cases, subtasks,
+-- statements, binaries) changed into a versioned archive on the storage
+-- volume. Rows are not tied to `problems`, so backups outlive a deleted
+-- problem and an admin can restore it from them.
+
+CREATE TABLE IF NOT EXISTS problem_backups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    problem_id UUID NOT NULL,
+    -- Title when backed up, to find backups of deleted problems
+    problem_title VARCHAR(255) NOT NULL,
+    version INTEGER NOT NULL,
+    archive_path TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    checksum VARCHAR(64) NOT NULL,  -- SHA-256 of the archive, hex
+    -- SHA-256 of the package contents; unchanged packages are not backed up
+    fingerprint VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    restored_at TIMESTAMPTZ,
+    restored_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE (problem_id, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_problem_backups_created
+    ON problem_backups(created_at DESC);
@@ -0,0 +1,54 @@
+-- Migration: Account deletion
+-- A user asks for their account to be deleted; after a grace period, in
+-- which they can change their mind, Horus anonymizes it. An admin can
+-- anonymize an account at once. Anonymizing keeps the row, so contests,
+-- problems and standings the user took part in stay intact, but scrambles
+-- the username, replaces the email, locks the password and drops personal
+-- data. Submissions are kept without their client details, or deleted,
+-- depending on `ACCOUNT_DELETION_SUBMISSIONS`.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS deletion_requested_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deletion_scheduled_for TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS anonymized_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_deletion_scheduled
+    ON users(deletion_scheduled_for) WHERE deletion_scheduled_for IS NOT NULL;
+
+-- Anonymize a user. Returns the stored files of deleted ZIP submissions for
+-- the caller to remove.
+CREATE OR REPLACE FUNCTION anonymize_user(p_user_id UUID, p_delete_submissions BOOLEAN)
+RETURNS SETOF TEXT AS $$
+BEGIN
+    IF p_delete_submissions THEN
+        RETURN QUERY
+            WITH deleted AS (
+                DELETE FROM submissions WHERE user_id = p_user_id RETURNING file_path
+            )
+            SELECT file_path::TEXT FROM deleted WHERE file_path IS NOT NULL;
+    ELSE
+        UPDATE submissions SET ip_address = NULL, user_agent = NULL
+        WHERE user_id = p_user_id;
+    END IF;
+
+    DELETE FROM sessions WHERE user_id = p_user_id;
+    DELETE FROM notifications WHERE user_id = p_user_id;
+    DELETE FROM notification_preferences WHERE user_id = p_user_id;
+    DELETE FROM saved_contest_filters WHERE user_id = p_user_id;
+    DELETE FROM contest_telemetry_events WHERE user_id = p_user_id;
+    DELETE FROM problem_recommendations WHERE user_id = p_user_id;
+    DELETE FROM user_practice_profiles WHERE user_id = p_user_id;
+
+    UPDATE users
+    SET username = 'deleted_' || LEFT(REPLACE(id::TEXT, '-', ''), 12),
+        email = id::TEXT || '@deleted.invalid',
+        password_hash = '!',
+        display_name = NULL,
+        bio = NULL,
+        role = 'participant',
+        deletion_scheduled_for = NULL,
+        anonymized_at = NOW(),
+        updated_at = NOW()
+    WHERE id = p_user_id;
+END;
+$$ LANGUAGE plpgsql;
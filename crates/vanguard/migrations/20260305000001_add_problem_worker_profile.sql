@@ -0,0 +1,9 @@
+-- Worker profile a problem's submissions are judged on. Its run jobs go to
+-- the profile's streams, which only Minos instances started with that
+-- WORKER_PROFILE read.
+ALTER TABLE problems
+    ADD COLUMN worker_profile VARCHAR(32) NOT NULL DEFAULT 'standard'
+        CHECK (worker_profile ~ '^[a-z0-9_-]+$');
+
+CREATE INDEX idx_problems_worker_profile ON problems(worker_profile)
+    WHERE worker_profile <> 'standard';
@@ -0,0 +1,40 @@
+-- Migration: Certified final standings
+-- Once a contest is over, organizers certify its standings. Each
+-- certification stores the exact signed JSON document, its SHA-256 and
+-- the hash of the previous version, so a later rejudge or edit can only
+-- add a new version, never silently rewrite an earlier one.
+
+CREATE TABLE IF NOT EXISTS contest_final_standings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL CHECK (version > 0),
+    document TEXT NOT NULL,
+    content_hash VARCHAR(64) NOT NULL,
+    previous_hash VARCHAR(64),
+    signature TEXT NOT NULL,
+    signing_key_id VARCHAR(64) NOT NULL,
+    signing_algorithm VARCHAR(16) NOT NULL,
+    reason TEXT,
+    certified_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    certified_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (contest_id, version),
+    CHECK ((version = 1) = (previous_hash IS NULL))
+);
+
+-- Certified rows are never changed; only cascades from deleting the
+-- contest or the certifying user reach them
+CREATE OR REPLACE FUNCTION reject_final_standings_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF pg_trigger_depth() > 1 THEN
+        RETURN CASE WHEN TG_OP = 'DELETE' THEN OLD ELSE NEW END;
+    END IF;
+    RAISE EXCEPTION 'Certified standings cannot be changed';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS contest_final_standings_immutable ON contest_final_standings;
+CREATE TRIGGER contest_final_standings_immutable
+    BEFORE UPDATE OR DELETE ON contest_final_standings
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_final_standings_change();
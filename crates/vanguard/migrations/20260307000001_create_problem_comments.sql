@@ -0,0 +1,35 @@
+-- Migration: Problem discussion threads
+-- Threaded comments under a problem. Replies point at their parent and
+-- record their depth, so threads can be capped and fetched in one pass.
+-- Moderators hide comments (reversibly) instead of deleting them; authors
+-- deleting their own comment keep the placeholder so replies stay in
+-- place. Discussions lock automatically while a contest containing the
+-- problem runs, and problem owners can lock them by hand.
+
+CREATE TABLE IF NOT EXISTS problem_comments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    parent_id UUID REFERENCES problem_comments(id) ON DELETE CASCADE,
+    depth INTEGER NOT NULL DEFAULT 0 CHECK (depth >= 0),
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    hidden_at TIMESTAMPTZ,
+    hidden_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    hidden_reason TEXT,
+    deleted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Last change by the author; moderation does not count
+    edited_at TIMESTAMPTZ,
+    CHECK ((parent_id IS NULL) = (depth = 0))
+);
+
+CREATE INDEX IF NOT EXISTS idx_problem_comments_threads
+    ON problem_comments(problem_id, created_at DESC) WHERE parent_id IS NULL;
+CREATE INDEX IF NOT EXISTS idx_problem_comments_parent
+    ON problem_comments(parent_id, created_at);
+-- Duplicate and flood checks look up an author's recent comments
+CREATE INDEX IF NOT EXISTS idx_problem_comments_author
+    ON problem_comments(author_id, created_at DESC);
+
+ALTER TABLE problems
+    ADD COLUMN IF NOT EXISTS discussion_locked BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,20 @@
+-- Migration: Pre-start test submissions
+-- Collaborators with can_test_solutions (and the contest owner) can submit
+-- to a contest before it starts to check its problems. Those submissions
+-- are judged as usual but flagged `is_test`: they stay out of the standings
+-- and statistics and are visible to the contest team only.
+
+ALTER TABLE contest_collaborators
+    ADD COLUMN IF NOT EXISTS can_test_solutions BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE contest_collaborators SET can_test_solutions = true
+WHERE role IN ('co-owner', 'co-setter', 'tester');
+
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS is_test BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE submissions
+    ADD CONSTRAINT submissions_test_not_practice CHECK (NOT (is_test AND is_practice));
+
+CREATE INDEX IF NOT EXISTS idx_submissions_contest_test
+    ON submissions(contest_id) WHERE is_test;
@@ -0,0 +1,35 @@
+-- Migration: Runtime toolchain versions
+-- Each runtime can name a command that prints its compiler or interpreter
+-- version (`g++ --version`). Sisyphus runs it in the runtime's image the
+-- first time it compiles with a digest and keeps the first line it prints.
+-- The version belongs to the digest, so contests pinned to an older digest
+-- keep reporting the version they compile with. Every submission records
+-- the version it was compiled with.
+
+ALTER TABLE runtimes
+    ADD COLUMN IF NOT EXISTS version_command TEXT;
+
+UPDATE runtimes SET version_command = CASE name
+        WHEN 'cpp' THEN 'g++ --version'
+        WHEN 'c' THEN 'gcc --version'
+        WHEN 'rust' THEN 'rustc --version'
+        WHEN 'go' THEN 'go version'
+        WHEN 'python' THEN 'python3 --version'
+        WHEN 'zig' THEN 'zig version'
+    END
+WHERE version_command IS NULL
+  AND name IN ('cpp', 'c', 'rust', 'go', 'python', 'zig');
+
+CREATE TABLE IF NOT EXISTS runtime_versions (
+    runtime_name VARCHAR(32) NOT NULL REFERENCES runtimes(name) ON DELETE CASCADE,
+    image_digest VARCHAR(80) NOT NULL,
+    -- Command the version was read with; a changed command is run again
+    version_command TEXT NOT NULL,
+    version VARCHAR(200) NOT NULL,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (runtime_name, image_digest)
+);
+
+-- Version a submission was compiled with (NULL without a version command)
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS runtime_version VARCHAR(200);
@@ -0,0 +1,45 @@
+-- Migration: Sample runs
+-- Contestants can run their code on a problem's sample before submitting.
+-- A run is compiled by Sisyphus and executed by Minos on streams of their
+-- own, so it never waits behind judging, and it is never scored.
+
+CREATE TABLE IF NOT EXISTS sample_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    problem_id UUID NOT NULL REFERENCES problems(id) ON DELETE CASCADE,
+    -- Contest whose limits and language list apply (NULL for standalone)
+    contest_id UUID REFERENCES contests(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    language VARCHAR(32) NOT NULL,
+    source_code TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'compiling', 'running', 'completed',
+                          'compilation_error', 'failed')),
+    compile_log TEXT,
+    -- Compiled artifact, removed once the run completes
+    binary_path TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_sample_runs_user
+    ON sample_runs(user_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS sample_run_results (
+    run_id UUID NOT NULL REFERENCES sample_runs(id) ON DELETE CASCADE,
+    sample_number INTEGER NOT NULL,
+    verdict VARCHAR(30) NOT NULL,
+    time_ms BIGINT,
+    memory_kb BIGINT,
+    -- Comparison comment or runtime error
+    message TEXT,
+    -- What the program wrote, cut at the capture limit
+    output TEXT,
+    stderr TEXT,
+    output_truncated BOOLEAN NOT NULL DEFAULT false,
+    -- First line where the output differs from the expected one
+    diff_line INTEGER,
+    diff_expected TEXT,
+    diff_actual TEXT,
+    PRIMARY KEY (run_id, sample_number)
+);
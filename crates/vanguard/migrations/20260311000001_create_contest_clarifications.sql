@@ -0,0 +1,21 @@
+-- Migration: Contest clarifications
+-- Participants ask about a contest (or one of its problems) while it runs;
+-- staff with `can_answer_clarifications` (or a role granting
+-- `answer_clarifications`) answer them. An answer is private to the asker
+-- unless it is made public to every participant.
+
+CREATE TABLE IF NOT EXISTS contest_clarifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contest_id UUID NOT NULL REFERENCES contests(id) ON DELETE CASCADE,
+    problem_id UUID REFERENCES problems(id) ON DELETE SET NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    question TEXT NOT NULL,
+    answer TEXT,
+    is_public BOOLEAN NOT NULL DEFAULT FALSE,
+    answered_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    answered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contest_clarifications_contest
+    ON contest_clarifications(contest_id, created_at DESC);
@@ -0,0 +1,14 @@
+-- Migration: Waitlist promotion notifications
+-- A user promoted from a contest waitlist into a free seat is told through
+-- the notification feed (and by email if they asked for it).
+
+ALTER TABLE notifications DROP CONSTRAINT IF EXISTS notifications_kind_check;
+ALTER TABLE notifications ADD CONSTRAINT notifications_kind_check
+    CHECK (kind IN ('clarification_answered', 'verdict_ready', 'contest_starting',
+                    'waitlist_promoted'));
+
+ALTER TABLE notification_preferences
+    DROP CONSTRAINT IF EXISTS notification_preferences_kind_check;
+ALTER TABLE notification_preferences ADD CONSTRAINT notification_preferences_kind_check
+    CHECK (kind IN ('clarification_answered', 'verdict_ready', 'contest_starting',
+                    'waitlist_promoted'));
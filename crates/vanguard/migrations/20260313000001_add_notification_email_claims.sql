@@ -0,0 +1,7 @@
+-- Migration: Email delivery claims
+-- Horus claims a notification (`email_claimed_at`) and commits before
+-- talking to the SMTP server, so no row lock is held during a send. A claim
+-- left by a crashed run expires and the notification is sent again.
+
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS email_claimed_at TIMESTAMPTZ;
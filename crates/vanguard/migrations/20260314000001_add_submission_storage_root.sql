@@ -0,0 +1,16 @@
+-- Migration: Storage root per submission
+-- A submission keeps the storage root its contest had when it was made, so
+-- its ZIP, compiled artifact and kept test outputs stay together (and are
+-- cleaned up) there even if the contest's root changes later. NULL is the
+-- shared data volume.
+
+ALTER TABLE submissions
+    ADD COLUMN IF NOT EXISTS storage_root TEXT
+        CHECK (storage_root IS NULL OR storage_root LIKE '/%');
+
+UPDATE submissions s
+SET storage_root = c.storage_root
+FROM contests c
+WHERE c.id = s.contest_id
+  AND c.storage_root IS NOT NULL
+  AND s.storage_root IS NULL;
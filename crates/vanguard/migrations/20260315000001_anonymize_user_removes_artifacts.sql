@@ -0,0 +1,58 @@
+-- Migration: Remove every stored file of an erased account's submissions
+-- When an anonymized user's submissions are deleted, `anonymize_user` now
+-- also returns the paths of their compiled artifacts (a binary, or a bundle
+-- directory) and of the outputs kept for their failing tests, not only
+-- those of their ZIP submissions, so the caller removes all of them at
+-- once. The artifact rows are deleted with the submissions rather than
+-- left detached for Horus.
+
+CREATE OR REPLACE FUNCTION anonymize_user(p_user_id UUID, p_delete_submissions BOOLEAN)
+RETURNS SETOF TEXT AS $$
+BEGIN
+    IF p_delete_submissions THEN
+        RETURN QUERY
+            SELECT r.output_path::TEXT
+            FROM submission_results r
+            JOIN submissions s ON s.id = r.submission_id
+            WHERE s.user_id = p_user_id AND r.output_path IS NOT NULL;
+
+        RETURN QUERY
+            WITH deleted AS (
+                DELETE FROM submission_artifacts a
+                USING submissions s
+                WHERE s.id = a.submission_id AND s.user_id = p_user_id
+                RETURNING a.path
+            )
+            SELECT path::TEXT FROM deleted;
+
+        RETURN QUERY
+            WITH deleted AS (
+                DELETE FROM submissions WHERE user_id = p_user_id RETURNING file_path
+            )
+            SELECT file_path::TEXT FROM deleted WHERE file_path IS NOT NULL;
+    ELSE
+        UPDATE submissions SET ip_address = NULL, user_agent = NULL
+        WHERE user_id = p_user_id;
+    END IF;
+
+    DELETE FROM sessions WHERE user_id = p_user_id;
+    DELETE FROM notifications WHERE user_id = p_user_id;
+    DELETE FROM notification_preferences WHERE user_id = p_user_id;
+    DELETE FROM saved_contest_filters WHERE user_id = p_user_id;
+    DELETE FROM contest_telemetry_events WHERE user_id = p_user_id;
+    DELETE FROM problem_recommendations WHERE user_id = p_user_id;
+    DELETE FROM user_practice_profiles WHERE user_id = p_user_id;
+
+    UPDATE users
+    SET username = 'deleted_' || LEFT(REPLACE(id::TEXT, '-', ''), 12),
+        email = id::TEXT || '@deleted.invalid',
+        password_hash = '!',
+        display_name = NULL,
+        bio = NULL,
+        role = 'participant',
+        deletion_scheduled_for = NULL,
+        anonymized_at = NOW(),
+        updated_at = NOW()
+    WHERE id = p_user_id;
+END;
+$$ LANGUAGE plpgsql;
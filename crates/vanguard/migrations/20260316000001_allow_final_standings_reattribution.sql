@@ -0,0 +1,20 @@
+-- Migration: Let account merges move the certifier of final standings
+-- Merging a duplicate account reassigns every reference to it, including
+-- `certified_by` of the standings it certified. That UPDATE runs at
+-- trigger depth 1, so the immutability trigger rejected it and the whole
+-- merge rolled back. An UPDATE that changes nothing but `certified_by` is
+-- now allowed; the signed document and its hashes still cannot change.
+
+CREATE OR REPLACE FUNCTION reject_final_standings_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF pg_trigger_depth() > 1 THEN
+        RETURN CASE WHEN TG_OP = 'DELETE' THEN OLD ELSE NEW END;
+    END IF;
+    IF TG_OP = 'UPDATE'
+       AND to_jsonb(NEW) - 'certified_by' = to_jsonb(OLD) - 'certified_by' THEN
+        RETURN NEW;
+    END IF;
+    RAISE EXCEPTION 'Certified standings cannot be changed';
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,305 @@
+//! API versions and the compatibility layer between them.
+//!
+//! The version is part of the path: `/api/v1` is stable and keeps its
+//! contract, `/api/v2` is where breaking changes land. Both mount the same
+//! handlers wherever their behavior is unchanged; a v2 router only replaces
+//! the routes that differ (cursor-paginated lists, see
+//! [`crate::pagination`]). Handlers return v1-shaped errors, and
+//! [`api_version_middleware`] rewrites them into the structured v2
+//! envelope on the way out, so no handler has to know which version served
+//! it. Every response names its version in `API-Version`, and
+//! `GET /api/versions` lists the versions with their status.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        HeaderName, HeaderValue,
+    },
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+/// Response header naming the API version that served the request
+pub const API_VERSION_HEADER: HeaderName = HeaderName::from_static("api-version");
+
+/// Largest error body rewritten into the v2 envelope
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// A version of the HTTP API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    fn number(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "1",
+            ApiVersion::V2 => "2",
+        }
+    }
+
+    /// `stable` versions keep their contract; `preview` ones may still change
+    fn status(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "stable",
+            ApiVersion::V2 => "preview",
+        }
+    }
+}
+
+/// Tag requests and responses with the version of the router they hit, and
+/// rewrite v2 error responses into the v2 envelope.
+///
+/// Install with `from_fn_with_state(ApiVersion::V2, api_version_middleware)`
+/// outside the version's other layers, so their errors are rewritten too.
+pub async fn api_version_middleware(
+    State(version): State<ApiVersion>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    request.extensions_mut().insert(version);
+
+    let mut response = next.run(request).await;
+    if version == ApiVersion::V2 {
+        response = v2_error_envelope(response, request_id).await;
+    }
+    response
+        .headers_mut()
+        .insert(API_VERSION_HEADER, HeaderValue::from_static(version.number()));
+    response
+}
+
+/// v1 error body, as written by `ApiError` and the rate limiters
+#[derive(Debug, Deserialize)]
+struct V1ErrorResponse {
+    error: V1ErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct V1ErrorBody {
+    code: String,
+    message: String,
+    original_message: Option<String>,
+    details: Option<serde_json::Value>,
+}
+
+/// v2 error envelope: the status and request id are repeated in the body,
+/// and what v1 puts in free-form `details` gets its own fields.
+#[derive(Debug, Serialize)]
+struct V2ErrorResponse {
+    error: V2ErrorBody,
+}
+
+#[derive(Debug, Serialize)]
+struct V2ErrorBody {
+    status: u16,
+    code: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+    /// Failed validation rules (`field`, `rule`, `message`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after_secs: Option<u64>,
+    /// Anything else an error carries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
+}
+
+/// Rewrite a v1 JSON error response into the v2 envelope. Other responses,
+/// and error bodies that are not v1 errors, pass through unchanged.
+async fn v2_error_envelope(response: Response, request_id: Option<String>) -> Response {
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let Ok(V1ErrorResponse { error }) = serde_json::from_slice(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let mut envelope = V2ErrorBody {
+        status: status.as_u16(),
+        code: error.code,
+        message: error.message,
+        original_message: error.original_message,
+        request_id,
+        fields: Vec::new(),
+        retry_after_secs: None,
+        details: None,
+    };
+    match error.details {
+        Some(serde_json::Value::Array(fields)) => envelope.fields = fields,
+        Some(serde_json::Value::Object(mut details)) => {
+            envelope.retry_after_secs = details
+                .remove("retry_after_secs")
+                .and_then(|v| v.as_u64());
+            if !details.is_empty() {
+                envelope.details = Some(serde_json::Value::Object(details));
+            }
+        }
+        other => envelope.details = other,
+    }
+
+    let body = serde_json::to_vec(&V2ErrorResponse { error: envelope })
+        .expect("error envelope serializes");
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// One API version
+#[derive(Debug, Serialize)]
+pub struct ApiVersionInfo {
+    pub version: &'static str,
+    pub status: &'static str,
+    pub base_path: String,
+}
+
+/// The API versions this server serves
+#[derive(Debug, Serialize)]
+pub struct ApiVersionsResponse {
+    pub versions: Vec<ApiVersionInfo>,
+}
+
+/// GET /api/versions - Supported API versions
+pub async fn list_api_versions() -> Json<ApiVersionsResponse> {
+    Json(ApiVersionsResponse {
+        versions: ApiVersion::ALL
+            .into_iter()
+            .map(|v| ApiVersionInfo {
+                version: v.as_str(),
+                status: v.status(),
+                base_path: format!("/api/{}", v.as_str()),
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn json_response(status: StatusCode, body: Value) -> Response {
+        let body = serde_json::to_vec(&body).unwrap();
+        Response::builder()
+            .status(status)
+            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_LENGTH, body.len())
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validation_details_become_fields() {
+        let field = json!({"field": "username", "rule": "length", "message": "too short"});
+        let response = json_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            json!({"error": {
+                "code": "VALIDATION_ERROR",
+                "message": "Validation failed for 1 field(s)",
+                "details": [field],
+            }}),
+        );
+
+        let response = v2_error_envelope(response, Some("req-1".to_string())).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(response.headers().get(CONTENT_LENGTH).is_none());
+        assert_eq!(
+            body_json(response).await,
+            json!({"error": {
+                "status": 422,
+                "code": "VALIDATION_ERROR",
+                "message": "Validation failed for 1 field(s)",
+                "request_id": "req-1",
+                "fields": [field],
+            }})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_is_lifted_out_of_details() {
+        let response = json_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            json!({"error": {
+                "code": "RATE_LIMIT_EXCEEDED",
+                "message": "Too many requests",
+                "original_message": "Too many requests",
+                "details": {"retry_after_secs": 30, "limit": 10},
+            }}),
+        );
+
+        let body = body_json(v2_error_envelope(response, None).await).await;
+        assert_eq!(
+            body,
+            json!({"error": {
+                "status": 429,
+                "code": "RATE_LIMIT_EXCEEDED",
+                "message": "Too many requests",
+                "original_message": "Too many requests",
+                "retry_after_secs": 30,
+                "details": {"limit": 10},
+            }})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_other_responses_pass_through() {
+        let ok = json!({"error": {"code": "X", "message": "not an error"}});
+        let response = v2_error_envelope(json_response(StatusCode::OK, ok.clone()), None).await;
+        assert_eq!(body_json(response).await, ok);
+
+        let foreign = json!({"message": "not a v1 error"});
+        let response = json_response(StatusCode::BAD_GATEWAY, foreign.clone());
+        let response = v2_error_envelope(response, None).await;
+        assert_eq!(body_json(response).await, foreign);
+
+        let response = Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(CONTENT_TYPE, "text/plain")
+            .body(Body::from("missing"))
+            .unwrap();
+        let response = v2_error_envelope(response, None).await;
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], b"missing");
+    }
+}
@@ -0,0 +1,86 @@
+//! Read replica routing.
+//!
+//! Mutations always go to the primary (`AppState::db`). Read-heavy listing,
+//! leaderboard and profile queries use `AppState::read_db`, which is served
+//! by a read replica (`DATABASE_REPLICA_URL`) when one is configured. A
+//! background task pings the replica and reads fall back to the primary
+//! while it is unreachable.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+/// How often the replica is probed
+const REPLICA_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Probes slower than this count as failures
+const REPLICA_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Optional read replica and its last known health
+#[derive(Clone, Default)]
+pub struct ReadReplica {
+    pool: Option<PgPool>,
+    healthy: Arc<AtomicBool>,
+}
+
+impl ReadReplica {
+    /// Replica at `replica_url`.
+    ///
+    /// The pool connects lazily and is only used once the monitor has
+    /// reached it, so a replica that is down at startup does not stop the
+    /// gateway from starting.
+    pub fn connect(replica_url: &str) -> Result<Self, sqlx::Error> {
+        let pool = PgPoolOptions::new()
+            .max_connections(20)
+            .acquire_timeout(Duration::from_secs(2))
+            .idle_timeout(Duration::from_secs(600))
+            .connect_lazy(replica_url)?;
+
+        Ok(Self {
+            pool: Some(pool),
+            healthy: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Whether a replica is configured
+    pub fn is_configured(&self) -> bool {
+        self.pool.is_some()
+    }
+
+    /// The replica pool, if configured and currently healthy
+    pub fn pool(&self) -> Option<&PgPool> {
+        self.pool
+            .as_ref()
+            .filter(|_| self.healthy.load(Ordering::Relaxed))
+    }
+
+    /// Start probing the replica in the background. No-op without a replica.
+    pub fn spawn_monitor(&self) {
+        let Some(pool) = self.pool.clone() else {
+            return;
+        };
+        let healthy = self.healthy.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REPLICA_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let probe = sqlx::query("SELECT 1").fetch_one(&pool);
+                let ok = matches!(
+                    tokio::time::timeout(REPLICA_CHECK_TIMEOUT, probe).await,
+                    Ok(Ok(_))
+                );
+
+                let was_healthy = healthy.swap(ok, Ordering::Relaxed);
+                if ok && !was_healthy {
+                    tracing::info!("Read replica is reachable, routing reads to it");
+                } else if !ok && was_healthy {
+                    tracing::warn!("Read replica is unreachable, routing reads to the primary");
+                }
+            }
+        });
+    }
+}
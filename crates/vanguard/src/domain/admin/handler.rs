@@ -4,15 +4,29 @@
 
 use axum::{
     extract::{Extension, Path, Query, State},
+    http::StatusCode,
     Json,
 };
 use chrono::Utc;
+use olympus_common::cleanup::ArtifactClass;
+use olympus_common::problem_backup::{
+    BackupManifest, ARCHIVE_BINARIES_PREFIX, BACKUP_TABLES, FORMAT_VERSION, MANIFEST_FILE,
+};
+use olympus_common::storage::PROBLEM_BINARIES_DIR;
+use olympus_common::stream_id::{is_stream_id, stream_id_age_ms};
+use olympus_common::worker_profile::{profile_stream, DEFAULT_WORKER_PROFILE};
+use olympus_common::JudgePriority;
 use sqlx::FromRow;
 use uuid::Uuid;
-use validator::Validate;
 
+use crate::config::Config;
+use crate::domain::auth::session;
+use crate::domain::roles;
+use crate::domain::users::{account, quota, UserQuotaResponse};
 use crate::error::{ApiError, ApiResult};
+use crate::extract::{QueueRead, ServiceCaller, ValidatedJson};
 use crate::middleware::auth::AuthUser;
+use crate::middleware::request_log::tag_stream_entry;
 use crate::state::AppState;
 
 use super::request::*;
@@ -51,25 +65,20 @@ pub async fn admin_list_users(
     // Build dynamic query
     let mut conditions = vec!["1=1".to_string()];
     let mut data_bind_idx = 3u32; // $1 = limit, $2 = offset for data query
-    let mut count_bind_idx = 1u32; // count query has no limit/offset
 
     if query.role.is_some() {
         conditions.push(format!("role = ${data}", data = data_bind_idx));
         data_bind_idx += 1;
-        count_bind_idx += 1;
     }
     if query.is_banned.is_some() {
         conditions.push(format!("is_banned = ${data}", data = data_bind_idx));
         data_bind_idx += 1;
-        count_bind_idx += 1;
     }
     if query.search.is_some() {
         conditions.push(format!(
             "(username ILIKE ${data} OR email ILIKE ${data})",
             data = data_bind_idx
         ));
-        // data_bind_idx += 1;
-        // count_bind_idx += 1;
     }
 
     let where_clause = conditions.join(" AND ");
@@ -154,18 +163,15 @@ pub async fn update_user_role(
     State(state): State<AppState>,
     Extension(admin): Extension<AuthUser>,
     Path(user_id): Path<Uuid>,
-    Json(payload): Json<UpdateUserRoleRequest>,
+    ValidatedJson(payload): ValidatedJson<UpdateUserRoleRequest>,
 ) -> ApiResult<Json<UpdateRoleResponse>> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Prevent admins from changing their own role
     if admin.id == user_id {
         return Err(ApiError::Validation(
             "Cannot change your own role".to_string(),
         ));
     }
+    roles::ensure_role_exists(&state, &payload.role).await?;
 
     let row = sqlx::query_as::<_, UpdateRoleRow>(
         r#"
@@ -211,12 +217,8 @@ pub async fn ban_user(
     State(state): State<AppState>,
     Extension(admin): Extension<AuthUser>,
     Path(user_id): Path<Uuid>,
-    Json(payload): Json<BanUserRequest>,
+    ValidatedJson(payload): ValidatedJson<BanUserRequest>,
 ) -> ApiResult<Json<BanResponse>> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Prevent banning yourself
     if admin.id == user_id {
         return Err(ApiError::Validation("Cannot ban yourself".to_string()));
@@ -306,6 +308,530 @@ struct BanRow {
     banned_reason: Option<String>,
 }
 
+/// User references merged by hand in [`merge_users`], because the tables are
+/// keyed by (something, user) and both accounts may have a row
+const MERGE_SEPARATE_COLUMNS: &[(&str, &str)] = &[
+    ("submissions", "user_id"),
+    ("contest_participants", "user_id"),
+    ("contest_collaborators", "user_id"),
+    ("problem_sheet_progress", "user_id"),
+    ("contest_waitlist", "user_id"),
+    ("organization_members", "user_id"),
+    ("saved_contest_filters", "user_id"),
+];
+
+/// Per-account rows deleted with the duplicate instead of moved: the legacy
+/// `sessions` table (refresh sessions in Redis are revoked after the merge),
+/// statistics the submissions trigger rebuilds for the survivor,
+/// notifications and recommendations
+const MERGE_DROPPED_COLUMNS: &[(&str, &str)] = &[
+    ("sessions", "user_id"),
+    ("user_activity_days", "user_id"),
+    ("user_solves", "user_id"),
+    ("notifications", "user_id"),
+    ("notification_preferences", "user_id"),
+    ("user_practice_profiles", "user_id"),
+    ("problem_recommendations", "user_id"),
+];
+
+/// Every column with a foreign key to `users(id)`. A merge moves each one
+/// not listed above to the surviving account, so references added by later
+/// migrations are covered without touching the merge.
+async fn user_reference_columns(
+    tx: &mut sqlx::PgConnection,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT kcu.table_name::text, kcu.column_name::text
+        FROM information_schema.table_constraints tc
+        JOIN information_schema.key_column_usage kcu
+          USING (constraint_schema, constraint_name)
+        JOIN information_schema.constraint_column_usage ccu
+          USING (constraint_schema, constraint_name)
+        WHERE tc.constraint_type = 'FOREIGN KEY'
+          AND tc.table_schema = current_schema()
+          AND ccu.table_name = 'users'
+          AND ccu.column_name = 'id'
+        ORDER BY 1, 2
+        "#,
+    )
+    .fetch_all(tx)
+    .await
+}
+
+/// Whether a merge moves `table.column` with a plain UPDATE
+fn merge_reassigns(table: &str, column: &str) -> bool {
+    let listed = |list: &[(&str, &str)]| list.iter().any(|&(t, c)| t == table && c == column);
+    !listed(MERGE_SEPARATE_COLUMNS) && !listed(MERGE_DROPPED_COLUMNS)
+}
+
+#[derive(Debug, FromRow)]
+struct MergeUserRow {
+    id: Uuid,
+    username: String,
+    role: String,
+}
+
+/// POST /api/v1/admin/users/{id}/merge/{other_id}
+///
+/// Merge the duplicate account `other_id` into `id`. Submissions, contest
+/// registrations and waitlist entries, collaborator roles, organization
+/// memberships, sheet progress and saved filters move to `id`; where both
+/// accounts have a row for the same contest, organization or name, the
+/// surviving account's row is kept. Every other reference to the duplicate
+/// (owned contests and problems, authored posts and comments, audit trails)
+/// is moved as well. The duplicate is then deleted and the merge recorded in
+/// the admin audit log, all in one transaction. Its refresh sessions are
+/// revoked once the transaction commits.
+pub async fn merge_users(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path((user_id, other_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<MergeUsersResponse>> {
+    if user_id == other_id {
+        return Err(ApiError::Validation(
+            "Cannot merge an account into itself".to_string(),
+        ));
+    }
+    if admin.id == other_id {
+        return Err(ApiError::Validation(
+            "Cannot merge away your own account".to_string(),
+        ));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    // Lock both accounts so concurrent merges cannot chain through them
+    let users = sqlx::query_as::<_, MergeUserRow>(
+        "SELECT id, username, role FROM users WHERE id = ANY($1) ORDER BY id FOR UPDATE",
+    )
+    .bind(vec![user_id, other_id])
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let survivor = users
+        .iter()
+        .find(|u| u.id == user_id)
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+    let duplicate = users
+        .iter()
+        .find(|u| u.id == other_id)
+        .ok_or_else(|| ApiError::NotFound("User to merge not found".to_string()))?;
+
+    let MergeCounts {
+        submissions_moved,
+        registrations_moved,
+        problems_moved,
+        contests_moved,
+    } = merge_user_rows(&mut tx, user_id, other_id).await?;
+
+    let details = serde_json::json!({
+        "merged_user_id": other_id,
+        "merged_username": duplicate.username,
+        "merged_role": duplicate.role,
+        "surviving_username": survivor.username,
+        "submissions_moved": submissions_moved,
+        "registrations_moved": registrations_moved,
+        "problems_moved": problems_moved,
+        "contests_moved": contests_moved,
+    });
+    let audit_log_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO admin_audit_log (actor_id, action, target_type, target_id, details)
+        VALUES ($1, 'user_merge', 'user', $2, $3::jsonb)
+        RETURNING id
+        "#,
+    )
+    .bind(admin.id)
+    .bind(user_id)
+    .bind(details.to_string())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    // Refresh sessions live in Redis, not with the deleted row. The merge
+    // has committed either way, so failing to end them is only logged.
+    let revoked = match state.redis.get().await {
+        Ok(mut conn) => session::revoke_all_sessions(&mut conn, other_id)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = revoked {
+        tracing::warn!(
+            merged_user = %other_id,
+            "Failed to revoke sessions of merged account: {}",
+            e
+        );
+    }
+
+    tracing::info!(
+        admin_id = %admin.id,
+        surviving_user = %user_id,
+        merged_user = %other_id,
+        submissions_moved,
+        "Admin merged user accounts"
+    );
+
+    Ok(Json(MergeUsersResponse {
+        id: user_id,
+        merged_user_id: other_id,
+        merged_username: duplicate.username.clone(),
+        submissions_moved,
+        registrations_moved,
+        problems_moved,
+        contests_moved,
+        audit_log_id,
+    }))
+}
+
+/// Rows moved by [`merge_user_rows`]
+struct MergeCounts {
+    submissions_moved: u64,
+    registrations_moved: u64,
+    problems_moved: u64,
+    contests_moved: u64,
+}
+
+/// Move everything of `other_id` to `user_id` and delete `other_id`, as
+/// described on [`merge_users`]
+async fn merge_user_rows(
+    conn: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    other_id: Uuid,
+) -> Result<MergeCounts, sqlx::Error> {
+    let submissions_moved = sqlx::query("UPDATE submissions SET user_id = $1 WHERE user_id = $2")
+        .bind(user_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    // Registrations: keep the survivor's row (with the earlier registration
+    // time) where both accounts registered for the same contest
+    sqlx::query(
+        r#"
+        UPDATE contest_participants s
+        SET registered_at = LEAST(s.registered_at, d.registered_at)
+        FROM contest_participants d
+        WHERE s.user_id = $1 AND d.user_id = $2 AND s.contest_id = d.contest_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(
+        r#"
+        DELETE FROM contest_participants d
+        USING contest_participants s
+        WHERE d.user_id = $2 AND s.user_id = $1 AND s.contest_id = d.contest_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    let registrations_moved =
+        sqlx::query("UPDATE contest_participants SET user_id = $1 WHERE user_id = $2")
+            .bind(user_id)
+            .bind(other_id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+
+    sqlx::query(
+        r#"
+        DELETE FROM contest_collaborators d
+        USING contest_collaborators s
+        WHERE d.user_id = $2 AND s.user_id = $1 AND s.contest_id = d.contest_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE contest_collaborators SET user_id = $1 WHERE user_id = $2")
+        .bind(user_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM problem_sheet_progress d
+        USING problem_sheet_progress s
+        WHERE d.user_id = $2 AND s.user_id = $1
+          AND s.sheet_id = d.sheet_id AND s.problem_id = d.problem_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE problem_sheet_progress SET user_id = $1 WHERE user_id = $2")
+        .bind(user_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Waitlist: keep the earlier place in line, and drop entries for
+    // contests the survivor is now registered for
+    sqlx::query(
+        r#"
+        UPDATE contest_waitlist s
+        SET joined_at = LEAST(s.joined_at, d.joined_at)
+        FROM contest_waitlist d
+        WHERE s.user_id = $1 AND d.user_id = $2 AND s.contest_id = d.contest_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(
+        r#"
+        DELETE FROM contest_waitlist d
+        USING contest_waitlist s
+        WHERE d.user_id = $2 AND s.user_id = $1 AND s.contest_id = d.contest_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE contest_waitlist SET user_id = $1 WHERE user_id = $2")
+        .bind(user_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        r#"
+        DELETE FROM contest_waitlist w
+        USING contest_participants cp
+        WHERE w.user_id = $1 AND cp.user_id = $1 AND cp.contest_id = w.contest_id
+        "#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Memberships: where both belong to an organization, keep the stronger role
+    sqlx::query(
+        r#"
+        UPDATE organization_members s
+        SET role = d.role
+        FROM organization_members d
+        WHERE s.user_id = $1 AND d.user_id = $2 AND s.organization_id = d.organization_id
+          AND array_position(ARRAY['member', 'admin', 'owner']::varchar[], d.role)
+            > array_position(ARRAY['member', 'admin', 'owner']::varchar[], s.role)
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(
+        r#"
+        DELETE FROM organization_members d
+        USING organization_members s
+        WHERE d.user_id = $2 AND s.user_id = $1 AND s.organization_id = d.organization_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE organization_members SET user_id = $1 WHERE user_id = $2")
+        .bind(user_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Saved filters: the survivor's filter wins where both used a name
+    sqlx::query(
+        r#"
+        DELETE FROM saved_contest_filters d
+        USING saved_contest_filters s
+        WHERE d.user_id = $2 AND s.user_id = $1 AND s.name = d.name
+        "#,
+    )
+    .bind(user_id)
+    .bind(other_id)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE saved_contest_filters SET user_id = $1 WHERE user_id = $2")
+        .bind(user_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Every other reference moves with one plain UPDATE. A table needs its
+    // own statements above and an entry in MERGE_SEPARATE_COLUMNS when the
+    // moved rows can collide with the survivor's (a primary key or UNIQUE
+    // constraint that includes the column), and an entry in
+    // MERGE_DROPPED_COLUMNS when its rows should go with the duplicate
+    // instead. A trigger on the table must let this UPDATE through, as
+    // the one on contest_final_standings does for `certified_by`.
+    // `test_merge_moves_every_user_reference` fills every such column for
+    // both accounts, so a new table that breaks this fails there.
+    let mut problems_moved = 0;
+    let mut contests_moved = 0;
+    for (table, column) in user_reference_columns(&mut *conn).await? {
+        if !merge_reassigns(&table, &column) {
+            continue;
+        }
+        let moved = sqlx::query(&format!(
+            r#"UPDATE "{table}" SET "{column}" = $1 WHERE "{column}" = $2"#
+        ))
+        .bind(user_id)
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+        match (table.as_str(), column.as_str()) {
+            ("problems", "owner_id") => problems_moved = moved,
+            ("contests", "owner_id") => contests_moved = moved,
+            _ => {}
+        }
+    }
+
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(other_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(MergeCounts {
+        submissions_moved,
+        registrations_moved,
+        problems_moved,
+        contests_moved,
+    })
+}
+
+/// POST /api/v1/admin/users/{id}/anonymize
+///
+/// Anonymize a user at once, whether or not they asked for their account
+/// to be deleted.
+pub async fn anonymize_user(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(user_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<AnonymizeUserRequest>,
+) -> ApiResult<Json<AnonymizeUserResponse>> {
+    if admin.id == user_id {
+        return Err(ApiError::Validation(
+            "Cannot anonymize your own account".to_string(),
+        ));
+    }
+
+    let anonymized_at: Option<chrono::DateTime<Utc>> =
+        sqlx::query_scalar("SELECT anonymized_at FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+    if anonymized_at.is_some() {
+        return Err(ApiError::Conflict(
+            "User is already anonymized".to_string(),
+        ));
+    }
+
+    let delete_submissions = payload
+        .delete_submissions
+        .unwrap_or(state.config.account_deletion_deletes_submissions);
+    account::anonymize_user(&state, user_id, delete_submissions).await?;
+
+    let (username, anonymized_at): (String, chrono::DateTime<Utc>) =
+        sqlx::query_as("SELECT username, anonymized_at FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    let details = serde_json::json!({ "delete_submissions": delete_submissions });
+    let audit_log_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO admin_audit_log (actor_id, action, target_type, target_id, details)
+        VALUES ($1, 'user_anonymize', 'user', $2, $3::jsonb)
+        RETURNING id
+        "#,
+    )
+    .bind(admin.id)
+    .bind(user_id)
+    .bind(details.to_string())
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        target_user = %user_id,
+        delete_submissions,
+        "Admin anonymized user"
+    );
+
+    Ok(Json(AnonymizeUserResponse {
+        id: user_id,
+        username,
+        submissions_deleted: delete_submissions,
+        anonymized_at,
+        audit_log_id,
+    }))
+}
+
+/// GET /api/v1/admin/users/{id}/quota
+///
+/// Get a user's quota limits and usage.
+pub async fn get_user_quota(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> ApiResult<Json<UserQuotaResponse>> {
+    Ok(Json(quota::fetch_user_quota(&state.db, user_id).await?))
+}
+
+/// PUT /api/v1/admin/users/{id}/quota
+///
+/// Set a user's problem, storage and concurrent rejudge limits. Usage over
+/// a lowered limit is kept; only new problems, uploads and rejudges are
+/// refused.
+pub async fn update_user_quota(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(user_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateUserQuotaRequest>,
+) -> ApiResult<Json<UserQuotaResponse>> {
+    let updated = sqlx::query(
+        r#"
+        UPDATE users
+        SET max_problems = $1, max_storage_bytes = $2, max_concurrent_rejudges = $3,
+            updated_at = NOW()
+        WHERE id = $4
+        "#,
+    )
+    .bind(payload.max_problems)
+    .bind(payload.max_storage_bytes)
+    .bind(payload.max_concurrent_rejudges)
+    .bind(user_id)
+    .execute(&state.db)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("User not found".to_string()));
+    }
+
+    tracing::info!(
+        admin_id = %admin.id,
+        target_user = %user_id,
+        max_problems = ?payload.max_problems,
+        max_storage_bytes = ?payload.max_storage_bytes,
+        max_concurrent_rejudges = ?payload.max_concurrent_rejudges,
+        "Admin updated user quota"
+    );
+
+    Ok(Json(quota::fetch_user_quota(&state.db, user_id).await?))
+}
+
 // =============================================================================
 // 7.2 System Management
 // =============================================================================
@@ -317,6 +843,31 @@ struct RoleCountRow {
     count: i64,
 }
 
+/// Row type for storage usage snapshots
+#[derive(Debug, FromRow)]
+struct StorageUsageRow {
+    category: String,
+    bytes: i64,
+    file_count: i64,
+    budget_bytes: Option<i64>,
+    recorded_at: chrono::DateTime<Utc>,
+}
+
+impl From<StorageUsageRow> for StorageUsageEntry {
+    fn from(row: StorageUsageRow) -> Self {
+        StorageUsageEntry {
+            category: row.category,
+            bytes: row.bytes,
+            file_count: row.file_count,
+            budget_bytes: row.budget_bytes,
+            recorded_at: row.recorded_at,
+        }
+    }
+}
+
+/// Maximum days of storage history returned.
+const MAX_STORAGE_HISTORY_DAYS: u32 = 90;
+
 /// GET /api/v1/admin/stats
 ///
 /// System-wide statistics dashboard.
@@ -371,7 +922,9 @@ pub async fn system_stats(State(state): State<AppState>) -> ApiResult<Json<Syste
             .fetch_one(&state.db)
             .await?;
     let rejected_submissions: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM submissions WHERE status IN ('wrong_answer', 'time_limit', 'memory_limit', 'runtime_error', 'compilation_error')",
+        "SELECT COUNT(*) FROM submissions WHERE status IN ('wrong_answer', 'presentation_error', \
+         'time_limit', 'memory_limit', 'output_limit', 'idleness_limit', 'runtime_error', \
+         'compilation_error', 'validation_failed')",
     )
     .fetch_one(&state.db)
     .await?;
@@ -381,6 +934,18 @@ pub async fn system_stats(State(state): State<AppState>) -> ApiResult<Json<Syste
         .fetch_one(&state.db)
         .await?;
 
+    // Latest storage usage snapshot recorded by Horus
+    let usage: Vec<StorageUsageRow> = sqlx::query_as(
+        r#"
+        SELECT category, bytes, file_count, budget_bytes, recorded_at
+        FROM storage_usage_snapshots
+        WHERE recorded_at = (SELECT MAX(recorded_at) FROM storage_usage_snapshots)
+        ORDER BY category
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await?;
+
     Ok(Json(SystemStatsResponse {
         users: UserStats {
             total: total_users,
@@ -410,79 +975,238 @@ pub async fn system_stats(State(state): State<AppState>) -> ApiResult<Json<Syste
         storage: StorageStats {
             submissions_count: total_submissions,
             results_count,
+            usage: usage.into_iter().map(Into::into).collect(),
         },
     }))
 }
 
+/// GET /api/v1/admin/storage
+///
+/// Storage usage snapshots recorded by Horus over the last `days` days,
+/// oldest first.
+pub async fn storage_history(
+    State(state): State<AppState>,
+    Query(query): Query<StorageHistoryQuery>,
+) -> ApiResult<Json<StorageHistoryResponse>> {
+    let days = query.days.clamp(1, MAX_STORAGE_HISTORY_DAYS);
+
+    let rows: Vec<StorageUsageRow> = sqlx::query_as(
+        r#"
+        SELECT category, bytes, file_count, budget_bytes, recorded_at
+        FROM storage_usage_snapshots
+        WHERE recorded_at >= NOW() - INTERVAL '1 day' * $1
+          AND ($2::text IS NULL OR category = $2)
+        ORDER BY recorded_at, category
+        "#,
+    )
+    .bind(days as i32)
+    .bind(&query.category)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(StorageHistoryResponse {
+        days,
+        entries: rows.into_iter().map(Into::into).collect(),
+    }))
+}
+
 // =============================================================================
 // 7.3 Queue Management
 // =============================================================================
 
 /// GET /api/v1/admin/queue
 ///
-/// Get Redis Stream queue status for compile_queue and run_queue.
+/// Get Redis Stream queue status for the compile and run queues, one
+/// entry per priority stream.
 pub async fn get_queue_info(State(state): State<AppState>) -> ApiResult<Json<QueueInfoResponse>> {
     let mut conn = state.redis.get().await?;
+    let compile_queue = &state.config.compile_stream;
+    let run_queue = &state.config.run_stream;
 
     let mut queues = Vec::new();
 
-    for stream_name in &["compile_queue", "run_queue"] {
-        let detail = get_stream_info(&mut conn, stream_name).await;
-        queues.push(detail);
+    for priority in JudgePriority::ALL {
+        let stream_name = priority.stream_name(compile_queue);
+        queues.push(get_stream_info(&mut conn, compile_queue, stream_name, priority, None).await);
+    }
+    for (stream_name, profile, priority) in state.config.worker_profiles.streams(run_queue) {
+        queues.push(
+            get_stream_info(&mut conn, run_queue, stream_name, priority, Some(profile)).await,
+        );
     }
 
     Ok(Json(QueueInfoResponse { queues }))
 }
 
-/// Get info about a single Redis Stream.
-async fn get_stream_info(conn: &mut deadpool_redis::Connection, stream_name: &str) -> QueueDetail {
+/// GET /api/v1/internal/queue
+///
+/// The queue status of `GET /admin/queue` for internal services (e.g. an
+/// autoscaler), with a service token scoped `queue:read` instead of an
+/// admin session.
+pub async fn service_queue_info(
+    State(state): State<AppState>,
+    caller: ServiceCaller<QueueRead>,
+) -> ApiResult<Json<QueueInfoResponse>> {
+    tracing::debug!(service = %caller.claims.sub, "Service read queue status");
+    get_queue_info(State(state)).await
+}
+
+/// Get info about a single priority stream of a queue.
+async fn get_stream_info(
+    conn: &mut deadpool_redis::Connection,
+    queue: &str,
+    stream_name: String,
+    priority: JudgePriority,
+    worker_profile: Option<&str>,
+) -> QueueDetail {
     // Get stream length
     let length: i64 = redis::cmd("XLEN")
-        .arg(stream_name)
+        .arg(&stream_name)
         .query_async(conn)
         .await
         .unwrap_or(0);
 
     // Get consumer group info
-    let groups = get_consumer_groups(conn, stream_name).await;
+    let groups = get_consumer_groups(conn, &stream_name).await;
 
     // Get pending entries (from all groups)
     let mut pending = Vec::new();
     for group in &groups {
-        if let Ok(entries) = get_pending_entries(conn, stream_name, &group.name).await {
+        if let Ok(entries) = get_pending_entries(conn, &stream_name, &group.name).await {
             pending.extend(entries);
         }
     }
 
+    // Paused flag checked by Sisyphus/Minos before XREADGROUP; it covers
+    // every priority stream of the queue
+    let paused: bool = redis::cmd("EXISTS")
+        .arg(pause_key(queue))
+        .query_async(conn)
+        .await
+        .unwrap_or(false);
+
     QueueDetail {
-        name: stream_name.to_string(),
+        name: stream_name,
+        queue: queue.to_string(),
+        priority,
+        worker_profile: worker_profile.map(str::to_string),
         length,
+        paused,
         consumer_groups: groups,
         pending_entries: pending,
     }
 }
 
-/// Get consumer groups for a stream.
-async fn get_consumer_groups(
-    conn: &mut deadpool_redis::Connection,
-    stream_name: &str,
-) -> Vec<ConsumerGroupInfo> {
-    // XINFO GROUPS <stream>
-    let result: Result<Vec<redis::Value>, _> = redis::cmd("XINFO")
-        .arg("GROUPS")
-        .arg(stream_name)
-        .query_async(conn)
-        .await;
-
-    match result {
-        Ok(values) => parse_group_info(values),
-        Err(_) => Vec::new(),
-    }
+/// Queues that can be inspected and paused: the configured compile and run
+/// streams. Each has one stream per `JudgePriority`, and the run queue one
+/// per worker profile on top; pausing a queue pauses all of them.
+fn judge_queues(config: &Config) -> [&str; 2] {
+    [&config.compile_stream, &config.run_stream]
 }
 
-/// Parse XINFO GROUPS response into ConsumerGroupInfo.
-fn parse_group_info(values: Vec<redis::Value>) -> Vec<ConsumerGroupInfo> {
-    let mut groups = Vec::new();
+/// Redis key whose presence pauses consumption of a queue.
+fn pause_key(stream_name: &str) -> String {
+    format!("queue_paused:{}", stream_name)
+}
+
+/// POST /api/v1/admin/queues/{name}/pause
+///
+/// Pause a judging queue. Workers finish their in-flight job and then stop
+/// reading new messages until the queue is resumed; queued messages stay in
+/// the stream.
+pub async fn pause_queue(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<QueuePauseResponse>> {
+    validate_queue_name(&state.config, &name)?;
+
+    let value = serde_json::json!({
+        "paused_by": admin.id,
+        "paused_at": Utc::now().to_rfc3339(),
+    });
+
+    let mut conn = state.redis.get().await?;
+    redis::cmd("SET")
+        .arg(pause_key(&name))
+        .arg(value.to_string())
+        .query_async::<()>(&mut conn)
+        .await?;
+
+    tracing::warn!(admin_id = %admin.id, queue = %name, "Admin paused queue");
+
+    Ok(Json(QueuePauseResponse {
+        name,
+        paused: true,
+        message: "Queue paused; workers stop after their current job".to_string(),
+    }))
+}
+
+/// POST /api/v1/admin/queues/{name}/resume
+///
+/// Resume a paused judging queue.
+pub async fn resume_queue(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<QueuePauseResponse>> {
+    validate_queue_name(&state.config, &name)?;
+
+    let mut conn = state.redis.get().await?;
+    let removed: i64 = redis::cmd("DEL")
+        .arg(pause_key(&name))
+        .query_async(&mut conn)
+        .await?;
+
+    tracing::info!(admin_id = %admin.id, queue = %name, "Admin resumed queue");
+
+    let message = if removed > 0 {
+        "Queue resumed"
+    } else {
+        "Queue was not paused"
+    };
+
+    Ok(Json(QueuePauseResponse {
+        name,
+        paused: false,
+        message: message.to_string(),
+    }))
+}
+
+fn validate_queue_name(config: &Config, name: &str) -> ApiResult<()> {
+    let queues = judge_queues(config);
+    if queues.contains(&name) {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound(format!(
+            "Unknown queue '{}'. Expected one of: {}",
+            name,
+            queues.join(", ")
+        )))
+    }
+}
+
+/// Get consumer groups for a stream.
+async fn get_consumer_groups(
+    conn: &mut deadpool_redis::Connection,
+    stream_name: &str,
+) -> Vec<ConsumerGroupInfo> {
+    // XINFO GROUPS <stream>
+    let result: Result<Vec<redis::Value>, _> = redis::cmd("XINFO")
+        .arg("GROUPS")
+        .arg(stream_name)
+        .query_async(conn)
+        .await;
+
+    match result {
+        Ok(values) => parse_group_info(values),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Parse XINFO GROUPS response into ConsumerGroupInfo.
+fn parse_group_info(values: Vec<redis::Value>) -> Vec<ConsumerGroupInfo> {
+    let mut groups = Vec::new();
 
     for value in values {
         if let redis::Value::Array(fields) = value {
@@ -602,6 +1326,448 @@ async fn get_pending_entries(
     Ok(entries)
 }
 
+/// Dead-letter stream each queue's failed jobs are moved to
+fn dead_letter_streams(config: &Config) -> [(&str, String); 2] {
+    [
+        (
+            &config.compile_stream,
+            format!("{}_dead_letter", config.compile_stream),
+        ),
+        (&config.run_stream, format!("{}_dlq", config.run_stream)),
+    ]
+}
+
+/// Most entries returned per page by the stream browser
+const MAX_STREAM_ENTRIES: u32 = 200;
+
+/// A stream the queue browser may show and modify
+struct BrowsableStream {
+    name: String,
+    queue: String,
+    /// `None` for a dead-letter stream
+    priority: Option<JudgePriority>,
+    /// Set for the run queue's judging streams
+    worker_profile: Option<String>,
+}
+
+impl BrowsableStream {
+    fn all(config: &Config) -> Vec<Self> {
+        let mut streams = Vec::new();
+        for (queue, dead_letter) in dead_letter_streams(config) {
+            if queue == config.run_stream {
+                for (name, profile, priority) in config.worker_profiles.streams(queue) {
+                    streams.push(Self {
+                        name,
+                        queue: queue.to_string(),
+                        priority: Some(priority),
+                        worker_profile: Some(profile.to_string()),
+                    });
+                }
+            } else {
+                for priority in JudgePriority::ALL {
+                    streams.push(Self {
+                        name: priority.stream_name(queue),
+                        queue: queue.to_string(),
+                        priority: Some(priority),
+                        worker_profile: None,
+                    });
+                }
+            }
+            streams.push(Self {
+                name: dead_letter,
+                queue: queue.to_string(),
+                priority: None,
+                worker_profile: None,
+            });
+        }
+        streams
+    }
+
+    fn find(name: &str, config: &Config) -> ApiResult<Self> {
+        Self::all(config)
+            .into_iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| ApiError::NotFound(format!("Unknown stream '{}'", name)))
+    }
+
+    fn is_dead_letter(&self) -> bool {
+        self.priority.is_none()
+    }
+}
+
+/// One entry as stored in a stream, fields in order
+struct RawStreamEntry {
+    id: String,
+    fields: Vec<(String, String)>,
+}
+
+/// GET /api/v1/admin/queues
+///
+/// Every judging stream and dead-letter stream with its length, consumer
+/// groups, pending entries per consumer and the age of the oldest entry
+/// and oldest pending entry.
+pub async fn browse_queues(State(state): State<AppState>) -> ApiResult<Json<QueueBrowserResponse>> {
+    let mut conn = state.redis.get().await?;
+    let now_ms = Utc::now().timestamp_millis();
+
+    let mut streams = Vec::new();
+    for stream in BrowsableStream::all(&state.config) {
+        let length: i64 = redis::cmd("XLEN")
+            .arg(&stream.name)
+            .query_async(&mut conn)
+            .await
+            .unwrap_or(0);
+
+        let oldest_entry_age_ms = read_stream_range(&mut conn, &stream.name, "-", 1)
+            .await
+            .ok()
+            .and_then(|entries| entries.into_iter().next())
+            .and_then(|entry| stream_id_age_ms(&entry.id, now_ms));
+
+        let mut consumer_groups = Vec::new();
+        for group in get_consumer_groups(&mut conn, &stream.name).await {
+            let oldest_pending_age_ms = get_oldest_pending_id(&mut conn, &stream.name, &group.name)
+                .await
+                .and_then(|id| stream_id_age_ms(&id, now_ms));
+            let consumers = get_group_consumers(&mut conn, &stream.name, &group.name).await;
+
+            consumer_groups.push(StreamGroupOverview {
+                name: group.name,
+                pending: group.pending,
+                last_delivered_id: group.last_delivered_id,
+                oldest_pending_age_ms,
+                consumers,
+            });
+        }
+
+        streams.push(StreamOverview {
+            dead_letter: stream.is_dead_letter(),
+            name: stream.name,
+            queue: stream.queue.clone(),
+            priority: stream.priority,
+            worker_profile: stream.worker_profile,
+            length,
+            oldest_entry_age_ms,
+            consumer_groups,
+        });
+    }
+
+    Ok(Json(QueueBrowserResponse { streams }))
+}
+
+/// GET /api/v1/admin/queues/{name}/entries
+///
+/// Entries of one stream, oldest first, starting at `start`.
+pub async fn list_stream_entries(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<StreamEntriesQuery>,
+) -> ApiResult<Json<StreamEntriesResponse>> {
+    let stream = BrowsableStream::find(&name, &state.config)?;
+    let start = query.start.as_deref().unwrap_or("-");
+    if start != "-" && !is_stream_id(start) {
+        return Err(ApiError::Validation(format!(
+            "Invalid stream entry id '{}'",
+            start
+        )));
+    }
+
+    let mut conn = state.redis.get().await?;
+    let now_ms = Utc::now().timestamp_millis();
+    let count = query.count.clamp(1, MAX_STREAM_ENTRIES);
+    let entries = read_stream_range(&mut conn, &stream.name, start, count).await?;
+
+    Ok(Json(StreamEntriesResponse {
+        stream: stream.name,
+        entries: entries
+            .into_iter()
+            .map(|entry| StreamEntryInfo {
+                age_ms: stream_id_age_ms(&entry.id, now_ms),
+                id: entry.id,
+                fields: entry.fields.into_iter().collect(),
+            })
+            .collect(),
+    }))
+}
+
+/// DELETE /api/v1/admin/queues/{name}/entries/{entry_id}
+///
+/// Drop an entry: it is acknowledged in every consumer group, so no worker
+/// retries it, and removed from the stream.
+pub async fn delete_stream_entry(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path((name, entry_id)): Path<(String, String)>,
+) -> ApiResult<Json<StreamEntryActionResponse>> {
+    let stream = BrowsableStream::find(&name, &state.config)?;
+    let mut conn = state.redis.get().await?;
+
+    if remove_stream_entry(&mut conn, &stream.name, &entry_id).await? == 0 {
+        return Err(ApiError::NotFound("Stream entry not found".to_string()));
+    }
+
+    tracing::warn!(
+        admin_id = %admin.id,
+        stream = %stream.name,
+        entry_id = %entry_id,
+        "Admin deleted stream entry"
+    );
+
+    Ok(Json(StreamEntryActionResponse {
+        stream: stream.name,
+        id: entry_id,
+        requeued_to: None,
+        new_id: None,
+        message: "Entry deleted".to_string(),
+    }))
+}
+
+/// POST /api/v1/admin/queues/{name}/entries/{entry_id}/requeue
+///
+/// Add an entry again as a fresh message and drop the original. Entries of
+/// a judging stream (e.g. stuck with a dead consumer) go back to the same
+/// stream; dead-lettered jobs go to their queue's normal-priority stream
+/// with the retry count reset, and their submission leaves `system_error`.
+pub async fn requeue_stream_entry(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path((name, entry_id)): Path<(String, String)>,
+) -> ApiResult<Json<StreamEntryActionResponse>> {
+    let stream = BrowsableStream::find(&name, &state.config)?;
+    let mut conn = state.redis.get().await?;
+
+    let entry = read_stream_entry(&mut conn, &stream.name, &entry_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Stream entry not found".to_string()))?;
+
+    // Dead-lettered run jobs go back to the profile of the worker that gave
+    // up on them, or the default profile if it is no longer run
+    let dead_letter = stream.is_dead_letter();
+    let target = if dead_letter {
+        let profile = entry
+            .fields
+            .iter()
+            .find(|(key, _)| key == "worker_profile")
+            .map(|(_, value)| value.as_str())
+            .filter(|profile| state.config.worker_profiles.contains(profile))
+            .unwrap_or(DEFAULT_WORKER_PROFILE);
+        JudgePriority::Normal.stream_name(&profile_stream(&stream.queue, profile))
+    } else {
+        stream.name.clone()
+    };
+
+    let mut xadd = redis::cmd("XADD");
+    xadd.arg(&target).arg("*");
+    for (key, value) in &entry.fields {
+        match key.as_str() {
+            "error" | "failed_at" | "worker_profile" if dead_letter => {}
+            "retry_count" if dead_letter => {
+                xadd.arg(key).arg("0");
+            }
+            _ => {
+                xadd.arg(key).arg(value);
+            }
+        }
+    }
+    let new_id: String = xadd.query_async(&mut conn).await?;
+    remove_stream_entry(&mut conn, &stream.name, &entry_id).await?;
+
+    // Dead-lettering marked the submission system_error; put it back in the
+    // state the queue's workers pick it up from
+    let submission_id = entry
+        .fields
+        .iter()
+        .find(|(key, _)| key == "submission_id")
+        .and_then(|(_, value)| value.parse::<Uuid>().ok());
+    if let Some(submission_id) = submission_id.filter(|_| dead_letter) {
+        let status = if stream.queue == state.config.compile_stream {
+            "pending"
+        } else {
+            "compiled"
+        };
+        sqlx::query("UPDATE submissions SET status = $1 WHERE id = $2 AND status = 'system_error'")
+            .bind(status)
+            .bind(submission_id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    tracing::info!(
+        admin_id = %admin.id,
+        stream = %stream.name,
+        entry_id = %entry_id,
+        target = %target,
+        new_id = %new_id,
+        "Admin re-enqueued stream entry"
+    );
+
+    Ok(Json(StreamEntryActionResponse {
+        stream: stream.name,
+        id: entry_id,
+        message: format!("Entry re-enqueued to {}", target),
+        requeued_to: Some(target),
+        new_id: Some(new_id),
+    }))
+}
+
+fn redis_string(value: &redis::Value) -> Option<String> {
+    match value {
+        redis::Value::BulkString(b) => Some(String::from_utf8_lossy(b).to_string()),
+        redis::Value::SimpleString(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// Up to `count` entries from `start` (inclusive) onwards.
+async fn read_stream_range(
+    conn: &mut deadpool_redis::Connection,
+    stream_name: &str,
+    start: &str,
+    count: u32,
+) -> Result<Vec<RawStreamEntry>, redis::RedisError> {
+    // XRANGE <stream> <start> + COUNT <count>
+    let result: redis::Value = redis::cmd("XRANGE")
+        .arg(stream_name)
+        .arg(start)
+        .arg("+")
+        .arg("COUNT")
+        .arg(count)
+        .query_async(conn)
+        .await?;
+
+    let mut entries = Vec::new();
+    if let redis::Value::Array(items) = result {
+        for item in items {
+            let redis::Value::Array(parts) = item else {
+                continue;
+            };
+            let (Some(id), Some(redis::Value::Array(values))) =
+                (parts.first().and_then(redis_string), parts.get(1))
+            else {
+                continue;
+            };
+            let fields = values
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [key, value] => Some((redis_string(key)?, redis_string(value)?)),
+                    _ => None,
+                })
+                .collect();
+            entries.push(RawStreamEntry { id, fields });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// The entry with exactly `id`, if the stream still has it.
+async fn read_stream_entry(
+    conn: &mut deadpool_redis::Connection,
+    stream_name: &str,
+    id: &str,
+) -> ApiResult<Option<RawStreamEntry>> {
+    if !is_stream_id(id) {
+        return Err(ApiError::Validation(format!(
+            "Invalid stream entry id '{}'",
+            id
+        )));
+    }
+    let entries = read_stream_range(conn, stream_name, id, 1).await?;
+    Ok(entries.into_iter().find(|entry| entry.id == id))
+}
+
+/// Acknowledge an entry in every consumer group and delete it. Returns the
+/// number of entries deleted (0 or 1).
+async fn remove_stream_entry(
+    conn: &mut deadpool_redis::Connection,
+    stream_name: &str,
+    id: &str,
+) -> ApiResult<i64> {
+    if !is_stream_id(id) {
+        return Err(ApiError::Validation(format!(
+            "Invalid stream entry id '{}'",
+            id
+        )));
+    }
+    for group in get_consumer_groups(conn, stream_name).await {
+        redis::cmd("XACK")
+            .arg(stream_name)
+            .arg(&group.name)
+            .arg(id)
+            .query_async::<i64>(&mut *conn)
+            .await?;
+    }
+    let removed: i64 = redis::cmd("XDEL")
+        .arg(stream_name)
+        .arg(id)
+        .query_async(conn)
+        .await?;
+    Ok(removed)
+}
+
+/// Id of the oldest entry delivered to a group but not yet acknowledged.
+async fn get_oldest_pending_id(
+    conn: &mut deadpool_redis::Connection,
+    stream_name: &str,
+    group_name: &str,
+) -> Option<String> {
+    // XPENDING <stream> <group> → [count, min id, max id, consumers]
+    let result: redis::Value = redis::cmd("XPENDING")
+        .arg(stream_name)
+        .arg(group_name)
+        .query_async(conn)
+        .await
+        .ok()?;
+
+    match result {
+        redis::Value::Array(summary) => summary.get(1).and_then(redis_string),
+        _ => None,
+    }
+}
+
+/// Consumers of a group with their pending counts and idle times.
+async fn get_group_consumers(
+    conn: &mut deadpool_redis::Connection,
+    stream_name: &str,
+    group_name: &str,
+) -> Vec<StreamConsumerInfo> {
+    // XINFO CONSUMERS <stream> <group>
+    let result: Result<Vec<redis::Value>, _> = redis::cmd("XINFO")
+        .arg("CONSUMERS")
+        .arg(stream_name)
+        .arg(group_name)
+        .query_async(conn)
+        .await;
+
+    let mut consumers = Vec::new();
+    for value in result.unwrap_or_default() {
+        let redis::Value::Array(fields) = value else {
+            continue;
+        };
+        let mut consumer = StreamConsumerInfo {
+            name: String::new(),
+            pending: 0,
+            idle_ms: 0,
+        };
+        for pair in fields.chunks(2) {
+            let [key, value] = pair else {
+                continue;
+            };
+            match (redis_string(key).as_deref(), value) {
+                (Some("name"), value) => consumer.name = redis_string(value).unwrap_or_default(),
+                (Some("pending"), redis::Value::Int(n)) => consumer.pending = *n,
+                (Some("idle"), redis::Value::Int(n)) => consumer.idle_ms = *n,
+                _ => {}
+            }
+        }
+        if !consumer.name.is_empty() {
+            consumers.push(consumer);
+        }
+    }
+
+    consumers
+}
+
 /// POST /api/v1/admin/queue/{id}/rejudge
 ///
 /// Rejudge a submission by resetting its status and pushing to compile_queue.
@@ -636,9 +1802,11 @@ pub async fn rejudge_submission(
             passed_test_cases = NULL,
             max_time_ms = NULL,
             max_memory_kb = NULL,
+            total_instructions = NULL,
             compilation_log = NULL,
             compiled_at = NULL,
-            judged_at = NULL
+            judged_at = NULL,
+            reconcile_attempts = 0
         WHERE id = $1
         "#,
     )
@@ -652,27 +1820,27 @@ pub async fn rejudge_submission(
         .execute(&state.db)
         .await?;
 
-    // Look up file_path for the submission
-    let file_path: Option<String> =
-        sqlx::query_scalar("SELECT file_path FROM submissions WHERE id = $1")
+    // Look up file_path and tenant for the submission
+    let (file_path, tenant): (Option<String>, Option<Uuid>) =
+        sqlx::query_as("SELECT file_path, organization_id FROM submissions WHERE id = $1")
             .bind(submission_id)
-            .fetch_optional(&state.db)
-            .await?
-            .flatten();
+            .fetch_one(&state.db)
+            .await?;
 
-    // Push to compile_queue Redis Stream
+    // Push to the low priority compile stream so live contests go first
     let mut conn = state.redis.get().await?;
-    redis::cmd("XADD")
-        .arg("compile_queue")
+    let mut xadd = redis::cmd("XADD");
+    xadd.arg(JudgePriority::Low.stream_name(&state.config.compile_stream))
         .arg("*")
         .arg("submission_id")
         .arg(submission_id.to_string())
         .arg("file_path")
-        .arg(file_path.unwrap_or_default())
-        .arg("priority")
-        .arg("1")
-        .query_async::<String>(&mut conn)
-        .await?;
+        .arg(file_path.unwrap_or_default());
+    if let Some(tenant) = tenant {
+        xadd.arg("tenant").arg(tenant.to_string());
+    }
+    tag_stream_entry(&mut xadd);
+    xadd.query_async::<String>(&mut conn).await?;
 
     tracing::info!(
         admin_id = %admin.id,
@@ -754,12 +1922,8 @@ pub async fn list_rules(
 pub async fn save_rule(
     State(state): State<AppState>,
     Extension(admin): Extension<AuthUser>,
-    Json(payload): Json<SaveRuleConfigRequest>,
+    ValidatedJson(payload): ValidatedJson<SaveRuleConfigRequest>,
 ) -> ApiResult<Json<SaveRuleResponse>> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Validate that the config JSON is a valid RuleConfig
     serde_json::from_value::<olympus_rules::config::RuleConfig>(payload.config.clone())
         .map_err(|e| {
@@ -830,12 +1994,8 @@ pub async fn update_rule(
     State(state): State<AppState>,
     Extension(admin): Extension<AuthUser>,
     Path(rule_id): Path<Uuid>,
-    Json(payload): Json<UpdateRuleConfigRequest>,
+    ValidatedJson(payload): ValidatedJson<UpdateRuleConfigRequest>,
 ) -> ApiResult<Json<RuleConfigResponse>> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Validate config JSON if provided
     if let Some(ref config) = payload.config {
         serde_json::from_value::<olympus_rules::config::RuleConfig>(config.clone())
@@ -916,33 +2076,233 @@ fn row_to_response(row: RuleConfigRow) -> RuleConfigResponse {
 }
 
 // =============================================================================
-// 7.2 Container Management
+// 7.5 Cleanup Policies
 // =============================================================================
 
-/// GET /api/v1/admin/containers
+/// Redis `config_reload` payload asking Horus for a dry run of every class.
+const CLEANUP_DRY_RUN_SIGNAL: &str = "horus:dry_run";
+
+/// Database row for cleanup_policies
+#[derive(Debug, FromRow)]
+struct CleanupPolicyRow {
+    artifact_class: String,
+    enabled: Option<bool>,
+    retention_hours: Option<i32>,
+    dry_run: bool,
+    updated_by: Option<Uuid>,
+    updated_at: chrono::DateTime<Utc>,
+}
+
+impl From<CleanupPolicyRow> for CleanupPolicyResponse {
+    fn from(row: CleanupPolicyRow) -> Self {
+        CleanupPolicyResponse {
+            artifact_class: row.artifact_class,
+            enabled: row.enabled,
+            retention_hours: row.retention_hours,
+            dry_run: row.dry_run,
+            updated_by: row.updated_by,
+            updated_at: Some(row.updated_at),
+        }
+    }
+}
+
+/// Database row for cleanup_reports
+#[derive(Debug, FromRow)]
+struct CleanupReportRow {
+    artifact_class: String,
+    items: serde_json::Value,
+    items_count: i32,
+    total_bytes: i64,
+    errors: i32,
+    created_at: chrono::DateTime<Utc>,
+}
+
+/// GET /api/v1/admin/cleanup/policies
 ///
-/// List running Docker containers that are part of the system (Sisyphus
-/// compilation containers). Uses `docker ps` and `docker stats` under the
-/// hood.
-pub async fn list_containers(
-    State(_state): State<AppState>,
-) -> ApiResult<Json<ContainerListResponse>> {
-    use std::collections::HashMap;
-    use tokio::process::Command;
+/// List the cleanup policy overrides of every artifact class; classes never
+/// edited keep Horus's environment settings.
+pub async fn list_cleanup_policies(
+    State(state): State<AppState>,
+) -> ApiResult<Json<CleanupPolicyListResponse>> {
+    let mut rows: Vec<CleanupPolicyRow> = sqlx::query_as(
+        "SELECT artifact_class, enabled, retention_hours, dry_run, updated_by, updated_at \
+         FROM cleanup_policies",
+    )
+    .fetch_all(&state.db)
+    .await?;
 
-    // Known compilation images — any running container using one of these is
-    // likely a Sisyphus compile container.
-    let known_images: &[&str] = &[
-        "gcc", "rust", "golang", "python", "zig", "euantorano/zig", "ubuntu",
-    ];
+    let policies = ArtifactClass::ALL
+        .into_iter()
+        .map(|class| {
+            let row = rows.iter().position(|r| r.artifact_class == class.as_str());
+            match row {
+                Some(i) => rows.swap_remove(i).into(),
+                None => CleanupPolicyResponse {
+                    artifact_class: class.as_str().to_string(),
+                    enabled: None,
+                    retention_hours: None,
+                    dry_run: false,
+                    updated_by: None,
+                    updated_at: None,
+                },
+            }
+        })
+        .collect();
 
-    // 1. List running containers as JSON
-    let ps_output = Command::new("docker")
-        .args([
-            "ps",
-            "--no-trunc",
-            "--format",
-            "{{.ID}}\t{{.Image}}\t{{.Status}}\t{{.CreatedAt}}\t{{.State}}",
+    Ok(Json(CleanupPolicyListResponse { policies }))
+}
+
+/// PUT /api/v1/admin/cleanup/policies/{class}
+///
+/// Override fields of a cleanup policy; Horus reloads it before the next run.
+pub async fn update_cleanup_policy(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(class): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateCleanupPolicyRequest>,
+) -> ApiResult<Json<CleanupPolicyResponse>> {
+    if ArtifactClass::parse(&class).is_none() {
+        return Err(ApiError::NotFound(format!(
+            "Unknown cleanup class '{}'. Valid: {}",
+            class,
+            ArtifactClass::ALL.map(ArtifactClass::as_str).join(", ")
+        )));
+    }
+
+    let row = sqlx::query_as::<_, CleanupPolicyRow>(
+        r#"
+        INSERT INTO cleanup_policies (artifact_class, enabled, retention_hours, dry_run, updated_by)
+        VALUES ($5, $1, $2, COALESCE($3, FALSE), $4)
+        ON CONFLICT (artifact_class) DO UPDATE
+        SET enabled = COALESCE($1, cleanup_policies.enabled),
+            retention_hours = COALESCE($2, cleanup_policies.retention_hours),
+            dry_run = COALESCE($3, cleanup_policies.dry_run),
+            updated_by = $4
+        RETURNING artifact_class, enabled, retention_hours, dry_run, updated_by, updated_at
+        "#,
+    )
+    .bind(payload.enabled)
+    .bind(payload.retention_hours)
+    .bind(payload.dry_run)
+    .bind(admin.id)
+    .bind(&class)
+    .fetch_one(&state.db)
+    .await?;
+
+    // Notify Horus to reload its policies
+    let mut conn = state.redis.get().await?;
+    let _ = redis::cmd("PUBLISH")
+        .arg("config_reload")
+        .arg("horus")
+        .query_async::<i64>(&mut conn)
+        .await;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        artifact_class = %class,
+        enabled = ?row.enabled,
+        retention_hours = ?row.retention_hours,
+        dry_run = row.dry_run,
+        "Admin updated cleanup policy"
+    );
+
+    Ok(Json(row.into()))
+}
+
+/// POST /api/v1/admin/cleanup/dry-run
+///
+/// Ask Horus to dry-run every cleanup class now. Reports appear under
+/// `GET /api/v1/admin/cleanup/dry-run` once it finishes.
+pub async fn request_cleanup_dry_run(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+) -> ApiResult<Json<CleanupDryRunResponse>> {
+    let mut conn = state.redis.get().await?;
+    let receivers: i64 = redis::cmd("PUBLISH")
+        .arg("config_reload")
+        .arg(CLEANUP_DRY_RUN_SIGNAL)
+        .query_async(&mut conn)
+        .await?;
+
+    tracing::info!(admin_id = %admin.id, "Admin requested cleanup dry run");
+
+    Ok(Json(CleanupDryRunResponse {
+        success: receivers > 0,
+        message: if receivers > 0 {
+            "Dry run requested; reports will appear shortly".to_string()
+        } else {
+            "No Horus instance is listening; dry run not started".to_string()
+        },
+    }))
+}
+
+/// GET /api/v1/admin/cleanup/dry-run
+///
+/// Latest dry-run report per artifact class: what would be deleted.
+pub async fn list_cleanup_reports(
+    State(state): State<AppState>,
+) -> ApiResult<Json<CleanupReportListResponse>> {
+    let rows: Vec<CleanupReportRow> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (artifact_class)
+               artifact_class, items, items_count, total_bytes, errors, created_at
+        FROM cleanup_reports
+        ORDER BY artifact_class, created_at DESC
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(CleanupReportListResponse {
+        reports: rows
+            .into_iter()
+            .map(|r| CleanupReportResponse {
+                artifact_class: r.artifact_class,
+                items: r.items,
+                items_count: r.items_count,
+                total_bytes: r.total_bytes,
+                errors: r.errors,
+                created_at: r.created_at,
+            })
+            .collect(),
+    }))
+}
+
+// =============================================================================
+// 7.2 Container Management
+// =============================================================================
+
+/// GET /api/v1/admin/containers
+///
+/// List running Docker containers that are part of the system (Sisyphus
+/// compilation containers). Uses `docker ps` and `docker stats` under the
+/// hood.
+pub async fn list_containers(
+    State(state): State<AppState>,
+) -> ApiResult<Json<ContainerListResponse>> {
+    use std::collections::HashMap;
+    use tokio::process::Command;
+
+    // Known compilation images — any running container using one of these is
+    // likely a Sisyphus compile container. Images come from the runtime
+    // registry (repository part only, so tag changes still match) plus the
+    // generic fallback image.
+    let registered_images: Vec<String> = sqlx::query_scalar("SELECT image FROM runtimes")
+        .fetch_all(&state.db)
+        .await?;
+    let mut known_images: Vec<String> = registered_images
+        .iter()
+        .map(|image| image.split(':').next().unwrap_or(image).to_string())
+        .collect();
+    known_images.push("ubuntu".to_string());
+
+    // 1. List running containers as JSON
+    let ps_output = Command::new("docker")
+        .args([
+            "ps",
+            "--no-trunc",
+            "--format",
+            "{{.ID}}\t{{.Image}}\t{{.Status}}\t{{.CreatedAt}}\t{{.State}}",
         ])
         .stdin(std::process::Stdio::null())
         .stdout(std::process::Stdio::piped())
@@ -978,9 +2338,7 @@ pub async fn list_containers(
 
         // Filter: only include containers whose image matches a known
         // compilation image prefix.
-        let is_sisyphus = known_images
-            .iter()
-            .any(|prefix| image.starts_with(prefix));
+        let is_sisyphus = known_images.iter().any(|prefix| image.starts_with(prefix));
         if !is_sisyphus {
             continue;
         }
@@ -1074,10 +2432,11 @@ pub async fn list_containers(
 
 /// Database row for submissions to rejudge
 #[derive(Debug, FromRow)]
-struct RejudgeSubmissionRow {
+pub(crate) struct RejudgeSubmissionRow {
     id: Uuid,
     status: String,
     file_path: Option<String>,
+    organization_id: Option<Uuid>,
 }
 
 /// POST /api/v1/admin/contests/{id}/rejudge
@@ -1102,17 +2461,48 @@ pub async fn rejudge_contest(
 
     // Fetch all submissions for this contest
     let submissions: Vec<RejudgeSubmissionRow> = sqlx::query_as(
-        "SELECT id, status, file_path FROM submissions WHERE contest_id = $1",
+        "SELECT id, status, file_path, organization_id FROM submissions WHERE contest_id = $1",
     )
     .bind(contest_id)
     .fetch_all(&state.db)
     .await?;
 
+    let (rejudged_count, skipped_count) =
+        rejudge_submissions(&state, &submissions, admin.id).await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        contest_id = %contest_id,
+        rejudged = rejudged_count,
+        skipped = skipped_count,
+        "Admin requested contest-wide rejudge"
+    );
+
+    Ok(Json(ContestRejudgeResponse {
+        contest_id,
+        rejudged_count,
+        skipped_count,
+        message: format!(
+            "Rejudged {} submissions, skipped {} (in-progress)",
+            rejudged_count, skipped_count
+        ),
+    }))
+}
+
+/// Reset `submissions` and push them to the low priority compile stream.
+/// Submissions still compiling or judging are skipped. Returns the rejudged
+/// and skipped counts, or `QuotaExceeded` if `requested_by` already has as
+/// many rejudges running as their quota allows.
+pub(crate) async fn rejudge_submissions(
+    state: &AppState,
+    submissions: &[RejudgeSubmissionRow],
+    requested_by: Uuid,
+) -> ApiResult<(usize, usize)> {
     let mut rejudged_count = 0usize;
     let mut skipped_count = 0usize;
     let mut rejudge_ids: Vec<Uuid> = Vec::new();
 
-    for sub in &submissions {
+    for sub in submissions {
         if sub.status == "compiling" || sub.status == "judging" {
             skipped_count += 1;
             continue;
@@ -1121,6 +2511,18 @@ pub async fn rejudge_contest(
     }
 
     if !rejudge_ids.is_empty() {
+        quota::enforce_rejudge_quota(&state.db, requested_by).await?;
+
+        // Track the rejudge so it counts against the concurrent rejudge quota
+        // until its submissions are judged
+        let batch_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO rejudge_batches (requested_by, submission_count) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(requested_by)
+        .bind(rejudge_ids.len() as i32)
+        .fetch_one(&state.db)
+        .await?;
+
         // Batch reset all eligible submissions
         sqlx::query(
             r#"
@@ -1130,13 +2532,17 @@ pub async fn rejudge_contest(
                 passed_test_cases = NULL,
                 max_time_ms = NULL,
                 max_memory_kb = NULL,
+                total_instructions = NULL,
                 compilation_log = NULL,
                 compiled_at = NULL,
-                judged_at = NULL
+                judged_at = NULL,
+                reconcile_attempts = 0,
+                rejudge_batch_id = $2
             WHERE id = ANY($1)
             "#,
         )
         .bind(&rejudge_ids)
+        .bind(batch_id)
         .execute(&state.db)
         .await?;
 
@@ -1146,42 +2552,1283 @@ pub async fn rejudge_contest(
             .execute(&state.db)
             .await?;
 
-        // Push each submission to compile_queue
+        // Push each submission to the low priority compile stream
+        let compile_stream = JudgePriority::Low.stream_name(&state.config.compile_stream);
         let mut conn = state.redis.get().await?;
-        for sub in &submissions {
+        for sub in submissions {
             if !rejudge_ids.contains(&sub.id) {
                 continue;
             }
-            redis::cmd("XADD")
-                .arg("compile_queue")
+            let mut xadd = redis::cmd("XADD");
+            xadd.arg(&compile_stream)
                 .arg("*")
                 .arg("submission_id")
                 .arg(sub.id.to_string())
                 .arg("file_path")
-                .arg(sub.file_path.as_deref().unwrap_or(""))
-                .arg("priority")
-                .arg("1")
-                .query_async::<String>(&mut conn)
-                .await?;
+                .arg(sub.file_path.as_deref().unwrap_or(""));
+            if let Some(tenant) = sub.organization_id {
+                xadd.arg("tenant").arg(tenant.to_string());
+            }
+            tag_stream_entry(&mut xadd);
+            xadd.query_async::<String>(&mut conn).await?;
             rejudged_count += 1;
         }
     }
 
+    Ok((rejudged_count, skipped_count))
+}
+
+// =============================================================================
+// 7.6 Test Reproduction
+// =============================================================================
+
+const TEST_REPRODUCTION_COLUMNS: &str = "id, submission_id, test_case_number, generator_seed, \
+     generator_version, original_verdict, status, verdict, time_ms, memory_kb, checker_output, \
+     error, capture_limit_bytes, stdout, stderr, program_output, checker_log, output_truncated, \
+     requested_by, created_at, completed_at";
+
+/// How long a debug run is waited for before answering with the pending run
+const DEBUG_RUN_WAIT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How often a debug run is checked while waiting for it
+const DEBUG_RUN_POLL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Database row for a judged test about to be reproduced
+#[derive(Debug, FromRow)]
+struct JudgedTestRow {
+    verdict: String,
+    generator_seed: Option<String>,
+    generator_version: Option<i32>,
+    current_generator_version: i32,
+    has_artifact: bool,
+}
+
+/// Database row for test_reproductions
+#[derive(Debug, FromRow)]
+struct TestReproductionRow {
+    id: Uuid,
+    submission_id: Uuid,
+    test_case_number: i32,
+    generator_seed: String,
+    generator_version: i32,
+    original_verdict: String,
+    status: String,
+    verdict: Option<String>,
+    time_ms: Option<i32>,
+    memory_kb: Option<i32>,
+    checker_output: Option<String>,
+    error: Option<String>,
+    capture_limit_bytes: Option<i32>,
+    stdout: Option<String>,
+    stderr: Option<String>,
+    program_output: Option<String>,
+    checker_log: Option<String>,
+    output_truncated: Option<bool>,
+    requested_by: Option<Uuid>,
+    created_at: chrono::DateTime<Utc>,
+    completed_at: Option<chrono::DateTime<Utc>>,
+}
+
+impl From<TestReproductionRow> for TestReproductionResponse {
+    fn from(row: TestReproductionRow) -> Self {
+        let debug = row
+            .capture_limit_bytes
+            .map(|capture_limit_bytes| DebugCaptureInfo {
+                capture_limit_bytes,
+                stdout: row.stdout,
+                stderr: row.stderr,
+                output: row.program_output,
+                checker_log: row.checker_log,
+                truncated: row.output_truncated,
+            });
+
+        TestReproductionResponse {
+            matches_original: row.verdict.as_ref().map(|v| *v == row.original_verdict),
+            id: row.id,
+            submission_id: row.submission_id,
+            test_case_number: row.test_case_number,
+            generator_seed: row.generator_seed,
+            generator_version: row.generator_version,
+            original_verdict: row.original_verdict,
+            status: row.status,
+            verdict: row.verdict,
+            time_ms: row.time_ms,
+            memory_kb: row.memory_kb,
+            checker_output: row.checker_output,
+            error: row.error,
+            debug,
+            requested_by: row.requested_by,
+            created_at: row.created_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+/// POST /api/v1/admin/submissions/{id}/reproduce/{test}
+///
+/// Run one judged test of a submission again with the generator seed and
+/// version it was judged with. A Minos worker picks the request up from the
+/// regeneration stream; poll `GET /api/v1/admin/reproductions/{id}` for the result.
+pub async fn reproduce_test(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path((submission_id, test)): Path<(Uuid, i32)>,
+) -> ApiResult<(StatusCode, Json<TestReproductionResponse>)> {
+    let row = queue_reproduction(&state, &admin, submission_id, test, None).await?;
+    Ok((StatusCode::ACCEPTED, Json(row.into())))
+}
+
+/// POST /api/v1/admin/submissions/{id}/debug-run
+///
+/// Reproduce one judged test of a submission, keeping the program's stdout,
+/// stderr and output file and the checker's comment, for investigating
+/// disputed verdicts. Small tests finish within a few seconds and are
+/// answered with the result (`200`); otherwise the pending run is returned
+/// (`202`) to poll at `GET /api/v1/admin/reproductions/{id}`.
+pub async fn debug_run(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(submission_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<DebugRunRequest>,
+) -> ApiResult<(StatusCode, Json<TestReproductionResponse>)> {
+    let row = queue_reproduction(
+        &state,
+        &admin,
+        submission_id,
+        payload.test,
+        Some(payload.capture_limit_bytes),
+    )
+    .await?;
+
+    let deadline = tokio::time::Instant::now() + DEBUG_RUN_WAIT;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(DEBUG_RUN_POLL).await;
+        let current = sqlx::query_as::<_, TestReproductionRow>(&format!(
+            "SELECT {} FROM test_reproductions WHERE id = $1",
+            TEST_REPRODUCTION_COLUMNS
+        ))
+        .bind(row.id)
+        .fetch_one(&state.db)
+        .await?;
+        if current.status != "pending" {
+            return Ok((StatusCode::OK, Json(current.into())));
+        }
+    }
+
+    Ok((StatusCode::ACCEPTED, Json(row.into())))
+}
+
+/// Record a reproduction of a judged test and queue it for a Minos worker.
+/// A capture limit makes it a debug run.
+async fn queue_reproduction(
+    state: &AppState,
+    admin: &AuthUser,
+    submission_id: Uuid,
+    test: i32,
+    capture_limit_bytes: Option<i32>,
+) -> ApiResult<TestReproductionRow> {
+    let judged = sqlx::query_as::<_, JudgedTestRow>(
+        r#"
+        SELECT r.verdict, r.generator_seed, r.generator_version,
+               p.generator_version AS current_generator_version,
+               EXISTS (SELECT 1 FROM submission_artifacts a WHERE a.submission_id = s.id)
+                   AS has_artifact
+        FROM submissions s
+        JOIN problems p ON p.id = s.problem_id
+        JOIN submission_results r ON r.submission_id = s.id AND r.test_case_number = $2
+        WHERE s.id = $1
+        "#,
+    )
+    .bind(submission_id)
+    .bind(test)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Test result not found".to_string()))?;
+
+    let (Some(seed), Some(version)) = (judged.generator_seed, judged.generator_version) else {
+        return Err(ApiError::Conflict(
+            "This test was judged before generator seeds were recorded".to_string(),
+        ));
+    };
+    if version != judged.current_generator_version {
+        return Err(ApiError::Conflict(format!(
+            "The problem's generator has changed since this test was judged (v{} -> v{})",
+            version, judged.current_generator_version
+        )));
+    }
+    if !judged.has_artifact {
+        return Err(ApiError::Conflict(
+            "The submission's compiled artifact no longer exists".to_string(),
+        ));
+    }
+
+    let row = sqlx::query_as::<_, TestReproductionRow>(&format!(
+        r#"
+        INSERT INTO test_reproductions
+            (submission_id, test_case_number, generator_seed, generator_version,
+             original_verdict, capture_limit_bytes, requested_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {}
+        "#,
+        TEST_REPRODUCTION_COLUMNS
+    ))
+    .bind(submission_id)
+    .bind(test)
+    .bind(&seed)
+    .bind(version)
+    .bind(&judged.verdict)
+    .bind(capture_limit_bytes)
+    .bind(admin.id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let mut conn = state.redis.get().await?;
+    redis::cmd("XADD")
+        .arg(&state.config.regen_stream)
+        .arg("*")
+        .arg("kind")
+        .arg("reproduce")
+        .arg("reproduction_id")
+        .arg(row.id.to_string())
+        .query_async::<String>(&mut conn)
+        .await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        submission_id = %submission_id,
+        test = test,
+        reproduction_id = %row.id,
+        debug = capture_limit_bytes.is_some(),
+        "Admin requested test reproduction"
+    );
+
+    Ok(row)
+}
+
+/// GET /api/v1/admin/reproductions/{id}
+///
+/// Status and result of a test reproduction.
+pub async fn get_reproduction(
+    State(state): State<AppState>,
+    Path(reproduction_id): Path<Uuid>,
+) -> ApiResult<Json<TestReproductionResponse>> {
+    let row = sqlx::query_as::<_, TestReproductionRow>(&format!(
+        "SELECT {} FROM test_reproductions WHERE id = $1",
+        TEST_REPRODUCTION_COLUMNS
+    ))
+    .bind(reproduction_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Reproduction not found".to_string()))?;
+
+    Ok(Json(row.into()))
+}
+
+// =============================================================================
+// 7.7 Submission Search
+// =============================================================================
+
+/// Shortest search text; trigram indexes need three characters to help
+const MIN_SEARCH_CHARS: usize = 3;
+
+/// Longest search text
+const MAX_SEARCH_CHARS: usize = 200;
+
+/// Matching lines returned per submission
+const MAX_MATCHES_PER_SUBMISSION: usize = 5;
+
+/// Characters of a matching line returned
+const MAX_MATCH_LINE_CHARS: usize = 200;
+
+/// Database row for a submission found by source search
+#[derive(Debug, FromRow)]
+struct SubmissionSearchRow {
+    id: Uuid,
+    user_id: Uuid,
+    username: String,
+    problem_id: Uuid,
+    contest_id: Option<Uuid>,
+    language: Option<String>,
+    status: String,
+    submitted_at: chrono::DateTime<Utc>,
+    source_code: String,
+}
+
+/// GET /api/v1/admin/submissions/search
+///
+/// Submissions whose source contains the given text, newest first, with the
+/// lines it appears on. Meant for cheating investigations, e.g. finding
+/// every submission to a contest that contains a leaked solution. Archive
+/// submissions have no stored source and are never found.
+pub async fn search_submissions(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Query(query): Query<SearchSubmissionsQuery>,
+) -> ApiResult<Json<SubmissionSearchResponse>> {
+    let chars = query.q.chars().count();
+    if !(MIN_SEARCH_CHARS..=MAX_SEARCH_CHARS).contains(&chars) {
+        return Err(ApiError::Validation(format!(
+            "Search text must be {}-{} characters",
+            MIN_SEARCH_CHARS, MAX_SEARCH_CHARS
+        )));
+    }
+
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+
+    // LIKE wildcards in the text match themselves
+    let pattern = format!(
+        "%{}%",
+        query
+            .q
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let operator = if query.case_sensitive {
+        "LIKE"
+    } else {
+        "ILIKE"
+    };
+    let filter = format!(
+        "s.source_code {} $1 \
+         AND ($2::uuid IS NULL OR s.contest_id = $2) \
+         AND ($3::uuid IS NULL OR s.problem_id = $3) \
+         AND ($4::uuid IS NULL OR s.user_id = $4)",
+        operator
+    );
+
+    let rows = sqlx::query_as::<_, SubmissionSearchRow>(&format!(
+        r#"
+        SELECT s.id, s.user_id, u.username, s.problem_id, s.contest_id, s.language,
+               s.status, s.submitted_at, s.source_code
+        FROM submissions s
+        JOIN users u ON u.id = s.user_id
+        WHERE {}
+        ORDER BY s.submitted_at DESC
+        LIMIT $5 OFFSET $6
+        "#,
+        filter
+    ))
+    .bind(&pattern)
+    .bind(query.contest_id)
+    .bind(query.problem_id)
+    .bind(query.user_id)
+    .bind(per_page as i64 + 1)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    // Counting every match would scan the whole index on each page; one
+    // extra row tells whether there is a next page
+    let has_more = rows.len() > per_page as usize;
+    let rows = rows.into_iter().take(per_page as usize);
+
+    tracing::info!(
+        admin_id = %admin.id,
+        query = %query.q,
+        contest_id = ?query.contest_id,
+        page,
+        "Admin searched submission sources"
+    );
+
+    let needle = if query.case_sensitive {
+        query.q.clone()
+    } else {
+        query.q.to_lowercase()
+    };
+
+    Ok(Json(SubmissionSearchResponse {
+        submissions: rows
+            .map(|row| {
+                let (matches, match_count) =
+                    matching_lines(&row.source_code, &needle, query.case_sensitive);
+                SubmissionSearchHit {
+                    submission_id: row.id,
+                    user_id: row.user_id,
+                    username: row.username,
+                    problem_id: row.problem_id,
+                    contest_id: row.contest_id,
+                    language: row.language,
+                    status: row.status,
+                    submitted_at: row.submitted_at,
+                    matches,
+                    match_count,
+                }
+            })
+            .collect(),
+        query: query.q,
+        case_sensitive: query.case_sensitive,
+        page,
+        per_page,
+        has_more,
+    }))
+}
+
+/// The first few lines of `source` containing `needle` (already lowercased
+/// unless the search is case-sensitive), and how many lines contain it.
+fn matching_lines(source: &str, needle: &str, case_sensitive: bool) -> (Vec<SourceMatch>, usize) {
+    let mut matches = Vec::new();
+    let mut count = 0;
+    for (idx, line) in source.lines().enumerate() {
+        let found = if case_sensitive {
+            line.contains(needle)
+        } else {
+            line.to_lowercase().contains(needle)
+        };
+        if !found {
+            continue;
+        }
+        count += 1;
+        if matches.len() < MAX_MATCHES_PER_SUBMISSION {
+            matches.push(SourceMatch {
+                line: idx + 1,
+                text: line.trim().chars().take(MAX_MATCH_LINE_CHARS).collect(),
+            });
+        }
+    }
+    (matches, count)
+}
+
+// =============================================================================
+// 7.8 Upload Scans
+// =============================================================================
+
+/// Database row for an upload scan
+#[derive(Debug, FromRow)]
+struct UploadScanRow {
+    id: Uuid,
+    target_type: String,
+    target_id: Uuid,
+    scanner: String,
+    status: String,
+    detail: Option<String>,
+    checksum: String,
+    size_bytes: i64,
+    action: String,
+    uploaded_by: Option<Uuid>,
+    created_at: chrono::DateTime<Utc>,
+}
+
+/// GET /api/v1/admin/upload-scans
+///
+/// Scan results for uploaded binaries and submissions, newest first.
+pub async fn list_upload_scans(
+    State(state): State<AppState>,
+    Query(query): Query<ListUploadScansQuery>,
+) -> ApiResult<Json<UploadScanListResponse>> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+
+    let rows: Vec<UploadScanRow> = sqlx::query_as(
+        r#"
+        SELECT id, target_type, target_id, scanner, status, detail, checksum, size_bytes,
+               action, uploaded_by, created_at
+        FROM upload_scans
+        WHERE ($1::text IS NULL OR status = $1) AND ($2::text IS NULL OR target_type = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(&query.status)
+    .bind(&query.target_type)
+    .bind(per_page as i64)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM upload_scans
+        WHERE ($1::text IS NULL OR status = $1) AND ($2::text IS NULL OR target_type = $2)
+        "#,
+    )
+    .bind(&query.status)
+    .bind(&query.target_type)
+    .fetch_one(&state.db)
+    .await?;
+
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
+
+    Ok(Json(UploadScanListResponse {
+        scans: rows
+            .into_iter()
+            .map(|r| UploadScanResponse {
+                id: r.id,
+                target_type: r.target_type,
+                target_id: r.target_id,
+                scanner: r.scanner,
+                status: r.status,
+                detail: r.detail,
+                checksum: r.checksum,
+                size_bytes: r.size_bytes,
+                action: r.action,
+                uploaded_by: r.uploaded_by,
+                created_at: r.created_at,
+            })
+            .collect(),
+        pagination: Pagination {
+            page,
+            per_page,
+            total,
+            total_pages,
+        },
+    }))
+}
+
+// =============================================================================
+// 7.9 Contest Storage
+// =============================================================================
+
+/// GET /api/v1/admin/contests/{id}/storage
+///
+/// Get the storage root of a contest's submissions and artifacts.
+pub async fn get_contest_storage(
+    State(state): State<AppState>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<ContestStorageResponse>> {
+    let storage_root: Option<String> =
+        sqlx::query_scalar("SELECT storage_root FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
+
+    Ok(Json(contest_storage_response(
+        &state,
+        contest_id,
+        storage_root,
+    )))
+}
+
+/// PUT /api/v1/admin/contests/{id}/storage
+///
+/// Set the storage root of a contest, for data residency requirements. Only
+/// data stored afterwards goes there; submissions and artifacts already
+/// stored stay where they are.
+pub async fn update_contest_storage(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateContestStorageRequest>,
+) -> ApiResult<Json<ContestStorageResponse>> {
+    if let Some(ref root) = payload.storage_root {
+        if !state.config.storage_roots.is_contest_root(root) {
+            return Err(ApiError::Validation(format!(
+                "Storage root '{}' is not one of the configured contest storage roots",
+                root
+            )));
+        }
+        let is_dir = tokio::fs::metadata(root)
+            .await
+            .map(|meta| meta.is_dir())
+            .unwrap_or(false);
+        if !is_dir {
+            return Err(ApiError::Validation(format!(
+                "Storage root '{}' is not a directory",
+                root
+            )));
+        }
+    }
+
+    let updated = sqlx::query(
+        "UPDATE contests SET storage_root = $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(&payload.storage_root)
+    .bind(contest_id)
+    .execute(&state.db)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Contest not found".to_string()));
+    }
+
     tracing::info!(
         admin_id = %admin.id,
         contest_id = %contest_id,
-        rejudged = rejudged_count,
-        skipped = skipped_count,
-        "Admin requested contest-wide rejudge"
+        storage_root = ?payload.storage_root,
+        "Admin changed contest storage root"
     );
 
-    Ok(Json(ContestRejudgeResponse {
+    Ok(Json(contest_storage_response(
+        &state,
         contest_id,
-        rejudged_count,
-        skipped_count,
-        message: format!(
-            "Rejudged {} submissions, skipped {} (in-progress)",
-            rejudged_count, skipped_count
-        ),
+        payload.storage_root,
+    )))
+}
+
+fn contest_storage_response(
+    state: &AppState,
+    contest_id: Uuid,
+    storage_root: Option<String>,
+) -> ContestStorageResponse {
+    ContestStorageResponse {
+        contest_id,
+        storage_root,
+        available_roots: state
+            .config
+            .storage_roots
+            .contest_roots()
+            .iter()
+            .map(|root| root.to_string_lossy().into_owned())
+            .collect(),
+    }
+}
+
+// =============================================================================
+// 7.10 Problem Backups
+// =============================================================================
+
+/// Database row for a problem backup
+#[derive(Debug, FromRow)]
+struct ProblemBackupRow {
+    id: Uuid,
+    problem_id: Uuid,
+    problem_title: String,
+    version: i32,
+    archive_path: String,
+    size_bytes: i64,
+    checksum: String,
+    problem_exists: bool,
+    created_at: chrono::DateTime<Utc>,
+    restored_at: Option<chrono::DateTime<Utc>>,
+    restored_by: Option<Uuid>,
+}
+
+impl From<ProblemBackupRow> for ProblemBackupResponse {
+    fn from(row: ProblemBackupRow) -> Self {
+        ProblemBackupResponse {
+            id: row.id,
+            problem_id: row.problem_id,
+            problem_title: row.problem_title,
+            version: row.version,
+            size_bytes: row.size_bytes,
+            checksum: row.checksum,
+            problem_exists: row.problem_exists,
+            created_at: row.created_at,
+            restored_at: row.restored_at,
+            restored_by: row.restored_by,
+        }
+    }
+}
+
+const PROBLEM_BACKUP_COLUMNS: &str = "b.id, b.problem_id, b.problem_title, b.version, \
+     b.archive_path, b.size_bytes, b.checksum, \
+     EXISTS (SELECT 1 FROM problems p WHERE p.id = b.problem_id) AS problem_exists, \
+     b.created_at, b.restored_at, b.restored_by";
+
+/// GET /api/v1/admin/problem-backups
+///
+/// List problem package backups, newest first.
+pub async fn list_problem_backups(
+    State(state): State<AppState>,
+    Query(query): Query<ListProblemBackupsQuery>,
+) -> ApiResult<Json<ProblemBackupListResponse>> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+
+    let filter = "($1::uuid IS NULL OR b.problem_id = $1) \
+         AND (NOT $2 OR NOT EXISTS (SELECT 1 FROM problems p WHERE p.id = b.problem_id))";
+
+    let sql = format!(
+        "SELECT {} FROM problem_backups b WHERE {} \
+         ORDER BY b.created_at DESC, b.version DESC LIMIT $3 OFFSET $4",
+        PROBLEM_BACKUP_COLUMNS, filter
+    );
+    let rows: Vec<ProblemBackupRow> = sqlx::query_as(&sql)
+        .bind(query.problem_id)
+        .bind(query.deleted)
+        .bind(per_page as i64)
+        .bind(offset)
+        .fetch_all(&state.db)
+        .await?;
+
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM problem_backups b WHERE {}",
+        filter
+    ))
+    .bind(query.problem_id)
+    .bind(query.deleted)
+    .fetch_one(&state.db)
+    .await?;
+
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
+
+    Ok(Json(ProblemBackupListResponse {
+        backups: rows.into_iter().map(ProblemBackupResponse::from).collect(),
+        pagination: Pagination {
+            page,
+            per_page,
+            total,
+            total_pages,
+        },
+    }))
+}
+
+/// POST /api/v1/admin/problem-backups/{id}/restore
+///
+/// Recreate a deleted problem from a backup: its metadata, test cases,
+/// subtasks, statements, binary versions and checker tests, and the files of
+/// its binaries directory. Submissions and contest links are not part of a
+/// backup. A problem that still exists is not overwritten.
+pub async fn restore_problem_backup(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(backup_id): Path<Uuid>,
+) -> ApiResult<Json<RestoreProblemBackupResponse>> {
+    let sql = format!(
+        "SELECT {} FROM problem_backups b WHERE b.id = $1",
+        PROBLEM_BACKUP_COLUMNS
+    );
+    let backup: ProblemBackupRow = sqlx::query_as(&sql)
+        .bind(backup_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Backup not found".to_string()))?;
+
+    if backup.problem_exists {
+        return Err(ApiError::Conflict(
+            "Problem still exists; only deleted problems can be restored".to_string(),
+        ));
+    }
+
+    let binaries_dir = state
+        .config
+        .storage_roots
+        .base()
+        .join(PROBLEM_BINARIES_DIR)
+        .join(backup.problem_id.to_string());
+    let (manifest, files_restored) = {
+        let archive_path = backup.archive_path.clone();
+        let checksum = backup.checksum.clone();
+        tokio::task::spawn_blocking(move || {
+            extract_problem_backup(
+                std::path::Path::new(&archive_path),
+                &checksum,
+                &binaries_dir,
+            )
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("Restore task failed: {}", e)))??
+    };
+    if manifest.problem_id != backup.problem_id {
+        return Err(ApiError::Conflict(
+            "Backup archive belongs to a different problem".to_string(),
+        ));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let mut rows_restored = 0;
+    for &table in BACKUP_TABLES {
+        let Some(rows) = manifest.tables.get(table) else {
+            continue;
+        };
+        let sql = format!(
+            "INSERT INTO {0} SELECT * FROM jsonb_populate_recordset(NULL::{0}, $1::jsonb)",
+            table
+        );
+        rows_restored += sqlx::query(&sql)
+            .bind(rows.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    ApiError::Conflict(format!(
+                        "Backup references rows that no longer exist: {}",
+                        db.message()
+                    ))
+                }
+                e => ApiError::Database(e),
+            })?
+            .rows_affected();
+    }
+
+    sqlx::query("UPDATE problem_backups SET restored_at = NOW(), restored_by = $1 WHERE id = $2")
+        .bind(admin.id)
+        .bind(backup_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        backup_id = %backup_id,
+        problem_id = %backup.problem_id,
+        version = backup.version,
+        rows_restored,
+        files_restored,
+        "Admin restored problem from backup"
+    );
+
+    let mut restored = ProblemBackupResponse::from(backup);
+    restored.problem_exists = true;
+    restored.restored_at = Some(Utc::now());
+    restored.restored_by = Some(admin.id);
+
+    Ok(Json(RestoreProblemBackupResponse {
+        backup: restored,
+        rows_restored,
+        files_restored,
     }))
 }
+
+/// Verify a backup archive, write its binaries into `binaries_dir` and
+/// return its manifest with the number of files written
+fn extract_problem_backup(
+    archive_path: &std::path::Path,
+    checksum: &str,
+    binaries_dir: &std::path::Path,
+) -> ApiResult<(BackupManifest, u32)> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+    use std::os::unix::fs::PermissionsExt;
+
+    let internal = |e: std::io::Error| ApiError::Internal(format!("Backup archive: {}", e));
+    let corrupt = |e: zip::result::ZipError| {
+        ApiError::Conflict(format!("Backup archive is unreadable: {}", e))
+    };
+
+    let mut file = std::fs::File::open(archive_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            ApiError::Conflict("Backup archive is missing from storage".to_string())
+        }
+        _ => internal(e),
+    })?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(internal)?;
+    if format!("{:x}", hasher.finalize()) != checksum {
+        return Err(ApiError::Conflict(
+            "Backup archive does not match its checksum".to_string(),
+        ));
+    }
+
+    let mut archive = zip::ZipArchive::new(file).map_err(corrupt)?;
+    let manifest: BackupManifest = {
+        let mut json = String::new();
+        archive
+            .by_name(MANIFEST_FILE)
+            .map_err(corrupt)?
+            .read_to_string(&mut json)
+            .map_err(internal)?;
+        serde_json::from_str(&json)
+            .map_err(|e| ApiError::Conflict(format!("Backup manifest is invalid: {}", e)))?
+    };
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(ApiError::Conflict(format!(
+            "Backup format {} is newer than this server supports",
+            manifest.format_version
+        )));
+    }
+
+    let mut files = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(corrupt)?;
+        if !entry.is_file() {
+            continue;
+        }
+        // Entries that would escape the binaries directory are skipped
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let Ok(relative) = name.strip_prefix(ARCHIVE_BINARIES_PREFIX) else {
+            continue;
+        };
+        let target = binaries_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(internal)?;
+        }
+        let mut out = std::fs::File::create(&target).map_err(internal)?;
+        std::io::copy(&mut entry, &mut out).map_err(internal)?;
+        if let Some(mode) = entry.unix_mode() {
+            std::fs::set_permissions(&target, std::fs::Permissions::from_mode(mode))
+                .map_err(internal)?;
+        }
+        files += 1;
+    }
+
+    Ok((manifest, files))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::repositories::testing::TestDb;
+
+    /// (table, column) of every `REFERENCES users` in the migrations
+    fn migration_user_references() -> Vec<(String, String)> {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+        let mut references = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let sql = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            let mut table = String::new();
+            for line in sql.lines() {
+                let line = line.trim();
+                for prefix in [
+                    "CREATE TABLE IF NOT EXISTS ",
+                    "CREATE TABLE ",
+                    "ALTER TABLE ",
+                ] {
+                    if let Some(rest) = line.strip_prefix(prefix) {
+                        table = rest.split([' ', '(']).next().unwrap().to_string();
+                        break;
+                    }
+                }
+                if line.contains("REFERENCES users") {
+                    let column = line
+                        .trim_start_matches("ADD COLUMN IF NOT EXISTS ")
+                        .trim_start_matches("ADD COLUMN ")
+                        .split_whitespace()
+                        .next()
+                        .unwrap();
+                    references.push((table.clone(), column.to_string()));
+                }
+            }
+        }
+        references
+    }
+
+    #[test]
+    fn test_merge_lists_name_real_user_references() {
+        let references = migration_user_references();
+        for &(table, column) in MERGE_SEPARATE_COLUMNS.iter().chain(MERGE_DROPPED_COLUMNS) {
+            assert!(
+                references.iter().any(|(t, c)| t == table && c == column),
+                "{table}.{column} is not a reference to users"
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_reassigns_single_column_references() {
+        for (table, column) in [
+            ("contest_clarifications", "user_id"),
+            ("contest_clarifications", "answered_by"),
+            ("problem_comments", "author_id"),
+            ("contest_posts", "author_id"),
+            ("sample_runs", "user_id"),
+            ("contests", "owner_id"),
+            ("problems", "owner_id"),
+        ] {
+            assert!(
+                migration_user_references()
+                    .iter()
+                    .any(|(t, c)| t == table && c == column),
+                "{table}.{column} is not a reference to users"
+            );
+            assert!(merge_reassigns(table, column), "{table}.{column}");
+        }
+    }
+
+    #[test]
+    fn test_merge_does_not_reassign_separately_merged_references() {
+        assert!(!merge_reassigns("submissions", "user_id"));
+        assert!(!merge_reassigns("contest_participants", "user_id"));
+        assert!(!merge_reassigns("sessions", "user_id"));
+        assert!(!merge_reassigns("user_solves", "user_id"));
+    }
+
+    #[derive(Debug, FromRow)]
+    struct FixtureColumn {
+        name: String,
+        udt_name: String,
+        not_null: bool,
+        has_default: bool,
+        max_length: Option<i32>,
+    }
+
+    /// Values the generic fixture would get wrong (CHECK constraints other
+    /// than a list of allowed values)
+    const FIXTURE_VALUES: &[(&str, &str, &str)] =
+        &[("contests", "end_time", "NOW() + INTERVAL '1 hour'")];
+
+    /// SQL literal for a column of the `nth` fixture row
+    async fn fixture_value(
+        conn: &mut sqlx::PgConnection,
+        table: &str,
+        column: &FixtureColumn,
+        nth: usize,
+    ) -> String {
+        if let Some(&(_, _, value)) = FIXTURE_VALUES
+            .iter()
+            .find(|&&(t, c, _)| t == table && c == column.name)
+        {
+            return value.to_string();
+        }
+        // `CHECK (column IN (...))`: a different allowed value per row
+        let check: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT pg_get_constraintdef(oid) FROM pg_constraint
+            WHERE conrelid = $1::regclass AND contype = 'c'
+              AND pg_get_constraintdef(oid) LIKE '%((' || $2 || ')::text = ANY%'
+            LIMIT 1
+            "#,
+        )
+        .bind(table)
+        .bind(&column.name)
+        .fetch_optional(&mut *conn)
+        .await
+        .unwrap();
+        if let Some(check) = check {
+            let allowed: Vec<&str> = check.split('\'').skip(1).step_by(2).collect();
+            return format!("'{}'", allowed[nth % allowed.len()]);
+        }
+        let text = |value: String| {
+            let len = column.max_length.map_or(value.len(), |max| max as usize);
+            format!("'{}'", &value[..value.len().min(len)])
+        };
+        match column.udt_name.as_str() {
+            "uuid" => format!("'{}'::uuid", Uuid::new_v4()),
+            "text" | "varchar" | "bpchar" => text(format!("f{}", Uuid::new_v4().simple())),
+            "int2" | "int4" | "int8" | "numeric" | "float4" | "float8" => "1".to_string(),
+            "bool" => "false".to_string(),
+            "timestamptz" | "timestamp" => "NOW()".to_string(),
+            "date" => "CURRENT_DATE".to_string(),
+            "interval" => "INTERVAL '1 hour'".to_string(),
+            "json" | "jsonb" => "'{}'".to_string(),
+            "inet" => "'127.0.0.1'".to_string(),
+            "bytea" => "'\\x00'".to_string(),
+            array if array.starts_with('_') => format!("'{{}}'::{}", array),
+            other => {
+                let label: String = sqlx::query_scalar(
+                    "SELECT e.enumlabel::text FROM pg_enum e JOIN pg_type t ON t.oid = e.enumtypid \
+                     WHERE t.typname = $1 ORDER BY e.enumsortorder LIMIT 1",
+                )
+                .bind(other)
+                .fetch_optional(&mut *conn)
+                .await
+                .unwrap()
+                .unwrap_or_else(|| panic!("no fixture value for {table}.{} ({other})", column.name));
+                format!("'{}'", label)
+            }
+        }
+    }
+
+    /// Give `users` one row each in `table`, referencing them through
+    /// `column`. The rows share every other key column, so a unique key
+    /// that includes `column` makes them collide once merged.
+    async fn insert_fixture_rows(
+        conn: &mut sqlx::PgConnection,
+        table: &str,
+        column: &str,
+        user_columns: &[String],
+        users: [Uuid; 2],
+    ) {
+        let columns: Vec<FixtureColumn> = sqlx::query_as(
+            "SELECT column_name::text AS name, udt_name::text, is_nullable = 'NO' AS not_null, \
+                    (column_default IS NOT NULL OR is_identity = 'YES' OR is_generated = 'ALWAYS') \
+                        AS has_default, \
+                    character_maximum_length::int AS max_length \
+             FROM information_schema.columns \
+             WHERE table_schema = current_schema() AND table_name = $1 \
+             ORDER BY ordinal_position",
+        )
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        // Columns of unique keys without `column` differ between the rows
+        let fresh: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT a.attname::text \
+             FROM pg_index i JOIN pg_attribute a \
+               ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey) \
+             WHERE i.indrelid = $1::regclass AND i.indisunique \
+               AND NOT EXISTS (SELECT 1 FROM pg_attribute c \
+                               WHERE c.attrelid = i.indrelid AND c.attnum = ANY(i.indkey) \
+                                 AND c.attname = $2)",
+        )
+        .bind(table)
+        .bind(column)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+
+        let mut shared: BTreeMap<String, String> = BTreeMap::new();
+        for (nth, user) in users.into_iter().enumerate() {
+            let mut names = Vec::new();
+            let mut values = Vec::new();
+            for c in &columns {
+                let value = if c.name == column || (c.not_null && user_columns.contains(&c.name)) {
+                    format!("'{}'::uuid", user)
+                } else if !c.not_null || c.has_default {
+                    continue;
+                } else if fresh.contains(&c.name) {
+                    fixture_value(conn, table, c, nth).await
+                } else if let Some(value) = shared.get(&c.name) {
+                    value.clone()
+                } else {
+                    let value = fixture_value(conn, table, c, 0).await;
+                    shared.insert(c.name.clone(), value.clone());
+                    value
+                };
+                names.push(format!("\"{}\"", c.name));
+                values.push(value);
+            }
+            sqlx::query(&format!(
+                "INSERT INTO \"{table}\" ({}) VALUES ({})",
+                names.join(", "),
+                values.join(", ")
+            ))
+            .execute(&mut *conn)
+            .await
+            .unwrap_or_else(|e| panic!("fixture row in {table}.{column}: {e}"));
+        }
+    }
+
+    async fn references_to(
+        conn: &mut sqlx::PgConnection,
+        table: &str,
+        column: &str,
+        user: Uuid,
+    ) -> i64 {
+        sqlx::query_scalar(&format!(
+            r#"SELECT COUNT(*) FROM "{table}" WHERE "{column}" = $1"#
+        ))
+        .bind(user)
+        .fetch_one(conn)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_every_user_reference() {
+        let Some(db) = TestDb::new().await else {
+            return;
+        };
+        let survivor = db.user("survivor", "organizer").await;
+        let duplicate = db.user("duplicate", "organizer").await;
+        let mut conn = db.pool.acquire().await.unwrap();
+
+        let references = user_reference_columns(&mut conn).await.unwrap();
+        // Fixture rows point at contests, problems and the like that do not
+        // exist: drop the foreign keys to anything but users, and skip
+        // triggers while inserting. The merge runs with every trigger.
+        sqlx::query(
+            r#"
+            DO $$
+            DECLARE fk record;
+            BEGIN
+                FOR fk IN SELECT conrelid::regclass AS tbl, conname FROM pg_constraint
+                          WHERE contype = 'f' AND confrelid <> 'users'::regclass LOOP
+                    EXECUTE format('ALTER TABLE %s DROP CONSTRAINT %I', fk.tbl, fk.conname);
+                END LOOP;
+            END $$
+            "#,
+        )
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+        sqlx::query("SET session_replication_role = replica")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        for (table, column) in &references {
+            let user_columns: Vec<String> = references
+                .iter()
+                .filter(|(t, _)| t == table)
+                .map(|(_, c)| c.clone())
+                .collect();
+            insert_fixture_rows(
+                &mut conn,
+                table,
+                column,
+                &user_columns,
+                [survivor, duplicate],
+            )
+            .await;
+        }
+        sqlx::query("SET session_replication_role = DEFAULT")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+
+        let mut before = Vec::new();
+        for (table, column) in &references {
+            before.push(
+                references_to(&mut conn, table, column, survivor).await
+                    + references_to(&mut conn, table, column, duplicate).await,
+            );
+        }
+
+        let mut tx = db.pool.begin().await.unwrap();
+        merge_user_rows(&mut tx, survivor, duplicate)
+            .await
+            .unwrap_or_else(|e| panic!("merge failed: {e}"));
+        tx.commit().await.unwrap();
+
+        for ((table, column), before) in references.iter().zip(before) {
+            assert_eq!(
+                references_to(&mut conn, table, column, duplicate).await,
+                0,
+                "{table}.{column}"
+            );
+            let after = references_to(&mut conn, table, column, survivor).await;
+            if merge_reassigns(table, column) {
+                assert_eq!(after, before, "{table}.{column} lost rows");
+            } else {
+                assert!(after >= 1, "{table}.{column} lost the survivor's rows");
+            }
+        }
+        let certified_by: Option<Uuid> =
+            sqlx::query_scalar("SELECT DISTINCT certified_by FROM contest_final_standings")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(certified_by, Some(survivor));
+
+        drop(conn);
+        db.drop().await;
+    }
+
+    fn lines_of(matches: &[SourceMatch]) -> Vec<usize> {
+        matches.iter().map(|m| m.line).collect()
+    }
+
+    #[test]
+    fn test_matching_lines_numbers_and_trims() {
+        let source = "int main() {\n    read_fast();\n}\n  // read_fast v2\n";
+        let (matches, count) = matching_lines(source, "read_fast", true);
+        assert_eq!(count, 2);
+        assert_eq!(lines_of(&matches), [2, 4]);
+        assert_eq!(matches[0].text, "read_fast();");
+        assert_eq!(matches[1].text, "// read_fast v2");
+    }
+
+    #[test]
+    fn test_matching_lines_case() {
+        let source = "FastIO\nfastio\n";
+        let (_, count) = matching_lines(source, "fastio", true);
+        assert_eq!(count, 1);
+        // Case-insensitive searches pass the needle lowercased
+        let (matches, count) = matching_lines(source, "fastio", false);
+        assert_eq!(count, 2);
+        assert_eq!(lines_of(&matches), [1, 2]);
+    }
+
+    #[test]
+    fn test_matching_lines_caps_lines_but_counts_all() {
+        let source = "x\n".repeat(MAX_MATCHES_PER_SUBMISSION + 3);
+        let (matches, count) = matching_lines(&source, "x", true);
+        assert_eq!(count, MAX_MATCHES_PER_SUBMISSION + 3);
+        assert_eq!(matches.len(), MAX_MATCHES_PER_SUBMISSION);
+    }
+
+    #[test]
+    fn test_matching_lines_cuts_long_lines_on_char_boundaries() {
+        let source = format!("needle {}", "é".repeat(MAX_MATCH_LINE_CHARS));
+        let (matches, _) = matching_lines(&source, "needle", true);
+        assert_eq!(matches[0].text.chars().count(), MAX_MATCH_LINE_CHARS);
+        assert!(matches[0].text.starts_with("needle "));
+    }
+
+    #[test]
+    fn test_matching_lines_without_match() {
+        let (matches, count) = matching_lines("a\nb\n", "zzz", false);
+        assert!(matches.is_empty());
+        assert_eq!(count, 0);
+    }
+}
@@ -8,5 +8,4 @@ pub mod request;
 pub mod response;
 
 pub use handler::*;
-pub use request::*;
 pub use response::*;
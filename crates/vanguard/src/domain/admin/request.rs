@@ -1,6 +1,7 @@
 //! Admin request DTOs.
 
 use serde::Deserialize;
+use uuid::Uuid;
 use validator::Validate;
 
 /// Query parameters for admin user listing
@@ -31,22 +32,11 @@ fn default_per_page() -> u32 {
 /// Update user role request
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateUserRoleRequest {
-    #[validate(custom(function = "validate_role"))]
+    /// Name of a built-in or custom role
+    #[validate(length(min = 1, max = 20, message = "Role must be 1-20 characters"))]
     pub role: String,
 }
 
-fn validate_role(role: &str) -> Result<(), validator::ValidationError> {
-    match role {
-        "admin" | "organizer" | "participant" | "spectator" => Ok(()),
-        _ => {
-            let mut err = validator::ValidationError::new("invalid_role");
-            err.message =
-                Some("Role must be one of: admin, organizer, participant, spectator".into());
-            Err(err)
-        }
-    }
-}
-
 /// Ban user request
 #[derive(Debug, Deserialize, Validate)]
 pub struct BanUserRequest {
@@ -54,24 +44,53 @@ pub struct BanUserRequest {
     pub reason: String,
 }
 
-/// Query for queue listing
-#[derive(Debug, Deserialize, Default)]
-pub struct QueueQuery {
-    /// Stream name: compile_queue or run_queue
-    pub stream: Option<String>,
-    /// Maximum entries to return
-    #[serde(default = "default_queue_count")]
-    pub count: u32,
+/// Anonymize a user now, skipping their grace period
+#[derive(Debug, Deserialize, Validate, Default)]
+pub struct AnonymizeUserRequest {
+    /// Delete their submissions instead of keeping them anonymously;
+    /// defaults to `ACCOUNT_DELETION_SUBMISSIONS`
+    pub delete_submissions: Option<bool>,
+}
+
+/// User quota request. All limits are replaced; `null` removes a limit.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateUserQuotaRequest {
+    #[validate(range(min = 0, message = "Problem limit must not be negative"))]
+    pub max_problems: Option<i32>,
+
+    #[validate(range(min = 1, message = "Storage quota must be positive"))]
+    pub max_storage_bytes: Option<i64>,
+
+    #[validate(range(min = 1, message = "Concurrent rejudge limit must be positive"))]
+    pub max_concurrent_rejudges: Option<i32>,
+}
+
+/// Query for storage usage history
+#[derive(Debug, Deserialize)]
+pub struct StorageHistoryQuery {
+    /// Days of history to return (1-90)
+    #[serde(default = "default_history_days")]
+    pub days: u32,
+    /// Only this storage category
+    pub category: Option<String>,
+}
+
+fn default_history_days() -> u32 {
+    7
 }
 
 fn default_queue_count() -> u32 {
     50
 }
 
-/// Rejudge request
+/// Query for browsing the entries of a stream
 #[derive(Debug, Deserialize)]
-pub struct RejudgeRequest {
-    pub submission_id: uuid::Uuid,
+pub struct StreamEntriesQuery {
+    /// First entry id to return (inclusive); defaults to the oldest
+    pub start: Option<String>,
+    /// Maximum entries to return (max 200)
+    #[serde(default = "default_queue_count")]
+    pub count: u32,
 }
 
 /// Save rule config request
@@ -135,3 +154,89 @@ pub struct ListRulesQuery {
     /// Filter by enabled status
     pub enabled: Option<bool>,
 }
+
+/// Update cleanup policy request
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCleanupPolicyRequest {
+    /// Whether the cleanup job runs
+    pub enabled: Option<bool>,
+    /// Age in hours after which artifacts are eligible (up to 10 years)
+    #[validate(range(min = 1, max = 87600, message = "Retention must be 1-87600 hours"))]
+    pub retention_hours: Option<i32>,
+    /// Report candidates instead of deleting them
+    pub dry_run: Option<bool>,
+}
+
+/// Debug run of one judged test of a submission
+#[derive(Debug, Deserialize, Validate)]
+pub struct DebugRunRequest {
+    /// Test case number (1-indexed)
+    #[validate(range(min = 1, message = "Test number must be at least 1"))]
+    pub test: i32,
+    /// Bytes kept of each of stdout, stderr, the output file and the
+    /// checker's comment (default 64 KiB, at most 1 MiB)
+    #[serde(default = "default_capture_limit_bytes")]
+    #[validate(range(
+        min = 1,
+        max = 1048576,
+        message = "Capture limit must be 1-1048576 bytes"
+    ))]
+    pub capture_limit_bytes: i32,
+}
+
+fn default_capture_limit_bytes() -> i32 {
+    64 * 1024
+}
+
+/// Query for searching submission sources
+#[derive(Debug, Deserialize)]
+pub struct SearchSubmissionsQuery {
+    /// Text the source must contain (3-200 characters; `%` and `_` match
+    /// themselves)
+    pub q: String,
+    /// Match case exactly instead of ignoring it
+    #[serde(default)]
+    pub case_sensitive: bool,
+    pub contest_id: Option<Uuid>,
+    pub problem_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+}
+
+/// Query for listing upload scan results
+#[derive(Debug, Deserialize, Default)]
+pub struct ListUploadScansQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+    /// Filter by result: clean, infected, error
+    pub status: Option<String>,
+    /// Filter by upload type: generator, checker, submission
+    pub target_type: Option<String>,
+}
+
+/// Contest storage root request; `null` moves new data back to the shared
+/// volume
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateContestStorageRequest {
+    /// One of the directories listed in `CONTEST_STORAGE_ROOTS`
+    pub storage_root: Option<String>,
+}
+
+/// Query for listing problem backups
+#[derive(Debug, Deserialize, Default)]
+pub struct ListProblemBackupsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+    /// Only backups of this problem
+    pub problem_id: Option<Uuid>,
+    /// Only backups of problems that no longer exist
+    #[serde(default)]
+    pub deleted: bool,
+}
@@ -1,6 +1,7 @@
 //! Admin response DTOs.
 
 use chrono::{DateTime, Utc};
+use olympus_common::JudgePriority;
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -62,6 +63,32 @@ pub struct BanResponse {
     pub banned_reason: Option<String>,
 }
 
+/// Response after anonymizing a user
+#[derive(Debug, Serialize)]
+pub struct AnonymizeUserResponse {
+    pub id: Uuid,
+    /// Scrambled username the account now has
+    pub username: String,
+    pub submissions_deleted: bool,
+    pub anonymized_at: DateTime<Utc>,
+    pub audit_log_id: Uuid,
+}
+
+/// Response after merging a duplicate account into another
+#[derive(Debug, Serialize)]
+pub struct MergeUsersResponse {
+    /// Surviving account
+    pub id: Uuid,
+    /// Deleted duplicate
+    pub merged_user_id: Uuid,
+    pub merged_username: String,
+    pub submissions_moved: u64,
+    pub registrations_moved: u64,
+    pub problems_moved: u64,
+    pub contests_moved: u64,
+    pub audit_log_id: Uuid,
+}
+
 // =============================================================================
 // System Stats
 // =============================================================================
@@ -110,6 +137,25 @@ pub struct SubmissionStats {
 pub struct StorageStats {
     pub submissions_count: i64,
     pub results_count: i64,
+    /// Latest Horus usage snapshot (empty until its first run)
+    pub usage: Vec<StorageUsageEntry>,
+}
+
+/// Measured usage of one storage directory
+#[derive(Debug, Serialize)]
+pub struct StorageUsageEntry {
+    pub category: String,
+    pub bytes: i64,
+    pub file_count: i64,
+    pub budget_bytes: Option<i64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Storage usage history
+#[derive(Debug, Serialize)]
+pub struct StorageHistoryResponse {
+    pub days: u32,
+    pub entries: Vec<StorageUsageEntry>,
 }
 
 // =============================================================================
@@ -124,8 +170,17 @@ pub struct QueueInfoResponse {
 
 #[derive(Debug, Serialize)]
 pub struct QueueDetail {
+    /// Stream name (`compile_queue:high`)
     pub name: String,
+    /// Queue the stream belongs to, as used by pause/resume
+    pub queue: String,
+    pub priority: JudgePriority,
+    /// Worker profile of a run queue stream
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker_profile: Option<String>,
     pub length: i64,
+    /// Whether workers are currently paused for this queue
+    pub paused: bool,
     pub consumer_groups: Vec<ConsumerGroupInfo>,
     pub pending_entries: Vec<PendingEntry>,
 }
@@ -146,6 +201,81 @@ pub struct PendingEntry {
     pub delivery_count: i64,
 }
 
+/// Judging streams and their dead-letter streams
+#[derive(Debug, Serialize)]
+pub struct QueueBrowserResponse {
+    pub streams: Vec<StreamOverview>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamOverview {
+    /// Stream name (`run_queue:high`, `run_queue.highmem`, `run_queue_dlq`)
+    pub name: String,
+    /// Queue the stream belongs to
+    pub queue: String,
+    /// `None` for dead-letter streams
+    pub priority: Option<JudgePriority>,
+    /// Worker profile of a run queue judging stream
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker_profile: Option<String>,
+    pub dead_letter: bool,
+    pub length: i64,
+    /// Age of the oldest entry still in the stream
+    pub oldest_entry_age_ms: Option<i64>,
+    pub consumer_groups: Vec<StreamGroupOverview>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamGroupOverview {
+    pub name: String,
+    pub pending: i64,
+    pub last_delivered_id: String,
+    /// Age of the oldest entry delivered but not yet acknowledged
+    pub oldest_pending_age_ms: Option<i64>,
+    pub consumers: Vec<StreamConsumerInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamConsumerInfo {
+    pub name: String,
+    pub pending: i64,
+    pub idle_ms: i64,
+}
+
+/// A page of raw stream entries
+#[derive(Debug, Serialize)]
+pub struct StreamEntriesResponse {
+    pub stream: String,
+    pub entries: Vec<StreamEntryInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamEntryInfo {
+    pub id: String,
+    pub age_ms: Option<i64>,
+    pub fields: std::collections::BTreeMap<String, String>,
+}
+
+/// Response after deleting or re-enqueueing a stream entry
+#[derive(Debug, Serialize)]
+pub struct StreamEntryActionResponse {
+    pub stream: String,
+    pub id: String,
+    /// Stream the entry was re-enqueued to
+    pub requeued_to: Option<String>,
+    /// Id of the re-enqueued entry
+    pub new_id: Option<String>,
+    pub message: String,
+}
+
+/// Response after pausing or resuming a queue
+#[derive(Debug, Serialize)]
+pub struct QueuePauseResponse {
+    pub name: String,
+    pub paused: bool,
+    pub message: String,
+}
+
 /// Response after rejudge
 #[derive(Debug, Serialize)]
 pub struct RejudgeResponse {
@@ -226,3 +356,218 @@ pub struct SaveRuleResponse {
     pub success: bool,
     pub message: String,
 }
+
+// =============================================================================
+// Cleanup Policies
+// =============================================================================
+
+/// Cleanup policy overrides for one artifact class; `null` fields keep
+/// Horus's environment setting
+#[derive(Debug, Serialize)]
+pub struct CleanupPolicyResponse {
+    pub artifact_class: String,
+    pub enabled: Option<bool>,
+    pub retention_hours: Option<i32>,
+    pub dry_run: bool,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Cleanup policy list response
+#[derive(Debug, Serialize)]
+pub struct CleanupPolicyListResponse {
+    pub policies: Vec<CleanupPolicyResponse>,
+}
+
+/// Latest dry-run report for one artifact class
+#[derive(Debug, Serialize)]
+pub struct CleanupReportResponse {
+    pub artifact_class: String,
+    /// `[{ "path" | "submission_id", "bytes" }]` that would have been deleted
+    pub items: serde_json::Value,
+    pub items_count: i32,
+    pub total_bytes: i64,
+    pub errors: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Dry-run report list response
+#[derive(Debug, Serialize)]
+pub struct CleanupReportListResponse {
+    pub reports: Vec<CleanupReportResponse>,
+}
+
+/// Response after requesting a dry run
+#[derive(Debug, Serialize)]
+pub struct CleanupDryRunResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+// =============================================================================
+// Test Reproduction
+// =============================================================================
+
+/// Single-test re-run of a submission
+#[derive(Debug, Serialize)]
+pub struct TestReproductionResponse {
+    pub id: Uuid,
+    pub submission_id: Uuid,
+    pub test_case_number: i32,
+    pub generator_seed: String,
+    pub generator_version: i32,
+    pub original_verdict: String,
+    /// `pending`, `completed` or `failed`
+    pub status: String,
+    pub verdict: Option<String>,
+    pub time_ms: Option<i32>,
+    pub memory_kb: Option<i32>,
+    pub checker_output: Option<String>,
+    /// Whether the re-run reached the original verdict (once completed)
+    pub matches_original: Option<bool>,
+    pub error: Option<String>,
+    /// What the program printed and the checker said (debug runs only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug: Option<DebugCaptureInfo>,
+    pub requested_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Output kept by a debug run, each part cut to `capture_limit_bytes`
+/// (all `None` until the run completes)
+#[derive(Debug, Serialize)]
+pub struct DebugCaptureInfo {
+    pub capture_limit_bytes: i32,
+    /// Only kept when the program exited on its own
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    /// What the program wrote to its output file
+    pub output: Option<String>,
+    pub checker_log: Option<String>,
+    /// Whether any part was cut at the limit
+    pub truncated: Option<bool>,
+}
+
+// =============================================================================
+// Submission Search
+// =============================================================================
+
+/// A source line containing the search text
+#[derive(Debug, Serialize)]
+pub struct SourceMatch {
+    /// 1-based line number
+    pub line: usize,
+    /// The line, cut to 200 characters
+    pub text: String,
+}
+
+/// A submission whose source contains the search text
+#[derive(Debug, Serialize)]
+pub struct SubmissionSearchHit {
+    pub submission_id: Uuid,
+    pub user_id: Uuid,
+    pub username: String,
+    pub problem_id: Uuid,
+    pub contest_id: Option<Uuid>,
+    pub language: Option<String>,
+    pub status: String,
+    pub submitted_at: DateTime<Utc>,
+    /// Lines containing the text (first few only)
+    pub matches: Vec<SourceMatch>,
+    /// Lines containing the text in total
+    pub match_count: usize,
+}
+
+/// Submission search results, newest first
+#[derive(Debug, Serialize)]
+pub struct SubmissionSearchResponse {
+    pub query: String,
+    pub case_sensitive: bool,
+    pub submissions: Vec<SubmissionSearchHit>,
+    pub page: u32,
+    pub per_page: u32,
+    /// Whether a later page has more hits (no total is counted)
+    pub has_more: bool,
+}
+
+// =============================================================================
+// Upload Scans
+// =============================================================================
+
+/// A recorded upload scan
+#[derive(Debug, Serialize)]
+pub struct UploadScanResponse {
+    pub id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub scanner: String,
+    pub status: String,
+    /// Matched signature, or why the scan failed
+    pub detail: Option<String>,
+    pub checksum: String,
+    pub size_bytes: i64,
+    /// `allowed` or `rejected`
+    pub action: String,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Upload scan list response
+#[derive(Debug, Serialize)]
+pub struct UploadScanListResponse {
+    pub scans: Vec<UploadScanResponse>,
+    pub pagination: Pagination,
+}
+
+// =============================================================================
+// Contest Storage
+// =============================================================================
+
+/// Where a contest's submissions and artifacts are stored
+#[derive(Debug, Serialize)]
+pub struct ContestStorageResponse {
+    pub contest_id: Uuid,
+    /// `null` when the contest uses the shared data volume
+    pub storage_root: Option<String>,
+    /// Roots a contest may be given (`CONTEST_STORAGE_ROOTS`)
+    pub available_roots: Vec<String>,
+}
+
+// =============================================================================
+// Problem Backups
+// =============================================================================
+
+/// A problem package archive written by Horus
+#[derive(Debug, Serialize)]
+pub struct ProblemBackupResponse {
+    pub id: Uuid,
+    pub problem_id: Uuid,
+    pub problem_title: String,
+    pub version: i32,
+    pub size_bytes: i64,
+    /// SHA-256 of the archive, hex
+    pub checksum: String,
+    /// Whether the problem still exists; only deleted problems can be restored
+    pub problem_exists: bool,
+    pub created_at: DateTime<Utc>,
+    pub restored_at: Option<DateTime<Utc>>,
+    pub restored_by: Option<Uuid>,
+}
+
+/// Problem backup list response
+#[derive(Debug, Serialize)]
+pub struct ProblemBackupListResponse {
+    pub backups: Vec<ProblemBackupResponse>,
+    pub pagination: Pagination,
+}
+
+/// Result of restoring a problem from a backup
+#[derive(Debug, Serialize)]
+pub struct RestoreProblemBackupResponse {
+    pub backup: ProblemBackupResponse,
+    /// Rows inserted across the package tables
+    pub rows_restored: u64,
+    /// Files written to the problem's binaries directory
+    pub files_restored: u32,
+}
@@ -0,0 +1,309 @@
+//! Announcement handlers.
+//!
+//! Admin endpoints publish, edit, and remove announcements; the public list
+//! endpoint returns the ones inside their display window so frontends can
+//! render a banner.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use olympus_rules::roles::Permission;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::extract::ValidatedJson;
+use crate::middleware::auth::AuthUser;
+use crate::repositories::contests::{self as contest_repo, ContestFilter, ContestViewer};
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+const ANNOUNCEMENT_COLUMNS: &str = "id, title, body, severity, contest_id, starts_at, ends_at, \
+     created_by, created_at, updated_at";
+
+/// Database row for an announcement
+#[derive(Debug, FromRow)]
+struct AnnouncementRow {
+    id: Uuid,
+    title: String,
+    body: String,
+    severity: String,
+    contest_id: Option<Uuid>,
+    starts_at: DateTime<Utc>,
+    ends_at: Option<DateTime<Utc>>,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// GET /api/v1/announcements
+///
+/// List announcements currently showing: site-wide ones, plus those for
+/// `contest_id` when the caller can see that contest. Most severe first.
+pub async fn list_active_announcements(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Query(query): Query<ActiveAnnouncementsQuery>,
+) -> ApiResult<Json<AnnouncementListResponse>> {
+    if let Some(contest_id) = query.contest_id {
+        let viewer = match user {
+            Some(Extension(ref u)) if u.can(Permission::ViewPrivateContent) => ContestViewer::Admin,
+            Some(Extension(ref u)) => ContestViewer::User(u.id),
+            None => ContestViewer::Anonymous,
+        };
+        let filter = ContestFilter::new(viewer).id(Some(contest_id));
+        if contest_repo::count(state.read_db(), &filter).await? == 0 {
+            return Err(ApiError::NotFound("Contest not found".to_string()));
+        }
+    }
+
+    let rows = sqlx::query_as::<_, AnnouncementRow>(&format!(
+        r#"
+        SELECT {}
+        FROM announcements
+        WHERE starts_at <= NOW() AND (ends_at IS NULL OR ends_at > NOW())
+          AND (contest_id IS NULL OR contest_id = $1)
+        ORDER BY array_position(ARRAY['critical', 'warning', 'info']::varchar[], severity),
+                 starts_at DESC
+        "#,
+        ANNOUNCEMENT_COLUMNS
+    ))
+    .bind(query.contest_id)
+    .fetch_all(state.read_db())
+    .await?;
+
+    Ok(Json(AnnouncementListResponse {
+        announcements: rows.into_iter().map(row_to_announcement).collect(),
+    }))
+}
+
+/// Announcements of a contest currently showing, most severe first.
+/// Site-wide ones are left out; the contest's clarification feed carries
+/// these alongside its questions.
+pub(crate) async fn active_contest_announcements(
+    db: &sqlx::PgPool,
+    contest_id: Uuid,
+) -> ApiResult<Vec<Announcement>> {
+    let rows = sqlx::query_as::<_, AnnouncementRow>(&format!(
+        r#"
+        SELECT {}
+        FROM announcements
+        WHERE starts_at <= NOW() AND (ends_at IS NULL OR ends_at > NOW())
+          AND contest_id = $1
+        ORDER BY array_position(ARRAY['critical', 'warning', 'info']::varchar[], severity),
+                 starts_at DESC
+        "#,
+        ANNOUNCEMENT_COLUMNS
+    ))
+    .bind(contest_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows.into_iter().map(row_to_announcement).collect())
+}
+
+/// GET /api/v1/admin/announcements
+///
+/// List current and scheduled announcements, newest first. Ended ones are
+/// included with `include_expired=true`.
+pub async fn list_announcements(
+    State(state): State<AppState>,
+    Query(query): Query<ListAnnouncementsQuery>,
+) -> ApiResult<Json<AdminAnnouncementListResponse>> {
+    let rows = sqlx::query_as::<_, AnnouncementRow>(&format!(
+        r#"
+        SELECT {}
+        FROM announcements
+        WHERE ($1::uuid IS NULL OR contest_id = $1)
+          AND ($2 OR ends_at IS NULL OR ends_at > NOW())
+        ORDER BY starts_at DESC
+        "#,
+        ANNOUNCEMENT_COLUMNS
+    ))
+    .bind(query.contest_id)
+    .bind(query.include_expired)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(AdminAnnouncementListResponse {
+        announcements: rows.into_iter().map(row_to_response).collect(),
+    }))
+}
+
+/// POST /api/v1/admin/announcements
+///
+/// Publish an announcement.
+pub async fn create_announcement(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<CreateAnnouncementRequest>,
+) -> ApiResult<(StatusCode, Json<AnnouncementResponse>)> {
+    if let Some(contest_id) = payload.contest_id {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contests WHERE id = $1)")
+                .bind(contest_id)
+                .fetch_one(&state.db)
+                .await?;
+        if !exists {
+            return Err(ApiError::NotFound("Contest not found".to_string()));
+        }
+    }
+
+    let starts_at = payload.starts_at.unwrap_or_else(Utc::now);
+    validate_window(starts_at, payload.ends_at)?;
+
+    let row = sqlx::query_as::<_, AnnouncementRow>(&format!(
+        r#"
+        INSERT INTO announcements (title, body, severity, contest_id, starts_at, ends_at, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING {}
+        "#,
+        ANNOUNCEMENT_COLUMNS
+    ))
+    .bind(&payload.title)
+    .bind(&payload.body)
+    .bind(payload.severity.as_str())
+    .bind(payload.contest_id)
+    .bind(starts_at)
+    .bind(payload.ends_at)
+    .bind(admin.id)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        announcement_id = %row.id,
+        severity = %row.severity,
+        "Admin published announcement"
+    );
+
+    Ok((StatusCode::CREATED, Json(row_to_response(row))))
+}
+
+/// PUT /api/v1/admin/announcements/{id}
+///
+/// Partially update an announcement. `clear_ends_at` removes the end time.
+pub async fn update_announcement(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(announcement_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateAnnouncementRequest>,
+) -> ApiResult<Json<AnnouncementResponse>> {
+    let existing = sqlx::query_as::<_, AnnouncementRow>(&format!(
+        "SELECT {} FROM announcements WHERE id = $1",
+        ANNOUNCEMENT_COLUMNS
+    ))
+    .bind(announcement_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Announcement not found".to_string()))?;
+
+    let starts_at = payload.starts_at.unwrap_or(existing.starts_at);
+    let ends_at = if payload.clear_ends_at {
+        None
+    } else {
+        payload.ends_at.or(existing.ends_at)
+    };
+    validate_window(starts_at, ends_at)?;
+
+    let row = sqlx::query_as::<_, AnnouncementRow>(&format!(
+        r#"
+        UPDATE announcements
+        SET title = $1, body = $2, severity = $3, starts_at = $4, ends_at = $5
+        WHERE id = $6
+        RETURNING {}
+        "#,
+        ANNOUNCEMENT_COLUMNS
+    ))
+    .bind(payload.title.as_ref().unwrap_or(&existing.title))
+    .bind(payload.body.as_ref().unwrap_or(&existing.body))
+    .bind(
+        payload
+            .severity
+            .map(|s| s.as_str())
+            .unwrap_or(&existing.severity),
+    )
+    .bind(starts_at)
+    .bind(ends_at)
+    .bind(announcement_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        announcement_id = %row.id,
+        "Admin updated announcement"
+    );
+
+    Ok(Json(row_to_response(row)))
+}
+
+/// DELETE /api/v1/admin/announcements/{id}
+///
+/// Remove an announcement.
+pub async fn delete_announcement(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(announcement_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM announcements WHERE id = $1")
+        .bind(announcement_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Announcement not found".to_string()));
+    }
+
+    tracing::info!(
+        admin_id = %admin.id,
+        announcement_id = %announcement_id,
+        "Admin removed announcement"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reject display windows that end before they start.
+fn validate_window(starts_at: DateTime<Utc>, ends_at: Option<DateTime<Utc>>) -> ApiResult<()> {
+    match ends_at {
+        Some(ends_at) if ends_at <= starts_at => Err(ApiError::Validation(
+            "ends_at must be after starts_at".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Helper to convert a DB row to the public DTO.
+fn row_to_announcement(row: AnnouncementRow) -> Announcement {
+    Announcement {
+        id: row.id,
+        title: row.title,
+        body: row.body,
+        severity: row.severity,
+        contest_id: row.contest_id,
+        starts_at: row.starts_at,
+        ends_at: row.ends_at,
+    }
+}
+
+/// Helper to convert a DB row to response DTO.
+fn row_to_response(row: AnnouncementRow) -> AnnouncementResponse {
+    AnnouncementResponse {
+        id: row.id,
+        title: row.title,
+        body: row.body,
+        severity: row.severity,
+        contest_id: row.contest_id,
+        starts_at: row.starts_at,
+        ends_at: row.ends_at,
+        created_by: row.created_by,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    }
+}
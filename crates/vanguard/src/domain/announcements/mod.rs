@@ -0,0 +1,11 @@
+//! System announcements domain module.
+//!
+//! Admins publish notices (maintenance windows, incident reports) with a
+//! severity and a display window. The public list returns the ones currently
+//! showing, site-wide plus those for a requested contest.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
@@ -0,0 +1,85 @@
+//! Announcement request DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+/// How prominently an announcement is shown
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AnnouncementSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnnouncementSeverity::Info => "info",
+            AnnouncementSeverity::Warning => "warning",
+            AnnouncementSeverity::Critical => "critical",
+        }
+    }
+}
+
+/// Publish an announcement
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAnnouncementRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: String,
+
+    #[validate(length(min = 1, max = 10000, message = "Body must be 1-10000 characters"))]
+    pub body: String,
+
+    #[serde(default)]
+    pub severity: AnnouncementSeverity,
+
+    /// Only show the announcement alongside this contest
+    pub contest_id: Option<Uuid>,
+
+    /// When to start showing it (defaults to now)
+    pub starts_at: Option<DateTime<Utc>>,
+
+    /// When to stop showing it (shown until removed if omitted)
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Update an announcement (partial update)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAnnouncementRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: Option<String>,
+
+    #[validate(length(min = 1, max = 10000, message = "Body must be 1-10000 characters"))]
+    pub body: Option<String>,
+
+    pub severity: Option<AnnouncementSeverity>,
+
+    pub starts_at: Option<DateTime<Utc>>,
+
+    pub ends_at: Option<DateTime<Utc>>,
+
+    /// Remove the end time so the announcement shows until removed
+    #[serde(default)]
+    pub clear_ends_at: bool,
+}
+
+/// Query for the public announcement list
+#[derive(Debug, Deserialize, Default)]
+pub struct ActiveAnnouncementsQuery {
+    /// Also include announcements for this contest
+    pub contest_id: Option<Uuid>,
+}
+
+/// Query for listing announcements (admin)
+#[derive(Debug, Deserialize, Default)]
+pub struct ListAnnouncementsQuery {
+    /// Filter by contest
+    pub contest_id: Option<Uuid>,
+    /// Include announcements whose display window has ended
+    #[serde(default)]
+    pub include_expired: bool,
+}
@@ -0,0 +1,44 @@
+//! Announcement response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Announcement currently showing
+#[derive(Debug, Serialize)]
+pub struct Announcement {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub severity: String,
+    pub contest_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Public announcement list, most severe first
+#[derive(Debug, Serialize)]
+pub struct AnnouncementListResponse {
+    pub announcements: Vec<Announcement>,
+}
+
+/// Full announcement (admin view)
+#[derive(Debug, Serialize)]
+pub struct AnnouncementResponse {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub severity: String,
+    pub contest_id: Option<Uuid>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Announcement list response (admin view)
+#[derive(Debug, Serialize)]
+pub struct AdminAnnouncementListResponse {
+    pub announcements: Vec<AnnouncementResponse>,
+}
@@ -5,21 +5,25 @@ use argon2::{
     Argon2,
 };
 use axum::{
-    extract::{Extension, State},
+    extract::{Extension, Path, State},
     http::StatusCode,
     Json,
 };
 use chrono::{DateTime, Utc};
+use jsonwebtoken::jwk::JwkSet;
 use sqlx::{FromRow, Row};
 use uuid::Uuid;
-use validator::Validate;
 
 use super::{
-    jwt::JwtManager,
-    request::{LoginRequest, RefreshRequest, RegisterRequest},
-    response::{AuthTokensResponse, LoginResponse, LogoutResponse, RegisterResponse, UserResponse},
+    request::{IssueServiceTokenRequest, LoginRequest, RefreshRequest, RegisterRequest},
+    response::{
+        AuthTokensResponse, LoginResponse, LogoutResponse, RegisterResponse, ServiceTokenResponse,
+        SessionListResponse, SessionResponse, UserResponse,
+    },
+    session,
 };
-use crate::error::{ApiError, ApiResult};
+use crate::error::{self, ApiError, ApiResult};
+use crate::extract::{ClientInfo, ValidatedJson, SERVICE_SCOPES};
 use crate::middleware::auth::AuthUser;
 use crate::state::AppState;
 
@@ -42,13 +46,9 @@ struct UserRow {
 /// Register a new user account.
 pub async fn register(
     State(state): State<AppState>,
-    Json(payload): Json<RegisterRequest>,
+    client: ClientInfo,
+    ValidatedJson(payload): ValidatedJson<RegisterRequest>,
 ) -> ApiResult<(StatusCode, Json<RegisterResponse>)> {
-    // Validate request
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Check if username exists
     let exists: (bool,) = sqlx::query_as("SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)")
         .bind(&payload.username)
@@ -98,30 +98,30 @@ pub async fn register(
     .bind(&display_name)
     .bind(now)
     .execute(&state.db)
-    .await?;
+    .await
+    .map_err(error::conflict_on_unique(
+        "Username or email already registered",
+    ))?;
 
     // Generate tokens
-    let jwt_manager = JwtManager::new(
-        &state.config.jwt_secret,
-        state.config.jwt_access_expiration,
-        state.config.jwt_refresh_expiration,
-    );
-
     let session_id = Uuid::new_v4();
     let access_token =
-        jwt_manager.generate_access_token(user_id, &payload.username, "participant")?;
-    let refresh_token = jwt_manager.generate_refresh_token(user_id, session_id)?;
+        state
+            .jwt
+            .generate_access_token(user_id, &payload.username, "participant", session_id)?;
+    let refresh_token = state.jwt.generate_refresh_token(user_id, session_id)?;
 
     // Store refresh token session in Redis
     let mut conn = state.redis.get().await?;
-    let session_key = format!("session:{}:{}", user_id, session_id);
-    redis::cmd("SET")
-        .arg(&session_key)
-        .arg("active")
-        .arg("EX")
-        .arg(state.config.jwt_refresh_expiration)
-        .query_async::<()>(&mut conn)
-        .await?;
+    session::store_session(
+        &mut conn,
+        user_id,
+        session_id,
+        &client,
+        now,
+        state.config.jwt_refresh_expiration,
+    )
+    .await?;
 
     let response = RegisterResponse {
         user: UserResponse {
@@ -137,7 +137,7 @@ pub async fn register(
         tokens: AuthTokensResponse::new(
             access_token,
             refresh_token,
-            state.config.jwt_access_expiration,
+            state.jwt.access_expiration_for("participant"),
         ),
     };
 
@@ -149,19 +149,15 @@ pub async fn register(
 /// Login with username/email and password.
 pub async fn login(
     State(state): State<AppState>,
-    Json(payload): Json<LoginRequest>,
+    client: ClientInfo,
+    ValidatedJson(payload): ValidatedJson<LoginRequest>,
 ) -> ApiResult<Json<LoginResponse>> {
-    // Validate request
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Find user by username or email
     let user: UserRow = sqlx::query_as(
         r#"
         SELECT id, username, email, password_hash, display_name, bio, role, created_at, updated_at
         FROM users
-        WHERE username = $1 OR email = $1
+        WHERE (username = $1 OR email = $1) AND anonymized_at IS NULL
         "#,
     )
     .bind(&payload.identifier)
@@ -178,27 +174,26 @@ pub async fn login(
         .map_err(|_| ApiError::InvalidCredentials)?;
 
     // Generate tokens
-    let jwt_manager = JwtManager::new(
-        &state.config.jwt_secret,
-        state.config.jwt_access_expiration,
-        state.config.jwt_refresh_expiration,
-    );
-
     let session_id = Uuid::new_v4();
-    let access_token = jwt_manager.generate_access_token(user.id, &user.username, &user.role)?;
-    let refresh_token = jwt_manager.generate_refresh_token(user.id, session_id)?;
+    let access_token =
+        state
+            .jwt
+            .generate_access_token(user.id, &user.username, &user.role, session_id)?;
+    let refresh_token = state.jwt.generate_refresh_token(user.id, session_id)?;
 
     // Store refresh token session in Redis
     let mut conn = state.redis.get().await?;
-    let session_key = format!("session:{}:{}", user.id, session_id);
-    redis::cmd("SET")
-        .arg(&session_key)
-        .arg("active")
-        .arg("EX")
-        .arg(state.config.jwt_refresh_expiration)
-        .query_async::<()>(&mut conn)
-        .await?;
+    session::store_session(
+        &mut conn,
+        user.id,
+        session_id,
+        &client,
+        Utc::now(),
+        state.config.jwt_refresh_expiration,
+    )
+    .await?;
 
+    let expires_in = state.jwt.access_expiration_for(&user.role);
     let response = LoginResponse {
         user: UserResponse {
             id: user.id,
@@ -210,11 +205,7 @@ pub async fn login(
             created_at: user.created_at,
             updated_at: user.updated_at,
         },
-        tokens: AuthTokensResponse::new(
-            access_token,
-            refresh_token,
-            state.config.jwt_access_expiration,
-        ),
+        tokens: AuthTokensResponse::new(access_token, refresh_token, expires_in),
     };
 
     Ok(Json(response))
@@ -225,31 +216,22 @@ pub async fn login(
 /// Refresh access token using refresh token.
 pub async fn refresh(
     State(state): State<AppState>,
-    Json(payload): Json<RefreshRequest>,
+    client: ClientInfo,
+    ValidatedJson(payload): ValidatedJson<RefreshRequest>,
 ) -> ApiResult<Json<AuthTokensResponse>> {
-    let jwt_manager = JwtManager::new(
-        &state.config.jwt_secret,
-        state.config.jwt_access_expiration,
-        state.config.jwt_refresh_expiration,
-    );
-
     // Verify refresh token
-    let claims = jwt_manager.verify_refresh_token(&payload.refresh_token)?;
+    let claims = state.jwt.verify_refresh_token(&payload.refresh_token)?;
 
     // Check if session is valid in Redis
     let mut conn = state.redis.get().await?;
-    let session_key = format!("session:{}:{}", claims.sub, claims.session_id);
-    let exists: Option<String> = redis::cmd("GET")
-        .arg(&session_key)
-        .query_async(&mut conn)
-        .await?;
-
-    if exists.is_none() {
-        return Err(ApiError::Token("Session has been revoked".to_string()));
-    }
+    let created_at = session::session_started_at(&mut conn, claims.sub, claims.session_id)
+        .await?
+        .ok_or_else(|| ApiError::Token("Session has been revoked".to_string()))?;
 
     // Fetch user info
-    let row = sqlx::query("SELECT username, role FROM users WHERE id = $1")
+    let row = sqlx::query(
+        "SELECT username, role FROM users WHERE id = $1 AND anonymized_at IS NULL",
+    )
         .bind(claims.sub)
         .fetch_optional(&state.db)
         .await?
@@ -260,28 +242,30 @@ pub async fn refresh(
 
     // Generate new tokens
     let new_session_id = Uuid::new_v4();
-    let access_token = jwt_manager.generate_access_token(claims.sub, &username, &role)?;
-    let refresh_token = jwt_manager.generate_refresh_token(claims.sub, new_session_id)?;
-
-    // Invalidate old session and create new one
-    redis::cmd("DEL")
-        .arg(&session_key)
-        .query_async::<()>(&mut conn)
-        .await?;
-
-    let new_session_key = format!("session:{}:{}", claims.sub, new_session_id);
-    redis::cmd("SET")
-        .arg(&new_session_key)
-        .arg("active")
-        .arg("EX")
-        .arg(state.config.jwt_refresh_expiration)
-        .query_async::<()>(&mut conn)
-        .await?;
+    let access_token =
+        state
+            .jwt
+            .generate_access_token(claims.sub, &username, &role, new_session_id)?;
+    let refresh_token = state
+        .jwt
+        .generate_refresh_token(claims.sub, new_session_id)?;
+
+    // Invalidate old session and create new one, keeping when it started
+    session::revoke_session(&mut conn, claims.sub, claims.session_id).await?;
+    session::store_session(
+        &mut conn,
+        claims.sub,
+        new_session_id,
+        &client,
+        created_at,
+        state.config.jwt_refresh_expiration,
+    )
+    .await?;
 
     Ok(Json(AuthTokensResponse::new(
         access_token,
         refresh_token,
-        state.config.jwt_access_expiration,
+        state.jwt.access_expiration_for(&role),
     )))
 }
 
@@ -294,22 +278,53 @@ pub async fn logout(
 ) -> ApiResult<Json<LogoutResponse>> {
     // Invalidate all sessions for the user
     let mut conn = state.redis.get().await?;
+    session::revoke_all_sessions(&mut conn, user.id).await?;
 
-    // Delete all sessions for this user (simplified - in production you'd track individual sessions)
-    let pattern = format!("session:{}:*", user.id);
-    let keys: Vec<String> = redis::cmd("KEYS")
-        .arg(&pattern)
-        .query_async(&mut conn)
-        .await?;
+    Ok(Json(LogoutResponse::default()))
+}
 
-    if !keys.is_empty() {
-        redis::cmd("DEL")
-            .arg(&keys)
-            .query_async::<()>(&mut conn)
-            .await?;
+/// GET /api/v1/auth/sessions
+///
+/// List the caller's active sessions, most recently active first.
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Json<SessionListResponse>> {
+    let mut conn = state.redis.get().await?;
+    let mut sessions: Vec<SessionResponse> = session::list_sessions(&mut conn, user.id)
+        .await?
+        .into_iter()
+        .map(|s| SessionResponse {
+            id: s.id,
+            created_at: s.created_at,
+            last_active_at: s.last_active_at,
+            ip: s.ip,
+            user_agent: s.user_agent,
+            current: s.id == user.session_id,
+        })
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_active_at));
+
+    Ok(Json(SessionListResponse { sessions }))
+}
+
+/// DELETE /api/v1/auth/sessions/{id}
+///
+/// Revoke one of the caller's sessions. Its refresh token stops working at
+/// once; access tokens already issued for it last until they expire.
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(session_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let mut conn = state.redis.get().await?;
+    if !session::revoke_session(&mut conn, user.id, session_id).await? {
+        return Err(ApiError::NotFound("Session not found".to_string()));
     }
 
-    Ok(Json(LogoutResponse::default()))
+    tracing::info!(user_id = %user.id, session_id = %session_id, "Revoked session");
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
 /// GET /api/v1/auth/me
@@ -342,3 +357,67 @@ pub async fn me(
         updated_at: user_data.updated_at,
     }))
 }
+
+/// GET /.well-known/jwks.json
+///
+/// Public keys that verify issued tokens, by `kid`. Empty when tokens are
+/// signed with a shared secret.
+pub async fn jwks(State(state): State<AppState>) -> Json<JwkSet> {
+    Json(state.jwt.jwks().clone())
+}
+
+/// POST /api/v1/admin/service-tokens
+///
+/// Issue a short-lived token for an internal service. Service tokens are
+/// only accepted by service routes (`/internal`, see `ServiceCaller`),
+/// never as user tokens, so only scopes some service route checks can be
+/// requested.
+pub async fn issue_service_token(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<IssueServiceTokenRequest>,
+) -> ApiResult<(StatusCode, Json<ServiceTokenResponse>)> {
+    if let Some(unknown) = payload
+        .scopes
+        .iter()
+        .find(|scope| !SERVICE_SCOPES.contains(&scope.as_str()))
+    {
+        return Err(ApiError::Validation(format!(
+            "Unknown service scope '{}' (expected one of: {})",
+            unknown,
+            SERVICE_SCOPES.join(", ")
+        )));
+    }
+
+    let max_ttl = state.jwt.service_token_max_ttl();
+    let ttl = payload.ttl_secs.unwrap_or(max_ttl);
+    if ttl > max_ttl {
+        return Err(ApiError::Validation(format!(
+            "Service tokens live at most {} seconds",
+            max_ttl
+        )));
+    }
+
+    let token = state
+        .jwt
+        .generate_service_token(&payload.service, &payload.scopes, ttl)?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        service = %payload.service,
+        scopes = ?payload.scopes,
+        ttl,
+        "Admin issued service token"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ServiceTokenResponse {
+            token,
+            token_type: "Bearer",
+            service: payload.service,
+            scopes: payload.scopes,
+            expires_in: ttl,
+        }),
+    ))
+}
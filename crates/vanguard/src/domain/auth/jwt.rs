@@ -1,10 +1,27 @@
 //! JWT token handling.
+//!
+//! Every token names its signing key in the `kid` header. The current key
+//! signs new tokens; retired keys from `JWT_PREVIOUS_KEYS` still verify the
+//! tokens they issued, so keys can be rotated without logging everyone out.
+//! Tokens issued before key ids existed carry no `kid` and are verified with
+//! the current key until `JWT_UNKEYED_GRACE_UNTIL`.
+//! With an ES256 key file the public keys are published as a JWKS; shared
+//! secrets are never published. Service tokens carry their own audience
+//! (`JWT_SERVICE_AUDIENCE`), so they are never accepted as user tokens and
+//! user tokens never pass as service tokens. The same keys sign certified documents,
+//! such as final contest standings.
 
-use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::jwk::{Jwk, JwkSet, PublicKeyUse};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::config::Config;
 use crate::error::ApiError;
 
 /// JWT claims for access tokens
@@ -16,6 +33,12 @@ pub struct AccessTokenClaims {
     pub username: String,
     /// User role
     pub role: String,
+    /// Session the token was issued for
+    pub session_id: Uuid,
+    /// Issuer
+    pub iss: String,
+    /// Audience
+    pub aud: String,
     /// Expiration time (Unix timestamp)
     pub exp: i64,
     /// Issued at (Unix timestamp)
@@ -29,6 +52,10 @@ pub struct AccessTokenClaims {
 pub struct RefreshTokenClaims {
     /// Subject (user ID)
     pub sub: Uuid,
+    /// Issuer
+    pub iss: String,
+    /// Audience
+    pub aud: String,
     /// Expiration time (Unix timestamp)
     pub exp: i64,
     /// Issued at (Unix timestamp)
@@ -39,23 +66,150 @@ pub struct RefreshTokenClaims {
     pub session_id: Uuid,
 }
 
+/// JWT claims for internal service tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServiceTokenClaims {
+    /// Subject (service name, e.g. `minos`)
+    pub sub: String,
+    /// Operations the service may perform (e.g. `queue:read`)
+    pub scopes: Vec<String>,
+    /// Issuer
+    pub iss: String,
+    /// Audience
+    pub aud: String,
+    /// Expiration time (Unix timestamp)
+    pub exp: i64,
+    /// Issued at (Unix timestamp)
+    pub iat: i64,
+    /// Token ID
+    pub jti: Uuid,
+    /// Token type
+    pub token_type: String,
+}
+
+impl ServiceTokenClaims {
+    /// Whether the token allows `scope`
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// Signature over a document, with the key that made it
+#[derive(Debug, Clone)]
+pub struct DetachedSignature {
+    pub key_id: String,
+    /// `ES256` or `HS256`
+    pub algorithm: String,
+    /// Base64url, without padding
+    pub signature: String,
+}
+
+/// Key that verifies tokens with one `kid`
+struct VerificationKey {
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
 /// JWT token manager
 pub struct JwtManager {
+    key_id: String,
+    algorithm: Algorithm,
     encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    verification_keys: HashMap<String, VerificationKey>,
+    unkeyed_grace_until: Option<DateTime<Utc>>,
+    jwks: JwkSet,
+    issuer: String,
+    audience: String,
+    service_audience: String,
     access_expiration: i64,
+    access_expiration_by_role: HashMap<String, i64>,
     refresh_expiration: i64,
+    service_token_max_ttl: i64,
 }
 
 impl JwtManager {
-    /// Create a new JWT manager
-    pub fn new(secret: &str, access_expiration: i64, refresh_expiration: i64) -> Self {
-        Self {
-            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
-            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
-            access_expiration,
-            refresh_expiration,
+    /// Create a JWT manager from configuration, loading key files
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let es256 = config.jwt_private_key_file.is_some();
+        let current = config
+            .jwt_private_key_file
+            .clone()
+            .unwrap_or_else(|| config.jwt_secret.clone());
+
+        let mut encoding_key = None;
+        let mut verification_keys = HashMap::new();
+        let mut jwks = JwkSet { keys: Vec::new() };
+
+        let previous_keys = parse_key_pairs(&config.jwt_previous_keys)?;
+        let unkeyed_grace_until = config
+            .jwt_unkeyed_grace_until
+            .as_deref()
+            .map(|until| {
+                DateTime::parse_from_rfc3339(until)
+                    .map(|until| until.with_timezone(&Utc))
+                    .map_err(|e| format!("JWT_UNKEYED_GRACE_UNTIL must be RFC 3339: {}", e))
+            })
+            .transpose()?;
+
+        let keys = std::iter::once((&config.jwt_key_id, &current))
+            .chain(previous_keys.iter().map(|(kid, key)| (kid, key)));
+        for (kid, material) in keys {
+            if verification_keys.contains_key(kid) {
+                return Err(format!("Duplicate JWT key id '{}'", kid));
+            }
+
+            let (encoding, verification) = if es256 {
+                let pem = std::fs::read(material)
+                    .map_err(|e| format!("Cannot read JWT key file {}: {}", material, e))?;
+                let encoding = EncodingKey::from_ec_pem(&pem)
+                    .map_err(|e| format!("Invalid ES256 key in {}: {}", material, e))?;
+                let mut jwk = Jwk::from_encoding_key(&encoding, Algorithm::ES256)
+                    .map_err(|e| format!("Invalid ES256 key in {}: {}", material, e))?;
+                jwk.common.key_id = Some(kid.clone());
+                jwk.common.public_key_use = Some(PublicKeyUse::Signature);
+                let key = DecodingKey::from_jwk(&jwk)
+                    .map_err(|e| format!("Invalid ES256 key in {}: {}", material, e))?;
+                jwks.keys.push(jwk);
+                (
+                    encoding,
+                    VerificationKey {
+                        algorithm: Algorithm::ES256,
+                        key,
+                    },
+                )
+            } else {
+                (
+                    EncodingKey::from_secret(material.as_bytes()),
+                    VerificationKey {
+                        algorithm: Algorithm::HS256,
+                        key: DecodingKey::from_secret(material.as_bytes()),
+                    },
+                )
+            };
+
+            encoding_key.get_or_insert(encoding);
+            verification_keys.insert(kid.clone(), verification);
         }
+
+        Ok(Self {
+            key_id: config.jwt_key_id.clone(),
+            algorithm: if es256 {
+                Algorithm::ES256
+            } else {
+                Algorithm::HS256
+            },
+            encoding_key: encoding_key.expect("current key is always loaded"),
+            verification_keys,
+            unkeyed_grace_until,
+            jwks,
+            issuer: config.jwt_issuer.clone(),
+            audience: config.jwt_audience.clone(),
+            service_audience: config.jwt_service_audience.clone(),
+            access_expiration: config.jwt_access_expiration,
+            access_expiration_by_role: config.jwt_access_expiration_by_role.clone(),
+            refresh_expiration: config.jwt_refresh_expiration,
+            service_token_max_ttl: config.jwt_service_token_max_ttl,
+        })
     }
 
     /// Generate an access token
@@ -64,21 +218,24 @@ impl JwtManager {
         user_id: Uuid,
         username: &str,
         role: &str,
+        session_id: Uuid,
     ) -> Result<String, ApiError> {
         let now = Utc::now();
-        let exp = now + Duration::seconds(self.access_expiration);
+        let exp = now + Duration::seconds(self.access_expiration_for(role));
 
         let claims = AccessTokenClaims {
             sub: user_id,
             username: username.to_string(),
             role: role.to_string(),
+            session_id,
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
             exp: exp.timestamp(),
             iat: now.timestamp(),
             token_type: "access".to_string(),
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key)
-            .map_err(|e| ApiError::Token(e.to_string()))
+        self.sign(&claims)
     }
 
     /// Generate a refresh token
@@ -92,49 +249,349 @@ impl JwtManager {
 
         let claims = RefreshTokenClaims {
             sub: user_id,
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
             exp: exp.timestamp(),
             iat: now.timestamp(),
             token_type: "refresh".to_string(),
             session_id,
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key)
-            .map_err(|e| ApiError::Token(e.to_string()))
+        self.sign(&claims)
+    }
+
+    /// Generate a service token valid for `ttl` seconds. Callers check `ttl`
+    /// against [`Self::service_token_max_ttl`].
+    pub fn generate_service_token(
+        &self,
+        service: &str,
+        scopes: &[String],
+        ttl: i64,
+    ) -> Result<String, ApiError> {
+        let now = Utc::now();
+        let exp = now + Duration::seconds(ttl.min(self.service_token_max_ttl));
+
+        let claims = ServiceTokenClaims {
+            sub: service.to_string(),
+            scopes: scopes.to_vec(),
+            iss: self.issuer.clone(),
+            aud: self.service_audience.clone(),
+            exp: exp.timestamp(),
+            iat: now.timestamp(),
+            jti: Uuid::new_v4(),
+            token_type: "service".to_string(),
+        };
+
+        self.sign(&claims)
     }
 
     /// Verify and decode an access token
     pub fn verify_access_token(&self, token: &str) -> Result<AccessTokenClaims, ApiError> {
-        let token_data: TokenData<AccessTokenClaims> =
-            decode(token, &self.decoding_key, &Validation::default())
-                .map_err(|e| ApiError::Token(e.to_string()))?;
+        let claims: AccessTokenClaims = self.verify(token, &self.audience)?;
 
-        if token_data.claims.token_type != "access" {
+        if claims.token_type != "access" {
             return Err(ApiError::Token("Invalid token type".to_string()));
         }
 
-        Ok(token_data.claims)
+        Ok(claims)
     }
 
     /// Verify and decode a refresh token
     pub fn verify_refresh_token(&self, token: &str) -> Result<RefreshTokenClaims, ApiError> {
-        let token_data: TokenData<RefreshTokenClaims> =
-            decode(token, &self.decoding_key, &Validation::default())
-                .map_err(|e| ApiError::Token(e.to_string()))?;
+        let claims: RefreshTokenClaims = self.verify(token, &self.audience)?;
 
-        if token_data.claims.token_type != "refresh" {
+        if claims.token_type != "refresh" {
             return Err(ApiError::Token("Invalid token type".to_string()));
         }
 
-        Ok(token_data.claims)
+        Ok(claims)
     }
 
-    /// Get access token expiration in seconds
-    pub fn access_expiration(&self) -> i64 {
-        self.access_expiration
+    /// Verify and decode a service token. Callers check its scopes.
+    pub fn verify_service_token(&self, token: &str) -> Result<ServiceTokenClaims, ApiError> {
+        let claims: ServiceTokenClaims = self.verify(token, &self.service_audience)?;
+
+        if claims.token_type != "service" {
+            return Err(ApiError::Token("Invalid token type".to_string()));
+        }
+
+        Ok(claims)
+    }
+
+    /// Access token expiration in seconds for users with `role`
+    pub fn access_expiration_for(&self, role: &str) -> i64 {
+        self.access_expiration_by_role
+            .get(role)
+            .copied()
+            .unwrap_or(self.access_expiration)
+    }
+
+    /// Longest lifetime of a service token in seconds
+    pub fn service_token_max_ttl(&self) -> i64 {
+        self.service_token_max_ttl
+    }
+
+    /// Public verification keys (empty when signing with a shared secret)
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+
+    /// Sign arbitrary bytes with the current key, for documents that are
+    /// not tokens. Returns the key id, algorithm and base64url signature.
+    pub fn sign_detached(&self, message: &[u8]) -> Result<DetachedSignature, ApiError> {
+        let signature = jsonwebtoken::crypto::sign(message, &self.encoding_key, self.algorithm)
+            .map_err(|e| ApiError::Internal(format!("Failed to sign document: {}", e)))?;
+
+        Ok(DetachedSignature {
+            key_id: self.key_id.clone(),
+            algorithm: format!("{:?}", self.algorithm),
+            signature,
+        })
+    }
+
+    /// Check a signature made by [`Self::sign_detached`]. `None` when the
+    /// key it names is no longer configured.
+    pub fn verify_detached(&self, key_id: &str, message: &[u8], signature: &str) -> Option<bool> {
+        let key = self.verification_keys.get(key_id)?;
+        Some(
+            jsonwebtoken::crypto::verify(signature, message, &key.key, key.algorithm)
+                .unwrap_or(false),
+        )
+    }
+
+    fn sign<T: Serialize>(&self, claims: &T) -> Result<String, ApiError> {
+        let mut header = Header::new(self.algorithm);
+        header.kid = Some(self.key_id.clone());
+
+        encode(&header, claims, &self.encoding_key).map_err(|e| ApiError::Token(e.to_string()))
+    }
+
+    /// Decode a token with the key its `kid` names, checking the issuer and
+    /// `audience`
+    fn verify<T: DeserializeOwned>(&self, token: &str, audience: &str) -> Result<T, ApiError> {
+        let header = decode_header(token).map_err(|e| ApiError::Token(e.to_string()))?;
+        let in_grace = self
+            .unkeyed_grace_until
+            .is_some_and(|until| Utc::now() < until);
+        let kid = match header.kid.as_deref() {
+            Some(kid) => kid,
+            None if in_grace => &self.key_id,
+            None => return Err(ApiError::Token("Token has no key id".to_string())),
+        };
+        let key = self
+            .verification_keys
+            .get(kid)
+            .ok_or_else(|| ApiError::Token("Unknown signing key".to_string()))?;
+
+        let mut validation = Validation::new(key.algorithm);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[audience]);
+        validation.set_required_spec_claims(&["exp", "sub", "iss", "aud"]);
+
+        decode::<T>(token, &key.key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| ApiError::Token(e.to_string()))
+    }
+}
+
+/// Parse comma-separated `kid=value` pairs
+fn parse_key_pairs(value: &str) -> Result<Vec<(String, String)>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (kid, key) = pair
+                .split_once('=')
+                .ok_or_else(|| "JWT_PREVIOUS_KEYS entries must be kid=value".to_string())?;
+            Ok((kid.trim().to_string(), key.trim().to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(key_id: &str, secret: &str) -> Config {
+        let mut config = Config::from_env();
+        config.jwt_secret = secret.to_string();
+        config.jwt_key_id = key_id.to_string();
+        config.jwt_private_key_file = None;
+        config.jwt_previous_keys = String::new();
+        config.jwt_unkeyed_grace_until = None;
+        config.jwt_issuer = "olympus".to_string();
+        config.jwt_audience = "olympus-api".to_string();
+        config.jwt_service_audience = "olympus-services".to_string();
+        config
+    }
+
+    fn manager(config: &Config) -> JwtManager {
+        JwtManager::from_config(config).unwrap()
+    }
+
+    fn access_token(jwt: &JwtManager) -> String {
+        jwt.generate_access_token(Uuid::new_v4(), "alice", "participant", Uuid::new_v4())
+            .unwrap()
+    }
+
+    /// A token signed with `secret` without a `kid` header, as issued before
+    /// key ids were introduced
+    fn unkeyed_token(secret: &str) -> String {
+        let now = Utc::now();
+        let claims = AccessTokenClaims {
+            sub: Uuid::new_v4(),
+            username: "alice".to_string(),
+            role: "participant".to_string(),
+            session_id: Uuid::new_v4(),
+            iss: "olympus".to_string(),
+            aud: "olympus-api".to_string(),
+            exp: (now + Duration::minutes(5)).timestamp(),
+            iat: now.timestamp(),
+            token_type: "access".to_string(),
+        };
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tokens_name_their_key_and_verify() {
+        let jwt = manager(&config("k1", "secret-one"));
+        let token = access_token(&jwt);
+
+        assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some("k1"));
+        let claims = jwt.verify_access_token(&token).unwrap();
+        assert_eq!(claims.username, "alice");
+        assert_eq!(claims.iss, "olympus");
+        assert_eq!(claims.aud, "olympus-api");
+    }
+
+    #[test]
+    fn test_retired_keys_verify_after_rotation() {
+        let old = manager(&config("k1", "secret-one"));
+        let token = access_token(&old);
+
+        let mut rotated = config("k2", "secret-two");
+        rotated.jwt_previous_keys = "k1=secret-one".to_string();
+        assert!(manager(&rotated).verify_access_token(&token).is_ok());
+
+        // Once the retired key is dropped its tokens are rejected
+        let dropped = manager(&config("k2", "secret-two"));
+        assert!(dropped.verify_access_token(&token).is_err());
+    }
+
+    #[test]
+    fn test_key_id_must_match_the_signing_key() {
+        let token = access_token(&manager(&config("k1", "secret-one")));
+        // Same id, different secret
+        assert!(manager(&config("k1", "secret-two"))
+            .verify_access_token(&token)
+            .is_err());
+    }
+
+    #[test]
+    fn test_issuer_and_audience_are_checked() {
+        let token = access_token(&manager(&config("k1", "secret-one")));
+
+        let mut other_issuer = config("k1", "secret-one");
+        other_issuer.jwt_issuer = "someone-else".to_string();
+        assert!(manager(&other_issuer).verify_access_token(&token).is_err());
+
+        let mut other_audience = config("k1", "secret-one");
+        other_audience.jwt_audience = "another-api".to_string();
+        assert!(manager(&other_audience)
+            .verify_access_token(&token)
+            .is_err());
+    }
+
+    #[test]
+    fn test_token_types_are_not_interchangeable() {
+        let jwt = manager(&config("k1", "secret-one"));
+        let refresh = jwt
+            .generate_refresh_token(Uuid::new_v4(), Uuid::new_v4())
+            .unwrap();
+        let service = jwt
+            .generate_service_token("autoscaler", &["queue:read".to_string()], 60)
+            .unwrap();
+
+        assert!(jwt.verify_access_token(&refresh).is_err());
+        assert!(jwt.verify_access_token(&service).is_err());
+        assert!(jwt.verify_refresh_token(&access_token(&jwt)).is_err());
+        assert!(jwt.verify_refresh_token(&refresh).is_ok());
+        assert!(jwt.verify_service_token(&access_token(&jwt)).is_err());
+        assert!(jwt.verify_service_token(&refresh).is_err());
+        assert!(jwt.verify_service_token(&service).is_ok());
+    }
+
+    #[test]
+    fn test_service_tokens_have_their_own_audience() {
+        let jwt = manager(&config("k1", "secret-one"));
+        let service = jwt
+            .generate_service_token("autoscaler", &["queue:read".to_string()], 60)
+            .unwrap();
+        let claims = jwt.verify_service_token(&service).unwrap();
+        assert_eq!(claims.aud, "olympus-services");
+        assert!(claims.has_scope("queue:read"));
+        assert!(!claims.has_scope("queue:manage"));
+
+        let mut other_audience = config("k1", "secret-one");
+        other_audience.jwt_service_audience = "other-services".to_string();
+        assert!(manager(&other_audience)
+            .verify_service_token(&service)
+            .is_err());
+    }
+
+    #[test]
+    fn test_unkeyed_tokens_verify_only_during_the_grace_period() {
+        let token = unkeyed_token("secret-one");
+
+        let strict = manager(&config("k1", "secret-one"));
+        assert!(strict.verify_access_token(&token).is_err());
+
+        let mut grace = config("k1", "secret-one");
+        grace.jwt_unkeyed_grace_until = Some((Utc::now() + Duration::days(1)).to_rfc3339());
+        assert!(manager(&grace).verify_access_token(&token).is_ok());
+        // Still only with the current key
+        let mut grace_other_key = grace.clone();
+        grace_other_key.jwt_secret = "secret-two".to_string();
+        assert!(manager(&grace_other_key)
+            .verify_access_token(&token)
+            .is_err());
+
+        let mut expired = config("k1", "secret-one");
+        expired.jwt_unkeyed_grace_until = Some((Utc::now() - Duration::days(1)).to_rfc3339());
+        assert!(manager(&expired).verify_access_token(&token).is_err());
+    }
+
+    #[test]
+    fn test_bad_key_configuration_is_an_error() {
+        let mut malformed = config("k1", "secret-one");
+        malformed.jwt_previous_keys = "old-secret-without-id".to_string();
+        assert!(JwtManager::from_config(&malformed).is_err());
+
+        let mut duplicate = config("k1", "secret-one");
+        duplicate.jwt_previous_keys = "k1=secret-two".to_string();
+        assert!(JwtManager::from_config(&duplicate).is_err());
+
+        let mut bad_grace = config("k1", "secret-one");
+        bad_grace.jwt_unkeyed_grace_until = Some("next week".to_string());
+        assert!(JwtManager::from_config(&bad_grace).is_err());
     }
 
-    /// Get refresh token expiration in seconds
-    pub fn refresh_expiration(&self) -> i64 {
-        self.refresh_expiration
+    #[test]
+    fn test_parse_key_pairs() {
+        assert_eq!(
+            parse_key_pairs(" a = one , ,b=two").unwrap(),
+            [
+                ("a".to_string(), "one".to_string()),
+                ("b".to_string(), "two".to_string())
+            ]
+        );
+        assert!(parse_key_pairs("").unwrap().is_empty());
+        assert!(parse_key_pairs("a=one,broken").is_err());
     }
 }
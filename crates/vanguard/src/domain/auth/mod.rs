@@ -4,8 +4,7 @@ mod handler;
 mod jwt;
 mod request;
 mod response;
+pub mod session;
 
 pub use handler::*;
 pub use jwt::*;
-pub use request::*;
-pub use response::*;
@@ -32,21 +32,42 @@ pub struct LoginRequest {
 }
 
 /// Refresh token request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct RefreshRequest {
+    #[validate(length(min = 1, message = "Refresh token is required"))]
     pub refresh_token: String,
 }
 
-/// Update user profile request
+/// Issue an internal service token (admin)
 #[derive(Debug, Deserialize, Validate)]
-pub struct UpdateProfileRequest {
-    #[validate(length(max = 64, message = "Display name must be at most 64 characters"))]
-    pub display_name: Option<String>,
+pub struct IssueServiceTokenRequest {
+    /// Calling service (e.g. `autoscaler`)
+    #[validate(regex(path = *SERVICE_NAME_REGEX, message = "Service must be 1-32 lowercase letters, digits or '-'"))]
+    pub service: String,
+
+    /// Operations the token allows (e.g. `queue:read`)
+    #[validate(length(min = 1, max = 16, message = "Between 1 and 16 scopes are required"))]
+    #[validate(custom(function = "validate_scopes"))]
+    pub scopes: Vec<String>,
+
+    /// Lifetime in seconds (defaults to, and is capped at, the configured maximum)
+    #[validate(range(min = 1, message = "TTL must be positive"))]
+    pub ttl_secs: Option<i64>,
+}
 
-    #[validate(length(max = 500, message = "Bio must be at most 500 characters"))]
-    pub bio: Option<String>,
+fn validate_scopes(scopes: &[String]) -> Result<(), validator::ValidationError> {
+    if scopes.iter().all(|scope| SCOPE_REGEX.is_match(scope)) {
+        Ok(())
+    } else {
+        let mut err = validator::ValidationError::new("invalid_scope");
+        err.message =
+            Some("Scopes must look like 'resource:action' (lowercase, '_' allowed)".into());
+        Err(err)
+    }
 }
 
 lazy_static::lazy_static! {
     static ref USERNAME_REGEX: regex::Regex = regex::Regex::new(r"^[a-zA-Z0-9_]+$").unwrap();
+    static ref SERVICE_NAME_REGEX: regex::Regex = regex::Regex::new(r"^[a-z0-9-]{1,32}$").unwrap();
+    static ref SCOPE_REGEX: regex::Regex = regex::Regex::new(r"^[a-z_]{1,32}(:[a-z_]{1,32})?$").unwrap();
 }
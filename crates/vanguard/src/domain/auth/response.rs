@@ -64,3 +64,32 @@ impl Default for LogoutResponse {
         }
     }
 }
+
+/// Issued service token
+#[derive(Debug, Serialize)]
+pub struct ServiceTokenResponse {
+    pub token: String,
+    pub token_type: &'static str,
+    pub service: String,
+    pub scopes: Vec<String>,
+    pub expires_in: i64,
+}
+
+/// Active session (signed-in device)
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Last sign-in or token refresh
+    pub last_active_at: Option<DateTime<Utc>>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Whether this is the session of the calling token
+    pub current: bool,
+}
+
+/// Session list response
+#[derive(Debug, Serialize)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionResponse>,
+}
@@ -0,0 +1,252 @@
+//! Refresh-token sessions.
+//!
+//! Each session is a Redis hash at `session:{user_id}:{session_id}` that
+//! expires with its refresh token. It records when the session started and
+//! when and from where it was last signed in or refreshed, for the session
+//! list. Refreshing rotates the session id but keeps `created_at`.
+//!
+//! A set at `sessions:{user_id}` indexes the ids of a user's sessions, so
+//! listing and revoking them never scans the keyspace. Ids whose hash has
+//! expired are pruned from the index as they are found.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use deadpool_redis::Connection;
+use uuid::Uuid;
+
+use crate::extract::ClientInfo;
+
+/// Stored session details
+#[derive(Debug)]
+pub struct StoredSession {
+    pub id: Uuid,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_active_at: Option<DateTime<Utc>>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+pub fn session_key(user_id: Uuid, session_id: Uuid) -> String {
+    format!("session:{}:{}", user_id, session_id)
+}
+
+/// Set of a user's session ids
+fn session_index_key(user_id: Uuid) -> String {
+    format!("sessions:{}", user_id)
+}
+
+/// Record a session used by `client` now, started at `created_at`.
+pub async fn store_session(
+    conn: &mut Connection,
+    user_id: Uuid,
+    session_id: Uuid,
+    client: &ClientInfo,
+    created_at: DateTime<Utc>,
+    ttl_secs: i64,
+) -> Result<(), redis::RedisError> {
+    let key = session_key(user_id, session_id);
+    let index = session_index_key(user_id);
+
+    redis::pipe()
+        .atomic()
+        .cmd("HSET")
+        .arg(&key)
+        .arg("created_at")
+        .arg(created_at.to_rfc3339())
+        .arg("last_active_at")
+        .arg(Utc::now().to_rfc3339())
+        .arg("ip")
+        .arg(client.ip.as_deref().unwrap_or(""))
+        .arg("user_agent")
+        .arg(client.user_agent.as_deref().unwrap_or(""))
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&key)
+        .arg(ttl_secs)
+        .ignore()
+        // Every session lives `ttl_secs`, so the newest one outlives the rest
+        .cmd("SADD")
+        .arg(&index)
+        .arg(session_id.to_string())
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&index)
+        .arg(ttl_secs)
+        .ignore()
+        .query_async(conn)
+        .await
+}
+
+/// When a live session started, or `None` if it expired or was revoked.
+pub async fn session_started_at(
+    conn: &mut Connection,
+    user_id: Uuid,
+    session_id: Uuid,
+) -> Result<Option<DateTime<Utc>>, redis::RedisError> {
+    let created_at: Option<String> = redis::cmd("HGET")
+        .arg(session_key(user_id, session_id))
+        .arg("created_at")
+        .query_async(conn)
+        .await?;
+
+    Ok(created_at.map(|value| parse_time(&value).unwrap_or_else(Utc::now)))
+}
+
+/// Every live session of a user.
+pub async fn list_sessions(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<Vec<StoredSession>, redis::RedisError> {
+    let index = session_index_key(user_id);
+    let members: Vec<String> = redis::cmd("SMEMBERS").arg(&index).query_async(conn).await?;
+    let ids: Vec<Uuid> = members.iter().filter_map(|id| id.parse().ok()).collect();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut pipe = redis::pipe();
+    for &id in &ids {
+        pipe.cmd("HGETALL").arg(session_key(user_id, id));
+    }
+    let details: Vec<HashMap<String, String>> = pipe.query_async(conn).await?;
+
+    let mut sessions = Vec::with_capacity(ids.len());
+    let mut expired = Vec::new();
+    for (id, fields) in ids.into_iter().zip(details) {
+        match stored_session(id, &fields) {
+            Some(session) => sessions.push(session),
+            None => expired.push(id.to_string()),
+        }
+    }
+    if !expired.is_empty() {
+        redis::cmd("SREM")
+            .arg(&index)
+            .arg(&expired)
+            .query_async::<()>(conn)
+            .await?;
+    }
+
+    Ok(sessions)
+}
+
+/// Session details from its hash, or `None` once the hash has expired
+fn stored_session(id: Uuid, fields: &HashMap<String, String>) -> Option<StoredSession> {
+    if fields.is_empty() {
+        return None;
+    }
+
+    let field = |name: &str| fields.get(name).filter(|v| !v.is_empty()).cloned();
+    Some(StoredSession {
+        id,
+        created_at: field("created_at").and_then(|v| parse_time(&v)),
+        last_active_at: field("last_active_at").and_then(|v| parse_time(&v)),
+        ip: field("ip"),
+        user_agent: field("user_agent"),
+    })
+}
+
+/// Revoke one session. Returns whether it existed.
+pub async fn revoke_session(
+    conn: &mut Connection,
+    user_id: Uuid,
+    session_id: Uuid,
+) -> Result<bool, redis::RedisError> {
+    let (removed,): (i64,) = redis::pipe()
+        .atomic()
+        .cmd("DEL")
+        .arg(session_key(user_id, session_id))
+        .cmd("SREM")
+        .arg(session_index_key(user_id))
+        .arg(session_id.to_string())
+        .ignore()
+        .query_async(conn)
+        .await?;
+
+    Ok(removed > 0)
+}
+
+/// Revoke every session of a user. Returns how many were live.
+pub async fn revoke_all_sessions(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<usize, redis::RedisError> {
+    let index = session_index_key(user_id);
+    let members: Vec<String> = redis::cmd("SMEMBERS").arg(&index).query_async(conn).await?;
+    let mut keys: Vec<String> = members
+        .iter()
+        .filter_map(|id| id.parse().ok())
+        .map(|id| session_key(user_id, id))
+        .collect();
+    keys.push(index);
+
+    let removed: usize = redis::cmd("DEL").arg(&keys).query_async(conn).await?;
+    // The index itself is one of the deleted keys
+    Ok(removed.saturating_sub(1))
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        let user = Uuid::parse_str("6f1c2a3e-9b7d-4c1e-8a2f-0d3b5e7c9a1b").unwrap();
+        let session = Uuid::parse_str("0d3b5e7c-9a1b-4c1e-8a2f-6f1c2a3e9b7d").unwrap();
+        assert_eq!(
+            session_key(user, session),
+            "session:6f1c2a3e-9b7d-4c1e-8a2f-0d3b5e7c9a1b:0d3b5e7c-9a1b-4c1e-8a2f-6f1c2a3e9b7d"
+        );
+        assert_eq!(
+            session_index_key(user),
+            "sessions:6f1c2a3e-9b7d-4c1e-8a2f-0d3b5e7c9a1b"
+        );
+    }
+
+    #[test]
+    fn test_stored_session_reads_the_hash() {
+        let id = Uuid::new_v4();
+        let fields: HashMap<String, String> = [
+            ("created_at", "2026-03-01T10:00:00+00:00"),
+            ("last_active_at", "2026-03-02T12:30:00+02:00"),
+            ("ip", "203.0.113.7"),
+            ("user_agent", ""),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let session = stored_session(id, &fields).unwrap();
+        assert_eq!(session.id, id);
+        assert_eq!(
+            session.created_at.unwrap().to_rfc3339(),
+            "2026-03-01T10:00:00+00:00"
+        );
+        assert_eq!(
+            session.last_active_at.unwrap().to_rfc3339(),
+            "2026-03-02T10:30:00+00:00"
+        );
+        assert_eq!(session.ip.as_deref(), Some("203.0.113.7"));
+        // Empty fields were not recorded
+        assert_eq!(session.user_agent, None);
+    }
+
+    #[test]
+    fn test_stored_session_tolerates_bad_times() {
+        let fields: HashMap<String, String> =
+            [("created_at".to_string(), "yesterday".to_string())].into();
+        let session = stored_session(Uuid::new_v4(), &fields).unwrap();
+        assert_eq!(session.created_at, None);
+    }
+
+    #[test]
+    fn test_expired_session_is_none() {
+        assert!(stored_session(Uuid::new_v4(), &HashMap::new()).is_none());
+    }
+}
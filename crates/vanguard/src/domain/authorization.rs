@@ -1,24 +1,35 @@
 //! Authorization helpers using olympus-rules specifications.
 //!
 //! This module provides convenient functions to check user permissions
-//! using the composable rules defined in olympus-rules.
+//! using the composable rules defined in olympus-rules, plus the
+//! [`Policy`] definitions evaluated by the [`Guard`](crate::extract::Guard)
+//! extractor.
 
 use std::sync::Arc;
 use uuid::Uuid;
 
 use olympus_rules::{
     auth_rules::{
-        CanAccessProblemBinaries, CanAddProblems, IsAdmin, IsCollaborator, IsContestOwner,
-        IsOrganizer, IsParticipant, IsProblemOwner, IsSubmissionOwner, IsValidUser, NotRateLimited,
+        ContestHasEnded, ContestIsPaused, ContestIsPublic, ContestIsRunning, ContestSourcesVisible,
+        ContestTestDataOpen, HasPermission, IsCollaborator, IsCollaboratorWith, IsContestOwner,
+        IsOwner, IsParticipant, IsProblemCollaboratorWith, IsProblemOwner, IsValidUser,
+        NotRateLimited, Resource,
     },
+    collaborators::CollaboratorPermission,
     context::AuthContext,
-    specification::Specification,
+    operators::Spec,
+    roles::Permission,
+    specification::{BoxedSpec, Specification},
 };
 
 use crate::error::{ApiError, ApiResult};
 use crate::middleware::auth::AuthUser;
 use crate::state::AppState;
 
+/// Role grants staff rights on every contest and problem; stands in for
+/// the contest owner in the rules below.
+const IS_MODERATOR: HasPermission = HasPermission(Permission::ModerateContests);
+
 /// Build an AuthContext from the current request state and user.
 pub fn build_auth_context(state: &AppState, user: &AuthUser) -> AuthContext {
     AuthContext::new(
@@ -28,6 +39,7 @@ pub fn build_auth_context(state: &AppState, user: &AuthUser) -> AuthContext {
         Arc::new(state.db.clone()),
         Arc::new(state.redis.clone()),
     )
+    .with_permissions(user.permissions)
 }
 
 /// Build an AuthContext with contest scope.
@@ -40,166 +52,295 @@ pub fn build_problem_context(state: &AppState, user: &AuthUser, problem_id: Uuid
     build_auth_context(state, user).with_problem(problem_id)
 }
 
-/// Build an AuthContext with submission scope.
-pub fn build_submission_context(
-    state: &AppState,
-    user: &AuthUser,
-    submission_id: Uuid,
-) -> AuthContext {
-    build_auth_context(state, user).with_submission(submission_id)
-}
-
-/// Build an AuthContext with contest and problem scope.
-pub fn build_contest_problem_context(
+/// Build an AuthContext scoped to a single resource.
+pub fn build_scoped_context(
     state: &AppState,
     user: &AuthUser,
-    contest_id: Uuid,
-    problem_id: Uuid,
+    resource: Resource,
+    id: Uuid,
 ) -> AuthContext {
-    build_auth_context(state, user)
-        .with_contest(contest_id)
-        .with_problem(problem_id)
+    let ctx = build_auth_context(state, user);
+    match resource {
+        Resource::Contest => ctx.with_contest(id),
+        Resource::Problem => ctx.with_problem(id),
+        Resource::Submission => ctx.with_submission(id),
+    }
 }
 
 // =============================================================================
-// Authorization check functions
+// Policies
 // =============================================================================
 
-/// Check if user is a valid (non-banned) user.
-pub async fn require_valid_user(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsValidUser.is_satisfied_by(ctx).await {
-        return Err(ApiError::Forbidden);
-    }
-    Ok(())
+/// Authorization policy checked by the [`Guard`](crate::extract::Guard)
+/// extractor before the handler runs.
+pub trait Policy: Send + Sync + 'static {
+    /// Resource the route targets; its id is read from the path.
+    const RESOURCE: Resource;
+
+    /// Rule the authenticated user must satisfy.
+    fn rule() -> BoxedSpec<AuthContext>;
 }
 
-/// Check if user is an admin.
-pub async fn require_admin(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsAdmin.is_satisfied_by(ctx).await {
-        return Err(ApiError::Forbidden);
+/// Policies used by vanguard routes.
+pub mod policies {
+    use std::sync::Arc;
+
+    use olympus_rules::auth_rules::{
+        IsCollaborator, IsCollaboratorWith, IsOwner, IsParticipant, IsProblemCollaboratorWith,
+        Resource,
+    };
+    use olympus_rules::collaborators::CollaboratorPermission;
+    use olympus_rules::context::AuthContext;
+    use olympus_rules::operators::Spec;
+    use olympus_rules::specification::BoxedSpec;
+
+    use super::{Policy, IS_MODERATOR};
+
+    /// Contest owner or moderator.
+    pub struct ContestOwner;
+
+    impl Policy for ContestOwner {
+        const RESOURCE: Resource = Resource::Contest;
+
+        fn rule() -> BoxedSpec<AuthContext> {
+            Arc::new(Spec(IS_MODERATOR) | Spec(IsOwner(Resource::Contest)))
+        }
     }
-    Ok(())
-}
 
-/// Check if user is an organizer (or admin).
-pub async fn require_organizer(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsOrganizer.is_satisfied_by(ctx).await {
-        return Err(ApiError::Forbidden);
+    /// Contest owner, moderator, or any collaborator.
+    pub struct ContestStaff;
+
+    impl Policy for ContestStaff {
+        const RESOURCE: Resource = Resource::Contest;
+
+        fn rule() -> BoxedSpec<AuthContext> {
+            Arc::new(Spec(IS_MODERATOR) | Spec(IsOwner(Resource::Contest)) | Spec(IsCollaborator))
+        }
     }
-    Ok(())
-}
 
-/// Check if user is a participant in the given contest.
-pub async fn require_participant(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsParticipant.is_satisfied_by(ctx).await {
-        return Err(ApiError::Forbidden);
+    /// Registered participant of the contest.
+    pub struct ContestParticipant;
+
+    impl Policy for ContestParticipant {
+        const RESOURCE: Resource = Resource::Contest;
+
+        fn rule() -> BoxedSpec<AuthContext> {
+            Arc::new(IsParticipant)
+        }
     }
-    Ok(())
-}
 
-/// Check if user is a collaborator of the given contest.
-pub async fn require_collaborator(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsCollaborator.is_satisfied_by(ctx).await {
-        return Err(ApiError::Forbidden);
+    /// Problem owner or moderator.
+    pub struct ProblemOwner;
+
+    impl Policy for ProblemOwner {
+        const RESOURCE: Resource = Resource::Problem;
+
+        fn rule() -> BoxedSpec<AuthContext> {
+            Arc::new(Spec(IS_MODERATOR) | Spec(IsOwner(Resource::Problem)))
+        }
+    }
+
+    /// Problem owner, moderator, or owner/collaborator with `can_edit_problems`
+    /// of a contest containing the problem.
+    pub struct EditProblem;
+
+    impl Policy for EditProblem {
+        const RESOURCE: Resource = Resource::Problem;
+
+        fn rule() -> BoxedSpec<AuthContext> {
+            Arc::new(
+                Spec(IS_MODERATOR)
+                    | Spec(IsOwner(Resource::Problem))
+                    | Spec(IsProblemCollaboratorWith(
+                        CollaboratorPermission::EditProblems,
+                    )),
+            )
+        }
+    }
+
+    /// Declare contest policies granted to the owner, moderators, and
+    /// collaborators holding one permission.
+    macro_rules! contest_permission_policies {
+        ($($(#[$doc:meta])* $name:ident => $permission:ident;)*) => {$(
+            $(#[$doc])*
+            pub struct $name;
+
+            impl Policy for $name {
+                const RESOURCE: Resource = Resource::Contest;
+
+                fn rule() -> BoxedSpec<AuthContext> {
+                    Arc::new(
+                        Spec(IS_MODERATOR)
+                            | Spec(IsOwner(Resource::Contest))
+                            | Spec(IsCollaboratorWith(CollaboratorPermission::$permission)),
+                    )
+                }
+            }
+        )*};
+    }
+
+    contest_permission_policies! {
+        /// Change contest settings.
+        EditContest => EditContest;
+        /// Change the contest's problem set.
+        AddProblems => AddProblems;
+        /// Remove participants.
+        ManageParticipants => ManageParticipants;
+        /// Rejudge contest submissions.
+        Rejudge => Rejudge;
+        /// Read every participant's sources and integrity telemetry.
+        ViewAllSources => ViewAllSources;
     }
-    Ok(())
 }
 
-/// Check if user is the owner of the given contest.
-pub async fn require_contest_owner(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsContestOwner.is_satisfied_by(ctx).await {
+// =============================================================================
+// Authorization check functions
+// =============================================================================
+
+/// Check an arbitrary rule, failing with `Forbidden`.
+pub async fn require<S: Specification<AuthContext>>(rule: S, ctx: &AuthContext) -> ApiResult<()> {
+    if !rule.is_satisfied_by(ctx).await {
         return Err(ApiError::Forbidden);
     }
     Ok(())
 }
 
-/// Check if user can modify the contest (owner, collaborator, or admin).
-pub async fn require_contest_modify_access(ctx: &AuthContext) -> ApiResult<()> {
-    // Admin always has access
-    if IsAdmin.is_satisfied_by(ctx).await {
+/// Check the context's contest is running, explaining why not otherwise.
+pub async fn require_contest_running(ctx: &AuthContext) -> ApiResult<()> {
+    if ContestIsRunning.is_satisfied_by(ctx).await {
         return Ok(());
     }
-    // Contest owner has access
-    if IsContestOwner.is_satisfied_by(ctx).await {
-        return Ok(());
-    }
-    // Collaborator has access
-    if IsCollaborator.is_satisfied_by(ctx).await {
-        return Ok(());
+
+    let started: Option<bool> =
+        sqlx::query_scalar("SELECT NOW() >= start_time FROM contests WHERE id = $1")
+            .bind(ctx.contest_id)
+            .fetch_optional(ctx.db.as_ref())
+            .await?;
+
+    match started {
+        None => Err(ApiError::NotFound("Contest not found".to_string())),
+        Some(false) => Err(ApiError::Validation(
+            "Contest has not started yet".to_string(),
+        )),
+        Some(true) => Err(ApiError::Validation("Contest has ended".to_string())),
     }
-    Err(ApiError::Forbidden)
 }
 
-/// Check if user can add problems to a contest.
-pub async fn require_can_add_problems(ctx: &AuthContext) -> ApiResult<()> {
-    if !CanAddProblems.is_satisfied_by(ctx).await {
-        return Err(ApiError::Forbidden);
+/// Check the context's contest is not paused.
+pub async fn require_contest_not_paused(ctx: &AuthContext) -> ApiResult<()> {
+    let contest_id = ctx
+        .contest_id
+        .ok_or_else(|| ApiError::Internal("Pause check without a contest".to_string()))?;
+    // A failed lookup rejects the request rather than letting it through
+    if ContestIsPaused::lookup(contest_id, ctx.db.as_ref()).await? {
+        return Err(ApiError::ContestPaused);
     }
     Ok(())
 }
 
-/// Check if user owns the given problem.
-pub async fn require_problem_owner(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsProblemOwner.is_satisfied_by(ctx).await {
+/// Check if the user's role grants a site permission.
+pub async fn require_permission(ctx: &AuthContext, permission: Permission) -> ApiResult<()> {
+    if !HasPermission(permission).is_satisfied_by(ctx).await {
         return Err(ApiError::Forbidden);
     }
     Ok(())
 }
 
-/// Check if user can modify the problem (owner or admin).
-pub async fn require_problem_modify_access(ctx: &AuthContext) -> ApiResult<()> {
-    if IsAdmin.is_satisfied_by(ctx).await {
-        return Ok(());
-    }
-    if IsProblemOwner.is_satisfied_by(ctx).await {
-        return Ok(());
+/// Whether the user holds a collaborator permission on the context's contest.
+/// A role granting `answer_clarifications` answers them in every contest.
+///
+/// Rule: IsModerator OR IsContestOwner OR IsCollaboratorWith(permission)
+pub async fn has_contest_permission(ctx: &AuthContext, permission: CollaboratorPermission) -> bool {
+    if permission == CollaboratorPermission::AnswerClarifications
+        && ctx.permissions.contains(Permission::AnswerClarifications)
+    {
+        return true;
     }
-    Err(ApiError::Forbidden)
+    let rule = Spec(IS_MODERATOR) | Spec(IsContestOwner) | Spec(IsCollaboratorWith(permission));
+    rule.is_satisfied_by(ctx).await
 }
 
-/// Check if user can access problem binaries.
-pub async fn require_problem_binary_access(ctx: &AuthContext) -> ApiResult<()> {
-    if !CanAccessProblemBinaries.is_satisfied_by(ctx).await {
+/// Check if user holds a collaborator permission on the given contest.
+pub async fn require_contest_permission(
+    ctx: &AuthContext,
+    permission: CollaboratorPermission,
+) -> ApiResult<()> {
+    if !has_contest_permission(ctx, permission).await {
         return Err(ApiError::Forbidden);
     }
     Ok(())
 }
 
-/// Check if user owns the given submission.
-pub async fn require_submission_owner(ctx: &AuthContext) -> ApiResult<()> {
-    if !IsSubmissionOwner.is_satisfied_by(ctx).await {
+/// Check if user holds a collaborator permission on the given problem
+/// (through any contest containing it).
+///
+/// Rule: IsModerator OR IsProblemOwner OR IsProblemCollaboratorWith(permission)
+pub async fn require_problem_permission(
+    ctx: &AuthContext,
+    permission: CollaboratorPermission,
+) -> ApiResult<()> {
+    let rule =
+        Spec(IS_MODERATOR) | Spec(IsProblemOwner) | Spec(IsProblemCollaboratorWith(permission));
+    if !rule.is_satisfied_by(ctx).await {
         return Err(ApiError::Forbidden);
     }
     Ok(())
 }
 
-/// Check if user can view the submission (owner, admin, or collaborator).
+/// Check if user can modify the problem (owner or moderator).
+pub async fn require_problem_modify_access(ctx: &AuthContext) -> ApiResult<()> {
+    require(Spec(IS_MODERATOR) | Spec(IsOwner(Resource::Problem)), ctx).await
+}
+
+/// Check if user can view the submission's details: its author, a role
+/// granting `view_private_content`, the owner / a collaborator with
+/// `can_view_submissions` of its contest, or anyone the contest's
+/// `source_visibility` admits once it has ended.
+/// The contest scope is optional (standalone submissions have none).
 pub async fn require_submission_view_access(ctx: &AuthContext) -> ApiResult<()> {
-    if IsAdmin.is_satisfied_by(ctx).await {
-        return Ok(());
-    }
-    if IsSubmissionOwner.is_satisfied_by(ctx).await {
+    if (Spec(HasPermission(Permission::ViewPrivateContent)) | Spec(IsOwner(Resource::Submission)))
+        .is_satisfied_by(ctx)
+        .await
+    {
         return Ok(());
     }
-    // For viewing submissions, collaborators of the contest should also have access
-    if IsCollaborator.is_satisfied_by(ctx).await {
+    if ctx.contest_id.is_some()
+        && (has_contest_permission(ctx, CollaboratorPermission::ViewAllSources).await
+            || ContestSourcesVisible.is_satisfied_by(ctx).await)
+    {
         return Ok(());
     }
     Err(ApiError::Forbidden)
 }
 
-/// Check if user is not rate limited.
-pub async fn require_not_rate_limited(ctx: &AuthContext) -> ApiResult<()> {
-    let rule = NotRateLimited::api_authenticated();
-    if !rule.is_satisfied_by(ctx).await {
-        return Err(ApiError::RateLimitExceeded);
+/// Check if user can download a submission's test input and kept output:
+/// problem staff (as for [`policies::EditProblem`]), or the submission's
+/// author once its contest has ended with `test_data_downloads` on.
+/// Requires problem and submission scope; the contest scope is optional.
+pub async fn require_test_data_access(ctx: &AuthContext) -> ApiResult<()> {
+    if (Spec(IS_MODERATOR)
+        | Spec(IsOwner(Resource::Problem))
+        | Spec(IsProblemCollaboratorWith(
+            CollaboratorPermission::EditProblems,
+        )))
+    .is_satisfied_by(ctx)
+    .await
+    {
+        return Ok(());
     }
-    Ok(())
+    if ctx.contest_id.is_some()
+        && (Spec(IsOwner(Resource::Submission)) & Spec(ContestTestDataOpen))
+            .is_satisfied_by(ctx)
+            .await
+    {
+        return Ok(());
+    }
+    Err(ApiError::Forbidden)
 }
 
 /// Check if user can submit to a contest.
 ///
-/// Rule: IsValidUser AND ((IsParticipant AND NotRateLimited) OR IsAdmin OR IsCollaborator)
+/// Rule: IsValidUser AND ((IsParticipant AND NotRateLimited) OR IsModerator OR IsCollaborator)
 pub async fn require_can_submit(ctx: &AuthContext) -> ApiResult<()> {
     // Must be valid user
     let valid_user = IsValidUser;
@@ -207,9 +348,8 @@ pub async fn require_can_submit(ctx: &AuthContext) -> ApiResult<()> {
         return Err(ApiError::Forbidden);
     }
 
-    // Admin always can submit
-    let admin = IsAdmin;
-    if admin.is_satisfied_by(ctx).await {
+    // Moderators always can submit
+    if IS_MODERATOR.is_satisfied_by(ctx).await {
         return Ok(());
     }
 
@@ -233,6 +373,72 @@ pub async fn require_can_submit(ctx: &AuthContext) -> ApiResult<()> {
     Err(ApiError::Forbidden)
 }
 
+/// How a contest submission counts, decided when it is made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContestSubmissionKind {
+    /// Made while the contest runs; counts towards the standings
+    Live,
+    /// Made after the contest ended ("upsolving")
+    Practice,
+    /// Made by the contest team before the start to check the problems;
+    /// visible to the team only
+    Test,
+}
+
+/// Check the user may submit to the context's contest now.
+///
+/// While the contest runs this is [`require_contest_running`],
+/// [`require_contest_not_paused`] and [`require_can_submit`]. Before it
+/// starts, only the owner, moderators and collaborators with
+/// `can_test_solutions` may submit, as test submissions. Once it has
+/// ended, submissions are practice ("upsolving") submissions, open to
+/// anyone who could see the contest:
+///
+/// Rule: IsValidUser AND (IsModerator OR IsContestOwner OR IsCollaborator OR
+/// IsParticipant OR ContestIsPublic) AND NotRateLimited
+pub async fn require_can_submit_to_contest(ctx: &AuthContext) -> ApiResult<ContestSubmissionKind> {
+    if !ContestHasEnded.is_satisfied_by(ctx).await {
+        if !ContestIsRunning.is_satisfied_by(ctx).await && can_test_solutions(ctx).await {
+            return Ok(ContestSubmissionKind::Test);
+        }
+        require_contest_running(ctx).await?;
+        require_contest_not_paused(ctx).await?;
+        require_can_submit(ctx).await?;
+        return Ok(ContestSubmissionKind::Live);
+    }
+
+    if !IsValidUser.is_satisfied_by(ctx).await {
+        return Err(ApiError::Forbidden);
+    }
+
+    let can_see: BoxedSpec<AuthContext> = Arc::new(
+        Spec(IS_MODERATOR)
+            | Spec(IsOwner(Resource::Contest))
+            | Spec(IsCollaborator)
+            | Spec(IsParticipant)
+            | Spec(ContestIsPublic),
+    );
+    if !can_see.is_satisfied_by(ctx).await {
+        return Err(ApiError::Forbidden);
+    }
+
+    if !NotRateLimited::submission().is_satisfied_by(ctx).await {
+        return Err(ApiError::RateLimitExceeded);
+    }
+
+    Ok(ContestSubmissionKind::Practice)
+}
+
+/// Whether the user may make test submissions to the context's contest.
+async fn can_test_solutions(ctx: &AuthContext) -> bool {
+    (Spec(IsValidUser)
+        & (Spec(IS_MODERATOR)
+            | Spec(IsOwner(Resource::Contest))
+            | Spec(IsCollaboratorWith(CollaboratorPermission::TestSolutions))))
+    .is_satisfied_by(ctx)
+    .await
+}
+
 /// Check if user can make a standalone submission (no contest).
 ///
 /// Rule: IsValidUser AND NotRateLimited
@@ -249,3 +455,98 @@ pub async fn require_can_submit_standalone(ctx: &AuthContext) -> ApiResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use olympus_rules::roles::PermissionSet;
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::policies::*;
+    use super::*;
+
+    /// Context whose database and Redis are unreachable, so every lookup
+    /// fails and only the role permissions can grant access.
+    fn offline_context(permissions: PermissionSet, resource: Resource) -> AuthContext {
+        let db = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://olympus@127.0.0.1:1/olympus")
+            .unwrap();
+        let redis = crate::config::create_redis_pool("redis://127.0.0.1:1").unwrap();
+        let ctx = AuthContext::new(
+            Uuid::new_v4(),
+            "participant".to_string(),
+            false,
+            Arc::new(db),
+            Arc::new(redis),
+        )
+        .with_permissions(permissions);
+        match resource {
+            Resource::Contest => ctx.with_contest(Uuid::new_v4()),
+            Resource::Problem => ctx.with_problem(Uuid::new_v4()),
+            Resource::Submission => ctx.with_submission(Uuid::new_v4()),
+        }
+    }
+
+    async fn allows<P: Policy>(permissions: PermissionSet) -> bool {
+        let ctx = offline_context(permissions, P::RESOURCE);
+        P::rule().is_satisfied_by(&ctx).await
+    }
+
+    fn moderator() -> PermissionSet {
+        [Permission::ModerateContests].into_iter().collect()
+    }
+
+    #[test]
+    fn test_policies_target_their_resource() {
+        assert_eq!(ContestOwner::RESOURCE, Resource::Contest);
+        assert_eq!(ContestStaff::RESOURCE, Resource::Contest);
+        assert_eq!(ContestParticipant::RESOURCE, Resource::Contest);
+        assert_eq!(EditContest::RESOURCE, Resource::Contest);
+        assert_eq!(Rejudge::RESOURCE, Resource::Contest);
+        assert_eq!(ProblemOwner::RESOURCE, Resource::Problem);
+        assert_eq!(EditProblem::RESOURCE, Resource::Problem);
+    }
+
+    #[tokio::test]
+    async fn test_moderators_pass_staff_policies() {
+        assert!(allows::<ContestOwner>(moderator()).await);
+        assert!(allows::<ContestStaff>(moderator()).await);
+        assert!(allows::<EditContest>(moderator()).await);
+        assert!(allows::<AddProblems>(moderator()).await);
+        assert!(allows::<ManageParticipants>(moderator()).await);
+        assert!(allows::<Rejudge>(moderator()).await);
+        assert!(allows::<ViewAllSources>(moderator()).await);
+        assert!(allows::<ProblemOwner>(moderator()).await);
+        assert!(allows::<EditProblem>(moderator()).await);
+    }
+
+    #[tokio::test]
+    async fn test_moderation_does_not_register_for_contests() {
+        assert!(!allows::<ContestParticipant>(moderator()).await);
+    }
+
+    #[tokio::test]
+    async fn test_policies_fail_closed_when_lookups_fail() {
+        let none = PermissionSet::default();
+        assert!(!allows::<ContestOwner>(none).await);
+        assert!(!allows::<ContestStaff>(none).await);
+        assert!(!allows::<ContestParticipant>(none).await);
+        assert!(!allows::<EditContest>(none).await);
+        assert!(!allows::<ViewAllSources>(none).await);
+        assert!(!allows::<ProblemOwner>(none).await);
+        assert!(!allows::<EditProblem>(none).await);
+    }
+
+    #[tokio::test]
+    async fn test_other_role_permissions_grant_no_staff_policy() {
+        let permissions: PermissionSet = Permission::ALL
+            .into_iter()
+            .filter(|p| *p != Permission::ModerateContests)
+            .collect();
+        assert!(!allows::<ContestOwner>(permissions).await);
+        assert!(!allows::<EditContest>(permissions).await);
+        assert!(!allows::<EditProblem>(permissions).await);
+    }
+}
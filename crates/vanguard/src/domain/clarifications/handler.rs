@@ -0,0 +1,293 @@
+//! Contest clarification handlers.
+//!
+//! Registered participants ask while the contest runs and read their own
+//! questions plus the public answers, alongside the contest's active
+//! announcements. Staff with `can_answer_clarifications` see every question
+//! and answer them.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use olympus_common::NotificationKind;
+use olympus_rules::auth_rules::IsParticipant;
+use olympus_rules::collaborators::CollaboratorPermission;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::announcements::handler::active_contest_announcements;
+use crate::domain::authorization::{
+    build_contest_context, has_contest_permission, policies, require, require_contest_permission,
+    require_contest_running,
+};
+use crate::error::{ApiError, ApiResult};
+use crate::extract::{Guard, ValidatedJson};
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+const CLARIFICATION_COLUMNS: &str = "cl.id, cl.contest_id, cl.problem_id, cp.problem_code, \
+     cl.user_id, cl.question, cl.answer, cl.is_public, cl.answered_at, cl.created_at";
+
+/// Database row for a clarification, with its problem code
+#[derive(Debug, FromRow)]
+struct ClarificationRow {
+    id: Uuid,
+    contest_id: Uuid,
+    problem_id: Option<Uuid>,
+    problem_code: Option<String>,
+    user_id: Option<Uuid>,
+    question: String,
+    answer: Option<String>,
+    is_public: bool,
+    answered_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+/// GET /api/v1/contests/{contest_id}/clarifications
+///
+/// Staff who answer clarifications get every question; participants get
+/// their own and the public ones. The contest's announcements currently
+/// showing are merged in, tagged `"type": "announcement"`.
+pub async fn list_clarifications(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<ClarificationListResponse>> {
+    require_contest_exists(&state, contest_id).await?;
+
+    let ctx = build_contest_context(&state, &user, contest_id);
+    let is_staff = has_contest_permission(&ctx, CollaboratorPermission::AnswerClarifications).await;
+    if !is_staff {
+        require(IsParticipant, &ctx).await?;
+    }
+
+    let rows = sqlx::query_as::<_, ClarificationRow>(&format!(
+        r#"
+        SELECT {}
+        FROM contest_clarifications cl
+        LEFT JOIN contest_problems cp
+               ON cp.contest_id = cl.contest_id AND cp.problem_id = cl.problem_id
+        WHERE cl.contest_id = $1
+          AND ($2 OR cl.user_id = $3 OR (cl.is_public AND cl.answer IS NOT NULL))
+        ORDER BY cl.created_at DESC
+        "#,
+        CLARIFICATION_COLUMNS
+    ))
+    .bind(contest_id)
+    .bind(is_staff)
+    .bind(user.id)
+    .fetch_all(state.read_db())
+    .await?;
+
+    let announcements = active_contest_announcements(state.read_db(), contest_id).await?;
+
+    let mut clarifications: Vec<ClarificationFeedItem> = rows
+        .into_iter()
+        .map(|row| ClarificationFeedItem::Clarification(row_to_response(row, is_staff, user.id)))
+        .chain(
+            announcements
+                .into_iter()
+                .map(ClarificationFeedItem::Announcement),
+        )
+        .collect();
+    clarifications.sort_by_key(|item| std::cmp::Reverse(item.posted_at()));
+
+    Ok(Json(ClarificationListResponse { clarifications }))
+}
+
+/// POST /api/v1/contests/{contest_id}/clarifications
+///
+/// Ask a question about the running contest or one of its problems.
+pub async fn create_clarification(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ContestParticipant>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreateClarificationRequest>,
+) -> ApiResult<(StatusCode, Json<ClarificationResponse>)> {
+    let ctx = build_contest_context(&state, &user, contest_id);
+    require_contest_running(&ctx).await?;
+
+    if let Some(problem_id) = payload.problem_id {
+        let in_contest: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM contest_problems WHERE contest_id = $1 AND problem_id = $2)",
+        )
+        .bind(contest_id)
+        .bind(problem_id)
+        .fetch_one(&state.db)
+        .await?;
+        if !in_contest {
+            return Err(ApiError::NotFound(
+                "Problem not found in contest".to_string(),
+            ));
+        }
+    }
+
+    let clarification_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO contest_clarifications (contest_id, problem_id, user_id, question)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id
+        "#,
+    )
+    .bind(contest_id)
+    .bind(payload.problem_id)
+    .bind(user.id)
+    .bind(&payload.question)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        clarification_id = %clarification_id,
+        user_id = %user.id,
+        "Clarification requested"
+    );
+
+    let row = fetch_clarification(&state.db, contest_id, clarification_id).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(row_to_response(row, false, user.id)),
+    ))
+}
+
+/// PUT /api/v1/contests/{contest_id}/clarifications/{clarification_id}/answer
+///
+/// Answer a clarification, or replace its answer.
+pub async fn answer_clarification(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((contest_id, clarification_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<AnswerClarificationRequest>,
+) -> ApiResult<Json<ClarificationResponse>> {
+    fetch_clarification(&state.db, contest_id, clarification_id).await?;
+
+    let ctx = build_contest_context(&state, &user, contest_id);
+    require_contest_permission(&ctx, CollaboratorPermission::AnswerClarifications).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE contest_clarifications
+        SET answer = $1, is_public = $2, answered_by = $3, answered_at = NOW()
+        WHERE id = $4
+        "#,
+    )
+    .bind(&payload.answer)
+    .bind(payload.is_public)
+    .bind(user.id)
+    .bind(clarification_id)
+    .execute(&mut *tx)
+    .await?;
+
+    notify_asker(&mut tx, clarification_id).await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        clarification_id = %clarification_id,
+        user_id = %user.id,
+        is_public = payload.is_public,
+        "Clarification answered"
+    );
+
+    let row = fetch_clarification(&state.db, contest_id, clarification_id).await?;
+    Ok(Json(row_to_response(row, true, user.id)))
+}
+
+/// Tell whoever asked that their question has an answer. Nobody is told
+/// when the asker's account is gone.
+async fn notify_asker(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    clarification_id: Uuid,
+) -> ApiResult<()> {
+    sqlx::query(
+        r#"
+        SELECT notify_user(
+            cl.user_id, $2,
+            LEFT('Your question in ' || c.title || ' was answered', 255),
+            LEFT(cl.answer, 200),
+            c.id, NULL
+        )
+        FROM contest_clarifications cl
+        JOIN contests c ON c.id = cl.contest_id
+        WHERE cl.id = $1 AND cl.user_id IS NOT NULL
+        "#,
+    )
+    .bind(clarification_id)
+    .bind(NotificationKind::ClarificationAnswered.as_str())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn require_contest_exists(state: &AppState, contest_id: Uuid) -> ApiResult<()> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contests WHERE id = $1)")
+        .bind(contest_id)
+        .fetch_one(state.read_db())
+        .await?;
+    if !exists {
+        return Err(ApiError::NotFound("Contest not found".to_string()));
+    }
+    Ok(())
+}
+
+async fn fetch_clarification(
+    db: &sqlx::PgPool,
+    contest_id: Uuid,
+    clarification_id: Uuid,
+) -> ApiResult<ClarificationRow> {
+    sqlx::query_as::<_, ClarificationRow>(&format!(
+        r#"
+        SELECT {}
+        FROM contest_clarifications cl
+        LEFT JOIN contest_problems cp
+               ON cp.contest_id = cl.contest_id AND cp.problem_id = cl.problem_id
+        WHERE cl.id = $1 AND cl.contest_id = $2
+        "#,
+        CLARIFICATION_COLUMNS
+    ))
+    .bind(clarification_id)
+    .bind(contest_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Clarification not found".to_string()))
+}
+
+/// The asker is only named to staff and to the asker themselves.
+fn row_to_response(row: ClarificationRow, is_staff: bool, viewer: Uuid) -> ClarificationResponse {
+    let user_id = row.user_id.filter(|asker| is_staff || *asker == viewer);
+    ClarificationResponse {
+        id: row.id,
+        contest_id: row.contest_id,
+        problem_id: row.problem_id,
+        problem_code: row.problem_code,
+        user_id,
+        question: row.question,
+        answer: row.answer,
+        is_public: row.is_public,
+        answered_at: row.answered_at,
+        created_at: row.created_at,
+    }
+}
+
+/// Clarification routes (signed-in participants and staff)
+pub fn clarification_routes() -> axum::Router<AppState> {
+    use axum::routing::{get, put};
+
+    axum::Router::new()
+        .route(
+            "/{contest_id}/clarifications",
+            get(list_clarifications).post(create_clarification),
+        )
+        .route(
+            "/{contest_id}/clarifications/{clarification_id}/answer",
+            put(answer_clarification),
+        )
+}
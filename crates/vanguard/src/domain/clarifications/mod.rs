@@ -0,0 +1,11 @@
+//! Contest clarifications domain module.
+//!
+//! Participants ask questions about a running contest or one of its
+//! problems. Staff holding `can_answer_clarifications` read every question
+//! and answer it, privately to the asker or publicly to all participants.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
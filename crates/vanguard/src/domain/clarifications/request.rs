@@ -0,0 +1,26 @@
+//! Contest clarification request DTOs.
+
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Ask a clarification
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateClarificationRequest {
+    /// Problem the question is about (`None` for the contest in general)
+    pub problem_id: Option<Uuid>,
+
+    #[validate(length(min = 1, max = 2000, message = "Question must be 1-2000 characters"))]
+    pub question: String,
+}
+
+/// Answer a clarification
+#[derive(Debug, Deserialize, Validate)]
+pub struct AnswerClarificationRequest {
+    #[validate(length(min = 1, max = 2000, message = "Answer must be 1-2000 characters"))]
+    pub answer: String,
+
+    /// Show the question and answer to every participant
+    #[serde(default)]
+    pub is_public: bool,
+}
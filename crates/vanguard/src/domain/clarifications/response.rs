@@ -0,0 +1,88 @@
+//! Contest clarification response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::announcements::response::Announcement;
+
+/// A clarification and its answer, if any
+#[derive(Debug, Serialize)]
+pub struct ClarificationResponse {
+    pub id: Uuid,
+    pub contest_id: Uuid,
+    pub problem_id: Option<Uuid>,
+    pub problem_code: Option<String>,
+    /// Who asked; only shown to staff and the asker
+    pub user_id: Option<Uuid>,
+    pub question: String,
+    pub answer: Option<String>,
+    pub is_public: bool,
+    pub answered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An entry of a contest's clarification feed, tagged with its `type`
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClarificationFeedItem {
+    Clarification(ClarificationResponse),
+    /// Contest announcement currently showing
+    Announcement(Announcement),
+}
+
+impl ClarificationFeedItem {
+    /// When the entry was posted: asked, or started showing
+    pub fn posted_at(&self) -> DateTime<Utc> {
+        match self {
+            Self::Clarification(clarification) => clarification.created_at,
+            Self::Announcement(announcement) => announcement.starts_at,
+        }
+    }
+}
+
+/// Clarifications the caller can see and the contest's active
+/// announcements, newest first
+#[derive(Debug, Serialize)]
+pub struct ClarificationListResponse {
+    pub clarifications: Vec<ClarificationFeedItem>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_items_are_tagged_with_their_type() {
+        let now = Utc::now();
+        let items = vec![
+            ClarificationFeedItem::Announcement(Announcement {
+                id: Uuid::nil(),
+                title: "Judge restarted".to_string(),
+                body: "Resubmit if stuck".to_string(),
+                severity: "warning".to_string(),
+                contest_id: Some(Uuid::nil()),
+                starts_at: now,
+                ends_at: None,
+            }),
+            ClarificationFeedItem::Clarification(ClarificationResponse {
+                id: Uuid::nil(),
+                contest_id: Uuid::nil(),
+                problem_id: None,
+                problem_code: None,
+                user_id: None,
+                question: "Is n inclusive?".to_string(),
+                answer: Some("Yes".to_string()),
+                is_public: true,
+                answered_at: Some(now),
+                created_at: now,
+            }),
+        ];
+
+        let json = serde_json::to_value(&items).unwrap();
+        assert_eq!(json[0]["type"], "announcement");
+        assert_eq!(json[0]["title"], "Judge restarted");
+        assert_eq!(json[1]["type"], "clarification");
+        assert_eq!(json[1]["question"], "Is n inclusive?");
+    }
+}
@@ -1,36 +1,56 @@
 //! Contest handlers.
 
+use std::collections::BTreeMap;
+
 use axum::{
     extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use olympus_common::NotificationKind;
+use olympus_rules::advancement::Standing;
+use olympus_rules::collaborators::{
+    CollaboratorPermission, CollaboratorPermissions, CollaboratorRole,
+};
+use olympus_rules::roles::Permission;
+use olympus_rules::specification::Specification;
 use sqlx::FromRow;
 use uuid::Uuid;
-use validator::Validate;
 
 use super::{
     request::{
-        AddCollaboratorRequest, CreateContestRequest, ListContestsQuery, ListParticipantsQuery,
-        UpdateContestRequest,
+        AddCollaboratorRequest, CarryOverRequest, ContestListFilters, CreateContestRequest,
+        CreateMirrorRequest, ExtendContestRequest, ListContestsQuery, ListParticipantsQuery,
+        ListWaitlistQuery, PauseContestRequest, UpdateCollaboratorRequest, UpdateContestRequest,
     },
     response::{
-        CollaboratorInfo, CollaboratorListResponse, ContestDetailResponse, ContestListResponse,
-        ContestResponse, ContestSummary, MessageResponse, OwnerInfo, Pagination, ParticipantInfo,
-        ParticipantListResponse, RegistrationResponse,
+        CarriedParticipant, CarryOverResponse, CollaboratorInfo, CollaboratorListResponse,
+        ContestDetailResponse, ContestListResponse, ContestPauseInfo, ContestPauseResponse,
+        ContestResponse, ContestStatisticsResponse, ContestSummary, LanguageStatistics,
+        MessageResponse, MirrorComparisonResponse, OwnerInfo, Pagination, ParticipantInfo,
+        ParticipantListResponse, ProblemComparison, ProblemStatistics, RegistrationResponse,
+        SubmissionTimeline, TimelineBucket, WaitlistEntry, WaitlistResponse,
     },
 };
+use crate::domain::admin::{self, ContestRejudgeResponse};
 use crate::domain::authorization::{
-    build_contest_context, require_contest_modify_access, require_organizer,
+    build_contest_context, has_contest_permission, policies, require_contest_running,
+    require_permission, Policy,
 };
-use crate::error::{ApiError, ApiResult};
+use crate::domain::organizations::{member_role, require_organization_manager};
+use crate::domain::submissions::handler::{standings_ctes, standings_order};
+use crate::error::{self, ApiError, ApiResult};
+use crate::extract::{Guard, ValidatedJson};
 use crate::middleware::auth::AuthUser;
+use crate::repositories::contests::{
+    self as contest_repo, ContestFilter, ContestStatus, ContestViewer,
+};
 use crate::state::AppState;
 
 /// Database row for contest with owner info
 #[derive(Debug, FromRow)]
-struct ContestRow {
+pub(crate) struct ContestRow {
     id: Uuid,
     title: String,
     description: Option<String>,
@@ -39,11 +59,22 @@ struct ContestRow {
     end_time: DateTime<Utc>,
     freeze_time: Option<DateTime<Utc>>,
     scoring_type: String,
-    is_public: bool,
+    pub(crate) is_public: bool,
     is_rated: bool,
     registration_required: bool,
     max_participants: Option<i32>,
     allowed_languages: Option<Vec<String>>,
+    submission_cooldown_secs: i32,
+    submissions_per_hour: Option<i32>,
+    max_submissions_per_problem: Option<i32>,
+    source_visibility: String,
+    is_proctored: bool,
+    test_data_downloads: bool,
+    scoreboard_feed: bool,
+    dynamic_min_percent: i32,
+    dynamic_decay: i32,
+    organization_id: Option<Uuid>,
+    mirror_of: Option<Uuid>,
     owner_id: Uuid,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
@@ -65,157 +96,46 @@ fn get_contest_status(start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Str
 // Contest CRUD
 // =============================================================================
 
-/// Database row for contest list query
-#[derive(Debug, FromRow)]
-struct ContestListRow {
-    id: Uuid,
-    title: String,
-    short_description: Option<String>,
-    start_time: DateTime<Utc>,
-    end_time: DateTime<Utc>,
-    scoring_type: String,
-    is_public: bool,
-    is_rated: bool,
-    owner_id: Uuid,
-    owner_username: String,
-    owner_display_name: Option<String>,
-    participant_count: i64,
-}
-
 /// GET /api/v1/contests
 ///
 /// List contests with pagination and filtering.
 pub async fn list_contests(
     State(state): State<AppState>,
-    _user: Option<Extension<AuthUser>>,
+    user: Option<Extension<AuthUser>>,
     Query(query): Query<ListContestsQuery>,
 ) -> ApiResult<Json<ContestListResponse>> {
     let page = query.page.max(1);
     let per_page = query.per_page.clamp(1, 100);
     let offset = ((page - 1) * per_page) as i64;
-    let now = Utc::now();
-
-    // Build dynamic WHERE conditions with proper parameter numbering.
-    // Data query: $1 = limit, $2 = offset, then $3+ for filters.
-    // Count query: $1+ for filters (no limit/offset).
-    let mut data_conditions = Vec::new();
-    let mut count_conditions = Vec::new();
-    let mut data_idx = 3u32;
-    let mut count_idx = 1u32;
-
-    // Visibility filter (literal — no bind parameter needed)
-    if query.public_only {
-        data_conditions.push("c.is_public = true".to_string());
-        count_conditions.push("c.is_public = true".to_string());
-    }
-
-    // Status filter (binds `now`)
-    let has_status = match query.status.as_deref() {
-        Some("upcoming") => {
-            data_conditions.push(format!("c.start_time > ${data_idx}"));
-            count_conditions.push(format!("c.start_time > ${count_idx}"));
-            data_idx += 1;
-            count_idx += 1;
-            true
-        }
-        Some("ongoing") => {
-            data_conditions.push(format!(
-                "c.start_time <= ${data_idx} AND c.end_time >= ${data_idx}"
-            ));
-            count_conditions.push(format!(
-                "c.start_time <= ${count_idx} AND c.end_time >= ${count_idx}"
-            ));
-            data_idx += 1;
-            count_idx += 1;
-            true
-        }
-        Some("past") => {
-            data_conditions.push(format!("c.end_time < ${data_idx}"));
-            count_conditions.push(format!("c.end_time < ${count_idx}"));
-            data_idx += 1;
-            count_idx += 1;
-            true
-        }
-        _ => false,
-    };
-
-    // Search filter (binds search pattern)
-    if query.search.is_some() {
-        data_conditions.push(format!("c.title ILIKE ${data_idx}"));
-        count_conditions.push(format!("c.title ILIKE ${count_idx}"));
-        data_idx += 1;
-        count_idx += 1;
-    }
-
-    // Owner filter (binds owner_id)
-    if query.owner_id.is_some() {
-        data_conditions.push(format!("c.owner_id = ${data_idx}"));
-        count_conditions.push(format!("c.owner_id = ${count_idx}"));
-        // data_idx += 1; count_idx += 1; // last filter, no need to increment
-    }
-
-    let data_where = if data_conditions.is_empty() {
-        "1=1".to_string()
-    } else {
-        data_conditions.join(" AND ")
-    };
-
-    let count_where = if count_conditions.is_empty() {
-        "1=1".to_string()
-    } else {
-        count_conditions.join(" AND ")
+    let viewer = match user {
+        Some(Extension(ref u)) if u.can(Permission::ViewPrivateContent) => ContestViewer::Admin,
+        Some(Extension(ref u)) => ContestViewer::User(u.id),
+        None => ContestViewer::Anonymous,
     };
-
-    let sql = format!(
-        r#"
-        SELECT
-            c.id, c.title, c.short_description, c.start_time, c.end_time,
-            c.scoring_type, c.is_public, c.is_rated,
-            u.id as owner_id, u.username as owner_username, u.display_name as owner_display_name,
-            COALESCE(p.participant_count, 0) as participant_count
-        FROM contests c
-        JOIN users u ON c.owner_id = u.id
-        LEFT JOIN (
-            SELECT contest_id, COUNT(*) as participant_count
-            FROM contest_participants
-            GROUP BY contest_id
-        ) p ON c.id = p.contest_id
-        WHERE {data_where}
-        ORDER BY c.start_time DESC
-        LIMIT $1 OFFSET $2
-        "#
-    );
-
-    let count_sql = format!("SELECT COUNT(*) FROM contests c WHERE {count_where}");
-
-    // Build queries with base binds
-    let mut q = sqlx::query_as::<_, ContestListRow>(&sql)
-        .bind(per_page as i64)
-        .bind(offset);
-    let mut cq = sqlx::query_scalar::<_, i64>(&count_sql);
-
-    // Bind optional filter values in the same order as the conditions above
-    if has_status {
-        q = q.bind(now);
-        cq = cq.bind(now);
-    }
-    if let Some(ref search) = query.search {
-        let pattern = format!("%{search}%");
-        q = q.bind(pattern.clone());
-        cq = cq.bind(pattern);
-    }
-    if let Some(owner_id) = query.owner_id {
-        q = q.bind(owner_id);
-        cq = cq.bind(owner_id);
+    let mut filters = query.filters();
+    if let Some(saved_filter) = query.saved_filter {
+        let user = user.as_ref().ok_or(ApiError::Unauthorized)?;
+        filters = filters.or(load_saved_filter(&state, saved_filter, user.id).await?);
     }
 
-    let rows = q
-        .fetch_all(&state.db)
+    let filter = ContestFilter::new(viewer)
+        .public_only(filters.public_only.unwrap_or(true))
+        .status(
+            filters.status.as_deref().and_then(ContestStatus::parse),
+            Utc::now(),
+        )
+        .rated(filters.is_rated)
+        .scoring_type(filters.scoring_type.map(|t| t.to_string()))
+        .search(filters.search)
+        .owner(filters.owner_id)
+        .organization(filters.organization_id)
+        .mirror_of(filters.mirror_of);
+
+    let rows = contest_repo::list(state.read_db(), &filter, per_page as i64, offset)
         .await
         .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
-    let total: i64 = cq
-        .fetch_one(&state.db)
+    let total = contest_repo::count(state.read_db(), &filter)
         .await
         .map_err(|e| ApiError::Internal(format!("Count query error: {}", e)))?;
 
@@ -230,6 +150,8 @@ pub async fn list_contests(
             scoring_type: r.scoring_type,
             is_public: r.is_public,
             is_rated: r.is_rated,
+            organization_id: r.organization_id,
+            mirror_of: r.mirror_of,
             participant_count: r.participant_count,
             owner: OwnerInfo {
                 id: r.owner_id,
@@ -253,22 +175,36 @@ pub async fn list_contests(
     }))
 }
 
+/// Filters of one of `user_id`'s saved contest filters
+async fn load_saved_filter(
+    state: &AppState,
+    filter_id: Uuid,
+    user_id: Uuid,
+) -> ApiResult<ContestListFilters> {
+    let filters: serde_json::Value = sqlx::query_scalar(
+        "SELECT filters FROM saved_contest_filters WHERE id = $1 AND user_id = $2",
+    )
+    .bind(filter_id)
+    .bind(user_id)
+    .fetch_optional(state.read_db())
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Saved filter not found".to_string()))?;
+
+    serde_json::from_value(filters)
+        .map_err(|e| ApiError::Internal(format!("Invalid saved filter {}: {}", filter_id, e)))
+}
+
 /// POST /api/v1/contests
 ///
 /// Create a new contest (organizer/admin only).
 pub async fn create_contest(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
-    Json(payload): Json<CreateContestRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateContestRequest>,
 ) -> ApiResult<(StatusCode, Json<ContestResponse>)> {
-    // Validate request
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Check user role using authorization rules
     let ctx = crate::domain::authorization::build_auth_context(&state, &user);
-    require_organizer(&ctx).await?;
+    require_permission(&ctx, Permission::CreateContests).await?;
 
     // Validate times
     if payload.end_time <= payload.start_time {
@@ -285,8 +221,14 @@ pub async fn create_contest(
         }
     }
 
+    if let Some(organization_id) = payload.organization_id {
+        require_organization_manager(&state.db, organization_id, &user).await?;
+    }
+
     let id = Uuid::new_v4();
     let now = Utc::now();
+    let submission_cooldown_secs = payload.submission_cooldown_secs.unwrap_or(0);
+    let source_visibility = payload.source_visibility.to_string();
 
     // Insert contest
     sqlx::query(
@@ -294,8 +236,14 @@ pub async fn create_contest(
         INSERT INTO contests (
             id, title, description, short_description, start_time, end_time, freeze_time,
             scoring_type, is_public, is_rated, registration_required, max_participants,
-            allowed_languages, owner_id, created_at, updated_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $15)
+            allowed_languages, submission_cooldown_secs, submissions_per_hour,
+            max_submissions_per_problem, source_visibility, is_proctored, test_data_downloads,
+            scoreboard_feed, organization_id, owner_id, created_at, updated_at,
+            dynamic_min_percent, dynamic_decay
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19,
+            $20, $21, $22, $23, $23, $24, $25
+        )
         "#,
     )
     .bind(id)
@@ -311,8 +259,18 @@ pub async fn create_contest(
     .bind(payload.registration_required)
     .bind(payload.max_participants)
     .bind(&payload.allowed_languages)
+    .bind(submission_cooldown_secs)
+    .bind(payload.submissions_per_hour)
+    .bind(payload.max_submissions_per_problem)
+    .bind(&source_visibility)
+    .bind(payload.is_proctored)
+    .bind(payload.test_data_downloads)
+    .bind(payload.scoreboard_feed)
+    .bind(payload.organization_id)
     .bind(user.id)
     .bind(now)
+    .bind(payload.dynamic_min_percent)
+    .bind(payload.dynamic_decay)
     .execute(&state.db)
     .await
     .map_err(|e| ApiError::Internal(format!("Failed to create contest: {}", e)))?;
@@ -333,6 +291,17 @@ pub async fn create_contest(
             registration_required: payload.registration_required,
             max_participants: payload.max_participants,
             allowed_languages: payload.allowed_languages,
+            submission_cooldown_secs,
+            submissions_per_hour: payload.submissions_per_hour,
+            max_submissions_per_problem: payload.max_submissions_per_problem,
+            source_visibility,
+            is_proctored: payload.is_proctored,
+            test_data_downloads: payload.test_data_downloads,
+            scoreboard_feed: payload.scoreboard_feed,
+            dynamic_min_percent: payload.dynamic_min_percent,
+            dynamic_decay: payload.dynamic_decay,
+            organization_id: payload.organization_id,
+            mirror_of: None,
             owner_id: user.id,
             created_at: now,
             updated_at: now,
@@ -348,43 +317,8 @@ pub async fn get_contest(
     user: Option<Extension<AuthUser>>,
     Path(contest_id): Path<Uuid>,
 ) -> ApiResult<Json<ContestDetailResponse>> {
-    // Fetch contest with owner info
-    let contest: Option<ContestRow> = sqlx::query_as(
-        r#"
-        SELECT id, title, description, short_description, start_time, end_time, freeze_time,
-               scoring_type, is_public, is_rated, registration_required, max_participants,
-               allowed_languages, owner_id, created_at, updated_at
-        FROM contests WHERE id = $1
-        "#,
-    )
-    .bind(contest_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
-
-    // Check visibility
+    let contest = load_visible_contest(&state, contest_id, user.as_ref().map(|u| &u.0)).await?;
     let user_id = user.as_ref().map(|u| u.id);
-    if !contest.is_public && user_id != Some(contest.owner_id) {
-        // Check if user is a collaborator
-        if let Some(uid) = user_id {
-            let is_collaborator: Option<(i64,)> = sqlx::query_as(
-                "SELECT 1 FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2",
-            )
-            .bind(contest_id)
-            .bind(uid)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-            if is_collaborator.is_none() {
-                return Err(ApiError::NotFound("Contest not found".to_string()));
-            }
-        } else {
-            return Err(ApiError::NotFound("Contest not found".to_string()));
-        }
-    }
 
     // Get owner info
     let owner: (Uuid, String, Option<String>) =
@@ -436,6 +370,13 @@ pub async fn get_contest(
 
     let is_owner = user_id == Some(contest.owner_id);
 
+    let paused_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT paused_at FROM contest_pauses WHERE contest_id = $1 AND resumed_at IS NULL",
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await?;
+
     Ok(Json(ContestDetailResponse {
         id: contest.id,
         title: contest.title,
@@ -450,6 +391,18 @@ pub async fn get_contest(
         registration_required: contest.registration_required,
         max_participants: contest.max_participants,
         allowed_languages: contest.allowed_languages,
+        submission_cooldown_secs: contest.submission_cooldown_secs,
+        submissions_per_hour: contest.submissions_per_hour,
+        max_submissions_per_problem: contest.max_submissions_per_problem,
+        source_visibility: contest.source_visibility,
+        is_proctored: contest.is_proctored,
+        test_data_downloads: contest.test_data_downloads,
+        scoreboard_feed: contest.scoreboard_feed,
+        dynamic_min_percent: contest.dynamic_min_percent,
+        dynamic_decay: contest.dynamic_decay,
+        organization_id: contest.organization_id,
+        mirror_of: contest.mirror_of,
+        paused_at,
         owner: OwnerInfo {
             id: owner.0,
             username: owner.1,
@@ -471,26 +424,14 @@ pub async fn get_contest(
 /// Update contest (owner or collaborator with edit permission).
 pub async fn update_contest(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    Guard { user, .. }: Guard<policies::EditContest>,
     Path(contest_id): Path<Uuid>,
-    Json(payload): Json<UpdateContestRequest>,
+    ValidatedJson(payload): ValidatedJson<UpdateContestRequest>,
 ) -> ApiResult<Json<ContestResponse>> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
-    // Check contest exists
-    let contest: Option<ContestRow> = sqlx::query_as("SELECT * FROM contests WHERE id = $1")
+    let contest: ContestRow = sqlx::query_as("SELECT * FROM contests WHERE id = $1")
         .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
-
-    // Check permission using authorization rules
-    let ctx = build_contest_context(&state, &user, contest_id);
-    require_contest_modify_access(&ctx).await?;
+        .fetch_one(&state.db)
+        .await?;
 
     // Build update
     let title = payload.title.unwrap_or(contest.title);
@@ -505,11 +446,72 @@ pub async fn update_contest(
         .unwrap_or(contest.scoring_type);
     let is_public = payload.is_public.unwrap_or(contest.is_public);
     let is_rated = payload.is_rated.unwrap_or(contest.is_rated);
+    if is_rated && contest.mirror_of.is_some() {
+        return Err(ApiError::Validation(
+            "Mirror contests cannot be rated".to_string(),
+        ));
+    }
     let registration_required = payload
         .registration_required
         .unwrap_or(contest.registration_required);
     let max_participants = payload.max_participants.or(contest.max_participants);
     let allowed_languages = payload.allowed_languages.or(contest.allowed_languages);
+    let submission_cooldown_secs = payload
+        .submission_cooldown_secs
+        .unwrap_or(contest.submission_cooldown_secs);
+    let submissions_per_hour = payload
+        .submissions_per_hour
+        .unwrap_or(contest.submissions_per_hour);
+    let max_submissions_per_problem = payload
+        .max_submissions_per_problem
+        .unwrap_or(contest.max_submissions_per_problem);
+    let source_visibility = payload
+        .source_visibility
+        .map(|v| v.to_string())
+        .unwrap_or(contest.source_visibility);
+    let is_proctored = payload.is_proctored.unwrap_or(contest.is_proctored);
+    let test_data_downloads = payload
+        .test_data_downloads
+        .unwrap_or(contest.test_data_downloads);
+    let scoreboard_feed = payload.scoreboard_feed.unwrap_or(contest.scoreboard_feed);
+    let dynamic_min_percent = payload
+        .dynamic_min_percent
+        .unwrap_or(contest.dynamic_min_percent);
+    let dynamic_decay = payload.dynamic_decay.unwrap_or(contest.dynamic_decay);
+
+    // Moving a contest into an organization, or out of one, takes a manager
+    // of that organization. Problems of an organization cannot leave its
+    // contests with it.
+    let organization_id = payload.organization_id.unwrap_or(contest.organization_id);
+    if organization_id != contest.organization_id {
+        if let Some(organization_id) = organization_id {
+            require_organization_manager(&state.db, organization_id, &user).await?;
+        }
+        if let Some(previous) = contest.organization_id {
+            require_organization_manager(&state.db, previous, &user).await?;
+        }
+
+        let foreign_problems: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM contest_problems cp
+                JOIN problems p ON p.id = cp.problem_id
+                WHERE cp.contest_id = $1
+                  AND p.organization_id IS NOT NULL
+                  AND p.organization_id IS DISTINCT FROM $2
+            )
+            "#,
+        )
+        .bind(contest_id)
+        .bind(organization_id)
+        .fetch_one(&state.db)
+        .await?;
+        if foreign_problems {
+            return Err(ApiError::Conflict(
+                "Contest has problems of another organization; remove them first".to_string(),
+            ));
+        }
+    }
 
     // Validate times
     if end_time <= start_time {
@@ -527,7 +529,10 @@ pub async fn update_contest(
             start_time = $5, end_time = $6, freeze_time = $7,
             scoring_type = $8, is_public = $9, is_rated = $10,
             registration_required = $11, max_participants = $12,
-            allowed_languages = $13, updated_at = $14
+            allowed_languages = $13, submission_cooldown_secs = $14, source_visibility = $15,
+            is_proctored = $16, test_data_downloads = $17, organization_id = $18,
+            updated_at = $19, submissions_per_hour = $20, max_submissions_per_problem = $21,
+            scoreboard_feed = $22, dynamic_min_percent = $23, dynamic_decay = $24
         WHERE id = $1
         "#,
     )
@@ -544,11 +549,30 @@ pub async fn update_contest(
     .bind(registration_required)
     .bind(max_participants)
     .bind(&allowed_languages)
+    .bind(submission_cooldown_secs)
+    .bind(&source_visibility)
+    .bind(is_proctored)
+    .bind(test_data_downloads)
+    .bind(organization_id)
     .bind(now)
+    .bind(submissions_per_hour)
+    .bind(max_submissions_per_problem)
+    .bind(scoreboard_feed)
+    .bind(dynamic_min_percent)
+    .bind(dynamic_decay)
     .execute(&state.db)
     .await
     .map_err(|e| ApiError::Internal(format!("Failed to update contest: {}", e)))?;
 
+    // A higher cap frees seats for waitlisted users
+    if max_participants != contest.max_participants && now < end_time {
+        let mut tx = state.db.begin().await?;
+        let (max_participants,) = lock_contest_seats(&mut tx, contest_id).await?;
+        let promoted = promote_waitlisted(&mut tx, contest_id, max_participants).await?;
+        notify_promoted(&mut tx, contest_id, &promoted).await?;
+        tx.commit().await?;
+    }
+
     Ok(Json(ContestResponse {
         id: contest_id,
         title,
@@ -563,41 +587,279 @@ pub async fn update_contest(
         registration_required,
         max_participants,
         allowed_languages,
+        submission_cooldown_secs,
+        submissions_per_hour,
+        max_submissions_per_problem,
+        source_visibility,
+        is_proctored,
+        test_data_downloads,
+        scoreboard_feed,
+        dynamic_min_percent,
+        dynamic_decay,
+        organization_id,
+        mirror_of: contest.mirror_of,
         owner_id: contest.owner_id,
         created_at: contest.created_at,
         updated_at: now,
     }))
 }
 
-/// DELETE /api/v1/contests/{id}
+/// The contest, if `user` may see it; a contest they may not see is reported
+/// as not found
+pub(crate) async fn load_visible_contest(
+    state: &AppState,
+    contest_id: Uuid,
+    user: Option<&AuthUser>,
+) -> ApiResult<ContestRow> {
+    let contest: Option<ContestRow> = sqlx::query_as(
+        r#"
+        SELECT id, title, description, short_description, start_time, end_time, freeze_time,
+               scoring_type, is_public, is_rated, registration_required, max_participants,
+               allowed_languages, submission_cooldown_secs, submissions_per_hour,
+               max_submissions_per_problem, source_visibility, is_proctored, test_data_downloads,
+               scoreboard_feed, dynamic_min_percent, dynamic_decay, organization_id, mirror_of,
+               owner_id, created_at, updated_at
+        FROM contests WHERE id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+
+    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
+    if !contest.is_public && !can_view_private_contest(state, &contest, user).await? {
+        return Err(ApiError::NotFound("Contest not found".to_string()));
+    }
+    Ok(contest)
+}
+
+/// Whether `user` is staff of the contest: its owner, a moderator or a
+/// collaborator
+pub(crate) async fn is_contest_staff(
+    state: &AppState,
+    user: Option<&AuthUser>,
+    contest_id: Uuid,
+) -> bool {
+    match user {
+        Some(user) => {
+            let ctx = build_contest_context(state, user, contest_id);
+            policies::ContestStaff::rule().is_satisfied_by(&ctx).await
+        }
+        None => false,
+    }
+}
+
+/// Whether `user` may see a contest that is not public: admins, the owner,
+/// collaborators and members of the hosting organization
+async fn can_view_private_contest(
+    state: &AppState,
+    contest: &ContestRow,
+    user: Option<&AuthUser>,
+) -> ApiResult<bool> {
+    let Some(user) = user else {
+        return Ok(false);
+    };
+    if user.can(Permission::ViewPrivateContent) || user.id == contest.owner_id {
+        return Ok(true);
+    }
+
+    let is_collaborator: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2)",
+    )
+    .bind(contest.id)
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await?;
+    if is_collaborator {
+        return Ok(true);
+    }
+
+    Ok(match contest.organization_id {
+        Some(org_id) => member_role(&state.db, org_id, user.id).await?.is_some(),
+        None => false,
+    })
+}
+
+/// POST /api/v1/contests/{id}/mirror
 ///
-/// Delete contest (owner or admin only).
-pub async fn delete_contest(
+/// Mirror a past contest for a training group (organizer/admin who can see
+/// the original). The mirror gets the original's problems, settings and
+/// duration from `start_time`, with a fresh leaderboard; it is never rated
+/// and has no scoreboard feed. It links back to the original contest, or to
+/// the original's original when mirroring a mirror.
+pub async fn create_mirror_contest(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
-    Path(contest_id): Path<Uuid>,
-) -> ApiResult<StatusCode> {
-    // Check contest exists
-    let contest: Option<(Uuid,)> = sqlx::query_as("SELECT owner_id FROM contests WHERE id = $1")
-        .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+    Path(source_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreateMirrorRequest>,
+) -> ApiResult<(StatusCode, Json<ContestResponse>)> {
+    let ctx = crate::domain::authorization::build_auth_context(&state, &user);
+    require_permission(&ctx, Permission::CreateContests).await?;
 
-    let _contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
+    let source: ContestRow = sqlx::query_as("SELECT * FROM contests WHERE id = $1")
+        .bind(source_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
+    if !source.is_public && !can_view_private_contest(&state, &source, Some(&user)).await? {
+        return Err(ApiError::NotFound("Contest not found".to_string()));
+    }
+    // Mirroring a mirror links the new one to the original, which the
+    // caller must be able to see as well
+    let original_is_public = match source.mirror_of {
+        Some(original_id) => {
+            load_visible_contest(&state, original_id, Some(&user))
+                .await?
+                .is_public
+        }
+        None => true,
+    };
+    if let Some(message) =
+        mirror_visibility_error(source.is_public && original_is_public, payload.is_public)
+    {
+        return Err(ApiError::Validation(message.to_string()));
+    }
 
-    // Check permission using authorization rules (must be owner or admin)
-    let ctx = build_contest_context(&state, &user, contest_id);
-    // Only owner or admin can delete
-    use crate::domain::authorization::require_contest_owner;
-    use olympus_rules::auth_rules::IsAdmin;
-    use olympus_rules::specification::Specification;
+    let now = Utc::now();
+    if source.end_time > now {
+        return Err(ApiError::Validation(
+            "Only contests that have ended can be mirrored".to_string(),
+        ));
+    }
+    if payload.start_time + (source.end_time - source.start_time) <= now {
+        return Err(ApiError::Validation(
+            "The mirror would already have ended".to_string(),
+        ));
+    }
 
-    let is_admin = IsAdmin.is_satisfied_by(&ctx).await;
-    if !is_admin {
-        require_contest_owner(&ctx).await?;
+    if let Some(organization_id) = payload.organization_id {
+        require_organization_manager(&state.db, organization_id, &user).await?;
     }
 
+    let id = Uuid::new_v4();
+    let mut tx = state.db.begin().await?;
+
+    // Times move with the start; everything else but the listed overrides
+    // is the original's
+    let mirror: ContestRow = sqlx::query_as(
+        r#"
+        INSERT INTO contests (
+            id, title, description, short_description, start_time, end_time, freeze_time,
+            scoring_type, is_public, is_rated, registration_required, max_participants,
+            allowed_languages, submission_cooldown_secs, submissions_per_hour,
+            max_submissions_per_problem, source_visibility, is_proctored, test_data_downloads,
+            scoreboard_feed, organization_id, owner_id, created_at, updated_at,
+            dynamic_min_percent, dynamic_decay, mirror_of
+        )
+        SELECT $1, COALESCE($2, LEFT(title, 246) || ' (mirror)'), description, short_description,
+               $3, $3 + (end_time - start_time), freeze_time + ($3 - start_time),
+               scoring_type, $4, false, $5, $6,
+               allowed_languages, submission_cooldown_secs, submissions_per_hour,
+               max_submissions_per_problem, source_visibility, is_proctored, test_data_downloads,
+               false, $7, $8, $9, $9,
+               dynamic_min_percent, dynamic_decay, COALESCE(mirror_of, id)
+        FROM contests WHERE id = $10
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&payload.title)
+    .bind(payload.start_time)
+    .bind(payload.is_public)
+    .bind(payload.registration_required)
+    .bind(payload.max_participants)
+    .bind(payload.organization_id)
+    .bind(user.id)
+    .bind(now)
+    .bind(source_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let problems = sqlx::query(
+        r#"
+        INSERT INTO contest_problems (
+            contest_id, problem_id, problem_code, sort_order, max_score, time_limit_ms,
+            memory_limit_kb, max_threads, network_allowed, allowed_languages, visible_from,
+            added_by
+        )
+        SELECT $1, problem_id, problem_code, sort_order, max_score, time_limit_ms,
+               memory_limit_kb, max_threads, network_allowed, allowed_languages,
+               visible_from + ($2 - $3::timestamptz), $4
+        FROM contest_problems WHERE contest_id = $5
+        "#,
+    )
+    .bind(id)
+    .bind(payload.start_time)
+    .bind(source.start_time)
+    .bind(user.id)
+    .bind(source_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+
+    tracing::info!(
+        contest_id = %id,
+        mirror_of = ?mirror.mirror_of,
+        user_id = %user.id,
+        problems,
+        "Mirror contest created"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ContestResponse {
+            id: mirror.id,
+            title: mirror.title,
+            description: mirror.description,
+            short_description: mirror.short_description,
+            start_time: mirror.start_time,
+            end_time: mirror.end_time,
+            freeze_time: mirror.freeze_time,
+            scoring_type: mirror.scoring_type,
+            is_public: mirror.is_public,
+            is_rated: mirror.is_rated,
+            registration_required: mirror.registration_required,
+            max_participants: mirror.max_participants,
+            allowed_languages: mirror.allowed_languages,
+            submission_cooldown_secs: mirror.submission_cooldown_secs,
+            submissions_per_hour: mirror.submissions_per_hour,
+            max_submissions_per_problem: mirror.max_submissions_per_problem,
+            source_visibility: mirror.source_visibility,
+            is_proctored: mirror.is_proctored,
+            test_data_downloads: mirror.test_data_downloads,
+            scoreboard_feed: mirror.scoreboard_feed,
+            dynamic_min_percent: mirror.dynamic_min_percent,
+            dynamic_decay: mirror.dynamic_decay,
+            organization_id: mirror.organization_id,
+            mirror_of: mirror.mirror_of,
+            owner_id: mirror.owner_id,
+            created_at: mirror.created_at,
+            updated_at: mirror.updated_at,
+        }),
+    ))
+}
+
+/// A mirror of a private contest must stay private, or it would publish the
+/// original's problems
+fn mirror_visibility_error(
+    original_is_public: bool,
+    mirror_is_public: bool,
+) -> Option<&'static str> {
+    (mirror_is_public && !original_is_public)
+        .then_some("A private contest can only be mirrored as a private contest")
+}
+
+/// DELETE /api/v1/contests/{id}
+///
+/// Delete contest (owner or admin only).
+pub async fn delete_contest(
+    State(state): State<AppState>,
+    _guard: Guard<policies::ContestOwner>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
     sqlx::query("DELETE FROM contests WHERE id = $1")
         .bind(contest_id)
         .execute(&state.db)
@@ -613,15 +875,17 @@ pub async fn delete_contest(
 
 /// POST /api/v1/contests/{id}/register
 ///
-/// Register for a contest.
+/// Register for a contest. A full contest puts the caller on its waitlist
+/// and answers 202 with their position.
 pub async fn register_for_contest(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
     Path(contest_id): Path<Uuid>,
 ) -> ApiResult<(StatusCode, Json<RegistrationResponse>)> {
     // Check contest exists and is open for registration
-    let contest: Option<(DateTime<Utc>, DateTime<Utc>, bool, Option<i32>)> = sqlx::query_as(
-        "SELECT start_time, end_time, registration_required, max_participants FROM contests WHERE id = $1"
+    #[allow(clippy::type_complexity)]
+    let contest: Option<(DateTime<Utc>, DateTime<Utc>, bool, Option<i32>, bool, Option<Uuid>)> = sqlx::query_as(
+        "SELECT start_time, end_time, registration_required, max_participants, is_public, organization_id FROM contests WHERE id = $1"
     )
         .bind(contest_id)
         .fetch_optional(&state.db)
@@ -630,6 +894,15 @@ pub async fn register_for_contest(
 
     let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
 
+    // Members-only organization contests
+    if let (false, Some(org_id)) = (contest.4, contest.5) {
+        if !user.can(Permission::ViewPrivateContent)
+            && member_role(&state.db, org_id, user.id).await?.is_none()
+        {
+            return Err(ApiError::NotFound("Contest not found".to_string()));
+        }
+    }
+
     let now = Utc::now();
 
     // Check if contest has ended
@@ -639,12 +912,15 @@ pub async fn register_for_contest(
         ));
     }
 
-    // Check if already registered
+    let mut tx = state.db.begin().await?;
+    let (max_participants,) = lock_contest_seats(&mut tx, contest_id).await?;
+
+    // Check if already registered or waitlisted
     let existing: Option<(i64,)> =
         sqlx::query_as("SELECT 1 FROM contest_participants WHERE contest_id = $1 AND user_id = $2")
             .bind(contest_id)
             .bind(user.id)
-            .fetch_optional(&state.db)
+            .fetch_optional(&mut *tx)
             .await
             .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
@@ -654,17 +930,48 @@ pub async fn register_for_contest(
         ));
     }
 
-    // Check max participants
-    if let Some(max) = contest.3 {
+    if waitlist_position(&mut tx, contest_id, user.id)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Validation(
+            "Already on the waitlist for this contest".to_string(),
+        ));
+    }
+
+    // Full contests put the user on the waitlist instead
+    if let Some(max) = max_participants {
         let count: (i64,) =
             sqlx::query_as("SELECT COUNT(*) FROM contest_participants WHERE contest_id = $1")
                 .bind(contest_id)
-                .fetch_one(&state.db)
+                .fetch_one(&mut *tx)
                 .await
                 .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
         if count.0 >= max as i64 {
-            return Err(ApiError::Validation("Contest is full".to_string()));
+            sqlx::query(
+                "INSERT INTO contest_waitlist (contest_id, user_id, joined_at) VALUES ($1, $2, $3)",
+            )
+            .bind(contest_id)
+            .bind(user.id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to join waitlist: {}", e)))?;
+
+            let position = waitlist_position(&mut tx, contest_id, user.id).await?;
+            tx.commit().await?;
+
+            return Ok((
+                StatusCode::ACCEPTED,
+                Json(RegistrationResponse {
+                    message: "Contest is full; added to the waitlist".to_string(),
+                    contest_id,
+                    status: "waitlisted".to_string(),
+                    registered_at: now,
+                    waitlist_position: position,
+                }),
+            ));
         }
     }
 
@@ -678,34 +985,57 @@ pub async fn register_for_contest(
     .bind(contest_id)
     .bind(user.id)
     .bind(now)
-    .execute(&state.db)
+    .execute(&mut *tx)
     .await
     .map_err(|e| ApiError::Internal(format!("Failed to register: {}", e)))?;
 
+    tx.commit().await?;
+
     Ok((
         StatusCode::CREATED,
         Json(RegistrationResponse {
             message: "Successfully registered for contest".to_string(),
             contest_id,
+            status: "registered".to_string(),
             registered_at: now,
+            waitlist_position: None,
         }),
     ))
 }
 
 /// POST /api/v1/contests/{id}/unregister
 ///
-/// Unregister from a contest.
+/// Unregister from a contest, or leave its waitlist. A freed seat goes to
+/// the first waitlisted user.
 pub async fn unregister_from_contest(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
     Path(contest_id): Path<Uuid>,
 ) -> ApiResult<Json<MessageResponse>> {
+    let mut tx = state.db.begin().await?;
+    let (max_participants,) = lock_contest_seats(&mut tx, contest_id).await?;
+
+    // Leaving the waitlist is allowed at any time
+    let left_waitlist =
+        sqlx::query("DELETE FROM contest_waitlist WHERE contest_id = $1 AND user_id = $2")
+            .bind(contest_id)
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+
+    if left_waitlist.rows_affected() > 0 {
+        tx.commit().await?;
+        return Ok(Json(MessageResponse {
+            message: "Left the contest waitlist".to_string(),
+        }));
+    }
+
     // Check if registered
     let existing: Option<(i64,)> =
         sqlx::query_as("SELECT 1 FROM contest_participants WHERE contest_id = $1 AND user_id = $2")
             .bind(contest_id)
             .bind(user.id)
-            .fetch_optional(&state.db)
+            .fetch_optional(&mut *tx)
             .await
             .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
@@ -716,14 +1046,11 @@ pub async fn unregister_from_contest(
     }
 
     // Check if contest has started (cannot unregister after start)
-    let contest: Option<(DateTime<Utc>,)> =
-        sqlx::query_as("SELECT start_time FROM contests WHERE id = $1")
-            .bind(contest_id)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
+    let contest: (DateTime<Utc>,) = sqlx::query_as("SELECT start_time FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
     if Utc::now() >= contest.0 {
         return Err(ApiError::Validation(
@@ -734,31 +1061,214 @@ pub async fn unregister_from_contest(
     sqlx::query("DELETE FROM contest_participants WHERE contest_id = $1 AND user_id = $2")
         .bind(contest_id)
         .bind(user.id)
-        .execute(&state.db)
+        .execute(&mut *tx)
         .await
         .map_err(|e| ApiError::Internal(format!("Failed to unregister: {}", e)))?;
 
+    let promoted = promote_waitlisted(&mut tx, contest_id, max_participants).await?;
+    notify_promoted(&mut tx, contest_id, &promoted).await?;
+    tx.commit().await?;
+
     Ok(Json(MessageResponse {
         message: "Successfully unregistered from contest".to_string(),
     }))
 }
 
-/// GET /api/v1/contests/{id}/participants
+/// GET /api/v1/contests/{id}/waitlist
 ///
-/// List contest participants.
-pub async fn list_participants(
+/// List the waitlist in promotion order (owner, admin, or collaborator with
+/// `can_manage_participants`).
+pub async fn list_waitlist(
     State(state): State<AppState>,
+    _guard: Guard<policies::ManageParticipants>,
     Path(contest_id): Path<Uuid>,
-    Query(query): Query<ListParticipantsQuery>,
-) -> ApiResult<Json<ParticipantListResponse>> {
-    // Check contest exists
-    let exists: Option<(i64,)> = sqlx::query_as("SELECT 1 FROM contests WHERE id = $1")
-        .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    if exists.is_none() {
+    Query(query): Query<ListWaitlistQuery>,
+) -> ApiResult<Json<WaitlistResponse>> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+
+    let rows: Vec<(Uuid, String, Option<String>, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        SELECT u.id, u.username, u.display_name, w.joined_at
+        FROM contest_waitlist w
+        JOIN users u ON w.user_id = u.id
+        WHERE w.contest_id = $1
+        ORDER BY w.joined_at, w.user_id
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(contest_id)
+    .bind(per_page as i64)
+    .bind(offset)
+    .fetch_all(state.read_db())
+    .await?;
+
+    let total: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM contest_waitlist WHERE contest_id = $1")
+            .bind(contest_id)
+            .fetch_one(state.read_db())
+            .await?;
+
+    let entries = rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| WaitlistEntry {
+            position: offset + i as i64 + 1,
+            user: OwnerInfo {
+                id: row.0,
+                username: row.1,
+                display_name: row.2,
+            },
+            joined_at: row.3,
+        })
+        .collect();
+
+    let total_pages = ((total.0 as f64) / (per_page as f64)).ceil() as u32;
+
+    Ok(Json(WaitlistResponse {
+        entries,
+        pagination: Pagination {
+            page,
+            per_page,
+            total: total.0,
+            total_pages,
+        },
+    }))
+}
+
+/// Lock the contest row so seat counts stay accurate until commit, returning
+/// its `max_participants`
+async fn lock_contest_seats(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contest_id: Uuid,
+) -> ApiResult<(Option<i32>,)> {
+    sqlx::query_as("SELECT max_participants FROM contests WHERE id = $1 FOR UPDATE")
+        .bind(contest_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or(ApiError::NotFound("Contest not found".to_string()))
+}
+
+/// 1-based waitlist position of `user_id`, if waitlisted
+async fn waitlist_position(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contest_id: Uuid,
+    user_id: Uuid,
+) -> ApiResult<Option<i64>> {
+    let position = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM contest_waitlist me
+        JOIN contest_waitlist w ON w.contest_id = me.contest_id
+            AND (w.joined_at, w.user_id) <= (me.joined_at, me.user_id)
+        WHERE me.contest_id = $1 AND me.user_id = $2
+        GROUP BY me.user_id
+        "#,
+    )
+    .bind(contest_id)
+    .bind(user_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(position)
+}
+
+/// Move waitlisted users into free seats, first come first served. Must run
+/// after [`lock_contest_seats`] in the same transaction. Nobody is promoted
+/// once the contest has ended.
+async fn promote_waitlisted(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contest_id: Uuid,
+    max_participants: Option<i32>,
+) -> ApiResult<Vec<Uuid>> {
+    let promoted = sqlx::query_scalar(
+        r#"
+        WITH seats AS (
+            SELECT CASE WHEN c.end_time <= NOW() THEN 0
+                        WHEN $2::int IS NULL THEN NULL
+                        ELSE GREATEST($2 - (SELECT COUNT(*) FROM contest_participants cp
+                                            WHERE cp.contest_id = c.id), 0)
+                   END AS free
+            FROM contests c
+            WHERE c.id = $1
+        ),
+        next AS (
+            DELETE FROM contest_waitlist
+            WHERE contest_id = $1 AND user_id IN (
+                SELECT user_id FROM contest_waitlist
+                WHERE contest_id = $1
+                ORDER BY joined_at, user_id
+                LIMIT (SELECT free FROM seats)
+            )
+            RETURNING user_id
+        )
+        INSERT INTO contest_participants (contest_id, user_id, registered_at, status)
+        SELECT $1, user_id, NOW(), 'registered' FROM next
+        ON CONFLICT (contest_id, user_id) DO NOTHING
+        RETURNING user_id
+        "#,
+    )
+    .bind(contest_id)
+    .bind(max_participants)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(promoted)
+}
+
+/// Tell promoted users they got a seat, in the transaction that promoted
+/// them
+async fn notify_promoted(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contest_id: Uuid,
+    user_ids: &[Uuid],
+) -> ApiResult<()> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    for user_id in user_ids {
+        tracing::info!(contest_id = %contest_id, user_id = %user_id, "Promoted from waitlist");
+    }
+
+    sqlx::query(
+        r#"
+        SELECT notify_user(
+            u.user_id, $3,
+            'You got a seat in ' || c.title,
+            'A seat opened up and you are now registered for this contest.',
+            c.id, NULL
+        )
+        FROM unnest($2::uuid[]) AS u(user_id)
+        JOIN contests c ON c.id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .bind(user_ids)
+    .bind(NotificationKind::WaitlistPromoted.as_str())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// GET /api/v1/contests/{id}/participants
+///
+/// List contest participants.
+pub async fn list_participants(
+    State(state): State<AppState>,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<ListParticipantsQuery>,
+) -> ApiResult<Json<ParticipantListResponse>> {
+    // Check contest exists
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT 1 FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_optional(state.read_db())
+        .await
+        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+
+    if exists.is_none() {
         return Err(ApiError::NotFound("Contest not found".to_string()));
     }
 
@@ -793,6 +1303,7 @@ pub async fn list_participants(
         order_by, order
     );
 
+    #[allow(clippy::type_complexity)]
     let rows: Vec<(
         Uuid,
         Uuid,
@@ -808,14 +1319,14 @@ pub async fn list_participants(
         .bind(contest_id)
         .bind(per_page as i64)
         .bind(offset)
-        .fetch_all(&state.db)
+        .fetch_all(state.read_db())
         .await
         .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
     let total: (i64,) =
         sqlx::query_as("SELECT COUNT(*) FROM contest_participants WHERE contest_id = $1")
             .bind(contest_id)
-            .fetch_one(&state.db)
+            .fetch_one(state.read_db())
             .await
             .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
@@ -850,83 +1361,502 @@ pub async fn list_participants(
     }))
 }
 
+/// DELETE /api/v1/contests/{id}/participants/{user_id}
+///
+/// Remove a participant (owner, admin, or collaborator with
+/// `can_manage_participants`). The freed seat goes to the first waitlisted
+/// user.
+pub async fn remove_participant(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ManageParticipants>,
+    Path((contest_id, target_user_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    let mut tx = state.db.begin().await?;
+    let (max_participants,) = lock_contest_seats(&mut tx, contest_id).await?;
+
+    let result =
+        sqlx::query("DELETE FROM contest_participants WHERE contest_id = $1 AND user_id = $2")
+            .bind(contest_id)
+            .bind(target_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Participant not found".to_string()));
+    }
+
+    let promoted = promote_waitlisted(&mut tx, contest_id, max_participants).await?;
+    notify_promoted(&mut tx, contest_id, &promoted).await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        participant_id = %target_user_id,
+        removed_by = %user.id,
+        "Removed contest participant"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// A participant of the previous round with their final standing
+#[derive(Debug, FromRow)]
+struct SourceStandingRow {
+    user_id: Uuid,
+    username: String,
+    display_name: Option<String>,
+    rank: Option<i64>,
+    total_score: f64,
+    problems_solved: i64,
+}
+
+/// POST /api/v1/contests/{id}/carry-over
+///
+/// Register the participants of a previous round of a series who meet the
+/// advancement rules (owner, admin, or collaborator with
+/// `can_manage_participants` on both contests). Advancing participants are
+/// registered best rank first; once the contest is full the rest join the
+/// waitlist in the same order. Disqualified participants never advance.
+pub async fn carry_over_participants(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ManageParticipants>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CarryOverRequest>,
+) -> ApiResult<Json<CarryOverResponse>> {
+    let source_id = payload.source_contest_id;
+    if source_id == contest_id {
+        return Err(ApiError::Validation(
+            "A contest cannot carry over its own participants".to_string(),
+        ));
+    }
+    payload.rules.validate().map_err(ApiError::Validation)?;
+
+    let source_end: DateTime<Utc> =
+        sqlx::query_scalar("SELECT end_time FROM contests WHERE id = $1")
+            .bind(source_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Source contest not found".to_string()))?;
+    if !has_contest_permission(
+        &build_contest_context(&state, &user, source_id),
+        CollaboratorPermission::ManageParticipants,
+    )
+    .await
+    {
+        return Err(ApiError::Forbidden);
+    }
+
+    let now = Utc::now();
+    if source_end > now {
+        return Err(ApiError::Validation(
+            "The source contest has not ended yet".to_string(),
+        ));
+    }
+
+    // Ranked exactly like the source's leaderboard (penalty breaks score
+    // ties), so `top` admits the participants the final standings show
+    let standings = sqlx::query_as::<_, SourceStandingRow>(concat!(
+        standings_ctes!(),
+        r#",
+        ranked AS (
+            SELECT ut.user_id, ut.total_score, ut.problems_solved,
+                   RANK() OVER (ORDER BY "#,
+        standings_order!(),
+        r#") as rank
+            FROM user_totals ut
+        )
+        SELECT
+            cp.user_id,
+            u.username,
+            u.display_name,
+            r.rank,
+            COALESCE(r.total_score, 0)::double precision as total_score,
+            COALESCE(r.problems_solved, 0)::bigint as problems_solved
+        FROM contest_participants cp
+        JOIN users u ON u.id = cp.user_id
+        LEFT JOIN ranked r ON r.user_id = cp.user_id
+        WHERE cp.contest_id = $1 AND cp.status <> 'disqualified'
+        ORDER BY r.rank ASC NULLS LAST, cp.registered_at
+        "#
+    ))
+    .bind(source_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let evaluated = standings.len();
+    let advancing: Vec<(SourceStandingRow, Standing)> = standings
+        .into_iter()
+        .map(|row| {
+            let standing = Standing {
+                rank: row.rank.map(|r| r as u32),
+                total_score: row.total_score,
+                problems_solved: row.problems_solved,
+            };
+            (row, standing)
+        })
+        .filter(|(_, standing)| payload.rules.admits(standing))
+        .collect();
+
+    let mut tx = state.db.begin().await?;
+    let (max_participants,) = lock_contest_seats(&mut tx, contest_id).await?;
+    let end_time: DateTime<Utc> = sqlx::query_scalar("SELECT end_time FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if end_time <= now {
+        return Err(ApiError::Validation(
+            "Contest has already ended".to_string(),
+        ));
+    }
+
+    let mut seats_taken: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM contest_participants WHERE contest_id = $1")
+            .bind(contest_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    let mut participants = Vec::with_capacity(advancing.len());
+    let (mut registered, mut waitlisted) = (0, 0);
+    for (row, standing) in advancing {
+        let status = if sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM contest_participants WHERE contest_id = $1 AND user_id = $2)",
+        )
+        .bind(contest_id)
+        .bind(row.user_id)
+        .fetch_one(&mut *tx)
+        .await?
+        {
+            "already_registered"
+        } else if waitlist_position(&mut tx, contest_id, row.user_id)
+            .await?
+            .is_some()
+        {
+            "already_waitlisted"
+        } else if max_participants.is_some_and(|max| seats_taken >= max as i64) {
+            // Successive join times keep the waitlist in rank order
+            sqlx::query(
+                "INSERT INTO contest_waitlist (contest_id, user_id, joined_at) VALUES ($1, $2, $3)",
+            )
+            .bind(contest_id)
+            .bind(row.user_id)
+            .bind(now + Duration::microseconds(waitlisted as i64))
+            .execute(&mut *tx)
+            .await?;
+            waitlisted += 1;
+            "waitlisted"
+        } else {
+            sqlx::query(
+                r#"
+                INSERT INTO contest_participants (contest_id, user_id, registered_at, status)
+                VALUES ($1, $2, $3, 'registered')
+                "#,
+            )
+            .bind(contest_id)
+            .bind(row.user_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+            seats_taken += 1;
+            registered += 1;
+            "registered"
+        };
+
+        participants.push(CarriedParticipant {
+            user: OwnerInfo {
+                id: row.user_id,
+                username: row.username,
+                display_name: row.display_name,
+            },
+            rank: standing.rank,
+            total_score: standing.total_score,
+            problems_solved: standing.problems_solved,
+            status: status.to_string(),
+        });
+    }
+
+    // A dry run reports the same outcome, then rolls back
+    if payload.dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+        tracing::info!(
+            contest_id = %contest_id,
+            source_contest_id = %source_id,
+            user_id = %user.id,
+            registered,
+            waitlisted,
+            "Carried over contest participants"
+        );
+    }
+
+    Ok(Json(CarryOverResponse {
+        contest_id,
+        source_contest_id: source_id,
+        dry_run: payload.dry_run,
+        evaluated,
+        registered,
+        waitlisted,
+        participants,
+    }))
+}
+
+/// POST /api/v1/contests/{id}/rejudge
+///
+/// Rejudge every submission in the contest (owner, admin, or collaborator
+/// with `can_rejudge`). Behaves like the admin contest rejudge.
+pub async fn rejudge_contest(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::Rejudge>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<ContestRejudgeResponse>> {
+    admin::rejudge_contest(State(state), Extension(user), Path(contest_id)).await
+}
+
 // =============================================================================
-// Collaborators
+// Pause and Extension
 // =============================================================================
 
-/// GET /api/v1/contests/{id}/collaborators
+/// Database row for a contest pause
+#[derive(Debug, FromRow)]
+struct ContestPauseRow {
+    id: Uuid,
+    reason: Option<String>,
+    paused_at: DateTime<Utc>,
+    resumed_at: Option<DateTime<Utc>>,
+}
+
+/// POST /api/v1/contests/{id}/pause
 ///
-/// List contest collaborators.
-pub async fn list_collaborators(
+/// Pause a running contest (owner, admin, or collaborator with
+/// `can_edit_contest`). Submissions are rejected until it resumes, and the
+/// pause does not count towards contest time.
+pub async fn pause_contest(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    Guard { user, .. }: Guard<policies::EditContest>,
     Path(contest_id): Path<Uuid>,
-) -> ApiResult<Json<CollaboratorListResponse>> {
-    // Check contest exists and user has permission to view
-    let contest: Option<(Uuid,)> = sqlx::query_as("SELECT owner_id FROM contests WHERE id = $1")
-        .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+    ValidatedJson(payload): ValidatedJson<PauseContestRequest>,
+) -> ApiResult<Json<ContestPauseResponse>> {
+    require_contest_running(&build_contest_context(&state, &user, contest_id)).await?;
 
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO contest_pauses (contest_id, reason, paused_by)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (contest_id) WHERE resumed_at IS NULL DO NOTHING
+        "#,
+    )
+    .bind(contest_id)
+    .bind(&payload.reason)
+    .bind(user.id)
+    .execute(&state.db)
+    .await?;
 
-    // Only owner, admin, or collaborators can view
-    let is_collaborator: Option<(i64,)> = sqlx::query_as(
-        "SELECT 1 FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2",
+    if inserted.rows_affected() == 0 {
+        return Err(ApiError::Conflict("Contest is already paused".to_string()));
+    }
+
+    tracing::warn!(
+        contest_id = %contest_id,
+        user_id = %user.id,
+        reason = ?payload.reason,
+        "Paused contest"
+    );
+
+    Ok(Json(fetch_pause_state(&state.db, contest_id).await?))
+}
+
+/// POST /api/v1/contests/{id}/resume
+///
+/// Resume a paused contest. The end time is unchanged; extend the contest
+/// separately to give the time back.
+pub async fn resume_contest(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditContest>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<ContestPauseResponse>> {
+    let resumed = sqlx::query(
+        r#"
+        UPDATE contest_pauses SET resumed_at = NOW(), resumed_by = $2
+        WHERE contest_id = $1 AND resumed_at IS NULL
+        "#,
     )
     .bind(contest_id)
     .bind(user.id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+    .execute(&state.db)
+    .await?;
 
-    if contest.0 != user.id && user.role != "admin" && is_collaborator.is_none() {
-        return Err(ApiError::Forbidden);
+    if resumed.rows_affected() == 0 {
+        return Err(ApiError::Conflict("Contest is not paused".to_string()));
     }
 
-    let rows: Vec<(
-        Uuid,
-        Uuid,
-        String,
-        Option<String>,
-        String,
-        bool,
-        bool,
-        bool,
-        DateTime<Utc>,
-    )> = sqlx::query_as(
+    tracing::info!(
+        contest_id = %contest_id,
+        user_id = %user.id,
+        "Resumed contest"
+    );
+
+    Ok(Json(fetch_pause_state(&state.db, contest_id).await?))
+}
+
+/// POST /api/v1/contests/{id}/extend
+///
+/// Move the end time (and freeze time, if set) of a contest that has not
+/// ended yet back by `minutes`.
+pub async fn extend_contest(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditContest>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ExtendContestRequest>,
+) -> ApiResult<Json<ContestPauseResponse>> {
+    let extended = sqlx::query(
         r#"
-            SELECT 
-                cc.id, u.id as user_id, u.username, u.display_name,
-                cc.role, cc.can_edit_contest, cc.can_add_problems, cc.can_view_submissions,
-                cc.added_at
-            FROM contest_collaborators cc
-            JOIN users u ON cc.user_id = u.id
-            WHERE cc.contest_id = $1
-            ORDER BY cc.added_at DESC
-            "#,
+        UPDATE contests
+        SET end_time = end_time + INTERVAL '1 minute' * $2,
+            freeze_time = freeze_time + INTERVAL '1 minute' * $2,
+            updated_at = NOW()
+        WHERE id = $1 AND end_time > NOW()
+        "#,
     )
     .bind(contest_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+    .bind(payload.minutes)
+    .execute(&state.db)
+    .await?;
 
-    let collaborators: Vec<CollaboratorInfo> = rows
-        .into_iter()
-        .map(|row| CollaboratorInfo {
-            id: row.0,
+    if extended.rows_affected() == 0 {
+        return Err(ApiError::Validation("Contest has ended".to_string()));
+    }
+
+    tracing::info!(
+        contest_id = %contest_id,
+        user_id = %user.id,
+        minutes = payload.minutes,
+        "Extended contest"
+    );
+
+    Ok(Json(fetch_pause_state(&state.db, contest_id).await?))
+}
+
+/// Load a contest's end time and pause history.
+async fn fetch_pause_state(db: &sqlx::PgPool, contest_id: Uuid) -> ApiResult<ContestPauseResponse> {
+    let (end_time, freeze_time): (DateTime<Utc>, Option<DateTime<Utc>>) =
+        sqlx::query_as("SELECT end_time, freeze_time FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_one(db)
+            .await?;
+
+    let rows: Vec<ContestPauseRow> = sqlx::query_as(
+        r#"
+        SELECT id, reason, paused_at, resumed_at
+        FROM contest_pauses WHERE contest_id = $1
+        ORDER BY paused_at
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(db)
+    .await?;
+
+    let now = Utc::now();
+    let paused_seconds = rows
+        .iter()
+        .map(|p| (p.resumed_at.unwrap_or(now) - p.paused_at).num_seconds())
+        .sum();
+
+    Ok(ContestPauseResponse {
+        contest_id,
+        paused: rows.iter().any(|p| p.resumed_at.is_none()),
+        end_time,
+        freeze_time,
+        paused_seconds,
+        pauses: rows
+            .into_iter()
+            .map(|p| ContestPauseInfo {
+                id: p.id,
+                reason: p.reason,
+                paused_at: p.paused_at,
+                resumed_at: p.resumed_at,
+            })
+            .collect(),
+    })
+}
+
+// =============================================================================
+// Collaborators
+// =============================================================================
+
+const COLLABORATOR_COLUMNS: &str = "cc.id, u.id as user_id, u.username, u.display_name, cc.role, \
+     cc.can_edit_contest, cc.can_add_problems, cc.can_edit_problems, cc.can_view_submissions, \
+     cc.can_rejudge, cc.can_manage_participants, cc.can_answer_clarifications, \
+     cc.can_publish_editorial, cc.can_test_solutions, cc.added_at";
+
+/// Database row for a collaborator joined with the user
+#[derive(Debug, FromRow)]
+struct CollaboratorRow {
+    id: Uuid,
+    user_id: Uuid,
+    username: String,
+    display_name: Option<String>,
+    role: String,
+    can_edit_contest: bool,
+    can_add_problems: bool,
+    can_edit_problems: bool,
+    can_view_submissions: bool,
+    can_rejudge: bool,
+    can_manage_participants: bool,
+    can_answer_clarifications: bool,
+    can_publish_editorial: bool,
+    can_test_solutions: bool,
+    added_at: DateTime<Utc>,
+}
+
+impl CollaboratorRow {
+    fn permissions(&self) -> CollaboratorPermissions {
+        CollaboratorPermissions {
+            can_edit_contest: self.can_edit_contest,
+            can_add_problems: self.can_add_problems,
+            can_edit_problems: self.can_edit_problems,
+            can_view_submissions: self.can_view_submissions,
+            can_rejudge: self.can_rejudge,
+            can_manage_participants: self.can_manage_participants,
+            can_answer_clarifications: self.can_answer_clarifications,
+            can_publish_editorial: self.can_publish_editorial,
+            can_test_solutions: self.can_test_solutions,
+        }
+    }
+
+    fn into_info(self) -> CollaboratorInfo {
+        CollaboratorInfo {
+            id: self.id,
+            permissions: self.permissions(),
             user: OwnerInfo {
-                id: row.1,
-                username: row.2,
-                display_name: row.3,
+                id: self.user_id,
+                username: self.username,
+                display_name: self.display_name,
             },
-            role: row.4,
-            can_edit_contest: row.5,
-            can_add_problems: row.6,
-            can_view_submissions: row.7,
-            added_at: row.8,
-        })
+            role: self.role,
+            added_at: self.added_at,
+        }
+    }
+}
+
+/// GET /api/v1/contests/{id}/collaborators
+///
+/// List contest collaborators.
+pub async fn list_collaborators(
+    State(state): State<AppState>,
+    _guard: Guard<policies::ContestStaff>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<CollaboratorListResponse>> {
+    let sql = format!(
+        "SELECT {} FROM contest_collaborators cc JOIN users u ON cc.user_id = u.id \
+         WHERE cc.contest_id = $1 ORDER BY cc.added_at DESC",
+        COLLABORATOR_COLUMNS
+    );
+    let collaborators = sqlx::query_as::<_, CollaboratorRow>(&sql)
+        .bind(contest_id)
+        .fetch_all(&state.db)
+        .await?
+        .into_iter()
+        .map(CollaboratorRow::into_info)
         .collect();
 
     Ok(Json(CollaboratorListResponse { collaborators }))
@@ -937,27 +1867,10 @@ pub async fn list_collaborators(
 /// Add a collaborator to contest.
 pub async fn add_collaborator(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    Guard { user, .. }: Guard<policies::ContestOwner>,
     Path(contest_id): Path<Uuid>,
-    Json(payload): Json<AddCollaboratorRequest>,
+    ValidatedJson(payload): ValidatedJson<AddCollaboratorRequest>,
 ) -> ApiResult<(StatusCode, Json<CollaboratorInfo>)> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
-    // Check contest exists and user is owner or admin
-    let contest: Option<(Uuid,)> = sqlx::query_as("SELECT owner_id FROM contests WHERE id = $1")
-        .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
-
-    if contest.0 != user.id && user.role != "admin" {
-        return Err(ApiError::Forbidden);
-    }
-
     // Check target user exists
     let target_user: Option<(Uuid, String, Option<String>)> =
         sqlx::query_as("SELECT id, username, display_name FROM users WHERE id = $1")
@@ -986,27 +1899,37 @@ pub async fn add_collaborator(
 
     let id = Uuid::new_v4();
     let now = Utc::now();
+    let role: CollaboratorRole = payload.role.parse().map_err(ApiError::Validation)?;
+    let permissions = payload.permissions.apply(role.default_permissions());
 
     sqlx::query(
         r#"
         INSERT INTO contest_collaborators (
             id, contest_id, user_id, role, can_edit_contest, can_add_problems,
-            can_view_submissions, added_at, added_by
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            can_edit_problems, can_view_submissions, can_rejudge, can_manage_participants,
+            can_answer_clarifications, can_publish_editorial, can_test_solutions,
+            added_at, added_by
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
         "#,
     )
     .bind(id)
     .bind(contest_id)
     .bind(payload.user_id)
-    .bind(&payload.role)
-    .bind(payload.can_edit_contest)
-    .bind(payload.can_add_problems)
-    .bind(payload.can_view_submissions)
+    .bind(role.as_str())
+    .bind(permissions.can_edit_contest)
+    .bind(permissions.can_add_problems)
+    .bind(permissions.can_edit_problems)
+    .bind(permissions.can_view_submissions)
+    .bind(permissions.can_rejudge)
+    .bind(permissions.can_manage_participants)
+    .bind(permissions.can_answer_clarifications)
+    .bind(permissions.can_publish_editorial)
+    .bind(permissions.can_test_solutions)
     .bind(now)
     .bind(user.id)
     .execute(&state.db)
     .await
-    .map_err(|e| ApiError::Internal(format!("Failed to add collaborator: {}", e)))?;
+    .map_err(error::conflict_on_unique("User is already a collaborator"))?;
 
     Ok((
         StatusCode::CREATED,
@@ -1017,36 +1940,91 @@ pub async fn add_collaborator(
                 username: target_user.1,
                 display_name: target_user.2,
             },
-            role: payload.role,
-            can_edit_contest: payload.can_edit_contest,
-            can_add_problems: payload.can_add_problems,
-            can_view_submissions: payload.can_view_submissions,
+            role: role.to_string(),
+            permissions,
             added_at: now,
         }),
     ))
 }
 
-/// DELETE /api/v1/contests/{id}/collaborators/{user_id}
+/// PUT /api/v1/contests/{id}/collaborators/{user_id}
 ///
-/// Remove a collaborator from contest.
-pub async fn remove_collaborator(
+/// Change a collaborator's role and/or individual permissions.
+pub async fn update_collaborator(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    Guard { user, .. }: Guard<policies::ContestOwner>,
     Path((contest_id, target_user_id)): Path<(Uuid, Uuid)>,
-) -> ApiResult<StatusCode> {
-    // Check contest exists and user is owner or admin
-    let contest: Option<(Uuid,)> = sqlx::query_as("SELECT owner_id FROM contests WHERE id = $1")
+    ValidatedJson(payload): ValidatedJson<UpdateCollaboratorRequest>,
+) -> ApiResult<Json<CollaboratorInfo>> {
+    let sql = format!(
+        "SELECT {} FROM contest_collaborators cc JOIN users u ON cc.user_id = u.id \
+         WHERE cc.contest_id = $1 AND cc.user_id = $2",
+        COLLABORATOR_COLUMNS
+    );
+    let existing = sqlx::query_as::<_, CollaboratorRow>(&sql)
         .bind(contest_id)
+        .bind(target_user_id)
         .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+        .await?
+        .ok_or(ApiError::NotFound("Collaborator not found".to_string()))?;
 
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
+    let (role, base) = match payload.role.as_deref() {
+        Some(role) => {
+            let role: CollaboratorRole = role.parse().map_err(ApiError::Validation)?;
+            (role.to_string(), role.default_permissions())
+        }
+        None => (existing.role.clone(), existing.permissions()),
+    };
+    let permissions = payload.permissions.apply(base);
 
-    if contest.0 != user.id && user.role != "admin" {
-        return Err(ApiError::Forbidden);
-    }
+    sqlx::query(
+        r#"
+        UPDATE contest_collaborators SET
+            role = $3, can_edit_contest = $4, can_add_problems = $5, can_edit_problems = $6,
+            can_view_submissions = $7, can_rejudge = $8, can_manage_participants = $9,
+            can_answer_clarifications = $10, can_publish_editorial = $11,
+            can_test_solutions = $12
+        WHERE contest_id = $1 AND user_id = $2
+        "#,
+    )
+    .bind(contest_id)
+    .bind(target_user_id)
+    .bind(&role)
+    .bind(permissions.can_edit_contest)
+    .bind(permissions.can_add_problems)
+    .bind(permissions.can_edit_problems)
+    .bind(permissions.can_view_submissions)
+    .bind(permissions.can_rejudge)
+    .bind(permissions.can_manage_participants)
+    .bind(permissions.can_answer_clarifications)
+    .bind(permissions.can_publish_editorial)
+    .bind(permissions.can_test_solutions)
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        collaborator_id = %target_user_id,
+        updated_by = %user.id,
+        role = %role,
+        "Updated collaborator permissions"
+    );
 
+    Ok(Json(CollaboratorInfo {
+        role,
+        permissions,
+        ..existing.into_info()
+    }))
+}
+
+/// DELETE /api/v1/contests/{id}/collaborators/{user_id}
+///
+/// Remove a collaborator from contest.
+pub async fn remove_collaborator(
+    State(state): State<AppState>,
+    _guard: Guard<policies::ContestOwner>,
+    Path((contest_id, target_user_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
     let result =
         sqlx::query("DELETE FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2")
             .bind(contest_id)
@@ -1062,14 +2040,395 @@ pub async fn remove_collaborator(
     Ok(StatusCode::NO_CONTENT)
 }
 
+// =============================================================================
+// Statistics
+// =============================================================================
+
+/// How long computed statistics are served from Redis
+const STATISTICS_CACHE_SECS: u64 = 30;
+
+/// Most buckets in a submission timeline
+const MAX_TIMELINE_BUCKETS: i64 = 60;
+
+/// Timeline bucket widths to pick from, in minutes
+const TIMELINE_BUCKET_MINUTES: [i64; 12] = [1, 2, 5, 10, 15, 30, 60, 120, 180, 360, 720, 1440];
+
+#[derive(Debug, FromRow)]
+struct StatisticsContestRow {
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    freeze_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+struct ProblemStatisticsRow {
+    problem_id: Uuid,
+    problem_code: String,
+    title: String,
+    submissions: i64,
+    attempted_by: i64,
+    solved_by: i64,
+    avg_accepted_time_ms: Option<f64>,
+    avg_accepted_memory_kb: Option<f64>,
+}
+
+/// GET /api/v1/contests/{id}/statistics
+///
+/// Per-problem solve counts, verdict and language distributions, a
+/// submission timeline and the average time and memory of accepted
+/// solutions. Practice submissions are not counted. The aggregates run on
+/// the read replica and are cached in Redis for `STATISTICS_CACHE_SECS`.
+/// A private contest's statistics are visible to those who can see it, and
+/// only staff see any before the start or any about problems not yet
+/// released (`visible_from`).
+pub async fn get_contest_statistics(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<ContestStatisticsResponse>> {
+    let user = user.as_ref().map(|u| &u.0);
+    let contest = load_visible_contest(&state, contest_id, user).await?;
+
+    // Nothing about the problems is shown before the start, except to staff
+    let is_staff = is_contest_staff(&state, user, contest_id).await;
+    if Utc::now() < contest.start_time && !is_staff {
+        return Err(ApiError::Forbidden);
+    }
+
+    Ok(Json(
+        contest_statistics(&state, contest_id, is_staff).await?,
+    ))
+}
+
+/// GET /api/v1/contests/{id}/statistics/comparison
+///
+/// A mirror contest's statistics next to its original's, with each
+/// problem's solve rate in both. Both contests must be visible to the
+/// caller, and before the mirror starts only its staff see anything. 404
+/// for a contest that is not a mirror.
+pub async fn get_mirror_comparison(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<MirrorComparisonResponse>> {
+    let user = user.as_ref().map(|u| &u.0);
+    let contest = load_visible_contest(&state, contest_id, user).await?;
+    let original_id = contest
+        .mirror_of
+        .ok_or_else(|| ApiError::NotFound("Contest is not a mirror".to_string()))?;
+    load_visible_contest(&state, original_id, user).await?;
+
+    let is_staff = is_contest_staff(&state, user, contest_id).await;
+    if Utc::now() < contest.start_time && !is_staff {
+        return Err(ApiError::Forbidden);
+    }
+    let is_original_staff = is_contest_staff(&state, user, original_id).await;
+
+    let mirror = contest_statistics(&state, contest_id, is_staff).await?;
+    let original = contest_statistics(&state, original_id, is_original_staff).await?;
+    let problems = compare_problems(
+        (&mirror.problems, mirror.participants),
+        (&original.problems, original.participants),
+    );
+
+    Ok(Json(MirrorComparisonResponse {
+        contest_id,
+        original_contest_id: original_id,
+        problems,
+        mirror,
+        original,
+    }))
+}
+
+/// Per mirror problem, how many solved it in the mirror and in the original
+/// and what share of each contest's participants that is. Each side is the
+/// contest's problem statistics and participant count.
+fn compare_problems(
+    mirror: (&[ProblemStatistics], i64),
+    original: (&[ProblemStatistics], i64),
+) -> Vec<ProblemComparison> {
+    let solve_rate = |solved_by: i64, participants: i64| {
+        (participants > 0).then(|| solved_by as f64 / participants as f64)
+    };
+    let (mirror_problems, mirror_participants) = mirror;
+    let (original_problems, original_participants) = original;
+    mirror_problems
+        .iter()
+        .map(|problem| {
+            let original_solved_by = original_problems
+                .iter()
+                .find(|p| p.problem_id == problem.problem_id)
+                .map_or(0, |p| p.solved_by);
+            ProblemComparison {
+                problem_id: problem.problem_id,
+                problem_code: problem.problem_code.clone(),
+                title: problem.title.clone(),
+                mirror_solved_by: problem.solved_by,
+                original_solved_by,
+                mirror_solve_rate: solve_rate(problem.solved_by, mirror_participants),
+                original_solve_rate: solve_rate(original_solved_by, original_participants),
+            }
+        })
+        .collect()
+}
+
+/// Statistics of a contest, from the Redis cache when fresh. Staff and
+/// everyone else are cached apart, as only staff see unreleased problems.
+async fn contest_statistics(
+    state: &AppState,
+    contest_id: Uuid,
+    is_staff: bool,
+) -> ApiResult<ContestStatisticsResponse> {
+    let cache_key = format!(
+        "contest_statistics:{}:{}",
+        contest_id,
+        if is_staff { "staff" } else { "public" }
+    );
+    let mut conn = state.redis.get().await?;
+
+    let cached: Option<String> = redis::cmd("GET")
+        .arg(&cache_key)
+        .query_async(&mut *conn)
+        .await?;
+    if let Some(statistics) = cached.and_then(|json| serde_json::from_str(&json).ok()) {
+        return Ok(statistics);
+    }
+
+    let statistics = compute_contest_statistics(state, contest_id, is_staff).await?;
+
+    let json = serde_json::to_string(&statistics)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize statistics: {}", e)))?;
+    redis::cmd("SET")
+        .arg(&cache_key)
+        .arg(json)
+        .arg("EX")
+        .arg(STATISTICS_CACHE_SECS)
+        .query_async::<()>(&mut *conn)
+        .await?;
+
+    Ok(statistics)
+}
+
+/// Run the statistics aggregates for one contest, over every problem or,
+/// without `all_problems`, only the released ones
+async fn compute_contest_statistics(
+    state: &AppState,
+    contest_id: Uuid,
+    all_problems: bool,
+) -> ApiResult<ContestStatisticsResponse> {
+    let db = state.read_db();
+    let contest: StatisticsContestRow =
+        sqlx::query_as("SELECT start_time, end_time, freeze_time FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
+
+    // While the leaderboard is frozen, later submissions stay hidden here too
+    let now = Utc::now();
+    let cutoff = contest
+        .freeze_time
+        .filter(|&freeze| now >= freeze && now < contest.end_time);
+
+    let problem_rows: Vec<ProblemStatisticsRow> = sqlx::query_as(
+        r#"
+        SELECT cp.problem_id, cp.problem_code, p.title,
+               COUNT(s.id) as submissions,
+               COUNT(DISTINCT s.user_id) as attempted_by,
+               COUNT(DISTINCT s.user_id) FILTER (WHERE s.status = 'accepted') as solved_by,
+               (AVG(s.max_time_ms) FILTER (WHERE s.status = 'accepted'))::double precision
+                   as avg_accepted_time_ms,
+               (AVG(s.max_memory_kb) FILTER (WHERE s.status = 'accepted'))::double precision
+                   as avg_accepted_memory_kb
+        FROM contest_problems cp
+        JOIN problems p ON p.id = cp.problem_id
+        LEFT JOIN submissions s
+            ON s.contest_id = cp.contest_id AND s.problem_id = cp.problem_id
+           AND NOT s.is_practice AND NOT s.is_test
+           AND ($2::timestamptz IS NULL OR s.submitted_at < $2)
+        WHERE cp.contest_id = $1
+          AND ($3 OR cp.visible_from IS NULL OR cp.visible_from <= NOW())
+        GROUP BY cp.problem_id, cp.problem_code, p.title, cp.sort_order
+        ORDER BY cp.sort_order
+        "#,
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .bind(all_problems)
+    .fetch_all(db)
+    .await?;
+
+    let status_counts: Vec<(Uuid, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT problem_id, status, COUNT(*)
+        FROM submissions
+        WHERE contest_id = $1 AND NOT is_practice AND NOT is_test
+          AND ($2::timestamptz IS NULL OR submitted_at < $2)
+          AND ($3 OR problem_id IN (
+                SELECT problem_id FROM contest_problems
+                WHERE contest_id = $1 AND (visible_from IS NULL OR visible_from <= NOW())
+              ))
+        GROUP BY problem_id, status
+        "#,
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .bind(all_problems)
+    .fetch_all(db)
+    .await?;
+
+    let languages: Vec<(String, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT COALESCE(language, 'unknown'), COUNT(*),
+               COUNT(*) FILTER (WHERE status = 'accepted')
+        FROM submissions
+        WHERE contest_id = $1 AND NOT is_practice AND NOT is_test
+          AND ($2::timestamptz IS NULL OR submitted_at < $2)
+          AND ($3 OR problem_id IN (
+                SELECT problem_id FROM contest_problems
+                WHERE contest_id = $1 AND (visible_from IS NULL OR visible_from <= NOW())
+              ))
+        GROUP BY 1
+        ORDER BY 2 DESC, 1
+        "#,
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .bind(all_problems)
+    .fetch_all(db)
+    .await?;
+
+    let participants: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(DISTINCT user_id)
+        FROM submissions
+        WHERE contest_id = $1 AND NOT is_practice AND NOT is_test
+          AND ($2::timestamptz IS NULL OR submitted_at < $2)
+          AND ($3 OR problem_id IN (
+                SELECT problem_id FROM contest_problems
+                WHERE contest_id = $1 AND (visible_from IS NULL OR visible_from <= NOW())
+              ))
+        "#,
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .bind(all_problems)
+    .fetch_one(db)
+    .await?;
+
+    // Timeline from the start to the end, the freeze or now, whichever is first
+    let timeline_end = cutoff.unwrap_or(contest.end_time).min(now);
+    let span_minutes = (timeline_end - contest.start_time).num_minutes().max(0);
+    let bucket_minutes = timeline_bucket_minutes(span_minutes);
+    let bucket_count = (span_minutes + bucket_minutes - 1) / bucket_minutes;
+
+    let bucket_counts: Vec<(i64, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT FLOOR(EXTRACT(EPOCH FROM submitted_at - $4) / $5)::bigint as bucket,
+               COUNT(*), COUNT(*) FILTER (WHERE status = 'accepted')
+        FROM submissions
+        WHERE contest_id = $1 AND NOT is_practice AND NOT is_test
+          AND ($2::timestamptz IS NULL OR submitted_at < $2)
+          AND ($3 OR problem_id IN (
+                SELECT problem_id FROM contest_problems
+                WHERE contest_id = $1 AND (visible_from IS NULL OR visible_from <= NOW())
+              ))
+          AND submitted_at >= $4
+        GROUP BY bucket
+        "#,
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .bind(all_problems)
+    .bind(contest.start_time)
+    .bind((bucket_minutes * 60) as f64)
+    .fetch_all(db)
+    .await?;
+
+    let mut buckets: Vec<TimelineBucket> = (0..bucket_count)
+        .map(|i| TimelineBucket {
+            start: contest.start_time + Duration::minutes(i * bucket_minutes),
+            submissions: 0,
+            accepted: 0,
+        })
+        .collect();
+    for (bucket, submissions, accepted) in bucket_counts {
+        if let Some(b) = usize::try_from(bucket)
+            .ok()
+            .and_then(|i| buckets.get_mut(i))
+        {
+            b.submissions = submissions;
+            b.accepted = accepted;
+        }
+    }
+
+    let mut verdicts = BTreeMap::new();
+    let problems = problem_rows
+        .into_iter()
+        .map(|row| {
+            let mut problem_verdicts = BTreeMap::new();
+            for (problem_id, status, count) in &status_counts {
+                if *problem_id == row.problem_id {
+                    problem_verdicts.insert(status.clone(), *count);
+                    *verdicts.entry(status.clone()).or_insert(0) += count;
+                }
+            }
+            ProblemStatistics {
+                problem_id: row.problem_id,
+                problem_code: row.problem_code,
+                title: row.title,
+                submissions: row.submissions,
+                attempted_by: row.attempted_by,
+                solved_by: row.solved_by,
+                verdicts: problem_verdicts,
+                avg_accepted_time_ms: row.avg_accepted_time_ms,
+                avg_accepted_memory_kb: row.avg_accepted_memory_kb,
+            }
+        })
+        .collect();
+
+    Ok(ContestStatisticsResponse {
+        contest_id,
+        frozen: cutoff.is_some(),
+        total_submissions: verdicts.values().sum(),
+        participants,
+        problems,
+        verdicts,
+        languages: languages
+            .into_iter()
+            .map(|(language, submissions, accepted)| LanguageStatistics {
+                language,
+                submissions,
+                accepted,
+            })
+            .collect(),
+        timeline: SubmissionTimeline {
+            bucket_minutes,
+            buckets,
+        },
+        generated_at: now,
+    })
+}
+
+/// Narrowest bucket width that covers `span_minutes` in at most
+/// `MAX_TIMELINE_BUCKETS` buckets
+fn timeline_bucket_minutes(span_minutes: i64) -> i64 {
+    TIMELINE_BUCKET_MINUTES
+        .into_iter()
+        .find(|&width| span_minutes <= width * MAX_TIMELINE_BUCKETS)
+        .unwrap_or_else(|| (span_minutes + MAX_TIMELINE_BUCKETS - 1) / MAX_TIMELINE_BUCKETS)
+}
+
 /// Create routes for contests
 pub fn contest_routes() -> axum::Router<AppState> {
-    use axum::routing::{delete, get, post, put};
+    use axum::routing::get;
 
     axum::Router::new()
         .route("/", get(list_contests))
         .route("/{id}", get(get_contest))
         .route("/{id}/participants", get(list_participants))
+        .route("/{id}/statistics", get(get_contest_statistics))
+        .route("/{id}/statistics/comparison", get(get_mirror_comparison))
 }
 
 pub fn protected_contest_routes() -> axum::Router<AppState> {
@@ -1083,5 +2442,67 @@ pub fn protected_contest_routes() -> axum::Router<AppState> {
         .route("/{id}/unregister", post(unregister_from_contest))
         .route("/{id}/collaborators", get(list_collaborators))
         .route("/{id}/collaborators", post(add_collaborator))
+        .route("/{id}/collaborators/{user_id}", put(update_collaborator))
         .route("/{id}/collaborators/{user_id}", delete(remove_collaborator))
+        .route("/{id}/participants/{user_id}", delete(remove_participant))
+        .route("/{id}/waitlist", get(list_waitlist))
+        .route("/{id}/carry-over", post(carry_over_participants))
+        .route("/{id}/mirror", post(create_mirror_contest))
+        .route("/{id}/rejudge", post(rejudge_contest))
+        .route("/{id}/pause", post(pause_contest))
+        .route("/{id}/resume", post(resume_contest))
+        .route("/{id}/extend", post(extend_contest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(problem_id: Uuid, code: &str, solved_by: i64) -> ProblemStatistics {
+        ProblemStatistics {
+            problem_id,
+            problem_code: code.to_string(),
+            title: format!("Problem {}", code),
+            submissions: solved_by * 2,
+            attempted_by: solved_by,
+            solved_by,
+            verdicts: BTreeMap::new(),
+            avg_accepted_time_ms: None,
+            avg_accepted_memory_kb: None,
+        }
+    }
+
+    #[test]
+    fn test_private_contest_cannot_be_mirrored_as_public() {
+        assert!(mirror_visibility_error(false, true).is_some());
+        assert!(mirror_visibility_error(false, false).is_none());
+        assert!(mirror_visibility_error(true, true).is_none());
+        assert!(mirror_visibility_error(true, false).is_none());
+    }
+
+    #[test]
+    fn test_compare_problems_in_mirror_order_with_solve_rates() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mirror = [problem(b, "A", 3), problem(a, "B", 1)];
+        let original = [problem(a, "A", 40), problem(b, "B", 10)];
+
+        let compared = compare_problems((&mirror, 4), (&original, 50));
+        assert_eq!(compared.len(), 2);
+        assert_eq!(compared[0].problem_id, b);
+        assert_eq!(compared[0].problem_code, "A");
+        assert_eq!(compared[0].mirror_solved_by, 3);
+        assert_eq!(compared[0].original_solved_by, 10);
+        assert_eq!(compared[0].mirror_solve_rate, Some(0.75));
+        assert_eq!(compared[0].original_solve_rate, Some(0.2));
+        assert_eq!(compared[1].original_solved_by, 40);
+    }
+
+    #[test]
+    fn test_compare_problems_without_participants_or_original_problem() {
+        let mirror = [problem(Uuid::new_v4(), "A", 0)];
+        let compared = compare_problems((&mirror, 0), (&[], 0));
+        assert_eq!(compared[0].original_solved_by, 0);
+        assert_eq!(compared[0].mirror_solve_rate, None);
+        assert_eq!(compared[0].original_solve_rate, None);
+    }
 }
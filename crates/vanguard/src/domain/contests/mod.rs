@@ -6,4 +6,3 @@ pub mod response;
 
 pub use handler::*;
 pub use request::*;
-pub use response::*;
@@ -1,22 +1,26 @@
 //! Contest request DTOs.
 
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use olympus_rules::advancement::AdvancementRules;
+use olympus_rules::collaborators::{CollaboratorPermissions, CollaboratorRole};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::Validate;
 
+use crate::extract::double_option;
+
 /// Scoring type for contests
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ScoringType {
+    #[default]
     Icpc,
     Ioi,
     Custom,
-}
-
-impl Default for ScoringType {
-    fn default() -> Self {
-        Self::Icpc
-    }
+    /// Accepted submissions are scored by runtime relative to the fastest one
+    Performance,
+    /// Problems are worth less the more participants solve them
+    Dynamic,
 }
 
 impl std::fmt::Display for ScoringType {
@@ -25,6 +29,31 @@ impl std::fmt::Display for ScoringType {
             ScoringType::Icpc => write!(f, "icpc"),
             ScoringType::Ioi => write!(f, "ioi"),
             ScoringType::Custom => write!(f, "custom"),
+            ScoringType::Performance => write!(f, "performance"),
+            ScoringType::Dynamic => write!(f, "dynamic"),
+        }
+    }
+}
+
+/// Who may read other users' submission sources once the contest has ended
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceVisibility {
+    /// Only the author and contest staff
+    #[default]
+    Private,
+    /// Every registered participant
+    Participants,
+    /// Every signed-in user
+    Public,
+}
+
+impl std::fmt::Display for SourceVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceVisibility::Private => write!(f, "private"),
+            SourceVisibility::Participants => write!(f, "participants"),
+            SourceVisibility::Public => write!(f, "public"),
         }
     }
 }
@@ -62,12 +91,62 @@ pub struct CreateContestRequest {
     pub max_participants: Option<i32>,
 
     pub allowed_languages: Option<Vec<String>>,
+
+    /// Minimum seconds between a user's submissions to the same problem (0 = off)
+    #[validate(range(min = 0, max = 3600, message = "Cooldown must be 0-3600 seconds"))]
+    pub submission_cooldown_secs: Option<i32>,
+
+    /// Submissions per hour per user while the contest runs, replacing the
+    /// global limit (`None` = global default)
+    #[validate(range(min = 1, max = 10000, message = "Hourly limit must be 1-10000"))]
+    pub submissions_per_hour: Option<i32>,
+
+    /// Submissions per user to each problem while the contest runs (`None` = unlimited)
+    #[validate(range(min = 1, max = 10000, message = "Per-problem limit must be 1-10000"))]
+    pub max_submissions_per_problem: Option<i32>,
+
+    /// Who may read other users' sources after the contest ends
+    #[serde(default)]
+    pub source_visibility: SourceVisibility,
+
+    /// Clients report anti-cheat telemetry (pastes, tab switches)
+    #[serde(default)]
+    pub is_proctored: bool,
+
+    /// Participants may download failing tests of their submissions after the contest
+    #[serde(default)]
+    pub test_data_downloads: bool,
+
+    /// Publish the unauthenticated scoreboard feed for projector displays
+    #[serde(default)]
+    pub scoreboard_feed: bool,
+
+    /// Share of a problem's points left once it is widely solved (`dynamic` scoring)
+    #[validate(range(min = 0, max = 100, message = "Minimum percent must be 0-100"))]
+    #[serde(default = "default_dynamic_min_percent")]
+    pub dynamic_min_percent: i32,
+
+    /// Solvers after the first at which a problem reaches its minimum (`dynamic` scoring)
+    #[validate(range(min = 1, max = 100000, message = "Decay must be 1-100000 solvers"))]
+    #[serde(default = "default_dynamic_decay")]
+    pub dynamic_decay: i32,
+
+    /// Hosting organization; a non-public hosted contest is members-only
+    pub organization_id: Option<uuid::Uuid>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_dynamic_min_percent() -> i32 {
+    30
+}
+
+fn default_dynamic_decay() -> i32 {
+    20
+}
+
 /// Update contest request
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateContestRequest {
@@ -95,6 +174,37 @@ pub struct UpdateContestRequest {
     pub max_participants: Option<i32>,
 
     pub allowed_languages: Option<Vec<String>>,
+
+    #[validate(range(min = 0, max = 3600, message = "Cooldown must be 0-3600 seconds"))]
+    pub submission_cooldown_secs: Option<i32>,
+
+    /// `null` removes the hourly limit
+    #[validate(range(min = 1, max = 10000, message = "Hourly limit must be 1-10000"))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub submissions_per_hour: Option<Option<i32>>,
+
+    /// `null` removes the per-problem limit
+    #[validate(range(min = 1, max = 10000, message = "Per-problem limit must be 1-10000"))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub max_submissions_per_problem: Option<Option<i32>>,
+
+    pub source_visibility: Option<SourceVisibility>,
+
+    pub is_proctored: Option<bool>,
+
+    pub test_data_downloads: Option<bool>,
+
+    pub scoreboard_feed: Option<bool>,
+
+    #[validate(range(min = 0, max = 100, message = "Minimum percent must be 0-100"))]
+    pub dynamic_min_percent: Option<i32>,
+
+    #[validate(range(min = 1, max = 100000, message = "Decay must be 1-100000 solvers"))]
+    pub dynamic_decay: Option<i32>,
+
+    /// `null` takes the contest out of its organization
+    #[serde(default, deserialize_with = "double_option")]
+    pub organization_id: Option<Option<uuid::Uuid>>,
 }
 
 /// List contests query parameters
@@ -109,15 +219,101 @@ pub struct ListContestsQuery {
     /// Filter: upcoming, ongoing, past, all
     pub status: Option<String>,
 
+    /// Only rated (or only unrated) contests
+    pub is_rated: Option<bool>,
+
+    /// Filter by scoring type
+    pub scoring_type: Option<ScoringType>,
+
     /// Filter by owner_id
     pub owner_id: Option<uuid::Uuid>,
 
     /// Search by title
     pub search: Option<String>,
 
-    /// Only public contests
-    #[serde(default = "default_true")]
-    pub public_only: bool,
+    /// Filter by hosting organization
+    pub organization_id: Option<uuid::Uuid>,
+
+    /// Only mirrors of this contest
+    pub mirror_of: Option<uuid::Uuid>,
+
+    /// Only public contests (default true)
+    pub public_only: Option<bool>,
+
+    /// Apply one of the caller's saved filters; parameters given here
+    /// override it
+    pub saved_filter: Option<uuid::Uuid>,
+}
+
+impl ListContestsQuery {
+    /// The filter parameters of this query
+    pub fn filters(&self) -> ContestListFilters {
+        ContestListFilters {
+            status: self.status.clone(),
+            is_rated: self.is_rated,
+            scoring_type: self.scoring_type.clone(),
+            owner_id: self.owner_id,
+            search: self.search.clone(),
+            organization_id: self.organization_id,
+            mirror_of: self.mirror_of,
+            public_only: self.public_only,
+        }
+    }
+}
+
+/// Contest list filters, as given on the list query or saved by a user
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct ContestListFilters {
+    #[validate(custom(function = "validate_contest_status"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_rated: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring_type: Option<ScoringType>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<uuid::Uuid>,
+
+    #[validate(length(max = 100, message = "Search must be at most 100 characters"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<uuid::Uuid>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror_of: Option<uuid::Uuid>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_only: Option<bool>,
+}
+
+impl ContestListFilters {
+    /// These filters, with `saved` filling in the ones not set
+    pub fn or(self, saved: ContestListFilters) -> Self {
+        Self {
+            status: self.status.or(saved.status),
+            is_rated: self.is_rated.or(saved.is_rated),
+            scoring_type: self.scoring_type.or(saved.scoring_type),
+            owner_id: self.owner_id.or(saved.owner_id),
+            search: self.search.or(saved.search),
+            organization_id: self.organization_id.or(saved.organization_id),
+            mirror_of: self.mirror_of.or(saved.mirror_of),
+            public_only: self.public_only.or(saved.public_only),
+        }
+    }
+}
+
+fn validate_contest_status(status: &str) -> Result<(), validator::ValidationError> {
+    if matches!(status, "upcoming" | "ongoing" | "past" | "all") {
+        return Ok(());
+    }
+    let mut err = validator::ValidationError::new("invalid_status");
+    err.message = Some("Status must be upcoming, ongoing, past, or all".into());
+    Err(err)
 }
 
 fn default_page() -> u32 {
@@ -129,6 +325,8 @@ fn default_per_page() -> u32 {
 }
 
 /// Add collaborator request
+///
+/// The role fills in default permissions; flags given explicitly override them.
 #[derive(Debug, Deserialize, Validate)]
 pub struct AddCollaboratorRequest {
     pub user_id: uuid::Uuid,
@@ -136,27 +334,121 @@ pub struct AddCollaboratorRequest {
     #[validate(custom(function = "validate_collaborator_role"))]
     pub role: String,
 
-    #[serde(default)]
-    pub can_edit_contest: bool,
+    #[serde(flatten)]
+    pub permissions: PermissionOverrides,
+}
 
-    #[serde(default)]
-    pub can_add_problems: bool,
+/// Update collaborator request
+///
+/// Changing the role resets permissions to the new role's defaults before
+/// applying explicit flags.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCollaboratorRequest {
+    #[validate(custom(function = "validate_collaborator_role"))]
+    pub role: Option<String>,
 
-    #[serde(default = "default_true")]
-    pub can_view_submissions: bool,
+    #[serde(flatten)]
+    pub permissions: PermissionOverrides,
 }
 
-fn validate_collaborator_role(role: &str) -> Result<(), validator::ValidationError> {
-    match role {
-        "co-owner" | "problem-setter" | "tester" => Ok(()),
-        _ => {
-            let mut err = validator::ValidationError::new("invalid_role");
-            err.message = Some("Role must be co-owner, problem-setter, or tester".into());
-            Err(err)
+/// Explicit collaborator permission flags (unset = keep the default)
+#[derive(Debug, Default, Deserialize)]
+pub struct PermissionOverrides {
+    pub can_edit_contest: Option<bool>,
+    pub can_add_problems: Option<bool>,
+    pub can_edit_problems: Option<bool>,
+    pub can_view_submissions: Option<bool>,
+    pub can_rejudge: Option<bool>,
+    pub can_manage_participants: Option<bool>,
+    pub can_answer_clarifications: Option<bool>,
+    pub can_publish_editorial: Option<bool>,
+    pub can_test_solutions: Option<bool>,
+}
+
+impl PermissionOverrides {
+    /// Apply the explicit flags on top of `base`.
+    pub fn apply(&self, base: CollaboratorPermissions) -> CollaboratorPermissions {
+        CollaboratorPermissions {
+            can_edit_contest: self.can_edit_contest.unwrap_or(base.can_edit_contest),
+            can_add_problems: self.can_add_problems.unwrap_or(base.can_add_problems),
+            can_edit_problems: self.can_edit_problems.unwrap_or(base.can_edit_problems),
+            can_view_submissions: self
+                .can_view_submissions
+                .unwrap_or(base.can_view_submissions),
+            can_rejudge: self.can_rejudge.unwrap_or(base.can_rejudge),
+            can_manage_participants: self
+                .can_manage_participants
+                .unwrap_or(base.can_manage_participants),
+            can_answer_clarifications: self
+                .can_answer_clarifications
+                .unwrap_or(base.can_answer_clarifications),
+            can_publish_editorial: self
+                .can_publish_editorial
+                .unwrap_or(base.can_publish_editorial),
+            can_test_solutions: self.can_test_solutions.unwrap_or(base.can_test_solutions),
         }
     }
 }
 
+fn validate_collaborator_role(role: &str) -> Result<(), validator::ValidationError> {
+    role.parse::<CollaboratorRole>().map(|_| ()).map_err(|_| {
+        let mut err = validator::ValidationError::new("invalid_role");
+        err.message = Some("Role must be co-owner, co-setter, tester, or observer".into());
+        err
+    })
+}
+
+/// Pause contest request
+#[derive(Debug, Deserialize, Validate)]
+pub struct PauseContestRequest {
+    /// Shown to participants, e.g. "Judge outage, investigating"
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+/// Extend contest request
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExtendContestRequest {
+    /// Minutes added to the end time (and the freeze time, if set)
+    #[validate(range(min = 1, max = 1440, message = "Extension must be 1-1440 minutes"))]
+    pub minutes: i32,
+}
+
+/// Carry participants over from a previous round of a series
+#[derive(Debug, Deserialize, Validate)]
+pub struct CarryOverRequest {
+    /// The previous round; it must have ended
+    pub source_contest_id: Uuid,
+    /// Who advances; everyone not disqualified when empty
+    #[serde(default)]
+    pub rules: AdvancementRules,
+    /// Report who would advance without registering anyone
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Mirror a past contest
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateMirrorRequest {
+    /// Defaults to the original's title with " (mirror)" appended
+    #[validate(length(min = 3, max = 255, message = "Title must be 3-255 characters"))]
+    pub title: Option<String>,
+
+    /// The mirror runs for as long as the original from here
+    pub start_time: DateTime<Utc>,
+
+    #[serde(default)]
+    pub is_public: bool,
+
+    #[serde(default = "default_true")]
+    pub registration_required: bool,
+
+    pub max_participants: Option<i32>,
+
+    /// Training group (organization) hosting the mirror
+    pub organization_id: Option<Uuid>,
+}
+
 /// List participants query
 #[derive(Debug, Deserialize)]
 pub struct ListParticipantsQuery {
@@ -174,6 +466,59 @@ pub struct ListParticipantsQuery {
     pub sort_order: String,
 }
 
+/// List waitlist query
+#[derive(Debug, Deserialize)]
+pub struct ListWaitlistQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+}
+
 fn default_sort_order() -> String {
     "desc".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(json: &str) -> UpdateContestRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_update_keeps_absent_submission_limits() {
+        let payload = update("{}");
+        assert_eq!(payload.submissions_per_hour, None);
+        assert_eq!(payload.max_submissions_per_problem, None);
+    }
+
+    #[test]
+    fn test_update_clears_submission_limits_with_null() {
+        let payload =
+            update(r#"{"submissions_per_hour": null, "max_submissions_per_problem": null}"#);
+        assert_eq!(payload.submissions_per_hour, Some(None));
+        assert_eq!(payload.max_submissions_per_problem, Some(None));
+        assert!(payload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_update_sets_submission_limits() {
+        let payload = update(r#"{"submissions_per_hour": 30, "max_submissions_per_problem": 5}"#);
+        assert_eq!(payload.submissions_per_hour, Some(Some(30)));
+        assert_eq!(payload.max_submissions_per_problem, Some(Some(5)));
+        assert!(payload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_update_rejects_out_of_range_submission_limits() {
+        let errors = update(r#"{"submissions_per_hour": 0, "max_submissions_per_problem": 10001}"#)
+            .validate()
+            .unwrap_err();
+        let fields = errors.field_errors();
+        assert!(fields.contains_key("submissions_per_hour"));
+        assert!(fields.contains_key("max_submissions_per_problem"));
+    }
+}
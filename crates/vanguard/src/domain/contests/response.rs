@@ -1,7 +1,10 @@
 //! Contest response DTOs.
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use olympus_rules::collaborators::CollaboratorPermissions;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Contest summary for list responses
@@ -15,6 +18,9 @@ pub struct ContestSummary {
     pub scoring_type: String,
     pub is_public: bool,
     pub is_rated: bool,
+    pub organization_id: Option<Uuid>,
+    /// Original contest, for a mirror
+    pub mirror_of: Option<Uuid>,
     pub participant_count: i64,
     pub owner: OwnerInfo,
     pub status: String,
@@ -60,6 +66,20 @@ pub struct ContestDetailResponse {
     pub registration_required: bool,
     pub max_participants: Option<i32>,
     pub allowed_languages: Option<Vec<String>>,
+    pub submission_cooldown_secs: i32,
+    pub submissions_per_hour: Option<i32>,
+    pub max_submissions_per_problem: Option<i32>,
+    pub source_visibility: String,
+    pub is_proctored: bool,
+    pub test_data_downloads: bool,
+    pub scoreboard_feed: bool,
+    pub dynamic_min_percent: i32,
+    pub dynamic_decay: i32,
+    pub organization_id: Option<Uuid>,
+    /// Original contest, for a mirror
+    pub mirror_of: Option<Uuid>,
+    /// Start of the open pause, if the contest is paused
+    pub paused_at: Option<DateTime<Utc>>,
     pub owner: OwnerInfo,
     pub participant_count: i64,
     pub problem_count: i64,
@@ -87,6 +107,18 @@ pub struct ContestResponse {
     pub registration_required: bool,
     pub max_participants: Option<i32>,
     pub allowed_languages: Option<Vec<String>>,
+    pub submission_cooldown_secs: i32,
+    pub submissions_per_hour: Option<i32>,
+    pub max_submissions_per_problem: Option<i32>,
+    pub source_visibility: String,
+    pub is_proctored: bool,
+    pub test_data_downloads: bool,
+    pub scoreboard_feed: bool,
+    pub dynamic_min_percent: i32,
+    pub dynamic_decay: i32,
+    pub organization_id: Option<Uuid>,
+    /// Original contest, for a mirror
+    pub mirror_of: Option<Uuid>,
     pub owner_id: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -98,9 +130,8 @@ pub struct CollaboratorInfo {
     pub id: Uuid,
     pub user: OwnerInfo,
     pub role: String,
-    pub can_edit_contest: bool,
-    pub can_add_problems: bool,
-    pub can_view_submissions: bool,
+    #[serde(flatten)]
+    pub permissions: CollaboratorPermissions,
     pub added_at: DateTime<Utc>,
 }
 
@@ -135,7 +166,156 @@ pub struct ParticipantListResponse {
 pub struct RegistrationResponse {
     pub message: String,
     pub contest_id: Uuid,
+    /// `registered`, or `waitlisted` when the contest is full
+    pub status: String,
+    /// When the user registered or joined the waitlist
     pub registered_at: DateTime<Utc>,
+    /// 1-based place in the waitlist (only when waitlisted)
+    pub waitlist_position: Option<i64>,
+}
+
+/// A waitlisted user
+#[derive(Debug, Serialize)]
+pub struct WaitlistEntry {
+    pub position: i64,
+    pub user: OwnerInfo,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Waitlist response
+#[derive(Debug, Serialize)]
+pub struct WaitlistResponse {
+    pub entries: Vec<WaitlistEntry>,
+    pub pagination: Pagination,
+}
+
+/// A participant advancing from the previous round
+#[derive(Debug, Serialize)]
+pub struct CarriedParticipant {
+    pub user: OwnerInfo,
+    /// Rank in the previous round, `None` without any submission
+    pub rank: Option<u32>,
+    pub total_score: f64,
+    pub problems_solved: i64,
+    /// `registered`, `waitlisted` (the contest is full), or
+    /// `already_registered` / `already_waitlisted`
+    pub status: String,
+}
+
+/// Result of carrying participants over
+#[derive(Debug, Serialize)]
+pub struct CarryOverResponse {
+    pub contest_id: Uuid,
+    pub source_contest_id: Uuid,
+    pub dry_run: bool,
+    /// Participants of the previous round the rules were applied to
+    pub evaluated: usize,
+    pub registered: usize,
+    pub waitlisted: usize,
+    /// Advancing participants, best rank first
+    pub participants: Vec<CarriedParticipant>,
+}
+
+/// A contest pause
+#[derive(Debug, Serialize)]
+pub struct ContestPauseInfo {
+    pub id: Uuid,
+    pub reason: Option<String>,
+    pub paused_at: DateTime<Utc>,
+    pub resumed_at: Option<DateTime<Utc>>,
+}
+
+/// Pause state after a pause, resume or extension
+#[derive(Debug, Serialize)]
+pub struct ContestPauseResponse {
+    pub contest_id: Uuid,
+    pub paused: bool,
+    pub end_time: DateTime<Utc>,
+    pub freeze_time: Option<DateTime<Utc>>,
+    /// Total seconds the contest has been paused so far
+    pub paused_seconds: i64,
+    pub pauses: Vec<ContestPauseInfo>,
+}
+
+/// Aggregate statistics of a contest's submissions. While the leaderboard
+/// is frozen only submissions made before the freeze are counted.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContestStatisticsResponse {
+    pub contest_id: Uuid,
+    pub frozen: bool,
+    pub total_submissions: i64,
+    /// Users with at least one submission
+    pub participants: i64,
+    pub problems: Vec<ProblemStatistics>,
+    /// Submission count per status
+    pub verdicts: BTreeMap<String, i64>,
+    pub languages: Vec<LanguageStatistics>,
+    pub timeline: SubmissionTimeline,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A mirror's statistics next to those of its original contest
+#[derive(Debug, Serialize)]
+pub struct MirrorComparisonResponse {
+    pub contest_id: Uuid,
+    pub original_contest_id: Uuid,
+    /// Per problem, in the mirror's order
+    pub problems: Vec<ProblemComparison>,
+    pub mirror: ContestStatisticsResponse,
+    pub original: ContestStatisticsResponse,
+}
+
+/// How one problem went in the mirror and in the original
+#[derive(Debug, Serialize)]
+pub struct ProblemComparison {
+    pub problem_id: Uuid,
+    pub problem_code: String,
+    pub title: String,
+    pub mirror_solved_by: i64,
+    pub original_solved_by: i64,
+    /// Share of the contest's participants who solved it (`None` without
+    /// participants)
+    pub mirror_solve_rate: Option<f64>,
+    pub original_solve_rate: Option<f64>,
+}
+
+/// Statistics of one contest problem
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemStatistics {
+    pub problem_id: Uuid,
+    pub problem_code: String,
+    pub title: String,
+    pub submissions: i64,
+    pub attempted_by: i64,
+    pub solved_by: i64,
+    /// Submission count per status
+    pub verdicts: BTreeMap<String, i64>,
+    /// Mean of the slowest test's time over accepted submissions
+    pub avg_accepted_time_ms: Option<f64>,
+    /// Mean of the peak memory over accepted submissions
+    pub avg_accepted_memory_kb: Option<f64>,
+}
+
+/// Submissions in one language
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LanguageStatistics {
+    pub language: String,
+    pub submissions: i64,
+    pub accepted: i64,
+}
+
+/// Submissions per fixed-width interval from the contest start
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmissionTimeline {
+    pub bucket_minutes: i64,
+    pub buckets: Vec<TimelineBucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimelineBucket {
+    pub start: DateTime<Utc>,
+    pub submissions: i64,
+    pub accepted: i64,
 }
 
 /// Simple message response
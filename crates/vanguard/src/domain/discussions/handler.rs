@@ -0,0 +1,600 @@
+//! Problem discussion handlers.
+//!
+//! Comments form threads: a top-level comment starts one and replies nest
+//! up to [`MAX_COMMENT_DEPTH`] levels. While a contest containing the
+//! problem is running the discussion is locked, and its comments are only
+//! shown to the problem's staff so they cannot give solutions away.
+//! Besides the per-user rate limit on posting and editing, the same text
+//! cannot be posted twice within [`DUPLICATE_WINDOW_MINUTES`].
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::authorization::{
+    build_problem_context, policies, require_problem_modify_access,
+};
+use crate::domain::problems::markdown::render_markdown;
+use crate::domain::problems::require_problem_visible;
+use crate::error::{ApiError, ApiResult};
+use crate::extract::{Guard, ValidatedJson};
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+/// Deepest reply level; top-level comments are level 0
+const MAX_COMMENT_DEPTH: i32 = 5;
+
+/// An author cannot post the same text again within this many minutes
+const DUPLICATE_WINDOW_MINUTES: i32 = 10;
+
+const COMMENT_COLUMNS: &str = "pc.id, pc.problem_id, pc.parent_id, pc.depth, pc.author_id, \
+     u.username as author_username, u.display_name as author_display_name, pc.body, \
+     pc.hidden_at, pc.hidden_reason, pc.deleted_at, pc.created_at, pc.edited_at";
+
+/// Database row for a comment, with its author
+#[derive(Debug, FromRow)]
+struct CommentRow {
+    id: Uuid,
+    problem_id: Uuid,
+    parent_id: Option<Uuid>,
+    depth: i32,
+    author_id: Option<Uuid>,
+    author_username: Option<String>,
+    author_display_name: Option<String>,
+    body: String,
+    hidden_at: Option<DateTime<Utc>>,
+    hidden_reason: Option<String>,
+    deleted_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+}
+
+/// The problem a discussion belongs to
+#[derive(Debug, FromRow)]
+struct DiscussionProblem {
+    is_public: bool,
+    owner_id: Uuid,
+    organization_id: Option<Uuid>,
+    discussion_locked: bool,
+}
+
+/// GET /api/v1/problems/{id}/comments
+///
+/// A page of the problem's threads, newest first, each with all its
+/// replies.
+pub async fn list_comments(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(problem_id): Path<Uuid>,
+    Query(query): Query<ListCommentsQuery>,
+) -> ApiResult<Json<DiscussionResponse>> {
+    let user = user.map(|Extension(u)| u);
+    let problem = load_problem(&state, user.as_ref(), problem_id).await?;
+    let lock = discussion_lock(&state, problem_id, &problem).await?;
+    let is_staff = is_problem_staff(&state, user.as_ref(), problem_id).await;
+
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 50);
+    let offset = ((page - 1) * per_page) as i64;
+
+    if matches!(lock, Some(DiscussionLock::Contest { .. })) && !is_staff {
+        return Ok(Json(DiscussionResponse {
+            problem_id,
+            lock,
+            threads: Vec::new(),
+            pagination: Pagination {
+                page,
+                per_page,
+                total: 0,
+                total_pages: 0,
+            },
+        }));
+    }
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM problem_comments WHERE problem_id = $1 AND parent_id IS NULL",
+    )
+    .bind(problem_id)
+    .fetch_one(state.read_db())
+    .await?;
+
+    let roots = sqlx::query_as::<_, CommentRow>(&format!(
+        r#"
+        SELECT {}
+        FROM problem_comments pc
+        LEFT JOIN users u ON u.id = pc.author_id
+        WHERE pc.problem_id = $1 AND pc.parent_id IS NULL
+        ORDER BY pc.created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(problem_id)
+    .bind(per_page as i64)
+    .bind(offset)
+    .fetch_all(state.read_db())
+    .await?;
+
+    let root_ids: Vec<Uuid> = roots.iter().map(|r| r.id).collect();
+    let replies = sqlx::query_as::<_, CommentRow>(&format!(
+        r#"
+        WITH RECURSIVE thread AS (
+            SELECT c.* FROM problem_comments c WHERE c.parent_id = ANY($1)
+            UNION ALL
+            SELECT c.* FROM problem_comments c JOIN thread t ON c.parent_id = t.id
+        )
+        SELECT {}
+        FROM thread pc
+        LEFT JOIN users u ON u.id = pc.author_id
+        ORDER BY pc.created_at
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(&root_ids)
+    .fetch_all(state.read_db())
+    .await?;
+
+    let mut children: HashMap<Uuid, Vec<CommentRow>> = HashMap::new();
+    for reply in replies {
+        if let Some(parent_id) = reply.parent_id {
+            children.entry(parent_id).or_default().push(reply);
+        }
+    }
+
+    let threads = roots
+        .into_iter()
+        .map(|root| build_thread(root, &mut children, is_staff))
+        .collect();
+
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
+
+    Ok(Json(DiscussionResponse {
+        problem_id,
+        lock,
+        threads,
+        pagination: Pagination {
+            page,
+            per_page,
+            total,
+            total_pages,
+        },
+    }))
+}
+
+/// POST /api/v1/problems/{id}/comments
+///
+/// Start a thread, or reply to a comment with `parent_id`.
+pub async fn create_comment(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreateCommentRequest>,
+) -> ApiResult<(StatusCode, Json<CommentResponse>)> {
+    let problem = load_problem(&state, Some(&user), problem_id).await?;
+    require_unlocked(&state, problem_id, &problem).await?;
+
+    let depth = match payload.parent_id {
+        Some(parent_id) => {
+            let parent = fetch_comment(&state.db, problem_id, parent_id).await?;
+            if parent.deleted_at.is_some() || parent.hidden_at.is_some() {
+                return Err(ApiError::Validation(
+                    "Cannot reply to a removed comment".to_string(),
+                ));
+            }
+            if parent.depth >= MAX_COMMENT_DEPTH {
+                return Err(ApiError::Validation(format!(
+                    "Replies can nest at most {} levels deep",
+                    MAX_COMMENT_DEPTH
+                )));
+            }
+            parent.depth + 1
+        }
+        None => 0,
+    };
+
+    let duplicate: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM problem_comments
+            WHERE author_id = $1 AND body = $2
+              AND created_at > NOW() - make_interval(mins => $3)
+        )
+        "#,
+    )
+    .bind(user.id)
+    .bind(&payload.body)
+    .bind(DUPLICATE_WINDOW_MINUTES)
+    .fetch_one(&state.db)
+    .await?;
+
+    if duplicate {
+        return Err(ApiError::Conflict(
+            "You already posted this comment".to_string(),
+        ));
+    }
+
+    let comment_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO problem_comments (problem_id, parent_id, depth, author_id, body)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(problem_id)
+    .bind(payload.parent_id)
+    .bind(depth)
+    .bind(user.id)
+    .bind(&payload.body)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        comment_id = %comment_id,
+        user_id = %user.id,
+        "Problem comment posted"
+    );
+
+    let row = fetch_comment(&state.db, problem_id, comment_id).await?;
+    Ok((StatusCode::CREATED, Json(row_to_response(row, true))))
+}
+
+/// PUT /api/v1/problems/{id}/comments/{comment_id}
+///
+/// Edit one's own comment.
+pub async fn update_comment(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((problem_id, comment_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<UpdateCommentRequest>,
+) -> ApiResult<Json<CommentResponse>> {
+    let problem = load_problem(&state, Some(&user), problem_id).await?;
+    let existing = fetch_comment(&state.db, problem_id, comment_id).await?;
+
+    if existing.author_id != Some(user.id) {
+        return Err(ApiError::Forbidden);
+    }
+    if existing.deleted_at.is_some() {
+        return Err(ApiError::NotFound("Comment not found".to_string()));
+    }
+    if existing.hidden_at.is_some() {
+        return Err(ApiError::Validation(
+            "Hidden comments cannot be edited".to_string(),
+        ));
+    }
+    require_unlocked(&state, problem_id, &problem).await?;
+
+    sqlx::query("UPDATE problem_comments SET body = $1, edited_at = NOW() WHERE id = $2")
+        .bind(&payload.body)
+        .bind(comment_id)
+        .execute(&state.db)
+        .await?;
+
+    let row = fetch_comment(&state.db, problem_id, comment_id).await?;
+    Ok(Json(row_to_response(row, true)))
+}
+
+/// DELETE /api/v1/problems/{id}/comments/{comment_id}
+///
+/// Delete a comment: the author's own, or any as the problem's staff. Its
+/// replies stay, under a placeholder.
+pub async fn delete_comment(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((problem_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    let existing = fetch_comment(&state.db, problem_id, comment_id).await?;
+    if existing.deleted_at.is_some() {
+        return Err(ApiError::NotFound("Comment not found".to_string()));
+    }
+    if existing.author_id != Some(user.id)
+        && !is_problem_staff(&state, Some(&user), problem_id).await
+    {
+        return Err(ApiError::Forbidden);
+    }
+
+    sqlx::query("UPDATE problem_comments SET deleted_at = NOW(), body = '' WHERE id = $1")
+        .bind(comment_id)
+        .execute(&state.db)
+        .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        comment_id = %comment_id,
+        user_id = %user.id,
+        "Problem comment deleted"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/problems/{id}/comments/{comment_id}/hide
+///
+/// Hide a comment from everyone but the problem's staff.
+pub async fn hide_comment(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ProblemOwner>,
+    Path((problem_id, comment_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<HideCommentRequest>,
+) -> ApiResult<Json<CommentResponse>> {
+    let existing = fetch_comment(&state.db, problem_id, comment_id).await?;
+    if existing.deleted_at.is_some() {
+        return Err(ApiError::NotFound("Comment not found".to_string()));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE problem_comments
+        SET hidden_at = COALESCE(hidden_at, NOW()), hidden_by = $1, hidden_reason = $2
+        WHERE id = $3
+        "#,
+    )
+    .bind(user.id)
+    .bind(&payload.reason)
+    .bind(comment_id)
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        comment_id = %comment_id,
+        user_id = %user.id,
+        reason = ?payload.reason,
+        "Problem comment hidden"
+    );
+
+    let row = fetch_comment(&state.db, problem_id, comment_id).await?;
+    Ok(Json(row_to_response(row, true)))
+}
+
+/// POST /api/v1/problems/{id}/comments/{comment_id}/unhide
+///
+/// Show a hidden comment again.
+pub async fn unhide_comment(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ProblemOwner>,
+    Path((problem_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<CommentResponse>> {
+    let existing = fetch_comment(&state.db, problem_id, comment_id).await?;
+    if existing.hidden_at.is_none() {
+        return Err(ApiError::Conflict("Comment is not hidden".to_string()));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE problem_comments
+        SET hidden_at = NULL, hidden_by = NULL, hidden_reason = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(comment_id)
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        comment_id = %comment_id,
+        user_id = %user.id,
+        "Problem comment unhidden"
+    );
+
+    let row = fetch_comment(&state.db, problem_id, comment_id).await?;
+    Ok(Json(row_to_response(row, true)))
+}
+
+/// PUT /api/v1/problems/{id}/comments/lock
+///
+/// Lock or unlock the discussion by hand. Contest locks apply regardless.
+pub async fn lock_discussion(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ProblemOwner>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<LockDiscussionRequest>,
+) -> ApiResult<Json<DiscussionLockResponse>> {
+    let problem: DiscussionProblem = sqlx::query_as(
+        r#"
+        UPDATE problems SET discussion_locked = $1
+        WHERE id = $2
+        RETURNING is_public, owner_id, organization_id, discussion_locked
+        "#,
+    )
+    .bind(payload.locked)
+    .bind(problem_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        locked = payload.locked,
+        "Problem discussion lock changed"
+    );
+
+    Ok(Json(DiscussionLockResponse {
+        problem_id,
+        discussion_locked: problem.discussion_locked,
+        lock: discussion_lock(&state, problem_id, &problem).await?,
+    }))
+}
+
+/// Load the problem, failing with `404` unless the user can see it.
+async fn load_problem(
+    state: &AppState,
+    user: Option<&AuthUser>,
+    problem_id: Uuid,
+) -> ApiResult<DiscussionProblem> {
+    let problem: DiscussionProblem = sqlx::query_as(
+        "SELECT is_public, owner_id, organization_id, discussion_locked FROM problems WHERE id = $1",
+    )
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    require_problem_visible(
+        state,
+        user,
+        problem_id,
+        problem.is_public,
+        problem.owner_id,
+        problem.organization_id,
+    )
+    .await?;
+
+    Ok(problem)
+}
+
+/// The lock in effect: a running contest containing the problem (until
+/// the last one ends), else a manual lock.
+async fn discussion_lock(
+    state: &AppState,
+    problem_id: Uuid,
+    problem: &DiscussionProblem,
+) -> ApiResult<Option<DiscussionLock>> {
+    let contest_end: Option<DateTime<Utc>> = sqlx::query_scalar(
+        r#"
+        SELECT MAX(c.end_time)
+        FROM contest_problems cp
+        JOIN contests c ON c.id = cp.contest_id
+        WHERE cp.problem_id = $1 AND NOW() BETWEEN c.start_time AND c.end_time
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(match contest_end {
+        Some(until) => Some(DiscussionLock::Contest { until }),
+        None if problem.discussion_locked => Some(DiscussionLock::Moderator),
+        None => None,
+    })
+}
+
+/// Fail with `409` while the discussion is locked.
+async fn require_unlocked(
+    state: &AppState,
+    problem_id: Uuid,
+    problem: &DiscussionProblem,
+) -> ApiResult<()> {
+    match discussion_lock(state, problem_id, problem).await? {
+        Some(DiscussionLock::Contest { .. }) => Err(ApiError::Conflict(
+            "Discussion is locked while a contest containing this problem is running".to_string(),
+        )),
+        Some(DiscussionLock::Moderator) => {
+            Err(ApiError::Conflict("Discussion is locked".to_string()))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Whether the user is the problem's owner or a moderator.
+async fn is_problem_staff(state: &AppState, user: Option<&AuthUser>, problem_id: Uuid) -> bool {
+    match user {
+        Some(u) => {
+            let ctx = build_problem_context(state, u, problem_id);
+            require_problem_modify_access(&ctx).await.is_ok()
+        }
+        None => false,
+    }
+}
+
+async fn fetch_comment(
+    db: &sqlx::PgPool,
+    problem_id: Uuid,
+    comment_id: Uuid,
+) -> ApiResult<CommentRow> {
+    sqlx::query_as::<_, CommentRow>(&format!(
+        r#"
+        SELECT {}
+        FROM problem_comments pc
+        LEFT JOIN users u ON u.id = pc.author_id
+        WHERE pc.id = $1 AND pc.problem_id = $2
+        "#,
+        COMMENT_COLUMNS
+    ))
+    .bind(comment_id)
+    .bind(problem_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Comment not found".to_string()))
+}
+
+/// Attach each comment's replies, taking them out of `children`.
+fn build_thread(
+    row: CommentRow,
+    children: &mut HashMap<Uuid, Vec<CommentRow>>,
+    is_staff: bool,
+) -> CommentResponse {
+    let replies = children.remove(&row.id).unwrap_or_default();
+    let mut comment = row_to_response(row, is_staff);
+    comment.replies = replies
+        .into_iter()
+        .map(|reply| build_thread(reply, children, is_staff))
+        .collect();
+    comment
+}
+
+fn row_to_response(row: CommentRow, is_staff: bool) -> CommentResponse {
+    let deleted = row.deleted_at.is_some();
+    let hidden = row.hidden_at.is_some();
+    let author = match (row.author_id, row.author_username) {
+        (Some(id), Some(username)) if !deleted => Some(UserInfo {
+            id,
+            username,
+            display_name: row.author_display_name,
+        }),
+        _ => None,
+    };
+    let body = (!deleted && (!hidden || is_staff)).then_some(row.body);
+    CommentResponse {
+        id: row.id,
+        problem_id: row.problem_id,
+        parent_id: row.parent_id,
+        author,
+        body_html: body.as_deref().map(|b| render_markdown(b).html),
+        body,
+        hidden,
+        hidden_reason: row.hidden_reason.filter(|_| is_staff),
+        deleted,
+        created_at: row.created_at,
+        edited_at: row.edited_at,
+        replies: Vec::new(),
+    }
+}
+
+/// Discussion routes (optional auth: hidden comments are shown to staff)
+pub fn comment_routes() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    axum::Router::new().route("/{id}/comments", get(list_comments))
+}
+
+/// Posting and editing comments (signed-in users, rate limited)
+pub fn comment_write_routes() -> axum::Router<AppState> {
+    use axum::routing::{post, put};
+
+    axum::Router::new()
+        .route("/{id}/comments", post(create_comment))
+        .route("/{id}/comments/{comment_id}", put(update_comment))
+}
+
+/// Deleting comments and moderation (authors, problem owners, moderators)
+pub fn protected_comment_routes() -> axum::Router<AppState> {
+    use axum::routing::{delete, post, put};
+
+    axum::Router::new()
+        .route("/{id}/comments/lock", put(lock_discussion))
+        .route("/{id}/comments/{comment_id}", delete(delete_comment))
+        .route("/{id}/comments/{comment_id}/hide", post(hide_comment))
+        .route("/{id}/comments/{comment_id}/unhide", post(unhide_comment))
+}
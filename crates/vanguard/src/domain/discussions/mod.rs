@@ -0,0 +1,12 @@
+//! Problem discussion domain module.
+//!
+//! Threaded comments under a problem. Anyone who can see the problem reads
+//! them; signed-in users post and reply. Discussions lock while a contest
+//! containing the problem is running, and the problem's owner and
+//! moderators hide comments and lock threads by hand.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
@@ -0,0 +1,56 @@
+//! Problem discussion request DTOs.
+
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Query parameters for a problem's discussion
+#[derive(Debug, Deserialize)]
+pub struct ListCommentsQuery {
+    /// Page of top-level threads (1-indexed)
+    #[serde(default = "default_page")]
+    pub page: u32,
+    /// Threads per page (at most 50)
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+/// Post a comment
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCommentRequest {
+    /// Markdown
+    #[validate(length(min = 1, max = 10000, message = "Body must be 1-10000 characters"))]
+    pub body: String,
+
+    /// Comment this one replies to; a new thread if omitted
+    pub parent_id: Option<Uuid>,
+}
+
+/// Edit one's own comment
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCommentRequest {
+    #[validate(length(min = 1, max = 10000, message = "Body must be 1-10000 characters"))]
+    pub body: String,
+}
+
+/// Hide a comment
+#[derive(Debug, Deserialize, Validate)]
+pub struct HideCommentRequest {
+    /// Shown to the problem's staff, e.g. "Spoiler"
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+/// Lock or unlock a discussion
+#[derive(Debug, Deserialize, Validate)]
+pub struct LockDiscussionRequest {
+    pub locked: bool,
+}
@@ -0,0 +1,76 @@
+//! Problem discussion response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// User information
+#[derive(Debug, Serialize)]
+pub struct UserInfo {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+}
+
+/// Pagination info
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub total_pages: u32,
+}
+
+/// Why a discussion takes no new comments
+#[derive(Debug, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DiscussionLock {
+    /// A contest containing the problem is running
+    Contest { until: DateTime<Utc> },
+    /// Locked by the problem's owner or a moderator
+    Moderator,
+}
+
+/// A comment with its replies
+#[derive(Debug, Serialize)]
+pub struct CommentResponse {
+    pub id: Uuid,
+    pub problem_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    /// `None` once the comment is deleted or its author's account is gone
+    pub author: Option<UserInfo>,
+    /// Markdown source; `None` for deleted comments and, except for the
+    /// problem's staff, hidden ones
+    pub body: Option<String>,
+    /// Sanitized HTML rendering of `body`
+    pub body_html: Option<String>,
+    pub hidden: bool,
+    /// Only shown to the problem's staff
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden_reason: Option<String>,
+    pub deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Oldest first
+    pub replies: Vec<CommentResponse>,
+}
+
+/// A page of a problem's discussion threads, newest first
+#[derive(Debug, Serialize)]
+pub struct DiscussionResponse {
+    pub problem_id: Uuid,
+    /// Set while no new comments are taken
+    pub lock: Option<DiscussionLock>,
+    pub threads: Vec<CommentResponse>,
+    pub pagination: Pagination,
+}
+
+/// Discussion lock state after a change
+#[derive(Debug, Serialize)]
+pub struct DiscussionLockResponse {
+    pub problem_id: Uuid,
+    /// Locked by hand, regardless of contests
+    pub discussion_locked: bool,
+    /// Lock currently in effect
+    pub lock: Option<DiscussionLock>,
+}
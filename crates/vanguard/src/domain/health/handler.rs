@@ -1,22 +1,43 @@
 //! Health check handlers.
 
 use axum::{extract::State, http::StatusCode, Json};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::state::AppState;
 
+/// Redis key Horus sets while queued judge messages wait too long
+const JUDGING_DEGRADED_KEY: &str = "judging_degraded";
+
 /// Health check response
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
     pub status: &'static str,
+    /// Set when a dependency is down or judging is delayed
+    pub degraded: bool,
     pub version: &'static str,
     pub services: ServiceHealth,
+    /// Present while judging is delayed; the API itself keeps serving
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub judging_delay: Option<JudgingDelay>,
+}
+
+/// Judging delay reported by Horus' queue age check
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JudgingDelay {
+    /// Stream with the oldest unacknowledged message
+    pub stream: String,
+    pub oldest_age_secs: u64,
+    pub max_age_secs: u64,
 }
 
 /// Individual service health status
 #[derive(Debug, Serialize)]
 pub struct ServiceHealth {
     pub database: ServiceStatus,
+    /// Read replica, if configured. Reads fall back to the primary while it
+    /// is down, so it does not affect the overall status.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_replica: Option<ServiceStatus>,
     pub redis: ServiceStatus,
 }
 
@@ -50,7 +71,8 @@ impl ServiceStatus {
 
 /// GET /health
 ///
-/// Returns the health status of the API and its dependencies.
+/// Returns the health status of the API and its dependencies. Responds 503
+/// only when a dependency is down; delayed judging is reported with 200.
 pub async fn health_check(
     State(state): State<AppState>,
 ) -> Result<Json<HealthResponse>, (StatusCode, Json<HealthResponse>)> {
@@ -68,12 +90,39 @@ pub async fn health_check(
         }
     };
 
-    // Check Redis health
+    // Check read replica health
+    let replica_status = if state.replica.is_configured() {
+        Some(match state.replica.pool() {
+            Some(pool) => {
+                let start = std::time::Instant::now();
+                match sqlx::query("SELECT 1").fetch_one(pool).await {
+                    Ok(_) => ServiceStatus::healthy(start.elapsed().as_millis() as u64),
+                    Err(e) => ServiceStatus::unhealthy(e.to_string()),
+                }
+            }
+            None => ServiceStatus::unhealthy("unreachable, reads use the primary".to_string()),
+        })
+    } else {
+        None
+    };
+
+    // Check Redis health, and whether Horus flagged judging as delayed
+    let mut judging_delay = None;
     let redis_status = {
         let start = std::time::Instant::now();
         match state.redis.get().await {
             Ok(mut conn) => match redis::cmd("PING").query_async::<String>(&mut conn).await {
-                Ok(_) => ServiceStatus::healthy(start.elapsed().as_millis() as u64),
+                Ok(_) => {
+                    let latency_ms = start.elapsed().as_millis() as u64;
+                    judging_delay = redis::cmd("GET")
+                        .arg(JUDGING_DEGRADED_KEY)
+                        .query_async::<Option<String>>(&mut conn)
+                        .await
+                        .ok()
+                        .flatten()
+                        .and_then(|raw| serde_json::from_str(&raw).ok());
+                    ServiceStatus::healthy(latency_ms)
+                }
                 Err(e) => {
                     all_healthy = false;
                     ServiceStatus::unhealthy(e.to_string())
@@ -86,15 +135,20 @@ pub async fn health_check(
         }
     };
 
+    let degraded = !all_healthy || judging_delay.is_some();
     let response = HealthResponse {
-        status: if all_healthy { "healthy" } else { "degraded" },
+        status: if degraded { "degraded" } else { "healthy" },
+        degraded,
         version: env!("CARGO_PKG_VERSION"),
         services: ServiceHealth {
             database: db_status,
+            database_replica: replica_status,
             redis: redis_status,
         },
+        judging_delay,
     };
 
+    // A judging delay alone does not take the gateway out of rotation
     if all_healthy {
         Ok(Json(response))
     } else {
@@ -0,0 +1,336 @@
+//! Contest integrity handlers.
+//!
+//! Participants' clients post telemetry batches while a proctored contest
+//! runs; staff holding `can_view_submissions` read the aggregated report.
+
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::authorization::{build_contest_context, policies, require_contest_running};
+use crate::error::{ApiError, ApiResult, FieldError};
+use crate::extract::{Guard, ValidatedJson};
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+/// Maximum number of events in one telemetry batch.
+const MAX_TELEMETRY_EVENTS: usize = 100;
+
+/// Pastes shorter than this many characters are not worth reporting.
+const LARGE_PASTE_CHARS: i32 = 100;
+
+/// Events returned with a single participant's report.
+const MAX_REPORT_EVENTS: i64 = 500;
+
+/// Events are accepted up to this far ahead of the server clock.
+const MAX_CLOCK_SKEW_SECS: i64 = 60;
+
+/// Per-participant aggregation shared by both report endpoints.
+const PARTICIPANT_INTEGRITY_SELECT: &str = r#"
+    SELECT u.id, u.username, u.display_name,
+           COUNT(e.id) FILTER (WHERE e.event_type = 'paste') AS paste_count,
+           COALESCE(SUM(e.amount) FILTER (WHERE e.event_type = 'paste'), 0)::BIGINT
+               AS pasted_chars,
+           COALESCE(MAX(e.amount) FILTER (WHERE e.event_type = 'paste'), 0)::BIGINT
+               AS largest_paste,
+           COUNT(e.id) FILTER (WHERE e.event_type = 'tab_switch') AS tab_switch_count,
+           COALESCE(SUM(e.amount) FILTER (WHERE e.event_type = 'tab_switch'), 0)::BIGINT
+               AS time_away_ms,
+           COUNT(DISTINCT e.submission_id) FILTER (WHERE e.event_type = 'paste')
+               AS submissions_with_pastes,
+           MAX(e.occurred_at) AS last_event_at
+    FROM contest_participants cp
+    JOIN users u ON u.id = cp.user_id
+    LEFT JOIN contest_telemetry_events e
+        ON e.contest_id = cp.contest_id AND e.user_id = cp.user_id
+    WHERE cp.contest_id = $1
+"#;
+
+/// Database row for a participant's telemetry summary
+#[derive(Debug, FromRow)]
+struct ParticipantIntegrityRow {
+    id: Uuid,
+    username: String,
+    display_name: Option<String>,
+    paste_count: i64,
+    pasted_chars: i64,
+    largest_paste: i64,
+    tab_switch_count: i64,
+    time_away_ms: i64,
+    submissions_with_pastes: i64,
+    last_event_at: Option<DateTime<Utc>>,
+}
+
+impl From<ParticipantIntegrityRow> for ParticipantIntegrity {
+    fn from(row: ParticipantIntegrityRow) -> Self {
+        ParticipantIntegrity {
+            user: UserInfo {
+                id: row.id,
+                username: row.username,
+                display_name: row.display_name,
+            },
+            paste_count: row.paste_count,
+            pasted_chars: row.pasted_chars,
+            largest_paste: row.largest_paste,
+            tab_switch_count: row.tab_switch_count,
+            time_away_ms: row.time_away_ms,
+            submissions_with_pastes: row.submissions_with_pastes,
+            last_event_at: row.last_event_at,
+        }
+    }
+}
+
+/// Database row for a stored telemetry event
+#[derive(Debug, FromRow)]
+struct TelemetryEventRow {
+    event_type: String,
+    problem_id: Option<Uuid>,
+    submission_id: Option<Uuid>,
+    amount: i32,
+    occurred_at: DateTime<Utc>,
+}
+
+/// POST /api/v1/contests/{id}/telemetry
+///
+/// Ingest a batch of telemetry events (registered participants of a running
+/// proctored contest). Pastes below the reporting threshold are dropped.
+pub async fn ingest_telemetry(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ContestParticipant>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<TelemetryBatchRequest>,
+) -> ApiResult<Json<TelemetryIngestResponse>> {
+    if payload.events.is_empty() || payload.events.len() > MAX_TELEMETRY_EVENTS {
+        return Err(ApiError::InvalidFields(vec![FieldError {
+            field: "events".to_string(),
+            rule: "length".to_string(),
+            message: format!("A batch holds 1-{} events", MAX_TELEMETRY_EVENTS),
+        }]));
+    }
+
+    let is_proctored: bool = sqlx::query_scalar("SELECT is_proctored FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_one(&state.db)
+        .await?;
+    if !is_proctored {
+        return Err(ApiError::Validation("Contest is not proctored".to_string()));
+    }
+
+    let ctx = build_contest_context(&state, &user, contest_id);
+    require_contest_running(&ctx).await?;
+
+    let latest = Utc::now() + chrono::Duration::seconds(MAX_CLOCK_SKEW_SECS);
+    if payload.events.iter().any(|e| e.occurred_at > latest) {
+        return Err(ApiError::Validation(
+            "Event timestamps must not be in the future".to_string(),
+        ));
+    }
+
+    let total = payload.events.len();
+    let events: Vec<TelemetryEvent> = payload
+        .events
+        .into_iter()
+        .filter(|e| e.event_type != TelemetryEventType::Paste || e.amount >= LARGE_PASTE_CHARS)
+        .collect();
+
+    ensure_own_contest_submissions(&state, contest_id, user.id, &events).await?;
+    ensure_contest_problems(&state, contest_id, &events).await?;
+
+    if !events.is_empty() {
+        let problem_ids: Vec<Option<Uuid>> = events.iter().map(|e| e.problem_id).collect();
+        let submission_ids: Vec<Option<Uuid>> = events.iter().map(|e| e.submission_id).collect();
+        let event_types: Vec<String> = events.iter().map(|e| e.event_type.to_string()).collect();
+        let amounts: Vec<i32> = events.iter().map(|e| e.amount).collect();
+        let occurred_at: Vec<DateTime<Utc>> = events.iter().map(|e| e.occurred_at).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO contest_telemetry_events (
+                contest_id, user_id, problem_id, submission_id, event_type, amount, occurred_at
+            )
+            SELECT $1, $2, e.problem_id, e.submission_id, e.event_type, e.amount, e.occurred_at
+            FROM UNNEST($3::uuid[], $4::uuid[], $5::text[], $6::int[], $7::timestamptz[])
+                AS e(problem_id, submission_id, event_type, amount, occurred_at)
+            "#,
+        )
+        .bind(contest_id)
+        .bind(user.id)
+        .bind(&problem_ids)
+        .bind(&submission_ids)
+        .bind(&event_types)
+        .bind(&amounts)
+        .bind(&occurred_at)
+        .execute(&state.db)
+        .await?;
+    }
+
+    Ok(Json(TelemetryIngestResponse {
+        accepted: events.len(),
+        dropped: total - events.len(),
+    }))
+}
+
+/// Check every referenced submission is the user's own in this contest.
+async fn ensure_own_contest_submissions(
+    state: &AppState,
+    contest_id: Uuid,
+    user_id: Uuid,
+    events: &[TelemetryEvent],
+) -> ApiResult<()> {
+    let ids: Vec<Uuid> = events
+        .iter()
+        .filter_map(|e| e.submission_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let found: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM submissions WHERE id = ANY($1) AND user_id = $2 AND contest_id = $3",
+    )
+    .bind(&ids)
+    .bind(user_id)
+    .bind(contest_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    if found != ids.len() as i64 {
+        return Err(ApiError::Validation(
+            "Events reference submissions that are not yours in this contest".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check every referenced problem belongs to the contest.
+async fn ensure_contest_problems(
+    state: &AppState,
+    contest_id: Uuid,
+    events: &[TelemetryEvent],
+) -> ApiResult<()> {
+    let ids: Vec<Uuid> = events
+        .iter()
+        .filter_map(|e| e.problem_id)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let found: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contest_problems WHERE contest_id = $1 AND problem_id = ANY($2)",
+    )
+    .bind(contest_id)
+    .bind(&ids)
+    .fetch_one(&state.db)
+    .await?;
+
+    if found != ids.len() as i64 {
+        return Err(ApiError::Validation(
+            "Events reference problems outside this contest".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /api/v1/contests/{id}/integrity
+///
+/// Per-participant telemetry summary (owner, admin, or collaborator with
+/// `can_view_submissions`), ordered by pasted volume then tab switches.
+pub async fn get_integrity_report(
+    State(state): State<AppState>,
+    _guard: Guard<policies::ViewAllSources>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<IntegrityReportResponse>> {
+    let sql = format!(
+        "{} GROUP BY u.id, u.username, u.display_name \
+         ORDER BY pasted_chars DESC, tab_switch_count DESC, u.username",
+        PARTICIPANT_INTEGRITY_SELECT
+    );
+    let rows: Vec<ParticipantIntegrityRow> = sqlx::query_as(&sql)
+        .bind(contest_id)
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(IntegrityReportResponse {
+        contest_id,
+        participants: rows.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /api/v1/contests/{id}/integrity/{user_id}
+///
+/// One participant's telemetry summary and most recent events.
+pub async fn get_participant_integrity(
+    State(state): State<AppState>,
+    _guard: Guard<policies::ViewAllSources>,
+    Path((contest_id, user_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<ParticipantIntegrityResponse>> {
+    let sql = format!(
+        "{} AND cp.user_id = $2 GROUP BY u.id, u.username, u.display_name",
+        PARTICIPANT_INTEGRITY_SELECT
+    );
+    let row: ParticipantIntegrityRow = sqlx::query_as(&sql)
+        .bind(contest_id)
+        .bind(user_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Participant not found".to_string()))?;
+
+    let events: Vec<TelemetryEventRow> = sqlx::query_as(
+        r#"
+        SELECT event_type, problem_id, submission_id, amount, occurred_at
+        FROM contest_telemetry_events
+        WHERE contest_id = $1 AND user_id = $2
+        ORDER BY occurred_at DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(contest_id)
+    .bind(user_id)
+    .bind(MAX_REPORT_EVENTS)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(ParticipantIntegrityResponse {
+        contest_id,
+        summary: row.into(),
+        events: events
+            .into_iter()
+            .map(|e| TelemetryEventInfo {
+                event_type: e.event_type,
+                problem_id: e.problem_id,
+                submission_id: e.submission_id,
+                amount: e.amount,
+                occurred_at: e.occurred_at,
+            })
+            .collect(),
+    }))
+}
+
+/// Telemetry ingestion route (participants; rate limited separately)
+pub fn telemetry_routes() -> axum::Router<AppState> {
+    use axum::routing::post;
+
+    axum::Router::new().route("/{id}/telemetry", post(ingest_telemetry))
+}
+
+/// Integrity report routes (contest staff)
+pub fn integrity_routes() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    axum::Router::new()
+        .route("/{id}/integrity", get(get_integrity_report))
+        .route("/{id}/integrity/{user_id}", get(get_participant_integrity))
+}
@@ -0,0 +1,11 @@
+//! Contest integrity domain module.
+//!
+//! Clients of a proctored contest report anti-cheat telemetry (large pastes,
+//! tab switches) while participants work. Organizers read it back as a
+//! per-participant integrity report.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
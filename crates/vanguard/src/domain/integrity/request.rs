@@ -0,0 +1,51 @@
+//! Contest integrity request DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Kind of client-side telemetry event
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryEventType {
+    /// Text pasted into the editor; `amount` is the pasted length in characters
+    Paste,
+    /// The contest tab lost focus; `amount` is the time away in milliseconds
+    TabSwitch,
+}
+
+impl std::fmt::Display for TelemetryEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TelemetryEventType::Paste => write!(f, "paste"),
+            TelemetryEventType::TabSwitch => write!(f, "tab_switch"),
+        }
+    }
+}
+
+/// A single telemetry event
+#[derive(Debug, Deserialize, Validate)]
+pub struct TelemetryEvent {
+    #[serde(rename = "type")]
+    pub event_type: TelemetryEventType,
+
+    /// Problem being worked on, if known
+    pub problem_id: Option<Uuid>,
+
+    /// Submission the event led up to, if already submitted
+    pub submission_id: Option<Uuid>,
+
+    #[validate(range(min = 0, message = "Amount must not be negative"))]
+    pub amount: i32,
+
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Batch of telemetry events from one client
+#[derive(Debug, Deserialize, Validate)]
+pub struct TelemetryBatchRequest {
+    /// 1 to `MAX_TELEMETRY_EVENTS` events, checked by the handler
+    #[validate(nested)]
+    pub events: Vec<TelemetryEvent>,
+}
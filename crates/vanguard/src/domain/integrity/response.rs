@@ -0,0 +1,62 @@
+//! Contest integrity response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// User information
+#[derive(Debug, Serialize)]
+pub struct UserInfo {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+}
+
+/// Telemetry ingestion result
+#[derive(Debug, Serialize)]
+pub struct TelemetryIngestResponse {
+    /// Events stored
+    pub accepted: usize,
+    /// Pastes below the reporting threshold, discarded
+    pub dropped: usize,
+}
+
+/// Telemetry summary of one participant
+#[derive(Debug, Serialize)]
+pub struct ParticipantIntegrity {
+    pub user: UserInfo,
+    pub paste_count: i64,
+    pub pasted_chars: i64,
+    pub largest_paste: i64,
+    pub tab_switch_count: i64,
+    pub time_away_ms: i64,
+    /// Submissions with at least one large paste linked to them
+    pub submissions_with_pastes: i64,
+    pub last_event_at: Option<DateTime<Utc>>,
+}
+
+/// Integrity report of a contest, most suspicious participants first
+#[derive(Debug, Serialize)]
+pub struct IntegrityReportResponse {
+    pub contest_id: Uuid,
+    pub participants: Vec<ParticipantIntegrity>,
+}
+
+/// A stored telemetry event
+#[derive(Debug, Serialize)]
+pub struct TelemetryEventInfo {
+    pub event_type: String,
+    pub problem_id: Option<Uuid>,
+    pub submission_id: Option<Uuid>,
+    pub amount: i32,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Integrity report of one participant with their recent events
+#[derive(Debug, Serialize)]
+pub struct ParticipantIntegrityResponse {
+    pub contest_id: Uuid,
+    #[serde(flatten)]
+    pub summary: ParticipantIntegrity,
+    pub events: Vec<TelemetryEventInfo>,
+}
@@ -1,10 +1,20 @@
 //! Domain modules containing business logic and handlers.
 
 pub mod admin;
+pub mod announcements;
 pub mod auth;
 pub mod authorization;
+pub mod clarifications;
 pub mod contests;
+pub mod discussions;
 pub mod health;
+pub mod integrity;
+pub mod notifications;
+pub mod organizations;
+pub mod posts;
 pub mod problems;
+pub mod roles;
+pub mod runtimes;
+pub mod sheets;
 pub mod submissions;
 pub mod users;
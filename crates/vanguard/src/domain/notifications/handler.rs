@@ -0,0 +1,305 @@
+//! Notification handlers.
+//!
+//! Every endpoint acts on the caller's own feed. Notifications are created
+//! by the services that see the events (Minos for verdicts, Horus for
+//! contests starting) through the `notify_user` database function, which
+//! applies the recipient's email preference.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use olympus_common::NotificationKind;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::extract::ValidatedJson;
+use crate::middleware::auth::AuthUser;
+use crate::pagination::{Cursor, CursorPage, CursorQuery};
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+/// Database row for a notification
+#[derive(Debug, FromRow)]
+struct NotificationRow {
+    id: Uuid,
+    kind: String,
+    title: String,
+    body: String,
+    contest_id: Option<Uuid>,
+    submission_id: Option<Uuid>,
+    email: bool,
+    read_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+/// GET /api/v1/notifications
+///
+/// The caller's notifications, newest first, with the unread count.
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<ListNotificationsQuery>,
+) -> ApiResult<Json<NotificationListResponse>> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+
+    let rows = sqlx::query_as::<_, NotificationRow>(
+        r#"
+        SELECT id, kind, title, body, contest_id, submission_id, email, read_at, created_at
+        FROM notifications
+        WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(user.id)
+    .bind(query.unread_only)
+    .bind(per_page as i64)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    let (total, unread_count): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FILTER (WHERE NOT $2 OR read_at IS NULL),
+               COUNT(*) FILTER (WHERE read_at IS NULL)
+        FROM notifications
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(user.id)
+    .bind(query.unread_only)
+    .fetch_one(&state.db)
+    .await?;
+
+    let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
+
+    Ok(Json(NotificationListResponse {
+        notifications: rows.into_iter().map(row_to_response).collect(),
+        unread_count,
+        pagination: Pagination {
+            page,
+            per_page,
+            total,
+            total_pages,
+        },
+    }))
+}
+
+/// GET /api/v2/notifications
+///
+/// The caller's notifications, newest first, paginated by cursor, with the
+/// unread count.
+pub async fn list_notifications_v2(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(page): Query<CursorQuery>,
+    Query(query): Query<NotificationFeedQuery>,
+) -> ApiResult<Json<NotificationFeedResponse>> {
+    let after = page.after()?;
+
+    let rows = sqlx::query_as::<_, NotificationRow>(
+        r#"
+        SELECT id, kind, title, body, contest_id, submission_id, email, read_at, created_at
+        FROM notifications
+        WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+          AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(user.id)
+    .bind(query.unread_only)
+    .bind(after.map(|c| c.at))
+    .bind(after.map(|c| c.id))
+    .bind(page.fetch_limit())
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(NotificationFeedResponse {
+        page: CursorPage::from_rows(
+            rows,
+            &page,
+            |row| Cursor::new(row.created_at, row.id),
+            row_to_response,
+        ),
+        unread_count: unread_count(&state.db, user.id).await?,
+    }))
+}
+
+/// GET /api/v1/notifications/unread-count
+///
+/// Number of unread notifications, for polling a badge.
+pub async fn get_unread_count(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Json<UnreadCountResponse>> {
+    Ok(Json(UnreadCountResponse {
+        unread_count: unread_count(&state.db, user.id).await?,
+    }))
+}
+
+/// POST /api/v1/notifications/{id}/read
+///
+/// Mark one of the caller's notifications read. Marking a read one again
+/// keeps its original `read_at`.
+pub async fn mark_notification_read(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let result = sqlx::query(
+        "UPDATE notifications SET read_at = COALESCE(read_at, NOW()) \
+         WHERE id = $1 AND user_id = $2",
+    )
+    .bind(id)
+    .bind(user.id)
+    .execute(&state.db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Notification not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/notifications/read-all
+///
+/// Mark every unread notification of the caller read.
+pub async fn mark_all_notifications_read(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Json<MarkAllReadResponse>> {
+    let result = sqlx::query(
+        "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL",
+    )
+    .bind(user.id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Json(MarkAllReadResponse {
+        marked: result.rows_affected(),
+    }))
+}
+
+/// GET /api/v1/notifications/preferences
+///
+/// The caller's delivery preference for every kind of notification.
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Json<NotificationPreferencesResponse>> {
+    Ok(Json(load_preferences(&state.db, user.id).await?))
+}
+
+/// PUT /api/v1/notifications/preferences
+///
+/// Choose which kinds of notification are also sent by email. Kinds not
+/// listed keep their current setting.
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<UpdateNotificationPreferencesRequest>,
+) -> ApiResult<Json<NotificationPreferencesResponse>> {
+    let mut tx = state.db.begin().await?;
+    for preference in &payload.preferences {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_preferences (user_id, kind, email)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, kind) DO UPDATE SET email = EXCLUDED.email
+            "#,
+        )
+        .bind(user.id)
+        .bind(preference.kind.as_str())
+        .bind(preference.email)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(load_preferences(&state.db, user.id).await?))
+}
+
+async fn unread_count(db: &PgPool, user_id: Uuid) -> ApiResult<i64> {
+    let (count,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL")
+            .bind(user_id)
+            .fetch_one(db)
+            .await?;
+    Ok(count)
+}
+
+/// Every kind with the user's setting, in-app only where none is stored
+async fn load_preferences(
+    db: &PgPool,
+    user_id: Uuid,
+) -> ApiResult<NotificationPreferencesResponse> {
+    let stored: Vec<(String, bool)> =
+        sqlx::query_as("SELECT kind, email FROM notification_preferences WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(db)
+            .await?;
+
+    let preferences = NotificationKind::ALL
+        .into_iter()
+        .map(|kind| NotificationPreference {
+            kind,
+            email: stored
+                .iter()
+                .any(|(stored_kind, email)| *email && stored_kind == kind.as_str()),
+        })
+        .collect();
+
+    Ok(NotificationPreferencesResponse { preferences })
+}
+
+fn row_to_response(row: NotificationRow) -> NotificationResponse {
+    NotificationResponse {
+        id: row.id,
+        kind: row.kind,
+        title: row.title,
+        body: row.body,
+        contest_id: row.contest_id,
+        submission_id: row.submission_id,
+        email: row.email,
+        read_at: row.read_at,
+        created_at: row.created_at,
+    }
+}
+
+/// Notification routes (all require authentication)
+pub fn notification_routes() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    shared_notification_routes().route("/", get(list_notifications))
+}
+
+/// v2 notification routes: the feed is paginated by cursor
+pub fn notification_routes_v2() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    shared_notification_routes().route("/", get(list_notifications_v2))
+}
+
+/// Notification routes unchanged between API versions
+fn shared_notification_routes() -> axum::Router<AppState> {
+    use axum::routing::{get, post};
+
+    axum::Router::new()
+        .route("/unread-count", get(get_unread_count))
+        .route("/read-all", post(mark_all_notifications_read))
+        .route(
+            "/preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route("/{id}/read", post(mark_notification_read))
+}
@@ -0,0 +1,11 @@
+//! Notification feed domain module.
+//!
+//! Services add entries to a user's feed when something happens that concerns
+//! them (a verdict is ready, a contest they registered for starts soon). Users
+//! read and dismiss them here and choose which kinds are also sent by email.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
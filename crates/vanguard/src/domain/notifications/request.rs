@@ -0,0 +1,50 @@
+//! Notification request DTOs.
+
+use olympus_common::NotificationKind;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Query parameters for the notification feed
+#[derive(Debug, Deserialize)]
+pub struct ListNotificationsQuery {
+    /// Page number (1-indexed)
+    #[serde(default = "default_page")]
+    pub page: u32,
+    /// Items per page
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+    /// Only return notifications not yet read
+    #[serde(default)]
+    pub unread_only: bool,
+}
+
+/// Filters of the v2 notification feed, next to its `CursorQuery`
+#[derive(Debug, Deserialize)]
+pub struct NotificationFeedQuery {
+    /// Only return notifications not yet read
+    #[serde(default)]
+    pub unread_only: bool,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+/// Delivery preference for one kind of notification
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationPreferenceInput {
+    pub kind: NotificationKind,
+    /// Also send this kind by email (always shown in-app)
+    pub email: bool,
+}
+
+/// Update delivery preferences; kinds not listed keep their setting
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateNotificationPreferencesRequest {
+    #[validate(length(min = 1, max = 16, message = "Provide 1-16 preferences"))]
+    pub preferences: Vec<NotificationPreferenceInput>,
+}
@@ -0,0 +1,73 @@
+//! Notification response DTOs.
+
+use chrono::{DateTime, Utc};
+use olympus_common::NotificationKind;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::pagination::CursorPage;
+
+/// Pagination info
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub total_pages: u32,
+}
+
+/// One entry of the notification feed
+#[derive(Debug, Serialize)]
+pub struct NotificationResponse {
+    pub id: Uuid,
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub contest_id: Option<Uuid>,
+    pub submission_id: Option<Uuid>,
+    /// Whether it is (or was) also sent by email
+    pub email: bool,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Notification feed, newest first
+#[derive(Debug, Serialize)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<NotificationResponse>,
+    pub unread_count: i64,
+    pub pagination: Pagination,
+}
+
+/// v2 notification feed, newest first, paginated by cursor
+#[derive(Debug, Serialize)]
+pub struct NotificationFeedResponse {
+    #[serde(flatten)]
+    pub page: CursorPage<NotificationResponse>,
+    pub unread_count: i64,
+}
+
+/// Number of unread notifications
+#[derive(Debug, Serialize)]
+pub struct UnreadCountResponse {
+    pub unread_count: i64,
+}
+
+/// Result of marking every notification read
+#[derive(Debug, Serialize)]
+pub struct MarkAllReadResponse {
+    pub marked: u64,
+}
+
+/// Delivery preference for one kind of notification
+#[derive(Debug, Serialize)]
+pub struct NotificationPreference {
+    pub kind: NotificationKind,
+    pub email: bool,
+}
+
+/// Delivery preferences for every kind of notification
+#[derive(Debug, Serialize)]
+pub struct NotificationPreferencesResponse {
+    pub preferences: Vec<NotificationPreference>,
+}
@@ -0,0 +1,649 @@
+//! Organization handlers.
+//!
+//! Organizers create organizations and become their owner. Owners and
+//! organization admins (and site admins) manage the organization and its
+//! members; users can also join with the organization's join code.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use olympus_rules::roles::Permission;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::domain::authorization::{build_auth_context, require_permission};
+use crate::error::{self, ApiError, ApiResult};
+use crate::extract::ValidatedJson;
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+use super::tenancy::TENANT_STORAGE_SQL;
+
+/// Database row for an organization with owner info
+#[derive(Debug, FromRow)]
+struct OrganizationRow {
+    id: Uuid,
+    slug: String,
+    name: String,
+    description: Option<String>,
+    join_code: Option<String>,
+    owner_id: Uuid,
+    owner_username: String,
+    owner_display_name: Option<String>,
+    member_count: i64,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Role of a user in an organization, if they are a member.
+pub(crate) async fn member_role(
+    db: &PgPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> ApiResult<Option<String>> {
+    let role = sqlx::query_scalar(
+        "SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(role)
+}
+
+/// Require that the user may manage an organization (site admin, or the
+/// organization's owner or admin).
+pub(crate) async fn require_organization_manager(
+    db: &PgPool,
+    organization_id: Uuid,
+    user: &AuthUser,
+) -> ApiResult<()> {
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM organizations WHERE id = $1)")
+            .bind(organization_id)
+            .fetch_one(db)
+            .await?;
+
+    if !exists {
+        return Err(ApiError::NotFound("Organization not found".to_string()));
+    }
+
+    if user.can(Permission::ManagePlatform) {
+        return Ok(());
+    }
+
+    match member_role(db, organization_id, user.id).await?.as_deref() {
+        Some("owner" | "admin") => Ok(()),
+        _ => Err(ApiError::Forbidden),
+    }
+}
+
+/// GET /api/v1/organizations
+///
+/// List organizations with pagination and search.
+pub async fn list_organizations(
+    State(state): State<AppState>,
+    Query(query): Query<ListOrganizationsQuery>,
+) -> ApiResult<Json<OrganizationListResponse>> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+    let pattern = query.search.as_ref().map(|s| format!("%{}%", s));
+
+    let rows = sqlx::query_as::<_, (Uuid, String, String, i64, DateTime<Utc>)>(
+        r#"
+        SELECT o.id, o.slug, o.name,
+               (SELECT COUNT(*) FROM organization_members m WHERE m.organization_id = o.id),
+               o.created_at
+        FROM organizations o
+        WHERE ($1::text IS NULL OR o.name ILIKE $1 OR o.slug ILIKE $1)
+        ORDER BY o.name
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(&pattern)
+    .bind(per_page as i64)
+    .bind(offset)
+    .fetch_all(state.read_db())
+    .await?;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM organizations o WHERE ($1::text IS NULL OR o.name ILIKE $1 OR o.slug ILIKE $1)",
+    )
+    .bind(&pattern)
+    .fetch_one(state.read_db())
+    .await?;
+
+    let organizations = rows
+        .into_iter()
+        .map(
+            |(id, slug, name, member_count, created_at)| OrganizationSummary {
+                id,
+                slug,
+                name,
+                member_count,
+                created_at,
+            },
+        )
+        .collect();
+
+    Ok(Json(OrganizationListResponse {
+        organizations,
+        pagination: Pagination {
+            page,
+            per_page,
+            total,
+            total_pages: ((total as f64) / (per_page as f64)).ceil() as u32,
+        },
+    }))
+}
+
+/// POST /api/v1/organizations
+///
+/// Create an organization (organizer/admin only). The creator becomes its owner.
+pub async fn create_organization(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<CreateOrganizationRequest>,
+) -> ApiResult<(StatusCode, Json<OrganizationResponse>)> {
+    let ctx = build_auth_context(&state, &user);
+    require_permission(&ctx, Permission::CreateOrganizations).await?;
+
+    let taken: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM organizations WHERE slug = $1)")
+            .bind(&payload.slug)
+            .fetch_one(&state.db)
+            .await?;
+
+    if taken {
+        return Err(ApiError::Conflict(format!(
+            "Organization slug '{}' is already taken",
+            payload.slug
+        )));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    let organization_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO organizations (slug, name, description, join_code, owner_id)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(&payload.slug)
+    .bind(&payload.name)
+    .bind(&payload.description)
+    .bind(&payload.join_code)
+    .bind(user.id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(error::conflict_on_unique(format!(
+        "Organization slug '{}' is already taken",
+        payload.slug
+    )))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO organization_members (organization_id, user_id, role, added_by)
+        VALUES ($1, $2, 'owner', $2)
+        "#,
+    )
+    .bind(organization_id)
+    .bind(user.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        user_id = %user.id,
+        organization_id = %organization_id,
+        slug = %payload.slug,
+        "Created organization"
+    );
+
+    let organization = fetch_organization(&state.db, organization_id, Some(&user)).await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+/// GET /api/v1/organizations/{id}
+///
+/// Get organization details. The join code is only shown to its managers.
+pub async fn get_organization(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(organization_id): Path<Uuid>,
+) -> ApiResult<Json<OrganizationResponse>> {
+    let viewer = user.as_ref().map(|Extension(u)| u);
+    Ok(Json(
+        fetch_organization(&state.db, organization_id, viewer).await?,
+    ))
+}
+
+/// PUT /api/v1/organizations/{id}
+///
+/// Partially update an organization (owner, organization admin, or site admin).
+pub async fn update_organization(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(organization_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateOrganizationRequest>,
+) -> ApiResult<Json<OrganizationResponse>> {
+    require_organization_manager(&state.db, organization_id, &user).await?;
+
+    // `join_code: ""` disables joining by code; omitting it keeps the current one
+    let clear_join_code = payload.join_code.as_deref() == Some("");
+    let join_code = payload.join_code.as_deref().filter(|c| !c.is_empty());
+    if join_code.is_some_and(|c| c.len() < 6) {
+        return Err(ApiError::Validation(
+            "Join code must be 6-64 characters".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE organizations
+        SET name = COALESCE($1, name),
+            description = COALESCE($2, description),
+            join_code = CASE WHEN $3 THEN NULL ELSE COALESCE($4, join_code) END,
+            updated_at = NOW()
+        WHERE id = $5
+        "#,
+    )
+    .bind(&payload.name)
+    .bind(&payload.description)
+    .bind(clear_join_code)
+    .bind(join_code)
+    .bind(organization_id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Json(
+        fetch_organization(&state.db, organization_id, Some(&user)).await?,
+    ))
+}
+
+/// DELETE /api/v1/organizations/{id}
+///
+/// Delete an organization (owner or site admin). Hosted contests are kept
+/// but no longer restricted to members.
+pub async fn delete_organization(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(organization_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let owner_id: Uuid = sqlx::query_scalar("SELECT owner_id FROM organizations WHERE id = $1")
+        .bind(organization_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Organization not found".to_string()))?;
+
+    if owner_id != user.id && !user.can(Permission::ManagePlatform) {
+        return Err(ApiError::Forbidden);
+    }
+
+    sqlx::query("DELETE FROM organizations WHERE id = $1")
+        .bind(organization_id)
+        .execute(&state.db)
+        .await?;
+
+    tracing::info!(
+        user_id = %user.id,
+        organization_id = %organization_id,
+        "Deleted organization"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/organizations/{id}/members
+///
+/// List members (members and site admins only).
+pub async fn list_members(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(organization_id): Path<Uuid>,
+) -> ApiResult<Json<MemberListResponse>> {
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM organizations WHERE id = $1)")
+            .bind(organization_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    if !exists {
+        return Err(ApiError::NotFound("Organization not found".to_string()));
+    }
+
+    if !user.can(Permission::ViewPrivateContent)
+        && member_role(&state.db, organization_id, user.id)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::Forbidden);
+    }
+
+    let rows = sqlx::query_as::<_, (Uuid, String, Option<String>, String, DateTime<Utc>)>(
+        r#"
+        SELECT u.id, u.username, u.display_name, m.role, m.joined_at
+        FROM organization_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1
+        ORDER BY CASE m.role WHEN 'owner' THEN 0 WHEN 'admin' THEN 1 ELSE 2 END,
+                 u.username
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let members = rows
+        .into_iter()
+        .map(|(id, username, display_name, role, joined_at)| MemberInfo {
+            user: UserInfo {
+                id,
+                username,
+                display_name,
+            },
+            role,
+            joined_at,
+        })
+        .collect();
+
+    Ok(Json(MemberListResponse { members }))
+}
+
+/// POST /api/v1/organizations/{id}/members
+///
+/// Add a member (owner, organization admin, or site admin).
+pub async fn add_member(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(organization_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<AddMemberRequest>,
+) -> ApiResult<(StatusCode, Json<MemberInfo>)> {
+    require_organization_manager(&state.db, organization_id, &user).await?;
+
+    let target = sqlx::query_as::<_, (Uuid, String, Option<String>)>(
+        "SELECT id, username, display_name FROM users WHERE id = $1",
+    )
+    .bind(payload.user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+    let joined_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        r#"
+        INSERT INTO organization_members (organization_id, user_id, role, added_by)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (organization_id, user_id) DO NOTHING
+        RETURNING joined_at
+        "#,
+    )
+    .bind(organization_id)
+    .bind(payload.user_id)
+    .bind(&payload.role)
+    .bind(user.id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let joined_at = joined_at.ok_or_else(|| {
+        ApiError::Conflict("User is already a member of this organization".to_string())
+    })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(MemberInfo {
+            user: UserInfo {
+                id: target.0,
+                username: target.1,
+                display_name: target.2,
+            },
+            role: payload.role,
+            joined_at,
+        }),
+    ))
+}
+
+/// DELETE /api/v1/organizations/{id}/members/{user_id}
+///
+/// Remove a member (managers), or leave the organization (the member
+/// themselves). The owner cannot be removed.
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((organization_id, target_user_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    if target_user_id != user.id {
+        require_organization_manager(&state.db, organization_id, &user).await?;
+    }
+
+    let role = member_role(&state.db, organization_id, target_user_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Member not found".to_string()))?;
+
+    if role == "owner" {
+        return Err(ApiError::Validation(
+            "The organization owner cannot be removed".to_string(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2")
+        .bind(organization_id)
+        .bind(target_user_id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/organizations/{id}/join
+///
+/// Join an organization with its join code.
+pub async fn join_organization(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(organization_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<JoinOrganizationRequest>,
+) -> ApiResult<(StatusCode, Json<OrganizationResponse>)> {
+    let join_code: Option<String> =
+        sqlx::query_scalar("SELECT join_code FROM organizations WHERE id = $1")
+            .bind(organization_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Organization not found".to_string()))?;
+
+    if join_code.as_deref() != Some(payload.join_code.as_str()) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO organization_members (organization_id, user_id, role)
+        VALUES ($1, $2, 'member')
+        ON CONFLICT (organization_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(organization_id)
+    .bind(user.id)
+    .execute(&state.db)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Err(ApiError::Conflict(
+            "Already a member of this organization".to_string(),
+        ));
+    }
+
+    let organization = fetch_organization(&state.db, organization_id, Some(&user)).await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+/// GET /api/v1/organizations/{id}/usage
+///
+/// Tenant limits and current usage (organization managers and site admins).
+pub async fn get_tenant_usage(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(organization_id): Path<Uuid>,
+) -> ApiResult<Json<TenantUsageResponse>> {
+    require_organization_manager(&state.db, organization_id, &user).await?;
+    Ok(Json(fetch_tenant_usage(&state.db, organization_id).await?))
+}
+
+/// PUT /api/v1/organizations/{id}/limits
+///
+/// Set an organization's submission rate limit and storage quota (site
+/// admins only).
+pub async fn update_tenant_limits(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(organization_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateTenantLimitsRequest>,
+) -> ApiResult<Json<TenantUsageResponse>> {
+    if !user.can(Permission::ManagePlatform) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE organizations
+        SET submissions_per_minute = $1, storage_quota_bytes = $2, updated_at = NOW()
+        WHERE id = $3
+        "#,
+    )
+    .bind(payload.submissions_per_minute)
+    .bind(payload.storage_quota_bytes)
+    .bind(organization_id)
+    .execute(&state.db)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Organization not found".to_string()));
+    }
+
+    tracing::info!(
+        admin_id = %user.id,
+        organization_id = %organization_id,
+        submissions_per_minute = ?payload.submissions_per_minute,
+        storage_quota_bytes = ?payload.storage_quota_bytes,
+        "Updated tenant limits"
+    );
+
+    Ok(Json(fetch_tenant_usage(&state.db, organization_id).await?))
+}
+
+/// Load an organization's tenant limits and usage.
+async fn fetch_tenant_usage(db: &PgPool, organization_id: Uuid) -> ApiResult<TenantUsageResponse> {
+    let (submissions_per_minute, storage_quota_bytes): (Option<i32>, Option<i64>) = sqlx::query_as(
+        "SELECT submissions_per_minute, storage_quota_bytes FROM organizations WHERE id = $1",
+    )
+    .bind(organization_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Organization not found".to_string()))?;
+
+    let storage_used_bytes: i64 = sqlx::query_scalar(TENANT_STORAGE_SQL)
+        .bind(organization_id)
+        .fetch_one(db)
+        .await?;
+
+    let (submission_count, problem_count): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT (SELECT COUNT(*) FROM submissions WHERE organization_id = $1),
+               (SELECT COUNT(*) FROM problems WHERE organization_id = $1)
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_one(db)
+    .await?;
+
+    Ok(TenantUsageResponse {
+        organization_id,
+        submissions_per_minute,
+        storage_quota_bytes,
+        storage_used_bytes,
+        submission_count,
+        problem_count,
+    })
+}
+
+/// Load an organization as seen by `viewer`.
+async fn fetch_organization(
+    db: &PgPool,
+    organization_id: Uuid,
+    viewer: Option<&AuthUser>,
+) -> ApiResult<OrganizationResponse> {
+    let row = sqlx::query_as::<_, OrganizationRow>(
+        r#"
+        SELECT o.id, o.slug, o.name, o.description, o.join_code,
+               u.id as owner_id, u.username as owner_username,
+               u.display_name as owner_display_name,
+               (SELECT COUNT(*) FROM organization_members m
+                WHERE m.organization_id = o.id) as member_count,
+               o.created_at, o.updated_at
+        FROM organizations o
+        JOIN users u ON u.id = o.owner_id
+        WHERE o.id = $1
+        "#,
+    )
+    .bind(organization_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Organization not found".to_string()))?;
+
+    let my_role = match viewer {
+        Some(viewer) => member_role(db, organization_id, viewer.id).await?,
+        None => None,
+    };
+    let is_manager = viewer.is_some_and(|v| v.can(Permission::ManagePlatform))
+        || matches!(my_role.as_deref(), Some("owner" | "admin"));
+
+    Ok(OrganizationResponse {
+        id: row.id,
+        slug: row.slug,
+        name: row.name,
+        description: row.description,
+        owner: UserInfo {
+            id: row.owner_id,
+            username: row.owner_username,
+            display_name: row.owner_display_name,
+        },
+        member_count: row.member_count,
+        joinable: row.join_code.is_some(),
+        join_code: row.join_code.filter(|_| is_manager),
+        my_role,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    })
+}
+
+/// Create routes for organizations
+pub fn organization_routes() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    axum::Router::new()
+        .route("/", get(list_organizations))
+        .route("/{id}", get(get_organization))
+}
+
+pub fn protected_organization_routes() -> axum::Router<AppState> {
+    use axum::routing::{delete, get, post, put};
+
+    axum::Router::new()
+        .route("/", post(create_organization))
+        .route("/{id}", put(update_organization))
+        .route("/{id}", delete(delete_organization))
+        .route("/{id}/members", get(list_members))
+        .route("/{id}/members", post(add_member))
+        .route("/{id}/members/{user_id}", delete(remove_member))
+        .route("/{id}/join", post(join_organization))
+        .route("/{id}/usage", get(get_tenant_usage))
+        .route("/{id}/limits", put(update_tenant_limits))
+}
@@ -0,0 +1,17 @@
+//! Organizations domain module.
+//!
+//! Organizations (universities, companies, training groups) have members and
+//! can host contests. A non-public contest hosted by an organization is only
+//! visible to its members, and contest leaderboards can be filtered to one
+//! organization's members.
+//!
+//! Organizations are also tenants: they can own problems, their submissions
+//! are tagged for the judge workers, and site admins can cap their submission
+//! rate and storage (see [`tenancy`]).
+
+pub mod handler;
+pub mod request;
+pub mod response;
+pub mod tenancy;
+
+pub use handler::*;
@@ -0,0 +1,119 @@
+//! Organization request DTOs.
+
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Create organization request
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrganizationRequest {
+    /// URL-friendly identifier (e.g. `mit`, `acme-training`)
+    #[validate(custom(function = "validate_slug"))]
+    pub slug: String,
+
+    #[validate(length(min = 2, max = 255, message = "Name must be 2-255 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 5000, message = "Description must be at most 5000 characters"))]
+    pub description: Option<String>,
+
+    /// Code users can join with (omit for invitation only)
+    #[validate(length(min = 6, max = 64, message = "Join code must be 6-64 characters"))]
+    pub join_code: Option<String>,
+}
+
+/// Update organization request (partial update)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateOrganizationRequest {
+    #[validate(length(min = 2, max = 255, message = "Name must be 2-255 characters"))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 5000, message = "Description must be at most 5000 characters"))]
+    pub description: Option<String>,
+
+    /// New join code; an empty string disables joining by code
+    #[validate(length(max = 64, message = "Join code must be at most 64 characters"))]
+    pub join_code: Option<String>,
+}
+
+/// List organizations query parameters
+#[derive(Debug, Deserialize)]
+pub struct ListOrganizationsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+
+    /// Search by name or slug
+    pub search: Option<String>,
+}
+
+/// Add member request
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddMemberRequest {
+    pub user_id: Uuid,
+
+    #[serde(default = "default_member_role")]
+    #[validate(custom(function = "validate_member_role"))]
+    pub role: String,
+}
+
+/// Join organization request
+#[derive(Debug, Deserialize, Validate)]
+pub struct JoinOrganizationRequest {
+    #[validate(length(min = 1, message = "Join code is required"))]
+    pub join_code: String,
+}
+
+/// Tenant limits request (site admins). Both limits are replaced; `null`
+/// removes a limit.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTenantLimitsRequest {
+    #[validate(range(min = 1, message = "Submission rate limit must be positive"))]
+    pub submissions_per_minute: Option<i32>,
+
+    #[validate(range(min = 1, message = "Storage quota must be positive"))]
+    pub storage_quota_bytes: Option<i64>,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
+
+fn default_member_role() -> String {
+    "member".to_string()
+}
+
+fn validate_slug(slug: &str) -> Result<(), validator::ValidationError> {
+    let valid = slug.len() >= 2
+        && slug.len() <= 50
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+
+    if valid {
+        Ok(())
+    } else {
+        let mut err = validator::ValidationError::new("invalid_slug");
+        err.message = Some("Slug must be 2-50 lowercase letters, digits or inner hyphens".into());
+        Err(err)
+    }
+}
+
+fn validate_member_role(role: &str) -> Result<(), validator::ValidationError> {
+    match role {
+        "admin" | "member" => Ok(()),
+        _ => {
+            let mut err = validator::ValidationError::new("invalid_role");
+            err.message = Some("Role must be admin or member".into());
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Organization response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// User information
+#[derive(Debug, Serialize)]
+pub struct UserInfo {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+}
+
+/// Organization summary for list responses
+#[derive(Debug, Serialize)]
+pub struct OrganizationSummary {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Organization list response
+#[derive(Debug, Serialize)]
+pub struct OrganizationListResponse {
+    pub organizations: Vec<OrganizationSummary>,
+    pub pagination: Pagination,
+}
+
+/// Pagination info
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub total_pages: u32,
+}
+
+/// Full organization details
+#[derive(Debug, Serialize)]
+pub struct OrganizationResponse {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner: UserInfo,
+    pub member_count: i64,
+    /// Whether users can join with a code
+    pub joinable: bool,
+    /// Only shown to organization admins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub join_code: Option<String>,
+    /// Caller's role in the organization, if a member
+    pub my_role: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Organization member
+#[derive(Debug, Serialize)]
+pub struct MemberInfo {
+    pub user: UserInfo,
+    pub role: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Member list response
+#[derive(Debug, Serialize)]
+pub struct MemberListResponse {
+    pub members: Vec<MemberInfo>,
+}
+
+/// Tenant limits and current usage
+#[derive(Debug, Serialize)]
+pub struct TenantUsageResponse {
+    pub organization_id: Uuid,
+    pub submissions_per_minute: Option<i32>,
+    pub storage_quota_bytes: Option<i64>,
+    pub storage_used_bytes: i64,
+    pub submission_count: i64,
+    pub problem_count: i64,
+}
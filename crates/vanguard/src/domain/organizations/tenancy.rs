@@ -0,0 +1,114 @@
+//! Organizations as tenants.
+//!
+//! A submission belongs to the tenant of its contest, or for standalone
+//! submissions to the tenant of its problem. The tenant is stored on the
+//! submission and tagged on judge stream messages, and its submission rate
+//! limit and storage quota (set by site admins) are enforced at submit time.
+
+use olympus_rules::roles::Permission;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::middleware::rate_limit::check_rate_limit;
+use crate::state::AppState;
+
+use super::handler::member_role;
+
+/// Window of the per-tenant submission rate limit.
+const TENANT_RATE_WINDOW_SECS: u64 = 60;
+
+/// Bytes a tenant currently stores: submission sources and uploads plus
+/// compiled artifacts.
+pub(crate) const TENANT_STORAGE_SQL: &str = r#"
+    SELECT (
+        COALESCE((SELECT SUM(COALESCE(s.file_size_bytes, 0)
+                             + COALESCE(OCTET_LENGTH(s.source_code), 0))
+                  FROM submissions s WHERE s.organization_id = $1), 0)
+        + COALESCE((SELECT SUM(a.size_bytes)
+                    FROM submission_artifacts a
+                    JOIN submissions s ON s.id = a.submission_id
+                    WHERE s.organization_id = $1), 0)
+    )::BIGINT
+"#;
+
+/// Tenant limits configured by site admins
+#[derive(Debug, sqlx::FromRow)]
+struct TenantLimitsRow {
+    submissions_per_minute: Option<i32>,
+    storage_quota_bytes: Option<i64>,
+}
+
+/// Tenant a submission is made under: the contest's organization, or the
+/// problem's for standalone submissions.
+pub async fn submission_tenant(
+    db: &PgPool,
+    contest_id: Option<Uuid>,
+    problem_id: Uuid,
+) -> ApiResult<Option<Uuid>> {
+    let tenant = match contest_id {
+        Some(contest_id) => {
+            sqlx::query_scalar("SELECT organization_id FROM contests WHERE id = $1")
+                .bind(contest_id)
+                .fetch_optional(db)
+                .await?
+        }
+        None => {
+            sqlx::query_scalar("SELECT organization_id FROM problems WHERE id = $1")
+                .bind(problem_id)
+                .fetch_optional(db)
+                .await?
+        }
+    };
+
+    Ok(tenant.flatten())
+}
+
+/// Require that the user belongs to the tenant (site admins always do).
+pub async fn require_tenant_member(db: &PgPool, tenant_id: Uuid, user: &AuthUser) -> ApiResult<()> {
+    if user.can(Permission::ViewPrivateContent)
+        || member_role(db, tenant_id, user.id).await?.is_some()
+    {
+        return Ok(());
+    }
+    Err(ApiError::Forbidden)
+}
+
+/// Enforce the tenant's submission rate limit and storage quota for a new
+/// submission of `incoming_bytes`.
+pub async fn enforce_tenant_limits(
+    state: &AppState,
+    tenant_id: Uuid,
+    incoming_bytes: i64,
+) -> ApiResult<()> {
+    let limits: TenantLimitsRow = sqlx::query_as(
+        "SELECT submissions_per_minute, storage_quota_bytes FROM organizations WHERE id = $1",
+    )
+    .bind(tenant_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Organization not found".to_string()))?;
+
+    if let Some(quota) = limits.storage_quota_bytes {
+        let used: i64 = sqlx::query_scalar(TENANT_STORAGE_SQL)
+            .bind(tenant_id)
+            .fetch_one(&state.db)
+            .await?;
+        if used + incoming_bytes > quota {
+            return Err(ApiError::QuotaExceeded(
+                "The organization's storage quota is used up".to_string(),
+            ));
+        }
+    }
+
+    if let Some(limit) = limits.submissions_per_minute {
+        let key = format!("rl:tenant:{}", tenant_id);
+        let info = check_rate_limit(state, &key, limit as u64, TENANT_RATE_WINDOW_SECS).await?;
+        if !info.allowed {
+            return Err(ApiError::RateLimitExceeded);
+        }
+    }
+
+    Ok(())
+}
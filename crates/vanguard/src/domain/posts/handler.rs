@@ -0,0 +1,371 @@
+//! Contest post handlers.
+//!
+//! Contest staff with `can_edit_contest` write, edit and remove posts and
+//! see drafts and scheduled posts; editorials take `can_publish_editorial`
+//! instead. Everyone else who can see the contest reads the posts inside
+//! their visibility window.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use olympus_rules::collaborators::CollaboratorPermission;
+use olympus_rules::roles::Permission;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::authorization::{
+    build_contest_context, has_contest_permission, policies, require_contest_permission,
+};
+use crate::domain::problems::markdown::render_markdown;
+use crate::error::{ApiError, ApiResult};
+use crate::extract::{Guard, ValidatedJson};
+use crate::middleware::auth::AuthUser;
+use crate::repositories::contests::{self as contest_repo, ContestFilter, ContestViewer};
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+const POST_COLUMNS: &str = "cp.id, cp.contest_id, cp.kind, cp.title, cp.body, cp.visible_from, \
+     cp.visible_until, cp.author_id, u.username as author_username, \
+     u.display_name as author_display_name, cp.created_at, cp.updated_at";
+
+/// Database row for a post, with its author
+#[derive(Debug, FromRow)]
+struct PostRow {
+    id: Uuid,
+    contest_id: Uuid,
+    kind: String,
+    title: String,
+    body: String,
+    visible_from: Option<DateTime<Utc>>,
+    visible_until: Option<DateTime<Utc>>,
+    author_id: Option<Uuid>,
+    author_username: Option<String>,
+    author_display_name: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// GET /api/v1/contests/{contest_id}/posts
+///
+/// List a contest's posts, newest first. Staff also get drafts, scheduled
+/// and expired posts.
+pub async fn list_posts(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<PostListResponse>> {
+    let user = user.map(|Extension(u)| u);
+    let is_staff = require_contest_visible(&state, user.as_ref(), contest_id).await?;
+
+    let rows = sqlx::query_as::<_, PostRow>(&format!(
+        r#"
+        SELECT {}
+        FROM contest_posts cp
+        LEFT JOIN users u ON u.id = cp.author_id
+        WHERE cp.contest_id = $1
+          AND ($2 OR (cp.visible_from <= NOW()
+                      AND (cp.visible_until IS NULL OR cp.visible_until > NOW())))
+        ORDER BY cp.visible_from DESC NULLS FIRST, cp.created_at DESC
+        "#,
+        POST_COLUMNS
+    ))
+    .bind(contest_id)
+    .bind(is_staff)
+    .fetch_all(state.read_db())
+    .await?;
+
+    Ok(Json(PostListResponse {
+        posts: rows.into_iter().map(row_to_summary).collect(),
+    }))
+}
+
+/// GET /api/v1/contests/{contest_id}/posts/{post_id}
+///
+/// Get a post with its rendered body.
+pub async fn get_post(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path((contest_id, post_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<PostResponse>> {
+    let user = user.map(|Extension(u)| u);
+    let is_staff = require_contest_visible(&state, user.as_ref(), contest_id).await?;
+
+    let row = fetch_post(state.read_db(), contest_id, post_id).await?;
+    let now = Utc::now();
+    let published = row.visible_from.is_some_and(|from| from <= now)
+        && row.visible_until.is_none_or(|until| until > now);
+    if !published && !is_staff {
+        return Err(ApiError::NotFound("Post not found".to_string()));
+    }
+
+    Ok(Json(row_to_response(row)))
+}
+
+/// POST /api/v1/contests/{contest_id}/posts
+///
+/// Write a post, published now unless it is a draft or `visible_from` is
+/// later.
+pub async fn create_post(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ContestStaff>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CreatePostRequest>,
+) -> ApiResult<(StatusCode, Json<PostResponse>)> {
+    require_post_permission(&state, &user, contest_id, payload.kind.as_str()).await?;
+
+    let visible_from = if payload.draft {
+        None
+    } else {
+        Some(payload.visible_from.unwrap_or_else(Utc::now))
+    };
+    validate_window(visible_from, payload.visible_until)?;
+
+    let post_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO contest_posts
+            (contest_id, kind, title, body, visible_from, visible_until, author_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+    )
+    .bind(contest_id)
+    .bind(payload.kind.as_str())
+    .bind(&payload.title)
+    .bind(&payload.body)
+    .bind(visible_from)
+    .bind(payload.visible_until)
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        post_id = %post_id,
+        user_id = %user.id,
+        draft = payload.draft,
+        "Contest post created"
+    );
+
+    let row = fetch_post(&state.db, contest_id, post_id).await?;
+    Ok((StatusCode::CREATED, Json(row_to_response(row))))
+}
+
+/// PUT /api/v1/contests/{contest_id}/posts/{post_id}
+///
+/// Partially update a post. `draft` unpublishes or publishes it,
+/// `clear_visible_until` removes the end time.
+pub async fn update_post(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ContestStaff>,
+    Path((contest_id, post_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<UpdatePostRequest>,
+) -> ApiResult<Json<PostResponse>> {
+    let existing = fetch_post(&state.db, contest_id, post_id).await?;
+    require_post_permission(&state, &user, contest_id, &existing.kind).await?;
+    if let Some(kind) = payload.kind {
+        require_post_permission(&state, &user, contest_id, kind.as_str()).await?;
+    }
+
+    let visible_from = match payload.draft {
+        Some(true) => None,
+        Some(false) => payload
+            .visible_from
+            .or(existing.visible_from)
+            .or_else(|| Some(Utc::now())),
+        None => payload.visible_from.or(existing.visible_from),
+    };
+    let visible_until = if payload.clear_visible_until {
+        None
+    } else {
+        payload.visible_until.or(existing.visible_until)
+    };
+    validate_window(visible_from, visible_until)?;
+
+    sqlx::query(
+        r#"
+        UPDATE contest_posts
+        SET kind = $1, title = $2, body = $3, visible_from = $4, visible_until = $5
+        WHERE id = $6
+        "#,
+    )
+    .bind(payload.kind.map(|k| k.as_str()).unwrap_or(&existing.kind))
+    .bind(payload.title.as_ref().unwrap_or(&existing.title))
+    .bind(payload.body.as_ref().unwrap_or(&existing.body))
+    .bind(visible_from)
+    .bind(visible_until)
+    .bind(post_id)
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        post_id = %post_id,
+        user_id = %user.id,
+        "Contest post updated"
+    );
+
+    let row = fetch_post(&state.db, contest_id, post_id).await?;
+    Ok(Json(row_to_response(row)))
+}
+
+/// DELETE /api/v1/contests/{contest_id}/posts/{post_id}
+///
+/// Remove a post.
+pub async fn delete_post(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::ContestStaff>,
+    Path((contest_id, post_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    let existing = fetch_post(&state.db, contest_id, post_id).await?;
+    require_post_permission(&state, &user, contest_id, &existing.kind).await?;
+
+    let result = sqlx::query("DELETE FROM contest_posts WHERE id = $1 AND contest_id = $2")
+        .bind(post_id)
+        .bind(contest_id)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Post not found".to_string()));
+    }
+
+    tracing::info!(
+        contest_id = %contest_id,
+        post_id = %post_id,
+        user_id = %user.id,
+        "Contest post removed"
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Fail with `404` unless the caller can see the contest. Returns whether
+/// they may also see unpublished posts.
+async fn require_contest_visible(
+    state: &AppState,
+    user: Option<&AuthUser>,
+    contest_id: Uuid,
+) -> ApiResult<bool> {
+    let viewer = match user {
+        Some(u) if u.can(Permission::ViewPrivateContent) => ContestViewer::Admin,
+        Some(u) => ContestViewer::User(u.id),
+        None => ContestViewer::Anonymous,
+    };
+    let filter = ContestFilter::new(viewer).id(Some(contest_id));
+    if contest_repo::count(state.read_db(), &filter).await? == 0 {
+        return Err(ApiError::NotFound("Contest not found".to_string()));
+    }
+
+    Ok(match user {
+        Some(u) => {
+            let ctx = build_contest_context(state, u, contest_id);
+            has_contest_permission(&ctx, CollaboratorPermission::EditContest).await
+                || has_contest_permission(&ctx, CollaboratorPermission::PublishEditorial).await
+        }
+        None => false,
+    })
+}
+
+/// Fail with `403` unless the caller may write posts of `kind`: editorials
+/// take `can_publish_editorial`, other posts `can_edit_contest`.
+async fn require_post_permission(
+    state: &AppState,
+    user: &AuthUser,
+    contest_id: Uuid,
+    kind: &str,
+) -> ApiResult<()> {
+    let permission = if kind == PostKind::Editorial.as_str() {
+        CollaboratorPermission::PublishEditorial
+    } else {
+        CollaboratorPermission::EditContest
+    };
+    let ctx = build_contest_context(state, user, contest_id);
+    require_contest_permission(&ctx, permission).await
+}
+
+async fn fetch_post(db: &sqlx::PgPool, contest_id: Uuid, post_id: Uuid) -> ApiResult<PostRow> {
+    sqlx::query_as::<_, PostRow>(&format!(
+        r#"
+        SELECT {}
+        FROM contest_posts cp
+        LEFT JOIN users u ON u.id = cp.author_id
+        WHERE cp.id = $1 AND cp.contest_id = $2
+        "#,
+        POST_COLUMNS
+    ))
+    .bind(post_id)
+    .bind(contest_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Post not found".to_string()))
+}
+
+/// Reject visibility windows that end before they start.
+fn validate_window(
+    visible_from: Option<DateTime<Utc>>,
+    visible_until: Option<DateTime<Utc>>,
+) -> ApiResult<()> {
+    match (visible_from, visible_until) {
+        (Some(from), Some(until)) if until <= from => Err(ApiError::Validation(
+            "visible_until must be after visible_from".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn row_to_summary(row: PostRow) -> PostSummary {
+    let author = match (row.author_id, row.author_username) {
+        (Some(id), Some(username)) => Some(UserInfo {
+            id,
+            username,
+            display_name: row.author_display_name,
+        }),
+        _ => None,
+    };
+    PostSummary {
+        id: row.id,
+        contest_id: row.contest_id,
+        kind: row.kind,
+        title: row.title,
+        author,
+        visible_from: row.visible_from,
+        visible_until: row.visible_until,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    }
+}
+
+fn row_to_response(mut row: PostRow) -> PostResponse {
+    let body = std::mem::take(&mut row.body);
+    let body_html = render_markdown(&body).html;
+    PostResponse {
+        summary: row_to_summary(row),
+        body,
+        body_html,
+    }
+}
+
+/// Post routes (optional auth: drafts are for contest staff)
+pub fn post_routes() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    axum::Router::new()
+        .route("/{contest_id}/posts", get(list_posts))
+        .route("/{contest_id}/posts/{post_id}", get(get_post))
+}
+
+/// Post editing routes (contest staff with `can_edit_contest`, or
+/// `can_publish_editorial` for editorials)
+pub fn protected_post_routes() -> axum::Router<AppState> {
+    use axum::routing::{delete, post, put};
+
+    axum::Router::new()
+        .route("/{contest_id}/posts", post(create_post))
+        .route("/{contest_id}/posts/{post_id}", put(update_post))
+        .route("/{contest_id}/posts/{post_id}", delete(delete_post))
+}
@@ -0,0 +1,11 @@
+//! Contest blog posts domain module.
+//!
+//! Organizers publish Markdown posts attached to a contest (announcements,
+//! editorials) with a visibility window. Anyone who can see the contest
+//! reads the published ones; drafts and scheduled posts are for its staff.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
@@ -0,0 +1,73 @@
+//! Contest post request DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use validator::Validate;
+
+/// What a post is about
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostKind {
+    #[default]
+    Announcement,
+    Editorial,
+    General,
+}
+
+impl PostKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostKind::Announcement => "announcement",
+            PostKind::Editorial => "editorial",
+            PostKind::General => "general",
+        }
+    }
+}
+
+/// Write a contest post
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreatePostRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: String,
+
+    /// Markdown
+    #[validate(length(min = 1, max = 100000, message = "Body must be 1-100000 characters"))]
+    pub body: String,
+
+    #[serde(default)]
+    pub kind: PostKind,
+
+    /// Keep the post unpublished
+    #[serde(default)]
+    pub draft: bool,
+
+    /// When to publish it (defaults to now; ignored for drafts)
+    pub visible_from: Option<DateTime<Utc>>,
+
+    /// When to take it down (public until removed if omitted)
+    pub visible_until: Option<DateTime<Utc>>,
+}
+
+/// Update a contest post (partial update)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdatePostRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: Option<String>,
+
+    #[validate(length(min = 1, max = 100000, message = "Body must be 1-100000 characters"))]
+    pub body: Option<String>,
+
+    pub kind: Option<PostKind>,
+
+    /// `true` unpublishes the post; `false` publishes a draft now unless
+    /// `visible_from` is given
+    pub draft: Option<bool>,
+
+    pub visible_from: Option<DateTime<Utc>>,
+
+    pub visible_until: Option<DateTime<Utc>>,
+
+    /// Remove the end time so the post stays up until removed
+    #[serde(default)]
+    pub clear_visible_until: bool,
+}
@@ -0,0 +1,45 @@
+//! Contest post response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// User information
+#[derive(Debug, Serialize)]
+pub struct UserInfo {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+}
+
+/// Post in a list, without its body
+#[derive(Debug, Serialize)]
+pub struct PostSummary {
+    pub id: Uuid,
+    pub contest_id: Uuid,
+    pub kind: String,
+    pub title: String,
+    pub author: Option<UserInfo>,
+    /// `None` for drafts
+    pub visible_from: Option<DateTime<Utc>>,
+    pub visible_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Contest posts, newest first
+#[derive(Debug, Serialize)]
+pub struct PostListResponse {
+    pub posts: Vec<PostSummary>,
+}
+
+/// Full post
+#[derive(Debug, Serialize)]
+pub struct PostResponse {
+    #[serde(flatten)]
+    pub summary: PostSummary,
+    /// Markdown source
+    pub body: String,
+    /// Sanitized HTML rendering of `body`
+    pub body_html: String,
+}
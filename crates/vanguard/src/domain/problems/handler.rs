@@ -1,28 +1,68 @@
 //! Problem handlers.
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use axum::{
     extract::{Extension, Multipart, Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, HeaderName, StatusCode},
     Json,
 };
 use chrono::{DateTime, Utc};
 use deadpool_redis::redis;
+use olympus_common::storage::USER_BINARIES_DIR;
+use olympus_common::worker_profile::{profile_stream, DEFAULT_WORKER_PROFILE};
+use olympus_common::OutputComparison;
+use olympus_rules::collaborators::CollaboratorPermission;
+use olympus_rules::pipeline::{required_binaries, Pipeline};
+use olympus_rules::roles::Permission;
 use sqlx::FromRow;
 use uuid::Uuid;
 use validator::Validate;
 
 use super::{
+    localization::{canonicalize_locale, negotiate_locale, parse_accept_language},
+    markdown::render_markdown,
     request::{
-        AddProblemToContestRequest, CreateProblemRequest, ListProblemsQuery, UpdateProblemRequest,
+        AddProblemToContestRequest, BulkAddProblemsRequest, CalibrateTimeLimitRequest,
+        CreateProblemRequest, GetProblemQuery, ListProblemsQuery, ProblemFeedQuery,
+        ReorderContestProblemsRequest, RollbackBinaryQuery, StatementFormat, SubtaskRequest,
+        UpdateCheckerTestsRequest, UpdateContestProblemVisibilityRequest, UpdatePipelineRequest,
+        UpdateProblemRequest, UpdateProblemScoringRequest, UpdateReferenceSolutionsRequest,
+        UpsertStatementRequest, ValidateDraftRequest,
     },
     response::{
-        ContestProblemInfo, ContestProblemsResponse, MessageResponse, OwnerInfo, Pagination,
-        ProblemDetailResponse, ProblemListResponse, ProblemResponse, ProblemSummary,
+        AcceptedSolutionInfo, BinaryRollbackResponse, BinaryVersionInfo, BinaryVersionListResponse,
+        BulkAddConflict, BulkAddProblemsResponse, CalibrationResultInfo, CheckerTestInfo,
+        CheckerTestResultInfo, CheckerTestRunResponse, CheckerTestsResponse,
+        ContestProblemBaselineResponse, ContestProblemInfo,
+        ContestProblemsResponse, DraftCheck, DraftCheckSeverity, DraftValidationResponse,
+        MessageResponse, MyProblemProgressResponse, OwnerInfo, Pagination, ProblemDetailResponse,
+        ProblemLanguageStatistics, ProblemListResponse, ProblemPipelineResponse, ProblemResponse,
+        ProblemScoringResponse, ProblemStatisticsResponse, ProblemSummary, RecommendedProblem,
+        RecommendedProblemsResponse, ReferenceSolutionInfo, ReferenceSolutionsResponse,
+        StatementListResponse, StatementResponse, SubmissionProgressEntry, SubtaskInfo,
+        TestLimitInfo, TestWeightInfo, TimeLimitCalibrationResponse,
     },
+    samples::{self, ReferenceSampleOutcome},
+};
+use crate::domain::admin::{self, RejudgeSubmissionRow};
+use crate::domain::authorization::{
+    build_auth_context, build_problem_context, policies, require_permission,
+    require_problem_permission, require_submission_view_access,
 };
-use crate::error::{ApiError, ApiResult};
+use crate::domain::organizations::{member_role, require_organization_manager};
+use crate::domain::users::quota;
+use crate::error::{self, ApiError, ApiResult, FieldError};
+use crate::extract::{Guard, ValidatedJson};
 use crate::middleware::auth::AuthUser;
+use crate::middleware::request_log::tag_stream_entry;
+use crate::pagination::{Cursor, CursorPage, CursorQuery};
+use crate::repositories::problems::{
+    self as problem_repo, ProblemFilter, ProblemListRow, ProblemViewer,
+};
+use crate::scanner::ScanTarget;
 use crate::state::AppState;
+use crate::upload::{self, SpooledUpload};
 
 /// Database row for problem
 #[derive(Debug, FromRow)]
@@ -43,6 +83,7 @@ struct ProblemRow {
     num_test_cases: i32,
     generator_path: Option<String>,
     checker_path: Option<String>,
+    reference_path: Option<String>,
     max_threads: i32,
     network_allowed: bool,
     max_score: i32,
@@ -52,6 +93,64 @@ struct ProblemRow {
     owner_id: Uuid,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    benchmark_scored: bool,
+    organization_id: Option<Uuid>,
+    statement_locale: String,
+    rank_by_instructions: bool,
+    output_comparison: Option<String>,
+    comparison_epsilon: f64,
+    worker_profile: String,
+}
+
+/// Statement fields of a problem or of one of its translations
+#[derive(Debug, FromRow)]
+struct StatementRow {
+    title: String,
+    description: String,
+    input_format: Option<String>,
+    output_format: Option<String>,
+    constraints: Option<String>,
+    sample_explanation: Option<String>,
+}
+
+impl StatementRow {
+    /// This translation, with the optional sections it leaves out taken
+    /// from `base`.
+    fn or(self, base: StatementRow) -> StatementRow {
+        StatementRow {
+            title: self.title,
+            description: self.description,
+            input_format: self.input_format.or(base.input_format),
+            output_format: self.output_format.or(base.output_format),
+            constraints: self.constraints.or(base.constraints),
+            sample_explanation: self.sample_explanation.or(base.sample_explanation),
+        }
+    }
+
+    /// Render the Markdown sections (all but the title) to sanitized HTML,
+    /// returning the languages of their fenced code blocks.
+    fn render_html(&mut self) -> BTreeSet<String> {
+        let mut code_languages = BTreeSet::new();
+        let mut render = |markdown: &mut String| {
+            let rendered = render_markdown(markdown);
+            code_languages.extend(rendered.code_languages);
+            *markdown = rendered.html;
+        };
+
+        render(&mut self.description);
+        for section in [
+            &mut self.input_format,
+            &mut self.output_format,
+            &mut self.constraints,
+            &mut self.sample_explanation,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            render(section);
+        }
+        code_languages
+    }
 }
 
 // =============================================================================
@@ -61,25 +160,6 @@ struct ProblemRow {
 /// GET /api/v1/problems
 ///
 /// List problems with pagination and filtering.
-/// Database row for problem list query
-#[derive(Debug, FromRow)]
-struct ProblemListRow {
-    id: Uuid,
-    title: String,
-    difficulty: Option<String>,
-    tags: Option<Vec<String>>,
-    time_limit_ms: i32,
-    memory_limit_kb: i32,
-    max_threads: i32,
-    network_allowed: bool,
-    max_score: i32,
-    is_public: bool,
-    created_at: DateTime<Utc>,
-    owner_id: Uuid,
-    owner_username: String,
-    owner_display_name: Option<String>,
-}
-
 pub async fn list_problems(
     State(state): State<AppState>,
     user: Option<Extension<AuthUser>>,
@@ -89,128 +169,22 @@ pub async fn list_problems(
     let per_page = query.per_page.clamp(1, 100);
     let offset = ((page - 1) * per_page) as i64;
 
-    let user_id = user.as_ref().map(|u| u.id);
-    let user_role = user.as_ref().map(|u| u.role.as_str());
-    let is_admin = user_role == Some("admin");
-
-    // Visibility logic:
-    //   public_only=true  -> only public problems (anyone)
-    //   public_only=false -> public problems PLUS non-public problems the user can access:
-    //     - admin sees all
-    //     - problem owner sees their own
-    //     - contest owner / collaborator sees problems in their contests
-    let (visibility_filter, needs_user_bind) = if query.public_only {
-        ("AND p.is_public = true".to_string(), false)
-    } else if is_admin {
-        // Admin sees everything
-        (String::new(), false)
-    } else if let Some(_uid) = user_id {
-        // Authenticated non-admin: public OR owned OR in a contest they own/collaborate on
-        let filter = r#"AND (
-                p.is_public = true
-                OR p.owner_id = $3
-                OR EXISTS (
-                    SELECT 1 FROM contest_problems cp
-                    JOIN contests c ON c.id = cp.contest_id
-                    WHERE cp.problem_id = p.id AND c.owner_id = $3
-                )
-                OR EXISTS (
-                    SELECT 1 FROM contest_problems cp
-                    JOIN contest_collaborators cc ON cc.contest_id = cp.contest_id
-                    WHERE cp.problem_id = p.id AND cc.user_id = $3
-                )
-            )"#;
-        (filter.to_string(), true)
-    } else {
-        // Anonymous: only public
-        ("AND p.is_public = true".to_string(), false)
-    };
-
-    let sql = format!(
-        r#"
-        SELECT
-            p.id, p.title, p.difficulty, p.tags, p.time_limit_ms, p.memory_limit_kb,
-            p.max_threads, p.network_allowed,
-            p.max_score, p.is_public, p.created_at,
-            u.id as owner_id, u.username as owner_username, u.display_name as owner_display_name
-        FROM problems p
-        JOIN users u ON p.owner_id = u.id
-        WHERE 1=1 {visibility_filter}
-        ORDER BY p.created_at DESC
-        LIMIT $1 OFFSET $2
-        "#
-    );
-
-    let count_sql = if query.public_only {
-        "SELECT COUNT(*) FROM problems WHERE is_public = true".to_string()
-    } else if is_admin {
-        "SELECT COUNT(*) FROM problems".to_string()
-    } else if needs_user_bind {
-        format!(
-            r#"SELECT COUNT(*) FROM problems p WHERE (
-                p.is_public = true
-                OR p.owner_id = $1
-                OR EXISTS (
-                    SELECT 1 FROM contest_problems cp
-                    JOIN contests c ON c.id = cp.contest_id
-                    WHERE cp.problem_id = p.id AND c.owner_id = $1
-                )
-                OR EXISTS (
-                    SELECT 1 FROM contest_problems cp
-                    JOIN contest_collaborators cc ON cc.contest_id = cp.contest_id
-                    WHERE cp.problem_id = p.id AND cc.user_id = $1
-                )
-            )"#
-        )
-    } else {
-        "SELECT COUNT(*) FROM problems WHERE is_public = true".to_string()
-    };
-
-    // Build & execute data query
-    let mut q = sqlx::query_as::<_, ProblemListRow>(&sql)
-        .bind(per_page as i64)
-        .bind(offset);
-    if needs_user_bind {
-        q = q.bind(user_id.unwrap());
-    }
+    let filter = ProblemFilter::new(problem_viewer(user.as_deref()))
+        .public_only(query.public_only)
+        .difficulty(query.difficulty)
+        .tag(query.tag)
+        .owner(query.owner_id)
+        .search(query.search);
 
-    let rows = q
-        .fetch_all(&state.db)
+    let rows = problem_repo::list(state.read_db(), &filter, per_page as i64, offset)
         .await
         .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
-    // Build & execute count query
-    let mut cq = sqlx::query_scalar::<_, i64>(&count_sql);
-    if needs_user_bind {
-        cq = cq.bind(user_id.unwrap());
-    }
-
-    let total: i64 = cq
-        .fetch_one(&state.db)
+    let total = problem_repo::count(state.read_db(), &filter)
         .await
         .map_err(|e| ApiError::Internal(format!("Count query error: {}", e)))?;
 
-    let problems: Vec<ProblemSummary> = rows
-        .into_iter()
-        .map(|r| ProblemSummary {
-            id: r.id,
-            title: r.title,
-            difficulty: r.difficulty,
-            tags: r.tags,
-            time_limit_ms: r.time_limit_ms,
-            memory_limit_kb: r.memory_limit_kb,
-            max_threads: r.max_threads,
-            network_allowed: r.network_allowed,
-            max_score: r.max_score,
-            is_public: r.is_public,
-            created_at: r.created_at,
-            owner: OwnerInfo {
-                id: r.owner_id,
-                username: r.owner_username,
-                display_name: r.owner_display_name,
-            },
-        })
-        .collect();
+    let problems: Vec<ProblemSummary> = rows.into_iter().map(row_to_summary).collect();
 
     let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
 
@@ -225,23 +199,87 @@ pub async fn list_problems(
     }))
 }
 
+/// GET /api/v2/problems
+///
+/// List problems, paginated by cursor.
+pub async fn list_problems_v2(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Query(page): Query<CursorQuery>,
+    Query(query): Query<ProblemFeedQuery>,
+) -> ApiResult<Json<CursorPage<ProblemSummary>>> {
+    let filter = ProblemFilter::new(problem_viewer(user.as_deref()))
+        .public_only(query.public_only)
+        .difficulty(query.difficulty)
+        .tag(query.tag)
+        .owner(query.owner_id)
+        .search(query.search)
+        .after(page.after()?);
+
+    let rows = problem_repo::list(state.read_db(), &filter, page.fetch_limit(), 0)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+
+    Ok(Json(CursorPage::from_rows(
+        rows,
+        &page,
+        |row| Cursor::new(row.created_at, row.id),
+        row_to_summary,
+    )))
+}
+
+fn problem_viewer(user: Option<&AuthUser>) -> ProblemViewer {
+    match user {
+        Some(u) if u.can(Permission::ViewPrivateContent) => ProblemViewer::Admin,
+        Some(u) => ProblemViewer::User(u.id),
+        None => ProblemViewer::Anonymous,
+    }
+}
+
+fn row_to_summary(r: ProblemListRow) -> ProblemSummary {
+    ProblemSummary {
+        id: r.id,
+        title: r.title,
+        difficulty: r.difficulty,
+        tags: r.tags,
+        time_limit_ms: r.time_limit_ms,
+        memory_limit_kb: r.memory_limit_kb,
+        max_threads: r.max_threads,
+        network_allowed: r.network_allowed,
+        max_score: r.max_score,
+        is_public: r.is_public,
+        organization_id: r.organization_id,
+        created_at: r.created_at,
+        owner: OwnerInfo {
+            id: r.owner_id,
+            username: r.owner_username,
+            display_name: r.owner_display_name,
+        },
+    }
+}
+
 /// POST /api/v1/problems
 ///
 /// Create a new problem.
 pub async fn create_problem(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
-    Json(payload): Json<CreateProblemRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateProblemRequest>,
 ) -> ApiResult<(StatusCode, Json<ProblemResponse>)> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    // Check the user's role may create problems
+    require_permission(
+        &build_auth_context(&state, &user),
+        Permission::CreateProblems,
+    )
+    .await?;
 
-    // Check user role (must be organizer or admin)
-    if user.role != "admin" && user.role != "organizer" {
-        return Err(ApiError::Forbidden);
+    // Only organization managers can create problems for an organization
+    if let Some(organization_id) = payload.organization_id {
+        require_organization_manager(&state.db, organization_id, &user).await?;
     }
 
+    quota::enforce_problem_quota(&state.db, user.id).await?;
+
     // Validate max_threads against system-wide cap
     let max_threads_limit = state.config.max_threads_limit;
     if payload.max_threads > max_threads_limit {
@@ -251,9 +289,20 @@ pub async fn create_problem(
         )));
     }
 
+    let worker_profile = payload
+        .worker_profile
+        .clone()
+        .unwrap_or_else(|| DEFAULT_WORKER_PROFILE.to_string());
+    validate_worker_profile(&state, &worker_profile)?;
+
     let id = Uuid::new_v4();
     let now = Utc::now();
     let difficulty = payload.difficulty.as_ref().map(|d| d.to_string());
+    let statement_locale = payload
+        .statement_locale
+        .as_deref()
+        .and_then(canonicalize_locale)
+        .unwrap_or_else(|| "en".to_string());
 
     // Note: generator_path and checker_path are set to NULL on creation
     // They will be populated when binaries are uploaded via separate endpoints
@@ -265,10 +314,11 @@ pub async fn create_problem(
             time_limit_ms, memory_limit_kb, num_test_cases, generator_path, checker_path,
             max_threads, network_allowed,
             max_score, partial_scoring, is_public, allowed_languages, owner_id,
-            created_at, updated_at
+            created_at, updated_at, benchmark_scored, organization_id, statement_locale,
+            rank_by_instructions, output_comparison, comparison_epsilon, worker_profile
         ) VALUES (
             $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, NULL, NULL,
-            $15, $16, $17, $18, $19, $20, $21, $22, $22
+            $15, $16, $17, $18, $19, $20, $21, $22, $22, $23, $24, $25, $26, $27, $28, $29
         )
         "#,
     )
@@ -294,10 +344,23 @@ pub async fn create_problem(
     .bind(&payload.allowed_languages)
     .bind(user.id)
     .bind(now)
+    .bind(payload.benchmark_scored)
+    .bind(payload.organization_id)
+    .bind(&statement_locale)
+    .bind(payload.rank_by_instructions)
+    .bind(payload.output_comparison.map(|c| c.as_str()))
+    .bind(payload.comparison_epsilon)
+    .bind(&worker_profile)
     .execute(&state.db)
     .await
     .map_err(|e| ApiError::Internal(format!("Failed to create problem: {}", e)))?;
 
+    let message = if payload.output_comparison.is_some() {
+        "Problem created. Upload generator and reference binaries to activate."
+    } else {
+        "Problem created. Upload generator and checker binaries to activate."
+    };
+
     Ok((
         StatusCode::CREATED,
         Json(ProblemResponse {
@@ -322,26 +385,316 @@ pub async fn create_problem(
             checker_uploaded: false,
             max_score: payload.max_score,
             partial_scoring: payload.partial_scoring,
+            benchmark_scored: payload.benchmark_scored,
+            rank_by_instructions: payload.rank_by_instructions,
+            worker_profile,
+            output_comparison: payload.output_comparison,
+            comparison_epsilon: payload.comparison_epsilon,
             is_public: payload.is_public,
             allowed_languages: payload.allowed_languages,
+            organization_id: payload.organization_id,
+            statement_locale,
             owner_id: user.id,
             created_at: now,
             updated_at: now,
-            message: Some(
-                "Problem created. Upload generator and checker binaries to activate.".to_string(),
-            ),
+            message: Some(message.to_string()),
         }),
     ))
 }
 
+/// POST /api/v1/problems/validate-draft
+///
+/// Check a problem definition without saving it and return a checklist of
+/// everything that blocks creating it or should be fixed first. With
+/// `problem_id`, also check the binaries uploaded to that problem.
+pub async fn validate_problem_draft(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<ValidateDraftRequest>,
+) -> ApiResult<Json<DraftValidationResponse>> {
+    match payload.problem_id {
+        Some(problem_id) => {
+            require_problem_permission(
+                &build_problem_context(&state, &user, problem_id),
+                CollaboratorPermission::EditProblems,
+            )
+            .await?
+        }
+        None => {
+            require_permission(
+                &build_auth_context(&state, &user),
+                Permission::CreateProblems,
+            )
+            .await?
+        }
+    }
+
+    let draft = &payload.problem;
+    let mut checks = Vec::new();
+
+    // Field rules of the create request
+    let field_errors = match draft.validate() {
+        Ok(()) => Vec::new(),
+        Err(errors) => error::field_errors(&errors),
+    };
+    if field_errors.is_empty() {
+        checks.push(draft_check("fields", None, true, "All fields are valid"));
+    }
+    checks.extend(field_errors.into_iter().map(|e| DraftCheck {
+        check: "fields",
+        field: Some(e.field),
+        severity: DraftCheckSeverity::Error,
+        passed: false,
+        message: e.message,
+    }));
+
+    let max_threads_limit = state.config.max_threads_limit;
+    checks.push(if draft.max_threads > max_threads_limit {
+        draft_check(
+            "max_threads",
+            Some("max_threads"),
+            false,
+            format!(
+                "max_threads ({}) exceeds the system limit of {}",
+                draft.max_threads, max_threads_limit
+            ),
+        )
+    } else {
+        draft_check(
+            "max_threads",
+            Some("max_threads"),
+            true,
+            "Within the system limit",
+        )
+    });
+
+    let sample = sample_check(draft);
+    let has_sample = sample.passed;
+    checks.push(sample);
+    if let (Some(problem_id), true) = (payload.problem_id, has_sample) {
+        checks.push(reference_sample_check(&state, &user, problem_id, draft).await?);
+    }
+
+    checks.push(if draft.num_test_cases >= 1 {
+        draft_check(
+            "test_cases",
+            Some("num_test_cases"),
+            true,
+            format!("{} test cases", draft.num_test_cases),
+        )
+    } else {
+        draft_check(
+            "test_cases",
+            Some("num_test_cases"),
+            false,
+            "At least one test case is required",
+        )
+    });
+
+    if let Some(languages) = draft.allowed_languages.as_ref().filter(|l| !l.is_empty()) {
+        let runtimes: Vec<(String, bool)> =
+            sqlx::query_as("SELECT name, is_enabled FROM runtimes WHERE name = ANY($1)")
+                .bind(languages)
+                .fetch_all(state.read_db())
+                .await?;
+        let runtimes: BTreeMap<String, bool> = runtimes.into_iter().collect();
+        for language in languages {
+            checks.push(match runtimes.get(language) {
+                Some(true) => draft_check(
+                    "languages",
+                    Some("allowed_languages"),
+                    true,
+                    format!("Language '{}' is available", language),
+                ),
+                Some(false) => DraftCheck {
+                    severity: DraftCheckSeverity::Warning,
+                    ..draft_check(
+                        "languages",
+                        Some("allowed_languages"),
+                        false,
+                        format!("Language '{}' is currently disabled", language),
+                    )
+                },
+                None => draft_check(
+                    "languages",
+                    Some("allowed_languages"),
+                    false,
+                    format!("Unsupported language '{}'", language),
+                ),
+            });
+        }
+    }
+
+    // Binaries can only be uploaded once the problem exists
+    let compares_output = draft.output_comparison.is_some();
+    match payload.problem_id {
+        Some(problem_id) => {
+            let pipeline = load_pipeline(&state.db, problem_id).await?;
+            let missing = missing_binaries(problem_id, pipeline.as_ref(), compares_output).await;
+            for name in required_binaries(pipeline.as_ref(), compares_output)
+                .into_iter()
+                .filter_map(|program| program.binary_name())
+            {
+                checks.push(if missing.contains(&name) {
+                    draft_check(
+                        "binaries",
+                        None,
+                        false,
+                        format!("The {} binary has not been uploaded", name),
+                    )
+                } else {
+                    draft_check(
+                        "binaries",
+                        None,
+                        true,
+                        format!("The {} binary is uploaded", name),
+                    )
+                });
+            }
+        }
+        None => {
+            for name in required_binaries(None, compares_output)
+                .into_iter()
+                .filter_map(|program| program.binary_name())
+            {
+                checks.push(DraftCheck {
+                    severity: DraftCheckSeverity::Warning,
+                    ..draft_check(
+                        "binaries",
+                        None,
+                        false,
+                        format!("Upload the {} binary after creating the problem", name),
+                    )
+                });
+            }
+        }
+    }
+
+    let ready = checks
+        .iter()
+        .all(|c| c.passed || c.severity != DraftCheckSeverity::Error);
+
+    Ok(Json(DraftValidationResponse {
+        problem_id: payload.problem_id,
+        ready,
+        checks,
+    }))
+}
+
+/// Draft check of error severity
+fn draft_check(
+    check: &'static str,
+    field: Option<&str>,
+    passed: bool,
+    message: impl Into<String>,
+) -> DraftCheck {
+    DraftCheck {
+        check,
+        field: field.map(str::to_string),
+        severity: DraftCheckSeverity::Error,
+        passed,
+        message: message.into(),
+    }
+}
+
+/// Sample input and output must come together; a problem without them
+/// gets a warning.
+fn sample_check(draft: &CreateProblemRequest) -> DraftCheck {
+    let given = |sample: &Option<String>| sample.as_deref().is_some_and(|s| !s.trim().is_empty());
+    match (given(&draft.sample_input), given(&draft.sample_output)) {
+        (true, true) => draft_check("samples", None, true, "Sample input and output are given"),
+        (true, false) => draft_check(
+            "samples",
+            Some("sample_output"),
+            false,
+            "sample_input is given without sample_output",
+        ),
+        (false, true) => draft_check(
+            "samples",
+            Some("sample_input"),
+            false,
+            "sample_output is given without sample_input",
+        ),
+        (false, false) if given(&draft.sample_explanation) => draft_check(
+            "samples",
+            Some("sample_explanation"),
+            false,
+            "sample_explanation is given without a sample",
+        ),
+        (false, false) => DraftCheck {
+            severity: DraftCheckSeverity::Warning,
+            ..draft_check(
+                "samples",
+                None,
+                false,
+                "No sample; contestants see no example input and output",
+            )
+        },
+    }
+}
+
+/// The statement sample must be what the model reference solution prints
+/// on the sample input. Only the saved sample is run, so a draft whose
+/// sample differs from it gets a warning to save first; while the run is
+/// pending the check is a warning too.
+async fn reference_sample_check(
+    state: &AppState,
+    user: &AuthUser,
+    problem_id: Uuid,
+    draft: &CreateProblemRequest,
+) -> ApiResult<DraftCheck> {
+    let warning = |message: &str| DraftCheck {
+        severity: DraftCheckSeverity::Warning,
+        ..draft_check("sample_reference", Some("sample_output"), false, message)
+    };
+
+    let (saved_input, saved_output): (Option<String>, Option<String>) =
+        sqlx::query_as("SELECT sample_input, sample_output FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+    let same = |draft: &Option<String>, saved: &Option<String>| {
+        draft.as_deref().map(str::trim_end) == saved.as_deref().map(str::trim_end)
+    };
+    if !same(&draft.sample_input, &saved_input) || !same(&draft.sample_output, &saved_output) {
+        return Ok(warning(
+            "Save the sample to check it against the reference solution",
+        ));
+    }
+
+    Ok(
+        match samples::check_reference_sample(state, problem_id, user.id).await? {
+            ReferenceSampleOutcome::NoReference => {
+                warning("Mark a model reference solution to check the sample output against it")
+            }
+            ReferenceSampleOutcome::Pending => warning(
+                "The reference solution is running on the sample; validate again for the result",
+            ),
+            ReferenceSampleOutcome::Passed => draft_check(
+                "sample_reference",
+                Some("sample_output"),
+                true,
+                "The reference solution prints the sample output",
+            ),
+            ReferenceSampleOutcome::Failed(message) => {
+                draft_check("sample_reference", Some("sample_output"), false, message)
+            }
+        },
+    )
+}
+
 /// GET /api/v1/problems/{id}
 ///
-/// Get problem details.
+/// Get problem details. The statement is served in the `?locale=` or
+/// `Accept-Language` locale if the problem has it (see `localization`).
 pub async fn get_problem(
     State(state): State<AppState>,
     user: Option<Extension<AuthUser>>,
     Path(problem_id): Path<Uuid>,
-) -> ApiResult<Json<ProblemDetailResponse>> {
+    Query(query): Query<GetProblemQuery>,
+    headers: HeaderMap,
+) -> ApiResult<([(HeaderName, String); 2], Json<ProblemDetailResponse>)> {
     let problem: Option<ProblemRow> = sqlx::query_as("SELECT * FROM problems WHERE id = $1")
         .bind(problem_id)
         .fetch_optional(&state.db)
@@ -352,33 +705,18 @@ pub async fn get_problem(
 
     // Check visibility
     let user_id = user.as_ref().map(|u| u.id);
-    let user_role = user.as_ref().map(|u| u.role.as_str());
-
-    if !problem.is_public && user_id != Some(problem.owner_id) && user_role != Some("admin") {
-        // Check if user is a contest owner or collaborator for any contest containing this problem
-        let has_access = if let Some(uid) = user_id {
-            let access: Option<(i32,)> = sqlx::query_as(
-                r#"SELECT 1 FROM contest_problems cp
-                   WHERE cp.problem_id = $1 AND (
-                       EXISTS (SELECT 1 FROM contests c WHERE c.id = cp.contest_id AND c.owner_id = $2)
-                       OR EXISTS (SELECT 1 FROM contest_collaborators cc WHERE cc.contest_id = cp.contest_id AND cc.user_id = $2)
-                   )
-                   LIMIT 1"#,
-            )
-            .bind(problem_id)
-            .bind(uid)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-            access.is_some()
-        } else {
-            false
-        };
-
-        if !has_access {
-            return Err(ApiError::NotFound("Problem not found".to_string()));
-        }
-    }
+    let is_moderator = user
+        .as_ref()
+        .is_some_and(|u| u.can(Permission::ModerateContests));
+    require_problem_visible(
+        &state,
+        user.as_deref(),
+        problem_id,
+        problem.is_public,
+        problem.owner_id,
+        problem.organization_id,
+    )
+    .await?;
 
     // Get owner info
     let owner: (Uuid, String, Option<String>) =
@@ -390,61 +728,132 @@ pub async fn get_problem(
 
     let is_owner = user_id == Some(problem.owner_id);
 
-    Ok(Json(ProblemDetailResponse {
-        id: problem.id,
+    // Statement negotiation: ?locale= first, then Accept-Language
+    let mut preferred = Vec::new();
+    if let Some(ref locale) = query.locale {
+        preferred.push(canonicalize_locale(locale).ok_or_else(|| {
+            ApiError::Validation("Locale must be a language tag such as en or pt-BR".to_string())
+        })?);
+    }
+    if let Some(accept) = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+    {
+        preferred.extend(parse_accept_language(accept));
+    }
+
+    let translations: Vec<String> = sqlx::query_scalar(
+        "SELECT locale FROM problem_statements WHERE problem_id = $1 ORDER BY locale",
+    )
+    .bind(problem_id)
+    .fetch_all(&state.db)
+    .await?;
+    let mut available_locales = vec![problem.statement_locale.clone()];
+    available_locales.extend(translations);
+
+    let locale = negotiate_locale(&preferred, &available_locales)
+        .unwrap_or(&problem.statement_locale)
+        .to_string();
+
+    let mut statement = StatementRow {
         title: problem.title,
         description: problem.description,
         input_format: problem.input_format,
         output_format: problem.output_format,
         constraints: problem.constraints,
-        sample_input: problem.sample_input,
-        sample_output: problem.sample_output,
         sample_explanation: problem.sample_explanation,
-        difficulty: problem.difficulty,
-        tags: problem.tags,
-        time_limit_ms: problem.time_limit_ms,
-        memory_limit_kb: problem.memory_limit_kb,
-        max_threads: problem.max_threads,
-        network_allowed: problem.network_allowed,
-        num_test_cases: problem.num_test_cases,
-        generator_path: if is_owner || user_role == Some("admin") {
-            problem.generator_path
-        } else {
-            None
-        },
-        checker_path: if is_owner || user_role == Some("admin") {
-            problem.checker_path
-        } else {
-            None
-        },
-        max_score: problem.max_score,
-        partial_scoring: problem.partial_scoring,
-        is_public: problem.is_public,
-        allowed_languages: problem.allowed_languages,
-        owner: OwnerInfo {
-            id: owner.0,
-            username: owner.1,
-            display_name: owner.2,
-        },
-        is_owner,
-        created_at: problem.created_at,
-        updated_at: problem.updated_at,
-    }))
+    };
+    if locale != problem.statement_locale {
+        let translation: StatementRow = sqlx::query_as(
+            r#"
+            SELECT title, description, input_format, output_format, constraints, sample_explanation
+            FROM problem_statements WHERE problem_id = $1 AND locale = $2
+            "#,
+        )
+        .bind(problem_id)
+        .bind(&locale)
+        .fetch_one(&state.db)
+        .await?;
+        statement = translation.or(statement);
+    }
+
+    let code_languages = match query.format {
+        StatementFormat::Html => statement.render_html(),
+        StatementFormat::Markdown => BTreeSet::new(),
+    };
+
+    let content_headers = [
+        (header::CONTENT_LANGUAGE, locale.clone()),
+        (header::VARY, header::ACCEPT_LANGUAGE.to_string()),
+    ];
+
+    Ok((
+        content_headers,
+        Json(ProblemDetailResponse {
+            id: problem.id,
+            title: statement.title,
+            description: statement.description,
+            input_format: statement.input_format,
+            output_format: statement.output_format,
+            constraints: statement.constraints,
+            sample_input: problem.sample_input,
+            sample_output: problem.sample_output,
+            sample_explanation: statement.sample_explanation,
+            difficulty: problem.difficulty,
+            tags: problem.tags,
+            time_limit_ms: problem.time_limit_ms,
+            memory_limit_kb: problem.memory_limit_kb,
+            max_threads: problem.max_threads,
+            network_allowed: problem.network_allowed,
+            num_test_cases: problem.num_test_cases,
+            generator_path: if is_owner || is_moderator {
+                problem.generator_path
+            } else {
+                None
+            },
+            checker_path: if is_owner || is_moderator {
+                problem.checker_path
+            } else {
+                None
+            },
+            max_score: problem.max_score,
+            partial_scoring: problem.partial_scoring,
+            benchmark_scored: problem.benchmark_scored,
+            rank_by_instructions: problem.rank_by_instructions,
+            worker_profile: problem.worker_profile,
+            output_comparison: problem
+                .output_comparison
+                .as_deref()
+                .and_then(OutputComparison::parse),
+            comparison_epsilon: problem.comparison_epsilon,
+            is_public: problem.is_public,
+            allowed_languages: problem.allowed_languages,
+            organization_id: problem.organization_id,
+            locale,
+            available_locales,
+            format: query.format,
+            code_languages: code_languages.into_iter().collect(),
+            owner: OwnerInfo {
+                id: owner.0,
+                username: owner.1,
+                display_name: owner.2,
+            },
+            is_owner,
+            created_at: problem.created_at,
+            updated_at: problem.updated_at,
+        }),
+    ))
 }
 
 /// PUT /api/v1/problems/{id}
 ///
-/// Update problem (owner or admin only).
+/// Update problem (owner, admin, or contest collaborator with `can_edit_problems`).
 pub async fn update_problem(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
     Path(problem_id): Path<Uuid>,
-    Json(payload): Json<UpdateProblemRequest>,
+    ValidatedJson(payload): ValidatedJson<UpdateProblemRequest>,
 ) -> ApiResult<Json<ProblemResponse>> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     let problem: Option<ProblemRow> = sqlx::query_as("SELECT * FROM problems WHERE id = $1")
         .bind(problem_id)
         .fetch_optional(&state.db)
@@ -453,11 +862,6 @@ pub async fn update_problem(
 
     let problem = problem.ok_or(ApiError::NotFound("Problem not found".to_string()))?;
 
-    // Check permission
-    if problem.owner_id != user.id && user.role != "admin" {
-        return Err(ApiError::Forbidden);
-    }
-
     // Build updated values
     let title = payload.title.unwrap_or(problem.title);
     let description = payload.description.unwrap_or(problem.description);
@@ -491,8 +895,78 @@ pub async fn update_problem(
     // They are updated via the dedicated upload endpoints
     let max_score = payload.max_score.unwrap_or(problem.max_score);
     let partial_scoring = payload.partial_scoring.unwrap_or(problem.partial_scoring);
+    let benchmark_scored = payload.benchmark_scored.unwrap_or(problem.benchmark_scored);
+    let rank_by_instructions = payload
+        .rank_by_instructions
+        .unwrap_or(problem.rank_by_instructions);
+    let worker_profile = payload.worker_profile.unwrap_or(problem.worker_profile);
+    validate_worker_profile(&state, &worker_profile)?;
+    let previous_comparison = problem
+        .output_comparison
+        .as_deref()
+        .and_then(OutputComparison::parse);
+    let output_comparison = if payload.clear_output_comparison {
+        None
+    } else {
+        payload.output_comparison.or(previous_comparison)
+    };
+    let comparison_epsilon = payload
+        .comparison_epsilon
+        .unwrap_or(problem.comparison_epsilon);
     let is_public = payload.is_public.unwrap_or(problem.is_public);
     let allowed_languages = payload.allowed_languages.or(problem.allowed_languages);
+    let statement_locale = payload
+        .statement_locale
+        .as_deref()
+        .and_then(canonicalize_locale)
+        .unwrap_or(problem.statement_locale);
+
+    // Moving a problem into an organization, or out of one, takes a manager
+    // of that organization, and an organization problem stays within its
+    // organization's contests
+    let organization_id = payload.organization_id.unwrap_or(problem.organization_id);
+    if organization_id != problem.organization_id {
+        if let Some(organization_id) = organization_id {
+            require_organization_manager(&state.db, organization_id, &user).await?;
+
+            let used_elsewhere: bool = sqlx::query_scalar(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM contest_problems cp
+                    JOIN contests c ON c.id = cp.contest_id
+                    WHERE cp.problem_id = $1 AND c.organization_id IS DISTINCT FROM $2
+                )
+                "#,
+            )
+            .bind(problem_id)
+            .bind(organization_id)
+            .fetch_one(&state.db)
+            .await?;
+            if used_elsewhere {
+                return Err(ApiError::Conflict(
+                    "Problem is used in contests outside the organization".to_string(),
+                ));
+            }
+        }
+        if let Some(previous) = problem.organization_id {
+            require_organization_manager(&state.db, previous, &user).await?;
+        }
+    }
+
+    // The problem's own locale cannot also have a translation
+    let translated: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM problem_statements WHERE problem_id = $1 AND locale = $2)",
+    )
+    .bind(problem_id)
+    .bind(&statement_locale)
+    .fetch_one(&state.db)
+    .await?;
+    if translated {
+        return Err(ApiError::Conflict(format!(
+            "A '{}' translation exists; delete it before using it as the statement locale",
+            statement_locale
+        )));
+    }
 
     let now = Utc::now();
 
@@ -503,7 +977,10 @@ pub async fn update_problem(
             constraints = $6, sample_input = $7, sample_output = $8, sample_explanation = $9,
             difficulty = $10, tags = $11, time_limit_ms = $12, memory_limit_kb = $13,
             num_test_cases = $14, max_threads = $15, network_allowed = $16, max_score = $17,
-            partial_scoring = $18, is_public = $19, allowed_languages = $20, updated_at = $21
+            partial_scoring = $18, is_public = $19, allowed_languages = $20, updated_at = $21,
+            benchmark_scored = $22, statement_locale = $23, rank_by_instructions = $24,
+            output_comparison = $25, comparison_epsilon = $26, worker_profile = $27,
+            organization_id = $28
         WHERE id = $1
         "#,
     )
@@ -528,14 +1005,32 @@ pub async fn update_problem(
     .bind(is_public)
     .bind(&allowed_languages)
     .bind(now)
+    .bind(benchmark_scored)
+    .bind(&statement_locale)
+    .bind(rank_by_instructions)
+    .bind(output_comparison.map(|c| c.as_str()))
+    .bind(comparison_epsilon)
+    .bind(&worker_profile)
+    .bind(organization_id)
     .execute(&state.db)
     .await
     .map_err(|e| ApiError::Internal(format!("Failed to update problem: {}", e)))?;
 
+    // Judging with the reference instead of the checker (or back) changes
+    // the binaries pending submissions wait for
+    if output_comparison.is_some() != previous_comparison.is_some() {
+        requeue_pending_submissions(&state, problem_id).await?;
+    }
+
     // Determine problem status based on binary uploads
     let generator_uploaded = problem.generator_path.is_some();
     let checker_uploaded = problem.checker_path.is_some();
-    let status = if generator_uploaded && checker_uploaded {
+    let judge_uploaded = if output_comparison.is_some() {
+        problem.reference_path.is_some()
+    } else {
+        checker_uploaded
+    };
+    let status = if generator_uploaded && judge_uploaded {
         "ready"
     } else {
         "draft"
@@ -563,8 +1058,15 @@ pub async fn update_problem(
         checker_uploaded,
         max_score,
         partial_scoring,
+        benchmark_scored,
+        rank_by_instructions,
+        worker_profile,
+        output_comparison,
+        comparison_epsilon,
         is_public,
         allowed_languages,
+        organization_id,
+        statement_locale,
         owner_id: problem.owner_id,
         created_at: problem.created_at,
         updated_at: now,
@@ -574,199 +1076,1982 @@ pub async fn update_problem(
 
 /// DELETE /api/v1/problems/{id}
 ///
-/// Delete problem (owner or admin only).
+/// Delete problem (owner or admin only). Admins can restore it from the
+/// backups Horus keeps (`/api/v1/admin/problem-backups`).
 pub async fn delete_problem(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    _guard: Guard<policies::ProblemOwner>,
     Path(problem_id): Path<Uuid>,
 ) -> ApiResult<StatusCode> {
-    let problem: Option<(Uuid,)> = sqlx::query_as("SELECT owner_id FROM problems WHERE id = $1")
+    sqlx::query("DELETE FROM problems WHERE id = $1")
         .bind(problem_id)
-        .fetch_optional(&state.db)
+        .execute(&state.db)
         .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+        .map_err(|e| ApiError::Internal(format!("Failed to delete problem: {}", e)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// =============================================================================
+// Statement Translations
+// =============================================================================
+
+/// Database row for a statement translation
+#[derive(Debug, FromRow)]
+struct TranslationRow {
+    locale: String,
+    title: String,
+    description: String,
+    input_format: Option<String>,
+    output_format: Option<String>,
+    constraints: Option<String>,
+    sample_explanation: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<TranslationRow> for StatementResponse {
+    fn from(row: TranslationRow) -> Self {
+        StatementResponse {
+            locale: row.locale,
+            title: row.title,
+            description: row.description,
+            input_format: row.input_format,
+            output_format: row.output_format,
+            constraints: row.constraints,
+            sample_explanation: row.sample_explanation,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+const TRANSLATION_COLUMNS: &str = "locale, title, description, input_format, output_format, \
+     constraints, sample_explanation, updated_at";
+
+/// GET /api/v1/problems/{id}/statements
+///
+/// List a problem's statement translations.
+pub async fn list_statements(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<StatementListResponse>> {
+    let statement_locale: String =
+        sqlx::query_scalar("SELECT statement_locale FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    let sql = format!(
+        "SELECT {} FROM problem_statements WHERE problem_id = $1 ORDER BY locale",
+        TRANSLATION_COLUMNS
+    );
+    let rows: Vec<TranslationRow> = sqlx::query_as(&sql)
+        .bind(problem_id)
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(StatementListResponse {
+        problem_id,
+        statement_locale,
+        statements: rows.into_iter().map(StatementResponse::from).collect(),
+    }))
+}
+
+/// PUT /api/v1/problems/{id}/statements/{locale}
+///
+/// Create or replace the translation into `locale`.
+pub async fn upsert_statement(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path((problem_id, locale)): Path<(Uuid, String)>,
+    ValidatedJson(payload): ValidatedJson<UpsertStatementRequest>,
+) -> ApiResult<Json<StatementResponse>> {
+    let locale = canonicalize_locale(&locale).ok_or_else(|| {
+        ApiError::Validation("Locale must be a language tag such as en or pt-BR".to_string())
+    })?;
+
+    let statement_locale: String =
+        sqlx::query_scalar("SELECT statement_locale FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_one(&state.db)
+            .await?;
+    if locale == statement_locale {
+        return Err(ApiError::Validation(format!(
+            "The problem's own statement is in '{}'; update the problem instead",
+            locale
+        )));
+    }
+
+    let sql = format!(
+        r#"
+        INSERT INTO problem_statements (
+            problem_id, locale, title, description, input_format, output_format,
+            constraints, sample_explanation, updated_by
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (problem_id, locale) DO UPDATE SET
+            title = EXCLUDED.title,
+            description = EXCLUDED.description,
+            input_format = EXCLUDED.input_format,
+            output_format = EXCLUDED.output_format,
+            constraints = EXCLUDED.constraints,
+            sample_explanation = EXCLUDED.sample_explanation,
+            updated_by = EXCLUDED.updated_by
+        RETURNING {}
+        "#,
+        TRANSLATION_COLUMNS
+    );
+    let row: TranslationRow = sqlx::query_as(&sql)
+        .bind(problem_id)
+        .bind(&locale)
+        .bind(&payload.title)
+        .bind(&payload.description)
+        .bind(&payload.input_format)
+        .bind(&payload.output_format)
+        .bind(&payload.constraints)
+        .bind(&payload.sample_explanation)
+        .bind(user.id)
+        .fetch_one(&state.db)
+        .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        locale = %locale,
+        user_id = %user.id,
+        "Saved problem statement translation"
+    );
+
+    Ok(Json(row.into()))
+}
+
+/// DELETE /api/v1/problems/{id}/statements/{locale}
+///
+/// Delete the translation into `locale`.
+pub async fn delete_statement(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path((problem_id, locale)): Path<(Uuid, String)>,
+) -> ApiResult<StatusCode> {
+    let locale = canonicalize_locale(&locale)
+        .ok_or_else(|| ApiError::NotFound("Translation not found".to_string()))?;
+
+    let result =
+        sqlx::query("DELETE FROM problem_statements WHERE problem_id = $1 AND locale = $2")
+            .bind(problem_id)
+            .bind(&locale)
+            .execute(&state.db)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Translation not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// =============================================================================
+// Partial Scoring
+// =============================================================================
+
+/// GET /api/v1/problems/{id}/scoring
+///
+/// Get a problem's subtasks, test weights and test limits.
+pub async fn get_problem_scoring(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<ProblemScoringResponse>> {
+    Ok(Json(fetch_problem_scoring(&state.db, problem_id).await?))
+}
+
+/// PUT /api/v1/problems/{id}/scoring
+///
+/// Replace a problem's subtasks, test weights and test limits. Minos reads
+/// them on every judge run (weights only for partial-scoring problems), so
+/// rejudges pick them up too.
+pub async fn update_problem_scoring(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateProblemScoringRequest>,
+) -> ApiResult<Json<ProblemScoringResponse>> {
+    let num_test_cases: i32 =
+        sqlx::query_scalar("SELECT num_test_cases FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_one(&state.db)
+            .await?;
+
+    let errors = scoring_config_errors(&payload, num_test_cases);
+    if !errors.is_empty() {
+        return Err(ApiError::InvalidFields(errors));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query("DELETE FROM problem_subtasks WHERE problem_id = $1")
+        .bind(problem_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (idx, subtask) in payload.subtasks.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO problem_subtasks
+                (problem_id, subtask_number, first_test, last_test, points,
+                 time_limit_ms, memory_limit_kb)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(problem_id)
+        .bind(idx as i32 + 1)
+        .bind(subtask.first_test)
+        .bind(subtask.last_test)
+        .bind(subtask.points)
+        .bind(subtask.time_limit_ms)
+        .bind(subtask.memory_limit_kb)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Unlisted tests go back to the default weight and inherited limits
+    sqlx::query(
+        r#"
+        UPDATE test_cases
+        SET score_weight = 1, time_limit_ms = NULL, memory_limit_kb = NULL
+        WHERE problem_id = $1
+        "#,
+    )
+    .bind(problem_id)
+    .execute(&mut *tx)
+    .await?;
+
+    for weight in &payload.test_weights {
+        sqlx::query(
+            r#"
+            INSERT INTO test_cases (problem_id, case_number, score_weight)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (problem_id, case_number)
+            DO UPDATE SET score_weight = EXCLUDED.score_weight
+            "#,
+        )
+        .bind(problem_id)
+        .bind(weight.test)
+        .bind(weight.weight)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for limits in &payload.test_limits {
+        sqlx::query(
+            r#"
+            INSERT INTO test_cases (problem_id, case_number, time_limit_ms, memory_limit_kb)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (problem_id, case_number)
+            DO UPDATE SET time_limit_ms = EXCLUDED.time_limit_ms,
+                          memory_limit_kb = EXCLUDED.memory_limit_kb
+            "#,
+        )
+        .bind(problem_id)
+        .bind(limits.test)
+        .bind(limits.time_limit_ms)
+        .bind(limits.memory_limit_kb)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    tracing::info!(
+        user_id = %user.id,
+        problem_id = %problem_id,
+        subtasks = payload.subtasks.len(),
+        test_weights = payload.test_weights.len(),
+        test_limits = payload.test_limits.len(),
+        "Updated problem scoring"
+    );
+
+    Ok(Json(fetch_problem_scoring(&state.db, problem_id).await?))
+}
+
+/// Checks on a scoring config that depend on the problem's test count.
+fn scoring_config_errors(
+    payload: &UpdateProblemScoringRequest,
+    num_test_cases: i32,
+) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    let mut error = |field: String, rule: &str, message: String| {
+        errors.push(FieldError {
+            field,
+            rule: rule.to_string(),
+            message,
+        })
+    };
+
+    for (i, subtask) in payload.subtasks.iter().enumerate() {
+        if subtask.last_test < subtask.first_test {
+            error(
+                format!("subtasks[{}].last_test", i),
+                "range",
+                "last_test must not be before first_test".to_string(),
+            );
+        } else if subtask.last_test > num_test_cases {
+            error(
+                format!("subtasks[{}].last_test", i),
+                "range",
+                format!("Problem only has {} test cases", num_test_cases),
+            );
+        }
+    }
+
+    let mut ranges: Vec<(usize, &SubtaskRequest)> = payload.subtasks.iter().enumerate().collect();
+    ranges.sort_by_key(|(_, subtask)| subtask.first_test);
+    for pair in ranges.windows(2) {
+        let ((_, prev), (i, next)) = (pair[0], pair[1]);
+        if next.first_test <= prev.last_test {
+            error(
+                format!("subtasks[{}].first_test", i),
+                "overlap",
+                format!(
+                    "Subtask overlaps tests {}-{}",
+                    prev.first_test, prev.last_test
+                ),
+            );
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for (i, weight) in payload.test_weights.iter().enumerate() {
+        if weight.test > num_test_cases {
+            error(
+                format!("test_weights[{}].test", i),
+                "range",
+                format!("Problem only has {} test cases", num_test_cases),
+            );
+        } else if !seen.insert(weight.test) {
+            error(
+                format!("test_weights[{}].test", i),
+                "duplicate",
+                format!("Test {} is listed more than once", weight.test),
+            );
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for (i, limits) in payload.test_limits.iter().enumerate() {
+        if limits.test > num_test_cases {
+            error(
+                format!("test_limits[{}].test", i),
+                "range",
+                format!("Problem only has {} test cases", num_test_cases),
+            );
+        } else if !seen.insert(limits.test) {
+            error(
+                format!("test_limits[{}].test", i),
+                "duplicate",
+                format!("Test {} is listed more than once", limits.test),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Database row for problem_subtasks
+#[derive(Debug, FromRow)]
+struct SubtaskRow {
+    subtask_number: i32,
+    first_test: i32,
+    last_test: i32,
+    points: i32,
+    time_limit_ms: Option<i32>,
+    memory_limit_kb: Option<i32>,
+}
+
+/// Load a problem's subtasks, non-default test weights and test limits.
+async fn fetch_problem_scoring(
+    db: &sqlx::PgPool,
+    problem_id: Uuid,
+) -> ApiResult<ProblemScoringResponse> {
+    let (partial_scoring, num_test_cases): (bool, i32) =
+        sqlx::query_as("SELECT partial_scoring, num_test_cases FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    let subtasks = sqlx::query_as::<_, SubtaskRow>(
+        r#"
+        SELECT subtask_number, first_test, last_test, points, time_limit_ms, memory_limit_kb
+        FROM problem_subtasks
+        WHERE problem_id = $1
+        ORDER BY subtask_number
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_all(db)
+    .await?;
+
+    let test_weights = sqlx::query_as::<_, (i32, i32)>(
+        r#"
+        SELECT case_number, score_weight
+        FROM test_cases
+        WHERE problem_id = $1 AND score_weight <> 1
+        ORDER BY case_number
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_all(db)
+    .await?;
+
+    let test_limits = sqlx::query_as::<_, (i32, Option<i32>, Option<i32>)>(
+        r#"
+        SELECT case_number, time_limit_ms, memory_limit_kb
+        FROM test_cases
+        WHERE problem_id = $1
+          AND (time_limit_ms IS NOT NULL OR memory_limit_kb IS NOT NULL)
+        ORDER BY case_number
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(ProblemScoringResponse {
+        problem_id,
+        partial_scoring,
+        num_test_cases,
+        subtasks: subtasks
+            .into_iter()
+            .map(|row| SubtaskInfo {
+                subtask_number: row.subtask_number,
+                first_test: row.first_test,
+                last_test: row.last_test,
+                points: row.points,
+                time_limit_ms: row.time_limit_ms,
+                memory_limit_kb: row.memory_limit_kb,
+            })
+            .collect(),
+        test_weights: test_weights
+            .into_iter()
+            .map(|(test, weight)| TestWeightInfo { test, weight })
+            .collect(),
+        test_limits: test_limits
+            .into_iter()
+            .map(|(test, time_limit_ms, memory_limit_kb)| TestLimitInfo {
+                test,
+                time_limit_ms,
+                memory_limit_kb,
+            })
+            .collect(),
+    })
+}
+
+/// Fail with `404` unless the user may see the problem: it is public, or
+/// they are an admin, its owner, the owner or a collaborator of a contest
+/// containing it, or a member of its organization.
+pub(crate) async fn require_problem_visible(
+    state: &AppState,
+    user: Option<&AuthUser>,
+    problem_id: Uuid,
+    is_public: bool,
+    owner_id: Uuid,
+    organization_id: Option<Uuid>,
+) -> ApiResult<()> {
+    let user_id = user.map(|u| u.id);
+    let sees_private = user.is_some_and(|u| u.can(Permission::ViewPrivateContent));
+
+    if !is_public && user_id != Some(owner_id) && !sees_private {
+        // Check if user is a contest owner or collaborator for any contest containing this problem
+        let has_access = if let Some(uid) = user_id {
+            let access: Option<(i32,)> = sqlx::query_as(
+                r#"SELECT 1 FROM contest_problems cp
+                   WHERE cp.problem_id = $1 AND (
+                       EXISTS (SELECT 1 FROM contests c WHERE c.id = cp.contest_id AND c.owner_id = $2)
+                       OR EXISTS (SELECT 1 FROM contest_collaborators cc WHERE cc.contest_id = cp.contest_id AND cc.user_id = $2)
+                   )
+                   LIMIT 1"#,
+            )
+            .bind(problem_id)
+            .bind(uid)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+            let is_member = match organization_id {
+                Some(organization_id) => member_role(&state.db, organization_id, uid)
+                    .await?
+                    .is_some(),
+                None => false,
+            };
+            access.is_some() || is_member
+        } else {
+            false
+        };
+
+        if !has_access {
+            return Err(ApiError::NotFound("Problem not found".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Judging Pipelines
+// =============================================================================
+
+/// GET /api/v1/problems/{id}/pipeline
+///
+/// Get a problem's judging pipeline and the binaries it needs.
+pub async fn get_problem_pipeline(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<ProblemPipelineResponse>> {
+    let pipeline = load_pipeline(&state.db, problem_id).await?;
+    let compares_output = compares_output(&state.db, problem_id).await?;
+    Ok(Json(
+        pipeline_response(problem_id, pipeline, compares_output).await,
+    ))
+}
+
+/// PUT /api/v1/problems/{id}/pipeline
+///
+/// Replace a problem's judging pipeline. Minos reads it on every judge run,
+/// so rejudges use the new pipeline too.
+pub async fn update_problem_pipeline(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdatePipelineRequest>,
+) -> ApiResult<Json<ProblemPipelineResponse>> {
+    let json = serde_json::to_string(&payload.pipeline)
+        .map_err(|e| ApiError::Internal(format!("Failed to encode pipeline: {}", e)))?;
+
+    let updated =
+        sqlx::query("UPDATE problems SET pipeline = $1::jsonb, updated_at = NOW() WHERE id = $2")
+            .bind(&json)
+            .bind(problem_id)
+            .execute(&state.db)
+            .await?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Problem not found".to_string()));
+    }
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        steps = payload.pipeline.steps.len(),
+        "Problem pipeline updated"
+    );
+
+    // The new pipeline may need fewer binaries than the old one
+    requeue_pending_submissions(&state, problem_id).await?;
+
+    Ok(Json(
+        pipeline_response(problem_id, Some(payload.pipeline), false).await,
+    ))
+}
+
+/// DELETE /api/v1/problems/{id}/pipeline
+///
+/// Go back to the built-in generate, run and check sequence.
+pub async fn delete_problem_pipeline(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<ProblemPipelineResponse>> {
+    let updated =
+        sqlx::query("UPDATE problems SET pipeline = NULL, updated_at = NOW() WHERE id = $1")
+            .bind(problem_id)
+            .execute(&state.db)
+            .await?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Problem not found".to_string()));
+    }
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        "Problem pipeline removed"
+    );
+
+    requeue_pending_submissions(&state, problem_id).await?;
+
+    let compares_output = compares_output(&state.db, problem_id).await?;
+    Ok(Json(
+        pipeline_response(problem_id, None, compares_output).await,
+    ))
+}
+
+/// A problem's stored pipeline, `None` if it uses the built-in sequence
+async fn load_pipeline(db: &sqlx::PgPool, problem_id: Uuid) -> ApiResult<Option<Pipeline>> {
+    let pipeline: Option<String> =
+        sqlx::query_scalar("SELECT pipeline::text FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    pipeline
+        .map(|json| Pipeline::parse(&json).map_err(ApiError::Internal))
+        .transpose()
+}
+
+/// Whether the problem is judged by a built-in output comparison instead
+/// of its checker
+async fn compares_output(db: &sqlx::PgPool, problem_id: Uuid) -> ApiResult<bool> {
+    sqlx::query_scalar("SELECT output_comparison IS NOT NULL FROM problems WHERE id = $1")
+        .bind(problem_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))
+}
+
+/// Binaries the problem needs to be judged that have not been uploaded
+async fn missing_binaries(
+    problem_id: Uuid,
+    pipeline: Option<&Pipeline>,
+    compares_output: bool,
+) -> Vec<&'static str> {
+    let dir_path = format!("/mnt/data/binaries/problems/{}", problem_id);
+    let mut missing = Vec::new();
+    for name in required_binaries(pipeline, compares_output)
+        .into_iter()
+        .filter_map(|program| program.binary_name())
+    {
+        if tokio::fs::metadata(format!("{}/{}", dir_path, name))
+            .await
+            .is_err()
+        {
+            missing.push(name);
+        }
+    }
+    missing
+}
+
+async fn pipeline_response(
+    problem_id: Uuid,
+    pipeline: Option<Pipeline>,
+    compares_output: bool,
+) -> ProblemPipelineResponse {
+    let missing_binaries = missing_binaries(problem_id, pipeline.as_ref(), compares_output).await;
+    ProblemPipelineResponse {
+        problem_id,
+        required_binaries: required_binaries(pipeline.as_ref(), compares_output)
+            .into_iter()
+            .filter_map(|program| program.binary_name())
+            .collect(),
+        missing_binaries,
+        pipeline,
+    }
+}
+
+// =============================================================================
+// Checker Self-Tests
+// =============================================================================
+
+/// Maximum cases in a checker self-test suite
+const MAX_CHECKER_TESTS: usize = 50;
+
+/// Request body limit for replacing a suite
+const MAX_CHECKER_SUITE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Minutes a self-test run may stay pending before it is reported as failed,
+/// e.g. when its request never reached a worker
+const CHECKER_TEST_RUN_TIMEOUT_MINS: i32 = 15;
+
+const CHECKER_TEST_RUN_COLUMNS: &str = "id, problem_id, checker_version, status, total, \
+     mismatches, error, requested_by, created_at, completed_at";
+
+/// Database row for checker_tests
+#[derive(Debug, FromRow)]
+struct CheckerTestRow {
+    test_number: i32,
+    input: String,
+    output: String,
+    answer: Option<String>,
+    expected_verdict: String,
+}
+
+/// Database row for checker_test_runs
+#[derive(Debug, FromRow)]
+struct CheckerTestRunRow {
+    id: Uuid,
+    problem_id: Uuid,
+    checker_version: i32,
+    status: String,
+    total: i32,
+    mismatches: Option<i32>,
+    error: Option<String>,
+    requested_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+/// GET /api/v1/problems/{id}/checker/tests
+///
+/// Get a problem's checker self-test suite.
+pub async fn get_checker_tests(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<CheckerTestsResponse>> {
+    Ok(Json(load_checker_tests(&state.db, problem_id).await?))
+}
+
+/// PUT /api/v1/problems/{id}/checker/tests
+///
+/// Replace a problem's checker self-test suite. Cases are numbered in the
+/// order given.
+pub async fn update_checker_tests(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateCheckerTestsRequest>,
+) -> ApiResult<Json<CheckerTestsResponse>> {
+    if payload.tests.len() > MAX_CHECKER_TESTS {
+        return Err(ApiError::InvalidFields(vec![FieldError {
+            field: "tests".to_string(),
+            rule: "length".to_string(),
+            message: format!("A checker suite has at most {} tests", MAX_CHECKER_TESTS),
+        }]));
+    }
+
+    // Only the growth of the suite counts against the owner's storage quota
+    let suite_bytes: i64 = payload
+        .tests
+        .iter()
+        .map(|t| (t.input.len() + t.output.len() + t.answer.as_ref().map_or(0, String::len)) as i64)
+        .sum();
+    let current_bytes: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(OCTET_LENGTH(input) + OCTET_LENGTH(output)
+                            + COALESCE(OCTET_LENGTH(answer), 0)), 0)::BIGINT
+        FROM checker_tests WHERE problem_id = $1
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_one(&state.db)
+    .await?;
+    quota::enforce_storage_quota(&state.db, problem_id, suite_bytes - current_bytes).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    // Lock the problem so concurrent replacements do not interleave
+    let exists: Option<Uuid> =
+        sqlx::query_scalar("SELECT id FROM problems WHERE id = $1 FOR UPDATE")
+            .bind(problem_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound("Problem not found".to_string()));
+    }
+
+    sqlx::query("DELETE FROM checker_tests WHERE problem_id = $1")
+        .bind(problem_id)
+        .execute(&mut *tx)
+        .await?;
+    for (idx, test) in payload.tests.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO checker_tests
+                (problem_id, test_number, input, output, answer, expected_verdict)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(problem_id)
+        .bind(idx as i32 + 1)
+        .bind(&test.input)
+        .bind(&test.output)
+        .bind(&test.answer)
+        .bind(test.expected_verdict.as_str())
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        tests = payload.tests.len(),
+        "Checker self-test suite updated"
+    );
+
+    Ok(Json(load_checker_tests(&state.db, problem_id).await?))
+}
+
+/// POST /api/v1/problems/{id}/checker/test
+///
+/// Run the problem's checker on every case of its self-test suite. A Minos
+/// worker picks the run up from the regeneration stream and runs the checker
+/// in the sandbox; poll `GET /api/v1/problems/{id}/checker/test/{run_id}`
+/// for the verdicts and mismatches.
+pub async fn run_checker_tests(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<(StatusCode, Json<CheckerTestRunResponse>)> {
+    let (checker_version, total): (i32, i64) = sqlx::query_as(
+        r#"
+        SELECT p.checker_version,
+               (SELECT COUNT(*) FROM checker_tests t WHERE t.problem_id = p.id)
+        FROM problems p WHERE p.id = $1
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    if total == 0 {
+        return Err(ApiError::Conflict(
+            "The problem has no checker self-tests".to_string(),
+        ));
+    }
+    if tokio::fs::metadata(format!(
+        "/mnt/data/binaries/problems/{}/checker",
+        problem_id
+    ))
+    .await
+    .is_err()
+    {
+        return Err(ApiError::Conflict(
+            "No checker has been uploaded for this problem".to_string(),
+        ));
+    }
+
+    let row = sqlx::query_as::<_, CheckerTestRunRow>(&format!(
+        r#"
+        INSERT INTO checker_test_runs (problem_id, checker_version, total, requested_by)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {}
+        "#,
+        CHECKER_TEST_RUN_COLUMNS
+    ))
+    .bind(problem_id)
+    .bind(checker_version)
+    .bind(total as i32)
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let queued: ApiResult<String> = async {
+        let mut conn = state.redis.get().await?;
+        Ok(redis::cmd("XADD")
+            .arg(&state.config.regen_stream)
+            .arg("*")
+            .arg("kind")
+            .arg("checker_test")
+            .arg("run_id")
+            .arg(row.id.to_string())
+            .query_async(&mut conn)
+            .await?)
+    }
+    .await;
+    if let Err(e) = queued {
+        // Nothing will pick the run up, so don't leave it pending
+        fail_checker_test_run(&state.db, row.id, "Failed to queue the run").await?;
+        return Err(e);
+    }
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        run_id = %row.id,
+        tests = total,
+        "Checker self-test run requested"
+    );
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(checker_test_run_response(row, Vec::new())),
+    ))
+}
+
+/// GET /api/v1/problems/{id}/checker/test/{run_id}
+///
+/// Status of a checker self-test run and the checker's verdict on each case.
+pub async fn get_checker_test_run(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path((problem_id, run_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<CheckerTestRunResponse>> {
+    // A run no worker finished in time is reported as failed
+    sqlx::query(
+        r#"
+        UPDATE checker_test_runs
+        SET status = 'failed', error = 'Timed out waiting for a judge worker',
+            completed_at = NOW()
+        WHERE id = $1 AND problem_id = $2 AND status = 'pending'
+          AND created_at < NOW() - make_interval(mins => $3)
+        "#,
+    )
+    .bind(run_id)
+    .bind(problem_id)
+    .bind(CHECKER_TEST_RUN_TIMEOUT_MINS)
+    .execute(&state.db)
+    .await?;
+
+    let row = sqlx::query_as::<_, CheckerTestRunRow>(&format!(
+        "SELECT {} FROM checker_test_runs WHERE id = $1 AND problem_id = $2",
+        CHECKER_TEST_RUN_COLUMNS
+    ))
+    .bind(run_id)
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Checker test run not found".to_string()))?;
+
+    let results: Vec<(i32, String, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT test_number, expected_verdict, verdict, checker_output
+        FROM checker_test_results
+        WHERE run_id = $1
+        ORDER BY test_number
+        "#,
+    )
+    .bind(run_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let results = results
+        .into_iter()
+        .map(
+            |(test_number, expected_verdict, verdict, checker_output)| CheckerTestResultInfo {
+                matches: verdict == expected_verdict,
+                test_number,
+                expected_verdict,
+                verdict,
+                checker_output,
+            },
+        )
+        .collect();
+
+    Ok(Json(checker_test_run_response(row, results)))
+}
+
+async fn fail_checker_test_run(db: &sqlx::PgPool, run_id: Uuid, error: &str) -> ApiResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE checker_test_runs
+        SET status = 'failed', error = $1, completed_at = NOW()
+        WHERE id = $2 AND status = 'pending'
+        "#,
+    )
+    .bind(error)
+    .bind(run_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+async fn load_checker_tests(
+    db: &sqlx::PgPool,
+    problem_id: Uuid,
+) -> ApiResult<CheckerTestsResponse> {
+    let exists: Option<Uuid> = sqlx::query_scalar("SELECT id FROM problems WHERE id = $1")
+        .bind(problem_id)
+        .fetch_optional(db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound("Problem not found".to_string()));
+    }
+
+    let rows = sqlx::query_as::<_, CheckerTestRow>(
+        r#"
+        SELECT test_number, input, output, answer, expected_verdict
+        FROM checker_tests
+        WHERE problem_id = $1
+        ORDER BY test_number
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(CheckerTestsResponse {
+        problem_id,
+        tests: rows
+            .into_iter()
+            .map(|row| CheckerTestInfo {
+                test_number: row.test_number,
+                input: row.input,
+                output: row.output,
+                answer: row.answer,
+                expected_verdict: row.expected_verdict,
+            })
+            .collect(),
+    })
+}
+
+fn checker_test_run_response(
+    row: CheckerTestRunRow,
+    results: Vec<CheckerTestResultInfo>,
+) -> CheckerTestRunResponse {
+    CheckerTestRunResponse {
+        id: row.id,
+        problem_id: row.problem_id,
+        checker_version: row.checker_version,
+        status: row.status,
+        total: row.total,
+        mismatches: row.mismatches,
+        error: row.error,
+        requested_by: row.requested_by,
+        created_at: row.created_at,
+        completed_at: row.completed_at,
+        results,
+    }
+}
+
+// =============================================================================
+// Time Limit Calibration
+// =============================================================================
+
+/// Maximum reference solutions per problem
+const MAX_REFERENCE_SOLUTIONS: usize = 20;
+
+/// Safety margin over the slowest model solution when none is given
+const DEFAULT_CALIBRATION_MULTIPLIER: f64 = 3.0;
+
+/// Calibration runs under this multiple of the current time limit by
+/// default, so slow solutions show how slow they are
+const CALIBRATION_CEILING_FACTOR: i32 = 5;
+
+/// Upper bound of the default calibration ceiling
+const MAX_CALIBRATION_CEILING_MS: i32 = 60_000;
+
+const CALIBRATION_COLUMNS: &str = "id, problem_id, status, multiplier, ceiling_ms, \
+     current_time_limit_ms, model_time_ms, slow_time_ms, suggested_time_limit_ms, separation, \
+     worker_id, worker_speed_factor, error, requested_by, created_at, completed_at";
+
+/// Database row for problem_reference_solutions with its submission
+#[derive(Debug, FromRow)]
+struct ReferenceSolutionRow {
+    submission_id: Uuid,
+    role: String,
+    language: Option<String>,
+    status: String,
+    max_time_ms: Option<i32>,
+    marked_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+/// Database row for time_limit_calibrations
+#[derive(Debug, FromRow)]
+struct CalibrationRow {
+    id: Uuid,
+    problem_id: Uuid,
+    status: String,
+    multiplier: f64,
+    ceiling_ms: i32,
+    current_time_limit_ms: i32,
+    model_time_ms: Option<i32>,
+    slow_time_ms: Option<i32>,
+    suggested_time_limit_ms: Option<i32>,
+    separation: Option<String>,
+    worker_id: Option<String>,
+    worker_speed_factor: Option<f64>,
+    error: Option<String>,
+    requested_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+/// Database row for time_limit_calibration_results
+#[derive(Debug, FromRow)]
+struct CalibrationResultRow {
+    submission_id: Uuid,
+    role: String,
+    verdict: String,
+    max_time_ms: i32,
+    max_memory_kb: i32,
+    passed: i32,
+    total: i32,
+    error: Option<String>,
+}
+
+/// GET /api/v1/problems/{id}/reference-solutions
+///
+/// Get the submissions marked as a problem's model and slow solutions.
+pub async fn get_reference_solutions(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<ReferenceSolutionsResponse>> {
+    Ok(Json(load_reference_solutions(&state.db, problem_id).await?))
+}
+
+/// PUT /api/v1/problems/{id}/reference-solutions
+///
+/// Replace a problem's reference solutions. Each must be a compiled
+/// submission to the problem.
+pub async fn update_reference_solutions(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateReferenceSolutionsRequest>,
+) -> ApiResult<Json<ReferenceSolutionsResponse>> {
+    if payload.solutions.len() > MAX_REFERENCE_SOLUTIONS {
+        return Err(ApiError::InvalidFields(vec![FieldError {
+            field: "solutions".to_string(),
+            rule: "length".to_string(),
+            message: format!(
+                "A problem has at most {} reference solutions",
+                MAX_REFERENCE_SOLUTIONS
+            ),
+        }]));
+    }
+
+    let submission_ids: Vec<Uuid> = payload.solutions.iter().map(|s| s.submission_id).collect();
+    let compiled: BTreeSet<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT s.id
+        FROM submissions s
+        JOIN submission_artifacts a ON a.submission_id = s.id
+        WHERE s.id = ANY($1) AND s.problem_id = $2
+        "#,
+    )
+    .bind(&submission_ids)
+    .bind(problem_id)
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut seen = BTreeSet::new();
+    let mut errors = Vec::new();
+    for (idx, solution) in payload.solutions.iter().enumerate() {
+        let message = if !seen.insert(solution.submission_id) {
+            "Submission is listed more than once"
+        } else if !compiled.contains(&solution.submission_id) {
+            "Not a compiled submission to this problem"
+        } else {
+            continue;
+        };
+        errors.push(FieldError {
+            field: format!("solutions[{}].submission_id", idx),
+            rule: "reference".to_string(),
+            message: message.to_string(),
+        });
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::InvalidFields(errors));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    // Lock the problem so concurrent replacements do not interleave
+    let exists: Option<Uuid> =
+        sqlx::query_scalar("SELECT id FROM problems WHERE id = $1 FOR UPDATE")
+            .bind(problem_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound("Problem not found".to_string()));
+    }
+
+    sqlx::query("DELETE FROM problem_reference_solutions WHERE problem_id = $1")
+        .bind(problem_id)
+        .execute(&mut *tx)
+        .await?;
+    for solution in &payload.solutions {
+        sqlx::query(
+            r#"
+            INSERT INTO problem_reference_solutions (problem_id, submission_id, role, marked_by)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(problem_id)
+        .bind(solution.submission_id)
+        .bind(solution.role.as_str())
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
 
-    let problem = problem.ok_or(ApiError::NotFound("Problem not found".to_string()))?;
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        solutions = payload.solutions.len(),
+        "Reference solutions updated"
+    );
 
-    if problem.0 != user.id && user.role != "admin" {
-        return Err(ApiError::Forbidden);
+    Ok(Json(load_reference_solutions(&state.db, problem_id).await?))
+}
+
+/// POST /api/v1/problems/{id}/calibration
+///
+/// Calibrate the problem's time limit. A Minos worker picks the run up from
+/// the regeneration stream, runs every reference solution on all tests under
+/// the ceiling and suggests a limit; poll
+/// `GET /api/v1/problems/{id}/calibration/{calibration_id}` for the report.
+/// The problem's time limit is not changed.
+pub async fn calibrate_time_limit(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CalibrateTimeLimitRequest>,
+) -> ApiResult<(StatusCode, Json<TimeLimitCalibrationResponse>)> {
+    let (time_limit_ms, models): (i32, i64) = sqlx::query_as(
+        r#"
+        SELECT p.time_limit_ms,
+               (SELECT COUNT(*) FROM problem_reference_solutions r
+                WHERE r.problem_id = p.id AND r.role = 'model')
+        FROM problems p WHERE p.id = $1
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    if models == 0 {
+        return Err(ApiError::Conflict(
+            "The problem has no model reference solution".to_string(),
+        ));
     }
 
-    sqlx::query("DELETE FROM problems WHERE id = $1")
+    let multiplier = payload.multiplier.unwrap_or(DEFAULT_CALIBRATION_MULTIPLIER);
+    let ceiling_ms = payload.ceiling_ms.unwrap_or_else(|| {
+        time_limit_ms
+            .saturating_mul(CALIBRATION_CEILING_FACTOR)
+            .min(MAX_CALIBRATION_CEILING_MS)
+    });
+
+    let row = sqlx::query_as::<_, CalibrationRow>(&format!(
+        r#"
+        INSERT INTO time_limit_calibrations
+            (problem_id, multiplier, ceiling_ms, current_time_limit_ms, requested_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}
+        "#,
+        CALIBRATION_COLUMNS
+    ))
+    .bind(problem_id)
+    .bind(multiplier)
+    .bind(ceiling_ms)
+    .bind(time_limit_ms)
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let mut conn = state.redis.get().await?;
+    redis::cmd("XADD")
+        .arg(&state.config.regen_stream)
+        .arg("*")
+        .arg("kind")
+        .arg("calibrate")
+        .arg("calibration_id")
+        .arg(row.id.to_string())
+        .query_async::<String>(&mut conn)
+        .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        calibration_id = %row.id,
+        multiplier,
+        ceiling_ms,
+        "Time limit calibration requested"
+    );
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(calibration_response(row, Vec::new())),
+    ))
+}
+
+/// GET /api/v1/problems/{id}/calibration
+///
+/// The problem's most recent time limit calibration.
+pub async fn get_latest_calibration(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<TimeLimitCalibrationResponse>> {
+    let row = sqlx::query_as::<_, CalibrationRow>(&format!(
+        r#"
+        SELECT {} FROM time_limit_calibrations
+        WHERE problem_id = $1
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+        CALIBRATION_COLUMNS
+    ))
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("The problem has not been calibrated".to_string()))?;
+
+    Ok(Json(load_calibration_report(&state.db, row).await?))
+}
+
+/// GET /api/v1/problems/{id}/calibration/{calibration_id}
+///
+/// Status of a time limit calibration and how each reference solution did.
+pub async fn get_calibration(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path((problem_id, calibration_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<TimeLimitCalibrationResponse>> {
+    let row = sqlx::query_as::<_, CalibrationRow>(&format!(
+        "SELECT {} FROM time_limit_calibrations WHERE id = $1 AND problem_id = $2",
+        CALIBRATION_COLUMNS
+    ))
+    .bind(calibration_id)
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Calibration not found".to_string()))?;
+
+    Ok(Json(load_calibration_report(&state.db, row).await?))
+}
+
+async fn load_reference_solutions(
+    db: &sqlx::PgPool,
+    problem_id: Uuid,
+) -> ApiResult<ReferenceSolutionsResponse> {
+    let exists: Option<Uuid> = sqlx::query_scalar("SELECT id FROM problems WHERE id = $1")
         .bind(problem_id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Failed to delete problem: {}", e)))?;
+        .fetch_optional(db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound("Problem not found".to_string()));
+    }
 
-    Ok(StatusCode::NO_CONTENT)
+    let rows = sqlx::query_as::<_, ReferenceSolutionRow>(
+        r#"
+        SELECT r.submission_id, r.role, s.language, s.status, s.max_time_ms,
+               r.marked_by, r.created_at
+        FROM problem_reference_solutions r
+        JOIN submissions s ON s.id = r.submission_id
+        WHERE r.problem_id = $1
+        ORDER BY r.created_at
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(ReferenceSolutionsResponse {
+        problem_id,
+        solutions: rows
+            .into_iter()
+            .map(|row| ReferenceSolutionInfo {
+                submission_id: row.submission_id,
+                role: row.role,
+                language: row.language,
+                status: row.status,
+                max_time_ms: row.max_time_ms,
+                marked_by: row.marked_by,
+                created_at: row.created_at,
+            })
+            .collect(),
+    })
+}
+
+async fn load_calibration_report(
+    db: &sqlx::PgPool,
+    row: CalibrationRow,
+) -> ApiResult<TimeLimitCalibrationResponse> {
+    let results = sqlx::query_as::<_, CalibrationResultRow>(
+        r#"
+        SELECT submission_id, role, verdict, max_time_ms, max_memory_kb, passed, total, error
+        FROM time_limit_calibration_results
+        WHERE calibration_id = $1
+        ORDER BY role, max_time_ms
+        "#,
+    )
+    .bind(row.id)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|result| CalibrationResultInfo {
+        submission_id: result.submission_id,
+        role: result.role,
+        verdict: result.verdict,
+        max_time_ms: result.max_time_ms,
+        max_memory_kb: result.max_memory_kb,
+        passed: result.passed,
+        total: result.total,
+        error: result.error,
+    })
+    .collect();
+
+    Ok(calibration_response(row, results))
+}
+
+fn calibration_response(
+    row: CalibrationRow,
+    results: Vec<CalibrationResultInfo>,
+) -> TimeLimitCalibrationResponse {
+    TimeLimitCalibrationResponse {
+        id: row.id,
+        problem_id: row.problem_id,
+        status: row.status,
+        multiplier: row.multiplier,
+        ceiling_ms: row.ceiling_ms,
+        current_time_limit_ms: row.current_time_limit_ms,
+        model_time_ms: row.model_time_ms,
+        slow_time_ms: row.slow_time_ms,
+        suggested_time_limit_ms: row.suggested_time_limit_ms,
+        separation: row.separation,
+        worker_id: row.worker_id,
+        worker_speed_factor: row.worker_speed_factor,
+        error: row.error,
+        requested_by: row.requested_by,
+        created_at: row.created_at,
+        completed_at: row.completed_at,
+        results,
+    }
 }
 
 // =============================================================================
-// Contest Problems
+// Statistics
 // =============================================================================
 
-/// GET /api/v1/contests/{id}/problems
+/// Accepted solutions listed per ranking
+const STATISTICS_TOP_SOLUTIONS: i64 = 5;
+
+/// Submissions counted in problem statistics: judged ones, leaving out
+/// contests still running so live results do not leak, and the contest
+/// team's test submissions
+macro_rules! counted_problem_submissions {
+    () => {
+        r#"
+        SELECT s.*
+        FROM submissions s
+        LEFT JOIN contests c ON c.id = s.contest_id
+        WHERE s.problem_id = $1
+          AND (s.contest_id IS NULL OR s.is_practice OR c.end_time <= NOW())
+          AND NOT s.is_test
+          AND s.status NOT IN ('pending', 'compiling', 'compiled', 'judging', 'queue_pending')
+        "#
+    };
+}
+
+#[derive(Debug, FromRow)]
+struct ProblemVisibilityRow {
+    is_public: bool,
+    owner_id: Uuid,
+    organization_id: Option<Uuid>,
+}
+
+#[derive(Debug, FromRow)]
+struct AcceptedSolutionRow {
+    submission_id: Uuid,
+    contest_id: Option<Uuid>,
+    user_id: Uuid,
+    username: String,
+    display_name: Option<String>,
+    language: Option<String>,
+    time_ms: Option<i32>,
+    memory_kb: Option<i32>,
+    submitted_at: DateTime<Utc>,
+}
+
+/// GET /api/v1/problems/{id}/statistics
 ///
-/// List problems in a contest.
-pub async fn list_contest_problems(
+/// Submission and solver counts, acceptance rate, verdict and language
+/// breakdowns, and each user's fastest and lowest-memory accepted solution.
+/// Submissions of contests that have not ended yet are not counted, and a
+/// listed solution links its submission only for callers allowed to view it.
+pub async fn get_problem_statistics(
     State(state): State<AppState>,
     user: Option<Extension<AuthUser>>,
-    Path(contest_id): Path<Uuid>,
-) -> ApiResult<Json<ContestProblemsResponse>> {
-    // Check contest exists
-    let contest: Option<(bool, DateTime<Utc>, Uuid)> =
-        sqlx::query_as("SELECT is_public, start_time, owner_id FROM contests WHERE id = $1")
-            .bind(contest_id)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<ProblemStatisticsResponse>> {
+    let db = state.read_db();
+    let problem: ProblemVisibilityRow =
+        sqlx::query_as("SELECT is_public, owner_id, organization_id FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+    require_problem_visible(
+        &state,
+        user.as_deref(),
+        problem_id,
+        problem.is_public,
+        problem.owner_id,
+        problem.organization_id,
+    )
+    .await?;
 
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
+    let (attempted_by, solved_by): (i64, i64) = sqlx::query_as(concat!(
+        "WITH counted AS (",
+        counted_problem_submissions!(),
+        r#")
+        SELECT COUNT(DISTINCT user_id),
+               COUNT(DISTINCT user_id) FILTER (WHERE status = 'accepted')
+        FROM counted
+        "#
+    ))
+    .bind(problem_id)
+    .fetch_one(db)
+    .await?;
 
-    let user_id = user.as_ref().map(|u| u.id);
-    let user_role = user.as_ref().map(|u| u.role.as_str());
-    let now = Utc::now();
+    let status_counts: Vec<(String, i64)> = sqlx::query_as(concat!(
+        "WITH counted AS (",
+        counted_problem_submissions!(),
+        ") SELECT status, COUNT(*) FROM counted GROUP BY status"
+    ))
+    .bind(problem_id)
+    .fetch_all(db)
+    .await?;
 
-    // Check if user can view problems
-    // Problems visible if: contest started, or user is owner/admin/collaborator
-    let can_view = now >= contest.1 || user_id == Some(contest.2) || user_role == Some("admin");
+    let languages: Vec<(String, i64, i64)> = sqlx::query_as(concat!(
+        "WITH counted AS (",
+        counted_problem_submissions!(),
+        r#")
+        SELECT COALESCE(language, 'unknown'), COUNT(*),
+               COUNT(*) FILTER (WHERE status = 'accepted')
+        FROM counted
+        GROUP BY 1
+        ORDER BY 2 DESC, 1
+        "#
+    ))
+    .bind(problem_id)
+    .fetch_all(db)
+    .await?;
 
-    if !can_view {
-        // Check if collaborator
-        if let Some(uid) = user_id {
-            let is_collab: Option<(i64,)> = sqlx::query_as(
-                "SELECT 1 FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2",
+    // Each user's best accepted run by the given measure, best first
+    let best_solutions = |order_by: &'static str| {
+        format!(
+            r#"
+            WITH counted AS ({counted}),
+            best AS (
+                SELECT DISTINCT ON (s.user_id)
+                    s.id as submission_id, s.contest_id, s.user_id, u.username, u.display_name,
+                    s.language, s.max_time_ms as time_ms, s.max_memory_kb as memory_kb,
+                    s.submitted_at
+                FROM counted s
+                JOIN users u ON u.id = s.user_id
+                WHERE s.status = 'accepted' AND s.max_time_ms IS NOT NULL
+                  AND s.max_memory_kb IS NOT NULL
+                ORDER BY s.user_id, {order_by}, s.submitted_at
             )
-            .bind(contest_id)
-            .bind(uid)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+            SELECT * FROM best ORDER BY {order_by}, submitted_at LIMIT $2
+            "#,
+            counted = counted_problem_submissions!(),
+            order_by = order_by,
+        )
+    };
+    let fastest: Vec<AcceptedSolutionRow> = sqlx::query_as(&best_solutions("time_ms, memory_kb"))
+        .bind(problem_id)
+        .bind(STATISTICS_TOP_SOLUTIONS)
+        .fetch_all(db)
+        .await?;
+    let lowest_memory: Vec<AcceptedSolutionRow> =
+        sqlx::query_as(&best_solutions("memory_kb, time_ms"))
+            .bind(problem_id)
+            .bind(STATISTICS_TOP_SOLUTIONS)
+            .fetch_all(db)
+            .await?;
+
+    let verdicts: BTreeMap<String, i64> = status_counts.into_iter().collect();
+    let total_submissions: i64 = verdicts.values().sum();
+    let accepted_submissions = verdicts.get("accepted").copied().unwrap_or(0);
+
+    Ok(Json(ProblemStatisticsResponse {
+        problem_id,
+        total_submissions,
+        accepted_submissions,
+        acceptance_rate: (total_submissions > 0)
+            .then(|| accepted_submissions as f64 / total_submissions as f64),
+        attempted_by,
+        solved_by,
+        verdicts,
+        languages: languages
+            .into_iter()
+            .map(
+                |(language, submissions, accepted)| ProblemLanguageStatistics {
+                    language,
+                    submissions,
+                    accepted,
+                },
+            )
+            .collect(),
+        fastest: accepted_solutions(&state, user.as_deref(), fastest).await,
+        lowest_memory: accepted_solutions(&state, user.as_deref(), lowest_memory).await,
+    }))
+}
 
-            if is_collab.is_none() {
-                return Err(ApiError::Forbidden);
+/// Response entries for ranked solutions, linking each submission only if
+/// the user may view it
+async fn accepted_solutions(
+    state: &AppState,
+    user: Option<&AuthUser>,
+    rows: Vec<AcceptedSolutionRow>,
+) -> Vec<AcceptedSolutionInfo> {
+    let mut solutions = Vec::with_capacity(rows.len());
+    for row in rows {
+        let viewable = match user {
+            Some(user) => {
+                let mut ctx = build_auth_context(state, user).with_submission(row.submission_id);
+                if let Some(contest_id) = row.contest_id {
+                    ctx = ctx.with_contest(contest_id);
+                }
+                require_submission_view_access(&ctx).await.is_ok()
             }
-        } else {
-            return Err(ApiError::Forbidden);
+            None => false,
+        };
+        solutions.push(AcceptedSolutionInfo {
+            submission_id: viewable.then_some(row.submission_id),
+            user: OwnerInfo {
+                id: row.user_id,
+                username: row.username,
+                display_name: row.display_name,
+            },
+            language: row.language,
+            time_ms: row.time_ms,
+            memory_kb: row.memory_kb,
+            submitted_at: row.submitted_at,
+        });
+    }
+    solutions
+}
+
+// =============================================================================
+// Personal progress
+// =============================================================================
+
+/// Submissions listed in a user's progress on a problem
+const MY_PROGRESS_LIMIT: i64 = 200;
+
+#[derive(Debug, FromRow)]
+struct ProgressProblemRow {
+    is_public: bool,
+    owner_id: Uuid,
+    organization_id: Option<Uuid>,
+    rank_by_instructions: bool,
+}
+
+/// A judged submission with its per-test results aggregated
+#[derive(Debug, FromRow)]
+struct ProgressRow {
+    submission_id: Uuid,
+    contest_id: Option<Uuid>,
+    language: Option<String>,
+    status: String,
+    submitted_at: DateTime<Utc>,
+    tests_run: i64,
+    time_ms: Option<i32>,
+    total_time_ms: Option<i64>,
+    memory_kb: Option<i32>,
+    instructions: Option<i64>,
+}
+
+impl ProgressRow {
+    /// Ranking key of an accepted submission, lower is better: instructions
+    /// first on problems ranked by them, then time, then memory
+    fn rank_key(&self, by_instructions: bool) -> Option<(i64, i32, i32)> {
+        if self.status != "accepted" {
+            return None;
         }
+        let instructions = if by_instructions {
+            self.instructions?
+        } else {
+            0
+        };
+        Some((instructions, self.time_ms?, self.memory_kb?))
     }
+}
 
-    let rows: Vec<(
-        Uuid,
-        Uuid,
-        String,
-        String,
-        Option<String>,
-        i32,
-        i32,
-        i32,
-        bool,
-        i32,
-        i32,
-    )> = sqlx::query_as(
+/// GET /api/v1/problems/{id}/my-progress
+///
+/// The caller's judged submissions to a problem, oldest first, with time,
+/// memory and instructions aggregated from their test results and deltas
+/// against their best accepted submission. Each entry also says whether it
+/// improved on every accepted submission before it.
+pub async fn get_my_problem_progress(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<MyProblemProgressResponse>> {
+    let db = state.read_db();
+    let problem: ProgressProblemRow = sqlx::query_as(
+        "SELECT is_public, owner_id, organization_id, rank_by_instructions FROM problems WHERE id = $1",
+    )
+    .bind(problem_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    let mut rows: Vec<ProgressRow> = sqlx::query_as(
         r#"
-        SELECT 
-            cp.id, cp.problem_id, cp.problem_code, p.title, p.difficulty,
-            COALESCE(cp.time_limit_ms, p.time_limit_ms) as time_limit_ms,
-            COALESCE(cp.memory_limit_kb, p.memory_limit_kb) as memory_limit_kb,
-            COALESCE(cp.max_threads, p.max_threads) as max_threads,
-            COALESCE(cp.network_allowed, p.network_allowed) as network_allowed,
-            COALESCE(cp.max_score, p.max_score) as max_score,
-            cp.sort_order
-        FROM contest_problems cp
-        JOIN problems p ON cp.problem_id = p.id
-        WHERE cp.contest_id = $1
-        ORDER BY cp.sort_order, cp.problem_code
+        SELECT s.id as submission_id, s.contest_id, s.language, s.status, s.submitted_at,
+               COUNT(sr.id) as tests_run,
+               MAX(sr.time_ms) as time_ms,
+               SUM(sr.time_ms) as total_time_ms,
+               MAX(sr.memory_kb) as memory_kb,
+               SUM(sr.instructions)::BIGINT as instructions
+        FROM submissions s
+        LEFT JOIN submission_results sr ON sr.submission_id = s.id
+        WHERE s.problem_id = $1 AND s.user_id = $2
+          AND s.status NOT IN ('pending', 'compiling', 'compiled', 'judging', 'queue_pending')
+        GROUP BY s.id
+        ORDER BY s.submitted_at DESC
+        LIMIT $3
         "#,
     )
-    .bind(contest_id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+    .bind(problem_id)
+    .bind(user.id)
+    .bind(MY_PROGRESS_LIMIT)
+    .fetch_all(db)
+    .await?;
+    rows.reverse();
+
+    // Submitting to a problem is enough to follow one's own progress on it
+    // (e.g. a private problem of a past contest)
+    if rows.is_empty() {
+        require_problem_visible(
+            &state,
+            Some(&user),
+            problem_id,
+            problem.is_public,
+            problem.owner_id,
+            problem.organization_id,
+        )
+        .await?;
+    }
 
-    let problems: Vec<ContestProblemInfo> = rows
-        .into_iter()
-        .map(|row| ContestProblemInfo {
-            id: row.0,
-            problem_id: row.1,
-            problem_code: row.2,
-            title: row.3,
-            difficulty: row.4,
-            time_limit_ms: row.5,
-            memory_limit_kb: row.6,
-            max_threads: row.7,
-            network_allowed: row.8,
-            max_score: row.9,
-            sort_order: row.10,
-        })
-        .collect();
+    let by_instructions = problem.rank_by_instructions;
+    let best = rows
+        .iter()
+        .filter_map(|row| row.rank_key(by_instructions).map(|key| (key, row)))
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, row)| row);
+
+    let mut previous_best: Option<(i64, i32, i32)> = None;
+    let mut submissions = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let key = row.rank_key(by_instructions);
+        let improved = key.is_some_and(|key| previous_best.is_none_or(|best| key < best));
+        if improved {
+            previous_best = key;
+        }
+
+        // Deltas only compare accepted runs; positive means worse than best
+        let delta = |value: Option<i64>, best_value: Option<i64>| {
+            key.and(value).zip(best_value).map(|(v, b)| v - b)
+        };
+        let best_of = |f: fn(&ProgressRow) -> Option<i64>| best.and_then(f);
+        submissions.push(SubmissionProgressEntry {
+            submission_id: row.submission_id,
+            contest_id: row.contest_id,
+            language: row.language.clone(),
+            status: row.status.clone(),
+            submitted_at: row.submitted_at,
+            tests_run: row.tests_run,
+            time_ms: row.time_ms,
+            total_time_ms: row.total_time_ms,
+            memory_kb: row.memory_kb,
+            instructions: row.instructions,
+            time_delta_ms: delta(
+                row.time_ms.map(i64::from),
+                best_of(|b| b.time_ms.map(i64::from)),
+            ),
+            total_time_delta_ms: delta(row.total_time_ms, best_of(|b| b.total_time_ms)),
+            memory_delta_kb: delta(
+                row.memory_kb.map(i64::from),
+                best_of(|b| b.memory_kb.map(i64::from)),
+            ),
+            instructions_delta: delta(row.instructions, best_of(|b| b.instructions)),
+            is_best: best.is_some_and(|b| b.submission_id == row.submission_id),
+            improved,
+        });
+    }
+
+    Ok(Json(MyProblemProgressResponse {
+        problem_id,
+        ranked_by: if by_instructions {
+            "instructions"
+        } else {
+            "time"
+        }
+        .to_string(),
+        best_submission_id: best.map(|b| b.submission_id),
+        submissions,
+    }))
+}
+
+#[derive(Debug, FromRow)]
+struct RecommendationRow {
+    id: Uuid,
+    title: String,
+    difficulty: Option<String>,
+    tags: Option<Vec<String>>,
+    reason: String,
+    tag: Option<String>,
+    score: f64,
+}
+
+/// GET /api/v1/problems/recommended
+///
+/// Practice problems for the caller, ranked by Horus from their practice
+/// rating and the tags of what they solved. Problems that were solved or
+/// stopped being public since the last run are skipped.
+pub async fn get_recommended_problems(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Json<RecommendedProblemsResponse>> {
+    let db = state.read_db();
 
-    Ok(Json(ContestProblemsResponse { problems }))
+    let profile: Option<(i32, String, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT rating, target_difficulty, computed_at FROM user_practice_profiles \
+         WHERE user_id = $1",
+    )
+    .bind(user.id)
+    .fetch_optional(db)
+    .await?;
+
+    let rows: Vec<RecommendationRow> = sqlx::query_as(
+        r#"
+        SELECT p.id, p.title, p.difficulty, p.tags, r.reason, r.tag, r.score
+        FROM problem_recommendations r
+        JOIN problems p ON p.id = r.problem_id
+        WHERE r.user_id = $1 AND p.is_public
+          AND NOT EXISTS (
+              SELECT 1 FROM user_solves us
+              WHERE us.user_id = r.user_id AND us.problem_id = r.problem_id
+          )
+        ORDER BY r.rank
+        "#,
+    )
+    .bind(user.id)
+    .fetch_all(db)
+    .await?;
+
+    let (rating, target_difficulty, computed_at) = match profile {
+        Some((rating, target, computed_at)) => (Some(rating), Some(target), Some(computed_at)),
+        None => (None, None, None),
+    };
+
+    Ok(Json(RecommendedProblemsResponse {
+        rating,
+        target_difficulty,
+        computed_at,
+        problems: rows
+            .into_iter()
+            .map(|row| RecommendedProblem {
+                id: row.id,
+                title: row.title,
+                difficulty: row.difficulty,
+                tags: row.tags,
+                reason: row.reason,
+                tag: row.tag,
+                score: row.score,
+            })
+            .collect(),
+    }))
 }
 
-/// POST /api/v1/contests/{id}/problems
+// =============================================================================
+// Contest Problems
+// =============================================================================
+
+/// GET /api/v1/contests/{id}/problems
 ///
-/// Add a problem to contest.
-pub async fn add_problem_to_contest(
+/// List problems in a contest. Participants only see problems whose
+/// `visible_from` has passed.
+pub async fn list_contest_problems(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    user: Option<Extension<AuthUser>>,
     Path(contest_id): Path<Uuid>,
-    Json(payload): Json<AddProblemToContestRequest>,
-) -> ApiResult<(StatusCode, Json<ContestProblemInfo>)> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
-    // Check contest exists and user has permission
-    let contest: Option<(Uuid,)> = sqlx::query_as("SELECT owner_id FROM contests WHERE id = $1")
-        .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+) -> ApiResult<Json<ContestProblemsResponse>> {
+    // Check contest exists
+    let contest: Option<(bool, DateTime<Utc>, Uuid)> =
+        sqlx::query_as("SELECT is_public, start_time, owner_id FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_optional(state.read_db())
+            .await
+            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
 
     let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
 
-    // Check permission (owner, admin, or collaborator with can_add_problems)
-    let has_permission = if contest.0 == user.id || user.role == "admin" {
-        true
-    } else {
-        let collab: Option<(bool,)> = sqlx::query_as(
-            "SELECT can_add_problems FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2"
-        )
+    let user_id = user.as_ref().map(|u| u.id);
+    let is_moderator = user
+        .as_ref()
+        .is_some_and(|u| u.can(Permission::ModerateContests));
+    let now = Utc::now();
+
+    // Owners, moderators and collaborators see every problem at any time
+    let mut is_staff = user_id == Some(contest.2) || is_moderator;
+    if !is_staff {
+        if let Some(uid) = user_id {
+            let is_collab: Option<(i64,)> = sqlx::query_as(
+                "SELECT 1 FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2",
+            )
             .bind(contest_id)
-            .bind(user.id)
-            .fetch_optional(&state.db)
+            .bind(uid)
+            .fetch_optional(state.read_db())
             .await
             .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+            is_staff = is_collab.is_some();
+        }
+    }
 
-        collab.map(|c| c.0).unwrap_or(false)
-    };
-
-    if !has_permission {
+    // Everyone else sees problems once the contest has started, each from
+    // its release time
+    if !is_staff && now < contest.1 {
         return Err(ApiError::Forbidden);
     }
 
+    let released_at = (!is_staff).then_some(now);
+    let problems = fetch_contest_problems(state.read_db(), contest_id, released_at).await?;
+
+    Ok(Json(ContestProblemsResponse { problems }))
+}
+
+/// Problem defaults used when adding it to a contest: title, difficulty,
+/// time/memory limits, max score, network, max threads, allowed languages,
+/// owning organization.
+type ProblemDefaultsRow = (
+    String,
+    Option<String>,
+    i32,
+    i32,
+    i32,
+    bool,
+    i32,
+    Option<Vec<String>>,
+    Option<Uuid>,
+);
+
+/// Message if a problem owned by an organization cannot be used in a contest
+/// hosted by `contest_organization`.
+fn tenant_mismatch_error(
+    problem_organization: Option<Uuid>,
+    contest_organization: Option<Uuid>,
+) -> Option<String> {
+    match problem_organization {
+        Some(org) if contest_organization != Some(org) => Some(
+            "Problem belongs to an organization and can only be used in its contests".to_string(),
+        ),
+        _ => None,
+    }
+}
+
+/// POST /api/v1/contests/{id}/problems
+///
+/// Add a problem to contest.
+pub async fn add_problem_to_contest(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::AddProblems>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<AddProblemToContestRequest>,
+) -> ApiResult<(StatusCode, Json<ContestProblemInfo>)> {
     // Validate max_threads override against system-wide cap
-    if let Some(mt) = payload.max_threads {
-        let max_threads_limit = state.config.max_threads_limit;
-        if mt > max_threads_limit {
-            return Err(ApiError::Validation(
-                format!(
-                    "max_threads ({}) exceeds the system limit of {}. Please set a value between 1 and {}.",
-                    mt, max_threads_limit, max_threads_limit
-                ),
-            ));
-        }
-        if mt < 1 {
-            return Err(ApiError::Validation(
-                "max_threads must be at least 1.".to_string(),
-            ));
-        }
+    if let Some(message) =
+        max_threads_override_error(payload.max_threads, state.config.max_threads_limit)
+    {
+        return Err(ApiError::Validation(message));
     }
 
     // Check problem exists
-    let problem: Option<(String, Option<String>, i32, i32, i32, bool, i32)> = sqlx::query_as(
-        "SELECT title, difficulty, time_limit_ms, memory_limit_kb, max_score, network_allowed, max_threads FROM problems WHERE id = $1"
+    let problem: Option<ProblemDefaultsRow> = sqlx::query_as(
+        "SELECT title, difficulty, time_limit_ms, memory_limit_kb, max_score, network_allowed, max_threads, allowed_languages, organization_id FROM problems WHERE id = $1"
     )
         .bind(payload.problem_id)
         .fetch_optional(&state.db)
@@ -775,6 +3060,19 @@ pub async fn add_problem_to_contest(
 
     let problem = problem.ok_or(ApiError::NotFound("Problem not found".to_string()))?;
 
+    // Organization problems stay within their organization's contests
+    let (contest_organization, contest_end): (Option<Uuid>, DateTime<Utc>) =
+        sqlx::query_as("SELECT organization_id, end_time FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_one(&state.db)
+            .await?;
+    if let Some(message) = tenant_mismatch_error(problem.8, contest_organization) {
+        return Err(ApiError::Validation(message));
+    }
+    if let Some(message) = visible_from_error(payload.visible_from, contest_end) {
+        return Err(ApiError::Validation(message));
+    }
+
     // Check if problem already in contest
     let existing: Option<(i64,)> =
         sqlx::query_as("SELECT 1 FROM contest_problems WHERE contest_id = $1 AND problem_id = $2")
@@ -815,8 +3113,8 @@ pub async fn add_problem_to_contest(
         INSERT INTO contest_problems (
             id, contest_id, problem_id, problem_code, sort_order,
             max_score, time_limit_ms, memory_limit_kb, max_threads, network_allowed,
-            added_at, added_by
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            allowed_languages, added_at, added_by, visible_from
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
         "#,
     )
     .bind(id)
@@ -829,11 +3127,15 @@ pub async fn add_problem_to_contest(
     .bind(payload.memory_limit_kb)
     .bind(payload.max_threads)
     .bind(payload.network_allowed)
+    .bind(&payload.allowed_languages)
     .bind(now)
     .bind(user.id)
+    .bind(payload.visible_from)
     .execute(&state.db)
     .await
-    .map_err(|e| ApiError::Internal(format!("Failed to add problem: {}", e)))?;
+    .map_err(error::conflict_on_unique(
+        "Problem or problem code already in contest",
+    ))?;
 
     Ok((
         StatusCode::CREATED,
@@ -849,6 +3151,8 @@ pub async fn add_problem_to_contest(
             network_allowed: payload.network_allowed.unwrap_or(problem.5),
             max_score: payload.max_score.unwrap_or(problem.4),
             sort_order,
+            allowed_languages: payload.allowed_languages.or(problem.7),
+            visible_from: payload.visible_from,
         }),
     ))
 }
@@ -858,64 +3162,587 @@ pub async fn add_problem_to_contest(
 /// Remove a problem from contest.
 pub async fn remove_problem_from_contest(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    _guard: Guard<policies::AddProblems>,
     Path((contest_id, problem_id)): Path<(Uuid, Uuid)>,
 ) -> ApiResult<StatusCode> {
-    // Check contest exists and user has permission
-    let contest: Option<(Uuid,)> = sqlx::query_as("SELECT owner_id FROM contests WHERE id = $1")
+    let result =
+        sqlx::query("DELETE FROM contest_problems WHERE contest_id = $1 AND problem_id = $2")
+            .bind(contest_id)
+            .bind(problem_id)
+            .execute(&state.db)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to remove problem: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Problem not in contest".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /api/v1/contests/{id}/problems/{problem_id}/visibility
+///
+/// Schedule when a contest problem is released to participants.
+pub async fn update_contest_problem_visibility(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::AddProblems>,
+    Path((contest_id, problem_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(payload): ValidatedJson<UpdateContestProblemVisibilityRequest>,
+) -> ApiResult<Json<ContestProblemsResponse>> {
+    let contest_end: DateTime<Utc> =
+        sqlx::query_scalar("SELECT end_time FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_one(&state.db)
+            .await?;
+    if let Some(message) = visible_from_error(payload.visible_from, contest_end) {
+        return Err(ApiError::Validation(message));
+    }
+
+    let result = sqlx::query(
+        "UPDATE contest_problems SET visible_from = $1 WHERE contest_id = $2 AND problem_id = $3",
+    )
+    .bind(payload.visible_from)
+    .bind(contest_id)
+    .bind(problem_id)
+    .execute(&state.db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Problem not in contest".to_string()));
+    }
+
+    tracing::info!(
+        contest_id = %contest_id,
+        problem_id = %problem_id,
+        user_id = %user.id,
+        visible_from = ?payload.visible_from,
+        "Scheduled contest problem release"
+    );
+
+    let problems = fetch_contest_problems(&state.db, contest_id, None).await?;
+    Ok(Json(ContestProblemsResponse { problems }))
+}
+
+const BASELINE_COLUMNS: &str = "id, contest_id, problem_id, submission_id, status, verdict, \
+     max_time_ms, max_memory_kb, total_instructions, worker_id, worker_speed_factor, error, \
+     requested_by, created_at, completed_at";
+
+/// Database row for contest_problem_baselines
+#[derive(Debug, FromRow)]
+struct BaselineRow {
+    id: Uuid,
+    contest_id: Uuid,
+    problem_id: Uuid,
+    submission_id: Uuid,
+    status: String,
+    verdict: Option<String>,
+    max_time_ms: Option<i32>,
+    max_memory_kb: Option<i32>,
+    total_instructions: Option<i64>,
+    worker_id: Option<String>,
+    worker_speed_factor: Option<f64>,
+    error: Option<String>,
+    requested_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<BaselineRow> for ContestProblemBaselineResponse {
+    fn from(row: BaselineRow) -> Self {
+        Self {
+            id: row.id,
+            contest_id: row.contest_id,
+            problem_id: row.problem_id,
+            submission_id: row.submission_id,
+            status: row.status,
+            verdict: row.verdict,
+            max_time_ms: row.max_time_ms,
+            max_memory_kb: row.max_memory_kb,
+            total_instructions: row.total_instructions,
+            worker_id: row.worker_id,
+            worker_speed_factor: row.worker_speed_factor,
+            error: row.error,
+            requested_by: row.requested_by,
+            created_at: row.created_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+/// POST /api/v1/contests/{id}/problems/{problem_id}/baseline
+///
+/// Judge the problem's model reference solution as a baseline for a
+/// performance-scored contest. A Minos worker picks the run up from the
+/// regeneration stream and judges it under the contest's limits; once it is
+/// accepted, its time and memory appear on the leaderboard as a non-scoring
+/// entry. Replaces the problem's previous baseline in the contest.
+pub async fn judge_contest_problem_baseline(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::AddProblems>,
+    Path((contest_id, problem_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<(StatusCode, Json<ContestProblemBaselineResponse>)> {
+    let scoring_type: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT c.scoring_type FROM contests c
+        JOIN contest_problems cp ON cp.contest_id = c.id
+        WHERE c.id = $1 AND cp.problem_id = $2
+        "#,
+    )
+    .bind(contest_id)
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not in contest".to_string()))?;
+
+    if scoring_type.as_deref() != Some("performance") {
+        return Err(ApiError::Conflict(
+            "Baselines are shown on performance-scored contests only".to_string(),
+        ));
+    }
+
+    // The earliest marked model solution is the problem's reference
+    let submission_id: Uuid = sqlx::query_scalar(
+        r#"
+        SELECT submission_id FROM problem_reference_solutions
+        WHERE problem_id = $1 AND role = 'model'
+        ORDER BY created_at
+        LIMIT 1
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::Conflict("The problem has no model reference solution".to_string()))?;
+
+    let row = sqlx::query_as::<_, BaselineRow>(&format!(
+        r#"
+        INSERT INTO contest_problem_baselines
+            (contest_id, problem_id, submission_id, requested_by)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (contest_id, problem_id) DO UPDATE
+        SET submission_id = EXCLUDED.submission_id, status = 'pending', verdict = NULL,
+            max_time_ms = NULL, max_memory_kb = NULL, total_instructions = NULL,
+            worker_id = NULL, worker_speed_factor = NULL, error = NULL,
+            requested_by = EXCLUDED.requested_by, created_at = NOW(), completed_at = NULL
+        RETURNING {}
+        "#,
+        BASELINE_COLUMNS
+    ))
+    .bind(contest_id)
+    .bind(problem_id)
+    .bind(submission_id)
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let mut conn = state.redis.get().await?;
+    redis::cmd("XADD")
+        .arg(&state.config.regen_stream)
+        .arg("*")
+        .arg("kind")
+        .arg("baseline")
+        .arg("baseline_id")
+        .arg(row.id.to_string())
+        .query_async::<String>(&mut conn)
+        .await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        problem_id = %problem_id,
+        user_id = %user.id,
+        baseline_id = %row.id,
+        submission_id = %submission_id,
+        "Reference baseline requested"
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(row.into())))
+}
+
+/// GET /api/v1/contests/{id}/problems/{problem_id}/baseline
+///
+/// Status of the problem's reference baseline in the contest.
+pub async fn get_contest_problem_baseline(
+    State(state): State<AppState>,
+    _guard: Guard<policies::AddProblems>,
+    Path((contest_id, problem_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<ContestProblemBaselineResponse>> {
+    let row = sqlx::query_as::<_, BaselineRow>(&format!(
+        "SELECT {} FROM contest_problem_baselines WHERE contest_id = $1 AND problem_id = $2",
+        BASELINE_COLUMNS
+    ))
+    .bind(contest_id)
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("The problem has no baseline".to_string()))?;
+
+    Ok(Json(row.into()))
+}
+
+/// PUT /api/v1/contests/{id}/problems/order
+///
+/// Reorder contest problems. The request must list every problem code in the
+/// contest exactly once; `sort_order` is set to each code's position.
+pub async fn reorder_contest_problems(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::AddProblems>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<ReorderContestProblemsRequest>,
+) -> ApiResult<Json<ContestProblemsResponse>> {
+    let mut tx = state.db.begin().await?;
+
+    let current: Vec<String> = sqlx::query_scalar(
+        "SELECT problem_code FROM contest_problems WHERE contest_id = $1 FOR UPDATE",
+    )
+    .bind(contest_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut seen = std::collections::HashSet::new();
+    if let Some(dup) = payload
+        .problem_codes
+        .iter()
+        .find(|c| !seen.insert(c.as_str()))
+    {
+        return Err(ApiError::Validation(format!(
+            "Problem code '{}' is listed more than once",
+            dup
+        )));
+    }
+
+    if let Some(unknown) = payload.problem_codes.iter().find(|c| !current.contains(c)) {
+        return Err(ApiError::Validation(format!(
+            "Problem code '{}' is not in this contest",
+            unknown
+        )));
+    }
+
+    let missing: Vec<&str> = current
+        .iter()
+        .filter(|c| !seen.contains(c.as_str()))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(ApiError::Validation(format!(
+            "Order must include every problem in the contest; missing: {}",
+            missing.join(", ")
+        )));
+    }
+
+    for (position, code) in payload.problem_codes.iter().enumerate() {
+        sqlx::query(
+            "UPDATE contest_problems SET sort_order = $1 WHERE contest_id = $2 AND problem_code = $3",
+        )
+        .bind(position as i32)
         .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+        .bind(code)
+        .execute(&mut *tx)
+        .await?;
+    }
 
-    let contest = contest.ok_or(ApiError::NotFound("Contest not found".to_string()))?;
+    tx.commit().await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        user_id = %user.id,
+        count = payload.problem_codes.len(),
+        "Reordered contest problems"
+    );
+
+    let problems = fetch_contest_problems(&state.db, contest_id, None).await?;
+    Ok(Json(ContestProblemsResponse { problems }))
+}
+
+/// Maximum problems per bulk add request
+const MAX_BULK_PROBLEMS: usize = 50;
+
+/// POST /api/v1/contests/{id}/problems/bulk
+///
+/// Add several problems to a contest in one transaction. Items that are
+/// invalid (failed field rules or override) or conflict (unknown problem,
+/// already in the contest, code taken, duplicate within the request) are
+/// skipped and reported by index; the rest are inserted together.
+pub async fn bulk_add_problems_to_contest(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::AddProblems>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<BulkAddProblemsRequest>,
+) -> ApiResult<Json<BulkAddProblemsResponse>> {
+    if payload.problems.is_empty() || payload.problems.len() > MAX_BULK_PROBLEMS {
+        return Err(ApiError::InvalidFields(vec![FieldError {
+            field: "problems".to_string(),
+            rule: "length".to_string(),
+            message: format!(
+                "Between 1 and {} problems can be added at once",
+                MAX_BULK_PROBLEMS
+            ),
+        }]));
+    }
+
+    let mut tx = state.db.begin().await?;
+
+    // Serialize concurrent edits of this contest's problem set
+    let (contest_organization, contest_end): (Option<Uuid>, DateTime<Utc>) =
+        sqlx::query_as("SELECT organization_id, end_time FROM contests WHERE id = $1 FOR UPDATE")
+            .bind(contest_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    let existing: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT problem_id, problem_code FROM contest_problems WHERE contest_id = $1",
+    )
+    .bind(contest_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut taken_problems: std::collections::HashSet<Uuid> =
+        existing.iter().map(|(id, _)| *id).collect();
+    let mut taken_codes: std::collections::HashSet<String> =
+        existing.into_iter().map(|(_, code)| code).collect();
+
+    let mut next_sort_order: i32 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM contest_problems WHERE contest_id = $1",
+    )
+    .bind(contest_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let mut added = Vec::new();
+    let mut conflicts = Vec::new();
+    let now = Utc::now();
+
+    for (index, item) in payload.problems.into_iter().enumerate() {
+        let conflict = |reason: String| BulkAddConflict {
+            index,
+            problem_id: item.problem_id,
+            problem_code: item.problem_code.clone(),
+            reason,
+        };
+
+        if let Err(errors) = item.validate() {
+            let reason = error::field_errors(&errors)
+                .into_iter()
+                .map(|e| format!("{}: {}", e.field, e.message))
+                .collect::<Vec<_>>()
+                .join("; ");
+            conflicts.push(conflict(reason));
+            continue;
+        }
+        if let Some(message) =
+            max_threads_override_error(item.max_threads, state.config.max_threads_limit)
+        {
+            conflicts.push(conflict(message));
+            continue;
+        }
+        if let Some(message) = visible_from_error(item.visible_from, contest_end) {
+            conflicts.push(conflict(message));
+            continue;
+        }
+        if taken_problems.contains(&item.problem_id) {
+            conflicts.push(conflict("Problem already in contest".to_string()));
+            continue;
+        }
+        if taken_codes.contains(&item.problem_code) {
+            conflicts.push(conflict("Problem code already used in contest".to_string()));
+            continue;
+        }
+
+        let problem: Option<ProblemDefaultsRow> = sqlx::query_as(
+            "SELECT title, difficulty, time_limit_ms, memory_limit_kb, max_score, network_allowed, max_threads, allowed_languages, organization_id FROM problems WHERE id = $1"
+        )
+        .bind(item.problem_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(problem) = problem else {
+            conflicts.push(conflict("Problem not found".to_string()));
+            continue;
+        };
+        if let Some(message) = tenant_mismatch_error(problem.8, contest_organization) {
+            conflicts.push(conflict(message));
+            continue;
+        }
 
-    // Check permission
-    let has_permission = if contest.0 == user.id || user.role == "admin" {
-        true
-    } else {
-        let collab: Option<(bool,)> = sqlx::query_as(
-            "SELECT can_add_problems FROM contest_collaborators WHERE contest_id = $1 AND user_id = $2"
+        let id = Uuid::new_v4();
+        let sort_order = item.sort_order.unwrap_or(next_sort_order);
+        next_sort_order = next_sort_order.max(sort_order + 1);
+
+        sqlx::query(
+            r#"
+            INSERT INTO contest_problems (
+                id, contest_id, problem_id, problem_code, sort_order,
+                max_score, time_limit_ms, memory_limit_kb, max_threads, network_allowed,
+                allowed_languages, added_at, added_by, visible_from
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            "#,
         )
-            .bind(contest_id)
-            .bind(user.id)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+        .bind(id)
+        .bind(contest_id)
+        .bind(item.problem_id)
+        .bind(&item.problem_code)
+        .bind(sort_order)
+        .bind(item.max_score)
+        .bind(item.time_limit_ms)
+        .bind(item.memory_limit_kb)
+        .bind(item.max_threads)
+        .bind(item.network_allowed)
+        .bind(&item.allowed_languages)
+        .bind(now)
+        .bind(user.id)
+        .bind(item.visible_from)
+        .execute(&mut *tx)
+        .await?;
+
+        taken_problems.insert(item.problem_id);
+        taken_codes.insert(item.problem_code.clone());
+
+        added.push(ContestProblemInfo {
+            id,
+            problem_id: item.problem_id,
+            problem_code: item.problem_code,
+            title: problem.0,
+            difficulty: problem.1,
+            time_limit_ms: item.time_limit_ms.unwrap_or(problem.2),
+            memory_limit_kb: item.memory_limit_kb.unwrap_or(problem.3),
+            max_threads: item.max_threads.unwrap_or(problem.6),
+            network_allowed: item.network_allowed.unwrap_or(problem.5),
+            max_score: item.max_score.unwrap_or(problem.4),
+            sort_order,
+            allowed_languages: item.allowed_languages.or(problem.7),
+            visible_from: item.visible_from,
+        });
+    }
 
-        collab.map(|c| c.0).unwrap_or(false)
-    };
+    tx.commit().await?;
 
-    if !has_permission {
-        return Err(ApiError::Forbidden);
+    tracing::info!(
+        contest_id = %contest_id,
+        user_id = %user.id,
+        added = added.len(),
+        conflicts = conflicts.len(),
+        "Bulk added problems to contest"
+    );
+
+    Ok(Json(BulkAddProblemsResponse { added, conflicts }))
+}
+
+/// Validate a contest-level max_threads override against the system-wide cap.
+fn max_threads_override_error(max_threads: Option<i32>, limit: i32) -> Option<String> {
+    match max_threads {
+        Some(mt) if mt > limit => Some(format!(
+            "max_threads ({}) exceeds the system limit of {}. Please set a value between 1 and {}.",
+            mt, limit, limit
+        )),
+        Some(mt) if mt < 1 => Some("max_threads must be at least 1.".to_string()),
+        _ => None,
     }
+}
 
-    let result =
-        sqlx::query("DELETE FROM contest_problems WHERE contest_id = $1 AND problem_id = $2")
-            .bind(contest_id)
-            .bind(problem_id)
-            .execute(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Failed to remove problem: {}", e)))?;
+/// Problems can only be assigned a profile some judge workers run under,
+/// or their submissions would never be judged.
+fn validate_worker_profile(state: &AppState, profile: &str) -> ApiResult<()> {
+    let profiles = &state.config.worker_profiles;
+    if profiles.contains(profile) {
+        return Ok(());
+    }
+    Err(ApiError::Validation(format!(
+        "Unknown worker profile '{}'. Expected one of: {}",
+        profile,
+        profiles.names().join(", ")
+    )))
+}
 
-    if result.rows_affected() == 0 {
-        return Err(ApiError::NotFound("Problem not in contest".to_string()));
+/// A release time after the contest ends would hide the problem for good.
+fn visible_from_error(
+    visible_from: Option<DateTime<Utc>>,
+    contest_end: DateTime<Utc>,
+) -> Option<String> {
+    match visible_from {
+        Some(at) if at >= contest_end => {
+            Some("visible_from must be before the contest ends".to_string())
+        }
+        _ => None,
     }
+}
 
-    Ok(StatusCode::NO_CONTENT)
+/// Load a contest's problems with effective limits, in display order.
+/// With `released_at`, only problems released by then are included.
+async fn fetch_contest_problems(
+    db: &sqlx::PgPool,
+    contest_id: Uuid,
+    released_at: Option<DateTime<Utc>>,
+) -> ApiResult<Vec<ContestProblemInfo>> {
+    #[allow(clippy::type_complexity)]
+    let rows: Vec<(
+        Uuid,
+        Uuid,
+        String,
+        String,
+        Option<String>,
+        i32,
+        i32,
+        i32,
+        bool,
+        i32,
+        i32,
+        Option<Vec<String>>,
+        Option<DateTime<Utc>>,
+    )> = sqlx::query_as(
+        r#"
+        SELECT 
+            cp.id, cp.problem_id, cp.problem_code, p.title, p.difficulty,
+            COALESCE(cp.time_limit_ms, p.time_limit_ms) as time_limit_ms,
+            COALESCE(cp.memory_limit_kb, p.memory_limit_kb) as memory_limit_kb,
+            COALESCE(cp.max_threads, p.max_threads) as max_threads,
+            COALESCE(cp.network_allowed, p.network_allowed) as network_allowed,
+            COALESCE(cp.max_score, p.max_score) as max_score,
+            cp.sort_order,
+            COALESCE(cp.allowed_languages, p.allowed_languages) as allowed_languages,
+            cp.visible_from
+        FROM contest_problems cp
+        JOIN problems p ON cp.problem_id = p.id
+        WHERE cp.contest_id = $1
+          AND ($2::timestamptz IS NULL OR cp.visible_from IS NULL OR cp.visible_from <= $2)
+        ORDER BY cp.sort_order, cp.problem_code
+        "#,
+    )
+    .bind(contest_id)
+    .bind(released_at)
+    .fetch_all(db)
+    .await
+    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ContestProblemInfo {
+            id: row.0,
+            problem_id: row.1,
+            problem_code: row.2,
+            title: row.3,
+            difficulty: row.4,
+            time_limit_ms: row.5,
+            memory_limit_kb: row.6,
+            max_threads: row.7,
+            network_allowed: row.8,
+            max_score: row.9,
+            sort_order: row.10,
+            allowed_languages: row.11,
+            visible_from: row.12,
+        })
+        .collect())
 }
 
 // =============================================================================
-// Binary Upload Handlers (Generator & Checker)
+// Binary Upload Handlers (Generator, Checker & Reference)
 // =============================================================================
 
 /// Maximum binary file size (50MB)
 const MAX_BINARY_SIZE: usize = 50 * 1024 * 1024;
 
 /// Validate that uploaded file is a valid ELF executable
-fn validate_elf_binary(data: &[u8]) -> Result<(), ApiError> {
+fn validate_elf_binary(upload: &SpooledUpload) -> Result<(), ApiError> {
     // ELF magic number: 0x7F 'E' 'L' 'F'
-    if data.len() < 4 || &data[0..4] != b"\x7fELF" {
+    if !upload.starts_with(b"\x7fELF") {
         return Err(ApiError::Validation(
             "Uploaded file is not a valid Linux ELF executable".to_string(),
         ));
@@ -923,103 +3750,46 @@ fn validate_elf_binary(data: &[u8]) -> Result<(), ApiError> {
     Ok(())
 }
 
-/// Check if user has permission to access/modify problem binaries.
-///
-/// Access is granted to:
-/// 1. Admins (role = "admin")
-/// 2. Problem owner (owner_id matches user.id)
-/// 3. Organizers (role = "organizer") who own a contest containing this problem
-/// 4. Collaborators of any contest that contains this problem
-async fn check_problem_binary_permission(
-    state: &AppState,
-    problem_id: Uuid,
-    user: &AuthUser,
-) -> Result<(), ApiError> {
-    // Admins can always access
-    if user.role == "admin" {
-        return Ok(());
-    }
+/// Stream the `file` field of a binary upload to disk and check it is an
+/// ELF executable
+async fn read_binary_upload(multipart: &mut Multipart) -> ApiResult<SpooledUpload> {
+    let mut upload: Option<SpooledUpload> = None;
 
-    // Check if user is the problem owner
-    let owner_id: Option<Uuid> = sqlx::query_scalar("SELECT owner_id FROM problems WHERE id = $1")
-        .bind(problem_id)
-        .fetch_optional(&state.db)
+    while let Some(field) = multipart
+        .next_field()
         .await
-        .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    match owner_id {
-        Some(id) if id == user.id => return Ok(()),
-        None => return Err(ApiError::NotFound("Problem not found".to_string())),
-        _ => {}
-    }
-
-    // Check if user is an organizer who owns a contest containing this problem
-    let is_contest_owner: Option<bool> = sqlx::query_scalar(
-        r#"
-        SELECT EXISTS(
-            SELECT 1 FROM contest_problems cp
-            JOIN contests c ON c.id = cp.contest_id
-            WHERE cp.problem_id = $1 AND c.owner_id = $2
-        )
-        "#,
-    )
-    .bind(problem_id)
-    .bind(user.id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    if is_contest_owner.unwrap_or(false) {
-        return Ok(());
-    }
-
-    // Check if user is a collaborator of any contest containing this problem
-    // Collaborators with can_add_problems permission can manage problem binaries
-    let is_collaborator: Option<bool> = sqlx::query_scalar(
-        r#"
-        SELECT EXISTS(
-            SELECT 1 FROM contest_problems cp
-            JOIN contest_collaborators cc ON cc.contest_id = cp.contest_id
-            WHERE cp.problem_id = $1 
-              AND cc.user_id = $2
-              AND cc.can_add_problems = true
-        )
-        "#,
-    )
-    .bind(problem_id)
-    .bind(user.id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|e| ApiError::Internal(format!("Database error: {}", e)))?;
-
-    if is_collaborator.unwrap_or(false) {
-        return Ok(());
+        .map_err(|e| ApiError::Validation(format!("Failed to read multipart: {}", e)))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" {
+            upload = Some(SpooledUpload::from_field(field, MAX_BINARY_SIZE as u64).await?);
+        }
     }
 
-    Err(ApiError::Forbidden)
+    let upload = upload.ok_or_else(|| ApiError::Validation("No file uploaded".to_string()))?;
+    validate_elf_binary(&upload)?;
+    Ok(upload)
 }
 
-/// When both generator and checker binaries exist for a problem,
-/// find all submissions in `queue_pending` status for that problem
-/// and re-queue them on the `run_queue` Redis Stream for judging.
+/// When every binary the problem needs (generator and checker or
+/// reference, or those its pipeline runs) exists, find all submissions in `queue_pending`
+/// status for that problem and re-queue them on the `run_queue` Redis
+/// Stream of the problem's worker profile for judging.
 async fn requeue_pending_submissions(state: &AppState, problem_id: Uuid) -> ApiResult<u64> {
-    // Check if both binaries now exist
-    let dir_path = format!("/mnt/data/binaries/problems/{}", problem_id);
-    let generator_exists = tokio::fs::metadata(format!("{}/generator", dir_path))
-        .await
-        .is_ok();
-    let checker_exists = tokio::fs::metadata(format!("{}/checker", dir_path))
+    // Check if the binaries now exist
+    let pipeline = load_pipeline(&state.db, problem_id).await?;
+    let compares_output = compares_output(&state.db, problem_id).await?;
+    if !missing_binaries(problem_id, pipeline.as_ref(), compares_output)
         .await
-        .is_ok();
-
-    if !generator_exists || !checker_exists {
+        .is_empty()
+    {
         return Ok(0);
     }
 
     // Fetch problem limits for the run_queue message
     let problem = sqlx::query_as::<_, ProblemLimitsRow>(
         r#"
-        SELECT id, time_limit_ms, memory_limit_kb, num_test_cases, max_threads, network_allowed
+        SELECT time_limit_ms, memory_limit_kb, num_test_cases, worker_profile
         FROM problems WHERE id = $1
         "#,
     )
@@ -1031,8 +3801,9 @@ async fn requeue_pending_submissions(state: &AppState, problem_id: Uuid) -> ApiR
     // Find queue_pending submissions
     let pending_submissions = sqlx::query_as::<_, PendingSubmissionRow>(
         r#"
-        SELECT s.id as submission_id, s.contest_id
+        SELECT s.id as submission_id, s.contest_id, s.storage_root, a.path AS artifact_path
         FROM submissions s
+        LEFT JOIN submission_artifacts a ON a.submission_id = s.id
         WHERE s.problem_id = $1 AND s.status = 'queue_pending'
         "#,
     )
@@ -1044,16 +3815,38 @@ async fn requeue_pending_submissions(state: &AppState, problem_id: Uuid) -> ApiR
         return Ok(0);
     }
 
+    let run_stream = profile_stream(&state.config.run_stream, &problem.worker_profile);
     let mut conn = state.redis.get().await?;
     let mut requeued: u64 = 0;
 
     for sub in &pending_submissions {
-        // Look up the user binary path
-        let binary_path = format!("/mnt/data/binaries/users/{}_bin", sub.submission_id);
+        // The artifact Sisyphus recorded, else where it saves artifacts under
+        // the submission's storage root
+        let binary_path = match &sub.artifact_path {
+            Some(path) => path.clone(),
+            None => {
+                let Some(root) = state
+                    .config
+                    .storage_roots
+                    .resolve(sub.storage_root.as_deref())
+                else {
+                    tracing::warn!(
+                        submission_id = %sub.submission_id,
+                        storage_root = ?sub.storage_root,
+                        "Storage root is not configured; leaving the submission pending"
+                    );
+                    continue;
+                };
+                root.join(USER_BINARIES_DIR)
+                    .join(format!("{}_bin", sub.submission_id))
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        };
 
         // Push to run_queue
-        let _: String = redis::cmd("XADD")
-            .arg("run_queue")
+        let mut xadd = redis::cmd("XADD");
+        xadd.arg(&run_stream)
             .arg("*")
             .arg("submission_id")
             .arg(sub.submission_id.to_string())
@@ -1068,9 +3861,9 @@ async fn requeue_pending_submissions(state: &AppState, problem_id: Uuid) -> ApiR
             .arg("num_testcases")
             .arg(problem.num_test_cases.to_string())
             .arg("binary_path")
-            .arg(&binary_path)
-            .query_async(&mut *conn)
-            .await?;
+            .arg(&binary_path);
+        tag_stream_entry(&mut xadd);
+        let _: String = xadd.query_async(&mut *conn).await?;
 
         // Update status back to compiled
         sqlx::query("UPDATE submissions SET status = 'compiled' WHERE id = $1")
@@ -1092,213 +3885,731 @@ async fn requeue_pending_submissions(state: &AppState, problem_id: Uuid) -> ApiR
 
 #[derive(Debug, FromRow)]
 struct ProblemLimitsRow {
-    #[allow(dead_code)]
-    id: Uuid,
     time_limit_ms: i32,
     memory_limit_kb: i32,
     num_test_cases: i32,
-    max_threads: i32,
-    network_allowed: bool,
+    worker_profile: String,
 }
 
 #[derive(Debug, FromRow)]
 struct PendingSubmissionRow {
     submission_id: Uuid,
     contest_id: Option<Uuid>,
+    storage_root: Option<String>,
+    artifact_path: Option<String>,
 }
 
 /// POST /api/v1/problems/{id}/generator
 ///
 /// Upload generator binary for a problem.
 /// The binary will be stored at /mnt/data/binaries/problems/{problem_id}/generator
+/// and kept as `generator.v{version}` for rollback.
 pub async fn upload_generator(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
     Path(problem_id): Path<Uuid>,
     mut multipart: Multipart,
 ) -> ApiResult<Json<MessageResponse>> {
-    // Check permission
-    check_problem_binary_permission(&state, problem_id, &user).await?;
+    let upload = read_binary_upload(&mut multipart).await?;
+    let file_size = upload.size();
 
-    // Process multipart upload
-    let mut file_data: Option<Vec<u8>> = None;
+    // Store as a new version. A new generator means new seeds, so cached
+    // inputs and recorded seeds from the old one no longer apply.
+    let version =
+        store_binary_version(&state, problem_id, BinaryKind::Generator, upload, user.id).await?;
 
-    while let Some(field) = multipart
-        .next_field()
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        file_size = file_size,
+        version = version,
+        "Generator binary uploaded"
+    );
+
+    // Re-queue any queue_pending submissions now that binaries may be ready
+    let requeued = requeue_pending_submissions(&state, problem_id).await?;
+
+    let msg = if requeued > 0 {
+        format!(
+            "Generator version {} uploaded successfully. {} pending submission(s) re-queued for judging.",
+            version, requeued
+        )
+    } else {
+        format!("Generator version {} uploaded successfully", version)
+    };
+
+    Ok(Json(MessageResponse { message: msg }))
+}
+
+/// POST /api/v1/problems/{id}/checker
+///
+/// Upload checker/verifier binary for a problem.
+/// The binary will be stored at /mnt/data/binaries/problems/{problem_id}/checker
+/// and kept as `checker.v{version}` for rollback.
+pub async fn upload_checker(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<MessageResponse>> {
+    let upload = read_binary_upload(&mut multipart).await?;
+    let file_size = upload.size();
+
+    // Store as a new version
+    let version =
+        store_binary_version(&state, problem_id, BinaryKind::Checker, upload, user.id).await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        file_size = file_size,
+        version = version,
+        "Checker binary uploaded"
+    );
+
+    // Re-queue any queue_pending submissions now that binaries may be ready
+    let requeued = requeue_pending_submissions(&state, problem_id).await?;
+
+    let msg = if requeued > 0 {
+        format!(
+            "Checker version {} uploaded successfully. {} pending submission(s) re-queued for judging.",
+            version, requeued
+        )
+    } else {
+        format!("Checker version {} uploaded successfully", version)
+    };
+
+    Ok(Json(MessageResponse { message: msg }))
+}
+
+/// POST /api/v1/problems/{id}/reference
+///
+/// Upload the reference solution run by pipeline steps.
+/// The binary will be stored at /mnt/data/binaries/problems/{problem_id}/reference
+/// and kept as `reference.v{version}` for rollback.
+pub async fn upload_reference(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> ApiResult<Json<MessageResponse>> {
+    let upload = read_binary_upload(&mut multipart).await?;
+    let file_size = upload.size();
+
+    // Store as a new version
+    let version =
+        store_binary_version(&state, problem_id, BinaryKind::Reference, upload, user.id).await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        file_size = file_size,
+        version = version,
+        "Reference binary uploaded"
+    );
+
+    // Re-queue any queue_pending submissions now that binaries may be ready
+    let requeued = requeue_pending_submissions(&state, problem_id).await?;
+
+    let msg = if requeued > 0 {
+        format!(
+            "Reference version {} uploaded successfully. {} pending submission(s) re-queued for judging.",
+            version, requeued
+        )
+    } else {
+        format!("Reference version {} uploaded successfully", version)
+    };
+
+    Ok(Json(MessageResponse { message: msg }))
+}
+
+/// A problem binary kept with version history
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BinaryKind {
+    Generator,
+    Checker,
+    Reference,
+}
+
+impl BinaryKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BinaryKind::Generator => "generator",
+            BinaryKind::Checker => "checker",
+            BinaryKind::Reference => "reference",
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            BinaryKind::Generator => "Generator",
+            BinaryKind::Checker => "Checker",
+            BinaryKind::Reference => "Reference",
+        }
+    }
+
+    /// `problems` columns holding the active path and version
+    pub(crate) fn columns(self) -> (&'static str, &'static str) {
+        match self {
+            BinaryKind::Generator => ("generator_path", "generator_version"),
+            BinaryKind::Checker => ("checker_path", "checker_version"),
+            BinaryKind::Reference => ("reference_path", "reference_version"),
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct BinaryVersionRow {
+    version: i32,
+    checksum: String,
+    size_bytes: i64,
+    file_path: String,
+    uploaded_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Write an executable file
+async fn write_executable(path: &str, data: &[u8]) -> ApiResult<()> {
+    tokio::fs::write(path, data)
         .await
-        .map_err(|e| ApiError::Validation(format!("Failed to read multipart: {}", e)))?
+        .map_err(|e| ApiError::Internal(format!("Failed to save {}: {}", path, e)))?;
+    make_executable(path).await
+}
+
+async fn make_executable(path: &str) -> ApiResult<()> {
+    #[cfg(unix)]
     {
-        let name = field.name().unwrap_or_default().to_string();
-        if name == "file" {
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| ApiError::Validation(format!("Failed to read file: {}", e)))?;
+        use std::os::unix::fs::PermissionsExt;
+        let perms = std::fs::Permissions::from_mode(0o755);
+        tokio::fs::set_permissions(path, perms)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to set permissions: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Make a copy of a stored version the active binary, replacing the old one
+/// atomically so a judge never runs a half-written file
+async fn install_active_binary(active_path: &str, version_path: &str) -> ApiResult<()> {
+    let staging_path = format!("{}.{}.tmp", active_path, Uuid::new_v4());
+    let installed = async {
+        tokio::fs::copy(version_path, &staging_path)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to copy {}: {}", version_path, e)))?;
+        make_executable(&staging_path).await?;
+        tokio::fs::rename(&staging_path, active_path)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to install {}: {}", active_path, e)))
+    }
+    .await;
+
+    if installed.is_err() {
+        let _ = tokio::fs::remove_file(&staging_path).await;
+    }
+    installed
+}
+
+/// Install `version` as the active binary once the database says it is
+/// active. If the file cannot be installed the problem is pointed back at
+/// `previous`, the version judges are still running.
+async fn activate_binary_version(
+    db: &sqlx::PgPool,
+    problem_id: Uuid,
+    kind: BinaryKind,
+    version: (i32, &str),
+    previous: (i32, Option<&str>),
+) -> ApiResult<()> {
+    let (version, version_path) = version;
+    let active_path = binary_active_path(problem_id, kind);
+    let Err(e) = install_active_binary(&active_path, version_path).await else {
+        return Ok(());
+    };
+
+    let (path_column, version_column) = kind.columns();
+    let (previous_version, previous_path) = previous;
+    if let Err(revert) = sqlx::query(&format!(
+        "UPDATE problems SET {path} = $1, {version} = $2, updated_at = NOW() \
+         WHERE id = $3 AND {version} = $4",
+        path = path_column,
+        version = version_column
+    ))
+    .bind(previous_path)
+    .bind(previous_version)
+    .bind(problem_id)
+    .bind(version)
+    .execute(db)
+    .await
+    {
+        tracing::error!(
+            problem_id = %problem_id,
+            kind = kind.as_str(),
+            version,
+            "Failed to restore the active version after a failed install: {}",
+            revert
+        );
+    }
 
-            if data.len() > MAX_BINARY_SIZE {
-                return Err(ApiError::Validation(format!(
-                    "File size exceeds {}MB limit",
-                    MAX_BINARY_SIZE / 1024 / 1024
-                )));
-            }
+    Err(e)
+}
+
+/// Where the active copy of a problem binary lives
+fn binary_active_path(problem_id: Uuid, kind: BinaryKind) -> String {
+    format!(
+        "/mnt/data/binaries/problems/{}/{}",
+        problem_id,
+        kind.as_str()
+    )
+}
 
-            file_data = Some(data.to_vec());
+/// Remove files written for a change that was not committed
+async fn remove_uncommitted(paths: &[String]) {
+    for path in paths {
+        if let Err(e) = tokio::fs::remove_file(path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove {}: {}", path, e);
+            }
         }
     }
+}
 
-    let file_data =
-        file_data.ok_or_else(|| ApiError::Validation("No file uploaded".to_string()))?;
+/// Scan an uploaded binary, then store it as the next version and make it
+/// active.
+///
+/// A binary uploaded before versioning existed is archived under its current
+/// version number first, so it can still be rolled back to.
+pub(crate) async fn store_binary_version(
+    state: &AppState,
+    problem_id: Uuid,
+    kind: BinaryKind,
+    upload: SpooledUpload,
+    uploaded_by: Uuid,
+) -> ApiResult<i32> {
+    let dir_path = format!("/mnt/data/binaries/problems/{}", problem_id);
+    let active_path = binary_active_path(problem_id, kind);
 
-    // Validate it's an ELF binary
-    validate_elf_binary(&file_data)?;
+    quota::enforce_storage_quota(&state.db, problem_id, upload.size() as i64).await?;
 
-    // Create directory and save file
-    let dir_path = format!("/mnt/data/binaries/problems/{}", problem_id);
-    let file_path = format!("{}/generator", dir_path);
+    let target = ScanTarget {
+        kind: kind.as_str(),
+        id: problem_id,
+        uploaded_by,
+    };
+    state.scanner.check(&state.db, target, &upload).await?;
 
     tokio::fs::create_dir_all(&dir_path)
         .await
         .map_err(|e| ApiError::Internal(format!("Failed to create directory: {}", e)))?;
 
-    tokio::fs::write(&file_path, &file_data)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Failed to save generator: {}", e)))?;
+    // Version files written before the commit are removed if it never happens
+    let mut written = Vec::new();
+    let recorded = record_binary_version(
+        state,
+        problem_id,
+        kind,
+        upload,
+        uploaded_by,
+        &active_path,
+        &mut written,
+    )
+    .await;
+    let (version, version_path, previous) = match recorded {
+        Ok(recorded) => recorded,
+        Err(e) => {
+            remove_uncommitted(&written).await;
+            return Err(e);
+        }
+    };
 
-    // Make executable
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let perms = std::fs::Permissions::from_mode(0o755);
-        tokio::fs::set_permissions(&file_path, perms)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Failed to set permissions: {}", e)))?;
+    activate_binary_version(
+        &state.db,
+        problem_id,
+        kind,
+        (version, &version_path),
+        (previous.0, previous.1.as_deref()),
+    )
+    .await?;
+
+    Ok(version)
+}
+
+/// Store an upload as the next version of a binary and point the problem at
+/// it, in one transaction. Returns the new version, its file and the
+/// previously active version and path. Every file written is added to
+/// `written`.
+async fn record_binary_version(
+    state: &AppState,
+    problem_id: Uuid,
+    kind: BinaryKind,
+    upload: SpooledUpload,
+    uploaded_by: Uuid,
+    active_path: &str,
+    written: &mut Vec<String>,
+) -> ApiResult<(i32, String, (i32, Option<String>))> {
+    let (path_column, version_column) = kind.columns();
+    let mut tx = state.db.begin().await?;
+
+    // Lock the problem so concurrent uploads get distinct versions
+    let (current_path, current_version): (Option<String>, i32) = sqlx::query_as(&format!(
+        "SELECT {}, {} FROM problems WHERE id = $1 FOR UPDATE",
+        path_column, version_column
+    ))
+    .bind(problem_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    let latest: Option<i32> = sqlx::query_scalar(
+        "SELECT MAX(version) FROM problem_binary_versions WHERE problem_id = $1 AND kind = $2",
+    )
+    .bind(problem_id)
+    .bind(kind.as_str())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let (None, Some(current_path)) = (latest, &current_path) {
+        if let Ok(legacy) = tokio::fs::read(current_path).await {
+            let legacy_path = format!("{}.v{}", active_path, current_version);
+            written.push(legacy_path.clone());
+            write_executable(&legacy_path, &legacy).await?;
+            sqlx::query(
+                r#"
+                INSERT INTO problem_binary_versions
+                    (problem_id, kind, version, checksum, size_bytes, file_path)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(problem_id)
+            .bind(kind.as_str())
+            .bind(current_version)
+            .bind(sha256_hex(&legacy))
+            .bind(legacy.len() as i64)
+            .bind(&legacy_path)
+            .execute(&mut *tx)
+            .await?;
+        }
     }
 
-    // Update database with path
-    sqlx::query("UPDATE problems SET generator_path = $1, updated_at = NOW() WHERE id = $2")
-        .bind(&file_path)
-        .bind(problem_id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Failed to update problem: {}", e)))?;
+    let version = latest.unwrap_or(0).max(current_version) + 1;
+    let version_path = format!("{}.v{}", active_path, version);
+    let checksum = upload.sha256().to_string();
+    let size_bytes = upload.size() as i64;
+    written.push(version_path.clone());
+    upload.persist(&version_path).await?;
+    make_executable(&version_path).await?;
 
-    tracing::info!(
-        problem_id = %problem_id,
-        user_id = %user.id,
-        file_size = file_data.len(),
-        "Generator binary uploaded"
-    );
+    sqlx::query(
+        r#"
+        INSERT INTO problem_binary_versions
+            (problem_id, kind, version, checksum, size_bytes, file_path, uploaded_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(problem_id)
+    .bind(kind.as_str())
+    .bind(version)
+    .bind(&checksum)
+    .bind(size_bytes)
+    .bind(&version_path)
+    .bind(uploaded_by)
+    .execute(&mut *tx)
+    .await?;
 
-    // Re-queue any queue_pending submissions now that binaries may be ready
-    let requeued = requeue_pending_submissions(&state, problem_id).await?;
+    sqlx::query(&format!(
+        "UPDATE problems SET {} = $1, {} = $2, updated_at = NOW() WHERE id = $3",
+        path_column, version_column
+    ))
+    .bind(active_path)
+    .bind(version)
+    .bind(problem_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Internal(format!("Failed to update problem: {}", e)))?;
 
-    let msg = if requeued > 0 {
-        format!(
-            "Generator uploaded successfully. {} pending submission(s) re-queued for judging.",
-            requeued
-        )
-    } else {
-        "Generator uploaded successfully".to_string()
-    };
+    tx.commit().await?;
 
-    Ok(Json(MessageResponse { message: msg }))
+    Ok((version, version_path, (current_version, current_path)))
 }
 
-/// POST /api/v1/problems/{id}/checker
-///
-/// Upload checker/verifier binary for a problem.
-/// The binary will be stored at /mnt/data/binaries/problems/{problem_id}/checker
-pub async fn upload_checker(
-    State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
-    Path(problem_id): Path<Uuid>,
-    mut multipart: Multipart,
-) -> ApiResult<Json<MessageResponse>> {
-    // Check permission
-    check_problem_binary_permission(&state, problem_id, &user).await?;
-
-    // Process multipart upload
-    let mut file_data: Option<Vec<u8>> = None;
+/// Upload history of one binary, newest first
+async fn list_binary_versions(
+    state: &AppState,
+    problem_id: Uuid,
+    kind: BinaryKind,
+) -> ApiResult<BinaryVersionListResponse> {
+    let (_, version_column) = kind.columns();
+    let active_version: i32 = sqlx::query_scalar(&format!(
+        "SELECT {} FROM problems WHERE id = $1",
+        version_column
+    ))
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
 
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|e| ApiError::Validation(format!("Failed to read multipart: {}", e)))?
-    {
-        let name = field.name().unwrap_or_default().to_string();
-        if name == "file" {
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| ApiError::Validation(format!("Failed to read file: {}", e)))?;
+    let rows: Vec<BinaryVersionRow> = sqlx::query_as(
+        r#"
+        SELECT version, checksum, size_bytes, file_path, uploaded_by, created_at
+        FROM problem_binary_versions
+        WHERE problem_id = $1 AND kind = $2
+        ORDER BY version DESC
+        "#,
+    )
+    .bind(problem_id)
+    .bind(kind.as_str())
+    .fetch_all(&state.db)
+    .await?;
 
-            if data.len() > MAX_BINARY_SIZE {
-                return Err(ApiError::Validation(format!(
-                    "File size exceeds {}MB limit",
-                    MAX_BINARY_SIZE / 1024 / 1024
-                )));
-            }
+    Ok(BinaryVersionListResponse {
+        problem_id,
+        kind: kind.as_str().to_string(),
+        active_version,
+        versions: rows
+            .into_iter()
+            .map(|row| BinaryVersionInfo {
+                version: row.version,
+                checksum: row.checksum,
+                size_bytes: row.size_bytes,
+                uploaded_by: row.uploaded_by,
+                created_at: row.created_at,
+                is_active: row.version == active_version,
+            })
+            .collect(),
+    })
+}
 
-            file_data = Some(data.to_vec());
-        }
+/// Make a stored version active again, optionally rejudging the problem.
+async fn rollback_binary(
+    state: &AppState,
+    user: &AuthUser,
+    problem_id: Uuid,
+    kind: BinaryKind,
+    version: i32,
+    query: RollbackBinaryQuery,
+) -> ApiResult<BinaryRollbackResponse> {
+    let (path_column, version_column) = kind.columns();
+    let active_path = binary_active_path(problem_id, kind);
+
+    // Refuse before rolling back rather than leave the rejudge half done
+    if query.rejudge {
+        quota::enforce_rejudge_quota(&state.db, user.id).await?;
     }
 
-    let file_data =
-        file_data.ok_or_else(|| ApiError::Validation("No file uploaded".to_string()))?;
+    let mut tx = state.db.begin().await?;
 
-    // Validate it's an ELF binary
-    validate_elf_binary(&file_data)?;
+    let (current_path, current_version): (Option<String>, i32) = sqlx::query_as(&format!(
+        "SELECT {}, {} FROM problems WHERE id = $1 FOR UPDATE",
+        path_column, version_column
+    ))
+    .bind(problem_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
 
-    // Create directory and save file
-    let dir_path = format!("/mnt/data/binaries/problems/{}", problem_id);
-    let file_path = format!("{}/checker", dir_path);
+    let target: BinaryVersionRow = sqlx::query_as(
+        r#"
+        SELECT version, checksum, size_bytes, file_path, uploaded_by, created_at
+        FROM problem_binary_versions
+        WHERE problem_id = $1 AND kind = $2 AND version = $3
+        "#,
+    )
+    .bind(problem_id)
+    .bind(kind.as_str())
+    .bind(version)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::NotFound(format!("{} version {} not found", kind.label(), version)))?;
 
-    tokio::fs::create_dir_all(&dir_path)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Failed to create directory: {}", e)))?;
+    if current_version == version {
+        return Err(ApiError::Validation(format!(
+            "{} version {} is already active",
+            kind.label(),
+            version
+        )));
+    }
 
-    tokio::fs::write(&file_path, &file_data)
+    let data = tokio::fs::read(&target.file_path)
         .await
-        .map_err(|e| ApiError::Internal(format!("Failed to save checker: {}", e)))?;
-
-    // Make executable
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let perms = std::fs::Permissions::from_mode(0o755);
-        tokio::fs::set_permissions(&file_path, perms)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Failed to set permissions: {}", e)))?;
+        .map_err(|e| ApiError::Internal(format!("Failed to read {}: {}", target.file_path, e)))?;
+    if sha256_hex(&data) != target.checksum {
+        return Err(ApiError::Internal(format!(
+            "{} version {} does not match its checksum",
+            kind.label(),
+            version
+        )));
     }
 
-    // Update database with path
-    sqlx::query("UPDATE problems SET checker_path = $1, updated_at = NOW() WHERE id = $2")
-        .bind(&file_path)
-        .bind(problem_id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Failed to update problem: {}", e)))?;
+    // Rolling the generator back also restores that version's seeds
+    sqlx::query(&format!(
+        "UPDATE problems SET {} = $1, {} = $2, updated_at = NOW() WHERE id = $3",
+        path_column, version_column
+    ))
+    .bind(&active_path)
+    .bind(version)
+    .bind(problem_id)
+    .execute(&mut *tx)
+    .await?;
 
-    tracing::info!(
+    tx.commit().await?;
+    activate_binary_version(
+        &state.db,
+        problem_id,
+        kind,
+        (version, &target.file_path),
+        (current_version, current_path.as_deref()),
+    )
+    .await?;
+
+    tracing::warn!(
         problem_id = %problem_id,
         user_id = %user.id,
-        file_size = file_data.len(),
-        "Checker binary uploaded"
+        kind = kind.as_str(),
+        from_version = current_version,
+        to_version = version,
+        "Problem binary rolled back"
     );
 
-    // Re-queue any queue_pending submissions now that binaries may be ready
-    let requeued = requeue_pending_submissions(&state, problem_id).await?;
+    let requeued = requeue_pending_submissions(state, problem_id).await?;
 
-    let msg = if requeued > 0 {
-        format!(
-            "Checker uploaded successfully. {} pending submission(s) re-queued for judging.",
-            requeued
+    let (rejudged_count, skipped_count) = if query.rejudge {
+        let submissions: Vec<RejudgeSubmissionRow> = sqlx::query_as(
+            "SELECT id, status, file_path, organization_id FROM submissions WHERE problem_id = $1",
         )
+        .bind(problem_id)
+        .fetch_all(&state.db)
+        .await?;
+        admin::rejudge_submissions(state, &submissions, user.id).await?
     } else {
-        "Checker uploaded successfully".to_string()
+        (0, 0)
     };
 
-    Ok(Json(MessageResponse { message: msg }))
+    Ok(BinaryRollbackResponse {
+        problem_id,
+        kind: kind.as_str().to_string(),
+        version,
+        checksum: target.checksum,
+        requeued,
+        rejudged_count,
+        skipped_count,
+        message: format!(
+            "{} rolled back to version {}. {} submission(s) rejudged, {} skipped (in-progress).",
+            kind.label(),
+            version,
+            rejudged_count,
+            skipped_count
+        ),
+    })
+}
+
+/// GET /api/v1/problems/{id}/generator/versions
+///
+/// List stored generator versions.
+pub async fn list_generator_versions(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<BinaryVersionListResponse>> {
+    list_binary_versions(&state, problem_id, BinaryKind::Generator)
+        .await
+        .map(Json)
+}
+
+/// GET /api/v1/problems/{id}/checker/versions
+///
+/// List stored checker versions.
+pub async fn list_checker_versions(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<BinaryVersionListResponse>> {
+    list_binary_versions(&state, problem_id, BinaryKind::Checker)
+        .await
+        .map(Json)
+}
+
+/// GET /api/v1/problems/{id}/reference/versions
+///
+/// List stored reference solution versions.
+pub async fn list_reference_versions(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<Json<BinaryVersionListResponse>> {
+    list_binary_versions(&state, problem_id, BinaryKind::Reference)
+        .await
+        .map(Json)
+}
+
+/// POST /api/v1/problems/{id}/generator/rollback/{version}
+///
+/// Make a stored generator version active again. `?rejudge=true` also
+/// rejudges every submission to the problem.
+pub async fn rollback_generator(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path((problem_id, version)): Path<(Uuid, i32)>,
+    Query(query): Query<RollbackBinaryQuery>,
+) -> ApiResult<Json<BinaryRollbackResponse>> {
+    rollback_binary(
+        &state,
+        &user,
+        problem_id,
+        BinaryKind::Generator,
+        version,
+        query,
+    )
+    .await
+    .map(Json)
+}
+
+/// POST /api/v1/problems/{id}/checker/rollback/{version}
+///
+/// Make a stored checker version active again. `?rejudge=true` also
+/// rejudges every submission to the problem.
+pub async fn rollback_checker(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path((problem_id, version)): Path<(Uuid, i32)>,
+    Query(query): Query<RollbackBinaryQuery>,
+) -> ApiResult<Json<BinaryRollbackResponse>> {
+    rollback_binary(
+        &state,
+        &user,
+        problem_id,
+        BinaryKind::Checker,
+        version,
+        query,
+    )
+    .await
+    .map(Json)
+}
+
+/// POST /api/v1/problems/{id}/reference/rollback/{version}
+///
+/// Make a stored reference solution version active again. `?rejudge=true`
+/// also rejudges every submission to the problem.
+pub async fn rollback_reference(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditProblem>,
+    Path((problem_id, version)): Path<(Uuid, i32)>,
+    Query(query): Query<RollbackBinaryQuery>,
+) -> ApiResult<Json<BinaryRollbackResponse>> {
+    rollback_binary(
+        &state,
+        &user,
+        problem_id,
+        BinaryKind::Reference,
+        version,
+        query,
+    )
+    .await
+    .map(Json)
 }
 
 /// GET /api/v1/problems/{id}/generator
@@ -1306,15 +4617,12 @@ pub async fn upload_checker(
 /// Download generator binary for a problem.
 pub async fn download_generator(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    _guard: Guard<policies::EditProblem>,
     Path(problem_id): Path<Uuid>,
 ) -> ApiResult<axum::response::Response> {
     use axum::body::Body;
     use axum::response::IntoResponse;
 
-    // Check permission
-    check_problem_binary_permission(&state, problem_id, &user).await?;
-
     // Get file path from database
     let path: Option<String> =
         sqlx::query_scalar("SELECT generator_path FROM problems WHERE id = $1")
@@ -1350,15 +4658,12 @@ pub async fn download_generator(
 /// Download checker binary for a problem.
 pub async fn download_checker(
     State(state): State<AppState>,
-    Extension(user): Extension<AuthUser>,
+    _guard: Guard<policies::EditProblem>,
     Path(problem_id): Path<Uuid>,
 ) -> ApiResult<axum::response::Response> {
     use axum::body::Body;
     use axum::response::IntoResponse;
 
-    // Check permission
-    check_problem_binary_permission(&state, problem_id, &user).await?;
-
     // Get file path from database
     let path: Option<String> =
         sqlx::query_scalar("SELECT checker_path FROM problems WHERE id = $1")
@@ -1389,13 +4694,50 @@ pub async fn download_checker(
         .into_response())
 }
 
+/// GET /api/v1/problems/{id}/reference
+///
+/// Download the reference solution binary for a problem.
+pub async fn download_reference(
+    State(state): State<AppState>,
+    _guard: Guard<policies::EditProblem>,
+    Path(problem_id): Path<Uuid>,
+) -> ApiResult<axum::response::Response> {
+    use axum::body::Body;
+    use axum::response::IntoResponse;
+
+    let path: Option<String> =
+        sqlx::query_scalar("SELECT reference_path FROM problems WHERE id = $1")
+            .bind(problem_id)
+            .fetch_optional(&state.db)
+            .await?
+            .flatten();
+
+    let path = path.ok_or_else(|| {
+        ApiError::NotFound("Reference solution not uploaded for this problem".to_string())
+    })?;
+
+    let data = tokio::fs::read(&path)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to read reference: {}", e)))?;
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/octet-stream"),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                "attachment; filename=\"reference\"",
+            ),
+        ],
+        Body::from(data),
+    )
+        .into_response())
+}
+
 /// Create routes for problems
 pub fn problem_routes() -> axum::Router<AppState> {
     use axum::routing::get;
 
-    axum::Router::new()
-        .route("/", get(list_problems))
-        .route("/{id}", get(get_problem))
+    axum::Router::new().route("/{id}", get(get_problem))
 }
 
 pub fn protected_problem_routes() -> axum::Router<AppState> {
@@ -1403,10 +4745,163 @@ pub fn protected_problem_routes() -> axum::Router<AppState> {
 
     axum::Router::new()
         .route("/", post(create_problem))
+        .route("/recommended", get(get_recommended_problems))
+        .route("/{id}/my-progress", get(get_my_problem_progress))
+        .route("/validate-draft", post(validate_problem_draft))
         .route("/{id}", put(update_problem))
         .route("/{id}", delete(delete_problem))
-        .route("/{id}/generator", post(upload_generator))
+        .route(
+            "/{id}/generator",
+            post(upload_generator).layer(upload::body_limit(MAX_BINARY_SIZE)),
+        )
         .route("/{id}/generator", get(download_generator))
-        .route("/{id}/checker", post(upload_checker))
+        .route(
+            "/{id}/checker",
+            post(upload_checker).layer(upload::body_limit(MAX_BINARY_SIZE)),
+        )
         .route("/{id}/checker", get(download_checker))
+        .route("/{id}/generator/versions", get(list_generator_versions))
+        .route("/{id}/checker/versions", get(list_checker_versions))
+        .route(
+            "/{id}/generator/rollback/{version}",
+            post(rollback_generator),
+        )
+        .route("/{id}/checker/rollback/{version}", post(rollback_checker))
+        .route(
+            "/{id}/reference",
+            post(upload_reference).layer(upload::body_limit(MAX_BINARY_SIZE)),
+        )
+        .route("/{id}/reference", get(download_reference))
+        .route("/{id}/reference/versions", get(list_reference_versions))
+        .route(
+            "/{id}/reference/rollback/{version}",
+            post(rollback_reference),
+        )
+        .route("/{id}/scoring", get(get_problem_scoring))
+        .route("/{id}/scoring", put(update_problem_scoring))
+        .route("/{id}/pipeline", get(get_problem_pipeline))
+        .route("/{id}/pipeline", put(update_problem_pipeline))
+        .route("/{id}/pipeline", delete(delete_problem_pipeline))
+        .route("/{id}/checker/tests", get(get_checker_tests))
+        .route(
+            "/{id}/checker/tests",
+            put(update_checker_tests).layer(axum::extract::DefaultBodyLimit::max(
+                MAX_CHECKER_SUITE_BYTES,
+            )),
+        )
+        .route("/{id}/checker/test", post(run_checker_tests))
+        .route("/{id}/checker/test/{run_id}", get(get_checker_test_run))
+        .route("/{id}/reference-solutions", get(get_reference_solutions))
+        .route("/{id}/reference-solutions", put(update_reference_solutions))
+        .route("/{id}/calibration", get(get_latest_calibration))
+        .route("/{id}/calibration", post(calibrate_time_limit))
+        .route("/{id}/calibration/{calibration_id}", get(get_calibration))
+        .route("/{id}/statements", get(list_statements))
+        .route("/{id}/statements/{locale}", put(upsert_statement))
+        .route("/{id}/statements/{locale}", delete(delete_statement))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problems_without_an_organization_fit_any_contest() {
+        assert!(tenant_mismatch_error(None, None).is_none());
+        assert!(tenant_mismatch_error(None, Some(Uuid::new_v4())).is_none());
+    }
+
+    #[test]
+    fn test_organization_problems_fit_only_its_contests() {
+        let org = Uuid::new_v4();
+        assert!(tenant_mismatch_error(Some(org), Some(org)).is_none());
+        assert!(tenant_mismatch_error(Some(org), None).is_some());
+        assert!(tenant_mismatch_error(Some(org), Some(Uuid::new_v4())).is_some());
+    }
+
+    /// Fresh directory under the system temp dir
+    async fn scratch_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("vanguard-binaries-{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        dir
+    }
+
+    /// Names of the files in `dir`, sorted
+    async fn file_names(dir: &std::path::Path) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut entries = tokio::fs::read_dir(dir).await.unwrap();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_install_active_binary_replaces_the_active_copy() {
+        let dir = scratch_dir().await;
+        let active = dir.join("checker").to_string_lossy().into_owned();
+        let version = dir.join("checker.v2").to_string_lossy().into_owned();
+        tokio::fs::write(&active, b"v1").await.unwrap();
+        tokio::fs::write(&version, b"v2").await.unwrap();
+
+        install_active_binary(&active, &version).await.unwrap();
+
+        assert_eq!(tokio::fs::read(&active).await.unwrap(), b"v2");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = tokio::fs::metadata(&active)
+                .await
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+        // No staging copy is left behind
+        assert_eq!(file_names(&dir).await, ["checker", "checker.v2"]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_install_keeps_the_active_copy() {
+        let dir = scratch_dir().await;
+        let active = dir.join("checker").to_string_lossy().into_owned();
+        let missing = dir.join("checker.v9").to_string_lossy().into_owned();
+        tokio::fs::write(&active, b"v1").await.unwrap();
+
+        assert!(install_active_binary(&active, &missing).await.is_err());
+
+        assert_eq!(tokio::fs::read(&active).await.unwrap(), b"v1");
+        assert_eq!(file_names(&dir).await, ["checker"]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remove_uncommitted_files() {
+        let dir = scratch_dir().await;
+        let kept = dir.join("checker.v1").to_string_lossy().into_owned();
+        let written = dir.join("checker.v2").to_string_lossy().into_owned();
+        tokio::fs::write(&kept, b"v1").await.unwrap();
+        tokio::fs::write(&written, b"v2").await.unwrap();
+
+        // A path that was never written is skipped
+        let never_written = dir.join("checker.v3").to_string_lossy().into_owned();
+        remove_uncommitted(&[written, never_written]).await;
+
+        assert_eq!(file_names(&dir).await, ["checker.v1"]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[test]
+    fn test_binary_active_path() {
+        let problem = Uuid::parse_str("6f1c2a3e-9b7d-4c1e-8a2f-0d3b5e7c9a1b").unwrap();
+        assert_eq!(
+            binary_active_path(problem, BinaryKind::Checker),
+            "/mnt/data/binaries/problems/6f1c2a3e-9b7d-4c1e-8a2f-0d3b5e7c9a1b/checker"
+        );
+    }
 }
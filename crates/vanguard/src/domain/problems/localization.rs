@@ -0,0 +1,194 @@
+//! Statement locale negotiation.
+//!
+//! A problem's own statement is written in its `statement_locale`, and
+//! `problem_statements` holds translations into other locales. `get_problem`
+//! serves the locale asked for with `?locale=`, then the best match for the
+//! `Accept-Language` header, and falls back to the problem's own statement.
+
+/// Longest locale tag accepted (the width of the `locale` columns)
+pub const MAX_LOCALE_LEN: usize = 35;
+
+/// Canonical form of a BCP 47 language tag (`PT_br` becomes `pt-BR`,
+/// `zh-hant` becomes `zh-Hant`), or `None` if it is malformed.
+pub fn canonicalize_locale(tag: &str) -> Option<String> {
+    if tag.is_empty() || tag.len() > MAX_LOCALE_LEN {
+        return None;
+    }
+
+    let mut subtags = Vec::new();
+    for (i, subtag) in tag.split(['-', '_']).enumerate() {
+        let alphabetic = subtag.chars().all(|c| c.is_ascii_alphabetic());
+        if subtag.is_empty()
+            || subtag.len() > 8
+            || !subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return None;
+        }
+
+        let canonical = if i == 0 {
+            // Primary language: 2-3 letters
+            if !alphabetic || !(2..=3).contains(&subtag.len()) {
+                return None;
+            }
+            subtag.to_ascii_lowercase()
+        } else if alphabetic && subtag.len() == 2 {
+            // Region
+            subtag.to_ascii_uppercase()
+        } else if alphabetic && subtag.len() == 4 {
+            // Script
+            let mut script = subtag.to_ascii_lowercase();
+            script[..1].make_ascii_uppercase();
+            script
+        } else {
+            subtag.to_ascii_lowercase()
+        };
+        subtags.push(canonical);
+    }
+
+    Some(subtags.join("-"))
+}
+
+/// Locales in an `Accept-Language` header, most preferred first. Wildcards,
+/// malformed tags and `q=0` entries are skipped.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut ranges: Vec<(f32, usize, String)> = header
+        .split(',')
+        .enumerate()
+        .filter_map(|(position, item)| {
+            let mut fields = item.split(';');
+            let tag = fields.next()?.trim();
+            let quality = match fields.find_map(|f| f.trim().strip_prefix("q=")) {
+                Some(q) => q.trim().parse::<f32>().ok()?,
+                None => 1.0,
+            };
+            if tag == "*" || quality <= 0.0 {
+                return None;
+            }
+            Some((quality, position, canonicalize_locale(tag)?))
+        })
+        .collect();
+
+    // Highest quality first; ties keep header order
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    ranges.into_iter().map(|(_, _, tag)| tag).collect()
+}
+
+/// Best available locale for the preferences, most preferred first: an exact
+/// match, else one with the same primary language (`pt-BR` accepts `pt` or
+/// `pt-PT`). `None` if nothing matches.
+pub fn negotiate_locale<'a>(preferred: &[String], available: &'a [String]) -> Option<&'a str> {
+    for wanted in preferred {
+        if let Some(exact) = available.iter().find(|a| a.eq_ignore_ascii_case(wanted)) {
+            return Some(exact);
+        }
+
+        let language = primary_language(wanted);
+        if let Some(related) = available
+            .iter()
+            .find(|a| primary_language(a).eq_ignore_ascii_case(language))
+        {
+            return Some(related);
+        }
+    }
+    None
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_canonicalize_locale_cases_subtags() {
+        assert_eq!(canonicalize_locale("en").as_deref(), Some("en"));
+        assert_eq!(canonicalize_locale("PT_br").as_deref(), Some("pt-BR"));
+        assert_eq!(canonicalize_locale("zh-hant").as_deref(), Some("zh-Hant"));
+        assert_eq!(
+            canonicalize_locale("ZH-hant-tw").as_deref(),
+            Some("zh-Hant-TW")
+        );
+        assert_eq!(canonicalize_locale("es-419").as_deref(), Some("es-419"));
+    }
+
+    #[test]
+    fn test_canonicalize_locale_rejects_malformed_tags() {
+        assert_eq!(canonicalize_locale(""), None);
+        assert_eq!(canonicalize_locale("e"), None);
+        assert_eq!(canonicalize_locale("engl"), None);
+        assert_eq!(canonicalize_locale("12"), None);
+        assert_eq!(canonicalize_locale("en-"), None);
+        assert_eq!(canonicalize_locale("en--US"), None);
+        assert_eq!(canonicalize_locale("en-toolongsubtag"), None);
+        assert_eq!(canonicalize_locale("en-U$"), None);
+        assert_eq!(canonicalize_locale(&"en-abc".repeat(8)), None);
+    }
+
+    #[test]
+    fn test_parse_accept_language_orders_by_quality() {
+        assert_eq!(
+            parse_accept_language("fr;q=0.5, pt-br, en;q=0.8"),
+            tags(&["pt-BR", "en", "fr"])
+        );
+    }
+
+    #[test]
+    fn test_parse_accept_language_keeps_header_order_on_ties() {
+        assert_eq!(
+            parse_accept_language("de;q=0.7, fr;q=0.7, en"),
+            tags(&["en", "de", "fr"])
+        );
+    }
+
+    #[test]
+    fn test_parse_accept_language_skips_wildcards_zero_and_malformed() {
+        assert_eq!(
+            parse_accept_language("*, en;q=0, x;q=0.9, fr;q=abc, de ; q=0.3"),
+            tags(&["de"])
+        );
+        assert!(parse_accept_language("").is_empty());
+    }
+
+    #[test]
+    fn test_negotiate_locale_prefers_exact_matches() {
+        let available = tags(&["en", "pt-PT", "pt-BR"]);
+        assert_eq!(
+            negotiate_locale(&tags(&["pt-BR"]), &available),
+            Some("pt-BR")
+        );
+        assert_eq!(
+            negotiate_locale(&tags(&["pt-br"]), &available),
+            Some("pt-BR")
+        );
+    }
+
+    #[test]
+    fn test_negotiate_locale_falls_back_to_the_primary_language() {
+        let available = tags(&["en", "pt-PT"]);
+        assert_eq!(
+            negotiate_locale(&tags(&["pt-BR"]), &available),
+            Some("pt-PT")
+        );
+        assert_eq!(
+            negotiate_locale(&tags(&["en-GB", "pt"]), &available),
+            Some("en")
+        );
+    }
+
+    #[test]
+    fn test_negotiate_locale_walks_preferences_in_order() {
+        let available = tags(&["de", "fr"]);
+        assert_eq!(
+            negotiate_locale(&tags(&["ja", "fr", "de"]), &available),
+            Some("fr")
+        );
+        assert_eq!(negotiate_locale(&tags(&["ja", "ko"]), &available), None);
+        assert_eq!(negotiate_locale(&[], &available), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Statement rendering.
+//!
+//! Statements are stored as Markdown. `GET /problems/{id}?format=html`
+//! renders them here so every frontend shows the same HTML and untrusted
+//! markup is sanitized in one place. Math (`$...$`, `$$...$$`) is passed
+//! through as escaped TeX in `math-inline` / `math-display` spans for KaTeX,
+//! and fenced code blocks keep a `language-*` class for highlighting.
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use ammonia::Builder;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag};
+
+/// Classes allowed on `<span>`: the math wrappers KaTeX auto-render looks for
+const MATH_CLASSES: [&str; 3] = ["math", "math-inline", "math-display"];
+
+lazy_static::lazy_static! {
+    static ref SANITIZER: Builder<'static> = {
+        let mut builder = Builder::default();
+        builder
+            .add_tags(["del", "input"])
+            .add_tag_attributes("code", ["class"])
+            .add_tag_attributes("span", ["class"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .add_tag_attributes("td", ["style"])
+            .add_tag_attributes("th", ["style"])
+            .attribute_filter(filter_attribute);
+        builder
+    };
+}
+
+/// Rendered statement section
+#[derive(Debug, Default)]
+pub struct RenderedHtml {
+    pub html: String,
+    /// Languages of fenced code blocks, for loading highlighter grammars
+    pub code_languages: BTreeSet<String>,
+}
+
+/// Render Markdown to sanitized HTML.
+pub fn render_markdown(source: &str) -> RenderedHtml {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH;
+
+    let mut code_languages = BTreeSet::new();
+    let events = Parser::new_ext(source, options).map(|event| match event {
+        // Keep only the language from the info string (```cpp title="a.cpp")
+        Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+            let language = code_language(&info);
+            if let Some(ref language) = language {
+                code_languages.insert(language.clone());
+            }
+            let info = CowStr::from(language.unwrap_or_default());
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))
+        }
+        event => event,
+    });
+
+    let mut unsafe_html = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, events);
+
+    RenderedHtml {
+        html: SANITIZER.clean(&unsafe_html).to_string(),
+        code_languages,
+    }
+}
+
+/// Lowercased language of a fenced block's info string, if it is a plain
+/// identifier such as `cpp`, `c++` or `objective-c`.
+fn code_language(info: &str) -> Option<String> {
+    let language = info.split_whitespace().next()?.to_ascii_lowercase();
+    let valid = language.len() <= 32
+        && language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '#' | '-' | '_' | '.'));
+    valid.then_some(language)
+}
+
+fn filter_attribute<'u>(element: &str, attribute: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    match (element, attribute) {
+        ("code", "class") => {
+            let language = value.strip_prefix("language-")?;
+            (code_language(language).as_deref() == Some(language)).then_some(value.into())
+        }
+        ("span", "class") => {
+            let classes: Vec<&str> = value
+                .split_whitespace()
+                .filter(|class| MATH_CLASSES.contains(class))
+                .collect();
+            (!classes.is_empty()).then(|| classes.join(" ").into())
+        }
+        // Task list checkboxes only
+        ("input", "type") => (value == "checkbox").then_some(value.into()),
+        // Table column alignment only
+        ("td" | "th", "style") => matches!(
+            value,
+            "text-align: left" | "text-align: center" | "text-align: right"
+        )
+        .then_some(value.into()),
+        _ => Some(value.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html(source: &str) -> String {
+        render_markdown(source).html
+    }
+
+    #[test]
+    fn test_script_tags_are_removed() {
+        let out = html("Hello <script>alert(1)</script> world\n\n<script>\nalert(2)\n</script>");
+        assert!(!out.contains("<script"), "{}", out);
+        assert!(!out.contains("alert(2)"), "{}", out);
+        assert!(out.contains("Hello"));
+    }
+
+    #[test]
+    fn test_javascript_links_lose_their_href() {
+        for source in [
+            "[click](javascript:alert(1))",
+            "[click](JavaScript:alert(1))",
+            "<a href=\"javascript:alert(1)\">click</a>",
+            "<javascript:alert(1)>",
+        ] {
+            let out = html(source);
+            assert!(
+                !out.to_ascii_lowercase().contains("href=\"javascript"),
+                "{}",
+                out
+            );
+        }
+        assert!(html("[ok](https://example.com)").contains("href=\"https://example.com\""));
+    }
+
+    #[test]
+    fn test_event_handler_attributes_are_removed() {
+        let out = html(
+            "<img src=\"x.png\" onerror=\"alert(1)\"> <span onclick=\"alert(1)\" class=\"math\">x</span> <a href=\"https://example.com\" onmouseover=\"alert(1)\">a</a>",
+        );
+        assert!(!out.contains("onerror"), "{}", out);
+        assert!(!out.contains("onclick"), "{}", out);
+        assert!(!out.contains("onmouseover"), "{}", out);
+        assert!(!out.contains("alert"), "{}", out);
+    }
+
+    #[test]
+    fn test_raw_html_in_link_titles_stays_text() {
+        let out = html("[x](https://example.com \"<script>alert(1)</script>\")");
+        assert!(!out.contains("<script"), "{}", out);
+        assert!(out.contains("href=\"https://example.com\""), "{}", out);
+
+        let out = html("[x](https://example.com \"\\\" onmouseover=\\\"alert(1)\")");
+        assert!(!out.contains("\" onmouseover=\""), "{}", out);
+        assert!(out.contains("&quot; onmouseover=&quot;"), "{}", out);
+    }
+
+    #[test]
+    fn test_math_and_code_classes_survive() {
+        let rendered = render_markdown("$x^2$\n\n```cpp title=\"a.cpp\"\nint main() {}\n```");
+        assert!(
+            rendered.html.contains("class=\"math math-inline\""),
+            "{}",
+            rendered.html
+        );
+        assert!(
+            rendered.html.contains("class=\"language-cpp\""),
+            "{}",
+            rendered.html
+        );
+        assert_eq!(
+            rendered.code_languages.into_iter().collect::<Vec<_>>(),
+            vec!["cpp".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_unknown_classes_and_styles_are_dropped() {
+        let out = html(
+            "<span class=\"evil math\">x</span> <table><tr><td style=\"background: url(x)\">a</td></tr></table>",
+        );
+        assert!(out.contains("class=\"math\""), "{}", out);
+        assert!(!out.contains("evil"), "{}", out);
+        assert!(!out.contains("background"), "{}", out);
+    }
+}
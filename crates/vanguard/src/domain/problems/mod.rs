@@ -1,9 +1,10 @@
 //! Problems domain module.
 
 pub mod handler;
+pub mod localization;
+pub mod markdown;
 pub mod request;
 pub mod response;
+pub mod samples;
 
 pub use handler::*;
-pub use request::*;
-pub use response::*;
@@ -1,9 +1,14 @@
 //! Problem request DTOs.
 
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use olympus_common::{CheckerVerdict, OutputComparison, ReferenceRole};
+use olympus_rules::pipeline::Pipeline;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::extract::double_option;
+
 /// Problem difficulty levels
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -79,10 +84,39 @@ pub struct CreateProblemRequest {
     #[serde(default)]
     pub partial_scoring: bool,
 
+    /// Normalize reported run times by each judge worker's calibration factor.
+    #[serde(default)]
+    pub benchmark_scored: bool,
+
+    /// Rank performance-scored contests by CPU instructions instead of run time.
+    #[serde(default)]
+    pub rank_by_instructions: bool,
+
+    /// Worker profile judging the problem's submissions (`WORKER_PROFILES`);
+    /// `standard` when omitted
+    pub worker_profile: Option<String>,
+
+    /// Judge by comparing output with the reference solution's answer
+    /// instead of running a checker
+    pub output_comparison: Option<OutputComparison>,
+
+    /// Absolute or relative tolerance of the `float` comparison
+    #[validate(range(min = 0.0, max = 1.0, message = "Comparison epsilon must be 0-1"))]
+    #[serde(default = "default_comparison_epsilon")]
+    pub comparison_epsilon: f64,
+
     #[serde(default)]
     pub is_public: bool,
 
     pub allowed_languages: Option<Vec<String>>,
+
+    /// Owning organization; a non-public tenant problem is visible to its
+    /// members and can only be used in that organization's contests
+    pub organization_id: Option<Uuid>,
+
+    /// Locale the statement is written in (BCP 47, default `en`)
+    #[validate(custom(function = "validate_locale"))]
+    pub statement_locale: Option<String>,
 }
 
 fn default_time_limit() -> i32 {
@@ -105,6 +139,22 @@ fn default_max_score() -> i32 {
     100
 }
 
+fn default_comparison_epsilon() -> f64 {
+    1e-6
+}
+
+/// Problem definition to check before creating or activating it
+#[derive(Debug, Deserialize, Validate)]
+pub struct ValidateDraftRequest {
+    /// Existing problem whose uploaded binaries are checked too
+    pub problem_id: Option<Uuid>,
+
+    /// Same fields as `POST /api/v1/problems`. Its validation errors are
+    /// reported as checks, so it is not validated with the request.
+    #[serde(flatten)]
+    pub problem: CreateProblemRequest,
+}
+
 /// Update problem request
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateProblemRequest {
@@ -152,9 +202,38 @@ pub struct UpdateProblemRequest {
 
     pub partial_scoring: Option<bool>,
 
+    /// Normalize reported run times by each judge worker's calibration factor.
+    pub benchmark_scored: Option<bool>,
+
+    /// Rank performance-scored contests by CPU instructions instead of run time.
+    pub rank_by_instructions: Option<bool>,
+
+    /// Worker profile judging the problem's submissions (`WORKER_PROFILES`)
+    pub worker_profile: Option<String>,
+
+    /// Judge by comparing output with the reference solution's answer
+    /// instead of running a checker
+    pub output_comparison: Option<OutputComparison>,
+
+    /// Go back to judging with the uploaded checker
+    #[serde(default)]
+    pub clear_output_comparison: bool,
+
+    /// Absolute or relative tolerance of the `float` comparison
+    #[validate(range(min = 0.0, max = 1.0, message = "Comparison epsilon must be 0-1"))]
+    pub comparison_epsilon: Option<f64>,
+
     pub is_public: Option<bool>,
 
     pub allowed_languages: Option<Vec<String>>,
+
+    /// Locale the statement is written in
+    #[validate(custom(function = "validate_locale"))]
+    pub statement_locale: Option<String>,
+
+    /// Organization owning the problem; `null` takes it out of its organization
+    #[serde(default, deserialize_with = "double_option")]
+    pub organization_id: Option<Option<Uuid>>,
 }
 
 /// List problems query parameters
@@ -183,6 +262,74 @@ pub struct ListProblemsQuery {
     pub public_only: bool,
 }
 
+/// Filters of `GET /api/v2/problems`, next to its `CursorQuery`
+#[derive(Debug, Deserialize)]
+pub struct ProblemFeedQuery {
+    /// Filter by difficulty
+    pub difficulty: Option<String>,
+
+    /// Filter by tag
+    pub tag: Option<String>,
+
+    /// Filter by owner
+    pub owner_id: Option<Uuid>,
+
+    /// Search by title
+    pub search: Option<String>,
+
+    /// Only public problems
+    #[serde(default = "default_true")]
+    pub public_only: bool,
+}
+
+/// Get problem query parameters
+#[derive(Debug, Deserialize)]
+pub struct GetProblemQuery {
+    /// Statement locale; takes precedence over `Accept-Language`
+    pub locale: Option<String>,
+
+    /// Format of the statement sections (default `markdown`)
+    #[serde(default)]
+    pub format: StatementFormat,
+}
+
+/// Statement text format
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    /// Markdown source as stored
+    #[default]
+    Markdown,
+    /// Sanitized HTML rendered from the Markdown
+    Html,
+}
+
+/// Create or replace a statement translation
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpsertStatementRequest {
+    #[validate(length(min = 3, max = 255, message = "Title must be 3-255 characters"))]
+    pub title: String,
+
+    #[validate(length(min = 10, message = "Description must be at least 10 characters"))]
+    pub description: String,
+
+    pub input_format: Option<String>,
+    pub output_format: Option<String>,
+    pub constraints: Option<String>,
+    pub sample_explanation: Option<String>,
+}
+
+fn validate_locale(locale: &str) -> Result<(), validator::ValidationError> {
+    match super::localization::canonicalize_locale(locale) {
+        Some(_) => Ok(()),
+        None => {
+            let mut err = validator::ValidationError::new("invalid_locale");
+            err.message = Some("Locale must be a language tag such as en or pt-BR".into());
+            Err(err)
+        }
+    }
+}
+
 fn default_page() -> u32 {
     1
 }
@@ -219,4 +366,205 @@ pub struct AddProblemToContestRequest {
 
     /// Override network_allowed for this contest
     pub network_allowed: Option<bool>,
+
+    /// Override the problem's allowed languages for this contest
+    pub allowed_languages: Option<Vec<String>>,
+
+    /// Hide the problem from participants until this time (default: the
+    /// contest start)
+    pub visible_from: Option<DateTime<Utc>>,
+}
+
+/// Schedule when a contest problem is released
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateContestProblemVisibilityRequest {
+    /// `null` releases it with the contest start
+    pub visible_from: Option<DateTime<Utc>>,
+}
+
+/// Reorder contest problems request
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReorderContestProblemsRequest {
+    /// Every problem code in the contest, in the desired display order
+    #[validate(length(min = 1, message = "At least one problem code is required"))]
+    pub problem_codes: Vec<String>,
+}
+
+/// Bulk add problems to contest request
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkAddProblemsRequest {
+    /// At most `MAX_BULK_PROBLEMS` items, checked by the handler; each item
+    /// is validated on its own so one bad item does not reject the rest
+    pub problems: Vec<AddProblemToContestRequest>,
+}
+
+/// Subtask of a partial-scoring problem
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubtaskRequest {
+    /// First test case (inclusive)
+    #[validate(range(min = 1, max = 100, message = "Test number must be 1-100"))]
+    pub first_test: i32,
+
+    /// Last test case (inclusive)
+    #[validate(range(min = 1, max = 100, message = "Test number must be 1-100"))]
+    pub last_test: i32,
+
+    /// Relative points of this subtask
+    #[validate(range(min = 1, max = 10000, message = "Subtask points must be 1-10000"))]
+    pub points: i32,
+
+    /// Time limit of the subtask's tests, replacing the problem's
+    #[validate(range(min = 100, max = 60000, message = "Time limit must be 100-60000 ms"))]
+    pub time_limit_ms: Option<i32>,
+
+    /// Memory limit of the subtask's tests, replacing the problem's
+    #[validate(range(
+        min = 16384,
+        max = 2097152,
+        message = "Memory limit must be 16-2048 MB"
+    ))]
+    pub memory_limit_kb: Option<i32>,
+}
+
+/// Weight of a single test case
+#[derive(Debug, Deserialize, Validate)]
+pub struct TestWeightRequest {
+    #[validate(range(min = 1, max = 100, message = "Test number must be 1-100"))]
+    pub test: i32,
+
+    /// 0 excludes the test from scoring
+    #[validate(range(min = 0, max = 1000, message = "Test weight must be 0-1000"))]
+    pub weight: i32,
+}
+
+/// Limits of a single test case, replacing its subtask's and the problem's
+#[derive(Debug, Deserialize, Validate)]
+pub struct TestLimitRequest {
+    #[validate(range(min = 1, max = 100, message = "Test number must be 1-100"))]
+    pub test: i32,
+
+    #[validate(range(min = 100, max = 60000, message = "Time limit must be 100-60000 ms"))]
+    pub time_limit_ms: Option<i32>,
+
+    #[validate(range(
+        min = 16384,
+        max = 2097152,
+        message = "Memory limit must be 16-2048 MB"
+    ))]
+    pub memory_limit_kb: Option<i32>,
+}
+
+/// Replace a problem's partial scoring configuration
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateProblemScoringRequest {
+    /// Non-overlapping test ranges; empty scores all tests as one group
+    #[serde(default)]
+    #[validate(nested)]
+    pub subtasks: Vec<SubtaskRequest>,
+
+    /// Weight overrides; unlisted tests have weight 1
+    #[serde(default)]
+    #[validate(nested)]
+    pub test_weights: Vec<TestWeightRequest>,
+
+    /// Limit overrides; unlisted tests inherit their subtask's or the
+    /// problem's limits
+    #[serde(default)]
+    #[validate(nested)]
+    pub test_limits: Vec<TestLimitRequest>,
+}
+
+/// Replace a problem's judging pipeline
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdatePipelineRequest {
+    #[validate(custom(function = "validate_pipeline"))]
+    pub pipeline: Pipeline,
+}
+
+fn validate_pipeline(pipeline: &Pipeline) -> Result<(), validator::ValidationError> {
+    pipeline.validate().map_err(|message| {
+        let mut err = validator::ValidationError::new("invalid_pipeline");
+        err.message = Some(message.into());
+        err
+    })
+}
+
+/// A case of a checker self-test suite (texts up to 1 MB each)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CheckerTestRequest {
+    #[validate(length(max = 1048576, message = "Input must be at most 1 MB"))]
+    pub input: String,
+
+    /// Contestant output the checker is given
+    #[validate(length(max = 1048576, message = "Output must be at most 1 MB"))]
+    pub output: String,
+
+    /// Jury answer; the input is passed instead when omitted, as in judging
+    #[validate(length(max = 1048576, message = "Answer must be at most 1 MB"))]
+    pub answer: Option<String>,
+
+    pub expected_verdict: CheckerVerdict,
+}
+
+/// Replace a problem's checker self-test suite
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCheckerTestsRequest {
+    /// At most `MAX_CHECKER_TESTS` cases, numbered in order; checked by the
+    /// handler
+    #[validate(nested)]
+    pub tests: Vec<CheckerTestRequest>,
+}
+
+/// A submission used as a reference solution in time limit calibration
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReferenceSolutionRequest {
+    /// Compiled submission to the problem
+    pub submission_id: Uuid,
+    pub role: ReferenceRole,
+}
+
+/// Replace a problem's reference solutions
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateReferenceSolutionsRequest {
+    /// At most `MAX_REFERENCE_SOLUTIONS`; checked by the handler
+    #[validate(nested)]
+    pub solutions: Vec<ReferenceSolutionRequest>,
+}
+
+/// Options for a time limit calibration run
+#[derive(Debug, Deserialize, Validate)]
+pub struct CalibrateTimeLimitRequest {
+    /// Safety margin over the slowest model solution (default 3)
+    #[validate(range(min = 1.0, max = 10.0, message = "Multiplier must be 1-10"))]
+    pub multiplier: Option<f64>,
+
+    /// Time limit the solutions run under (default 5x the current limit)
+    #[validate(range(min = 100, max = 60000, message = "Ceiling must be 100-60000 ms"))]
+    pub ceiling_ms: Option<i32>,
+}
+
+/// Code to run on a problem's sample
+#[derive(Debug, Deserialize, Validate)]
+pub struct RunSamplesRequest {
+    /// Contest whose limits and languages apply; `None` for standalone
+    pub contest_id: Option<Uuid>,
+
+    /// Runtime name from the runtime registry (e.g. `cpp`)
+    #[validate(length(min = 1, max = 32, message = "Language must be 1-32 characters"))]
+    pub language: String,
+
+    #[validate(length(
+        min = 1,
+        max = 65536,
+        message = "Source code must be 1-65536 characters"
+    ))]
+    pub source_code: String,
+}
+
+/// Options for rolling back a problem binary
+#[derive(Debug, Default, Deserialize)]
+pub struct RollbackBinaryQuery {
+    /// Also rejudge every submission to the problem
+    #[serde(default)]
+    pub rejudge: bool,
 }
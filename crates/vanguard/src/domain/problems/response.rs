@@ -1,9 +1,15 @@
 //! Problem response DTOs.
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
+use olympus_common::OutputComparison;
+use olympus_rules::pipeline::Pipeline;
 use serde::Serialize;
 use uuid::Uuid;
 
+use super::request::StatementFormat;
+
 /// Owner information
 #[derive(Debug, Serialize)]
 pub struct OwnerInfo {
@@ -25,6 +31,7 @@ pub struct ProblemSummary {
     pub network_allowed: bool,
     pub max_score: i32,
     pub is_public: bool,
+    pub organization_id: Option<Uuid>,
     pub owner: OwnerInfo,
     pub created_at: DateTime<Utc>,
 }
@@ -68,8 +75,26 @@ pub struct ProblemDetailResponse {
     pub checker_path: Option<String>,
     pub max_score: i32,
     pub partial_scoring: bool,
+    pub benchmark_scored: bool,
+    pub rank_by_instructions: bool,
+    /// Worker profile judging the problem's submissions
+    pub worker_profile: String,
+    /// Built-in comparison replacing the checker, if any
+    pub output_comparison: Option<OutputComparison>,
+    pub comparison_epsilon: f64,
     pub is_public: bool,
     pub allowed_languages: Option<Vec<String>>,
+    pub organization_id: Option<Uuid>,
+    /// Locale of the statement fields above
+    pub locale: String,
+    /// The problem's own statement locale followed by its translations
+    pub available_locales: Vec<String>,
+    /// Format of `description`, `input_format`, `output_format`,
+    /// `constraints` and `sample_explanation`
+    pub format: StatementFormat,
+    /// Languages of fenced code blocks in the rendered HTML
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub code_languages: Vec<String>,
     pub owner: OwnerInfo,
     pub is_owner: bool,
     pub created_at: DateTime<Utc>,
@@ -95,7 +120,8 @@ pub struct ProblemResponse {
     pub max_threads: i32,
     pub network_allowed: bool,
     pub num_test_cases: i32,
-    /// Status of problem: "draft" until both generator and checker are uploaded, then "ready"
+    /// Status of problem: "draft" until both generator and checker (or, with
+    /// `output_comparison`, the reference solution) are uploaded, then "ready"
     pub status: String,
     /// Whether generator binary has been uploaded
     pub generator_uploaded: bool,
@@ -103,8 +129,17 @@ pub struct ProblemResponse {
     pub checker_uploaded: bool,
     pub max_score: i32,
     pub partial_scoring: bool,
+    pub benchmark_scored: bool,
+    pub rank_by_instructions: bool,
+    /// Worker profile judging the problem's submissions
+    pub worker_profile: String,
+    /// Built-in comparison replacing the checker, if any
+    pub output_comparison: Option<OutputComparison>,
+    pub comparison_epsilon: f64,
     pub is_public: bool,
     pub allowed_languages: Option<Vec<String>>,
+    pub organization_id: Option<Uuid>,
+    pub statement_locale: String,
     pub owner_id: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -127,6 +162,10 @@ pub struct ContestProblemInfo {
     pub network_allowed: bool,
     pub max_score: i32,
     pub sort_order: i32,
+    /// Effective language restriction (contest override or problem-level)
+    pub allowed_languages: Option<Vec<String>>,
+    /// Release time for participants; `None` = with the contest start
+    pub visible_from: Option<DateTime<Utc>>,
 }
 
 /// Contest problems list response
@@ -135,6 +174,447 @@ pub struct ContestProblemsResponse {
     pub problems: Vec<ContestProblemInfo>,
 }
 
+/// Item skipped by a bulk add
+#[derive(Debug, Serialize)]
+pub struct BulkAddConflict {
+    /// Position of the item in the request
+    pub index: usize,
+    pub problem_id: Uuid,
+    pub problem_code: String,
+    pub reason: String,
+}
+
+/// Bulk add problems to contest response
+#[derive(Debug, Serialize)]
+pub struct BulkAddProblemsResponse {
+    pub added: Vec<ContestProblemInfo>,
+    pub conflicts: Vec<BulkAddConflict>,
+}
+
+/// Subtask of a partial-scoring problem
+#[derive(Debug, Serialize)]
+pub struct SubtaskInfo {
+    pub subtask_number: i32,
+    pub first_test: i32,
+    pub last_test: i32,
+    pub points: i32,
+    pub time_limit_ms: Option<i32>,
+    pub memory_limit_kb: Option<i32>,
+}
+
+/// Weight override of a single test case
+#[derive(Debug, Serialize)]
+pub struct TestWeightInfo {
+    pub test: i32,
+    pub weight: i32,
+}
+
+/// Limit override of a single test case
+#[derive(Debug, Serialize)]
+pub struct TestLimitInfo {
+    pub test: i32,
+    pub time_limit_ms: Option<i32>,
+    pub memory_limit_kb: Option<i32>,
+}
+
+/// Partial scoring configuration of a problem
+#[derive(Debug, Serialize)]
+pub struct ProblemScoringResponse {
+    pub problem_id: Uuid,
+    pub partial_scoring: bool,
+    pub num_test_cases: i32,
+    pub subtasks: Vec<SubtaskInfo>,
+    pub test_weights: Vec<TestWeightInfo>,
+    pub test_limits: Vec<TestLimitInfo>,
+}
+
+/// Judging pipeline of a problem
+#[derive(Debug, Serialize)]
+pub struct ProblemPipelineResponse {
+    pub problem_id: Uuid,
+    /// `None` when tests are judged by the built-in generate, run and check
+    /// sequence
+    pub pipeline: Option<Pipeline>,
+    /// Problem binaries the problem needs before it can be judged
+    pub required_binaries: Vec<&'static str>,
+    /// Required binaries not uploaded yet
+    pub missing_binaries: Vec<&'static str>,
+}
+
+/// How much a failed draft check matters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DraftCheckSeverity {
+    /// Blocks creating or activating the problem
+    Error,
+    /// Worth fixing, but does not block
+    Warning,
+}
+
+/// One item of a draft validation checklist
+#[derive(Debug, Serialize)]
+pub struct DraftCheck {
+    /// `fields`, `max_threads`, `samples`, `test_cases`, `languages` or
+    /// `binaries`
+    pub check: &'static str,
+    /// Request field the check is about
+    pub field: Option<String>,
+    pub severity: DraftCheckSeverity,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// Checklist for a draft problem definition
+#[derive(Debug, Serialize)]
+pub struct DraftValidationResponse {
+    pub problem_id: Option<Uuid>,
+    /// No error check failed: the draft can be created, or with
+    /// `problem_id` activated
+    pub ready: bool,
+    pub checks: Vec<DraftCheck>,
+}
+
+/// A case of a checker self-test suite
+#[derive(Debug, Serialize)]
+pub struct CheckerTestInfo {
+    pub test_number: i32,
+    pub input: String,
+    pub output: String,
+    pub answer: Option<String>,
+    pub expected_verdict: String,
+}
+
+/// Checker self-test suite of a problem
+#[derive(Debug, Serialize)]
+pub struct CheckerTestsResponse {
+    pub problem_id: Uuid,
+    pub tests: Vec<CheckerTestInfo>,
+}
+
+/// What the checker said on one case of a self-test run
+#[derive(Debug, Serialize)]
+pub struct CheckerTestResultInfo {
+    pub test_number: i32,
+    pub expected_verdict: String,
+    /// `accepted`, `wrong_answer`, `presentation_error`, `partial_credit`
+    /// or `fail`
+    pub verdict: String,
+    pub matches: bool,
+    pub checker_output: Option<String>,
+}
+
+/// A run of a problem's checker self-test suite
+#[derive(Debug, Serialize)]
+pub struct CheckerTestRunResponse {
+    pub id: Uuid,
+    pub problem_id: Uuid,
+    pub checker_version: i32,
+    /// `pending`, `completed` or `failed`
+    pub status: String,
+    pub total: i32,
+    /// Cases whose verdict differed from the expected one, once completed
+    pub mismatches: Option<i32>,
+    pub error: Option<String>,
+    pub requested_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub results: Vec<CheckerTestResultInfo>,
+}
+
+/// A reference solution of a problem
+#[derive(Debug, Serialize)]
+pub struct ReferenceSolutionInfo {
+    pub submission_id: Uuid,
+    /// `model` or `slow`
+    pub role: String,
+    pub language: Option<String>,
+    /// Status of the submission's last judging
+    pub status: String,
+    pub max_time_ms: Option<i32>,
+    pub marked_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Reference solutions of a problem
+#[derive(Debug, Serialize)]
+pub struct ReferenceSolutionsResponse {
+    pub problem_id: Uuid,
+    pub solutions: Vec<ReferenceSolutionInfo>,
+}
+
+/// How one reference solution did in a calibration run
+#[derive(Debug, Serialize)]
+pub struct CalibrationResultInfo {
+    pub submission_id: Uuid,
+    pub role: String,
+    pub verdict: String,
+    /// Slowest test; the ceiling for a time limit verdict
+    pub max_time_ms: i32,
+    pub max_memory_kb: i32,
+    pub passed: i32,
+    pub total: i32,
+    pub error: Option<String>,
+}
+
+/// A time limit calibration run and its report
+#[derive(Debug, Serialize)]
+pub struct TimeLimitCalibrationResponse {
+    pub id: Uuid,
+    pub problem_id: Uuid,
+    /// `pending`, `completed` or `failed`
+    pub status: String,
+    pub multiplier: f64,
+    pub ceiling_ms: i32,
+    pub current_time_limit_ms: i32,
+    /// Slowest test of the slowest model solution
+    pub model_time_ms: Option<i32>,
+    /// Slowest test of the fastest slow solution
+    pub slow_time_ms: Option<i32>,
+    pub suggested_time_limit_ms: Option<i32>,
+    /// `clear`, `tight` or `none`
+    pub separation: Option<String>,
+    /// Judge worker that measured the times, and its speed factor
+    pub worker_id: Option<String>,
+    pub worker_speed_factor: Option<f64>,
+    pub error: Option<String>,
+    pub requested_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub results: Vec<CalibrationResultInfo>,
+}
+
+/// The model reference solution of a contest problem, judged as a baseline
+/// for the contest's benchmarking leaderboard
+#[derive(Debug, Serialize)]
+pub struct ContestProblemBaselineResponse {
+    pub id: Uuid,
+    pub contest_id: Uuid,
+    pub problem_id: Uuid,
+    pub submission_id: Uuid,
+    /// `pending`, `completed` or `failed`
+    pub status: String,
+    pub verdict: Option<String>,
+    /// Slowest test, normalized for benchmark-scored problems
+    pub max_time_ms: Option<i32>,
+    pub max_memory_kb: Option<i32>,
+    pub total_instructions: Option<i64>,
+    /// Judge worker that measured it, and its speed factor
+    pub worker_id: Option<String>,
+    pub worker_speed_factor: Option<f64>,
+    pub error: Option<String>,
+    pub requested_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A statement translation
+#[derive(Debug, Serialize)]
+pub struct StatementResponse {
+    pub locale: String,
+    pub title: String,
+    pub description: String,
+    pub input_format: Option<String>,
+    pub output_format: Option<String>,
+    pub constraints: Option<String>,
+    pub sample_explanation: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A problem's statement translations
+#[derive(Debug, Serialize)]
+pub struct StatementListResponse {
+    pub problem_id: Uuid,
+    /// Locale of the statement stored on the problem itself
+    pub statement_locale: String,
+    pub statements: Vec<StatementResponse>,
+}
+
+/// A stored generator or checker upload
+#[derive(Debug, Serialize)]
+pub struct BinaryVersionInfo {
+    pub version: i32,
+    /// SHA-256 of the binary, hex
+    pub checksum: String,
+    pub size_bytes: i64,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub is_active: bool,
+}
+
+/// Upload history of a problem binary, newest first
+#[derive(Debug, Serialize)]
+pub struct BinaryVersionListResponse {
+    pub problem_id: Uuid,
+    pub kind: String,
+    pub active_version: i32,
+    pub versions: Vec<BinaryVersionInfo>,
+}
+
+/// Result of rolling back a problem binary
+#[derive(Debug, Serialize)]
+pub struct BinaryRollbackResponse {
+    pub problem_id: Uuid,
+    pub kind: String,
+    pub version: i32,
+    pub checksum: String,
+    /// `queue_pending` submissions sent to judging
+    pub requeued: u64,
+    pub rejudged_count: usize,
+    pub skipped_count: usize,
+    pub message: String,
+}
+
+/// Submission statistics of a problem
+#[derive(Debug, Serialize)]
+pub struct ProblemStatisticsResponse {
+    pub problem_id: Uuid,
+    pub total_submissions: i64,
+    pub accepted_submissions: i64,
+    /// `accepted_submissions / total_submissions`; `None` without submissions
+    pub acceptance_rate: Option<f64>,
+    pub attempted_by: i64,
+    pub solved_by: i64,
+    /// Submission count per verdict
+    pub verdicts: BTreeMap<String, i64>,
+    pub languages: Vec<ProblemLanguageStatistics>,
+    /// Best accepted solution per user, fastest first
+    pub fastest: Vec<AcceptedSolutionInfo>,
+    /// Best accepted solution per user, least memory first
+    pub lowest_memory: Vec<AcceptedSolutionInfo>,
+}
+
+/// Submissions to a problem in one language
+#[derive(Debug, Serialize)]
+pub struct ProblemLanguageStatistics {
+    pub language: String,
+    pub submissions: i64,
+    pub accepted: i64,
+}
+
+/// An accepted solution in a statistics ranking
+#[derive(Debug, Serialize)]
+pub struct AcceptedSolutionInfo {
+    /// Only set when the caller may view the submission
+    pub submission_id: Option<Uuid>,
+    pub user: OwnerInfo,
+    pub language: Option<String>,
+    pub time_ms: Option<i32>,
+    pub memory_kb: Option<i32>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// The caller's submissions to a problem, oldest first
+#[derive(Debug, Serialize)]
+pub struct MyProblemProgressResponse {
+    pub problem_id: Uuid,
+    /// `instructions` on problems ranked by instruction count, else `time`
+    pub ranked_by: String,
+    /// Best accepted submission the deltas compare against
+    pub best_submission_id: Option<Uuid>,
+    pub submissions: Vec<SubmissionProgressEntry>,
+}
+
+/// A judged submission with its results aggregated over the tests run
+#[derive(Debug, Serialize)]
+pub struct SubmissionProgressEntry {
+    pub submission_id: Uuid,
+    pub contest_id: Option<Uuid>,
+    pub language: Option<String>,
+    pub status: String,
+    pub submitted_at: DateTime<Utc>,
+    pub tests_run: i64,
+    /// Slowest test
+    pub time_ms: Option<i32>,
+    /// Sum over all tests
+    pub total_time_ms: Option<i64>,
+    /// Peak over all tests
+    pub memory_kb: Option<i32>,
+    /// Sum over all tests (judges with perf counters only)
+    pub instructions: Option<i64>,
+    /// Differences to the best submission, positive when worse; only set
+    /// for accepted submissions
+    pub time_delta_ms: Option<i64>,
+    pub total_time_delta_ms: Option<i64>,
+    pub memory_delta_kb: Option<i64>,
+    pub instructions_delta: Option<i64>,
+    pub is_best: bool,
+    /// Better than every earlier accepted submission
+    pub improved: bool,
+}
+
+/// A run of the caller's code on a problem's sample
+#[derive(Debug, Serialize)]
+pub struct SampleRunResponse {
+    pub id: Uuid,
+    pub problem_id: Uuid,
+    pub contest_id: Option<Uuid>,
+    pub language: String,
+    /// `pending`, `compiling`, `running`, `completed`,
+    /// `compilation_error` or `failed`
+    pub status: String,
+    pub compile_log: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// One per sample, once completed
+    pub results: Vec<SampleResultInfo>,
+}
+
+/// The outcome of a sample run on one sample
+#[derive(Debug, Serialize)]
+pub struct SampleResultInfo {
+    pub sample_number: i32,
+    pub verdict: String,
+    pub passed: bool,
+    pub time_ms: Option<i64>,
+    pub memory_kb: Option<i64>,
+    /// Comparison comment or runtime error
+    pub message: Option<String>,
+    pub output: Option<String>,
+    pub stderr: Option<String>,
+    /// Whether the output or stderr was cut
+    pub output_truncated: bool,
+    /// Where the output first differs from the expected one
+    pub diff: Option<SampleDiffInfo>,
+}
+
+/// The first line where a sample's output differs from the expected one
+#[derive(Debug, Serialize)]
+pub struct SampleDiffInfo {
+    /// Line number (1-indexed)
+    pub line: i32,
+    /// `None` past the end of the expected output
+    pub expected: Option<String>,
+    /// `None` past the end of the output
+    pub actual: Option<String>,
+}
+
+/// A problem recommended for practice
+#[derive(Debug, Serialize)]
+pub struct RecommendedProblem {
+    pub id: Uuid,
+    pub title: String,
+    pub difficulty: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// `next_step`, `weak_tag` or `favorite_tag`
+    pub reason: String,
+    /// The tag a tag reason refers to
+    pub tag: Option<String>,
+    pub score: f64,
+}
+
+/// Practice recommendations for the caller
+#[derive(Debug, Serialize)]
+pub struct RecommendedProblemsResponse {
+    /// Practice rating from the difficulty of solved problems; `None` until
+    /// the first recommendation run after the user's first submission
+    pub rating: Option<i32>,
+    pub target_difficulty: Option<String>,
+    pub computed_at: Option<DateTime<Utc>>,
+    /// Best first; problems solved since the last run are left out
+    pub problems: Vec<RecommendedProblem>,
+}
+
 /// Simple message response
 #[derive(Debug, Serialize)]
 pub struct MessageResponse {
@@ -0,0 +1,507 @@
+//! Sample runs: trying code on a problem's sample without submitting.
+//!
+//! A run is recorded in `sample_runs` and queued on a stream of its own,
+//! which Sisyphus compiles from and hands on to Minos, so runs do not wait
+//! behind judging. Nothing is scored and no submission is created.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use deadpool_redis::redis;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::handler::require_problem_visible;
+use super::request::RunSamplesRequest;
+use super::response::{SampleDiffInfo, SampleResultInfo, SampleRunResponse};
+use crate::domain::authorization::{build_contest_context, require_can_submit_to_contest};
+use crate::domain::runtimes::ensure_runtime_enabled;
+use crate::domain::submissions::{ensure_language_allowed, require_problem_released};
+use crate::error::{ApiError, ApiResult};
+use crate::extract::ValidatedJson;
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+/// Stream Sisyphus compiles sample runs from
+const SAMPLE_COMPILE_STREAM: &str = "sample_compile_queue";
+
+/// Statuses of a run that has not finished
+const ACTIVE_STATUSES: [&str; 3] = ["pending", "compiling", "running"];
+
+/// A run older than this no longer blocks a new one, in case a worker
+/// lost it
+const ACTIVE_RUN_TIMEOUT_SECS: i64 = 120;
+
+const SAMPLE_RUN_COLUMNS: &str = "id, problem_id, contest_id, language, status, compile_log, \
+                                  error, created_at, completed_at";
+
+/// Row of `sample_runs`
+#[derive(Debug, FromRow)]
+struct SampleRunRow {
+    id: Uuid,
+    problem_id: Uuid,
+    contest_id: Option<Uuid>,
+    language: String,
+    status: String,
+    compile_log: Option<String>,
+    error: Option<String>,
+    created_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+/// Row of `sample_run_results`
+#[derive(Debug, FromRow)]
+struct SampleResultRow {
+    sample_number: i32,
+    verdict: String,
+    time_ms: Option<i64>,
+    memory_kb: Option<i64>,
+    message: Option<String>,
+    output: Option<String>,
+    stderr: Option<String>,
+    output_truncated: bool,
+    diff_line: Option<i32>,
+    diff_expected: Option<String>,
+    diff_actual: Option<String>,
+}
+
+/// Problem fields a sample run needs
+#[derive(Debug, FromRow)]
+struct SampleProblemRow {
+    is_public: bool,
+    owner_id: Uuid,
+    organization_id: Option<Uuid>,
+    has_sample: bool,
+}
+
+fn sample_run_response(row: SampleRunRow, results: Vec<SampleResultRow>) -> SampleRunResponse {
+    SampleRunResponse {
+        id: row.id,
+        problem_id: row.problem_id,
+        contest_id: row.contest_id,
+        language: row.language,
+        status: row.status,
+        compile_log: row.compile_log,
+        error: row.error,
+        created_at: row.created_at,
+        completed_at: row.completed_at,
+        results: results
+            .into_iter()
+            .map(|r| SampleResultInfo {
+                passed: r.verdict == "accepted",
+                sample_number: r.sample_number,
+                verdict: r.verdict,
+                time_ms: r.time_ms,
+                memory_kb: r.memory_kb,
+                message: r.message,
+                output: r.output,
+                stderr: r.stderr,
+                output_truncated: r.output_truncated,
+                diff: r.diff_line.map(|line| SampleDiffInfo {
+                    line,
+                    expected: r.diff_expected,
+                    actual: r.diff_actual,
+                }),
+            })
+            .collect(),
+    }
+}
+
+/// POST /api/v1/problems/{id}/run-samples
+///
+/// Compile the caller's code and run it on the problem's sample. Access
+/// follows submitting: with `contest_id` the caller must be able to submit
+/// to the contest and the problem must be released in it (its limits and
+/// languages then apply); without it the problem must be visible to them.
+/// One run per user at a time. Poll `GET .../run-samples/{run_id}` for the
+/// result.
+pub async fn run_samples(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(problem_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<RunSamplesRequest>,
+) -> ApiResult<(StatusCode, Json<SampleRunResponse>)> {
+    let problem: SampleProblemRow = sqlx::query_as(
+        r#"
+        SELECT is_public, owner_id, organization_id,
+               sample_input IS NOT NULL AND sample_output IS NOT NULL AS has_sample
+        FROM problems WHERE id = $1
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    match payload.contest_id {
+        Some(contest_id) => {
+            let ctx = build_contest_context(&state, &user, contest_id);
+            require_can_submit_to_contest(&ctx).await?;
+            require_problem_released(&state, &user, contest_id, problem_id).await?;
+        }
+        None => {
+            require_problem_visible(
+                &state,
+                Some(&user),
+                problem_id,
+                problem.is_public,
+                problem.owner_id,
+                problem.organization_id,
+            )
+            .await?;
+        }
+    }
+
+    if !problem.has_sample {
+        return Err(ApiError::Conflict("The problem has no sample".to_string()));
+    }
+
+    ensure_runtime_enabled(&state.db, &payload.language).await?;
+    ensure_language_allowed(&state.db, payload.contest_id, problem_id, &payload.language).await?;
+
+    let active: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM sample_runs
+            WHERE user_id = $1 AND status = ANY($2)
+              AND created_at > NOW() - make_interval(secs => $3)
+        )
+        "#,
+    )
+    .bind(user.id)
+    .bind(&ACTIVE_STATUSES[..])
+    .bind(ACTIVE_RUN_TIMEOUT_SECS as f64)
+    .fetch_one(&state.db)
+    .await?;
+    if active {
+        return Err(ApiError::Conflict(
+            "A sample run is already in progress".to_string(),
+        ));
+    }
+
+    let row = enqueue_sample_run(
+        &state,
+        problem_id,
+        payload.contest_id,
+        user.id,
+        &payload.language,
+        &payload.source_code,
+    )
+    .await?;
+
+    tracing::info!(
+        problem_id = %problem_id,
+        user_id = %user.id,
+        run_id = %row.id,
+        "Sample run requested"
+    );
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(sample_run_response(row, Vec::new())),
+    ))
+}
+
+/// Record a run and queue it for compilation
+async fn enqueue_sample_run(
+    state: &AppState,
+    problem_id: Uuid,
+    contest_id: Option<Uuid>,
+    user_id: Uuid,
+    language: &str,
+    source_code: &str,
+) -> ApiResult<SampleRunRow> {
+    let row = sqlx::query_as::<_, SampleRunRow>(&format!(
+        r#"
+        INSERT INTO sample_runs (problem_id, contest_id, user_id, language, source_code)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}
+        "#,
+        SAMPLE_RUN_COLUMNS
+    ))
+    .bind(problem_id)
+    .bind(contest_id)
+    .bind(user_id)
+    .bind(language)
+    .bind(source_code)
+    .fetch_one(&state.db)
+    .await?;
+
+    let mut conn = state.redis.get().await?;
+    redis::cmd("XADD")
+        .arg(SAMPLE_COMPILE_STREAM)
+        .arg("*")
+        .arg("run_id")
+        .arg(row.id.to_string())
+        .query_async::<String>(&mut conn)
+        .await?;
+
+    Ok(row)
+}
+
+/// How the problem's model reference solution does on its stored sample
+pub(super) enum ReferenceSampleOutcome {
+    /// No model solution with source code is marked
+    NoReference,
+    /// A run is in progress; one is started if there was none since the
+    /// problem or the solution last changed
+    Pending,
+    /// The reference output matches the sample output
+    Passed,
+    /// The run failed or the output differs, with the reason
+    Failed(String),
+}
+
+/// Latest standalone run of the reference source on the problem
+#[derive(Debug, FromRow)]
+struct ReferenceRunRow {
+    id: Uuid,
+    status: String,
+    compile_log: Option<String>,
+    error: Option<String>,
+    stale: bool,
+}
+
+/// Check the problem's stored sample output against what its model
+/// reference solution prints on the sample input. Runs go through the
+/// sample run pipeline as the caller's and are reused until the problem
+/// or the reference solution changes, so repeated validation does not
+/// queue new ones.
+pub(super) async fn check_reference_sample(
+    state: &AppState,
+    problem_id: Uuid,
+    user_id: Uuid,
+) -> ApiResult<ReferenceSampleOutcome> {
+    // The earliest marked model solution is the problem's reference
+    let reference: Option<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT s.language, s.source_code
+        FROM problem_reference_solutions r
+        JOIN submissions s ON s.id = r.submission_id
+        WHERE r.problem_id = $1 AND r.role = 'model' AND s.source_code IS NOT NULL
+        ORDER BY r.created_at
+        LIMIT 1
+        "#,
+    )
+    .bind(problem_id)
+    .fetch_optional(&state.db)
+    .await?;
+    let Some((language, source_code)) = reference else {
+        return Ok(ReferenceSampleOutcome::NoReference);
+    };
+
+    let run = sqlx::query_as::<_, ReferenceRunRow>(
+        r#"
+        SELECT r.id, r.status, r.compile_log, r.error,
+               r.status = ANY($4)
+                   AND r.created_at <= NOW() - make_interval(secs => $5) AS stale
+        FROM sample_runs r
+        JOIN problems p ON p.id = r.problem_id
+        WHERE r.problem_id = $1 AND r.contest_id IS NULL
+          AND r.language = $2 AND r.source_code = $3
+          AND r.created_at >= p.updated_at
+        ORDER BY r.created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(problem_id)
+    .bind(&language)
+    .bind(&source_code)
+    .bind(&ACTIVE_STATUSES[..])
+    .bind(ACTIVE_RUN_TIMEOUT_SECS as f64)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let run = match run {
+        Some(run) if !run.stale => run,
+        _ => {
+            let row = enqueue_sample_run(state, problem_id, None, user_id, &language, &source_code)
+                .await?;
+            tracing::info!(
+                problem_id = %problem_id,
+                run_id = %row.id,
+                "Reference sample run requested"
+            );
+            return Ok(ReferenceSampleOutcome::Pending);
+        }
+    };
+
+    match run.status.as_str() {
+        "completed" => {}
+        "compilation_error" => {
+            return Ok(ReferenceSampleOutcome::Failed(format!(
+                "The reference solution does not compile: {}",
+                run.compile_log.as_deref().unwrap_or("no compiler output")
+            )))
+        }
+        "failed" => {
+            return Ok(ReferenceSampleOutcome::Failed(format!(
+                "The reference solution could not be run on the sample: {}",
+                run.error.as_deref().unwrap_or("unknown error")
+            )))
+        }
+        _ => return Ok(ReferenceSampleOutcome::Pending),
+    }
+
+    let results = sqlx::query_as::<_, SampleResultRow>(
+        r#"
+        SELECT sample_number, verdict, time_ms, memory_kb, message, output, stderr,
+               output_truncated, diff_line, diff_expected, diff_actual
+        FROM sample_run_results
+        WHERE run_id = $1
+        ORDER BY sample_number
+        "#,
+    )
+    .bind(run.id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(reference_outcome(&results))
+}
+
+/// Outcome of a completed reference run from its per-sample results
+fn reference_outcome(results: &[SampleResultRow]) -> ReferenceSampleOutcome {
+    if results.is_empty() {
+        return ReferenceSampleOutcome::Failed(
+            "The reference run produced no sample results".to_string(),
+        );
+    }
+    match results.iter().find(|r| r.verdict != "accepted") {
+        None => ReferenceSampleOutcome::Passed,
+        Some(r) => {
+            let mut message = format!(
+                "The reference solution gets {} on sample {}",
+                r.verdict, r.sample_number
+            );
+            if let Some(line) = r.diff_line {
+                message.push_str(&format!(
+                    ": line {} is '{}' in the statement but '{}' in its output",
+                    line,
+                    r.diff_expected.as_deref().unwrap_or(""),
+                    r.diff_actual.as_deref().unwrap_or("")
+                ));
+            } else if let Some(detail) = &r.message {
+                message.push_str(&format!(": {}", detail));
+            }
+            ReferenceSampleOutcome::Failed(message)
+        }
+    }
+}
+
+/// GET /api/v1/problems/{id}/run-samples/{run_id}
+///
+/// Status of the caller's sample run and, once completed, the verdict,
+/// output and first differing line on each sample.
+pub async fn get_sample_run(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((problem_id, run_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<SampleRunResponse>> {
+    let row = sqlx::query_as::<_, SampleRunRow>(&format!(
+        "SELECT {} FROM sample_runs WHERE id = $1 AND problem_id = $2 AND user_id = $3",
+        SAMPLE_RUN_COLUMNS
+    ))
+    .bind(run_id)
+    .bind(problem_id)
+    .bind(user.id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Sample run not found".to_string()))?;
+
+    let results = sqlx::query_as::<_, SampleResultRow>(
+        r#"
+        SELECT sample_number, verdict, time_ms, memory_kb, message, output, stderr,
+               output_truncated, diff_line, diff_expected, diff_actual
+        FROM sample_run_results
+        WHERE run_id = $1
+        ORDER BY sample_number
+        "#,
+    )
+    .bind(run_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(sample_run_response(row, results)))
+}
+
+/// Starting sample runs (signed-in users, rate limited apart from
+/// submissions)
+pub fn sample_run_routes() -> axum::Router<AppState> {
+    use axum::routing::post;
+
+    axum::Router::new().route("/{id}/run-samples", post(run_samples))
+}
+
+/// Polling sample runs (their owners)
+pub fn protected_sample_run_routes() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    axum::Router::new().route("/{id}/run-samples/{run_id}", get(get_sample_run))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(sample_number: i32, verdict: &str) -> SampleResultRow {
+        SampleResultRow {
+            sample_number,
+            verdict: verdict.to_string(),
+            time_ms: Some(1),
+            memory_kb: Some(1024),
+            message: None,
+            output: None,
+            stderr: None,
+            output_truncated: false,
+            diff_line: None,
+            diff_expected: None,
+            diff_actual: None,
+        }
+    }
+
+    #[test]
+    fn reference_passes_when_every_sample_is_accepted() {
+        let results = [result(1, "accepted"), result(2, "accepted")];
+        assert!(matches!(
+            reference_outcome(&results),
+            ReferenceSampleOutcome::Passed
+        ));
+    }
+
+    #[test]
+    fn reference_failure_reports_the_first_differing_line() {
+        let mut wrong = result(2, "wrong_answer");
+        wrong.diff_line = Some(3);
+        wrong.diff_expected = Some("5".to_string());
+        wrong.diff_actual = Some("6".to_string());
+        let results = [result(1, "accepted"), wrong, result(3, "runtime_error")];
+
+        let ReferenceSampleOutcome::Failed(message) = reference_outcome(&results) else {
+            panic!("expected a failure");
+        };
+        assert!(message.contains("wrong_answer on sample 2"));
+        assert!(message.contains("line 3 is '5' in the statement but '6'"));
+    }
+
+    #[test]
+    fn reference_failure_without_a_diff_uses_the_message() {
+        let mut crashed = result(1, "runtime_error");
+        crashed.message = Some("exit code 1".to_string());
+
+        let ReferenceSampleOutcome::Failed(message) = reference_outcome(&[crashed]) else {
+            panic!("expected a failure");
+        };
+        assert!(message.ends_with(": exit code 1"));
+    }
+
+    #[test]
+    fn reference_run_without_results_fails() {
+        assert!(matches!(
+            reference_outcome(&[]),
+            ReferenceSampleOutcome::Failed(_)
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+//! Role management handlers.
+//!
+//! Admin endpoints list, create, update, and remove roles. Built-in roles
+//! cannot be removed and the admin role always keeps every permission, so
+//! the platform cannot be locked out of its own admin area. Changes apply
+//! to a user's requests as soon as the role cache refreshes; assigning a
+//! different role to a user takes effect with their next access token.
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use olympus_rules::roles::{BuiltinRole, Permission, PermissionSet};
+use sqlx::{FromRow, Postgres, Transaction};
+
+use crate::error::{ApiError, ApiResult};
+use crate::extract::ValidatedJson;
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+/// Database row for a role
+#[derive(Debug, FromRow)]
+struct RoleRow {
+    name: String,
+    description: Option<String>,
+    is_builtin: bool,
+    permissions: Vec<String>,
+    user_count: i64,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+const ROLE_SELECT: &str = r#"
+    SELECT r.name, r.description, r.is_builtin,
+           COALESCE(
+               (SELECT array_agg(permission::text) FROM role_permissions WHERE role = r.name),
+               '{}'
+           ) as permissions,
+           (SELECT COUNT(*) FROM users WHERE role = r.name) as user_count,
+           r.created_at, r.updated_at
+    FROM roles r
+"#;
+
+/// GET /api/v1/admin/roles
+///
+/// List every role with its permissions and how many users hold it.
+pub async fn list_roles(State(state): State<AppState>) -> ApiResult<Json<RoleListResponse>> {
+    let rows: Vec<RoleRow> = sqlx::query_as(&format!(
+        "{} ORDER BY r.is_builtin DESC, r.name",
+        ROLE_SELECT
+    ))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(RoleListResponse {
+        roles: rows.into_iter().map(row_to_response).collect(),
+        available_permissions: Permission::ALL.iter().map(|p| p.as_str()).collect(),
+    }))
+}
+
+/// POST /api/v1/admin/roles
+///
+/// Create a custom role.
+pub async fn create_role(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<CreateRoleRequest>,
+) -> ApiResult<(StatusCode, Json<RoleResponse>)> {
+    let permissions = parse_permissions(&payload.permissions)?;
+
+    let mut tx = state.db.begin().await?;
+    let inserted = sqlx::query(
+        "INSERT INTO roles (name, description) VALUES ($1, $2) ON CONFLICT (name) DO NOTHING",
+    )
+    .bind(&payload.name)
+    .bind(&payload.description)
+    .execute(&mut *tx)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Err(ApiError::Conflict(format!(
+            "Role '{}' already exists",
+            payload.name
+        )));
+    }
+
+    replace_permissions(&mut tx, &payload.name, permissions).await?;
+    tx.commit().await?;
+    state.roles.invalidate().await;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        role = %payload.name,
+        "Admin created role"
+    );
+
+    let role = fetch_role(&state, &payload.name).await?;
+    Ok((StatusCode::CREATED, Json(role)))
+}
+
+/// PUT /api/v1/admin/roles/{name}
+///
+/// Update a role's description and/or replace its permissions.
+pub async fn update_role(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateRoleRequest>,
+) -> ApiResult<Json<RoleResponse>> {
+    let permissions = payload
+        .permissions
+        .as_deref()
+        .map(parse_permissions)
+        .transpose()?;
+
+    if permissions.is_some() && name.parse() == Ok(BuiltinRole::Admin) {
+        return Err(ApiError::Validation(
+            "The admin role always holds every permission".to_string(),
+        ));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let updated = sqlx::query(
+        r#"
+        UPDATE roles
+        SET description = COALESCE($2, description), updated_at = NOW()
+        WHERE name = $1
+        "#,
+    )
+    .bind(&name)
+    .bind(&payload.description)
+    .execute(&mut *tx)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Role not found".to_string()));
+    }
+
+    if let Some(permissions) = permissions {
+        replace_permissions(&mut tx, &name, permissions).await?;
+    }
+    tx.commit().await?;
+    state.roles.invalidate().await;
+
+    tracing::info!(admin_id = %admin.id, role = %name, "Admin updated role");
+
+    Ok(Json(fetch_role(&state, &name).await?))
+}
+
+/// DELETE /api/v1/admin/roles/{name}
+///
+/// Remove a custom role no user holds.
+pub async fn delete_role(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> ApiResult<StatusCode> {
+    let role: Option<(bool, i64)> = sqlx::query_as(
+        "SELECT is_builtin, (SELECT COUNT(*) FROM users WHERE role = $1) FROM roles WHERE name = $1",
+    )
+    .bind(&name)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match role {
+        None => return Err(ApiError::NotFound("Role not found".to_string())),
+        Some((true, _)) => {
+            return Err(ApiError::Validation(
+                "Built-in roles cannot be deleted".to_string(),
+            ))
+        }
+        Some((false, users)) if users > 0 => {
+            return Err(ApiError::Conflict(format!(
+                "{} user(s) still hold this role",
+                users
+            )))
+        }
+        Some(_) => {}
+    }
+
+    // The users foreign key rejects the delete if someone was assigned the
+    // role in the meantime
+    sqlx::query("DELETE FROM roles WHERE name = $1 AND NOT is_builtin")
+        .bind(&name)
+        .execute(&state.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                ApiError::Conflict("Users still hold this role".to_string())
+            }
+            e => e.into(),
+        })?;
+    state.roles.invalidate().await;
+
+    tracing::info!(admin_id = %admin.id, role = %name, "Admin deleted role");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check a role exists, for assigning it to a user.
+pub async fn ensure_role_exists(state: &AppState, name: &str) -> ApiResult<()> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM roles WHERE name = $1)")
+        .bind(name)
+        .fetch_one(&state.db)
+        .await?;
+
+    if !exists {
+        return Err(ApiError::Validation(format!("Unknown role '{}'", name)));
+    }
+    Ok(())
+}
+
+fn parse_permissions(names: &[String]) -> ApiResult<PermissionSet> {
+    names
+        .iter()
+        .map(|name| name.parse::<Permission>().map_err(ApiError::Validation))
+        .collect()
+}
+
+async fn replace_permissions(
+    tx: &mut Transaction<'_, Postgres>,
+    role: &str,
+    permissions: PermissionSet,
+) -> ApiResult<()> {
+    sqlx::query("DELETE FROM role_permissions WHERE role = $1")
+        .bind(role)
+        .execute(&mut **tx)
+        .await?;
+
+    let names: Vec<&str> = permissions.iter().map(|p| p.as_str()).collect();
+    sqlx::query("INSERT INTO role_permissions (role, permission) SELECT $1, unnest($2::varchar[])")
+        .bind(role)
+        .bind(&names)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+async fn fetch_role(state: &AppState, name: &str) -> ApiResult<RoleResponse> {
+    let row: RoleRow = sqlx::query_as(&format!("{} WHERE r.name = $1", ROLE_SELECT))
+        .bind(name)
+        .fetch_one(&state.db)
+        .await?;
+    Ok(row_to_response(row))
+}
+
+/// Helper to convert a DB row to response DTO.
+fn row_to_response(row: RoleRow) -> RoleResponse {
+    let permissions: PermissionSet = row
+        .permissions
+        .iter()
+        .filter_map(|p| p.parse().ok())
+        .collect();
+
+    RoleResponse {
+        name: row.name,
+        description: row.description,
+        is_builtin: row.is_builtin,
+        permissions: permissions.iter().map(|p| p.as_str()).collect(),
+        user_count: row.user_count,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    }
+}
@@ -0,0 +1,17 @@
+//! Roles domain module.
+//!
+//! A user's role grants a set of site permissions (see
+//! [`olympus_rules::roles`]). Admins manage custom roles next to the
+//! built-in ones; [`RoleRegistry`] caches every role's permissions for the
+//! auth middleware, and handlers check them through
+//! [`AuthUser::can`](crate::middleware::auth::AuthUser::can) or the
+//! policies in [`authorization`](crate::domain::authorization) rather than
+//! comparing role names.
+
+pub mod handler;
+pub mod registry;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
+pub use registry::RoleRegistry;
@@ -0,0 +1,83 @@
+//! Cached role permissions.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use olympus_rules::roles::{BuiltinRole, PermissionSet};
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+
+/// How long loaded permissions are trusted. Changes made on this instance
+/// apply at once; other Vanguard instances pick them up within this time.
+const ROLE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+struct CachedRoles {
+    loaded_at: Instant,
+    roles: HashMap<String, PermissionSet>,
+}
+
+/// Permissions of every role, loaded from `role_permissions` so the auth
+/// middleware does not query them on every request.
+#[derive(Clone, Default)]
+pub struct RoleRegistry {
+    cache: Arc<RwLock<Option<CachedRoles>>>,
+}
+
+impl RoleRegistry {
+    /// Permissions granted by `role`; unknown roles grant none.
+    pub async fn permissions(&self, db: &PgPool, role: &str) -> PermissionSet {
+        if let Some(cached) = self.cache.read().await.as_ref() {
+            if cached.loaded_at.elapsed() < ROLE_CACHE_TTL {
+                return cached.roles.get(role).copied().unwrap_or_default();
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        match load_roles(db).await {
+            Ok(roles) => {
+                let permissions = roles.get(role).copied().unwrap_or_default();
+                *cache = Some(CachedRoles {
+                    loaded_at: Instant::now(),
+                    roles,
+                });
+                permissions
+            }
+            Err(e) => {
+                tracing::error!("Failed to load role permissions: {}", e);
+                // Keep serving the last known permissions, or the built-in
+                // defaults if there are none
+                match cache.as_ref() {
+                    Some(cached) => cached.roles.get(role).copied().unwrap_or_default(),
+                    None => role
+                        .parse::<BuiltinRole>()
+                        .map(BuiltinRole::default_permissions)
+                        .unwrap_or_default(),
+                }
+            }
+        }
+    }
+
+    /// Drop the cache after roles changed.
+    pub async fn invalidate(&self) {
+        *self.cache.write().await = None;
+    }
+}
+
+async fn load_roles(db: &PgPool) -> Result<HashMap<String, PermissionSet>, sqlx::Error> {
+    let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT r.name, rp.permission FROM roles r \
+         LEFT JOIN role_permissions rp ON rp.role = r.name",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut roles: HashMap<String, PermissionSet> = HashMap::new();
+    for (role, permission) in rows {
+        let permissions = roles.entry(role).or_default();
+        if let Some(permission) = permission.and_then(|p| p.parse().ok()) {
+            permissions.insert(permission);
+        }
+    }
+    Ok(roles)
+}
@@ -0,0 +1,49 @@
+//! Role request DTOs.
+
+use serde::Deserialize;
+use validator::Validate;
+
+/// Create a custom role
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRoleRequest {
+    /// Role key stored on users (e.g. `moderator`)
+    #[validate(custom(function = "validate_role_name"))]
+    pub name: String,
+
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    /// Permission names (e.g. `answer_clarifications`)
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// Update a role (partial update); `permissions` replaces the whole set
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRoleRequest {
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    pub permissions: Option<Vec<String>>,
+}
+
+fn validate_role_name(name: &str) -> Result<(), validator::ValidationError> {
+    let valid = !name.is_empty()
+        && name.len() <= 20
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "_-".contains(c));
+
+    if valid {
+        Ok(())
+    } else {
+        let mut err = validator::ValidationError::new("invalid_role_name");
+        err.message = Some(
+            "Name must be 1-20 characters of lowercase letters, digits, '_' or '-', \
+             starting with a letter"
+                .into(),
+        );
+        Err(err)
+    }
+}
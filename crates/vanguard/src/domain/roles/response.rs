@@ -0,0 +1,25 @@
+//! Role response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A role and the permissions it grants
+#[derive(Debug, Serialize)]
+pub struct RoleResponse {
+    pub name: String,
+    pub description: Option<String>,
+    pub is_builtin: bool,
+    pub permissions: Vec<&'static str>,
+    /// Users holding the role
+    pub user_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Role list response
+#[derive(Debug, Serialize)]
+pub struct RoleListResponse {
+    pub roles: Vec<RoleResponse>,
+    /// Every permission a role can grant
+    pub available_permissions: Vec<&'static str>,
+}
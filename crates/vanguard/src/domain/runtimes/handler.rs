@@ -0,0 +1,707 @@
+//! Runtime registry handlers.
+//!
+//! Admin endpoints register, update, and remove languages; the public list
+//! endpoint exposes the enabled ones to frontends. Sisyphus reads the same
+//! table when compiling, so changes take effect on the next job.
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::extract::ValidatedJson;
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+/// Binary name Sisyphus substitutes for `{binary}` in command templates
+const BINARY_NAME: &str = "main";
+
+const RUNTIME_COLUMNS: &str = "id, name, display_name, image, image_digest, digest_updated_at, \
+     source_file, compile_command, run_command, compile_timeout_secs, compile_memory_limit_mb, \
+     version_command, is_enabled, created_by, created_at, updated_at";
+
+/// Database row for a runtime
+#[derive(Debug, FromRow)]
+struct RuntimeRow {
+    id: Uuid,
+    name: String,
+    display_name: String,
+    image: String,
+    image_digest: Option<String>,
+    digest_updated_at: Option<chrono::DateTime<Utc>>,
+    source_file: String,
+    compile_command: Option<String>,
+    run_command: Option<String>,
+    compile_timeout_secs: Option<i32>,
+    compile_memory_limit_mb: Option<i32>,
+    version_command: Option<String>,
+    is_enabled: bool,
+    created_by: Option<Uuid>,
+    created_at: chrono::DateTime<Utc>,
+    updated_at: chrono::DateTime<Utc>,
+}
+
+/// Database row for the public runtime list
+#[derive(Debug, FromRow)]
+struct RuntimeSummaryRow {
+    name: String,
+    display_name: String,
+    source_file: String,
+    compile_command: Option<String>,
+    run_command: Option<String>,
+    version: Option<String>,
+    version_detected_at: Option<chrono::DateTime<Utc>>,
+}
+
+/// Database row for a rollout
+#[derive(Debug, FromRow)]
+struct RolloutRow {
+    id: Uuid,
+    runtime_name: String,
+    contest_id: Option<Uuid>,
+    image: String,
+    previous_digest: Option<String>,
+    image_digest: String,
+    rolled_by: Option<Uuid>,
+    created_at: chrono::DateTime<Utc>,
+}
+
+/// Reject submissions in languages that are not registered or disabled.
+pub async fn ensure_runtime_enabled(db: &PgPool, name: &str) -> ApiResult<()> {
+    let enabled: Option<bool> =
+        sqlx::query_scalar("SELECT is_enabled FROM runtimes WHERE name = $1")
+            .bind(name)
+            .fetch_optional(db)
+            .await?;
+
+    match enabled {
+        Some(true) => Ok(()),
+        Some(false) => Err(ApiError::Validation(format!(
+            "Language '{}' is currently disabled",
+            name
+        ))),
+        None => Err(ApiError::Validation(format!(
+            "Unsupported language '{}'",
+            name
+        ))),
+    }
+}
+
+/// GET /api/v1/runtimes
+///
+/// List enabled runtimes (languages users can submit in) with their
+/// commands and the toolchain version of their current digest.
+pub async fn list_enabled_runtimes(
+    State(state): State<AppState>,
+) -> ApiResult<Json<RuntimeSummaryListResponse>> {
+    let rows = sqlx::query_as::<_, RuntimeSummaryRow>(
+        r#"
+        SELECT r.name, r.display_name, r.source_file, r.compile_command, r.run_command,
+               v.version, v.detected_at AS version_detected_at
+        FROM runtimes r
+        LEFT JOIN runtime_versions v
+          ON v.runtime_name = r.name
+         AND v.image_digest = r.image_digest
+         AND v.version_command = r.version_command
+        WHERE r.is_enabled
+        ORDER BY r.name
+        "#,
+    )
+    .fetch_all(state.read_db())
+    .await?;
+
+    Ok(Json(RuntimeSummaryListResponse {
+        runtimes: rows
+            .into_iter()
+            .map(|row| {
+                let render = |template: &str| {
+                    template
+                        .replace("{source}", &row.source_file)
+                        .replace("{binary}", BINARY_NAME)
+                };
+                RuntimeSummary {
+                    compile_command: row.compile_command.as_deref().map(render),
+                    run_command: row.run_command.as_deref().map(render),
+                    name: row.name,
+                    display_name: row.display_name,
+                    source_file: row.source_file,
+                    version: row.version,
+                    version_detected_at: row.version_detected_at,
+                }
+            })
+            .collect(),
+    }))
+}
+
+/// GET /api/v1/admin/runtimes
+///
+/// List all registered runtimes, optionally filtered by enabled status.
+pub async fn list_runtimes(
+    State(state): State<AppState>,
+    Query(query): Query<ListRuntimesQuery>,
+) -> ApiResult<Json<RuntimeListResponse>> {
+    let sql = format!(
+        "SELECT {} FROM runtimes WHERE ($1::boolean IS NULL OR is_enabled = $1) ORDER BY name",
+        RUNTIME_COLUMNS
+    );
+
+    let rows = sqlx::query_as::<_, RuntimeRow>(&sql)
+        .bind(query.enabled)
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(RuntimeListResponse {
+        runtimes: rows.into_iter().map(row_to_response).collect(),
+    }))
+}
+
+/// POST /api/v1/admin/runtimes
+///
+/// Register a new runtime.
+pub async fn create_runtime(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<CreateRuntimeRequest>,
+) -> ApiResult<Json<RuntimeResponse>> {
+    let sql = format!(
+        r#"
+        INSERT INTO runtimes (
+            name, display_name, image, source_file, compile_command, run_command,
+            compile_timeout_secs, compile_memory_limit_mb, version_command, is_enabled,
+            created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (name) DO NOTHING
+        RETURNING {}
+        "#,
+        RUNTIME_COLUMNS
+    );
+
+    let row = sqlx::query_as::<_, RuntimeRow>(&sql)
+        .bind(&payload.name)
+        .bind(&payload.display_name)
+        .bind(&payload.image)
+        .bind(&payload.source_file)
+        .bind(non_empty(payload.compile_command.as_deref()))
+        .bind(non_empty(payload.run_command.as_deref()))
+        .bind(payload.compile_timeout_secs)
+        .bind(payload.compile_memory_limit_mb)
+        .bind(non_empty(payload.version_command.as_deref()))
+        .bind(payload.is_enabled)
+        .bind(admin.id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::Conflict(format!("Runtime '{}' already exists", payload.name)))?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        runtime = %row.name,
+        image = %row.image,
+        "Admin registered runtime"
+    );
+
+    Ok(Json(row_to_response(row)))
+}
+
+/// PUT /api/v1/admin/runtimes/{name}
+///
+/// Partially update a runtime. Passing an empty string for
+/// `compile_command`, `run_command` or `version_command` clears it. Changing the image clears
+/// its digest, so Sisyphus resolves the new tag on the next compile.
+pub async fn update_runtime(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateRuntimeRequest>,
+) -> ApiResult<Json<RuntimeResponse>> {
+    let existing = sqlx::query_as::<_, RuntimeRow>(&format!(
+        "SELECT {} FROM runtimes WHERE name = $1",
+        RUNTIME_COLUMNS
+    ))
+    .bind(&name)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Runtime not found".to_string()))?;
+
+    let compile_command = match payload.compile_command.as_deref() {
+        Some(cmd) => non_empty(Some(cmd)),
+        None => existing.compile_command.as_deref(),
+    };
+    let run_command = match payload.run_command.as_deref() {
+        Some(cmd) => non_empty(Some(cmd)),
+        None => existing.run_command.as_deref(),
+    };
+    let version_command = match payload.version_command.as_deref() {
+        Some(cmd) => non_empty(Some(cmd)),
+        None => existing.version_command.as_deref(),
+    };
+
+    let sql = format!(
+        r#"
+        UPDATE runtimes
+        SET display_name = $1,
+            image_digest = CASE WHEN image = $2 THEN image_digest END,
+            digest_updated_at = CASE WHEN image = $2 THEN digest_updated_at END,
+            image = $2,
+            source_file = $3,
+            compile_command = $4,
+            run_command = $5,
+            compile_timeout_secs = $6,
+            compile_memory_limit_mb = $7,
+            version_command = $8,
+            is_enabled = $9
+        WHERE id = $10
+        RETURNING {}
+        "#,
+        RUNTIME_COLUMNS
+    );
+
+    let row = sqlx::query_as::<_, RuntimeRow>(&sql)
+        .bind(
+            payload
+                .display_name
+                .as_ref()
+                .unwrap_or(&existing.display_name),
+        )
+        .bind(payload.image.as_ref().unwrap_or(&existing.image))
+        .bind(
+            payload
+                .source_file
+                .as_ref()
+                .unwrap_or(&existing.source_file),
+        )
+        .bind(compile_command)
+        .bind(run_command)
+        .bind(
+            payload
+                .compile_timeout_secs
+                .or(existing.compile_timeout_secs),
+        )
+        .bind(
+            payload
+                .compile_memory_limit_mb
+                .or(existing.compile_memory_limit_mb),
+        )
+        .bind(version_command)
+        .bind(payload.is_enabled.unwrap_or(existing.is_enabled))
+        .bind(existing.id)
+        .fetch_one(&state.db)
+        .await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        runtime = %row.name,
+        enabled = row.is_enabled,
+        "Admin updated runtime"
+    );
+
+    Ok(Json(row_to_response(row)))
+}
+
+/// DELETE /api/v1/admin/runtimes/{name}
+///
+/// Remove a runtime. Existing submissions keep their language string;
+/// new submissions in this language are rejected.
+pub async fn delete_runtime(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(name): Path<String>,
+) -> ApiResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM runtimes WHERE name = $1")
+        .bind(&name)
+        .execute(&state.db)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound("Runtime not found".to_string()));
+    }
+
+    tracing::info!(admin_id = %admin.id, runtime = %name, "Admin removed runtime");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/admin/runtimes/images
+///
+/// Image registry view: each runtime's image and digest, and how many
+/// contests pin it.
+pub async fn list_runtime_images(
+    State(state): State<AppState>,
+) -> ApiResult<Json<RuntimeImageListResponse>> {
+    let rows = sqlx::query_as::<
+        _,
+        (
+            String,
+            String,
+            Option<String>,
+            Option<chrono::DateTime<Utc>>,
+            i64,
+            i64,
+        ),
+    >(
+        r#"
+        SELECT r.name, r.image, r.image_digest, r.digest_updated_at,
+               COUNT(p.contest_id),
+               COUNT(p.contest_id) FILTER (
+                   WHERE c.end_time > NOW()
+                     AND (p.image <> r.image OR p.image_digest IS DISTINCT FROM r.image_digest)
+               )
+        FROM runtimes r
+        LEFT JOIN contest_runtime_pins p ON p.runtime_name = r.name
+        LEFT JOIN contests c ON c.id = p.contest_id
+        GROUP BY r.id
+        ORDER BY r.name
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(RuntimeImageListResponse {
+        runtimes: rows
+            .into_iter()
+            .map(
+                |(name, image, image_digest, digest_updated_at, pinned, outdated)| {
+                    RuntimeImageInfo {
+                        name,
+                        image,
+                        image_digest,
+                        digest_updated_at,
+                        pinned_contests: pinned,
+                        outdated_active_pins: outdated,
+                    }
+                },
+            )
+            .collect(),
+    }))
+}
+
+/// GET /api/v1/admin/runtimes/{name}/rollouts
+///
+/// Digest history of a runtime and its contest pins, newest first.
+pub async fn list_runtime_rollouts(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<RuntimeImageRolloutListResponse>> {
+    let rows = sqlx::query_as::<_, RolloutRow>(
+        r#"
+        SELECT id, runtime_name, contest_id, image, previous_digest, image_digest,
+               rolled_by, created_at
+        FROM runtime_image_rollouts
+        WHERE runtime_name = $1
+        ORDER BY created_at DESC
+        LIMIT 100
+        "#,
+    )
+    .bind(&name)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(RuntimeImageRolloutListResponse {
+        rollouts: rows.into_iter().map(rollout_to_response).collect(),
+    }))
+}
+
+/// PUT /api/v1/admin/runtimes/{name}/digest
+///
+/// Roll a runtime's image forward to a new digest of the same repository.
+/// New submissions compile from it; contests that already pinned the
+/// runtime keep their pin.
+pub async fn roll_runtime_digest(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path(name): Path<String>,
+    ValidatedJson(payload): ValidatedJson<RollRuntimeDigestRequest>,
+) -> ApiResult<Json<RuntimeResponse>> {
+    let mut tx = state.db.begin().await?;
+
+    let existing = sqlx::query_as::<_, RuntimeRow>(&format!(
+        "SELECT {} FROM runtimes WHERE name = $1 FOR UPDATE",
+        RUNTIME_COLUMNS
+    ))
+    .bind(&name)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Runtime not found".to_string()))?;
+
+    if existing.image_digest.as_deref() == Some(payload.image_digest.as_str()) {
+        return Err(ApiError::Conflict(
+            "Runtime is already on this digest".to_string(),
+        ));
+    }
+
+    let row = sqlx::query_as::<_, RuntimeRow>(&format!(
+        r#"
+        UPDATE runtimes SET image_digest = $1, digest_updated_at = NOW()
+        WHERE id = $2
+        RETURNING {}
+        "#,
+        RUNTIME_COLUMNS
+    ))
+    .bind(&payload.image_digest)
+    .bind(existing.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    record_rollout(
+        &mut tx,
+        &row.name,
+        None,
+        &row.image,
+        existing.image_digest.as_deref(),
+        &payload.image_digest,
+        admin.id,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        runtime = %row.name,
+        previous_digest = ?existing.image_digest,
+        digest = %payload.image_digest,
+        "Admin rolled runtime image digest"
+    );
+
+    Ok(Json(row_to_response(row)))
+}
+
+/// GET /api/v1/admin/contests/{id}/runtime-pins
+///
+/// Images a contest's submissions compile with.
+pub async fn list_contest_pins(
+    State(state): State<AppState>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Json<ContestRuntimePinListResponse>> {
+    ensure_contest_exists(&state.db, contest_id).await?;
+
+    let rows = sqlx::query_as::<
+        _,
+        (
+            String,
+            String,
+            String,
+            Option<String>,
+            Option<Uuid>,
+            chrono::DateTime<Utc>,
+        ),
+    >(
+        r#"
+        SELECT p.runtime_name, p.image, p.image_digest, r.image_digest,
+               p.pinned_by, p.pinned_at
+        FROM contest_runtime_pins p
+        JOIN runtimes r ON r.name = p.runtime_name
+        WHERE p.contest_id = $1
+        ORDER BY p.runtime_name
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(ContestRuntimePinListResponse {
+        contest_id,
+        pins: rows
+            .into_iter()
+            .map(
+                |(runtime_name, image, image_digest, current_digest, pinned_by, pinned_at)| {
+                    ContestRuntimePin {
+                        runtime_name,
+                        image,
+                        image_digest,
+                        current_digest,
+                        pinned_by,
+                        pinned_at,
+                    }
+                },
+            )
+            .collect(),
+    }))
+}
+
+/// PUT /api/v1/admin/contests/{id}/runtime-pins/{name}
+///
+/// Roll a contest's pin for a runtime forward (or pin it ahead of first
+/// use). Without a digest the runtime's current image and digest are
+/// pinned; with one, the pin keeps its image and moves to that digest.
+pub async fn roll_contest_pin(
+    State(state): State<AppState>,
+    Extension(admin): Extension<AuthUser>,
+    Path((contest_id, name)): Path<(Uuid, String)>,
+    ValidatedJson(payload): ValidatedJson<RollContestPinRequest>,
+) -> ApiResult<Json<ContestRuntimePinListResponse>> {
+    ensure_contest_exists(&state.db, contest_id).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    let (runtime_image, runtime_digest): (String, Option<String>) =
+        sqlx::query_as("SELECT image, image_digest FROM runtimes WHERE name = $1")
+            .bind(&name)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Runtime not found".to_string()))?;
+
+    let existing: Option<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT image, image_digest FROM contest_runtime_pins
+        WHERE contest_id = $1 AND runtime_name = $2
+        FOR UPDATE
+        "#,
+    )
+    .bind(contest_id)
+    .bind(&name)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let (image, digest) = match (payload.image_digest, &existing) {
+        (Some(digest), Some((image, _))) => (image.clone(), digest),
+        (Some(digest), None) => (runtime_image, digest),
+        (None, _) => (
+            runtime_image,
+            runtime_digest.ok_or_else(|| {
+                ApiError::Validation(
+                    "Runtime has no digest yet; pass image_digest explicitly".to_string(),
+                )
+            })?,
+        ),
+    };
+
+    if existing.as_ref() == Some(&(image.clone(), digest.clone())) {
+        return Err(ApiError::Conflict(
+            "Contest is already pinned to this image".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO contest_runtime_pins (contest_id, runtime_name, image, image_digest, pinned_by)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (contest_id, runtime_name) DO UPDATE
+        SET image = EXCLUDED.image,
+            image_digest = EXCLUDED.image_digest,
+            pinned_by = EXCLUDED.pinned_by,
+            pinned_at = NOW()
+        "#,
+    )
+    .bind(contest_id)
+    .bind(&name)
+    .bind(&image)
+    .bind(&digest)
+    .bind(admin.id)
+    .execute(&mut *tx)
+    .await?;
+
+    record_rollout(
+        &mut tx,
+        &name,
+        Some(contest_id),
+        &image,
+        existing.as_ref().map(|(_, digest)| digest.as_str()),
+        &digest,
+        admin.id,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        admin_id = %admin.id,
+        contest_id = %contest_id,
+        runtime = %name,
+        image = %image,
+        digest = %digest,
+        "Admin rolled contest runtime pin"
+    );
+
+    list_contest_pins(State(state), Path(contest_id)).await
+}
+
+async fn ensure_contest_exists(db: &PgPool, contest_id: Uuid) -> ApiResult<()> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contests WHERE id = $1)")
+        .bind(contest_id)
+        .fetch_one(db)
+        .await?;
+
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound("Contest not found".to_string()))
+    }
+}
+
+async fn record_rollout(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    runtime_name: &str,
+    contest_id: Option<Uuid>,
+    image: &str,
+    previous_digest: Option<&str>,
+    image_digest: &str,
+    rolled_by: Uuid,
+) -> ApiResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO runtime_image_rollouts (
+            runtime_name, contest_id, image, previous_digest, image_digest, rolled_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(runtime_name)
+    .bind(contest_id)
+    .bind(image)
+    .bind(previous_digest)
+    .bind(image_digest)
+    .bind(rolled_by)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Treat empty template strings as "not set".
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Helper to convert a DB row to response DTO.
+fn row_to_response(row: RuntimeRow) -> RuntimeResponse {
+    RuntimeResponse {
+        id: row.id,
+        name: row.name,
+        display_name: row.display_name,
+        image: row.image,
+        image_digest: row.image_digest,
+        digest_updated_at: row.digest_updated_at,
+        source_file: row.source_file,
+        compile_command: row.compile_command,
+        run_command: row.run_command,
+        compile_timeout_secs: row.compile_timeout_secs,
+        compile_memory_limit_mb: row.compile_memory_limit_mb,
+        version_command: row.version_command,
+        is_enabled: row.is_enabled,
+        created_by: row.created_by,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    }
+}
+
+fn rollout_to_response(row: RolloutRow) -> RuntimeImageRollout {
+    RuntimeImageRollout {
+        id: row.id,
+        runtime_name: row.runtime_name,
+        contest_id: row.contest_id,
+        image: row.image,
+        previous_digest: row.previous_digest,
+        image_digest: row.image_digest,
+        rolled_by: row.rolled_by,
+        created_at: row.created_at,
+    }
+}
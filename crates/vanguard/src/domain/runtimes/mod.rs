@@ -0,0 +1,11 @@
+//! Runtime registry domain module.
+//!
+//! Languages are registered by admins in the `runtimes` table (image,
+//! compile/run templates, source file name, compile limits) and resolved
+//! from there by Vanguard (submission validation) and Sisyphus (compilation).
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
@@ -0,0 +1,140 @@
+//! Runtime registry request DTOs.
+
+use serde::Deserialize;
+use validator::Validate;
+
+/// Register a new runtime (language)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRuntimeRequest {
+    /// Language key used in submissions (e.g. `cpp`, `zig`)
+    #[validate(custom(function = "validate_runtime_name"))]
+    pub name: String,
+
+    #[validate(length(min = 1, max = 64, message = "Display name must be 1-64 characters"))]
+    pub display_name: String,
+
+    /// Docker image Sisyphus compiles in
+    #[validate(length(min = 1, max = 255, message = "Image must be 1-255 characters"))]
+    pub image: String,
+
+    /// Source file name for source-code submissions (e.g. `main.cpp`)
+    #[validate(custom(function = "validate_source_file"))]
+    pub source_file: String,
+
+    /// Compile command template (`{source}`, `{binary}` placeholders)
+    pub compile_command: Option<String>,
+
+    /// Run command template; when set Sisyphus writes it to `run.sh`
+    pub run_command: Option<String>,
+
+    #[validate(range(min = 1, max = 600, message = "Compile timeout must be 1-600 seconds"))]
+    pub compile_timeout_secs: Option<i32>,
+
+    #[validate(range(min = 64, max = 16384, message = "Compile memory must be 64-16384 MB"))]
+    pub compile_memory_limit_mb: Option<i32>,
+
+    /// Command printing the toolchain version (e.g. `g++ --version`)
+    #[validate(length(max = 255, message = "Version command must be at most 255 characters"))]
+    pub version_command: Option<String>,
+
+    #[serde(default = "default_true")]
+    pub is_enabled: bool,
+}
+
+/// Update an existing runtime (partial update)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRuntimeRequest {
+    #[validate(length(min = 1, max = 64, message = "Display name must be 1-64 characters"))]
+    pub display_name: Option<String>,
+
+    #[validate(length(min = 1, max = 255, message = "Image must be 1-255 characters"))]
+    pub image: Option<String>,
+
+    #[validate(custom(function = "validate_source_file"))]
+    pub source_file: Option<String>,
+
+    pub compile_command: Option<String>,
+
+    pub run_command: Option<String>,
+
+    #[validate(range(min = 1, max = 600, message = "Compile timeout must be 1-600 seconds"))]
+    pub compile_timeout_secs: Option<i32>,
+
+    #[validate(range(min = 64, max = 16384, message = "Compile memory must be 64-16384 MB"))]
+    pub compile_memory_limit_mb: Option<i32>,
+
+    #[validate(length(max = 255, message = "Version command must be at most 255 characters"))]
+    pub version_command: Option<String>,
+
+    pub is_enabled: Option<bool>,
+}
+
+/// Roll a runtime's image forward to a new digest
+#[derive(Debug, Deserialize, Validate)]
+pub struct RollRuntimeDigestRequest {
+    /// Digest of the runtime's image repository (`sha256:<64 hex>`)
+    #[validate(regex(path = *DIGEST_REGEX, message = "Digest must be sha256:<64 hex characters>"))]
+    pub image_digest: String,
+}
+
+/// Roll a contest's pin for a runtime forward
+#[derive(Debug, Deserialize, Validate)]
+pub struct RollContestPinRequest {
+    /// Digest to pin; defaults to the runtime's current image and digest
+    #[validate(regex(path = *DIGEST_REGEX, message = "Digest must be sha256:<64 hex characters>"))]
+    pub image_digest: Option<String>,
+}
+
+/// Query for listing runtimes
+#[derive(Debug, Deserialize, Default)]
+pub struct ListRuntimesQuery {
+    /// Filter by enabled status
+    pub enabled: Option<bool>,
+}
+
+fn validate_runtime_name(name: &str) -> Result<(), validator::ValidationError> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "_+-".contains(c));
+
+    if valid {
+        Ok(())
+    } else {
+        let mut err = validator::ValidationError::new("invalid_runtime_name");
+        err.message = Some(
+            "Name must be 1-32 characters of lowercase letters, digits, '_', '+' or '-'".into(),
+        );
+        Err(err)
+    }
+}
+
+fn validate_source_file(file: &str) -> Result<(), validator::ValidationError> {
+    let valid = !file.is_empty()
+        && file.len() <= 64
+        && !file.starts_with('.')
+        && file
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+
+    if valid {
+        Ok(())
+    } else {
+        let mut err = validator::ValidationError::new("invalid_source_file");
+        err.message = Some("Source file must be a plain file name (e.g. main.cpp)".into());
+        Err(err)
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+lazy_static::lazy_static! {
+    static ref DIGEST_REGEX: regex::Regex = regex::Regex::new(r"^sha256:[0-9a-f]{64}$").unwrap();
+}
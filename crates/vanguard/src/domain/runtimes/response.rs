@@ -0,0 +1,116 @@
+//! Runtime registry response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Full runtime definition (admin view)
+#[derive(Debug, Serialize)]
+pub struct RuntimeResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub display_name: String,
+    pub image: String,
+    /// Digest Sisyphus compiles from; `None` until the tag is first resolved
+    pub image_digest: Option<String>,
+    pub digest_updated_at: Option<DateTime<Utc>>,
+    pub source_file: String,
+    pub compile_command: Option<String>,
+    pub run_command: Option<String>,
+    pub compile_timeout_secs: Option<i32>,
+    pub compile_memory_limit_mb: Option<i32>,
+    /// Command Sisyphus runs to detect the toolchain version
+    pub version_command: Option<String>,
+    pub is_enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Runtime list response (admin view)
+#[derive(Debug, Serialize)]
+pub struct RuntimeListResponse {
+    pub runtimes: Vec<RuntimeResponse>,
+}
+
+/// Public runtime summary (languages users can submit in)
+#[derive(Debug, Serialize)]
+pub struct RuntimeSummary {
+    pub name: String,
+    pub display_name: String,
+    pub source_file: String,
+    /// Compile command with placeholders filled in (flags included)
+    pub compile_command: Option<String>,
+    pub run_command: Option<String>,
+    /// Toolchain version of the current image (e.g. `g++ (GCC) 13.2.0`);
+    /// `None` until Sisyphus has compiled with it
+    pub version: Option<String>,
+    pub version_detected_at: Option<DateTime<Utc>>,
+}
+
+/// Public runtime list response
+#[derive(Debug, Serialize)]
+pub struct RuntimeSummaryListResponse {
+    pub runtimes: Vec<RuntimeSummary>,
+}
+
+/// A runtime's image in the registry view
+#[derive(Debug, Serialize)]
+pub struct RuntimeImageInfo {
+    pub name: String,
+    pub image: String,
+    pub image_digest: Option<String>,
+    pub digest_updated_at: Option<DateTime<Utc>>,
+    /// Contests with a pin for this runtime
+    pub pinned_contests: i64,
+    /// Contests still running or upcoming whose pin differs from the
+    /// runtime's current image
+    pub outdated_active_pins: i64,
+}
+
+/// Image registry view response
+#[derive(Debug, Serialize)]
+pub struct RuntimeImageListResponse {
+    pub runtimes: Vec<RuntimeImageInfo>,
+}
+
+/// A digest change of a runtime or a contest pin
+#[derive(Debug, Serialize)]
+pub struct RuntimeImageRollout {
+    pub id: Uuid,
+    pub runtime_name: String,
+    /// Set when a contest pin was rolled
+    pub contest_id: Option<Uuid>,
+    pub image: String,
+    pub previous_digest: Option<String>,
+    pub image_digest: String,
+    /// `None` when Sisyphus recorded a newly resolved tag
+    pub rolled_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Rollout history response
+#[derive(Debug, Serialize)]
+pub struct RuntimeImageRolloutListResponse {
+    pub rollouts: Vec<RuntimeImageRollout>,
+}
+
+/// A contest's pinned image for one runtime
+#[derive(Debug, Serialize)]
+pub struct ContestRuntimePin {
+    pub runtime_name: String,
+    pub image: String,
+    pub image_digest: String,
+    /// The runtime's current digest, to compare against
+    pub current_digest: Option<String>,
+    /// `None` when Sisyphus pinned it on first use
+    pub pinned_by: Option<Uuid>,
+    pub pinned_at: DateTime<Utc>,
+}
+
+/// Contest pin list response
+#[derive(Debug, Serialize)]
+pub struct ContestRuntimePinListResponse {
+    pub contest_id: Uuid,
+    pub pins: Vec<ContestRuntimePin>,
+}
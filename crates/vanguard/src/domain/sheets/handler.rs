@@ -0,0 +1,687 @@
+//! Problem sheet handlers.
+//!
+//! Organizers create sheets and become their owner. The owner, site admins
+//! and managers of the sheet's organization edit it; anyone who can see a
+//! sheet can track their own progress on it.
+
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use olympus_rules::roles::Permission;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::domain::authorization::{build_auth_context, require_permission};
+use crate::domain::organizations::{member_role, require_organization_manager};
+use crate::error::{ApiError, ApiResult};
+use crate::extract::ValidatedJson;
+use crate::middleware::auth::AuthUser;
+use crate::repositories::problems::{self as problem_repo, ProblemFilter, ProblemViewer};
+use crate::repositories::sheets::{self as sheet_repo, SheetFilter, SheetViewer};
+use crate::state::AppState;
+
+use super::request::*;
+use super::response::*;
+
+/// Most problems on one sheet
+const MAX_SHEET_PROBLEMS: usize = 500;
+
+/// Each user's done problems on sheet `$1`, with when they were first done
+/// (marked done, or first accepted submission).
+macro_rules! sheet_completions {
+    () => {
+        r#"
+        WITH done AS (
+            SELECT sp.user_id, sp.problem_id, sp.completed_at
+            FROM problem_sheet_progress sp
+            WHERE sp.sheet_id = $1
+            UNION ALL
+            SELECT s.user_id, s.problem_id, s.submitted_at
+            FROM submissions s
+            JOIN problem_sheet_items i ON i.problem_id = s.problem_id AND i.sheet_id = $1
+            WHERE s.status = 'accepted'
+        ),
+        done_problems AS (
+            SELECT user_id, problem_id, MIN(completed_at) as completed_at
+            FROM done
+            GROUP BY user_id, problem_id
+        )
+        "#
+    };
+}
+
+/// Database row for a sheet with owner info
+#[derive(Debug, FromRow)]
+struct SheetRow {
+    id: Uuid,
+    title: String,
+    description: Option<String>,
+    is_public: bool,
+    organization_id: Option<Uuid>,
+    owner_id: Uuid,
+    owner_username: String,
+    owner_display_name: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Database row for a sheet problem with the viewer's progress
+#[derive(Debug, FromRow)]
+struct SheetProblemRow {
+    problem_id: Uuid,
+    title: String,
+    difficulty: Option<String>,
+    position: i32,
+    note: Option<String>,
+    marked: bool,
+    solved: bool,
+}
+
+async fn fetch_sheet_row(db: &PgPool, sheet_id: Uuid) -> ApiResult<Option<SheetRow>> {
+    let row = sqlx::query_as::<_, SheetRow>(
+        r#"
+        SELECT ps.id, ps.title, ps.description, ps.is_public, ps.organization_id,
+               u.id as owner_id, u.username as owner_username,
+               u.display_name as owner_display_name,
+               ps.created_at, ps.updated_at
+        FROM problem_sheets ps
+        JOIN users u ON u.id = ps.owner_id
+        WHERE ps.id = $1
+        "#,
+    )
+    .bind(sheet_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row)
+}
+
+/// Whether `viewer` may see a sheet: public sheets, and otherwise the owner,
+/// site admins and members of the sheet's organization.
+async fn can_view_sheet(
+    db: &PgPool,
+    sheet: &SheetRow,
+    viewer: Option<&AuthUser>,
+) -> ApiResult<bool> {
+    if sheet.is_public {
+        return Ok(true);
+    }
+    let Some(viewer) = viewer else {
+        return Ok(false);
+    };
+    if viewer.can(Permission::ViewPrivateContent) || viewer.id == sheet.owner_id {
+        return Ok(true);
+    }
+
+    Ok(match sheet.organization_id {
+        Some(organization_id) => member_role(db, organization_id, viewer.id).await?.is_some(),
+        None => false,
+    })
+}
+
+/// Whether `user` may edit a sheet: the owner, site admins and managers of
+/// the sheet's organization.
+async fn can_edit_sheet(db: &PgPool, sheet: &SheetRow, user: &AuthUser) -> ApiResult<bool> {
+    if user.can(Permission::ManagePlatform) || user.id == sheet.owner_id {
+        return Ok(true);
+    }
+
+    Ok(match sheet.organization_id {
+        Some(organization_id) => matches!(
+            member_role(db, organization_id, user.id).await?.as_deref(),
+            Some("owner" | "admin")
+        ),
+        None => false,
+    })
+}
+
+/// Load a sheet `viewer` may see. Hidden sheets are reported as missing.
+async fn load_visible_sheet(
+    db: &PgPool,
+    sheet_id: Uuid,
+    viewer: Option<&AuthUser>,
+) -> ApiResult<SheetRow> {
+    match fetch_sheet_row(db, sheet_id).await? {
+        Some(sheet) if can_view_sheet(db, &sheet, viewer).await? => Ok(sheet),
+        _ => Err(ApiError::NotFound("Sheet not found".to_string())),
+    }
+}
+
+/// Load a sheet `user` may edit.
+async fn load_editable_sheet(db: &PgPool, sheet_id: Uuid, user: &AuthUser) -> ApiResult<SheetRow> {
+    let sheet = load_visible_sheet(db, sheet_id, Some(user)).await?;
+    if !can_edit_sheet(db, &sheet, user).await? {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(sheet)
+}
+
+/// Require every problem to be visible to `user`, and public if the sheet is.
+async fn require_sheet_problems_allowed(
+    db: &PgPool,
+    user: &AuthUser,
+    problem_ids: &[Uuid],
+    public_sheet: bool,
+) -> ApiResult<()> {
+    if problem_ids.is_empty() {
+        return Ok(());
+    }
+
+    let viewer = if user.can(Permission::ViewPrivateContent) {
+        ProblemViewer::Admin
+    } else {
+        ProblemViewer::User(user.id)
+    };
+    let filter = ProblemFilter::new(viewer)
+        .public_only(public_sheet)
+        .ids(problem_ids.to_vec());
+
+    let allowed = problem_repo::count(db, &filter).await?;
+    if allowed as usize == problem_ids.len() {
+        return Ok(());
+    }
+
+    Err(ApiError::Validation(if public_sheet {
+        "Public sheets may only contain public problems".to_string()
+    } else {
+        "Some problems do not exist or are not visible to you".to_string()
+    }))
+}
+
+/// GET /api/v1/sheets
+///
+/// List sheets visible to the caller with their progress on each.
+pub async fn list_sheets(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Query(query): Query<ListSheetsQuery>,
+) -> ApiResult<Json<SheetListResponse>> {
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+
+    let viewer = match user {
+        Some(Extension(ref u)) if u.can(Permission::ViewPrivateContent) => SheetViewer::Admin(u.id),
+        Some(Extension(ref u)) => SheetViewer::User(u.id),
+        None => SheetViewer::Anonymous,
+    };
+    let filter = SheetFilter::new(viewer)
+        .owner(query.owner_id)
+        .organization(query.organization_id);
+
+    let rows = sheet_repo::list(state.read_db(), &filter, per_page as i64, offset).await?;
+    let total = sheet_repo::count(state.read_db(), &filter).await?;
+
+    let sheets = rows
+        .into_iter()
+        .map(|row| SheetSummary {
+            id: row.id,
+            title: row.title,
+            description: row.description,
+            is_public: row.is_public,
+            organization_id: row.organization_id,
+            owner: UserInfo {
+                id: row.owner_id,
+                username: row.owner_username,
+                display_name: row.owner_display_name,
+            },
+            problem_count: row.problem_count,
+            completed_count: row.completed_count,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+        .collect();
+
+    Ok(Json(SheetListResponse {
+        sheets,
+        pagination: Pagination {
+            page,
+            per_page,
+            total,
+            total_pages: ((total as f64) / (per_page as f64)).ceil() as u32,
+        },
+    }))
+}
+
+/// POST /api/v1/sheets
+///
+/// Create an empty sheet (organizer/admin only). The creator becomes its owner.
+pub async fn create_sheet(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<CreateSheetRequest>,
+) -> ApiResult<(StatusCode, Json<SheetResponse>)> {
+    let ctx = build_auth_context(&state, &user);
+    require_permission(&ctx, Permission::CreateSheets).await?;
+
+    if let Some(organization_id) = payload.organization_id {
+        require_organization_manager(&state.db, organization_id, &user).await?;
+    }
+
+    let sheet_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO problem_sheets (title, description, owner_id, organization_id, is_public)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(&payload.title)
+    .bind(&payload.description)
+    .bind(user.id)
+    .bind(payload.organization_id)
+    .bind(payload.is_public)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(user_id = %user.id, sheet_id = %sheet_id, "Created problem sheet");
+
+    let sheet = load_visible_sheet(&state.db, sheet_id, Some(&user)).await?;
+    let response = sheet_response(&state.db, sheet, Some(&user)).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// GET /api/v1/sheets/{id}
+///
+/// Get a sheet with its problems in order and the caller's progress.
+pub async fn get_sheet(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(sheet_id): Path<Uuid>,
+) -> ApiResult<Json<SheetResponse>> {
+    let viewer = user.as_ref().map(|Extension(u)| u);
+    let sheet = load_visible_sheet(state.read_db(), sheet_id, viewer).await?;
+    Ok(Json(sheet_response(state.read_db(), sheet, viewer).await?))
+}
+
+/// PUT /api/v1/sheets/{id}
+///
+/// Partially update a sheet. Making a sheet public requires all of its
+/// problems to be public.
+pub async fn update_sheet(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(sheet_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<UpdateSheetRequest>,
+) -> ApiResult<Json<SheetResponse>> {
+    let sheet = load_editable_sheet(&state.db, sheet_id, &user).await?;
+
+    if payload.is_public == Some(true) && !sheet.is_public {
+        let problem_ids: Vec<Uuid> =
+            sqlx::query_scalar("SELECT problem_id FROM problem_sheet_items WHERE sheet_id = $1")
+                .bind(sheet_id)
+                .fetch_all(&state.db)
+                .await?;
+        require_sheet_problems_allowed(&state.db, &user, &problem_ids, true).await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE problem_sheets
+        SET title = COALESCE($1, title),
+            description = COALESCE($2, description),
+            is_public = COALESCE($3, is_public)
+        WHERE id = $4
+        "#,
+    )
+    .bind(&payload.title)
+    .bind(&payload.description)
+    .bind(payload.is_public)
+    .bind(sheet_id)
+    .execute(&state.db)
+    .await?;
+
+    let sheet = load_visible_sheet(&state.db, sheet_id, Some(&user)).await?;
+    Ok(Json(sheet_response(&state.db, sheet, Some(&user)).await?))
+}
+
+/// DELETE /api/v1/sheets/{id}
+///
+/// Delete a sheet and everyone's progress on it. Its problems are kept.
+pub async fn delete_sheet(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(sheet_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    load_editable_sheet(&state.db, sheet_id, &user).await?;
+
+    sqlx::query("DELETE FROM problem_sheets WHERE id = $1")
+        .bind(sheet_id)
+        .execute(&state.db)
+        .await?;
+
+    tracing::info!(user_id = %user.id, sheet_id = %sheet_id, "Deleted problem sheet");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /api/v1/sheets/{id}/problems
+///
+/// Replace a sheet's problems. The order of the request is the display
+/// order. Progress marks on problems that are removed are dropped.
+pub async fn set_sheet_problems(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(sheet_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<SetSheetProblemsRequest>,
+) -> ApiResult<Json<SheetResponse>> {
+    let sheet = load_editable_sheet(&state.db, sheet_id, &user).await?;
+
+    if payload.problems.len() > MAX_SHEET_PROBLEMS {
+        return Err(ApiError::Validation(format!(
+            "A sheet can hold at most {} problems",
+            MAX_SHEET_PROBLEMS
+        )));
+    }
+
+    let problem_ids: Vec<Uuid> = payload.problems.iter().map(|p| p.problem_id).collect();
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = problem_ids.iter().find(|id| !seen.insert(**id)) {
+        return Err(ApiError::Validation(format!(
+            "Problem {} is listed more than once",
+            duplicate
+        )));
+    }
+
+    require_sheet_problems_allowed(&state.db, &user, &problem_ids, sheet.is_public).await?;
+
+    let positions: Vec<i32> = (1..=problem_ids.len() as i32).collect();
+    let notes: Vec<Option<String>> = payload.problems.into_iter().map(|p| p.note).collect();
+
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query("DELETE FROM problem_sheet_items WHERE sheet_id = $1")
+        .bind(sheet_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO problem_sheet_items (sheet_id, problem_id, position, note)
+        SELECT $1, item.problem_id, item.position, item.note
+        FROM UNNEST($2::uuid[], $3::int[], $4::text[]) AS item(problem_id, position, note)
+        "#,
+    )
+    .bind(sheet_id)
+    .bind(&problem_ids)
+    .bind(&positions)
+    .bind(&notes)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM problem_sheet_progress WHERE sheet_id = $1 AND problem_id <> ALL($2)")
+        .bind(sheet_id)
+        .bind(&problem_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE problem_sheets SET updated_at = NOW() WHERE id = $1")
+        .bind(sheet_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    let sheet = load_visible_sheet(&state.db, sheet_id, Some(&user)).await?;
+    Ok(Json(sheet_response(&state.db, sheet, Some(&user)).await?))
+}
+
+/// PUT /api/v1/sheets/{id}/progress/{problem_id}
+///
+/// Mark a sheet problem as done by the caller.
+pub async fn mark_problem_done(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((sheet_id, problem_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    load_visible_sheet(&state.db, sheet_id, Some(&user)).await?;
+    require_sheet_problem(&state.db, sheet_id, problem_id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO problem_sheet_progress (sheet_id, problem_id, user_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (sheet_id, problem_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(sheet_id)
+    .bind(problem_id)
+    .bind(user.id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/v1/sheets/{id}/progress/{problem_id}
+///
+/// Remove the caller's done mark. A problem the caller has an accepted
+/// submission for still counts as done.
+pub async fn unmark_problem_done(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((sheet_id, problem_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    load_visible_sheet(&state.db, sheet_id, Some(&user)).await?;
+    require_sheet_problem(&state.db, sheet_id, problem_id).await?;
+
+    sqlx::query(
+        "DELETE FROM problem_sheet_progress WHERE sheet_id = $1 AND problem_id = $2 AND user_id = $3",
+    )
+    .bind(sheet_id)
+    .bind(problem_id)
+    .bind(user.id)
+    .execute(&state.db)
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/sheets/{id}/progress
+///
+/// Progress of every user who has done at least one of the sheet's
+/// problems, most completed first (sheet editors only).
+pub async fn get_sheet_progress(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(sheet_id): Path<Uuid>,
+    Query(query): Query<SheetProgressQuery>,
+) -> ApiResult<Json<SheetProgressResponse>> {
+    load_editable_sheet(&state.db, sheet_id, &user).await?;
+
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, 100);
+    let offset = ((page - 1) * per_page) as i64;
+
+    let problem_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM problem_sheet_items WHERE sheet_id = $1")
+            .bind(sheet_id)
+            .fetch_one(state.read_db())
+            .await?;
+
+    let rows =
+        sqlx::query_as::<_, (Uuid, String, Option<String>, i64, Option<DateTime<Utc>>)>(concat!(
+            sheet_completions!(),
+            r#"
+            SELECT u.id, u.username, u.display_name,
+                   COUNT(*) as completed_count,
+                   MAX(dp.completed_at) as last_completed_at
+            FROM done_problems dp
+            JOIN users u ON u.id = dp.user_id
+            GROUP BY u.id, u.username, u.display_name
+            ORDER BY completed_count DESC, last_completed_at ASC, u.username
+            LIMIT $2 OFFSET $3
+            "#
+        ))
+        .bind(sheet_id)
+        .bind(per_page as i64)
+        .bind(offset)
+        .fetch_all(state.read_db())
+        .await?;
+
+    let total: i64 = sqlx::query_scalar(concat!(
+        sheet_completions!(),
+        "SELECT COUNT(DISTINCT user_id) FROM done_problems"
+    ))
+    .bind(sheet_id)
+    .fetch_one(state.read_db())
+    .await?;
+
+    let entries = rows
+        .into_iter()
+        .map(
+            |(id, username, display_name, completed_count, last_completed_at)| SheetProgressEntry {
+                user: UserInfo {
+                    id,
+                    username,
+                    display_name,
+                },
+                completed_count,
+                last_completed_at,
+            },
+        )
+        .collect();
+
+    Ok(Json(SheetProgressResponse {
+        sheet_id,
+        problem_count,
+        entries,
+        pagination: Pagination {
+            page,
+            per_page,
+            total,
+            total_pages: ((total as f64) / (per_page as f64)).ceil() as u32,
+        },
+    }))
+}
+
+/// Fail with 404 unless the problem is on the sheet.
+async fn require_sheet_problem(db: &PgPool, sheet_id: Uuid, problem_id: Uuid) -> ApiResult<()> {
+    let on_sheet: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM problem_sheet_items WHERE sheet_id = $1 AND problem_id = $2)",
+    )
+    .bind(sheet_id)
+    .bind(problem_id)
+    .fetch_one(db)
+    .await?;
+
+    if !on_sheet {
+        return Err(ApiError::NotFound(
+            "Problem is not on this sheet".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Build the full sheet response as seen by `viewer`.
+async fn sheet_response(
+    db: &PgPool,
+    sheet: SheetRow,
+    viewer: Option<&AuthUser>,
+) -> ApiResult<SheetResponse> {
+    let rows = sqlx::query_as::<_, SheetProblemRow>(
+        r#"
+        SELECT i.problem_id, p.title, p.difficulty, i.position, i.note,
+               ($2::uuid IS NOT NULL AND EXISTS (
+                   SELECT 1 FROM problem_sheet_progress sp
+                   WHERE sp.sheet_id = i.sheet_id AND sp.problem_id = i.problem_id
+                     AND sp.user_id = $2
+               )) as marked,
+               ($2::uuid IS NOT NULL AND EXISTS (
+                   SELECT 1 FROM submissions s
+                   WHERE s.problem_id = i.problem_id AND s.user_id = $2
+                     AND s.status = 'accepted'
+               )) as solved
+        FROM problem_sheet_items i
+        JOIN problems p ON p.id = i.problem_id
+        WHERE i.sheet_id = $1
+        ORDER BY i.position
+        "#,
+    )
+    .bind(sheet.id)
+    .bind(viewer.map(|v| v.id))
+    .fetch_all(db)
+    .await?;
+
+    let can_edit = match viewer {
+        Some(viewer) => can_edit_sheet(db, &sheet, viewer).await?,
+        None => false,
+    };
+
+    // A problem made private after it was added stays on the sheet but is
+    // only listed to viewers who can still see it
+    let visible = if rows.is_empty() {
+        HashSet::new()
+    } else {
+        let problem_viewer = match viewer {
+            Some(viewer) if viewer.can(Permission::ViewPrivateContent) => ProblemViewer::Admin,
+            Some(viewer) => ProblemViewer::User(viewer.id),
+            None => ProblemViewer::Anonymous,
+        };
+        let filter =
+            ProblemFilter::new(problem_viewer).ids(rows.iter().map(|row| row.problem_id).collect());
+        problem_repo::ids(db, &filter)
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>()
+    };
+
+    let problems: Vec<SheetProblem> = rows
+        .into_iter()
+        .filter(|row| visible.contains(&row.problem_id))
+        .map(|row| SheetProblem {
+            problem_id: row.problem_id,
+            title: row.title,
+            difficulty: row.difficulty,
+            position: row.position,
+            note: row.note,
+            completed: row.marked || row.solved,
+            solved: row.solved,
+        })
+        .collect();
+    let completed_count = problems.iter().filter(|p| p.completed).count() as i64;
+
+    Ok(SheetResponse {
+        id: sheet.id,
+        title: sheet.title,
+        description: sheet.description,
+        is_public: sheet.is_public,
+        organization_id: sheet.organization_id,
+        owner: UserInfo {
+            id: sheet.owner_id,
+            username: sheet.owner_username,
+            display_name: sheet.owner_display_name,
+        },
+        problems,
+        completed_count,
+        can_edit,
+        created_at: sheet.created_at,
+        updated_at: sheet.updated_at,
+    })
+}
+
+/// Create routes for problem sheets
+pub fn sheet_routes() -> axum::Router<AppState> {
+    use axum::routing::get;
+
+    axum::Router::new()
+        .route("/", get(list_sheets))
+        .route("/{id}", get(get_sheet))
+}
+
+pub fn protected_sheet_routes() -> axum::Router<AppState> {
+    use axum::routing::{delete, get, post, put};
+
+    axum::Router::new()
+        .route("/", post(create_sheet))
+        .route("/{id}", put(update_sheet))
+        .route("/{id}", delete(delete_sheet))
+        .route("/{id}/problems", put(set_sheet_problems))
+        .route("/{id}/progress", get(get_sheet_progress))
+        .route("/{id}/progress/{problem_id}", put(mark_problem_done))
+        .route("/{id}/progress/{problem_id}", delete(unmark_problem_done))
+}
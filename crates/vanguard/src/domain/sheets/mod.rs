@@ -0,0 +1,12 @@
+//! Problem sheets domain module.
+//!
+//! Sheets ("gyms", training sheets) are ordered collections of existing
+//! problems with notes, curated by organizers independently of contests.
+//! Users mark sheet problems as done; a problem also counts as done once the
+//! user has an accepted submission for it, in any contest or standalone.
+
+pub mod handler;
+pub mod request;
+pub mod response;
+
+pub use handler::*;
@@ -0,0 +1,83 @@
+//! Problem sheet request DTOs.
+
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Create sheet request
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateSheetRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: String,
+
+    #[validate(length(max = 10000, message = "Description must be at most 10000 characters"))]
+    pub description: Option<String>,
+
+    /// Public sheets may only contain public problems
+    #[serde(default)]
+    pub is_public: bool,
+
+    /// Share the sheet with this organization's members (managers only)
+    pub organization_id: Option<Uuid>,
+}
+
+/// Update sheet request (partial update)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateSheetRequest {
+    #[validate(length(min = 1, max = 255, message = "Title must be 1-255 characters"))]
+    pub title: Option<String>,
+
+    #[validate(length(max = 10000, message = "Description must be at most 10000 characters"))]
+    pub description: Option<String>,
+
+    pub is_public: Option<bool>,
+}
+
+/// A problem on a sheet
+#[derive(Debug, Deserialize, Validate)]
+pub struct SheetProblemRequest {
+    pub problem_id: Uuid,
+
+    #[validate(length(max = 2000, message = "Note must be at most 2000 characters"))]
+    pub note: Option<String>,
+}
+
+/// Replace a sheet's problems; the order given is the display order
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetSheetProblemsRequest {
+    /// At most `MAX_SHEET_PROBLEMS` problems, checked by the handler
+    #[validate(nested)]
+    pub problems: Vec<SheetProblemRequest>,
+}
+
+/// List sheets query parameters
+#[derive(Debug, Deserialize)]
+pub struct ListSheetsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+
+    pub owner_id: Option<Uuid>,
+
+    pub organization_id: Option<Uuid>,
+}
+
+/// Sheet progress query parameters
+#[derive(Debug, Deserialize)]
+pub struct SheetProgressQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+
+    #[serde(default = "default_per_page")]
+    pub per_page: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    20
+}
@@ -0,0 +1,93 @@
+//! Problem sheet response DTOs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// User information
+#[derive(Debug, Serialize)]
+pub struct UserInfo {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+}
+
+/// Pagination info
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub total_pages: u32,
+}
+
+/// Sheet summary for list responses
+#[derive(Debug, Serialize)]
+pub struct SheetSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub organization_id: Option<Uuid>,
+    pub owner: UserInfo,
+    pub problem_count: i64,
+    /// Problems the caller has done (0 when not signed in)
+    pub completed_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Sheet list response
+#[derive(Debug, Serialize)]
+pub struct SheetListResponse {
+    pub sheets: Vec<SheetSummary>,
+    pub pagination: Pagination,
+}
+
+/// A problem on a sheet with the caller's progress
+#[derive(Debug, Serialize)]
+pub struct SheetProblem {
+    pub problem_id: Uuid,
+    pub title: String,
+    pub difficulty: Option<String>,
+    pub position: i32,
+    pub note: Option<String>,
+    /// Accepted submission or marked done by the caller
+    pub completed: bool,
+    /// Whether the caller has an accepted submission for the problem
+    pub solved: bool,
+}
+
+/// Full sheet with its problems
+#[derive(Debug, Serialize)]
+pub struct SheetResponse {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub organization_id: Option<Uuid>,
+    pub owner: UserInfo,
+    pub problems: Vec<SheetProblem>,
+    pub completed_count: i64,
+    /// Whether the caller may edit the sheet
+    pub can_edit: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One user's progress on a sheet
+#[derive(Debug, Serialize)]
+pub struct SheetProgressEntry {
+    pub user: UserInfo,
+    pub completed_count: i64,
+    pub last_completed_at: Option<DateTime<Utc>>,
+}
+
+/// Progress of every user who has done at least one problem on a sheet
+#[derive(Debug, Serialize)]
+pub struct SheetProgressResponse {
+    pub sheet_id: Uuid,
+    pub problem_count: i64,
+    pub entries: Vec<SheetProgressEntry>,
+    pub pagination: Pagination,
+}
@@ -0,0 +1,144 @@
+//! Admission control for new submissions.
+//!
+//! When the judge falls behind, practice and standalone submissions are
+//! turned away with `503` `JUDGE_OVERLOADED` so live contests keep the
+//! judge's capacity; live contest submissions are always admitted. The
+//! backlog is read from the compile and run streams on each submission that
+//! could be turned away: entries not yet delivered to the workers plus those
+//! delivered but not acknowledged. Over `ADMISSION_QUEUE_THRESHOLD` the
+//! submission is rejected with a `Retry-After` of
+//! `ADMISSION_RETRY_AFTER_SECS` per multiple of the threshold in the
+//! backlog, so clients back off further the deeper the queue.
+
+use olympus_common::JudgePriority;
+use redis::streams::{StreamInfoGroupsReply, StreamRangeReply};
+
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+
+/// `Retry-After` never exceeds this many times the configured base
+const MAX_RETRY_AFTER_FACTOR: u64 = 10;
+
+/// Admit a submission queued at `priority`, or fail with
+/// `JudgeOverloaded` while the judge backlog is over the threshold.
+/// Submissions to a running contest (`High`) are always admitted.
+pub async fn admit_submission(state: &AppState, priority: JudgePriority) -> ApiResult<()> {
+    let threshold = state.config.admission_queue_threshold;
+    if priority == JudgePriority::High || threshold == 0 {
+        return Ok(());
+    }
+
+    let backlog = judge_backlog(state, threshold).await?;
+    if backlog <= threshold {
+        return Ok(());
+    }
+
+    let retry_after = retry_after_secs(backlog, threshold, state.config.admission_retry_after_secs);
+    tracing::warn!(
+        backlog,
+        threshold,
+        retry_after,
+        "Judge backlog over threshold, turning away submission"
+    );
+    Err(ApiError::JudgeOverloaded(retry_after))
+}
+
+/// `Retry-After` for `backlog`: the base per multiple of the threshold
+fn retry_after_secs(backlog: u64, threshold: u64, base: u64) -> u64 {
+    base * (backlog / threshold).clamp(1, MAX_RETRY_AFTER_FACTOR)
+}
+
+/// Unfinished entries across the judge streams: the compile stream at every
+/// priority and the run stream at every priority and worker profile
+async fn judge_backlog(state: &AppState, threshold: u64) -> ApiResult<u64> {
+    let mut conn = state.redis.get().await?;
+
+    let config = &state.config;
+    let mut streams = JudgePriority::streams(&config.compile_stream);
+    streams.extend(
+        config
+            .worker_profiles
+            .streams(&config.run_stream)
+            .into_iter()
+            .map(|(stream, _, _)| stream),
+    );
+
+    let mut backlog = 0;
+    for stream in streams {
+        backlog += stream_backlog(&mut conn, &stream, threshold).await?;
+    }
+    Ok(backlog)
+}
+
+/// Entries of `stream` its busiest consumer group has not acknowledged.
+///
+/// Streams are not trimmed, so the length counts every job ever queued; only
+/// a stream no worker has attached to yet is counted whole. Where Redis
+/// cannot tell a group's lag (after entries were deleted) the undelivered
+/// entries are counted, up to `cap`.
+async fn stream_backlog(
+    conn: &mut deadpool_redis::Connection,
+    stream: &str,
+    cap: u64,
+) -> ApiResult<u64> {
+    let exists: bool = redis::cmd("EXISTS").arg(stream).query_async(conn).await?;
+    if !exists {
+        return Ok(0);
+    }
+
+    let info: StreamInfoGroupsReply = redis::cmd("XINFO")
+        .arg("GROUPS")
+        .arg(stream)
+        .query_async(conn)
+        .await?;
+    if info.groups.is_empty() {
+        let length: u64 = redis::cmd("XLEN").arg(stream).query_async(conn).await?;
+        return Ok(length);
+    }
+
+    let mut busiest = 0;
+    for group in info.groups {
+        let lag = match group.lag {
+            Some(lag) => lag as u64,
+            None => {
+                let undelivered: StreamRangeReply = redis::cmd("XRANGE")
+                    .arg(stream)
+                    .arg(format!("({}", group.last_delivered_id))
+                    .arg("+")
+                    .arg("COUNT")
+                    .arg(cap + 1)
+                    .query_async(conn)
+                    .await?;
+                undelivered.ids.len() as u64
+            }
+        };
+        busiest = busiest.max(group.pending as u64 + lag);
+    }
+    Ok(busiest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after_is_the_base_just_over_the_threshold() {
+        assert_eq!(retry_after_secs(101, 100, 5), 5);
+        assert_eq!(retry_after_secs(199, 100, 5), 5);
+    }
+
+    #[test]
+    fn test_retry_after_grows_with_each_multiple_of_the_threshold() {
+        assert_eq!(retry_after_secs(200, 100, 5), 10);
+        assert_eq!(retry_after_secs(350, 100, 5), 15);
+    }
+
+    #[test]
+    fn test_retry_after_is_capped() {
+        assert_eq!(retry_after_secs(1_000, 100, 5), 50);
+        assert_eq!(
+            retry_after_secs(1_000_000, 100, 5),
+            5 * MAX_RETRY_AFTER_FACTOR
+        );
+    }
+}
@@ -0,0 +1,576 @@
+//! Certified final standings.
+//!
+//! `POST /contests/{id}/standings/final` certifies a finished contest's
+//! standings: the ranked rows are written once as a JSON document, hashed
+//! with SHA-256 and signed with the server's current JWT key, and stored
+//! next to the hash of the previous version. Stored versions are never
+//! rewritten (only their certifier follows an account merge), so a later
+//! rejudge or edit can only be published by certifying a new version,
+//! which stays chained to the ones before it.
+//!
+//! `GET /contests/{id}/standings/final` serves a version with the exact
+//! signed text. With an ES256 key, anyone can check the signature against
+//! the JWKS at `/.well-known/jwks.json`; the response also reports the
+//! server's own checks and, with `?compare=true`, whether the live
+//! standings still agree.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Response,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::authorization::policies;
+use crate::error::{ApiError, ApiResult};
+use crate::extract::{Guard, ValidatedJson};
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+use super::request::{CertifyStandingsRequest, FinalStandingsQuery};
+use super::response::{
+    FinalStandingsCell, FinalStandingsDocument, FinalStandingsEntry, FinalStandingsProblem,
+    FinalStandingsResponse, FinalStandingsVerification,
+};
+use super::scoreboard::{build_scoreboard, standings_response, standings_visibility, Scoreboard};
+
+#[derive(Debug, FromRow)]
+struct FinalStandingsRow {
+    version: i32,
+    document: String,
+    content_hash: String,
+    previous_hash: Option<String>,
+    signature: String,
+    signing_key_id: String,
+    signing_algorithm: String,
+    reason: Option<String>,
+    certified_by: Option<Uuid>,
+    certified_at: DateTime<Utc>,
+    /// `content_hash` of the version before this one
+    predecessor_hash: Option<String>,
+    latest_version: i32,
+}
+
+const FINAL_STANDINGS_COLUMNS: &str = r#"
+    f.version, f.document, f.content_hash, f.previous_hash, f.signature,
+    f.signing_key_id, f.signing_algorithm, f.reason, f.certified_by, f.certified_at,
+    (SELECT p.content_hash FROM contest_final_standings p
+     WHERE p.contest_id = f.contest_id AND p.version = f.version - 1) as predecessor_hash,
+    (SELECT MAX(l.version) FROM contest_final_standings l
+     WHERE l.contest_id = f.contest_id) as latest_version
+"#;
+
+/// POST /api/v1/contests/{id}/standings/final - Certify the final standings
+///
+/// Only once the contest has ended and every submission is judged. Fails
+/// with a conflict when the results are unchanged since the latest version.
+pub async fn certify_final_standings(
+    State(state): State<AppState>,
+    Guard { user, .. }: Guard<policies::EditContest>,
+    Path(contest_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<CertifyStandingsRequest>,
+) -> ApiResult<(StatusCode, Json<FinalStandingsResponse>)> {
+    let ended: bool = sqlx::query_scalar("SELECT end_time <= NOW() FROM contests WHERE id = $1")
+        .bind(contest_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
+
+    if !ended {
+        return Err(ApiError::Validation(
+            "Standings can only be certified after the contest has ended".to_string(),
+        ));
+    }
+
+    let unjudged: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM submissions
+        WHERE contest_id = $1 AND NOT is_practice AND NOT is_test
+          AND status IN ('pending', 'compiling', 'compiled', 'judging', 'queue_pending')
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    if unjudged > 0 {
+        return Err(ApiError::Conflict(format!(
+            "{} submissions are still being judged",
+            unjudged
+        )));
+    }
+
+    let board = build_scoreboard(&state, contest_id).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    // Serializes certifications of the contest, so versions stay gapless
+    sqlx::query("SELECT id FROM contests WHERE id = $1 FOR UPDATE")
+        .bind(contest_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let latest: Option<(i32, String, String)> = sqlx::query_as(
+        r#"
+        SELECT version, content_hash, document FROM contest_final_standings
+        WHERE contest_id = $1
+        ORDER BY version DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let mut document = standings_document(contest_id, board);
+    if let Some((version, hash, text)) = latest {
+        chain_to(&mut document, version, hash, &text)?;
+    }
+
+    let text = serde_json::to_string(&document)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize standings: {}", e)))?;
+    let content_hash = sha256_hex(text.as_bytes());
+    let signed = state.jwt.sign_detached(text.as_bytes())?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO contest_final_standings
+            (contest_id, version, document, content_hash, previous_hash, signature,
+             signing_key_id, signing_algorithm, reason, certified_by, certified_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#,
+    )
+    .bind(contest_id)
+    .bind(document.version)
+    .bind(&text)
+    .bind(&content_hash)
+    .bind(&document.previous_hash)
+    .bind(&signed.signature)
+    .bind(&signed.key_id)
+    .bind(&signed.algorithm)
+    .bind(&payload.reason)
+    .bind(user.id)
+    .bind(document.certified_at)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        contest_id = %contest_id,
+        user_id = %user.id,
+        version = document.version,
+        content_hash = %content_hash,
+        "Certified final standings"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(FinalStandingsResponse {
+            contest_id,
+            version: document.version,
+            latest_version: document.version,
+            certified_at: document.certified_at,
+            certified_by: Some(user.id),
+            reason: payload.reason,
+            content_hash,
+            previous_hash: document.previous_hash.clone(),
+            signature: signed.signature,
+            signing_key_id: signed.key_id,
+            signing_algorithm: signed.algorithm,
+            verification: FinalStandingsVerification {
+                hash_valid: true,
+                signature_valid: Some(true),
+                chain_valid: true,
+                matches_current_standings: Some(true),
+            },
+            standings: document,
+            document: text,
+        }),
+    ))
+}
+
+/// GET /api/v1/contests/{id}/standings/final - Certified final standings
+///
+/// The latest version, or the one named by `?version=`, for contests the
+/// caller may see.
+pub async fn get_final_standings(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(contest_id): Path<Uuid>,
+    Query(query): Query<FinalStandingsQuery>,
+) -> ApiResult<Response> {
+    let shared = standings_visibility(&state, contest_id, user.as_ref().map(|u| &u.0)).await?;
+    let row: FinalStandingsRow = sqlx::query_as(&format!(
+        r#"
+        SELECT {} FROM contest_final_standings f
+        WHERE f.contest_id = $1 AND ($2::int IS NULL OR f.version = $2)
+        ORDER BY f.version DESC
+        LIMIT 1
+        "#,
+        FINAL_STANDINGS_COLUMNS
+    ))
+    .bind(contest_id)
+    .bind(query.version)
+    .fetch_optional(state.read_db())
+    .await?
+    .ok_or_else(|| match query.version {
+        Some(version) => ApiError::NotFound(format!(
+            "Version {} of the final standings not found",
+            version
+        )),
+        None => ApiError::NotFound("Final standings have not been certified".to_string()),
+    })?;
+
+    let document = parse_document(&row.document)?;
+    // Rebuilding the live standings is the expensive part; only on request
+    let matches_current_standings = if query.compare {
+        let current = standings_document(contest_id, build_scoreboard(&state, contest_id).await?);
+        Some(same_results(&document.entries, &current.entries))
+    } else {
+        None
+    };
+
+    let verification = FinalStandingsVerification {
+        hash_valid: hash_valid(&row),
+        signature_valid: state.jwt.verify_detached(
+            &row.signing_key_id,
+            row.document.as_bytes(),
+            &row.signature,
+        ),
+        chain_valid: chain_valid(&row, &document, contest_id),
+        matches_current_standings,
+    };
+
+    let response = Json(FinalStandingsResponse {
+        contest_id,
+        version: row.version,
+        latest_version: row.latest_version,
+        certified_at: row.certified_at,
+        certified_by: row.certified_by,
+        reason: row.reason,
+        standings: document,
+        document: row.document,
+        content_hash: row.content_hash,
+        previous_hash: row.previous_hash,
+        signature: row.signature,
+        signing_key_id: row.signing_key_id,
+        signing_algorithm: row.signing_algorithm,
+        verification,
+    });
+    Ok(standings_response(response, shared))
+}
+
+/// First version of the standings document for a scoreboard
+fn standings_document(contest_id: Uuid, board: Scoreboard) -> FinalStandingsDocument {
+    FinalStandingsDocument {
+        contest_id,
+        contest_title: board.contest.title,
+        scoring_type: board.contest.scoring_type,
+        start_time: board.contest.start_time,
+        end_time: board.contest.end_time,
+        version: 1,
+        previous_hash: None,
+        certified_at: Utc::now(),
+        problems: board
+            .problems
+            .into_iter()
+            .map(|p| FinalStandingsProblem {
+                problem_code: p.problem_code,
+                title: p.title,
+                max_score: p.max_score,
+            })
+            .collect(),
+        entries: board
+            .rows
+            .into_iter()
+            .map(|row| FinalStandingsEntry {
+                rank: row.rank,
+                user_id: row.user_id,
+                username: row.username,
+                display_name: row.display_name,
+                solved: row.solved,
+                score: row.score,
+                penalty: row.penalty,
+                cells: row
+                    .cells
+                    .into_iter()
+                    .map(|c| FinalStandingsCell {
+                        problem_code: c.problem_code,
+                        score: c.score,
+                        attempts: c.attempts,
+                        solved: c.solved,
+                        solved_minute: c.solved_minute,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+/// Make `document` the version after `latest`, stored as `latest_text`
+/// with hash `latest_hash`. Fails with a conflict when the results have not
+/// changed since.
+fn chain_to(
+    document: &mut FinalStandingsDocument,
+    latest: i32,
+    latest_hash: String,
+    latest_text: &str,
+) -> ApiResult<()> {
+    let previous = parse_document(latest_text)?;
+    if same_results(&previous.entries, &document.entries) {
+        return Err(ApiError::Conflict(format!(
+            "Standings are unchanged since version {}",
+            latest
+        )));
+    }
+    document.version = latest + 1;
+    document.previous_hash = Some(latest_hash);
+    Ok(())
+}
+
+/// `content_hash` is the hash of the stored text
+fn hash_valid(row: &FinalStandingsRow) -> bool {
+    sha256_hex(row.document.as_bytes()) == row.content_hash
+}
+
+/// The stored version follows its predecessor, and its signed document
+/// names the same contest, version and predecessor
+fn chain_valid(
+    row: &FinalStandingsRow,
+    document: &FinalStandingsDocument,
+    contest_id: Uuid,
+) -> bool {
+    row.previous_hash == row.predecessor_hash
+        && document.previous_hash == row.previous_hash
+        && document.version == row.version
+        && document.contest_id == contest_id
+}
+
+/// Whether two standings rank the same users with the same results.
+/// Renames do not count as changes.
+fn same_results(a: &[FinalStandingsEntry], b: &[FinalStandingsEntry]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.user_id == b.user_id
+                && a.rank == b.rank
+                && a.solved == b.solved
+                && a.score == b.score
+                && a.penalty == b.penalty
+                && a.cells == b.cells
+        })
+}
+
+fn parse_document(text: &str) -> ApiResult<FinalStandingsDocument> {
+    serde_json::from_str(text)
+        .map_err(|e| ApiError::Internal(format!("Corrupt final standings document: {}", e)))
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::testing::TestDb;
+
+    fn cell(problem_code: &str, score: f64, solved_minute: Option<i64>) -> FinalStandingsCell {
+        FinalStandingsCell {
+            problem_code: problem_code.to_string(),
+            score,
+            attempts: 1,
+            solved: solved_minute.is_some(),
+            solved_minute,
+        }
+    }
+
+    fn entry(rank: i32, user: u128, username: &str) -> FinalStandingsEntry {
+        FinalStandingsEntry {
+            rank,
+            user_id: Uuid::from_u128(user),
+            username: username.to_string(),
+            display_name: None,
+            solved: 1,
+            score: 100.0,
+            penalty: 20,
+            cells: vec![cell("A", 100.0, Some(20)), cell("B", 0.0, None)],
+        }
+    }
+
+    fn document(contest_id: Uuid, entries: Vec<FinalStandingsEntry>) -> FinalStandingsDocument {
+        FinalStandingsDocument {
+            contest_id,
+            contest_title: "Round 1".to_string(),
+            scoring_type: "icpc".to_string(),
+            start_time: Utc::now(),
+            end_time: Utc::now(),
+            version: 1,
+            previous_hash: None,
+            certified_at: Utc::now(),
+            problems: Vec::new(),
+            entries,
+        }
+    }
+
+    /// A stored version as `get_final_standings` reads it back
+    fn stored(
+        document: &FinalStandingsDocument,
+        predecessor_hash: Option<&str>,
+    ) -> FinalStandingsRow {
+        let text = serde_json::to_string(document).unwrap();
+        FinalStandingsRow {
+            version: document.version,
+            content_hash: sha256_hex(text.as_bytes()),
+            document: text,
+            previous_hash: document.previous_hash.clone(),
+            signature: String::new(),
+            signing_key_id: "key".to_string(),
+            signing_algorithm: "ES256".to_string(),
+            reason: None,
+            certified_by: None,
+            certified_at: document.certified_at,
+            predecessor_hash: predecessor_hash.map(str::to_string),
+            latest_version: document.version,
+        }
+    }
+
+    #[test]
+    fn test_same_results_ignores_renames() {
+        let mut renamed = entry(1, 1, "alice");
+        renamed.username = "alice2".to_string();
+        renamed.display_name = Some("Alice".to_string());
+        assert!(same_results(&[entry(1, 1, "alice")], &[renamed]));
+    }
+
+    #[test]
+    fn test_same_results_sees_rank_and_cell_changes() {
+        let before = [entry(1, 1, "alice"), entry(2, 2, "bob")];
+
+        let mut reranked = [entry(1, 1, "alice"), entry(2, 2, "bob")];
+        reranked[1].rank = 1;
+        assert!(!same_results(&before, &reranked));
+
+        // Same totals, but the accepted run is now a minute later
+        let mut rejudged = [entry(1, 1, "alice"), entry(2, 2, "bob")];
+        rejudged[0].cells[0] = cell("A", 100.0, Some(21));
+        assert!(!same_results(&before, &rejudged));
+
+        assert!(!same_results(&before, &before[..1]));
+    }
+
+    #[test]
+    fn test_chain_to_bumps_the_version_or_conflicts() {
+        let contest_id = Uuid::new_v4();
+        let first = document(contest_id, vec![entry(1, 1, "alice")]);
+        let first_text = serde_json::to_string(&first).unwrap();
+
+        let mut unchanged = document(contest_id, vec![entry(1, 1, "alice")]);
+        match chain_to(&mut unchanged, 1, "h1".to_string(), &first_text) {
+            Err(ApiError::Conflict(message)) => {
+                assert_eq!(message, "Standings are unchanged since version 1")
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+
+        let mut changed = document(contest_id, vec![entry(1, 1, "alice"), entry(2, 2, "bob")]);
+        chain_to(&mut changed, 1, "h1".to_string(), &first_text).unwrap();
+        assert_eq!(changed.version, 2);
+        assert_eq!(changed.previous_hash.as_deref(), Some("h1"));
+    }
+
+    #[test]
+    fn test_stored_hash_matches_the_document_text() {
+        let contest_id = Uuid::new_v4();
+        let row = stored(&document(contest_id, vec![entry(1, 1, "alice")]), None);
+        assert!(hash_valid(&row));
+        assert_eq!(row.content_hash.len(), 64);
+
+        let mut tampered = stored(&document(contest_id, vec![entry(1, 1, "alice")]), None);
+        tampered.document = tampered.document.replace("alice", "mallory");
+        assert!(!hash_valid(&tampered));
+    }
+
+    #[test]
+    fn test_chain_valid_checks_the_predecessor() {
+        let contest_id = Uuid::new_v4();
+        let mut second = document(contest_id, vec![entry(1, 1, "alice")]);
+        second.version = 2;
+        second.previous_hash = Some("h1".to_string());
+
+        let row = stored(&second, Some("h1"));
+        assert!(chain_valid(&row, &second, contest_id));
+
+        // The version before was replaced after this one was certified
+        let row = stored(&second, Some("h1-rewritten"));
+        assert!(!chain_valid(&row, &second, contest_id));
+
+        // The document names another contest
+        let row = stored(&second, Some("h1"));
+        assert!(!chain_valid(&row, &second, Uuid::new_v4()));
+    }
+
+    #[tokio::test]
+    async fn test_only_the_certifier_of_stored_standings_can_change() {
+        let Some(db) = TestDb::new().await else {
+            return;
+        };
+        let organizer = db.user("organizer", "organizer").await;
+        let duplicate = db.user("duplicate", "organizer").await;
+        let contest: Uuid = sqlx::query_scalar(
+            "INSERT INTO contests (title, start_time, end_time, owner_id) \
+             VALUES ('round', NOW() - INTERVAL '2 hours', NOW() - INTERVAL '1 hour', $1) \
+             RETURNING id",
+        )
+        .bind(organizer)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO contest_final_standings \
+                 (contest_id, version, document, content_hash, signature, \
+                  signing_key_id, signing_algorithm, certified_by) \
+             VALUES ($1, 1, '{}', 'hash', 'sig', 'key', 'HS256', $2)",
+        )
+        .bind(contest)
+        .bind(duplicate)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        // What an account merge does
+        sqlx::query("UPDATE contest_final_standings SET certified_by = $1 WHERE certified_by = $2")
+            .bind(organizer)
+            .bind(duplicate)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        for statement in [
+            "UPDATE contest_final_standings SET document = '{\"forged\": true}'",
+            "UPDATE contest_final_standings SET certified_by = NULL, reason = 'edited'",
+            "DELETE FROM contest_final_standings",
+        ] {
+            let error = sqlx::query(statement).execute(&db.pool).await.unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .contains("Certified standings cannot be changed"),
+                "{statement}: {error}"
+            );
+        }
+
+        let certified_by: Option<Uuid> =
+            sqlx::query_scalar("SELECT certified_by FROM contest_final_standings")
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!(certified_by, Some(organizer));
+
+        db.drop().await;
+    }
+}
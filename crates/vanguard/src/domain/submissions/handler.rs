@@ -1,107 +1,82 @@
 //! Submission handlers.
 
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
 use axum::{
+    body::Body,
     extract::{Multipart, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Extension, Json,
 };
 use chrono::Utc;
+use olympus_common::storage::{GENERATOR_VERSION_MARKER, SUBMISSIONS_DIR};
+use olympus_common::JudgePriority;
+use olympus_rules::roles::Permission;
 use uuid::Uuid;
-use validator::Validate;
 
 use crate::domain::authorization::{
-    build_auth_context, build_contest_context, require_can_submit, require_can_submit_standalone,
+    build_auth_context, build_contest_context, policies, require_can_submit_standalone,
+    require_can_submit_to_contest, require_submission_view_access, require_test_data_access,
+    ContestSubmissionKind, Policy,
+};
+use crate::domain::contests::handler::is_contest_staff;
+use crate::domain::organizations::tenancy::{
+    enforce_tenant_limits, require_tenant_member, submission_tenant,
 };
+use crate::domain::runtimes::ensure_runtime_enabled;
 use crate::error::{ApiError, ApiResult};
+use crate::extract::ValidatedJson;
 use crate::middleware::auth::AuthUser;
+use crate::middleware::request_log::tag_stream_entry;
+use crate::pagination::{Cursor, CursorPage, CursorQuery};
+use crate::repositories::submissions::{
+    self as submission_repo, SubmissionFilter, SubmissionListRow,
+};
+use crate::scanner::ScanTarget;
 use crate::state::AppState;
+use crate::upload::SpooledUpload;
 
+use super::admission::admit_submission;
 use super::request::{
-    CreateSubmissionRequest, LeaderboardQuery, ListSubmissionsQuery, ZipSubmissionParams,
+    CreateSubmissionRequest, LeaderboardQuery, ListSubmissionsQuery, MyStandingQuery, SourceQuery,
+    SubmissionFeedQuery, SubmissionStatusRequest, ZipSubmissionParams,
 };
 use super::response::*;
+use super::scoreboard::{standings_response, standings_visibility};
 
 /// POST /api/v1/submissions - Submit source code
 ///
 /// If `contest_id` is provided, validates the contest is active and the user
 /// is authorized to submit (participant/collaborator/admin). The problem must
-/// be assigned to that contest.
+/// be assigned to that contest. After the contest has ended, the submission
+/// is accepted as a practice ("upsolving") submission instead; before it
+/// starts, the contest team may submit test submissions (`is_test`).
 ///
 /// If `contest_id` is omitted, this is a standalone (practice) submission.
 /// The problem must exist and the user simply needs to be valid and not rate-limited.
 pub async fn create_submission(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
-    Json(payload): Json<CreateSubmissionRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateSubmissionRequest>,
 ) -> ApiResult<Json<SubmissionResponse>> {
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(format!("{}", e)))?;
-
     let user_id = user.id;
-    let lang_str = payload.language.to_string();
+    let lang_str = payload.language.clone();
+    ensure_runtime_enabled(&state.db, &lang_str).await?;
 
+    let mut is_practice = false;
+    let mut is_test = false;
     if let Some(contest_id) = payload.contest_id {
         // ── Contest submission ──────────────────────────────────────────
-        let contest = sqlx::query_as::<_, ContestCheckRow>(
-            r#"
-            SELECT id, status, starts_at, ends_at, allowed_languages
-            FROM contests WHERE id = $1
-            "#,
-        )
-        .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await?
-        .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
-
-        // Check contest is running
-        let now = Utc::now();
-        if contest.status != "active" {
-            return Err(ApiError::Validation("Contest is not active".to_string()));
-        }
-        if now < contest.starts_at {
-            return Err(ApiError::Validation(
-                "Contest has not started yet".to_string(),
-            ));
-        }
-        if now > contest.ends_at {
-            return Err(ApiError::Validation("Contest has ended".to_string()));
-        }
-
-        // Check language is allowed
-        if let Some(allowed) = &contest.allowed_languages {
-            if !allowed.contains(&lang_str) {
-                return Err(ApiError::Validation(format!(
-                    "Language '{}' is not allowed in this contest",
-                    lang_str
-                )));
-            }
-        }
-
-        // Check submission permission using authorization rules
+        // Check contest is running (or over, for practice) and the user may submit to it
         let ctx = build_contest_context(&state, &user, contest_id);
-        require_can_submit(&ctx).await?;
-
-        // Check problem exists and is in contest
-        let problem_in_contest: Option<bool> = sqlx::query_scalar::<_, Option<bool>>(
-            r#"
-            SELECT EXISTS(
-                SELECT 1 FROM problems p
-                JOIN contest_problems cp ON cp.problem_id = p.id
-                WHERE p.id = $1 AND cp.contest_id = $2
-            )
-            "#,
-        )
-        .bind(payload.problem_id)
-        .bind(contest_id)
-        .fetch_one(&state.db)
-        .await?;
-        let problem_in_contest = problem_in_contest.unwrap_or(false);
+        let kind = require_can_submit_to_contest(&ctx).await?;
+        is_practice = kind == ContestSubmissionKind::Practice;
+        is_test = kind == ContestSubmissionKind::Test;
 
-        if !problem_in_contest {
-            return Err(ApiError::NotFound(
-                "Problem not found in this contest".to_string(),
-            ));
-        }
+        // Check problem is in contest and released
+        require_problem_released(&state, &user, contest_id, payload.problem_id).await?;
     } else {
         // ── Standalone submission ──────────────────────────────────────
         let ctx = build_auth_context(&state, &user);
@@ -120,72 +95,336 @@ pub async fn create_submission(
         }
     }
 
-    // Create submission
-    let submission_id = Uuid::new_v4();
-    let submitted_at = Utc::now();
+    // Standalone submissions to a tenant's problem are for its members only
+    let tenant_id = submission_tenant(&state.db, payload.contest_id, payload.problem_id).await?;
+    if let (Some(tenant_id), None) = (tenant_id, payload.contest_id) {
+        require_tenant_member(&state.db, tenant_id, &user).await?;
+    }
 
-    sqlx::query(
-        r#"
-        INSERT INTO submissions (
-            id, contest_id, problem_id, user_id,
-            submission_type, language, source_code,
-            status, submitted_at
+    ensure_language_allowed(&state.db, payload.contest_id, payload.problem_id, &lang_str).await?;
+
+    // Practice gives way to live contests while the judge is behind
+    let live = payload.contest_id.is_some() && !is_practice && !is_test;
+    let priority = queue_priority(live);
+    admit_submission(&state, priority).await?;
+
+    // The cooldown paces live contests; practice is covered by the rate limit
+    let cooldown = match payload.contest_id.filter(|_| live) {
+        Some(contest_id) => {
+            claim_submission_cooldown(&state, contest_id, user_id, payload.problem_id).await?
+        }
+        None => None,
+    };
+
+    // Give the cooldown back if the submission is not created after all
+    let queued: ApiResult<Json<SubmissionResponse>> = async {
+        if let Some(tenant_id) = tenant_id {
+            enforce_tenant_limits(&state, tenant_id, payload.source_code.len() as i64).await?;
+        }
+
+        // Create submission
+        let submission_id = Uuid::new_v4();
+        let submitted_at = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO submissions (
+                id, contest_id, problem_id, user_id,
+                submission_type, language, source_code,
+                status, submitted_at, organization_id, is_practice, is_test, storage_root
+            )
+            VALUES ($1, $2, $3, $4, 'source', $5, $6, 'pending', $7, $8, $9, $10,
+                    (SELECT storage_root FROM contests WHERE id = $2))
+            "#,
         )
-        VALUES ($1, $2, $3, $4, 'source', $5, $6, 'pending', $7)
-        "#,
-    )
-    .bind(submission_id)
-    .bind(payload.contest_id) // NULL for standalone
-    .bind(payload.problem_id)
-    .bind(user_id)
-    .bind(&lang_str)
-    .bind(&payload.source_code)
-    .bind(submitted_at)
-    .execute(&state.db)
-    .await?;
+        .bind(submission_id)
+        .bind(payload.contest_id) // NULL for standalone
+        .bind(payload.problem_id)
+        .bind(user_id)
+        .bind(&lang_str)
+        .bind(&payload.source_code)
+        .bind(submitted_at)
+        .bind(tenant_id)
+        .bind(is_practice)
+        .bind(is_test)
+        .execute(&state.db)
+        .await?;
+
+        // Queue for compilation via Redis Stream
+        let mut conn = state.redis.get().await?;
+
+        let mut xadd = redis::cmd("XADD");
+        xadd.arg(priority.stream_name(&state.config.compile_stream))
+            .arg("*")
+            .arg("submission_id")
+            .arg(submission_id.to_string())
+            .arg("type")
+            .arg("source")
+            .arg("language")
+            .arg(&lang_str);
+
+        if let Some(tenant_id) = tenant_id {
+            xadd.arg("tenant").arg(tenant_id.to_string());
+        }
+        tag_stream_entry(&mut xadd);
+
+        let stream_id: String = xadd.query_async(&mut *conn).await?;
+
+        tracing::info!(
+            submission_id = %submission_id,
+            stream_id = %stream_id,
+            contest_id = ?payload.contest_id,
+            "Submission queued for compilation"
+        );
+
+        Ok(Json(SubmissionResponse {
+            id: submission_id,
+            contest_id: payload.contest_id,
+            problem_id: payload.problem_id,
+            submission_type: "source".to_string(),
+            language: Some(lang_str),
+            status: "pending".to_string(),
+            submitted_at,
+            is_practice,
+            is_test,
+            message: "Submission queued for compilation".to_string(),
+        }))
+    }
+    .await;
+    if queued.is_err() {
+        if let Some(claim) = cooldown {
+            claim.release(&state).await;
+        }
+    }
+    queued
+}
+
+/// Judge queue priority for a new submission.
+///
+/// Live contest submissions are judged ahead of practice, test and
+/// standalone work.
+fn queue_priority(live: bool) -> JudgePriority {
+    if live {
+        JudgePriority::High
+    } else {
+        JudgePriority::Normal
+    }
+}
+
+/// Start the per-problem resubmission cooldown, or fail if it is running.
+///
+/// `SET NX EX` claims the slot atomically, so concurrent requests cannot
+/// both get through. Call after all other validation so rejected requests
+/// do not start a cooldown, and release the claim if the submission is not
+/// created after all. Contests without a cooldown return `None`.
+async fn claim_submission_cooldown(
+    state: &AppState,
+    contest_id: Uuid,
+    user_id: Uuid,
+    problem_id: Uuid,
+) -> ApiResult<Option<CooldownClaim>> {
+    let cooldown_secs: i32 =
+        sqlx::query_scalar("SELECT submission_cooldown_secs FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_optional(&state.db)
+            .await?
+            .unwrap_or(0);
+
+    if cooldown_secs <= 0 {
+        return Ok(None);
+    }
 
-    // Queue for compilation via Redis Stream
+    let key = format!("submit_cooldown:{}:{}:{}", contest_id, user_id, problem_id);
     let mut conn = state.redis.get().await?;
 
-    let stream_id: String = redis::cmd("XADD")
-        .arg("compile_queue")
-        .arg("*")
-        .arg("submission_id")
-        .arg(submission_id.to_string())
-        .arg("type")
-        .arg("source")
-        .arg("language")
-        .arg(&lang_str)
+    let claimed: Option<String> = redis::cmd("SET")
+        .arg(&key)
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(cooldown_secs)
         .query_async(&mut *conn)
         .await?;
 
-    tracing::info!(
-        submission_id = %submission_id,
-        stream_id = %stream_id,
-        contest_id = ?payload.contest_id,
-        "Submission queued for compilation"
-    );
-
-    Ok(Json(SubmissionResponse {
-        id: submission_id,
-        contest_id: payload.contest_id,
-        problem_id: payload.problem_id,
-        submission_type: "source".to_string(),
-        language: Some(lang_str),
-        status: "pending".to_string(),
-        submitted_at,
-        message: "Submission queued for compilation".to_string(),
-    }))
+    if claimed.is_some() {
+        return Ok(Some(CooldownClaim { key }));
+    }
+
+    let ttl: i64 = redis::cmd("TTL").arg(&key).query_async(&mut *conn).await?;
+    Err(ApiError::SubmissionCooldown(ttl.max(1) as u64))
+}
+
+/// A claimed resubmission cooldown
+struct CooldownClaim {
+    key: String,
+}
+
+impl CooldownClaim {
+    /// Give the cooldown back (best-effort; it expires on its own otherwise)
+    async fn release(self, state: &AppState) {
+        let release = async {
+            let mut conn = state.redis.get().await?;
+            redis::cmd("DEL")
+                .arg(&self.key)
+                .query_async::<i64>(&mut *conn)
+                .await?;
+            Ok::<_, ApiError>(())
+        };
+
+        if let Err(e) = release.await {
+            tracing::warn!("Failed to release submission cooldown {}: {}", self.key, e);
+        }
+    }
 }
 
 #[derive(Debug, sqlx::FromRow)]
-struct ContestCheckRow {
-    #[allow(dead_code)]
-    id: Uuid,
-    status: String,
-    starts_at: chrono::DateTime<Utc>,
-    ends_at: chrono::DateTime<Utc>,
-    allowed_languages: Option<Vec<String>>,
+struct LanguageRestrictionRow {
+    contest_languages: Option<Vec<String>>,
+    problem_languages: Option<Vec<String>>,
+}
+
+/// Reject languages outside the effective allow-list for a problem.
+///
+/// The allow-list is the intersection of the contest's `allowed_languages`
+/// and the problem's, where a `contest_problems.allowed_languages` override
+/// replaces the problem-level list within that contest. `NULL` lists do not
+/// restrict anything.
+pub(crate) async fn ensure_language_allowed(
+    db: &sqlx::PgPool,
+    contest_id: Option<Uuid>,
+    problem_id: Uuid,
+    language: &str,
+) -> ApiResult<()> {
+    let lists: Option<LanguageRestrictionRow> = match contest_id {
+        Some(contest_id) => {
+            sqlx::query_as(
+                r#"
+                SELECT c.allowed_languages AS contest_languages,
+                       COALESCE(cp.allowed_languages, p.allowed_languages) AS problem_languages
+                FROM contest_problems cp
+                JOIN contests c ON c.id = cp.contest_id
+                JOIN problems p ON p.id = cp.problem_id
+                WHERE cp.contest_id = $1 AND cp.problem_id = $2
+                "#,
+            )
+            .bind(contest_id)
+            .bind(problem_id)
+            .fetch_optional(db)
+            .await?
+        }
+        None => {
+            sqlx::query_as(
+                r#"
+                SELECT NULL::TEXT[] AS contest_languages, allowed_languages AS problem_languages
+                FROM problems WHERE id = $1
+                "#,
+            )
+            .bind(problem_id)
+            .fetch_optional(db)
+            .await?
+        }
+    };
+
+    let Some(lists) = lists else {
+        return Ok(());
+    };
+
+    let allowed = match (lists.contest_languages, lists.problem_languages) {
+        (None, None) => return Ok(()),
+        (Some(list), None) | (None, Some(list)) => list,
+        (Some(contest), Some(problem)) => contest
+            .into_iter()
+            .filter(|lang| problem.contains(lang))
+            .collect(),
+    };
+
+    if allowed.iter().any(|lang| lang == language) {
+        return Ok(());
+    }
+
+    let scope = if contest_id.is_some() {
+        "this problem in this contest"
+    } else {
+        "this problem"
+    };
+
+    Err(ApiError::Validation(if allowed.is_empty() {
+        format!("No languages are allowed for {}", scope)
+    } else {
+        format!(
+            "Language '{}' is not allowed for {}. Allowed languages: {}",
+            language,
+            scope,
+            allowed.join(", ")
+        )
+    }))
+}
+
+/// The problem must be in the contest and, for participants, past its
+/// `visible_from`. Unreleased problems look missing so their existence
+/// doesn't leak; owners, collaborators and moderators can always submit.
+pub(crate) async fn require_problem_released(
+    state: &AppState,
+    user: &AuthUser,
+    contest_id: Uuid,
+    problem_id: Uuid,
+) -> ApiResult<()> {
+    let row: Option<(bool, bool)> = sqlx::query_as(
+        r#"
+        SELECT
+            cp.visible_from IS NULL OR cp.visible_from <= NOW(),
+            c.owner_id = $3 OR EXISTS(
+                SELECT 1 FROM contest_collaborators cc
+                WHERE cc.contest_id = c.id AND cc.user_id = $3
+            )
+        FROM contest_problems cp
+        JOIN contests c ON c.id = cp.contest_id
+        WHERE cp.problem_id = $1 AND cp.contest_id = $2
+        "#,
+    )
+    .bind(problem_id)
+    .bind(contest_id)
+    .bind(user.id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match row {
+        Some((released, is_staff))
+            if released || is_staff || user.can(Permission::ModerateContests) =>
+        {
+            Ok(())
+        }
+        _ => Err(ApiError::NotFound(
+            "Problem not found in this contest".to_string(),
+        )),
+    }
+}
+
+/// Storage root of a contest (`None` for the shared volume) and its
+/// submissions directory under it
+async fn contest_submissions_dir(
+    state: &AppState,
+    contest_id: Uuid,
+) -> ApiResult<(Option<String>, PathBuf)> {
+    let storage_root: Option<String> =
+        sqlx::query_scalar("SELECT storage_root FROM contests WHERE id = $1")
+            .bind(contest_id)
+            .fetch_optional(&state.db)
+            .await?
+            .flatten();
+
+    let root = state
+        .config
+        .storage_roots
+        .resolve(storage_root.as_deref())
+        .ok_or_else(|| {
+            ApiError::Internal(format!(
+                "Storage root {:?} of contest {} is not configured",
+                storage_root, contest_id
+            ))
+        })?;
+    let dir = root.join(SUBMISSIONS_DIR);
+    Ok((storage_root, dir))
 }
 
 /// POST /api/v1/submissions/upload - Submit ZIP file (algorithmic benchmark)
@@ -200,54 +439,16 @@ pub async fn create_zip_submission(
 ) -> ApiResult<Json<SubmissionResponse>> {
     let user_id = user.id;
 
+    let mut is_practice = false;
+    let mut is_test = false;
     if let Some(contest_id) = params.contest_id {
         // ── Contest submission ──────────────────────────────────────────
-        let contest = sqlx::query_as::<_, ContestCheckRowSimple>(
-            r#"
-            SELECT id, status, starts_at, ends_at
-            FROM contests WHERE id = $1
-            "#,
-        )
-        .bind(contest_id)
-        .fetch_optional(&state.db)
-        .await?
-        .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
-
-        let now = Utc::now();
-        if contest.status != "active" {
-            return Err(ApiError::Validation("Contest is not active".to_string()));
-        }
-        if now < contest.starts_at {
-            return Err(ApiError::Validation(
-                "Contest has not started yet".to_string(),
-            ));
-        }
-        if now > contest.ends_at {
-            return Err(ApiError::Validation("Contest has ended".to_string()));
-        }
-
         let ctx = build_contest_context(&state, &user, contest_id);
-        require_can_submit(&ctx).await?;
-
-        let problem_in_contest: Option<bool> = sqlx::query_scalar::<_, Option<bool>>(
-            r#"
-            SELECT EXISTS(
-                SELECT 1 FROM problems p
-                JOIN contest_problems cp ON cp.problem_id = p.id
-                WHERE p.id = $1 AND cp.contest_id = $2
-            )
-            "#,
-        )
-        .bind(params.problem_id)
-        .bind(contest_id)
-        .fetch_one(&state.db)
-        .await?;
+        let kind = require_can_submit_to_contest(&ctx).await?;
+        is_practice = kind == ContestSubmissionKind::Practice;
+        is_test = kind == ContestSubmissionKind::Test;
 
-        if !problem_in_contest.unwrap_or(false) {
-            return Err(ApiError::NotFound(
-                "Problem not found in this contest".to_string(),
-            ));
-        }
+        require_problem_released(&state, &user, contest_id, params.problem_id).await?;
     } else {
         // ── Standalone submission ──────────────────────────────────────
         let ctx = build_auth_context(&state, &user);
@@ -265,6 +466,11 @@ pub async fn create_zip_submission(
         }
     }
 
+    // Turn practice away before reading the upload while the judge is behind
+    let live = params.contest_id.is_some() && !is_practice && !is_test;
+    let priority = queue_priority(live);
+    admit_submission(&state, priority).await?;
+
     // Get upload size limit (contest-specific or default)
     let max_size = if let Some(contest_id) = params.contest_id {
         get_contest_upload_limit(&state.db, contest_id).await?
@@ -272,8 +478,8 @@ pub async fn create_zip_submission(
         DEFAULT_MAX_SUBMISSION_SIZE
     };
 
-    // Process multipart form with streaming size validation
-    let mut zip_data: Option<Vec<u8>> = None;
+    // Stream the file to disk, checking the size limit as it arrives
+    let mut upload: Option<SpooledUpload> = None;
 
     while let Some(field) = multipart
         .next_field()
@@ -282,128 +488,167 @@ pub async fn create_zip_submission(
     {
         let name = field.name().unwrap_or_default().to_string();
         if name == "file" {
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| ApiError::Validation(format!("Failed to read file: {}", e)))?;
-
-            if data.len() > max_size {
-                return Err(ApiError::Validation(format!(
-                    "File size ({:.2}MB) exceeds limit ({:.2}MB)",
-                    data.len() as f64 / 1024.0 / 1024.0,
-                    max_size as f64 / 1024.0 / 1024.0
-                )));
-            }
-
-            zip_data = Some(data.to_vec());
+            upload = Some(SpooledUpload::from_field(field, max_size as u64).await?);
         }
     }
 
-    let zip_data = zip_data.ok_or_else(|| ApiError::Validation("No file uploaded".to_string()))?;
+    let upload = upload.ok_or_else(|| ApiError::Validation("No file uploaded".to_string()))?;
 
-    validate_zip_structure(&zip_data)?;
+    // Only the cheap checks happen here; Sisyphus validates the archive's
+    // contents as it extracts them
+    if !upload.starts_with(ZIP_MAGIC) {
+        return Err(ApiError::Validation("Invalid ZIP file".to_string()));
+    }
 
-    let submission_id = Uuid::new_v4();
-    let submitted_at = Utc::now();
-    let file_size = zip_data.len() as i64;
-    let lang_str = params.language.as_ref().map(|l| l.to_string());
+    // Standalone submissions to a tenant's problem are for its members only
+    let tenant_id = submission_tenant(&state.db, params.contest_id, params.problem_id).await?;
+    if let (Some(tenant_id), None) = (tenant_id, params.contest_id) {
+        require_tenant_member(&state.db, tenant_id, &user).await?;
+    }
 
-    // Save ZIP to storage – use "standalone" bucket when no contest
-    let storage_path = if let Some(contest_id) = params.contest_id {
-        format!(
-            "/mnt/data/submissions/{}/{}/{}.zip",
-            contest_id, user_id, submission_id
-        )
-    } else {
-        format!(
-            "/mnt/data/submissions/standalone/{}/{}.zip",
-            user_id, submission_id
-        )
+    let cooldown = match params.contest_id.filter(|_| live) {
+        Some(contest_id) => {
+            claim_submission_cooldown(&state, contest_id, user_id, params.problem_id).await?
+        }
+        None => None,
     };
 
-    if let Some(parent) = std::path::Path::new(&storage_path).parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .map_err(|e| ApiError::Internal(format!("Failed to create directory: {}", e)))?;
-    }
+    // Give the cooldown back if the submission is not created after all
+    let queued: ApiResult<Json<SubmissionResponse>> = async {
+        if let Some(tenant_id) = tenant_id {
+            enforce_tenant_limits(&state, tenant_id, upload.size() as i64).await?;
+        }
 
-    tokio::fs::write(&storage_path, &zip_data)
-        .await
-        .map_err(|e| ApiError::Internal(format!("Failed to save submission: {}", e)))?;
+        let submission_id = Uuid::new_v4();
+        let submitted_at = Utc::now();
+        let file_size = upload.size() as i64;
+        let lang_str = params.language.clone();
+        if let Some(ref lang) = lang_str {
+            ensure_runtime_enabled(&state.db, lang).await?;
+            ensure_language_allowed(&state.db, params.contest_id, params.problem_id, lang).await?;
+        }
 
-    sqlx::query(
-        r#"
-        INSERT INTO submissions (
-            id, contest_id, problem_id, user_id,
-            submission_type, language, file_path, file_size_bytes,
-            status, submitted_at
+        let target = ScanTarget {
+            kind: "submission",
+            id: submission_id,
+            uploaded_by: user_id,
+        };
+        state.scanner.check(&state.db, target, &upload).await?;
+
+        // Save ZIP to storage – under the contest's storage root if it has one,
+        // and in the "standalone" directory when no contest
+        let (storage_root, storage_path) = match params.contest_id {
+            Some(contest_id) => {
+                let (root, dir) = contest_submissions_dir(&state, contest_id).await?;
+                let path = dir.join(format!("{}/{}/{}.zip", contest_id, user_id, submission_id));
+                (root, path)
+            }
+            None => (
+                None,
+                state
+                    .config
+                    .storage_roots
+                    .base()
+                    .join(SUBMISSIONS_DIR)
+                    .join(format!("standalone/{}/{}.zip", user_id, submission_id)),
+            ),
+        };
+        let storage_path = storage_path.to_string_lossy().into_owned();
+
+        if let Some(parent) = std::path::Path::new(&storage_path).parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| ApiError::Internal(format!("Failed to create directory: {}", e)))?;
+        }
+
+        upload.persist(&storage_path).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO submissions (
+                id, contest_id, problem_id, user_id,
+                submission_type, language, file_path, file_size_bytes,
+                status, submitted_at, organization_id, is_practice, is_test, storage_root
+            )
+            VALUES ($1, $2, $3, $4, 'zip', $5, $6, $7, 'pending', $8, $9, $10, $11, $12)
+            "#,
         )
-        VALUES ($1, $2, $3, $4, 'zip', $5, $6, $7, 'pending', $8)
-        "#,
-    )
-    .bind(submission_id)
-    .bind(params.contest_id) // NULL for standalone
-    .bind(params.problem_id)
-    .bind(user_id)
-    .bind(&lang_str)
-    .bind(&storage_path)
-    .bind(file_size)
-    .bind(submitted_at)
-    .execute(&state.db)
-    .await?;
+        .bind(submission_id)
+        .bind(params.contest_id) // NULL for standalone
+        .bind(params.problem_id)
+        .bind(user_id)
+        .bind(&lang_str)
+        .bind(&storage_path)
+        .bind(file_size)
+        .bind(submitted_at)
+        .bind(tenant_id)
+        .bind(is_practice)
+        .bind(is_test)
+        .bind(&storage_root)
+        .execute(&state.db)
+        .await?;
 
-    let mut conn = state.redis.get().await?;
+        let mut conn = state.redis.get().await?;
 
-    let mut xadd = redis::cmd("XADD");
-    xadd.arg("compile_queue")
-        .arg("*")
-        .arg("submission_id")
-        .arg(submission_id.to_string())
-        .arg("type")
-        .arg("zip")
-        .arg("file_path")
-        .arg(&storage_path);
-
-    if let Some(ref lang) = lang_str {
-        xadd.arg("language").arg(lang);
-    }
+        let mut xadd = redis::cmd("XADD");
+        xadd.arg(priority.stream_name(&state.config.compile_stream))
+            .arg("*")
+            .arg("submission_id")
+            .arg(submission_id.to_string())
+            .arg("type")
+            .arg("zip")
+            .arg("file_path")
+            .arg(&storage_path);
 
-    let stream_id: String = xadd.query_async(&mut *conn).await?;
-
-    tracing::info!(
-        submission_id = %submission_id,
-        stream_id = %stream_id,
-        contest_id = ?params.contest_id,
-        language = ?lang_str,
-        "ZIP submission queued for compilation"
-    );
-
-    Ok(Json(SubmissionResponse {
-        id: submission_id,
-        contest_id: params.contest_id,
-        problem_id: params.problem_id,
-        submission_type: "zip".to_string(),
-        language: lang_str,
-        status: "pending".to_string(),
-        submitted_at,
-        message: "Submission queued for compilation".to_string(),
-    }))
-}
+        if let Some(ref lang) = lang_str {
+            xadd.arg("language").arg(lang);
+        }
 
-#[derive(Debug, sqlx::FromRow)]
-struct ContestCheckRowSimple {
-    #[allow(dead_code)]
-    id: Uuid,
-    status: String,
-    starts_at: chrono::DateTime<Utc>,
-    ends_at: chrono::DateTime<Utc>,
+        if let Some(tenant_id) = tenant_id {
+            xadd.arg("tenant").arg(tenant_id.to_string());
+        }
+        tag_stream_entry(&mut xadd);
+
+        let stream_id: String = xadd.query_async(&mut *conn).await?;
+
+        tracing::info!(
+            submission_id = %submission_id,
+            stream_id = %stream_id,
+            contest_id = ?params.contest_id,
+            language = ?lang_str,
+            "ZIP submission queued for compilation"
+        );
+
+        Ok(Json(SubmissionResponse {
+            id: submission_id,
+            contest_id: params.contest_id,
+            problem_id: params.problem_id,
+            submission_type: "zip".to_string(),
+            language: lang_str,
+            status: "pending".to_string(),
+            submitted_at,
+            is_practice,
+            is_test,
+            message: "Submission queued for compilation".to_string(),
+        }))
+    }
+    .await;
+    if queued.is_err() {
+        if let Some(claim) = cooldown {
+            claim.release(&state).await;
+        }
+    }
+    queued
 }
 
+/// Local file header signature every non-empty ZIP starts with
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
 /// Default submission size limit in bytes (10MB)
 const DEFAULT_MAX_SUBMISSION_SIZE: usize = 10 * 1024 * 1024;
 
 /// Maximum allowed submission size in bytes (100MB)
-const MAX_ALLOWED_SUBMISSION_SIZE: usize = 100 * 1024 * 1024;
+pub(crate) const MAX_ALLOWED_SUBMISSION_SIZE: usize = 100 * 1024 * 1024;
 
 /// Get contest-specific upload limit or return default
 async fn get_contest_upload_limit(db: &sqlx::PgPool, contest_id: Uuid) -> Result<usize, ApiError> {
@@ -419,149 +664,85 @@ async fn get_contest_upload_limit(db: &sqlx::PgPool, contest_id: Uuid) -> Result
         .unwrap_or(DEFAULT_MAX_SUBMISSION_SIZE))
 }
 
-/// Validate ZIP structure with security checks
-/// - Must contain compile.sh and run.sh
-/// - No symlinks pointing outside archive
-/// - No absolute paths
-/// - Total uncompressed size < 5x compressed size (zip bomb protection)
-fn validate_zip_structure(data: &[u8]) -> Result<(), ApiError> {
-    use std::io::Cursor;
-    use zip::ZipArchive;
-
-    let compressed_size = data.len();
-    let reader = Cursor::new(data);
-    let mut archive = ZipArchive::new(reader)
-        .map_err(|e| ApiError::Validation(format!("Invalid ZIP file: {}", e)))?;
-
-    let mut has_compile = false;
-    let mut has_run = false;
-    let mut total_uncompressed: u64 = 0;
-    let max_uncompressed = (compressed_size as u64) * 5; // Zip bomb protection
-
-    for i in 0..archive.len() {
-        let file = archive
-            .by_index(i)
-            .map_err(|e| ApiError::Validation(format!("Failed to read ZIP entry: {}", e)))?;
-
-        let name = file.name();
-
-        // Security: Check for path traversal
-        if name.contains("..") {
-            return Err(ApiError::Validation(
-                "ZIP contains path traversal (..): rejected for security".to_string(),
-            ));
-        }
-
-        // Security: Check for absolute paths
-        if name.starts_with('/') {
-            return Err(ApiError::Validation(
-                "ZIP contains absolute path: rejected for security".to_string(),
-            ));
-        }
-
-        // Security: Check for symlinks
-        if file.is_symlink() {
-            return Err(ApiError::Validation(
-                "ZIP contains symlinks: rejected for security".to_string(),
-            ));
-        }
+/// GET /api/v1/submissions - List submissions
+pub async fn list_submissions(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(params): Query<ListSubmissionsQuery>,
+) -> ApiResult<Json<SubmissionListResponse>> {
+    let filter = SubmissionFilter::default()
+        .contest(params.contest_id)
+        .problem(params.problem_id)
+        .user(params.user_id)
+        .status(params.status.clone())
+        .language(params.language.clone())
+        .practice(params.practice)
+        .test(params.test)
+        .visible_to(test_viewer(&user));
+
+    list_filtered_submissions(&state, &filter, &params).await
+}
 
-        // Track uncompressed size for zip bomb detection
-        total_uncompressed += file.size();
-        if total_uncompressed > max_uncompressed {
-            return Err(ApiError::Validation(
-                "ZIP uncompressed size exceeds limit (potential zip bomb)".to_string(),
-            ));
-        }
+/// GET /api/v2/submissions - List submissions, paginated by cursor
+pub async fn list_submissions_v2(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Query(page): Query<CursorQuery>,
+    Query(params): Query<SubmissionFeedQuery>,
+) -> ApiResult<Json<CursorPage<SubmissionSummary>>> {
+    let filter = SubmissionFilter::default()
+        .contest(params.contest_id)
+        .problem(params.problem_id)
+        .user(params.user_id)
+        .status(params.status)
+        .language(params.language)
+        .practice(params.practice)
+        .test(params.test)
+        .visible_to(test_viewer(&user));
+
+    submission_feed(&state, filter, &page).await
+}
 
-        // Check for required files
-        let normalized = name.trim_start_matches("./");
-        if normalized == "compile.sh" {
-            has_compile = true;
-        }
-        if normalized == "run.sh" {
-            has_run = true;
-        }
-    }
+/// Viewer whose test submissions a list may show; moderators see them all.
+fn test_viewer(user: &AuthUser) -> Option<Uuid> {
+    (!user.can(Permission::ModerateContests)).then_some(user.id)
+}
 
-    if !has_compile {
-        return Err(ApiError::Validation(
-            "ZIP must contain compile.sh".to_string(),
-        ));
+/// Test submissions (`is_test`) are visible to their contest's team only;
+/// to anyone else they look missing.
+async fn require_test_submission_visible(
+    state: &AppState,
+    user: &AuthUser,
+    contest_id: Option<Uuid>,
+    is_test: bool,
+) -> ApiResult<()> {
+    if !is_test {
+        return Ok(());
     }
-    if !has_run {
-        return Err(ApiError::Validation("ZIP must contain run.sh".to_string()));
+    if let Some(contest_id) = contest_id {
+        let ctx = build_contest_context(state, user, contest_id);
+        if policies::ContestStaff::rule().is_satisfied_by(&ctx).await {
+            return Ok(());
+        }
     }
-
-    Ok(())
+    Err(ApiError::NotFound("Submission not found".to_string()))
 }
 
-/// GET /api/v1/submissions - List submissions
-pub async fn list_submissions(
-    State(state): State<AppState>,
-    Extension(_user): Extension<AuthUser>,
-    Query(params): Query<ListSubmissionsQuery>,
+/// Page of submissions matching `filter`, paginated by `params`
+async fn list_filtered_submissions(
+    state: &AppState,
+    filter: &SubmissionFilter,
+    params: &ListSubmissionsQuery,
 ) -> ApiResult<Json<SubmissionListResponse>> {
     let offset = ((params.page.max(1) - 1) * params.per_page) as i64;
     let limit = params.per_page.min(100) as i64;
 
-    let submissions = sqlx::query_as::<_, SubmissionRow>(
-        r#"
-        SELECT 
-            s.id, s.contest_id, s.problem_id, s.user_id,
-            s.language, s.status, s.score,
-            s.max_time_ms, s.max_memory_kb, s.submitted_at,
-            u.username, u.display_name,
-            p.title as problem_title, cp.problem_code,
-            c.title as contest_title
-        FROM submissions s
-        JOIN users u ON u.id = s.user_id
-        JOIN problems p ON p.id = s.problem_id
-        LEFT JOIN contests c ON c.id = s.contest_id
-        LEFT JOIN contest_problems cp ON cp.contest_id = s.contest_id AND cp.problem_id = s.problem_id
-        ORDER BY s.submitted_at DESC
-        LIMIT $1 OFFSET $2
-        "#,
-    )
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.db)
-    .await?;
-
-    let total: Option<i64> =
-        sqlx::query_scalar::<_, Option<i64>>("SELECT COUNT(*) FROM submissions")
-            .fetch_one(&state.db)
-            .await?;
-    let total = total.unwrap_or(0);
+    let rows = submission_repo::list(state.read_db(), filter, limit, offset).await?;
+    let total = submission_repo::count(state.read_db(), filter).await?;
 
     let total_pages = ((total as f64) / (params.per_page as f64)).ceil() as u32;
 
-    let submissions: Vec<SubmissionSummary> = submissions
-        .into_iter()
-        .map(|row| SubmissionSummary {
-            id: row.id,
-            user: UserInfo {
-                id: row.user_id,
-                username: row.username,
-                display_name: row.display_name,
-            },
-            problem: ProblemInfo {
-                id: row.problem_id,
-                title: row.problem_title,
-                problem_code: row.problem_code,
-            },
-            contest: row
-                .contest_id
-                .zip(row.contest_title)
-                .map(|(id, title)| ContestInfo { id, title }),
-            language: row.language,
-            status: row.status,
-            score: row.score,
-            max_time_ms: row.max_time_ms,
-            max_memory_kb: row.max_memory_kb,
-            submitted_at: row.submitted_at,
-        })
-        .collect();
+    let submissions: Vec<SubmissionSummary> = rows.into_iter().map(row_to_summary).collect();
 
     Ok(Json(SubmissionListResponse {
         submissions,
@@ -574,23 +755,49 @@ pub async fn list_submissions(
     }))
 }
 
-#[derive(Debug, sqlx::FromRow)]
-struct SubmissionRow {
-    id: Uuid,
-    contest_id: Option<Uuid>,
-    problem_id: Uuid,
-    user_id: Uuid,
-    language: Option<String>,
-    status: String,
-    score: Option<i32>,
-    max_time_ms: Option<i32>,
-    max_memory_kb: Option<i32>,
-    submitted_at: chrono::DateTime<Utc>,
-    username: String,
-    display_name: Option<String>,
-    problem_title: String,
-    problem_code: Option<String>,
-    contest_title: Option<String>,
+/// Page of submissions matching `filter`, after `page`'s cursor
+async fn submission_feed(
+    state: &AppState,
+    filter: SubmissionFilter,
+    page: &CursorQuery,
+) -> ApiResult<Json<CursorPage<SubmissionSummary>>> {
+    let filter = filter.after(page.after()?);
+    let rows = submission_repo::list(state.read_db(), &filter, page.fetch_limit(), 0).await?;
+
+    Ok(Json(CursorPage::from_rows(
+        rows,
+        page,
+        |row| Cursor::new(row.submitted_at, row.id),
+        row_to_summary,
+    )))
+}
+
+fn row_to_summary(row: SubmissionListRow) -> SubmissionSummary {
+    SubmissionSummary {
+        id: row.id,
+        user: UserInfo {
+            id: row.user_id,
+            username: row.username,
+            display_name: row.display_name,
+        },
+        problem: ProblemInfo {
+            id: row.problem_id,
+            title: row.problem_title,
+            problem_code: row.problem_code,
+        },
+        contest: row
+            .contest_id
+            .zip(row.contest_title)
+            .map(|(id, title)| ContestInfo { id, title }),
+        language: row.language,
+        status: row.status,
+        score: row.score,
+        max_time_ms: row.max_time_ms,
+        max_memory_kb: row.max_memory_kb,
+        is_practice: row.is_practice,
+        is_test: row.is_test,
+        submitted_at: row.submitted_at,
+    }
 }
 
 /// GET /api/v1/submissions/{id} - Get submission details
@@ -603,10 +810,10 @@ pub async fn get_submission(
         r#"
         SELECT 
             s.id, s.contest_id, s.problem_id, s.user_id,
-            s.submission_type, s.language, s.status, s.score,
+            s.submission_type, s.language, s.runtime_version, s.status, s.score,
             s.total_test_cases, s.passed_test_cases,
-            s.max_time_ms, s.max_memory_kb, s.compilation_log,
-            s.submitted_at, s.compiled_at, s.judged_at,
+            s.max_time_ms, s.max_memory_kb, s.total_instructions, s.compilation_log,
+            s.is_practice, s.is_test, s.submitted_at, s.compiled_at, s.judged_at,
             u.username, u.display_name,
             p.title as problem_title, cp.problem_code,
             c.title as contest_title
@@ -622,8 +829,11 @@ pub async fn get_submission(
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| ApiError::NotFound("Submission not found".to_string()))?;
+    require_test_submission_visible(&state, &user, row.contest_id, row.is_test).await?;
 
-    let is_owner = row.user_id == user.id || user.role == "admin";
+    let is_owner = row.user_id == user.id || user.can(Permission::ViewPrivateContent);
+    let can_view_details =
+        is_owner || can_view_submission_details(&state, &user, row.id, row.contest_id).await;
 
     Ok(Json(SubmissionDetailResponse {
         id: row.id,
@@ -643,13 +853,21 @@ pub async fn get_submission(
             .map(|(id, title)| ContestInfo { id, title }),
         submission_type: row.submission_type,
         language: row.language,
+        runtime_version: row.runtime_version,
         status: row.status,
         score: row.score,
         total_test_cases: row.total_test_cases,
         passed_test_cases: row.passed_test_cases,
         max_time_ms: row.max_time_ms,
         max_memory_kb: row.max_memory_kb,
-        compilation_log: if is_owner { row.compilation_log } else { None },
+        total_instructions: row.total_instructions,
+        compilation_log: if can_view_details {
+            row.compilation_log
+        } else {
+            None
+        },
+        is_practice: row.is_practice,
+        is_test: row.is_test,
         submitted_at: row.submitted_at,
         compiled_at: row.compiled_at,
         judged_at: row.judged_at,
@@ -657,6 +875,93 @@ pub async fn get_submission(
     }))
 }
 
+/// POST /api/v1/submissions/status - Get the status of many submissions
+///
+/// Lets list pages follow up to 100 submissions being judged in one request
+/// instead of polling each one.
+pub async fn get_submission_statuses(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<SubmissionStatusRequest>,
+) -> ApiResult<Json<SubmissionStatusResponse>> {
+    let mut ids = payload.ids;
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+
+    let rows = sqlx::query_as::<_, BatchStatusRow>(
+        r#"
+        SELECT id, contest_id, is_test, status, score,
+               passed_test_cases, total_test_cases,
+               max_time_ms, max_memory_kb, judged_at
+        FROM submissions
+        WHERE id = ANY($1)
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(&state.db)
+    .await?;
+
+    // Test submissions the caller may not see are reported as missing, like
+    // on the single-submission endpoints; staff checks are made per contest
+    let mut staff_of: HashMap<Option<Uuid>, bool> = HashMap::new();
+    let mut by_id: HashMap<Uuid, BatchStatusRow> = HashMap::with_capacity(rows.len());
+    for row in rows {
+        if row.is_test {
+            let visible = match staff_of.get(&row.contest_id) {
+                Some(&visible) => visible,
+                None => {
+                    let visible =
+                        require_test_submission_visible(&state, &user, row.contest_id, true)
+                            .await
+                            .is_ok();
+                    staff_of.insert(row.contest_id, visible);
+                    visible
+                }
+            };
+            if !visible {
+                continue;
+            }
+        }
+        by_id.insert(row.id, row);
+    }
+    let mut submissions = Vec::with_capacity(by_id.len());
+    let mut not_found = Vec::new();
+    for id in ids {
+        match by_id.remove(&id) {
+            Some(row) => submissions.push(SubmissionStatus {
+                id: row.id,
+                status: row.status,
+                score: row.score,
+                passed_test_cases: row.passed_test_cases,
+                total_test_cases: row.total_test_cases,
+                max_time_ms: row.max_time_ms,
+                max_memory_kb: row.max_memory_kb,
+                judged_at: row.judged_at,
+            }),
+            None => not_found.push(id),
+        }
+    }
+
+    Ok(Json(SubmissionStatusResponse {
+        submissions,
+        not_found,
+    }))
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct BatchStatusRow {
+    id: Uuid,
+    contest_id: Option<Uuid>,
+    is_test: bool,
+    status: String,
+    score: Option<f64>,
+    passed_test_cases: Option<i32>,
+    total_test_cases: Option<i32>,
+    max_time_ms: Option<i32>,
+    max_memory_kb: Option<i32>,
+    judged_at: Option<chrono::DateTime<Utc>>,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 struct SubmissionDetailRow {
     id: Uuid,
@@ -665,13 +970,17 @@ struct SubmissionDetailRow {
     user_id: Uuid,
     submission_type: String,
     language: Option<String>,
+    runtime_version: Option<String>,
     status: String,
-    score: Option<i32>,
+    score: Option<f64>,
     total_test_cases: Option<i32>,
     passed_test_cases: Option<i32>,
     max_time_ms: Option<i32>,
     max_memory_kb: Option<i32>,
+    total_instructions: Option<i64>,
     compilation_log: Option<String>,
+    is_practice: bool,
+    is_test: bool,
     submitted_at: chrono::DateTime<Utc>,
     compiled_at: Option<chrono::DateTime<Utc>>,
     judged_at: Option<chrono::DateTime<Utc>>,
@@ -691,7 +1000,7 @@ pub async fn get_submission_results(
     // First check submission exists and get owner info
     let submission = sqlx::query_as::<_, SubmissionStatusRow>(
         r#"
-        SELECT user_id, status, score, total_test_cases, passed_test_cases
+        SELECT contest_id, is_test, status, score, total_test_cases, passed_test_cases
         FROM submissions WHERE id = $1
         "#,
     )
@@ -699,17 +1008,18 @@ pub async fn get_submission_results(
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| ApiError::NotFound("Submission not found".to_string()))?;
+    require_test_submission_visible(&state, &user, submission.contest_id, submission.is_test)
+        .await?;
 
-    // Only owner, admin, or after contest can see detailed results
-    let is_owner = submission.user_id == user.id || user.role == "admin";
-
-    if !is_owner {
+    // Only the author, admins, and collaborators allowed to view sources
+    if !can_view_submission_details(&state, &user, id, submission.contest_id).await {
         return Err(ApiError::Forbidden);
     }
 
     let results = sqlx::query_as::<_, TestCaseResultRow>(
         r#"
-        SELECT test_case_number, verdict, time_ms, memory_kb, checker_score
+        SELECT test_case_number, verdict, time_ms, memory_kb, checker_score, network_bytes,
+               instructions, time_limit_ms, memory_limit_kb
         FROM submission_results
         WHERE submission_id = $1
         ORDER BY test_case_number
@@ -733,6 +1043,10 @@ pub async fn get_submission_results(
                 time_ms: r.time_ms,
                 memory_kb: r.memory_kb,
                 checker_score: r.checker_score,
+                network_bytes: r.network_bytes,
+                instructions: r.instructions,
+                time_limit_ms: r.time_limit_ms,
+                memory_limit_kb: r.memory_limit_kb,
             })
             .collect(),
     }))
@@ -740,9 +1054,10 @@ pub async fn get_submission_results(
 
 #[derive(Debug, sqlx::FromRow)]
 struct SubmissionStatusRow {
-    user_id: Uuid,
+    contest_id: Option<Uuid>,
+    is_test: bool,
     status: String,
-    score: Option<i32>,
+    score: Option<f64>,
     total_test_cases: Option<i32>,
     passed_test_cases: Option<i32>,
 }
@@ -754,158 +1069,549 @@ struct TestCaseResultRow {
     time_ms: Option<i32>,
     memory_kb: Option<i32>,
     checker_score: Option<f64>,
+    network_bytes: Option<i64>,
+    instructions: Option<i64>,
+    time_limit_ms: Option<i32>,
+    memory_limit_kb: Option<i32>,
+}
+
+/// Whether the user may see a submission's source, results and compile log:
+/// its author, an admin, or (for contest submissions) the contest owner and
+/// collaborators with `can_view_submissions`.
+async fn can_view_submission_details(
+    state: &AppState,
+    user: &AuthUser,
+    submission_id: Uuid,
+    contest_id: Option<Uuid>,
+) -> bool {
+    let mut ctx = build_auth_context(state, user).with_submission(submission_id);
+    if let Some(contest_id) = contest_id {
+        ctx = ctx.with_contest(contest_id);
+    }
+    require_submission_view_access(&ctx).await.is_ok()
 }
 
 /// GET /api/v1/submissions/{id}/source - Get source code
+///
+/// With `?metadata=true` the response also carries highlighting metadata.
+/// A request that accepts `text/plain` but not JSON gets the raw source as
+/// an attachment named after the runtime's source file instead.
 pub async fn get_submission_source(
     State(state): State<AppState>,
     Extension(user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
-) -> ApiResult<Json<SourceCodeResponse>> {
+    Query(params): Query<SourceQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
     let submission = sqlx::query_as::<_, SubmissionSourceRow>(
         r#"
-        SELECT user_id, submission_type, language, source_code
-        FROM submissions WHERE id = $1
+        SELECT s.contest_id, s.is_test, s.submission_type, s.language, s.source_code,
+               r.source_file
+        FROM submissions s
+        LEFT JOIN runtimes r ON r.name = s.language
+        WHERE s.id = $1
         "#,
     )
     .bind(id)
     .fetch_optional(&state.db)
     .await?
     .ok_or_else(|| ApiError::NotFound("Submission not found".to_string()))?;
+    require_test_submission_visible(&state, &user, submission.contest_id, submission.is_test)
+        .await?;
 
-    // Only owner or admin can view source
-    if submission.user_id != user.id && user.role != "admin" {
+    // Only the author, admins, and collaborators allowed to view sources
+    if !can_view_submission_details(&state, &user, id, submission.contest_id).await {
         return Err(ApiError::Forbidden);
     }
 
-    Ok(Json(SourceCodeResponse {
+    let filename = submission
+        .source_file
+        .clone()
+        .unwrap_or_else(|| format!("{}.txt", id));
+
+    let mut response = if wants_plain_text(&headers) {
+        let Some(source_code) = submission.source_code else {
+            return Err(ApiError::Validation(
+                "ZIP submissions have no plain-text source; download the archive instead"
+                    .to_string(),
+            ));
+        };
+        (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/plain; charset=utf-8".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename),
+                ),
+            ],
+            source_code,
+        )
+            .into_response()
+    } else {
+        source_json_response(id, submission, filename, params.metadata)
+    };
+
+    // The representation depends on Accept, so caches must key on it
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("Accept"));
+    Ok(response)
+}
+
+/// The JSON form of a submission's source, with highlighting metadata on request
+fn source_json_response(
+    id: Uuid,
+    submission: SubmissionSourceRow,
+    filename: String,
+    with_metadata: bool,
+) -> Response {
+    let metadata = match (&submission.source_code, with_metadata) {
+        (Some(source_code), true) => Some(SourceMetadata {
+            grammar: submission.source_file.as_deref().and_then(source_grammar),
+            filename,
+            line_count: source_code.lines().count(),
+            byte_size: source_code.len(),
+        }),
+        _ => None,
+    };
+
+    Json(SourceCodeResponse {
         submission_id: id,
         language: submission.language,
         source_code: submission.source_code,
         submission_type: submission.submission_type,
-    }))
+        metadata,
+    })
+    .into_response()
 }
 
 #[derive(Debug, sqlx::FromRow)]
 struct SubmissionSourceRow {
-    user_id: Uuid,
+    contest_id: Option<Uuid>,
+    is_test: bool,
     submission_type: String,
     language: Option<String>,
     source_code: Option<String>,
+    /// Source file name of the submission's runtime, if still registered
+    source_file: Option<String>,
 }
 
-/// GET /api/v1/users/{id}/submissions - Get user's submissions
-pub async fn get_user_submissions(
-    State(state): State<AppState>,
-    Extension(_user): Extension<AuthUser>,
-    Path(user_id): Path<Uuid>,
-    Query(params): Query<ListSubmissionsQuery>,
-) -> ApiResult<Json<SubmissionListResponse>> {
-    let offset = ((params.page.max(1) - 1) * params.per_page) as i64;
-    let limit = params.per_page.min(100) as i64;
+/// Whether the `Accept` header asks for `text/plain` without also accepting
+/// JSON (browsers and clients sending `*/*` keep getting JSON).
+fn wants_plain_text(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let media_types: Vec<&str> = accept
+        .split(',')
+        .map(|part| part.split(';').next().unwrap_or("").trim())
+        .collect();
+    media_types.contains(&"text/plain")
+        && !media_types
+            .iter()
+            .any(|media_type| matches!(*media_type, "application/json" | "application/*" | "*/*"))
+}
+
+/// Highlighter grammar id for a runtime's source file, by its extension.
+///
+/// Ids follow the names common highlighters (highlight.js, Prism, Shiki)
+/// share, so frontends can pass them through unchanged.
+fn source_grammar(source_file: &str) -> Option<String> {
+    let extension = source_file.rsplit_once('.')?.1.to_ascii_lowercase();
+    let grammar = match extension.as_str() {
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "c++" | "hpp" => "cpp",
+        "cs" => "csharp",
+        "go" => "go",
+        "hs" => "haskell",
+        "java" => "java",
+        "js" | "mjs" => "javascript",
+        "kt" | "kts" => "kotlin",
+        "ml" => "ocaml",
+        "pas" => "pascal",
+        "php" => "php",
+        "py" => "python",
+        "rb" => "ruby",
+        "rs" => "rust",
+        "scala" => "scala",
+        "sh" => "bash",
+        "swift" => "swift",
+        "ts" => "typescript",
+        "zig" => "zig",
+        _ => return None,
+    };
+    Some(grammar.to_string())
+}
+
+// =============================================================================
+// Test Data Downloads
+// =============================================================================
+
+/// Largest test input served by the debugging downloads (kept outputs are
+/// already capped by Minos)
+const MAX_TEST_INPUT_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Seconds a client should wait before retrying while an input regenerates
+const REGEN_RETRY_SECS: u64 = 5;
 
-    let submissions = sqlx::query_as::<_, SubmissionRow>(
+/// Header set when a download was cut at the size cap
+const TRUNCATED_HEADER: &str = "X-Content-Truncated";
+
+#[derive(Debug, sqlx::FromRow)]
+struct TestDataRow {
+    problem_id: Uuid,
+    contest_id: Option<Uuid>,
+    verdict: String,
+    generator_seed: Option<String>,
+    generator_version: Option<i32>,
+    current_generator_version: i32,
+    output_path: Option<String>,
+    output_truncated: bool,
+}
+
+/// Load a failing test of a submission the user may download test data of.
+async fn load_failing_test(
+    state: &AppState,
+    user: &AuthUser,
+    submission_id: Uuid,
+    test: i32,
+) -> ApiResult<TestDataRow> {
+    let row = sqlx::query_as::<_, TestDataRow>(
         r#"
-        SELECT 
-            s.id, s.contest_id, s.problem_id, s.user_id,
-            s.language, s.status, s.score,
-            s.max_time_ms, s.max_memory_kb, s.submitted_at,
-            u.username, u.display_name,
-            p.title as problem_title, cp.problem_code,
-            c.title as contest_title
+        SELECT s.problem_id, s.contest_id, r.verdict, r.generator_seed, r.generator_version,
+               p.generator_version AS current_generator_version, r.output_path,
+               r.output_truncated
         FROM submissions s
-        JOIN users u ON u.id = s.user_id
         JOIN problems p ON p.id = s.problem_id
-        LEFT JOIN contests c ON c.id = s.contest_id
-        LEFT JOIN contest_problems cp ON cp.contest_id = s.contest_id AND cp.problem_id = s.problem_id
-        WHERE s.user_id = $1
-        ORDER BY s.submitted_at DESC
-        LIMIT $2 OFFSET $3
+        JOIN submission_results r ON r.submission_id = s.id AND r.test_case_number = $2
+        WHERE s.id = $1
         "#,
     )
-    .bind(user_id)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.db)
-    .await?;
+    .bind(submission_id)
+    .bind(test)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Test result not found".to_string()))?;
 
-    let total: Option<i64> =
-        sqlx::query_scalar::<_, Option<i64>>("SELECT COUNT(*) FROM submissions WHERE user_id = $1")
-            .bind(user_id)
-            .fetch_one(&state.db)
-            .await?;
-    let total = total.unwrap_or(0);
+    let mut ctx = build_auth_context(state, user)
+        .with_submission(submission_id)
+        .with_problem(row.problem_id);
+    if let Some(contest_id) = row.contest_id {
+        ctx = ctx.with_contest(contest_id);
+    }
+    require_test_data_access(&ctx).await?;
 
-    let total_pages = ((total as f64) / (params.per_page as f64)).ceil() as u32;
+    if row.verdict == "accepted" {
+        return Err(ApiError::Validation(
+            "Only failing tests can be downloaded".to_string(),
+        ));
+    }
 
-    let submissions: Vec<SubmissionSummary> = submissions
-        .into_iter()
-        .map(|row| SubmissionSummary {
-            id: row.id,
-            user: UserInfo {
-                id: row.user_id,
-                username: row.username,
-                display_name: row.display_name,
-            },
-            problem: ProblemInfo {
-                id: row.problem_id,
-                title: row.problem_title,
-                problem_code: row.problem_code,
-            },
-            contest: row
-                .contest_id
-                .zip(row.contest_title)
-                .map(|(id, title)| ContestInfo { id, title }),
-            language: row.language,
-            status: row.status,
-            score: row.score,
-            max_time_ms: row.max_time_ms,
-            max_memory_kb: row.max_memory_kb,
-            submitted_at: row.submitted_at,
-        })
-        .collect();
+    Ok(row)
+}
 
-    Ok(Json(SubmissionListResponse {
-        submissions,
-        pagination: Pagination {
-            page: params.page,
-            per_page: params.per_page,
-            total,
-            total_pages,
-        },
-    }))
+/// Plain-text attachment response for a test file.
+fn test_file_response(filename: String, data: Vec<u8>, truncated: bool) -> Response {
+    let mut response = (
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/plain; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from(data),
+    )
+        .into_response();
+    if truncated {
+        response
+            .headers_mut()
+            .insert(TRUNCATED_HEADER, header::HeaderValue::from_static("true"));
+    }
+    response
 }
 
+/// GET /api/v1/submissions/{id}/tests/{test}/input - Download a failing test's input
+///
+/// Inputs evicted from the test case cache are regenerated by Minos with the
+/// recorded generator seed; until then this returns `202 Accepted` with
+/// `Retry-After`. Returns `409 Conflict` once the problem's generator has been
+/// replaced, since the input can no longer be reproduced.
+pub async fn download_test_input(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((id, test)): Path<(Uuid, i32)>,
+) -> ApiResult<Response> {
+    use tokio::io::AsyncReadExt;
+
+    let row = load_failing_test(&state, &user, id, test).await?;
+
+    let (Some(seed), Some(version)) = (row.generator_seed, row.generator_version) else {
+        return Err(ApiError::Conflict(
+            "This test was judged before generator seeds were recorded".to_string(),
+        ));
+    };
+    if version != row.current_generator_version {
+        return Err(ApiError::Conflict(
+            "The problem's generator has changed since this test was judged".to_string(),
+        ));
+    }
+
+    let dir = format!("/mnt/data/testcases/{}", row.problem_id);
+    let filename = format!("input_{:03}.txt", test);
+    let path = format!("{}/{}", dir, filename);
+
+    // Inputs cached for another generator version are stale
+    let cached_version = tokio::fs::read_to_string(format!("{}/{}", dir, GENERATOR_VERSION_MARKER))
+        .await
+        .ok()
+        .and_then(|v| v.trim().parse::<i32>().ok());
+    let file = if cached_version == Some(version) {
+        tokio::fs::File::open(&path).await
+    } else {
+        Err(std::io::ErrorKind::NotFound.into())
+    };
+
+    match file {
+        Ok(file) => {
+            let size = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+            let mut data = Vec::new();
+            file.take(MAX_TEST_INPUT_BYTES)
+                .read_to_end(&mut data)
+                .await
+                .map_err(|e| ApiError::Internal(format!("Failed to read test input: {}", e)))?;
+            Ok(test_file_response(
+                filename,
+                data,
+                size > MAX_TEST_INPUT_BYTES,
+            ))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            // One request per input while a regeneration is in flight
+            let mut conn = state.redis.get().await?;
+            let claimed: Option<String> = redis::cmd("SET")
+                .arg(format!("testcase_regen:{}:{}", row.problem_id, test))
+                .arg(1)
+                .arg("NX")
+                .arg("EX")
+                .arg(REGEN_RETRY_SECS * 12)
+                .query_async(&mut *conn)
+                .await?;
+            if claimed.is_some() {
+                redis::cmd("XADD")
+                    .arg(&state.config.regen_stream)
+                    .arg("*")
+                    .arg("problem_id")
+                    .arg(row.problem_id.to_string())
+                    .arg("test")
+                    .arg(test)
+                    .arg("seed")
+                    .arg(&seed)
+                    .arg("version")
+                    .arg(version)
+                    .query_async::<String>(&mut *conn)
+                    .await?;
+            }
+
+            Ok((
+                StatusCode::ACCEPTED,
+                [(header::RETRY_AFTER, REGEN_RETRY_SECS.to_string())],
+                Json(MessageResponse {
+                    message: "Test input is being regenerated; retry shortly".to_string(),
+                }),
+            )
+                .into_response())
+        }
+        Err(e) => Err(ApiError::Internal(format!(
+            "Failed to read test input: {}",
+            e
+        ))),
+    }
+}
+
+/// GET /api/v1/submissions/{id}/tests/{test}/output - Download the submission's output on a failing test
+pub async fn download_test_output(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path((id, test)): Path<(Uuid, i32)>,
+) -> ApiResult<Response> {
+    let row = load_failing_test(&state, &user, id, test).await?;
+
+    let path = row
+        .output_path
+        .ok_or_else(|| ApiError::NotFound("No output was kept for this test".to_string()))?;
+    let data = tokio::fs::read(&path).await.map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            ApiError::NotFound("No output was kept for this test".to_string())
+        }
+        _ => ApiError::Internal(format!("Failed to read test output: {}", e)),
+    })?;
+
+    Ok(test_file_response(
+        format!("output_{:03}.txt", test),
+        data,
+        row.output_truncated,
+    ))
+}
+
+/// GET /api/v1/users/{id}/submissions - Get user's submissions
+pub async fn get_user_submissions(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(user_id): Path<Uuid>,
+    Query(params): Query<ListSubmissionsQuery>,
+) -> ApiResult<Json<SubmissionListResponse>> {
+    let filter = SubmissionFilter::default()
+        .user(Some(user_id))
+        .contest(params.contest_id)
+        .problem(params.problem_id)
+        .status(params.status.clone())
+        .language(params.language.clone())
+        .practice(params.practice)
+        .test(params.test)
+        .visible_to(test_viewer(&user));
+
+    list_filtered_submissions(&state, &filter, &params).await
+}
+
+/// GET /api/v2/users/{id}/submissions - Get user's submissions, paginated
+/// by cursor
+pub async fn get_user_submissions_v2(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(user_id): Path<Uuid>,
+    Query(page): Query<CursorQuery>,
+    Query(params): Query<SubmissionFeedQuery>,
+) -> ApiResult<Json<CursorPage<SubmissionSummary>>> {
+    let filter = SubmissionFilter::default()
+        .user(Some(user_id))
+        .contest(params.contest_id)
+        .problem(params.problem_id)
+        .status(params.status)
+        .language(params.language)
+        .practice(params.practice)
+        .test(params.test)
+        .visible_to(test_viewer(&user));
+
+    submission_feed(&state, filter, &page).await
+}
+
+/// Most rows returned on each side of the caller by `get_my_standing`
+const MAX_STANDING_NEIGHBORS: u32 = 25;
+
+/// Per-user contest totals (`user_totals`) shared by the standings queries.
+///
+/// Binds `$1` to the contest id. A macro rather than a `const` so it can be
+/// spliced into the query literals with `concat!`.
+macro_rules! standings_ctes {
+    () => {
+        r#"
+        WITH user_problem_scores AS (
+            SELECT 
+                s.user_id,
+                s.problem_id,
+                MAX(CASE WHEN s.status = 'accepted' OR p.partial_scoring THEN s.score ELSE 0 END) as best_score,
+                COUNT(*) as attempts,
+                BOOL_OR(s.status = 'accepted') as solved,
+                MIN(CASE WHEN s.status = 'accepted' THEN s.submitted_at END) as first_solved_at,
+                COUNT(*) FILTER (
+                    WHERE s.status IN ('wrong_answer', 'presentation_error', 'time_limit', 'memory_limit',
+                                       'output_limit', 'idleness_limit', 'runtime_error')
+                      AND NOT EXISTS (
+                          SELECT 1 FROM submissions a
+                          WHERE a.contest_id = s.contest_id AND a.user_id = s.user_id
+                            AND a.problem_id = s.problem_id AND a.status = 'accepted'
+                            AND a.submitted_at <= s.submitted_at AND NOT a.is_test
+                      )
+                ) as rejected_before_ac
+            FROM submissions s
+            JOIN problems p ON p.id = s.problem_id
+            WHERE s.contest_id = $1 AND NOT s.is_practice AND NOT s.is_test
+            GROUP BY s.user_id, s.problem_id
+        ),
+        user_totals AS (
+            SELECT 
+                ups.user_id,
+                SUM(ups.best_score) as total_score,
+                COUNT(CASE WHEN ups.solved THEN 1 END) as problems_solved,
+                -- ICPC penalty: contest minutes to the first AC (pauses
+                -- excluded) plus 20 per rejected attempt before it
+                SUM(CASE WHEN ups.solved THEN
+                    contest_elapsed_seconds($1, ups.first_solved_at) / 60
+                    + 20 * ups.rejected_before_ac
+                END) as total_penalty,
+                SUM(ups.attempts) as total_attempts,
+                MAX(ups.first_solved_at) as last_ac
+            FROM user_problem_scores ups
+            GROUP BY ups.user_id
+        )"#
+    };
+}
+pub(crate) use standings_ctes;
+
+/// Standings order over `user_totals ut`: score, then penalty, then the
+/// earlier last accepted submission. Every ranking of `standings_ctes!`
+/// uses it so ranks agree between endpoints.
+macro_rules! standings_order {
+    () => {
+        "ut.total_score DESC, COALESCE(ut.total_penalty, 0) ASC, ut.last_ac ASC NULLS LAST"
+    };
+}
+pub(crate) use standings_order;
+
 /// GET /api/v1/contests/{id}/leaderboard - Get contest leaderboard
+///
+/// Problems not yet released (`visible_from`) are listed to staff only.
 pub async fn get_contest_leaderboard(
     State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
     Path(contest_id): Path<Uuid>,
     Query(params): Query<LeaderboardQuery>,
-) -> ApiResult<Json<LeaderboardResponse>> {
+) -> ApiResult<Response> {
+    let user = user.as_ref().map(|u| &u.0);
+    let public = standings_visibility(&state, contest_id, user).await?;
+    let is_staff = is_contest_staff(&state, user, contest_id).await;
+
     // Get contest info
     let contest = sqlx::query_as::<_, ContestLeaderboardRow>(
         r#"
-        SELECT id, title, scoring_type, leaderboard_frozen
-        FROM contests WHERE id = $1
+        SELECT c.title, c.scoring_type,
+               (c.freeze_time IS NOT NULL AND NOW() >= c.freeze_time AND NOW() < c.end_time)
+                   as leaderboard_frozen,
+               (SELECT cp.paused_at FROM contest_pauses cp
+                WHERE cp.contest_id = c.id AND cp.resumed_at IS NULL) as paused_at
+        FROM contests c WHERE c.id = $1
         "#,
     )
     .bind(contest_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(state.read_db())
     .await?
     .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
 
-    // Get problems in contest
+    // Get problems in contest; unreleased ones (`visible_from`) only for staff
     let problems = sqlx::query_as::<_, ContestProblemRow>(
         r#"
-        SELECT p.id, p.title, cp.problem_code, cp.points
+        SELECT p.title, cp.problem_code,
+               COALESCE(cp.max_score, p.max_score) as points
         FROM problems p
         JOIN contest_problems cp ON cp.problem_id = p.id
         WHERE cp.contest_id = $1
-        ORDER BY cp.order_index
+          AND ($2 OR cp.visible_from IS NULL OR cp.visible_from <= NOW())
+        ORDER BY cp.sort_order
         "#,
     )
     .bind(contest_id)
-    .fetch_all(&state.db)
+    .bind(is_staff)
+    .fetch_all(state.read_db())
     .await?;
 
     let leaderboard_problems: Vec<LeaderboardProblem> = problems
@@ -921,58 +1627,50 @@ pub async fn get_contest_leaderboard(
     let offset = ((params.page.max(1) - 1) * params.per_page) as i64;
     let limit = params.per_page.min(100) as i64;
 
-    let standings = sqlx::query_as::<_, StandingRow>(
+    let standings = sqlx::query_as::<_, StandingRow>(concat!(
+        standings_ctes!(),
         r#"
-        WITH user_problem_scores AS (
-            SELECT 
-                s.user_id,
-                s.problem_id,
-                MAX(CASE WHEN s.status = 'accepted' THEN s.score ELSE 0 END) as best_score,
-                COUNT(*) as attempts,
-                BOOL_OR(s.status = 'accepted') as solved,
-                MIN(CASE WHEN s.status = 'accepted' THEN s.submitted_at END) as first_solved_at
-            FROM submissions s
-            WHERE s.contest_id = $1
-            GROUP BY s.user_id, s.problem_id
-        ),
-        user_totals AS (
-            SELECT 
-                ups.user_id,
-                SUM(ups.best_score) as total_score,
-                COUNT(CASE WHEN ups.solved THEN 1 END) as problems_solved,
-                SUM(ups.attempts) as total_attempts,
-                MAX(ups.first_solved_at) as last_ac
-            FROM user_problem_scores ups
-            GROUP BY ups.user_id
-        )
         SELECT 
             ut.user_id,
             u.username,
             u.display_name,
-            COALESCE(ut.total_score, 0)::bigint as total_score,
+            COALESCE(ut.total_score, 0)::double precision as total_score,
             COALESCE(ut.problems_solved, 0)::bigint as problems_solved,
+            COALESCE(ut.total_penalty, 0)::bigint as total_penalty,
             ut.last_ac as last_submission_at
         FROM user_totals ut
         JOIN users u ON u.id = ut.user_id
-        ORDER BY ut.total_score DESC, ut.last_ac ASC NULLS LAST
+        WHERE $4::uuid IS NULL OR EXISTS (
+            SELECT 1 FROM organization_members om
+            WHERE om.organization_id = $4 AND om.user_id = ut.user_id
+        )
+        ORDER BY "#,
+        standings_order!(),
+        r#", ut.user_id
         LIMIT $2 OFFSET $3
-        "#,
-    )
+        "#
+    ))
     .bind(contest_id)
     .bind(limit)
     .bind(offset)
-    .fetch_all(&state.db)
+    .bind(params.organization_id)
+    .fetch_all(state.read_db())
     .await?;
 
     let total: Option<i64> = sqlx::query_scalar::<_, Option<i64>>(
         r#"
-        SELECT COUNT(DISTINCT user_id) 
-        FROM submissions 
-        WHERE contest_id = $1
+        SELECT COUNT(DISTINCT s.user_id)
+        FROM submissions s
+        WHERE s.contest_id = $1 AND NOT s.is_practice AND NOT s.is_test
+          AND ($2::uuid IS NULL OR EXISTS (
+              SELECT 1 FROM organization_members om
+              WHERE om.organization_id = $2 AND om.user_id = s.user_id
+          ))
         "#,
     )
     .bind(contest_id)
-    .fetch_one(&state.db)
+    .bind(params.organization_id)
+    .fetch_one(state.read_db())
     .await?;
     let total = total.unwrap_or(0);
 
@@ -983,53 +1681,36 @@ pub async fn get_contest_leaderboard(
     let base_rank = offset as i32 + 1;
 
     for (idx, row) in standings.into_iter().enumerate() {
-        // Get per-problem scores for this user
-        let problem_scores = sqlx::query_as::<_, ProblemScoreRow>(
-            r#"
-            SELECT 
-                cp.problem_code,
-                MAX(CASE WHEN s.status = 'accepted' THEN s.score ELSE 0 END) as score,
-                COUNT(s.id) as attempts,
-                BOOL_OR(s.status = 'accepted') as solved,
-                MIN(CASE WHEN s.status = 'accepted' THEN s.submitted_at END) as first_solved_at
-            FROM contest_problems cp
-            JOIN problems p ON p.id = cp.problem_id
-            LEFT JOIN submissions s ON s.problem_id = p.id AND s.user_id = $1 AND s.contest_id = $2
-            WHERE cp.contest_id = $2
-            GROUP BY p.id, cp.problem_code, cp.order_index
-            ORDER BY cp.order_index
-            "#,
-        )
-        .bind(row.user_id)
-        .bind(contest_id)
-        .fetch_all(&state.db)
-        .await?;
-
-        entries.push(LeaderboardEntry {
-            rank: base_rank + idx as i32,
-            user: UserInfo {
-                id: row.user_id,
-                username: row.username,
-                display_name: row.display_name,
-            },
-            total_score: row.total_score as i32,
-            total_penalty: 0, // TODO: ICPC penalty calculation
-            problems_solved: row.problems_solved as i32,
-            problem_scores: problem_scores
-                .into_iter()
-                .map(|ps| ProblemScore {
-                    problem_code: ps.problem_code.unwrap_or_default(),
-                    score: ps.score.map(|s| s as i32),
-                    attempts: ps.attempts as i32,
-                    solved: ps.solved.unwrap_or(false),
-                    first_solved_at: ps.first_solved_at,
-                })
-                .collect(),
-            last_submission_at: row.last_submission_at,
-        });
+        let problem_scores = fetch_problem_scores(state.read_db(), contest_id, row.user_id).await?;
+        entries.push(row.into_entry(base_rank + idx as i32, problem_scores));
     }
 
-    Ok(Json(LeaderboardResponse {
+    // Reference baselines, listed beside the standings without a rank
+    let baselines = sqlx::query_as::<_, BaselineRow>(
+        r#"
+        SELECT cp.problem_code, b.max_time_ms, b.max_memory_kb, b.total_instructions,
+               b.completed_at
+        FROM contest_problem_baselines b
+        JOIN contest_problems cp
+             ON cp.contest_id = b.contest_id AND cp.problem_id = b.problem_id
+        WHERE b.contest_id = $1 AND b.status = 'completed' AND b.verdict = 'accepted'
+        ORDER BY cp.sort_order
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(state.read_db())
+    .await?
+    .into_iter()
+    .map(|row| LeaderboardBaseline {
+        problem_code: row.problem_code.unwrap_or_default(),
+        max_time_ms: row.max_time_ms,
+        max_memory_kb: row.max_memory_kb,
+        total_instructions: row.total_instructions,
+        judged_at: row.completed_at,
+    })
+    .collect();
+
+    let response = Json(LeaderboardResponse {
         contest_id,
         contest_title: contest.title,
         scoring_type: contest.scoring_type.unwrap_or_else(|| "ioi".to_string()),
@@ -1041,42 +1722,301 @@ pub async fn get_contest_leaderboard(
             total_pages,
         },
         frozen: contest.leaderboard_frozen.unwrap_or(false),
+        paused: contest.paused_at.is_some(),
+        paused_at: contest.paused_at,
         problems: leaderboard_problems,
+        baselines,
+    });
+
+    // The staff copy lists unreleased problems: keep it out of shared caches
+    Ok(standings_response(response, public && !is_staff))
+}
+
+/// GET /api/v1/contests/{id}/leaderboard/me - Caller's standing and neighbors
+///
+/// Ranks the whole contest in one query and returns only the window around
+/// the caller, so clients need not page through the leaderboard to find
+/// themselves. Ranks match `fetch_standing_entry` (ties share a rank).
+pub async fn get_my_standing(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(contest_id): Path<Uuid>,
+    Query(params): Query<MyStandingQuery>,
+) -> ApiResult<Json<MyStandingResponse>> {
+    let frozen: Option<Option<bool>> = sqlx::query_scalar(
+        r#"
+        SELECT freeze_time IS NOT NULL AND NOW() >= freeze_time AND NOW() < end_time
+        FROM contests WHERE id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(state.read_db())
+    .await?;
+    let frozen = frozen
+        .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?
+        .unwrap_or(false);
+
+    let neighbors = params.neighbors.min(MAX_STANDING_NEIGHBORS) as i64;
+
+    // `position` breaks rank ties deterministically so the window has a
+    // stable size; `rank` is what clients display
+    let rows = sqlx::query_as::<_, WindowStandingRow>(concat!(
+        standings_ctes!(),
+        r#",
+        ranked AS (
+            SELECT 
+                ut.*,
+                RANK() OVER (ORDER BY "#,
+        standings_order!(),
+        r#") as rank,
+                ROW_NUMBER() OVER (ORDER BY "#,
+        standings_order!(),
+        r#", ut.user_id) as position,
+                COUNT(*) OVER () as total
+            FROM user_totals ut
+            WHERE $3::uuid IS NULL OR EXISTS (
+                SELECT 1 FROM organization_members om
+                WHERE om.organization_id = $3 AND om.user_id = ut.user_id
+            )
+        ),
+        me AS (
+            SELECT position FROM ranked WHERE user_id = $2
+        )
+        SELECT 
+            r.rank,
+            r.total,
+            r.user_id,
+            u.username,
+            u.display_name,
+            COALESCE(r.total_score, 0)::double precision as total_score,
+            COALESCE(r.problems_solved, 0)::bigint as problems_solved,
+            COALESCE(r.total_penalty, 0)::bigint as total_penalty,
+            r.last_ac as last_submission_at
+        FROM ranked r
+        CROSS JOIN me
+        JOIN users u ON u.id = r.user_id
+        WHERE r.position BETWEEN me.position - $4 AND me.position + $4
+        ORDER BY r.position
+        "#
+    ))
+    .bind(contest_id)
+    .bind(user.id)
+    .bind(params.organization_id)
+    .bind(neighbors)
+    .fetch_all(state.read_db())
+    .await?;
+
+    // The window is empty when the caller is not ranked; still report the
+    // leaderboard size
+    let total = match rows.first() {
+        Some(row) => row.total,
+        None => sqlx::query_scalar::<_, Option<i64>>(
+            r#"
+            SELECT COUNT(DISTINCT s.user_id)
+            FROM submissions s
+            WHERE s.contest_id = $1 AND NOT s.is_practice AND NOT s.is_test
+              AND ($2::uuid IS NULL OR EXISTS (
+                  SELECT 1 FROM organization_members om
+                  WHERE om.organization_id = $2 AND om.user_id = s.user_id
+              ))
+            "#,
+        )
+        .bind(contest_id)
+        .bind(params.organization_id)
+        .fetch_one(state.read_db())
+        .await?
+        .unwrap_or(0),
+    };
+
+    let mut entry = None;
+    let mut above = Vec::new();
+    let mut below = Vec::new();
+
+    for row in rows {
+        let is_caller = row.standing.user_id == user.id;
+        let problem_scores =
+            fetch_problem_scores(state.read_db(), contest_id, row.standing.user_id).await?;
+        let ranked = row.standing.into_entry(row.rank as i32, problem_scores);
+
+        if is_caller {
+            entry = Some(ranked);
+        } else if entry.is_none() {
+            above.push(ranked);
+        } else {
+            below.push(ranked);
+        }
+    }
+
+    Ok(Json(MyStandingResponse {
+        contest_id,
+        entry,
+        above,
+        below,
+        total,
+        frozen,
     }))
 }
 
+/// Per-problem scores for one user on a contest leaderboard.
+async fn fetch_problem_scores(
+    db: &sqlx::PgPool,
+    contest_id: Uuid,
+    user_id: Uuid,
+) -> ApiResult<Vec<ProblemScore>> {
+    let rows = sqlx::query_as::<_, ProblemScoreRow>(
+        r#"
+        SELECT 
+            cp.problem_code,
+            MAX(CASE WHEN s.status = 'accepted' OR p.partial_scoring THEN s.score ELSE 0 END) as score,
+            COUNT(s.id) as attempts,
+            BOOL_OR(s.status = 'accepted') as solved,
+            MIN(CASE WHEN s.status = 'accepted' THEN s.submitted_at END) as first_solved_at
+        FROM contest_problems cp
+        JOIN problems p ON p.id = cp.problem_id
+        LEFT JOIN submissions s ON s.problem_id = p.id AND s.user_id = $1 AND s.contest_id = $2
+            AND NOT s.is_practice AND NOT s.is_test
+        WHERE cp.contest_id = $2
+        GROUP BY p.id, cp.problem_code, cp.sort_order
+        ORDER BY cp.sort_order
+        "#,
+    )
+    .bind(user_id)
+    .bind(contest_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|ps| ProblemScore {
+            problem_code: ps.problem_code.unwrap_or_default(),
+            score: ps.score,
+            attempts: ps.attempts as i32,
+            solved: ps.solved.unwrap_or(false),
+            first_solved_at: ps.first_solved_at,
+        })
+        .collect())
+}
+
+/// Current leaderboard row (with rank) for a single user.
+///
+/// Uses the same ordering as `get_contest_leaderboard`, so the rank matches
+/// what a full fetch would return.
+pub(crate) async fn fetch_standing_entry(
+    db: &sqlx::PgPool,
+    contest_id: Uuid,
+    user_id: Uuid,
+) -> ApiResult<Option<LeaderboardEntry>> {
+    let row = sqlx::query_as::<_, RankedStandingRow>(concat!(
+        standings_ctes!(),
+        r#",
+        ranked AS (
+            SELECT 
+                ut.*,
+                RANK() OVER (ORDER BY "#,
+        standings_order!(),
+        r#") as rank
+            FROM user_totals ut
+        )
+        SELECT 
+            r.rank,
+            r.user_id,
+            u.username,
+            u.display_name,
+            COALESCE(r.total_score, 0)::double precision as total_score,
+            COALESCE(r.problems_solved, 0)::bigint as problems_solved,
+            COALESCE(r.total_penalty, 0)::bigint as total_penalty,
+            r.last_ac as last_submission_at
+        FROM ranked r
+        JOIN users u ON u.id = r.user_id
+        WHERE r.user_id = $2
+        "#
+    ))
+    .bind(contest_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let problem_scores = fetch_problem_scores(db, contest_id, user_id).await?;
+    let rank = row.rank as i32;
+    Ok(Some(row.standing.into_entry(rank, problem_scores)))
+}
+
 #[derive(Debug, sqlx::FromRow)]
 struct ContestLeaderboardRow {
-    #[allow(dead_code)]
-    id: Uuid,
     title: String,
     scoring_type: Option<String>,
     leaderboard_frozen: Option<bool>,
+    paused_at: Option<chrono::DateTime<Utc>>,
 }
 
 #[derive(Debug, sqlx::FromRow)]
 struct ContestProblemRow {
-    #[allow(dead_code)]
-    id: Uuid,
     title: String,
     problem_code: Option<String>,
     points: Option<i32>,
 }
 
+#[derive(Debug, sqlx::FromRow)]
+struct BaselineRow {
+    problem_code: Option<String>,
+    max_time_ms: i32,
+    max_memory_kb: i32,
+    total_instructions: Option<i64>,
+    completed_at: Option<chrono::DateTime<Utc>>,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 struct StandingRow {
     user_id: Uuid,
     username: String,
     display_name: Option<String>,
-    total_score: i64,
+    total_score: f64,
     problems_solved: i64,
+    total_penalty: i64,
     last_submission_at: Option<chrono::DateTime<Utc>>,
 }
 
+impl StandingRow {
+    fn into_entry(self, rank: i32, problem_scores: Vec<ProblemScore>) -> LeaderboardEntry {
+        LeaderboardEntry {
+            rank,
+            user: UserInfo {
+                id: self.user_id,
+                username: self.username,
+                display_name: self.display_name,
+            },
+            total_score: self.total_score,
+            total_penalty: self.total_penalty as i32,
+            problems_solved: self.problems_solved as i32,
+            problem_scores,
+            last_submission_at: self.last_submission_at,
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RankedStandingRow {
+    rank: i64,
+    #[sqlx(flatten)]
+    standing: StandingRow,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct WindowStandingRow {
+    rank: i64,
+    total: i64,
+    #[sqlx(flatten)]
+    standing: StandingRow,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 struct ProblemScoreRow {
     problem_code: Option<String>,
-    score: Option<i64>,
+    score: Option<f64>,
     attempts: i64,
     solved: Option<bool>,
     first_solved_at: Option<chrono::DateTime<Utc>>,
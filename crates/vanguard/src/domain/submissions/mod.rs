@@ -1,9 +1,14 @@
 //! Submissions domain module.
 
+pub mod admission;
+pub mod final_standings;
 pub mod handler;
 pub mod request;
 pub mod response;
+pub mod scoreboard;
+pub mod stream;
 
+pub use final_standings::*;
 pub use handler::*;
-pub use request::*;
-pub use response::*;
+pub use scoreboard::*;
+pub use stream::*;
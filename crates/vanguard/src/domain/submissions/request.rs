@@ -4,31 +4,6 @@ use serde::Deserialize;
 use uuid::Uuid;
 use validator::Validate;
 
-/// Supported programming languages
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum Language {
-    Cpp,
-    C,
-    Rust,
-    Go,
-    Python,
-    Zig,
-}
-
-impl std::fmt::Display for Language {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Language::Cpp => write!(f, "cpp"),
-            Language::C => write!(f, "c"),
-            Language::Rust => write!(f, "rust"),
-            Language::Go => write!(f, "go"),
-            Language::Python => write!(f, "python"),
-            Language::Zig => write!(f, "zig"),
-        }
-    }
-}
-
 /// Create submission request (legacy source code)
 ///
 /// If `contest_id` is provided, the problem must be in that contest and
@@ -40,7 +15,9 @@ pub struct CreateSubmissionRequest {
     pub contest_id: Option<Uuid>,
     pub problem_id: Uuid,
 
-    pub language: Language,
+    /// Runtime name from the runtime registry (e.g. `cpp`)
+    #[validate(length(min = 1, max = 32, message = "Language must be 1-32 characters"))]
+    pub language: String,
 
     #[validate(length(
         min = 1,
@@ -50,6 +27,13 @@ pub struct CreateSubmissionRequest {
     pub source_code: String,
 }
 
+/// Batch submission status request
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmissionStatusRequest {
+    #[validate(length(min = 1, max = 100, message = "Request 1-100 submissions"))]
+    pub ids: Vec<Uuid>,
+}
+
 /// ZIP submission upload query parameters
 /// Used with multipart/form-data file upload
 ///
@@ -63,7 +47,7 @@ pub struct ZipSubmissionParams {
     /// Optional language hint so Sisyphus can set up the correct compiler
     /// toolchain before running compile.sh. If omitted, Sisyphus relies
     /// entirely on compile.sh to handle compilation.
-    pub language: Option<Language>,
+    pub language: Option<String>,
 }
 
 /// List submissions query parameters
@@ -89,6 +73,28 @@ pub struct ListSubmissionsQuery {
 
     /// Filter by language
     pub language: Option<String>,
+
+    /// `true`: only practice ("upsolving") submissions made after their
+    /// contest ended; `false`: only in-contest and standalone submissions
+    pub practice: Option<bool>,
+
+    /// `true`: only the contest team's pre-start test submissions;
+    /// `false`: leave them out. Others never see test submissions.
+    pub test: Option<bool>,
+}
+
+/// Filters of the v2 submission lists, next to their `CursorQuery`
+#[derive(Debug, Deserialize)]
+pub struct SubmissionFeedQuery {
+    pub contest_id: Option<Uuid>,
+    pub problem_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub language: Option<String>,
+    /// As for [`ListSubmissionsQuery::practice`]
+    pub practice: Option<bool>,
+    /// As for [`ListSubmissionsQuery::test`]
+    pub test: Option<bool>,
 }
 
 fn default_page() -> u32 {
@@ -107,8 +113,53 @@ pub struct LeaderboardQuery {
 
     #[serde(default = "default_leaderboard_per_page")]
     pub per_page: u32,
+
+    /// Only rank members of this organization
+    pub organization_id: Option<Uuid>,
 }
 
 fn default_leaderboard_per_page() -> u32 {
     50
 }
+
+/// Query parameters for the caller's own leaderboard standing
+#[derive(Debug, Deserialize)]
+pub struct MyStandingQuery {
+    /// Rows to include above and below the caller (at most 25)
+    #[serde(default = "default_standing_neighbors")]
+    pub neighbors: u32,
+
+    /// Only rank members of this organization
+    pub organization_id: Option<Uuid>,
+}
+
+fn default_standing_neighbors() -> u32 {
+    2
+}
+
+/// Query parameters for a submission's source
+#[derive(Debug, Default, Deserialize)]
+pub struct SourceQuery {
+    /// Include highlighting metadata (grammar, line count, byte size)
+    #[serde(default)]
+    pub metadata: bool,
+}
+
+/// Certify final standings request
+#[derive(Debug, Deserialize, Validate)]
+pub struct CertifyStandingsRequest {
+    /// Why a new version is certified, e.g. "Rejudged problem C"
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+/// Query parameters for certified final standings
+#[derive(Debug, Deserialize)]
+pub struct FinalStandingsQuery {
+    /// Certified version to return; the latest by default
+    pub version: Option<i32>,
+    /// Also recompute the live standings and report whether they still
+    /// agree (`matches_current_standings`)
+    #[serde(default)]
+    pub compare: bool,
+}
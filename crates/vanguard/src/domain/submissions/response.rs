@@ -1,7 +1,7 @@
 //! Submission response DTOs.
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// User info for submissions
@@ -37,9 +37,14 @@ pub struct SubmissionSummary {
     pub contest: Option<ContestInfo>,
     pub language: Option<String>,
     pub status: String,
-    pub score: Option<i32>,
+    pub score: Option<f64>,
     pub max_time_ms: Option<i32>,
     pub max_memory_kb: Option<i32>,
+    /// Submitted after the contest ended; not counted in the standings.
+    pub is_practice: bool,
+    /// Submitted by the contest team before the start; not counted in the
+    /// standings and visible to the team only.
+    pub is_test: bool,
     pub submitted_at: DateTime<Utc>,
 }
 
@@ -69,13 +74,22 @@ pub struct SubmissionDetailResponse {
     pub contest: Option<ContestInfo>,
     pub submission_type: String,
     pub language: Option<String>,
+    /// Toolchain version it was compiled with (e.g. `rustc 1.79.0 (...)`)
+    pub runtime_version: Option<String>,
     pub status: String,
-    pub score: Option<i32>,
+    pub score: Option<f64>,
     pub total_test_cases: Option<i32>,
     pub passed_test_cases: Option<i32>,
     pub max_time_ms: Option<i32>,
     pub max_memory_kb: Option<i32>,
+    /// CPU instructions summed over all tests, when the judge counted them
+    pub total_instructions: Option<i64>,
     pub compilation_log: Option<String>,
+    /// Submitted after the contest ended; not counted in the standings.
+    pub is_practice: bool,
+    /// Submitted by the contest team before the start; not counted in the
+    /// standings and visible to the team only.
+    pub is_test: bool,
     pub submitted_at: DateTime<Utc>,
     pub compiled_at: Option<DateTime<Utc>>,
     pub judged_at: Option<DateTime<Utc>>,
@@ -93,6 +107,11 @@ pub struct SubmissionResponse {
     pub language: Option<String>,
     pub status: String,
     pub submitted_at: DateTime<Utc>,
+    /// Submitted after the contest ended; not counted in the standings.
+    pub is_practice: bool,
+    /// Submitted by the contest team before the start; not counted in the
+    /// standings and visible to the team only.
+    pub is_test: bool,
     pub message: String,
 }
 
@@ -104,6 +123,13 @@ pub struct TestCaseResult {
     pub time_ms: Option<i32>,
     pub memory_kb: Option<i32>,
     pub checker_score: Option<f64>,
+    /// Bytes sent and received, for problems that allow network access
+    pub network_bytes: Option<i64>,
+    /// CPU instructions retired, when the judge counted them
+    pub instructions: Option<i64>,
+    /// Limits the test ran under, after per-test and subtask overrides
+    pub time_limit_ms: Option<i32>,
+    pub memory_limit_kb: Option<i32>,
 }
 
 /// Submission results response
@@ -111,12 +137,34 @@ pub struct TestCaseResult {
 pub struct SubmissionResultsResponse {
     pub submission_id: Uuid,
     pub status: String,
-    pub score: Option<i32>,
+    pub score: Option<f64>,
     pub total_test_cases: Option<i32>,
     pub passed_test_cases: Option<i32>,
     pub results: Vec<TestCaseResult>,
 }
 
+/// Compact status of one submission
+#[derive(Debug, Serialize)]
+pub struct SubmissionStatus {
+    pub id: Uuid,
+    pub status: String,
+    pub score: Option<f64>,
+    pub passed_test_cases: Option<i32>,
+    pub total_test_cases: Option<i32>,
+    pub max_time_ms: Option<i32>,
+    pub max_memory_kb: Option<i32>,
+    pub judged_at: Option<DateTime<Utc>>,
+}
+
+/// Batch submission status response
+#[derive(Debug, Serialize)]
+pub struct SubmissionStatusResponse {
+    /// In the order requested, without duplicates
+    pub submissions: Vec<SubmissionStatus>,
+    /// Requested IDs with no submission
+    pub not_found: Vec<Uuid>,
+}
+
 /// Source code response
 #[derive(Debug, Serialize)]
 pub struct SourceCodeResponse {
@@ -124,6 +172,21 @@ pub struct SourceCodeResponse {
     pub language: Option<String>,
     pub source_code: Option<String>,
     pub submission_type: String,
+    /// Only with `?metadata=true`, and only for source submissions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<SourceMetadata>,
+}
+
+/// Highlighting metadata of a submission's source
+#[derive(Debug, Serialize)]
+pub struct SourceMetadata {
+    /// Highlighter grammar id (e.g. `cpp`, `python`), `null` if the
+    /// runtime's source file extension is not a known one
+    pub grammar: Option<String>,
+    /// Suggested file name, from the runtime's source file
+    pub filename: String,
+    pub line_count: usize,
+    pub byte_size: usize,
 }
 
 /// Leaderboard entry
@@ -131,7 +194,7 @@ pub struct SourceCodeResponse {
 pub struct LeaderboardEntry {
     pub rank: i32,
     pub user: UserInfo,
-    pub total_score: i32,
+    pub total_score: f64,
     pub total_penalty: i32,
     pub problems_solved: i32,
     pub problem_scores: Vec<ProblemScore>,
@@ -142,7 +205,7 @@ pub struct LeaderboardEntry {
 #[derive(Debug, Serialize)]
 pub struct ProblemScore {
     pub problem_code: String,
-    pub score: Option<i32>,
+    pub score: Option<f64>,
     pub attempts: i32,
     pub solved: bool,
     pub first_solved_at: Option<DateTime<Utc>>,
@@ -157,7 +220,38 @@ pub struct LeaderboardResponse {
     pub entries: Vec<LeaderboardEntry>,
     pub pagination: Pagination,
     pub frozen: bool,
+    /// Whether the contest is paused; submissions resume when it is
+    pub paused: bool,
+    pub paused_at: Option<DateTime<Utc>>,
     pub problems: Vec<LeaderboardProblem>,
+    /// Reference solutions of performance-scored problems, shown for
+    /// comparison; they are not ranked and do not affect scores
+    pub baselines: Vec<LeaderboardBaseline>,
+}
+
+/// Accepted reference solution of a problem, judged on the contest's workers
+#[derive(Debug, Serialize)]
+pub struct LeaderboardBaseline {
+    pub problem_code: String,
+    pub max_time_ms: i32,
+    pub max_memory_kb: i32,
+    pub total_instructions: Option<i64>,
+    pub judged_at: Option<DateTime<Utc>>,
+}
+
+/// The caller's leaderboard row with the rows around it
+#[derive(Debug, Serialize)]
+pub struct MyStandingResponse {
+    pub contest_id: Uuid,
+    /// `None` until the caller has a judged contest submission
+    pub entry: Option<LeaderboardEntry>,
+    /// Rows ranked just above the caller, best first
+    pub above: Vec<LeaderboardEntry>,
+    /// Rows ranked just below the caller, best first
+    pub below: Vec<LeaderboardEntry>,
+    /// Ranked participants on the (filtered) leaderboard
+    pub total: i64,
+    pub frozen: bool,
 }
 
 /// Problem info for leaderboard header
@@ -168,6 +262,142 @@ pub struct LeaderboardProblem {
     pub max_score: i32,
 }
 
+/// Scoreboard feed for projector displays, every row at once
+#[derive(Debug, Serialize)]
+pub struct ScoreboardResponse {
+    pub contest_id: Uuid,
+    pub contest_title: String,
+    pub scoring_type: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub freeze_time: Option<DateTime<Utc>>,
+    /// Submissions after `freeze_time` are only counted as `pending`
+    pub frozen: bool,
+    pub paused: bool,
+    pub problems: Vec<LeaderboardProblem>,
+    pub rows: Vec<ScoreboardRow>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Scoreboard feed row
+#[derive(Debug, Serialize)]
+pub struct ScoreboardRow {
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub rank: i32,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub solved: i32,
+    pub score: f64,
+    pub penalty: i64,
+    /// One per problem, in contest order
+    pub cells: Vec<ScoreboardCell>,
+}
+
+/// One user's result on one problem
+#[derive(Debug, Serialize)]
+pub struct ScoreboardCell {
+    pub problem_code: String,
+    pub score: f64,
+    pub attempts: i32,
+    /// Attempts hidden by the freeze
+    pub pending: i32,
+    pub solved: bool,
+    /// Contest minute of the first accepted submission
+    pub solved_minute: Option<i64>,
+    /// Nobody solved the problem earlier
+    pub first_solve: bool,
+}
+
+/// The signed document of certified final standings. Stored and served
+/// byte for byte as it was signed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FinalStandingsDocument {
+    pub contest_id: Uuid,
+    pub contest_title: String,
+    pub scoring_type: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub version: i32,
+    /// `content_hash` of the previous version, `None` for the first
+    pub previous_hash: Option<String>,
+    pub certified_at: DateTime<Utc>,
+    pub problems: Vec<FinalStandingsProblem>,
+    pub entries: Vec<FinalStandingsEntry>,
+}
+
+/// Problem column of certified standings
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FinalStandingsProblem {
+    pub problem_code: String,
+    pub title: String,
+    pub max_score: i32,
+}
+
+/// One participant's certified result
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FinalStandingsEntry {
+    pub rank: i32,
+    pub user_id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub solved: i32,
+    pub score: f64,
+    pub penalty: i64,
+    /// One per problem, in contest order
+    pub cells: Vec<FinalStandingsCell>,
+}
+
+/// One participant's certified result on one problem
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct FinalStandingsCell {
+    pub problem_code: String,
+    pub score: f64,
+    pub attempts: i32,
+    pub solved: bool,
+    pub solved_minute: Option<i64>,
+}
+
+/// A certified version of a contest's final standings
+#[derive(Debug, Serialize)]
+pub struct FinalStandingsResponse {
+    pub contest_id: Uuid,
+    pub version: i32,
+    pub latest_version: i32,
+    pub certified_at: DateTime<Utc>,
+    pub certified_by: Option<Uuid>,
+    pub reason: Option<String>,
+    pub standings: FinalStandingsDocument,
+    /// The exact signed text of `standings`, for verifying the hash and
+    /// signature independently
+    pub document: String,
+    /// SHA-256 of `document`, hex
+    pub content_hash: String,
+    pub previous_hash: Option<String>,
+    /// Signature over `document`, base64url
+    pub signature: String,
+    /// JWKS `kid` of the signing key
+    pub signing_key_id: String,
+    pub signing_algorithm: String,
+    pub verification: FinalStandingsVerification,
+}
+
+/// Server-side checks of a certified version
+#[derive(Debug, Serialize)]
+pub struct FinalStandingsVerification {
+    /// `content_hash` matches the document
+    pub hash_valid: bool,
+    /// `None` when the signing key has since been retired
+    pub signature_valid: Option<bool>,
+    /// `previous_hash` is the previous version's hash
+    pub chain_valid: bool,
+    /// Recomputing the standings now gives the same results; `false` after
+    /// a rejudge or edit changed them since certification. Only computed
+    /// when asked for with `?compare=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matches_current_standings: Option<bool>,
+}
+
 /// Simple message response
 #[derive(Debug, Serialize)]
 pub struct MessageResponse {
@@ -0,0 +1,612 @@
+//! Full-contest scoreboard exports.
+//!
+//! `GET /contests/{id}/leaderboard.csv` downloads every ranked row at once,
+//! and `GET /contests/{id}/scoreboard` is a compact JSON feed for projector
+//! displays at onsite events. The feed needs no authentication and is only
+//! served once organizers turn on the contest's `scoreboard_feed` setting.
+//!
+//! Like the leaderboard, both are only served for contests the caller may
+//! see; a private contest's standings are kept out of shared caches.
+//!
+//! Both hide frozen cells: between `freeze_time` and the end of the contest,
+//! submissions made after the freeze do not count towards any score and are
+//! only reported as pending attempts. Rows are ranked like
+//! `get_contest_leaderboard` (ties share a rank).
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::domain::contests::handler::load_visible_contest;
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthUser;
+use crate::middleware::caching::CachePolicy;
+use crate::state::AppState;
+
+use super::response::{LeaderboardProblem, ScoreboardCell, ScoreboardResponse, ScoreboardRow};
+
+#[derive(Debug, FromRow)]
+pub(crate) struct ScoreboardContestRow {
+    pub title: String,
+    pub scoring_type: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub freeze_time: Option<DateTime<Utc>>,
+    pub scoreboard_feed: bool,
+    pub paused_at: Option<DateTime<Utc>>,
+}
+
+impl ScoreboardContestRow {
+    /// Submissions from this time on are hidden, while the board is frozen
+    fn cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.freeze_time
+            .filter(|&freeze| now >= freeze && now < self.end_time)
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct ScoreboardProblemRow {
+    problem_code: Option<String>,
+    title: String,
+    points: Option<i32>,
+}
+
+/// One user's result on one problem
+#[derive(Debug, FromRow)]
+struct CellRow {
+    user_id: Uuid,
+    username: String,
+    display_name: Option<String>,
+    /// `None` for problems since removed from the contest; they still count
+    /// towards the totals, as on the leaderboard
+    problem_code: Option<String>,
+    score: f64,
+    attempts: i64,
+    pending: i64,
+    solved: bool,
+    first_solved_at: Option<DateTime<Utc>>,
+    solved_minute: Option<i64>,
+    penalty: i64,
+}
+
+/// A contest's full scoreboard.
+pub(crate) struct Scoreboard {
+    pub contest: ScoreboardContestRow,
+    pub problems: Vec<LeaderboardProblem>,
+    pub rows: Vec<ScoreboardRow>,
+    pub frozen: bool,
+}
+
+/// Check that the caller may see a contest's standings: private contests
+/// look missing to anyone who may not see them. Returns whether shared
+/// caches may keep the standings, i.e. whether the contest is public.
+pub(crate) async fn standings_visibility(
+    state: &AppState,
+    contest_id: Uuid,
+    user: Option<&AuthUser>,
+) -> ApiResult<bool> {
+    let contest = load_visible_contest(state, contest_id, user).await?;
+    Ok(contest.is_public)
+}
+
+/// Standings response that only shared caches may keep when `shared`;
+/// otherwise each caller's browser revalidates its own copy.
+pub(crate) fn standings_response(response: impl IntoResponse, shared: bool) -> Response {
+    if shared {
+        response.into_response()
+    } else {
+        ([CachePolicy::Revalidate.header()], response).into_response()
+    }
+}
+
+/// GET /api/v1/contests/{id}/scoreboard - Scoreboard feed for projector displays
+pub async fn get_scoreboard_feed(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Response> {
+    let shared = standings_visibility(&state, contest_id, user.as_ref().map(|u| &u.0)).await?;
+    let board = build_scoreboard(&state, contest_id).await?;
+
+    if !board.contest.scoreboard_feed {
+        return Err(ApiError::NotFound(
+            "This contest has no scoreboard feed".to_string(),
+        ));
+    }
+
+    let response = Json(ScoreboardResponse {
+        contest_id,
+        contest_title: board.contest.title,
+        scoring_type: board.contest.scoring_type,
+        start_time: board.contest.start_time,
+        end_time: board.contest.end_time,
+        freeze_time: board.contest.freeze_time,
+        frozen: board.frozen,
+        paused: board.contest.paused_at.is_some(),
+        problems: board.problems,
+        rows: board.rows,
+        generated_at: Utc::now(),
+    });
+    Ok(standings_response(response, shared))
+}
+
+/// GET /api/v1/contests/{id}/leaderboard.csv - Download the leaderboard as CSV
+///
+/// Per problem, the score, the counted attempts and the contest minute of
+/// the first accepted submission, plus the pending attempts while frozen.
+pub async fn get_leaderboard_csv(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Response> {
+    let shared = standings_visibility(&state, contest_id, user.as_ref().map(|u| &u.0)).await?;
+    let board = build_scoreboard(&state, contest_id).await?;
+
+    let mut header_row = vec![
+        "Rank".to_string(),
+        "Username".to_string(),
+        "Display Name".to_string(),
+        "Solved".to_string(),
+        "Score".to_string(),
+        "Penalty".to_string(),
+    ];
+    for problem in &board.problems {
+        let code = &problem.problem_code;
+        header_row.push(format!("{} Score", code));
+        header_row.push(format!("{} Attempts", code));
+        header_row.push(format!("{} Minute", code));
+        if board.frozen {
+            header_row.push(format!("{} Pending", code));
+        }
+    }
+
+    let mut csv = String::new();
+    push_csv_record(&mut csv, &header_row);
+
+    for row in &board.rows {
+        let mut record = vec![
+            row.rank.to_string(),
+            csv_text(&row.username),
+            csv_text(row.display_name.as_deref().unwrap_or_default()),
+            row.solved.to_string(),
+            row.score.to_string(),
+            row.penalty.to_string(),
+        ];
+        for cell in &row.cells {
+            let attempted = cell.attempts > 0;
+            record.push(if attempted {
+                cell.score.to_string()
+            } else {
+                String::new()
+            });
+            record.push(cell.attempts.to_string());
+            record.push(
+                cell.solved_minute
+                    .map(|m| m.to_string())
+                    .unwrap_or_default(),
+            );
+            if board.frozen {
+                record.push(cell.pending.to_string());
+            }
+        }
+        push_csv_record(&mut csv, &record);
+    }
+
+    let response = (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"leaderboard-{}.csv\"", contest_id),
+            ),
+        ],
+        Body::from(csv),
+    );
+    Ok(standings_response(response, shared))
+}
+
+/// Rank every participant of a contest, hiding frozen submissions.
+pub(crate) async fn build_scoreboard(
+    state: &AppState,
+    contest_id: Uuid,
+) -> ApiResult<Scoreboard> {
+    let db = state.read_db();
+
+    let contest: ScoreboardContestRow = sqlx::query_as(
+        r#"
+        SELECT c.title, c.scoring_type, c.start_time, c.end_time, c.freeze_time,
+               c.scoreboard_feed,
+               (SELECT cp.paused_at FROM contest_pauses cp
+                WHERE cp.contest_id = c.id AND cp.resumed_at IS NULL) as paused_at
+        FROM contests c WHERE c.id = $1
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Contest not found".to_string()))?;
+
+    let cutoff = contest.cutoff(Utc::now());
+
+    let problem_rows: Vec<ScoreboardProblemRow> = sqlx::query_as(
+        r#"
+        SELECT cp.problem_code, p.title, COALESCE(cp.max_score, p.max_score) as points
+        FROM contest_problems cp
+        JOIN problems p ON p.id = cp.problem_id
+        WHERE cp.contest_id = $1
+          AND (cp.visible_from IS NULL OR cp.visible_from <= NOW())
+        ORDER BY cp.sort_order
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_all(db)
+    .await?;
+
+    let problems: Vec<LeaderboardProblem> = problem_rows
+        .into_iter()
+        .map(|p| LeaderboardProblem {
+            problem_code: p.problem_code.unwrap_or_default(),
+            title: p.title,
+            max_score: p.points.unwrap_or(100),
+        })
+        .collect();
+
+    // Same scoring as `standings_ctes!`, restricted to submissions before
+    // the cutoff; later ones are only counted as pending
+    let cells: Vec<CellRow> = sqlx::query_as(
+        r#"
+        WITH contest_submissions AS (
+            SELECT s.id, s.user_id, s.problem_id, s.status, s.score, s.submitted_at,
+                   ($2::timestamptz IS NULL OR s.submitted_at < $2) as visible
+            FROM submissions s
+            WHERE s.contest_id = $1 AND NOT s.is_practice AND NOT s.is_test
+        ),
+        cells AS (
+            SELECT
+                s.user_id,
+                s.problem_id,
+                COALESCE(MAX(CASE WHEN s.status = 'accepted' OR p.partial_scoring THEN s.score ELSE 0 END)
+                    FILTER (WHERE s.visible), 0) as score,
+                COUNT(*) FILTER (WHERE s.visible) as attempts,
+                COUNT(*) FILTER (WHERE NOT s.visible) as pending,
+                COALESCE(BOOL_OR(s.status = 'accepted') FILTER (WHERE s.visible), false) as solved,
+                MIN(s.submitted_at) FILTER (WHERE s.visible AND s.status = 'accepted') as first_solved_at,
+                COUNT(*) FILTER (
+                    WHERE s.visible
+                      AND s.status IN ('wrong_answer', 'presentation_error', 'time_limit', 'memory_limit',
+                                       'output_limit', 'idleness_limit', 'runtime_error')
+                      AND NOT EXISTS (
+                          SELECT 1 FROM contest_submissions a
+                          WHERE a.user_id = s.user_id AND a.problem_id = s.problem_id
+                            AND a.status = 'accepted' AND a.submitted_at <= s.submitted_at
+                      )
+                ) as rejected_before_ac
+            FROM contest_submissions s
+            JOIN problems p ON p.id = s.problem_id
+            GROUP BY s.user_id, s.problem_id
+        )
+        SELECT
+            c.user_id,
+            u.username,
+            u.display_name,
+            cp.problem_code,
+            c.score::double precision as score,
+            c.attempts,
+            c.pending,
+            c.solved,
+            c.first_solved_at,
+            CASE WHEN c.solved THEN contest_elapsed_seconds($1, c.first_solved_at) / 60 END
+                as solved_minute,
+            CASE WHEN c.solved THEN
+                contest_elapsed_seconds($1, c.first_solved_at) / 60 + 20 * c.rejected_before_ac
+            ELSE 0 END as penalty
+        FROM cells c
+        JOIN users u ON u.id = c.user_id
+        LEFT JOIN contest_problems cp ON cp.contest_id = $1 AND cp.problem_id = c.problem_id
+        "#,
+    )
+    .bind(contest_id)
+    .bind(cutoff)
+    .fetch_all(db)
+    .await?;
+
+    let rows = rank_rows(&problems, cells);
+
+    Ok(Scoreboard {
+        contest,
+        problems,
+        rows,
+        frozen: cutoff.is_some(),
+    })
+}
+
+/// Group cells into rows and rank them like `standings_order!`: by score,
+/// then penalty, then the earlier last accepted submission.
+fn rank_rows(problems: &[LeaderboardProblem], cells: Vec<CellRow>) -> Vec<ScoreboardRow> {
+    let column: HashMap<&str, usize> = problems
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.problem_code.as_str(), i))
+        .collect();
+
+    // Earliest solve of each problem, for highlighting first solves
+    let mut first_solves: HashMap<&str, DateTime<Utc>> = HashMap::new();
+    for cell in &cells {
+        if let (Some(code), Some(at)) = (cell.problem_code.as_deref(), cell.first_solved_at) {
+            first_solves
+                .entry(code)
+                .and_modify(|first| *first = (*first).min(at))
+                .or_insert(at);
+        }
+    }
+
+    let mut rows: Vec<(ScoreboardRow, Option<DateTime<Utc>>)> = Vec::new();
+    let mut index: HashMap<Uuid, usize> = HashMap::new();
+
+    for cell in &cells {
+        let i = *index.entry(cell.user_id).or_insert_with(|| {
+            let empty = problems
+                .iter()
+                .map(|p| ScoreboardCell {
+                    problem_code: p.problem_code.clone(),
+                    score: 0.0,
+                    attempts: 0,
+                    pending: 0,
+                    solved: false,
+                    solved_minute: None,
+                    first_solve: false,
+                })
+                .collect();
+            rows.push((
+                ScoreboardRow {
+                    user_id: cell.user_id,
+                    rank: 0,
+                    username: cell.username.clone(),
+                    display_name: cell.display_name.clone(),
+                    solved: 0,
+                    score: 0.0,
+                    penalty: 0,
+                    cells: empty,
+                },
+                None,
+            ));
+            rows.len() - 1
+        });
+
+        let (row, last_ac) = &mut rows[i];
+        row.score += cell.score;
+        row.penalty += cell.penalty;
+        if cell.solved {
+            row.solved += 1;
+            *last_ac = (*last_ac).max(cell.first_solved_at);
+        }
+
+        let Some(&col) = cell.problem_code.as_deref().and_then(|c| column.get(c)) else {
+            continue;
+        };
+        let code = problems[col].problem_code.as_str();
+        row.cells[col] = ScoreboardCell {
+            problem_code: code.to_string(),
+            score: cell.score,
+            attempts: cell.attempts as i32,
+            pending: cell.pending as i32,
+            solved: cell.solved,
+            solved_minute: cell.solved_minute,
+            first_solve: cell.first_solved_at.is_some()
+                && cell.first_solved_at == first_solves.get(code).copied(),
+        };
+    }
+
+    rows.sort_by(|(a, a_last), (b, b_last)| {
+        standing_cmp((a, *a_last), (b, *b_last)).then_with(|| a.username.cmp(&b.username))
+    });
+
+    // Rows equal on the whole standings key share a rank, as with RANK()
+    let mut ranked: Vec<(ScoreboardRow, Option<DateTime<Utc>>)> = Vec::with_capacity(rows.len());
+    for (position, (mut row, last_ac)) in rows.into_iter().enumerate() {
+        row.rank = match ranked.last() {
+            Some((prev, prev_last))
+                if standing_cmp((prev, *prev_last), (&row, last_ac)).is_eq() =>
+            {
+                prev.rank
+            }
+            _ => position as i32 + 1,
+        };
+        ranked.push((row, last_ac));
+    }
+    ranked.into_iter().map(|(row, _)| row).collect()
+}
+
+/// Order of two rows on the standings key of `standings_order!`: higher
+/// score, then lower penalty, then the earlier last accepted submission,
+/// with rows that have none after the rest
+fn standing_cmp(
+    (a, a_last): (&ScoreboardRow, Option<DateTime<Utc>>),
+    (b, b_last): (&ScoreboardRow, Option<DateTime<Utc>>),
+) -> Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then_with(|| a.penalty.cmp(&b.penalty))
+        .then_with(|| a_last.is_none().cmp(&b_last.is_none()))
+        .then_with(|| a_last.cmp(&b_last))
+}
+
+/// User-supplied text for a CSV cell. Spreadsheets evaluate cells starting
+/// with these characters as formulas.
+fn csv_text(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Append one CSV record (RFC 4180), with a CRLF line ending.
+fn push_csv_record(out: &mut String, fields: &[String]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            let _ = write!(out, "\"{}\"", field.replace('"', "\"\""));
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn problems(codes: &[&str]) -> Vec<LeaderboardProblem> {
+        codes
+            .iter()
+            .map(|code| LeaderboardProblem {
+                problem_code: code.to_string(),
+                title: format!("Problem {}", code),
+                max_score: 100,
+            })
+            .collect()
+    }
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 10, minute, 0).unwrap()
+    }
+
+    /// A cell of user `user`, solved at `solved_at` with `penalty`
+    fn cell(user: u128, code: &str, solved_at: Option<u32>, penalty: i64) -> CellRow {
+        let solved = solved_at.is_some();
+        CellRow {
+            user_id: Uuid::from_u128(user),
+            username: format!("user{}", user),
+            display_name: None,
+            problem_code: Some(code.to_string()),
+            score: if solved { 100.0 } else { 0.0 },
+            attempts: 1,
+            pending: 0,
+            solved,
+            first_solved_at: solved_at.map(at),
+            solved_minute: solved_at.map(i64::from),
+            penalty,
+        }
+    }
+
+    fn ranking(rows: &[ScoreboardRow]) -> Vec<(&str, i32)> {
+        rows.iter()
+            .map(|row| (row.username.as_str(), row.rank))
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_by_score_first() {
+        let rows = rank_rows(
+            &problems(&["A", "B"]),
+            vec![
+                cell(1, "A", Some(50), 50),
+                cell(2, "A", Some(5), 5),
+                cell(2, "B", Some(10), 10),
+            ],
+        );
+        assert_eq!(ranking(&rows), [("user2", 1), ("user1", 2)]);
+        assert_eq!(rows[0].solved, 2);
+        assert_eq!(rows[0].penalty, 15);
+    }
+
+    #[test]
+    fn test_equal_score_ranks_by_penalty_before_last_solve() {
+        // user1 solved last at minute 10 but with 40 minutes of penalty;
+        // user2 solved later with less penalty and ranks first
+        let rows = rank_rows(
+            &problems(&["A"]),
+            vec![cell(1, "A", Some(10), 40), cell(2, "A", Some(30), 30)],
+        );
+        assert_eq!(ranking(&rows), [("user2", 1), ("user1", 2)]);
+    }
+
+    #[test]
+    fn test_equal_penalty_ranks_by_earlier_last_solve() {
+        let rows = rank_rows(
+            &problems(&["A"]),
+            vec![cell(1, "A", Some(30), 30), cell(2, "A", Some(20), 30)],
+        );
+        assert_eq!(ranking(&rows), [("user2", 1), ("user1", 2)]);
+    }
+
+    #[test]
+    fn test_ties_share_a_rank() {
+        let rows = rank_rows(
+            &problems(&["A"]),
+            vec![
+                cell(1, "A", Some(20), 20),
+                cell(2, "A", Some(20), 20),
+                cell(3, "A", None, 0),
+            ],
+        );
+        assert_eq!(ranking(&rows), [("user1", 1), ("user2", 1), ("user3", 3)]);
+    }
+
+    #[test]
+    fn test_first_solve_is_marked() {
+        let rows = rank_rows(
+            &problems(&["A"]),
+            vec![cell(1, "A", Some(20), 20), cell(2, "A", Some(10), 10)],
+        );
+        assert!(rows[0].cells[0].first_solve);
+        assert!(!rows[1].cells[0].first_solve);
+    }
+
+    #[test]
+    fn test_removed_problems_count_towards_totals() {
+        let mut removed = cell(1, "Z", Some(15), 15);
+        removed.problem_code = None;
+        let rows = rank_rows(&problems(&["A"]), vec![cell(1, "A", Some(5), 5), removed]);
+
+        assert_eq!(rows[0].score, 200.0);
+        assert_eq!(rows[0].solved, 2);
+        assert_eq!(rows[0].cells.len(), 1);
+    }
+
+    #[test]
+    fn test_csv_text_neutralizes_formulas() {
+        assert_eq!(csv_text("alice"), "alice");
+        assert_eq!(csv_text("=SUM(A1:A9)"), "'=SUM(A1:A9)");
+        assert_eq!(csv_text("+1"), "'+1");
+        assert_eq!(csv_text("-1"), "'-1");
+        assert_eq!(csv_text("@cmd"), "'@cmd");
+        assert_eq!(csv_text("\tx"), "'\tx");
+        assert_eq!(csv_text("a=b"), "a=b");
+    }
+
+    #[test]
+    fn test_push_csv_record_quotes_when_needed() {
+        let mut csv = String::new();
+        push_csv_record(&mut csv, &["plain".to_string(), "a,b".to_string()]);
+        push_csv_record(
+            &mut csv,
+            &[
+                "say \"hi\"".to_string(),
+                "two\nlines".to_string(),
+                String::new(),
+            ],
+        );
+        assert_eq!(
+            csv,
+            "plain,\"a,b\"\r\n\"say \"\"hi\"\"\",\"two\nlines\",\r\n"
+        );
+    }
+}
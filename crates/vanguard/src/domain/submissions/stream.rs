@@ -0,0 +1,164 @@
+//! Live contest leaderboard (Server-Sent Events).
+//!
+//! Minos publishes a small JSON event on the `leaderboard_updates` Redis
+//! channel whenever a contest submission is judged. A single subscriber task
+//! per Vanguard instance relays those events into an in-process broadcast
+//! channel; each SSE client filters it by contest and receives the changed
+//! standings row instead of re-polling the full leaderboard.
+//!
+//! Event types sent to clients:
+//! - `standing`: updated `LeaderboardEntry` for one user
+//! - `refresh`: many rows may have changed (performance rescoring, or the
+//!   client fell behind) — refetch `GET /contests/{id}/leaderboard`
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+    Extension,
+};
+use futures::Stream;
+use serde::Deserialize;
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::error::ApiResult;
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+use super::handler::fetch_standing_entry;
+use super::scoreboard::standings_visibility;
+
+/// Redis pub/sub channel Minos publishes standings changes on.
+pub const LEADERBOARD_CHANNEL: &str = "leaderboard_updates";
+
+/// Capacity of the in-process broadcast channel.
+pub const LEADERBOARD_BROADCAST_CAPACITY: usize = 1024;
+
+/// Standings change published by Minos.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LeaderboardEvent {
+    pub contest_id: Uuid,
+    pub user_id: Uuid,
+    /// Other users' scores may have changed as well
+    #[serde(default)]
+    pub rescored: bool,
+}
+
+/// Spawn the background task relaying Redis pub/sub events to SSE clients.
+///
+/// Reconnects automatically if the subscription drops.
+pub fn spawn_leaderboard_listener(
+    redis_url: String,
+    sender: broadcast::Sender<LeaderboardEvent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = run_listener(&redis_url, &sender).await {
+                tracing::error!("Leaderboard listener error: {}. Reconnecting in 5s...", e);
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    })
+}
+
+async fn run_listener(
+    redis_url: &str,
+    sender: &broadcast::Sender<LeaderboardEvent>,
+) -> anyhow::Result<()> {
+    use futures::StreamExt;
+
+    let client = redis::Client::open(redis_url)?;
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(LEADERBOARD_CHANNEL).await?;
+    tracing::info!("Subscribed to {} channel", LEADERBOARD_CHANNEL);
+
+    let mut messages = pubsub.on_message();
+    while let Some(msg) = messages.next().await {
+        let payload: String = msg.get_payload()?;
+        match serde_json::from_str::<LeaderboardEvent>(&payload) {
+            // No receivers is fine — nobody is watching a scoreboard
+            Ok(event) => {
+                let _ = sender.send(event);
+            }
+            Err(e) => tracing::warn!("Ignoring malformed leaderboard event: {}", e),
+        }
+    }
+
+    Err(anyhow::anyhow!("Pub/sub stream ended unexpectedly"))
+}
+
+/// GET /api/v1/contests/{id}/leaderboard/stream - Live leaderboard updates (SSE)
+///
+/// Private contests look missing to anyone who may not see them.
+pub async fn stream_contest_leaderboard(
+    State(state): State<AppState>,
+    user: Option<Extension<AuthUser>>,
+    Path(contest_id): Path<Uuid>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    standings_visibility(&state, contest_id, user.as_ref().map(|u| &u.0)).await?;
+
+    let rx = state.leaderboard_events.subscribe();
+    let stream = futures::stream::unfold((rx, state.db.clone()), move |(mut rx, db)| async move {
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) if event.contest_id == contest_id => event,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "Leaderboard stream lagged, requesting refresh");
+                    return Some((Ok(refresh_event(contest_id)), (rx, db)));
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+
+            match next_event(&db, &event).await {
+                Ok(Some(sse)) => return Some((Ok(sse), (rx, db))),
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!("Failed to build leaderboard update: {}", e);
+                    continue;
+                }
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Build the SSE event for a standings change, or `None` if nothing should
+/// be sent (frozen leaderboard, user not on the board).
+async fn next_event(db: &PgPool, event: &LeaderboardEvent) -> ApiResult<Option<Event>> {
+    // Standings are hidden between freeze_time and the end of the contest
+    let frozen: Option<bool> = sqlx::query_scalar(
+        r#"
+        SELECT freeze_time IS NOT NULL AND NOW() >= freeze_time AND NOW() < end_time
+        FROM contests WHERE id = $1
+        "#,
+    )
+    .bind(event.contest_id)
+    .fetch_optional(db)
+    .await?;
+
+    if frozen.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    if event.rescored {
+        return Ok(Some(refresh_event(event.contest_id)));
+    }
+
+    let Some(entry) = fetch_standing_entry(db, event.contest_id, event.user_id).await? else {
+        return Ok(None);
+    };
+
+    Ok(Event::default().event("standing").json_data(&entry).ok())
+}
+
+fn refresh_event(contest_id: Uuid) -> Event {
+    Event::default()
+        .event("refresh")
+        .data(serde_json::json!({ "contest_id": contest_id }).to_string())
+}
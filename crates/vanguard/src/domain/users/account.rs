@@ -0,0 +1,263 @@
+//! Account deletion and personal data export.
+//!
+//! Deleting an account anonymizes it (the `anonymize_user` database
+//! function) rather than removing the row, so the contests, problems and
+//! standings the user took part in stay intact. Users ask for it themselves
+//! and have a grace period to change their mind; Horus anonymizes accounts
+//! whose grace period is over, and admins can anonymize one at once.
+//!
+//! Exports are written to an unnamed temporary file on the data volume and
+//! streamed from there, so stored ZIP submissions never sit in memory.
+
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::domain::auth::session;
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+use crate::upload::UPLOAD_TEMP_DIR;
+
+/// Anonymize a user now, delete the stored files of their submissions (ZIPs,
+/// compiled artifacts and kept test outputs) if their submissions are
+/// deleted, and end their sessions.
+pub async fn anonymize_user(
+    state: &AppState,
+    user_id: Uuid,
+    delete_submissions: bool,
+) -> ApiResult<()> {
+    let files: Vec<String> = sqlx::query_scalar("SELECT anonymize_user($1, $2)")
+        .bind(user_id)
+        .bind(delete_submissions)
+        .fetch_all(&state.db)
+        .await?;
+
+    for file in files {
+        if let Err(e) = state
+            .config
+            .storage_roots
+            .remove_submission_file(Path::new(&file))
+            .await
+        {
+            tracing::warn!("Failed to remove submission file {}: {}", file, e);
+        }
+    }
+
+    let mut conn = state.redis.get().await?;
+    session::revoke_all_sessions(&mut conn, user_id).await?;
+
+    Ok(())
+}
+
+/// Source of a submission in the export
+#[derive(Debug, sqlx::FromRow)]
+struct ExportSourceRow {
+    id: Uuid,
+    source_code: Option<String>,
+    file_path: Option<String>,
+    /// Source file name of the submission's runtime, if still registered
+    source_file: Option<String>,
+}
+
+/// A built export archive, read from the start
+pub struct ExportArchive {
+    /// Temporary file holding the archive, removed once closed
+    pub file: tokio::fs::File,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Build the ZIP archive of a user's personal data:
+///
+/// - `profile.json`: the account
+/// - `contests.json`: contest registrations
+/// - `submissions.json`: metadata of every submission
+/// - `sources/{submission_id}/{file}`: submitted sources, and ZIP
+///   submissions as `sources/{submission_id}.zip`
+pub async fn export_user_data(state: &AppState, user_id: Uuid) -> ApiResult<ExportArchive> {
+    let profile: String = sqlx::query_scalar(
+        r#"
+        SELECT jsonb_build_object(
+            'id', id, 'username', username, 'email', email,
+            'display_name', display_name, 'bio', bio, 'role', role,
+            'created_at', created_at, 'updated_at', updated_at,
+            'deletion_scheduled_for', deletion_scheduled_for
+        )::text
+        FROM users WHERE id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+    let contests: String = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(jsonb_agg(jsonb_build_object(
+            'contest_id', c.id, 'title', c.title, 'registered_at', cp.registered_at
+        ) ORDER BY cp.registered_at), '[]')::text
+        FROM contest_participants cp
+        JOIN contests c ON c.id = cp.contest_id
+        WHERE cp.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let submissions: String = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(jsonb_agg(jsonb_build_object(
+            'id', s.id, 'contest_id', s.contest_id, 'problem_id', s.problem_id,
+            'problem_title', p.title, 'submission_type', s.submission_type,
+            'language', s.language, 'status', s.status, 'score', s.score,
+            'passed_test_cases', s.passed_test_cases,
+            'total_test_cases', s.total_test_cases,
+            'max_time_ms', s.max_time_ms, 'max_memory_kb', s.max_memory_kb,
+            'submitted_at', s.submitted_at, 'judged_at', s.judged_at,
+            'ip_address', HOST(s.ip_address), 'user_agent', s.user_agent
+        ) ORDER BY s.submitted_at), '[]')::text
+        FROM submissions s
+        JOIN problems p ON p.id = s.problem_id
+        WHERE s.user_id = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let sources: Vec<ExportSourceRow> = sqlx::query_as(
+        r#"
+        SELECT s.id, s.source_code, s.file_path, r.source_file
+        FROM submissions s
+        LEFT JOIN runtimes r ON r.name = s.language
+        WHERE s.user_id = $1
+        ORDER BY s.submitted_at
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let (file, size) = tokio::task::spawn_blocking(move || {
+        write_export(
+            Path::new(UPLOAD_TEMP_DIR),
+            &profile,
+            &contests,
+            &submissions,
+            &sources,
+        )
+        .map_err(|e| ApiError::Internal(format!("Failed to build export: {}", e)))
+    })
+    .await
+    .map_err(|e| ApiError::Internal(format!("Export task failed: {}", e)))??;
+
+    Ok(ExportArchive {
+        file: tokio::fs::File::from_std(file),
+        size,
+    })
+}
+
+/// Write the archive to a new temporary file in `dir`. Returns the file,
+/// rewound, and its size.
+fn write_export(
+    dir: &Path,
+    profile: &str,
+    contests: &str,
+    submissions: &str,
+    sources: &[ExportSourceRow],
+) -> anyhow::Result<(std::fs::File, u64)> {
+    std::fs::create_dir_all(dir)?;
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut writer = ZipWriter::new(tempfile::tempfile_in(dir)?);
+
+    for (name, json) in [
+        ("profile.json", profile),
+        ("contests.json", contests),
+        ("submissions.json", submissions),
+    ] {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        writer.start_file(name, options)?;
+        serde_json::to_writer_pretty(&mut writer, &value)?;
+    }
+
+    for source in sources {
+        if let Some(ref code) = source.source_code {
+            let file = source.source_file.as_deref().unwrap_or("source.txt");
+            writer.start_file(format!("sources/{}/{}", source.id, file), options)?;
+            writer.write_all(code.as_bytes())?;
+        } else if let Some(ref path) = source.file_path {
+            // Archives removed by retention cleanup are left out
+            let Ok(mut file) = std::fs::File::open(path) else {
+                continue;
+            };
+            writer.start_file(format!("sources/{}.zip", source.id), options)?;
+            std::io::copy(&mut file, &mut writer)?;
+        }
+    }
+
+    let mut file = writer.finish()?;
+    let size = file.stream_position()?;
+    file.seek(SeekFrom::Start(0))?;
+    Ok((file, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn test_export_is_written_to_a_rewound_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_submission = dir.path().join("submission.zip");
+        std::fs::write(&zip_submission, b"PK zip bytes").unwrap();
+        let source_id = Uuid::new_v4();
+        let source = ExportSourceRow {
+            id: source_id,
+            source_code: Some("int main() {}".to_string()),
+            file_path: None,
+            source_file: Some("main.cpp".to_string()),
+        };
+        let archived = ExportSourceRow {
+            id: Uuid::new_v4(),
+            source_code: None,
+            file_path: Some(zip_submission.to_string_lossy().into_owned()),
+            source_file: None,
+        };
+        let removed = ExportSourceRow {
+            id: Uuid::new_v4(),
+            source_code: None,
+            file_path: Some(dir.path().join("gone.zip").to_string_lossy().into_owned()),
+            source_file: None,
+        };
+
+        let (file, size) = write_export(
+            dir.path(),
+            r#"{"username": "alice"}"#,
+            "[]",
+            "[]",
+            &[source, archived, removed],
+        )
+        .unwrap();
+        assert_eq!(file.metadata().unwrap().len(), size);
+        // Unnamed: nothing is left behind in the directory
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let mut zip = zip::ZipArchive::new(file).unwrap();
+        // The archive whose file is gone is left out
+        assert_eq!(zip.len(), 5);
+        assert!(zip.by_name("profile.json").is_ok());
+
+        let mut code = String::new();
+        zip.by_name(&format!("sources/{}/main.cpp", source_id))
+            .unwrap()
+            .read_to_string(&mut code)
+            .unwrap();
+        assert_eq!(code, "int main() {}");
+    }
+}
@@ -1,35 +1,41 @@
 //! User management handlers.
 
+use std::collections::BTreeMap;
+
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use axum::{
+    body::Body,
     extract::{Extension, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use olympus_rules::roles::Permission;
 use sqlx::{FromRow, Row};
+use tokio_util::io::ReaderStream;
 use uuid::Uuid;
-use validator::Validate;
 
 use super::{
-    request::{ListUsersQuery, UpdateUserRequest},
+    account,
+    request::{
+        DeleteAccountRequest, ListUsersQuery, SaveContestFilterRequest, UpdateUserRequest,
+        UserFeedQuery,
+    },
     response::{
-        Pagination, UpdateUserResponse, UserListResponse, UserProfileResponse, UserStatsResponse,
-        UserSummary,
+        AccountDeletionResponse, ContestParticipation, HeatmapDay, LanguageSolves, Pagination,
+        SavedContestFilterListResponse, SavedContestFilterResponse, UpdateUserResponse,
+        UserListResponse, UserProfileResponse, UserQuotaResponse, UserStatsResponse, UserSummary,
     },
+    quota,
 };
-use crate::error::{ApiError, ApiResult};
+use crate::error::{self, ApiError, ApiResult};
+use crate::extract::ValidatedJson;
 use crate::middleware::auth::AuthUser;
+use crate::pagination::{Cursor, CursorPage, CursorQuery};
+use crate::repositories::users::{self as user_repo, UserFilter, UserListRow};
 use crate::state::AppState;
 
-/// User summary row from database
-#[derive(Debug, FromRow)]
-struct UserSummaryRow {
-    id: Uuid,
-    username: String,
-    display_name: Option<String>,
-    role: String,
-    created_at: DateTime<Utc>,
-}
-
 /// User profile row from database
 #[derive(Debug, FromRow)]
 struct UserProfileRow {
@@ -39,7 +45,6 @@ struct UserProfileRow {
     bio: Option<String>,
     role: String,
     created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
 }
 
 /// GET /api/v1/users
@@ -53,99 +58,14 @@ pub async fn list_users(
     let per_page = query.per_page.clamp(1, 100);
     let offset = ((page - 1) * per_page) as i64;
 
-    // Build dynamic query based on filters
-    let mut sql =
-        String::from("SELECT id, username, display_name, role, created_at FROM users WHERE 1=1");
-    let mut count_sql = String::from("SELECT COUNT(*) FROM users WHERE 1=1");
-
-    if let Some(ref role) = query.role {
-        sql.push_str(" AND role = $3");
-        count_sql.push_str(" AND role = $1");
-    }
-
-    if let Some(ref search) = query.search {
-        if query.role.is_some() {
-            sql.push_str(" AND (username ILIKE $4 OR display_name ILIKE $4)");
-            count_sql.push_str(" AND (username ILIKE $2 OR display_name ILIKE $2)");
-        } else {
-            sql.push_str(" AND (username ILIKE $3 OR display_name ILIKE $3)");
-            count_sql.push_str(" AND (username ILIKE $1 OR display_name ILIKE $1)");
-        }
-    }
-
-    sql.push_str(" ORDER BY created_at DESC LIMIT $1 OFFSET $2");
-
-    // Execute queries based on filters
-    let (users, total): (Vec<UserSummaryRow>, i64) = match (&query.role, &query.search) {
-        (Some(role), Some(search)) => {
-            let search_pattern = format!("%{}%", search);
-            let users: Vec<UserSummaryRow> = sqlx::query_as(&sql)
-                .bind(per_page as i64)
-                .bind(offset)
-                .bind(role)
-                .bind(&search_pattern)
-                .fetch_all(&state.db)
-                .await?;
-            let total: (i64,) = sqlx::query_as(&count_sql)
-                .bind(role)
-                .bind(&search_pattern)
-                .fetch_one(&state.db)
-                .await?;
-            (users, total.0)
-        }
-        (Some(role), None) => {
-            let users: Vec<UserSummaryRow> = sqlx::query_as(&sql)
-                .bind(per_page as i64)
-                .bind(offset)
-                .bind(role)
-                .fetch_all(&state.db)
-                .await?;
-            let total: (i64,) = sqlx::query_as(&count_sql)
-                .bind(role)
-                .fetch_one(&state.db)
-                .await?;
-            (users, total.0)
-        }
-        (None, Some(search)) => {
-            let search_pattern = format!("%{}%", search);
-            let users: Vec<UserSummaryRow> = sqlx::query_as(&sql)
-                .bind(per_page as i64)
-                .bind(offset)
-                .bind(&search_pattern)
-                .fetch_all(&state.db)
-                .await?;
-            let total: (i64,) = sqlx::query_as(&count_sql)
-                .bind(&search_pattern)
-                .fetch_one(&state.db)
-                .await?;
-            (users, total.0)
-        }
-        (None, None) => {
-            let sql = "SELECT id, username, display_name, role, created_at FROM users ORDER BY created_at DESC LIMIT $1 OFFSET $2";
-            let count_sql = "SELECT COUNT(*) FROM users";
-            let users: Vec<UserSummaryRow> = sqlx::query_as(sql)
-                .bind(per_page as i64)
-                .bind(offset)
-                .fetch_all(&state.db)
-                .await?;
-            let total: (i64,) = sqlx::query_as(count_sql).fetch_one(&state.db).await?;
-            (users, total.0)
-        }
-    };
+    let filter = UserFilter::new().role(query.role).search(query.search);
+    let users = user_repo::list(state.read_db(), &filter, per_page as i64, offset).await?;
+    let total = user_repo::count(state.read_db(), &filter).await?;
 
     let total_pages = ((total as f64) / (per_page as f64)).ceil() as u32;
 
     Ok(Json(UserListResponse {
-        users: users
-            .into_iter()
-            .map(|u| UserSummary {
-                id: u.id,
-                username: u.username,
-                display_name: u.display_name,
-                role: u.role,
-                created_at: u.created_at,
-            })
-            .collect(),
+        users: users.into_iter().map(row_to_summary).collect(),
         pagination: Pagination {
             page,
             per_page,
@@ -155,6 +75,38 @@ pub async fn list_users(
     }))
 }
 
+/// GET /api/v2/users
+///
+/// List users, paginated by cursor.
+pub async fn list_users_v2(
+    State(state): State<AppState>,
+    Query(page): Query<CursorQuery>,
+    Query(query): Query<UserFeedQuery>,
+) -> ApiResult<Json<CursorPage<UserSummary>>> {
+    let filter = UserFilter::new()
+        .role(query.role)
+        .search(query.search)
+        .after(page.after()?);
+    let rows = user_repo::list(state.read_db(), &filter, page.fetch_limit(), 0).await?;
+
+    Ok(Json(CursorPage::from_rows(
+        rows,
+        &page,
+        |row| Cursor::new(row.created_at, row.id),
+        row_to_summary,
+    )))
+}
+
+fn row_to_summary(row: UserListRow) -> UserSummary {
+    UserSummary {
+        id: row.id,
+        username: row.username,
+        display_name: row.display_name,
+        role: row.role,
+        created_at: row.created_at,
+    }
+}
+
 /// GET /api/v1/users/{id}
 ///
 /// Get a user's public profile.
@@ -163,10 +115,10 @@ pub async fn get_user(
     Path(user_id): Path<Uuid>,
 ) -> ApiResult<Json<UserProfileResponse>> {
     let user: UserProfileRow = sqlx::query_as(
-        "SELECT id, username, display_name, bio, role, created_at, updated_at FROM users WHERE id = $1"
+        "SELECT id, username, display_name, bio, role, created_at FROM users WHERE id = $1",
     )
     .bind(user_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(state.read_db())
     .await?
     .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
 
@@ -187,18 +139,13 @@ pub async fn update_user(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
     Path(user_id): Path<Uuid>,
-    Json(payload): Json<UpdateUserRequest>,
+    ValidatedJson(payload): ValidatedJson<UpdateUserRequest>,
 ) -> ApiResult<Json<UpdateUserResponse>> {
     // Check if user is updating their own profile
     if auth_user.id != user_id {
         return Err(ApiError::Forbidden);
     }
 
-    // Validate request
-    payload
-        .validate()
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
-
     // Update user
     let now = Utc::now();
     let row = sqlx::query(
@@ -229,30 +176,558 @@ pub async fn update_user(
     }))
 }
 
+/// Days of activity shown in the stats heatmap
+const HEATMAP_DAYS: i64 = 365;
+
+/// Most recent contests listed in the participation history
+const CONTEST_HISTORY_LIMIT: i64 = 50;
+
+#[derive(Debug, FromRow)]
+struct ActivityDayRow {
+    day: NaiveDate,
+    submissions: i32,
+    accepted: i32,
+}
+
+#[derive(Debug, FromRow)]
+struct ContestHistoryRow {
+    contest_id: Uuid,
+    title: String,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    registered_at: DateTime<Utc>,
+    submissions: i64,
+    problems_solved: i64,
+}
+
 /// GET /api/v1/users/{id}/stats
 ///
-/// Get a user's statistics.
+/// Get a user's statistics: totals, a daily activity heatmap, streaks of
+/// days with an accepted submission, solves per language and difficulty,
+/// and public contest history. Everything but the contest history is read
+/// from `user_activity_days` and `user_solves`, which a trigger on
+/// `submissions` keeps current. Solves only count public problems unless
+/// the caller is the user or may view private content.
 pub async fn get_user_stats(
     State(state): State<AppState>,
+    viewer: Option<Extension<AuthUser>>,
     Path(user_id): Path<Uuid>,
 ) -> ApiResult<Json<UserStatsResponse>> {
+    let db = state.read_db();
+    let all_problems = viewer
+        .as_ref()
+        .is_some_and(|v| v.id == user_id || v.can(Permission::ViewPrivateContent));
+
     // Verify user exists
     let exists: (bool,) = sqlx::query_as("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
         .bind(user_id)
-        .fetch_one(&state.db)
+        .fetch_one(db)
         .await?;
 
     if !exists.0 {
         return Err(ApiError::NotFound("User not found".to_string()));
     }
 
-    // For now, return placeholder stats since submissions table doesn't exist yet
-    // This will be updated when submissions are implemented in Phase 3
+    let days: Vec<ActivityDayRow> = sqlx::query_as(
+        r#"
+        SELECT day, submissions, accepted
+        FROM user_activity_days
+        WHERE user_id = $1 AND submissions > 0
+        ORDER BY day
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+
+    // Days on which a problem was solved for the first time, in any language
+    let first_solves: Vec<(NaiveDate, i64)> = sqlx::query_as(
+        r#"
+        SELECT (first_solved_at AT TIME ZONE 'UTC')::date as day, COUNT(*)
+        FROM (
+            SELECT MIN(us.first_solved_at) as first_solved_at
+            FROM user_solves us
+            JOIN problems p ON p.id = us.problem_id
+            WHERE us.user_id = $1 AND ($2 OR p.is_public)
+            GROUP BY us.problem_id
+        ) solved
+        GROUP BY 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(all_problems)
+    .fetch_all(db)
+    .await?;
+
+    let languages: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT us.language, COUNT(*)
+        FROM user_solves us
+        JOIN problems p ON p.id = us.problem_id
+        WHERE us.user_id = $1 AND ($2 OR p.is_public)
+        GROUP BY us.language
+        ORDER BY 2 DESC, 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(all_problems)
+    .fetch_all(db)
+    .await?;
+
+    let difficulties: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT COALESCE(p.difficulty, 'unrated'), COUNT(*)
+        FROM (SELECT DISTINCT problem_id FROM user_solves WHERE user_id = $1) us
+        JOIN problems p ON p.id = us.problem_id
+        WHERE $2 OR p.is_public
+        GROUP BY 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(all_problems)
+    .fetch_all(db)
+    .await?;
+
+    let (contests_participated,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM contest_participants WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(db)
+            .await?;
+
+    // Bounded by the user's registrations, so computed on the fly
+    let contests: Vec<ContestHistoryRow> = sqlx::query_as(
+        r#"
+        SELECT c.id as contest_id, c.title, c.start_time, c.end_time, cp.registered_at,
+               COALESCE(s.submissions, 0) as submissions,
+               COALESCE(s.problems_solved, 0) as problems_solved
+        FROM contest_participants cp
+        JOIN contests c ON c.id = cp.contest_id
+        LEFT JOIN LATERAL (
+            SELECT COUNT(*) as submissions,
+                   COUNT(DISTINCT problem_id) FILTER (WHERE status = 'accepted') as problems_solved
+            FROM submissions
+            WHERE contest_id = c.id AND user_id = cp.user_id AND NOT is_practice AND NOT is_test
+        ) s ON true
+        WHERE cp.user_id = $1 AND c.is_public
+        ORDER BY c.start_time DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(CONTEST_HISTORY_LIMIT)
+    .fetch_all(db)
+    .await?;
+
+    let today = Utc::now().date_naive();
+    let active_days: Vec<NaiveDate> = days
+        .iter()
+        .filter(|d| d.accepted > 0)
+        .map(|d| d.day)
+        .collect();
+    let (current_streak, best_streak) = streaks(&active_days, today);
+
+    let mut heatmap: BTreeMap<NaiveDate, HeatmapDay> = BTreeMap::new();
+    let heatmap_start = today - Duration::days(HEATMAP_DAYS - 1);
+    for day in days.iter().filter(|d| d.day >= heatmap_start) {
+        heatmap.insert(
+            day.day,
+            HeatmapDay {
+                date: day.day,
+                submissions: day.submissions as i64,
+                accepted: day.accepted as i64,
+                solved: 0,
+            },
+        );
+    }
+    for (day, solved) in first_solves {
+        if let Some(entry) = heatmap.get_mut(&day) {
+            entry.solved = solved;
+        }
+    }
+
     Ok(Json(UserStatsResponse {
         user_id,
-        total_submissions: 0,
-        accepted_submissions: 0,
-        contests_participated: 0,
-        problems_solved: 0,
+        total_submissions: days.iter().map(|d| d.submissions as i64).sum(),
+        accepted_submissions: days.iter().map(|d| d.accepted as i64).sum(),
+        contests_participated,
+        problems_solved: difficulties.iter().map(|(_, count)| count).sum(),
+        current_streak,
+        best_streak,
+        heatmap: heatmap.into_values().collect(),
+        languages: languages
+            .into_iter()
+            .map(|(language, problems_solved)| LanguageSolves {
+                language,
+                problems_solved,
+            })
+            .collect(),
+        difficulties: difficulties.into_iter().collect(),
+        contests: contests
+            .into_iter()
+            .map(|c| ContestParticipation {
+                contest_id: c.contest_id,
+                title: c.title,
+                start_time: c.start_time,
+                end_time: c.end_time,
+                registered_at: c.registered_at,
+                submissions: c.submissions,
+                problems_solved: c.problems_solved,
+            })
+            .collect(),
+    }))
+}
+
+/// GET /api/v1/users/me/quota
+///
+/// The caller's resource quotas and how much of each they use.
+pub async fn get_my_quota(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Json<UserQuotaResponse>> {
+    Ok(Json(
+        quota::fetch_user_quota(state.read_db(), user.id).await?,
+    ))
+}
+
+/// Saved contest filters a user may keep
+const MAX_SAVED_FILTERS: i64 = 50;
+
+/// Saved contest filter row from database
+#[derive(Debug, FromRow)]
+struct SavedFilterRow {
+    id: Uuid,
+    name: String,
+    filters: serde_json::Value,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl SavedFilterRow {
+    fn into_response(self) -> SavedContestFilterResponse {
+        SavedContestFilterResponse {
+            id: self.id,
+            name: self.name,
+            // Written from the same type, so this only loses filters that
+            // are no longer supported
+            filters: serde_json::from_value(self.filters).unwrap_or_default(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// GET /api/v1/users/me/filters
+///
+/// The caller's saved contest filters, by name.
+pub async fn list_contest_filters(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Json<SavedContestFilterListResponse>> {
+    let rows: Vec<SavedFilterRow> = sqlx::query_as(
+        r#"
+        SELECT id, name, filters, created_at, updated_at
+        FROM saved_contest_filters
+        WHERE user_id = $1
+        ORDER BY name
+        "#,
+    )
+    .bind(user.id)
+    .fetch_all(state.read_db())
+    .await?;
+
+    Ok(Json(SavedContestFilterListResponse {
+        filters: rows
+            .into_iter()
+            .map(SavedFilterRow::into_response)
+            .collect(),
     }))
 }
+
+/// POST /api/v1/users/me/filters
+///
+/// Save a contest list filter under a name. Apply it with
+/// `GET /api/v1/contests?saved_filter={id}`.
+pub async fn create_contest_filter(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<SaveContestFilterRequest>,
+) -> ApiResult<(StatusCode, Json<SavedContestFilterResponse>)> {
+    let saved: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM saved_contest_filters WHERE user_id = $1")
+            .bind(user.id)
+            .fetch_one(&state.db)
+            .await?;
+    if saved >= MAX_SAVED_FILTERS {
+        return Err(ApiError::QuotaExceeded(format!(
+            "At most {} saved filters",
+            MAX_SAVED_FILTERS
+        )));
+    }
+
+    let filters = serde_json::to_value(&payload.filters)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize filters: {}", e)))?;
+
+    let row: SavedFilterRow = sqlx::query_as(
+        r#"
+        INSERT INTO saved_contest_filters (user_id, name, filters)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, name) DO NOTHING
+        RETURNING id, name, filters, created_at, updated_at
+        "#,
+    )
+    .bind(user.id)
+    .bind(&payload.name)
+    .bind(filters)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| {
+        ApiError::Conflict(format!("A filter named '{}' already exists", payload.name))
+    })?;
+
+    Ok((StatusCode::CREATED, Json(row.into_response())))
+}
+
+/// PUT /api/v1/users/me/filters/{id}
+///
+/// Rename a saved contest filter and replace its filters.
+pub async fn update_contest_filter(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(filter_id): Path<Uuid>,
+    ValidatedJson(payload): ValidatedJson<SaveContestFilterRequest>,
+) -> ApiResult<Json<SavedContestFilterResponse>> {
+    let filters = serde_json::to_value(&payload.filters)
+        .map_err(|e| ApiError::Internal(format!("Failed to serialize filters: {}", e)))?;
+
+    let taken: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM saved_contest_filters WHERE user_id = $1 AND name = $2 AND id <> $3)",
+    )
+    .bind(user.id)
+    .bind(&payload.name)
+    .bind(filter_id)
+    .fetch_one(&state.db)
+    .await?;
+    if taken {
+        return Err(ApiError::Conflict(format!(
+            "A filter named '{}' already exists",
+            payload.name
+        )));
+    }
+
+    let row: SavedFilterRow = sqlx::query_as(
+        r#"
+        UPDATE saved_contest_filters SET name = $1, filters = $2
+        WHERE id = $3 AND user_id = $4
+        RETURNING id, name, filters, created_at, updated_at
+        "#,
+    )
+    .bind(&payload.name)
+    .bind(filters)
+    .bind(filter_id)
+    .bind(user.id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(error::conflict_on_unique(format!(
+        "A filter named '{}' already exists",
+        payload.name
+    )))?
+    .ok_or_else(|| ApiError::NotFound("Saved filter not found".to_string()))?;
+
+    Ok(Json(row.into_response()))
+}
+
+/// DELETE /api/v1/users/me/filters/{id}
+pub async fn delete_contest_filter(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    Path(filter_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let deleted = sqlx::query("DELETE FROM saved_contest_filters WHERE id = $1 AND user_id = $2")
+        .bind(filter_id)
+        .bind(user.id)
+        .execute(&state.db)
+        .await?
+        .rows_affected();
+
+    if deleted == 0 {
+        return Err(ApiError::NotFound("Saved filter not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/users/me/export
+///
+/// Download the caller's personal data as a ZIP archive: their profile,
+/// contest registrations, submission metadata and submitted sources.
+pub async fn export_my_data(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<Response> {
+    let archive = account::export_user_data(&state, user.id).await?;
+
+    tracing::info!(user_id = %user.id, bytes = archive.size, "User exported their data");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_LENGTH, archive.size.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"algojudge-{}.zip\"", user.username),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(archive.file)),
+    )
+        .into_response())
+}
+
+/// DELETE /api/v1/users/me
+///
+/// Ask for the caller's account to be deleted. It is anonymized once the
+/// grace period (`ACCOUNT_DELETION_GRACE_DAYS`) is over, unless the request
+/// is cancelled first. Asking again keeps the original schedule.
+pub async fn request_account_deletion(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+    ValidatedJson(payload): ValidatedJson<DeleteAccountRequest>,
+) -> ApiResult<Json<AccountDeletionResponse>> {
+    let password_hash: String = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1")
+        .bind(user.id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+    let parsed_hash = PasswordHash::new(&password_hash)
+        .map_err(|_| ApiError::Internal("Invalid password hash".to_string()))?;
+    Argon2::default()
+        .verify_password(payload.password.as_bytes(), &parsed_hash)
+        .map_err(|_| ApiError::InvalidCredentials)?;
+
+    let (requested_at, scheduled_for): (DateTime<Utc>, DateTime<Utc>) = sqlx::query_as(
+        r#"
+        UPDATE users
+        SET deletion_requested_at = COALESCE(deletion_requested_at, NOW()),
+            deletion_scheduled_for = COALESCE(deletion_scheduled_for, NOW() + make_interval(days => $1)),
+            updated_at = NOW()
+        WHERE id = $2
+        RETURNING deletion_requested_at, deletion_scheduled_for
+        "#,
+    )
+    .bind(state.config.account_deletion_grace_days as i32)
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await?;
+
+    tracing::info!(
+        user_id = %user.id,
+        scheduled_for = %scheduled_for,
+        "User requested account deletion"
+    );
+
+    Ok(Json(AccountDeletionResponse {
+        requested_at,
+        scheduled_for,
+        submissions: if state.config.account_deletion_deletes_submissions {
+            "delete"
+        } else {
+            "anonymize"
+        },
+    }))
+}
+
+/// DELETE /api/v1/users/me/deletion
+///
+/// Cancel a pending account deletion.
+pub async fn cancel_account_deletion(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthUser>,
+) -> ApiResult<StatusCode> {
+    let cancelled = sqlx::query(
+        r#"
+        UPDATE users
+        SET deletion_requested_at = NULL, deletion_scheduled_for = NULL, updated_at = NOW()
+        WHERE id = $1 AND deletion_scheduled_for IS NOT NULL
+        "#,
+    )
+    .bind(user.id)
+    .execute(&state.db)
+    .await?
+    .rows_affected();
+
+    if cancelled == 0 {
+        return Err(ApiError::NotFound(
+            "No account deletion is pending".to_string(),
+        ));
+    }
+
+    tracing::info!(user_id = %user.id, "User cancelled account deletion");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Current and best run of consecutive days in `days` (ascending, distinct).
+/// The current streak still counts if its last day is yesterday, so it does
+/// not reset before the user has had a chance to solve something today.
+fn streaks(days: &[NaiveDate], today: NaiveDate) -> (i64, i64) {
+    let mut best = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days {
+        run = match previous {
+            Some(p) if day - p == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        best = best.max(run);
+        previous = Some(day);
+    }
+
+    let current = match previous {
+        Some(last) if today - last <= Duration::days(1) => run,
+        _ => 0,
+    };
+    (current, best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    fn days(days: &[u32]) -> Vec<NaiveDate> {
+        days.iter().map(|&d| date(d)).collect()
+    }
+
+    #[test]
+    fn test_no_active_days_has_no_streak() {
+        assert_eq!(streaks(&[], date(10)), (0, 0));
+    }
+
+    #[test]
+    fn test_streak_ending_today_or_yesterday_is_current() {
+        assert_eq!(streaks(&days(&[8, 9, 10]), date(10)), (3, 3));
+        assert_eq!(streaks(&days(&[7, 8, 9]), date(10)), (3, 3));
+    }
+
+    #[test]
+    fn test_streak_ending_earlier_is_broken() {
+        assert_eq!(streaks(&days(&[6, 7, 8]), date(10)), (0, 3));
+    }
+
+    #[test]
+    fn test_best_streak_is_the_longest_run() {
+        assert_eq!(streaks(&days(&[1, 2, 3, 4, 6, 9, 10]), date(10)), (2, 4));
+        assert_eq!(streaks(&days(&[1, 3, 5]), date(5)), (1, 1));
+    }
+
+    #[test]
+    fn test_streaks_cross_month_boundaries() {
+        let active = [
+            NaiveDate::from_ymd_opt(2026, 2, 27).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 2, 28).unwrap(),
+            date(1),
+        ];
+        assert_eq!(streaks(&active, date(2)), (3, 3));
+    }
+}
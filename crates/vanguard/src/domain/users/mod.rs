@@ -1,9 +1,10 @@
 //! User management domain module.
 
+pub mod account;
 mod handler;
+pub mod quota;
 mod request;
 mod response;
 
 pub use handler::*;
-pub use request::*;
 pub use response::*;
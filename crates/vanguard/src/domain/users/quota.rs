@@ -0,0 +1,160 @@
+//! Per-user resource quotas.
+//!
+//! Site admins can cap how many problems a user owns, how many bytes their
+//! problems' binaries and test data take, and how many rejudges they may
+//! have running at once. A limit that is not set does not apply. Storage
+//! is charged to the problem's owner, whoever uploads.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+
+use super::response::{QuotaUsage, UserQuotaResponse};
+
+/// Bytes a user's problems store: every binary version plus test cases and
+/// checker self-tests.
+const USER_STORAGE_SQL: &str = r#"
+    SELECT (
+        COALESCE((SELECT SUM(v.size_bytes)
+                  FROM problem_binary_versions v
+                  JOIN problems p ON p.id = v.problem_id
+                  WHERE p.owner_id = $1), 0)
+        + COALESCE((SELECT SUM(COALESCE(OCTET_LENGTH(t.input), 0)
+                               + COALESCE(OCTET_LENGTH(t.expected_output), 0))
+                    FROM test_cases t
+                    JOIN problems p ON p.id = t.problem_id
+                    WHERE p.owner_id = $1), 0)
+        + COALESCE((SELECT SUM(OCTET_LENGTH(c.input) + OCTET_LENGTH(c.output)
+                               + COALESCE(OCTET_LENGTH(c.answer), 0))
+                    FROM checker_tests c
+                    JOIN problems p ON p.id = c.problem_id
+                    WHERE p.owner_id = $1), 0)
+    )::BIGINT
+"#;
+
+/// Rejudges a user requested that still have unjudged submissions
+const ACTIVE_REJUDGES_SQL: &str = r#"
+    SELECT COUNT(*)
+    FROM rejudge_batches b
+    WHERE b.requested_by = $1
+      AND EXISTS (
+          SELECT 1 FROM submissions s
+          WHERE s.rejudge_batch_id = b.id
+            AND s.status IN ('pending', 'compiling', 'compiled', 'judging', 'queue_pending')
+      )
+"#;
+
+/// Quota limits set by site admins
+#[derive(Debug, sqlx::FromRow)]
+struct UserLimitsRow {
+    max_problems: Option<i32>,
+    max_storage_bytes: Option<i64>,
+    max_concurrent_rejudges: Option<i32>,
+}
+
+async fn load_limits(db: &PgPool, user_id: Uuid) -> ApiResult<UserLimitsRow> {
+    sqlx::query_as(
+        "SELECT max_problems, max_storage_bytes, max_concurrent_rejudges FROM users WHERE id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()))
+}
+
+async fn problem_count(db: &PgPool, user_id: Uuid) -> ApiResult<i64> {
+    Ok(
+        sqlx::query_scalar("SELECT COUNT(*) FROM problems WHERE owner_id = $1")
+            .bind(user_id)
+            .fetch_one(db)
+            .await?,
+    )
+}
+
+async fn storage_used(db: &PgPool, user_id: Uuid) -> ApiResult<i64> {
+    Ok(sqlx::query_scalar(USER_STORAGE_SQL)
+        .bind(user_id)
+        .fetch_one(db)
+        .await?)
+}
+
+async fn active_rejudges(db: &PgPool, user_id: Uuid) -> ApiResult<i64> {
+    Ok(sqlx::query_scalar(ACTIVE_REJUDGES_SQL)
+        .bind(user_id)
+        .fetch_one(db)
+        .await?)
+}
+
+/// Enforce the user's problem quota before they create another problem.
+pub async fn enforce_problem_quota(db: &PgPool, user_id: Uuid) -> ApiResult<()> {
+    if let Some(limit) = load_limits(db, user_id).await?.max_problems {
+        if problem_count(db, user_id).await? >= limit as i64 {
+            return Err(ApiError::QuotaExceeded(format!(
+                "You can own at most {} problems",
+                limit
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Enforce the storage quota of the problem's owner before `incoming_bytes`
+/// more are stored for it. Shrinking uploads (`incoming_bytes <= 0`) always
+/// pass.
+pub async fn enforce_storage_quota(
+    db: &PgPool,
+    problem_id: Uuid,
+    incoming_bytes: i64,
+) -> ApiResult<()> {
+    if incoming_bytes <= 0 {
+        return Ok(());
+    }
+    let owner_id: Uuid = sqlx::query_scalar("SELECT owner_id FROM problems WHERE id = $1")
+        .bind(problem_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Problem not found".to_string()))?;
+
+    if let Some(quota) = load_limits(db, owner_id).await?.max_storage_bytes {
+        if storage_used(db, owner_id).await? + incoming_bytes > quota {
+            return Err(ApiError::QuotaExceeded(
+                "The problem owner's storage quota is used up".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Enforce the user's concurrent rejudge quota before they start another.
+pub async fn enforce_rejudge_quota(db: &PgPool, user_id: Uuid) -> ApiResult<()> {
+    if let Some(limit) = load_limits(db, user_id).await?.max_concurrent_rejudges {
+        if active_rejudges(db, user_id).await? >= limit as i64 {
+            return Err(ApiError::QuotaExceeded(format!(
+                "At most {} rejudges may run at once; wait for one to finish",
+                limit
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Load a user's quota limits and current usage.
+pub async fn fetch_user_quota(db: &PgPool, user_id: Uuid) -> ApiResult<UserQuotaResponse> {
+    let limits = load_limits(db, user_id).await?;
+    Ok(UserQuotaResponse {
+        user_id,
+        problems: QuotaUsage {
+            used: problem_count(db, user_id).await?,
+            limit: limits.max_problems.map(i64::from),
+        },
+        storage_bytes: QuotaUsage {
+            used: storage_used(db, user_id).await?,
+            limit: limits.max_storage_bytes,
+        },
+        concurrent_rejudges: QuotaUsage {
+            used: active_rejudges(db, user_id).await?,
+            limit: limits.max_concurrent_rejudges.map(i64::from),
+        },
+    })
+}
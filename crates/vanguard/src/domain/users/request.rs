@@ -3,6 +3,8 @@
 use serde::Deserialize;
 use validator::Validate;
 
+use crate::domain::contests::ContestListFilters;
+
 /// Query parameters for listing users
 #[derive(Debug, Deserialize, Default)]
 pub struct ListUsersQuery {
@@ -18,6 +20,15 @@ pub struct ListUsersQuery {
     pub search: Option<String>,
 }
 
+/// Filters of `GET /api/v2/users`, next to its `CursorQuery`
+#[derive(Debug, Deserialize)]
+pub struct UserFeedQuery {
+    /// Filter by role
+    pub role: Option<String>,
+    /// Search by username or display name
+    pub search: Option<String>,
+}
+
 fn default_page() -> u32 {
     1
 }
@@ -35,3 +46,22 @@ pub struct UpdateUserRequest {
     #[validate(length(max = 500, message = "Bio must be at most 500 characters"))]
     pub bio: Option<String>,
 }
+
+/// Account deletion request; the password confirms it is the user asking
+#[derive(Debug, Deserialize, Validate)]
+pub struct DeleteAccountRequest {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+/// Save (or replace) a named contest list filter
+#[derive(Debug, Deserialize, Validate)]
+pub struct SaveContestFilterRequest {
+    #[validate(length(min = 1, max = 64, message = "Name must be 1-64 characters"))]
+    pub name: String,
+
+    /// The list filters to apply, as on `GET /contests`
+    #[validate(nested)]
+    #[serde(default)]
+    pub filters: ContestListFilters,
+}
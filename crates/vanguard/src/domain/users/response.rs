@@ -1,9 +1,13 @@
 //! User management response DTOs.
 
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Serialize;
 use uuid::Uuid;
 
+use crate::domain::contests::ContestListFilters;
+
 /// User list response
 #[derive(Debug, Serialize)]
 pub struct UserListResponse {
@@ -49,6 +53,65 @@ pub struct UserStatsResponse {
     pub accepted_submissions: i64,
     pub contests_participated: i64,
     pub problems_solved: i64,
+    /// Consecutive days with an accepted submission, ending today or yesterday
+    pub current_streak: i64,
+    pub best_streak: i64,
+    /// Days with submissions in the last year, oldest first
+    pub heatmap: Vec<HeatmapDay>,
+    pub languages: Vec<LanguageSolves>,
+    /// Problems solved per difficulty
+    pub difficulties: BTreeMap<String, i64>,
+    /// Public contests registered for, most recent first
+    pub contests: Vec<ContestParticipation>,
+}
+
+/// One day of activity (UTC)
+#[derive(Debug, Serialize)]
+pub struct HeatmapDay {
+    pub date: NaiveDate,
+    pub submissions: i64,
+    pub accepted: i64,
+    /// Problems solved for the first time that day
+    pub solved: i64,
+}
+
+/// Problems solved in one language
+#[derive(Debug, Serialize)]
+pub struct LanguageSolves {
+    pub language: String,
+    pub problems_solved: i64,
+}
+
+/// A contest the user registered for
+#[derive(Debug, Serialize)]
+pub struct ContestParticipation {
+    pub contest_id: Uuid,
+    pub title: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub registered_at: DateTime<Utc>,
+    pub submissions: i64,
+    pub problems_solved: i64,
+}
+
+/// A quota's current usage and limit
+#[derive(Debug, Serialize)]
+pub struct QuotaUsage {
+    pub used: i64,
+    /// `None` when no limit is set
+    pub limit: Option<i64>,
+}
+
+/// A user's resource quotas
+#[derive(Debug, Serialize)]
+pub struct UserQuotaResponse {
+    pub user_id: Uuid,
+    /// Problems the user owns
+    pub problems: QuotaUsage,
+    /// Bytes of binaries and test data of the user's problems
+    pub storage_bytes: QuotaUsage,
+    /// The user's rejudges that have not finished
+    pub concurrent_rejudges: QuotaUsage,
 }
 
 /// Update user response
@@ -61,3 +124,29 @@ pub struct UpdateUserResponse {
     pub role: String,
     pub updated_at: DateTime<Utc>,
 }
+
+/// A saved contest list filter
+#[derive(Debug, Serialize)]
+pub struct SavedContestFilterResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub filters: ContestListFilters,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The caller's saved contest filters
+#[derive(Debug, Serialize)]
+pub struct SavedContestFilterListResponse {
+    pub filters: Vec<SavedContestFilterResponse>,
+}
+
+/// A pending account deletion
+#[derive(Debug, Serialize)]
+pub struct AccountDeletionResponse {
+    pub requested_at: DateTime<Utc>,
+    /// When the account will be anonymized unless the request is cancelled
+    pub scheduled_for: DateTime<Utc>,
+    /// `anonymize` (kept without the user's details) or `delete`
+    pub submissions: &'static str,
+}
@@ -1,13 +1,16 @@
 //! Error handling and API error responses.
 
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use olympus_common::i18n;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::middleware::locale::current_locale;
+
 /// API error response
 #[derive(Debug, Serialize)]
 pub struct ApiErrorResponse {
@@ -18,10 +21,54 @@ pub struct ApiErrorResponse {
 pub struct ApiErrorBody {
     pub code: &'static str,
     pub message: String,
+    /// English message, when `message` was translated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<serde_json::Value>,
 }
 
+impl ApiErrorBody {
+    /// Error body in the request's locale. `message` is the English text;
+    /// in any other locale the catalog message for `key` replaces it and the
+    /// English text moves to `original_message`.
+    pub fn localized(
+        code: &'static str,
+        key: &str,
+        params: &[(&str, String)],
+        message: String,
+        details: Option<serde_json::Value>,
+    ) -> Self {
+        let translated = match current_locale() {
+            i18n::DEFAULT_LOCALE => None,
+            locale => i18n::message(locale, key).map(|t| i18n::render(t, params)),
+        };
+        let (message, original_message) = match translated {
+            Some(translated) => (translated, Some(message)),
+            None => (message, None),
+        };
+
+        Self {
+            code,
+            message,
+            original_message,
+            details,
+        }
+    }
+}
+
+/// A single failed validation rule on a request field
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    /// Field path, e.g. `username` or `test_cases[2].input`
+    pub field: String,
+    /// Validator rule code, e.g. `length`, `email`, `invalid_role`
+    pub rule: String,
+    /// Human-readable message, in the request's locale when the rule is a
+    /// standard one
+    pub message: String,
+}
+
 /// Application error type
 #[derive(Error, Debug)]
 pub enum ApiError {
@@ -40,12 +87,30 @@ pub enum ApiError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Validation failed for {} field(s)", .0.len())]
+    InvalidFields(Vec<FieldError>),
+
     #[error("Conflict: {0}")]
     Conflict(String),
 
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
+    #[error("Please wait {0} seconds before submitting to this problem again")]
+    SubmissionCooldown(u64),
+
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    #[error("Contest is paused; submissions are not accepted until it resumes")]
+    ContestPaused,
+
+    #[error("{0}")]
+    ServiceUnavailable(String),
+
+    #[error("The judge is busy; practice submissions are paused, try again in {0} seconds")]
+    JudgeOverloaded(u64),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -70,8 +135,14 @@ impl ApiError {
             ApiError::Forbidden => StatusCode::FORBIDDEN,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
             ApiError::Conflict(_) => StatusCode::CONFLICT,
             ApiError::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::SubmissionCooldown(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::QuotaExceeded(_) => StatusCode::FORBIDDEN,
+            ApiError::ContestPaused => StatusCode::CONFLICT,
+            ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::JudgeOverloaded(_) => StatusCode::SERVICE_UNAVAILABLE,
             ApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::Redis(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::RedisCmd(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -87,8 +158,14 @@ impl ApiError {
             ApiError::Forbidden => "FORBIDDEN",
             ApiError::NotFound(_) => "NOT_FOUND",
             ApiError::Validation(_) => "VALIDATION_ERROR",
+            ApiError::InvalidFields(_) => "VALIDATION_ERROR",
             ApiError::Conflict(_) => "CONFLICT",
             ApiError::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            ApiError::SubmissionCooldown(_) => "SUBMISSION_COOLDOWN",
+            ApiError::QuotaExceeded(_) => "QUOTA_EXCEEDED",
+            ApiError::ContestPaused => "CONTEST_PAUSED",
+            ApiError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            ApiError::JudgeOverloaded(_) => "JUDGE_OVERLOADED",
             ApiError::Database(_) => "DATABASE_ERROR",
             ApiError::Redis(_) => "CACHE_ERROR",
             ApiError::RedisCmd(_) => "CACHE_ERROR",
@@ -124,17 +201,214 @@ impl IntoResponse for ApiError {
             _ => self.to_string(),
         };
 
+        let details = match &self {
+            ApiError::InvalidFields(fields) => serde_json::to_value(fields).ok(),
+            ApiError::SubmissionCooldown(secs) | ApiError::JudgeOverloaded(secs) => {
+                Some(serde_json::json!({ "retry_after_secs": secs }))
+            }
+            _ => None,
+        };
+
+        let (key, params) = match &self {
+            ApiError::InvalidFields(fields) => (
+                "VALIDATION_FAILED",
+                vec![("count", fields.len().to_string())],
+            ),
+            ApiError::SubmissionCooldown(secs) | ApiError::JudgeOverloaded(secs) => {
+                (code, vec![("secs", secs.to_string())])
+            }
+            _ => (code, Vec::new()),
+        };
+
         let body = ApiErrorResponse {
-            error: ApiErrorBody {
-                code,
-                message,
-                details: None,
-            },
+            error: ApiErrorBody::localized(code, key, &params, message, details),
+        };
+
+        let mut response = (status, Json(body)).into_response();
+        if let ApiError::SubmissionCooldown(secs) | ApiError::JudgeOverloaded(secs) = &self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(*secs));
+        }
+        response
+    }
+}
+
+impl From<validator::ValidationErrors> for ApiError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        ApiError::InvalidFields(field_errors(&errors))
+    }
+}
+
+/// Whether a database error is a unique constraint violation
+pub fn is_unique_violation(err: &sqlx::Error) -> bool {
+    err.as_database_error()
+        .is_some_and(|e| e.is_unique_violation())
+}
+
+/// Map a unique violation to `Conflict(message)` and pass other errors on.
+/// For inserts whose existence check can lose a race with a concurrent
+/// request.
+pub fn conflict_on_unique(message: impl Into<String>) -> impl FnOnce(sqlx::Error) -> ApiError {
+    let message = message.into();
+    move |err| {
+        if is_unique_violation(&err) {
+            ApiError::Conflict(message)
+        } else {
+            ApiError::Database(err)
+        }
+    }
+}
+
+/// Validator errors as field errors, sorted by field path
+pub fn field_errors(errors: &validator::ValidationErrors) -> Vec<FieldError> {
+    let mut fields = Vec::new();
+    collect_field_errors(errors, None, &mut fields);
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    fields
+}
+
+/// Flatten (possibly nested) validator errors into dotted field paths.
+fn collect_field_errors(
+    errors: &validator::ValidationErrors,
+    prefix: Option<&str>,
+    out: &mut Vec<FieldError>,
+) {
+    use validator::ValidationErrorsKind;
+
+    let locale = current_locale();
+    for (field, kind) in errors.errors() {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, field),
+            None => field.to_string(),
         };
 
-        (status, Json(body)).into_response()
+        match kind {
+            ValidationErrorsKind::Field(errs) => {
+                out.extend(errs.iter().map(|e| {
+                    let english = e
+                        .message
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| format!("Invalid value ({})", e.code));
+                    FieldError {
+                        field: path.clone(),
+                        rule: e.code.to_string(),
+                        message: match locale {
+                            i18n::DEFAULT_LOCALE => english,
+                            locale => localize_field_error(locale, e).unwrap_or(english),
+                        },
+                    }
+                }));
+            }
+            ValidationErrorsKind::Struct(inner) => {
+                collect_field_errors(inner, Some(&path), out);
+            }
+            ValidationErrorsKind::List(items) => {
+                for (index, inner) in items {
+                    collect_field_errors(inner, Some(&format!("{}[{}]", path, index)), out);
+                }
+            }
+        }
     }
 }
 
+/// Catalog message for a failed validator rule. Custom rules get the
+/// generic `field.invalid` message.
+fn localize_field_error(locale: &str, error: &validator::ValidationError) -> Option<String> {
+    let params: Vec<(&str, String)> = ["min", "max", "equal"]
+        .into_iter()
+        .filter_map(|name| {
+            let value = error.params.get(name)?;
+            let value = match value.as_str() {
+                Some(s) => s.to_string(),
+                None => value.to_string(),
+            };
+            Some((name, value))
+        })
+        .collect();
+    let has = |name: &str| params.iter().any(|(n, _)| *n == name);
+
+    let key = match (error.code.as_ref(), has("min"), has("max")) {
+        ("length", _, _) if has("equal") => "field.length.equal",
+        ("length", true, true) => "field.length.between",
+        ("length", true, false) => "field.length.min",
+        ("length", false, true) => "field.length.max",
+        ("range", true, true) => "field.range.between",
+        ("range", true, false) => "field.range.min",
+        ("range", false, true) => "field.range.max",
+        ("email", ..) => "field.email",
+        ("url", ..) => "field.url",
+        ("required", ..) => "field.required",
+        ("must_match", ..) => "field.must_match",
+        ("regex", ..) => "field.regex",
+        _ => "field.invalid",
+    };
+
+    i18n::message(locale, key).map(|template| i18n::render(template, &params))
+}
+
 /// Result type alias for API handlers
 pub type ApiResult<T> = Result<T, ApiError>;
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use validator::ValidationError;
+
+    use super::*;
+
+    fn rule(code: &'static str, params: &[(&'static str, i64)]) -> ValidationError {
+        let mut error = ValidationError::new(code);
+        for (name, value) in params {
+            error.add_param(Cow::Borrowed(name), value);
+        }
+        error
+    }
+
+    #[test]
+    fn test_localize_length_rules() {
+        assert_eq!(
+            localize_field_error("fr", &rule("length", &[("min", 3), ("max", 20)])).as_deref(),
+            Some("La longueur doit être comprise entre 3 et 20")
+        );
+        assert_eq!(
+            localize_field_error("fr", &rule("length", &[("min", 3)])).as_deref(),
+            Some("La longueur doit être d'au moins 3")
+        );
+        assert_eq!(
+            localize_field_error("fr", &rule("length", &[("max", 20)])).as_deref(),
+            Some("La longueur doit être d'au plus 20")
+        );
+        assert_eq!(
+            localize_field_error("fr", &rule("length", &[("equal", 6)])).as_deref(),
+            Some("La longueur doit être exactement 6")
+        );
+    }
+
+    #[test]
+    fn test_localize_range_and_plain_rules() {
+        assert_eq!(
+            localize_field_error("fr", &rule("range", &[("min", 1), ("max", 100)])).as_deref(),
+            Some("Doit être compris entre 1 et 100")
+        );
+        assert_eq!(
+            localize_field_error("fr", &rule("email", &[])).as_deref(),
+            Some("Doit être une adresse e-mail valide")
+        );
+    }
+
+    #[test]
+    fn test_localize_custom_rule_is_generic() {
+        assert_eq!(
+            localize_field_error("fr", &rule("reserved_username", &[])).as_deref(),
+            Some("Valeur invalide")
+        );
+    }
+
+    #[test]
+    fn test_localize_unknown_locale() {
+        assert_eq!(localize_field_error("ja", &rule("email", &[])), None);
+    }
+}
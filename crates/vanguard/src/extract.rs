@@ -0,0 +1,361 @@
+//! Request extractors.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{
+        rejection::JsonRejection, ConnectInfo, FromRequest, FromRequestParts, Path, Request,
+    },
+    http::{header, request::Parts, HeaderMap},
+    Json,
+};
+use olympus_rules::{auth_rules::Resource, specification::Specification};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domain::auth::{JwtManager, ServiceTokenClaims};
+use crate::domain::authorization::{build_scoped_context, Policy};
+use crate::error::ApiError;
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+/// JSON body extractor that runs `validator` rules before the handler.
+///
+/// Malformed bodies are rejected with `VALIDATION_ERROR` and the parser
+/// message; rule failures are rejected with one `details` entry per field
+/// (`field`, `rule`, `message`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|e: JsonRejection| ApiError::Validation(e.body_text()))?;
+
+        value.validate()?;
+
+        Ok(Self(value))
+    }
+}
+
+/// Deserialize a field that can be cleared: absent is `None` (keep the
+/// current value) and `null` is `Some(None)` (clear it). Use with
+/// `#[serde(default, deserialize_with = "double_option")]`.
+pub fn double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Where a request came from: the client IP (peer address, falling back to
+/// `X-Forwarded-For` like the rate limiter) and its `User-Agent`.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ci| ci.0.ip().to_string())
+            .or_else(|| {
+                parts
+                    .headers
+                    .get("X-Forwarded-For")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.split(',').next())
+                    .map(|s| s.trim().to_string())
+            });
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|ua| ua.chars().take(256).collect());
+
+        Ok(Self { ip, user_agent })
+    }
+}
+
+/// Authorization guard: rejects the request unless the authenticated user
+/// satisfies policy `P` for the resource named in the path.
+///
+/// The resource id is read from the `{contest_id}` / `{problem_id}` /
+/// `{submission_id}` path parameter, falling back to `{id}`. Requests
+/// without a user are rejected with `UNAUTHORIZED`, unknown resources with
+/// `NOT_FOUND`, and denied requests with `FORBIDDEN`.
+pub struct Guard<P> {
+    pub user: AuthUser,
+    _policy: PhantomData<P>,
+}
+
+impl<P: Policy> FromRequestParts<AppState> for Guard<P> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user = parts
+            .extensions
+            .get::<AuthUser>()
+            .cloned()
+            .ok_or(ApiError::Unauthorized)?;
+
+        let Path(params) = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| ApiError::Validation(e.body_text()))?;
+        let id = resource_id(&params, P::RESOURCE)?;
+
+        let sql = format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1)",
+            P::RESOURCE.table()
+        );
+        let exists: bool = sqlx::query_scalar(&sql)
+            .bind(id)
+            .fetch_one(&state.db)
+            .await?;
+        if !exists {
+            return Err(ApiError::NotFound(format!(
+                "{} not found",
+                resource_name(P::RESOURCE)
+            )));
+        }
+
+        let ctx = build_scoped_context(state, &user, P::RESOURCE, id);
+        if !P::rule().is_satisfied_by(&ctx).await {
+            return Err(ApiError::Forbidden);
+        }
+
+        Ok(Self {
+            user,
+            _policy: PhantomData,
+        })
+    }
+}
+
+/// Operation a service token must allow to pass [`ServiceCaller`].
+pub trait ServiceScope {
+    const SCOPE: &'static str;
+}
+
+/// Reading the judge queue depths
+pub struct QueueRead;
+
+impl ServiceScope for QueueRead {
+    const SCOPE: &'static str = "queue:read";
+}
+
+/// Every scope some service route checks; service tokens are only issued
+/// for these.
+pub const SERVICE_SCOPES: &[&str] = &[QueueRead::SCOPE];
+
+/// Service authentication: rejects the request unless it carries a service
+/// token allowing scope `S`.
+///
+/// Requests without a bearer token are rejected with `UNAUTHORIZED`, user
+/// tokens and invalid service tokens with `TOKEN_ERROR`, and service
+/// tokens without the scope with `FORBIDDEN`.
+pub struct ServiceCaller<S> {
+    pub claims: ServiceTokenClaims,
+    _scope: PhantomData<S>,
+}
+
+impl<S: ServiceScope> FromRequestParts<AppState> for ServiceCaller<S> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let claims = authorize_service(&state.jwt, &parts.headers, S::SCOPE)?;
+        Ok(Self {
+            claims,
+            _scope: PhantomData,
+        })
+    }
+}
+
+/// Verify the bearer service token in `headers` and check it allows `scope`.
+fn authorize_service(
+    jwt: &JwtManager,
+    headers: &HeaderMap,
+    scope: &str,
+) -> Result<ServiceTokenClaims, ApiError> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(ApiError::Unauthorized)?;
+
+    let claims = jwt.verify_service_token(token)?;
+    if !claims.has_scope(scope) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(claims)
+}
+
+/// Parse the guarded resource's id from the path parameters.
+fn resource_id(params: &HashMap<String, String>, resource: Resource) -> Result<Uuid, ApiError> {
+    let specific = match resource {
+        Resource::Contest => "contest_id",
+        Resource::Problem => "problem_id",
+        Resource::Submission => "submission_id",
+    };
+
+    let raw = params
+        .get(specific)
+        .or_else(|| params.get("id"))
+        .ok_or_else(|| ApiError::Internal(format!("Route has no {} parameter", specific)))?;
+
+    raw.parse()
+        .map_err(|_| ApiError::Validation(format!("Invalid {}: '{}'", specific, raw)))
+}
+
+fn resource_name(resource: Resource) -> &'static str {
+    match resource {
+        Resource::Contest => "Contest",
+        Resource::Problem => "Problem",
+        Resource::Submission => "Submission",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Patch {
+        #[serde(default, deserialize_with = "double_option")]
+        organization_id: Option<Option<Uuid>>,
+    }
+
+    #[test]
+    fn test_double_option_tells_absent_from_null() {
+        let absent: Patch = serde_json::from_str("{}").unwrap();
+        assert_eq!(absent.organization_id, None);
+
+        let null: Patch = serde_json::from_str(r#"{"organization_id": null}"#).unwrap();
+        assert_eq!(null.organization_id, Some(None));
+
+        let id = Uuid::new_v4();
+        let set: Patch =
+            serde_json::from_str(&format!(r#"{{"organization_id": "{}"}}"#, id)).unwrap();
+        assert_eq!(set.organization_id, Some(Some(id)));
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn jwt() -> JwtManager {
+        let mut config = crate::config::Config::from_env();
+        config.jwt_secret = "secret-one".to_string();
+        config.jwt_key_id = "k1".to_string();
+        config.jwt_private_key_file = None;
+        config.jwt_previous_keys = String::new();
+        config.jwt_unkeyed_grace_until = None;
+        JwtManager::from_config(&config).unwrap()
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_service_token_with_the_scope_is_authorized() {
+        let jwt = jwt();
+        let token = jwt
+            .generate_service_token("autoscaler", &[QueueRead::SCOPE.to_string()], 60)
+            .unwrap();
+        let claims = authorize_service(&jwt, &bearer(&token), QueueRead::SCOPE).unwrap();
+        assert_eq!(claims.sub, "autoscaler");
+    }
+
+    #[test]
+    fn test_service_token_without_the_scope_is_forbidden() {
+        let jwt = jwt();
+        let token = jwt
+            .generate_service_token("autoscaler", &["queue:manage".to_string()], 60)
+            .unwrap();
+        assert!(matches!(
+            authorize_service(&jwt, &bearer(&token), QueueRead::SCOPE),
+            Err(ApiError::Forbidden)
+        ));
+    }
+
+    #[test]
+    fn test_user_tokens_are_not_service_tokens() {
+        let jwt = jwt();
+        let access = jwt
+            .generate_access_token(Uuid::new_v4(), "admin", "admin", Uuid::new_v4())
+            .unwrap();
+        assert!(matches!(
+            authorize_service(&jwt, &bearer(&access), QueueRead::SCOPE),
+            Err(ApiError::Token(_))
+        ));
+        assert!(matches!(
+            authorize_service(&jwt, &HeaderMap::new(), QueueRead::SCOPE),
+            Err(ApiError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn test_resource_id_prefers_the_specific_parameter() {
+        let contest = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let params = params(&[
+            ("contest_id", &contest.to_string()),
+            ("id", &other.to_string()),
+        ]);
+        assert_eq!(resource_id(&params, Resource::Contest).unwrap(), contest);
+    }
+
+    #[test]
+    fn test_resource_id_falls_back_to_id() {
+        let problem = Uuid::new_v4();
+        let params = params(&[("id", &problem.to_string())]);
+        assert_eq!(resource_id(&params, Resource::Problem).unwrap(), problem);
+    }
+
+    #[test]
+    fn test_resource_id_rejects_missing_and_invalid_ids() {
+        let missing = params(&[("contest_id", &Uuid::new_v4().to_string())]);
+        assert!(matches!(
+            resource_id(&missing, Resource::Submission),
+            Err(ApiError::Internal(_))
+        ));
+
+        let invalid = params(&[("contest_id", "not-a-uuid")]);
+        assert!(matches!(
+            resource_id(&invalid, Resource::Contest),
+            Err(ApiError::Validation(_))
+        ));
+    }
+}
@@ -2,47 +2,73 @@
 //!
 //! The main entry point for the Olympus API Gateway service.
 
+mod compat;
 mod config;
+mod db;
 mod domain;
 mod error;
+mod extract;
 mod middleware;
+mod pagination;
+mod repositories;
+mod scanner;
+mod seed;
+mod server;
 mod state;
+mod tls;
+mod upload;
 
 use std::net::SocketAddr;
+use std::time::Duration;
 
-use axum::http::{header, Method};
 use axum::{
     middleware as axum_middleware,
-    routing::{get, post},
+    routing::{get, post, MethodRouter},
     Router,
 };
+use axum_server::{accept::DefaultAcceptor, tls_rustls::RustlsAcceptor};
 use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
+    compression::CompressionLayer,
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    timeout::RequestBodyTimeoutLayer,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use crate::compat::{api_version_middleware, ApiVersion};
 use crate::config::{create_db_pool, create_redis_pool, Config, RateLimitConfig};
-use crate::domain::{admin, auth, contests, health, problems, submissions, users};
+use crate::db::ReadReplica;
+use crate::domain::{
+    admin, announcements, auth, clarifications, contests, discussions, health, integrity,
+    notifications, organizations, posts, problems, roles, runtimes, sheets, submissions, users,
+};
 use crate::middleware::{
-    auth::{admin_middleware, auth_middleware},
+    auth::{admin_middleware, auth_middleware, optional_auth_middleware},
+    body_limit::body_limit_middleware,
+    caching::{etag_middleware, CachePolicy},
+    cors::cors_layer,
+    locale::locale_middleware,
     rate_limit::*,
+    request_log::request_log_middleware,
 };
+use crate::server::ConnectionLimit;
 use crate::state::AppState;
+use crate::tls::TlsFiles;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
+    // Initialize tracing; LOG_FORMAT=json writes one JSON object per event
+    let json_logs = std::env::var("LOG_FORMAT").is_ok_and(|format| format == "json");
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "vanguard=debug,tower_http=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(json_logs.then(|| tracing_subscriber::fmt::layer().json()))
+        .with((!json_logs).then(tracing_subscriber::fmt::layer))
         .init();
 
     // Load configuration
-    let config = Config::from_env();
+    let config = Config::load()?;
     let rate_limit_config = RateLimitConfig::default();
 
     tracing::info!("Starting Vanguard API Gateway");
@@ -58,27 +84,91 @@ async fn main() -> anyhow::Result<()> {
     sqlx::migrate!("./migrations").run(&db_pool).await?;
     tracing::info!("Migrations complete");
 
+    // Read replica (optional)
+    let replica = match &config.database_replica_url {
+        Some(url) => {
+            tracing::info!("Read replica configured");
+            ReadReplica::connect(url)?
+        }
+        None => ReadReplica::default(),
+    };
+    replica.spawn_monitor();
+
     // Create Redis pool
     tracing::info!("Connecting to Redis...");
     let redis_pool = create_redis_pool(&config.redis_url)?;
     tracing::info!("Redis connected");
 
+    // Load token signing keys
+    let jwt = auth::JwtManager::from_config(&config).map_err(anyhow::Error::msg)?;
+
     // Create app state
-    let state = AppState::new(db_pool, redis_pool, config.clone(), rate_limit_config);
+    let state = AppState::new(
+        db_pool,
+        replica,
+        redis_pool,
+        config.clone(),
+        rate_limit_config,
+        jwt,
+    );
+
+    // `vanguard --seed` fills a development database and exits
+    if std::env::args().any(|arg| arg == "--seed") {
+        return seed::run(&state).await;
+    }
+
+    // Relay judge events to live leaderboard streams
+    submissions::spawn_leaderboard_listener(
+        config.redis_url.clone(),
+        state.leaderboard_events.clone(),
+    );
+
+    // Optional TLS termination, checked before anything is served
+    let tls_files = TlsFiles::from_config(
+        config.tls_cert_path.as_deref(),
+        config.tls_key_path.as_deref(),
+    )?;
 
     // Build router
     let app = create_router(state);
 
     // Start server
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
-    tracing::info!("Listening on {}", addr);
-
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    )
-    .await?;
+    let host: std::net::IpAddr = config
+        .host
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid HOST {:?}: {}", config.host, e))?;
+    let addr = SocketAddr::from((host, config.port));
+    let mut server = axum_server::bind(addr);
+    server::configure_http(server.http_builder(), &config);
+    let make_service = app.into_make_service_with_connect_info::<SocketAddr>();
+
+    match tls_files {
+        Some(files) => {
+            let tls_config = files.load().await?;
+            files.spawn_reload(
+                tls_config.clone(),
+                Duration::from_secs(config.tls_reload_interval_secs),
+            );
+            let acceptor = RustlsAcceptor::new(tls_config)
+                .handshake_timeout(Duration::from_secs(config.header_read_timeout_secs));
+
+            tracing::info!("Listening on {} (HTTPS, HTTP/2)", addr);
+            server
+                .acceptor(ConnectionLimit::new(acceptor, config.max_connections))
+                .serve(make_service)
+                .await?;
+        }
+        None => {
+            tracing::info!("Listening on {}", addr);
+            server
+                .acceptor(ConnectionLimit::new(
+                    DefaultAcceptor::new(),
+                    config.max_connections,
+                ))
+                .serve(make_service)
+                .await?;
+        }
+    }
 
     Ok(())
 }
@@ -113,6 +203,8 @@ fn create_router(state: AppState) -> Router {
     let protected_auth_routes = Router::new()
         .route("/logout", post(auth::logout))
         .route("/me", get(auth::me))
+        .route("/sessions", get(auth::list_sessions))
+        .route("/sessions/{id}", axum::routing::delete(auth::revoke_session))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
@@ -123,14 +215,30 @@ fn create_router(state: AppState) -> Router {
         .merge(public_auth_routes)
         .merge(protected_auth_routes);
 
-    // Public user routes
+    // Public user routes (optional auth: users see all of their own stats)
     let public_user_routes = Router::new()
-        .route("/", get(users::list_users))
         .route("/{id}", get(users::get_user))
-        .route("/{id}/stats", get(users::get_user_stats));
+        .route("/{id}/stats", get(users::get_user_stats))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            optional_auth_middleware,
+        ));
 
     // Protected user routes
     let protected_user_routes = Router::new()
+        .route("/me", axum::routing::delete(users::request_account_deletion))
+        .route("/me/deletion", axum::routing::delete(users::cancel_account_deletion))
+        .route("/me/export", post(users::export_my_data))
+        .route("/me/quota", get(users::get_my_quota))
+        .route(
+            "/me/filters",
+            get(users::list_contest_filters).post(users::create_contest_filter),
+        )
+        .route(
+            "/me/filters/{id}",
+            axum::routing::put(users::update_contest_filter)
+                .delete(users::delete_contest_filter),
+        )
         .route("/{id}", axum::routing::put(users::update_user))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
@@ -142,8 +250,11 @@ fn create_router(state: AppState) -> Router {
         .merge(public_user_routes)
         .merge(protected_user_routes);
 
-    // Public contest routes
-    let public_contest_routes = contests::contest_routes();
+    // Public contest routes (optional auth: private contests are visible to
+    // their owner, collaborators, and hosting organization's members)
+    let public_contest_routes = contests::contest_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), optional_auth_middleware),
+    );
 
     // Protected contest routes
     let protected_contest_routes = contests::protected_contest_routes().layer(
@@ -155,18 +266,106 @@ fn create_router(state: AppState) -> Router {
         .merge(public_contest_routes)
         .merge(protected_contest_routes);
 
-    // Public problem routes
-    let public_problem_routes = problems::problem_routes();
+    // Public organization routes (optional auth: managers see the join code)
+    let public_organization_routes = organizations::organization_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), optional_auth_middleware),
+    );
+
+    // Protected organization routes
+    let protected_organization_routes = organizations::protected_organization_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
+    // Combine organization routes
+    let organization_routes = Router::new()
+        .merge(public_organization_routes)
+        .merge(protected_organization_routes);
+
+    // Public sheet routes (optional auth: members see their organization's sheets)
+    let public_sheet_routes = sheets::sheet_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), optional_auth_middleware),
+    );
+
+    // Protected sheet routes
+    let protected_sheet_routes = sheets::protected_sheet_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
+    // Combine sheet routes
+    let sheet_routes = Router::new()
+        .merge(public_sheet_routes)
+        .merge(protected_sheet_routes);
+
+    // Public problem routes: statements are revalidated by ETag
+    let public_problem_routes = problems::problem_routes()
+        .layer(axum_middleware::from_fn(etag_middleware))
+        .layer(CachePolicy::Revalidate.layer());
 
     // Protected problem routes
     let protected_problem_routes = problems::protected_problem_routes().layer(
         axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
     );
 
+    // Problem statistics (optional auth: private problems and links to
+    // ranked solutions follow the caller's access)
+    let problem_statistics_routes = Router::new()
+        .route("/{id}/statistics", get(problems::get_problem_statistics))
+        .layer(axum_middleware::from_fn(etag_middleware))
+        .layer(CachePolicy::Revalidate.layer())
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            optional_auth_middleware,
+        ));
+
+    // Problem discussions (optional auth: hidden comments are shown to the
+    // problem's staff); posting and editing have their own rate limit
+    let public_comment_routes = discussions::comment_routes()
+        .layer(axum_middleware::from_fn(etag_middleware))
+        .layer(CachePolicy::Revalidate.layer())
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            optional_auth_middleware,
+        ));
+
+    let comment_write_routes = discussions::comment_write_routes()
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            comment_rate_limit_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    let protected_comment_routes = discussions::protected_comment_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
+    // Sample runs: starting one has its own rate limit, polling does not
+    let sample_run_routes = problems::samples::sample_run_routes()
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            sample_run_rate_limit_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    let protected_sample_run_routes = problems::samples::protected_sample_run_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
     // Combine problem routes
     let problem_routes = Router::new()
         .merge(public_problem_routes)
-        .merge(protected_problem_routes);
+        .merge(protected_problem_routes)
+        .merge(problem_statistics_routes)
+        .merge(public_comment_routes)
+        .merge(comment_write_routes)
+        .merge(protected_comment_routes)
+        .merge(sample_run_routes)
+        .merge(protected_sample_run_routes);
 
     // Contest problems routes (nested under contests)
     let contest_problems_routes = Router::new()
@@ -180,6 +379,33 @@ fn create_router(state: AppState) -> Router {
                 axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
             ),
         )
+        .route(
+            "/{contest_id}/problems/order",
+            axum::routing::put(problems::reorder_contest_problems).layer(
+                axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+            ),
+        )
+        .route(
+            "/{contest_id}/problems/bulk",
+            axum::routing::post(problems::bulk_add_problems_to_contest).layer(
+                axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+            ),
+        )
+        .route(
+            "/{contest_id}/problems/{problem_id}/visibility",
+            axum::routing::put(problems::update_contest_problem_visibility).layer(
+                axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+            ),
+        )
+        .route(
+            "/{contest_id}/problems/{problem_id}/baseline",
+            get(problems::get_contest_problem_baseline)
+                .post(problems::judge_contest_problem_baseline)
+                .layer(axum_middleware::from_fn_with_state(
+                    state.clone(),
+                    auth_middleware,
+                )),
+        )
         .route(
             "/{contest_id}/problems/{problem_id}",
             axum::routing::delete(problems::remove_problem_from_contest).layer(
@@ -187,17 +413,128 @@ fn create_router(state: AppState) -> Router {
             ),
         );
 
-    // Contest leaderboard routes
-    let contest_leaderboard_routes = Router::new().route(
-        "/{contest_id}/leaderboard",
-        get(submissions::get_contest_leaderboard),
+    // Contest leaderboard routes (optional auth: private contests' standings
+    // follow contest visibility, staff see unreleased problems): the
+    // snapshot, its CSV export, the scoreboard feed and the certified final
+    // standings are cached briefly and revalidated by ETag, the caller's own
+    // standing is private, the stream is never cached
+    let contest_leaderboard_routes = Router::new()
+        .route(
+            "/{contest_id}/leaderboard",
+            get(submissions::get_contest_leaderboard),
+        )
+        .route(
+            "/{contest_id}/leaderboard.csv",
+            get(submissions::get_leaderboard_csv),
+        )
+        .route(
+            "/{contest_id}/scoreboard",
+            get(submissions::get_scoreboard_feed),
+        )
+        .route(
+            "/{contest_id}/standings/final",
+            get(submissions::get_final_standings),
+        )
+        .layer(axum_middleware::from_fn(etag_middleware))
+        .layer(CachePolicy::ShortLived(5).layer())
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            optional_auth_middleware,
+        ))
+        .merge(
+            Router::new()
+                .route(
+                    "/{contest_id}/leaderboard/me",
+                    get(submissions::get_my_standing),
+                )
+                .layer(axum_middleware::from_fn(etag_middleware))
+                .layer(CachePolicy::Revalidate.layer())
+                .layer(axum_middleware::from_fn_with_state(
+                    state.clone(),
+                    auth_middleware,
+                )),
+        )
+        .route(
+            "/{contest_id}/leaderboard/stream",
+            get(submissions::stream_contest_leaderboard).layer(
+                axum_middleware::from_fn_with_state(state.clone(), optional_auth_middleware),
+            ),
+        )
+        .route(
+            "/{contest_id}/standings/final",
+            post(submissions::certify_final_standings).layer(
+                axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+            ),
+        );
+
+    // Contest integrity routes: telemetry ingestion has its own rate limit
+    let contest_telemetry_routes = integrity::telemetry_routes()
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            telemetry_rate_limit_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    let contest_integrity_routes = integrity::integrity_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
+    // Contest blog posts (optional auth: drafts are for contest staff)
+    let public_contest_post_routes = posts::post_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), optional_auth_middleware),
+    );
+
+    let protected_contest_post_routes = posts::protected_post_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
+    let contest_post_routes = Router::new()
+        .merge(public_contest_post_routes)
+        .merge(protected_contest_post_routes);
+
+    // Contest clarifications (participants ask, staff answer)
+    let contest_clarification_routes = clarifications::clarification_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
+    // Announcements currently showing (optional auth: contest notices follow
+    // contest visibility)
+    let announcement_routes = Router::new()
+        .route("/", get(announcements::list_active_announcements))
+        .layer(axum_middleware::from_fn(etag_middleware))
+        .layer(CachePolicy::Revalidate.layer())
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            optional_auth_middleware,
+        ));
+
+    // The caller's notification feed and delivery preferences
+    let notification_routes = notifications::notification_routes().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
+    );
+
+    let notification_routes_v2 = notifications::notification_routes_v2().layer(
+        axum_middleware::from_fn_with_state(state.clone(), auth_middleware),
     );
 
+    // Public runtime (language) list
+    let runtime_routes = Router::new()
+        .route("/", get(runtimes::list_enabled_runtimes))
+        .layer(CachePolicy::Public(300).layer());
+
     // Submission routes (all protected)
     // Create routes with additional submission rate limit
     let submission_create_routes = Router::new()
         .route("/", post(submissions::create_submission))
-        .route("/upload", post(submissions::create_zip_submission))
+        .route(
+            "/upload",
+            post(submissions::create_zip_submission).layer(upload::body_limit(
+                submissions::handler::MAX_ALLOWED_SUBMISSION_SIZE,
+            )),
+        )
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             submission_rate_limit_middleware,
@@ -209,10 +546,18 @@ fn create_router(state: AppState) -> Router {
 
     // Read-only submission routes (no submission rate limit)
     let submission_read_routes = Router::new()
-        .route("/", get(submissions::list_submissions))
+        .route("/status", post(submissions::get_submission_statuses))
         .route("/{id}", get(submissions::get_submission))
         .route("/{id}/results", get(submissions::get_submission_results))
         .route("/{id}/source", get(submissions::get_submission_source))
+        .route(
+            "/{id}/tests/{test}/input",
+            get(submissions::download_test_input),
+        )
+        .route(
+            "/{id}/tests/{test}/output",
+            get(submissions::download_test_output),
+        )
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
@@ -223,13 +568,20 @@ fn create_router(state: AppState) -> Router {
         .merge(submission_create_routes)
         .merge(submission_read_routes);
 
-    // User submissions route
-    let user_submissions_routes = Router::new().route(
-        "/{id}/submissions",
-        get(submissions::get_user_submissions).layer(axum_middleware::from_fn_with_state(
-            state.clone(),
-            auth_middleware,
-        )),
+    // Lists with numbered pages in v1 and cursors in v2
+    let list_routes_v1 = list_routes(
+        &state,
+        get(users::list_users),
+        get(problems::list_problems),
+        get(submissions::list_submissions),
+        get(submissions::get_user_submissions),
+    );
+    let list_routes_v2 = list_routes(
+        &state,
+        get(users::list_users_v2),
+        get(problems::list_problems_v2),
+        get(submissions::list_submissions_v2),
+        get(submissions::get_user_submissions_v2),
     );
 
     // Admin routes (requires auth + admin role)
@@ -241,17 +593,100 @@ fn create_router(state: AppState) -> Router {
         )
         .route("/users/{id}/ban", post(admin::ban_user))
         .route("/users/{id}/unban", post(admin::unban_user))
+        .route("/users/{id}/merge/{other_id}", post(admin::merge_users))
+        .route("/users/{id}/anonymize", post(admin::anonymize_user))
+        .route(
+            "/users/{id}/quota",
+            get(admin::get_user_quota).put(admin::update_user_quota),
+        )
         .route("/stats", get(admin::system_stats))
+        .route("/storage", get(admin::storage_history))
         .route("/containers", get(admin::list_containers))
         .route("/queue", get(admin::get_queue_info))
         .route("/queue/{id}/rejudge", post(admin::rejudge_submission))
+        .route(
+            "/submissions/{id}/reproduce/{test}",
+            post(admin::reproduce_test),
+        )
+        .route("/submissions/{id}/debug-run", post(admin::debug_run))
+        .route("/reproductions/{id}", get(admin::get_reproduction))
+        .route("/submissions/search", get(admin::search_submissions))
+        .route("/queues/{name}/pause", post(admin::pause_queue))
+        .route("/queues/{name}/resume", post(admin::resume_queue))
+        .route("/queues", get(admin::browse_queues))
+        .route("/queues/{name}/entries", get(admin::list_stream_entries))
+        .route(
+            "/queues/{name}/entries/{entry_id}",
+            axum::routing::delete(admin::delete_stream_entry),
+        )
+        .route(
+            "/queues/{name}/entries/{entry_id}/requeue",
+            post(admin::requeue_stream_entry),
+        )
         .route(
             "/contests/{id}/rejudge",
             post(admin::rejudge_contest),
         )
+        .route(
+            "/contests/{id}/storage",
+            get(admin::get_contest_storage).put(admin::update_contest_storage),
+        )
         .route("/rules", get(admin::list_rules))
         .route("/rules", post(admin::save_rule))
         .route("/rules/{id}", axum::routing::put(admin::update_rule))
+        .route("/cleanup/policies", get(admin::list_cleanup_policies))
+        .route(
+            "/cleanup/policies/{class}",
+            axum::routing::put(admin::update_cleanup_policy),
+        )
+        .route(
+            "/cleanup/dry-run",
+            get(admin::list_cleanup_reports).post(admin::request_cleanup_dry_run),
+        )
+        .route("/service-tokens", post(auth::issue_service_token))
+        .route("/upload-scans", get(admin::list_upload_scans))
+        .route("/problem-backups", get(admin::list_problem_backups))
+        .route(
+            "/problem-backups/{id}/restore",
+            post(admin::restore_problem_backup),
+        )
+        .route("/roles", get(roles::list_roles).post(roles::create_role))
+        .route(
+            "/roles/{name}",
+            axum::routing::put(roles::update_role).delete(roles::delete_role),
+        )
+        .route("/runtimes", get(runtimes::list_runtimes))
+        .route("/runtimes", post(runtimes::create_runtime))
+        .route("/runtimes/images", get(runtimes::list_runtime_images))
+        .route(
+            "/runtimes/{name}",
+            axum::routing::put(runtimes::update_runtime).delete(runtimes::delete_runtime),
+        )
+        .route(
+            "/runtimes/{name}/rollouts",
+            get(runtimes::list_runtime_rollouts),
+        )
+        .route(
+            "/runtimes/{name}/digest",
+            axum::routing::put(runtimes::roll_runtime_digest),
+        )
+        .route(
+            "/contests/{id}/runtime-pins",
+            get(runtimes::list_contest_pins),
+        )
+        .route(
+            "/contests/{id}/runtime-pins/{name}",
+            axum::routing::put(runtimes::roll_contest_pin),
+        )
+        .route(
+            "/announcements",
+            get(announcements::list_announcements).post(announcements::create_announcement),
+        )
+        .route(
+            "/announcements/{id}",
+            axum::routing::put(announcements::update_announcement)
+                .delete(announcements::delete_announcement),
+        )
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             admin_middleware,
@@ -261,47 +696,128 @@ fn create_router(state: AppState) -> Router {
             auth_middleware,
         ));
 
-    // API v1 routes
-    let api_v1 = Router::new()
+    // Internal service routes: each handler checks the scope of a service
+    // token instead of a user session
+    let internal_routes = Router::new().route("/queue", get(admin::service_queue_info));
+
+    // Routes every API version serves with the same handlers
+    let shared_api_routes = Router::new()
         .nest("/auth", auth_routes)
+        .nest("/announcements", announcement_routes)
         .nest("/users", user_routes)
-        .merge(Router::new().nest("/users", user_submissions_routes))
         .nest("/contests", contest_routes)
         .merge(Router::new().nest("/contests", contest_problems_routes))
         .merge(Router::new().nest("/contests", contest_leaderboard_routes))
+        .merge(Router::new().nest("/contests", contest_telemetry_routes))
+        .merge(Router::new().nest("/contests", contest_integrity_routes))
+        .merge(Router::new().nest("/contests", contest_post_routes))
+        .merge(Router::new().nest("/contests", contest_clarification_routes))
+        .nest("/organizations", organization_routes)
         .nest("/problems", problem_routes)
+        .nest("/sheets", sheet_routes)
+        .nest("/runtimes", runtime_routes)
         .nest("/submissions", submission_routes)
         .nest("/admin", admin_routes)
+        .nest("/internal", internal_routes);
+
+    // API v1 routes (stable)
+    let api_v1 = shared_api_routes
+        .clone()
+        .merge(list_routes_v1)
+        .nest("/notifications", notification_routes)
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             api_rate_limit_middleware,
+        ))
+        // Anything without its own policy is not cached
+        .layer(CachePolicy::NoStore.layer())
+        .layer(axum_middleware::from_fn_with_state(
+            ApiVersion::V1,
+            api_version_middleware,
+        ));
+
+    // API v2 routes: v1's handlers where unchanged, cursor-paginated lists
+    // where they differ, and the structured error envelope for everything
+    let api_v2 = shared_api_routes
+        .merge(list_routes_v2)
+        .nest("/notifications", notification_routes_v2)
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            api_rate_limit_middleware,
+        ))
+        .layer(CachePolicy::NoStore.layer())
+        .layer(axum_middleware::from_fn_with_state(
+            ApiVersion::V2,
+            api_version_middleware,
         ));
 
-    // CORS configuration - permissive for development
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods([
-            Method::GET,
-            Method::POST,
-            Method::PUT,
-            Method::DELETE,
-            Method::OPTIONS,
-            Method::PATCH,
-        ])
-        .allow_headers([
-            header::CONTENT_TYPE,
-            header::AUTHORIZATION,
-            header::ACCEPT,
-            header::ORIGIN,
-        ])
-        .expose_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+    // Supported API versions
+    let api_version_routes = Router::new()
+        .route("/api/versions", get(compat::list_api_versions))
+        .layer(CachePolicy::Public(300).layer());
+
+    // CORS allowlist from the configuration
+    let cors = cors_layer(&state.config);
 
     // Main router
     // Note: Layers are applied bottom-up, so CORS must be last to wrap everything
+    // Token verification keys for other services
+    let jwks_routes = Router::new()
+        .route("/.well-known/jwks.json", get(auth::jwks))
+        .layer(CachePolicy::Public(300).layer());
+
     Router::new()
         .nest("/health", health_routes)
+        .merge(jwks_routes)
+        .merge(api_version_routes)
         .nest("/api/v1", api_v1)
+        .nest("/api/v2", api_v2)
+        .layer(axum_middleware::from_fn(locale_middleware))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            body_limit_middleware,
+        ))
+        .layer(RequestBodyTimeoutLayer::new(Duration::from_secs(
+            state.config.body_read_timeout_secs,
+        )))
+        .layer(CompressionLayer::new())
         .layer(cors)
-        .layer(TraceLayer::new_for_http())
+        .layer(axum_middleware::from_fn(request_log_middleware))
+        // Reuse the caller's X-Request-Id or assign one, and echo it back
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .with_state(state)
 }
+
+/// The lists whose pagination differs between API versions, with the layers
+/// each version shares
+fn list_routes(
+    state: &AppState,
+    users: MethodRouter<AppState>,
+    problems: MethodRouter<AppState>,
+    submissions: MethodRouter<AppState>,
+    user_submissions: MethodRouter<AppState>,
+) -> Router<AppState> {
+    let user_list =
+        Router::new()
+            .route("/users", users)
+            .layer(axum_middleware::from_fn_with_state(
+                state.clone(),
+                optional_auth_middleware,
+            ));
+
+    let problem_list = Router::new()
+        .route("/problems", problems)
+        .layer(axum_middleware::from_fn(etag_middleware))
+        .layer(CachePolicy::Revalidate.layer());
+
+    let submission_lists = Router::new()
+        .route("/submissions", submissions)
+        .route("/users/{id}/submissions", user_submissions)
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ));
+
+    user_list.merge(problem_list).merge(submission_lists)
+}
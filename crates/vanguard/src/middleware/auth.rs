@@ -6,10 +6,11 @@ use axum::{
     middleware::Next,
     response::Response,
 };
+use olympus_rules::roles::{Permission, PermissionSet};
 use uuid::Uuid;
 
-use crate::domain::auth::JwtManager;
 use crate::error::ApiError;
+use crate::middleware::request_log::record_user;
 use crate::state::AppState;
 
 /// Authenticated user information extracted from JWT.
@@ -18,6 +19,17 @@ pub struct AuthUser {
     pub id: Uuid,
     pub username: String,
     pub role: String,
+    /// Site permissions granted by the role
+    pub permissions: PermissionSet,
+    /// Session the access token belongs to
+    pub session_id: Uuid,
+}
+
+impl AuthUser {
+    /// Whether the user's role grants `permission`.
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions.contains(permission)
+    }
 }
 
 /// Authentication middleware.
@@ -40,20 +52,18 @@ pub async fn auth_middleware(
         .ok_or(ApiError::Unauthorized)?;
 
     // Verify token
-    let jwt_manager = JwtManager::new(
-        &state.config.jwt_secret,
-        state.config.jwt_access_expiration,
-        state.config.jwt_refresh_expiration,
-    );
-
-    let claims = jwt_manager.verify_access_token(token)?;
+    let claims = state.jwt.verify_access_token(token)?;
 
     // Add user info to request extensions
+    let permissions = state.roles.permissions(&state.db, &claims.role).await;
     let auth_user = AuthUser {
         id: claims.sub,
         username: claims.username,
         role: claims.role,
+        permissions,
+        session_id: claims.session_id,
     };
+    record_user(auth_user.id);
     request.extensions_mut().insert(auth_user);
 
     Ok(next.run(request).await)
@@ -62,7 +72,6 @@ pub async fn auth_middleware(
 /// Optional authentication middleware.
 ///
 /// Extracts JWT if present but doesn't fail if missing.
-#[allow(dead_code)]
 pub async fn optional_auth_middleware(
     State(state): State<AppState>,
     mut request: Request,
@@ -74,18 +83,16 @@ pub async fn optional_auth_middleware(
         .and_then(|value| value.to_str().ok())
     {
         if let Some(token) = auth_header.strip_prefix("Bearer ") {
-            let jwt_manager = JwtManager::new(
-                &state.config.jwt_secret,
-                state.config.jwt_access_expiration,
-                state.config.jwt_refresh_expiration,
-            );
-
-            if let Ok(claims) = jwt_manager.verify_access_token(token) {
+            if let Ok(claims) = state.jwt.verify_access_token(token) {
+                let permissions = state.roles.permissions(&state.db, &claims.role).await;
                 let auth_user = AuthUser {
                     id: claims.sub,
                     username: claims.username,
                     role: claims.role,
+                    permissions,
+                    session_id: claims.session_id,
                 };
+                record_user(auth_user.id);
                 request.extensions_mut().insert(auth_user);
             }
         }
@@ -96,7 +103,7 @@ pub async fn optional_auth_middleware(
 
 /// Admin-only middleware.
 ///
-/// Requires the user to have admin role.
+/// Requires the user's role to grant `manage_platform`.
 pub async fn admin_middleware(
     State(_state): State<AppState>,
     request: Request,
@@ -107,28 +114,7 @@ pub async fn admin_middleware(
         .get::<AuthUser>()
         .ok_or(ApiError::Unauthorized)?;
 
-    if auth_user.role != "admin" {
-        return Err(ApiError::Forbidden);
-    }
-
-    Ok(next.run(request).await)
-}
-
-/// Organizer or Admin middleware.
-///
-/// Requires the user to have admin or organizer role.
-#[allow(dead_code)]
-pub async fn organizer_middleware(
-    State(_state): State<AppState>,
-    request: Request,
-    next: Next,
-) -> Result<Response, ApiError> {
-    let auth_user = request
-        .extensions()
-        .get::<AuthUser>()
-        .ok_or(ApiError::Unauthorized)?;
-
-    if auth_user.role != "admin" && auth_user.role != "organizer" {
+    if !auth_user.can(Permission::ManagePlatform) {
         return Err(ApiError::Forbidden);
     }
 
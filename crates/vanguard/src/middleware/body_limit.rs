@@ -0,0 +1,86 @@
+//! Request body size limits.
+//!
+//! Every request body read through an extractor is capped at
+//! `MAX_BODY_KB`, or at the limit of the longest `BODY_LIMIT_OVERRIDES`
+//! prefix matching its path. Routes that accept files set their own limit
+//! from the file type (contest ZIP limit, binaries, checker suites), which
+//! takes precedence.
+
+use axum::{
+    extract::{DefaultBodyLimit, Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::state::AppState;
+
+/// Body limit in bytes for a request to `path`: that of the longest
+/// matching override prefix, else `default`
+fn limit_for_path(overrides: &[(String, usize)], default: usize, path: &str) -> usize {
+    overrides
+        .iter()
+        .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map_or(default, |(_, limit)| *limit)
+}
+
+/// Apply the configured body limit to the request
+pub async fn body_limit_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let config = &state.config;
+    let limit = limit_for_path(
+        &config.body_limit_overrides,
+        config.max_body_bytes,
+        request.uri().path(),
+    );
+    DefaultBodyLimit::max(limit).apply(&mut request);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides() -> Vec<(String, usize)> {
+        vec![
+            ("/api/v1/problems".to_string(), 4096),
+            ("/api/v1/problems/import".to_string(), 65536),
+            ("/api/v1/admin".to_string(), 512),
+        ]
+    }
+
+    #[test]
+    fn test_limit_without_matching_prefix_is_the_default() {
+        assert_eq!(limit_for_path(&overrides(), 2048, "/api/v1/contests"), 2048);
+        assert_eq!(limit_for_path(&[], 2048, "/api/v1/problems"), 2048);
+    }
+
+    #[test]
+    fn test_limit_uses_matching_prefix() {
+        assert_eq!(
+            limit_for_path(&overrides(), 2048, "/api/v1/admin/users"),
+            512
+        );
+        assert_eq!(
+            limit_for_path(&overrides(), 2048, "/api/v1/problems/abc"),
+            4096
+        );
+    }
+
+    #[test]
+    fn test_limit_prefers_longest_prefix_in_any_order() {
+        assert_eq!(
+            limit_for_path(&overrides(), 2048, "/api/v1/problems/import/zip"),
+            65536
+        );
+        let mut reversed = overrides();
+        reversed.reverse();
+        assert_eq!(
+            limit_for_path(&reversed, 2048, "/api/v1/problems/import/zip"),
+            65536
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! HTTP caching: ETags and per-route `Cache-Control` policies.
+//!
+//! `etag_middleware` tags successful GET responses with a hash of their body
+//! and answers a matching `If-None-Match` with an empty `304 Not Modified`.
+//! The handler still runs, but clients polling statements and leaderboards
+//! during a contest only download them when they change.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+        HeaderName, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+/// Largest body that is buffered to compute an ETag; bigger responses are
+/// passed through untagged.
+const MAX_ETAG_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// `Cache-Control` policy for a class of routes
+#[derive(Debug, Clone, Copy)]
+pub enum CachePolicy {
+    /// Never stored: credentials, personal data, mutations
+    NoStore,
+    /// Per-user data a browser may keep but must revalidate on every use
+    Revalidate,
+    /// Shared data that may be served from cache for a few seconds
+    ShortLived(u32),
+    /// Shared data that rarely changes
+    Public(u32),
+}
+
+impl CachePolicy {
+    fn header_value(self) -> HeaderValue {
+        match self {
+            CachePolicy::NoStore => HeaderValue::from_static("no-store"),
+            CachePolicy::Revalidate => HeaderValue::from_static("private, no-cache"),
+            CachePolicy::ShortLived(secs) => {
+                HeaderValue::from_str(&format!("public, max-age={}, must-revalidate", secs))
+                    .expect("valid header value")
+            }
+            CachePolicy::Public(secs) => {
+                HeaderValue::from_str(&format!("public, max-age={}", secs))
+                    .expect("valid header value")
+            }
+        }
+    }
+
+    /// `Cache-Control` header of this policy, for a handler whose response
+    /// does not fit its route's default (e.g. one only staff may see).
+    pub fn header(self) -> (HeaderName, HeaderValue) {
+        (CACHE_CONTROL, self.header_value())
+    }
+
+    /// Layer applying this policy to responses that do not set their own.
+    ///
+    /// Inner layers win, so a route-specific policy can sit inside a
+    /// router-wide default.
+    pub fn layer(self) -> SetResponseHeaderLayer<HeaderValue> {
+        SetResponseHeaderLayer::if_not_present(CACHE_CONTROL, self.header_value())
+    }
+}
+
+/// Middleware adding a weak ETag to successful GET responses and answering
+/// `If-None-Match` revalidations with `304 Not Modified`.
+pub async fn etag_middleware(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+
+    let response = next.run(request).await;
+    let streaming = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"text/event-stream"));
+    if response.status() != StatusCode::OK || streaming || response.headers().contains_key(ETAG) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_ETAG_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Failed to buffer response for ETag: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // Weak: compression changes the bytes on the wire, not the content
+    let etag = format!("W/\"{:x}\"", Sha256::digest(&bytes));
+    let etag = HeaderValue::from_str(&etag).expect("hex digest is a valid header value");
+
+    if if_none_match.is_some_and(|v| etag_matches(&v, &etag)) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        for name in [CACHE_CONTROL, ETAG] {
+            if let Some(value) = parts.headers.get(&name) {
+                not_modified.headers_mut().insert(name, value.clone());
+            }
+        }
+        not_modified.headers_mut().insert(ETAG, etag);
+        return not_modified;
+    }
+
+    parts.headers.insert(ETAG, etag);
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Weak comparison of an `If-None-Match` list against an ETag.
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(candidates) = if_none_match.to_str() else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag.to_str().unwrap_or_default());
+
+    candidates
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(if_none_match: &'static str, etag: &'static str) -> bool {
+        etag_matches(
+            &HeaderValue::from_static(if_none_match),
+            &HeaderValue::from_static(etag),
+        )
+    }
+
+    #[test]
+    fn test_etag_matches_weak_and_strong_validators() {
+        assert!(matches("W/\"abc\"", "W/\"abc\""));
+        assert!(matches("\"abc\"", "W/\"abc\""));
+        assert!(matches("W/\"abc\"", "\"abc\""));
+        assert!(matches("\"abc\"", "\"abc\""));
+        assert!(!matches("W/\"abd\"", "W/\"abc\""));
+        assert!(!matches("\"abc\"", "W/\"abcd\""));
+    }
+
+    #[test]
+    fn test_etag_matches_any_of_a_list() {
+        assert!(matches("\"x\", W/\"abc\" ,\"y\"", "W/\"abc\""));
+        assert!(!matches("\"x\", \"y\"", "W/\"abc\""));
+    }
+
+    #[test]
+    fn test_etag_matches_wildcard() {
+        assert!(matches("*", "W/\"abc\""));
+        assert!(matches("\"x\", *", "\"abc\""));
+    }
+
+    #[test]
+    fn test_etag_matches_rejects_unreadable_header() {
+        let if_none_match = HeaderValue::from_bytes(b"\"abc\xff\"").unwrap();
+        assert!(!etag_matches(
+            &if_none_match,
+            &HeaderValue::from_static("W/\"abc\"")
+        ));
+    }
+}
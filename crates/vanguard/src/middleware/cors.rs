@@ -0,0 +1,134 @@
+//! Cross-origin resource sharing.
+//!
+//! Browsers may call the API from the origins in `CORS_ALLOWED_ORIGINS`
+//! (any origin with `*`, the development default; none elsewhere unless
+//! listed). Credentials are only allowed with `CORS_ALLOW_CREDENTIALS`, and
+//! never together with `*` outside development, where the request's origin
+//! is echoed back instead.
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use crate::config::Config;
+
+/// Response headers cross-origin callers may read
+const EXPOSED_HEADERS: [HeaderName; 7] = [
+    header::CONTENT_TYPE,
+    header::AUTHORIZATION,
+    header::CONTENT_DISPOSITION,
+    header::CONTENT_LANGUAGE,
+    header::ETAG,
+    header::RETRY_AFTER,
+    HeaderName::from_static("x-request-id"),
+];
+
+/// Check the CORS settings when the configuration is loaded: every origin
+/// and header name must be valid in an HTTP header, and credentials cannot
+/// be allowed from any origin outside development.
+pub fn validate_cors(
+    origins: &[String],
+    headers: &[String],
+    allow_credentials: bool,
+    environment: &str,
+) -> Result<(), String> {
+    let any_origin = origins.iter().any(|o| o == "*");
+    if any_origin && allow_credentials && environment != "development" {
+        return Err(
+            "CORS_ALLOW_CREDENTIALS cannot be combined with CORS_ALLOWED_ORIGINS=*".to_string(),
+        );
+    }
+    if let Some(origin) = origins
+        .iter()
+        .find(|o| *o != "*" && HeaderValue::from_str(o).is_err())
+    {
+        return Err(format!(
+            "Invalid origin in CORS_ALLOWED_ORIGINS: {}",
+            origin
+        ));
+    }
+    if let Some(name) = headers
+        .iter()
+        .find(|h| HeaderName::from_bytes(h.as_bytes()).is_err())
+    {
+        return Err(format!("Invalid header in CORS_ALLOWED_HEADERS: {}", name));
+    }
+    Ok(())
+}
+
+/// Build the CORS layer from a configuration checked by `validate_cors`.
+pub fn cors_layer(config: &Config) -> CorsLayer {
+    let any_origin = config.cors_allowed_origins.iter().any(|o| o == "*");
+
+    let allow_origin = if any_origin && config.cors_allow_credentials {
+        AllowOrigin::mirror_request()
+    } else if any_origin {
+        AllowOrigin::from(Any)
+    } else {
+        AllowOrigin::list(
+            config
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        )
+    };
+
+    let allowed_headers: Vec<HeaderName> = config
+        .cors_allowed_headers
+        .iter()
+        .filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+            Method::PATCH,
+        ])
+        .allow_headers(allowed_headers)
+        .allow_credentials(config.cors_allow_credentials)
+        .expose_headers(EXPOSED_HEADERS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[&str]) -> Vec<String> {
+        items.iter().map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_cors_accepts_listed_origins() {
+        let origins = list(&["https://judge.example.com", "http://localhost:3000"]);
+        let headers = list(&["authorization", "content-type"]);
+        assert!(validate_cors(&origins, &headers, true, "production").is_ok());
+    }
+
+    #[test]
+    fn test_validate_cors_credentials_with_any_origin_only_in_development() {
+        let origins = list(&["*"]);
+        assert!(validate_cors(&origins, &[], true, "development").is_ok());
+        assert!(validate_cors(&origins, &[], false, "production").is_ok());
+
+        let err = validate_cors(&origins, &[], true, "production").unwrap_err();
+        assert!(err.contains("CORS_ALLOW_CREDENTIALS"));
+    }
+
+    #[test]
+    fn test_validate_cors_rejects_invalid_origin() {
+        let origins = list(&["https://judge.example.com", "bad\norigin"]);
+        let err = validate_cors(&origins, &[], false, "production").unwrap_err();
+        assert!(err.contains("CORS_ALLOWED_ORIGINS"));
+    }
+
+    #[test]
+    fn test_validate_cors_rejects_invalid_header() {
+        let headers = list(&["authorization", "x bad"]);
+        let err = validate_cors(&[], &headers, false, "production").unwrap_err();
+        assert_eq!(err, "Invalid header in CORS_ALLOWED_HEADERS: x bad");
+    }
+}
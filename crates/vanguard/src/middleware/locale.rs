@@ -0,0 +1,88 @@
+//! Request locale for error messages.
+//!
+//! `locale_middleware` negotiates the caller's `Accept-Language` against the
+//! locales of the `olympus_common::i18n` catalog and keeps the result for the
+//! rest of the request, where `ApiError` responses pick it up through
+//! `current_locale`. Error responses in another locale than English carry
+//! `Content-Language`.
+
+use axum::{
+    extract::Request,
+    http::{
+        header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, VARY},
+        HeaderValue,
+    },
+    middleware::Next,
+    response::Response,
+};
+use olympus_common::i18n;
+
+use crate::domain::problems::localization::parse_accept_language;
+
+tokio::task_local! {
+    static LOCALE: &'static str;
+}
+
+/// Locale negotiated for the current request (`en` outside one)
+pub fn current_locale() -> &'static str {
+    LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or(i18n::DEFAULT_LOCALE)
+}
+
+/// Negotiate the error message locale from `Accept-Language`
+pub async fn locale_middleware(request: Request, next: Next) -> Response {
+    let locale = request_locale(
+        request
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok()),
+    );
+
+    let mut response = LOCALE.scope(locale, next.run(request)).await;
+
+    let status = response.status();
+    if (status.is_client_error() || status.is_server_error()) && locale != i18n::DEFAULT_LOCALE {
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale));
+        headers.append(VARY, HeaderValue::from_static("Accept-Language"));
+    }
+
+    response
+}
+
+/// Catalog locale for an `Accept-Language` header value
+fn request_locale(accept_language: Option<&str>) -> &'static str {
+    let preferred = accept_language
+        .map(parse_accept_language)
+        .unwrap_or_default();
+    i18n::negotiate(&preferred)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_locale_follows_quality() {
+        assert_eq!(request_locale(Some("de;q=0.5, fr;q=0.9")), "fr");
+        assert_eq!(request_locale(Some("ja, es;q=0.8, de;q=0.9")), "de");
+        // Equal quality keeps header order
+        assert_eq!(request_locale(Some("ru;q=0.7, pt;q=0.7")), "ru");
+        // Excluded languages are skipped
+        assert_eq!(request_locale(Some("fr;q=0, de;q=0.1")), "de");
+    }
+
+    #[test]
+    fn test_request_locale_falls_back_to_english() {
+        assert_eq!(request_locale(None), i18n::DEFAULT_LOCALE);
+        assert_eq!(request_locale(Some("")), i18n::DEFAULT_LOCALE);
+        assert_eq!(request_locale(Some("*")), i18n::DEFAULT_LOCALE);
+        assert_eq!(request_locale(Some("ja, ko;q=0.5")), i18n::DEFAULT_LOCALE);
+    }
+
+    #[test]
+    fn test_current_locale_outside_a_request() {
+        assert_eq!(current_locale(), i18n::DEFAULT_LOCALE);
+    }
+}
@@ -1,4 +1,9 @@
 //! Middleware modules.
 
 pub mod auth;
+pub mod body_limit;
+pub mod caching;
+pub mod cors;
+pub mod locale;
 pub mod rate_limit;
+pub mod request_log;
@@ -1,13 +1,16 @@
 //! Rate limiting middleware using Redis.
 
 use axum::{
-    extract::{ConnectInfo, Request, State},
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, Query, Request, State},
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
+use serde::Deserialize;
 use std::net::SocketAddr;
+use uuid::Uuid;
 
 use crate::error::{ApiError, ApiErrorBody, ApiErrorResponse};
 use crate::middleware::auth::AuthUser;
@@ -28,6 +31,9 @@ pub enum RateLimitTier {
     Login,
     Register,
     Submission,
+    Telemetry,
+    Comment,
+    SampleRun,
     ApiAuth,
     ApiAnon,
 }
@@ -38,6 +44,9 @@ impl RateLimitTier {
             RateLimitTier::Login => "rl:login",
             RateLimitTier::Register => "rl:register",
             RateLimitTier::Submission => "rl:submit",
+            RateLimitTier::Telemetry => "rl:telemetry",
+            RateLimitTier::Comment => "rl:comment",
+            RateLimitTier::SampleRun => "rl:sample",
             RateLimitTier::ApiAuth => "rl:api",
             RateLimitTier::ApiAnon => "rl:api",
         }
@@ -90,11 +99,13 @@ fn add_rate_limit_headers(response: &mut Response, info: &RateLimitInfo) {
 /// Create rate limit exceeded response
 fn rate_limit_response(info: &RateLimitInfo) -> Response {
     let body = ApiErrorResponse {
-        error: ApiErrorBody {
-            code: "RATE_LIMIT_EXCEEDED",
-            message: format!("Rate limit exceeded. Try again in {} seconds.", info.reset),
-            details: None,
-        },
+        error: ApiErrorBody::localized(
+            "RATE_LIMIT_EXCEEDED",
+            "RATE_LIMIT_EXCEEDED",
+            &[],
+            format!("Rate limit exceeded. Try again in {} seconds.", info.reset),
+            None,
+        ),
     };
 
     let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
@@ -241,19 +252,272 @@ pub async fn register_rate_limit_middleware(
     }
 }
 
+/// Largest submission body buffered to find its contest; the JSON extractor
+/// rejects anything bigger anyway
+const MAX_SUBMISSION_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Contest and problem a submission targets: query parameters for ZIP
+/// uploads, JSON body fields otherwise
+#[derive(Debug, Default, Deserialize)]
+struct SubmissionScope {
+    contest_id: Option<Uuid>,
+    problem_id: Option<Uuid>,
+}
+
+/// Organizer-configured limits of a running contest
+#[derive(Debug, sqlx::FromRow)]
+struct ContestRateLimits {
+    submissions_per_hour: Option<i32>,
+    max_submissions_per_problem: Option<i32>,
+    /// Seconds until the contest ends
+    remaining_secs: i64,
+}
+
+/// Read the submission's scope, buffering a JSON body and putting it back.
+async fn submission_scope(request: Request) -> Result<(Request, SubmissionScope), Response> {
+    if let Ok(Query(scope)) = Query::<SubmissionScope>::try_from_uri(request.uri()) {
+        if scope.contest_id.is_some() {
+            return Ok((request, scope));
+        }
+    }
+
+    let is_json = request
+        .headers()
+        .get(axum::http::header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if !is_json {
+        return Ok((request, SubmissionScope::default()));
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = to_bytes(body, MAX_SUBMISSION_BODY_BYTES)
+        .await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE.into_response())?;
+    // Malformed bodies are left for the handler to reject
+    let scope = serde_json::from_slice(&bytes).unwrap_or_default();
+    Ok((Request::from_parts(parts, Body::from(bytes)), scope))
+}
+
+/// Limits of the contest if it is running and overrides anything
+async fn running_contest_limits(
+    state: &AppState,
+    contest_id: Uuid,
+) -> Result<Option<ContestRateLimits>, ApiError> {
+    let limits: Option<ContestRateLimits> = sqlx::query_as(
+        r#"
+        SELECT submissions_per_hour, max_submissions_per_problem,
+               EXTRACT(EPOCH FROM end_time - NOW())::bigint as remaining_secs
+        FROM contests
+        WHERE id = $1 AND NOW() BETWEEN start_time AND end_time
+          AND (submissions_per_hour IS NOT NULL OR max_submissions_per_problem IS NOT NULL)
+        "#,
+    )
+    .bind(contest_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    Ok(limits)
+}
+
+/// Create the response for an exhausted per-problem submission limit
+fn problem_limit_response(limit: u64) -> Response {
+    let body = ApiErrorResponse {
+        error: ApiErrorBody::localized(
+            "RATE_LIMIT_EXCEEDED",
+            "RATE_LIMIT_EXCEEDED",
+            &[],
+            format!(
+                "Submission limit of {} for this problem in this contest reached.",
+                limit
+            ),
+            None,
+        ),
+    };
+
+    (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response()
+}
+
+/// Counters the submission limits are kept in
+trait SubmissionCounters {
+    /// Current count of `key`, without counting a request
+    async fn count(&self, key: &str) -> Result<u64, ApiError>;
+
+    /// Count one request against `key`; a new count lasts `window_secs`
+    async fn record(
+        &self,
+        key: &str,
+        limit: u64,
+        window_secs: u64,
+    ) -> Result<RateLimitInfo, ApiError>;
+}
+
+impl SubmissionCounters for AppState {
+    async fn count(&self, key: &str) -> Result<u64, ApiError> {
+        let mut conn = self.redis.get().await?;
+        let count: Option<u64> = redis::cmd("GET").arg(key).query_async(&mut conn).await?;
+        Ok(count.unwrap_or(0))
+    }
+
+    async fn record(
+        &self,
+        key: &str,
+        limit: u64,
+        window_secs: u64,
+    ) -> Result<RateLimitInfo, ApiError> {
+        check_rate_limit(self, key, limit, window_secs).await
+    }
+}
+
+/// A counted limit: Redis key, limit and window in seconds
+type CountedLimit = (String, u64, u64);
+
+/// The limits a submission is held to
+#[derive(Debug)]
+struct SubmissionLimits {
+    /// The contest's hourly limit, or the global one
+    rate: CountedLimit,
+    /// The contest's cap on submissions to the problem
+    problem: Option<CountedLimit>,
+}
+
+/// Check a submission against its limits before it is handled. The
+/// per-problem cap is only read here, so a request it turns away is not
+/// charged to the hourly limit.
+async fn admit_submission(
+    counters: &impl SubmissionCounters,
+    limits: &SubmissionLimits,
+) -> Result<Option<RateLimitInfo>, Response> {
+    if let Some((key, max, _)) = &limits.problem {
+        match counters.count(key).await {
+            Ok(count) if count >= *max => return Err(problem_limit_response(*max)),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Rate limit check failed: {:?}", e),
+        }
+    }
+
+    let (key, limit, window) = &limits.rate;
+    match counters.record(key, *limit, *window).await {
+        Ok(info) if !info.allowed => Err(rate_limit_response(&info)),
+        Ok(info) => Ok(Some(info)),
+        Err(e) => {
+            tracing::error!("Rate limit check failed: {:?}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Count a handled submission against its problem's cap if it was stored
+async fn record_submission(
+    counters: &impl SubmissionCounters,
+    limits: &SubmissionLimits,
+    status: StatusCode,
+) {
+    if let Some((key, max, window)) = &limits.problem {
+        if counts_against_problem_limit(status) {
+            if let Err(e) = counters.record(key, *max, *window).await {
+                tracing::error!("Rate limit update failed: {:?}", e);
+            }
+        }
+    }
+}
+
 /// Rate limiting middleware for submissions.
+///
+/// While a contest runs, its `submissions_per_hour` replaces the global
+/// limit and `max_submissions_per_problem` caps each problem, both counted
+/// under contest-scoped keys. Other submissions use the global limit. The
+/// per-problem cap is checked first and only stored submissions (a 2xx
+/// response) count against it.
 pub async fn submission_rate_limit_middleware(
     State(state): State<AppState>,
     request: Request,
     next: Next,
 ) -> Response {
-    let key = get_client_key(&request, RateLimitTier::Submission);
+    let (request, scope) = match submission_scope(request).await {
+        Ok(scoped) => scoped,
+        Err(response) => return response,
+    };
+    let user_id = request.extensions().get::<AuthUser>().map(|u| u.id);
+
+    let contest = match (scope.contest_id, user_id) {
+        (Some(contest_id), Some(user_id)) => {
+            match running_contest_limits(&state, contest_id).await {
+                Ok(limits) => limits.map(|limits| {
+                    (
+                        format!("rl:submit:contest:{}:{}", contest_id, user_id),
+                        limits,
+                    )
+                }),
+                Err(e) => {
+                    tracing::error!("Contest rate limit lookup failed: {:?}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let hourly = contest.as_ref().and_then(|(contest_key, limits)| {
+        let per_hour = limits.submissions_per_hour?;
+        Some((contest_key.clone(), per_hour as u64, 3600))
+    });
+    let problem = match (&contest, scope.problem_id) {
+        (Some((contest_key, limits)), Some(problem_id)) => {
+            limits.max_submissions_per_problem.map(|max| {
+                // Outlives the contest, including an extension of up to a day
+                let window = limits.remaining_secs.max(0) as u64 + 86400;
+                (
+                    format!("{}:{}", contest_key, problem_id),
+                    max as u64,
+                    window,
+                )
+            })
+        }
+        _ => None,
+    };
+    let limits = SubmissionLimits {
+        rate: hourly.unwrap_or_else(|| {
+            (
+                get_client_key(&request, RateLimitTier::Submission),
+                state.rate_limit_config.submission_limit,
+                state.rate_limit_config.submission_window,
+            )
+        }),
+        problem,
+    };
+
+    let info = match admit_submission(&state, &limits).await {
+        Ok(info) => info,
+        Err(response) => return response,
+    };
+
+    let mut response = next.run(request).await;
+    record_submission(&state, &limits, response.status()).await;
+    if let Some(info) = info {
+        add_rate_limit_headers(&mut response, &info);
+    }
+    response
+}
+
+/// Whether a submission request with this response status was stored and so
+/// uses up one of the problem's submissions
+fn counts_against_problem_limit(status: StatusCode) -> bool {
+    status.is_success()
+}
+
+/// Rate limiting middleware for anti-cheat telemetry ingestion.
+pub async fn telemetry_rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = get_client_key(&request, RateLimitTier::Telemetry);
 
     match check_rate_limit(
         &state,
         &key,
-        state.rate_limit_config.submission_limit,
-        state.rate_limit_config.submission_window,
+        state.rate_limit_config.telemetry_limit,
+        state.rate_limit_config.telemetry_window,
     )
     .await
     {
@@ -272,3 +536,183 @@ pub async fn submission_rate_limit_middleware(
         }
     }
 }
+
+/// Rate limiting middleware for problem discussion comments.
+pub async fn comment_rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = get_client_key(&request, RateLimitTier::Comment);
+
+    match check_rate_limit(
+        &state,
+        &key,
+        state.rate_limit_config.comment_limit,
+        state.rate_limit_config.comment_window,
+    )
+    .await
+    {
+        Ok(info) => {
+            if !info.allowed {
+                return rate_limit_response(&info);
+            }
+
+            let mut response = next.run(request).await;
+            add_rate_limit_headers(&mut response, &info);
+            response
+        }
+        Err(e) => {
+            tracing::error!("Rate limit check failed: {:?}", e);
+            next.run(request).await
+        }
+    }
+}
+
+/// Rate limiting middleware for sample runs, separate from the submission
+/// limit so trying code on the samples never costs a submission.
+pub async fn sample_run_rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = get_client_key(&request, RateLimitTier::SampleRun);
+
+    match check_rate_limit(
+        &state,
+        &key,
+        state.rate_limit_config.sample_run_limit,
+        state.rate_limit_config.sample_run_window,
+    )
+    .await
+    {
+        Ok(info) => {
+            if !info.allowed {
+                return rate_limit_response(&info);
+            }
+
+            let mut response = next.run(request).await;
+            add_rate_limit_headers(&mut response, &info);
+            response
+        }
+        Err(e) => {
+            tracing::error!("Rate limit check failed: {:?}", e);
+            next.run(request).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_stored_submissions_count_against_problem_limit() {
+        assert!(counts_against_problem_limit(StatusCode::OK));
+        assert!(counts_against_problem_limit(StatusCode::CREATED));
+        assert!(counts_against_problem_limit(StatusCode::ACCEPTED));
+
+        assert!(!counts_against_problem_limit(StatusCode::BAD_REQUEST));
+        assert!(!counts_against_problem_limit(StatusCode::FORBIDDEN));
+        assert!(!counts_against_problem_limit(StatusCode::PAYLOAD_TOO_LARGE));
+        assert!(!counts_against_problem_limit(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!counts_against_problem_limit(
+            StatusCode::INTERNAL_SERVER_ERROR
+        ));
+    }
+
+    #[test]
+    fn test_problem_limit_response() {
+        let response = problem_limit_response(5);
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    /// Counters without expiry, for the submission limits
+    #[derive(Default)]
+    struct MemoryCounters(std::sync::Mutex<std::collections::HashMap<String, u64>>);
+
+    impl MemoryCounters {
+        fn get(&self, key: &str) -> u64 {
+            self.0.lock().unwrap().get(key).copied().unwrap_or(0)
+        }
+    }
+
+    impl SubmissionCounters for MemoryCounters {
+        async fn count(&self, key: &str) -> Result<u64, ApiError> {
+            Ok(self.get(key))
+        }
+
+        async fn record(
+            &self,
+            key: &str,
+            limit: u64,
+            window_secs: u64,
+        ) -> Result<RateLimitInfo, ApiError> {
+            let mut counts = self.0.lock().unwrap();
+            let count = counts.entry(key.to_string()).or_default();
+            *count += 1;
+            Ok(RateLimitInfo {
+                limit,
+                remaining: limit.saturating_sub(*count),
+                reset: window_secs,
+                allowed: *count <= limit,
+            })
+        }
+    }
+
+    fn limits(per_hour: u64, per_problem: Option<u64>) -> SubmissionLimits {
+        SubmissionLimits {
+            rate: ("hourly".to_string(), per_hour, 3600),
+            problem: per_problem.map(|max| ("problem".to_string(), max, 7200)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hourly_limit_turns_submissions_away_once_used_up() {
+        let counters = MemoryCounters::default();
+        let limits = limits(2, None);
+
+        for remaining in [1, 0] {
+            let info = admit_submission(&counters, &limits).await.unwrap().unwrap();
+            assert_eq!(info.remaining, remaining);
+            record_submission(&counters, &limits, StatusCode::CREATED).await;
+        }
+
+        let response = admit_submission(&counters, &limits).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("Retry-After"));
+    }
+
+    #[tokio::test]
+    async fn test_problem_limit_counts_only_stored_submissions() {
+        let counters = MemoryCounters::default();
+        let limits = limits(10, Some(1));
+
+        // Rejected by the handler: admitted, but the problem's one
+        // submission is still there
+        admit_submission(&counters, &limits).await.unwrap();
+        record_submission(&counters, &limits, StatusCode::BAD_REQUEST).await;
+        assert_eq!(counters.get("problem"), 0);
+
+        admit_submission(&counters, &limits).await.unwrap();
+        record_submission(&counters, &limits, StatusCode::CREATED).await;
+        assert_eq!(counters.get("problem"), 1);
+        assert_eq!(counters.get("hourly"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_problem_limit_is_not_charged_to_the_hourly_limit() {
+        let counters = MemoryCounters::default();
+        let limits = limits(10, Some(1));
+
+        admit_submission(&counters, &limits).await.unwrap();
+        record_submission(&counters, &limits, StatusCode::CREATED).await;
+
+        for _ in 0..3 {
+            let response = admit_submission(&counters, &limits).await.unwrap_err();
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        }
+        assert_eq!(counters.get("hourly"), 1);
+        assert_eq!(counters.get("problem"), 1);
+    }
+}
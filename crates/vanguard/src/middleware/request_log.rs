@@ -0,0 +1,167 @@
+//! Structured request logging and request ids.
+//!
+//! Every request gets an id: the caller's `X-Request-Id` if it sent one,
+//! otherwise a fresh UUID. The id is echoed back in the response header and
+//! kept for the rest of the request, where handlers queueing work copy it
+//! into the stream entry (`tag_stream_entry`) so Sisyphus and Minos log it
+//! next to the submission. `request_log_middleware` runs the request in a
+//! span carrying the id, method and route template; the auth middlewares
+//! add the user id, and a `Request completed` event with the status and
+//! latency closes it.
+
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{field, Instrument};
+use uuid::Uuid;
+
+/// Header carrying the request id
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Stream entry field carrying the request id
+pub const REQUEST_ID_FIELD: &str = "request_id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Id of the current request, `None` outside one
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Add the current request id to a stream entry being built with XADD
+pub fn tag_stream_entry(xadd: &mut redis::Cmd) {
+    if let Some(request_id) = current_request_id() {
+        xadd.arg(REQUEST_ID_FIELD).arg(request_id);
+    }
+}
+
+/// Record the authenticated user on the current request's span
+pub fn record_user(user_id: Uuid) {
+    tracing::Span::current().record("user_id", field::display(user_id));
+}
+
+/// Log the request with its id, route, user, status and latency
+pub async fn request_log_middleware(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    // The route template keeps ids out of the field; unmatched requests
+    // fall back to the path
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        route = %route,
+        user_id = field::Empty,
+    );
+
+    let response = REQUEST_ID
+        .scope(request_id, next.run(request))
+        .instrument(span.clone())
+        .await;
+
+    let status = response.status().as_u16();
+    let latency_ms = started.elapsed().as_millis() as u64;
+    span.in_scope(|| {
+        if response.status().is_server_error() {
+            tracing::error!(status, latency_ms, "Request completed");
+        } else {
+            tracing::info!(status, latency_ms, "Request completed");
+        }
+    });
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn packed(cmd: &redis::Cmd) -> String {
+        String::from_utf8_lossy(&cmd.get_packed_command()).into_owned()
+    }
+
+    fn xadd() -> redis::Cmd {
+        let mut cmd = redis::cmd("XADD");
+        cmd.arg("compile_queue")
+            .arg("*")
+            .arg("submission_id")
+            .arg("42");
+        cmd
+    }
+
+    #[test]
+    fn test_no_request_id_outside_a_request() {
+        assert_eq!(current_request_id(), None);
+
+        let mut cmd = xadd();
+        tag_stream_entry(&mut cmd);
+        assert!(!packed(&cmd).contains(REQUEST_ID_FIELD));
+    }
+
+    #[tokio::test]
+    async fn test_tag_stream_entry_adds_the_request_id() {
+        let cmd = REQUEST_ID
+            .scope("req-1".to_string(), async {
+                let mut cmd = xadd();
+                tag_stream_entry(&mut cmd);
+                cmd
+            })
+            .await;
+
+        let packed = packed(&cmd);
+        assert!(packed.ends_with("$10\r\nrequest_id\r\n$5\r\nreq-1\r\n"));
+        assert!(packed.contains("submission_id"));
+    }
+
+    async fn request_id_seen_by_handler(header: Option<&str>) -> String {
+        let app = Router::new()
+            .route(
+                "/probe",
+                get(|| async { current_request_id().unwrap_or_default() }),
+            )
+            .layer(middleware::from_fn(request_log_middleware));
+
+        let mut request = Request::builder().uri("/probe");
+        if let Some(id) = header {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handlers_see_the_request_id_header() {
+        assert_eq!(request_id_seen_by_handler(Some("abc-123")).await, "abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_request_without_id_header_gets_an_empty_id() {
+        // In the router `SetRequestIdLayer` fills the header in first
+        assert_eq!(request_id_seen_by_handler(None).await, "");
+    }
+}
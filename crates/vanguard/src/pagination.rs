@@ -0,0 +1,180 @@
+//! Cursor pagination for `/api/v2` lists.
+//!
+//! v1 lists take `page`/`per_page` and count every row; v2 lists take an
+//! opaque `cursor` and a `limit` instead and page by keyset, newest first:
+//! the cursor is the sort timestamp and id of the last item returned, and
+//! the next page starts strictly after it. Pages stay stable while new rows
+//! arrive and cost the same however deep the client scrolls.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+
+/// Largest `limit` a v2 list accepts
+pub const MAX_CURSOR_LIMIT: u32 = 100;
+
+/// Position after the last item of a page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cursor {
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { at, id }
+    }
+
+    /// Opaque form handed to clients
+    pub fn encode(&self) -> String {
+        format!("{}.{}", self.at.timestamp_micros(), self.id.simple())
+    }
+
+    pub fn decode(value: &str) -> ApiResult<Self> {
+        let invalid = || ApiError::Validation("Invalid cursor".to_string());
+        let (micros, id) = value.split_once('.').ok_or_else(invalid)?;
+        let at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let id = Uuid::try_parse(id).map_err(|_| invalid())?;
+        Ok(Self { at, id })
+    }
+}
+
+/// Query parameters of a cursor-paginated list
+#[derive(Debug, Deserialize)]
+pub struct CursorQuery {
+    /// `next_cursor` of the previous page; the first page if omitted
+    pub cursor: Option<String>,
+    /// Items per page (at most [`MAX_CURSOR_LIMIT`])
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+fn default_limit() -> u32 {
+    20
+}
+
+impl CursorQuery {
+    pub fn limit(&self) -> u32 {
+        self.limit.clamp(1, MAX_CURSOR_LIMIT)
+    }
+
+    /// Rows to fetch: one more than the limit, to tell if a next page exists
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit() as i64 + 1
+    }
+
+    pub fn after(&self) -> ApiResult<Option<Cursor>> {
+        self.cursor.as_deref().map(Cursor::decode).transpose()
+    }
+}
+
+/// One page of a cursor-paginated list
+#[derive(Debug, Serialize)]
+pub struct CursorPage<T> {
+    pub data: Vec<T>,
+    /// Pass as `cursor` for the next page; `None` on the last one
+    pub next_cursor: Option<String>,
+}
+
+impl<T> CursorPage<T> {
+    /// Build a page from up to [`CursorQuery::fetch_limit`] rows in list
+    /// order; `key` gives each row's position.
+    pub fn from_rows<R>(
+        mut rows: Vec<R>,
+        query: &CursorQuery,
+        key: impl Fn(&R) -> Cursor,
+        map: impl FnMut(R) -> T,
+    ) -> Self {
+        let limit = query.limit() as usize;
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(|row| key(row).encode())
+        } else {
+            None
+        };
+
+        Self {
+            data: rows.into_iter().map(map).collect(),
+            next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn cursor(micros: i64) -> Cursor {
+        Cursor::new(
+            Utc.timestamp_micros(micros).unwrap(),
+            Uuid::from_u128(0x1234_5678_9abc_def0_1234_5678_9abc_def0),
+        )
+    }
+
+    fn query(cursor: Option<&str>, limit: u32) -> CursorQuery {
+        CursorQuery {
+            cursor: cursor.map(str::to_string),
+            limit,
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        for c in [cursor(1_767_225_600_123_456), cursor(0), cursor(-1)] {
+            assert_eq!(Cursor::decode(&c.encode()).unwrap(), c);
+        }
+        assert_eq!(
+            cursor(1_767_225_600_123_456).encode(),
+            "1767225600123456.123456789abcdef0123456789abcdef0"
+        );
+    }
+
+    #[test]
+    fn test_malformed_cursors_are_rejected() {
+        let id = Uuid::nil().simple().to_string();
+        for value in [
+            String::new(),
+            "1767225600123456".to_string(),
+            format!(".{id}"),
+            format!("soon.{id}"),
+            format!("{}.{id}", i64::MAX),
+            "1767225600123456.not-a-uuid".to_string(),
+            "1767225600123456.".to_string(),
+        ] {
+            assert!(
+                matches!(Cursor::decode(&value), Err(ApiError::Validation(_))),
+                "accepted {value:?}"
+            );
+        }
+        assert!(query(Some("garbage"), 20).after().is_err());
+        assert_eq!(query(None, 20).after().unwrap(), None);
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        assert_eq!(query(None, 0).limit(), 1);
+        assert_eq!(query(None, 500).limit(), MAX_CURSOR_LIMIT);
+        assert_eq!(query(None, 20).fetch_limit(), 21);
+    }
+
+    #[test]
+    fn test_next_cursor_only_when_more_rows_exist() {
+        let rows = |n: i64| (0..n).rev().map(cursor).collect::<Vec<_>>();
+
+        let page = CursorPage::from_rows(rows(3), &query(None, 2), |c| *c, |c| c.at);
+        assert_eq!(page.data.len(), 2);
+        assert_eq!(page.next_cursor, Some(cursor(1).encode()));
+
+        let page = CursorPage::from_rows(rows(2), &query(None, 2), |c| *c, |c| c.at);
+        assert_eq!(page.data.len(), 2);
+        assert_eq!(page.next_cursor, None);
+    }
+}
@@ -0,0 +1,375 @@
+//! Contest queries.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Contest lifecycle filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContestStatus {
+    Upcoming,
+    Ongoing,
+    Past,
+}
+
+impl ContestStatus {
+    /// Parse a `status` query value; `all` and unknown values mean no filter
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "upcoming" => Some(Self::Upcoming),
+            "ongoing" => Some(Self::Ongoing),
+            "past" => Some(Self::Past),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Upcoming => "upcoming",
+            Self::Ongoing => "ongoing",
+            Self::Past => "past",
+        }
+    }
+}
+
+/// Who is listing contests, for members-only organization contests
+#[derive(Debug, Clone, Copy)]
+pub enum ContestViewer {
+    /// Sees every contest
+    Admin,
+    /// Also sees organization contests they belong to, own or collaborate on
+    User(Uuid),
+    Anonymous,
+}
+
+/// Filters for [`list`] and [`count`]
+#[derive(Debug, Clone)]
+pub struct ContestFilter {
+    viewer: ContestViewer,
+    id: Option<Uuid>,
+    public_only: bool,
+    status: Option<(ContestStatus, DateTime<Utc>)>,
+    rated: Option<bool>,
+    scoring_type: Option<String>,
+    search: Option<String>,
+    owner_id: Option<Uuid>,
+    organization_id: Option<Uuid>,
+    mirror_of: Option<Uuid>,
+}
+
+impl ContestFilter {
+    /// Every contest `viewer` may see
+    pub fn new(viewer: ContestViewer) -> Self {
+        Self {
+            viewer,
+            id: None,
+            public_only: false,
+            status: None,
+            rated: None,
+            scoring_type: None,
+            search: None,
+            owner_id: None,
+            organization_id: None,
+            mirror_of: None,
+        }
+    }
+
+    /// Only the contest with this id
+    pub fn id(mut self, id: Option<Uuid>) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Only public contests
+    pub fn public_only(mut self, public_only: bool) -> Self {
+        self.public_only = public_only;
+        self
+    }
+
+    /// Only contests in `status` at `now`
+    pub fn status(mut self, status: Option<ContestStatus>, now: DateTime<Utc>) -> Self {
+        self.status = status.map(|s| (s, now));
+        self
+    }
+
+    /// Only rated (`true`) or unrated (`false`) contests
+    pub fn rated(mut self, rated: Option<bool>) -> Self {
+        self.rated = rated;
+        self
+    }
+
+    /// Only contests scored with `scoring_type`
+    pub fn scoring_type(mut self, scoring_type: Option<String>) -> Self {
+        self.scoring_type = scoring_type;
+        self
+    }
+
+    /// Titles containing `search` (case-insensitive)
+    pub fn search(mut self, search: Option<String>) -> Self {
+        self.search = search;
+        self
+    }
+
+    pub fn owner(mut self, owner_id: Option<Uuid>) -> Self {
+        self.owner_id = owner_id;
+        self
+    }
+
+    pub fn organization(mut self, organization_id: Option<Uuid>) -> Self {
+        self.organization_id = organization_id;
+        self
+    }
+
+    /// Only mirrors of this contest
+    pub fn mirror_of(mut self, mirror_of: Option<Uuid>) -> Self {
+        self.mirror_of = mirror_of;
+        self
+    }
+
+    /// `ILIKE` pattern for the search term
+    fn search_pattern(&self) -> Option<String> {
+        self.search.as_ref().map(|search| format!("%{}%", search))
+    }
+
+    /// Whether the viewer sees every contest, and who they are otherwise
+    fn viewer(&self) -> (bool, Option<Uuid>) {
+        match self.viewer {
+            ContestViewer::Admin => (true, None),
+            ContestViewer::User(viewer_id) => (false, Some(viewer_id)),
+            ContestViewer::Anonymous => (false, None),
+        }
+    }
+}
+
+/// Contest list entry with owner and participant count
+#[derive(Debug)]
+pub struct ContestListRow {
+    pub id: Uuid,
+    pub title: String,
+    pub short_description: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub scoring_type: String,
+    pub is_public: bool,
+    pub is_rated: bool,
+    pub organization_id: Option<Uuid>,
+    pub mirror_of: Option<Uuid>,
+    pub owner_id: Uuid,
+    pub owner_username: String,
+    pub owner_display_name: Option<String>,
+    pub participant_count: i64,
+}
+
+/// One page of contests matching `filter`, latest start first
+pub async fn list(
+    db: &PgPool,
+    filter: &ContestFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ContestListRow>, sqlx::Error> {
+    let (status, now) = filter.status.map(|(s, now)| (s.as_str(), now)).unzip();
+    let (sees_all, viewer_id) = filter.viewer();
+    sqlx::query_as!(
+        ContestListRow,
+        r#"
+        SELECT
+            c.id, c.title, c.short_description, c.start_time, c.end_time,
+            c.scoring_type, c.is_public, c.is_rated, c.organization_id, c.mirror_of,
+            u.id as owner_id, u.username as owner_username, u.display_name as owner_display_name,
+            COALESCE(p.participant_count, 0) as "participant_count!"
+        FROM contests c
+        JOIN users u ON c.owner_id = u.id
+        LEFT JOIN (
+            SELECT contest_id, COUNT(*) as participant_count
+            FROM contest_participants
+            GROUP BY contest_id
+        ) p ON c.id = p.contest_id
+        WHERE ($1::uuid IS NULL OR c.id = $1)
+          AND (NOT $2 OR c.is_public)
+          AND ($3::text IS NULL
+               OR ($3 = 'upcoming' AND c.start_time > $4)
+               OR ($3 = 'ongoing' AND c.start_time <= $4 AND c.end_time >= $4)
+               OR ($3 = 'past' AND c.end_time < $4))
+          AND ($5::bool IS NULL OR c.is_rated = $5)
+          AND ($6::text IS NULL OR c.scoring_type = $6)
+          AND ($7::text IS NULL OR c.title ILIKE $7)
+          AND ($8::uuid IS NULL OR c.owner_id = $8)
+          AND ($9::uuid IS NULL OR c.organization_id = $9)
+          AND ($10::uuid IS NULL OR c.mirror_of = $10)
+          AND ($11 OR c.organization_id IS NULL OR c.is_public OR c.owner_id = $12
+               OR EXISTS (SELECT 1 FROM organization_members om
+                          WHERE om.organization_id = c.organization_id AND om.user_id = $12)
+               OR EXISTS (SELECT 1 FROM contest_collaborators cc
+                          WHERE cc.contest_id = c.id AND cc.user_id = $12))
+        ORDER BY c.start_time DESC
+        LIMIT $13 OFFSET $14
+        "#,
+        filter.id,
+        filter.public_only,
+        status,
+        now,
+        filter.rated,
+        filter.scoring_type,
+        filter.search_pattern(),
+        filter.owner_id,
+        filter.organization_id,
+        filter.mirror_of,
+        sees_all,
+        viewer_id,
+        limit,
+        offset
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Number of contests matching `filter` (the WHERE clause of [`list`];
+/// keep the two in step)
+pub async fn count(db: &PgPool, filter: &ContestFilter) -> Result<i64, sqlx::Error> {
+    let (status, now) = filter.status.map(|(s, now)| (s.as_str(), now)).unzip();
+    let (sees_all, viewer_id) = filter.viewer();
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM contests c
+        WHERE ($1::uuid IS NULL OR c.id = $1)
+          AND (NOT $2 OR c.is_public)
+          AND ($3::text IS NULL
+               OR ($3 = 'upcoming' AND c.start_time > $4)
+               OR ($3 = 'ongoing' AND c.start_time <= $4 AND c.end_time >= $4)
+               OR ($3 = 'past' AND c.end_time < $4))
+          AND ($5::bool IS NULL OR c.is_rated = $5)
+          AND ($6::text IS NULL OR c.scoring_type = $6)
+          AND ($7::text IS NULL OR c.title ILIKE $7)
+          AND ($8::uuid IS NULL OR c.owner_id = $8)
+          AND ($9::uuid IS NULL OR c.organization_id = $9)
+          AND ($10::uuid IS NULL OR c.mirror_of = $10)
+          AND ($11 OR c.organization_id IS NULL OR c.is_public OR c.owner_id = $12
+               OR EXISTS (SELECT 1 FROM organization_members om
+                          WHERE om.organization_id = c.organization_id AND om.user_id = $12)
+               OR EXISTS (SELECT 1 FROM contest_collaborators cc
+                          WHERE cc.contest_id = c.id AND cc.user_id = $12))
+        "#,
+        filter.id,
+        filter.public_only,
+        status,
+        now,
+        filter.rated,
+        filter.scoring_type,
+        filter.search_pattern(),
+        filter.owner_id,
+        filter.organization_id,
+        filter.mirror_of,
+        sees_all,
+        viewer_id
+    )
+    .fetch_one(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::repositories::testing::TestDb;
+
+    async fn contest(
+        db: &PgPool,
+        title: &str,
+        owner: Uuid,
+        start: DateTime<Utc>,
+        organization_id: Option<Uuid>,
+    ) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO contests (title, start_time, end_time, owner_id, organization_id, is_public) \
+             VALUES ($1, $2, $2 + INTERVAL '1 hour', $3, $4, $4 IS NULL) RETURNING id",
+        )
+        .bind(title)
+        .bind(start)
+        .bind(owner)
+        .bind(organization_id)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    async fn titles(db: &PgPool, filter: &ContestFilter) -> Vec<String> {
+        let mut titles: Vec<String> = list(db, filter, 50, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.title)
+            .collect();
+        titles.sort();
+        assert_eq!(count(db, filter).await.unwrap(), titles.len() as i64);
+        titles
+    }
+
+    #[tokio::test]
+    async fn test_status_and_members_only_contests() {
+        let Some(db) = TestDb::new().await else {
+            return;
+        };
+        let owner = db.user("owner", "organizer").await;
+        let member = db.user("member", "participant").await;
+        let stranger = db.user("stranger", "participant").await;
+        let organization: Uuid = sqlx::query_scalar(
+            "INSERT INTO organizations (slug, name, owner_id) VALUES ('club', 'Club', $1) RETURNING id",
+        )
+        .bind(owner)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO organization_members (organization_id, user_id) VALUES ($1, $2)")
+            .bind(organization)
+            .bind(member)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        contest(&db.pool, "next", owner, now + Duration::days(1), None).await;
+        contest(&db.pool, "live", owner, now - Duration::minutes(30), None).await;
+        contest(&db.pool, "done", owner, now - Duration::days(1), None).await;
+        contest(
+            &db.pool,
+            "club",
+            owner,
+            now - Duration::days(2),
+            Some(organization),
+        )
+        .await;
+
+        let upcoming =
+            ContestFilter::new(ContestViewer::Anonymous).status(Some(ContestStatus::Upcoming), now);
+        assert_eq!(titles(&db.pool, &upcoming).await, ["next"]);
+        let ongoing =
+            ContestFilter::new(ContestViewer::Anonymous).status(Some(ContestStatus::Ongoing), now);
+        assert_eq!(titles(&db.pool, &ongoing).await, ["live"]);
+        let past =
+            ContestFilter::new(ContestViewer::Anonymous).status(Some(ContestStatus::Past), now);
+        assert_eq!(titles(&db.pool, &past).await, ["done"]);
+
+        let everything = ["club", "done", "live", "next"];
+        assert_eq!(
+            titles(&db.pool, &ContestFilter::new(ContestViewer::Admin)).await,
+            everything
+        );
+        assert_eq!(
+            titles(&db.pool, &ContestFilter::new(ContestViewer::User(member))).await,
+            everything
+        );
+        assert_eq!(
+            titles(&db.pool, &ContestFilter::new(ContestViewer::User(stranger))).await,
+            ["done", "live", "next"]
+        );
+        let member_public = ContestFilter::new(ContestViewer::User(member)).public_only(true);
+        assert_eq!(
+            titles(&db.pool, &member_public).await,
+            ["done", "live", "next"]
+        );
+
+        db.drop().await;
+    }
+}
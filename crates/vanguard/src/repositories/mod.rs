@@ -0,0 +1,68 @@
+//! Repository layer.
+//!
+//! List queries with optional filters live here as fixed SQL checked at
+//! compile time (`sqlx::query_as!`) against the migrated schema, or against
+//! the `.sqlx` metadata when no database is reachable. An unset filter is
+//! bound as NULL and its `$n IS NULL OR ...` condition passes, so handlers
+//! never format SQL. Each repository exposes a filter builder plus the
+//! queries that use it.
+
+pub mod contests;
+pub mod problems;
+pub mod sheets;
+pub mod submissions;
+pub mod users;
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::str::FromStr;
+
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    /// A freshly migrated database from `TEST_DATABASE_URL`
+    pub struct TestDb {
+        pub pool: PgPool,
+        admin: PgPool,
+        name: String,
+    }
+
+    impl TestDb {
+        /// `None` (the test is skipped) when `TEST_DATABASE_URL` is unset
+        pub async fn new() -> Option<Self> {
+            let url = std::env::var("TEST_DATABASE_URL").ok()?;
+            let name = format!("vanguard_repo_{}", Uuid::new_v4().simple());
+            let admin = PgPool::connect(&url).await.unwrap();
+            sqlx::query(&format!("CREATE DATABASE {}", name))
+                .execute(&admin)
+                .await
+                .unwrap();
+
+            let options = PgConnectOptions::from_str(&url).unwrap().database(&name);
+            let pool = PgPoolOptions::new().connect_with(options).await.unwrap();
+            sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+            Some(Self { pool, admin, name })
+        }
+
+        pub async fn user(&self, username: &str, role: &str) -> Uuid {
+            sqlx::query_scalar(
+                "INSERT INTO users (id, username, email, password_hash, role) \
+                 VALUES (gen_random_uuid(), $1, $1 || '@example.com', '!', $2) RETURNING id",
+            )
+            .bind(username)
+            .bind(role)
+            .fetch_one(&self.pool)
+            .await
+            .unwrap()
+        }
+
+        pub async fn drop(self) {
+            self.pool.close().await;
+            sqlx::query(&format!("DROP DATABASE {} WITH (FORCE)", self.name))
+                .execute(&self.admin)
+                .await
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Problem queries.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::pagination::Cursor;
+
+/// Who is listing problems
+#[derive(Debug, Clone, Copy)]
+pub enum ProblemViewer {
+    /// Sees every problem
+    Admin,
+    /// Also sees problems they own, problems in contests they own or
+    /// collaborate on, and their organizations' problems
+    User(Uuid),
+    Anonymous,
+}
+
+/// Filters for [`list`] and [`count`]
+#[derive(Debug, Clone)]
+pub struct ProblemFilter {
+    viewer: ProblemViewer,
+    public_only: bool,
+    ids: Option<Vec<Uuid>>,
+    difficulty: Option<String>,
+    tag: Option<String>,
+    owner_id: Option<Uuid>,
+    search: Option<String>,
+    after: Option<Cursor>,
+}
+
+impl ProblemFilter {
+    /// Every problem `viewer` may see
+    pub fn new(viewer: ProblemViewer) -> Self {
+        Self {
+            viewer,
+            public_only: false,
+            ids: None,
+            difficulty: None,
+            tag: None,
+            owner_id: None,
+            search: None,
+            after: None,
+        }
+    }
+
+    /// Only public problems
+    pub fn public_only(mut self, public_only: bool) -> Self {
+        self.public_only = public_only;
+        self
+    }
+
+    /// Only problems with one of these ids
+    pub fn ids(mut self, ids: Vec<Uuid>) -> Self {
+        self.ids = Some(ids);
+        self
+    }
+
+    /// Only problems of this difficulty
+    pub fn difficulty(mut self, difficulty: Option<String>) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Only problems tagged `tag`
+    pub fn tag(mut self, tag: Option<String>) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    pub fn owner(mut self, owner_id: Option<Uuid>) -> Self {
+        self.owner_id = owner_id;
+        self
+    }
+
+    /// Titles containing `search` (case-insensitive)
+    pub fn search(mut self, search: Option<String>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Only problems listed after `cursor` (the last of the previous
+    /// page); [`count`] and [`ids`] ignore it
+    pub fn after(mut self, cursor: Option<Cursor>) -> Self {
+        self.after = cursor;
+        self
+    }
+
+    /// `ILIKE` pattern for the search term
+    fn search_pattern(&self) -> Option<String> {
+        self.search.as_ref().map(|search| format!("%{}%", search))
+    }
+
+    /// Whether the viewer sees every problem, and who they are otherwise.
+    /// `public_only` hides private problems from everyone.
+    fn viewer(&self) -> (bool, Option<Uuid>) {
+        match (self.public_only, self.viewer) {
+            (false, ProblemViewer::Admin) => (true, None),
+            (false, ProblemViewer::User(viewer_id)) => (false, Some(viewer_id)),
+            _ => (false, None),
+        }
+    }
+}
+
+/// Problem list entry with owner
+#[derive(Debug)]
+pub struct ProblemListRow {
+    pub id: Uuid,
+    pub title: String,
+    pub difficulty: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub time_limit_ms: i32,
+    pub memory_limit_kb: i32,
+    pub max_threads: i32,
+    pub network_allowed: bool,
+    pub max_score: i32,
+    pub is_public: bool,
+    pub organization_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub owner_id: Uuid,
+    pub owner_username: String,
+    pub owner_display_name: Option<String>,
+}
+
+/// One page of problems matching `filter`, newest first
+pub async fn list(
+    db: &PgPool,
+    filter: &ProblemFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ProblemListRow>, sqlx::Error> {
+    let (sees_all, viewer_id) = filter.viewer();
+    sqlx::query_as!(
+        ProblemListRow,
+        r#"
+        SELECT
+            p.id, p.title, p.difficulty, p.tags, p.time_limit_ms, p.memory_limit_kb,
+            p.max_threads, p.network_allowed,
+            p.max_score, p.is_public, p.organization_id, p.created_at,
+            u.id as owner_id, u.username as owner_username, u.display_name as owner_display_name
+        FROM problems p
+        JOIN users u ON p.owner_id = u.id
+        WHERE ($1::uuid[] IS NULL OR p.id = ANY($1))
+          AND ($2::text IS NULL OR p.difficulty = $2)
+          AND ($3::text IS NULL OR $3 = ANY(p.tags))
+          AND ($4::uuid IS NULL OR p.owner_id = $4)
+          AND ($5::text IS NULL OR p.title ILIKE $5)
+          AND ($6 OR p.is_public = true OR p.owner_id = $7
+               OR EXISTS (SELECT 1 FROM contest_problems cp
+                          JOIN contests c ON c.id = cp.contest_id
+                          WHERE cp.problem_id = p.id AND c.owner_id = $7)
+               OR EXISTS (SELECT 1 FROM contest_problems cp
+                          JOIN contest_collaborators cc ON cc.contest_id = cp.contest_id
+                          WHERE cp.problem_id = p.id AND cc.user_id = $7)
+               OR EXISTS (SELECT 1 FROM organization_members om
+                          WHERE om.organization_id = p.organization_id AND om.user_id = $7))
+          AND ($8::timestamptz IS NULL OR (p.created_at, p.id) < ($8, $9))
+        ORDER BY p.created_at DESC, p.id DESC
+        LIMIT $10 OFFSET $11
+        "#,
+        filter.ids.as_deref(),
+        filter.difficulty,
+        filter.tag,
+        filter.owner_id,
+        filter.search_pattern(),
+        sees_all,
+        viewer_id,
+        filter.after.map(|c| c.at),
+        filter.after.map(|c| c.id),
+        limit,
+        offset
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Number of problems matching `filter` (the WHERE clause of [`list`]; keep
+/// the three queries in step)
+pub async fn count(db: &PgPool, filter: &ProblemFilter) -> Result<i64, sqlx::Error> {
+    let (sees_all, viewer_id) = filter.viewer();
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM problems p
+        WHERE ($1::uuid[] IS NULL OR p.id = ANY($1))
+          AND ($2::text IS NULL OR p.difficulty = $2)
+          AND ($3::text IS NULL OR $3 = ANY(p.tags))
+          AND ($4::uuid IS NULL OR p.owner_id = $4)
+          AND ($5::text IS NULL OR p.title ILIKE $5)
+          AND ($6 OR p.is_public = true OR p.owner_id = $7
+               OR EXISTS (SELECT 1 FROM contest_problems cp
+                          JOIN contests c ON c.id = cp.contest_id
+                          WHERE cp.problem_id = p.id AND c.owner_id = $7)
+               OR EXISTS (SELECT 1 FROM contest_problems cp
+                          JOIN contest_collaborators cc ON cc.contest_id = cp.contest_id
+                          WHERE cp.problem_id = p.id AND cc.user_id = $7)
+               OR EXISTS (SELECT 1 FROM organization_members om
+                          WHERE om.organization_id = p.organization_id AND om.user_id = $7))
+        "#,
+        filter.ids.as_deref(),
+        filter.difficulty,
+        filter.tag,
+        filter.owner_id,
+        filter.search_pattern(),
+        sees_all,
+        viewer_id
+    )
+    .fetch_one(db)
+    .await
+}
+
+/// Ids of the problems matching `filter`
+pub async fn ids(db: &PgPool, filter: &ProblemFilter) -> Result<Vec<Uuid>, sqlx::Error> {
+    let (sees_all, viewer_id) = filter.viewer();
+    sqlx::query_scalar!(
+        r#"
+        SELECT p.id
+        FROM problems p
+        WHERE ($1::uuid[] IS NULL OR p.id = ANY($1))
+          AND ($2::text IS NULL OR p.difficulty = $2)
+          AND ($3::text IS NULL OR $3 = ANY(p.tags))
+          AND ($4::uuid IS NULL OR p.owner_id = $4)
+          AND ($5::text IS NULL OR p.title ILIKE $5)
+          AND ($6 OR p.is_public = true OR p.owner_id = $7
+               OR EXISTS (SELECT 1 FROM contest_problems cp
+                          JOIN contests c ON c.id = cp.contest_id
+                          WHERE cp.problem_id = p.id AND c.owner_id = $7)
+               OR EXISTS (SELECT 1 FROM contest_problems cp
+                          JOIN contest_collaborators cc ON cc.contest_id = cp.contest_id
+                          WHERE cp.problem_id = p.id AND cc.user_id = $7)
+               OR EXISTS (SELECT 1 FROM organization_members om
+                          WHERE om.organization_id = p.organization_id AND om.user_id = $7))
+        "#,
+        filter.ids.as_deref(),
+        filter.difficulty,
+        filter.tag,
+        filter.owner_id,
+        filter.search_pattern(),
+        sees_all,
+        viewer_id
+    )
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::testing::TestDb;
+
+    async fn problem(db: &PgPool, title: &str, owner: Uuid, is_public: bool) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO problems (title, description, owner_id, is_public) \
+             VALUES ($1, '', $2, $3) RETURNING id",
+        )
+        .bind(title)
+        .bind(owner)
+        .bind(is_public)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    async fn titles(db: &PgPool, filter: &ProblemFilter) -> Vec<String> {
+        let mut titles: Vec<String> = list(db, filter, 50, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.title)
+            .collect();
+        titles.sort();
+        assert_eq!(count(db, filter).await.unwrap(), titles.len() as i64);
+        assert_eq!(ids(db, filter).await.unwrap().len(), titles.len());
+        titles
+    }
+
+    #[tokio::test]
+    async fn test_private_problems_are_listed_to_those_granted_them() {
+        let Some(db) = TestDb::new().await else {
+            return;
+        };
+        let setter = db.user("setter", "organizer").await;
+        let tester = db.user("tester", "participant").await;
+        let stranger = db.user("stranger", "participant").await;
+
+        problem(&db.pool, "draft", setter, false).await;
+        let shared = problem(&db.pool, "shared", setter, false).await;
+        let public = problem(&db.pool, "public", setter, true).await;
+
+        let contest: Uuid = sqlx::query_scalar(
+            "INSERT INTO contests (title, start_time, end_time, owner_id) \
+             VALUES ('round', NOW(), NOW() + INTERVAL '1 hour', $1) RETURNING id",
+        )
+        .bind(setter)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO contest_problems (contest_id, problem_id, problem_code) VALUES ($1, $2, 'A')",
+        )
+        .bind(contest)
+        .bind(shared)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO contest_collaborators (contest_id, user_id) VALUES ($1, $2)")
+            .bind(contest)
+            .bind(tester)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let all = ["draft", "public", "shared"];
+        assert_eq!(
+            titles(&db.pool, &ProblemFilter::new(ProblemViewer::Admin)).await,
+            all
+        );
+        assert_eq!(
+            titles(&db.pool, &ProblemFilter::new(ProblemViewer::User(setter))).await,
+            all
+        );
+        assert_eq!(
+            titles(&db.pool, &ProblemFilter::new(ProblemViewer::User(tester))).await,
+            ["public", "shared"]
+        );
+        assert_eq!(
+            titles(&db.pool, &ProblemFilter::new(ProblemViewer::User(stranger))).await,
+            ["public"]
+        );
+        assert_eq!(
+            titles(&db.pool, &ProblemFilter::new(ProblemViewer::Anonymous)).await,
+            ["public"]
+        );
+        let public_only = ProblemFilter::new(ProblemViewer::Admin).public_only(true);
+        assert_eq!(titles(&db.pool, &public_only).await, ["public"]);
+
+        let picked = ProblemFilter::new(ProblemViewer::User(tester)).ids(vec![shared, public]);
+        assert_eq!(titles(&db.pool, &picked).await, ["public", "shared"]);
+        let searched = ProblemFilter::new(ProblemViewer::Admin).search(Some("AR".to_string()));
+        assert_eq!(titles(&db.pool, &searched).await, ["shared"]);
+
+        db.drop().await;
+    }
+}
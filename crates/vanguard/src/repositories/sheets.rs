@@ -0,0 +1,140 @@
+//! Problem sheet queries.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Who is listing sheets
+#[derive(Debug, Clone, Copy)]
+pub enum SheetViewer {
+    /// Sees every sheet
+    Admin(Uuid),
+    /// Also sees sheets they own and their organizations' sheets
+    User(Uuid),
+    Anonymous,
+}
+
+impl SheetViewer {
+    fn user_id(&self) -> Option<Uuid> {
+        match *self {
+            SheetViewer::Admin(id) | SheetViewer::User(id) => Some(id),
+            SheetViewer::Anonymous => None,
+        }
+    }
+}
+
+/// Filters for [`list`] and [`count`]
+#[derive(Debug, Clone)]
+pub struct SheetFilter {
+    viewer: SheetViewer,
+    owner_id: Option<Uuid>,
+    organization_id: Option<Uuid>,
+}
+
+impl SheetFilter {
+    /// Every sheet `viewer` may see
+    pub fn new(viewer: SheetViewer) -> Self {
+        Self {
+            viewer,
+            owner_id: None,
+            organization_id: None,
+        }
+    }
+
+    pub fn owner(mut self, owner_id: Option<Uuid>) -> Self {
+        self.owner_id = owner_id;
+        self
+    }
+
+    pub fn organization(mut self, organization_id: Option<Uuid>) -> Self {
+        self.organization_id = organization_id;
+        self
+    }
+}
+
+/// Sheet list entry with owner and the viewer's progress
+#[derive(Debug)]
+pub struct SheetListRow {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub organization_id: Option<Uuid>,
+    pub owner_id: Uuid,
+    pub owner_username: String,
+    pub owner_display_name: Option<String>,
+    pub problem_count: i64,
+    /// Problems the viewer has done (0 for anonymous viewers)
+    pub completed_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One page of sheets matching `filter`, most recently updated first.
+/// A sheet item is done when the viewer marked it done or has an accepted
+/// submission for the problem anywhere.
+pub async fn list(
+    db: &PgPool,
+    filter: &SheetFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SheetListRow>, sqlx::Error> {
+    sqlx::query_as!(
+        SheetListRow,
+        r#"
+        SELECT
+            ps.id, ps.title, ps.description, ps.is_public, ps.organization_id,
+            u.id as owner_id, u.username as owner_username, u.display_name as owner_display_name,
+            (SELECT COUNT(*) FROM problem_sheet_items i WHERE i.sheet_id = ps.id)
+                as "problem_count!",
+            (SELECT COUNT(*) FROM problem_sheet_items i
+             WHERE i.sheet_id = ps.id
+               AND (EXISTS (SELECT 1 FROM problem_sheet_progress sp
+                            WHERE sp.sheet_id = i.sheet_id AND sp.problem_id = i.problem_id
+                              AND sp.user_id = $1)
+                    OR EXISTS (SELECT 1 FROM submissions s
+                               WHERE s.problem_id = i.problem_id AND s.status = 'accepted'
+                                 AND s.user_id = $1))) as "completed_count!",
+            ps.created_at, ps.updated_at
+        FROM problem_sheets ps
+        JOIN users u ON ps.owner_id = u.id
+        WHERE ($2 OR ps.is_public = true OR ps.owner_id = $1
+               OR EXISTS (SELECT 1 FROM organization_members om
+                          WHERE om.organization_id = ps.organization_id AND om.user_id = $1))
+          AND ($3::uuid IS NULL OR ps.owner_id = $3)
+          AND ($4::uuid IS NULL OR ps.organization_id = $4)
+        ORDER BY ps.updated_at DESC
+        LIMIT $5 OFFSET $6
+        "#,
+        filter.viewer.user_id(),
+        matches!(filter.viewer, SheetViewer::Admin(_)),
+        filter.owner_id,
+        filter.organization_id,
+        limit,
+        offset
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Number of sheets matching `filter` (the WHERE clause of [`list`]; keep
+/// the two in step)
+pub async fn count(db: &PgPool, filter: &SheetFilter) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM problem_sheets ps
+        WHERE ($2 OR ps.is_public = true OR ps.owner_id = $1
+               OR EXISTS (SELECT 1 FROM organization_members om
+                          WHERE om.organization_id = ps.organization_id AND om.user_id = $1))
+          AND ($3::uuid IS NULL OR ps.owner_id = $3)
+          AND ($4::uuid IS NULL OR ps.organization_id = $4)
+        "#,
+        filter.viewer.user_id(),
+        matches!(filter.viewer, SheetViewer::Admin(_)),
+        filter.owner_id,
+        filter.organization_id
+    )
+    .fetch_one(db)
+    .await
+}
@@ -0,0 +1,273 @@
+//! Submission queries.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::pagination::Cursor;
+
+/// Filters for [`list`] and [`count`]
+#[derive(Debug, Clone, Default)]
+pub struct SubmissionFilter {
+    contest_id: Option<Uuid>,
+    problem_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    status: Option<String>,
+    language: Option<String>,
+    practice: Option<bool>,
+    test: Option<bool>,
+    viewer: Option<Uuid>,
+    after: Option<Cursor>,
+}
+
+impl SubmissionFilter {
+    pub fn contest(mut self, contest_id: Option<Uuid>) -> Self {
+        self.contest_id = contest_id;
+        self
+    }
+
+    pub fn problem(mut self, problem_id: Option<Uuid>) -> Self {
+        self.problem_id = problem_id;
+        self
+    }
+
+    pub fn user(mut self, user_id: Option<Uuid>) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    pub fn status(mut self, status: Option<String>) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Only practice (`Some(true)`) or only in-contest (`Some(false)`)
+    /// submissions
+    pub fn practice(mut self, practice: Option<bool>) -> Self {
+        self.practice = practice;
+        self
+    }
+
+    /// Only test (`Some(true)`) or only non-test (`Some(false)`) submissions
+    pub fn test(mut self, test: Option<bool>) -> Self {
+        self.test = test;
+        self
+    }
+
+    /// Leave out test submissions unless `viewer` made them or is on their
+    /// contest's team (its owner or a collaborator). `None` shows them all.
+    pub fn visible_to(mut self, viewer: Option<Uuid>) -> Self {
+        self.viewer = viewer;
+        self
+    }
+
+    /// Only submissions listed after `cursor` (the last of the previous
+    /// page); [`count`] ignores it
+    pub fn after(mut self, cursor: Option<Cursor>) -> Self {
+        self.after = cursor;
+        self
+    }
+}
+
+/// Submission list entry with user, problem and contest titles
+#[derive(Debug)]
+pub struct SubmissionListRow {
+    pub id: Uuid,
+    pub contest_id: Option<Uuid>,
+    pub problem_id: Uuid,
+    pub user_id: Uuid,
+    pub language: Option<String>,
+    pub status: String,
+    pub score: Option<f64>,
+    pub max_time_ms: Option<i32>,
+    pub max_memory_kb: Option<i32>,
+    pub is_practice: bool,
+    pub is_test: bool,
+    pub submitted_at: DateTime<Utc>,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub problem_title: String,
+    pub problem_code: Option<String>,
+    pub contest_title: Option<String>,
+}
+
+/// One page of submissions matching `filter`, newest first
+pub async fn list(
+    db: &PgPool,
+    filter: &SubmissionFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SubmissionListRow>, sqlx::Error> {
+    sqlx::query_as!(
+        SubmissionListRow,
+        r#"
+        SELECT
+            s.id, s.contest_id, s.problem_id, s.user_id,
+            s.language, s.status, s.score,
+            s.max_time_ms, s.max_memory_kb, s.is_practice, s.is_test, s.submitted_at,
+            u.username, u.display_name,
+            p.title as problem_title, cp.problem_code as "problem_code?",
+            c.title as "contest_title?"
+        FROM submissions s
+        JOIN users u ON u.id = s.user_id
+        JOIN problems p ON p.id = s.problem_id
+        LEFT JOIN contests c ON c.id = s.contest_id
+        LEFT JOIN contest_problems cp ON cp.contest_id = s.contest_id AND cp.problem_id = s.problem_id
+        WHERE ($1::uuid IS NULL OR s.contest_id = $1)
+          AND ($2::uuid IS NULL OR s.problem_id = $2)
+          AND ($3::uuid IS NULL OR s.user_id = $3)
+          AND ($4::text IS NULL OR s.status = $4)
+          AND ($5::text IS NULL OR s.language = $5)
+          AND ($6::bool IS NULL OR s.is_practice = $6)
+          AND ($7::bool IS NULL OR s.is_test = $7)
+          AND ($8::uuid IS NULL OR NOT s.is_test OR s.user_id = $8
+               OR EXISTS (SELECT 1 FROM contests tc WHERE tc.id = s.contest_id
+                          AND (tc.owner_id = $8
+                               OR EXISTS (SELECT 1 FROM contest_collaborators tcc
+                                          WHERE tcc.contest_id = tc.id AND tcc.user_id = $8))))
+          AND ($9::timestamptz IS NULL OR (s.submitted_at, s.id) < ($9, $10))
+        ORDER BY s.submitted_at DESC, s.id DESC
+        LIMIT $11 OFFSET $12
+        "#,
+        filter.contest_id,
+        filter.problem_id,
+        filter.user_id,
+        filter.status,
+        filter.language,
+        filter.practice,
+        filter.test,
+        filter.viewer,
+        filter.after.map(|c| c.at),
+        filter.after.map(|c| c.id),
+        limit,
+        offset
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Number of submissions matching `filter` (the WHERE clause of [`list`];
+/// keep the two in step)
+pub async fn count(db: &PgPool, filter: &SubmissionFilter) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM submissions s
+        WHERE ($1::uuid IS NULL OR s.contest_id = $1)
+          AND ($2::uuid IS NULL OR s.problem_id = $2)
+          AND ($3::uuid IS NULL OR s.user_id = $3)
+          AND ($4::text IS NULL OR s.status = $4)
+          AND ($5::text IS NULL OR s.language = $5)
+          AND ($6::bool IS NULL OR s.is_practice = $6)
+          AND ($7::bool IS NULL OR s.is_test = $7)
+          AND ($8::uuid IS NULL OR NOT s.is_test OR s.user_id = $8
+               OR EXISTS (SELECT 1 FROM contests tc WHERE tc.id = s.contest_id
+                          AND (tc.owner_id = $8
+                               OR EXISTS (SELECT 1 FROM contest_collaborators tcc
+                                          WHERE tcc.contest_id = tc.id AND tcc.user_id = $8))))
+        "#,
+        filter.contest_id,
+        filter.problem_id,
+        filter.user_id,
+        filter.status,
+        filter.language,
+        filter.practice,
+        filter.test,
+        filter.viewer
+    )
+    .fetch_one(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::testing::TestDb;
+
+    async fn problem(db: &PgPool, owner: Uuid) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO problems (title, description, owner_id) VALUES ('p', 'd', $1) RETURNING id",
+        )
+        .bind(owner)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_practice_submissions_list_without_contest_columns() {
+        let Some(db) = TestDb::new().await else {
+            return;
+        };
+        let user = db.user("coder", "participant").await;
+        let problem = problem(&db.pool, user).await;
+        sqlx::query(
+            "INSERT INTO submissions (problem_id, user_id, is_practice) VALUES ($1, $2, TRUE)",
+        )
+        .bind(problem)
+        .bind(user)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let rows = list(&db.pool, &SubmissionFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].problem_code, None);
+        assert_eq!(rows[0].contest_title, None);
+        assert_eq!(
+            count(&db.pool, &SubmissionFilter::default()).await.unwrap(),
+            1
+        );
+
+        db.drop().await;
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pages_cover_every_submission_once() {
+        let Some(db) = TestDb::new().await else {
+            return;
+        };
+        let user = db.user("coder", "participant").await;
+        let problem = problem(&db.pool, user).await;
+        // Two pairs share a timestamp, so pages must break ties by id
+        for minutes in [1, 2, 2, 3, 3] {
+            sqlx::query(
+                "INSERT INTO submissions (problem_id, user_id, submitted_at) \
+                 VALUES ($1, $2, TIMESTAMPTZ '2026-01-01' + make_interval(mins => $3))",
+            )
+            .bind(problem)
+            .bind(user)
+            .bind(minutes)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        }
+
+        let all = list(&db.pool, &SubmissionFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 5);
+
+        let mut paged = Vec::new();
+        let mut after = None;
+        loop {
+            let filter = SubmissionFilter::default().after(after);
+            let page = list(&db.pool, &filter, 2, 0).await.unwrap();
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = Some(Cursor::new(last.submitted_at, last.id));
+            paged.extend(page.into_iter().map(|row| row.id));
+        }
+        assert_eq!(paged, all.iter().map(|row| row.id).collect::<Vec<_>>());
+
+        db.drop().await;
+    }
+}
@@ -0,0 +1,143 @@
+//! User queries.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::pagination::Cursor;
+
+/// Filters for [`list`] and [`count`]
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    role: Option<String>,
+    search: Option<String>,
+    after: Option<Cursor>,
+}
+
+impl UserFilter {
+    /// Every user
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only users with this role
+    pub fn role(mut self, role: Option<String>) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Usernames or display names containing `search` (case-insensitive)
+    pub fn search(mut self, search: Option<String>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Only users listed after `cursor` (the last of the previous page);
+    /// [`count`] ignores it
+    pub fn after(mut self, cursor: Option<Cursor>) -> Self {
+        self.after = cursor;
+        self
+    }
+
+    /// `ILIKE` pattern for the search term
+    fn search_pattern(&self) -> Option<String> {
+        self.search.as_ref().map(|search| format!("%{}%", search))
+    }
+}
+
+/// User list entry
+#[derive(Debug)]
+pub struct UserListRow {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One page of users matching `filter`, newest first
+pub async fn list(
+    db: &PgPool,
+    filter: &UserFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<UserListRow>, sqlx::Error> {
+    sqlx::query_as!(
+        UserListRow,
+        r#"
+        SELECT id, username, display_name, role, created_at
+        FROM users
+        WHERE ($1::text IS NULL OR role = $1)
+          AND ($2::text IS NULL OR username ILIKE $2 OR display_name ILIKE $2)
+          AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $5 OFFSET $6
+        "#,
+        filter.role,
+        filter.search_pattern(),
+        filter.after.map(|c| c.at),
+        filter.after.map(|c| c.id),
+        limit,
+        offset
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Number of users matching `filter` (the WHERE clause of [`list`]; keep
+/// the two in step)
+pub async fn count(db: &PgPool, filter: &UserFilter) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM users
+        WHERE ($1::text IS NULL OR role = $1)
+          AND ($2::text IS NULL OR username ILIKE $2 OR display_name ILIKE $2)
+        "#,
+        filter.role,
+        filter.search_pattern()
+    )
+    .fetch_one(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::testing::TestDb;
+
+    async fn usernames(db: &PgPool, filter: &UserFilter) -> Vec<String> {
+        let mut names: Vec<String> = list(db, filter, 50, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.username)
+            .collect();
+        names.sort();
+        assert_eq!(count(db, filter).await.unwrap(), names.len() as i64);
+        names
+    }
+
+    #[tokio::test]
+    async fn test_role_and_search_filters_combine() {
+        let Some(db) = TestDb::new().await else {
+            return;
+        };
+        db.user("annika", "admin").await;
+        db.user("joanna", "participant").await;
+        db.user("bob", "admin").await;
+
+        assert_eq!(
+            usernames(&db.pool, &UserFilter::new()).await,
+            ["annika", "bob", "joanna"]
+        );
+        let admins = UserFilter::new().role(Some("admin".to_string()));
+        assert_eq!(usernames(&db.pool, &admins).await, ["annika", "bob"]);
+        let search = UserFilter::new().search(Some("ANN".to_string()));
+        assert_eq!(usernames(&db.pool, &search).await, ["annika", "joanna"]);
+        let both = admins.search(Some("ann".to_string()));
+        assert_eq!(usernames(&db.pool, &both).await, ["annika"]);
+
+        db.drop().await;
+    }
+}
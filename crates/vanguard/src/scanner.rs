@@ -0,0 +1,298 @@
+//! Upload scanning.
+//!
+//! Uploaded problem binaries and ZIP submissions are passed to a [`Scanner`]
+//! before they are stored. Every result is recorded in `upload_scans`. In
+//! enforce mode flagged files are rejected, and so are uploads the scanner
+//! could not check; with `UPLOAD_SCAN_MODE=audit` uploads are only recorded.
+//! Scanning is off unless a scanner is configured (`CLAMAV_ADDRESS`).
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use sqlx::PgPool;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{ApiError, ApiResult};
+use crate::upload::SpooledUpload;
+
+/// Size of the chunks streamed to clamd
+const CLAMAV_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Outcome of scanning one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// Flagged, with the signature that matched
+    Infected(String),
+}
+
+/// A malware or static-analysis scanner
+pub trait Scanner: Send + Sync {
+    /// Name recorded with each result
+    fn name(&self) -> &'static str;
+
+    /// Scan the file at `path`. `Err` means the file could not be checked.
+    fn scan<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, Result<ScanVerdict, String>>;
+}
+
+/// ClamAV daemon reached over a Unix socket (an absolute path) or TCP
+/// (`host:port`), using the `INSTREAM` command
+pub struct ClamAvScanner {
+    address: String,
+}
+
+impl ClamAvScanner {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+        }
+    }
+
+    async fn instream<S>(mut stream: S, path: &Path) -> std::io::Result<String>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut chunk = vec![0; CLAMAV_CHUNK_SIZE];
+
+        stream.write_all(b"zINSTREAM\0").await?;
+        loop {
+            let n = file.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            stream.write_all(&(n as u32).to_be_bytes()).await?;
+            stream.write_all(&chunk[..n]).await?;
+        }
+        stream.write_all(&[0; 4]).await?;
+        stream.flush().await?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await?;
+        Ok(String::from_utf8_lossy(&reply)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string())
+    }
+
+    /// Parse a reply such as `stream: OK` or `stream: Eicar-Signature FOUND`
+    fn parse_reply(reply: &str) -> Result<ScanVerdict, String> {
+        let result = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+
+        if result == "OK" {
+            Ok(ScanVerdict::Clean)
+        } else if let Some(signature) = result.strip_suffix("FOUND") {
+            Ok(ScanVerdict::Infected(signature.trim().to_string()))
+        } else {
+            Err(format!("clamd: {}", result))
+        }
+    }
+}
+
+impl Scanner for ClamAvScanner {
+    fn name(&self) -> &'static str {
+        "clamav"
+    }
+
+    fn scan<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, Result<ScanVerdict, String>> {
+        Box::pin(async move {
+            let reply = if self.address.starts_with('/') {
+                let stream = tokio::net::UnixStream::connect(&self.address)
+                    .await
+                    .map_err(|e| format!("Cannot connect to clamd at {}: {}", self.address, e))?;
+                Self::instream(stream, path).await
+            } else {
+                let stream = tokio::net::TcpStream::connect(&self.address)
+                    .await
+                    .map_err(|e| format!("Cannot connect to clamd at {}: {}", self.address, e))?;
+                Self::instream(stream, path).await
+            }
+            .map_err(|e| format!("clamd I/O error: {}", e))?;
+
+            Self::parse_reply(&reply)
+        })
+    }
+}
+
+/// What an upload is for, recorded with its scan result
+#[derive(Debug, Clone, Copy)]
+pub struct ScanTarget {
+    /// `generator`, `checker` or `submission`
+    pub kind: &'static str,
+    /// Problem (binaries) or submission id
+    pub id: Uuid,
+    pub uploaded_by: Uuid,
+}
+
+/// The configured scanner and how its results are acted on
+#[derive(Clone)]
+pub struct UploadScanner {
+    scanner: Option<Arc<dyn Scanner>>,
+    audit_only: bool,
+    timeout: Duration,
+}
+
+impl UploadScanner {
+    pub fn new(scanner: Option<Arc<dyn Scanner>>, audit_only: bool, timeout: Duration) -> Self {
+        Self {
+            scanner,
+            audit_only,
+            timeout,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let scanner = config
+            .clamav_address
+            .as_ref()
+            .map(|address| Arc::new(ClamAvScanner::new(address.clone())) as Arc<dyn Scanner>);
+
+        Self::new(
+            scanner,
+            config.upload_scan_audit_only,
+            Duration::from_secs(config.upload_scan_timeout_secs),
+        )
+    }
+
+    /// Scan an upload and record the result. Flagged or unscannable files
+    /// are rejected unless running audit-only.
+    pub async fn check(
+        &self,
+        db: &PgPool,
+        target: ScanTarget,
+        upload: &SpooledUpload,
+    ) -> ApiResult<()> {
+        let Some(scanner) = &self.scanner else {
+            return Ok(());
+        };
+
+        let verdict = tokio::time::timeout(self.timeout, scanner.scan(upload.path()))
+            .await
+            .unwrap_or_else(|_| Err(format!("Scan timed out after {:?}", self.timeout)));
+
+        let (status, detail) = match &verdict {
+            Ok(ScanVerdict::Clean) => ("clean", None),
+            Ok(ScanVerdict::Infected(signature)) => ("infected", Some(signature.as_str())),
+            Err(error) => ("error", Some(error.as_str())),
+        };
+        let rejected = status != "clean" && !self.audit_only;
+
+        sqlx::query(
+            r#"
+            INSERT INTO upload_scans
+                (target_type, target_id, scanner, status, detail, checksum, size_bytes, action, uploaded_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(target.kind)
+        .bind(target.id)
+        .bind(scanner.name())
+        .bind(status)
+        .bind(detail)
+        .bind(upload.sha256())
+        .bind(upload.size() as i64)
+        .bind(if rejected { "rejected" } else { "allowed" })
+        .bind(target.uploaded_by)
+        .execute(db)
+        .await?;
+
+        if status != "clean" {
+            tracing::warn!(
+                target_type = target.kind,
+                target_id = %target.id,
+                uploaded_by = %target.uploaded_by,
+                status = status,
+                detail = detail.unwrap_or_default(),
+                rejected = rejected,
+                "Upload scan did not come back clean"
+            );
+        }
+
+        match verdict {
+            _ if !rejected => Ok(()),
+            Ok(_) => Err(ApiError::Validation(
+                "Uploaded file was flagged by the malware scanner".to_string(),
+            )),
+            Err(_) => Err(ApiError::ServiceUnavailable(
+                "Upload scanner is unavailable; try again later".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clean_reply() {
+        assert_eq!(
+            ClamAvScanner::parse_reply("stream: OK"),
+            Ok(ScanVerdict::Clean)
+        );
+        assert_eq!(ClamAvScanner::parse_reply("OK"), Ok(ScanVerdict::Clean));
+    }
+
+    #[test]
+    fn test_parse_found_reply() {
+        assert_eq!(
+            ClamAvScanner::parse_reply("stream: Eicar-Signature FOUND"),
+            Ok(ScanVerdict::Infected("Eicar-Signature".to_string()))
+        );
+        assert_eq!(
+            ClamAvScanner::parse_reply("stream: Win.Test.EICAR_HDB-1 FOUND"),
+            Ok(ScanVerdict::Infected("Win.Test.EICAR_HDB-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_error_reply() {
+        assert_eq!(
+            ClamAvScanner::parse_reply("INSTREAM size limit exceeded. ERROR"),
+            Err("clamd: INSTREAM size limit exceeded. ERROR".to_string())
+        );
+        assert_eq!(
+            ClamAvScanner::parse_reply("stream: Can't allocate memory ERROR"),
+            Err("clamd: Can't allocate memory ERROR".to_string())
+        );
+        assert!(ClamAvScanner::parse_reply("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_instream_sends_chunks_and_reads_reply() {
+        let path = std::env::temp_dir().join(format!("vanguard-scan-{}", Uuid::new_v4()));
+        tokio::fs::write(&path, b"payload").await.unwrap();
+
+        let (client, mut clamd) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            let mut command = [0; 10];
+            clamd.read_exact(&mut command).await.unwrap();
+            assert_eq!(&command, b"zINSTREAM\0");
+
+            let mut received = Vec::new();
+            loop {
+                let mut len = [0; 4];
+                clamd.read_exact(&mut len).await.unwrap();
+                let len = u32::from_be_bytes(len) as usize;
+                if len == 0 {
+                    break;
+                }
+                let mut chunk = vec![0; len];
+                clamd.read_exact(&mut chunk).await.unwrap();
+                received.extend(chunk);
+            }
+            clamd.write_all(b"stream: OK\0").await.unwrap();
+            received
+        });
+
+        let reply = ClamAvScanner::instream(client, &path).await.unwrap();
+        assert_eq!(reply, "stream: OK");
+        assert_eq!(server.await.unwrap(), b"payload");
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
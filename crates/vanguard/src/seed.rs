@@ -0,0 +1,488 @@
+//! Development fixtures.
+//!
+//! `vanguard --seed` fills a development database with enough data to try
+//! every part of the judge without hand-crafting it: users of each role, one
+//! contest per lifecycle state, problems with working generators and
+//! checkers, and a batch of submissions queued for judging. The generators
+//! and checkers are shell scripts, stored through the same versioned path as
+//! uploaded binaries.
+//!
+//! Seeding refuses to run in production. Users, problems and contests that
+//! already exist are left alone, so it is safe to repeat.
+
+use anyhow::{bail, Context, Result};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
+    Argon2,
+};
+use chrono::{DateTime, Duration, Utc};
+use olympus_common::JudgePriority;
+use uuid::Uuid;
+
+use crate::domain::problems::handler::{store_binary_version, BinaryKind};
+use crate::state::AppState;
+use crate::upload::SpooledUpload;
+
+/// Password of every seeded account
+const SEED_PASSWORD: &str = "olympus-dev";
+
+/// Username, role and display name of each seeded account
+const USERS: [(&str, &str, &str); 8] = [
+    ("admin", "admin", "Ada Admin"),
+    ("organizer", "organizer", "Olive Organizer"),
+    ("alice", "participant", "Alice"),
+    ("bob", "participant", "Bob"),
+    ("carol", "participant", "Carol"),
+    ("dave", "participant", "Dave"),
+    ("erin", "participant", "Erin"),
+    ("spectator", "spectator", "Sam Spectator"),
+];
+
+struct SeedProblem {
+    code: &'static str,
+    title: &'static str,
+    description: &'static str,
+    input_format: &'static str,
+    output_format: &'static str,
+    constraints: &'static str,
+    sample_input: &'static str,
+    sample_output: &'static str,
+    difficulty: &'static str,
+    tags: &'static [&'static str],
+    num_test_cases: i32,
+    generator: &'static str,
+    checker: &'static str,
+    /// Language and source of the solutions participants submit, cycled
+    /// through so every verdict shows up
+    solutions: &'static [(&'static str, &'static str)],
+}
+
+const PROBLEMS: [SeedProblem; 2] = [
+    SeedProblem {
+        code: "A",
+        title: "A + B",
+        description: "Given two integers, print their sum.",
+        input_format: "A single line with two integers `a` and `b`.",
+        output_format: "Print `a + b`.",
+        constraints: "0 ≤ a, b < 10^9",
+        sample_input: "2 3\n",
+        sample_output: "5\n",
+        difficulty: "easy",
+        tags: &["implementation", "math"],
+        num_test_cases: 5,
+        generator: r#"#!/bin/sh
+# generator <test_number> <seed>: two integers in [0, 10^9)
+seed=$2
+echo "$((seed % 1000000000)) $((seed / 1000000000 % 1000000000))"
+"#,
+        checker: r#"#!/bin/sh
+# checker <input> <output> <answer>
+read -r a b < "$1"
+read -r got < "$2" || true
+want=$((a + b))
+if [ "$got" = "$want" ]; then
+    echo "ok"
+    exit 0
+fi
+echo "expected $want, found '$got'"
+exit 1
+"#,
+        solutions: &[
+            ("python", "a, b = map(int, input().split())\nprint(a + b)\n"),
+            (
+                "cpp",
+                "#include <iostream>\n\nint main() {\n    long long a, b;\n    std::cin >> a >> b;\n    std::cout << a + b << '\\n';\n}\n",
+            ),
+            ("python", "a, b = map(int, input().split())\nprint(a - b)\n"),
+            (
+                "cpp",
+                "int main() {\n    for (volatile unsigned long i = 0;; i++) {\n    }\n}\n",
+            ),
+            ("python", "a, b = map(int, input().split(','))\nprint(a + b)\n"),
+            (
+                "cpp",
+                "#include <iostream>\n\nint main() {\n    long long a, b\n    std::cin >> a >> b;\n}\n",
+            ),
+        ],
+    },
+    SeedProblem {
+        code: "B",
+        title: "Maximum",
+        description: "Given a sequence of integers, print the largest one.",
+        input_format: "The first line contains `n`. The second line contains `n` integers.",
+        output_format: "Print the largest of the `n` integers.",
+        constraints: "1 ≤ n ≤ 10^4, |a_i| ≤ 10^9",
+        sample_input: "5\n3 -1 4 1 -5\n",
+        sample_output: "4\n",
+        difficulty: "easy",
+        tags: &["implementation"],
+        num_test_cases: 10,
+        generator: r#"#!/bin/sh
+# generator <test_number> <seed>: 1000 * test_number integers in [-10^9, 10^9]
+exec awk -v n="$(($1 * 1000))" -v seed="$(($2 % 2147483647))" 'BEGIN {
+    srand(seed)
+    print n
+    for (i = 1; i <= n; i++)
+        printf "%d%s", int(rand() * 2000000001) - 1000000000, (i < n ? " " : "\n")
+}'
+"#,
+        checker: r#"#!/bin/sh
+# checker <input> <output> <answer>
+want=$(awk 'NR == 2 { m = $1; for (i = 2; i <= NF; i++) if ($i + 0 > m + 0) m = $i; print m }' "$1")
+read -r got < "$2" || true
+if [ "$got" = "$want" ]; then
+    echo "ok"
+    exit 0
+fi
+echo "expected $want, found '$got'"
+exit 1
+"#,
+        solutions: &[
+            (
+                "cpp",
+                "#include <algorithm>\n#include <iostream>\n#include <vector>\n\nint main() {\n    int n;\n    std::cin >> n;\n    std::vector<long long> a(n);\n    for (auto &x : a) std::cin >> x;\n    std::cout << *std::max_element(a.begin(), a.end()) << '\\n';\n}\n",
+            ),
+            ("python", "input()\nprint(max(map(int, input().split())))\n"),
+            ("python", "input()\nprint(min(map(int, input().split())))\n"),
+        ],
+    },
+];
+
+/// Populate the database with development fixtures. Entities that already
+/// exist are kept, so an interrupted seed can simply be run again.
+pub async fn run(state: &AppState) -> Result<()> {
+    if state.config.is_production() {
+        bail!("Refusing to seed a production database");
+    }
+
+    let now = Utc::now();
+    let mut tx = state.db.begin().await?;
+
+    // ── Users ───────────────────────────────────────────────────────────
+    let password_hash = Argon2::default()
+        .hash_password(SEED_PASSWORD.as_bytes(), &SaltString::generate(&mut OsRng))
+        .map_err(|e| anyhow::anyhow!("Password hashing failed: {}", e))?
+        .to_string();
+
+    let mut user_ids = Vec::new();
+    let mut users_created = 0;
+    for (username, role, display_name) in USERS {
+        let inserted: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO users (id, username, email, password_hash, display_name, role)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (username) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(username)
+        .bind(format!("{}@olympus.local", username))
+        .bind(&password_hash)
+        .bind(display_name)
+        .bind(role)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let id = match inserted {
+            Some(id) => {
+                users_created += 1;
+                id
+            }
+            None => {
+                sqlx::query_scalar("SELECT id FROM users WHERE username = $1")
+                    .bind(username)
+                    .fetch_one(&mut *tx)
+                    .await?
+            }
+        };
+        user_ids.push((id, role));
+    }
+
+    let organizer_id = user_ids[1].0;
+    let participants: Vec<Uuid> = user_ids
+        .iter()
+        .filter(|(_, role)| *role == "participant")
+        .map(|(id, _)| *id)
+        .collect();
+
+    // ── Problems ────────────────────────────────────────────────────────
+    let mut problem_ids = Vec::new();
+    let mut problems_created = 0;
+    for problem in &PROBLEMS {
+        let existing: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM problems WHERE title = $1 AND owner_id = $2")
+                .bind(problem.title)
+                .bind(organizer_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if let Some(id) = existing {
+            problem_ids.push(id);
+            continue;
+        }
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO problems (
+                id, title, description, input_format, output_format, constraints,
+                sample_input, sample_output, difficulty, tags, num_test_cases,
+                is_public, owner_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, true, $12)
+            "#,
+        )
+        .bind(id)
+        .bind(problem.title)
+        .bind(problem.description)
+        .bind(problem.input_format)
+        .bind(problem.output_format)
+        .bind(problem.constraints)
+        .bind(problem.sample_input)
+        .bind(problem.sample_output)
+        .bind(problem.difficulty)
+        .bind(problem.tags)
+        .bind(problem.num_test_cases)
+        .bind(organizer_id)
+        .execute(&mut *tx)
+        .await?;
+
+        problems_created += 1;
+        problem_ids.push(id);
+    }
+
+    // ── Contests: one per lifecycle state ───────────────────────────────
+    let contests = [
+        (
+            "Olympus Warm-up",
+            "A short past contest, open for practice.",
+            now - Duration::days(7),
+            now - Duration::days(7) + Duration::hours(2),
+            "icpc",
+        ),
+        (
+            "Olympus Weekly",
+            "Running now; the leaderboard freezes for the final hour.",
+            now - Duration::hours(1),
+            now + Duration::hours(2),
+            "icpc",
+        ),
+        (
+            "Olympus Open",
+            "Starts in three days. Registration is open.",
+            now + Duration::days(3),
+            now + Duration::days(3) + Duration::hours(3),
+            "ioi",
+        ),
+    ];
+
+    let mut contests_created = 0;
+    let mut submissions = Vec::new();
+    for (title, description, start_time, end_time, scoring_type) in contests {
+        let existing: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM contests WHERE title = $1 AND owner_id = $2")
+                .bind(title)
+                .bind(organizer_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let contest_id = match existing {
+            Some(id) => id,
+            None => {
+                let id = Uuid::new_v4();
+                let freeze_time = (scoring_type == "icpc").then(|| end_time - Duration::hours(1));
+                sqlx::query(
+                    r#"
+                    INSERT INTO contests (
+                        id, title, description, short_description, start_time, end_time,
+                        freeze_time, scoring_type, is_public, owner_id
+                    ) VALUES ($1, $2, $3, $3, $4, $5, $6, $7, true, $8)
+                    "#,
+                )
+                .bind(id)
+                .bind(title)
+                .bind(description)
+                .bind(start_time)
+                .bind(end_time)
+                .bind(freeze_time)
+                .bind(scoring_type)
+                .bind(organizer_id)
+                .execute(&mut *tx)
+                .await?;
+                contests_created += 1;
+                id
+            }
+        };
+
+        for (sort_order, (problem, problem_id)) in PROBLEMS.iter().zip(&problem_ids).enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO contest_problems (contest_id, problem_id, problem_code, sort_order, added_by)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(contest_id)
+            .bind(problem_id)
+            .bind(problem.code)
+            .bind(sort_order as i32)
+            .bind(organizer_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for user_id in &participants {
+            sqlx::query(
+                r#"
+                INSERT INTO contest_participants (contest_id, user_id, registered_at, status)
+                VALUES ($1, $2, $3, 'registered')
+                ON CONFLICT (contest_id, user_id) DO NOTHING
+                "#,
+            )
+            .bind(contest_id)
+            .bind(user_id)
+            .bind(start_time.min(now) - Duration::hours(1))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Submissions come with the contest; an existing one keeps its own
+        if existing.is_none() && start_time <= now {
+            submissions.extend(contest_submissions(
+                contest_id,
+                start_time,
+                end_time.min(now),
+                &participants,
+                &problem_ids,
+            ));
+        }
+    }
+
+    // ── Submissions ─────────────────────────────────────────────────────
+    for submission in &submissions {
+        sqlx::query(
+            r#"
+            INSERT INTO submissions (
+                id, contest_id, problem_id, user_id,
+                submission_type, language, source_code, status, submitted_at
+            )
+            VALUES ($1, $2, $3, $4, 'source', $5, $6, 'pending', $7)
+            "#,
+        )
+        .bind(submission.id)
+        .bind(submission.contest_id)
+        .bind(submission.problem_id)
+        .bind(submission.user_id)
+        .bind(submission.language)
+        .bind(submission.source_code)
+        .bind(submission.submitted_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    // ── Generators and checkers ─────────────────────────────────────────
+    // Stored through the upload path, which writes files and commits on its
+    // own; a problem still missing one (say, after an interrupted seed)
+    // gets it now
+    for (problem, problem_id) in PROBLEMS.iter().zip(&problem_ids) {
+        for (kind, script) in [
+            (BinaryKind::Generator, problem.generator),
+            (BinaryKind::Checker, problem.checker),
+        ] {
+            let (path_column, _) = kind.columns();
+            let stored: bool = sqlx::query_scalar(&format!(
+                "SELECT {} IS NOT NULL FROM problems WHERE id = $1",
+                path_column
+            ))
+            .bind(problem_id)
+            .fetch_one(&state.db)
+            .await?;
+            if stored {
+                continue;
+            }
+
+            let upload = SpooledUpload::from_bytes(script.as_bytes()).await?;
+            store_binary_version(state, *problem_id, kind, upload, organizer_id)
+                .await
+                .with_context(|| format!("Storing {} of '{}'", kind.label(), problem.title))?;
+        }
+    }
+
+    // ── Queue the new submissions like any other ────────────────────────
+    let mut conn = state.redis.get().await?;
+    for submission in &submissions {
+        redis::cmd("XADD")
+            .arg(JudgePriority::Low.stream_name(&state.config.compile_stream))
+            .arg("*")
+            .arg("submission_id")
+            .arg(submission.id.to_string())
+            .arg("type")
+            .arg("source")
+            .arg("language")
+            .arg(submission.language)
+            .query_async::<String>(&mut conn)
+            .await?;
+    }
+
+    tracing::info!(
+        users = users_created,
+        problems = problems_created,
+        contests = contests_created,
+        submissions = submissions.len(),
+        "Seed data created"
+    );
+    tracing::info!(
+        "Log in as any of {} with password '{}'",
+        USERS.map(|(username, _, _)| username).join(", "),
+        SEED_PASSWORD
+    );
+
+    Ok(())
+}
+
+struct SeedSubmission {
+    id: Uuid,
+    contest_id: Uuid,
+    problem_id: Uuid,
+    user_id: Uuid,
+    language: &'static str,
+    source_code: &'static str,
+    submitted_at: DateTime<Utc>,
+}
+
+/// Two submissions per participant and problem, spread over `start..end`.
+/// Participant `i` starts at solution `i` of each problem, so the batch
+/// mixes accepted runs with every failing verdict.
+fn contest_submissions(
+    contest_id: Uuid,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    participants: &[Uuid],
+    problem_ids: &[Uuid],
+) -> Vec<SeedSubmission> {
+    let count = 2 * participants.len() * PROBLEMS.len();
+    let mut submissions = Vec::new();
+    for (i, user_id) in participants.iter().enumerate() {
+        for (problem, problem_id) in PROBLEMS.iter().zip(problem_ids) {
+            for attempt in 0..2 {
+                let (language, source_code) =
+                    problem.solutions[(i + attempt) % problem.solutions.len()];
+                submissions.push(SeedSubmission {
+                    id: Uuid::new_v4(),
+                    contest_id,
+                    problem_id: *problem_id,
+                    user_id: *user_id,
+                    language,
+                    source_code,
+                    submitted_at: spread(start, end, submissions.len(), count),
+                });
+            }
+        }
+    }
+    submissions
+}
+
+/// The `index`-th of `count` instants evenly spaced over `start..end`
+fn spread(start: DateTime<Utc>, end: DateTime<Utc>, index: usize, count: usize) -> DateTime<Utc> {
+    start + (end - start) * (index as i32 + 1) / (count as i32 + 1)
+}
@@ -0,0 +1,174 @@
+//! Connection handling for the gateway.
+//!
+//! Cheap denial of service during a contest mostly means holding many
+//! connections open while sending little. Clients get
+//! `HEADER_READ_TIMEOUT_SECS` to send a request's headers (and to finish the
+//! TLS handshake), HTTP/2 connections are capped at
+//! `HTTP2_MAX_CONCURRENT_STREAMS` requests at a time, and at most
+//! `MAX_CONNECTIONS` connections are open at once; further ones are closed
+//! as soon as they are accepted. Slow request bodies are cut off by the
+//! router's body read timeout.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use axum_server::accept::Accept;
+use hyper_util::rt::TokioTimer;
+use hyper_util::server::conn::auto::Builder;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::Config;
+
+/// Apply the header read timeout and HTTP/2 stream cap to `builder`.
+pub fn configure_http<E>(builder: &mut Builder<E>, config: &Config) {
+    let header_read_timeout = Duration::from_secs(config.header_read_timeout_secs);
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .header_read_timeout(header_read_timeout);
+    builder
+        .http2()
+        .timer(TokioTimer::new())
+        .max_concurrent_streams(config.http2_max_concurrent_streams);
+}
+
+/// Acceptor that keeps at most a fixed number of connections open.
+#[derive(Clone)]
+pub struct ConnectionLimit<A> {
+    inner: A,
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl<A> ConnectionLimit<A> {
+    /// Wrap `inner`, allowing `max_connections` open connections (0 = no
+    /// cap).
+    pub fn new(inner: A, max_connections: usize) -> Self {
+        Self {
+            inner,
+            permits: (max_connections > 0).then(|| Arc::new(Semaphore::new(max_connections))),
+        }
+    }
+}
+
+impl<A, I, S> Accept<I, S> for ConnectionLimit<A>
+where
+    A: Accept<I, S>,
+    A::Stream: Send + 'static,
+    A::Service: Send + 'static,
+    A::Future: Send + 'static,
+{
+    type Stream = LimitedStream<A::Stream>;
+    type Service = A::Service;
+    type Future =
+        Pin<Box<dyn Future<Output = io::Result<(Self::Stream, Self::Service)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let permit = match &self.permits {
+            Some(permits) => match permits.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    tracing::debug!("Connection limit reached, closing new connection");
+                    return Box::pin(std::future::ready(Err(io::Error::other(
+                        "connection limit reached",
+                    ))));
+                }
+            },
+            None => None,
+        };
+
+        let accepted = self.inner.accept(stream, service);
+        Box::pin(async move {
+            let (stream, service) = accepted.await?;
+            Ok((
+                LimitedStream {
+                    inner: stream,
+                    _permit: permit,
+                },
+                service,
+            ))
+        })
+    }
+}
+
+/// A connection holding one of the limited permits until it is dropped
+pub struct LimitedStream<T> {
+    inner: T,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for LimitedStream<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for LimitedStream<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum_server::accept::DefaultAcceptor;
+
+    async fn accept(limit: &ConnectionLimit<DefaultAcceptor>) -> io::Result<LimitedStream<()>> {
+        limit.accept((), ()).await.map(|(stream, ())| stream)
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit_closes_connections_over_the_cap() {
+        let limit = ConnectionLimit::new(DefaultAcceptor::new(), 2);
+        let first = accept(&limit).await.expect("first connection");
+        let _second = accept(&limit).await.expect("second connection");
+        assert!(accept(&limit).await.is_err());
+
+        // A closed connection frees its place
+        drop(first);
+        assert!(accept(&limit).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit_zero_is_uncapped() {
+        let limit = ConnectionLimit::new(DefaultAcceptor::new(), 0);
+        let mut open = Vec::new();
+        for _ in 0..100 {
+            open.push(accept(&limit).await.expect("connection"));
+        }
+        assert_eq!(open.len(), 100);
+    }
+}
@@ -4,35 +4,67 @@ use std::sync::Arc;
 
 use deadpool_redis::Pool as RedisPool;
 use sqlx::PgPool;
+use tokio::sync::broadcast;
 
 use crate::config::{Config, RateLimitConfig};
+use crate::db::ReadReplica;
+use crate::domain::auth::JwtManager;
+use crate::domain::roles::RoleRegistry;
+use crate::domain::submissions::{LeaderboardEvent, LEADERBOARD_BROADCAST_CAPACITY};
+use crate::scanner::UploadScanner;
 
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
-    /// PostgreSQL connection pool
+    /// PostgreSQL primary pool; all writes go here
     pub db: PgPool,
+    /// Optional read replica for lag-tolerant reads
+    pub replica: ReadReplica,
     /// Redis connection pool
     pub redis: RedisPool,
     /// Application configuration
     pub config: Arc<Config>,
     /// Rate limit configuration
     pub rate_limit_config: Arc<RateLimitConfig>,
+    /// Token signing and verification keys
+    pub jwt: Arc<JwtManager>,
+    /// Scans uploaded binaries and submissions
+    pub scanner: UploadScanner,
+    /// Cached permissions of every role
+    pub roles: RoleRegistry,
+    /// Live leaderboard events relayed from Redis pub/sub
+    pub leaderboard_events: broadcast::Sender<LeaderboardEvent>,
 }
 
 impl AppState {
     /// Create a new AppState
     pub fn new(
         db: PgPool,
+        replica: ReadReplica,
         redis: RedisPool,
         config: Config,
         rate_limit_config: RateLimitConfig,
+        jwt: JwtManager,
     ) -> Self {
+        let (leaderboard_events, _) = broadcast::channel(LEADERBOARD_BROADCAST_CAPACITY);
+        let scanner = UploadScanner::from_config(&config);
+
         Self {
             db,
+            replica,
             redis,
             config: Arc::new(config),
             rate_limit_config: Arc::new(rate_limit_config),
+            jwt: Arc::new(jwt),
+            scanner,
+            roles: RoleRegistry::default(),
+            leaderboard_events,
         }
     }
+
+    /// Pool for reads that tolerate replication lag: the replica while it is
+    /// healthy, otherwise the primary
+    pub fn read_db(&self) -> &PgPool {
+        self.replica.pool().unwrap_or(&self.db)
+    }
 }
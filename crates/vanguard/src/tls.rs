@@ -0,0 +1,82 @@
+//! Native TLS termination.
+//!
+//! With `TLS_CERT_PATH` and `TLS_KEY_PATH` set, Vanguard serves HTTPS itself
+//! with rustls and negotiates HTTP/2 or HTTP/1.1 over ALPN, so small
+//! deployments need no reverse proxy. The PEM files are re-read on an
+//! interval, which picks up certificates renewed by an ACME client such as
+//! certbot without a restart.
+
+use std::time::Duration;
+
+use anyhow::Context;
+use axum_server::tls_rustls::RustlsConfig;
+
+/// Certificate and key files to serve
+#[derive(Debug, Clone)]
+pub struct TlsFiles {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+impl TlsFiles {
+    /// The configured files, `None` for plain HTTP. Setting only one of the
+    /// two is a configuration error.
+    pub fn from_config(
+        cert_path: Option<&str>,
+        key_path: Option<&str>,
+    ) -> anyhow::Result<Option<Self>> {
+        match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Ok(Some(Self {
+                cert_path: cert_path.to_string(),
+                key_path: key_path.to_string(),
+            })),
+            (None, None) => Ok(None),
+            _ => anyhow::bail!("TLS_CERT_PATH and TLS_KEY_PATH must be set together"),
+        }
+    }
+
+    /// Load the certificate and key. ALPN offers `h2` and `http/1.1`.
+    pub async fn load(&self) -> anyhow::Result<RustlsConfig> {
+        // Workspace builds enable both rustls backends, which leaves no
+        // process default; pick the one the JWT signer already uses
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+        RustlsConfig::from_pem_file(&self.cert_path, &self.key_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to load TLS certificate {} and key {}",
+                    self.cert_path, self.key_path
+                )
+            })
+    }
+
+    /// Re-read the files every `interval` in the background. A failed
+    /// reload keeps serving the previous certificate. No-op for a zero
+    /// interval.
+    pub fn spawn_reload(&self, config: RustlsConfig, interval: Duration) {
+        if interval.is_zero() {
+            return;
+        }
+        let files = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; the files were just loaded
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match config
+                    .reload_from_pem_file(&files.cert_path, &files.key_path)
+                    .await
+                {
+                    Ok(()) => tracing::debug!("Reloaded TLS certificate"),
+                    Err(e) => tracing::warn!(
+                        "Failed to reload TLS certificate, keeping the current one: {}",
+                        e
+                    ),
+                }
+            }
+        });
+    }
+}
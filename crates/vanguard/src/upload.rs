@@ -0,0 +1,223 @@
+//! Uploads spooled to disk.
+//!
+//! Multipart file fields are streamed to a temporary file chunk by chunk,
+//! checking the size limit as data arrives, so a large upload never sits in
+//! memory. Each upload gets its own directory under `/mnt/data/temp`, on the
+//! same filesystem as its final location so storing it is a rename; Horus
+//! removes directories a crash leaves behind.
+
+use std::path::{Path, PathBuf};
+
+use axum::extract::{multipart::Field, DefaultBodyLimit};
+use sha2::{Digest, Sha256};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+
+/// Where uploads are spooled
+pub(crate) const UPLOAD_TEMP_DIR: &str = "/mnt/data/temp";
+
+/// Bytes kept from the start of an upload for magic number checks
+const HEAD_LEN: usize = 16;
+
+/// Room for multipart framing and small form fields next to the file
+const MULTIPART_OVERHEAD: usize = 1024 * 1024;
+
+/// Request body limit for a route accepting files of up to `max_file_size`
+/// bytes. The handler enforces the exact limit while streaming.
+pub fn body_limit(max_file_size: usize) -> DefaultBodyLimit {
+    DefaultBodyLimit::max(max_file_size + MULTIPART_OVERHEAD)
+}
+
+/// An uploaded file on disk, deleted when dropped unless persisted
+#[derive(Debug)]
+pub struct SpooledUpload {
+    dir: PathBuf,
+    path: PathBuf,
+    size: u64,
+    sha256: String,
+    head: Vec<u8>,
+}
+
+impl SpooledUpload {
+    /// Stream a multipart field to disk, rejecting it as soon as it grows
+    /// past `max_size` bytes.
+    pub async fn from_field(mut field: Field<'_>, max_size: u64) -> ApiResult<Self> {
+        let mut writer = SpoolWriter::create().await?;
+
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| ApiError::Validation(format!("Failed to read file: {}", e)))?
+        {
+            if writer.upload.size + chunk.len() as u64 > max_size {
+                return Err(ApiError::Validation(format!(
+                    "File size exceeds {}MB limit",
+                    max_size as f64 / 1024.0 / 1024.0
+                )));
+            }
+            writer.write(&chunk).await?;
+        }
+
+        writer.finish().await
+    }
+
+    /// Spool data already in memory, for callers that build files
+    /// themselves.
+    pub async fn from_bytes(data: &[u8]) -> ApiResult<Self> {
+        let mut writer = SpoolWriter::create().await?;
+        writer.write(data).await?;
+        writer.finish().await
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Hex SHA-256 of the contents
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+
+    /// Whether the file starts with `magic` (at most 16 bytes)
+    pub fn starts_with(&self, magic: &[u8]) -> bool {
+        self.head.starts_with(magic)
+    }
+
+    /// Move the file to `dest`, replacing any file there.
+    pub async fn persist(self, dest: impl AsRef<Path>) -> ApiResult<()> {
+        let dest = dest.as_ref();
+        if tokio::fs::rename(&self.path, dest).await.is_err() {
+            // Spool directory on another filesystem
+            tokio::fs::copy(&self.path, dest).await.map_err(|e| {
+                ApiError::Internal(format!("Failed to save {}: {}", dest.display(), e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SpooledUpload {
+    fn drop(&mut self) {
+        // Removing a large file can block, so keep it off the async workers
+        let dir = std::mem::take(&mut self.dir);
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn_blocking(move || remove_spool_dir(&dir));
+            }
+            Err(_) => remove_spool_dir(&dir),
+        }
+    }
+}
+
+fn remove_spool_dir(dir: &Path) {
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove upload {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// An upload being written
+struct SpoolWriter {
+    upload: SpooledUpload,
+    file: File,
+    hasher: Sha256,
+}
+
+impl SpoolWriter {
+    async fn create() -> ApiResult<Self> {
+        let dir = Path::new(UPLOAD_TEMP_DIR).join(format!("upload_{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to create upload directory: {}", e)))?;
+
+        // Owned from here on, so the directory goes away on any error
+        let upload = SpooledUpload {
+            path: dir.join("file"),
+            dir,
+            size: 0,
+            sha256: String::new(),
+            head: Vec::with_capacity(HEAD_LEN),
+        };
+        let file = File::create(&upload.path)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to create upload file: {}", e)))?;
+
+        Ok(Self {
+            upload,
+            file,
+            hasher: Sha256::new(),
+        })
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> ApiResult<()> {
+        let missing = HEAD_LEN - self.upload.head.len();
+        self.upload
+            .head
+            .extend_from_slice(&chunk[..missing.min(chunk.len())]);
+        self.hasher.update(chunk);
+        self.upload.size += chunk.len() as u64;
+
+        self.file
+            .write_all(chunk)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to write upload: {}", e)))
+    }
+
+    async fn finish(mut self) -> ApiResult<SpooledUpload> {
+        self.file
+            .flush()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to write upload: {}", e)))?;
+        self.upload.sha256 = format!("{:x}", self.hasher.finalize());
+        Ok(self.upload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn spooled_in(dir: PathBuf) -> SpooledUpload {
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("file"), b"data").unwrap();
+        SpooledUpload {
+            path: dir.join("file"),
+            dir,
+            size: 4,
+            sha256: String::new(),
+            head: b"data".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_drop_outside_runtime_removes_directory() {
+        let dir = std::env::temp_dir().join(format!("upload-test-{}", Uuid::new_v4()));
+        drop(spooled_in(dir.clone()));
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_drop_in_runtime_removes_directory_in_background() {
+        let dir = std::env::temp_dir().join(format!("upload-test-{}", Uuid::new_v4()));
+        drop(spooled_in(dir.clone()));
+
+        for _ in 0..100 {
+            if !dir.exists() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("upload directory was not removed");
+    }
+}
@@ -0,0 +1,80 @@
+#!/bin/bash
+# Restricted egress network for submissions of network_allowed problems.
+#
+# Builds the network namespace named by SUBMISSION_EGRESS_NETNS, whose only
+# way out is a NAT'd veth pair:
+#   - link-local (cloud metadata), private, loopback and multicast
+#     destinations are dropped, and nothing on the judge itself is reachable
+#   - new connections are rate-limited and bandwidth is capped with tc in
+#     both directions
+# Minos joins each network-enabled run to the namespace and reads its traffic
+# from the counters of SUBMISSION_EGRESS_INTERFACE.
+#
+# Run as root on the judge (the Minos image runs it at startup). Re-running
+# tears down and rebuilds everything.
+set -euo pipefail
+
+NETNS="$(basename "${SUBMISSION_EGRESS_NETNS:-/run/netns/minos-egress}")"
+NS_IF="${SUBMISSION_EGRESS_INTERFACE:-egress0}"
+HOST_IF="${EGRESS_HOST_INTERFACE:-minos-egress0}"
+HOST_ADDR="${EGRESS_HOST_ADDR:-10.213.0.1}"
+NS_ADDR="${EGRESS_NS_ADDR:-10.213.0.2}"
+RATE="${EGRESS_RATE:-10mbit}"
+CONN_RATE="${EGRESS_CONN_RATE:-20/second}"
+CHAIN="MINOS_EGRESS"
+
+BLOCKED=(
+    0.0.0.0/8
+    10.0.0.0/8
+    100.64.0.0/10
+    127.0.0.0/8
+    169.254.0.0/16
+    172.16.0.0/12
+    192.0.0.0/24
+    192.168.0.0/16
+    198.18.0.0/15
+    224.0.0.0/4
+    240.0.0.0/4
+)
+
+echo "🌐 Setting up egress namespace $NETNS..."
+
+# ── Tear down a previous setup ──────────────────────────────────────
+ip netns del "$NETNS" 2>/dev/null || true
+ip link del "$HOST_IF" 2>/dev/null || true
+iptables -D INPUT -i "$HOST_IF" -j DROP 2>/dev/null || true
+iptables -D FORWARD -i "$HOST_IF" -j "$CHAIN" 2>/dev/null || true
+iptables -D FORWARD -o "$HOST_IF" -m conntrack --ctstate ESTABLISHED,RELATED -j ACCEPT 2>/dev/null || true
+iptables -t nat -D POSTROUTING -s "$NS_ADDR/32" -j MASQUERADE 2>/dev/null || true
+iptables -N "$CHAIN" 2>/dev/null || iptables -F "$CHAIN"
+
+# ── Namespace and veth pair ─────────────────────────────────────────
+ip netns add "$NETNS"
+ip link add "$HOST_IF" type veth peer name "$NS_IF" netns "$NETNS"
+ip addr add "$HOST_ADDR/30" dev "$HOST_IF"
+ip link set "$HOST_IF" up
+ip -n "$NETNS" addr add "$NS_ADDR/30" dev "$NS_IF"
+ip -n "$NETNS" link set lo up
+ip -n "$NETNS" link set "$NS_IF" up
+ip -n "$NETNS" route add default via "$HOST_ADDR"
+# Only IPv4 is filtered and NAT'd
+ip netns exec "$NETNS" sysctl -qw net.ipv6.conf.all.disable_ipv6=1
+sysctl -qw net.ipv4.ip_forward=1
+
+# ── Filtering ───────────────────────────────────────────────────────
+iptables -I INPUT -i "$HOST_IF" -j DROP
+iptables -A "$CHAIN" -m conntrack --ctstate ESTABLISHED,RELATED -j ACCEPT
+for net in "${BLOCKED[@]}"; do
+    iptables -A "$CHAIN" -d "$net" -j DROP
+done
+iptables -A "$CHAIN" -m conntrack --ctstate NEW -m limit --limit "$CONN_RATE" --limit-burst 40 -j ACCEPT
+iptables -A "$CHAIN" -j DROP
+iptables -I FORWARD -i "$HOST_IF" -j "$CHAIN"
+iptables -I FORWARD -o "$HOST_IF" -m conntrack --ctstate ESTABLISHED,RELATED -j ACCEPT
+iptables -t nat -A POSTROUTING -s "$NS_ADDR/32" -j MASQUERADE
+
+# ── Bandwidth ───────────────────────────────────────────────────────
+tc qdisc add dev "$HOST_IF" root tbf rate "$RATE" burst 64kb latency 400ms
+tc -n "$NETNS" qdisc add dev "$NS_IF" root tbf rate "$RATE" burst 64kb latency 400ms
+
+echo "✅ Egress namespace ready at /run/netns/$NETNS ($RATE, $CONN_RATE new connections)"
@@ -62,4 +62,5 @@ echo "   docker compose logs -f vanguard    # View API logs"
 echo "   docker compose logs -f sisyphus    # View compiler logs"
 echo "   docker compose logs -f minos       # View judge logs"
 echo "   docker compose ps                  # Check service status"
+echo "   docker compose exec vanguard vanguard --seed  # Load development fixtures"
 echo "   docker compose down                # Stop all services"